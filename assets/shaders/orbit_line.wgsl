@@ -0,0 +1,28 @@
+@group(2) @binding(0) var<uniform> color: vec4<f32>; // rgb tint, a opacity (zoom fade applied)
+@group(2) @binding(1) var<uniform> trail: vec4<f32>; // .x body's true anomaly / 2π, .y fade exponent
+
+struct FragmentInput {
+    @builtin(position) frag_coord: vec4<f32>,
+    @location(0) world_position: vec4<f32>,
+    @location(1) world_normal: vec3<f32>,
+    @location(2) uv: vec2<f32>,
+};
+
+@fragment
+fn fragment(in: FragmentInput) -> @location(0) vec4<f32> {
+    // U is the vertex's true anomaly as a fraction of the orbit. The trail is
+    // brightest at the body and fades out over the orbit behind it.
+    let behind = fract(trail.x - in.uv.x);
+    let alpha = color.a * pow(1.0 - behind, trail.y);
+
+    // Glow boost near the head of the trail
+    var glow = 1.0;
+    if behind < 0.08 {
+        glow = 1.3;
+    }
+
+    if alpha <= 0.01 {
+        discard;
+    }
+    return vec4<f32>(min(color.rgb * glow, vec3<f32>(1.0)), alpha);
+}
@@ -0,0 +1,63 @@
+@group(2) @binding(0) var<uniform> color: vec4<f32>;        // rgb tint, a opacity
+@group(2) @binding(1) var<uniform> sun_position: vec4<f32>; // .xyz world position
+@group(2) @binding(2) var<uniform> planet: vec4<f32>;       // .xyz center, .w radius
+@group(2) @binding(3) var<uniform> bands: vec4<f32>;        // .x seed, .y has texture
+@group(2) @binding(4) var ring_texture: texture_2d<f32>;
+@group(2) @binding(5) var ring_sampler: sampler;
+
+struct FragmentInput {
+    @builtin(position) frag_coord: vec4<f32>,
+    @location(0) world_position: vec4<f32>,
+    @location(1) world_normal: vec3<f32>,
+    @location(2) uv: vec2<f32>,
+};
+
+// Pseudo-random value per band index, varied by the ring's seed
+fn hash(x: f32) -> f32 {
+    return fract(sin(x * 12.9898 + bands.x * 78.233) * 43758.5453);
+}
+
+// Smooth 1D value noise across the ring radius
+fn band_noise(x: f32) -> f32 {
+    let i = floor(x);
+    let f = fract(x);
+    let u = f * f * (3.0 - 2.0 * f);
+    return mix(hash(i), hash(i + 1.0), u);
+}
+
+@fragment
+fn fragment(in: FragmentInput) -> @location(0) vec4<f32> {
+    // U runs from the inner (0) to the outer (1) edge
+    let radial = in.uv.x;
+
+    // Sampled unconditionally to keep control flow uniform
+    let textured = textureSample(ring_texture, ring_sampler, in.uv);
+
+    // Procedural bands: broad ringlets, fine structure and a few clear gaps
+    let broad = band_noise(radial * 12.0);
+    let fine = band_noise(radial * 90.0);
+    let gaps = smoothstep(0.08, 0.2, band_noise(radial * 30.0 + 7.0));
+    let edges = smoothstep(0.0, 0.04, radial) * (1.0 - smoothstep(0.96, 1.0, radial));
+    let density = (0.35 + 0.45 * broad + 0.2 * fine) * gaps * edges;
+    let procedural = vec4<f32>(color.rgb * (0.75 + 0.5 * fine), density);
+
+    var base = procedural;
+    if bands.y > 0.5 {
+        base = vec4<f32>(textured.rgb * color.rgb, textured.a);
+    }
+
+    // Planet shadow: is the planet between this fragment and the star?
+    let p = in.world_position.xyz;
+    let to_sun = normalize(sun_position.xyz - p);
+    let to_planet = planet.xyz - p;
+    let along = dot(to_planet, to_sun);
+    let miss = length(to_planet - to_sun * along);
+    var lit = 1.0;
+    if along > 0.0 {
+        // Soft penumbra over a few percent of the radius
+        lit = smoothstep(planet.w * 0.97, planet.w * 1.03, miss);
+    }
+    let light = 0.1 + 0.9 * lit;
+
+    return vec4<f32>(base.rgb * light, base.a * color.a);
+}
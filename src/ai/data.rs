@@ -0,0 +1,112 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::fs;
+use std::path::PathBuf;
+
+use super::types::{DoctrineDefinition, Priority};
+use crate::modding::ModList;
+
+/// Base doctrines data file
+pub const DOCTRINES_PATH: &str = "assets/data/doctrines.ron";
+
+/// Resource containing all doctrines loaded from data, in file order
+#[derive(Resource, Debug, Clone, Default)]
+pub struct DoctrinesData {
+    pub doctrines: Vec<DoctrineDefinition>,
+}
+
+impl DoctrinesData {
+    /// Get a doctrine by ID
+    pub fn get(&self, id: &str) -> Option<&DoctrineDefinition> {
+        self.doctrines.iter().find(|doctrine| doctrine.id == id)
+    }
+
+    /// Add a definition, replacing one with the same ID in place
+    pub fn insert(&mut self, definition: DoctrineDefinition) {
+        match self.doctrines.iter_mut().find(|d| d.id == definition.id) {
+            Some(existing) => *existing = definition,
+            None => self.doctrines.push(definition),
+        }
+    }
+}
+
+/// Structure of the doctrines data file
+#[derive(Debug, Deserialize)]
+struct DoctrinesFile {
+    doctrines: Vec<DoctrineDefinition>,
+}
+
+/// Check that a doctrine spends the faction's output somewhere: its weights
+/// can't be negative and not all zero, research needs a focus and the
+/// hostility factor can't be negative
+pub fn validate_doctrine(definition: &DoctrineDefinition) -> Result<(), String> {
+    let weights = Priority::ALL.map(|p| definition.weight(p));
+    if weights.iter().any(|w| !w.is_finite() || *w < 0.0) {
+        return Err(format!(
+            "doctrine '{}' has a negative weight",
+            definition.id
+        ));
+    }
+    if weights.iter().all(|w| *w == 0.0) {
+        return Err(format!("doctrine '{}' spends on nothing", definition.id));
+    }
+    if definition.research > 0.0 && definition.research_focus.is_empty() {
+        return Err(format!(
+            "doctrine '{}' researches without a focus",
+            definition.id
+        ));
+    }
+    if !definition.hostility_factor.is_finite() || definition.hostility_factor < 0.0 {
+        return Err(format!(
+            "doctrine '{}' has a negative hostility factor",
+            definition.id
+        ));
+    }
+    Ok(())
+}
+
+/// System to load doctrines from data file at startup, followed by the
+/// doctrines of enabled mods
+pub fn load_doctrines(mut commands: Commands, mods: Option<Res<ModList>>) {
+    let mut data = DoctrinesData::default();
+    let mut paths = vec![PathBuf::from(DOCTRINES_PATH)];
+    paths.extend(mods.map_or(Vec::new(), |mods| mods.data_files("doctrines.ron")));
+
+    for path in paths {
+        match fs::read_to_string(&path) {
+            Ok(contents) => match ron::from_str::<DoctrinesFile>(&contents) {
+                Ok(file) => {
+                    for definition in file.doctrines {
+                        match validate_doctrine(&definition) {
+                            Ok(()) => data.insert(definition),
+                            Err(e) => error!("Skipping invalid doctrine definition: {}", e),
+                        }
+                    }
+                }
+                Err(e) => error!("Failed to parse {}: {}", path.display(), e),
+            },
+            Err(e) => warn!(
+                "Doctrines data file not found at {}: {}. Factions will stand still.",
+                path.display(),
+                e
+            ),
+        }
+    }
+    info!("Loaded {} doctrines", data.doctrines.len());
+    commands.insert_resource(data);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_doctrines_file_parses_and_validates() {
+        let contents = fs::read_to_string(DOCTRINES_PATH).unwrap();
+        let file: DoctrinesFile = ron::from_str(&contents).unwrap();
+        assert!(!file.doctrines.is_empty());
+        for definition in &file.doctrines {
+            validate_doctrine(definition).unwrap();
+        }
+    }
+}
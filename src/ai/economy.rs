@@ -0,0 +1,108 @@
+//! Abstracted economy of the AI factions
+//!
+//! AI factions are not simulated mine by mine. Each of their colonies is a
+//! population growing along a logistic curve towards what its world can
+//! hold, and the colony's industrial output follows its population. Their
+//! fleets are counts of warships whose upkeep is paid before new ones are
+//! laid down, so fleets level off where a faction's industry can carry
+//! them. The full simulation only takes over where the civilization meets
+//! a faction: its squadrons in orbit are real fleets, and a colony that
+//! broke away from the civilization keeps the population its abstract
+//! colony grows to. What agents report of the rest is an estimate, the
+//! closer the stronger their network.
+
+/// Population in millions the worlds of a faction's starting colonies hold
+pub const HOMEWORLD_CAPACITY_MILLIONS: f64 = 50.0;
+/// Population in millions of a faction's starting colonies
+pub const STARTING_POPULATION_MILLIONS: f64 = 20.0;
+/// Population in millions the worlds of later colonies hold
+pub const FRONTIER_CAPACITY_MILLIONS: f64 = 20.0;
+/// Room to grow of a colony that broke away from the civilization, as a
+/// multiple of the colonists it took along
+pub const BREAKAWAY_CAPACITY_FACTOR: f64 = 1.5;
+/// Settlers in millions founding a colony
+pub const SETTLER_MILLIONS: f64 = 0.5;
+/// Growth rate per month of a colony far from what its world holds
+pub const GROWTH_PER_MONTH: f64 = 0.03;
+/// Points produced per month per million colonists
+pub const OUTPUT_PER_MILLION: f64 = 5.0;
+/// Points per month every warship costs to keep in service
+pub const WARSHIP_UPKEEP: f64 = 10.0;
+/// Error of agents' estimates without any network, as a share
+pub const MAX_ESTIMATE_ERROR: f64 = 0.5;
+
+/// A colony of an AI faction, simulated as a handful of numbers
+#[derive(Debug, Clone, PartialEq)]
+pub struct AbstractColony {
+    pub population_millions: f64,
+    /// Population in millions its world can hold
+    pub capacity_millions: f64,
+}
+
+impl AbstractColony {
+    pub fn new(population_millions: f64, capacity_millions: f64) -> Self {
+        Self {
+            population_millions,
+            capacity_millions,
+        }
+    }
+
+    /// Grow along the logistic curve for `months`
+    pub fn grow(&mut self, months: f64) {
+        let (population, capacity) = (self.population_millions, self.capacity_millions);
+        if population <= 0.0 || capacity <= 0.0 {
+            return;
+        }
+        let decay = (-GROWTH_PER_MONTH * months).exp();
+        self.population_millions = capacity / (1.0 + (capacity - population) / population * decay);
+    }
+
+    /// Points produced per month
+    pub fn output(&self) -> f64 {
+        self.population_millions * OUTPUT_PER_MILLION
+    }
+}
+
+/// Colonies a faction starts a game with
+pub fn starting_colonies(count: u32) -> Vec<AbstractColony> {
+    (0..count)
+        .map(|_| AbstractColony::new(STARTING_POPULATION_MILLIONS, HOMEWORLD_CAPACITY_MILLIONS))
+        .collect()
+}
+
+/// Agents' estimate of a figure and its margin of error: without a network
+/// it is off by up to [`MAX_ESTIMATE_ERROR`] in the direction of `bias`
+/// (-1 to 1), with a full network it is exact
+pub fn estimate(value: f64, network: f64, bias: f64) -> (f64, f64) {
+    let margin = value.abs() * (1.0 - network.clamp(0.0, 1.0)) * MAX_ESTIMATE_ERROR;
+    (value + bias.clamp(-1.0, 1.0) * margin, margin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_colonies_grow_along_a_logistic_curve() {
+        let mut colony = AbstractColony::new(SETTLER_MILLIONS, FRONTIER_CAPACITY_MILLIONS);
+        let mut yearly = Vec::new();
+        for _ in 0..30 {
+            let before = colony.population_millions;
+            colony.grow(12.0);
+            yearly.push(colony.population_millions - before);
+        }
+        // Growth speeds up, peaks half-way and fades towards the capacity
+        let peak = yearly
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap()
+            .0;
+        assert!(peak > 0 && peak < yearly.len() - 1);
+        assert!(colony.population_millions < FRONTIER_CAPACITY_MILLIONS);
+        assert!(colony.population_millions > FRONTIER_CAPACITY_MILLIONS * 0.95);
+
+        assert_eq!(estimate(100.0, 1.0, 1.0), (100.0, 0.0));
+        assert_eq!(estimate(100.0, 0.0, -1.0), (50.0, 50.0));
+    }
+}
@@ -0,0 +1,48 @@
+//! AI factions
+//!
+//! Gives the factions of the solar system a strategy of their own:
+//! - Doctrines loaded from `assets/data/doctrines.ron` (expansionist,
+//!   isolationist, mercantile, militarist), weighing colonization,
+//!   research, fleet building and trade
+//! - A doctrine for every faction, named in its definition or drawn from
+//!   the game seed so that every game plays differently
+//! - Monthly faction turns that found colonies, research the doctrine's
+//!   focus categories, build warships and freighters; hostile factions
+//!   send their warships to blockade the civilization's colonies
+//! - An abstracted economy: colonies growing along logistic curves and
+//!   fleets held back by their upkeep, reconciled with the full simulation
+//!   where the civilization meets a faction
+//! - Doctrines and moves reported by the agent networks inside factions,
+//!   and estimates of their strength as close as the networks are strong
+
+use bevy::prelude::*;
+
+pub mod data;
+pub mod economy;
+pub mod systems;
+pub mod types;
+
+pub use data::{load_doctrines, DoctrinesData};
+pub use economy::{estimate, AbstractColony};
+pub use systems::{
+    read_doctrine, run_factions, AiRng, DoctrineReading, FactionState, FactionStates,
+    DOCTRINE_KNOWN_NETWORK, DOCTRINE_TELLS_NETWORK,
+};
+pub use types::{DoctrineDefinition, DoctrineId, Priority};
+
+/// Plugin that adds doctrines and faction turns to the Bevy app
+pub struct AiPlugin;
+
+impl Plugin for AiPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FactionStates>()
+            .init_resource::<AiRng>()
+            .add_systems(Startup, load_doctrines)
+            .add_systems(
+                FixedUpdate,
+                run_factions
+                    .after(crate::economy::advance_economy_clock)
+                    .after(crate::fleets::update_blockades),
+            );
+    }
+}
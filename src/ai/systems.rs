@@ -0,0 +1,485 @@
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::{HashMap, VecDeque};
+
+use super::data::DoctrinesData;
+use super::economy::{
+    estimate, starting_colonies, AbstractColony, BREAKAWAY_CAPACITY_FACTOR,
+    FRONTIER_CAPACITY_MILLIONS, SETTLER_MILLIONS, WARSHIP_UPKEEP,
+};
+use super::types::{DoctrineDefinition, DoctrineId, Priority};
+use crate::economy::components::Population;
+use crate::economy::EconomyClock;
+use crate::fleets::{HostileFleet, HostileFleetDeparted};
+use crate::game_state::{EventLog, GameSeed, LogSeverity};
+use crate::independence::Secessions;
+use crate::intel::Intel;
+use crate::plugins::solar_system::CelestialBody;
+use crate::research::TechCategory;
+use crate::trade::{FactionId, FactionsData};
+use crate::ui::SimulationTime;
+
+/// Economy ticks (days) between two turns of the factions
+pub const FACTION_INTERVAL_TICKS: u64 = 30;
+/// Colonies a faction holds at the start of a game
+pub const STARTING_COLONIES: u32 = 3;
+/// Output gained per research level, as a share
+pub const OUTPUT_PER_RESEARCH_LEVEL: f64 = 0.02;
+/// Points to found the next colony, per colony the faction already holds
+pub const COLONY_COST: f64 = 400.0;
+/// Points for the first research level in a category; every further level
+/// costs as much more
+pub const RESEARCH_LEVEL_COST: f64 = 300.0;
+/// Points per warship
+pub const WARSHIP_COST: f64 = 150.0;
+/// Crew aboard a warship
+pub const CREW_PER_WARSHIP: u32 = 15;
+/// Warships a hostile faction waits for before sending them on a raid
+pub const RAID_WARSHIPS: u32 = 3;
+/// Days of supplies a raiding squadron carries
+pub const RAID_DAYS: f64 = 90.0;
+/// Months of output kept to tell how fast a faction grows
+pub const OUTPUT_HISTORY_MONTHS: usize = 12;
+/// Trade capacity in Mt per year added per point spent on freighters
+pub const TRADE_MT_PER_POINT: f64 = 0.01;
+/// Network strength at which agents report a faction's tells and moves
+pub const DOCTRINE_TELLS_NETWORK: f64 = 0.25;
+/// Network strength at which agents find out a faction's doctrine
+pub const DOCTRINE_KNOWN_NETWORK: f64 = 0.5;
+
+/// Research points needed for `level` levels in a category
+fn research_points_for(level: u32) -> f64 {
+    RESEARCH_LEVEL_COST * (level * (level + 1)) as f64 / 2.0
+}
+
+/// What came of a month of spending
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Spending {
+    pub colonies_founded: u32,
+    pub warships_built: u32,
+    pub freighters_mt_per_year: f64,
+}
+
+/// Where an AI faction stands, driven by its doctrine
+#[derive(Debug, Clone, PartialEq)]
+pub struct FactionState {
+    pub doctrine: DoctrineId,
+    pub colonies: Vec<AbstractColony>,
+    /// Points towards the next colony
+    pub colonization: f64,
+    /// Research points gathered per category
+    pub research: HashMap<TechCategory, f64>,
+    /// Points towards the next warship
+    pub shipbuilding: f64,
+    /// Warships in its yards, waiting to go out
+    pub warships: u32,
+    /// Warships out on a raid, in the full simulation as a hostile fleet
+    pub deployed: u32,
+    /// Raids it sent against the civilization
+    pub raids: u32,
+    /// Output of the last months, oldest first
+    pub output_history: VecDeque<f64>,
+    /// Which way agents' estimates of it are off, from -1 to 1
+    pub estimate_bias: f64,
+}
+
+impl FactionState {
+    pub fn new(doctrine: DoctrineId) -> Self {
+        Self {
+            doctrine,
+            colonies: starting_colonies(STARTING_COLONIES),
+            colonization: 0.0,
+            research: HashMap::new(),
+            shipbuilding: 0.0,
+            warships: 0,
+            deployed: 0,
+            raids: 0,
+            output_history: VecDeque::new(),
+            estimate_bias: 0.0,
+        }
+    }
+
+    /// Colonists in millions in all its colonies
+    pub fn population_millions(&self) -> f64 {
+        self.colonies.iter().map(|c| c.population_millions).sum()
+    }
+
+    /// Growth of its output over the months on record, per year
+    pub fn annual_growth(&self) -> Option<f64> {
+        let (first, last) = (self.output_history.front()?, self.output_history.back()?);
+        let months = self.output_history.len() - 1;
+        if months == 0 || *first <= 0.0 {
+            return None;
+        }
+        Some((last / first).powf(12.0 / months as f64) - 1.0)
+    }
+
+    /// Agents' estimate of one of its figures and the margin of error
+    pub fn estimate(&self, value: f64, network: f64) -> (f64, f64) {
+        estimate(value, network, self.estimate_bias)
+    }
+
+    /// Research level reached in a category
+    pub fn research_level(&self, category: TechCategory) -> u32 {
+        let points = self.research.get(&category).copied().unwrap_or(0.0);
+        let mut level = 0;
+        while research_points_for(level + 1) <= points {
+            level += 1;
+        }
+        level
+    }
+
+    /// Research levels reached in all categories
+    pub fn research_levels(&self) -> u32 {
+        self.research.keys().map(|c| self.research_level(*c)).sum()
+    }
+
+    /// Points produced per month
+    pub fn output(&self) -> f64 {
+        let industry: f64 = self.colonies.iter().map(|c| c.output()).sum();
+        industry * (1.0 + self.research_levels() as f64 * OUTPUT_PER_RESEARCH_LEVEL)
+    }
+
+    /// Grow its colonies for `months` and spend their output as the
+    /// doctrine says. Each month grown leaves its output on record. The
+    /// upkeep of its warships comes out of the fleet budget first;
+    /// sabotaged shipyards build nothing.
+    pub fn spend(
+        &mut self,
+        doctrine: &DoctrineDefinition,
+        months: f64,
+        shipyards_down: bool,
+    ) -> Spending {
+        let mut remaining = months;
+        while remaining > 0.0 {
+            let step = remaining.min(1.0);
+            for colony in &mut self.colonies {
+                colony.grow(step);
+            }
+            self.output_history.push_back(self.output());
+            remaining -= step;
+        }
+        while self.output_history.len() > OUTPUT_HISTORY_MONTHS {
+            self.output_history.pop_front();
+        }
+        let output = self.output() * months;
+        let mut spending = Spending::default();
+
+        self.colonization += output * doctrine.share(Priority::Colonization);
+        while self.colonization >= COLONY_COST * self.colonies.len() as f64 {
+            self.colonization -= COLONY_COST * self.colonies.len() as f64;
+            self.colonies.push(AbstractColony::new(
+                SETTLER_MILLIONS,
+                FRONTIER_CAPACITY_MILLIONS,
+            ));
+            spending.colonies_founded += 1;
+        }
+
+        let research = output * doctrine.share(Priority::Research);
+        for (category, share) in doctrine.focus_shares() {
+            *self.research.entry(category).or_insert(0.0) += research * share;
+        }
+
+        let upkeep = (self.warships + self.deployed) as f64 * WARSHIP_UPKEEP * months;
+        if !shipyards_down {
+            self.shipbuilding += (output * doctrine.share(Priority::Fleets) - upkeep).max(0.0);
+            let built = (self.shipbuilding / WARSHIP_COST).floor();
+            self.shipbuilding -= built * WARSHIP_COST;
+            self.warships += built as u32;
+            spending.warships_built = built as u32;
+        }
+
+        spending.freighters_mt_per_year =
+            output * doctrine.share(Priority::Trade) * TRADE_MT_PER_POINT;
+        spending
+    }
+}
+
+/// Every AI faction's state
+#[derive(Resource, Debug, Clone, Default)]
+pub struct FactionStates {
+    pub states: HashMap<FactionId, FactionState>,
+}
+
+impl FactionStates {
+    pub fn get(&self, faction: &str) -> Option<&FactionState> {
+        self.states.get(faction)
+    }
+}
+
+/// What agents know of a faction's doctrine
+#[derive(Debug, Clone, Copy)]
+pub enum DoctrineReading<'a> {
+    /// Nothing without a network inside the faction
+    Unknown,
+    /// What its doctrine gives away
+    Tells(&'a str),
+    Known(&'a DoctrineDefinition),
+}
+
+/// What a network of the given strength reveals of a doctrine
+pub fn read_doctrine(doctrine: &DoctrineDefinition, network: f64) -> DoctrineReading<'_> {
+    if network >= DOCTRINE_KNOWN_NETWORK {
+        DoctrineReading::Known(doctrine)
+    } else if network >= DOCTRINE_TELLS_NETWORK {
+        DoctrineReading::Tells(&doctrine.tells)
+    } else {
+        DoctrineReading::Unknown
+    }
+}
+
+/// Random number generator for the factions' doctrines and raids, seeded
+/// from the [`GameSeed`]
+#[derive(Resource, Debug, Clone)]
+pub struct AiRng(pub StdRng);
+
+impl FromWorld for AiRng {
+    fn from_world(world: &mut World) -> Self {
+        Self(GameSeed::rng(world, 0x4149_5354_5241))
+    }
+}
+
+/// System that gives every faction its doctrine, drawn from the game seed
+/// unless the faction names one, and plays the factions' turns once a
+/// month: their colonies grow, and they found colonies, research, build
+/// warships and freighters as their doctrine says; hostile factions send
+/// their warships to blockade a colony of the civilization. Squadrons
+/// coming back from the full simulation rejoin their faction unless they
+/// were driven off, and colonies that broke away from the civilization
+/// keep the population their abstract colony grows to. Agents with a
+/// foothold in a faction report its moves.
+#[allow(clippy::too_many_arguments)]
+pub fn run_factions(
+    mut commands: Commands,
+    clock: Res<EconomyClock>,
+    mut states: ResMut<FactionStates>,
+    mut rng: ResMut<AiRng>,
+    factions: Option<ResMut<FactionsData>>,
+    doctrines: Option<Res<DoctrinesData>>,
+    intel: Option<Res<Intel>>,
+    colonies: Query<(Entity, &Population, &CelestialBody)>,
+    (mut log, sim_time): (Option<ResMut<EventLog>>, Option<Res<SimulationTime>>),
+    mut departed: EventReader<HostileFleetDeparted>,
+    mut secessions: Option<ResMut<Secessions>>,
+) {
+    if clock.due == 0 {
+        return;
+    }
+    let (Some(mut factions), Some(doctrines)) = (factions, doctrines) else {
+        return;
+    };
+    if doctrines.doctrines.is_empty() {
+        return;
+    }
+
+    // Factions that are gone (retaken colonies) leave no state behind
+    states.states.retain(|id, _| factions.get(id).is_some());
+    let order = factions.order.clone();
+    for id in &order {
+        if states.states.contains_key(id) {
+            continue;
+        }
+        let Some(faction) = factions.get_mut(id) else {
+            continue;
+        };
+        let doctrine = faction
+            .doctrine
+            .as_deref()
+            .and_then(|d| doctrines.get(d))
+            .or_else(|| doctrines.doctrines.choose(&mut rng.0))
+            .unwrap();
+        faction.hostility *= doctrine.hostility_factor;
+        info!("{} follows the {} doctrine", faction.name, doctrine.name);
+        let mut state = FactionState::new(doctrine.id.clone());
+        state.estimate_bias = rng.0.gen_range(-1.0..1.0);
+        // A colony that broke away starts from the colonists it took along
+        if let Some(record) = secessions.as_ref().and_then(|s| s.get(id)) {
+            let population = record.population.count / 1e6;
+            state.colonies = vec![AbstractColony::new(
+                population,
+                population * BREAKAWAY_CAPACITY_FACTOR,
+            )];
+        }
+        states.states.insert(id.clone(), state);
+    }
+
+    for event in departed.read() {
+        let Some(state) = event
+            .faction
+            .as_ref()
+            .and_then(|f| states.states.get_mut(f))
+        else {
+            continue;
+        };
+        let warships = (event.crew / CREW_PER_WARSHIP).min(state.deployed);
+        state.deployed -= warships;
+        if !event.driven_off {
+            state.warships += warships;
+        }
+    }
+
+    let months = clock.processed / FACTION_INTERVAL_TICKS
+        - (clock.processed - clock.due) / FACTION_INTERVAL_TICKS;
+    if months == 0 {
+        return;
+    }
+    let months = months as f64;
+
+    let mut targets: Vec<(Entity, String)> = colonies
+        .iter()
+        .filter(|(_, population, _)| population.count > 0.0)
+        .map(|(entity, _, body)| (entity, body.name.clone()))
+        .collect();
+    targets.sort_by_key(|(entity, _)| *entity);
+
+    let mut messages = Vec::new();
+    for id in &order {
+        let (Some(state), Some(faction)) = (states.states.get_mut(id), factions.get_mut(id)) else {
+            continue;
+        };
+        let Some(doctrine) = doctrines.get(&state.doctrine) else {
+            continue;
+        };
+        let known = intel.as_ref().map_or(0.0, |i| i.network(id)) >= DOCTRINE_TELLS_NETWORK;
+        let shipyards_down = intel.as_ref().is_some_and(|i| i.capacity_factor(id) < 1.0);
+
+        let spending = state.spend(doctrine, months, shipyards_down);
+        faction.capacity_mt_per_year += spending.freighters_mt_per_year;
+        if known && spending.colonies_founded > 0 {
+            messages.push((
+                LogSeverity::Info,
+                format!(
+                    "Agents report that the {} founded a new colony, {} in all",
+                    faction.name,
+                    state.colonies.len()
+                ),
+            ));
+        }
+
+        let raid_chance = (faction.hostility / 12.0 * months).min(1.0);
+        if state.warships >= RAID_WARSHIPS && raid_chance > 0.0 && rng.0.gen_bool(raid_chance) {
+            let Some((body, body_name)) = targets.choose(&mut rng.0) else {
+                continue;
+            };
+            commands.spawn(HostileFleet {
+                name: format!("{} squadron", faction.name),
+                faction: Some(id.clone()),
+                body: *body,
+                crew: state.warships * CREW_PER_WARSHIP,
+                days_left: RAID_DAYS,
+            });
+            if known {
+                messages.push((
+                    LogSeverity::Warning,
+                    format!(
+                        "Agents report {} warships of the {} leaving for {}",
+                        state.warships, faction.name, body_name
+                    ),
+                ));
+            }
+            state.deployed += state.warships;
+            state.warships = 0;
+            state.raids += 1;
+        }
+    }
+
+    // Colonies that broke away come back as they grew, if ever retaken
+    if let Some(secessions) = secessions.as_mut() {
+        for record in &mut secessions.colonies {
+            let colony = states
+                .states
+                .get(&record.faction)
+                .and_then(|s| s.colonies.first());
+            if let Some(colony) = colony {
+                record.population.count = colony.population_millions * 1e6;
+            }
+        }
+    }
+
+    for (severity, message) in messages {
+        info!("{}", message);
+        if let (Some(log), Some(sim_time)) = (log.as_mut(), sim_time.as_ref()) {
+            log.push(sim_time.format_date_time(), severity, message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doctrine(colonization: f64, fleets: f64) -> DoctrineDefinition {
+        DoctrineDefinition {
+            id: "test".to_string(),
+            name: "Test".to_string(),
+            description: String::new(),
+            colonization,
+            research: 1.0,
+            fleets,
+            trade: 0.0,
+            research_focus: vec![TechCategory::Weapons],
+            hostility_factor: 1.0,
+            tells: "Busy yards".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_doctrine_drives_spending() {
+        let expansionist = doctrine(3.0, 0.0);
+        let militarist = doctrine(0.0, 3.0);
+        let mut settlers = FactionState::new("expansionist".to_string());
+        let mut soldiers = FactionState::new("militarist".to_string());
+        for _ in 0..24 {
+            settlers.spend(&expansionist, 1.0, false);
+            soldiers.spend(&militarist, 1.0, false);
+        }
+        assert!(settlers.colonies.len() > STARTING_COLONIES as usize);
+        assert_eq!(settlers.warships, 0);
+        assert_eq!(soldiers.colonies.len(), STARTING_COLONIES as usize);
+        assert!(soldiers.warships > 0);
+        assert!(settlers.annual_growth().unwrap() > 0.0);
+
+        // Upkeep levels the fleet off where the industry can carry it
+        let fleet = soldiers.warships;
+        for _ in 0..24 {
+            soldiers.spend(&militarist, 1.0, false);
+        }
+        let output = soldiers.output() * militarist.share(Priority::Fleets);
+        assert!(soldiers.warships > fleet);
+        assert!(soldiers.warships as f64 <= output / WARSHIP_UPKEEP + 1.0);
+        assert!(soldiers.research_level(TechCategory::Weapons) > 0);
+
+        // Sabotaged shipyards build nothing
+        let warships = soldiers.warships;
+        soldiers.spend(&militarist, 1.0, true);
+        assert_eq!(soldiers.warships, warships);
+
+        assert!(matches!(
+            read_doctrine(&militarist, 0.0),
+            DoctrineReading::Unknown
+        ));
+        assert!(matches!(
+            read_doctrine(&militarist, DOCTRINE_TELLS_NETWORK),
+            DoctrineReading::Tells("Busy yards")
+        ));
+        assert!(matches!(
+            read_doctrine(&militarist, 1.0),
+            DoctrineReading::Known(_)
+        ));
+    }
+
+    #[test]
+    fn test_each_month_spent_at_once_is_on_record() {
+        let mut state = FactionState::new("test".to_string());
+        state.spend(&doctrine(1.0, 1.0), 3.0, false);
+        assert_eq!(state.output_history.len(), 3);
+        assert!(state
+            .output_history
+            .iter()
+            .zip(state.output_history.iter().skip(1))
+            .all(|(earlier, later)| later > earlier));
+        assert!(state.annual_growth().is_some());
+    }
+}
@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+
+use crate::research::TechCategory;
+
+/// Unique identifier for a doctrine
+pub type DoctrineId = String;
+
+/// What an AI faction spends its output on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Priority {
+    Colonization,
+    Research,
+    Fleets,
+    Trade,
+}
+
+impl Priority {
+    pub const ALL: [Priority; 4] = [
+        Priority::Colonization,
+        Priority::Research,
+        Priority::Fleets,
+        Priority::Trade,
+    ];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Priority::Colonization => "Colonization",
+            Priority::Research => "Research",
+            Priority::Fleets => "Fleets",
+            Priority::Trade => "Trade",
+        }
+    }
+}
+
+/// Strategy an AI faction follows, loaded from data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoctrineDefinition {
+    /// Unique identifier
+    pub id: DoctrineId,
+    /// Display name
+    pub name: String,
+    /// Description shown once agents have found out the doctrine
+    pub description: String,
+    /// Relative weight of colonization in the faction's spending
+    #[serde(default)]
+    pub colonization: f64,
+    /// Relative weight of research
+    #[serde(default)]
+    pub research: f64,
+    /// Relative weight of fleet building
+    #[serde(default)]
+    pub fleets: f64,
+    /// Relative weight of freighters for trade
+    #[serde(default)]
+    pub trade: f64,
+    /// Categories its research goes into, the first getting the most
+    #[serde(default)]
+    pub research_focus: Vec<TechCategory>,
+    /// Factor on the faction's hostility
+    #[serde(default = "default_hostility_factor")]
+    pub hostility_factor: f64,
+    /// What agents notice about factions following it before they find out
+    /// the doctrine itself
+    pub tells: String,
+}
+
+fn default_hostility_factor() -> f64 {
+    1.0
+}
+
+impl DoctrineDefinition {
+    /// Relative weight of a priority
+    pub fn weight(&self, priority: Priority) -> f64 {
+        match priority {
+            Priority::Colonization => self.colonization,
+            Priority::Research => self.research,
+            Priority::Fleets => self.fleets,
+            Priority::Trade => self.trade,
+        }
+    }
+
+    /// Share of the faction's output spent on a priority
+    pub fn share(&self, priority: Priority) -> f64 {
+        let total: f64 = Priority::ALL.iter().map(|p| self.weight(*p)).sum();
+        if total > 0.0 {
+            self.weight(priority) / total
+        } else {
+            0.0
+        }
+    }
+
+    /// Share of the research output going into each focus category: every
+    /// category gets half of what the one before it gets
+    pub fn focus_shares(&self) -> Vec<(TechCategory, f64)> {
+        let weights: Vec<f64> = (0..self.research_focus.len())
+            .map(|i| 0.5f64.powi(i as i32))
+            .collect();
+        let total: f64 = weights.iter().sum();
+        self.research_focus
+            .iter()
+            .zip(weights)
+            .map(|(category, weight)| (*category, weight / total))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shares_are_normalized() {
+        let doctrine = DoctrineDefinition {
+            id: "militarist".to_string(),
+            name: "Militarist".to_string(),
+            description: String::new(),
+            colonization: 1.0,
+            research: 1.0,
+            fleets: 2.0,
+            trade: 0.0,
+            research_focus: vec![TechCategory::Weapons, TechCategory::Propulsion],
+            hostility_factor: 2.0,
+            tells: String::new(),
+        };
+        assert_eq!(doctrine.share(Priority::Fleets), 0.5);
+        assert_eq!(doctrine.share(Priority::Trade), 0.0);
+        let focus = doctrine.focus_shares();
+        assert!((focus[0].1 - 2.0 / 3.0).abs() < 1e-12);
+        assert!((focus[1].1 - 1.0 / 3.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,342 @@
+//! Long-period comets
+//!
+//! Stars keep their cometary cloud as a [`CometReservoir`]. Now and then
+//! ([`COMET_MEAN_DAYS`]) a passing star or the galactic tide knocks one of
+//! its comets onto a highly eccentric orbit that dives into the inner
+//! system. The comet is spawned when it brightens at the frost line and
+//! announced with a discovery event, which leaves a window of weeks to
+//! months before it reaches perihelion. Once it has swung back out into the
+//! cloud it is removed again, unless someone settled on it.
+
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::Rng;
+use std::f64::consts::{PI, TAU};
+
+use super::components::{
+    CometActivity, KeplerOrbit, OrbitCenter, OrbitPath, SpaceCoordinates, SystemId,
+};
+use super::procedural::{CometaryCloud, ProceduralBody, SOLAR_MASS_KG};
+use super::systems::orbit_position_from_mean_anomaly;
+use crate::economy::components::{OrbitsBody, Population, StarSystem};
+use crate::economy::{EconomyClock, SECONDS_PER_YEAR};
+use crate::game_state::{EventLog, GameSeed, LogSeverity};
+use crate::plugins::solar_system::{setup_solar_system, CelestialBody, Comet, Star};
+use crate::plugins::solar_system_data::{AsteroidClass, BodyType};
+use crate::ui::SimulationTime;
+
+/// Mean days between two comets falling in from one cloud
+pub const COMET_MEAN_DAYS: f64 = 365.25;
+/// Perihelia of new comets as shares of the frost line
+pub const PERIHELION_FROST_SHARE: (f64, f64) = (0.05, 0.6);
+/// Comets from one cloud in the inner system at once
+pub const MAX_VISITING_COMETS: usize = 12;
+/// The Sun's cloud the long-period comets fall in from. Its real Oort cloud
+/// reaches thousands of AU; this keeps the orbits within what the solver
+/// handles.
+pub const SOL_COMET_CLOUD: CometaryCloud = CometaryCloud {
+    inner_au: 30.0,
+    outer_au: 100.0,
+    count: 0,
+    inclination: PI,
+};
+
+/// The cometary cloud of a star, which sends comets into its inner system
+#[derive(Component, Debug, Clone)]
+pub struct CometReservoir {
+    pub cloud: CometaryCloud,
+    /// Comets sent in so far
+    pub sent: usize,
+}
+
+impl CometReservoir {
+    pub fn new(cloud: CometaryCloud) -> Self {
+        Self { cloud, sent: 0 }
+    }
+}
+
+/// A comet that fell in from the cloud of `star`
+#[derive(Component, Debug, Clone, Copy)]
+pub struct VisitingComet {
+    pub star: Entity,
+    /// Elapsed simulation seconds at perihelion
+    pub perihelion_seconds: f64,
+}
+
+/// Sent when a new comet is discovered falling into a system
+#[derive(Event, Debug, Clone)]
+pub struct CometDiscovered {
+    pub comet: Entity,
+    pub name: String,
+    pub perihelion_au: f64,
+    pub days_to_perihelion: f64,
+}
+
+/// Random number generator for comets, seeded from the [`GameSeed`]
+#[derive(Resource, Debug, Clone)]
+pub struct CometRng(pub StdRng);
+
+impl FromWorld for CometRng {
+    fn from_world(world: &mut World) -> Self {
+        Self(GameSeed::rng(world, 0x434f_4d45_5453))
+    }
+}
+
+/// Orbit of a comet falling in from `cloud` around a star of `star_mass_sol`
+/// with its frost line at `frost_line_au`, placed where it crosses the frost
+/// line inbound at `elapsed` simulation seconds. Returns the orbit and the
+/// seconds until perihelion.
+pub fn infalling_orbit(
+    cloud: &CometaryCloud,
+    frost_line_au: f64,
+    star_mass_sol: f64,
+    elapsed: f64,
+    rng: &mut impl Rng,
+) -> (KeplerOrbit, f64) {
+    let (low, high) = PERIHELION_FROST_SHARE;
+    let perihelion = frost_line_au * rng.gen_range(low..high);
+    let aphelion = rng.gen_range(cloud.inner_au..cloud.outer_au);
+    let a = (perihelion + aphelion) / 2.0;
+    let e = (aphelion - perihelion) / (aphelion + perihelion);
+    let period_seconds = a.powf(1.5) / star_mass_sol.max(0.01).sqrt() * SECONDS_PER_YEAR;
+    let mean_motion = TAU / period_seconds;
+
+    // Mean anomaly still to go from the frost line to perihelion
+    let discovery = frost_line_au.min(aphelion);
+    let eccentric_anomaly = ((1.0 - discovery / a) / e).clamp(-1.0, 1.0).acos();
+    let to_go = eccentric_anomaly - e * eccentric_anomaly.sin();
+
+    // Long-period comets come in from every direction
+    let inclination = rng.gen_range(-1.0f64..1.0).acos();
+    let orbit = KeplerOrbit::new(
+        e,
+        a,
+        inclination,
+        rng.gen_range(0.0..TAU),
+        rng.gen_range(0.0..TAU),
+        (-to_go - mean_motion * elapsed).rem_euclid(TAU),
+        mean_motion,
+    );
+    (orbit, to_go / mean_motion)
+}
+
+/// Designation of a new comet in the style of "C/2031 B2": year, letter of
+/// the half month and number, with the star in parentheses
+pub fn comet_designation(timestamp: i64, number: usize, star_name: &str) -> String {
+    const HALF_MONTHS: &[u8] = b"ABCDEFGHJKLMNOPQRSTUVWXY";
+    // Civil date of the Unix day (Hinnant's days-to-civil algorithm)
+    let days = timestamp.div_euclid(86400) + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    let half_month = ((month - 1) * 2 + i64::from(day > 15)) as usize;
+    format!(
+        "C/{} {}{} ({})",
+        year, HALF_MONTHS[half_month] as char, number, star_name
+    )
+}
+
+/// System that gives Sol's Sun its cometary cloud
+#[allow(clippy::type_complexity)]
+pub fn add_sol_comet_reservoir(
+    mut commands: Commands,
+    stars: Query<(Entity, Option<&SystemId>), (With<Star>, Without<CometReservoir>)>,
+) {
+    if let Some((sun, _)) = stars
+        .iter()
+        .find(|(_, system_id)| system_id.is_none_or(|s| s.0 == 0))
+    {
+        commands
+            .entity(sun)
+            .insert(CometReservoir::new(SOL_COMET_CLOUD));
+    }
+}
+
+/// System that sends comets in from the clouds once per due economy tick
+/// and announces their discovery
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn send_comets(
+    mut commands: Commands,
+    clock: Option<Res<EconomyClock>>,
+    mut rng: ResMut<CometRng>,
+    mut reservoirs: Query<(
+        Entity,
+        &CelestialBody,
+        &mut CometReservoir,
+        Option<&StarSystem>,
+        Option<&SystemId>,
+    )>,
+    visitors: Query<&VisitingComet>,
+    sim_time: Option<Res<SimulationTime>>,
+    mut discovered: EventWriter<CometDiscovered>,
+    mut log: Option<ResMut<EventLog>>,
+) {
+    let Some(clock) = clock.filter(|c| c.due > 0) else {
+        return;
+    };
+    let chance = (clock.due as f64 / COMET_MEAN_DAYS).min(1.0);
+    let elapsed = sim_time.as_ref().map_or(0.0, |t| t.elapsed_seconds());
+    let timestamp = sim_time.as_ref().map_or(0, |t| t.current_timestamp());
+
+    for (star, body, mut reservoir, system, system_id) in reservoirs.iter_mut() {
+        if !rng.0.gen_bool(chance)
+            || visitors.iter().filter(|v| v.star == star).count() >= MAX_VISITING_COMETS
+        {
+            continue;
+        }
+        let frost_line = system.map_or(StarSystem::default().frost_line_au, |s| s.frost_line_au);
+        let (orbit, to_perihelion) = infalling_orbit(
+            &reservoir.cloud,
+            frost_line,
+            body.mass / SOLAR_MASS_KG,
+            elapsed,
+            &mut rng.0,
+        );
+        reservoir.sent += 1;
+        let name = comet_designation(timestamp, reservoir.sent, &body.name);
+
+        // Fresh from the cloud: large, icy and switching on early
+        let radius: f32 = rng.0.gen_range(1.0..20.0);
+        let mass = (4.0 / 3.0) * PI * (radius as f64 * 1000.0).powi(3) * 500.0;
+        let activity = CometActivity {
+            activation_radius_au: frost_line.max(3.0),
+            dust_fraction: rng.0.gen_range(0.3..0.7),
+        };
+        let comet = commands
+            .spawn((
+                Comet,
+                CelestialBody {
+                    name: name.clone(),
+                    mass,
+                    radius,
+                    body_type: BodyType::Comet,
+                    visual_radius: radius,
+                    asteroid_class: Some(AsteroidClass::PType),
+                },
+                orbit,
+                OrbitPath::new(Color::srgba(0.4, 0.6, 0.8, 0.3)),
+                SpaceCoordinates::default(),
+                OrbitCenter(star),
+                OrbitsBody::new(star),
+                SystemId(system_id.map_or(0, |s| s.0)),
+                activity,
+                ProceduralBody,
+                VisitingComet {
+                    star,
+                    perihelion_seconds: elapsed + to_perihelion,
+                },
+            ))
+            .id();
+
+        let perihelion_au = orbit.semi_major_axis * (1.0 - orbit.eccentricity);
+        let days_to_perihelion = to_perihelion / 86400.0;
+        let message = format!(
+            "New comet {} discovered: perihelion {:.2} AU from {} in {:.0} days",
+            name, perihelion_au, body.name, days_to_perihelion
+        );
+        info!("{}", message);
+        if let (Some(log), Some(sim_time)) = (log.as_mut(), sim_time.as_ref()) {
+            log.push(sim_time.format_date_time(), LogSeverity::Info, message);
+        }
+        discovered.send(CometDiscovered {
+            comet,
+            name,
+            perihelion_au,
+            days_to_perihelion,
+        });
+    }
+}
+
+/// System that removes comets once they are back out in their cloud,
+/// unless they were settled
+pub fn retire_departed_comets(
+    mut commands: Commands,
+    sim_time: Res<SimulationTime>,
+    comets: Query<(Entity, &VisitingComet, &KeplerOrbit), Without<Population>>,
+    reservoirs: Query<&CometReservoir>,
+) {
+    let elapsed = sim_time.elapsed_seconds();
+    for (entity, visitor, orbit) in comets.iter() {
+        if elapsed < visitor.perihelion_seconds {
+            continue;
+        }
+        let mean_anomaly = orbit.mean_anomaly_epoch + orbit.mean_motion * elapsed;
+        let distance = orbit_position_from_mean_anomaly(orbit, mean_anomaly).length();
+        let cloud = reservoirs
+            .get(visitor.star)
+            .map_or(SOL_COMET_CLOUD.inner_au, |r| r.cloud.inner_au);
+        if distance > cloud {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// Plugin that sends long-period comets in from the stars' clouds
+pub struct CometInfluxPlugin;
+
+impl Plugin for CometInfluxPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CometRng>()
+            .add_event::<CometDiscovered>()
+            .add_systems(Startup, add_sol_comet_reservoir.after(setup_solar_system))
+            .add_systems(
+                FixedUpdate,
+                (
+                    send_comets.after(crate::economy::advance_economy_clock),
+                    retire_departed_comets,
+                ),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_infalling_comets_cross_the_frost_line_before_perihelion() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let elapsed = 1e8;
+        for _ in 0..50 {
+            let (orbit, to_perihelion) =
+                infalling_orbit(&SOL_COMET_CLOUD, 4.85, 1.0, elapsed, &mut rng);
+            assert!(orbit.eccentricity > 0.8 && orbit.eccentricity < 1.0);
+            let perihelion = orbit.semi_major_axis * (1.0 - orbit.eccentricity);
+            assert!((4.85 * 0.05..4.85 * 0.6).contains(&perihelion));
+
+            // At the frost line now, at perihelion a window later
+            let now = orbit.mean_anomaly_epoch + orbit.mean_motion * elapsed;
+            let distance = orbit_position_from_mean_anomaly(&orbit, now).length();
+            assert!((distance - 4.85).abs() < 1e-3, "distance {}", distance);
+            assert!(to_perihelion > 86400.0 && to_perihelion < SECONDS_PER_YEAR * 5.0);
+            let later =
+                orbit_position_from_mean_anomaly(&orbit, now + orbit.mean_motion * to_perihelion);
+            assert!((later.length() - perihelion).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_comet_designations() {
+        // 1 January and 20 February 2031
+        assert_eq!(
+            comet_designation(1_924_992_000, 1, "Sun"),
+            "C/2031 A1 (Sun)"
+        );
+        assert_eq!(
+            comet_designation(1_924_992_000 + 50 * 86400, 3, "Tau Ceti"),
+            "C/2031 D3 (Tau Ceti)"
+        );
+    }
+}
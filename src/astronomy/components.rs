@@ -25,12 +25,56 @@ pub struct CurrentStarSystem(pub usize);
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
 pub struct SystemId(pub usize);
 
+/// Marker for bodies of a star system other than the one in view. Their
+/// orbits are sampled once per simulated day rather than every tick, and
+/// their positions are not interpolated between samples.
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct Dormant;
+
 /// Component referencing the entity that this body orbits around.
 /// Without this component, orbits are computed relative to the universe origin (0,0,0).
 /// With it, the orbit position is offset by the parent entity's SpaceCoordinates.
 #[derive(Component, Debug, Clone, Copy)]
 pub struct OrbitCenter(pub Entity);
 
+/// The two most recent fixed-timestep orbit samples of a body.
+///
+/// Orbits are propagated in `FixedUpdate`; every frame the rendered position
+/// is interpolated between these samples. Positions are split into the
+/// parent's position and the offset from it, so that the offset can be swept
+/// along the orbit arc rather than cut across as a chord.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct OrbitSamples {
+    /// Simulation time (seconds) of the current sample
+    pub time: f64,
+    pub previous_parent: DVec3,
+    pub previous_offset: DVec3,
+    pub current_parent: DVec3,
+    pub current_offset: DVec3,
+}
+
+impl OrbitSamples {
+    /// Samples holding a single position, e.g. for a freshly spawned body
+    pub fn at(time: f64, parent: DVec3, offset: DVec3) -> Self {
+        Self {
+            time,
+            previous_parent: parent,
+            previous_offset: offset,
+            current_parent: parent,
+            current_offset: offset,
+        }
+    }
+
+    /// Shift the current sample into the previous slot and store a new one
+    pub fn push(&mut self, time: f64, parent: DVec3, offset: DVec3) {
+        self.previous_parent = self.current_parent;
+        self.previous_offset = self.current_offset;
+        self.current_parent = parent;
+        self.current_offset = offset;
+        self.time = time;
+    }
+}
+
 impl SpaceCoordinates {
     /// Create new space coordinates from a DVec3 position
     pub fn new(position: DVec3) -> Self {
@@ -47,7 +91,7 @@ impl SpaceCoordinates {
 
 /// Keplerian orbital elements for realistic orbital mechanics.
 /// All angular measurements are in radians, distances in Astronomical Units (AU).
-#[derive(Component, Debug, Clone, Copy)]
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
 pub struct KeplerOrbit {
     /// Eccentricity (e) - shape of the orbit (0 = circle, 0-1 = ellipse, 1 = parabola, >1 = hyperbola)
     pub eccentricity: f64,
@@ -222,8 +266,42 @@ pub struct CometTail {
     pub comet_entity: Entity,
     /// Whether this is an ion tail (true) or dust tail (false)
     pub is_ion_tail: bool,
+    /// Current activity level of the parent comet (0.0 = dormant, 1.0 = peak)
+    pub intensity: f32,
+    /// Fraction of the comet's total tail output carried by this tail (0.0-1.0).
+    /// The ion and dust shares of one comet always sum to 1.0.
+    pub share: f32,
+    /// Brightness last written into this tail's material, used to skip redundant updates
+    pub brightness: f32,
+}
+
+/// Per-comet sublimation parameters controlling when and how strongly a tail forms.
+///
+/// Comets without this component use [`CometActivity::default`].
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct CometActivity {
+    /// Heliocentric distance in AU (for a 1 L☉ star) inside which the comet becomes active.
+    /// The effective radius scales with `sqrt(L)` of the parent star.
+    pub activation_radius_au: f64,
+    /// Dust-to-gas character of the nucleus (0.0 = pure gas, 1.0 = pure dust).
+    /// Dusty comets keep a dominant dust tail even close to the star.
+    pub dust_fraction: f32,
+}
+
+impl Default for CometActivity {
+    fn default() -> Self {
+        Self {
+            activation_radius_au: 5.0,
+            dust_fraction: 0.5,
+        }
+    }
 }
 
+/// Bolometric luminosity of a star in solar units (L☉).
+/// Stars without this component are treated as Sun-like (1.0 L☉).
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct StellarLuminosity(pub f64);
+
 /// Local orbit amplification factor for moons.
 ///
 /// Scales the orbital position so moons render outside their parent's visual mesh.
@@ -233,6 +311,39 @@ pub struct CometTail {
 #[derive(Component, Debug, Clone, Copy)]
 pub struct LocalOrbitAmplification(pub f32);
 
+/// Level of detail a celestial body is drawn at, chosen each frame by
+/// `update_body_lod_visibility` from the camera distance.
+///
+/// Bodies without this component are drawn in full.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BodyLod {
+    /// Drawn with its own mesh
+    #[default]
+    Full,
+    /// Mesh hidden, drawn as a point marker (distant comets and asteroids)
+    Point,
+    /// Folded into its parent's moon-group marker
+    Collapsed,
+    /// Not drawn at all (other star systems)
+    Hidden,
+}
+
+impl BodyLod {
+    /// Whether the cursor can pick the body: only what is drawn on its own
+    pub fn pickable(self) -> bool {
+        matches!(self, BodyLod::Full | BodyLod::Point)
+    }
+
+    /// Visibility of the body's own mesh
+    pub fn mesh_visibility(self) -> Visibility {
+        if self == BodyLod::Full {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        }
+    }
+}
+
 /// Marker component for a glossy selection ring mesh.
 #[derive(Component, Debug, Clone, Copy)]
 pub struct SelectionMarker;
@@ -319,6 +430,8 @@ pub struct AtmosphereComposition {
     /// Maximum harvest altitude pressure achievable with current technology (gas giants only)
     /// Technology research can increase this limit to allow deeper, more efficient harvesting.
     /// Default: 50 bar for basic tech, can be increased to 100+ bar with advanced tech.
+    /// Once the economy runs, `update_harvest_depths` keeps both harvest fields at the
+    /// limit set by research.
     pub max_harvest_altitude_bar: f32,
 }
 
@@ -476,6 +589,66 @@ impl AtmosphereComposition {
     }
 }
 
+/// Lowest surface pressure in millibars that pressure domes can be anchored
+/// against; below it the surface counts as airless (Mars just makes it)
+pub const DOME_MIN_PRESSURE_MBAR: f32 = 5.0;
+/// Highest surface pressure in millibars domes can hold out
+pub const DOME_MAX_PRESSURE_MBAR: f32 = 4000.0;
+/// Surface temperatures in Celsius domes can be heated or cooled against
+pub const DOME_TEMPERATURE_RANGE_C: std::ops::RangeInclusive<f32> = -100.0..=100.0;
+
+/// What it takes to keep colonists alive on a body
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HabitatClass {
+    /// Breathable air: colonists live in the open
+    Open,
+    /// A marginal atmosphere: colonists live under pressure domes that are
+    /// topped up with oxygen and heated or cooled
+    Domed,
+    /// Airless, crushing or extreme worlds: colonists live in sealed
+    /// habitats with closed-loop life support
+    Sealed,
+}
+
+impl HabitatClass {
+    /// Infrastructure share of the colony cost
+    pub fn base_colony_cost(&self) -> f32 {
+        match self {
+            HabitatClass::Open => 0.0,
+            HabitatClass::Domed => 1.0,
+            HabitatClass::Sealed => 2.0,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            HabitatClass::Open => "Open air",
+            HabitatClass::Domed => "Pressure domes",
+            HabitatClass::Sealed => "Closed-loop habitats",
+        }
+    }
+}
+
+/// Habitat a body calls for, from its atmosphere and surface temperature
+pub fn habitat_class(
+    temperature_celsius: f32,
+    atmosphere: Option<&AtmosphereComposition>,
+) -> HabitatClass {
+    match atmosphere {
+        Some(atmosphere) if atmosphere.breathable => HabitatClass::Open,
+        Some(atmosphere)
+            if atmosphere.can_support_atmosphere
+                && !atmosphere.is_reference_pressure
+                && (DOME_MIN_PRESSURE_MBAR..=DOME_MAX_PRESSURE_MBAR)
+                    .contains(&atmosphere.surface_pressure_mbar)
+                && DOME_TEMPERATURE_RANGE_C.contains(&temperature_celsius) =>
+        {
+            HabitatClass::Domed
+        }
+        _ => HabitatClass::Sealed,
+    }
+}
+
 /// Calculate colony cost for any body, even without atmosphere.
 ///
 /// Returns the colony cost factor (0.0 = Earth-like/Ideal).
@@ -492,14 +665,9 @@ pub fn calculate_general_colony_cost(gravity_g: f32, temperature_celsius: f32, a
         return f32::INFINITY;
     }
 
-    let mut cost = 0.0;
-
     // 2. Base Infrastructure Cost
-    // If no atmosphere or not breathable, base cost is 2.0 (Closed Cycle/Pressurized)
-    let breathable = atmosphere.map_or(false, |a| a.breathable);
-    if !breathable {
-        cost = 2.0;
-    }
+    // Open air costs nothing, pressure domes 1.0 and closed-loop habitats 2.0
+    let mut cost = habitat_class(temperature_celsius, atmosphere).base_colony_cost();
 
     // 3. Temperature Cost
     // In Aurora, cost increases as temperature deviates further from habitable range
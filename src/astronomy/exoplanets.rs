@@ -4,8 +4,17 @@
 //! integrate it with the procedural generation system.
 
 use bevy::prelude::*;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 
+use super::components::KeplerOrbit;
+use super::nearby_stars::PlanetData;
+use crate::plugins::solar_system_data::BodyType;
+
+/// Spread of the random inclination given to planets without a measured one
+/// (radians, roughly the scatter of the solar system's planets)
+const UNKNOWN_INCLINATION_SPREAD: f64 = 0.15;
+
 /// Represents a confirmed exoplanet from the NASA Exoplanet Archive
 /// These are spawned as 'Real' planets before procedural gap-filling
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -76,6 +85,24 @@ impl ConfirmedPlanet {
         }
     }
 
+    /// Convert an entry of the nearby-stars catalog. A radius of zero or a
+    /// missing mass counts as unknown and is estimated on use.
+    pub fn from_catalog(data: &PlanetData) -> Self {
+        Self {
+            name: data.name.clone(),
+            mass_earth: (data.mass_earth > 0.0).then_some(data.mass_earth),
+            radius_earth: data.radius_earth.filter(|r| *r > 0.0),
+            period_days: data.period_days,
+            semi_major_axis_au: data.semi_major_axis_au as f64,
+            eccentricity: data.eccentricity as f64,
+            inclination_deg: data.inclination_deg,
+            planet_type: data.planet_type.clone(),
+            discovery_method: data.discovery_method.clone(),
+            discovery_year: data.discovery_year,
+            equilibrium_temp_k: None,
+        }
+    }
+
     /// Set the mass of the planet in Earth masses
     pub fn with_mass(mut self, mass_earth: f32) -> Self {
         self.mass_earth = Some(mass_earth);
@@ -196,6 +223,33 @@ impl ConfirmedPlanet {
         let period_seconds = (self.period_days as f64) * 86400.0; // days to seconds
         std::f64::consts::TAU / period_seconds
     }
+
+    /// Orbit with the catalog elements. Angles the catalog does not provide
+    /// (node, periapsis, phase and an unmeasured inclination) are drawn from
+    /// `rng`.
+    pub fn kepler_orbit(&self, rng: &mut impl Rng) -> KeplerOrbit {
+        let inclination = self.inclination_deg.map_or_else(
+            || rng.gen_range(0.0..UNKNOWN_INCLINATION_SPREAD),
+            f64::to_radians,
+        );
+        KeplerOrbit::new(
+            self.eccentricity,
+            self.semi_major_axis_au,
+            inclination,
+            rng.gen_range(0.0..std::f64::consts::TAU),
+            rng.gen_range(0.0..std::f64::consts::TAU),
+            rng.gen_range(0.0..std::f64::consts::TAU),
+            self.mean_motion(),
+        )
+    }
+
+    /// Body type the planet is simulated as
+    pub fn body_type(&self) -> BodyType {
+        match self.planet_type.as_str() {
+            "Gas Giant" | "Jupiter-like" | "Ice Giant" | "Neptune-like" => BodyType::GasGiant,
+            _ => BodyType::Planet,
+        }
+    }
 }
 
 /// Marker component indicating this planet is a confirmed exoplanet (real data)
@@ -203,6 +257,13 @@ impl ConfirmedPlanet {
 #[derive(Component, Debug, Clone, Copy)]
 pub struct RealPlanet;
 
+/// Discovery details of a confirmed exoplanet, shown with the body
+#[derive(Component, Debug, Clone, Default)]
+pub struct ExoplanetDiscovery {
+    pub method: Option<String>,
+    pub year: Option<u16>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -257,6 +318,34 @@ mod tests {
         assert!(radius > 300.0 && radius < 320.0);
     }
 
+    #[test]
+    fn test_from_catalog_treats_zero_values_as_unknown() {
+        let data = PlanetData {
+            name: "GJ 1061 d".to_string(),
+            mass_earth: 1.64,
+            radius_earth: Some(0.0),
+            period_days: 13.0,
+            semi_major_axis_au: 0.054,
+            eccentricity: 0.1,
+            planet_type: "Telluric".to_string(),
+            orbits_star: 0,
+            inclination_deg: Some(30.0),
+            discovery_method: Some("Radial Velocity".to_string()),
+            discovery_year: Some(2019),
+        };
+        let planet = ConfirmedPlanet::from_catalog(&data);
+        assert_eq!(planet.mass_earth, Some(1.64));
+        assert_eq!(planet.radius_earth, None);
+        assert!(planet.estimated_radius_earth() > 1.0);
+        assert_eq!(planet.discovery_year, Some(2019));
+        assert_eq!(planet.body_type(), BodyType::Planet);
+
+        let mut rng = rand::rngs::mock::StepRng::new(0, 1);
+        let orbit = planet.kepler_orbit(&mut rng);
+        assert!((orbit.inclination - 30f64.to_radians()).abs() < 1e-12);
+        assert!((orbit.semi_major_axis - 0.054).abs() < 1e-6);
+    }
+
     #[test]
     fn test_mean_motion_calculation() {
         let earth_like = ConfirmedPlanet::new(
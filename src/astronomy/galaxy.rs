@@ -0,0 +1,403 @@
+//! Procedural stellar neighbourhood beyond the nearby-star catalog
+//!
+//! The hand-curated catalog only reaches about 16 light years. Past the
+//! outermost catalog system the starmap is filled with generated systems out
+//! to [`GalaxySettings::radius_ly`]. Primaries follow the spectral-class
+//! frequencies of the solar neighbourhood (red dwarfs dominate, O and B stars
+//! are vanishingly rare), a class-dependent fraction gets a companion, and
+//! every system carries a metallicity drawn around the local thin-disk mean.
+//!
+//! Generation is deterministic: the same [`GameSeed`](crate::game_state::GameSeed)
+//! and settings always produce the same stars.
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use std::f64::consts::PI;
+
+/// Mixed into the game seed so the galaxy does not share a random stream
+/// with the system populator
+const GALAXY_SEED_SALT: u64 = 0x6a1a_c71c_5747_2b1d;
+
+/// Stellar systems per cubic light year near the Sun
+pub const LOCAL_SYSTEM_DENSITY: f64 = 0.003;
+
+/// Closest distance between two generated systems
+const MIN_SEPARATION_LY: f64 = 0.5;
+
+/// Configuration of the procedural starmap
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct GalaxySettings {
+    /// Generated systems fill the sphere out to this distance from Sol
+    pub radius_ly: f64,
+    /// Systems per cubic light year
+    pub density_per_ly3: f64,
+}
+
+impl Default for GalaxySettings {
+    /// About 800 systems: the starmap, route planner and command palette
+    /// walk every one of them each frame
+    fn default() -> Self {
+        Self {
+            radius_ly: 40.0,
+            density_per_ly3: LOCAL_SYSTEM_DENSITY,
+        }
+    }
+}
+
+/// Spectral class of a generated star
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StellarClass {
+    O,
+    B,
+    A,
+    F,
+    G,
+    K,
+    M,
+    /// White dwarf
+    D,
+    /// Brown dwarfs (L, T and Y)
+    L,
+    T,
+    Y,
+}
+
+impl StellarClass {
+    /// Share of system primaries per class in the solar neighbourhood
+    const FREQUENCIES: [(StellarClass, f64); 11] = [
+        (StellarClass::O, 0.000_03),
+        (StellarClass::B, 0.001),
+        (StellarClass::A, 0.006),
+        (StellarClass::F, 0.03),
+        (StellarClass::G, 0.07),
+        (StellarClass::K, 0.12),
+        (StellarClass::M, 0.68),
+        (StellarClass::D, 0.05),
+        (StellarClass::L, 0.02),
+        (StellarClass::T, 0.015),
+        (StellarClass::Y, 0.005),
+    ];
+
+    /// Order from hottest to coolest, used to pick dimmer companions
+    const ORDER: [StellarClass; 11] = [
+        StellarClass::O,
+        StellarClass::B,
+        StellarClass::A,
+        StellarClass::F,
+        StellarClass::G,
+        StellarClass::K,
+        StellarClass::M,
+        StellarClass::L,
+        StellarClass::T,
+        StellarClass::Y,
+        StellarClass::D,
+    ];
+
+    pub fn letter(&self) -> char {
+        match self {
+            StellarClass::O => 'O',
+            StellarClass::B => 'B',
+            StellarClass::A => 'A',
+            StellarClass::F => 'F',
+            StellarClass::G => 'G',
+            StellarClass::K => 'K',
+            StellarClass::M => 'M',
+            StellarClass::D => 'D',
+            StellarClass::L => 'L',
+            StellarClass::T => 'T',
+            StellarClass::Y => 'Y',
+        }
+    }
+
+    /// Fraction of systems with this primary that have a companion
+    pub fn multiplicity(&self) -> f64 {
+        match self {
+            StellarClass::O | StellarClass::B => 0.7,
+            StellarClass::A => 0.6,
+            StellarClass::F | StellarClass::G => 0.45,
+            StellarClass::K => 0.4,
+            StellarClass::M => 0.27,
+            StellarClass::D => 0.25,
+            StellarClass::L | StellarClass::T | StellarClass::Y => 0.2,
+        }
+    }
+
+    /// Typical mass in solar masses across subclasses 0 (hot) to 9 (cool)
+    fn mass_range(&self) -> (f32, f32) {
+        match self {
+            StellarClass::O => (60.0, 16.0),
+            StellarClass::B => (16.0, 2.1),
+            StellarClass::A => (2.1, 1.4),
+            StellarClass::F => (1.4, 1.04),
+            StellarClass::G => (1.04, 0.8),
+            StellarClass::K => (0.8, 0.45),
+            StellarClass::M => (0.45, 0.08),
+            StellarClass::D => (0.6, 0.6),
+            StellarClass::L => (0.08, 0.06),
+            StellarClass::T => (0.06, 0.03),
+            StellarClass::Y => (0.03, 0.01),
+        }
+    }
+
+    /// Draw a class with the neighbourhood frequencies
+    fn sample(rng: &mut impl Rng) -> Self {
+        let total: f64 = Self::FREQUENCIES.iter().map(|(_, f)| f).sum();
+        let mut roll = rng.gen::<f64>() * total;
+        for (class, frequency) in Self::FREQUENCIES {
+            if roll < frequency {
+                return class;
+            }
+            roll -= frequency;
+        }
+        StellarClass::M
+    }
+
+    /// Draw a companion no hotter than this star, weighted by frequency
+    fn sample_companion(&self, rng: &mut impl Rng) -> Self {
+        let rank = Self::ORDER.iter().position(|c| c == self).unwrap_or(0);
+        let candidates: Vec<(StellarClass, f64)> = Self::FREQUENCIES
+            .iter()
+            .filter(|(class, _)| {
+                Self::ORDER.iter().position(|c| c == class).unwrap_or(0) >= rank
+            })
+            .copied()
+            .collect();
+        let total: f64 = candidates.iter().map(|(_, f)| f).sum();
+        let mut roll = rng.gen::<f64>() * total;
+        for (class, frequency) in &candidates {
+            if roll < *frequency {
+                return *class;
+            }
+            roll -= frequency;
+        }
+        *self
+    }
+}
+
+/// One star of a generated system
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratedStar {
+    pub class: StellarClass,
+    /// Spectral type such as `"K3V"` or `"DA5"`
+    pub spectral_type: String,
+    pub mass_sol: f32,
+}
+
+impl GeneratedStar {
+    fn sample(class: StellarClass, rng: &mut impl Rng) -> Self {
+        let subclass = rng.gen_range(0..10u8);
+        let spectral_type = match class {
+            StellarClass::D => format!("DA{}", subclass),
+            StellarClass::L | StellarClass::T | StellarClass::Y => {
+                format!("{}{}", class.letter(), subclass)
+            }
+            _ => format!("{}{}V", class.letter(), subclass),
+        };
+        let (hot, cool) = class.mass_range();
+        let mass_sol = hot + (cool - hot) * subclass as f32 / 9.0;
+        Self {
+            class,
+            spectral_type,
+            mass_sol,
+        }
+    }
+}
+
+/// A procedurally generated star system
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratedSystem {
+    pub name: String,
+    /// Galactic-frame position relative to Sol in light years
+    pub position_ly: DVec3,
+    pub primary: GeneratedStar,
+    /// Second star of a binary
+    pub companion: Option<GeneratedStar>,
+    /// Mean separation of a binary in AU
+    pub separation_au: f64,
+    /// Iron abundance relative to the Sun in dex
+    pub metallicity: f32,
+}
+
+impl GeneratedSystem {
+    pub fn distance_ly(&self) -> f64 {
+        self.position_ly.length()
+    }
+}
+
+/// Generated systems, in the order their starmap ids are assigned
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ProceduralStars {
+    /// System id of the first generated system (ids below belong to Sol and
+    /// the catalog)
+    pub first_id: usize,
+    pub systems: Vec<GeneratedSystem>,
+}
+
+impl ProceduralStars {
+    pub fn get_by_id(&self, id: usize) -> Option<&GeneratedSystem> {
+        id.checked_sub(self.first_id)
+            .and_then(|index| self.systems.get(index))
+    }
+}
+
+/// Standard normal deviate (Box-Muller)
+fn normal(rng: &mut impl Rng) -> f64 {
+    let u1: f64 = rng.gen_range(f64::EPSILON..1.0);
+    let u2: f64 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+}
+
+/// Generate the systems between `inner_radius_ly` (the edge of the real
+/// catalog) and the configured radius.
+///
+/// The shell keeps generated systems from crowding the catalogued ones, so
+/// real data always wins where it exists.
+pub fn generate_systems(
+    seed: u64,
+    settings: &GalaxySettings,
+    inner_radius_ly: f64,
+) -> Vec<GeneratedSystem> {
+    let outer = settings.radius_ly;
+    if outer <= inner_radius_ly || settings.density_per_ly3 <= 0.0 {
+        return Vec::new();
+    }
+
+    let mut rng = StdRng::seed_from_u64(seed ^ GALAXY_SEED_SALT);
+    let volume = 4.0 / 3.0 * PI * (outer.powi(3) - inner_radius_ly.powi(3));
+    let count = (volume * settings.density_per_ly3).round() as usize;
+
+    let mut positions: Vec<DVec3> = Vec::with_capacity(count);
+    let mut systems = Vec::with_capacity(count);
+    let mut attempts = 0;
+    while systems.len() < count && attempts < count * 4 {
+        attempts += 1;
+
+        // Uniform in the shell: radius from the cube-root of a uniform volume
+        let (r3_min, r3_max) = (inner_radius_ly.powi(3), outer.powi(3));
+        let r = rng.gen_range(r3_min..r3_max).cbrt();
+        let cos_theta: f64 = rng.gen_range(-1.0..1.0);
+        let phi: f64 = rng.gen_range(0.0..2.0 * PI);
+        let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+        let position = DVec3::new(
+            r * sin_theta * phi.cos(),
+            r * cos_theta,
+            r * sin_theta * phi.sin(),
+        );
+
+        // Metallicity and class are drawn before the spacing check so a
+        // rejected position does not shift the stream of accepted systems
+        let primary = GeneratedStar::sample(StellarClass::sample(&mut rng), &mut rng);
+        let binary = rng.gen_bool(primary.class.multiplicity());
+        let companion_class = primary.class.sample_companion(&mut rng);
+        // Log-normal separations peaking around 40 AU
+        let separation_au = 10f64.powf(1.6 + 1.2 * normal(&mut rng)).clamp(0.05, 10_000.0);
+        let metallicity = (-0.05 + 0.2 * normal(&mut rng)).clamp(-1.5, 0.6) as f32;
+
+        if positions
+            .iter()
+            .any(|p| p.distance_squared(position) < MIN_SEPARATION_LY * MIN_SEPARATION_LY)
+        {
+            continue;
+        }
+
+        let companion = binary.then(|| GeneratedStar::sample(companion_class, &mut rng));
+        positions.push(position);
+        systems.push(GeneratedSystem {
+            name: String::new(),
+            position_ly: position,
+            primary,
+            companion,
+            separation_au: if binary { separation_au } else { 0.0 },
+            metallicity,
+        });
+    }
+
+    // Nearest first, so starmap ids and catalog numbers grow with distance
+    systems.sort_by(|a, b| a.distance_ly().total_cmp(&b.distance_ly()));
+    for (i, system) in systems.iter_mut().enumerate() {
+        let suffix = if system.companion.is_some() { " AB" } else { "" };
+        system.name = format!("HSC {:05}{}", i + 1, suffix);
+    }
+    systems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(radius_ly: f64) -> GalaxySettings {
+        GalaxySettings {
+            radius_ly,
+            ..default()
+        }
+    }
+
+    #[test]
+    fn test_generation_is_deterministic() {
+        let a = generate_systems(42, &settings(40.0), 16.0);
+        let b = generate_systems(42, &settings(40.0), 16.0);
+        let c = generate_systems(43, &settings(40.0), 16.0);
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_systems_fill_the_shell_outside_the_catalog() {
+        let systems = generate_systems(7, &settings(40.0), 16.0);
+        let expected = 4.0 / 3.0 * PI * (40f64.powi(3) - 16f64.powi(3)) * LOCAL_SYSTEM_DENSITY;
+        assert!((systems.len() as f64) > expected * 0.95);
+        assert!(systems
+            .iter()
+            .all(|s| (16.0..=40.0).contains(&s.distance_ly())));
+        assert!(generate_systems(7, &settings(10.0), 16.0).is_empty());
+    }
+
+    #[test]
+    fn test_spectral_distribution_is_dominated_by_red_dwarfs() {
+        let systems = generate_systems(1, &settings(60.0), 16.0);
+        let share = |class: StellarClass| {
+            systems.iter().filter(|s| s.primary.class == class).count() as f64
+                / systems.len() as f64
+        };
+        assert!(share(StellarClass::M) > 0.6);
+        assert!(share(StellarClass::G) < 0.1);
+        assert!(share(StellarClass::M) > share(StellarClass::K));
+
+        let binaries = systems.iter().filter(|s| s.companion.is_some()).count() as f64;
+        let fraction = binaries / systems.len() as f64;
+        assert!((0.2..0.45).contains(&fraction));
+        for system in systems.iter().filter(|s| s.companion.is_some()) {
+            assert!(system.name.ends_with(" AB"));
+            assert!(system.separation_au > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_lookup_by_system_id() {
+        let stars = ProceduralStars {
+            first_id: 51,
+            systems: generate_systems(5, &settings(30.0), 16.0),
+        };
+        assert!(stars.get_by_id(50).is_none());
+        assert_eq!(stars.get_by_id(51), stars.systems.first());
+        assert!(stars.get_by_id(51 + stars.systems.len()).is_none());
+    }
+
+    #[test]
+    fn test_companions_are_not_hotter_than_primaries() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..200 {
+            let companion = StellarClass::K.sample_companion(&mut rng);
+            assert!(!matches!(
+                companion,
+                StellarClass::O
+                    | StellarClass::B
+                    | StellarClass::A
+                    | StellarClass::F
+                    | StellarClass::G
+            ));
+        }
+    }
+}
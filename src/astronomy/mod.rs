@@ -7,34 +7,61 @@
 //! - KeplerOrbit: Standard orbital elements for elliptical orbits
 //! - Kepler solver: Newton-Raphson solver for orbit propagation
 //! - Floating origin: Conversion from simulation to rendering coordinates
+//! - Stellar evolution: stars brightening over deep time and red dwarf flares
+//! - Comet influx: long-period comets falling in from the stars' clouds
 
 use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
 
+pub mod comet_influx;
 pub mod components;
 pub mod ephemeris;
 pub mod exoplanets;
+pub mod galaxy;
+pub mod multiple_stars;
 pub mod nearby_stars;
 pub mod procedural;
+pub mod rotation;
+pub mod spatial_index;
+pub mod stellar_evolution;
 pub mod systems;
+pub mod trajectory;
+pub mod visual_scale;
 
+pub use comet_influx::{CometDiscovered, CometInfluxPlugin, CometReservoir, VisitingComet};
 pub use components::{
-    AtmosphereComposition, AtmosphericGas, CometTail, Destroyed, FloatingOrigin, Hovered,
-    KeplerOrbit, LocalOrbitAmplification, OrbitCenter, OrbitPath, Selected, SpaceCoordinates,
-    SurfaceTemperature, calculate_general_colony_cost,
+    AtmosphereComposition, AtmosphericGas, BodyLod, CometActivity, CometTail, Destroyed, Dormant,
+    FloatingOrigin, Hovered, KeplerOrbit, LocalOrbitAmplification, OrbitCenter, OrbitPath,
+    OrbitSamples, Selected, SpaceCoordinates, StellarLuminosity, SurfaceTemperature,
+    calculate_general_colony_cost, habitat_class, HabitatClass,
 };
 pub use ephemeris::{calculate_position_for_body, calculate_positions_at_timestamp};
-pub use exoplanets::{ConfirmedPlanet, RealPlanet};
+pub use exoplanets::{ConfirmedPlanet, ExoplanetDiscovery, RealPlanet};
+pub use galaxy::{GalaxySettings, GeneratedSystem, ProceduralStars};
 pub use procedural::{
-    calculate_frost_line, map_star_to_system_architecture, AsteroidBelt, CometaryCloud, PlanetType,
-    ProceduralPlanet, SystemArchitecture,
+    calculate_frost_line, map_star_to_system_architecture, resonant_populations, AsteroidBelt,
+    BeltPopulation, CometaryCloud, PlanetType, ProceduralBody, ProceduralPlanet,
+    SystemArchitecture, AU_M, EARTH_MASS_KG, G, SOLAR_MASS_KG,
 };
+pub use rotation::{update_local_days, LocalDay, SECONDS_PER_DAY};
+pub use spatial_index::{update_spatial_index, SpatialEntry, SpatialIndex};
+pub use stellar_evolution::{
+    evolve_stars, habitable_zone, roll_stellar_flares, StellarEvolution,
+    StellarEvolutionPlugin, StellarEvolutionSettings,
+};
+pub use trajectory::{draw_trajectory_ghosts, TrajectorySettings};
+pub use visual_scale::{animate_visual_scale, apply_visual_scale, VisualScale};
 pub use systems::{
-    animate_marker_dots, check_natural_destruction, despawn_hover_markers,
-    despawn_selection_markers, draw_orbit_paths, fade_destroyed_bodies, handle_body_hover,
-    handle_body_selection, manage_comet_tail_meshes, orbit_position_from_mean_anomaly,
-    propagate_orbits, scale_markers_with_zoom, spawn_hover_markers, spawn_selection_markers,
-    update_body_lod_visibility, update_orbit_visibility, update_render_transform,
-    update_tail_transforms, zoom_camera_to_anchored_body, SCALING_FACTOR,
+    animate_marker_dots, check_natural_destruction, comet_tail_activity, comet_tail_scale,
+    despawn_hover_markers, despawn_selection_markers, draw_lod_markers,
+    fade_destroyed_bodies, handle_body_hover, handle_body_selection, manage_comet_tail_meshes,
+    floating_origin_rebase_offset, interpolate_orbit_offset, interpolate_orbit_positions,
+    orbit_offset_at, orbit_position_from_mean_anomaly, propagate_orbits,
+    rebase_floating_origin, scale_markers_with_zoom,
+    spawn_hover_markers, spawn_selection_markers, update_body_lod_visibility,
+    update_orbit_visibility, update_render_transform, update_tail_transforms,
+    zoom_camera_to_anchored_body, DORMANT_SAMPLE_SECONDS, SCALING_FACTOR,
+    SIMULATION_TICK_HZ,
 };
 
 /// Plugin that adds astronomy systems to the Bevy app
@@ -42,19 +69,38 @@ pub struct AstronomyPlugin;
 
 impl Plugin for AstronomyPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(nearby_stars::NearbyStarsPlugin)
+        app.add_plugins((
+            nearby_stars::NearbyStarsPlugin,
+            StellarEvolutionPlugin,
+            CometInfluxPlugin,
+        ))
+            .init_resource::<SpatialIndex>()
+            .init_resource::<VisualScale>()
+            .insert_resource(Time::<Fixed>::from_hz(SIMULATION_TICK_HZ))
+            .add_systems(FixedUpdate, propagate_orbits)
+            .add_systems(
+                Update,
+                (animate_visual_scale, apply_visual_scale)
+                    .chain()
+                    .before(update_render_transform),
+            )
+            .add_systems(
+                PostUpdate,
+                update_spatial_index.after(bevy::transform::TransformSystem::TransformPropagate),
+            )
             .add_systems(
                 Update,
                 (
-                    // Core orbital mechanics
-                    propagate_orbits,
-                    update_render_transform.after(propagate_orbits),
+                    // Core orbital mechanics (propagation itself runs in FixedUpdate)
+                    interpolate_orbit_positions,
+                    rebase_floating_origin.before(update_render_transform),
+                    update_render_transform.after(interpolate_orbit_positions),
                     // Destruction and lifecycle
-                    check_natural_destruction.after(propagate_orbits),
+                    check_natural_destruction.after(interpolate_orbit_positions),
                     fade_destroyed_bodies.after(check_natural_destruction),
-                    // Selection and hover
-                    handle_body_selection,
-                    handle_body_hover,
+                    // Selection and hover (egui pointer input, so only with a window)
+                    (handle_body_selection, handle_body_hover)
+                        .run_if(any_with_component::<PrimaryWindow>),
                     // Selection/hover markers
                     spawn_selection_markers,
                     despawn_selection_markers,
@@ -66,13 +112,17 @@ impl Plugin for AstronomyPlugin {
                     zoom_camera_to_anchored_body,
                     // Visibility / LOD
                     update_orbit_visibility,
-                    update_body_lod_visibility,
-                    // Rendering
-                    draw_orbit_paths.after(update_orbit_visibility),
+                    (update_body_lod_visibility, draw_lod_markers)
+                        .chain()
+                        .after(update_render_transform),
+                    // Rendering (orbit paths are cached meshes, see render::orbit_lines)
+                    draw_trajectory_ghosts.after(update_render_transform),
                     
                     // Comet Visuals
                     manage_comet_tail_meshes,
-                    update_tail_transforms.after(propagate_orbits),
+                    update_tail_transforms.after(interpolate_orbit_positions),
+                    // Day length of newly spinning bodies
+                    update_local_days,
                 ),
             );
     }
@@ -0,0 +1,400 @@
+//! Orbital hierarchy of binary and multiple star systems
+//!
+//! The catalog lists the stars of a system and the binary orbits between
+//! them. [`StarHierarchy`] nests those orbits tightest first, so a close pair
+//! can itself orbit a third star (EZ Aquarii) or a distant pair can be a wide
+//! companion of a single star (Epsilon Indi). Each pair orbits its own
+//! barycenter.
+//!
+//! Planets are only stable in two regions of a binary (Holman & Wiegert 1999):
+//! close to one star (S-type, circumstellar) or far outside both
+//! (P-type, circumbinary). [`planet_zone`] classifies a semi-major axis into
+//! the hierarchy node a planet there would orbit.
+
+use std::f64::consts::PI;
+
+use bevy::math::DVec3;
+
+use super::components::KeplerOrbit;
+use super::nearby_stars::{BinaryOrbitData, StarData};
+use crate::economy::SECONDS_PER_YEAR;
+
+/// Distance at which companions without a catalogued orbit are placed
+pub const WIDE_COMPANION_SEPARATION_AU: f64 = 1000.0;
+
+/// One node of the hierarchy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HierarchyNode {
+    /// A single star, by index into the system's star list
+    Star(usize),
+    /// Two nodes orbiting their common barycenter along `orbit` (index into
+    /// the system's binary orbits)
+    Pair {
+        orbit: usize,
+        primary: usize,
+        secondary: usize,
+    },
+}
+
+/// Nested binary orbits of a star system
+#[derive(Debug, Clone, PartialEq)]
+pub struct StarHierarchy {
+    /// Star nodes first (node `i` is star `i`), then pairs from tightest to
+    /// widest
+    pub nodes: Vec<HierarchyNode>,
+    /// Top-level nodes: the main body of the system first, then companions
+    /// with no catalogued orbit
+    pub roots: Vec<usize>,
+    parents: Vec<Option<usize>>,
+}
+
+impl StarHierarchy {
+    /// Nest the binary orbits of a system, tightest first. Orbits with
+    /// invalid indices or joining stars that are already bound together are
+    /// skipped.
+    pub fn build(star_count: usize, orbits: &[BinaryOrbitData]) -> Self {
+        let mut nodes: Vec<HierarchyNode> = (0..star_count).map(HierarchyNode::Star).collect();
+        let mut parents: Vec<Option<usize>> = vec![None; star_count];
+
+        let mut order: Vec<usize> = (0..orbits.len()).collect();
+        order.sort_by(|a, b| {
+            orbits[*a]
+                .semi_major_axis_au
+                .total_cmp(&orbits[*b].semi_major_axis_au)
+        });
+
+        let top = |parents: &[Option<usize>], mut node: usize| {
+            while let Some(parent) = parents[node] {
+                node = parent;
+            }
+            node
+        };
+
+        for index in order {
+            let orbit = &orbits[index];
+            if orbit.primary_idx >= star_count || orbit.secondary_idx >= star_count {
+                continue;
+            }
+            let primary = top(&parents, orbit.primary_idx);
+            let secondary = top(&parents, orbit.secondary_idx);
+            if primary == secondary {
+                continue;
+            }
+            let pair = nodes.len();
+            nodes.push(HierarchyNode::Pair {
+                orbit: index,
+                primary,
+                secondary,
+            });
+            parents.push(None);
+            parents[primary] = Some(pair);
+            parents[secondary] = Some(pair);
+        }
+
+        let mut roots: Vec<usize> = (0..nodes.len()).filter(|n| parents[*n].is_none()).collect();
+        // The node holding the first (primary) star anchors the system
+        if star_count > 0 {
+            let main = top(&parents, 0);
+            roots.retain(|n| *n != main);
+            roots.insert(0, main);
+        }
+
+        Self {
+            nodes,
+            roots,
+            parents,
+        }
+    }
+
+    /// Pair node a node orbits in, if any
+    pub fn parent(&self, node: usize) -> Option<usize> {
+        self.parents.get(node).copied().flatten()
+    }
+
+    /// The other half of the pair `node` belongs to
+    pub fn sibling(&self, node: usize) -> Option<usize> {
+        match self.nodes[self.parent(node)?] {
+            HierarchyNode::Pair {
+                primary, secondary, ..
+            } => Some(if primary == node { secondary } else { primary }),
+            HierarchyNode::Star(_) => None,
+        }
+    }
+
+    /// Indices of the stars below a node
+    pub fn stars_in(&self, node: usize) -> Vec<usize> {
+        match self.nodes[node] {
+            HierarchyNode::Star(star) => vec![star],
+            HierarchyNode::Pair {
+                primary, secondary, ..
+            } => {
+                let mut stars = self.stars_in(primary);
+                stars.extend(self.stars_in(secondary));
+                stars
+            }
+        }
+    }
+
+    /// Total mass below a node in solar masses
+    pub fn mass_sol(&self, node: usize, stars: &[StarData]) -> f64 {
+        self.stars_in(node)
+            .iter()
+            .map(|s| stars[*s].mass_sol as f64)
+            .sum()
+    }
+
+    /// Combined luminosity below a node in solar luminosities
+    pub fn luminosity_sol(&self, node: usize, stars: &[StarData]) -> f64 {
+        self.stars_in(node)
+            .iter()
+            .map(|s| stars[*s].luminosity_sol as f64)
+            .sum()
+    }
+}
+
+/// Outer edge of stable circumstellar (S-type) orbits around a star of a
+/// binary, in AU. `host` and `companion` are the masses of the two
+/// components.
+pub fn s_type_limit_au(binary_sma_au: f64, eccentricity: f64, host: f64, companion: f64) -> f64 {
+    let mu = companion / (host + companion);
+    let e = eccentricity;
+    let ratio = 0.464 - 0.380 * mu - 0.631 * e + 0.586 * mu * e + 0.150 * e * e
+        - 0.198 * mu * e * e;
+    binary_sma_au * ratio.max(0.0)
+}
+
+/// Inner edge of stable circumbinary (P-type) orbits around a binary, in AU
+pub fn p_type_limit_au(binary_sma_au: f64, eccentricity: f64, primary: f64, secondary: f64) -> f64 {
+    let mu = secondary.min(primary) / (primary + secondary);
+    let e = eccentricity;
+    let ratio = 1.60 + 5.10 * e - 2.22 * e * e + 4.12 * mu - 4.27 * e * mu - 5.09 * mu * mu
+        + 4.61 * e * e * mu * mu;
+    binary_sma_au * ratio
+}
+
+/// Where a planet at a given distance from a star can orbit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanetZone {
+    /// Stable around this hierarchy node: a star (S-type) or a pair
+    /// barycenter (P-type)
+    Stable(usize),
+    /// Inside the gap between the two stable regions of a binary
+    Unstable,
+}
+
+/// Classify a planet orbiting the hierarchy node `host` (a star or a pair)
+/// at `semi_major_axis_au`. Orbits too wide to stay around the host move out
+/// to the enclosing pair if they clear its circumbinary limit.
+pub fn planet_zone(
+    hierarchy: &StarHierarchy,
+    stars: &[StarData],
+    orbits: &[BinaryOrbitData],
+    host: usize,
+    semi_major_axis_au: f64,
+) -> PlanetZone {
+    if let HierarchyNode::Pair {
+        orbit,
+        primary,
+        secondary,
+    } = hierarchy.nodes[host]
+    {
+        let binary = &orbits[orbit];
+        let limit = p_type_limit_au(
+            binary.semi_major_axis_au,
+            binary.eccentricity,
+            hierarchy.mass_sol(primary, stars),
+            hierarchy.mass_sol(secondary, stars),
+        );
+        if semi_major_axis_au <= limit {
+            return PlanetZone::Unstable;
+        }
+    }
+
+    let mut node = host;
+    while let Some(pair) = hierarchy.parent(node) {
+        let HierarchyNode::Pair {
+            orbit,
+            primary,
+            secondary,
+        } = hierarchy.nodes[pair]
+        else {
+            break;
+        };
+        let binary = &orbits[orbit];
+        let sibling = if primary == node { secondary } else { primary };
+        let host_mass = hierarchy.mass_sol(node, stars);
+        let companion_mass = hierarchy.mass_sol(sibling, stars);
+
+        let s_limit = s_type_limit_au(
+            binary.semi_major_axis_au,
+            binary.eccentricity,
+            host_mass,
+            companion_mass,
+        );
+        if semi_major_axis_au < s_limit {
+            return PlanetZone::Stable(node);
+        }
+        let p_limit = p_type_limit_au(
+            binary.semi_major_axis_au,
+            binary.eccentricity,
+            host_mass,
+            companion_mass,
+        );
+        if semi_major_axis_au <= p_limit {
+            return PlanetZone::Unstable;
+        }
+        node = pair;
+    }
+    PlanetZone::Stable(node)
+}
+
+/// Static offset in AU from the system's position of the `index`th root of
+/// the hierarchy, for companions without a catalogued orbit (like Proxima)
+pub fn wide_companion_offset(index: usize) -> DVec3 {
+    DVec3::new(WIDE_COMPANION_SEPARATION_AU * index as f64, 0.0, 0.0)
+}
+
+/// Barycentric orbits of the two halves of a pair: each follows the binary
+/// orbit scaled by the other's share of the mass, on opposite sides.
+/// `node_angle` and `mean_anomaly` orient and phase the pair.
+pub fn pair_orbits(
+    binary: &BinaryOrbitData,
+    primary_mass: f64,
+    secondary_mass: f64,
+    node_angle: f64,
+    mean_anomaly: f64,
+) -> (KeplerOrbit, KeplerOrbit) {
+    let total_mass = primary_mass + secondary_mass;
+    let mean_motion = 2.0 * PI / (binary.period_years * SECONDS_PER_YEAR);
+    let inclination = binary.inclination_deg.to_radians();
+    let periapsis = binary.arg_periastron_deg.to_radians();
+
+    let orbit = |semi_major_axis: f64, argument_of_periapsis: f64| KeplerOrbit {
+        eccentricity: binary.eccentricity,
+        semi_major_axis,
+        inclination,
+        longitude_ascending_node: node_angle,
+        argument_of_periapsis,
+        mean_anomaly_epoch: mean_anomaly,
+        mean_motion,
+    };
+    (
+        orbit(binary.semi_major_axis_au * secondary_mass / total_mass, periapsis + PI),
+        orbit(binary.semi_major_axis_au * primary_mass / total_mass, periapsis),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn star(mass_sol: f32, luminosity_sol: f32) -> StarData {
+        StarData {
+            name: "Test".to_string(),
+            spectral_type: "M5V".to_string(),
+            mass_sol,
+            radius_sol: 0.2,
+            temp_k: 3000.0,
+            luminosity_sol,
+            metallicity: None,
+            planets: Vec::new(),
+        }
+    }
+
+    fn binary(primary_idx: usize, secondary_idx: usize, sma: f64, e: f64) -> BinaryOrbitData {
+        BinaryOrbitData {
+            label: "AB".to_string(),
+            primary_idx,
+            secondary_idx,
+            semi_major_axis_au: sma,
+            period_years: 10.0,
+            eccentricity: e,
+            inclination_deg: 0.0,
+            arg_periastron_deg: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_hierarchy_nests_tightest_orbits_first() {
+        // EZ Aquarii: A and C are a 0.03 AU pair that B orbits at 1.18 AU
+        let orbits = vec![binary(0, 2, 0.03, 0.0), binary(0, 1, 1.18, 0.44)];
+        let hierarchy = StarHierarchy::build(3, &orbits);
+        assert_eq!(
+            hierarchy.nodes[3],
+            HierarchyNode::Pair {
+                orbit: 0,
+                primary: 0,
+                secondary: 2
+            }
+        );
+        assert_eq!(
+            hierarchy.nodes[4],
+            HierarchyNode::Pair {
+                orbit: 1,
+                primary: 3,
+                secondary: 1
+            }
+        );
+        assert_eq!(hierarchy.roots, vec![4]);
+        assert_eq!(hierarchy.sibling(1), Some(3));
+        assert_eq!(hierarchy.stars_in(4), vec![0, 2, 1]);
+    }
+
+    #[test]
+    fn test_unbound_stars_become_wide_companions() {
+        // Epsilon Indi: the brown dwarf pair has no orbit around A
+        let hierarchy = StarHierarchy::build(3, &[binary(1, 2, 2.1, 0.27)]);
+        assert_eq!(hierarchy.roots, vec![0, 3]);
+        assert_eq!(hierarchy.parent(0), None);
+    }
+
+    #[test]
+    fn test_stability_limits() {
+        // Equal-mass circular binary: S-type inside ~0.27a, P-type beyond ~2.4a
+        let s = s_type_limit_au(10.0, 0.0, 1.0, 1.0);
+        let p = p_type_limit_au(10.0, 0.0, 1.0, 1.0);
+        assert!((s - 2.74).abs() < 0.01);
+        assert!((p - 23.87).abs() < 0.01);
+        // Eccentricity shrinks the S-type region and widens the gap
+        assert!(s_type_limit_au(10.0, 0.5, 1.0, 1.0) < s);
+        assert!(p_type_limit_au(10.0, 0.5, 1.0, 1.0) > p);
+    }
+
+    #[test]
+    fn test_planet_zone_classification() {
+        let stars = vec![star(1.0, 1.0), star(1.0, 1.0)];
+        let orbits = vec![binary(0, 1, 10.0, 0.0)];
+        let hierarchy = StarHierarchy::build(2, &orbits);
+        assert_eq!(
+            planet_zone(&hierarchy, &stars, &orbits, 0, 1.0),
+            PlanetZone::Stable(0)
+        );
+        assert_eq!(
+            planet_zone(&hierarchy, &stars, &orbits, 1, 10.0),
+            PlanetZone::Unstable
+        );
+        assert_eq!(
+            planet_zone(&hierarchy, &stars, &orbits, 0, 40.0),
+            PlanetZone::Stable(2)
+        );
+        // Circumbinary hosts reject orbits inside the gap
+        assert_eq!(
+            planet_zone(&hierarchy, &stars, &orbits, 2, 10.0),
+            PlanetZone::Unstable
+        );
+        assert_eq!(
+            planet_zone(&hierarchy, &stars, &orbits, 2, 40.0),
+            PlanetZone::Stable(2)
+        );
+        assert!((hierarchy.luminosity_sol(2, &stars) - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_pair_orbits_split_by_mass() {
+        let (primary, secondary) = pair_orbits(&binary(0, 1, 12.0, 0.3), 2.0, 1.0, 0.5, 0.0);
+        assert!((primary.semi_major_axis - 4.0).abs() < 1e-9);
+        assert!((secondary.semi_major_axis - 8.0).abs() < 1e-9);
+        assert_eq!(primary.mean_motion, secondary.mean_motion);
+        assert!((primary.argument_of_periapsis - secondary.argument_of_periapsis - PI).abs() < 1e-9);
+    }
+}
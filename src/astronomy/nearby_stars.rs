@@ -1,7 +1,9 @@
 use bevy::prelude::*;
 use serde::Deserialize;
 use std::fs;
-use std::path::Path;
+use std::path::PathBuf;
+
+use crate::modding::ModList;
 
 pub struct NearbyStarsPlugin;
 
@@ -72,6 +74,14 @@ pub struct PlanetData {
     /// Index of the star this planet orbits (0 = first star, 1 = second, etc.)
     #[serde(default)]
     pub orbits_star: usize,
+    /// Orbital inclination in degrees, when measured
+    #[serde(default)]
+    pub inclination_deg: Option<f64>,
+    /// Discovery method (e.g. "Radial Velocity", "Transit")
+    #[serde(default)]
+    pub discovery_method: Option<String>,
+    #[serde(default)]
+    pub discovery_year: Option<u16>,
 }
 
 /// Binary star orbital relationship
@@ -97,16 +107,112 @@ pub struct BinaryOrbitData {
     pub arg_periastron_deg: f64,
 }
 
-fn load_nearby_stars_data(mut stars_data: ResMut<NearbyStarsData>) {
-    let path = Path::new("assets/data/nearest_stars_raw.json");
-    match fs::read_to_string(path) {
-        Ok(content) => match serde_json::from_str::<Vec<StarSystemData>>(&content) {
-            Ok(data) => {
-                info!("Loaded data for {} nearby star systems.", data.len());
-                stars_data.systems = data;
+/// Nearby star catalogue file
+pub const NEARBY_STARS_PATH: &str = "assets/data/nearest_stars_raw.json";
+
+/// Planets and binary orbits pointing at stars their system does not have
+pub fn unknown_star_references(systems: &[StarSystemData]) -> Vec<String> {
+    let mut unknown = Vec::new();
+    for system in systems {
+        let count = system.stars.len();
+        for planet in system.stars.iter().flat_map(|star| &star.planets) {
+            if planet.orbits_star >= count {
+                unknown.push(format!(
+                    "{}: planet '{}' orbits star {} of {}",
+                    system.system_name, planet.name, planet.orbits_star, count
+                ));
+            }
+        }
+        for orbit in &system.binary_orbits {
+            if orbit.primary_idx >= count || orbit.secondary_idx >= count {
+                unknown.push(format!(
+                    "{}: binary orbit '{}' pairs stars {} and {} of {}",
+                    system.system_name,
+                    orbit.label,
+                    orbit.primary_idx,
+                    orbit.secondary_idx,
+                    count
+                ));
             }
-            Err(e) => error!("Failed to parse nearby stars data: {}", e),
-        },
-        Err(e) => warn!("Could not read nearby stars data file: {}", e),
+        }
+    }
+    unknown
+}
+
+/// Replace the systems with the same name and append the new ones, so the
+/// ids of catalogue systems stay the same
+pub fn merge_star_systems(systems: &mut Vec<StarSystemData>, other: Vec<StarSystemData>) {
+    for system in other {
+        match systems
+            .iter_mut()
+            .find(|s| s.system_name == system.system_name)
+        {
+            Some(existing) => *existing = system,
+            None => systems.push(system),
+        }
+    }
+}
+
+/// Read the nearby star catalogue followed by the star systems of enabled
+/// mods. Files that can't be read or parsed are skipped and reported in the
+/// returned errors.
+pub fn read_nearby_stars(mods: Option<&ModList>) -> (Vec<StarSystemData>, Vec<String>) {
+    let mut systems = Vec::new();
+    let mut errors = Vec::new();
+
+    let mut paths = vec![PathBuf::from(NEARBY_STARS_PATH)];
+    paths.extend(mods.map_or(Vec::new(), |mods| mods.data_files("nearest_stars_raw.json")));
+    for path in paths {
+        match fs::read_to_string(&path) {
+            Ok(content) => match serde_json::from_str::<Vec<StarSystemData>>(&content) {
+                Ok(data) => merge_star_systems(&mut systems, data),
+                Err(e) => errors.push(format!("Failed to parse {}: {}", path.display(), e)),
+            },
+            Err(e) => errors.push(format!("Failed to read {}: {}", path.display(), e)),
+        }
+    }
+
+    (systems, errors)
+}
+
+fn load_nearby_stars_data(mut stars_data: ResMut<NearbyStarsData>, mods: Option<Res<ModList>>) {
+    let (data, errors) = read_nearby_stars(mods.as_deref());
+    for e in errors {
+        error!("{}", e);
+    }
+    info!("Loaded data for {} nearby star systems.", data.len());
+    for reference in unknown_star_references(&data) {
+        warn!("Nearby stars data: {}", reference);
+    }
+    stars_data.systems = data;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn system(name: &str, distance_ly: f32) -> StarSystemData {
+        StarSystemData {
+            system_name: name.to_string(),
+            distance_ly,
+            stars: Vec::new(),
+            binary_orbits: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_mod_systems_replace_by_name_and_append() {
+        let mut systems = vec![
+            system("Alpha Centauri", 4.37),
+            system("Barnard's Star", 5.96),
+        ];
+        merge_star_systems(
+            &mut systems,
+            vec![system("Alpha Centauri", 4.4), system("Kepler-452", 1800.0)],
+        );
+
+        let names: Vec<&str> = systems.iter().map(|s| s.system_name.as_str()).collect();
+        assert_eq!(names, ["Alpha Centauri", "Barnard's Star", "Kepler-452"]);
+        assert_eq!(systems[0].distance_ly, 4.4);
     }
 }
@@ -6,11 +6,47 @@
 
 use bevy::prelude::*;
 use rand::prelude::*;
+use rand::rngs::StdRng;
+use std::collections::hash_map::DefaultHasher;
 use std::f64::consts::PI;
+use std::hash::{Hash, Hasher};
 
 use super::components::KeplerOrbit;
+use super::rotation::SECONDS_PER_DAY;
+use super::visual_scale::AU_KM;
+use crate::construction::LagrangePoint;
+use crate::plugins::solar_system::Ring;
 use crate::plugins::solar_system_data::BodyType;
 
+/// Mass of the Earth in kg, the unit of planet masses
+pub const EARTH_MASS_KG: f64 = 5.972e24;
+/// Mass of the Sun in kg
+pub const SOLAR_MASS_KG: f64 = 1.989e30;
+/// Gravitational constant in m³/(kg⋅s²)
+pub const G: f64 = 6.674e-11;
+/// Astronomical unit in metres
+pub const AU_M: f64 = AU_KM * 1000.0;
+
+/// Chance that a gas or ice giant carries a ring system
+pub const GIANT_RING_CHANCE: f64 = 0.35;
+
+/// Giants from this mass on clear a Hilda family at the 3:2 resonance
+/// inside their orbit, like Jupiter
+pub const HILDA_MIN_MASS_EARTH: f32 = 50.0;
+/// Largest eccentricity of Trojans; resonant families reach twice that
+const TROJAN_MAX_ECCENTRICITY: f64 = 0.15;
+
+/// Share of a giant's mass that ends up in its regular moons (Jupiter's
+/// Galilean moons and Saturn's Titan all come to about 1e-4)
+pub const REGULAR_MOON_MASS_RATIO: f64 = 1e-4;
+/// Regular moons form inside this share of the planet's Hill sphere
+pub const REGULAR_MOON_HILL_FRACTION: f64 = 0.05;
+/// Captured moons stay bound out to about half the Hill sphere
+pub const CAPTURED_MOON_HILL_FRACTION: f64 = 0.5;
+/// Period ratios of neighbouring regular moons, like the 2:1 Laplace chain
+/// of Io, Europa and Ganymede and Titan and Hyperion's 4:3
+const MOON_RESONANCES: [f64; 3] = [2.0, 1.5, 4.0 / 3.0];
+
 /// System architecture parameters for a star system
 /// Defines the structure of rocky planets, gas giants, belts, and clouds
 #[derive(Debug, Clone)]
@@ -68,6 +104,11 @@ pub struct ProceduralPlanet {
     pub planet_type: PlanetType,
 }
 
+/// Marker for bodies invented by the generator rather than taken from a
+/// catalog, so the UI can tell them apart from real ones
+#[derive(Component, Debug, Clone, Copy)]
+pub struct ProceduralBody;
+
 /// Type of procedurally generated planet
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PlanetType {
@@ -90,6 +131,46 @@ pub struct AsteroidBelt {
 
     /// Average inclination of the belt in radians
     pub inclination: f64,
+
+    /// How the members are spread along their orbits
+    pub population: BeltPopulation,
+}
+
+/// How the members of an asteroid belt are spread around their star
+#[derive(Debug, Clone, Copy, Default)]
+pub enum BeltPopulation {
+    /// A uniform annulus between the belt's edges, like the main belt
+    #[default]
+    Annulus,
+    /// A Trojan swarm sharing a planet's orbit, librating around its L4 or
+    /// L5 point
+    Trojan {
+        planet: KeplerOrbit,
+        point: LagrangePoint,
+    },
+    /// A family in mean-motion resonance with a planet, going round `orbits`
+    /// times while the planet goes round `planet_orbits` times: the Hildas
+    /// at 3:2 inside Jupiter, the plutinos at 2:3 and twotinos at 1:2
+    /// outside Neptune
+    Resonant {
+        planet: KeplerOrbit,
+        orbits: u32,
+        planet_orbits: u32,
+    },
+}
+
+impl BeltPopulation {
+    /// Period of the members over that of their planet
+    pub fn period_ratio(&self) -> f64 {
+        match *self {
+            Self::Annulus | Self::Trojan { .. } => 1.0,
+            Self::Resonant {
+                orbits,
+                planet_orbits,
+                ..
+            } => planet_orbits as f64 / orbits as f64,
+        }
+    }
 }
 
 /// Cometary cloud configuration
@@ -403,6 +484,7 @@ fn generate_asteroid_belt(
         outer_au: outer,
         count: rng.gen_range(50..200), // Number of asteroids to spawn
         inclination: rng.gen_range(0.0..0.1), // Low to moderate inclination
+        population: BeltPopulation::Annulus,
     }
 }
 
@@ -461,7 +543,6 @@ impl ProceduralPlanet {
 
     /// Calculate mass in kilograms
     pub fn mass_kg(&self) -> f64 {
-        const EARTH_MASS_KG: f64 = 5.972e24;
         (self.mass_earth as f64) * EARTH_MASS_KG
     }
 
@@ -472,6 +553,263 @@ impl ProceduralPlanet {
     }
 }
 
+/// Decide whether a giant planet has rings and generate them, returning the
+/// ring and its mass in kg. The outcome depends only on the planet's name, so
+/// the data entity and the visual of the same planet agree.
+pub fn ring_for_giant(
+    planet_name: &str,
+    planet_radius_km: f32,
+    beyond_frost_line: bool,
+) -> Option<(Ring, f64)> {
+    let mut hasher = DefaultHasher::new();
+    planet_name.hash(&mut hasher);
+    let mut rng = StdRng::seed_from_u64(hasher.finish());
+    if !rng.gen_bool(GIANT_RING_CHANCE) {
+        return None;
+    }
+
+    // Rings survive inside the Roche limit, roughly 1.2 to 2.5 planet radii.
+    // Beyond the frost line they are mostly ice, closer in mostly rock dust.
+    let ring = Ring {
+        inner_radius_km: planet_radius_km * rng.gen_range(1.2..1.6),
+        outer_radius_km: planet_radius_km * rng.gen_range(1.8..2.5),
+        ice_fraction: if beyond_frost_line {
+            rng.gen_range(0.6..0.98)
+        } else {
+            rng.gen_range(0.05..0.3)
+        },
+    };
+    let mass_kg = 10f64.powf(rng.gen_range(14.0..19.5));
+    Some((ring, mass_kg))
+}
+
+/// A moon generated for a giant planet, on an orbit around the planet
+#[derive(Debug, Clone)]
+pub struct ProceduralMoon {
+    pub name: String,
+    pub semi_major_axis_au: f64,
+    pub eccentricity: f64,
+    pub inclination: f64,
+    pub longitude_ascending_node: f64,
+    pub argument_of_periapsis: f64,
+    pub mean_anomaly_epoch: f64,
+    pub period_days: f64,
+    pub mass_kg: f64,
+    pub radius_km: f32,
+    /// Captured rather than formed in the planet's disk: distant, eccentric
+    /// and often retrograde
+    pub captured: bool,
+}
+
+impl ProceduralMoon {
+    /// Convert to a KeplerOrbit component around the planet
+    pub fn to_kepler_orbit(&self) -> KeplerOrbit {
+        KeplerOrbit::new(
+            self.eccentricity,
+            self.semi_major_axis_au,
+            self.inclination,
+            self.longitude_ascending_node,
+            self.argument_of_periapsis,
+            self.mean_anomaly_epoch,
+            std::f64::consts::TAU / (self.period_days * 86400.0),
+        )
+    }
+}
+
+/// Roman numeral of a moon's number, as in "Jupiter I"
+fn roman_numeral(mut n: usize) -> String {
+    const NUMERALS: [(usize, &str); 5] = [(10, "X"), (9, "IX"), (5, "V"), (4, "IV"), (1, "I")];
+    let mut numeral = String::new();
+    for (value, symbol) in NUMERALS {
+        while n >= value {
+            numeral.push_str(symbol);
+            n -= value;
+        }
+    }
+    numeral
+}
+
+/// Period in days of a moon at `semi_major_axis_au` around a planet of
+/// `planet_mass_kg` (Kepler's third law)
+fn moon_period_days(semi_major_axis_au: f64, planet_mass_kg: f64) -> f64 {
+    let a_m = semi_major_axis_au * AU_M;
+    std::f64::consts::TAU * (a_m.powi(3) / (G * planet_mass_kg)).sqrt() / SECONDS_PER_DAY
+}
+
+/// Radius in km of a body of `mass_kg` and `density` in kg/m³
+fn radius_from_density(mass_kg: f64, density: f64) -> f32 {
+    ((3.0 * mass_kg / (4.0 * PI * density)).cbrt() / 1000.0) as f32
+}
+
+/// The moon system of a giant planet, seeded from its name like its rings.
+///
+/// Regular moons formed in the planet's disk: more massive giants get more
+/// of them (one or two for ice giants, up to six for super-Jupiters), the
+/// innermost a few planet radii out beyond the Roche limit and each next one
+/// in a mean-motion resonance with its inner neighbour, all on near-circular
+/// orbits in the planet's equator. Beyond them the planet captures a handful
+/// of small bodies on distant, eccentric and inclined orbits, many of them
+/// retrograde. Moons beyond the frost line are icy and light, those closer
+/// in rocky. Moons are returned ordered outward and numbered that way.
+pub fn moons_for_giant(
+    planet_name: &str,
+    planet_mass_earth: f32,
+    planet_radius_km: f32,
+    semi_major_axis_au: f64,
+    star_mass_sol: f64,
+    beyond_frost_line: bool,
+) -> Vec<ProceduralMoon> {
+    let mut hasher = DefaultHasher::new();
+    (planet_name, "moons").hash(&mut hasher);
+    let mut rng = StdRng::seed_from_u64(hasher.finish());
+
+    let planet_mass_kg = planet_mass_earth as f64 * EARTH_MASS_KG;
+    let planet_radius_au = planet_radius_km as f64 / AU_KM;
+    let hill_au = semi_major_axis_au
+        * (planet_mass_kg / (3.0 * star_mass_sol.max(0.01) * SOLAR_MASS_KG)).cbrt();
+    let density = if beyond_frost_line { 1900.0 } else { 3200.0 };
+
+    // (semi-major axis, eccentricity, inclination, mass, captured)
+    let mut orbits = Vec::new();
+
+    // Regular moons: about one more per doubling of mass past ten Earths,
+    // sharing the disk's mass. Close-in giants have small Hill spheres and
+    // lose the outer ones.
+    let doublings = (planet_mass_earth as f64 / 10.0).max(1.0).log2();
+    let regular_count = (1.0 + doublings * 0.75).round().clamp(1.0, 6.0) as usize;
+    let shares: Vec<f64> = (0..regular_count)
+        .map(|_| rng.gen_range(0.1..1.0))
+        .collect();
+    let share_total: f64 = shares.iter().sum();
+    let mut a = planet_radius_au * rng.gen_range(4.0..8.0);
+    for share in shares {
+        if a > hill_au * REGULAR_MOON_HILL_FRACTION {
+            break;
+        }
+        let mass_kg = planet_mass_kg * REGULAR_MOON_MASS_RATIO * share / share_total;
+        orbits.push((
+            a,
+            rng.gen_range(0.0..0.03),
+            rng.gen_range(0.0..0.01),
+            mass_kg,
+            false,
+        ));
+        let ratio = MOON_RESONANCES[rng.gen_range(0..MOON_RESONANCES.len())];
+        a *= ratio.powf(2.0 / 3.0);
+    }
+
+    // Captured moons: small, far out and tilted, half of them retrograde
+    let inner_au = a.max(hill_au * REGULAR_MOON_HILL_FRACTION);
+    let outer_au = hill_au * CAPTURED_MOON_HILL_FRACTION;
+    let captured_count = if outer_au > inner_au {
+        rng.gen_range(0..=(1.0 + doublings).round() as usize)
+    } else {
+        0
+    };
+    for _ in 0..captured_count {
+        let inclination = rng.gen_range(0.4..1.2);
+        orbits.push((
+            rng.gen_range(inner_au..outer_au),
+            rng.gen_range(0.1..0.5),
+            if rng.gen_bool(0.5) {
+                PI - inclination
+            } else {
+                inclination
+            },
+            10f64.powf(rng.gen_range(15.0..19.5)),
+            true,
+        ));
+    }
+
+    orbits.sort_by(|a, b| a.0.total_cmp(&b.0));
+    orbits
+        .into_iter()
+        .enumerate()
+        .map(
+            |(index, (a, eccentricity, inclination, mass_kg, captured))| ProceduralMoon {
+                name: format!("{} {}", planet_name, roman_numeral(index + 1)),
+                semi_major_axis_au: a,
+                eccentricity,
+                inclination,
+                longitude_ascending_node: rng.gen_range(0.0..2.0 * PI),
+                argument_of_periapsis: rng.gen_range(0.0..2.0 * PI),
+                mean_anomaly_epoch: rng.gen_range(0.0..2.0 * PI),
+                period_days: moon_period_days(a, planet_mass_kg),
+                mass_kg,
+                radius_km: radius_from_density(mass_kg, density),
+                captured,
+            },
+        )
+        .collect()
+}
+
+/// A planet's resonant asteroid populations: `count` members around
+/// `planet`'s orbit at `period_ratio` times its period
+fn resonant_belt(planet: &KeplerOrbit, population: BeltPopulation, count: usize) -> AsteroidBelt {
+    let a = planet.semi_major_axis * population.period_ratio().powf(2.0 / 3.0);
+    let spread = match population {
+        BeltPopulation::Trojan { .. } => TROJAN_MAX_ECCENTRICITY,
+        _ => 2.0 * TROJAN_MAX_ECCENTRICITY,
+    };
+    AsteroidBelt {
+        inner_au: a * (1.0 - spread),
+        outer_au: a * (1.0 + spread),
+        count,
+        inclination: planet.inclination,
+        population,
+    }
+}
+
+/// Trojan swarms and resonant families a giant planet gathers, seeded from
+/// its name.
+///
+/// Every giant keeps Trojans at its L4 and L5 points (see
+/// [`lagrange_orbit`](crate::construction::lagrange_orbit)), more the
+/// heavier it is. The innermost giant, if it is Jupiter-like, herds a Hilda
+/// family at the 3:2 resonance between it and the main belt, and the
+/// outermost giant of a system shepherds a Kuiper belt analog: plutinos at
+/// 2:3 and twotinos at 1:2 outside it.
+pub fn resonant_populations(
+    planet_name: &str,
+    planet: &KeplerOrbit,
+    planet_mass_earth: f32,
+    innermost: bool,
+    outermost: bool,
+) -> Vec<AsteroidBelt> {
+    let mut hasher = DefaultHasher::new();
+    (planet_name, "resonances").hash(&mut hasher);
+    let mut rng = StdRng::seed_from_u64(hasher.finish());
+
+    let trojans = (planet_mass_earth as f64 / 318.0 * 150.0).clamp(20.0, 300.0) as usize;
+    let mut belts: Vec<AsteroidBelt> = [LagrangePoint::L4, LagrangePoint::L5]
+        .into_iter()
+        .map(|point| {
+            let population = BeltPopulation::Trojan {
+                planet: *planet,
+                point,
+            };
+            resonant_belt(planet, population, rng.gen_range(trojans / 2..=trojans))
+        })
+        .collect();
+
+    let mut family = |orbits: u32, planet_orbits: u32, count: std::ops::Range<usize>| {
+        let population = BeltPopulation::Resonant {
+            planet: *planet,
+            orbits,
+            planet_orbits,
+        };
+        resonant_belt(planet, population, rng.gen_range(count))
+    };
+    if innermost && planet_mass_earth >= HILDA_MIN_MASS_EARTH {
+        belts.push(family(3, 2, 50..120));
+    }
+    if outermost {
+        belts.push(family(2, 3, 80..160));
+        belts.push(family(1, 2, 30..80));
+    }
+    belts
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -578,4 +916,73 @@ mod tests {
         assert_eq!(kepler.eccentricity, planets[0].eccentricity);
         assert!(kepler.mean_motion > 0.0);
     }
+
+    #[test]
+    fn test_giant_rings_are_deterministic_and_inside_roche_limit() {
+        let ringed: Vec<_> = (0..100)
+            .filter_map(|i| ring_for_giant(&format!("HSC {:05} b", i), 70_000.0, true))
+            .collect();
+        assert!(!ringed.is_empty() && ringed.len() < 100);
+        for (ring, mass) in &ringed {
+            assert!(ring.inner_radius_km < ring.outer_radius_km);
+            assert!(ring.outer_radius_km <= 70_000.0 * 2.5);
+            assert!(ring.ice_fraction >= 0.6);
+            assert!(*mass > 0.0);
+        }
+
+        let name = "HSC 00042 c";
+        assert_eq!(
+            ring_for_giant(name, 50_000.0, false),
+            ring_for_giant(name, 50_000.0, false)
+        );
+    }
+
+    #[test]
+    fn test_giant_moons_scale_with_mass_and_keep_resonances() {
+        // A Jupiter at 5.2 AU and a Neptune at 30 AU around a Sun
+        let jupiter = moons_for_giant("HSC 00007 b", 318.0, 69_911.0, 5.2, 1.0, true);
+        let neptune = moons_for_giant("HSC 00007 c", 17.0, 24_622.0, 30.0, 1.0, true);
+        let regular = |moons: &[ProceduralMoon]| moons.iter().filter(|m| !m.captured).count();
+        assert!(regular(&jupiter) > regular(&neptune) && regular(&neptune) >= 1);
+
+        let inner: Vec<_> = jupiter.iter().filter(|m| !m.captured).collect();
+        assert!(inner[0].semi_major_axis_au * AU_KM > 4.0 * 69_911.0);
+        for pair in inner.windows(2) {
+            let ratio = pair[1].period_days / pair[0].period_days;
+            assert!(MOON_RESONANCES.iter().any(|r| (ratio - r).abs() < 1e-6));
+        }
+        for moon in jupiter.iter().filter(|m| m.captured) {
+            assert!(moon.eccentricity >= 0.1 && moon.inclination >= 0.4);
+        }
+        assert_eq!(jupiter[0].name, "HSC 00007 b I");
+        assert!(jupiter[0].to_kepler_orbit().mean_motion > 0.0);
+
+        // Same planet, same moons; a hot Jupiter's Hill sphere holds none
+        let again = moons_for_giant("HSC 00007 b", 318.0, 69_911.0, 5.2, 1.0, true);
+        assert_eq!(again.len(), jupiter.len());
+        assert!(moons_for_giant("HSC 00007 d", 318.0, 69_911.0, 0.03, 1.0, false).is_empty());
+    }
+
+    #[test]
+    fn test_resonant_populations_follow_the_giants() {
+        let jupiter = KeplerOrbit::new(0.05, 5.2, 0.02, 1.0, 0.3, 0.5, 1.68e-8);
+        let only = resonant_populations("HSC 00009 b", &jupiter, 318.0, true, true);
+        // L4 and L5 Trojans, the Hildas, plutinos and twotinos
+        assert_eq!(only.len(), 5);
+        // Plutinos and twotinos are eccentric enough to cross the giant's
+        // orbit, but their semi-major axes lie outside it
+        let a = |belt: &AsteroidBelt| (belt.inner_au + belt.outer_au) / 2.0;
+        assert!((a(&only[2]) - 5.2 * (2.0f64 / 3.0).powf(2.0 / 3.0)).abs() < 0.01);
+        assert!(a(&only[3]) > 5.2 && a(&only[4]) > a(&only[3]));
+
+        // A middle giant keeps just its Trojans, a light one no Hildas
+        assert_eq!(
+            resonant_populations("HSC 00009 c", &jupiter, 318.0, false, false).len(),
+            2
+        );
+        assert_eq!(
+            resonant_populations("HSC 00009 d", &jupiter, 17.0, true, false).len(),
+            2
+        );
+    }
 }
@@ -0,0 +1,208 @@
+//! Planetary rotation: spin states for generated planets and the length of
+//! the local solar day
+//!
+//! The visible spin itself is driven by [`RotationSpeed`] and [`AxialTilt`]
+//! (see `rotate_bodies`). This module derives how long a day lasts on the
+//! surface, which depends on the sidereal rotation *and* the orbit around the
+//! star: Venus spins once every 243 days but its sun returns every 117.
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use super::components::{KeplerOrbit, OrbitCenter, StellarLuminosity};
+use crate::plugins::solar_system::{AxialTilt, RotationSpeed};
+
+/// Seconds in an Earth day
+pub const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// Planets closer than this (scaled by the cube root of the host's mass in
+/// solar masses) are assumed to be tidally locked to their star
+pub const TIDAL_LOCK_DISTANCE_AU: f64 = 0.15;
+
+/// Relative difference between rotation and orbital period below which a
+/// body counts as tidally locked
+const LOCK_TOLERANCE: f64 = 1e-3;
+
+/// Length of the solar day on a body's surface
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct LocalDay {
+    /// Seconds from one noon to the next, `None` if the body is tidally
+    /// locked (or does not rotate) and the star never moves in its sky
+    pub solar_day_seconds: Option<f64>,
+}
+
+impl LocalDay {
+    pub fn is_tidally_locked(&self) -> bool {
+        self.solar_day_seconds.is_none()
+    }
+
+    /// Average fraction of the time a surface site spends in daylight:
+    /// half on a rotating body, all of it on the day side of a locked one
+    pub fn daylight_fraction(&self) -> f64 {
+        if self.is_tidally_locked() {
+            1.0
+        } else {
+            0.5
+        }
+    }
+}
+
+/// Solar day length from the signed sidereal rotation period (negative for
+/// retrograde spin) and the orbital period around the star, both in seconds.
+/// Returns `None` when the body is tidally locked.
+pub fn solar_day_seconds(sidereal_period: f64, orbital_period: f64) -> Option<f64> {
+    if !sidereal_period.is_finite() || sidereal_period == 0.0 {
+        return None;
+    }
+    if orbital_period <= 0.0 || !orbital_period.is_finite() {
+        return Some(sidereal_period.abs());
+    }
+    // 1/S = 1/P_rot - 1/P_orb for prograde orbits
+    let synodic_rate = 1.0 / sidereal_period - 1.0 / orbital_period;
+    if (synodic_rate * orbital_period).abs() < LOCK_TOLERANCE {
+        return None;
+    }
+    Some(1.0 / synodic_rate.abs())
+}
+
+/// Rotation state of a generated planet
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spin {
+    /// Signed sidereal rotation period in days (negative = retrograde)
+    pub period_days: f32,
+    /// Obliquity in radians
+    pub obliquity: f32,
+    /// Right-ascension direction of the north pole in radians
+    pub north_pole_ra: f32,
+}
+
+impl Spin {
+    /// Components that make `rotate_bodies` spin the body
+    pub fn components(&self) -> (RotationSpeed, AxialTilt) {
+        (
+            RotationSpeed::from_period_days(self.period_days),
+            AxialTilt {
+                obliquity: self.obliquity,
+                north_pole_ra: self.north_pole_ra,
+            },
+        )
+    }
+}
+
+/// Pick a plausible spin for a planet without measured rotation. Close-in
+/// planets are tidally locked with almost no tilt; giants spin in hours;
+/// rocky worlds get a day of half a day to several days, occasionally
+/// retrograde.
+pub fn sample_spin(
+    semi_major_axis_au: f64,
+    host_mass_sol: f64,
+    orbital_period_days: f64,
+    giant: bool,
+    rng: &mut impl Rng,
+) -> Spin {
+    let north_pole_ra = rng.gen_range(0.0..std::f32::consts::TAU);
+    if semi_major_axis_au < TIDAL_LOCK_DISTANCE_AU * host_mass_sol.max(0.01).cbrt() {
+        return Spin {
+            period_days: orbital_period_days as f32,
+            obliquity: rng.gen_range(0.0..2.0_f32).to_radians(),
+            north_pole_ra,
+        };
+    }
+
+    let (period_days, obliquity_deg) = if giant {
+        (rng.gen_range(0.35..0.75), rng.gen_range(0.0..30.0_f32))
+    } else {
+        let period: f32 = rng.gen_range(0.5..5.0);
+        let retrograde = rng.gen_bool(0.1);
+        (if retrograde { -period } else { period }, rng.gen_range(0.0..45.0_f32))
+    };
+    Spin {
+        period_days,
+        obliquity: obliquity_deg.to_radians(),
+        north_pole_ra,
+    }
+}
+
+/// System that gives newly spinning bodies their [`LocalDay`], measured
+/// against the orbit of the body (or its host) around the star
+pub fn update_local_days(
+    mut commands: Commands,
+    spinning: Query<(Entity, &RotationSpeed), Added<RotationSpeed>>,
+    orbits: Query<(&KeplerOrbit, &OrbitCenter)>,
+    lights: Query<(), With<StellarLuminosity>>,
+) {
+    for (entity, rotation) in spinning.iter() {
+        // Stars and unbound bodies have no day
+        let mut current = entity;
+        let mut orbital_period = None;
+        while let Ok((orbit, center)) = orbits.get(current) {
+            if lights.contains(center.0) {
+                orbital_period = Some(KeplerOrbit::period_from_mean_motion(orbit.mean_motion));
+                break;
+            }
+            current = center.0;
+        }
+        let Some(orbital_period) = orbital_period else {
+            continue;
+        };
+
+        let solar_day_seconds = rotation
+            .period_seconds()
+            .and_then(|sidereal| solar_day_seconds(sidereal, orbital_period));
+        commands.entity(entity).insert(LocalDay { solar_day_seconds });
+    }
+}
+
+/// Format a solar day for display
+pub fn format_day_length(day: &LocalDay) -> String {
+    match day.solar_day_seconds {
+        None => "Tidally locked".to_string(),
+        Some(seconds) if seconds < SECONDS_PER_DAY * 2.0 => {
+            format!("{:.1} h", seconds / 3_600.0)
+        }
+        Some(seconds) => format!("{:.1} days", seconds / SECONDS_PER_DAY),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const YEAR: f64 = 365.25 * SECONDS_PER_DAY;
+
+    #[test]
+    fn test_solar_day_of_earth_and_venus() {
+        let earth = solar_day_seconds(0.99727 * SECONDS_PER_DAY, YEAR).unwrap();
+        assert!((earth / SECONDS_PER_DAY - 1.0).abs() < 1e-3);
+
+        // Retrograde spin shortens the day: 243 days sidereal, 117 solar
+        let venus = solar_day_seconds(-243.025 * SECONDS_PER_DAY, 224.7 * SECONDS_PER_DAY)
+            .unwrap();
+        assert!((venus / SECONDS_PER_DAY - 116.75).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_tidally_locked_body_has_no_day() {
+        let period = 11.2 * SECONDS_PER_DAY;
+        assert_eq!(solar_day_seconds(period, period), None);
+        assert_eq!(solar_day_seconds(0.0, period), None);
+
+        let day = LocalDay {
+            solar_day_seconds: None,
+        };
+        assert!(day.is_tidally_locked());
+        assert_eq!(day.daylight_fraction(), 1.0);
+    }
+
+    #[test]
+    fn test_close_planets_are_locked() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let spin = sample_spin(0.0485, 0.12, 11.2, false, &mut rng);
+        assert_eq!(spin.period_days, 11.2);
+
+        let spin = sample_spin(5.2, 1.0, 4333.0, true, &mut rng);
+        assert!(spin.period_days > 0.0 && spin.period_days < 1.0);
+    }
+}
@@ -0,0 +1,401 @@
+//! Spatial index for celestial bodies
+//!
+//! A uniform hash grid over simulation space (AU) that replaces linear scans
+//! over every body. Entries are only re-bucketed when a body crosses a cell
+//! boundary, so the per-frame cost is dominated by bodies that actually move.
+//!
+//! Used for mouse picking (`ray_candidates`), range queries such as sensor
+//! coverage (`within_radius`) and nearest-body lookups such as the colony
+//! sensors that see a contact (`nearest`).
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use super::components::{FloatingOrigin, SpaceCoordinates, SystemId};
+use super::systems::SCALING_FACTOR;
+use crate::plugins::solar_system::CelestialBody;
+
+/// Default grid cell edge length in AU.
+/// Large enough that a planet and its moons share a cell, small enough that
+/// inner-system bodies are split across many cells.
+pub const DEFAULT_CELL_SIZE_AU: f64 = 0.5;
+
+type CellKey = (i64, i64, i64);
+
+/// A body stored in the index.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpatialEntry {
+    /// Simulation position in AU
+    pub position: DVec3,
+    /// Bounding radius in AU, covering the visual mesh and any render offset
+    /// (e.g. amplified moon orbits) so picking never misses a body
+    pub radius: f64,
+    /// Star system the body belongs to
+    pub system_id: usize,
+    cell: CellKey,
+}
+
+#[derive(Debug, Default, Clone)]
+struct Cell {
+    entities: Vec<Entity>,
+    /// Largest entry radius in this cell (conservative; may be stale-high)
+    max_radius: f64,
+}
+
+/// Uniform grid of celestial bodies keyed by their `SpaceCoordinates`.
+#[derive(Resource, Debug, Clone)]
+pub struct SpatialIndex {
+    cell_size: f64,
+    cells: HashMap<CellKey, Cell>,
+    entries: HashMap<Entity, SpatialEntry>,
+}
+
+impl Default for SpatialIndex {
+    fn default() -> Self {
+        Self::new(DEFAULT_CELL_SIZE_AU)
+    }
+}
+
+impl SpatialIndex {
+    /// Create an empty index with the given cell size in AU.
+    pub fn new(cell_size: f64) -> Self {
+        assert!(cell_size > 0.0, "Cell size must be positive");
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+            entries: HashMap::new(),
+        }
+    }
+
+    pub fn cell_size(&self) -> f64 {
+        self.cell_size
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, entity: Entity) -> Option<&SpatialEntry> {
+        self.entries.get(&entity)
+    }
+
+    fn cell_key(&self, position: DVec3) -> CellKey {
+        let cell = (position / self.cell_size).floor();
+        (cell.x as i64, cell.y as i64, cell.z as i64)
+    }
+
+    fn cell_center(&self, key: CellKey) -> DVec3 {
+        (DVec3::new(key.0 as f64, key.1 as f64, key.2 as f64) + 0.5) * self.cell_size
+    }
+
+    /// Insert or move a body. Only touches the grid when the body changes cell.
+    pub fn insert(&mut self, entity: Entity, position: DVec3, radius: f64, system_id: usize) {
+        let key = self.cell_key(position);
+
+        if let Some(entry) = self.entries.get_mut(&entity) {
+            let old_key = entry.cell;
+            entry.position = position;
+            entry.radius = radius;
+            entry.system_id = system_id;
+            entry.cell = key;
+
+            if old_key == key {
+                let cell = self.cells.entry(key).or_default();
+                cell.max_radius = cell.max_radius.max(radius);
+                return;
+            }
+            self.detach(entity, old_key);
+        } else {
+            self.entries.insert(
+                entity,
+                SpatialEntry {
+                    position,
+                    radius,
+                    system_id,
+                    cell: key,
+                },
+            );
+        }
+
+        let cell = self.cells.entry(key).or_default();
+        cell.entities.push(entity);
+        cell.max_radius = cell.max_radius.max(radius);
+    }
+
+    /// Remove a body from the index.
+    pub fn remove(&mut self, entity: Entity) -> Option<SpatialEntry> {
+        let entry = self.entries.remove(&entity)?;
+        self.detach(entity, entry.cell);
+        Some(entry)
+    }
+
+    /// Drop an entity from a cell's list, freeing the cell when it empties.
+    fn detach(&mut self, entity: Entity, key: CellKey) {
+        let Some(cell) = self.cells.get_mut(&key) else {
+            return;
+        };
+        cell.entities.retain(|e| *e != entity);
+        if cell.entities.is_empty() {
+            self.cells.remove(&key);
+        } else {
+            cell.max_radius = cell
+                .entities
+                .iter()
+                .filter_map(|e| self.entries.get(e))
+                .map(|entry| entry.radius)
+                .fold(0.0, f64::max);
+        }
+    }
+
+    /// All bodies whose center lies within `radius` AU of `center`,
+    /// sorted by distance (closest first).
+    pub fn within_radius(&self, center: DVec3, radius: f64) -> Vec<(Entity, f64)> {
+        let min = self.cell_key(center - DVec3::splat(radius));
+        let max = self.cell_key(center + DVec3::splat(radius));
+
+        let mut results = Vec::new();
+        for x in min.0..=max.0 {
+            for y in min.1..=max.1 {
+                for z in min.2..=max.2 {
+                    let Some(cell) = self.cells.get(&(x, y, z)) else {
+                        continue;
+                    };
+                    for entity in &cell.entities {
+                        let entry = &self.entries[entity];
+                        let distance = entry.position.distance(center);
+                        if distance <= radius {
+                            results.push((*entity, distance));
+                        }
+                    }
+                }
+            }
+        }
+        results.sort_by(|a, b| a.1.total_cmp(&b.1));
+        results
+    }
+
+    /// Closest body to `center` within `max_radius` AU that passes `filter`.
+    ///
+    /// Searches outward one shell of cells at a time, so nearby hits return
+    /// without visiting the rest of the grid.
+    pub fn nearest(
+        &self,
+        center: DVec3,
+        max_radius: f64,
+        filter: impl Fn(Entity, &SpatialEntry) -> bool,
+    ) -> Option<(Entity, f64)> {
+        let origin = self.cell_key(center);
+        let max_ring = (max_radius / self.cell_size).ceil() + 1.0;
+        let mut best: Option<(Entity, f64)> = None;
+
+        // For very large radii walking empty shells costs more than a flat scan
+        let shell_cells = (2.0 * max_ring + 1.0).powi(3);
+        if shell_cells > (self.cells.len() * 8) as f64 {
+            for (entity, entry) in &self.entries {
+                let distance = entry.position.distance(center);
+                if distance <= max_radius
+                    && filter(*entity, entry)
+                    && best.is_none_or(|(_, d)| distance < d)
+                {
+                    best = Some((*entity, distance));
+                }
+            }
+            return best;
+        }
+
+        for ring in 0..=max_ring as i64 {
+            // Anything in later rings is at least (ring - 1) cells away
+            if let Some((_, distance)) = best {
+                if distance < (ring - 1).max(0) as f64 * self.cell_size {
+                    break;
+                }
+            }
+
+            for x in -ring..=ring {
+                for y in -ring..=ring {
+                    for z in -ring..=ring {
+                        // Only visit the shell of this ring
+                        if x.abs().max(y.abs()).max(z.abs()) != ring {
+                            continue;
+                        }
+                        let key = (origin.0 + x, origin.1 + y, origin.2 + z);
+                        let Some(cell) = self.cells.get(&key) else {
+                            continue;
+                        };
+                        for entity in &cell.entities {
+                            let entry = &self.entries[entity];
+                            let distance = entry.position.distance(center);
+                            if distance > max_radius || !filter(*entity, entry) {
+                                continue;
+                            }
+                            if best.is_none_or(|(_, d)| distance < d) {
+                                best = Some((*entity, distance));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    /// Bodies whose bounding sphere (plus `margin` AU) may intersect a ray.
+    ///
+    /// This is a broad-phase cull: whole cells are rejected against the ray
+    /// before any body is looked at. Callers still run their exact test.
+    pub fn ray_candidates(&self, origin: DVec3, direction: DVec3, margin: f64) -> Vec<Entity> {
+        let direction = direction.normalize_or_zero();
+        let half_diagonal = self.cell_size * 3f64.sqrt() * 0.5;
+
+        let mut results = Vec::new();
+        for (key, cell) in &self.cells {
+            let reach = half_diagonal + cell.max_radius + margin;
+            let to_cell = self.cell_center(*key) - origin;
+            let projection = to_cell.dot(direction);
+            if projection < -reach {
+                continue;
+            }
+            let perpendicular = (to_cell - direction * projection).length();
+            if perpendicular <= reach {
+                results.extend(cell.entities.iter().copied());
+            }
+        }
+        results
+    }
+}
+
+/// Keep the spatial index in sync with body positions.
+///
+/// Runs after transform propagation so the bounding radius can include the
+/// offset between a body's simulation position and where it is drawn.
+#[allow(clippy::type_complexity)]
+pub fn update_spatial_index(
+    mut index: ResMut<SpatialIndex>,
+    floating_origin: Res<FloatingOrigin>,
+    body_query: Query<
+        (
+            Entity,
+            &CelestialBody,
+            &GlobalTransform,
+            Option<&SpaceCoordinates>,
+            Option<&SystemId>,
+        ),
+        Changed<GlobalTransform>,
+    >,
+    mut removed: RemovedComponents<CelestialBody>,
+) {
+    for entity in removed.read() {
+        index.remove(entity);
+    }
+
+    for (entity, body, transform, coords, system_id) in body_query.iter() {
+        // Where the body is drawn, expressed in simulation space
+        let render_position =
+            transform.translation().as_dvec3() / SCALING_FACTOR + floating_origin.position;
+        let position = coords.map_or(render_position, |c| c.position);
+        let radius =
+            body.visual_radius as f64 / SCALING_FACTOR + position.distance(render_position);
+
+        index.insert(
+            entity,
+            position,
+            radius,
+            system_id.map(|s| s.0).unwrap_or(0),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entity(index: u32) -> Entity {
+        Entity::from_raw(index)
+    }
+
+    #[test]
+    fn test_insert_move_and_remove() {
+        let mut index = SpatialIndex::new(1.0);
+        index.insert(entity(1), DVec3::new(0.2, 0.2, 0.0), 0.01, 0);
+        assert_eq!(index.len(), 1);
+
+        // Move within the same cell, then across a boundary
+        index.insert(entity(1), DVec3::new(0.8, 0.2, 0.0), 0.01, 0);
+        assert_eq!(index.cells.len(), 1);
+        index.insert(entity(1), DVec3::new(3.5, 0.2, 0.0), 0.01, 0);
+        assert_eq!(index.cells.len(), 1);
+        assert_eq!(index.get(entity(1)).unwrap().position.x, 3.5);
+
+        assert!(index.remove(entity(1)).is_some());
+        assert!(index.is_empty());
+        assert!(index.cells.is_empty());
+    }
+
+    #[test]
+    fn test_within_radius_sorted() {
+        let mut index = SpatialIndex::new(0.5);
+        index.insert(entity(1), DVec3::new(1.0, 0.0, 0.0), 0.0, 0);
+        index.insert(entity(2), DVec3::new(0.3, 0.0, 0.0), 0.0, 0);
+        index.insert(entity(3), DVec3::new(5.0, 0.0, 0.0), 0.0, 0);
+
+        let hits = index.within_radius(DVec3::ZERO, 1.5);
+        let entities: Vec<Entity> = hits.iter().map(|h| h.0).collect();
+        assert_eq!(entities, vec![entity(2), entity(1)]);
+    }
+
+    #[test]
+    fn test_nearest_with_filter() {
+        let mut index = SpatialIndex::new(0.5);
+        index.insert(entity(1), DVec3::new(0.1, 0.0, 0.0), 0.0, 0);
+        index.insert(entity(2), DVec3::new(2.0, 0.0, 0.0), 0.0, 1);
+        index.insert(entity(3), DVec3::new(-4.0, 0.0, 0.0), 0.0, 1);
+
+        let (nearest, _) = index.nearest(DVec3::ZERO, 10.0, |_, _| true).unwrap();
+        assert_eq!(nearest, entity(1));
+
+        let (nearest, distance) = index
+            .nearest(DVec3::ZERO, 10.0, |_, e| e.system_id == 1)
+            .unwrap();
+        assert_eq!(nearest, entity(2));
+        assert!((distance - 2.0).abs() < 1e-12);
+
+        assert!(index
+            .nearest(DVec3::ZERO, 1.0, |_, e| e.system_id == 1)
+            .is_none());
+
+        // Huge search radii fall back to a flat scan with the same answer
+        let (nearest, _) = index
+            .nearest(DVec3::ZERO, 1.0e6, |_, e| e.system_id == 1)
+            .unwrap();
+        assert_eq!(nearest, entity(2));
+    }
+
+    #[test]
+    fn test_ray_candidates_cull_distant_cells() {
+        let mut index = SpatialIndex::new(0.5);
+        index.insert(entity(1), DVec3::new(5.0, 0.0, 0.0), 0.01, 0);
+        index.insert(entity(2), DVec3::new(5.0, 20.0, 0.0), 0.01, 0);
+
+        let hits = index.ray_candidates(DVec3::ZERO, DVec3::X, 0.01);
+        assert!(hits.contains(&entity(1)));
+        assert!(!hits.contains(&entity(2)));
+
+        // Behind the ray origin
+        let hits = index.ray_candidates(DVec3::new(10.0, 0.0, 0.0), DVec3::X, 0.01);
+        assert!(hits.is_empty());
+    }
+
+    #[test]
+    fn test_large_bodies_widen_their_cell() {
+        let mut index = SpatialIndex::new(0.5);
+        // A star-sized bounding radius reaches rays well outside its cell
+        index.insert(entity(1), DVec3::ZERO, 2.0, 0);
+        let hits = index.ray_candidates(DVec3::new(-10.0, 1.5, 0.0), DVec3::X, 0.0);
+        assert_eq!(hits, vec![entity(1)]);
+    }
+}
@@ -0,0 +1,437 @@
+//! Stellar evolution and flares
+//!
+//! Main-sequence stars slowly brighten as they burn their hydrogen: the Sun
+//! by about a tenth every billion years, heavier stars far faster. Every
+//! [`EVOLUTION_INTERVAL_YEARS`] the luminosity of each star is re-evaluated
+//! from its luminosity at the start of the game, and the frost line of its
+//! system and the surface temperatures of its bodies follow. Colonies whose
+//! world warms noticeably or drifts out of the habitable zone are warned
+//! about in the event log.
+//!
+//! Real rates barely move a star within a campaign, so
+//! [`StellarEvolutionSettings::deep_time`] can speed up the clock for
+//! multi-millennia games (console: `deep_time <factor>`).
+//!
+//! Red dwarfs are flare stars: on any day one may flare
+//! ([`FLARE_MEAN_DAYS`]) and bathe the colonies around it in a radiation
+//! storm.
+
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::Rng;
+use std::collections::HashMap;
+
+use super::components::{
+    AtmosphereComposition, KeplerOrbit, OrbitCenter, StellarLuminosity, SurfaceTemperature,
+};
+use super::procedural::calculate_frost_line;
+use crate::console::{ConsoleCommand, RegisterConsoleCommand};
+use crate::economy::components::{Population, SpectralClass, StarSystem};
+use crate::economy::{EconomyClock, Radiation, SECONDS_PER_YEAR};
+use crate::game_state::{EventLog, GameSeed, LogSeverity};
+use crate::plugins::solar_system::{CelestialBody, Star};
+use crate::ui::SimulationTime;
+
+/// In-game years between two re-evaluations of the stars
+pub const EVOLUTION_INTERVAL_YEARS: f64 = 10.0;
+/// Main-sequence lifetime of the Sun in years
+pub const SOLAR_MAIN_SEQUENCE_YEARS: f64 = 1e10;
+/// Luminosity a main-sequence star gains over its lifetime, as a share of
+/// its luminosity at the start of the game
+pub const MAIN_SEQUENCE_BRIGHTENING: f64 = 1.0;
+/// Habitable zone around a star of one solar luminosity in AU
+pub const HABITABLE_ZONE_AU: (f64, f64) = (0.95, 1.37);
+/// Warming of a colony's world that is logged
+pub const WARMING_WARNING_C: f32 = 1.0;
+/// Mean days between two flares of a red dwarf
+pub const FLARE_MEAN_DAYS: f64 = 90.0;
+/// Radiation multiplier of a flare on the colonies around the star
+pub const FLARE_MULTIPLIER: f64 = 3.0;
+/// Days a flare's radiation storm lasts
+pub const FLARE_DAYS: f64 = 2.0;
+
+/// How fast stars evolve
+#[derive(Resource, Debug, Clone)]
+pub struct StellarEvolutionSettings {
+    /// Stellar years per in-game year (1.0 is realistic)
+    pub deep_time: f64,
+}
+
+impl Default for StellarEvolutionSettings {
+    fn default() -> Self {
+        Self { deep_time: 1.0 }
+    }
+}
+
+/// Luminosity of a star at the start of the game, which its evolution is
+/// measured from
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct StellarEvolution {
+    pub initial_luminosity: f64,
+}
+
+/// Random number generator for flares, seeded from the [`GameSeed`]
+#[derive(Resource, Debug, Clone)]
+pub struct FlareRng(pub StdRng);
+
+impl FromWorld for FlareRng {
+    fn from_world(world: &mut World) -> Self {
+        Self(GameSeed::rng(world, 0x464c_4152_4553))
+    }
+}
+
+/// Main-sequence lifetime in years of a star of `luminosity` (solar
+/// luminosities): from L ∝ M^3.5 it burns through its fuel as M^-2.5
+pub fn main_sequence_years(luminosity: f64) -> f64 {
+    SOLAR_MAIN_SEQUENCE_YEARS * luminosity.max(1e-4).powf(-2.5 / 3.5)
+}
+
+/// Luminosity of a star `years` after it had `initial_luminosity`, brightening
+/// steadily until it leaves the main sequence
+pub fn evolved_luminosity(initial_luminosity: f64, years: f64) -> f64 {
+    let lifetime = main_sequence_years(initial_luminosity);
+    initial_luminosity * (1.0 + MAIN_SEQUENCE_BRIGHTENING * (years / lifetime).clamp(0.0, 1.0))
+}
+
+/// Inner and outer edge in AU of the habitable zone of a star of
+/// `luminosity`
+pub fn habitable_zone(luminosity: f64) -> (f64, f64) {
+    let scale = luminosity.max(0.0).sqrt();
+    (HABITABLE_ZONE_AU.0 * scale, HABITABLE_ZONE_AU.1 * scale)
+}
+
+/// Temperature of a body lit by a star that went from `old_luminosity` to
+/// `new_luminosity`: the equilibrium temperature goes with L^¼
+pub fn scaled_temperature(celsius: f32, old_luminosity: f64, new_luminosity: f64) -> f32 {
+    if old_luminosity <= 0.0 {
+        return celsius;
+    }
+    let kelvin = (celsius as f64 + 273.15).max(0.0);
+    (kelvin * (new_luminosity / old_luminosity).powf(0.25) - 273.15) as f32
+}
+
+/// Star each body orbits, directly or through its parents, with the
+/// semi-major axis in AU of the orbit around the star
+fn host_stars(
+    centers: &HashMap<Entity, (Entity, f64)>,
+    is_star: impl Fn(Entity) -> bool,
+) -> HashMap<Entity, (Entity, f64)> {
+    let mut hosts = HashMap::new();
+    for &body in centers.keys() {
+        let mut current = body;
+        // Moons of moons are as deep as systems go
+        for _ in 0..4 {
+            let Some(&(center, semi_major_axis)) = centers.get(&current) else {
+                break;
+            };
+            if is_star(center) {
+                hosts.insert(body, (center, semi_major_axis));
+                break;
+            }
+            current = center;
+        }
+    }
+    hosts
+}
+
+/// System that re-evaluates the luminosity of every star at long intervals,
+/// moves frost lines and surface temperatures with it and warns about
+/// colonies that warm or leave the habitable zone
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn evolve_stars(
+    mut commands: Commands,
+    sim_time: Option<Res<SimulationTime>>,
+    settings: Res<StellarEvolutionSettings>,
+    mut last_years: Local<Option<f64>>,
+    mut stars: Query<
+        (
+            Entity,
+            &CelestialBody,
+            &mut StellarLuminosity,
+            Option<&mut StarSystem>,
+            Option<&StellarEvolution>,
+        ),
+        With<Star>,
+    >,
+    mut bodies: Query<
+        (
+            Entity,
+            &CelestialBody,
+            Option<&OrbitCenter>,
+            Option<&KeplerOrbit>,
+            Option<&mut SurfaceTemperature>,
+            Option<&mut AtmosphereComposition>,
+            Option<&Population>,
+        ),
+        Without<Star>,
+    >,
+    mut log: Option<ResMut<EventLog>>,
+) {
+    let Some(sim_time) = sim_time else {
+        return;
+    };
+    let years = sim_time.elapsed_seconds() / SECONDS_PER_YEAR;
+    if last_years.is_some_and(|last| years - last < EVOLUTION_INTERVAL_YEARS) {
+        return;
+    }
+    *last_years = Some(years);
+    let stellar_years = years * settings.deep_time;
+
+    // Old and new luminosity of every star that changed
+    let mut changed = HashMap::new();
+    for (entity, body, mut luminosity, star_system, evolution) in stars.iter_mut() {
+        let Some(evolution) = evolution else {
+            commands.entity(entity).insert(StellarEvolution {
+                initial_luminosity: luminosity.0,
+            });
+            continue;
+        };
+        let evolved = evolved_luminosity(evolution.initial_luminosity, stellar_years);
+        if evolved == luminosity.0 {
+            continue;
+        }
+        changed.insert(entity, (body.name.clone(), luminosity.0, evolved));
+        luminosity.0 = evolved;
+        if let Some(mut star_system) = star_system {
+            star_system.frost_line_au = calculate_frost_line(evolved);
+        }
+    }
+    if changed.is_empty() {
+        return;
+    }
+
+    let centers: HashMap<Entity, (Entity, f64)> = bodies
+        .iter()
+        .filter_map(|(entity, _, center, orbit, ..)| {
+            Some((
+                entity,
+                (center?.0, orbit.map_or(0.0, |o| o.semi_major_axis)),
+            ))
+        })
+        .collect();
+    let hosts = host_stars(&centers, |entity| stars.contains(entity));
+
+    for (entity, body, _, _, temperature, atmosphere, population) in bodies.iter_mut() {
+        let Some((star, distance_au)) = hosts.get(&entity) else {
+            continue;
+        };
+        let Some((star_name, old, new)) = changed.get(star) else {
+            continue;
+        };
+        let mut warming = 0.0;
+        if let Some(mut temperature) = temperature {
+            let average = scaled_temperature(temperature.average_celsius, *old, *new);
+            warming = average - temperature.average_celsius;
+            temperature.average_celsius = average;
+            temperature.min_celsius = scaled_temperature(temperature.min_celsius, *old, *new);
+            temperature.max_celsius = scaled_temperature(temperature.max_celsius, *old, *new);
+        }
+        if let Some(mut atmosphere) = atmosphere {
+            atmosphere.surface_temperature_celsius =
+                scaled_temperature(atmosphere.surface_temperature_celsius, *old, *new);
+        }
+
+        if population.is_none_or(|p| p.count <= 0.0) {
+            continue;
+        }
+        let was_habitable = {
+            let (inner, outer) = habitable_zone(*old);
+            (inner..=outer).contains(distance_au)
+        };
+        let (inner, outer) = habitable_zone(*new);
+        let message = if was_habitable && !(inner..=outer).contains(distance_au) {
+            format!(
+                "{} has left the habitable zone of {}, which now begins at {:.2} AU",
+                body.name, star_name, inner
+            )
+        } else if warming >= WARMING_WARNING_C {
+            format!(
+                "{} warmed by {:.1}°C as {} brightened",
+                body.name, warming, star_name
+            )
+        } else {
+            continue;
+        };
+        if let Some(log) = log.as_mut() {
+            log.push(sim_time.format_date_time(), LogSeverity::Warning, message);
+        }
+    }
+}
+
+/// System that lets red dwarfs flare once per due economy tick, putting the
+/// colonies around them under a radiation storm
+#[allow(clippy::type_complexity)]
+pub fn roll_stellar_flares(
+    clock: Option<Res<EconomyClock>>,
+    mut rng: ResMut<FlareRng>,
+    stars: Query<(Entity, &CelestialBody, &StarSystem), With<Star>>,
+    centers: Query<(Entity, &OrbitCenter)>,
+    mut colonies: Query<(Entity, &CelestialBody, &mut Radiation)>,
+    sim_time: Option<Res<SimulationTime>>,
+    mut log: Option<ResMut<EventLog>>,
+) {
+    let Some(clock) = clock.filter(|c| c.due > 0) else {
+        return;
+    };
+    let chance = (clock.due as f64 / FLARE_MEAN_DAYS).min(1.0);
+    let flaring: Vec<(Entity, String)> = stars
+        .iter()
+        .filter(|(.., system)| system.spectral_class == SpectralClass::M)
+        .filter(|_| rng.0.gen_bool(chance))
+        .map(|(entity, body, _)| (entity, body.name.clone()))
+        .collect();
+    if flaring.is_empty() {
+        return;
+    }
+
+    let parents: HashMap<Entity, (Entity, f64)> = centers
+        .iter()
+        .map(|(entity, center)| (entity, (center.0, 0.0)))
+        .collect();
+    let hosts = host_stars(&parents, |entity| stars.contains(entity));
+    for (star, star_name) in flaring {
+        let mut hit = Vec::new();
+        for (entity, body, mut radiation) in colonies.iter_mut() {
+            if hosts.get(&entity).is_some_and(|(host, _)| *host == star) {
+                radiation.add_storm(FLARE_MULTIPLIER, FLARE_DAYS);
+                hit.push(body.name.clone());
+            }
+        }
+        if hit.is_empty() {
+            continue;
+        }
+        let message = format!(
+            "{} flares: radiation storm over {}",
+            star_name,
+            hit.join(", ")
+        );
+        info!("{}", message);
+        if let (Some(log), Some(sim_time)) = (log.as_mut(), sim_time.as_ref()) {
+            log.push(sim_time.format_date_time(), LogSeverity::Warning, message);
+        }
+    }
+}
+
+/// Console command setting how many stellar years pass per in-game year
+fn deep_time(world: &mut World, args: &[String]) -> Result<String, String> {
+    let [factor] = args else {
+        return Err("Usage: deep_time <factor>".to_string());
+    };
+    let factor: f64 = factor
+        .parse()
+        .ok()
+        .filter(|f: &f64| f.is_finite() && *f >= 0.0)
+        .ok_or_else(|| format!("Not a factor: {}", factor))?;
+    world.resource_mut::<StellarEvolutionSettings>().deep_time = factor;
+    Ok(format!("Stars now age {} years per in-game year", factor))
+}
+
+/// Plugin that ages the stars and lets red dwarfs flare
+pub struct StellarEvolutionPlugin;
+
+impl Plugin for StellarEvolutionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<StellarEvolutionSettings>()
+            .init_resource::<FlareRng>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    evolve_stars,
+                    roll_stellar_flares
+                        .after(crate::economy::advance_economy_clock)
+                        .before(crate::economy::update_radiation),
+                ),
+            )
+            .register_console_command(ConsoleCommand {
+                name: "deep_time",
+                usage: "<factor>",
+                description: "Set how many years stars age per in-game year (1 is realistic)",
+                handler: deep_time,
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stars_brighten_and_their_zones_drift() {
+        assert_eq!(main_sequence_years(1.0), SOLAR_MAIN_SEQUENCE_YEARS);
+        // Heavy stars burn out far sooner
+        assert!(main_sequence_years(1000.0) < 1e8);
+        assert!(main_sequence_years(0.01) > 1e11);
+
+        let sun = evolved_luminosity(1.0, 1e9);
+        assert!((sun - 1.1).abs() < 1e-12);
+        assert_eq!(
+            evolved_luminosity(1.0, 1e12),
+            1.0 + MAIN_SEQUENCE_BRIGHTENING
+        );
+
+        let (inner, outer) = habitable_zone(sun);
+        assert!(inner < 1.0 && outer > 1.0);
+        assert!(habitable_zone(4.0).0 > 1.0);
+
+        // A tenth more light warms Earth by about 7 K
+        let earth = scaled_temperature(15.0, 1.0, sun);
+        assert!((earth - 15.0 - 6.9).abs() < 0.1, "{}", earth);
+        assert_eq!(scaled_temperature(15.0, 1.0, 1.0), 15.0);
+    }
+
+    #[test]
+    fn test_evolution_warns_colonies() {
+        use crate::plugins::solar_system_data::BodyType;
+
+        let mut app = App::new();
+        app.init_resource::<StellarEvolutionSettings>()
+            .init_resource::<SimulationTime>()
+            .init_resource::<EventLog>()
+            .add_systems(Update, evolve_stars);
+        let sun = app
+            .world_mut()
+            .spawn((
+                CelestialBody::fixture("Sun", BodyType::Star),
+                Star,
+                StellarLuminosity(1.0),
+                StarSystem::sun_like(),
+            ))
+            .id();
+        let earth = app
+            .world_mut()
+            .spawn((
+                CelestialBody::fixture("Earth", BodyType::Planet),
+                OrbitCenter(sun),
+                KeplerOrbit::new(0.0167, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0),
+                SurfaceTemperature {
+                    average_celsius: 15.0,
+                    min_celsius: -35.0,
+                    max_celsius: 65.0,
+                },
+                Population::new(8e9),
+            ))
+            .id();
+        app.update();
+        assert!(app.world().get::<StellarEvolution>(sun).is_some());
+
+        // A billion stellar years pass within the next interval
+        app.world_mut()
+            .resource_mut::<StellarEvolutionSettings>()
+            .deep_time = 1e8;
+        app.world_mut().resource_mut::<SimulationTime>().elapsed =
+            EVOLUTION_INTERVAL_YEARS * SECONDS_PER_YEAR;
+        app.update();
+
+        let luminosity = app.world().get::<StellarLuminosity>(sun).unwrap().0;
+        assert!((luminosity - 1.1).abs() < 1e-9);
+        let frost_line = app.world().get::<StarSystem>(sun).unwrap().frost_line_au;
+        assert_eq!(frost_line, calculate_frost_line(luminosity));
+        let temperature = app.world().get::<SurfaceTemperature>(earth).unwrap();
+        assert!(temperature.average_celsius > 20.0);
+        let log = app.world().resource::<EventLog>();
+        assert!(log
+            .entries
+            .iter()
+            .any(|e| e.message.contains("Earth warmed")));
+    }
+}
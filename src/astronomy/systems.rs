@@ -1,17 +1,20 @@
-use bevy::math::DVec3;
+use bevy::math::{DVec3, Vec3A};
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
+use std::collections::HashMap;
 
 use super::components::{
-    CometTail, CurrentStarSystem, Destroyed, HoverMarker, Hovered, KeplerOrbit,
-    LocalOrbitAmplification, MarkerDot, MarkerOwner, OrbitCenter, OrbitPath, Selected,
-    SelectionMarker, SpaceCoordinates, SystemId,
+    BodyLod, CometActivity, CometTail, CurrentStarSystem, Destroyed, Dormant, FloatingOrigin,
+    HoverMarker, Hovered, KeplerOrbit, LocalOrbitAmplification, MarkerDot, MarkerOwner,
+    OrbitCenter, OrbitPath, OrbitSamples, Selected, SelectionMarker, SpaceCoordinates,
+    StellarLuminosity, SystemId,
 };
+use super::spatial_index::SpatialIndex;
 use crate::plugins::camera::{CameraAnchor, GameCamera, OrbitCamera, ViewMode};
 use crate::plugins::solar_system::{
-    CelestialBody, Comet, LogicalParent, Moon, Planet, Star,
+    Asteroid, CelestialBody, Comet, LogicalParent, Moon, Planet, Ring, Star,
 };
-use crate::plugins::solar_system_data::calculate_visual_radius;
+use crate::plugins::solar_system_data::{calculate_visual_radius, BodyType};
 use crate::game_state::ActiveMenu;
 use crate::ui::SimulationTime;
 
@@ -27,6 +30,20 @@ const SELECTION_CLICK_RADIUS: f32 = 20.0;
 /// Padding for the hover ring around celestial bodies (in Bevy units)
 const HOVER_RING_PADDING: f32 = 8.0; // Creates visible gap between marker and body
 
+/// Camera distance, in parent visual radii, within which a planet's moons are
+/// drawn individually instead of as a single moon-group marker
+pub const MOON_EXPAND_RADII: f32 = 60.0;
+
+/// Camera distance, in visual radii, beyond which comets and asteroids are
+/// drawn as points
+pub const MINOR_BODY_POINT_RADII: f32 = 2000.0;
+
+/// Radius of the moon-group ring, in parent visual radii
+const MOON_GROUP_RING_RADII: f32 = 2.0;
+
+/// Point marker radius per unit of camera distance (constant screen size)
+const LOD_POINT_MARKER_SCALE: f32 = 0.004;
+
 /// Maximum iterations for Kepler solver
 const MAX_KEPLER_ITERATIONS: u32 = 50;
 
@@ -152,7 +169,7 @@ fn orbital_radius(semi_major_axis: f64, eccentricity: f64, true_anomaly: f64) ->
 /// Calculate the 3D orbital position directly from a true anomaly.
 /// Unlike `orbit_position_from_mean_anomaly`, this skips the Kepler solver
 /// and is used for drawing orbit paths with uniform geometric spacing.
-fn orbit_position_from_true_anomaly(orbit: &KeplerOrbit, true_anomaly: f64) -> DVec3 {
+pub fn orbit_position_from_true_anomaly(orbit: &KeplerOrbit, true_anomaly: f64) -> DVec3 {
     let radius = orbital_radius(orbit.semi_major_axis, orbit.eccentricity, true_anomaly);
 
     let x_orbital = radius * true_anomaly.cos();
@@ -176,88 +193,265 @@ fn orbit_position_from_true_anomaly(orbit: &KeplerOrbit, true_anomaly: f64) -> D
 }
 
 /// Convert mean anomaly to true anomaly via the Kepler solver
-fn mean_anomaly_to_true_anomaly(mean_anomaly: f64, eccentricity: f64) -> f64 {
+pub fn mean_anomaly_to_true_anomaly(mean_anomaly: f64, eccentricity: f64) -> f64 {
     let e_anom = solve_kepler(mean_anomaly, eccentricity);
     eccentric_to_true_anomaly(e_anom, eccentricity)
 }
 
+/// Rate (Hz) of the fixed timestep on which orbits are propagated and the
+/// economy ticks. Rendered positions are interpolated between ticks.
+pub const SIMULATION_TICK_HZ: f64 = 20.0;
+
+/// Simulated seconds between orbit samples of [`Dormant`] bodies
+pub const DORMANT_SAMPLE_SECONDS: f64 = 86_400.0;
+
+/// Nesting depth after which an orbit hierarchy is assumed to be cyclic.
+const MAX_ORBIT_DEPTH: usize = 16;
+
+/// Position of a body relative to its orbit center at the given time (AU).
+pub fn orbit_offset_at(orbit: &KeplerOrbit, elapsed_time: f64) -> DVec3 {
+    // Calculate current mean anomaly: M = M₀ + n*t
+    let mean_anomaly = orbit.mean_anomaly_epoch + orbit.mean_motion * elapsed_time;
+    orbit_position_from_mean_anomaly(orbit, mean_anomaly)
+}
+
+/// World position of `entity` at the given time, resolving its chain of orbit
+/// centers. Non-orbiting centers (static stars) use their current coordinates.
+fn resolve_orbit_position(
+    entity: Entity,
+    elapsed_time: f64,
+    orbits: &HashMap<Entity, (KeplerOrbit, Option<Entity>)>,
+    coords: &Query<&mut SpaceCoordinates>,
+    resolved: &mut HashMap<Entity, DVec3>,
+    depth: usize,
+) -> DVec3 {
+    if let Some(position) = resolved.get(&entity) {
+        return *position;
+    }
+
+    let position = match orbits.get(&entity) {
+        Some((orbit, center)) if depth < MAX_ORBIT_DEPTH => {
+            let parent = center
+                .map(|c| {
+                    resolve_orbit_position(c, elapsed_time, orbits, coords, resolved, depth + 1)
+                })
+                .unwrap_or(DVec3::ZERO);
+            parent + orbit_offset_at(orbit, elapsed_time)
+        }
+        _ => coords
+            .get(entity)
+            .map(|c| c.position)
+            .unwrap_or(DVec3::ZERO),
+    };
+    resolved.insert(entity, position);
+    position
+}
+
 /// System that propagates all orbits based on Keplerian mechanics
-/// Updates SpaceCoordinates based on KeplerOrbit elements and elapsed time
 /// Uses SimulationTime to allow time scaling via UI controls
 ///
+/// Runs on the fixed timestep ([`SIMULATION_TICK_HZ`]) and records each
+/// result into the body's [`OrbitSamples`], from which
+/// [`interpolate_orbit_positions`] derives the rendered position every frame.
+/// Bodies without samples yet get them inserted and have their
+/// [`SpaceCoordinates`] set directly. Bodies already sampled at the current
+/// simulation time (e.g. while paused) are skipped.
+///
 /// If an entity has an [`OrbitCenter`] component, its orbital position is
-/// computed relative to that parent entity's current [`SpaceCoordinates`].
+/// computed relative to that parent entity at the same simulation time.
 /// Without it, the orbit is relative to the universe origin (0,0,0), which
 /// is correct for Sol-system bodies orbiting the Sun.
+///
+/// [`Dormant`] bodies, in systems out of view, are only sampled again once
+/// their last sample is [`DORMANT_SAMPLE_SECONDS`] old, and their
+/// [`SpaceCoordinates`] are set straight to the new sample.
 pub fn propagate_orbits(
+    mut commands: Commands,
     sim_time: Res<SimulationTime>,
-    mut param_set: ParamSet<(
-        Query<(Entity, &KeplerOrbit, Option<&OrbitCenter>)>,
-        Query<&mut SpaceCoordinates>,
-        Query<&SpaceCoordinates, Without<KeplerOrbit>>,
-        Query<&SpaceCoordinates>,
-    )>,
+    orbit_query: Query<(Entity, &KeplerOrbit, Option<&OrbitCenter>)>,
+    dormant_query: Query<(), With<Dormant>>,
+    mut samples_query: Query<&mut OrbitSamples>,
+    mut coords_query: Query<&mut SpaceCoordinates>,
 ) {
     // Get elapsed simulation time in seconds
     let elapsed_time = sim_time.elapsed_seconds();
 
-    // First pass: collect all orbiting entities and their (copied) orbital data
-    let mut entries: Vec<(Entity, KeplerOrbit, Option<Entity>)> = Vec::new();
-    for (entity, orbit, orbit_center) in param_set.p0().iter() {
-        entries.push((entity, *orbit, orbit_center.map(|oc| oc.0)));
+    let orbits: HashMap<Entity, (KeplerOrbit, Option<Entity>)> = orbit_query
+        .iter()
+        .map(|(entity, orbit, center)| (entity, (*orbit, center.map(|c| c.0))))
+        .collect();
+
+    // First pass: solve every stale orbit, sharing parent positions between children
+    let mut resolved = HashMap::new();
+    let mut updates = Vec::new();
+    for (&entity, (orbit, center)) in &orbits {
+        let dormant = dormant_query.contains(entity);
+        if samples_query.get(entity).is_ok_and(|samples| {
+            samples.time == elapsed_time
+                || (dormant && (elapsed_time - samples.time).abs() < DORMANT_SAMPLE_SECONDS)
+        }) {
+            continue;
+        }
+
+        let parent = center
+            .map(|c| {
+                resolve_orbit_position(c, elapsed_time, &orbits, &coords_query, &mut resolved, 1)
+            })
+            .unwrap_or(DVec3::ZERO);
+        let offset = orbit_offset_at(orbit, elapsed_time);
+        updates.push((entity, parent, offset, dormant));
     }
 
-    // Second pass: perform lookups and mutation without holding the p0 iterator borrow
-    for (entity, orbit, orbit_center_entity) in entries {
-        // Calculate current mean anomaly: M = M₀ + n*t
-        let mean_anomaly = orbit.mean_anomaly_epoch + orbit.mean_motion * elapsed_time;
+    // Second pass: record the samples
+    for (entity, parent, offset, dormant) in updates {
+        if let Ok(mut samples) = samples_query.get_mut(entity) {
+            samples.push(elapsed_time, parent, offset);
+            // Dormant bodies are not interpolated between samples
+            if dormant {
+                if let Ok(mut coords) = coords_query.get_mut(entity) {
+                    coords.position = parent + offset;
+                }
+            }
+            continue;
+        }
+
+        commands
+            .entity(entity)
+            .insert(OrbitSamples::at(elapsed_time, parent, offset));
+        if let Ok(mut coords) = coords_query.get_mut(entity) {
+            coords.position = parent + offset;
+        }
+    }
+}
 
-        // Compute orbital position relative to center
-        let orbit_pos = orbit_position_from_mean_anomaly(&orbit, mean_anomaly);
+/// Interpolate between two orbit offsets, sweeping the direction along the
+/// arc between them and blending the radius linearly. Falls back to a plain
+/// lerp for degenerate offsets and snaps to `to` when the two are opposite.
+pub fn interpolate_orbit_offset(from: DVec3, to: DVec3, t: f64) -> DVec3 {
+    let from_radius = from.length();
+    let to_radius = to.length();
+    if from_radius < f64::EPSILON || to_radius < f64::EPSILON {
+        return from.lerp(to, t);
+    }
 
-        // Add parent position if an OrbitCenter is specified
-        let parent_pos = if let Some(oc_entity) = orbit_center_entity {
-            // Try non-orbiting centers first (static stars), then orbiting ones (binary stars)
-            if let Ok(sc) = param_set.p2().get(oc_entity) {
-                sc.position
-            } else if let Ok(sc) = param_set.p3().get(oc_entity) {
-                sc.position
-            } else {
-                DVec3::ZERO
-            }
-        } else {
-            DVec3::ZERO
-        };
+    let from_dir = from / from_radius;
+    let to_dir = to / to_radius;
+    let angle = from_dir.dot(to_dir).clamp(-1.0, 1.0).acos();
+    if angle < 1e-9 {
+        return from.lerp(to, t);
+    }
+    let sin_angle = angle.sin();
+    if sin_angle < 1e-6 {
+        return to;
+    }
 
-        // Update space coordinates (in AU)
-        if let Ok(mut coords) = param_set.p1().get_mut(entity) {
-            coords.position = parent_pos + orbit_pos;
+    let direction = (from_dir * ((1.0 - t) * angle).sin() + to_dir * (t * angle).sin()) / sin_angle;
+    direction * (from_radius + (to_radius - from_radius) * t)
+}
+
+/// System that places orbiting bodies between their last two fixed-timestep
+/// samples, using how far the fixed clock has run past the latest tick.
+/// [`Dormant`] bodies keep the position of their latest sample.
+pub fn interpolate_orbit_positions(
+    fixed_time: Res<Time<Fixed>>,
+    mut query: Query<(&OrbitSamples, &mut SpaceCoordinates), Without<Dormant>>,
+) {
+    let t = fixed_time.overstep_fraction_f64().clamp(0.0, 1.0);
+
+    for (samples, mut coords) in query.iter_mut() {
+        let parent = samples.previous_parent.lerp(samples.current_parent, t);
+        let offset = interpolate_orbit_offset(samples.previous_offset, samples.current_offset, t);
+        let position = parent + offset;
+        // Avoid triggering change detection while the simulation is paused
+        if coords.position != position {
+            coords.position = position;
         }
     }
 }
 
+/// Distance (Bevy units) the camera target may drift from the render origin
+/// before the floating origin is rebased onto it. At this range f32 still
+/// resolves well below a thousandth of a unit.
+pub const FLOATING_ORIGIN_REBASE_THRESHOLD: f32 = 15_000.0;
+
+/// Offset by which the render origin should move to re-center on the camera
+/// target, or `None` while the target is still close enough to the origin.
+pub fn floating_origin_rebase_offset(target_center: Vec3, threshold: f32) -> Option<Vec3> {
+    (target_center.length() > threshold).then_some(target_center)
+}
+
+/// System that re-centers the floating origin on the camera target.
+///
+/// When the camera target strays beyond [`FLOATING_ORIGIN_REBASE_THRESHOLD`], the
+/// origin moves onto it and every world-space transform is shifted by the same
+/// amount, so nothing visibly jumps. Global transforms are shifted as well so
+/// that systems reading them before the next propagation (camera anchoring,
+/// picking) stay consistent within the frame. `update_render_transform` then
+/// re-derives all body positions from their `SpaceCoordinates`.
+#[allow(clippy::type_complexity)]
+pub fn rebase_floating_origin(
+    mut floating_origin: ResMut<FloatingOrigin>,
+    mut camera_query: Query<&mut OrbitCamera, With<GameCamera>>,
+    mut root_query: Query<&mut Transform, (Without<Parent>, Without<Node>)>,
+    mut global_query: Query<&mut GlobalTransform, Without<Node>>,
+) {
+    let Ok(mut orbit_camera) = camera_query.get_single_mut() else {
+        return;
+    };
+    let Some(shift) =
+        floating_origin_rebase_offset(orbit_camera.target_center, FLOATING_ORIGIN_REBASE_THRESHOLD)
+    else {
+        return;
+    };
+
+    floating_origin.position += shift.as_dvec3() / SCALING_FACTOR;
+    orbit_camera.target_center -= shift;
+
+    for mut transform in root_query.iter_mut() {
+        transform.translation -= shift;
+    }
+    for mut global in global_query.iter_mut() {
+        let mut affine = global.affine();
+        affine.translation -= Vec3A::from(shift);
+        *global = GlobalTransform::from(affine);
+    }
+
+    debug!(
+        "Rebased floating origin by {:?} to {:?} AU",
+        shift, floating_origin.position
+    );
+}
+
 /// System that converts high-precision SpaceCoordinates to rendering Transform.
 /// Implements "floating origin" technique by scaling down coordinates and converting to f32.
 ///
+/// Only bodies whose coordinates changed are updated, except when the floating
+/// origin moves (rebasing or a system transition), which re-derives every body.
+///
 /// For moons with a [`LocalOrbitAmplification`] component the local position is
 /// additionally scaled so that the moon renders outside the parent's visual mesh.
+#[allow(clippy::type_complexity)]
 pub fn update_render_transform(
-    mut query: Query<
-        (
-            &SpaceCoordinates,
-            &mut Transform,
-            Option<&LocalOrbitAmplification>,
-            Option<&LogicalParent>,
-        ),
-        Changed<SpaceCoordinates>,
-    >,
+    mut query: Query<(
+        Ref<SpaceCoordinates>,
+        &mut Transform,
+        Option<Ref<LocalOrbitAmplification>>,
+        Option<&LogicalParent>,
+    )>,
     parent_coords: Query<&SpaceCoordinates>,
     floating_origin: Option<Res<crate::astronomy::components::FloatingOrigin>>,
 ) {
+    let origin_changed = floating_origin.as_ref().is_some_and(|fo| fo.is_changed());
     let origin_offset = floating_origin.map(|fo| fo.position).unwrap_or(DVec3::ZERO);
 
     for (coords, mut transform, amplification, logical_parent) in query.iter_mut() {
-        let amp = amplification.map(|a| a.0 as f64).unwrap_or(1.0);
+        // Moon amplification changes with the visual scale (see visual_scale)
+        let amplification_changed = amplification.as_ref().is_some_and(|a| a.is_changed());
+        if !origin_changed && !coords.is_changed() && !amplification_changed {
+            continue;
+        }
+
+        let amp = amplification.as_ref().map(|a| a.0 as f64).unwrap_or(1.0);
 
         // Convert from AU to Bevy units, applying local amplification for moons
         // Shift by floating origin BEFORE scaling
@@ -287,121 +481,6 @@ pub fn update_render_transform(
     }
 }
 
-/// System that draws orbit paths as fading trails (Terra Invicta style).
-/// The trail is brightest at the body's current position and fades out
-/// behind it, creating a comet-tail effect along the orbit.
-///
-/// Samples uniformly in **true anomaly** so that highly eccentric orbits
-/// (comets, long-period objects) get even point density along the geometric
-/// ellipse rather than clustering near apoapsis.
-pub fn draw_orbit_paths(
-    mut gizmos: Gizmos,
-    sim_time: Res<SimulationTime>,
-    current_system: Res<CurrentStarSystem>,
-    query: Query<(
-        &KeplerOrbit,
-        &OrbitPath,
-        Option<&LogicalParent>,
-        Option<&LocalOrbitAmplification>,
-        Option<&Visibility>,
-        Option<&SystemId>,
-    )>,
-    parent_coords: Query<&SpaceCoordinates>,
-    floating_origin: Option<Res<crate::astronomy::components::FloatingOrigin>>,
-) {
-    let elapsed_time = sim_time.elapsed_seconds();
-    let origin_offset = floating_origin.map(|fo| fo.position).unwrap_or(DVec3::ZERO);
-
-    for (orbit, path, logical_parent, amplification, visibility, system_id) in query.iter() {
-        if !path.visible {
-            continue;
-        }
-
-        // Only draw orbits for bodies in the current star system
-        let body_system = system_id.map(|s| s.0).unwrap_or(0);
-        if body_system != current_system.0 {
-            continue;
-        }
-
-        // If the entity is hidden (e.g. because it's a moon whose parent is not anchored), don't draw the orbit
-        if let Some(vis) = visibility {
-            if *vis == Visibility::Hidden {
-                continue;
-            }
-        }
-
-        let amp = amplification.map(|a| a.0 as f64).unwrap_or(1.0);
-
-        let parent_offset = logical_parent
-            .and_then(|lp| parent_coords.get(lp.0).ok())
-            .map(|sc| {
-                let pos = (sc.position - origin_offset) * SCALING_FACTOR;
-                Vec3::new(pos.x as f32, pos.y as f32, pos.z as f32)
-            })
-            .unwrap_or(Vec3::ZERO);
-
-        // Current true anomaly of the body
-        let current_mean_anomaly = orbit.mean_anomaly_epoch + orbit.mean_motion * elapsed_time;
-        let current_true_anomaly = mean_anomaly_to_true_anomaly(
-            current_mean_anomaly.rem_euclid(std::f64::consts::TAU),
-            orbit.eccentricity,
-        );
-
-        // Use more segments for eccentric orbits to keep the periapsis region smooth
-        let segments = if orbit.eccentricity > 0.6 {
-            (path.segments as f64 * (1.0 + orbit.eccentricity * 2.0)) as u32
-        } else {
-            path.segments
-        };
-
-        let true_anomaly_step = std::f64::consts::TAU / (segments as f64);
-
-        // Extract base color channels from path color
-        let base = path.color.to_srgba();
-
-        // Trail covers the full orbit but fades from current position backwards.
-        // Segment 0 is the body's current position (brightest).
-        // Segment N is the point just before the body (dimmest / invisible).
-        let mut prev_point: Option<Vec3> = None;
-
-        for i in 0..=segments {
-            // Walk backwards from the current position in true anomaly
-            let true_anomaly = current_true_anomaly - (i as f64) * true_anomaly_step;
-            let position_au = orbit_position_from_true_anomaly(orbit, true_anomaly);
-
-            let scaled_x = (position_au.x * SCALING_FACTOR * amp) as f32;
-            let scaled_y = (position_au.y * SCALING_FACTOR * amp) as f32;
-            let scaled_z = (position_au.z * SCALING_FACTOR * amp) as f32;
-
-            let point = Vec3::new(scaled_x, scaled_y, scaled_z) + parent_offset;
-
-            if let Some(prev) = prev_point {
-                // t goes from 0.0 (at the body) to 1.0 (full orbit behind)
-                let t = i as f32 / segments as f32;
-
-                // Fade curve: bright near the body, fading to near-zero
-                // Use a smooth power curve for a natural look
-                let alpha = base.alpha * (1.0 - t).powf(1.8);
-
-                // Glow boost near the head of the trail
-                let glow = if t < 0.08 { 1.3 } else { 1.0 };
-
-                if alpha > 0.01 {
-                    let segment_color = Color::srgba(
-                        (base.red * glow).min(1.0),
-                        (base.green * glow).min(1.0),
-                        (base.blue * glow).min(1.0),
-                        alpha,
-                    );
-                    gizmos.line(prev, point, segment_color);
-                }
-            }
-
-            prev_point = Some(point);
-        }
-    }
-}
-
 /// Distance in AU within which a comet tail becomes visible.
 /// Real comets start developing tails around 3-5 AU from the Sun.
 const COMET_TAIL_ONSET_AU: f64 = 5.0;
@@ -511,65 +590,183 @@ fn create_tail_cone_mesh(
     mesh
 }
 
+/// Heliocentric distance (AU, scaled to 1 L☉) at which tail brightness saturates.
+const COMET_TAIL_SATURATION_AU: f64 = 0.5;
+
+/// Minimum change in brightness before a tail material is rewritten.
+const COMET_TAIL_BRIGHTNESS_EPSILON: f32 = 0.01;
+
+/// Snapshot of a comet's sublimation state at a given distance from its star.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CometTailActivity {
+    /// Overall activity level (0.0 at the activation radius, 1.0 at the star)
+    pub intensity: f32,
+    /// Combined brightness of both tails (0.0-1.0)
+    pub brightness: f32,
+    /// Fraction of the tail output carried by the ion tail (0.0-1.0).
+    /// The dust tail carries the remainder.
+    pub ion_share: f32,
+}
+
+impl CometTailActivity {
+    /// Share of the tail output carried by the ion or dust tail.
+    pub fn share(&self, is_ion_tail: bool) -> f32 {
+        if is_ion_tail {
+            self.ion_share
+        } else {
+            1.0 - self.ion_share
+        }
+    }
+}
+
+/// Compute comet activity from heliocentric distance and the parent star's luminosity.
+///
+/// The distance is first converted to the equivalent distance around a 1 L☉ star
+/// (`r / sqrt(L)`), so that a comet receives the same insolation-driven activity
+/// around any star. Returns `None` when the comet is beyond its activation radius
+/// or too close to the star to render a tail.
+///
+/// Close to the star, UV ionisation strengthens the ion tail; further out the
+/// slower-forming dust tail dominates. `dust_fraction` shifts this split per comet.
+pub fn comet_tail_activity(
+    distance_au: f64,
+    luminosity_solar: f64,
+    activity: &CometActivity,
+) -> Option<CometTailActivity> {
+    let flux_scale = luminosity_solar.max(1e-6).sqrt();
+    let effective_distance = distance_au / flux_scale;
+
+    if effective_distance > activity.activation_radius_au
+        || effective_distance < COMET_TAIL_MIN_DISTANCE_AU
+    {
+        return None;
+    }
+
+    let intensity =
+        ((1.0 - effective_distance / activity.activation_radius_au) as f32).clamp(0.0, 1.0);
+    let proximity_boost =
+        (COMET_TAIL_SATURATION_AU * 4.0 / effective_distance.max(COMET_TAIL_SATURATION_AU)) as f32;
+    let brightness = (intensity * proximity_boost.min(2.0)).clamp(0.0, 1.0);
+    let ion_share =
+        ((1.0 - activity.dust_fraction.clamp(0.0, 1.0)) * (0.5 + intensity)).clamp(0.0, 1.0);
+
+    Some(CometTailActivity {
+        intensity,
+        brightness,
+        ion_share,
+    })
+}
+
+/// Write a tail's brightness into its material.
+/// Tails are unlit and additive, so the base color alpha scales the baked vertex
+/// gradient; the emissive term keeps the HDR glare in step with it.
+fn apply_comet_tail_brightness(
+    material: &mut StandardMaterial,
+    is_ion_tail: bool,
+    brightness: f32,
+) {
+    material.base_color = Color::srgba(1.0, 1.0, 1.0, brightness);
+    material.emissive = if is_ion_tail {
+        LinearRgba::new(0.5, 0.7, 1.0, 0.0) * brightness * 10.0
+    } else {
+        LinearRgba::new(1.0, 0.75, 0.3, 0.0) * brightness * 8.0
+    };
+}
+
 /// System that spawns and manages volumetric 3D mesh-based comet tails.
 /// Creates true geometry with gradient transparency for realistic appearance.
+///
+/// Activity is evaluated every frame relative to the comet's parent star, so tail
+/// length, brightness and the ion/dust split track heliocentric distance continuously.
+/// Tails are removed once a comet moves beyond its activation radius.
+#[allow(clippy::type_complexity)]
 pub fn manage_comet_tail_meshes(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     current_system: Res<CurrentStarSystem>,
     comet_query: Query<
-        (Entity, &CelestialBody, &KeplerOrbit, &SpaceCoordinates, Option<&SystemId>),
+        (
+            Entity,
+            &CelestialBody,
+            &SpaceCoordinates,
+            Option<&SystemId>,
+            Option<&OrbitCenter>,
+            Option<&CometActivity>,
+        ),
         (With<Comet>, Without<Destroyed>),
     >,
-    tail_query: Query<(Entity, &CometTail)>,
-    existing_tails: Query<&CometTail>,
+    star_query: Query<(&SpaceCoordinates, Option<&StellarLuminosity>), With<Star>>,
+    mut tail_query: Query<(Entity, &mut CometTail, &Handle<StandardMaterial>)>,
 ) {
-    // Track which comets should have tails
-    let mut comets_needing_tails = std::collections::HashSet::new();
+    // Activity of every comet that should currently have tails
+    let mut active_comets = std::collections::HashMap::new();
 
-    for (entity, body, _orbit, coords, system_id) in comet_query.iter() {
+    for (entity, body, coords, system_id, orbit_center, activity) in comet_query.iter() {
         // Only manage tails for comets in the current star system
         let body_system = system_id.map(|s| s.0).unwrap_or(0);
         if body_system != current_system.0 {
             continue;
         }
 
-        let distance_au = coords.position.length();
+        let (star_position, luminosity) = comet_parent_star(orbit_center, &star_query);
+        let distance_au = (coords.position - star_position).length();
+        let activity = activity.copied().unwrap_or_default();
 
-        // Check if tail should be visible
-        if distance_au <= COMET_TAIL_ONSET_AU
-            && distance_au >= COMET_TAIL_MIN_DISTANCE_AU
-            && distance_au > 1e-6
-        {
-            comets_needing_tails.insert(entity);
-
-            // Check if this comet already has tails
-            let has_tails = existing_tails.iter().any(|t| t.comet_entity == entity);
-
-            if !has_tails {
-                // Spawn tail meshes for this comet
-                spawn_comet_tail_meshes(
-                    &mut commands,
-                    &mut meshes,
-                    &mut materials,
-                    entity,
-                    body,
-                    coords,
-                    distance_au,
-                );
-            }
+        let Some(tail_activity) = comet_tail_activity(distance_au, luminosity, &activity) else {
+            continue;
+        };
+        active_comets.insert(entity, tail_activity);
+
+        // Check if this comet already has tails
+        let has_tails = tail_query.iter().any(|(_, t, _)| t.comet_entity == entity);
+
+        if !has_tails {
+            spawn_comet_tail_meshes(
+                &mut commands,
+                &mut meshes,
+                &mut materials,
+                entity,
+                body,
+                &tail_activity,
+            );
         }
     }
 
-    // Despawn tails for comets that no longer need them
-    for (tail_entity, tail) in tail_query.iter() {
-        if !comets_needing_tails.contains(&tail.comet_entity) {
+    for (tail_entity, mut tail, material_handle) in tail_query.iter_mut() {
+        // Despawn tails for comets that are no longer active
+        let Some(tail_activity) = active_comets.get(&tail.comet_entity) else {
             commands.entity(tail_entity).despawn_recursive();
+            continue;
+        };
+
+        let share = tail_activity.share(tail.is_ion_tail);
+        let brightness = (tail_activity.brightness * share * 2.0).clamp(0.0, 1.0);
+        tail.intensity = tail_activity.intensity;
+        tail.share = share;
+
+        // Only touch the material asset when the change is visible
+        if (brightness - tail.brightness).abs() >= COMET_TAIL_BRIGHTNESS_EPSILON {
+            if let Some(material) = materials.get_mut(material_handle) {
+                apply_comet_tail_brightness(material, tail.is_ion_tail, brightness);
+            }
+            tail.brightness = brightness;
         }
     }
 }
 
+/// Resolve the position and luminosity of the star a comet orbits.
+/// Falls back to a Sun-like star at the origin when no parent is known.
+fn comet_parent_star(
+    orbit_center: Option<&OrbitCenter>,
+    star_query: &Query<(&SpaceCoordinates, Option<&StellarLuminosity>), With<Star>>,
+) -> (DVec3, f64) {
+    orbit_center
+        .and_then(|center| star_query.get(center.0).ok())
+        .map(|(coords, luminosity)| (coords.position, luminosity.map_or(1.0, |l| l.0)))
+        .unwrap_or((DVec3::ZERO, 1.0))
+}
+
 /// Spawns ion and dust tail meshes for a comet
 fn spawn_comet_tail_meshes(
     commands: &mut Commands,
@@ -577,14 +774,8 @@ fn spawn_comet_tail_meshes(
     materials: &mut ResMut<Assets<StandardMaterial>>,
     comet_entity: Entity,
     body: &CelestialBody,
-    _coords: &SpaceCoordinates,
-    distance_au: f64,
+    activity: &CometTailActivity,
 ) {
-    // Calculate tail parameters
-    let intensity = ((1.0 - distance_au / COMET_TAIL_ONSET_AU) as f32).clamp(0.0, 1.0);
-    // Adjusted proximity boost - less aggressive than before
-    let proximity_boost = (2.0 / distance_au.max(0.5)) as f32;
-    let brightness = (intensity * proximity_boost.min(2.0)).clamp(0.0, 1.0);
     // Base tail length for spawning geometry - scaling will be applied dynamically
     let tail_length = COMET_TAIL_MAX_LENGTH;
 
@@ -594,11 +785,14 @@ fn spawn_comet_tail_meshes(
         seed = seed.wrapping_mul(31).wrapping_add(byte as u32);
     }
 
+    // Vertex alpha only shapes the gradient; brightness is driven by the material
     // === ION TAIL (Type I): narrow, bluish-white ===
     // Use fixed small radii, slightly larger as requested
+    let ion_share = activity.share(true);
+    let ion_brightness = (activity.brightness * ion_share * 2.0).clamp(0.0, 1.0);
     let ion_base_radius = 1.5;
     let ion_tip_radius = 0.3;
-    let ion_base_color = Color::srgba(0.7, 0.85, 1.0, brightness * 0.6);
+    let ion_base_color = Color::srgba(0.7, 0.85, 1.0, 0.6);
     let ion_tip_color = Color::srgba(0.5, 0.75, 1.0, 0.0);
 
     let ion_mesh = meshes.add(create_tail_cone_mesh(
@@ -609,14 +803,14 @@ fn spawn_comet_tail_meshes(
         ion_tip_color,
     ));
 
-    let ion_material = materials.add(StandardMaterial {
-        base_color: Color::WHITE,
-        emissive: LinearRgba::new(0.5, 0.7, 1.0, 0.0) * brightness * 10.0, // Increased glare (HDR)
+    let mut ion_material = StandardMaterial {
         alpha_mode: AlphaMode::Add,
         unlit: true,
         cull_mode: None, // Double-sided
         ..default()
-    });
+    };
+    apply_comet_tail_brightness(&mut ion_material, true, ion_brightness);
+    let ion_material = materials.add(ion_material);
 
     commands.spawn((
         PbrBundle {
@@ -628,14 +822,19 @@ fn spawn_comet_tail_meshes(
         CometTail {
             comet_entity,
             is_ion_tail: true,
+            intensity: activity.intensity,
+            share: ion_share,
+            brightness: ion_brightness,
         },
     ));
 
     // === DUST TAIL (Type II): wider, yellowish ===
     // Fixed radii, wider than ion tail and enclosing it at base
+    let dust_share = activity.share(false);
+    let dust_brightness = (activity.brightness * dust_share * 2.0).clamp(0.0, 1.0);
     let dust_base_radius = 2.5;
     let dust_tip_radius = 0.6;
-    let dust_base_color = Color::srgba(1.0, 0.85, 0.4, brightness * 0.5);
+    let dust_base_color = Color::srgba(1.0, 0.85, 0.4, 0.5);
     let dust_tip_color = Color::srgba(1.0, 0.7, 0.2, 0.0);
 
     let dust_mesh = meshes.add(create_tail_cone_mesh(
@@ -646,14 +845,14 @@ fn spawn_comet_tail_meshes(
         dust_tip_color,
     ));
 
-    let dust_material = materials.add(StandardMaterial {
-        base_color: Color::WHITE,
-        emissive: LinearRgba::new(1.0, 0.75, 0.3, 0.0) * brightness * 8.0, // Increased glare (HDR)
+    let mut dust_material = StandardMaterial {
         alpha_mode: AlphaMode::Add,
         unlit: true,
         cull_mode: None,
         ..default()
-    });
+    };
+    apply_comet_tail_brightness(&mut dust_material, false, dust_brightness);
+    let dust_material = materials.add(dust_material);
 
     commands.spawn((
         PbrBundle {
@@ -665,33 +864,61 @@ fn spawn_comet_tail_meshes(
         CometTail {
             comet_entity,
             is_ion_tail: false,
+            intensity: activity.intensity,
+            share: dust_share,
+            brightness: dust_brightness,
         },
     ));
 }
 
+/// Length and width scale of a tail mesh for a given activity level and share.
+///
+/// Length grows linearly with activity so the tail emerges from zero at the
+/// activation radius; the tail carrying the larger share is drawn longer.
+/// Width grows more slowly so faint tails still read as a thin streak.
+pub fn comet_tail_scale(intensity: f32, share: f32) -> Vec3 {
+    let length = (intensity * (0.5 + share)).max(0.01);
+    let width = intensity.sqrt().max(0.01);
+    Vec3::new(width, width, length)
+}
+
 /// System that updates tail mesh positions and orientations each frame.
-/// Tails always point away from the sun and follow their parent comet.
+/// Tails always point away from their parent star and follow their parent comet.
 pub fn update_tail_transforms(
-    comet_query: Query<(&SpaceCoordinates, &KeplerOrbit, &CelestialBody), With<Comet>>,
+    comet_query: Query<
+        (
+            &SpaceCoordinates,
+            &KeplerOrbit,
+            &CelestialBody,
+            Option<&OrbitCenter>,
+        ),
+        With<Comet>,
+    >,
+    star_query: Query<&SpaceCoordinates, With<Star>>,
     mut tail_query: Query<(&mut Transform, &CometTail)>,
+    floating_origin: Option<Res<crate::astronomy::components::FloatingOrigin>>,
 ) {
+    let origin_offset = floating_origin.map(|fo| fo.position).unwrap_or(DVec3::ZERO);
+
     for (mut transform, tail) in tail_query.iter_mut() {
-        if let Ok((coords, orbit, body)) = comet_query.get(tail.comet_entity) {
+        if let Ok((coords, orbit, body, orbit_center)) = comet_query.get(tail.comet_entity) {
             // Convert comet position to rendering coordinates
-            let comet_pos_scaled = coords.position * SCALING_FACTOR;
+            let comet_pos_scaled = (coords.position - origin_offset) * SCALING_FACTOR;
             let comet_pos = Vec3::new(
                 comet_pos_scaled.x as f32,
                 comet_pos_scaled.y as f32,
                 comet_pos_scaled.z as f32,
             );
 
-            // Anti-sunward direction (sun at origin)
-            let to_sun = -comet_pos;
-            let sun_distance = to_sun.length();
-            if sun_distance < 1e-6 {
+            // Anti-starward direction (star at origin unless the comet has a parent)
+            let star_position = orbit_center
+                .and_then(|center| star_query.get(center.0).ok())
+                .map_or(DVec3::ZERO, |star| star.position);
+            let to_star = (star_position - coords.position).as_vec3();
+            if to_star.length() < 1e-6 {
                 continue;
             }
-            let anti_sun_dir = -to_sun.normalize();
+            let anti_sun_dir = -to_star.normalize();
 
             // Offset tail to start at comet surface
             // Both tails start at the same point to avoid dual-cone effect
@@ -700,19 +927,8 @@ pub fn update_tail_transforms(
 
             transform.translation = comet_pos + surface_offset;
 
-            // Compute dynamic scale based on distance from sun
-            // Tail should be small/invisible near onset and grow larger near sun
-            let distance_au = coords.position.length() as f32;
-            let onset_au = COMET_TAIL_ONSET_AU as f32;
-            
-            // Normalized intensity (0.0 at onset, 1.0 at sun)
-            let intensity = ((1.0 - distance_au / onset_au)).clamp(0.0, 1.0);
-            
-            // Scale factor: start small (0.1) and grow to full size (1.0)
-            let dynamic_scale = (0.1 + intensity * 0.9).max(0.01);
-            
-            // Apply scale to length (Z) and width (X, Y)
-            transform.scale = Vec3::splat(dynamic_scale);
+            // Length and width follow the activity computed by manage_comet_tail_meshes
+            transform.scale = comet_tail_scale(tail.intensity, tail.share);
 
             // Orient tail to point away from sun
             // Cone extends along +Z axis, so look along anti-sunward direction
@@ -966,7 +1182,7 @@ pub fn draw_comet_tails(
 
 /// Perihelion distance (in AU) at which ISON disintegrates
 /// Historical: ISON broke apart around 730,000 km from sun surface (0.0049 AU from center)
-const ISON_DESTRUCTION_DISTANCE_AU: f64 = 0.005;
+pub const ISON_DESTRUCTION_DISTANCE_AU: f64 = 0.005;
 
 /// System that checks for natural destruction events (e.g., Comet ISON solar disintegration).
 /// This system monitors comets approaching the sun and triggers destruction for historically
@@ -1082,60 +1298,209 @@ pub fn update_orbit_visibility(
     }
 }
 
-/// System that toggles moon mesh visibility based on camera anchor.
+/// LOD level of a moon: drawn individually only when its parent is anchored
+/// or the camera is within `MOON_EXPAND_RADII` parent radii of the parent,
+/// otherwise folded into the parent's moon-group marker
+pub fn moon_lod(parent_anchored: bool, parent_distance: f32, parent_radius: f32) -> BodyLod {
+    if parent_anchored || parent_distance < parent_radius.max(1.0) * MOON_EXPAND_RADII {
+        BodyLod::Full
+    } else {
+        BodyLod::Collapsed
+    }
+}
+
+/// LOD level of a comet or asteroid: a point once its mesh would be a few
+/// pixels at most
+pub fn minor_body_lod(camera_distance: f32, visual_radius: f32) -> BodyLod {
+    if camera_distance > visual_radius.max(1.0) * MINOR_BODY_POINT_RADII {
+        BodyLod::Point
+    } else {
+        BodyLod::Full
+    }
+}
+
+type LodBodyQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut Visibility,
+        &'static GlobalTransform,
+        &'static CelestialBody,
+        Option<&'static LogicalParent>,
+        Has<Moon>,
+        Has<Comet>,
+        Has<Asteroid>,
+        Has<Selected>,
+        Option<&'static SystemId>,
+        Option<&'static mut BodyLod>,
+    ),
+    Without<Destroyed>,
+>;
+
+/// System that chooses each body's [`BodyLod`] and toggles its mesh.
 ///
-/// Moons are only visible when their parent planet is the camera's anchor.
-/// This prevents overlapping moon systems from different planets.
+/// Hierarchical LOD keeps frame time flat with thousands of bodies:
+/// - Moons collapse into one marker per planet unless the planet is the
+///   camera anchor or the camera is close to it. This also prevents
+///   overlapping moon systems from different planets.
+/// - Distant comets and asteroids are drawn as points (see `draw_lod_markers`);
+///   comet tails follow their comet.
+/// - Bodies from other star systems are hidden, even if selected or anchored.
 ///
-/// Also respects the current star system — bodies from other systems are
-/// left hidden even if selected or anchored.
+/// Selected bodies in the current system are always drawn in full. Collapsed
+/// and hidden bodies cannot be picked. Components are only written on a
+/// change so the renderer does not see every body as changed every frame.
 pub fn update_body_lod_visibility(
-    camera_query: Query<&CameraAnchor, With<GameCamera>>,
+    mut commands: Commands,
+    camera_query: Query<(&CameraAnchor, &GlobalTransform), With<GameCamera>>,
     current_system: Res<CurrentStarSystem>,
-    mut body_query: Query<
-        (
-            &mut Visibility,
-            Option<&LogicalParent>,
-            Option<&Moon>,
-            Option<&Selected>,
-            Option<&SystemId>,
-        ),
-        With<CelestialBody>,
-    >,
+    mut body_query: LodBodyQuery,
+    parent_query: Query<(&GlobalTransform, &CelestialBody)>,
+    mut tail_query: Query<(&CometTail, &mut Visibility), Without<CelestialBody>>,
 ) {
-    let Ok(anchor) = camera_query.get_single() else {
+    let Ok((anchor, camera_transform)) = camera_query.get_single() else {
         return;
     };
-
-    for (mut visibility, logical_parent, moon, selected, system_id) in body_query.iter_mut() {
-        // Bodies from other star systems must stay hidden, regardless of
-        // selection or anchor state.
+    let camera_position = camera_transform.translation();
+
+    for (
+        entity,
+        mut visibility,
+        transform,
+        body,
+        logical_parent,
+        moon,
+        comet,
+        asteroid,
+        selected,
+        system_id,
+        current_lod,
+    ) in body_query.iter_mut()
+    {
         let body_system = system_id.map(|s| s.0).unwrap_or(0);
-        if body_system != current_system.0 {
-            *visibility = Visibility::Hidden;
-            continue;
+        let lod = if body_system != current_system.0 {
+            BodyLod::Hidden
+        } else if selected || anchor.0 == Some(entity) {
+            BodyLod::Full
+        } else if moon {
+            let parent = logical_parent.map(|lp| lp.0);
+            let parent_anchored = anchor.0.is_some() && parent == anchor.0;
+            match parent.and_then(|p| parent_query.get(p).ok()) {
+                Some((parent_transform, parent_body)) => moon_lod(
+                    parent_anchored,
+                    parent_transform.translation().distance(camera_position),
+                    parent_body.visual_radius,
+                ),
+                None => moon_lod(parent_anchored, f32::INFINITY, 0.0),
+            }
+        } else if comet || asteroid {
+            minor_body_lod(
+                transform.translation().distance(camera_position),
+                body.visual_radius,
+            )
+        } else {
+            // Planets, stars and dwarf planets are always drawn
+            BodyLod::Full
+        };
+
+        let target = lod.mesh_visibility();
+        if *visibility != target {
+            *visibility = target;
+        }
+        match current_lod {
+            Some(mut current) if *current != lod => {
+                *current = lod;
+            }
+            None if lod != BodyLod::Full => {
+                commands.entity(entity).insert(lod);
+            }
+            _ => {}
         }
+    }
 
-        // Selected bodies in the current system are always visible
-        if selected.is_some() {
-            *visibility = Visibility::Inherited;
-            continue;
+    // Tails are separate entities: show them only with their comet's mesh
+    for (tail, mut visibility) in tail_query.iter_mut() {
+        let target = body_query
+            .get(tail.comet_entity)
+            .map_or(Visibility::Hidden, |(_, comet_visibility, ..)| *comet_visibility);
+        if *visibility != target {
+            *visibility = target;
         }
+    }
+}
 
-        if moon.is_some() {
-            // Moon visibility: only when parent planet is the camera anchor
-            let parent_anchored = anchor.0.is_some()
-                && logical_parent
-                    .map(|lp| Some(lp.0) == anchor.0)
-                    .unwrap_or(false);
-            *visibility = if parent_anchored {
-                Visibility::Inherited
-            } else {
-                Visibility::Hidden
-            };
+/// System that draws the collapsed LOD levels: one ring per planet standing
+/// in for its moons, and a small camera-facing circle for each point body.
+/// Markers keep a constant size on screen.
+pub fn draw_lod_markers(
+    mut gizmos: Gizmos,
+    camera_query: Query<&GlobalTransform, With<GameCamera>>,
+    body_query: Query<(&GlobalTransform, &CelestialBody, &BodyLod, Option<&LogicalParent>)>,
+    parent_query: Query<(&GlobalTransform, &CelestialBody, Option<&BodyLod>)>,
+    mut moon_groups: Local<HashMap<Entity, usize>>,
+) {
+    let Ok(camera_transform) = camera_query.get_single() else {
+        return;
+    };
+    let camera_position = camera_transform.translation();
+    let facing = |position: Vec3| Dir3::new(camera_position - position).unwrap_or(Dir3::Y);
+
+    moon_groups.clear();
+    for (transform, body, lod, logical_parent) in body_query.iter() {
+        match lod {
+            BodyLod::Point => {
+                let position = transform.translation();
+                let radius = position.distance(camera_position) * LOD_POINT_MARKER_SCALE;
+                let color = if body.body_type == BodyType::Comet {
+                    Color::srgba(0.6, 0.85, 1.0, 0.9)
+                } else {
+                    Color::srgba(0.7, 0.65, 0.55, 0.8)
+                };
+                gizmos
+                    .circle(position, facing(position), radius, color)
+                    .resolution(8);
+            }
+            BodyLod::Collapsed => {
+                if let Some(parent) = logical_parent {
+                    *moon_groups.entry(parent.0).or_default() += 1;
+                }
+            }
+            BodyLod::Full | BodyLod::Hidden => {}
         }
-        // Planets, Stars, Asteroids, Dwarf Planets: always visible
     }
+
+    for (&parent, &moons) in moon_groups.iter() {
+        let Ok((transform, body, lod)) = parent_query.get(parent) else {
+            continue;
+        };
+        if lod.is_some_and(|lod| *lod != BodyLod::Full) {
+            continue;
+        }
+        // Ring just outside the planet, a little brighter for larger moon systems
+        let position = transform.translation();
+        let alpha = (0.25 + 0.05 * moons as f32).min(0.6);
+        gizmos
+            .circle(
+                position,
+                facing(position),
+                body.visual_radius * MOON_GROUP_RING_RADII,
+                Color::srgba(0.75, 0.8, 0.9, alpha),
+            )
+            .resolution(48);
+    }
+}
+
+/// Bodies that may lie under a picking ray, culled through the spatial index.
+/// The ray is in render space; the index works in simulation space (AU).
+fn pick_candidates(
+    spatial_index: &SpatialIndex,
+    floating_origin: &FloatingOrigin,
+    ray: Ray3d,
+) -> Vec<Entity> {
+    let origin = ray.origin.as_dvec3() / SCALING_FACTOR + floating_origin.position;
+    let margin = SELECTION_CLICK_RADIUS as f64 / SCALING_FACTOR;
+    spatial_index.ray_candidates(origin, ray.direction.as_dvec3(), margin)
 }
 
 #[derive(Default)]
@@ -1144,14 +1509,35 @@ pub struct SelectionState {
     pub last_clicked_entity: Option<Entity>,
 }
 
+/// Whether a cursor ray passing `distance` from a body's center points at
+/// it. Rings only count on their annulus, so the planet in the middle stays
+/// clickable.
+fn cursor_hits_body(body: &CelestialBody, ring: Option<&Ring>, distance: f32) -> bool {
+    if let Some(ring) = ring {
+        let inner_radius = body.visual_radius * ring.inner_radius_km / ring.outer_radius_km;
+        if distance < inner_radius {
+            return false;
+        }
+    }
+    distance < body.visual_radius + SELECTION_CLICK_RADIUS
+}
+
 /// System that handles celestial body selection via mouse clicks
-#[allow(clippy::too_many_arguments)]
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn handle_body_selection(
     view_mode: Res<ViewMode>,
     mouse_button: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
     windows: Query<&Window, With<PrimaryWindow>>,
     camera_query: Query<(&Camera, &GlobalTransform), With<GameCamera>>,
-    body_query: Query<(Entity, &GlobalTransform, &CelestialBody, Option<&SystemId>)>,
+    body_query: Query<(
+        Entity,
+        &GlobalTransform,
+        &CelestialBody,
+        Option<&SystemId>,
+        Option<&Ring>,
+        Option<&BodyLod>,
+    )>,
     current_system: Res<CurrentStarSystem>,
     mut commands: Commands,
     selected_query: Query<Entity, With<Selected>>,
@@ -1160,6 +1546,8 @@ pub fn handle_body_selection(
     mut selection_state: Local<SelectionState>,
     mut egui_contexts: bevy_egui::EguiContexts,
     active_menu: Res<ActiveMenu>,
+    spatial_index: Res<SpatialIndex>,
+    floating_origin: Res<FloatingOrigin>,
 ) {
     // Disable body selection when a full-screen overlay menu is active
     if active_menu.current.blocks_world_interaction() {
@@ -1203,12 +1591,17 @@ pub fn handle_body_selection(
     // Stores: (Entity, distance from camera, body name)
     let mut closest_body: Option<(Entity, f32, String)> = None;
 
-    for (entity, transform, body, system_id) in body_query.iter() {
+    let candidates = pick_candidates(&spatial_index, &floating_origin, ray);
+    for (entity, transform, body, system_id, ring, lod) in body_query.iter_many(&candidates) {
         // Only interact with bodies in the current star system
         let body_system = system_id.map(|s| s.0).unwrap_or(0);
         if body_system != current_system.0 {
             continue;
         }
+        // Collapsed moons are picked through their planet
+        if lod.is_some_and(|lod| !lod.pickable()) {
+            continue;
+        }
 
         let body_pos = transform.translation();
 
@@ -1227,9 +1620,7 @@ pub fn handle_body_selection(
         // Check if click is within visual radius + margin
         // This allows clicking on the visible surface of large bodies, and provides
         // a generous margin for small bodies
-        let selection_radius = body.visual_radius + SELECTION_CLICK_RADIUS;
-
-        if distance < selection_radius {
+        if cursor_hits_body(body, ring, distance) {
             match closest_body {
                 None => closest_body = Some((entity, projection, body.name.clone())),
                 Some((_, prev_dist, _)) if projection < prev_dist => {
@@ -1240,6 +1631,26 @@ pub fn handle_body_selection(
         }
     }
 
+    // Ctrl-click adds the body to the selection or removes it, leaving the rest
+    let additive = keyboard.any_pressed([
+        KeyCode::ControlLeft,
+        KeyCode::ControlRight,
+        KeyCode::SuperLeft,
+        KeyCode::SuperRight,
+    ]);
+    if additive {
+        if let Some((entity, _, name)) = closest_body {
+            if selected_query.contains(entity) {
+                commands.entity(entity).remove::<Selected>();
+            } else {
+                commands.entity(entity).insert(Selected);
+                info!("Added celestial body to selection: {}", name);
+            }
+        }
+        selection_state.last_clicked_entity = None;
+        return;
+    }
+
     // Deselect all currently selected bodies
     for entity in selected_query.iter() {
         commands.entity(entity).remove::<Selected>();
@@ -1267,16 +1678,26 @@ pub fn handle_body_selection(
 }
 
 /// System that handles celestial body hover detection via mouse position
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn handle_body_hover(
     view_mode: Res<ViewMode>,
     windows: Query<&Window, With<PrimaryWindow>>,
     camera_query: Query<(&Camera, &GlobalTransform), With<GameCamera>>,
-    body_query: Query<(Entity, &GlobalTransform, &CelestialBody, Option<&SystemId>)>,
+    body_query: Query<(
+        Entity,
+        &GlobalTransform,
+        &CelestialBody,
+        Option<&SystemId>,
+        Option<&Ring>,
+        Option<&BodyLod>,
+    )>,
     current_system: Res<CurrentStarSystem>,
     mut commands: Commands,
     hovered_query: Query<Entity, With<Hovered>>,
     mut egui_contexts: bevy_egui::EguiContexts,
     active_menu: Res<ActiveMenu>,
+    spatial_index: Res<SpatialIndex>,
+    floating_origin: Res<FloatingOrigin>,
 ) {
     // Disable hover when a full-screen menu overlay is active (Research, etc.)
     if active_menu.current.blocks_world_interaction() {
@@ -1331,12 +1752,17 @@ pub fn handle_body_hover(
     // Find the closest body to the ray
     let mut closest_body: Option<(Entity, f32)> = None;
 
-    for (entity, transform, body, system_id) in body_query.iter() {
+    let candidates = pick_candidates(&spatial_index, &floating_origin, ray);
+    for (entity, transform, body, system_id, ring, lod) in body_query.iter_many(&candidates) {
         // Only interact with bodies in the current star system
         let body_system = system_id.map(|s| s.0).unwrap_or(0);
         if body_system != current_system.0 {
             continue;
         }
+        // Collapsed moons are picked through their planet
+        if lod.is_some_and(|lod| !lod.pickable()) {
+            continue;
+        }
 
         let body_pos = transform.translation();
 
@@ -1353,8 +1779,7 @@ pub fn handle_body_hover(
         let distance = (body_pos - closest_point).length();
 
         // Check if cursor is within hover radius (visual radius + margin)
-        let selection_radius = body.visual_radius + SELECTION_CLICK_RADIUS;
-        if distance < selection_radius {
+        if cursor_hits_body(body, ring, distance) {
             match closest_body {
                 None => closest_body = Some((entity, projection)),
                 Some((_, prev_dist)) if projection < prev_dist => {
@@ -1691,6 +2116,30 @@ pub fn scale_markers_with_zoom(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_moons_collapse_far_from_their_planet() {
+        let radius = 50.0;
+        let near = radius * MOON_EXPAND_RADII * 0.5;
+        let far = radius * MOON_EXPAND_RADII * 2.0;
+        assert_eq!(moon_lod(false, near, radius), BodyLod::Full);
+        assert_eq!(moon_lod(false, far, radius), BodyLod::Collapsed);
+        // An anchored planet always shows its moons
+        assert_eq!(moon_lod(true, far, radius), BodyLod::Full);
+        assert!(!BodyLod::Collapsed.pickable());
+        assert_eq!(BodyLod::Collapsed.mesh_visibility(), Visibility::Hidden);
+    }
+
+    #[test]
+    fn test_minor_bodies_become_points() {
+        let radius = 5.0;
+        assert_eq!(minor_body_lod(1000.0, radius), BodyLod::Full);
+        let lod = minor_body_lod(radius * MINOR_BODY_POINT_RADII * 1.5, radius);
+        assert_eq!(lod, BodyLod::Point);
+        // Points are still picked, but their mesh is hidden
+        assert!(lod.pickable());
+        assert_eq!(lod.mesh_visibility(), Visibility::Hidden);
+    }
+
     #[test]
     fn test_solve_kepler_circular_orbit() {
         // For circular orbit (e=0), eccentric anomaly should equal mean anomaly
@@ -1785,6 +2234,57 @@ mod tests {
         assert!(coords.position.x.abs() > 0.0 || coords.position.y.abs() > 0.0);
     }
 
+    #[test]
+    fn test_propagate_orbits_samples_moon_with_parent_at_same_time() {
+        let mut app = App::new();
+        app.init_resource::<SimulationTime>();
+        app.add_systems(Update, propagate_orbits);
+
+        let planet = app
+            .world_mut()
+            .spawn((KeplerOrbit::circular(1.0, 1.0), SpaceCoordinates::default()))
+            .id();
+        let moon = app
+            .world_mut()
+            .spawn((
+                KeplerOrbit::circular(0.01, 10.0),
+                OrbitCenter(planet),
+                SpaceCoordinates::default(),
+            ))
+            .id();
+
+        app.update();
+        app.world_mut().resource_mut::<SimulationTime>().elapsed = 0.5;
+        app.update();
+
+        let samples = *app.world().get::<OrbitSamples>(moon).unwrap();
+        let planet_samples = *app.world().get::<OrbitSamples>(planet).unwrap();
+        assert_eq!(samples.time, 0.5);
+        // The moon's center is the planet's freshly solved position, not a stale one
+        let planet_now = planet_samples.current_parent + planet_samples.current_offset;
+        assert!((samples.current_parent - planet_now).length() < 1e-12);
+        assert!((samples.current_offset.length() - 0.01).abs() < 1e-9);
+        assert_ne!(samples.previous_offset, samples.current_offset);
+    }
+
+    #[test]
+    fn test_interpolate_orbit_offset_follows_arc() {
+        let from = DVec3::new(1.0, 0.0, 0.0);
+        let to = DVec3::new(0.0, 1.0, 0.0);
+
+        let mid = interpolate_orbit_offset(from, to, 0.5);
+        // Stays on the circle instead of cutting the chord
+        assert!((mid.length() - 1.0).abs() < 1e-12);
+        assert!((mid.x - mid.y).abs() < 1e-12);
+
+        assert!((interpolate_orbit_offset(from, to, 0.0) - from).length() < 1e-12);
+        assert!((interpolate_orbit_offset(from, to, 1.0) - to).length() < 1e-12);
+
+        // Radius is blended linearly for eccentric orbits
+        let far = DVec3::new(0.0, 3.0, 0.0);
+        assert!((interpolate_orbit_offset(from, far, 0.5).length() - 2.0).abs() < 1e-12);
+    }
+
     #[test]
     fn test_update_render_transform_scaling() {
         // Test that the transform system correctly scales coordinates
@@ -1811,4 +2311,118 @@ mod tests {
         );
         assert!((transform.translation - expected).length() < 1e-5);
     }
+
+    #[test]
+    fn test_comet_activity_switches_off_beyond_activation_radius() {
+        let activity = CometActivity {
+            activation_radius_au: 4.0,
+            dust_fraction: 0.5,
+        };
+        assert!(comet_tail_activity(4.5, 1.0, &activity).is_none());
+        assert!(comet_tail_activity(3.5, 1.0, &activity).is_some());
+        // Too close to the star to render a tail
+        assert!(comet_tail_activity(0.01, 1.0, &activity).is_none());
+    }
+
+    #[test]
+    fn test_comet_activity_scales_with_luminosity() {
+        let activity = CometActivity::default();
+        // Same insolation: 2 AU around a 4 L☉ star equals 1 AU around the Sun
+        let sun = comet_tail_activity(1.0, 1.0, &activity).unwrap();
+        let bright = comet_tail_activity(2.0, 4.0, &activity).unwrap();
+        assert!((sun.intensity - bright.intensity).abs() < 1e-6);
+        assert!((sun.brightness - bright.brightness).abs() < 1e-6);
+
+        // A dim red dwarf leaves a comet at 1 AU dormant
+        assert!(comet_tail_activity(1.0, 0.01, &activity).is_none());
+    }
+
+    #[test]
+    fn test_comet_activity_grows_continuously_toward_star() {
+        let activity = CometActivity::default();
+        let mut previous = 0.0;
+        for step in 1..=45 {
+            let distance = 5.0 - step as f64 * 0.1;
+            let state = comet_tail_activity(distance, 1.0, &activity).unwrap();
+            assert!(state.intensity >= previous);
+            assert!(
+                state.intensity - previous < 0.05,
+                "intensity jumped at {distance} AU"
+            );
+            previous = state.intensity;
+        }
+
+        // Tail emerges from (near) zero size at the activation radius
+        let edge = comet_tail_activity(4.999, 1.0, &activity).unwrap();
+        assert!(comet_tail_scale(edge.intensity, edge.ion_share).z <= 0.01);
+    }
+
+    #[test]
+    fn test_comet_ion_share_rises_near_star() {
+        let activity = CometActivity::default();
+        let far = comet_tail_activity(4.0, 1.0, &activity).unwrap();
+        let near = comet_tail_activity(0.5, 1.0, &activity).unwrap();
+        assert!(near.ion_share > far.ion_share);
+        assert!(
+            far.share(false) > far.share(true),
+            "dust should dominate far out"
+        );
+        assert!((near.share(true) + near.share(false) - 1.0).abs() < 1e-6);
+
+        // A dustier nucleus keeps more of its output in the dust tail
+        let dusty = CometActivity {
+            dust_fraction: 0.9,
+            ..activity
+        };
+        let dusty_near = comet_tail_activity(0.5, 1.0, &dusty).unwrap();
+        assert!(dusty_near.ion_share < near.ion_share);
+    }
+
+    #[test]
+    fn test_floating_origin_rebase_offset_threshold() {
+        assert!(floating_origin_rebase_offset(Vec3::new(100.0, 0.0, 0.0), 1000.0).is_none());
+        let shift = floating_origin_rebase_offset(Vec3::new(0.0, 2000.0, 0.0), 1000.0);
+        assert_eq!(shift, Some(Vec3::new(0.0, 2000.0, 0.0)));
+    }
+
+    #[test]
+    fn test_rebase_floating_origin_shifts_static_bodies() {
+        let mut app = App::new();
+        app.init_resource::<FloatingOrigin>();
+        app.add_systems(
+            Update,
+            (rebase_floating_origin, update_render_transform).chain(),
+        );
+
+        // Camera target well beyond the rebase threshold (20 AU out on X)
+        let target = Vec3::new(20.0 * SCALING_FACTOR as f32, 0.0, 0.0);
+        app.world_mut().spawn((
+            GameCamera,
+            OrbitCamera {
+                target_center: target,
+                ..default()
+            },
+        ));
+        let body = app
+            .world_mut()
+            .spawn((
+                SpaceCoordinates::new(DVec3::new(21.0, 0.0, 0.0)),
+                Transform::default(),
+            ))
+            .id();
+
+        app.update();
+        // Second frame: coordinates are unchanged, only the origin moved
+        app.update();
+
+        let origin = app.world().resource::<FloatingOrigin>().position;
+        assert!((origin.x - 20.0).abs() < 1e-9);
+
+        let translation = app.world().get::<Transform>(body).unwrap().translation;
+        assert!((translation.x - SCALING_FACTOR as f32).abs() < 1e-3);
+
+        let mut cameras = app.world_mut().query::<&OrbitCamera>();
+        let camera = cameras.single(app.world());
+        assert!(camera.target_center.length() < 1e-3);
+    }
 }
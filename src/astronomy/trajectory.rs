@@ -0,0 +1,203 @@
+//! Trajectory prediction for selected bodies
+//!
+//! At high time scales a body jumps far along its orbit between frames. For
+//! every selected body, the Kepler solver predicts where it will be over the
+//! next `horizon_days`: the predicted path is drawn as a fading trail ahead
+//! of the body, with ghost markers at evenly spaced times. Moons follow their
+//! parent's predicted motion, so their ghosts land where they will actually
+//! be rather than around the parent's current position.
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::components::{
+    FloatingOrigin, KeplerOrbit, LocalOrbitAmplification, Selected, SpaceCoordinates,
+};
+use super::systems::{orbit_position_from_mean_anomaly, SCALING_FACTOR};
+use crate::plugins::solar_system::{CelestialBody, LogicalParent};
+use crate::ui::{SimulationTime, UiSettings};
+
+/// Points along the predicted trail
+const TRAIL_SEGMENTS: usize = 96;
+
+/// Parent levels followed before a hierarchy is assumed to be cyclic
+const MAX_PARENT_DEPTH: usize = 8;
+
+/// How far ahead trajectories are predicted and how many ghosts are shown
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrajectorySettings {
+    pub enabled: bool,
+    /// Prediction horizon in days
+    pub horizon_days: f64,
+    /// Ghost markers spread over the horizon
+    pub ghosts: u32,
+}
+
+impl Default for TrajectorySettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            horizon_days: 90.0,
+            ghosts: 6,
+        }
+    }
+}
+
+impl TrajectorySettings {
+    pub const MIN_HORIZON_DAYS: f64 = 1.0;
+    pub const MAX_HORIZON_DAYS: f64 = 3650.0;
+    pub const MAX_GHOSTS: u32 = 12;
+
+    pub fn clamped(self) -> Self {
+        Self {
+            horizon_days: self
+                .horizon_days
+                .clamp(Self::MIN_HORIZON_DAYS, Self::MAX_HORIZON_DAYS),
+            ghosts: self.ghosts.clamp(1, Self::MAX_GHOSTS),
+            ..self
+        }
+    }
+
+    /// Simulation times of the ghosts after `now`, the last at the horizon
+    pub fn ghost_times(&self, now: f64) -> Vec<f64> {
+        let horizon = self.horizon_days * 86_400.0;
+        (1..=self.ghosts)
+            .map(|i| now + horizon * i as f64 / self.ghosts as f64)
+            .collect()
+    }
+}
+
+/// Render-space offset of a body from its parent at simulation time `time`
+pub fn orbit_render_offset(orbit: &KeplerOrbit, amplification: f64, time: f64) -> Vec3 {
+    let mean_anomaly = orbit.mean_anomaly_epoch + orbit.mean_motion * time;
+    let position = orbit_position_from_mean_anomaly(orbit, mean_anomaly) * SCALING_FACTOR
+        * amplification;
+    position.as_vec3()
+}
+
+type PredictionQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static SpaceCoordinates,
+        Option<&'static KeplerOrbit>,
+        Option<&'static LogicalParent>,
+        Option<&'static LocalOrbitAmplification>,
+    ),
+>;
+
+/// Predicted render position of `entity` at `time`, laid out like the orbit
+/// paths: the orbit offset placed around the parent, whose own position is
+/// predicted the same way. Bodies without an orbit stay where they are.
+fn predict_render_position(
+    entity: Entity,
+    time: f64,
+    bodies: &PredictionQuery,
+    origin: DVec3,
+    depth: usize,
+) -> Option<Vec3> {
+    let (coords, orbit, parent, amplification) = bodies.get(entity).ok()?;
+    let Some(orbit) = orbit.filter(|_| depth < MAX_PARENT_DEPTH) else {
+        return Some(((coords.position - origin) * SCALING_FACTOR).as_vec3());
+    };
+    let parent_position = parent
+        .and_then(|p| predict_render_position(p.0, time, bodies, origin, depth + 1))
+        .unwrap_or(Vec3::ZERO);
+    let amplification = amplification.map_or(1.0, |a| a.0 as f64);
+    Some(parent_position + orbit_render_offset(orbit, amplification, time))
+}
+
+/// System that draws the predicted trail and ghosts of each selected body
+#[allow(clippy::type_complexity)]
+pub fn draw_trajectory_ghosts(
+    mut gizmos: Gizmos,
+    sim_time: Res<SimulationTime>,
+    settings: Res<UiSettings>,
+    floating_origin: Option<Res<FloatingOrigin>>,
+    selected: Query<
+        (Entity, &CelestialBody, Option<&Visibility>),
+        (With<Selected>, With<KeplerOrbit>),
+    >,
+    bodies: PredictionQuery,
+) {
+    let trajectory = &settings.trajectory;
+    if !trajectory.enabled {
+        return;
+    }
+    let origin = floating_origin.map_or(DVec3::ZERO, |fo| fo.position);
+    let now = sim_time.elapsed_seconds();
+    let horizon = trajectory.horizon_days * 86_400.0;
+    let color = Color::srgb(0.55, 0.85, 1.0);
+
+    for (entity, body, visibility) in selected.iter() {
+        if visibility == Some(&Visibility::Hidden) {
+            continue;
+        }
+        let predict = |time: f64| predict_render_position(entity, time, &bodies, origin, 0);
+
+        // Trail ahead of the body, fading towards the horizon
+        let mut previous = predict(now);
+        for i in 1..=TRAIL_SEGMENTS {
+            let t = i as f32 / TRAIL_SEGMENTS as f32;
+            let point = predict(now + horizon * t as f64);
+            if let (Some(from), Some(to)) = (previous, point) {
+                gizmos.line(from, to, color.with_alpha(0.8 * (1.0 - t) + 0.1));
+            }
+            previous = point;
+        }
+
+        // Ghosts, fainter the further ahead they are
+        let ghost_radius = body.visual_radius.max(1.0) * 1.2;
+        let count = trajectory.ghosts as f32;
+        for (index, time) in trajectory.ghost_times(now).into_iter().enumerate() {
+            if let Some(position) = predict(time) {
+                let alpha = 0.7 - 0.5 * index as f32 / count;
+                gizmos.sphere(position, Quat::IDENTITY, ghost_radius, color.with_alpha(alpha));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ghosts_spread_to_the_horizon() {
+        let settings = TrajectorySettings {
+            enabled: true,
+            horizon_days: 30.0,
+            ghosts: 3,
+        };
+        let times = settings.ghost_times(1000.0);
+        assert_eq!(
+            times,
+            vec![1000.0 + 864_000.0, 1000.0 + 1_728_000.0, 1000.0 + 2_592_000.0]
+        );
+
+        let clamped = TrajectorySettings {
+            horizon_days: 0.0,
+            ghosts: 40,
+            ..settings
+        }
+        .clamped();
+        assert_eq!(clamped.horizon_days, TrajectorySettings::MIN_HORIZON_DAYS);
+        assert_eq!(clamped.ghosts, TrajectorySettings::MAX_GHOSTS);
+    }
+
+    #[test]
+    fn test_half_an_orbit_ahead_is_opposite() {
+        let period = 86_400.0 * 100.0;
+        let orbit = KeplerOrbit::circular(1.0, KeplerOrbit::mean_motion_from_period(period));
+        let now = orbit_render_offset(&orbit, 1.0, 0.0);
+        let later = orbit_render_offset(&orbit, 1.0, period / 2.0);
+        assert!((now + later).length() < 1e-2);
+        assert!((now.length() - SCALING_FACTOR as f32).abs() < 1e-2);
+
+        // Amplified moon orbits are drawn further out
+        let amplified = orbit_render_offset(&orbit, 3.0, 0.0);
+        assert!((amplified - now * 3.0).length() < 1e-2);
+    }
+}
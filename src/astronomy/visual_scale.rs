@@ -0,0 +1,211 @@
+//! Adjustable visual scale
+//!
+//! Distances are rendered linearly (`SCALING_FACTOR`), but body sizes are
+//! inflated by `calculate_visual_radius` and moon orbits are pushed out by
+//! `LocalOrbitAmplification` so the system stays readable. `VisualScale`
+//! blends between that gameplay scale and true scale, where every body has
+//! its real radius and moons their real distance, and scales the moon
+//! amplification down on its own. Switching eases over
+//! `TRANSITION_SECONDS` of real time, so the bodies visibly shrink into place.
+
+use bevy::prelude::*;
+
+use super::components::LocalOrbitAmplification;
+use super::systems::SCALING_FACTOR;
+use crate::plugins::solar_system::CelestialBody;
+
+/// Kilometres per astronomical unit
+pub const AU_KM: f64 = 149_597_870.7;
+
+/// Real seconds a switch between gameplay and true scale takes
+pub const TRANSITION_SECONDS: f32 = 1.5;
+
+/// Blend between gameplay and true proportions
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct VisualScale {
+    /// Target: true scale instead of gameplay scale
+    pub true_scale: bool,
+    /// Share of the gameplay moon orbit amplification applied (0 = real distances)
+    pub moon_amplification: f32,
+    /// Current position of the transition (0 = gameplay, 1 = true scale)
+    pub blend: f32,
+}
+
+impl Default for VisualScale {
+    fn default() -> Self {
+        Self {
+            true_scale: false,
+            moon_amplification: 1.0,
+            blend: 0.0,
+        }
+    }
+}
+
+impl VisualScale {
+    fn target(&self) -> f32 {
+        if self.true_scale {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    /// Whether the blend has not reached the chosen scale yet
+    pub fn transitioning(&self) -> bool {
+        self.blend != self.target()
+    }
+
+    /// Move the blend `seconds` further towards the chosen scale
+    pub fn advance(&mut self, seconds: f32) {
+        let step = seconds / TRANSITION_SECONDS;
+        let target = self.target();
+        self.blend = if self.blend < target {
+            (self.blend + step).min(target)
+        } else {
+            (self.blend - step).max(target)
+        };
+    }
+
+    /// Blend with ease-in/out, so the transition starts and ends gently
+    pub fn eased_blend(&self) -> f32 {
+        let t = self.blend.clamp(0.0, 1.0);
+        t * t * (3.0 - 2.0 * t)
+    }
+}
+
+/// Render radius of a body of `radius_km` at true scale
+pub fn true_visual_radius(radius_km: f32) -> f32 {
+    (radius_km as f64 / AU_KM * SCALING_FACTOR) as f32
+}
+
+/// Mesh scale factor for a body drawn at `visual_radius`. Interpolated
+/// geometrically, so the size changes at an even pace across the orders of
+/// magnitude between the two scales.
+pub fn body_scale_factor(visual_radius: f32, radius_km: f32, blend: f32) -> f32 {
+    if visual_radius <= 0.0 || radius_km <= 0.0 {
+        return 1.0;
+    }
+    (true_visual_radius(radius_km) / visual_radius).powf(blend)
+}
+
+/// Moon orbit amplification after the slider and the true-scale blend
+pub fn effective_amplification(base: f32, share: f32, blend: f32) -> f32 {
+    1.0 + (base - 1.0) * share * (1.0 - blend)
+}
+
+/// Transform scale a body was spawned with
+#[derive(Component, Debug, Clone, Copy)]
+pub struct GameplayScale(pub Vec3);
+
+/// Moon orbit amplification a body was spawned with
+#[derive(Component, Debug, Clone, Copy)]
+pub struct GameplayAmplification(pub f32);
+
+/// System that moves the blend towards the chosen scale in real time,
+/// so the transition also plays while the simulation is paused
+pub fn animate_visual_scale(time: Res<Time<Real>>, mut scale: ResMut<VisualScale>) {
+    if scale.transitioning() {
+        scale.advance(time.delta_seconds());
+    }
+}
+
+/// System that applies the blend to body meshes and moon orbits.
+///
+/// Bodies record their spawn proportions the first time they are seen. The
+/// rest are only rewritten when the scale changes. Rings are spatial children
+/// of their planet and inherit its scale.
+#[allow(clippy::type_complexity)]
+pub fn apply_visual_scale(
+    mut commands: Commands,
+    scale: Res<VisualScale>,
+    mut new_bodies: Query<
+        (Entity, &CelestialBody, &mut Transform),
+        (Without<GameplayScale>, Without<Parent>),
+    >,
+    mut bodies: Query<(&CelestialBody, &GameplayScale, &mut Transform), Without<Parent>>,
+    mut new_moons: Query<(Entity, &mut LocalOrbitAmplification), Without<GameplayAmplification>>,
+    mut moons: Query<(&GameplayAmplification, &mut LocalOrbitAmplification)>,
+) {
+    let blend = scale.eased_blend();
+    let scaled = |body: &CelestialBody, base: Vec3| {
+        base * body_scale_factor(body.visual_radius, body.radius, blend)
+    };
+    let amplified = |base: f32| effective_amplification(base, scale.moon_amplification, blend);
+
+    for (entity, body, mut transform) in new_bodies.iter_mut() {
+        let base = transform.scale;
+        commands.entity(entity).insert(GameplayScale(base));
+        if blend > 0.0 {
+            transform.scale = scaled(body, base);
+        }
+    }
+    for (entity, mut amplification) in new_moons.iter_mut() {
+        let base = amplification.0;
+        commands.entity(entity).insert(GameplayAmplification(base));
+        let target = amplified(base);
+        if amplification.0 != target {
+            amplification.0 = target;
+        }
+    }
+
+    if !scale.is_changed() {
+        return;
+    }
+    for (body, base, mut transform) in bodies.iter_mut() {
+        let target = scaled(body, base.0);
+        if transform.scale != target {
+            transform.scale = target;
+        }
+    }
+    for (base, mut amplification) in moons.iter_mut() {
+        let target = amplified(base.0);
+        if amplification.0 != target {
+            amplification.0 = target;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_true_scale_uses_real_radii() {
+        // Earth at true scale is a tiny fraction of an AU
+        let earth = true_visual_radius(6371.0);
+        assert!((earth as f64 - 6371.0 / AU_KM * SCALING_FACTOR).abs() < 1e-6);
+
+        let visual = 63.71;
+        assert_eq!(body_scale_factor(visual, 6371.0, 0.0), 1.0);
+        assert!((body_scale_factor(visual, 6371.0, 1.0) * visual - earth).abs() < 1e-5);
+        // Halfway is the geometric mean of the two sizes
+        let half = body_scale_factor(visual, 6371.0, 0.5) * visual;
+        assert!((half - (visual * earth).sqrt()).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_amplification_fades_with_slider_and_blend() {
+        assert_eq!(effective_amplification(40.0, 1.0, 0.0), 40.0);
+        assert_eq!(effective_amplification(40.0, 0.5, 0.0), 20.5);
+        assert_eq!(effective_amplification(40.0, 1.0, 1.0), 1.0);
+    }
+
+    #[test]
+    fn test_transition_eases_to_target() {
+        let mut scale = VisualScale {
+            true_scale: true,
+            ..default()
+        };
+        assert!(scale.transitioning());
+        scale.advance(TRANSITION_SECONDS / 2.0);
+        assert!((scale.blend - 0.5).abs() < 1e-6);
+        assert!((scale.eased_blend() - 0.5).abs() < 1e-6);
+        scale.advance(TRANSITION_SECONDS);
+        assert_eq!(scale.blend, 1.0);
+        assert!(!scale.transitioning());
+
+        scale.true_scale = false;
+        scale.advance(TRANSITION_SECONDS * 0.25);
+        assert!((scale.blend - 0.75).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,134 @@
+use bevy::diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic};
+use bevy::prelude::*;
+use std::time::{Duration, Instant};
+
+/// Milliseconds spent in the fixed-timestep simulation schedules per frame
+pub const SIMULATION_TIME: DiagnosticPath = DiagnosticPath::const_new("helios/simulation_time");
+
+/// Milliseconds from the start of `First` to `Last`: all game and UI systems,
+/// without rendering and waiting for the next frame
+pub const MAIN_SCHEDULE_TIME: DiagnosticPath =
+    DiagnosticPath::const_new("helios/main_schedule_time");
+
+/// Start times of the schedules being measured in the current frame
+#[derive(Resource, Debug, Default)]
+pub struct ScheduleTimer {
+    frame_start: Option<Instant>,
+    fixed_start: Option<Instant>,
+    /// Time spent in the fixed-timestep schedules this frame; the fixed loop
+    /// may run several times or not at all
+    simulation: Duration,
+}
+
+/// Register the schedule timing diagnostics and the systems measuring them.
+/// The markers are not ordered against the other systems of their schedule,
+/// so the timings are close approximations.
+pub fn add_schedule_timings(app: &mut App) {
+    app.init_resource::<ScheduleTimer>()
+        .register_diagnostic(Diagnostic::new(SIMULATION_TIME).with_suffix(" ms"))
+        .register_diagnostic(Diagnostic::new(MAIN_SCHEDULE_TIME).with_suffix(" ms"))
+        .add_systems(First, begin_frame)
+        .add_systems(FixedFirst, begin_fixed_update)
+        .add_systems(FixedLast, end_fixed_update)
+        .add_systems(Last, end_frame);
+}
+
+fn begin_frame(mut timer: ResMut<ScheduleTimer>) {
+    timer.frame_start = Some(Instant::now());
+    timer.simulation = Duration::ZERO;
+}
+
+fn begin_fixed_update(mut timer: ResMut<ScheduleTimer>) {
+    timer.fixed_start = Some(Instant::now());
+}
+
+fn end_fixed_update(mut timer: ResMut<ScheduleTimer>) {
+    if let Some(start) = timer.fixed_start.take() {
+        timer.simulation += start.elapsed();
+    }
+}
+
+fn end_frame(timer: Res<ScheduleTimer>, mut diagnostics: Diagnostics) {
+    diagnostics.add_measurement(&SIMULATION_TIME, || timer.simulation.as_secs_f64() * 1000.0);
+    if let Some(start) = timer.frame_start {
+        diagnostics.add_measurement(&MAIN_SCHEDULE_TIME, || {
+            start.elapsed().as_secs_f64() * 1000.0
+        });
+    }
+}
+
+/// Frame time statistics of a benchmark run
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameTimeReport {
+    pub frames: usize,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl FrameTimeReport {
+    /// Summarise frame times in milliseconds; `None` without samples
+    pub fn from_samples(samples: &[f64]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        // Nearest-rank percentile
+        let percentile = |p: f64| {
+            let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+            sorted[rank.clamp(1, sorted.len()) - 1]
+        };
+        Some(Self {
+            frames: sorted.len(),
+            mean_ms: sorted.iter().sum::<f64>() / sorted.len() as f64,
+            p50_ms: percentile(50.0),
+            p95_ms: percentile(95.0),
+            p99_ms: percentile(99.0),
+            max_ms: sorted[sorted.len() - 1],
+        })
+    }
+
+    /// Average frames per second
+    pub fn mean_fps(&self) -> f64 {
+        1000.0 / self.mean_ms
+    }
+}
+
+impl std::fmt::Display for FrameTimeReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} frames, mean {:.2} ms ({:.1} FPS), p50 {:.2} ms, p95 {:.2} ms, p99 {:.2} ms, max {:.2} ms",
+            self.frames,
+            self.mean_ms,
+            self.mean_fps(),
+            self.p50_ms,
+            self.p95_ms,
+            self.p99_ms,
+            self.max_ms
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_time_percentiles() {
+        assert_eq!(FrameTimeReport::from_samples(&[]), None);
+
+        // 1..=100 ms in shuffled order
+        let samples: Vec<f64> = (0..100).map(|i| ((i * 37) % 100 + 1) as f64).collect();
+        let report = FrameTimeReport::from_samples(&samples).unwrap();
+        assert_eq!(report.frames, 100);
+        assert!((report.mean_ms - 50.5).abs() < 1e-9);
+        assert_eq!(report.p50_ms, 50.0);
+        assert_eq!(report.p95_ms, 95.0);
+        assert_eq!(report.p99_ms, 99.0);
+        assert_eq!(report.max_ms, 100.0);
+    }
+}
@@ -0,0 +1,198 @@
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::astronomy::components::{OrbitCenter, SystemId};
+use crate::astronomy::galaxy::GeneratedStar;
+use crate::astronomy::nearby_stars::StarData;
+use crate::astronomy::{
+    map_star_to_system_architecture, AsteroidBelt, BeltPopulation, ProceduralStars,
+    SpaceCoordinates,
+};
+use crate::economy::components::OrbitsBody;
+use crate::game_state::GameSeed;
+use crate::plugins::solar_system::{Asteroid, CelestialBody, Star};
+use crate::plugins::solar_system_data::{calculate_visual_radius, BodyType};
+use crate::plugins::starmap::LY_TO_AU;
+use crate::plugins::system_populator::{
+    spawn_asteroid_belt, spawn_cometary_cloud, spawn_procedural_planet,
+    spawn_star_entity_with_metallicity,
+};
+use crate::render::asteroid_belt::{belt_member, AsteroidBeltField};
+
+/// Asteroids spawned around Sol as individual entities
+pub const BENCHMARK_ASTEROIDS: usize = 10_000;
+
+/// Generated systems populated with planets, belts and comets
+pub const BENCHMARK_SYSTEMS: usize = 50;
+
+/// Mixed into the game seed for the benchmark asteroids
+const BENCHMARK_SEED_SALT: u64 = 0xbe7c_4a2c_0000_0000;
+
+/// Sol-centred belt the benchmark asteroids are drawn from
+fn benchmark_belt() -> AsteroidBelt {
+    AsteroidBelt {
+        inner_au: 1.8,
+        outer_au: 4.0,
+        count: BENCHMARK_ASTEROIDS,
+        inclination: 0.15,
+        population: BeltPopulation::Annulus,
+    }
+}
+
+/// Catalog-style data of a generated star, from main-sequence relations
+fn star_data(name: String, star: &GeneratedStar, metallicity: f32) -> StarData {
+    let luminosity_sol = star.mass_sol.powf(3.5);
+    let radius_sol = star.mass_sol.powf(0.8);
+    StarData {
+        name,
+        spectral_type: star.spectral_type.clone(),
+        mass_sol: star.mass_sol,
+        radius_sol,
+        temp_k: 5778.0 * (luminosity_sol / (radius_sol * radius_sol)).powf(0.25),
+        luminosity_sol,
+        metallicity: Some(metallicity),
+        planets: Vec::new(),
+    }
+}
+
+/// System that spawns the stress scene once the starmap and Sol exist:
+/// [`BENCHMARK_ASTEROIDS`] meshed asteroids on real orbits around the Sun,
+/// and the first [`BENCHMARK_SYSTEMS`] generated systems populated the way
+/// the catalog systems are
+pub fn spawn_benchmark_scene(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    game_seed: Res<GameSeed>,
+    procedural: Res<ProceduralStars>,
+    star_query: Query<(Entity, &CelestialBody, Option<&SystemId>), With<Star>>,
+) {
+    let Some((sun, sun_body, _)) = star_query
+        .iter()
+        .find(|(_, _, system_id)| system_id.is_none_or(|s| s.0 == 0))
+    else {
+        warn!("No star found for the benchmark asteroids");
+        return;
+    };
+
+    // Every asteroid shares one mesh and material, like promoted belt members
+    let mesh = meshes.add(Sphere::new(1.0).mesh().ico(2).unwrap());
+    let material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.5, 0.47, 0.43),
+        perceptual_roughness: 0.95,
+        ..default()
+    });
+    let field = AsteroidBeltField::new(
+        benchmark_belt(),
+        sun,
+        0,
+        &sun_body.name,
+        game_seed.value ^ BENCHMARK_SEED_SALT,
+    );
+    for index in 0..BENCHMARK_ASTEROIDS {
+        let member = belt_member(&field, index);
+        let visual_radius = calculate_visual_radius(BodyType::Asteroid, member.radius);
+        commands.spawn((
+            PbrBundle {
+                mesh: mesh.clone(),
+                material: material.clone(),
+                transform: Transform::from_scale(Vec3::splat(visual_radius)),
+                ..default()
+            },
+            Asteroid,
+            CelestialBody {
+                name: format!("Benchmark Asteroid {}", index + 1),
+                mass: member.mass,
+                radius: member.radius,
+                body_type: BodyType::Asteroid,
+                visual_radius,
+                asteroid_class: Some(member.asteroid_class),
+            },
+            member.orbit,
+            SpaceCoordinates::default(), // Updated by propagate_orbits
+            OrbitCenter(sun),
+            OrbitsBody::new(sun),
+            SystemId(0),
+        ));
+    }
+
+    let mut rng = StdRng::seed_from_u64(game_seed.value ^ BENCHMARK_SEED_SALT);
+    for (i, system) in procedural
+        .systems
+        .iter()
+        .take(BENCHMARK_SYSTEMS)
+        .enumerate()
+    {
+        let system_id = procedural.first_id + i;
+        let star = star_data(system.name.clone(), &system.primary, system.metallicity);
+        let host = spawn_star_entity_with_metallicity(
+            &mut commands,
+            &star,
+            system_id,
+            system.position_ly * LY_TO_AU,
+            system.metallicity,
+        );
+        let architecture = map_star_to_system_architecture(
+            &star.name,
+            star.luminosity_sol as f64,
+            0,
+            &[],
+            &mut rng,
+        );
+        for planet in architecture
+            .rocky_planets
+            .iter()
+            .chain(&architecture.gas_giants)
+        {
+            spawn_procedural_planet(&mut commands, planet, host, system_id, 1.0);
+        }
+        if let Some(belt) = &architecture.asteroid_belt {
+            spawn_asteroid_belt(
+                &mut commands,
+                belt,
+                host,
+                system_id,
+                &star.name,
+                game_seed.value,
+            );
+        }
+        if let Some(cloud) = &architecture.cometary_cloud {
+            spawn_cometary_cloud(
+                &mut commands,
+                cloud,
+                host,
+                system_id,
+                &star.name,
+                game_seed.value,
+            );
+        }
+    }
+
+    info!(
+        "Spawned benchmark scene: {} asteroids around {} and {} populated systems",
+        BENCHMARK_ASTEROIDS,
+        sun_body.name,
+        procedural.systems.len().min(BENCHMARK_SYSTEMS)
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::astronomy::galaxy::StellarClass;
+
+    #[test]
+    fn test_sun_like_star_data() {
+        let star = GeneratedStar {
+            class: StellarClass::G,
+            spectral_type: "G2V".to_string(),
+            mass_sol: 1.0,
+        };
+        let data = star_data("Test".to_string(), &star, 0.1);
+        assert_eq!(data.luminosity_sol, 1.0);
+        assert_eq!(data.radius_sol, 1.0);
+        assert!((data.temp_k - 5778.0).abs() < 1e-3);
+        assert_eq!(data.metallicity, Some(0.1));
+    }
+}
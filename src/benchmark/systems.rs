@@ -0,0 +1,140 @@
+use bevy::app::AppExit;
+use bevy::prelude::*;
+
+use super::diagnostics::FrameTimeReport;
+use crate::tutorial::Tutorial;
+use crate::ui::{NewGameScreen, TimeScale};
+
+/// Seconds of frame times recorded when `--benchmark-seconds` is not given
+pub const DEFAULT_BENCHMARK_SECONDS: f64 = 30.0;
+
+/// Seconds skipped before recording, while assets load and caches warm up
+pub const BENCHMARK_WARMUP_SECONDS: f64 = 5.0;
+
+/// Simulation speed of the benchmark: one day per second keeps every orbit
+/// moving
+pub const BENCHMARK_TIME_SCALE: f32 = 86_400.0;
+
+/// Settings of a benchmark run, from the command line
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchmarkOptions {
+    /// Real seconds of frame times recorded after the warm-up
+    pub seconds: f64,
+}
+
+impl Default for BenchmarkOptions {
+    fn default() -> Self {
+        Self {
+            seconds: DEFAULT_BENCHMARK_SECONDS,
+        }
+    }
+}
+
+impl BenchmarkOptions {
+    /// Parse the command line. Returns `None` without `--benchmark`.
+    pub fn from_args(args: impl Iterator<Item = String>) -> Result<Option<Self>, String> {
+        let args: Vec<String> = args.collect();
+        if !args.iter().any(|arg| arg == "--benchmark") {
+            return Ok(None);
+        }
+        let mut options = Self::default();
+        if let Some(i) = args.iter().position(|arg| arg == "--benchmark-seconds") {
+            options.seconds = args
+                .get(i + 1)
+                .and_then(|value| value.parse().ok())
+                .filter(|seconds: &f64| *seconds > 0.0)
+                .ok_or("--benchmark-seconds needs a positive number")?;
+        }
+        Ok(Some(options))
+    }
+}
+
+/// A benchmark in progress; only present with `--benchmark`
+#[derive(Resource, Debug, Clone, Default)]
+pub struct BenchmarkRun {
+    pub options: BenchmarkOptions,
+    /// Real seconds since the first update
+    pub elapsed: f64,
+    /// Frame times in milliseconds recorded after the warm-up
+    pub samples: Vec<f64>,
+}
+
+impl BenchmarkRun {
+    pub fn new(options: BenchmarkOptions) -> Self {
+        Self {
+            options,
+            ..default()
+        }
+    }
+
+    /// Whether frame times are still being skipped
+    pub fn warming_up(&self) -> bool {
+        self.elapsed < BENCHMARK_WARMUP_SECONDS
+    }
+
+    /// Real seconds until the run ends
+    pub fn remaining(&self) -> f64 {
+        (BENCHMARK_WARMUP_SECONDS + self.options.seconds - self.elapsed).max(0.0)
+    }
+}
+
+/// System that skips the new-game screen and sets the simulation running
+pub fn start_benchmark(
+    mut screen: ResMut<NewGameScreen>,
+    mut tutorial: ResMut<Tutorial>,
+    mut time_scale: ResMut<TimeScale>,
+) {
+    screen.open = false;
+    tutorial.enabled = false;
+    time_scale.scale = BENCHMARK_TIME_SCALE;
+}
+
+/// System that records the frame time of every frame after the warm-up and
+/// reports the statistics once the run is over
+pub fn record_frame_times(
+    time: Res<Time<Real>>,
+    mut run: ResMut<BenchmarkRun>,
+    mut exit: EventWriter<AppExit>,
+) {
+    let delta = time.delta_seconds_f64();
+    if !run.warming_up() {
+        run.samples.push(delta * 1000.0);
+    }
+    run.elapsed += delta;
+    if run.remaining() > 0.0 {
+        return;
+    }
+
+    match FrameTimeReport::from_samples(&run.samples) {
+        Some(report) => {
+            info!("Benchmark finished: {}", report);
+            println!("{}", report);
+        }
+        None => warn!("Benchmark finished without recording a frame"),
+    }
+    exit.send(AppExit::Success);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> Result<Option<BenchmarkOptions>, String> {
+        BenchmarkOptions::from_args(line.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn test_parse_benchmark_arguments() {
+        assert_eq!(parse("helios_ascension"), Ok(None));
+        assert_eq!(
+            parse("helios_ascension --benchmark"),
+            Ok(Some(BenchmarkOptions::default()))
+        );
+        assert_eq!(
+            parse("helios_ascension --benchmark --benchmark-seconds 12.5"),
+            Ok(Some(BenchmarkOptions { seconds: 12.5 }))
+        );
+        assert!(parse("helios_ascension --benchmark --benchmark-seconds").is_err());
+        assert!(parse("helios_ascension --benchmark --benchmark-seconds 0").is_err());
+    }
+}
@@ -0,0 +1,53 @@
+//! Player commands
+//!
+//! The interface doesn't change the game itself. Every change a player makes
+//! (construction, research, policies, trade, covert operations, surveys,
+//! colonies, fleet orders, event choices, funding, the speed of the clock
+//! and the end of a hotseat turn) is sent as a [`PlayerCommand`] event, which
+//! [`apply_player_commands`] carries out before the systems acting on it
+//! run. Commands refer to bodies, stations and fleets by name and
+//! serialize with serde, so the replay records and plays back the same
+//! commands the interface sends, and commands can be exchanged between
+//! players.
+
+use bevy::prelude::*;
+
+pub mod systems;
+pub mod types;
+
+pub use systems::{apply_player_commands, entity_name, find_entity, named_command, NamedEntities};
+pub use types::{PlayerCommand, Target};
+
+/// Plugin that carries out the commands the interface sends
+pub struct CommandsPlugin;
+
+impl Plugin for CommandsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PlayerCommand>().add_systems(
+            Update,
+            apply_player_commands
+                .before(crate::construction::process_construction_actions)
+                .before(crate::construction::process_blueprint_actions)
+                .before(crate::construction::found_stations)
+                .before(crate::research::systems::process_pending_research)
+                .before(crate::research::systems::process_stop_research)
+                .before(crate::research::systems::process_allocation_updates)
+                .before(crate::research::process_team_actions)
+                .before(crate::policies::process_policy_actions)
+                .before(crate::trade::process_trade_actions)
+                .before(crate::intel::process_intel_actions)
+                .before(crate::contact::process_contact_actions)
+                .before(crate::independence::process_independence_actions)
+                .before(crate::random_events::resolve_random_events)
+                .before(crate::economy::launch_survey_missions)
+                .before(crate::economy::found_requested_colonies)
+                .before(crate::fleets::issue_fleet_orders)
+                .before(crate::fleets::issue_crew_orders)
+                .before(crate::fleets::start_reloads)
+                .before(crate::fleets::issue_hull_orders)
+                .before(crate::fleets::launch_probes)
+                .before(crate::fleets::recover_salvage)
+                .before(crate::economy::apply_power_priorities),
+        );
+    }
+}
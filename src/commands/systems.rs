@@ -0,0 +1,416 @@
+use bevy::prelude::*;
+
+use super::types::{PlayerCommand, Target};
+use crate::console::Console;
+use crate::construction::{OrbitalStation, PendingBlueprintActions, PendingConstructionActions};
+use crate::contact::PendingContactActions;
+use crate::economy::{
+    ColonyFoundings, PowerPriorities, PowerPriorityChanges, Reservations, SurveyMissions, Treasury,
+};
+use crate::fleets::{Fleet, FleetOrders, Salvage};
+use crate::game_state::{EventLog, LogSeverity};
+use crate::hotseat::{Hotseat, Seat};
+use crate::independence::PendingIndependenceActions;
+use crate::intel::PendingIntelActions;
+use crate::plugins::solar_system::CelestialBody;
+use crate::policies::PendingPolicyActions;
+use crate::random_events::PendingEvents;
+use crate::research::{PendingResearchActions, ResearchTeam};
+use crate::trade::{PendingTradeActions, TradeAgreement};
+use crate::ui::{SimulationTime, TimeScale, TimeWarp};
+use crate::victory::VictoryState;
+
+/// Bodies, stations, fleets, research teams and salvage, which commands
+/// refer to by name
+pub type NamedEntities<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        Option<&'static CelestialBody>,
+        Option<&'static OrbitalStation>,
+        Option<&'static Fleet>,
+        Option<&'static ResearchTeam>,
+        Option<&'static Salvage>,
+    ),
+>;
+
+/// Name a command refers to `entity` by
+pub fn entity_name(named: &NamedEntities, entity: Entity) -> Option<String> {
+    let (_, body, station, fleet, team, salvage) = named.get(entity).ok()?;
+    body.map(|b| b.name.clone())
+        .or_else(|| station.map(|s| s.name.clone()))
+        .or_else(|| fleet.map(|f| f.name.clone()))
+        .or_else(|| team.map(|t| t.name.clone()))
+        .or_else(|| salvage.map(|s| s.name.clone()))
+}
+
+/// Entity a command's name refers to in this run
+pub fn find_entity(named: &NamedEntities, name: &str) -> Option<Entity> {
+    named
+        .iter()
+        .find_map(|(entity, body, station, fleet, team, salvage)| {
+            let matches = body.is_some_and(|b| b.name == name)
+                || station.is_some_and(|s| s.name == name)
+                || fleet.is_some_and(|f| f.name == name)
+                || team.is_some_and(|t| t.name == name)
+                || salvage.is_some_and(|s| s.name == name);
+            matches.then_some(entity)
+        })
+}
+
+/// The command with the names of the entities it targets filled in, as it
+/// is written to a replay or sent to another player
+pub fn named_command(named: &NamedEntities, command: &PlayerCommand) -> PlayerCommand {
+    let mut command = command.clone();
+    for target in command.targets_mut() {
+        if let Some(name) = target.entity.and_then(|e| entity_name(named, e)) {
+            target.name = name;
+        }
+    }
+    command
+}
+
+/// System that carries out the player commands sent this update: each goes
+/// where the system that acts on it picks it up, or changes the treasury
+/// sliders, reservations, time scale, time warp or end of the campaign
+/// directly. A command naming something that doesn't exist is dropped with
+/// a warning, as is one a hotseat game doesn't allow the player at the
+/// screen.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn apply_player_commands(
+    mut player_commands: EventReader<PlayerCommand>,
+    mut construction: ResMut<PendingConstructionActions>,
+    mut blueprints: ResMut<PendingBlueprintActions>,
+    mut research: ResMut<PendingResearchActions>,
+    mut policies: ResMut<PendingPolicyActions>,
+    mut trade: ResMut<PendingTradeActions>,
+    (mut intel, mut contact, mut independence): (
+        ResMut<PendingIntelActions>,
+        ResMut<PendingContactActions>,
+        ResMut<PendingIndependenceActions>,
+    ),
+    (mut events, mut console): (ResMut<PendingEvents>, Option<ResMut<Console>>),
+    mut survey: ResMut<SurveyMissions>,
+    mut foundings: ResMut<ColonyFoundings>,
+    mut fleet_orders: ResMut<FleetOrders>,
+    mut power_priorities: ResMut<PowerPriorityChanges>,
+    (mut treasury, mut reservations, mut time_scale, mut warp, mut victory): (
+        ResMut<Treasury>,
+        ResMut<Reservations>,
+        ResMut<TimeScale>,
+        Option<ResMut<TimeWarp>>,
+        Option<ResMut<VictoryState>>,
+    ),
+    named: NamedEntities,
+    (mut hotseat, seats): (Option<ResMut<Hotseat>>, Query<&Seat>),
+    (mut log, sim_time): (Option<ResMut<EventLog>>, Option<Res<SimulationTime>>),
+) {
+    let mut missing = Vec::new();
+    let mut refused = Vec::new();
+    for command in player_commands.read() {
+        if let Some(hotseat) = hotseat.as_deref_mut() {
+            let subject = command
+                .subject()
+                .and_then(|t| t.entity.or_else(|| find_entity(&named, &t.name)));
+            let owner = subject.and_then(|entity| {
+                let seat = seats.get(entity).ok()?.0;
+                Some((seat, entity_name(&named, entity).unwrap_or_default()))
+            });
+            let answers_to = owner.as_ref().map(|(seat, name)| (*seat, name.as_str()));
+            if let Err(reason) = hotseat.authorize(command, answers_to) {
+                refused.push(reason);
+                continue;
+            }
+            // A colony or station ordered at a body nobody holds answers to
+            // the player once it is founded
+            if let (Some(seat), Some(body), None) = (hotseat.active_seat(), subject, &owner) {
+                match command {
+                    PlayerCommand::FoundColony(_) => hotseat.colony_claims.push((body, seat)),
+                    PlayerCommand::FoundStation { .. } => hotseat.station_claims.push((body, seat)),
+                    _ => {}
+                }
+            }
+        }
+
+        // Picking a speed ends a warp under way
+        if let (
+            Some(warp),
+            PlayerCommand::SetTimeScale(_) | PlayerCommand::Pause | PlayerCommand::Resume,
+        ) = (warp.as_mut(), command)
+        {
+            warp.stop();
+        }
+
+        let mut find = |target: &Target| {
+            let found = target.entity.or_else(|| find_entity(&named, &target.name));
+            if found.is_none() {
+                missing.push(target.name.clone());
+            }
+            found
+        };
+
+        match command {
+            PlayerCommand::QueueBuilding { site, building } => {
+                if let Some(site) = find(site) {
+                    construction.queue.push((site, building.clone()));
+                }
+            }
+            PlayerCommand::CancelBuilding { site, index } => {
+                if let Some(site) = find(site) {
+                    construction.cancel.push((site, *index));
+                }
+            }
+            PlayerCommand::FoundStation {
+                host,
+                kind,
+                location,
+            } => {
+                if let Some(host) = find(host) {
+                    construction.found.push((host, *kind, *location));
+                }
+            }
+            PlayerCommand::ApplyBlueprint { site, blueprint } => {
+                if let Some(site) = find(site) {
+                    blueprints.apply.push((site, blueprint.clone()));
+                }
+            }
+            PlayerCommand::StopBlueprint(site) => {
+                if let Some(site) = find(site) {
+                    blueprints.stop.push(site);
+                }
+            }
+            PlayerCommand::StartResearch(tech) => research.start_research.push(tech.clone()),
+            PlayerCommand::StopResearch(tech) => research.stop_research.push(tech.clone()),
+            PlayerCommand::ResumeResearch(tech) => research.resume_research.push(tech.clone()),
+            PlayerCommand::AllocateResearch(tech, percent) => {
+                research.update_allocations.push((tech.clone(), *percent));
+            }
+            PlayerCommand::HireTeam(specialty) => research.hire_teams.push(*specialty),
+            PlayerCommand::RenameTeam { team, name } => {
+                if let Some(team) = find(team) {
+                    research.rename_teams.push((team, name.clone()));
+                }
+            }
+            PlayerCommand::RetireTeam(team) => {
+                if let Some(team) = find(team) {
+                    research.retire_teams.push(team);
+                }
+            }
+            PlayerCommand::EnactPolicy { policy, colony } => match colony {
+                Some(colony) => {
+                    if let Some(colony) = find(colony) {
+                        policies.enact.push((policy.clone(), Some(colony)));
+                    }
+                }
+                None => policies.enact.push((policy.clone(), None)),
+            },
+            PlayerCommand::RevokePolicy { policy, colony } => match colony {
+                Some(colony) => {
+                    if let Some(colony) = find(colony) {
+                        policies.revoke.push((policy.clone(), Some(colony)));
+                    }
+                }
+                None => policies.revoke.push((policy.clone(), None)),
+            },
+            PlayerCommand::SignTrade {
+                faction,
+                resource,
+                direction,
+                mt_per_year,
+            } => trade.sign.push(TradeAgreement::new(
+                faction.clone(),
+                *resource,
+                *direction,
+                *mt_per_year,
+            )),
+            PlayerCommand::CancelTrade {
+                faction,
+                resource,
+                direction,
+            } => trade.cancel.push((faction.clone(), *resource, *direction)),
+            PlayerCommand::RecruitAgents(count) => intel.recruit += count,
+            PlayerCommand::QueueOperation { kind, faction } => {
+                intel.queue.push((*kind, faction.clone()));
+            }
+            PlayerCommand::CancelOperation(index) => intel.cancel.push(*index),
+            PlayerCommand::CounterIntelligence { agent, on_duty } => {
+                intel.counter_intelligence.push((*agent, *on_duty));
+            }
+            PlayerCommand::BeginTranslation(alien) => contact.translate.push(alien.clone()),
+            PlayerCommand::SendEnvoy(alien) => contact.envoys.push(alien.clone()),
+            PlayerCommand::RecognizeIndependence(faction) => {
+                independence.recognize.push(faction.clone())
+            }
+            PlayerCommand::Survey(body) => {
+                if let Some(body) = find(body) {
+                    survey.request(body);
+                }
+            }
+            PlayerCommand::FoundColony(body) => {
+                if let Some(body) = find(body) {
+                    foundings.request(body);
+                }
+            }
+            PlayerCommand::MoveFleet { fleet, destination } => {
+                if let (Some(fleet), Some(destination)) = (find(fleet), find(destination)) {
+                    fleet_orders.move_to(fleet, destination);
+                }
+            }
+            PlayerCommand::RefuelFleet(fleet) => {
+                if let Some(fleet) = find(fleet) {
+                    fleet_orders.refuel(fleet);
+                }
+            }
+            PlayerCommand::RecoverSalvage { fleet, salvage } => {
+                if let (Some(fleet), Some(salvage)) = (find(fleet), find(salvage)) {
+                    fleet_orders.recover(fleet, salvage);
+                }
+            }
+            PlayerCommand::LoadOrdnance {
+                fleet,
+                ordnance,
+                rounds,
+            } => {
+                if let Some(fleet) = find(fleet) {
+                    fleet_orders.load(fleet, ordnance.clone(), *rounds);
+                }
+            }
+            PlayerCommand::CrewFleet(fleet) => {
+                if let Some(fleet) = find(fleet) {
+                    fleet_orders.crew(fleet);
+                }
+            }
+            PlayerCommand::ResupplyFleet(fleet) => {
+                if let Some(fleet) = find(fleet) {
+                    fleet_orders.resupply(fleet);
+                }
+            }
+            PlayerCommand::QueueHull { shipyard, hull } => {
+                if let Some(shipyard) = find(shipyard) {
+                    fleet_orders.build_hull(shipyard, hull.clone());
+                }
+            }
+            PlayerCommand::CancelHull { shipyard, index } => {
+                if let Some(shipyard) = find(shipyard) {
+                    fleet_orders.cancel_hull(shipyard, *index);
+                }
+            }
+            PlayerCommand::LaunchProbe { target, mission } => {
+                if let Some(target) = find(target) {
+                    fleet_orders.launch_probe(target, *mission);
+                }
+            }
+            PlayerCommand::LaunchInterstellarProbe(system) => {
+                fleet_orders.launch_interstellar_probe(*system);
+            }
+            PlayerCommand::PowerPriorities { colony, order } => {
+                if let Some(colony) = find(colony) {
+                    power_priorities.request(colony, PowerPriorities(*order));
+                }
+            }
+            PlayerCommand::EventChoice(choice) => events.choice = Some(*choice),
+            PlayerCommand::Funding {
+                research,
+                construction,
+            } => {
+                treasury.research_funding = *research;
+                treasury.construction_funding = *construction;
+            }
+            PlayerCommand::Reservations(enabled) => reservations.enabled = *enabled,
+            PlayerCommand::SetTimeScale(scale) => time_scale.scale = *scale,
+            PlayerCommand::Pause => time_scale.pause(),
+            PlayerCommand::Resume => time_scale.resume(),
+            PlayerCommand::WarpToNextEvent => {
+                if let Some(warp) = warp.as_mut() {
+                    warp.start(&time_scale);
+                }
+            }
+            PlayerCommand::ContinueInSandbox => {
+                if let Some(victory) = victory.as_mut() {
+                    victory.sandbox = true;
+                }
+                time_scale.resume();
+            }
+            PlayerCommand::Console(line) => {
+                if let Some(console) = console.as_mut() {
+                    console.pending.push(line.clone());
+                }
+            }
+            PlayerCommand::EndTurn => {
+                if hotseat.as_mut().is_some_and(|hotseat| hotseat.end_turn()) {
+                    time_scale.resume();
+                }
+            }
+        }
+    }
+
+    let messages = missing
+        .into_iter()
+        .map(|name| format!("Command dropped: '{}' not found", name))
+        .chain(
+            refused
+                .into_iter()
+                .map(|reason| format!("Command refused: {}", reason)),
+        );
+    for message in messages {
+        warn!("{}", message);
+        if let (Some(log), Some(sim_time)) = (log.as_mut(), sim_time.as_ref()) {
+            log.push(sim_time.format_date_time(), LogSeverity::Warning, message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::solar_system_data::BodyType;
+
+    #[test]
+    fn test_commands_reach_the_systems_acting_on_them() {
+        let mut app = App::new();
+        app.add_event::<PlayerCommand>()
+            .init_resource::<PendingConstructionActions>()
+            .init_resource::<PendingBlueprintActions>()
+            .init_resource::<PendingResearchActions>()
+            .init_resource::<PendingPolicyActions>()
+            .init_resource::<PendingTradeActions>()
+            .init_resource::<PendingIntelActions>()
+            .init_resource::<PendingContactActions>()
+            .init_resource::<PendingIndependenceActions>()
+            .init_resource::<PendingEvents>()
+            .init_resource::<SurveyMissions>()
+            .init_resource::<ColonyFoundings>()
+            .init_resource::<FleetOrders>()
+            .init_resource::<PowerPriorityChanges>()
+            .init_resource::<Treasury>()
+            .init_resource::<Reservations>()
+            .init_resource::<TimeScale>()
+            .add_systems(Update, apply_player_commands);
+        let mars = app
+            .world_mut()
+            .spawn(CelestialBody::fixture("Mars", BodyType::Planet))
+            .id();
+
+        // By entity from the interface, by name from a replay
+        app.world_mut()
+            .send_event(PlayerCommand::Survey(mars.into()));
+        app.world_mut()
+            .send_event(PlayerCommand::FoundColony("Mars".into()));
+        app.world_mut()
+            .send_event(PlayerCommand::FoundColony("Vulcan".into()));
+        app.world_mut()
+            .send_event(PlayerCommand::SetTimeScale(86_400.0));
+        app.world_mut().send_event(PlayerCommand::Pause);
+        app.update();
+
+        let world = app.world();
+        assert_eq!(world.resource::<SurveyMissions>().requested, vec![mars]);
+        assert_eq!(world.resource::<ColonyFoundings>().requested, vec![mars]);
+        let time_scale = world.resource::<TimeScale>();
+        assert!(time_scale.is_paused());
+        let mut resumed = time_scale.clone();
+        resumed.resume();
+        assert_eq!(resumed.scale, 86_400.0);
+    }
+}
@@ -0,0 +1,278 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::construction::{BuildingId, StationKind, StationLocation};
+use crate::contact::AlienId;
+use crate::economy::{PowerTier, ResourceType};
+use crate::fleets::ProbeMission;
+use crate::intel::OperationKind;
+use crate::policies::PolicyId;
+use crate::research::{TechCategory, TechnologyId};
+use crate::trade::{FactionId, TradeDirection};
+
+/// A body, station, fleet, research team or salvage a command refers to.
+/// It is written by name, so that a command applies to the entities of
+/// another run; given from the interface it also carries its entity.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub struct Target {
+    pub name: String,
+    /// Entity in this run, `None` until found by name
+    pub entity: Option<Entity>,
+}
+
+impl From<Entity> for Target {
+    fn from(entity: Entity) -> Self {
+        Self {
+            name: String::new(),
+            entity: Some(entity),
+        }
+    }
+}
+
+impl From<String> for Target {
+    fn from(name: String) -> Self {
+        Self { name, entity: None }
+    }
+}
+
+impl From<&str> for Target {
+    fn from(name: &str) -> Self {
+        Self::from(name.to_string())
+    }
+}
+
+impl From<Target> for String {
+    fn from(target: Target) -> Self {
+        target.name
+    }
+}
+
+/// A player command. The interface sends one for every change it makes to
+/// the game; `apply_player_commands` carries it out, and the replay records
+/// and plays back the same commands.
+#[derive(Event, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PlayerCommand {
+    QueueBuilding {
+        site: Target,
+        building: BuildingId,
+    },
+    CancelBuilding {
+        site: Target,
+        index: usize,
+    },
+    FoundStation {
+        host: Target,
+        kind: StationKind,
+        location: StationLocation,
+    },
+    /// Apply the saved blueprint with this name
+    ApplyBlueprint {
+        site: Target,
+        blueprint: String,
+    },
+    StopBlueprint(Target),
+    StartResearch(TechnologyId),
+    StopResearch(TechnologyId),
+    ResumeResearch(TechnologyId),
+    AllocateResearch(TechnologyId, f64),
+    /// Hire a research team, with the specialty picked for it
+    HireTeam(Option<TechCategory>),
+    RenameTeam {
+        team: Target,
+        name: String,
+    },
+    RetireTeam(Target),
+    /// Enact a policy, for an edict on the named colony
+    EnactPolicy {
+        policy: PolicyId,
+        colony: Option<Target>,
+    },
+    RevokePolicy {
+        policy: PolicyId,
+        colony: Option<Target>,
+    },
+    SignTrade {
+        faction: FactionId,
+        resource: ResourceType,
+        direction: TradeDirection,
+        mt_per_year: f64,
+    },
+    CancelTrade {
+        faction: FactionId,
+        resource: ResourceType,
+        direction: TradeDirection,
+    },
+    RecruitAgents(usize),
+    QueueOperation {
+        kind: OperationKind,
+        faction: FactionId,
+    },
+    CancelOperation(usize),
+    CounterIntelligence {
+        agent: u32,
+        on_duty: bool,
+    },
+    BeginTranslation(AlienId),
+    SendEnvoy(AlienId),
+    RecognizeIndependence(FactionId),
+    Survey(Target),
+    FoundColony(Target),
+    MoveFleet {
+        fleet: Target,
+        destination: Target,
+    },
+    RefuelFleet(Target),
+    RecoverSalvage {
+        fleet: Target,
+        salvage: Target,
+    },
+    LoadOrdnance {
+        fleet: Target,
+        ordnance: String,
+        rounds: u32,
+    },
+    CrewFleet(Target),
+    ResupplyFleet(Target),
+    QueueHull {
+        shipyard: Target,
+        hull: String,
+    },
+    CancelHull {
+        shipyard: Target,
+        index: usize,
+    },
+    LaunchProbe {
+        target: Target,
+        mission: ProbeMission,
+    },
+    /// Probe through the star system with this catalog id
+    LaunchInterstellarProbe(usize),
+    /// Choice picked for the random event waiting for a decision
+    EventChoice(usize),
+    /// Research and construction funding sliders of the treasury
+    Funding {
+        research: f64,
+        construction: f64,
+    },
+    /// Brownout priority order of a colony or station
+    PowerPriorities {
+        colony: Target,
+        order: [PowerTier; 3],
+    },
+    /// Whether queued projects earmark their cost in the stockpile
+    Reservations(bool),
+    /// Simulation seconds per real second
+    SetTimeScale(f32),
+    Pause,
+    /// Resume at the speed from before the pause
+    Resume,
+    /// Run the clock at the speed that lands on the next scheduled event
+    WarpToNextEvent,
+    /// Keep playing once the campaign has ended, without victory or defeat
+    ContinueInSandbox,
+    /// Line entered in the developer console
+    Console(String),
+    /// Hand a hotseat game to the next player, or run the month after the
+    /// last one
+    EndTurn,
+}
+
+/// The targets of a command, borrowed through `$iter` (`iter` or
+/// `iter_mut`), so [`PlayerCommand::targets`] and
+/// [`PlayerCommand::targets_mut`] share one list
+macro_rules! command_targets {
+    ($command:expr, $iter:ident) => {
+        match $command {
+            PlayerCommand::QueueBuilding { site, .. }
+            | PlayerCommand::CancelBuilding { site, .. }
+            | PlayerCommand::ApplyBlueprint { site, .. }
+            | PlayerCommand::StopBlueprint(site) => vec![site],
+            PlayerCommand::FoundStation { host, .. } => vec![host],
+            PlayerCommand::RenameTeam { team, .. } | PlayerCommand::RetireTeam(team) => {
+                vec![team]
+            }
+            PlayerCommand::EnactPolicy { colony, .. }
+            | PlayerCommand::RevokePolicy { colony, .. } => colony.$iter().collect(),
+            PlayerCommand::Survey(body) | PlayerCommand::FoundColony(body) => vec![body],
+            PlayerCommand::MoveFleet { fleet, destination } => vec![fleet, destination],
+            PlayerCommand::RecoverSalvage { fleet, salvage } => vec![fleet, salvage],
+            PlayerCommand::RefuelFleet(fleet)
+            | PlayerCommand::CrewFleet(fleet)
+            | PlayerCommand::ResupplyFleet(fleet)
+            | PlayerCommand::LoadOrdnance { fleet, .. } => vec![fleet],
+            PlayerCommand::QueueHull { shipyard, .. }
+            | PlayerCommand::CancelHull { shipyard, .. } => vec![shipyard],
+            PlayerCommand::LaunchProbe { target, .. } => vec![target],
+            PlayerCommand::PowerPriorities { colony, .. } => vec![colony],
+            _ => Vec::new(),
+        }
+    };
+}
+
+impl PlayerCommand {
+    /// Bodies, stations, fleets, teams and salvage the command refers to
+    pub fn targets(&self) -> Vec<&Target> {
+        command_targets!(self, iter)
+    }
+
+    /// The command's targets, to fill in their names or entities
+    pub fn targets_mut(&mut self) -> Vec<&mut Target> {
+        command_targets!(self, iter_mut)
+    }
+
+    /// The body, station, fleet or team the command acts on, which has to
+    /// answer to the player giving it; a probe's target is only where it
+    /// goes
+    pub fn subject(&self) -> Option<&Target> {
+        match self {
+            PlayerCommand::LaunchProbe { .. } => None,
+            _ => self.targets().into_iter().next(),
+        }
+    }
+
+    /// Names of the bodies, stations and fleets the command refers to
+    pub fn names(&self) -> Vec<&str> {
+        self.targets()
+            .into_iter()
+            .map(|t| t.name.as_str())
+            .collect()
+    }
+
+    /// Whether the command only starts and stops the clock or changes its
+    /// speed, which leaves the economy days it runs through the same
+    pub fn is_time_control(&self) -> bool {
+        matches!(
+            self,
+            PlayerCommand::SetTimeScale(_)
+                | PlayerCommand::Pause
+                | PlayerCommand::Resume
+                | PlayerCommand::WarpToNextEvent
+                | PlayerCommand::EndTurn
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_targets_are_written_by_name() {
+        let mut command = PlayerCommand::MoveFleet {
+            fleet: Target::from(Entity::from_raw(7)),
+            destination: "Mars".into(),
+        };
+        for target in command.targets_mut() {
+            if target.name.is_empty() {
+                target.name = "First Fleet".to_string();
+            }
+        }
+        let contents = ron::to_string(&command).unwrap();
+        assert!(contents.contains("fleet:\"First Fleet\""));
+        assert!(!contents.contains("entity"));
+        let read: PlayerCommand = ron::from_str(&contents).unwrap();
+        assert_eq!(read.names(), vec!["First Fleet", "Mars"]);
+        assert!(read.targets().iter().all(|t| t.entity.is_none()));
+    }
+}
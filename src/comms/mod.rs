@@ -0,0 +1,38 @@
+//! Light-speed command delay
+//!
+//! An optional hard-science mode picked on the new-game screen. With it on,
+//! fleet, shipyard and construction orders are sent from the capital, the
+//! most populated colony or station, and reach their fleet, colony or
+//! station only after the light-time of the distance between them. Orders
+//! on their way are listed with their arrival time in the Fleets window.
+
+use bevy::prelude::*;
+
+pub mod relay;
+
+pub use relay::{
+    light_delay_seconds, relay_orders, CommandLatency, Order, Transmission, Transmissions,
+    SPEED_OF_LIGHT_AU_PER_S,
+};
+
+/// Plugin that relays orders at the speed of light
+pub struct CommsPlugin;
+
+impl Plugin for CommsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CommandLatency>()
+            .init_resource::<Transmissions>()
+            .add_systems(
+                Update,
+                relay_orders
+                    .after(crate::commands::apply_player_commands)
+                    .before(crate::construction::process_construction_actions)
+                    .before(crate::construction::found_stations)
+                    .before(crate::fleets::issue_fleet_orders)
+                    .before(crate::fleets::issue_crew_orders)
+                    .before(crate::fleets::start_reloads)
+                    .before(crate::fleets::issue_hull_orders)
+                    .before(crate::fleets::recover_salvage),
+            );
+    }
+}
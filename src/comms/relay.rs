@@ -0,0 +1,263 @@
+use bevy::math::DVec3;
+use bevy::prelude::*;
+
+use crate::astronomy::components::SpaceCoordinates;
+use crate::construction::{BuildingId, PendingConstructionActions, StationKind, StationLocation};
+use crate::economy::components::Population;
+use crate::fleets::FleetOrders;
+use crate::ui::SimulationTime;
+
+/// Speed of light in AU per second
+pub const SPEED_OF_LIGHT_AU_PER_S: f64 = 299_792.458 / 149_597_870.7;
+
+/// Whether orders travel at the speed of light, picked on the new-game screen
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CommandLatency {
+    pub enabled: bool,
+}
+
+/// Seconds light needs between two positions in AU
+pub fn light_delay_seconds(from: DVec3, to: DVec3) -> f64 {
+    from.distance(to) / SPEED_OF_LIGHT_AU_PER_S
+}
+
+/// An order to a fleet, colony or station
+#[derive(Debug, Clone, PartialEq)]
+pub enum Order {
+    Move(Entity, Entity),
+    Refuel(Entity),
+    Load(Entity, String, u32),
+    Crew(Entity),
+    Resupply(Entity),
+    BuildHull(Entity, String),
+    CancelHull(Entity, usize),
+    QueueBuilding(Entity, BuildingId),
+    CancelBuilding(Entity, usize),
+    FoundStation(Entity, StationKind, StationLocation),
+    RecoverSalvage(Entity, Entity),
+}
+
+impl Order {
+    /// Fleet, colony or station the order is sent to
+    pub fn recipient(&self) -> Entity {
+        match self {
+            Order::Move(entity, _)
+            | Order::Refuel(entity)
+            | Order::Load(entity, ..)
+            | Order::Crew(entity)
+            | Order::Resupply(entity)
+            | Order::BuildHull(entity, _)
+            | Order::CancelHull(entity, _)
+            | Order::QueueBuilding(entity, _)
+            | Order::CancelBuilding(entity, _)
+            | Order::FoundStation(entity, ..)
+            | Order::RecoverSalvage(entity, _) => *entity,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Order::Move(..) => "Move",
+            Order::Refuel(_) => "Refuel",
+            Order::Load(..) => "Load ordnance",
+            Order::Crew(_) => "Crew",
+            Order::Resupply(_) => "Resupply",
+            Order::BuildHull(..) => "Build hull",
+            Order::CancelHull(..) => "Cancel hull",
+            Order::QueueBuilding(..) => "Build",
+            Order::CancelBuilding(..) => "Cancel construction",
+            Order::FoundStation(..) => "Found station",
+            Order::RecoverSalvage(..) => "Recover salvage",
+        }
+    }
+
+    /// Take every order out of the pending orders
+    fn drain(fleets: &mut FleetOrders, construction: &mut PendingConstructionActions) -> Vec<Self> {
+        let mut orders = Vec::new();
+        orders.extend(fleets.moves.drain(..).map(|(f, to)| Order::Move(f, to)));
+        orders.extend(fleets.refuels.drain(..).map(Order::Refuel));
+        orders.extend(fleets.loads.drain(..).map(|(f, o, n)| Order::Load(f, o, n)));
+        orders.extend(fleets.crewings.drain(..).map(Order::Crew));
+        orders.extend(fleets.resupplies.drain(..).map(Order::Resupply));
+        orders.extend(
+            fleets
+                .hulls
+                .drain(..)
+                .map(|(at, h)| Order::BuildHull(at, h)),
+        );
+        orders.extend(
+            fleets
+                .salvages
+                .drain(..)
+                .map(|(f, s)| Order::RecoverSalvage(f, s)),
+        );
+        orders.extend(
+            fleets
+                .cancelled_hulls
+                .drain(..)
+                .map(|(at, i)| Order::CancelHull(at, i)),
+        );
+        orders.extend(
+            construction
+                .queue
+                .drain(..)
+                .map(|(at, b)| Order::QueueBuilding(at, b)),
+        );
+        orders.extend(
+            construction
+                .cancel
+                .drain(..)
+                .map(|(at, i)| Order::CancelBuilding(at, i)),
+        );
+        orders.extend(
+            construction
+                .found
+                .drain(..)
+                .map(|(at, kind, location)| Order::FoundStation(at, kind, location)),
+        );
+        orders
+    }
+
+    /// Hand the order to the systems that carry it out
+    fn deliver(self, fleets: &mut FleetOrders, construction: &mut PendingConstructionActions) {
+        match self {
+            Order::Move(fleet, to) => fleets.move_to(fleet, to),
+            Order::Refuel(fleet) => fleets.refuel(fleet),
+            Order::Load(fleet, ordnance, count) => fleets.load(fleet, ordnance, count),
+            Order::Crew(fleet) => fleets.crew(fleet),
+            Order::Resupply(fleet) => fleets.resupply(fleet),
+            Order::BuildHull(at, hull) => fleets.build_hull(at, hull),
+            Order::CancelHull(at, index) => fleets.cancel_hull(at, index),
+            Order::RecoverSalvage(fleet, salvage) => fleets.recover(fleet, salvage),
+            Order::QueueBuilding(at, building) => construction.queue.push((at, building)),
+            Order::CancelBuilding(at, index) => construction.cancel.push((at, index)),
+            Order::FoundStation(at, kind, location) => {
+                construction.found.push((at, kind, location))
+            }
+        }
+    }
+}
+
+/// An order on its way to its recipient
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transmission {
+    pub order: Order,
+    /// Simulation time the order was given in seconds
+    pub sent_time: f64,
+    /// Simulation time the order arrives in seconds
+    pub arrival_time: f64,
+}
+
+/// Orders travelling from the capital
+#[derive(Resource, Debug, Clone, Default)]
+pub struct Transmissions {
+    pub in_flight: Vec<Transmission>,
+    /// Position of the capital, the most populated colony or station, in AU
+    pub capital: Option<DVec3>,
+}
+
+impl Transmissions {
+    /// Light delay from the capital to `position` in seconds
+    pub fn delay_to(&self, position: DVec3) -> Option<f64> {
+        self.capital
+            .map(|capital| light_delay_seconds(capital, position))
+    }
+}
+
+/// System that holds new fleet and construction orders until light from the
+/// capital reaches their recipient, and hands over the orders that arrived.
+/// Without command latency every order goes through at once.
+pub fn relay_orders(
+    latency: Res<CommandLatency>,
+    sim_time: Res<SimulationTime>,
+    mut transmissions: ResMut<Transmissions>,
+    mut fleet_orders: ResMut<FleetOrders>,
+    mut construction: ResMut<PendingConstructionActions>,
+    colonies: Query<(&Population, &SpaceCoordinates)>,
+    positions: Query<&SpaceCoordinates>,
+) {
+    transmissions.capital = colonies
+        .iter()
+        .filter(|(population, _)| population.count > 0.0)
+        .max_by(|a, b| a.0.count.total_cmp(&b.0.count))
+        .map(|(_, coords)| coords.position);
+    let now = sim_time.elapsed_seconds();
+
+    let orders = Order::drain(&mut fleet_orders, &mut construction);
+    for order in orders {
+        let delay = if latency.enabled {
+            positions
+                .get(order.recipient())
+                .ok()
+                .and_then(|coords| transmissions.delay_to(coords.position))
+                .unwrap_or(0.0)
+        } else {
+            0.0
+        };
+        transmissions.in_flight.push(Transmission {
+            order,
+            sent_time: now,
+            arrival_time: now + delay,
+        });
+    }
+
+    // Orders still in flight when the option is turned off arrive at once
+    let (arrived, in_flight): (Vec<_>, Vec<_>) = transmissions
+        .in_flight
+        .drain(..)
+        .partition(|t| !latency.enabled || t.arrival_time <= now);
+    transmissions.in_flight = in_flight;
+    for transmission in arrived {
+        transmission
+            .order
+            .deliver(&mut fleet_orders, &mut construction);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_orders_wait_for_light_from_the_capital() {
+        let mut app = App::new();
+        app.init_resource::<SimulationTime>()
+            .insert_resource(CommandLatency { enabled: true })
+            .init_resource::<Transmissions>()
+            .init_resource::<FleetOrders>()
+            .init_resource::<PendingConstructionActions>()
+            .add_systems(Update, relay_orders);
+
+        app.world_mut().spawn((
+            Population::new(8e9),
+            SpaceCoordinates::new(DVec3::new(1.0, 0.0, 0.0)),
+        ));
+        let mars = app
+            .world_mut()
+            .spawn(SpaceCoordinates::new(DVec3::new(1.5, 0.0, 0.0)))
+            .id();
+        let delay = light_delay_seconds(DVec3::X, DVec3::new(1.5, 0.0, 0.0));
+        // Half an AU is a little over four light-minutes
+        assert!((delay - 249.5).abs() < 0.1);
+
+        app.world_mut()
+            .resource_mut::<PendingConstructionActions>()
+            .queue
+            .push((mars, "iron_mine".to_string()));
+        app.update();
+        assert!(app
+            .world()
+            .resource::<PendingConstructionActions>()
+            .queue
+            .is_empty());
+        assert_eq!(app.world().resource::<Transmissions>().in_flight.len(), 1);
+
+        app.world_mut().resource_mut::<SimulationTime>().elapsed = delay;
+        app.update();
+        assert_eq!(
+            app.world().resource::<PendingConstructionActions>().queue,
+            vec![(mars, "iron_mine".to_string())]
+        );
+        assert!(app.world().resource::<Transmissions>().in_flight.is_empty());
+    }
+}
@@ -0,0 +1,304 @@
+//! Commands the console comes with
+
+use bevy::prelude::*;
+
+use super::{Console, ConsoleCommand, ConsoleCommands, RegisterConsoleCommand};
+use crate::astronomy::components::{CurrentStarSystem, SystemId};
+use crate::astronomy::Selected;
+use crate::economy::{GlobalBudget, ResourceType};
+use crate::plugins::camera::{CameraAnchor, GameCamera, OrbitCamera};
+use crate::plugins::solar_system::CelestialBody;
+use crate::research::{ResearchCompleted, ResearchProject, ResearchState, TechnologiesData};
+use crate::ui::{Selection, TimeScale, MAX_TIME_SCALE};
+
+/// Camera distance to a teleport target as a multiple of its visual radius
+const TELEPORT_VIEW_DISTANCE: f32 = 20.0;
+
+/// Lowercase name without spaces or underscores, for loose matching
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| !c.is_whitespace() && *c != '_')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Resource type by display name, ignoring case, spaces and underscores
+pub fn find_resource(name: &str) -> Option<ResourceType> {
+    let name = normalize(name);
+    ResourceType::all()
+        .iter()
+        .copied()
+        .find(|resource| normalize(resource.display_name()) == name)
+}
+
+fn parse_number(text: &str) -> Result<f64, String> {
+    text.parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite())
+        .ok_or_else(|| format!("'{}' is not a number", text))
+}
+
+fn help(world: &mut World, args: &[String]) -> Result<String, String> {
+    let commands = world.resource::<ConsoleCommands>();
+    if let Some(name) = args.first() {
+        let command = commands
+            .get(name)
+            .ok_or_else(|| format!("Unknown command '{}'", name))?;
+        return Ok(format!(
+            "{}: {}",
+            format!("{} {}", command.name, command.usage).trim_end(),
+            command.description
+        ));
+    }
+    Ok(commands
+        .iter()
+        .map(|command| {
+            format!("{} {}", command.name, command.usage)
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+fn clear(world: &mut World, _: &[String]) -> Result<String, String> {
+    world.resource_mut::<Console>().lines.clear();
+    Ok(String::new())
+}
+
+fn give_resource(world: &mut World, args: &[String]) -> Result<String, String> {
+    let [resource, amount] = args else {
+        return Err("Usage: give_resource <resource> <amount>".to_string());
+    };
+    let resource =
+        find_resource(resource).ok_or_else(|| format!("Unknown resource '{}'", resource))?;
+    let amount = parse_number(amount)?;
+    let mut budget = world
+        .get_resource_mut::<GlobalBudget>()
+        .ok_or("There is no stockpile")?;
+    // Negative amounts take resources away, down to an empty stockpile
+    let stockpile = budget.stockpiles.entry(resource).or_insert(0.0);
+    *stockpile = (*stockpile + amount).max(0.0);
+    Ok(format!(
+        "{} stockpile is now {:.1}",
+        resource.display_name(),
+        *stockpile
+    ))
+}
+
+fn unlock_tech(world: &mut World, args: &[String]) -> Result<String, String> {
+    let [id] = args else {
+        return Err("Usage: unlock_tech <technology id>".to_string());
+    };
+    let tech = world
+        .get_resource::<TechnologiesData>()
+        .and_then(|data| data.get_tech(id))
+        .cloned()
+        .ok_or_else(|| format!("Unknown technology '{}'", id))?;
+    let mut research = world
+        .get_resource_mut::<ResearchState>()
+        .ok_or("Research is not running")?;
+    if research.is_unlocked(&tech.id) {
+        return Ok(format!("{} is already unlocked", tech.name));
+    }
+    for modifier in &tech.modifiers {
+        research.add_modifier(modifier.modifier_type.clone(), modifier.value);
+    }
+    research.unlock_tech(tech.id.clone());
+    // A project still researching it would complete it a second time
+    let mut projects = world.query::<(Entity, &ResearchProject)>();
+    let underway: Vec<Entity> = projects
+        .iter(world)
+        .filter(|(_, project)| project.tech_id == tech.id)
+        .map(|(entity, _)| entity)
+        .collect();
+    for entity in underway {
+        world.despawn(entity);
+    }
+    world.send_event(ResearchCompleted {
+        tech_id: tech.id.clone(),
+    });
+    Ok(format!("Unlocked {}", tech.name))
+}
+
+fn set_time_scale(world: &mut World, args: &[String]) -> Result<String, String> {
+    let [scale] = args else {
+        return Err("Usage: set_time_scale <seconds per second>".to_string());
+    };
+    let scale = parse_number(scale)?;
+    if scale < 0.0 {
+        return Err("The time scale cannot be negative".to_string());
+    }
+    let scale = (scale as f32).min(MAX_TIME_SCALE);
+    let mut time_scale = world.resource_mut::<TimeScale>();
+    if scale == 0.0 {
+        time_scale.pause();
+    } else {
+        time_scale.scale = scale;
+    }
+    Ok(format!("Time scale set to {}", scale))
+}
+
+fn teleport_camera(world: &mut World, args: &[String]) -> Result<String, String> {
+    if args.is_empty() {
+        return Err("Usage: teleport_camera <body name>".to_string());
+    }
+    // Unquoted names with spaces arrive as several words
+    let name = normalize(&args.join(" "));
+    let current_system = world.get_resource::<CurrentStarSystem>().map_or(0, |c| c.0);
+    let mut bodies = world.query::<(Entity, &CelestialBody, Option<&SystemId>)>();
+    let (target, body_name, visual_radius, system) = bodies
+        .iter(world)
+        .find(|(_, body, _)| normalize(&body.name) == name)
+        .map(|(entity, body, system)| {
+            (
+                entity,
+                body.name.clone(),
+                body.visual_radius,
+                system.map_or(0, |s| s.0),
+            )
+        })
+        .ok_or_else(|| format!("No body named '{}'", args.join(" ")))?;
+    if system != current_system {
+        return Err(format!("{} is in another star system", body_name));
+    }
+
+    let mut cameras =
+        world.query_filtered::<(&mut CameraAnchor, &mut OrbitCamera), With<GameCamera>>();
+    let (mut anchor, mut orbit) = cameras
+        .get_single_mut(world)
+        .map_err(|_| "There is no game camera".to_string())?;
+    anchor.0 = Some(target);
+    orbit.radius =
+        (visual_radius * TELEPORT_VIEW_DISTANCE).clamp(orbit.min_radius, orbit.max_radius);
+
+    let mut selected = world.query_filtered::<Entity, With<Selected>>();
+    let previous: Vec<Entity> = selected.iter(world).collect();
+    for entity in previous {
+        world.entity_mut(entity).remove::<Selected>();
+    }
+    world.entity_mut(target).insert(Selected);
+    if let Some(mut selection) = world.get_resource_mut::<Selection>() {
+        selection.select(target);
+    }
+    Ok(format!("Camera moved to {}", body_name))
+}
+
+/// Register the built-in commands
+pub fn register_builtin_commands(app: &mut App) {
+    app.register_console_command(ConsoleCommand {
+        name: "help",
+        usage: "[command]",
+        description: "List the commands, or describe one",
+        handler: help,
+    })
+    .register_console_command(ConsoleCommand {
+        name: "clear",
+        usage: "",
+        description: "Clear the console output",
+        handler: clear,
+    })
+    .register_console_command(ConsoleCommand {
+        name: "give_resource",
+        usage: "<resource> <amount>",
+        description: "Add to (or with a negative amount, take from) the stockpile",
+        handler: give_resource,
+    })
+    .register_console_command(ConsoleCommand {
+        name: "unlock_tech",
+        usage: "<technology id>",
+        description: "Unlock a technology and apply its modifiers",
+        handler: unlock_tech,
+    })
+    .register_console_command(ConsoleCommand {
+        name: "set_time_scale",
+        usage: "<seconds per second>",
+        description: "Set the simulation speed; 0 pauses",
+        handler: set_time_scale,
+    })
+    .register_console_command(ConsoleCommand {
+        name: "teleport_camera",
+        usage: "<body name>",
+        description: "Anchor the camera on a body of the current star system and select it",
+        handler: teleport_camera,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::run_command;
+
+    #[test]
+    fn test_give_resource() {
+        assert_eq!(find_resource("iron"), Some(ResourceType::Iron));
+        assert_eq!(
+            find_resource("carbon_dioxide"),
+            Some(ResourceType::CarbonDioxide)
+        );
+        assert_eq!(find_resource("unobtainium"), None);
+
+        let mut world = World::new();
+        world.insert_resource(GlobalBudget::new());
+        world.init_resource::<ConsoleCommands>();
+        world
+            .resource_mut::<ConsoleCommands>()
+            .register(ConsoleCommand {
+                name: "give_resource",
+                usage: "",
+                description: "",
+                handler: give_resource,
+            });
+        let before = world
+            .resource::<GlobalBudget>()
+            .get_stockpile(&ResourceType::Iron);
+        run_command(&mut world, "give_resource iron 1000").unwrap();
+        assert_eq!(
+            world
+                .resource::<GlobalBudget>()
+                .get_stockpile(&ResourceType::Iron),
+            before + 1000.0
+        );
+        run_command(&mut world, "give_resource Iron -1e12").unwrap();
+        assert_eq!(
+            world
+                .resource::<GlobalBudget>()
+                .get_stockpile(&ResourceType::Iron),
+            0.0
+        );
+        assert!(run_command(&mut world, "give_resource iron lots").is_err());
+        assert!(run_command(&mut world, "give_resource iron").is_err());
+    }
+
+    #[test]
+    fn test_unlock_tech_ends_its_project() {
+        let mut world = World::new();
+        world.insert_resource(crate::research::read_technologies(None).0);
+        world.init_resource::<ResearchState>();
+        world.init_resource::<Events<ResearchCompleted>>();
+        world.init_resource::<ConsoleCommands>();
+        world
+            .resource_mut::<ConsoleCommands>()
+            .register(ConsoleCommand {
+                name: "unlock_tech",
+                usage: "",
+                description: "",
+                handler: unlock_tech,
+            });
+        let team = world.spawn_empty().id();
+        let project = world
+            .spawn(ResearchProject::new(
+                "basic_computing".to_string(),
+                100.0,
+                team,
+            ))
+            .id();
+
+        run_command(&mut world, "unlock_tech basic_computing").unwrap();
+        assert!(world
+            .resource::<ResearchState>()
+            .is_unlocked("basic_computing"));
+        assert!(world.get_entity(project).is_none());
+        assert_eq!(world.resource::<Events<ResearchCompleted>>().len(), 1);
+    }
+}
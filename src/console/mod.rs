@@ -0,0 +1,228 @@
+//! Developer console
+//!
+//! Opened with the backtick key, the console runs typed commands such as
+//! `give_resource iron 1000` or `teleport_camera Mars`. Commands live in the
+//! [`ConsoleCommands`] registry: any plugin can add its own with
+//! [`RegisterConsoleCommand::register_console_command`], giving a handler
+//! that gets the whole [`World`] and the command's arguments. Arguments are
+//! separated by spaces; quote names that contain them. Entered lines are sent
+//! as [`PlayerCommand::Console`](crate::commands::PlayerCommand::Console),
+//! so replays play them back.
+
+use bevy::prelude::*;
+use std::collections::BTreeMap;
+
+pub mod builtin;
+
+/// Lines of output kept in the console
+pub const CONSOLE_HISTORY: usize = 200;
+
+/// Runs a command with its arguments, returning the message to print or an
+/// error
+pub type ConsoleHandler = fn(&mut World, &[String]) -> Result<String, String>;
+
+/// A command the console can run
+#[derive(Debug, Clone, Copy)]
+pub struct ConsoleCommand {
+    pub name: &'static str,
+    /// Arguments, shown by `help`
+    pub usage: &'static str,
+    pub description: &'static str,
+    pub handler: ConsoleHandler,
+}
+
+/// Registry of the console commands by name
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ConsoleCommands {
+    commands: BTreeMap<&'static str, ConsoleCommand>,
+}
+
+impl ConsoleCommands {
+    /// Add a command, replacing any of the same name
+    pub fn register(&mut self, command: ConsoleCommand) {
+        if self.commands.insert(command.name, command).is_some() {
+            warn!("Console command '{}' registered twice", command.name);
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ConsoleCommand> {
+        self.commands.get(name)
+    }
+
+    /// Commands in alphabetical order
+    pub fn iter(&self) -> impl Iterator<Item = &ConsoleCommand> {
+        self.commands.values()
+    }
+}
+
+/// Lets plugins add console commands while building the app
+pub trait RegisterConsoleCommand {
+    fn register_console_command(&mut self, command: ConsoleCommand) -> &mut Self;
+}
+
+impl RegisterConsoleCommand for App {
+    fn register_console_command(&mut self, command: ConsoleCommand) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_insert_with(ConsoleCommands::default)
+            .register(command);
+        self
+    }
+}
+
+/// Kind of a line of console output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsoleLineKind {
+    Input,
+    Output,
+    Error,
+}
+
+/// State of the console window
+#[derive(Resource, Debug, Clone, Default)]
+pub struct Console {
+    pub open: bool,
+    /// Text being typed
+    pub input: String,
+    /// Output, oldest first
+    pub lines: Vec<(ConsoleLineKind, String)>,
+    /// Commands entered, oldest first, recalled with the arrow keys
+    pub entered: Vec<String>,
+    /// Commands waiting to be run on the next update
+    pub pending: Vec<String>,
+}
+
+impl Console {
+    pub fn print(&mut self, kind: ConsoleLineKind, text: impl Into<String>) {
+        self.lines.push((kind, text.into()));
+        if self.lines.len() > CONSOLE_HISTORY {
+            let excess = self.lines.len() - CONSOLE_HISTORY;
+            self.lines.drain(..excess);
+        }
+    }
+
+    /// Echo an entered command line and keep it for recall, returning the
+    /// line to send as a player command
+    pub fn submit(&mut self, line: &str) -> Option<String> {
+        let line = line.trim();
+        if line.is_empty() {
+            return None;
+        }
+        self.print(ConsoleLineKind::Input, format!("> {}", line));
+        if self.entered.last().map(String::as_str) != Some(line) {
+            self.entered.push(line.to_string());
+        }
+        Some(line.to_string())
+    }
+}
+
+/// Split a command line into words; double quotes group words with spaces
+pub fn tokenize(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quoted = false;
+    let mut started = false;
+    for c in line.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                started = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if started {
+                    words.push(std::mem::take(&mut word));
+                    started = false;
+                }
+            }
+            c => {
+                word.push(c);
+                started = true;
+            }
+        }
+    }
+    if started {
+        words.push(word);
+    }
+    words
+}
+
+/// Run one command line against the world
+pub fn run_command(world: &mut World, line: &str) -> Result<String, String> {
+    let words = tokenize(line);
+    let Some((name, args)) = words.split_first() else {
+        return Ok(String::new());
+    };
+    let command = world
+        .get_resource::<ConsoleCommands>()
+        .and_then(|commands| commands.get(name).copied())
+        .ok_or_else(|| format!("Unknown command '{}'; type help for a list", name))?;
+    (command.handler)(world, args)
+}
+
+/// Exclusive system that runs the commands entered in the console
+pub fn run_console_commands(world: &mut World) {
+    let pending = std::mem::take(&mut world.resource_mut::<Console>().pending);
+    for line in pending {
+        let result = run_command(world, &line);
+        let mut console = world.resource_mut::<Console>();
+        match result {
+            Ok(output) if output.is_empty() => {}
+            Ok(output) => console.print(ConsoleLineKind::Output, output),
+            Err(error) => console.print(ConsoleLineKind::Error, error),
+        }
+    }
+}
+
+/// Plugin that adds the console and its built-in commands
+pub struct ConsolePlugin;
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Console>()
+            .init_resource::<ConsoleCommands>()
+            .add_systems(
+                Update,
+                run_console_commands.after(crate::commands::apply_player_commands),
+            );
+        builtin::register_builtin_commands(app);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_command_lines() {
+        assert_eq!(
+            tokenize("  give_resource iron  1000 "),
+            ["give_resource", "iron", "1000"]
+        );
+        assert_eq!(
+            tokenize("teleport_camera \"Proxima Centauri b\""),
+            ["teleport_camera", "Proxima Centauri b"]
+        );
+        assert_eq!(tokenize("echo \"\""), ["echo", ""]);
+        assert!(tokenize("   ").is_empty());
+    }
+
+    #[test]
+    fn test_registered_commands_run() {
+        fn echo(_: &mut World, args: &[String]) -> Result<String, String> {
+            Ok(args.join(" "))
+        }
+
+        let mut app = App::new();
+        app.register_console_command(ConsoleCommand {
+            name: "echo",
+            usage: "<text>",
+            description: "Print the text",
+            handler: echo,
+        });
+        let world = app.world_mut();
+        assert_eq!(
+            run_command(world, "echo a \"b c\""),
+            Ok("a b c".to_string())
+        );
+        assert!(run_command(world, "missing").is_err());
+    }
+}
@@ -0,0 +1,428 @@
+//! Colony blueprints
+//!
+//! A [`Blueprint`] is a named building layout: the level every building chain
+//! of a colony stands at or is queued to. Blueprints are kept in
+//! `blueprints.ron` and can be exported and imported one at a time as RON
+//! text for sharing. Applied to a colony or station, a blueprint becomes a
+//! [`BlueprintProgress`] that queues the missing levels a few projects at a
+//! time, lowest levels first, each once the stockpile can pay for it and
+//! research allows it.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::components::{Building, ConstructionQueue};
+use super::data::BuildingsData;
+use super::stations::OrbitalStation;
+use super::systems::{built_level, can_afford, check_queueable, site_of};
+use super::types::BuildingId;
+use super::PendingConstructionActions;
+use crate::astronomy::components::AtmosphereComposition;
+use crate::comms::{Order, Transmissions};
+use crate::economy::budget::GlobalBudget;
+use crate::economy::reservations::Reservations;
+use crate::economy::tick::EconomyClock;
+use crate::game_state::{EventLog, LogSeverity};
+use crate::plugins::solar_system::{CelestialBody, LogicalParent};
+use crate::research::ResearchState;
+use crate::ui::SimulationTime;
+
+/// Blueprint library file, next to the settings
+pub const BLUEPRINTS_PATH: &str = "blueprints.ron";
+
+/// Projects a blueprint keeps in a colony's construction queue at most
+pub const BLUEPRINT_QUEUE_DEPTH: usize = 2;
+
+/// Level wanted of one building chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlueprintEntry {
+    pub building: BuildingId,
+    pub level: u32,
+}
+
+/// Named building layout
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Blueprint {
+    pub name: String,
+    pub entries: Vec<BlueprintEntry>,
+}
+
+impl Blueprint {
+    /// Layout of `colony`: its standing buildings by id, raised to the levels
+    /// already queued
+    pub fn of_colony<'a>(
+        name: String,
+        colony: Entity,
+        buildings: impl IntoIterator<Item = (&'a Building, &'a LogicalParent)>,
+        queue: Option<&ConstructionQueue>,
+    ) -> Self {
+        let mut entries: Vec<BlueprintEntry> = buildings
+            .into_iter()
+            .filter(|(_, parent)| parent.0 == colony)
+            .map(|(building, _)| BlueprintEntry {
+                building: building.id.clone(),
+                level: building.level,
+            })
+            .collect();
+        entries.sort_by(|a, b| a.building.cmp(&b.building));
+        for project in queue.into_iter().flat_map(|q| &q.projects) {
+            match entries
+                .iter_mut()
+                .find(|entry| entry.building == project.building_id)
+            {
+                Some(entry) => entry.level = entry.level.max(project.target_level),
+                None => entries.push(BlueprintEntry {
+                    building: project.building_id.clone(),
+                    level: project.target_level,
+                }),
+            }
+        }
+        Self { name, entries }
+    }
+
+    /// Levels still to queue, given the next level each chain would be
+    /// queued at, lowest first and in blueprint order among equals
+    pub fn steps(&self, next_level: impl Fn(&str) -> u32) -> Vec<(&BuildingId, u32)> {
+        let mut steps: Vec<_> = self
+            .entries
+            .iter()
+            .map(|entry| (&entry.building, next_level(&entry.building), entry.level))
+            .filter(|(_, next, wanted)| next <= wanted)
+            .map(|(building, next, _)| (building, next))
+            .collect();
+        steps.sort_by_key(|(_, level)| *level);
+        steps
+    }
+
+    pub fn from_ron(contents: &str) -> Result<Self, String> {
+        ron::from_str(contents).map_err(|e| e.to_string())
+    }
+
+    pub fn to_ron(&self) -> Result<String, String> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::new()).map_err(|e| e.to_string())
+    }
+}
+
+/// Saved blueprints, persisted to [`BLUEPRINTS_PATH`]
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Blueprints {
+    pub blueprints: Vec<Blueprint>,
+}
+
+impl Blueprints {
+    pub fn get(&self, name: &str) -> Option<&Blueprint> {
+        self.blueprints.iter().find(|b| b.name == name)
+    }
+
+    /// Add `blueprint`, replacing one of the same name
+    pub fn insert(&mut self, blueprint: Blueprint) {
+        match self
+            .blueprints
+            .iter_mut()
+            .find(|b| b.name == blueprint.name)
+        {
+            Some(existing) => *existing = blueprint,
+            None => self.blueprints.push(blueprint),
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.blueprints.retain(|b| b.name != name);
+    }
+
+    /// Load the library from `path`, empty if the file is missing or invalid
+    pub fn load(path: &str) -> Self {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => ron::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Invalid blueprints in {}: {}", path, e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Write the library to `path`
+    pub fn save(&self, path: &str) {
+        match ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::new()) {
+            Ok(contents) => match std::fs::write(path, contents) {
+                Ok(()) => info!("Saved blueprints to {}", path),
+                Err(e) => error!("Failed to write blueprints file: {}", e),
+            },
+            Err(e) => error!("Failed to serialize blueprints: {}", e),
+        }
+    }
+}
+
+/// Blueprint being built up at a colony or station
+#[derive(Component, Debug, Clone)]
+pub struct BlueprintProgress {
+    pub blueprint: Blueprint,
+}
+
+/// Blueprint requests from the UI
+#[derive(Resource, Debug, Clone, Default)]
+pub struct PendingBlueprintActions {
+    /// (colony, name) pairs to save the layout of
+    pub save: Vec<(Entity, String)>,
+    /// (colony, name) pairs of saved blueprints to apply
+    pub apply: Vec<(Entity, String)>,
+    /// Colonies to stop applying their blueprint at
+    pub stop: Vec<Entity>,
+    /// Blueprints imported from RON text
+    pub import: Vec<Blueprint>,
+    /// Names of blueprints to delete
+    pub delete: Vec<String>,
+}
+
+/// Name of a colony body or station for log messages
+fn colony_name(
+    entity: Entity,
+    stations: &Query<&OrbitalStation>,
+    bodies: &Query<(&CelestialBody, Option<&AtmosphereComposition>)>,
+) -> String {
+    if let Ok(station) = stations.get(entity) {
+        return station.name.clone();
+    }
+    bodies
+        .get(entity)
+        .map_or("Unknown".to_string(), |(body, _)| body.name.clone())
+}
+
+/// System to process blueprint requests queued from the UI. Applying a
+/// blueprint drops the chains the site cannot take and caps levels at the
+/// highest each chain has.
+#[allow(clippy::too_many_arguments)]
+pub fn process_blueprint_actions(
+    mut commands: Commands,
+    mut actions: ResMut<PendingBlueprintActions>,
+    mut blueprints: ResMut<Blueprints>,
+    buildings_data: Option<Res<BuildingsData>>,
+    sim_time: Res<SimulationTime>,
+    mut log: ResMut<EventLog>,
+    queues: Query<&ConstructionQueue>,
+    buildings: Query<(&Building, &LogicalParent)>,
+    stations: Query<&OrbitalStation>,
+    bodies: Query<(&CelestialBody, Option<&AtmosphereComposition>)>,
+) {
+    let Some(buildings_data) = buildings_data else {
+        return;
+    };
+
+    for (colony, name) in actions.save.drain(..) {
+        let blueprint =
+            Blueprint::of_colony(name, colony, buildings.iter(), queues.get(colony).ok());
+        info!(
+            "Saved blueprint {} with {} buildings",
+            blueprint.name,
+            blueprint.entries.len()
+        );
+        blueprints.insert(blueprint);
+    }
+
+    for blueprint in actions.import.drain(..) {
+        info!("Imported blueprint {}", blueprint.name);
+        blueprints.insert(blueprint);
+    }
+
+    for name in actions.delete.drain(..) {
+        blueprints.remove(&name);
+    }
+
+    for (colony, name) in actions.apply.drain(..) {
+        let Some(blueprint) = blueprints.get(&name) else {
+            warn!("Cannot apply unknown blueprint '{}'", name);
+            continue;
+        };
+        let site = site_of(colony, &stations, &bodies);
+        let entries: Vec<_> = blueprint
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                let definition = buildings_data
+                    .get(&entry.building)
+                    .filter(|d| d.site == site)?;
+                Some(BlueprintEntry {
+                    building: entry.building.clone(),
+                    level: entry.level.min(definition.max_level()),
+                })
+            })
+            .collect();
+        let dropped = blueprint.entries.len() - entries.len();
+        let mut message = format!(
+            "Applying blueprint {} at {}",
+            name,
+            colony_name(colony, &stations, &bodies)
+        );
+        if dropped > 0 {
+            message.push_str(&format!(
+                " ({} buildings do not fit this site and are left out)",
+                dropped
+            ));
+        }
+        log.push(sim_time.format_date_time(), LogSeverity::Info, message);
+        commands.entity(colony).insert(BlueprintProgress {
+            blueprint: Blueprint { name, entries },
+        });
+    }
+
+    for colony in actions.stop.drain(..) {
+        commands.entity(colony).remove::<BlueprintProgress>();
+    }
+}
+
+/// System that queues the next step of every blueprint being applied once per
+/// economy tick, keeping at most [`BLUEPRINT_QUEUE_DEPTH`] projects queued.
+///
+/// Steps research has not unlocked yet are passed over; otherwise the lowest
+/// step waits until the stock free of reservations holds its full cost, so a
+/// blueprint never queues projects that would stall. The blueprint is done once every level
+/// stands or is queued. Steps go through [`PendingConstructionActions`] like
+/// any other construction order.
+#[allow(clippy::too_many_arguments)]
+pub fn advance_blueprints(
+    mut commands: Commands,
+    clock: Res<EconomyClock>,
+    budget: Res<GlobalBudget>,
+    reservations: Res<Reservations>,
+    research_state: Res<ResearchState>,
+    buildings_data: Option<Res<BuildingsData>>,
+    sim_time: Res<SimulationTime>,
+    mut log: ResMut<EventLog>,
+    mut pending: ResMut<PendingConstructionActions>,
+    transmissions: Res<Transmissions>,
+    colonies: Query<(Entity, &BlueprintProgress, Option<&ConstructionQueue>)>,
+    buildings: Query<(&Building, &LogicalParent)>,
+    stations: Query<&OrbitalStation>,
+    bodies: Query<(&CelestialBody, Option<&AtmosphereComposition>)>,
+) {
+    let Some(buildings_data) = buildings_data else {
+        return;
+    };
+    if clock.due == 0 {
+        return;
+    }
+
+    for (colony, progress, queue) in &colonies {
+        // Orders from earlier ticks may not have reached the queue yet
+        let in_flight = transmissions
+            .in_flight
+            .iter()
+            .any(|t| matches!(&t.order, Order::QueueBuilding(entity, _) if *entity == colony));
+        if in_flight || pending.queue.iter().any(|(entity, _)| *entity == colony) {
+            continue;
+        }
+        let steps = progress.blueprint.steps(|id| {
+            let built = built_level(buildings.iter(), colony, id);
+            queue.map_or(built + 1, |q| q.next_level(id, built))
+        });
+        if steps.is_empty() {
+            commands.entity(colony).remove::<BlueprintProgress>();
+            log.push(
+                sim_time.format_date_time(),
+                LogSeverity::Info,
+                format!(
+                    "Blueprint {} is fully queued at {}",
+                    progress.blueprint.name,
+                    colony_name(colony, &stations, &bodies)
+                ),
+            );
+            continue;
+        }
+        if queue.is_some_and(|q| q.projects.len() >= BLUEPRINT_QUEUE_DEPTH) {
+            continue;
+        }
+
+        let next = steps.into_iter().find_map(|(id, level)| {
+            let definition = buildings_data.get(id)?;
+            check_queueable(definition, level, &research_state)
+                .ok()
+                .map(|building_level| (id, building_level))
+        });
+        if let Some((id, building_level)) = next {
+            if can_afford(&budget, &reservations, building_level, false) {
+                pending.queue.push((colony, id.clone()));
+            }
+        }
+    }
+}
+
+/// Persist the library whenever it changes
+pub fn save_blueprints(blueprints: Res<Blueprints>) {
+    if blueprints.is_changed() && !blueprints.is_added() {
+        blueprints.save(BLUEPRINTS_PATH);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::construction::ConstructionProject;
+
+    #[test]
+    fn test_blueprint_of_colony_and_steps() {
+        let colony = Entity::from_raw(1);
+        let other = Entity::from_raw(2);
+        let mine = Building {
+            id: "mine".to_string(),
+            level: 2,
+        };
+        let farm = Building {
+            id: "farm".to_string(),
+            level: 1,
+        };
+        let elsewhere = Building {
+            id: "shipyard".to_string(),
+            level: 1,
+        };
+        let mut queue = ConstructionQueue::default();
+        queue
+            .projects
+            .push_back(ConstructionProject::new("farm".to_string(), 2, 30.0));
+        queue
+            .projects
+            .push_back(ConstructionProject::new("lab".to_string(), 1, 30.0));
+        let buildings = [
+            (&mine, &LogicalParent(colony)),
+            (&farm, &LogicalParent(colony)),
+            (&elsewhere, &LogicalParent(other)),
+        ];
+
+        let blueprint = Blueprint::of_colony("Core".to_string(), colony, buildings, Some(&queue));
+        let levels: Vec<_> = blueprint
+            .entries
+            .iter()
+            .map(|e| (e.building.as_str(), e.level))
+            .collect();
+        assert_eq!(levels, [("farm", 2), ("mine", 2), ("lab", 1)]);
+
+        // A new colony with only the farm started builds every first level
+        // before any second one
+        let steps = blueprint.steps(|id| if id == "farm" { 2 } else { 1 });
+        let steps: Vec<_> = steps.iter().map(|(id, l)| (id.as_str(), *l)).collect();
+        assert_eq!(steps, [("mine", 1), ("lab", 1), ("farm", 2)]);
+        assert!(blueprint.steps(|_| 3).is_empty());
+    }
+
+    #[test]
+    fn test_blueprint_round_trips_and_replaces_by_name() {
+        let blueprint = Blueprint {
+            name: "Outpost".to_string(),
+            entries: vec![BlueprintEntry {
+                building: "mine".to_string(),
+                level: 3,
+            }],
+        };
+        let parsed = Blueprint::from_ron(&blueprint.to_ron().unwrap()).unwrap();
+        assert_eq!(parsed, blueprint);
+        assert!(Blueprint::from_ron("not a blueprint").is_err());
+
+        let mut library = Blueprints::default();
+        library.insert(blueprint.clone());
+        library.insert(Blueprint {
+            entries: Vec::new(),
+            ..blueprint
+        });
+        assert_eq!(library.blueprints.len(), 1);
+        assert!(library.get("Outpost").unwrap().entries.is_empty());
+    }
+}
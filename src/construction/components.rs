@@ -0,0 +1,98 @@
+use bevy::prelude::*;
+use std::collections::VecDeque;
+
+use super::types::BuildingId;
+
+/// A standing facility at a given level of its upgrade chain.
+///
+/// Each building is its own entity attached to the body it stands on through
+/// `LogicalParent`, and carries the economy components (power, mining,
+/// research) that implement its cumulative effects. Upgrades change the level
+/// in place instead of adding another building.
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+pub struct Building {
+    /// Building chain this facility belongs to
+    pub id: BuildingId,
+    /// Current level (1-based)
+    pub level: u32,
+}
+
+/// A queued construction or upgrade
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstructionProject {
+    /// Building chain being built or upgraded
+    pub building_id: BuildingId,
+    /// Level the building will have once the project completes
+    pub target_level: u32,
+    /// Days of work done so far
+    pub progress_days: f64,
+    /// Days of work needed
+    pub required_days: f64,
+    /// Work is done but the stockpile cannot cover the cost yet
+    pub stalled: bool,
+    /// The cost is earmarked in the stockpile (see `economy::reservations`)
+    pub reserved: bool,
+}
+
+impl ConstructionProject {
+    pub fn new(building_id: BuildingId, target_level: u32, required_days: f64) -> Self {
+        Self {
+            building_id,
+            target_level,
+            progress_days: 0.0,
+            required_days,
+            stalled: false,
+            reserved: false,
+        }
+    }
+
+    /// Progress as a fraction (0.0 to 1.0)
+    pub fn progress_fraction(&self) -> f32 {
+        if self.required_days <= 0.0 {
+            1.0
+        } else {
+            (self.progress_days / self.required_days).min(1.0) as f32
+        }
+    }
+
+    /// Whether all construction work has been done
+    pub fn is_complete(&self) -> bool {
+        self.progress_days >= self.required_days
+    }
+}
+
+/// Construction queue of a body; only the front project is worked on
+#[derive(Component, Debug, Clone, Default)]
+pub struct ConstructionQueue {
+    pub projects: VecDeque<ConstructionProject>,
+}
+
+impl ConstructionQueue {
+    /// Highest level already queued for a building chain
+    pub fn highest_queued_level(&self, building_id: &str) -> Option<u32> {
+        self.projects
+            .iter()
+            .filter(|p| p.building_id == building_id)
+            .map(|p| p.target_level)
+            .max()
+    }
+
+    /// Level the next project for a chain would build, given the level
+    /// currently standing (0 if not built yet)
+    pub fn next_level(&self, building_id: &str, built_level: u32) -> u32 {
+        self.highest_queued_level(building_id)
+            .unwrap_or(0)
+            .max(built_level)
+            + 1
+    }
+
+    /// Remove the project at `index` together with any later upgrades of the
+    /// same chain that depend on it
+    pub fn cancel(&mut self, index: usize) -> Option<ConstructionProject> {
+        let cancelled = self.projects.remove(index)?;
+        self.projects.retain(|p| {
+            p.building_id != cancelled.building_id || p.target_level < cancelled.target_level
+        });
+        Some(cancelled)
+    }
+}
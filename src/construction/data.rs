@@ -0,0 +1,242 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use super::types::{BuildingDefinition, BuildingId, BuildingSite};
+use crate::modding::ModList;
+use crate::research::TechnologiesData;
+
+/// Resource containing all building definitions loaded from data
+#[derive(Resource, Debug, Clone, Default)]
+pub struct BuildingsData {
+    /// All building chains indexed by ID
+    pub buildings: HashMap<BuildingId, BuildingDefinition>,
+    /// Building IDs in data file order, for display
+    pub order: Vec<BuildingId>,
+}
+
+impl BuildingsData {
+    /// Get a building definition by ID
+    pub fn get(&self, id: &str) -> Option<&BuildingDefinition> {
+        self.buildings.get(id)
+    }
+
+    /// Iterate building definitions in data file order
+    pub fn iter(&self) -> impl Iterator<Item = &BuildingDefinition> {
+        self.order.iter().filter_map(|id| self.buildings.get(id))
+    }
+
+    /// Add a definition, keeping the display order
+    pub fn insert(&mut self, definition: BuildingDefinition) {
+        if !self.buildings.contains_key(&definition.id) {
+            self.order.push(definition.id.clone());
+        }
+        self.buildings.insert(definition.id.clone(), definition);
+    }
+}
+
+/// Structure of the buildings data file
+#[derive(Debug, Deserialize)]
+struct BuildingsFile {
+    buildings: Vec<BuildingDefinition>,
+}
+
+/// Check that a definition can be represented by the economy components.
+/// A building mines and harvests at most one resource each across its whole
+/// chain and refines at most one good, only Dyson swarm collectors can draw
+/// on the host star's luminosity, only atmospheric facilities can harvest
+/// gas and only surface farms grow food in fields.
+pub fn validate_building(definition: &BuildingDefinition) -> Result<(), String> {
+    if definition.levels.is_empty() {
+        return Err(format!("building '{}' has no levels", definition.id));
+    }
+
+    let effects = definition.cumulative_effects(definition.max_level());
+    if effects.mining.len() > 1 {
+        return Err(format!(
+            "building '{}' mines more than one resource",
+            definition.id
+        ));
+    }
+    if effects.stellar_collection > 0.0 && definition.site != BuildingSite::DysonSwarm {
+        return Err(format!(
+            "building '{}' collects starlight outside a Dyson swarm",
+            definition.id
+        ));
+    }
+    if effects.gas_harvest.len() > 1 {
+        return Err(format!(
+            "building '{}' harvests more than one gas",
+            definition.id
+        ));
+    }
+    if !effects.gas_harvest.is_empty() && definition.site != BuildingSite::Atmosphere {
+        return Err(format!(
+            "building '{}' harvests gas outside an atmosphere",
+            definition.id
+        ));
+    }
+    if effects.refining.len() > 1 {
+        return Err(format!(
+            "building '{}' refines more than one good",
+            definition.id
+        ));
+    }
+    if effects.farming > 0.0 && effects.hydroponics > 0.0 {
+        return Err(format!(
+            "building '{}' mixes field farming and hydroponics",
+            definition.id
+        ));
+    }
+    if effects.farming > 0.0 && definition.site != BuildingSite::Surface {
+        return Err(format!(
+            "building '{}' farms away from a surface",
+            definition.id
+        ));
+    }
+    if (effects.domes > 0.0 || effects.closed_loop > 0.0)
+        && definition.site != BuildingSite::Surface
+    {
+        return Err(format!(
+            "building '{}' provides life support away from a surface",
+            definition.id
+        ));
+    }
+    if effects.shielding > 0.0 && definition.site != BuildingSite::Surface {
+        return Err(format!(
+            "building '{}' shelters from radiation away from a surface",
+            definition.id
+        ));
+    }
+    if effects.slipways > 0
+        && !matches!(
+            definition.site,
+            BuildingSite::Surface | BuildingSite::Station
+        )
+    {
+        return Err(format!(
+            "building '{}' builds hulls away from a surface or station",
+            definition.id
+        ));
+    }
+    if effects.ordnance_production.len() > 1 {
+        return Err(format!(
+            "building '{}' produces more than one ordnance type",
+            definition.id
+        ));
+    }
+    if let Some(unknown) = effects
+        .ordnance_production
+        .keys()
+        .find(|id| crate::fleets::ordnance::ordnance(id).is_none())
+    {
+        return Err(format!(
+            "building '{}' produces unknown ordnance '{}'",
+            definition.id, unknown
+        ));
+    }
+
+    Ok(())
+}
+
+/// Base building data file
+pub const BUILDINGS_PATH: &str = "assets/data/buildings.ron";
+
+impl BuildingsData {
+    /// Levels requiring a technology that is not in `technologies`
+    pub fn unknown_references(&self, technologies: &TechnologiesData) -> Vec<String> {
+        self.iter()
+            .flat_map(|definition| {
+                definition
+                    .levels
+                    .iter()
+                    .filter_map(|level| level.required_tech.as_ref())
+                    .filter(|tech| technologies.get_tech(tech).is_none())
+                    .map(move |tech| {
+                        format!(
+                            "building '{}' requires unknown technology '{}'",
+                            definition.id, tech
+                        )
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Read the base buildings followed by those of enabled mods. Invalid
+/// definitions and files that can't be read or parsed are skipped and
+/// reported in the returned errors.
+pub fn read_buildings(mods: Option<&ModList>) -> (BuildingsData, Vec<String>) {
+    let mut buildings_data = BuildingsData::default();
+    let mut errors = Vec::new();
+
+    let mut paths = vec![PathBuf::from(BUILDINGS_PATH)];
+    paths.extend(mods.map_or(Vec::new(), |mods| mods.data_files("buildings.ron")));
+    for path in paths {
+        let data = match fs::read_to_string(&path) {
+            Ok(contents) => match ron::from_str::<BuildingsFile>(&contents) {
+                Ok(data) => data,
+                Err(e) => {
+                    errors.push(format!("Failed to parse {}: {}", path.display(), e));
+                    continue;
+                }
+            },
+            Err(e) => {
+                errors.push(format!("Failed to read {}: {}", path.display(), e));
+                continue;
+            }
+        };
+        for definition in data.buildings {
+            match validate_building(&definition) {
+                Ok(()) => buildings_data.insert(definition),
+                Err(e) => errors.push(format!("Skipping invalid building definition: {}", e)),
+            }
+        }
+    }
+
+    (buildings_data, errors)
+}
+
+/// System to load building definitions from data file at startup, followed
+/// by the buildings of enabled mods
+pub fn load_buildings(mut commands: Commands, mods: Option<Res<ModList>>) {
+    info!("Loading building definitions...");
+
+    let (buildings_data, errors) = read_buildings(mods.as_deref());
+    for e in errors {
+        error!("{}", e);
+    }
+    info!("Loaded {} building definitions", buildings_data.order.len());
+
+    // Invalid definitions and unreadable files were left out; the rest stay
+    // buildable
+    commands.insert_resource(buildings_data);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buildings_file_parses_and_validates() {
+        let contents = fs::read_to_string("assets/data/buildings.ron").unwrap();
+        let data: BuildingsFile = ron::from_str(&contents).unwrap();
+
+        assert!(!data.buildings.is_empty());
+        for definition in &data.buildings {
+            validate_building(definition).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_buildings_require_known_technologies() {
+        let (buildings, errors) = read_buildings(None);
+        assert!(errors.is_empty(), "{:?}", errors);
+        let (technologies, errors) = crate::research::read_technologies(None);
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        assert!(buildings.unknown_references(&technologies).is_empty());
+    }
+}
@@ -0,0 +1,86 @@
+//! Construction system for colony facilities
+//!
+//! Provides:
+//! - Data-driven building definitions with upgrade chains (Mine I → II → III)
+//! - Escalating per-level costs and cumulative per-level effects
+//! - A per-body construction queue that upgrades buildings in place
+//! - Buildings as entities carrying the economy components for their effects
+//! - Orbital stations and megastructures with their own modules, power grid
+//!   and population
+//! - Named blueprints of colony layouts, applied to new colonies as the
+//!   stockpile allows
+
+use bevy::prelude::*;
+
+pub mod blueprints;
+pub mod components;
+pub mod data;
+pub mod stations;
+pub mod systems;
+pub mod types;
+
+pub use blueprints::{
+    advance_blueprints, process_blueprint_actions, save_blueprints, Blueprint, BlueprintEntry,
+    BlueprintProgress, Blueprints, PendingBlueprintActions, BLUEPRINTS_PATH,
+};
+pub use components::{Building, ConstructionProject, ConstructionQueue};
+pub use data::{load_buildings, read_buildings, BuildingsData, BUILDINGS_PATH};
+pub use stations::{
+    base_orbital_slots, check_station_site, found_stations, lagrange_orbit, orbital_slots,
+    station_orbit, update_station_population, update_station_transforms, update_station_visibility,
+    HabitatCapacity, LagrangePoint, OrbitalStation, StationHost, StationKind, StationLocation,
+};
+pub use systems::{
+    advance_construction, apply_building_effects, body_site, built_level, can_afford,
+    check_queueable, process_construction_actions, queue_building, site_of,
+};
+pub use types::{
+    BuildingDefinition, BuildingEffect, BuildingEffects, BuildingId, BuildingLevel, BuildingSite,
+};
+
+/// Collects construction requests from the UI to be processed by a Bevy system.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct PendingConstructionActions {
+    /// (body, building) pairs to build or upgrade by one level
+    pub queue: Vec<(Entity, BuildingId)>,
+    /// (body, queue index) pairs of projects to cancel
+    pub cancel: Vec<(Entity, usize)>,
+    /// Stations and megastructures to found at a host body
+    pub found: Vec<(Entity, StationKind, StationLocation)>,
+}
+
+/// Plugin that adds the construction system to the Bevy app
+pub struct ConstructionPlugin;
+
+impl Plugin for ConstructionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PendingConstructionActions>()
+            .init_resource::<PendingBlueprintActions>()
+            .insert_resource(Blueprints::load(BLUEPRINTS_PATH))
+            .add_systems(Startup, load_buildings)
+            // Construction progresses with the economy tick
+            .add_systems(
+                FixedUpdate,
+                (
+                    advance_construction.after(crate::economy::advance_economy_clock),
+                    advance_blueprints.after(advance_construction),
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    (
+                        found_stations,
+                        process_blueprint_actions,
+                        process_construction_actions,
+                        apply_building_effects,
+                    )
+                        .chain(),
+                    save_blueprints.after(process_blueprint_actions),
+                    update_station_population,
+                    update_station_visibility,
+                    update_station_transforms.after(crate::astronomy::update_render_transform),
+                ),
+            );
+    }
+}
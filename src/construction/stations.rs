@@ -0,0 +1,783 @@
+//! Orbital stations and megastructures
+//!
+//! Stations are entities of their own, orbiting a host body (or sitting at one
+//! of its Lagrange points) with their own power grid, population and
+//! construction queue. Modules are building chains restricted to the station's
+//! [`BuildingSite`], so they reuse the regular construction queue and effects.
+//! Orbital rings and Dyson swarms are megastructures built the same way around
+//! planets and stars respectively.
+//!
+//! Every body offers a limited number of orbital slots, each holding one
+//! station; megastructures need none. Bigger bodies offer more slots, and
+//! technologies with the [`ModifierType::OrbitalSlots`] modifier add more
+//! around every body.
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_3, PI};
+
+use super::components::ConstructionQueue;
+use super::data::BuildingsData;
+use super::systems::queue_building;
+use super::types::BuildingSite;
+use super::PendingConstructionActions;
+use crate::astronomy::components::{
+    CurrentStarSystem, KeplerOrbit, LocalOrbitAmplification, OrbitCenter, SpaceCoordinates,
+    StellarLuminosity, SystemId,
+};
+use crate::astronomy::{AU_M, G, SCALING_FACTOR, SOLAR_MASS_KG};
+use crate::economy::components::{Population, PowerGridHost};
+use crate::plugins::camera::ViewMode;
+use crate::plugins::solar_system::{CelestialBody, LogicalParent};
+use crate::plugins::solar_system_data::BodyType;
+use crate::research::types::ModifierType;
+use crate::research::ResearchState;
+
+/// Solar luminosity in Watts
+pub const SOLAR_LUMINOSITY_W: f64 = 3.828e26;
+
+/// Station orbit radius as a multiple of the host's visual radius, so the
+/// station is drawn clear of the host mesh
+const STATION_ORBIT_MARGIN: f64 = 1.6;
+/// Orbital ring radius as a multiple of the host's visual radius
+const ORBITAL_RING_MARGIN: f32 = 1.3;
+
+/// Kind of orbital structure
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum StationKind {
+    /// Modular station orbiting a body or parked at a Lagrange point
+    #[default]
+    Station,
+    /// Ring encircling a planet
+    OrbitalRing,
+    /// Swarm of solar collectors around a star
+    DysonSwarm,
+}
+
+impl StationKind {
+    pub const ALL: [StationKind; 3] = [Self::Station, Self::OrbitalRing, Self::DysonSwarm];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Station => "Orbital Station",
+            Self::OrbitalRing => "Orbital Ring",
+            Self::DysonSwarm => "Dyson Swarm",
+        }
+    }
+
+    /// Building site of the modules that can be added to this structure
+    pub fn site(&self) -> BuildingSite {
+        match self {
+            Self::Station => BuildingSite::Station,
+            Self::OrbitalRing => BuildingSite::OrbitalRing,
+            Self::DysonSwarm => BuildingSite::DysonSwarm,
+        }
+    }
+
+    /// Building chain queued when the structure is founded
+    pub fn core_building(&self) -> &'static str {
+        match self {
+            Self::Station => "station_core",
+            Self::OrbitalRing => "orbital_ring_segment",
+            Self::DysonSwarm => "dyson_collectors",
+        }
+    }
+
+    /// Technology required before the structure can be founded
+    pub fn required_tech(&self) -> Option<&'static str> {
+        match self {
+            Self::Station => None,
+            Self::OrbitalRing => Some("orbital_rings"),
+            Self::DysonSwarm => Some("dyson_swarm"),
+        }
+    }
+
+    /// Whether a body of the given type can host this structure
+    pub fn can_host(&self, body_type: BodyType) -> bool {
+        match self {
+            Self::Station => !matches!(body_type, BodyType::Star | BodyType::Ring),
+            Self::OrbitalRing => matches!(body_type, BodyType::Planet | BodyType::GasGiant),
+            Self::DysonSwarm => body_type == BodyType::Star,
+        }
+    }
+
+    /// Megastructures are unique per host
+    pub fn is_megastructure(&self) -> bool {
+        *self != Self::Station
+    }
+
+    /// Whether the structure takes up one of its host's orbital slots
+    pub fn uses_orbital_slot(&self) -> bool {
+        !self.is_megastructure()
+    }
+}
+
+/// Orbital slots a body of `body_type` offers before any research
+pub fn base_orbital_slots(body_type: BodyType) -> u32 {
+    match body_type {
+        BodyType::Planet | BodyType::GasGiant => 3,
+        BodyType::DwarfPlanet | BodyType::Moon => 2,
+        BodyType::Asteroid | BodyType::Comet => 1,
+        BodyType::Star | BodyType::Ring => 0,
+    }
+}
+
+/// Orbital slots around a body of `body_type` with the technologies
+/// researched so far
+pub fn orbital_slots(body_type: BodyType, research_state: &ResearchState) -> u32 {
+    let base = base_orbital_slots(body_type);
+    if base == 0 {
+        return 0;
+    }
+    let researched = research_state.get_modifier(ModifierType::OrbitalSlots);
+    base + researched.max(0.0) as u32
+}
+
+/// Lagrange point of a host body and its primary
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LagrangePoint {
+    L1,
+    L2,
+    L3,
+    L4,
+    L5,
+}
+
+impl LagrangePoint {
+    pub const ALL: [LagrangePoint; 5] = [Self::L1, Self::L2, Self::L3, Self::L4, Self::L5];
+}
+
+impl std::fmt::Display for LagrangePoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Where a station is placed relative to its host
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum StationLocation {
+    /// In orbit around the host
+    #[default]
+    Orbit,
+    /// At a Lagrange point of the host's orbit around its primary
+    Lagrange(LagrangePoint),
+}
+
+impl std::fmt::Display for StationLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Orbit => write!(f, "Orbit"),
+            Self::Lagrange(point) => write!(f, "Lagrange {}", point),
+        }
+    }
+}
+
+/// An orbital station or megastructure.
+///
+/// The station is attached to its host through `LogicalParent`, but hosts its
+/// own power grid ([`PowerGridHost`]) and construction queue. Its modules are
+/// [`Building`](super::Building)s attached to the station entity.
+#[derive(Component, Debug, Clone)]
+pub struct OrbitalStation {
+    pub name: String,
+    pub kind: StationKind,
+    /// Body the station belongs to
+    pub host: Entity,
+    pub location: StationLocation,
+    /// Entity whose rendered position the station is drawn relative to, for
+    /// stations whose host is not drawn at its true coordinates (moons with
+    /// amplified orbits). `None` uses the regular coordinate rendering.
+    pub render_frame: Option<Entity>,
+}
+
+/// Living space provided by a station module
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct HabitatCapacity(pub f64);
+
+/// Snapshot of the host body needed to place a station
+#[derive(Debug, Clone)]
+pub struct StationHost {
+    pub body_type: BodyType,
+    /// Host mass in kg
+    pub mass: f64,
+    /// Mass of the body the host orbits in kg
+    pub primary_mass: f64,
+    pub visual_radius: f32,
+    /// Host luminosity in solar units (stars only)
+    pub luminosity: f64,
+    pub orbit: Option<KeplerOrbit>,
+}
+
+/// Orbit of a point co-moving with a host at one of its Lagrange points.
+///
+/// The points are derived from the host's own orbit: L1 and L2 sit inside
+/// and outside the host at the Hill radius, L3 opposite the host, and L4/L5
+/// lead and trail it by 60°. Exact for circular orbits.
+pub fn lagrange_orbit(
+    host_orbit: &KeplerOrbit,
+    mass_ratio: f64,
+    point: LagrangePoint,
+) -> KeplerOrbit {
+    let hill = (mass_ratio / 3.0).cbrt();
+    let mut orbit = *host_orbit;
+    match point {
+        LagrangePoint::L1 => orbit.semi_major_axis *= 1.0 - hill,
+        LagrangePoint::L2 => orbit.semi_major_axis *= 1.0 + hill,
+        LagrangePoint::L3 => {
+            orbit.semi_major_axis *= 1.0 + 5.0 * mass_ratio / 12.0;
+            orbit.mean_anomaly_epoch += PI;
+        }
+        LagrangePoint::L4 => orbit.mean_anomaly_epoch += FRAC_PI_3,
+        LagrangePoint::L5 => orbit.mean_anomaly_epoch -= FRAC_PI_3,
+    }
+    orbit
+}
+
+/// Orbit of a structure relative to its orbit center.
+///
+/// Stations in orbit circle the host just outside its visual radius.
+/// Megastructures are centered on their host.
+pub fn station_orbit(
+    kind: StationKind,
+    location: StationLocation,
+    host: &StationHost,
+) -> Result<KeplerOrbit, String> {
+    match (kind, location) {
+        (StationKind::Station, StationLocation::Orbit) => {
+            let radius_au = host.visual_radius as f64 * STATION_ORBIT_MARGIN / SCALING_FACTOR;
+            let radius_m = radius_au * AU_M;
+            let mean_motion = (G * host.mass / radius_m.powi(3)).sqrt();
+            Ok(KeplerOrbit::circular(radius_au, mean_motion))
+        }
+        (StationKind::Station, StationLocation::Lagrange(point)) => {
+            let orbit = host
+                .orbit
+                .ok_or_else(|| "Host has no orbit with Lagrange points".to_string())?;
+            let mass_ratio = host.mass / (host.primary_mass + host.mass);
+            Ok(lagrange_orbit(&orbit, mass_ratio, point))
+        }
+        (_, StationLocation::Orbit) => Ok(KeplerOrbit::circular(0.0, 0.0)),
+        (_, StationLocation::Lagrange(_)) => Err(format!(
+            "{} can only be built around its host",
+            kind.display_name()
+        )),
+    }
+}
+
+/// Check whether a structure may be founded at a host right now
+pub fn check_station_site<'a>(
+    kind: StationKind,
+    location: StationLocation,
+    host_entity: Entity,
+    host: &StationHost,
+    research_state: &ResearchState,
+    existing: impl IntoIterator<Item = &'a OrbitalStation>,
+) -> Result<(), String> {
+    if !kind.can_host(host.body_type) {
+        return Err(format!(
+            "{} cannot be built at a {:?}",
+            kind.display_name(),
+            host.body_type
+        ));
+    }
+    if let Some(tech) = kind.required_tech() {
+        if !research_state.is_unlocked(tech) {
+            return Err(format!("Requires technology '{}'", tech));
+        }
+    }
+    let hosted: Vec<_> = existing
+        .into_iter()
+        .filter(|s| s.host == host_entity)
+        .collect();
+    if kind.is_megastructure() && hosted.iter().any(|s| s.kind == kind) {
+        return Err(format!("Already has a {}", kind.display_name()));
+    }
+    if kind.uses_orbital_slot() {
+        let slots = orbital_slots(host.body_type, research_state);
+        if hosted.iter().filter(|s| s.kind.uses_orbital_slot()).count() >= slots as usize {
+            return Err(format!("All {} orbital slots are taken", slots));
+        }
+    }
+    station_orbit(kind, location, host).map(|_| ())
+}
+
+/// Mesh and orientation of a structure; megastructures are tori lying in the
+/// orbital (XY) plane
+fn station_mesh(kind: StationKind, host: &StationHost) -> (Mesh, Quat) {
+    let flat = Quat::from_rotation_x(FRAC_PI_2 as f32);
+    match kind {
+        StationKind::Station => {
+            let size = (host.visual_radius * 0.08).max(0.05);
+            (Cuboid::new(size, size * 0.4, size).into(), Quat::IDENTITY)
+        }
+        StationKind::OrbitalRing => {
+            let major = host.visual_radius * ORBITAL_RING_MARGIN;
+            (Torus::new(major * 0.97, major * 1.03).into(), flat)
+        }
+        StationKind::DysonSwarm => {
+            // Collectors sit where the stellar flux matches Earth's
+            let major = (host.luminosity.sqrt() * SCALING_FACTOR) as f32;
+            (Torus::new(major * 0.995, major * 1.005).into(), flat)
+        }
+    }
+}
+
+/// Host components read when founding a structure
+type StationHostQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static CelestialBody,
+        Option<&'static KeplerOrbit>,
+        Option<&'static OrbitCenter>,
+        Option<&'static LogicalParent>,
+        Option<&'static SystemId>,
+        Option<&'static StellarLuminosity>,
+        Option<&'static LocalOrbitAmplification>,
+    ),
+>;
+
+/// System to found the stations and megastructures requested from the UI.
+///
+/// A new structure is spawned empty with its core module queued in its own
+/// construction queue; it is paid for and completed like any other building.
+#[allow(clippy::too_many_arguments)]
+pub fn found_stations(
+    mut commands: Commands,
+    mut pending: ResMut<PendingConstructionActions>,
+    buildings_data: Option<Res<BuildingsData>>,
+    research_state: Res<ResearchState>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    hosts: StationHostQuery,
+    stations: Query<&OrbitalStation>,
+) {
+    let Some(buildings_data) = buildings_data else {
+        return;
+    };
+
+    // Structures founded this frame, not yet visible to the query
+    let mut founded: Vec<OrbitalStation> = Vec::new();
+
+    for (host_entity, kind, location) in pending.found.drain(..) {
+        let Ok((body, orbit, center, parent, system, luminosity, amplification)) =
+            hosts.get(host_entity)
+        else {
+            continue;
+        };
+        let primary = center.map(|c| c.0).or(parent.map(|p| p.0));
+        let host = StationHost {
+            body_type: body.body_type,
+            mass: body.mass,
+            primary_mass: primary
+                .and_then(|p| hosts.get(p).ok())
+                .map_or(SOLAR_MASS_KG, |(primary, ..)| primary.mass),
+            visual_radius: body.visual_radius,
+            luminosity: luminosity.map_or(1.0, |l| l.0),
+            orbit: orbit.copied(),
+        };
+
+        let existing = stations.iter().chain(founded.iter());
+        if let Err(e) = check_station_site(
+            kind,
+            location,
+            host_entity,
+            &host,
+            &research_state,
+            existing,
+        ) {
+            warn!(
+                "Cannot found {} at {}: {}",
+                kind.display_name(),
+                body.name,
+                e
+            );
+            continue;
+        }
+        let Ok(station_orbit) = station_orbit(kind, location, &host) else {
+            continue;
+        };
+        let Some(core) = buildings_data.get(kind.core_building()) else {
+            warn!("Missing core building '{}'", kind.core_building());
+            continue;
+        };
+        let mut queue = ConstructionQueue::default();
+        if let Err(e) = queue_building(&mut queue, core, 0, &research_state) {
+            warn!(
+                "Cannot found {} at {}: {}",
+                kind.display_name(),
+                body.name,
+                e
+            );
+            continue;
+        }
+
+        // Stations in orbit and megastructures move with the host; Lagrange
+        // points share the host's orbit center. Hosts without one either
+        // orbit the origin or, for amplified moons, are drawn relative to the
+        // body they orbit.
+        let (orbit_center, render_frame) = match location {
+            StationLocation::Orbit => (Some(host_entity), Some(host_entity)),
+            StationLocation::Lagrange(_) => match (center, amplification) {
+                (Some(c), _) => (Some(c.0), Some(c.0)),
+                (None, Some(_)) => (None, parent.map(|p| p.0)),
+                (None, None) => (None, None),
+            },
+        };
+
+        let count = stations
+            .iter()
+            .chain(founded.iter())
+            .filter(|s| s.host == host_entity && s.kind == kind)
+            .count();
+        let name = match kind {
+            StationKind::Station => format!("{} Station {}", body.name, count + 1),
+            _ => format!("{} {}", body.name, kind.display_name()),
+        };
+        let station = OrbitalStation {
+            name: name.clone(),
+            kind,
+            host: host_entity,
+            location,
+            render_frame,
+        };
+
+        let (mesh, rotation) = station_mesh(kind, &host);
+        let mut entity = commands.spawn((
+            station.clone(),
+            Name::new(name.clone()),
+            station_orbit,
+            SpaceCoordinates::new(DVec3::ZERO),
+            LogicalParent(host_entity),
+            SystemId(system.map_or(0, |s| s.0)),
+            Population::default(),
+            PowerGridHost,
+            queue,
+            PbrBundle {
+                mesh: meshes.add(mesh),
+                material: materials.add(StandardMaterial {
+                    base_color: Color::srgb(0.75, 0.78, 0.82),
+                    emissive: LinearRgba::rgb(0.15, 0.18, 0.25),
+                    metallic: 0.8,
+                    perceptual_roughness: 0.4,
+                    ..default()
+                }),
+                transform: Transform::from_rotation(rotation),
+                ..default()
+            },
+        ));
+        if let Some(center) = orbit_center {
+            entity.insert(OrbitCenter(center));
+        }
+        if let (StationLocation::Lagrange(_), Some(amp)) = (location, amplification) {
+            entity.insert(*amp);
+        }
+
+        info!("Founded {} ({})", name, location);
+        founded.push(station);
+    }
+}
+
+/// System that crews every station to the habitat capacity of its modules
+pub fn update_station_population(
+    mut stations: Query<(Entity, &mut Population), With<OrbitalStation>>,
+    habitats: Query<(&HabitatCapacity, &LogicalParent)>,
+) {
+    let mut capacity: HashMap<Entity, f64> = HashMap::new();
+    for (habitat, parent) in habitats.iter() {
+        *capacity.entry(parent.0).or_insert(0.0) += habitat.0;
+    }
+    for (entity, mut population) in stations.iter_mut() {
+        let count = capacity.get(&entity).copied().unwrap_or(0.0);
+        if population.count != count {
+            population.count = count;
+        }
+    }
+}
+
+/// System that draws stations next to their rendered host (or the body an
+/// amplified moon orbits), overriding the plain coordinate rendering
+#[allow(clippy::type_complexity)]
+pub fn update_station_transforms(
+    mut stations: Query<(
+        &OrbitalStation,
+        &SpaceCoordinates,
+        Option<&OrbitCenter>,
+        Option<&LocalOrbitAmplification>,
+        &mut Transform,
+    )>,
+    frames: Query<&Transform, Without<OrbitalStation>>,
+    coords: Query<&SpaceCoordinates>,
+) {
+    for (station, station_coords, center, amplification, mut transform) in stations.iter_mut() {
+        let Some(frame_transform) = station.render_frame.and_then(|f| frames.get(f).ok()) else {
+            continue;
+        };
+        let base = center
+            .and_then(|c| coords.get(c.0).ok())
+            .map_or(DVec3::ZERO, |c| c.position);
+        let amp = amplification.map_or(1.0, |a| a.0 as f64);
+        let offset = (station_coords.position - base) * SCALING_FACTOR * amp;
+        transform.translation = frame_transform.translation + offset.as_vec3();
+    }
+}
+
+/// System that shows stations only in the system view of their own system
+pub fn update_station_visibility(
+    view_mode: Res<ViewMode>,
+    current_system: Res<CurrentStarSystem>,
+    mut stations: Query<(&mut Visibility, &SystemId), With<OrbitalStation>>,
+) {
+    for (mut visibility, system_id) in stations.iter_mut() {
+        let visible = *view_mode == ViewMode::System && system_id.0 == current_system.0;
+        visibility.set_if_neq(if visible {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::astronomy::orbit_position_from_mean_anomaly;
+
+    fn earth() -> StationHost {
+        StationHost {
+            body_type: BodyType::Planet,
+            mass: 5.972e24,
+            primary_mass: SOLAR_MASS_KG,
+            visual_radius: 6.0,
+            luminosity: 1.0,
+            orbit: Some(KeplerOrbit::circular(1.0, 1.99e-7)),
+        }
+    }
+
+    #[test]
+    fn test_lagrange_points_relative_to_host() {
+        let host = earth();
+        let orbit = host.orbit.unwrap();
+        let position = |o: &KeplerOrbit| orbit_position_from_mean_anomaly(o, o.mean_anomaly_epoch);
+        let host_position = position(&orbit);
+        let mu = host.mass / (host.primary_mass + host.mass);
+
+        let l1 = lagrange_orbit(&orbit, mu, LagrangePoint::L1);
+        let l2 = lagrange_orbit(&orbit, mu, LagrangePoint::L2);
+        // Sun–Earth L1/L2 are about 0.01 AU from Earth
+        assert!((host_position.length() - position(&l1).length() - 0.01).abs() < 0.001);
+        assert!((position(&l2).length() - host_position.length() - 0.01).abs() < 0.001);
+        // Co-moving with the host
+        assert_eq!(l1.mean_motion, orbit.mean_motion);
+
+        let l4 = lagrange_orbit(&orbit, mu, LagrangePoint::L4);
+        let angle = host_position.angle_between(position(&l4));
+        assert!((angle - FRAC_PI_3).abs() < 1e-9);
+        let l3 = position(&lagrange_orbit(&orbit, mu, LagrangePoint::L3));
+        assert!((host_position.angle_between(l3) - PI).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_station_site_rules() {
+        let research = ResearchState::default();
+        let host = earth();
+        let entity = Entity::from_raw(1);
+        let none: [&OrbitalStation; 0] = [];
+
+        assert!(check_station_site(
+            StationKind::Station,
+            StationLocation::Lagrange(LagrangePoint::L2),
+            entity,
+            &host,
+            &research,
+            none
+        )
+        .is_ok());
+        // Megastructures are gated behind research and restricted to their hosts
+        assert!(check_station_site(
+            StationKind::OrbitalRing,
+            StationLocation::Orbit,
+            entity,
+            &host,
+            &research,
+            none
+        )
+        .is_err());
+        let mut researched = ResearchState::default();
+        researched.unlock_tech("orbital_rings".to_string());
+        researched.unlock_tech("dyson_swarm".to_string());
+        assert!(check_station_site(
+            StationKind::OrbitalRing,
+            StationLocation::Orbit,
+            entity,
+            &host,
+            &researched,
+            none
+        )
+        .is_ok());
+        assert!(check_station_site(
+            StationKind::DysonSwarm,
+            StationLocation::Orbit,
+            entity,
+            &host,
+            &researched,
+            none
+        )
+        .is_err());
+
+        // Only one ring per planet
+        let ring = OrbitalStation {
+            name: "Ring".to_string(),
+            kind: StationKind::OrbitalRing,
+            host: entity,
+            location: StationLocation::Orbit,
+            render_frame: Some(entity),
+        };
+        assert!(check_station_site(
+            StationKind::OrbitalRing,
+            StationLocation::Orbit,
+            entity,
+            &host,
+            &researched,
+            [&ring]
+        )
+        .is_err());
+
+        // Stars have no Lagrange points of their own
+        let sun = StationHost {
+            body_type: BodyType::Star,
+            orbit: None,
+            ..earth()
+        };
+        assert!(check_station_site(
+            StationKind::DysonSwarm,
+            StationLocation::Lagrange(LagrangePoint::L4),
+            entity,
+            &sun,
+            &researched,
+            none
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_orbital_slots_limit_stations() {
+        let mut research = ResearchState::default();
+        let host = earth();
+        let entity = Entity::from_raw(1);
+        let station = |n: u32| OrbitalStation {
+            name: format!("Station {}", n),
+            kind: StationKind::Station,
+            host: entity,
+            location: StationLocation::Orbit,
+            render_frame: Some(entity),
+        };
+        let full: Vec<_> = (0..base_orbital_slots(BodyType::Planet))
+            .map(station)
+            .collect();
+        let found = |research: &ResearchState| {
+            check_station_site(
+                StationKind::Station,
+                StationLocation::Orbit,
+                entity,
+                &host,
+                research,
+                full.iter(),
+            )
+        };
+        assert!(found(&research).is_err());
+
+        // Research opens more slots, and megastructures need none
+        research.add_modifier(ModifierType::OrbitalSlots, 1.0);
+        assert!(found(&research).is_ok());
+        research.unlock_tech("orbital_rings".to_string());
+        assert!(check_station_site(
+            StationKind::OrbitalRing,
+            StationLocation::Orbit,
+            entity,
+            &host,
+            &research,
+            full.iter().chain([&station(9)]),
+        )
+        .is_ok());
+        assert_eq!(orbital_slots(BodyType::Star, &research), 0);
+    }
+
+    #[test]
+    fn test_station_modules_feed_station_grid_and_crew() {
+        use crate::construction::{apply_building_effects, Building, BuildingDefinition};
+        use crate::construction::{BuildingEffect, BuildingLevel};
+        use crate::economy::components::{PowerGenerator, PowerSourceType};
+
+        let module =
+            |id: &str, site: BuildingSite, effects: Vec<BuildingEffect>| BuildingDefinition {
+                id: id.to_string(),
+                name: id.to_string(),
+                description: String::new(),
+                levels: vec![BuildingLevel {
+                    name: id.to_string(),
+                    cost: HashMap::new(),
+                    refined_cost: HashMap::new(),
+                    build_days: 1.0,
+                    effects,
+                    required_tech: None,
+                }],
+                site,
+            };
+        let mut data = BuildingsData::default();
+        data.insert(module(
+            "habitat",
+            BuildingSite::Station,
+            vec![BuildingEffect::Habitat(500.0)],
+        ));
+        data.insert(module(
+            "collectors",
+            BuildingSite::DysonSwarm,
+            vec![BuildingEffect::StellarCollection(1e-9)],
+        ));
+
+        let mut app = App::new();
+        app.insert_resource(data).add_systems(
+            Update,
+            (apply_building_effects, update_station_population).chain(),
+        );
+
+        let star = app.world_mut().spawn(StellarLuminosity(2.0)).id();
+        let mut spawn_station = |kind: StationKind| {
+            app.world_mut()
+                .spawn((
+                    OrbitalStation {
+                        name: kind.display_name().to_string(),
+                        kind,
+                        host: star,
+                        location: StationLocation::Orbit,
+                        render_frame: None,
+                    },
+                    Population::default(),
+                ))
+                .id()
+        };
+        let station = spawn_station(StationKind::Station);
+        let swarm = spawn_station(StationKind::DysonSwarm);
+        let building = |id: &str| Building {
+            id: id.to_string(),
+            level: 1,
+        };
+        app.world_mut()
+            .spawn((building("habitat"), LogicalParent(station)));
+        let collectors = app
+            .world_mut()
+            .spawn((building("collectors"), LogicalParent(swarm)))
+            .id();
+
+        app.update();
+        app.update();
+
+        assert_eq!(app.world().get::<Population>(station).unwrap().count, 500.0);
+        let generator = app.world().get::<PowerGenerator>(collectors).unwrap();
+        assert_eq!(generator.source_type, PowerSourceType::Station);
+        assert!((generator.output - 2e-9 * SOLAR_LUMINOSITY_W).abs() < 1.0);
+    }
+}
@@ -0,0 +1,684 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use super::components::{Building, ConstructionProject, ConstructionQueue};
+use super::data::BuildingsData;
+use super::stations::{HabitatCapacity, OrbitalStation, SOLAR_LUMINOSITY_W};
+use super::types::{BuildingDefinition, BuildingId, BuildingLevel, BuildingSite};
+use super::PendingConstructionActions;
+use crate::astronomy::components::{AtmosphereComposition, StellarLuminosity};
+use crate::contact::ListeningPost;
+use crate::economy::budget::GlobalBudget;
+use crate::economy::food::Farm;
+use crate::economy::components::{
+    HeatSource, PowerConsumer, PowerGenerator, PowerRelay, PowerSourceType, PowerStorage, Radiator,
+};
+use crate::economy::harvesting::GasHarvester;
+use crate::economy::life_support::LifeSupportModule;
+use crate::economy::mining::MiningOperation;
+use crate::economy::production::Refinery;
+use crate::economy::radiation::RadiationShelter;
+use crate::economy::reservations::Reservations;
+use crate::economy::storage::PowerTier;
+use crate::economy::tick::EconomyClock;
+use crate::economy::treasury::Treasury;
+use crate::fleets::ordnance::{MagazineStorage, OrdnanceFactory};
+use crate::fleets::shipyards::ShipyardFacility;
+use crate::plugins::solar_system::{CelestialBody, LogicalParent};
+use crate::plugins::solar_system_data::BodyType;
+use crate::research::{EngineeringFacility, ResearchBuilding, ResearchState};
+
+/// Check whether `level` of a building chain may be queued right now
+pub fn check_queueable<'a>(
+    definition: &'a BuildingDefinition,
+    level: u32,
+    research_state: &ResearchState,
+) -> Result<&'a BuildingLevel, String> {
+    let Some(building_level) = definition.level(level) else {
+        return Err(format!(
+            "{} is already at its highest level",
+            definition.name
+        ));
+    };
+    if let Some(tech) = &building_level.required_tech {
+        if !research_state.is_unlocked(tech) {
+            return Err(format!("Requires technology '{}'", tech));
+        }
+    }
+    Ok(building_level)
+}
+
+/// Queue the next level of a building chain. Upgrades of a building that is
+/// already standing (or already queued) target the following level, so the
+/// chain is upgraded in place rather than duplicated.
+///
+/// Returns the level that was queued.
+pub fn queue_building(
+    queue: &mut ConstructionQueue,
+    definition: &BuildingDefinition,
+    built_level: u32,
+    research_state: &ResearchState,
+) -> Result<u32, String> {
+    let level = queue.next_level(&definition.id, built_level);
+    let building_level = check_queueable(definition, level, research_state)?;
+    queue.projects.push_back(ConstructionProject::new(
+        definition.id.clone(),
+        level,
+        building_level.build_days,
+    ));
+    Ok(level)
+}
+
+/// Level of a building chain standing on `body` (0 if not built)
+pub fn built_level<'a>(
+    buildings: impl IntoIterator<Item = (&'a Building, &'a LogicalParent)>,
+    body: Entity,
+    building_id: &str,
+) -> u32 {
+    buildings
+        .into_iter()
+        .find(|(building, parent)| parent.0 == body && building.id == building_id)
+        .map_or(0, |(building, _)| building.level)
+}
+
+/// Site offered by a body: its ring system, the atmosphere of a gas giant,
+/// otherwise its surface
+pub fn body_site(body_type: BodyType, atmosphere: Option<&AtmosphereComposition>) -> BuildingSite {
+    if body_type == BodyType::Ring {
+        BuildingSite::RingSystem
+    } else if atmosphere.is_some_and(|a| a.is_reference_pressure) {
+        BuildingSite::Atmosphere
+    } else {
+        BuildingSite::Surface
+    }
+}
+
+/// Site a building placed at `entity` would stand on: the module site of a
+/// station, otherwise the site the body offers (see [`body_site`])
+pub fn site_of(
+    entity: Entity,
+    stations: &Query<&OrbitalStation>,
+    bodies: &Query<(&CelestialBody, Option<&AtmosphereComposition>)>,
+) -> BuildingSite {
+    if let Ok(station) = stations.get(entity) {
+        return station.kind.site();
+    }
+    bodies
+        .get(entity)
+        .map_or(BuildingSite::Surface, |(body, atmosphere)| {
+            body_site(body.body_type, atmosphere)
+        })
+}
+
+/// System to process construction requests queued from the UI
+#[allow(clippy::too_many_arguments)]
+pub fn process_construction_actions(
+    mut commands: Commands,
+    mut pending: ResMut<PendingConstructionActions>,
+    buildings_data: Option<Res<BuildingsData>>,
+    research_state: Res<ResearchState>,
+    mut queues: Query<&mut ConstructionQueue>,
+    buildings: Query<(&Building, &LogicalParent)>,
+    stations: Query<&OrbitalStation>,
+    bodies: Query<(&CelestialBody, Option<&AtmosphereComposition>)>,
+) {
+    let Some(buildings_data) = buildings_data else {
+        return;
+    };
+
+    // Queues created this frame, inserted once all requests are handled
+    let mut new_queues: HashMap<Entity, ConstructionQueue> = HashMap::new();
+
+    for (body, building_id) in pending.queue.drain(..) {
+        let Some(definition) = buildings_data.get(&building_id) else {
+            warn!("Cannot queue unknown building '{}'", building_id);
+            continue;
+        };
+        if definition.site != site_of(body, &stations, &bodies) {
+            warn!("Cannot queue {} at this site", definition.name);
+            continue;
+        }
+        let level = built_level(buildings.iter(), body, &building_id);
+
+        let result = if let Ok(mut queue) = queues.get_mut(body) {
+            queue_building(&mut queue, definition, level, &research_state)
+        } else {
+            let queue = new_queues.entry(body).or_default();
+            queue_building(queue, definition, level, &research_state)
+        };
+
+        match result {
+            Ok(level) => info!("Queued {} level {}", definition.name, level),
+            Err(e) => warn!("Cannot queue {}: {}", definition.name, e),
+        }
+    }
+
+    for (body, index) in pending.cancel.drain(..) {
+        if let Ok(mut queue) = queues.get_mut(body) {
+            queue.cancel(index);
+        }
+    }
+
+    for (body, queue) in new_queues {
+        commands.entity(body).insert(queue);
+    }
+}
+
+/// Whether the stockpile holds the full cost of `level`, raw resources and
+/// refined goods alike: all of the stock if the cost is `reserved` for the
+/// project, otherwise what other projects have not earmarked
+pub fn can_afford(
+    budget: &GlobalBudget,
+    reservations: &Reservations,
+    level: &BuildingLevel,
+    reserved: bool,
+) -> bool {
+    reservations.covers(
+        budget,
+        reserved,
+        level
+            .cost
+            .iter()
+            .map(|(resource, amount)| (*resource, *amount)),
+        level
+            .refined_cost
+            .iter()
+            .map(|(good, amount)| (*good, *amount)),
+    )
+}
+
+/// Pay for a level completed at `site` out of the stockpile, all or nothing
+fn pay_building_cost(
+    budget: &mut GlobalBudget,
+    reservations: &Reservations,
+    site: Entity,
+    level: &BuildingLevel,
+    reserved: bool,
+) -> bool {
+    let affordable = can_afford(budget, reservations, level, reserved);
+    if affordable {
+        for (resource, amount) in &level.cost {
+            budget.consume_resource_at(site, *resource, *amount);
+        }
+        for (good, amount) in &level.refined_cost {
+            budget.consume_refined_at(site, *good, *amount);
+        }
+    }
+    affordable
+}
+
+/// System that advances the front project of every construction queue by one
+/// day per economy tick, scaled by the treasury's construction funding.
+///
+/// Costs are paid when the work is done, out of the project's earmark or the
+/// stock free of reservations; a project that cannot be paid for stalls at
+/// the front of its queue until the stockpile recovers. A finished
+/// project raises the level of the existing building in place, or spawns the
+/// building if this was its first level.
+#[allow(clippy::too_many_arguments)]
+pub fn advance_construction(
+    mut commands: Commands,
+    clock: Res<EconomyClock>,
+    mut budget: ResMut<GlobalBudget>,
+    reservations: Res<Reservations>,
+    treasury: Option<Res<Treasury>>,
+    buildings_data: Option<Res<BuildingsData>>,
+    mut queues: Query<(Entity, &mut ConstructionQueue)>,
+    mut buildings: Query<(Entity, &mut Building, &LogicalParent)>,
+) {
+    let Some(buildings_data) = buildings_data else {
+        return;
+    };
+    if clock.due == 0 {
+        return;
+    }
+
+    let days_per_tick = treasury.map_or(1.0, |t| t.construction_factor());
+
+    // Buildings spawned during this update, not yet visible to the query
+    let mut spawned: HashMap<(Entity, BuildingId), Entity> = HashMap::new();
+
+    for (body, mut queue) in queues.iter_mut() {
+        for _ in 0..clock.due {
+            let Some(project) = queue.projects.front_mut() else {
+                break;
+            };
+            if !project.is_complete() {
+                project.progress_days += days_per_tick;
+            }
+            if !project.is_complete() {
+                continue;
+            }
+
+            let Some(level) = buildings_data
+                .get(&project.building_id)
+                .and_then(|def| def.level(project.target_level))
+            else {
+                warn!(
+                    "Dropping project for unknown building '{}'",
+                    project.building_id
+                );
+                queue.projects.pop_front();
+                continue;
+            };
+            project.stalled =
+                !pay_building_cost(&mut budget, &reservations, body, level, project.reserved);
+            if project.stalled {
+                continue;
+            }
+
+            let building = Building {
+                id: project.building_id.clone(),
+                level: project.target_level,
+            };
+            if let Some((_, mut existing, _)) = buildings
+                .iter_mut()
+                .find(|(_, b, parent)| parent.0 == body && b.id == building.id)
+            {
+                existing.level = building.level;
+            } else if let Some(entity) = spawned.get(&(body, building.id.clone())) {
+                commands.entity(*entity).insert(building);
+            } else {
+                let key = (body, building.id.clone());
+                let entity = commands
+                    .spawn((building, LogicalParent(body), Name::new(level.name.clone())))
+                    .id();
+                spawned.insert(key, entity);
+            }
+            info!("Construction complete: {}", level.name);
+            queue.projects.pop_front();
+        }
+    }
+}
+
+/// System that keeps a building's economy components in line with the
+/// cumulative effects of its current level
+pub fn apply_building_effects(
+    mut commands: Commands,
+    buildings_data: Option<Res<BuildingsData>>,
+    buildings: Query<(Entity, &Building, Option<&LogicalParent>), Changed<Building>>,
+    stations: Query<&OrbitalStation>,
+    luminosities: Query<&StellarLuminosity>,
+    storages: Query<&PowerStorage>,
+) {
+    let Some(buildings_data) = buildings_data else {
+        return;
+    };
+
+    for (entity, building, parent) in buildings.iter() {
+        let Some(definition) = buildings_data.get(&building.id) else {
+            continue;
+        };
+        let effects = definition.cumulative_effects(building.level);
+        let mut entity_commands = commands.entity(entity);
+
+        if let Some(name) = definition.level(building.level).map(|l| l.name.clone()) {
+            entity_commands.insert(Name::new(name));
+        }
+
+        // Dyson collectors draw on the star their swarm surrounds
+        let stellar_output = if effects.stellar_collection > 0.0 {
+            let luminosity = parent
+                .and_then(|p| stations.get(p.0).ok())
+                .and_then(|station| luminosities.get(station.host).ok())
+                .map_or(1.0, |l| l.0);
+            effects.stellar_collection * luminosity * SOLAR_LUMINOSITY_W
+        } else {
+            0.0
+        };
+        let power_output = effects.power_output + stellar_output;
+
+        if power_output > 0.0 {
+            entity_commands.insert(PowerGenerator {
+                output: power_output,
+                source_type: if definition.site == BuildingSite::Surface {
+                    PowerSourceType::Planet
+                } else {
+                    PowerSourceType::Station
+                },
+            });
+        } else {
+            entity_commands.remove::<PowerGenerator>();
+        }
+
+        if effects.power_demand > 0.0 {
+            entity_commands.insert(PowerConsumer {
+                demand: effects.power_demand,
+            });
+        } else {
+            entity_commands.remove::<PowerConsumer>();
+        }
+
+        // Brownouts shed consumers by what they power
+        let tier = if !effects.mining.is_empty() || !effects.gas_harvest.is_empty() {
+            Some(PowerTier::Mining)
+        } else if !effects.refining.is_empty() || !effects.ordnance_production.is_empty() {
+            Some(PowerTier::Industry)
+        } else if effects.research_points > 0.0 || effects.engineering_points > 0.0 {
+            Some(PowerTier::Research)
+        } else {
+            None
+        };
+        match tier {
+            Some(tier) if effects.power_demand > 0.0 => {
+                entity_commands.insert(tier);
+            }
+            _ => {
+                entity_commands.remove::<PowerTier>();
+            }
+        }
+
+        if effects.storage_capacity > 0.0 {
+            // An upgrade keeps the charge already stored
+            let charge = storages.get(entity).map_or(0.0, |s| s.charge);
+            entity_commands.insert(PowerStorage {
+                capacity: effects.storage_capacity,
+                charge: charge.min(effects.storage_capacity),
+            });
+        } else {
+            entity_commands.remove::<PowerStorage>();
+        }
+
+        if let Some((resource, rate)) = effects.mining.iter().next() {
+            entity_commands.insert(MiningOperation {
+                resource_type: *resource,
+                base_rate_mt_per_year: *rate,
+                active: true,
+            });
+        } else {
+            entity_commands.remove::<MiningOperation>();
+        }
+
+        if let Some((resource, rate)) = effects.gas_harvest.iter().next() {
+            entity_commands.insert(GasHarvester {
+                resource_type: *resource,
+                base_rate_mt_per_year: *rate,
+            });
+        } else {
+            entity_commands.remove::<GasHarvester>();
+        }
+
+        if effects.research_points > 0.0 || effects.research_slots > 0 {
+            entity_commands.insert(ResearchBuilding {
+                points_per_second: effects.research_points,
+                slots: effects.research_slots,
+            });
+        } else {
+            entity_commands.remove::<ResearchBuilding>();
+        }
+
+        if effects.engineering_points > 0.0 || effects.engineering_slots > 0 {
+            entity_commands.insert(EngineeringFacility {
+                points_per_second: effects.engineering_points,
+                slots: effects.engineering_slots,
+            });
+        } else {
+            entity_commands.remove::<EngineeringFacility>();
+        }
+
+        if effects.habitat > 0.0 {
+            entity_commands.insert(HabitatCapacity(effects.habitat));
+        } else {
+            entity_commands.remove::<HabitatCapacity>();
+        }
+
+        if effects.relay_capacity > 0.0 {
+            entity_commands.insert(PowerRelay {
+                capacity: effects.relay_capacity,
+            });
+        } else {
+            entity_commands.remove::<PowerRelay>();
+        }
+
+        if effects.waste_heat > 0.0 {
+            entity_commands.insert(HeatSource {
+                output: effects.waste_heat,
+            });
+        } else {
+            entity_commands.remove::<HeatSource>();
+        }
+
+        if effects.radiator_capacity > 0.0 {
+            entity_commands.insert(Radiator {
+                capacity: effects.radiator_capacity,
+            });
+        } else {
+            entity_commands.remove::<Radiator>();
+        }
+
+        if let Some((ordnance, rate)) = effects.ordnance_production.iter().next() {
+            entity_commands.insert(OrdnanceFactory {
+                ordnance: ordnance.clone(),
+                rounds_per_year: *rate,
+                progress: 0.0,
+            });
+        } else {
+            entity_commands.remove::<OrdnanceFactory>();
+        }
+
+        if let Some((good, rate)) = effects.refining.iter().next() {
+            entity_commands.insert(Refinery {
+                good: *good,
+                mt_per_year: *rate,
+            });
+        } else {
+            entity_commands.remove::<Refinery>();
+        }
+
+        if effects.hydroponics > 0.0 {
+            entity_commands.insert(Farm {
+                mt_per_year: effects.hydroponics,
+                hydroponic: true,
+            });
+        } else if effects.farming > 0.0 {
+            entity_commands.insert(Farm {
+                mt_per_year: effects.farming,
+                hydroponic: false,
+            });
+        } else {
+            entity_commands.remove::<Farm>();
+        }
+
+        if effects.domes > 0.0 || effects.closed_loop > 0.0 {
+            entity_commands.insert(LifeSupportModule {
+                domes: effects.domes,
+                closed_loop: effects.closed_loop,
+            });
+        } else {
+            entity_commands.remove::<LifeSupportModule>();
+        }
+
+        if effects.shielding > 0.0 {
+            entity_commands.insert(RadiationShelter {
+                capacity: effects.shielding,
+            });
+        } else {
+            entity_commands.remove::<RadiationShelter>();
+        }
+
+        if effects.magazine_capacity > 0 {
+            entity_commands.insert(MagazineStorage {
+                capacity: effects.magazine_capacity,
+            });
+        } else {
+            entity_commands.remove::<MagazineStorage>();
+        }
+
+        if effects.slipways > 0 {
+            entity_commands.insert(ShipyardFacility {
+                tonnage_t: effects.shipyard_tonnage_t,
+                slipways: effects.slipways,
+            });
+        } else {
+            entity_commands.remove::<ShipyardFacility>();
+        }
+
+        if effects.listening > 0.0 {
+            entity_commands.insert(ListeningPost {
+                sensitivity: effects.listening,
+            });
+        } else {
+            entity_commands.remove::<ListeningPost>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::construction::types::BuildingEffect;
+    use crate::economy::production::RefinedGood;
+    use crate::economy::tick::{advance_economy_clock, ECONOMY_TICK_SECONDS};
+    use crate::economy::types::ResourceType;
+    use crate::ui::SimulationTime;
+
+    fn mine() -> BuildingDefinition {
+        let level = |name: &str, rate: f64, iron: f64, tech: Option<&str>| BuildingLevel {
+            name: name.to_string(),
+            cost: HashMap::from([(ResourceType::Iron, iron)]),
+            refined_cost: HashMap::new(),
+            build_days: 10.0,
+            effects: vec![BuildingEffect::Mining {
+                resource: ResourceType::Iron,
+                mt_per_year: rate,
+            }],
+            required_tech: tech.map(str::to_string),
+        };
+        BuildingDefinition {
+            id: "iron_mine".to_string(),
+            name: "Iron Mine".to_string(),
+            description: String::new(),
+            levels: vec![
+                level("Iron Mine I", 1.0, 5.0, None),
+                level("Iron Mine II", 2.0, 10.0, None),
+                level("Iron Mine III", 4.0, 20.0, Some("deep_core_mining")),
+            ],
+            site: BuildingSite::Surface,
+        }
+    }
+
+    #[test]
+    fn test_queueing_targets_next_level() {
+        let def = mine();
+        let research = ResearchState::default();
+        let mut queue = ConstructionQueue::default();
+
+        assert_eq!(queue_building(&mut queue, &def, 0, &research), Ok(1));
+        assert_eq!(queue_building(&mut queue, &def, 0, &research), Ok(2));
+        // Level III is gated behind research
+        assert!(queue_building(&mut queue, &def, 0, &research).is_err());
+
+        let mut researched = ResearchState::default();
+        researched.unlock_tech("deep_core_mining".to_string());
+        assert_eq!(queue_building(&mut queue, &def, 0, &researched), Ok(3));
+        assert!(queue_building(&mut queue, &def, 0, &researched).is_err());
+
+        // Cancelling level II also drops the level III upgrade built on it
+        queue.cancel(1);
+        assert_eq!(queue.projects.len(), 1);
+        assert_eq!(queue.next_level("iron_mine", 0), 2);
+    }
+
+    #[test]
+    fn test_upgrade_in_place_through_queue() {
+        let mut app = App::new();
+        let mut data = BuildingsData::default();
+        data.insert(mine());
+        app.insert_resource(data)
+            .init_resource::<SimulationTime>()
+            .init_resource::<EconomyClock>()
+            .init_resource::<GlobalBudget>()
+            .init_resource::<Reservations>()
+            .add_systems(
+                Update,
+                (
+                    advance_economy_clock,
+                    advance_construction,
+                    apply_building_effects,
+                )
+                    .chain(),
+            );
+
+        let body = app.world_mut().spawn_empty().id();
+        let research = ResearchState::default();
+        let mut queue = ConstructionQueue::default();
+        queue_building(&mut queue, &mine(), 0, &research).unwrap();
+        queue_building(&mut queue, &mine(), 0, &research).unwrap();
+        app.world_mut().entity_mut(body).insert(queue);
+
+        // Both projects finish within a single update
+        app.world_mut().resource_mut::<SimulationTime>().elapsed = 25.0 * ECONOMY_TICK_SECONDS;
+        app.update();
+        app.update();
+
+        let mut query = app.world_mut().query::<(&Building, &MiningOperation)>();
+        let built: Vec<_> = query.iter(app.world()).collect();
+        assert_eq!(built.len(), 1);
+        assert_eq!(built[0].0.level, 2);
+        assert_eq!(built[0].1.base_rate_mt_per_year, 3.0);
+        assert!(app
+            .world()
+            .get::<ConstructionQueue>(body)
+            .unwrap()
+            .projects
+            .is_empty());
+        // 50 Iron at start, minus 5 + 10
+        assert_eq!(
+            app.world()
+                .resource::<GlobalBudget>()
+                .get_stockpile(&ResourceType::Iron),
+            35.0
+        );
+    }
+
+    #[test]
+    fn test_unaffordable_project_stalls() {
+        let mut budget = GlobalBudget::new();
+        budget.stockpiles.insert(ResourceType::Iron, 4.0);
+        let def = mine();
+        let none = Reservations::default();
+
+        assert!(!pay_building_cost(
+            &mut budget,
+            &none,
+            Entity::PLACEHOLDER,
+            def.level(1).unwrap(),
+            false
+        ));
+        assert_eq!(budget.get_stockpile(&ResourceType::Iron), 4.0);
+
+        budget.add_resource(ResourceType::Iron, 1.0);
+        assert!(pay_building_cost(
+            &mut budget,
+            &none,
+            Entity::PLACEHOLDER,
+            def.level(1).unwrap(),
+            false
+        ));
+        assert_eq!(budget.get_stockpile(&ResourceType::Iron), 0.0);
+
+        // Refined goods are paid alongside raw resources
+        let mut level = def.level(1).unwrap().clone();
+        level.refined_cost.insert(RefinedGood::Steel, 3.0);
+        budget.add_resource(ResourceType::Iron, 5.0);
+        budget.refined.insert(RefinedGood::Steel, 2.0);
+        assert!(!pay_building_cost(
+            &mut budget,
+            &none,
+            Entity::PLACEHOLDER,
+            &level,
+            false
+        ));
+        assert_eq!(budget.get_stockpile(&ResourceType::Iron), 5.0);
+
+        budget.add_refined(RefinedGood::Steel, 1.0);
+        assert!(pay_building_cost(
+            &mut budget,
+            &none,
+            Entity::PLACEHOLDER,
+            &level,
+            false
+        ));
+        assert_eq!(budget.get_refined(&RefinedGood::Steel), 0.0);
+    }
+}
@@ -0,0 +1,293 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::economy::production::RefinedGood;
+use crate::economy::types::ResourceType;
+use crate::research::TechnologyId;
+
+/// Unique identifier for a building chain
+pub type BuildingId = String;
+
+/// Effect a building level contributes while it stands
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BuildingEffect {
+    /// Power generated in Watts
+    PowerOutput(f64),
+    /// Power drawn in Watts
+    PowerDemand(f64),
+    /// Extraction from the host body's deposits in Megatons per year
+    Mining {
+        resource: ResourceType,
+        mt_per_year: f64,
+    },
+    /// Research points generated per second
+    ResearchPoints(f64),
+    /// Engineering points generated per second
+    EngineeringPoints(f64),
+    /// Concurrent research projects the laboratory has room for
+    ResearchSlots(u32),
+    /// Concurrent engineering projects the workshop has room for
+    EngineeringSlots(u32),
+    /// Living space for this many people (stations and megastructures)
+    Habitat(f64),
+    /// Power relay capacity in Watts for sharing power within the system
+    Relay(f64),
+    /// Fraction of the host star's luminosity collected as power (Dyson swarms)
+    StellarCollection(f64),
+    /// Gas scooped from the host giant's atmosphere in Megatons per year at
+    /// the 1 bar level; deeper harvesting multiplies the yield
+    GasHarvest {
+        resource: ResourceType,
+        mt_per_year: f64,
+    },
+    /// Waste heat released in Watts on top of the power drawn (reactors)
+    WasteHeat(f64),
+    /// Heat rejection capacity in Watts
+    Radiator(f64),
+    /// Rounds of an ordnance type manufactured per year into the colony's
+    /// magazine
+    OrdnanceProduction { ordnance: String, rounds_per_year: f64 },
+    /// Rounds of ordnance the colony's magazine can hold
+    Magazine(u32),
+    /// Refined good produced in Megatons per year from raw resources in the
+    /// stockpile (refineries and factories)
+    Refining { good: RefinedGood, mt_per_year: f64 },
+    /// Food grown in Megatons per year by open or domed fields; the yield
+    /// falls with the host body's colony cost
+    Farming(f64),
+    /// Food grown in Megatons per year under artificial light, independent
+    /// of the host body
+    Hydroponics(f64),
+    /// Pressure domes housing this many people on a world with a marginal
+    /// atmosphere
+    Domes(f64),
+    /// Closed-loop life support for this many people, on any world
+    ClosedLoop(f64),
+    /// Radiation shelters housing this many people
+    Shielding(f64),
+    /// Slipways building hulls of up to `tonnage_t` tonnes (shipyards)
+    Shipyard { tonnage_t: f64, slipways: u32 },
+    /// Energy storage in Joules (batteries, capacitors)
+    Storage(f64),
+    /// Sensitivity to alien signals in listening points per day (radio
+    /// telescopes and SETI arrays)
+    Listening(f64),
+}
+
+/// Where a building can be constructed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BuildingSite {
+    /// On the surface of a planet, moon or asteroid
+    #[default]
+    Surface,
+    /// As a module of an orbital station
+    Station,
+    /// As a segment of an orbital ring around a planet
+    OrbitalRing,
+    /// As collectors of a Dyson swarm around a star
+    DysonSwarm,
+    /// Within a planetary ring system
+    RingSystem,
+    /// Floating in or skimming the atmosphere of a gas giant
+    Atmosphere,
+}
+
+/// One level of a building chain (e.g. "Mine II")
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildingLevel {
+    /// Display name of this level
+    pub name: String,
+    /// Resources consumed when construction completes
+    #[serde(default)]
+    pub cost: HashMap<ResourceType, f64>,
+    /// Refined goods consumed when construction completes
+    #[serde(default)]
+    pub refined_cost: HashMap<RefinedGood, f64>,
+    /// Construction time in in-game days
+    pub build_days: f64,
+    /// Effects added on top of the previous levels
+    pub effects: Vec<BuildingEffect>,
+    /// Technology required before this level can be queued
+    #[serde(default)]
+    pub required_tech: Option<TechnologyId>,
+}
+
+/// A building with its upgrade chain, loaded from data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildingDefinition {
+    /// Unique identifier
+    pub id: BuildingId,
+    /// Display name of the chain
+    pub name: String,
+    /// Description
+    pub description: String,
+    /// Levels in upgrade order; level 1 is the initial construction
+    pub levels: Vec<BuildingLevel>,
+    /// Where the building can be constructed
+    #[serde(default)]
+    pub site: BuildingSite,
+}
+
+impl BuildingDefinition {
+    /// Highest level this building can be upgraded to
+    pub fn max_level(&self) -> u32 {
+        self.levels.len() as u32
+    }
+
+    /// Look up a level by its 1-based number
+    pub fn level(&self, level: u32) -> Option<&BuildingLevel> {
+        level
+            .checked_sub(1)
+            .and_then(|index| self.levels.get(index as usize))
+    }
+
+    /// Combined effects of every level up to and including `level`
+    pub fn cumulative_effects(&self, level: u32) -> BuildingEffects {
+        let mut effects = BuildingEffects::default();
+        for building_level in self.levels.iter().take(level as usize) {
+            for effect in &building_level.effects {
+                effects.add(effect);
+            }
+        }
+        effects
+    }
+}
+
+/// Aggregated effects of a building at its current level
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BuildingEffects {
+    pub power_output: f64,
+    pub power_demand: f64,
+    pub mining: HashMap<ResourceType, f64>,
+    pub research_points: f64,
+    pub engineering_points: f64,
+    pub research_slots: u32,
+    pub engineering_slots: u32,
+    pub habitat: f64,
+    pub relay_capacity: f64,
+    pub stellar_collection: f64,
+    pub gas_harvest: HashMap<ResourceType, f64>,
+    pub waste_heat: f64,
+    pub radiator_capacity: f64,
+    pub ordnance_production: HashMap<String, f64>,
+    pub magazine_capacity: u32,
+    pub refining: HashMap<RefinedGood, f64>,
+    pub farming: f64,
+    pub hydroponics: f64,
+    pub domes: f64,
+    pub closed_loop: f64,
+    pub shielding: f64,
+    pub shipyard_tonnage_t: f64,
+    pub slipways: u32,
+    pub storage_capacity: f64,
+    pub listening: f64,
+}
+
+impl BuildingEffects {
+    /// Accumulate a single effect
+    pub fn add(&mut self, effect: &BuildingEffect) {
+        match effect {
+            BuildingEffect::PowerOutput(watts) => self.power_output += watts,
+            BuildingEffect::PowerDemand(watts) => self.power_demand += watts,
+            BuildingEffect::Mining {
+                resource,
+                mt_per_year,
+            } => *self.mining.entry(*resource).or_insert(0.0) += mt_per_year,
+            BuildingEffect::ResearchPoints(points) => self.research_points += points,
+            BuildingEffect::EngineeringPoints(points) => self.engineering_points += points,
+            BuildingEffect::ResearchSlots(slots) => self.research_slots += slots,
+            BuildingEffect::EngineeringSlots(slots) => self.engineering_slots += slots,
+            BuildingEffect::Habitat(people) => self.habitat += people,
+            BuildingEffect::Relay(watts) => self.relay_capacity += watts,
+            BuildingEffect::StellarCollection(fraction) => self.stellar_collection += fraction,
+            BuildingEffect::GasHarvest {
+                resource,
+                mt_per_year,
+            } => *self.gas_harvest.entry(*resource).or_insert(0.0) += mt_per_year,
+            BuildingEffect::WasteHeat(watts) => self.waste_heat += watts,
+            BuildingEffect::Radiator(watts) => self.radiator_capacity += watts,
+            BuildingEffect::OrdnanceProduction {
+                ordnance,
+                rounds_per_year,
+            } => {
+                *self
+                    .ordnance_production
+                    .entry(ordnance.clone())
+                    .or_insert(0.0) += rounds_per_year
+            }
+            BuildingEffect::Magazine(rounds) => self.magazine_capacity += rounds,
+            BuildingEffect::Refining { good, mt_per_year } => {
+                *self.refining.entry(*good).or_insert(0.0) += mt_per_year
+            }
+            BuildingEffect::Farming(mt_per_year) => self.farming += mt_per_year,
+            BuildingEffect::Hydroponics(mt_per_year) => self.hydroponics += mt_per_year,
+            BuildingEffect::Domes(people) => self.domes += people,
+            BuildingEffect::ClosedLoop(people) => self.closed_loop += people,
+            BuildingEffect::Shielding(people) => self.shielding += people,
+            BuildingEffect::Shipyard {
+                tonnage_t,
+                slipways,
+            } => {
+                self.shipyard_tonnage_t += tonnage_t;
+                self.slipways += slipways;
+            }
+            BuildingEffect::Storage(joules) => self.storage_capacity += joules,
+            BuildingEffect::Listening(points) => self.listening += points,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mine() -> BuildingDefinition {
+        let level = |name: &str, rate: f64, iron: f64| BuildingLevel {
+            name: name.to_string(),
+            cost: HashMap::from([(ResourceType::Iron, iron)]),
+            refined_cost: HashMap::new(),
+            build_days: 30.0,
+            effects: vec![
+                BuildingEffect::Mining {
+                    resource: ResourceType::Iron,
+                    mt_per_year: rate,
+                },
+                BuildingEffect::PowerDemand(1e6),
+            ],
+            required_tech: None,
+        };
+        BuildingDefinition {
+            id: "iron_mine".to_string(),
+            name: "Iron Mine".to_string(),
+            description: String::new(),
+            levels: vec![
+                level("Iron Mine I", 1.0, 10.0),
+                level("Iron Mine II", 2.0, 25.0),
+                level("Iron Mine III", 4.0, 60.0),
+            ],
+            site: BuildingSite::Surface,
+        }
+    }
+
+    #[test]
+    fn test_level_lookup_is_one_based() {
+        let def = mine();
+        assert_eq!(def.max_level(), 3);
+        assert!(def.level(0).is_none());
+        assert_eq!(def.level(1).unwrap().name, "Iron Mine I");
+        assert_eq!(def.level(3).unwrap().name, "Iron Mine III");
+        assert!(def.level(4).is_none());
+    }
+
+    #[test]
+    fn test_effects_are_cumulative() {
+        let def = mine();
+        let effects = def.cumulative_effects(2);
+        assert_eq!(effects.mining[&ResourceType::Iron], 3.0);
+        assert_eq!(effects.power_demand, 2e6);
+
+        let effects = def.cumulative_effects(3);
+        assert_eq!(effects.mining[&ResourceType::Iron], 7.0);
+        assert_eq!(def.cumulative_effects(0), BuildingEffects::default());
+    }
+}
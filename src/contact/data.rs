@@ -0,0 +1,147 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::fs;
+use std::path::PathBuf;
+
+use super::types::AlienDefinition;
+use crate::modding::ModList;
+use crate::random_events::{EventTarget, EventsData};
+
+/// Base alien civilizations data file
+pub const ALIENS_PATH: &str = "assets/data/aliens.ron";
+
+/// Resource containing all alien civilizations loaded from data, in file
+/// order for a stable listing in the UI
+#[derive(Resource, Debug, Clone, Default)]
+pub struct AliensData {
+    pub aliens: Vec<AlienDefinition>,
+}
+
+impl AliensData {
+    /// Get an alien civilization by ID
+    pub fn get(&self, id: &str) -> Option<&AlienDefinition> {
+        self.aliens.iter().find(|alien| alien.id == id)
+    }
+
+    /// Add a definition, replacing one with the same ID in place
+    pub fn insert(&mut self, definition: AlienDefinition) {
+        match self.aliens.iter_mut().find(|a| a.id == definition.id) {
+            Some(existing) => *existing = definition,
+            None => self.aliens.push(definition),
+        }
+    }
+}
+
+/// Structure of the aliens data file
+#[derive(Debug, Deserialize)]
+struct AliensFile {
+    aliens: Vec<AlienDefinition>,
+}
+
+/// Check that a civilization can be found and talked to: it needs a
+/// positive signal and translation cost, a disposition within -100 to 100
+/// and contact events that exist
+pub fn validate_alien(definition: &AlienDefinition, events: &EventsData) -> Result<(), String> {
+    if !definition.signal.is_finite() || definition.signal <= 0.0 {
+        return Err(format!(
+            "civilization '{}' has no positive signal",
+            definition.id
+        ));
+    }
+    if !definition.translation_cost.is_finite() || definition.translation_cost <= 0.0 {
+        return Err(format!(
+            "civilization '{}' has no positive translation cost",
+            definition.id
+        ));
+    }
+    if !(-100.0..=100.0).contains(&definition.disposition) {
+        return Err(format!(
+            "civilization '{}' has a disposition outside -100 to 100",
+            definition.id
+        ));
+    }
+    for id in [&definition.first_contact, &definition.translated] {
+        match events.get(id) {
+            Some(event) if event.target == EventTarget::Contact => {}
+            Some(_) => {
+                return Err(format!(
+                    "civilization '{}' uses event '{}', which is not a contact event",
+                    definition.id, id
+                ))
+            }
+            None => {
+                return Err(format!(
+                    "civilization '{}' uses unknown event '{}'",
+                    definition.id, id
+                ))
+            }
+        }
+    }
+    Ok(())
+}
+
+/// System to load alien civilizations from data file at startup, followed
+/// by the civilizations of enabled mods. Runs after the events are loaded,
+/// to check the contact events against them.
+pub fn load_aliens(
+    mut commands: Commands,
+    events: Option<Res<EventsData>>,
+    mods: Option<Res<ModList>>,
+) {
+    let mut data = AliensData::default();
+    let mut paths = vec![PathBuf::from(ALIENS_PATH)];
+    paths.extend(mods.map_or(Vec::new(), |mods| mods.data_files("aliens.ron")));
+
+    for path in paths {
+        match fs::read_to_string(&path) {
+            Ok(contents) => match ron::from_str::<AliensFile>(&contents) {
+                Ok(file) => {
+                    for definition in file.aliens {
+                        let valid = events
+                            .as_ref()
+                            .map_or(Ok(()), |events| validate_alien(&definition, events));
+                        match valid {
+                            Ok(()) => data.insert(definition),
+                            Err(e) => error!("Skipping invalid civilization definition: {}", e),
+                        }
+                    }
+                }
+                Err(e) => error!("Failed to parse {}: {}", path.display(), e),
+            },
+            Err(e) => warn!(
+                "Aliens data file not found at {}: {}. The galaxy will stay silent.",
+                path.display(),
+                e
+            ),
+        }
+    }
+    info!("Loaded {} alien civilizations", data.aliens.len());
+    commands.insert_resource(data);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random_events::EventDefinition;
+
+    #[derive(Deserialize)]
+    struct EventsFile {
+        events: Vec<EventDefinition>,
+    }
+
+    #[test]
+    fn test_aliens_file_parses_and_validates() {
+        let contents = fs::read_to_string("assets/data/events.ron").unwrap();
+        let mut events = EventsData::default();
+        for definition in ron::from_str::<EventsFile>(&contents).unwrap().events {
+            events.insert(definition);
+        }
+
+        let contents = fs::read_to_string(ALIENS_PATH).unwrap();
+        let file: AliensFile = ron::from_str(&contents).unwrap();
+        assert!(!file.aliens.is_empty());
+        for definition in &file.aliens {
+            validate_alien(definition, &events).unwrap();
+        }
+    }
+}
@@ -0,0 +1,54 @@
+//! First contact
+//!
+//! Provides the scaffolding for alien civilizations:
+//! - Civilizations loaded from `assets/data/aliens.ron`, each with a signal
+//!   strength, a translation cost and a disposition towards humanity
+//! - Listening posts (SETI arrays) gathering listening points every day,
+//!   raised by sensor technology, until a civilization's signal is detected
+//! - A first-contact event chain fired through the random event dialog
+//! - Translation as a research project drawing on the research pool once
+//!   Xenolinguistics is known
+//! - Opinion and relation (hostile to allied) of every civilization in
+//!   contact, drifting towards its disposition and raised by envoys, shown
+//!   in the Diplomacy window
+
+use bevy::prelude::*;
+
+pub mod data;
+pub mod systems;
+pub mod types;
+
+pub use data::{load_aliens, AliensData};
+pub use systems::{
+    advance_translations, apply_contact_choices, begin_translation, drift_relations,
+    listen_for_signals, listening_sensitivity, process_contact_actions, AlienContacts,
+    Civilization, ListeningPost, PendingContactActions, ENVOY_COST, TRANSLATION_RP_PER_DAY,
+    TRANSLATION_TECH,
+};
+pub use types::{AlienDefinition, AlienId, ContactState, Relation};
+
+/// Plugin that adds alien civilizations and first contact to the Bevy app
+pub struct ContactPlugin;
+
+impl Plugin for ContactPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AlienContacts>()
+            .init_resource::<PendingContactActions>()
+            .add_systems(
+                Startup,
+                load_aliens.after(crate::random_events::load_events),
+            )
+            .add_systems(
+                FixedUpdate,
+                (listen_for_signals, advance_translations, drift_relations)
+                    .after(crate::economy::advance_economy_clock),
+            )
+            .add_systems(
+                Update,
+                (
+                    apply_contact_choices.after(crate::random_events::resolve_random_events),
+                    process_contact_actions,
+                ),
+            );
+    }
+}
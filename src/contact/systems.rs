@@ -0,0 +1,531 @@
+use bevy::prelude::*;
+
+use super::data::AliensData;
+use super::types::{AlienDefinition, AlienId, ContactState, Relation};
+use crate::economy::{EconomyClock, LedgerLine, Treasury};
+use crate::game_state::{EventLog, LogSeverity};
+use crate::random_events::{EventEffect, EventId, EventResolved, EventsData, PendingEvents};
+use crate::research::types::ModifierType;
+use crate::research::ResearchState;
+use crate::ui::{SimulationTime, TimeScale};
+
+/// Technology needed to translate alien signals
+pub const TRANSLATION_TECH: &str = "xenolinguistics";
+
+/// Research points a translation draws from the pool per day
+pub const TRANSLATION_RP_PER_DAY: f64 = 10.0;
+
+/// Credits an envoy costs
+pub const ENVOY_COST: f64 = 5.0e7;
+
+/// Opinion an envoy wins
+pub const ENVOY_OPINION: f64 = 5.0;
+
+/// Opinion a civilization in contact moves towards its disposition per month
+pub const OPINION_DRIFT_PER_MONTH: f64 = 1.0;
+
+/// Economy ticks (days) between opinion drifts
+const DRIFT_INTERVAL_TICKS: u64 = 30;
+
+/// Radio telescopes and SETI arrays listening for alien signals
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct ListeningPost {
+    /// Listening points gathered per day
+    pub sensitivity: f64,
+}
+
+/// What the civilization knows of an alien one and what it thinks of it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Civilization {
+    pub id: AlienId,
+    pub state: ContactState,
+    /// Listening points gathered on its signal
+    pub detection: f64,
+    /// Research points spent on the translation
+    pub translation: f64,
+    /// Its opinion of the player, from -100 to 100
+    pub opinion: f64,
+    /// Contact event waiting for the player's decision
+    pub awaiting: Option<EventId>,
+}
+
+impl Civilization {
+    pub fn new(id: AlienId) -> Self {
+        Self {
+            id,
+            state: ContactState::Undetected,
+            detection: 0.0,
+            translation: 0.0,
+            opinion: 0.0,
+            awaiting: None,
+        }
+    }
+
+    pub fn relation(&self) -> Relation {
+        Relation::from_opinion(self.opinion)
+    }
+}
+
+/// Every alien civilization and how far contact with it has come
+#[derive(Resource, Debug, Clone, Default)]
+pub struct AlienContacts {
+    pub civilizations: Vec<Civilization>,
+}
+
+impl AlienContacts {
+    pub fn get(&self, id: &str) -> Option<&Civilization> {
+        self.civilizations.iter().find(|c| c.id == id)
+    }
+
+    pub fn get_mut(&mut self, id: &str) -> Option<&mut Civilization> {
+        self.civilizations.iter_mut().find(|c| c.id == id)
+    }
+
+    /// Civilizations whose signals have been picked up
+    pub fn known(&self) -> impl Iterator<Item = &Civilization> {
+        self.civilizations
+            .iter()
+            .filter(|c| c.state != ContactState::Undetected)
+    }
+
+    /// Add civilizations loaded since the last call
+    fn sync(&mut self, data: &AliensData) {
+        for alien in &data.aliens {
+            if self.get(&alien.id).is_none() {
+                self.civilizations.push(Civilization::new(alien.id.clone()));
+            }
+        }
+    }
+}
+
+/// Contact commands queued by the UI
+#[derive(Resource, Debug, Clone, Default)]
+pub struct PendingContactActions {
+    /// Civilizations whose signals to start translating
+    pub translate: Vec<AlienId>,
+    /// Civilizations to send an envoy to
+    pub envoys: Vec<AlienId>,
+}
+
+/// Listening points gathered per day by every listening post, raised by
+/// sensor technology
+pub fn listening_sensitivity<'a>(
+    posts: impl Iterator<Item = &'a ListeningPost>,
+    research_state: &ResearchState,
+) -> f64 {
+    let base: f64 = posts.map(|post| post.sensitivity).sum();
+    base * (1.0 + research_state.get_modifier(ModifierType::SignalDetection) / 100.0)
+}
+
+/// Start translating a detected civilization's signals
+pub fn begin_translation(
+    civilization: &mut Civilization,
+    research_state: &ResearchState,
+) -> Result<(), String> {
+    if civilization.state != ContactState::Detected {
+        return Err("only a detected signal can be translated".to_string());
+    }
+    if !research_state.is_unlocked(TRANSLATION_TECH) {
+        return Err("translating alien signals needs Xenolinguistics".to_string());
+    }
+    civilization.state = ContactState::Translating;
+    Ok(())
+}
+
+/// Queue a contact event for a civilization and pause for its dialog, like
+/// for a random event
+#[allow(clippy::too_many_arguments)]
+fn fire_contact_event(
+    civilization: &mut Civilization,
+    alien: &AlienDefinition,
+    event_id: &str,
+    events: &EventsData,
+    pending: &mut PendingEvents,
+    time_scale: &mut TimeScale,
+    log: &mut EventLog,
+    date: String,
+) {
+    let Some(event) = events.get(event_id) else {
+        return;
+    };
+    pending.present(event, None, Some(alien.name.clone()), time_scale, log, date);
+    civilization.awaiting = Some(event.id.clone());
+}
+
+/// System that gathers listening points on every due economy day and fires
+/// the first-contact event of each civilization whose signal stands out
+#[allow(clippy::too_many_arguments)]
+pub fn listen_for_signals(
+    clock: Res<EconomyClock>,
+    data: Option<Res<AliensData>>,
+    events: Option<Res<EventsData>>,
+    research_state: Res<ResearchState>,
+    sim_time: Res<SimulationTime>,
+    mut contacts: ResMut<AlienContacts>,
+    mut pending: ResMut<PendingEvents>,
+    mut time_scale: ResMut<TimeScale>,
+    mut log: ResMut<EventLog>,
+    posts: Query<&ListeningPost>,
+) {
+    if clock.due == 0 {
+        return;
+    }
+    let (Some(data), Some(events)) = (data, events) else {
+        return;
+    };
+    contacts.sync(&data);
+    let gathered = listening_sensitivity(posts.iter(), &research_state) * clock.due as f64;
+    if gathered <= 0.0 {
+        return;
+    }
+
+    for civilization in contacts.civilizations.iter_mut() {
+        if civilization.state != ContactState::Undetected {
+            continue;
+        }
+        let Some(alien) = data.get(&civilization.id) else {
+            continue;
+        };
+        civilization.detection += gathered;
+        if civilization.detection < alien.signal {
+            continue;
+        }
+        civilization.state = ContactState::Detected;
+        log.push(
+            sim_time.format_date_time(),
+            LogSeverity::Info,
+            format!(
+                "📡 A signal from {} ({:.1} ly) stands out of the noise",
+                alien.system_name, alien.distance_ly
+            ),
+        );
+        fire_contact_event(
+            civilization,
+            alien,
+            &alien.first_contact,
+            &events,
+            &mut pending,
+            &mut time_scale,
+            &mut log,
+            sim_time.format_date_time(),
+        );
+    }
+}
+
+/// System that spends research points on the translations under way on
+/// every due economy day, and fires the contact event of each one completed
+#[allow(clippy::too_many_arguments)]
+pub fn advance_translations(
+    clock: Res<EconomyClock>,
+    data: Option<Res<AliensData>>,
+    events: Option<Res<EventsData>>,
+    sim_time: Res<SimulationTime>,
+    mut research_state: ResMut<ResearchState>,
+    mut contacts: ResMut<AlienContacts>,
+    mut pending: ResMut<PendingEvents>,
+    mut time_scale: ResMut<TimeScale>,
+    mut log: ResMut<EventLog>,
+) {
+    if clock.due == 0 {
+        return;
+    }
+    let (Some(data), Some(events)) = (data, events) else {
+        return;
+    };
+    for civilization in contacts.civilizations.iter_mut() {
+        if civilization.state != ContactState::Translating {
+            continue;
+        }
+        let Some(alien) = data.get(&civilization.id) else {
+            continue;
+        };
+        let points = (TRANSLATION_RP_PER_DAY * clock.due as f64)
+            .min(research_state.research_points_available)
+            .min(alien.translation_cost - civilization.translation)
+            .max(0.0);
+        research_state.research_points_available -= points;
+        civilization.translation += points;
+        if civilization.translation < alien.translation_cost {
+            continue;
+        }
+        civilization.state = ContactState::Contacted;
+        fire_contact_event(
+            civilization,
+            alien,
+            &alien.translated,
+            &events,
+            &mut pending,
+            &mut time_scale,
+            &mut log,
+            sim_time.format_date_time(),
+        );
+    }
+}
+
+/// System that applies the contact effects of the choice made on a contact
+/// event to the civilization it concerns
+pub fn apply_contact_choices(
+    events: Option<Res<EventsData>>,
+    research_state: Res<ResearchState>,
+    sim_time: Res<SimulationTime>,
+    mut resolved: EventReader<EventResolved>,
+    mut contacts: ResMut<AlienContacts>,
+    mut log: ResMut<EventLog>,
+) {
+    let Some(events) = events else {
+        resolved.clear();
+        return;
+    };
+    for resolution in resolved.read() {
+        // Contact events are answered in the order they were queued
+        let Some(civilization) = contacts
+            .civilizations
+            .iter_mut()
+            .find(|c| c.awaiting.as_ref() == Some(&resolution.id))
+        else {
+            continue;
+        };
+        civilization.awaiting = None;
+        let Some(choice) = events
+            .get(&resolution.id)
+            .and_then(|event| event.choices.get(resolution.choice))
+        else {
+            continue;
+        };
+        for effect in &choice.effects {
+            match effect {
+                EventEffect::Opinion(change) => {
+                    civilization.opinion = (civilization.opinion + change).clamp(-100.0, 100.0);
+                }
+                EventEffect::BeginTranslation => {
+                    if let Err(e) = begin_translation(civilization, &research_state) {
+                        log.push(
+                            sim_time.format_date_time(),
+                            LogSeverity::Warning,
+                            format!("Translation not started: {}", e),
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// System that moves the opinion of every civilization in contact towards
+/// its disposition once a month, logging changes of relation
+pub fn drift_relations(
+    clock: Res<EconomyClock>,
+    data: Option<Res<AliensData>>,
+    sim_time: Res<SimulationTime>,
+    mut contacts: ResMut<AlienContacts>,
+    mut log: ResMut<EventLog>,
+) {
+    if clock.due == 0 {
+        return;
+    }
+    let Some(data) = data else {
+        return;
+    };
+    let months_passed = clock.processed / DRIFT_INTERVAL_TICKS
+        - (clock.processed - clock.due) / DRIFT_INTERVAL_TICKS;
+    if months_passed == 0 {
+        return;
+    }
+
+    for civilization in contacts.civilizations.iter_mut() {
+        if civilization.state != ContactState::Contacted {
+            continue;
+        }
+        let Some(alien) = data.get(&civilization.id) else {
+            continue;
+        };
+        let before = civilization.relation();
+        let gap = alien.disposition - civilization.opinion;
+        let step = OPINION_DRIFT_PER_MONTH * months_passed as f64;
+        civilization.opinion += gap.clamp(-step, step);
+        let after = civilization.relation();
+        if after != before {
+            log.push(
+                sim_time.format_date_time(),
+                if after < before {
+                    LogSeverity::Warning
+                } else {
+                    LogSeverity::Info
+                },
+                format!(
+                    "Relations with {} are now {}",
+                    alien.name,
+                    after.display_name()
+                ),
+            );
+        }
+    }
+}
+
+/// System that carries out the contact commands queued by the UI
+pub fn process_contact_actions(
+    mut pending: ResMut<PendingContactActions>,
+    mut contacts: ResMut<AlienContacts>,
+    research_state: Res<ResearchState>,
+    mut treasury: Option<ResMut<Treasury>>,
+    data: Option<Res<AliensData>>,
+    mut log: Option<ResMut<EventLog>>,
+    sim_time: Option<Res<SimulationTime>>,
+) {
+    if pending.translate.is_empty() && pending.envoys.is_empty() {
+        return;
+    }
+    let name = |id: &str| {
+        data.as_ref()
+            .and_then(|d| d.get(id))
+            .map_or(id.to_string(), |alien| alien.name.clone())
+    };
+
+    let mut messages = Vec::new();
+    for id in std::mem::take(&mut pending.translate) {
+        let Some(civilization) = contacts.get_mut(&id) else {
+            continue;
+        };
+        match begin_translation(civilization, &research_state) {
+            Ok(()) => messages.push(format!("Began translating the signal of {}", name(&id))),
+            Err(e) => warn!("Cannot translate the signal of {}: {}", id, e),
+        }
+    }
+
+    for id in std::mem::take(&mut pending.envoys) {
+        let Some(civilization) = contacts.get_mut(&id) else {
+            continue;
+        };
+        if civilization.state != ContactState::Contacted {
+            warn!("Cannot send an envoy to {} before contact", id);
+            continue;
+        }
+        if let Some(treasury) = treasury.as_mut() {
+            if treasury.over_credit_limit() {
+                warn!("Cannot send an envoy beyond the credit limit");
+                break;
+            }
+            treasury.spend(LedgerLine::Diplomacy, ENVOY_COST);
+        }
+        civilization.opinion = (civilization.opinion + ENVOY_OPINION).min(100.0);
+        messages.push(format!("Sent an envoy to {}", name(&id)));
+    }
+
+    for message in messages {
+        info!("{}", message);
+        if let (Some(log), Some(sim_time)) = (log.as_mut(), sim_time.as_ref()) {
+            log.push(sim_time.format_date_time(), LogSeverity::Info, message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random_events::{EventChoice, EventDefinition, EventTarget};
+
+    fn contact_event(id: &str, effects: Vec<EventEffect>) -> EventDefinition {
+        EventDefinition {
+            id: id.to_string(),
+            title: id.to_string(),
+            description: "A message from {colony}.".to_string(),
+            weight: 0.0,
+            target: EventTarget::Contact,
+            required_tech: None,
+            max_stability: None,
+            severity: LogSeverity::Info,
+            choices: vec![EventChoice {
+                label: "Listen".to_string(),
+                description: String::new(),
+                effects,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_signal_is_detected_translated_and_answered() {
+        let mut events = EventsData::default();
+        events.insert(contact_event(
+            "signal",
+            vec![EventEffect::BeginTranslation, EventEffect::Opinion(10.0)],
+        ));
+        events.insert(contact_event("greeting", vec![]));
+        let alien = AlienDefinition {
+            id: "ceti".to_string(),
+            name: "Ceti Chorus".to_string(),
+            description: String::new(),
+            system_name: "Tau Ceti".to_string(),
+            distance_ly: 11.9,
+            signal: 5.0,
+            translation_cost: 30.0,
+            disposition: 40.0,
+            first_contact: "signal".to_string(),
+            translated: "greeting".to_string(),
+        };
+        let mut research_state = ResearchState::default();
+        research_state
+            .unlocked_technologies
+            .insert(TRANSLATION_TECH.to_string());
+        research_state.research_points_available = 1000.0;
+
+        let mut app = App::new();
+        app.init_resource::<SimulationTime>()
+            .init_resource::<TimeScale>()
+            .init_resource::<EventLog>()
+            .init_resource::<PendingEvents>()
+            .init_resource::<AlienContacts>()
+            .insert_resource(EconomyClock {
+                due: 2,
+                ..Default::default()
+            })
+            .insert_resource(research_state)
+            .insert_resource(events)
+            .insert_resource(AliensData {
+                aliens: vec![alien],
+            })
+            .add_event::<EventResolved>()
+            .add_systems(
+                Update,
+                (
+                    listen_for_signals,
+                    apply_contact_choices,
+                    advance_translations,
+                )
+                    .chain(),
+            );
+        app.world_mut().spawn(ListeningPost { sensitivity: 2.0 });
+
+        // 4 points after two days, not enough
+        app.update();
+        let state = |app: &App| app.world().resource::<AlienContacts>().civilizations[0].clone();
+        assert_eq!(state(&app).state, ContactState::Undetected);
+
+        app.update();
+        assert_eq!(state(&app).state, ContactState::Detected);
+        assert_eq!(state(&app).awaiting.as_deref(), Some("signal"));
+        assert_eq!(app.world().resource::<PendingEvents>().queue.len(), 1);
+
+        app.world_mut().send_event(EventResolved {
+            id: "signal".to_string(),
+            target: None,
+            choice: 0,
+        });
+        app.update();
+        let civilization = state(&app);
+        assert_eq!(civilization.state, ContactState::Translating);
+        assert_eq!(civilization.opinion, 10.0);
+        assert_eq!(civilization.translation, 2.0 * TRANSLATION_RP_PER_DAY);
+
+        app.update();
+        let civilization = state(&app);
+        assert_eq!(civilization.state, ContactState::Contacted);
+        assert_eq!(civilization.awaiting.as_deref(), Some("greeting"));
+        assert_eq!(
+            app.world()
+                .resource::<ResearchState>()
+                .research_points_available,
+            970.0
+        );
+    }
+}
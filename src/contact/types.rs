@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+
+use crate::random_events::EventId;
+
+/// Unique identifier for an alien civilization
+pub type AlienId = String;
+
+/// An alien civilization, loaded from data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlienDefinition {
+    /// Unique identifier
+    pub id: AlienId,
+    /// Display name
+    pub name: String,
+    /// Description shown once contact is made
+    pub description: String,
+    /// Star system its signals come from
+    pub system_name: String,
+    /// Distance in light years
+    pub distance_ly: f64,
+    /// Listening points needed to pick its signals out of the noise
+    pub signal: f64,
+    /// Research points needed to translate its signals
+    pub translation_cost: f64,
+    /// Opinion of the player it settles towards once contact is made, from
+    /// -100 to 100
+    pub disposition: f64,
+    /// Contact event fired when its signals are detected
+    pub first_contact: EventId,
+    /// Contact event fired when the translation is complete
+    pub translated: EventId,
+}
+
+/// How far contact with a civilization has come
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ContactState {
+    /// Its signals have not been picked up yet
+    #[default]
+    Undetected,
+    /// Its signals have been picked up but not understood
+    Detected,
+    /// Its signals are being translated
+    Translating,
+    /// The translation is complete and the two can talk
+    Contacted,
+}
+
+impl ContactState {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ContactState::Undetected => "Undetected",
+            ContactState::Detected => "Signal detected",
+            ContactState::Translating => "Translating",
+            ContactState::Contacted => "In contact",
+        }
+    }
+}
+
+/// Standing of a civilization towards the player, following its opinion
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Relation {
+    Hostile,
+    Wary,
+    Neutral,
+    Cordial,
+    Allied,
+}
+
+impl Relation {
+    /// Relation at an opinion from -100 to 100
+    pub fn from_opinion(opinion: f64) -> Self {
+        if opinion < -50.0 {
+            Relation::Hostile
+        } else if opinion < -15.0 {
+            Relation::Wary
+        } else if opinion <= 15.0 {
+            Relation::Neutral
+        } else if opinion <= 50.0 {
+            Relation::Cordial
+        } else {
+            Relation::Allied
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Relation::Hostile => "Hostile",
+            Relation::Wary => "Wary",
+            Relation::Neutral => "Neutral",
+            Relation::Cordial => "Cordial",
+            Relation::Allied => "Allied",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relation_follows_opinion() {
+        assert_eq!(Relation::from_opinion(-100.0), Relation::Hostile);
+        assert_eq!(Relation::from_opinion(-30.0), Relation::Wary);
+        assert_eq!(Relation::from_opinion(0.0), Relation::Neutral);
+        assert_eq!(Relation::from_opinion(15.0), Relation::Neutral);
+        assert_eq!(Relation::from_opinion(40.0), Relation::Cordial);
+        assert_eq!(Relation::from_opinion(80.0), Relation::Allied);
+        assert!(Relation::Hostile < Relation::Allied);
+    }
+}
@@ -0,0 +1,239 @@
+//! Resource shortfall, power deficit and overheating alerts
+//!
+//! A resource is flagged when its stockpile would run out within the
+//! configured horizon at the current net rate (mining minus consumption).
+//! Power is flagged when the civilization-wide balance is negative or a
+//! single colony grid runs short, and a colony when it produces more waste
+//! heat than it can reject. New alerts and resolved ones are written to the
+//! [`EventLog`].
+
+use bevy::prelude::*;
+use std::collections::HashSet;
+
+use super::budget::{
+    format_power, GlobalBudget, PowerGrids, ResourceRateTracker, SECONDS_PER_MONTH,
+};
+use super::thermal::HeatBalance;
+use super::tick::ECONOMY_TICK_SECONDS;
+use super::types::ResourceType;
+use crate::game_state::{EventLog, LogSeverity};
+use crate::ui::SimulationTime;
+
+/// Days in the 30-day month the rates are expressed in
+const DAYS_PER_MONTH: f64 = SECONDS_PER_MONTH / ECONOMY_TICK_SECONDS;
+
+/// Player-configurable alert thresholds
+#[derive(Resource, Debug, Clone)]
+pub struct AlertSettings {
+    /// Warn when a stockpile would be exhausted within this many days
+    pub depletion_horizon_days: f64,
+}
+
+impl Default for AlertSettings {
+    fn default() -> Self {
+        Self {
+            depletion_horizon_days: 180.0,
+        }
+    }
+}
+
+/// What an alert is about
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AlertKind {
+    /// A stockpile runs out soon
+    ResourceShortfall(ResourceType),
+    /// Total power demand exceeds production
+    PowerDeficit,
+    /// A single colony grid is short of power
+    GridDeficit(Entity),
+    /// A colony or station produces more waste heat than it can reject
+    Overheating(Entity),
+}
+
+/// An active warning
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alert {
+    pub kind: AlertKind,
+    pub message: String,
+}
+
+/// Warnings raised by the last check
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ActiveAlerts {
+    pub alerts: Vec<Alert>,
+}
+
+impl ActiveAlerts {
+    /// Whether a stockpile of `resource` is running out
+    pub fn has_shortfall(&self, resource: &ResourceType) -> bool {
+        self.alerts
+            .iter()
+            .any(|a| a.kind == AlertKind::ResourceShortfall(*resource))
+    }
+
+    /// Whether any power alert is active
+    pub fn has_power_alert(&self) -> bool {
+        self.alerts.iter().any(|a| {
+            matches!(
+                a.kind,
+                AlertKind::PowerDeficit | AlertKind::GridDeficit(_)
+            )
+        })
+    }
+}
+
+/// Days until a stockpile runs out at `net_per_month`, or `None` if it is
+/// not shrinking
+pub fn projected_depletion_days(stockpile: f64, net_per_month: f64) -> Option<f64> {
+    if net_per_month >= 0.0 {
+        return None;
+    }
+    Some((stockpile.max(0.0) / -net_per_month) * DAYS_PER_MONTH)
+}
+
+/// System that re-evaluates the alerts and logs the ones that appear or clear
+#[allow(clippy::too_many_arguments)]
+pub fn update_alerts(
+    settings: Res<AlertSettings>,
+    budget: Res<GlobalBudget>,
+    rates: Res<ResourceRateTracker>,
+    power_grids: Res<PowerGrids>,
+    heat_balance: Res<HeatBalance>,
+    sim_time: Res<SimulationTime>,
+    mut active: ResMut<ActiveAlerts>,
+    mut log: ResMut<EventLog>,
+) {
+    let mut alerts = Vec::new();
+
+    for resource in ResourceType::all() {
+        let stockpile = budget.get_stockpile(resource);
+        let Some(days) = projected_depletion_days(stockpile, rates.get_net_rate(resource)) else {
+            continue;
+        };
+        if days < settings.depletion_horizon_days {
+            alerts.push(Alert {
+                kind: AlertKind::ResourceShortfall(*resource),
+                message: format!(
+                    "{} runs out in {:.0} days",
+                    resource.display_name(),
+                    days
+                ),
+            });
+        }
+    }
+
+    let net_power = budget.net_power();
+    if net_power < 0.0 {
+        alerts.push(Alert {
+            kind: AlertKind::PowerDeficit,
+            message: format!("Power deficit of {}", format_power(-net_power)),
+        });
+    }
+    for (entity, grid) in power_grids.sorted() {
+        if !grid.is_sufficient() {
+            alerts.push(Alert {
+                kind: AlertKind::GridDeficit(entity),
+                message: format!(
+                    "{} is short {} of power",
+                    grid.name,
+                    format_power(-grid.surplus())
+                ),
+            });
+        }
+    }
+
+    let mut overheating: Vec<_> = heat_balance
+        .locations
+        .iter()
+        .filter(|(_, heat)| heat.is_overheating())
+        .collect();
+    overheating.sort_by(|a, b| a.1.name.cmp(&b.1.name));
+    for (entity, heat) in overheating {
+        alerts.push(Alert {
+            kind: AlertKind::Overheating(*entity),
+            message: format!(
+                "{} is overheating, output throttled to {:.0}%",
+                heat.name,
+                heat.throttle() * 100.0
+            ),
+        });
+    }
+
+    let previous: HashSet<&AlertKind> = active.alerts.iter().map(|a| &a.kind).collect();
+    let current: HashSet<&AlertKind> = alerts.iter().map(|a| &a.kind).collect();
+    if previous == current {
+        // Keep the messages fresh without touching the log
+        if active.alerts != alerts {
+            active.alerts = alerts;
+        }
+        return;
+    }
+
+    let date = sim_time.format_date_time();
+    for alert in alerts.iter().filter(|a| !previous.contains(&a.kind)) {
+        log.push(date.clone(), LogSeverity::Warning, alert.message.clone());
+    }
+    for alert in active.alerts.iter().filter(|a| !current.contains(&a.kind)) {
+        let resolved = match &alert.kind {
+            AlertKind::ResourceShortfall(resource) => {
+                format!("{} supply is stable again", resource.display_name())
+            }
+            AlertKind::PowerDeficit => "Power production covers demand again".to_string(),
+            AlertKind::GridDeficit(_) => format!("Resolved: {}", alert.message),
+            AlertKind::Overheating(_) => format!("Resolved: {}", alert.message),
+        };
+        log.push(date.clone(), LogSeverity::Info, resolved);
+    }
+    active.alerts = alerts;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_projected_depletion_days() {
+        assert_eq!(projected_depletion_days(100.0, 5.0), None);
+        assert_eq!(projected_depletion_days(100.0, 0.0), None);
+        // 100 Mt at -50 Mt/month lasts two months
+        assert_eq!(projected_depletion_days(100.0, -50.0), Some(60.0));
+        assert_eq!(projected_depletion_days(0.0, -1.0), Some(0.0));
+    }
+
+    #[test]
+    fn test_alerts_are_logged_once_and_resolved() {
+        let mut app = App::new();
+        app.init_resource::<AlertSettings>()
+            .init_resource::<GlobalBudget>()
+            .init_resource::<ResourceRateTracker>()
+            .init_resource::<PowerGrids>()
+            .init_resource::<HeatBalance>()
+            .init_resource::<SimulationTime>()
+            .init_resource::<ActiveAlerts>()
+            .init_resource::<EventLog>()
+            .add_systems(Update, update_alerts);
+
+        // 50 Mt of iron used at 30 Mt/month lasts 50 days
+        app.world_mut()
+            .resource_mut::<ResourceRateTracker>()
+            .consumption_rates
+            .insert(ResourceType::Iron, 30.0);
+        app.update();
+        app.update();
+
+        let alerts = app.world().resource::<ActiveAlerts>();
+        assert!(alerts.has_shortfall(&ResourceType::Iron));
+        assert!(!alerts.has_power_alert());
+        assert_eq!(app.world().resource::<EventLog>().entries.len(), 1);
+
+        app.world_mut()
+            .resource_mut::<ResourceRateTracker>()
+            .consumption_rates
+            .clear();
+        app.update();
+        let log = app.world().resource::<EventLog>();
+        assert_eq!(log.entries.len(), 2);
+        assert_eq!(log.entries[1].severity, LogSeverity::Info);
+        assert!(app.world().resource::<ActiveAlerts>().alerts.is_empty());
+    }
+}
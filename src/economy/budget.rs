@@ -1,9 +1,19 @@
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
 
+use super::production::RefinedGood;
 use super::types::ResourceType;
-use crate::economy::{PowerGenerator, PowerSourceType};
+use crate::astronomy::components::SystemId;
+use crate::economy::life_support::LifeSupport;
+use crate::economy::maintenance::{condition_factor_for, Condition};
+use crate::economy::storage::{shed_load, storage_discharge, PowerPriorities, PowerTier};
+use crate::economy::{
+    PowerConsumer, PowerGenerator, PowerGridHost, PowerRelay, PowerSourceType, PowerStorage,
+};
+use crate::plugins::solar_system::{CelestialBody, LogicalParent};
+use crate::research::ResearchState;
 
 /// Tracks per-month income/production rates for all resources
 /// and research/engineering points for display in the resource bar.
@@ -13,6 +23,12 @@ use crate::economy::{PowerGenerator, PowerSourceType};
 pub struct ResourceRateTracker {
     /// Monthly production rate per resource type (Mt/month)
     pub resource_rates: HashMap<ResourceType, f64>,
+    /// Monthly consumption per resource type (Mt/month), measured from the
+    /// stockpile history
+    pub consumption_rates: HashMap<ResourceType, f64>,
+    /// Monthly extraction of all resources (Mt/month) per body or station
+    /// whose depot the mines and harvesters there fill
+    pub location_rates: HashMap<Entity, f64>,
     /// Monthly research point generation
     pub research_rate_per_month: f64,
     /// Monthly engineering point generation
@@ -34,13 +50,101 @@ impl ResourceRateTracker {
     pub fn get_category_rate(&self, resources: &[ResourceType]) -> f64 {
         resources.iter().map(|r| self.get_resource_rate(r)).sum()
     }
+
+    /// Get the monthly consumption for a resource type
+    pub fn get_consumption_rate(&self, resource: &ResourceType) -> f64 {
+        self.consumption_rates.get(resource).copied().unwrap_or(0.0)
+    }
+
+    /// Production minus consumption per month for a resource type
+    pub fn get_net_rate(&self, resource: &ResourceType) -> f64 {
+        self.get_resource_rate(resource) - self.get_consumption_rate(resource)
+    }
+}
+
+/// Stock held at one colony or station
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Depot {
+    pub stockpiles: HashMap<ResourceType, f64>,
+    pub refined: HashMap<RefinedGood, f64>,
+    /// Food surplus kept at the colony (in Megatons)
+    pub food: f64,
+}
+
+impl Depot {
+    pub fn get(&self, resource: &ResourceType) -> f64 {
+        self.stockpiles.get(resource).copied().unwrap_or(0.0)
+    }
+
+    pub fn get_refined(&self, good: &RefinedGood) -> f64 {
+        self.refined.get(good).copied().unwrap_or(0.0)
+    }
+
+    fn stockpile_mut(&mut self, resource: ResourceType) -> Option<&mut f64> {
+        self.stockpiles.get_mut(&resource)
+    }
+
+    fn refined_mut(&mut self, good: RefinedGood) -> Option<&mut f64> {
+        self.refined.get_mut(&good)
+    }
+
+    fn food_mut(&mut self, _: ()) -> Option<&mut f64> {
+        Some(&mut self.food)
+    }
+}
+
+/// Take `amount` of `key`, already taken off a total that is now `total`,
+/// out of the depots: from the depot at `first` as far as it holds it, then
+/// from the central reserve, then from the fullest depots outside `cut_off`
+/// until together they hold no more than the total
+fn draw_from_depots<K: Copy + Eq + Hash>(
+    depots: &mut HashMap<Entity, Depot>,
+    stock: fn(&mut Depot, K) -> Option<&mut f64>,
+    total: f64,
+    first: Option<Entity>,
+    key: K,
+    amount: f64,
+    cut_off: &HashSet<Entity>,
+) {
+    if let Some(held) = first
+        .and_then(|location| depots.get_mut(&location))
+        .and_then(|depot| stock(depot, key))
+    {
+        *held -= amount.min(*held);
+    }
+
+    let mut held: Vec<(Entity, f64)> = depots
+        .iter_mut()
+        .filter_map(|(entity, depot)| stock(depot, key).map(|a| (*entity, *a)))
+        .filter(|(_, amount)| *amount > 0.0)
+        .collect();
+    let mut excess = held.iter().map(|(_, amount)| amount).sum::<f64>() - total;
+    held.retain(|(entity, _)| !cut_off.contains(entity));
+    held.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    for (entity, amount) in held {
+        if excess <= 0.0 {
+            break;
+        }
+        let take = excess.min(amount);
+        if let Some(held) = depots.get_mut(&entity).and_then(|depot| stock(depot, key)) {
+            *held -= take;
+        }
+        excess -= take;
+    }
 }
 
 /// Global economic budget and resource management
 /// Tracks civilization-wide stockpiles and power generation
+///
+/// The stockpiles are the empire totals. Of these, [`Self::depots`] hold
+/// what was produced at each colony or station; the rest is the central
+/// reserve (starting stock, imports and windfalls). Consuming at a location
+/// draws on its own depot first, then on the central reserve, then on the
+/// fullest other depots. Depots [`Self::cut_off`] from the rest (by a
+/// blockade) serve only their own location.
 #[derive(Resource, Debug, Clone, Serialize, Deserialize)]
 pub struct GlobalBudget {
-    /// Global stockpiles of each resource type (in arbitrary units)
+    /// Empire-wide stockpiles of each resource type (in arbitrary units)
     pub stockpiles: HashMap<ResourceType, f64>,
 
     /// Energy grid status
@@ -51,6 +155,27 @@ pub struct GlobalBudget {
 
     /// Breakdown of power production by source
     pub power_breakdown: HashMap<PowerSourceType, f64>,
+
+    /// Cumulative amount of each resource spent from the stockpiles
+    #[serde(default)]
+    pub consumed_total: HashMap<ResourceType, f64>,
+
+    /// Stockpiles of refined goods (in Megatons)
+    #[serde(default)]
+    pub refined: HashMap<RefinedGood, f64>,
+
+    /// Food surplus shipped from farming colonies to those that import it
+    /// (in Megatons); part of it is held in the colonies' depots
+    #[serde(default)]
+    pub food: f64,
+
+    /// Stock held at each colony and station, part of the totals above
+    #[serde(skip)]
+    pub depots: HashMap<Entity, Depot>,
+
+    /// Colonies and stations whose depots are cut off from the rest
+    #[serde(skip)]
+    pub cut_off: HashSet<Entity>,
 }
 
 impl GlobalBudget {
@@ -64,11 +189,20 @@ impl GlobalBudget {
         stockpiles.insert(ResourceType::Iron, 50.0);
         stockpiles.insert(ResourceType::Copper, 20.0);
 
+        let mut refined = HashMap::new();
+        refined.insert(RefinedGood::Steel, 40.0);
+        refined.insert(RefinedGood::Electronics, 10.0);
+
         Self {
             stockpiles,
             energy_grid: EnergyGrid::default(),
             civilization_score: 0.0,
             power_breakdown: HashMap::new(),
+            consumed_total: HashMap::new(),
+            refined,
+            food: 200.0,
+            depots: HashMap::new(),
+            cut_off: HashSet::new(),
         }
     }
 
@@ -112,13 +246,266 @@ impl GlobalBudget {
             "Cannot consume negative resource amount: {}",
             amount
         );
+        self.consume_from(None, resource, amount)
+    }
+
+    /// Stock of `resource` in the depot at `location`
+    pub fn stockpile_at(&self, location: Entity, resource: &ResourceType) -> f64 {
+        self.depots.get(&location).map_or(0.0, |d| d.get(resource))
+    }
+
+    /// Stock of `resource` that can be drawn on from outside the depots cut
+    /// off from the rest
+    pub fn available_stockpile(&self, resource: &ResourceType) -> f64 {
+        let cut_off: f64 = self
+            .cut_off
+            .iter()
+            .map(|location| self.stockpile_at(*location, resource))
+            .sum();
+        (self.get_stockpile(resource) - cut_off).max(0.0)
+    }
+
+    /// Stock of `resource` held in no depot
+    pub fn central_stockpile(&self, resource: &ResourceType) -> f64 {
+        let located: f64 = self.depots.values().map(|d| d.get(resource)).sum();
+        (self.get_stockpile(resource) - located).max(0.0)
+    }
+
+    /// Add resources produced at `location` to its depot
+    ///
+    /// # Panics
+    /// Panics if amount is negative
+    pub fn add_resource_at(&mut self, location: Entity, resource: ResourceType, amount: f64) {
+        self.add_resource(resource, amount);
+        let depot = self.depots.entry(location).or_default();
+        *depot.stockpiles.entry(resource).or_insert(0.0) += amount;
+    }
+
+    /// Remove resources used at `location`, its own depot first (returns
+    /// true if successful)
+    ///
+    /// # Panics
+    /// Panics if amount is negative
+    pub fn consume_resource_at(
+        &mut self,
+        location: Entity,
+        resource: ResourceType,
+        amount: f64,
+    ) -> bool {
+        assert!(
+            amount >= 0.0,
+            "Cannot consume negative resource amount: {}",
+            amount
+        );
+        self.consume_from(Some(location), resource, amount)
+    }
+
+    fn consume_from(
+        &mut self,
+        location: Option<Entity>,
+        resource: ResourceType,
+        amount: f64,
+    ) -> bool {
         let current = self.get_stockpile(&resource);
-        if current >= amount {
-            self.stockpiles.insert(resource, current - amount);
-            true
+        let available = match location.filter(|l| self.cut_off.contains(l)) {
+            Some(location) => self.stockpile_at(location, &resource),
+            None => self.available_stockpile(&resource),
+        };
+        if available < amount {
+            return false;
+        }
+        self.stockpiles.insert(resource, current - amount);
+        *self.consumed_total.entry(resource).or_insert(0.0) += amount;
+        draw_from_depots(
+            &mut self.depots,
+            Depot::stockpile_mut,
+            current - amount,
+            location,
+            resource,
+            amount,
+            &self.cut_off,
+        );
+        true
+    }
+
+    /// Get the stockpile amount of a refined good
+    pub fn get_refined(&self, good: &RefinedGood) -> f64 {
+        self.refined.get(good).copied().unwrap_or(0.0)
+    }
+
+    /// Add refined goods to the stockpile
+    ///
+    /// # Panics
+    /// Panics if amount is negative
+    pub fn add_refined(&mut self, good: RefinedGood, amount: f64) {
+        assert!(
+            amount >= 0.0,
+            "Cannot add negative refined amount: {}",
+            amount
+        );
+        *self.refined.entry(good).or_insert(0.0) += amount;
+    }
+
+    /// Remove refined goods from the stockpile (returns true if successful)
+    ///
+    /// # Panics
+    /// Panics if amount is negative
+    pub fn consume_refined(&mut self, good: RefinedGood, amount: f64) -> bool {
+        assert!(
+            amount >= 0.0,
+            "Cannot consume negative refined amount: {}",
+            amount
+        );
+        self.consume_refined_from(None, good, amount)
+    }
+
+    /// Refined goods in the depot at `location`
+    pub fn refined_at(&self, location: Entity, good: &RefinedGood) -> f64 {
+        self.depots
+            .get(&location)
+            .map_or(0.0, |d| d.get_refined(good))
+    }
+
+    /// Refined goods that can be drawn on from outside the depots cut off
+    /// from the rest
+    pub fn available_refined(&self, good: &RefinedGood) -> f64 {
+        let cut_off: f64 = self
+            .cut_off
+            .iter()
+            .map(|location| self.refined_at(*location, good))
+            .sum();
+        (self.get_refined(good) - cut_off).max(0.0)
+    }
+
+    /// Refined goods held in no depot
+    pub fn central_refined(&self, good: &RefinedGood) -> f64 {
+        let located: f64 = self.depots.values().map(|d| d.get_refined(good)).sum();
+        (self.get_refined(good) - located).max(0.0)
+    }
+
+    /// Add refined goods produced at `location` to its depot
+    ///
+    /// # Panics
+    /// Panics if amount is negative
+    pub fn add_refined_at(&mut self, location: Entity, good: RefinedGood, amount: f64) {
+        self.add_refined(good, amount);
+        let depot = self.depots.entry(location).or_default();
+        *depot.refined.entry(good).or_insert(0.0) += amount;
+    }
+
+    /// Remove refined goods used at `location`, its own depot first (returns
+    /// true if successful)
+    ///
+    /// # Panics
+    /// Panics if amount is negative
+    pub fn consume_refined_at(&mut self, location: Entity, good: RefinedGood, amount: f64) -> bool {
+        assert!(
+            amount >= 0.0,
+            "Cannot consume negative refined amount: {}",
+            amount
+        );
+        self.consume_refined_from(Some(location), good, amount)
+    }
+
+    fn consume_refined_from(
+        &mut self,
+        location: Option<Entity>,
+        good: RefinedGood,
+        amount: f64,
+    ) -> bool {
+        let current = self.get_refined(&good);
+        let available = match location.filter(|l| self.cut_off.contains(l)) {
+            Some(location) => self.refined_at(location, &good),
+            None => self.available_refined(&good),
+        };
+        if available < amount {
+            return false;
+        }
+        self.refined.insert(good, current - amount);
+        draw_from_depots(
+            &mut self.depots,
+            Depot::refined_mut,
+            current - amount,
+            location,
+            good,
+            amount,
+            &self.cut_off,
+        );
+        true
+    }
+
+    /// Food in the depot at `location`
+    pub fn food_at(&self, location: Entity) -> f64 {
+        self.depots.get(&location).map_or(0.0, |d| d.food)
+    }
+
+    /// Food that can be shipped to colonies outside the depots cut off from
+    /// the rest
+    pub fn available_food(&self) -> f64 {
+        let cut_off: f64 = self
+            .cut_off
+            .iter()
+            .map(|location| self.food_at(*location))
+            .sum();
+        (self.food - cut_off).max(0.0)
+    }
+
+    /// Store a food surplus grown at `location` in its depot
+    pub fn add_food_at(&mut self, location: Entity, amount: f64) {
+        self.food += amount;
+        self.depots.entry(location).or_default().food += amount;
+    }
+
+    /// Feed `location` up to `amount` of food, from its own depot first and
+    /// then, unless it is cut off, from the central reserve and the fullest
+    /// other depots. Returns the food it got.
+    pub fn consume_food_at(&mut self, location: Entity, amount: f64) -> f64 {
+        let available = if self.cut_off.contains(&location) {
+            self.food_at(location)
         } else {
-            false
+            self.available_food()
+        };
+        let taken = amount.clamp(0.0, available);
+        self.food -= taken;
+        draw_from_depots(
+            &mut self.depots,
+            Depot::food_mut,
+            self.food,
+            Some(location),
+            (),
+            taken,
+            &self.cut_off,
+        );
+        taken
+    }
+
+    /// Take the depot at `location` out of the stockpiles, e.g. when the
+    /// colony is lost. Nothing it held counts as consumed.
+    pub fn seize_depot(&mut self, location: Entity) -> Depot {
+        let depot = self.depots.remove(&location).unwrap_or_default();
+        for (resource, amount) in &depot.stockpiles {
+            let current = self.get_stockpile(resource);
+            self.stockpiles
+                .insert(*resource, (current - amount).max(0.0));
+        }
+        for (good, amount) in &depot.refined {
+            let current = self.get_refined(good);
+            self.refined.insert(*good, (current - amount).max(0.0));
         }
+        self.food = (self.food - depot.food).max(0.0);
+        depot
+    }
+
+    /// Whether the depots hold more of anything than the totals, after stock
+    /// was taken off the totals directly
+    fn depots_exceed_totals(&self) -> bool {
+        ResourceType::all().iter().any(|resource| {
+            let located: f64 = self.depots.values().map(|d| d.get(resource)).sum();
+            located > self.get_stockpile(resource) + 1e-9
+        }) || RefinedGood::all().iter().any(|good| {
+            let located: f64 = self.depots.values().map(|d| d.get_refined(good)).sum();
+            located > self.get_refined(good) + 1e-9
+        }) || self.depots.values().map(|d| d.food).sum::<f64>() > self.food + 1e-9
     }
 
     /// Update civilization score based on power generation
@@ -136,6 +523,12 @@ impl GlobalBudget {
         }
     }
 
+    /// Kardashev index of the power produced, by Carl Sagan's formula
+    /// K = (log10(P) - 6) / 10 with P in watts (Type I at 10^16 W)
+    pub fn kardashev_index(&self) -> f64 {
+        ((self.energy_grid.produced.max(1.0).log10() - 6.0) / 10.0).max(0.0)
+    }
+
     /// Get the net power (produced - consumed)
     pub fn net_power(&self) -> f64 {
         self.energy_grid.produced - self.energy_grid.consumed
@@ -165,12 +558,25 @@ pub struct EnergyGrid {
 
     /// Total power consumed (in Watts)
     pub consumed: f64,
+
+    /// Total energy held in storage (in Joules)
+    #[serde(default)]
+    pub stored: f64,
+
+    /// Total storage capacity (in Joules)
+    #[serde(default)]
+    pub storage_capacity: f64,
 }
 
 impl EnergyGrid {
     /// Create a new energy grid with specified values
     pub fn new(produced: f64, consumed: f64) -> Self {
-        Self { produced, consumed }
+        Self {
+            produced,
+            consumed,
+            stored: 0.0,
+            storage_capacity: 0.0,
+        }
     }
 
     /// Get the surplus or deficit
@@ -198,10 +604,61 @@ impl Default for EnergyGrid {
         Self {
             produced: 1_000_000_000.0, // Start with 1 GW
             consumed: 500_000_000.0,   // Consuming 500 MW
+            stored: 0.0,
+            storage_capacity: 0.0,
         }
     }
 }
 
+/// System that keeps the depots within the totals: the stock of colonies and
+/// stations that are gone returns to the central reserve, and the fullest
+/// depots give up what scenarios, events or the console took off the totals
+pub fn settle_depots(mut budget: ResMut<GlobalBudget>, entities: Query<Entity>) {
+    let gone = budget
+        .depots
+        .keys()
+        .any(|entity| !entities.contains(*entity));
+    if !gone && !budget.depots_exceed_totals() {
+        return;
+    }
+    let budget = &mut *budget;
+    budget.depots.retain(|entity, _| entities.contains(*entity));
+    for resource in ResourceType::all() {
+        let total = budget.get_stockpile(resource);
+        draw_from_depots(
+            &mut budget.depots,
+            Depot::stockpile_mut,
+            total,
+            None,
+            *resource,
+            0.0,
+            &HashSet::new(),
+        );
+    }
+    for good in RefinedGood::all() {
+        let total = budget.get_refined(good);
+        draw_from_depots(
+            &mut budget.depots,
+            Depot::refined_mut,
+            total,
+            None,
+            *good,
+            0.0,
+            &HashSet::new(),
+        );
+    }
+    let total = budget.food;
+    draw_from_depots(
+        &mut budget.depots,
+        Depot::food_mut,
+        total,
+        None,
+        (),
+        0.0,
+        &HashSet::new(),
+    );
+}
+
 /// System that updates the civilization score based on power generation
 /// Uses Local state to track previous energy grid values for efficient change detection
 ///
@@ -244,6 +701,7 @@ pub fn format_power(watts: f64) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::research::types::ModifierType;
 
     #[test]
     fn test_global_budget_creation() {
@@ -270,6 +728,7 @@ mod tests {
         let success = budget.consume_resource(ResourceType::Iron, 50.0);
 
         assert!(success);
+        assert_eq!(budget.consumed_total[&ResourceType::Iron], 50.0);
     }
 
     #[test]
@@ -284,6 +743,66 @@ mod tests {
         assert_eq!(budget.get_stockpile(&ResourceType::Titanium), 10.0); // Unchanged
     }
 
+    #[test]
+    fn test_depots_draw_locally_first() {
+        let mut budget = GlobalBudget::new();
+        budget.stockpiles.insert(ResourceType::Iron, 10.0);
+        let mars = Entity::from_raw(1);
+        let ceres = Entity::from_raw(2);
+        budget.add_resource_at(mars, ResourceType::Iron, 20.0);
+        budget.add_resource_at(ceres, ResourceType::Iron, 5.0);
+        assert_eq!(budget.get_stockpile(&ResourceType::Iron), 35.0);
+        assert_eq!(budget.central_stockpile(&ResourceType::Iron), 10.0);
+
+        // Ceres uses its own stock, then the central reserve
+        assert!(budget.consume_resource_at(ceres, ResourceType::Iron, 8.0));
+        assert_eq!(budget.stockpile_at(ceres, &ResourceType::Iron), 0.0);
+        assert_eq!(budget.central_stockpile(&ResourceType::Iron), 7.0);
+
+        // Beyond the central reserve the fullest depot gives
+        assert!(budget.consume_resource(ResourceType::Iron, 12.0));
+        assert_eq!(budget.stockpile_at(mars, &ResourceType::Iron), 15.0);
+        assert_eq!(budget.central_stockpile(&ResourceType::Iron), 0.0);
+        assert!(!budget.consume_resource_at(mars, ResourceType::Iron, 16.0));
+    }
+
+    #[test]
+    fn test_cut_off_depots_serve_only_their_location() {
+        let mut budget = GlobalBudget::new();
+        budget.stockpiles.insert(ResourceType::Iron, 10.0);
+        let mars = Entity::from_raw(1);
+        let ceres = Entity::from_raw(2);
+        budget.add_resource_at(mars, ResourceType::Iron, 20.0);
+        budget.cut_off.insert(mars);
+        assert_eq!(budget.available_stockpile(&ResourceType::Iron), 10.0);
+
+        // The rest cannot reach the blockaded depot, nor it the rest
+        assert!(!budget.consume_resource_at(ceres, ResourceType::Iron, 15.0));
+        assert!(budget.consume_resource_at(ceres, ResourceType::Iron, 10.0));
+        assert_eq!(budget.stockpile_at(mars, &ResourceType::Iron), 20.0);
+        assert!(!budget.consume_resource_at(mars, ResourceType::Iron, 25.0));
+        assert!(budget.consume_resource_at(mars, ResourceType::Iron, 20.0));
+        assert_eq!(budget.get_stockpile(&ResourceType::Iron), 0.0);
+    }
+
+    #[test]
+    fn test_blockaded_colonies_live_on_their_own_food() {
+        let mut budget = GlobalBudget::new();
+        budget.food = 50.0;
+        let earth = Entity::from_raw(1);
+        let callisto = Entity::from_raw(2);
+        budget.add_food_at(callisto, 5.0);
+        budget.cut_off.insert(callisto);
+        assert_eq!(budget.available_food(), 50.0);
+
+        // The blockaded moon eats only its own depot, the rest only the
+        // central reserve
+        assert_eq!(budget.consume_food_at(callisto, 8.0), 5.0);
+        assert_eq!(budget.consume_food_at(earth, 60.0), 50.0);
+        assert!(budget.food.abs() < 1e-12);
+        assert_eq!(budget.food_at(callisto), 0.0);
+    }
+
     #[test]
     fn test_civilization_score_calculation() {
         let mut budget = GlobalBudget::new();
@@ -364,22 +883,431 @@ mod tests {
         let mut budget = GlobalBudget::new();
         budget.consume_resource(ResourceType::Iron, -50.0);
     }
+
+    fn spawn_colony(app: &mut App, name: &str, produced: f64, demand: f64) -> Entity {
+        app.world_mut()
+            .spawn((
+                CelestialBody::fixture(name, crate::plugins::solar_system_data::BodyType::Planet),
+                PowerGenerator {
+                    output: produced,
+                    source_type: PowerSourceType::Planet,
+                },
+                PowerConsumer { demand },
+            ))
+            .id()
+    }
+
+    fn power_grid_app() -> App {
+        let mut app = App::new();
+        app.init_resource::<GlobalBudget>()
+            .init_resource::<PowerGrids>()
+            .init_resource::<ResearchState>()
+            .add_systems(Update, update_power_grid);
+        app
+    }
+
+    #[test]
+    fn test_local_deficit_not_hidden_by_remote_surplus() {
+        let mut app = power_grid_app();
+        let earth = spawn_colony(&mut app, "Earth", 20e12, 18e12);
+        let mars = spawn_colony(&mut app, "Mars", 1e9, 2e9);
+        app.update();
+
+        let grids = app.world().resource::<PowerGrids>();
+        assert!(grids.grids[&earth].is_sufficient());
+        assert!(!grids.grids[&mars].is_sufficient());
+        assert!(grids.has_deficit());
+        // The civilization-wide totals still add up
+        let budget = app.world().resource::<GlobalBudget>();
+        assert!(budget.net_power() > 0.0);
+    }
+
+    #[test]
+    fn test_station_feeds_grid_of_its_body() {
+        let mut app = power_grid_app();
+        let mars = spawn_colony(&mut app, "Mars", 1e9, 2e9);
+        app.world_mut().spawn((
+            PowerGenerator {
+                output: 1.5e9,
+                source_type: PowerSourceType::Station,
+            },
+            LogicalParent(mars),
+        ));
+        app.update();
+
+        let grid = &app.world().resource::<PowerGrids>().grids[&mars];
+        assert_eq!(grid.produced, 2.5e9);
+        assert_eq!(grid.breakdown[&PowerSourceType::Station], 1.5e9);
+        assert!(grid.is_sufficient());
+    }
+
+    #[test]
+    fn test_grid_host_keeps_its_own_grid() {
+        let mut app = power_grid_app();
+        let mars = spawn_colony(&mut app, "Mars", 1e9, 2e9);
+        let station = app
+            .world_mut()
+            .spawn((PowerGridHost, Name::new("Mars Station 1"), LogicalParent(mars)))
+            .id();
+        app.world_mut().spawn((
+            PowerGenerator {
+                output: 5e8,
+                source_type: PowerSourceType::Station,
+            },
+            LogicalParent(station),
+        ));
+        app.update();
+
+        let grids = &app.world().resource::<PowerGrids>().grids;
+        assert_eq!(grids[&mars].produced, 1e9);
+        assert_eq!(grids[&station].produced, 5e8);
+        assert_eq!(grids[&station].name, "Mars Station 1");
+    }
+
+    #[test]
+    fn test_relays_require_research_and_respect_capacity() {
+        let mut app = power_grid_app();
+        let earth = spawn_colony(&mut app, "Earth", 20e12, 18e12);
+        let mars = spawn_colony(&mut app, "Mars", 1e9, 5e9);
+        app.world_mut()
+            .entity_mut(earth)
+            .insert(PowerRelay { capacity: 10e9 });
+        app.world_mut()
+            .entity_mut(mars)
+            .insert(PowerRelay { capacity: 3e9 });
+
+        app.update();
+        assert_eq!(app.world().resource::<PowerGrids>().grids[&mars].relayed, 0.0);
+
+        app.world_mut()
+            .resource_mut::<ResearchState>()
+            .add_modifier(ModifierType::UnlockMechanic(POWER_RELAY_MECHANIC.to_string()), 1.0);
+        app.update();
+
+        let grids = app.world().resource::<PowerGrids>();
+        // Mars imports as much as its relay can carry, which is not quite enough
+        assert!((grids.grids[&mars].relayed - 3e9).abs() < 1.0);
+        assert!((grids.grids[&earth].relayed + 3e9).abs() < 1.0);
+        assert!(!grids.grids[&mars].is_sufficient());
+    }
+
+    #[test]
+    fn test_relays_do_not_cross_star_systems() {
+        let mut grids = PowerGrids::default();
+        grids.grids.insert(
+            Entity::from_raw(1),
+            LocalGrid {
+                system_id: 0,
+                produced: 10.0,
+                relay_capacity: 10.0,
+                ..Default::default()
+            },
+        );
+        grids.grids.insert(
+            Entity::from_raw(2),
+            LocalGrid {
+                system_id: 1,
+                consumed: 5.0,
+                relay_capacity: 10.0,
+                ..Default::default()
+            },
+        );
+        grids.balance_relays();
+
+        assert_eq!(grids.grids[&Entity::from_raw(2)].relayed, 0.0);
+        assert!(grids.has_deficit());
+    }
 }
 
-/// System to aggregate power from all generators and update global budget
+/// Research mechanic that brings [`PowerRelay`]s online
+pub const POWER_RELAY_MECHANIC: &str = "orbital_power_relays";
+
+/// Power balance of a single location: a body together with the stations and
+/// ships attached to it. Each location must cover its own demand unless it can
+/// draw on other locations in the same system over power relays.
+#[derive(Debug, Clone, Default)]
+pub struct LocalGrid {
+    /// Display name of the body or station hosting the grid
+    pub name: String,
+    /// Star system the grid belongs to
+    pub system_id: usize,
+    /// Power produced locally (in Watts)
+    pub produced: f64,
+    /// Power demanded locally (in Watts)
+    pub consumed: f64,
+    /// Net power received over relays, negative when exporting (in Watts)
+    pub relayed: f64,
+    /// Transmission limit of the relays at this location (in Watts)
+    pub relay_capacity: f64,
+    /// Breakdown of local production by source
+    pub breakdown: HashMap<PowerSourceType, f64>,
+    /// Energy held in storage at this location (in Joules)
+    pub stored: f64,
+    /// Storage capacity at this location (in Joules)
+    pub storage_capacity: f64,
+    /// Power released from storage to cover the deficit (in Watts)
+    pub from_storage: f64,
+    /// Demand of each sheddable tier (in Watts); the rest is essential
+    pub tier_demand: HashMap<PowerTier, f64>,
+    /// Fraction of each tier's demand that is met, below 1.0 in a brownout
+    pub tier_supply: HashMap<PowerTier, f64>,
+}
+
+impl LocalGrid {
+    /// Surplus (or deficit) after relay transfers, before storage
+    pub fn balance(&self) -> f64 {
+        self.produced + self.relayed - self.consumed
+    }
+
+    /// Surplus (or deficit) after relay transfers and storage
+    pub fn surplus(&self) -> f64 {
+        self.balance() + self.from_storage
+    }
+
+    /// Returns true if local production plus imports and storage covers
+    /// demand
+    pub fn is_sufficient(&self) -> bool {
+        self.surplus() >= 0.0
+    }
+
+    /// Fraction of the demand of `tier` that is met
+    pub fn supply_of(&self, tier: PowerTier) -> f64 {
+        self.tier_supply.get(&tier).copied().unwrap_or(1.0)
+    }
+
+    /// Returns true if any tier is short of power
+    pub fn is_browned_out(&self) -> bool {
+        self.tier_supply.values().any(|supply| *supply < 1.0)
+    }
+
+    /// Cover the deficit from storage, then shed the tiers in `priorities`
+    /// order from whatever is still missing
+    pub fn apply_brownout(&mut self, priorities: &PowerPriorities) {
+        self.from_storage = storage_discharge(-self.balance(), self.stored);
+        self.tier_supply.clear();
+        if self.is_sufficient() {
+            return;
+        }
+        let tiers: f64 = self.tier_demand.values().sum();
+        self.tier_supply = shed_load(
+            self.produced + self.relayed + self.from_storage,
+            self.consumed - tiers,
+            &self.tier_demand,
+            &priorities.0,
+        );
+    }
+}
+
+/// Per-location power grids, keyed by the body hosting each grid
+#[derive(Resource, Debug, Clone, Default)]
+pub struct PowerGrids {
+    pub grids: HashMap<Entity, LocalGrid>,
+}
+
+impl PowerGrids {
+    /// Returns true if any location is short of power
+    pub fn has_deficit(&self) -> bool {
+        self.grids.values().any(|grid| !grid.is_sufficient())
+    }
+
+    /// Fraction of its power demand an entity of `tier` gets: that of the
+    /// location it hosts, or of the location it is attached to
+    pub fn supply_for(
+        &self,
+        entity: Entity,
+        parent: Option<&LogicalParent>,
+        tier: PowerTier,
+    ) -> f64 {
+        self.grids
+            .get(&entity)
+            .or_else(|| parent.and_then(|p| self.grids.get(&p.0)))
+            .map_or(1.0, |grid| grid.supply_of(tier))
+    }
+
+    /// Grids ordered by system and name, for stable display
+    pub fn sorted(&self) -> Vec<(Entity, &LocalGrid)> {
+        let mut grids: Vec<_> = self.grids.iter().map(|(e, g)| (*e, g)).collect();
+        grids.sort_by(|a, b| {
+            a.1.system_id
+                .cmp(&b.1.system_id)
+                .then_with(|| a.1.name.cmp(&b.1.name))
+        });
+        grids
+    }
+
+    /// Move surplus power to deficit grids in the same system, limited by the
+    /// relay capacity at both ends. Transfers are shared out proportionally.
+    pub fn balance_relays(&mut self) {
+        let mut systems: HashMap<usize, Vec<Entity>> = HashMap::new();
+        for (entity, grid) in &self.grids {
+            if grid.relay_capacity > 0.0 {
+                systems.entry(grid.system_id).or_default().push(*entity);
+            }
+        }
+
+        for members in systems.values() {
+            let mut supply = 0.0;
+            let mut demand = 0.0;
+            for entity in members {
+                let surplus = self.grids[entity].surplus();
+                let capacity = self.grids[entity].relay_capacity;
+                if surplus > 0.0 {
+                    supply += surplus.min(capacity);
+                } else {
+                    demand += (-surplus).min(capacity);
+                }
+            }
+
+            let transfer = supply.min(demand);
+            if transfer <= 0.0 {
+                continue;
+            }
+
+            for entity in members {
+                let Some(grid) = self.grids.get_mut(entity) else {
+                    continue;
+                };
+                let surplus = grid.surplus();
+                if surplus > 0.0 {
+                    grid.relayed -= surplus.min(grid.relay_capacity) * transfer / supply;
+                } else {
+                    grid.relayed += (-surplus).min(grid.relay_capacity) * transfer / demand;
+                }
+            }
+        }
+    }
+}
+
+/// Entities that host a local grid: celestial bodies and grid hosts such as
+/// orbital stations
+type GridLocations<'w, 's> = Query<
+    'w,
+    's,
+    (Option<&'static CelestialBody>, Option<&'static Name>, Option<&'static SystemId>),
+    Or<(With<CelestialBody>, With<PowerGridHost>)>,
+>;
+
+/// The location whose grid an entity feeds: itself if it hosts a grid,
+/// otherwise the body or station it is attached to.
+fn grid_location(
+    entity: Entity,
+    logical_parent: Option<&LogicalParent>,
+    locations: &GridLocations,
+) -> Entity {
+    if locations.contains(entity) {
+        entity
+    } else {
+        logical_parent.map(|parent| parent.0).unwrap_or(entity)
+    }
+}
+
+/// Get or create the grid hosted by `location`
+fn local_grid<'a>(
+    grids: &'a mut HashMap<Entity, LocalGrid>,
+    location: Entity,
+    locations: &GridLocations,
+) -> &'a mut LocalGrid {
+    grids.entry(location).or_insert_with(|| {
+        let (name, system_id) = locations
+            .get(location)
+            .map(|(body, name, system)| {
+                let name = body
+                    .map(|b| b.name.clone())
+                    .or_else(|| name.map(|n| n.to_string()))
+                    .unwrap_or_else(|| "Unknown".to_string());
+                (name, system.map_or(0, |s| s.0))
+            })
+            .unwrap_or_else(|_| ("Unknown".to_string(), 0));
+        LocalGrid {
+            name,
+            system_id,
+            ..default()
+        }
+    })
+}
+
+/// System to aggregate power per location and update the global budget.
+///
+/// Generators and consumers are grouped into a [`LocalGrid`] per body or
+/// station, so a deficit on one colony cannot be hidden by surplus elsewhere.
+/// Once orbital power relays are researched, locations with a [`PowerRelay`]
+/// share power with other relay-equipped locations in their star system.
+/// [`PowerStorage`] covers what is still missing, and past that the location
+/// browns out by its [`PowerPriorities`].
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn update_power_grid(
     mut budget: ResMut<GlobalBudget>,
-    query: Query<&PowerGenerator>,
+    mut power_grids: ResMut<PowerGrids>,
+    research_state: Option<Res<ResearchState>>,
+    generators: Query<(Entity, &PowerGenerator, Option<&LogicalParent>)>,
+    consumers: Query<(
+        Entity,
+        &PowerConsumer,
+        Option<&LogicalParent>,
+        Option<&PowerTier>,
+    )>,
+    relays: Query<(Entity, &PowerRelay, Option<&LogicalParent>)>,
+    storages: Query<(Entity, &PowerStorage, Option<&LogicalParent>)>,
+    life_support: Query<(Entity, &LifeSupport)>,
+    priorities: Query<&PowerPriorities>,
+    conditions: Query<&Condition>,
+    locations: GridLocations,
 ) {
-    let mut total_produced = 0.0;
+    let mut grids = HashMap::new();
     let mut breakdown = HashMap::new();
 
-    for generator in query.iter() {
-        total_produced += generator.output;
-        *breakdown.entry(generator.source_type).or_insert(0.0) += generator.output;
+    for (entity, generator, parent) in generators.iter() {
+        let location = grid_location(entity, parent, &locations);
+        let grid = local_grid(&mut grids, location, &locations);
+        // Worn power plants deliver less
+        let output = generator.output * condition_factor_for(&conditions, entity);
+        grid.produced += output;
+        *grid.breakdown.entry(generator.source_type).or_insert(0.0) += output;
+        *breakdown.entry(generator.source_type).or_insert(0.0) += output;
+    }
+
+    for (entity, consumer, parent, tier) in consumers.iter() {
+        let location = grid_location(entity, parent, &locations);
+        let grid = local_grid(&mut grids, location, &locations);
+        grid.consumed += consumer.demand;
+        if let Some(tier) = tier {
+            *grid.tier_demand.entry(*tier).or_insert(0.0) += consumer.demand;
+        }
+    }
+
+    for (entity, storage, parent) in storages.iter() {
+        let location = grid_location(entity, parent, &locations);
+        let grid = local_grid(&mut grids, location, &locations);
+        grid.stored += storage.charge;
+        grid.storage_capacity += storage.capacity;
+    }
+
+    // Heating and cooling the habitats of colonies
+    for (entity, life_support) in life_support.iter() {
+        if life_support.climate_w > 0.0 {
+            local_grid(&mut grids, entity, &locations).consumed += life_support.climate_w;
+        }
+    }
+
+    let relays_online = research_state.is_some_and(|r| r.has_mechanic(POWER_RELAY_MECHANIC));
+    if relays_online {
+        for (entity, relay, parent) in relays.iter() {
+            let location = grid_location(entity, parent, &locations);
+            local_grid(&mut grids, location, &locations).relay_capacity += relay.capacity;
+        }
+    }
+
+    power_grids.grids = grids;
+    power_grids.balance_relays();
+    for (location, grid) in power_grids.grids.iter_mut() {
+        grid.apply_brownout(&priorities.get(*location).copied().unwrap_or_default());
     }
 
-    // Update grid production
-    budget.energy_grid.produced = total_produced;
+    // Civilization-wide totals
+    budget.energy_grid.produced = power_grids.grids.values().map(|g| g.produced).sum();
+    budget.energy_grid.consumed = power_grids.grids.values().map(|g| g.consumed).sum();
+    budget.energy_grid.stored = power_grids.grids.values().map(|g| g.stored).sum();
+    budget.energy_grid.storage_capacity =
+        power_grids.grids.values().map(|g| g.storage_capacity).sum();
     budget.power_breakdown = breakdown;
 }
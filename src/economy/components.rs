@@ -33,6 +33,51 @@ pub struct PowerGenerator {
     pub source_type: PowerSourceType,
 }
 
+/// Component that draws power from the local grid of the body it sits on
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct PowerConsumer {
+    /// Power demand in Watts
+    pub demand: f64,
+}
+
+/// Component that releases waste heat into the location it sits on, on top of
+/// the power it draws (reactors, smelters)
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct HeatSource {
+    /// Waste heat in Watts
+    pub output: f64,
+}
+
+/// Component that dumps heat into space from the location it sits on
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct Radiator {
+    /// Heat rejection capacity in Watts
+    pub capacity: f64,
+}
+
+/// Component linking a body's grid to the other relay-equipped grids in the
+/// same star system. Only operational once orbital power relays are researched.
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct PowerRelay {
+    /// Maximum power in Watts that can be sent or received through the relay
+    pub capacity: f64,
+}
+
+/// Component storing surplus energy of the local grid (batteries,
+/// capacitors) and releasing it when the grid runs short
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct PowerStorage {
+    /// Energy the storage can hold in Joules
+    pub capacity: f64,
+    /// Energy currently held in Joules
+    pub charge: f64,
+}
+
+/// Marker for a non-body entity (such as an orbital station) that hosts its
+/// own local grid instead of joining the grid of its `LogicalParent`
+#[derive(Component, Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct PowerGridHost;
+
 /// Component that marks a star and defines its system properties
 /// Used for multi-star system support with different frost lines
 #[derive(Component, Debug, Clone, Copy, Serialize, Deserialize)]
@@ -131,14 +176,110 @@ impl Default for StarSystem {
 pub struct Population {
     /// Total population count
     pub count: f64,
+    /// Groups the population is made of, by origin and culture; filled in
+    /// by `drift_cultures` for colonies that have none yet
+    #[serde(default)]
+    pub groups: Vec<PopulationGroup>,
 }
 
 impl Default for Population {
     fn default() -> Self {
-        Self { count: 0.0 }
+        Self::new(0.0)
     }
 }
 
+impl Population {
+    pub fn new(count: f64) -> Self {
+        Self {
+            count,
+            groups: Vec::new(),
+        }
+    }
+
+    /// Groups of settlers leaving for a new colony from `origin`: the same
+    /// cultures in the same shares, yet to adapt to their new home
+    pub fn settlers(&self, origin: &str) -> Vec<PopulationGroup> {
+        self.groups
+            .iter()
+            .map(|group| PopulationGroup {
+                origin: origin.to_string(),
+                years: 0.0,
+                ..group.clone()
+            })
+            .collect()
+    }
+
+    /// Divergence from the capital's culture averaged over the groups
+    pub fn cultural_drift(&self) -> f64 {
+        self.groups.iter().map(|g| g.share * g.divergence).sum()
+    }
+
+    /// Share of the population adapted to its world in any way
+    pub fn adapted_share(&self) -> f64 {
+        self.groups
+            .iter()
+            .filter(|g| !g.traits.is_empty())
+            .map(|g| g.share)
+            .sum()
+    }
+
+    /// Share of the population that has drifted far enough to want
+    /// independence
+    pub fn independence_support(&self, threshold: f64) -> f64 {
+        self.groups
+            .iter()
+            .filter(|g| g.divergence >= threshold)
+            .map(|g| g.share)
+            .sum()
+    }
+}
+
+/// A trait a population group acquires over generations on its world
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AdaptationTrait {
+    /// Tall and light-boned from a life in low gravity
+    LowGravity,
+    /// Stocky and strong from a life in high gravity
+    HighGravity,
+    /// At home in the cold of the outer system
+    ColdAdapted,
+    /// At home in the heat of the inner system
+    HeatAdapted,
+    /// Hardened to a radiation dose that would sicken newcomers
+    RadiationHardy,
+    /// Born and raised aboard stations, never under open sky
+    Spacer,
+}
+
+impl AdaptationTrait {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            AdaptationTrait::LowGravity => "Low-gravity adapted",
+            AdaptationTrait::HighGravity => "High-gravity adapted",
+            AdaptationTrait::ColdAdapted => "Cold adapted",
+            AdaptationTrait::HeatAdapted => "Heat adapted",
+            AdaptationTrait::RadiationHardy => "Radiation hardy",
+            AdaptationTrait::Spacer => "Spacer",
+        }
+    }
+}
+
+/// Part of a colony's population sharing an origin and a culture
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PopulationGroup {
+    /// Culture the group identifies with
+    pub culture: String,
+    /// Colony the group's settlers came from
+    pub origin: String,
+    /// Share of the colony's population (0.0 to 1.0)
+    pub share: f64,
+    /// Cultural distance from the capital, from 0 to 100
+    pub divergence: f64,
+    /// Years the group has lived on this colony
+    pub years: f64,
+    pub traits: Vec<AdaptationTrait>,
+}
+
 
 /// Stellar spectral classification
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -261,6 +402,21 @@ pub enum SurveyLevel {
 }
 
 impl SurveyLevel {
+    /// The level a further survey mission would reach
+    pub fn next(&self) -> Option<SurveyLevel> {
+        match self {
+            SurveyLevel::Unsurveyed => Some(SurveyLevel::OrbitalScan),
+            SurveyLevel::OrbitalScan => Some(SurveyLevel::SeismicSurvey),
+            SurveyLevel::SeismicSurvey => Some(SurveyLevel::CoreSample),
+            SurveyLevel::CoreSample => None,
+        }
+    }
+
+    /// Survey tier (0 = unsurveyed, 3 = core sample)
+    pub fn tier(&self) -> u32 {
+        *self as u32
+    }
+
     pub fn discovered_amount(&self, reserve: &ResourceReserve) -> f64 {
         match self {
             SurveyLevel::Unsurveyed => 0.0,
@@ -0,0 +1,242 @@
+//! Demographics and cultural drift
+//!
+//! The population of every colony and station is made of groups by origin
+//! and culture (see [`PopulationGroup`]), and settlers carry the groups of
+//! the colony they left. Away from the capital, the most populated colony,
+//! the groups drift from its culture over the generations, the faster the
+//! longer its news take to arrive at the speed of light. A group drifted
+//! past [`DISTINCT_CULTURE_DIVERGENCE`] takes its colony's name as its
+//! culture, and past [`INDEPENDENCE_DIVERGENCE`] it wants independence.
+//! After a few generations groups also adapt to their world's gravity,
+//! climate and radiation, or to life aboard a station. Drift costs
+//! stability and adaptation eases the housing penalty (see the stability
+//! module).
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+
+use super::components::{AdaptationTrait, Population, PopulationGroup};
+use super::radiation::Radiation;
+use super::tick::EconomyClock;
+use crate::astronomy::{SpaceCoordinates, SurfaceTemperature};
+use crate::comms::light_delay_seconds;
+use crate::game_state::{EventLog, LogSeverity};
+use crate::plugins::solar_system::CelestialBody;
+use crate::stats::HOMEWORLD;
+use crate::ui::SimulationTime;
+
+/// Culture of colonists whose origin has not been recorded
+pub const FOUNDING_CULTURE: &str = "Terran";
+
+/// Years of a generation
+pub const GENERATION_YEARS: f64 = 25.0;
+
+/// Generations a group needs on a world to adapt to it
+pub const ADAPTATION_GENERATIONS: f64 = 3.0;
+
+/// Divergence gained per year by a colony very far from the capital
+pub const MAX_DRIFT_PER_YEAR: f64 = 0.5;
+
+/// Light delay from the capital at which drift reaches half its maximum
+/// rate, in seconds
+pub const HALF_DRIFT_DELAY_SECONDS: f64 = 1800.0;
+
+/// Divergence from which a group identifies with its colony's culture
+pub const DISTINCT_CULTURE_DIVERGENCE: f64 = 30.0;
+
+/// Divergence from which a group wants independence
+pub const INDEPENDENCE_DIVERGENCE: f64 = 60.0;
+
+/// Share of a colony wanting independence that makes up a movement
+pub const INDEPENDENCE_MOVEMENT_SUPPORT: f64 = 0.5;
+
+/// Divergence gained per year by a colony this many seconds of light delay
+/// from the capital
+pub fn drift_rate(delay_seconds: f64) -> f64 {
+    let delay = delay_seconds.max(0.0);
+    MAX_DRIFT_PER_YEAR * delay / (delay + HALF_DRIFT_DELAY_SECONDS)
+}
+
+/// Position of the capital, the most populated colony with coordinates
+pub fn capital_position<'a>(
+    colonies: impl Iterator<Item = (&'a Population, Option<&'a SpaceCoordinates>)>,
+) -> Option<DVec3> {
+    colonies
+        .filter(|(population, coords)| population.count > 0.0 && coords.is_some())
+        .max_by(|a, b| a.0.count.total_cmp(&b.0.count))
+        .and_then(|(_, coords)| coords.map(|c| c.position))
+}
+
+/// Traits the colonists of a body (or a station without one) adapt to
+pub fn environment_traits(
+    body: Option<&CelestialBody>,
+    temperature: Option<&SurfaceTemperature>,
+    radiation: Option<&Radiation>,
+) -> Vec<AdaptationTrait> {
+    let Some(body) = body else {
+        return vec![AdaptationTrait::Spacer];
+    };
+    let mut traits = Vec::new();
+    let gravity = body.surface_gravity();
+    if gravity < 0.4 {
+        traits.push(AdaptationTrait::LowGravity);
+    } else if gravity > 1.5 {
+        traits.push(AdaptationTrait::HighGravity);
+    }
+    match temperature.map(|t| t.average_celsius) {
+        Some(celsius) if celsius < -60.0 => traits.push(AdaptationTrait::ColdAdapted),
+        Some(celsius) if celsius > 60.0 => traits.push(AdaptationTrait::HeatAdapted),
+        _ => {}
+    }
+    if radiation.is_some_and(|r| r.dose > 0.3) {
+        traits.push(AdaptationTrait::RadiationHardy);
+    }
+    traits
+}
+
+/// System that lets the population groups of every colony drift from the
+/// capital's culture and adapt to their world on every due economy tick,
+/// logging new cultures and independence movements
+#[allow(clippy::type_complexity)]
+pub fn drift_cultures(
+    clock: Res<EconomyClock>,
+    mut colonies: Query<(
+        &mut Population,
+        Option<&SpaceCoordinates>,
+        Option<&CelestialBody>,
+        Option<&SurfaceTemperature>,
+        Option<&Radiation>,
+        Option<&Name>,
+    )>,
+    mut log: Option<ResMut<EventLog>>,
+    sim_time: Option<Res<SimulationTime>>,
+) {
+    if clock.due == 0 {
+        return;
+    }
+    let years = EconomyClock::TICK_YEARS * clock.due as f64;
+    let capital = capital_position(
+        colonies
+            .iter()
+            .map(|(population, coords, ..)| (population, coords)),
+    );
+
+    let mut messages = Vec::new();
+    for (mut population, coords, body, temperature, radiation, name) in colonies.iter_mut() {
+        if population.count <= 0.0 {
+            continue;
+        }
+        let name = body
+            .map(|b| b.name.clone())
+            .or_else(|| name.map(|n| n.to_string()))
+            .unwrap_or_else(|| "a station".to_string());
+        if population.groups.is_empty() {
+            population.groups.push(PopulationGroup {
+                culture: FOUNDING_CULTURE.to_string(),
+                origin: HOMEWORLD.to_string(),
+                share: 1.0,
+                divergence: 0.0,
+                years: 0.0,
+                traits: Vec::new(),
+            });
+        }
+
+        let delay = match (capital, coords) {
+            (Some(capital), Some(coords)) => light_delay_seconds(capital, coords.position),
+            _ => 0.0,
+        };
+        let rate = drift_rate(delay);
+        let traits = environment_traits(body, temperature, radiation);
+        let had_movement = population.independence_support(INDEPENDENCE_DIVERGENCE)
+            >= INDEPENDENCE_MOVEMENT_SUPPORT;
+
+        for group in population.groups.iter_mut() {
+            let before = group.divergence;
+            group.years += years;
+            group.divergence = (group.divergence + rate * years).min(100.0);
+            if before < DISTINCT_CULTURE_DIVERGENCE
+                && group.divergence >= DISTINCT_CULTURE_DIVERGENCE
+            {
+                group.culture = format!("{}-born", name);
+                messages.push((
+                    LogSeverity::Info,
+                    format!(
+                        "The settlers from {} on {} now see themselves as a culture of their own",
+                        group.origin, name
+                    ),
+                ));
+            }
+            if group.years >= ADAPTATION_GENERATIONS * GENERATION_YEARS {
+                for adaptation in &traits {
+                    if !group.traits.contains(adaptation) {
+                        group.traits.push(*adaptation);
+                    }
+                }
+            }
+        }
+
+        let has_movement = population.independence_support(INDEPENDENCE_DIVERGENCE)
+            >= INDEPENDENCE_MOVEMENT_SUPPORT;
+        if has_movement && !had_movement {
+            messages.push((
+                LogSeverity::Warning,
+                format!("An independence movement has formed on {}", name),
+            ));
+        }
+    }
+
+    for (severity, message) in messages {
+        info!("{}", message);
+        if let (Some(log), Some(sim_time)) = (log.as_mut(), sim_time.as_ref()) {
+            log.push(sim_time.format_date_time(), severity, message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distant_colonies_drift_and_adapt() {
+        let mut app = App::new();
+        app.init_resource::<EconomyClock>()
+            .add_systems(Update, drift_cultures);
+        let capital = app
+            .world_mut()
+            .spawn((
+                Population::new(8e9),
+                SpaceCoordinates::new(DVec3::new(1.0, 0.0, 0.0)),
+            ))
+            .id();
+        let station = app
+            .world_mut()
+            .spawn((
+                Population::new(10_000.0),
+                SpaceCoordinates::new(DVec3::new(30.0, 0.0, 0.0)),
+                Name::new("Neptune Station"),
+            ))
+            .id();
+
+        // A century
+        let century = (100.0 / EconomyClock::TICK_YEARS).round() as u64;
+        app.world_mut().resource_mut::<EconomyClock>().due = century;
+        app.update();
+
+        let capital = app.world().get::<Population>(capital).unwrap();
+        assert_eq!(capital.groups[0].culture, FOUNDING_CULTURE);
+        assert_eq!(capital.cultural_drift(), 0.0);
+        let group = &app.world().get::<Population>(station).unwrap().groups[0];
+        assert_eq!(group.culture, "Neptune Station-born");
+        assert_eq!(group.traits, vec![AdaptationTrait::Spacer]);
+        assert!(group.divergence < INDEPENDENCE_DIVERGENCE);
+
+        app.update();
+        let population = app.world().get::<Population>(station).unwrap();
+        assert_eq!(
+            population.independence_support(INDEPENDENCE_DIVERGENCE),
+            1.0
+        );
+        assert!(drift_rate(0.0) == 0.0 && drift_rate(1e9) < MAX_DRIFT_PER_YEAR);
+    }
+}
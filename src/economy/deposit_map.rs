@@ -0,0 +1,146 @@
+//! Spatial layout of deposits for ground mapping
+//!
+//! Deposits are stored as planet-wide reserves; the map gives them a place on
+//! the surface. Each resource is concentrated around a few deposit sites whose
+//! positions are derived deterministically from the body and resource, so the
+//! same body always shows the same map.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::hash_map::DefaultHasher;
+use std::f32::consts::{FRAC_PI_2, PI};
+use std::hash::{Hash, Hasher};
+
+use super::components::{MineralDeposit, SurveyLevel};
+use super::types::ResourceType;
+
+/// Survey level from which deposit locations are known
+pub const MAPPING_SURVEY_LEVEL: SurveyLevel = SurveyLevel::SeismicSurvey;
+
+/// Whether a body surveyed to `level` can be ground mapped
+pub fn is_mappable(level: SurveyLevel) -> bool {
+    level.tier() >= MAPPING_SURVEY_LEVEL.tier()
+}
+
+/// A concentration of one resource on a body's surface
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DepositSite {
+    /// Latitude in radians (-π/2 to π/2)
+    pub latitude: f32,
+    /// Longitude in radians (-π to π)
+    pub longitude: f32,
+    /// Angular radius in radians
+    pub spread: f32,
+    /// Peak density (0.0 to 1.0)
+    pub strength: f32,
+}
+
+impl DepositSite {
+    /// Great-circle angle between the site center and a surface point
+    fn angle_to(&self, latitude: f32, longitude: f32) -> f32 {
+        let cos_angle = self.latitude.sin() * latitude.sin()
+            + self.latitude.cos() * latitude.cos() * (self.longitude - longitude).cos();
+        cos_angle.clamp(-1.0, 1.0).acos()
+    }
+}
+
+fn site_seed(body_name: &str, resource: ResourceType) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    body_name.hash(&mut hasher);
+    resource.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Deposit sites of `resource` on a body. Richer, more concentrated deposits
+/// have more and stronger sites.
+pub fn deposit_sites(
+    body_name: &str,
+    resource: ResourceType,
+    deposit: &MineralDeposit,
+) -> Vec<DepositSite> {
+    let mut rng = StdRng::seed_from_u64(site_seed(body_name, resource));
+    let concentration = deposit.reserve.concentration.clamp(0.0, 1.0);
+    let count = 1 + (concentration * 4.0).round() as usize;
+
+    (0..count)
+        .map(|_| DepositSite {
+            // Uniform over the sphere rather than over latitude
+            latitude: rng.gen_range(-1.0f32..1.0).asin(),
+            longitude: rng.gen_range(-PI..PI),
+            spread: rng.gen_range(0.15..0.45),
+            strength: (0.4 + 0.6 * concentration) * rng.gen_range(0.6..1.0),
+        })
+        .collect()
+}
+
+/// Deposit density (0.0 to 1.0) at a surface point
+pub fn deposit_density(sites: &[DepositSite], latitude: f32, longitude: f32) -> f32 {
+    sites
+        .iter()
+        .map(|site| {
+            let d = site.angle_to(latitude, longitude) / site.spread;
+            site.strength * (-0.5 * d * d).exp()
+        })
+        .sum::<f32>()
+        .min(1.0)
+}
+
+/// Equirectangular density grid, row by row from the north pole and from
+/// longitude -180° eastwards
+pub fn density_grid(sites: &[DepositSite], width: usize, height: usize) -> Vec<f32> {
+    let mut grid = Vec::with_capacity(width * height);
+    for row in 0..height {
+        let latitude = FRAC_PI_2 - (row as f32 + 0.5) / height as f32 * PI;
+        for column in 0..width {
+            let longitude = (column as f32 + 0.5) / width as f32 * 2.0 * PI - PI;
+            grid.push(deposit_density(sites, latitude, longitude));
+        }
+    }
+    grid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sites_are_deterministic() {
+        let deposit = MineralDeposit::new(10.0, 100.0, 1000.0, 0.5, 0.5);
+        let a = deposit_sites("Mars", ResourceType::Iron, &deposit);
+        assert_eq!(a, deposit_sites("Mars", ResourceType::Iron, &deposit));
+        assert_ne!(a, deposit_sites("Mars", ResourceType::Water, &deposit));
+        assert_eq!(a.len(), 3);
+    }
+
+    #[test]
+    fn test_density_peaks_at_sites() {
+        let site = DepositSite {
+            latitude: 0.3,
+            longitude: -1.0,
+            spread: 0.2,
+            strength: 0.8,
+        };
+        let sites = [site];
+        assert!((deposit_density(&sites, 0.3, -1.0) - 0.8).abs() < 1e-6);
+        assert!(deposit_density(&sites, -0.3, 2.0) < 0.01);
+
+        let grid = density_grid(&sites, 36, 18);
+        assert_eq!(grid.len(), 36 * 18);
+        let peak = grid
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(i, _)| i)
+            .unwrap();
+        // Northern hemisphere, western longitudes
+        assert!(peak / 36 < 9);
+        assert!(peak % 36 < 18);
+    }
+
+    #[test]
+    fn test_mapping_requires_seismic_survey() {
+        assert!(!is_mappable(SurveyLevel::OrbitalScan));
+        assert!(is_mappable(SurveyLevel::SeismicSurvey));
+        assert!(is_mappable(SurveyLevel::CoreSample));
+    }
+}
@@ -0,0 +1,309 @@
+//! Food and agriculture
+//!
+//! Every colonist eats [`FOOD_PER_PERSON_YEAR_MT`]. Breathable, temperate
+//! worlds grow their food in the open; elsewhere colonies rely on farms
+//! ([`Farm`]) whose yield falls with the colony cost, and on hydroponics that
+//! grow the same anywhere. Once per economy tick each colony eats its own
+//! harvest first and stores any surplus in its depot
+//! ([`GlobalBudget::add_food_at`]). A deficit is covered from the colony's
+//! own depot, then shipped in from the central reserve and the depots of
+//! the farming worlds, so the frozen moons of the gas giants live on
+//! shipments through the same logistics as every other good. A blockaded
+//! colony lives on its own depot only, and its surplus stays there.
+//! Whatever cannot be covered is the colony's [`FoodSupply::shortage`],
+//! which slows or reverses its growth.
+
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use super::budget::GlobalBudget;
+use super::components::Population;
+use super::population::body_colony_cost;
+use super::thermal::HeatBalance;
+use super::tick::EconomyClock;
+use crate::astronomy::{AtmosphereComposition, SurfaceTemperature};
+use crate::fleets::crew::FOOD_PER_PERSON_DAY_T;
+use crate::game_state::{EventLog, LogSeverity};
+use crate::plugins::solar_system::{CelestialBody, LogicalParent};
+use crate::research::types::ModifierType;
+use crate::research::ResearchState;
+
+/// Food eaten per person and year in Megatons
+pub const FOOD_PER_PERSON_YEAR_MT: f64 = FOOD_PER_PERSON_DAY_T * 365.25 / 1e6;
+
+/// Yearly population decline of a colony that gets no food at all
+pub const STARVATION_DECLINE_PER_YEAR: f64 = 0.05;
+
+/// A farm or hydroponics bay growing food for the colony it stands on
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct Farm {
+    /// Output on an Earth-like world in Megatons per year
+    pub mt_per_year: f64,
+    /// Hydroponics grow at the full rate regardless of the host body
+    pub hydroponic: bool,
+}
+
+/// Food balance of a colony over the last economy tick
+#[derive(Component, Debug, Clone, Default, PartialEq)]
+pub struct FoodSupply {
+    /// Food grown locally in Megatons per year
+    pub produced_mt_per_year: f64,
+    /// Food eaten in Megatons per year
+    pub consumed_mt_per_year: f64,
+    /// Food drawn from the depots in Megatons per year
+    pub imported_mt_per_year: f64,
+    /// Fraction of the demand that went unmet (0.0 to 1.0)
+    pub shortage: f64,
+}
+
+/// Share of its own demand a colony grows without farms: all of it on a
+/// breathable, temperate world, nothing from a colony cost of 2 (sealed
+/// habitats) upward
+pub fn native_food_fraction(colony_cost: f32) -> f64 {
+    if !colony_cost.is_finite() {
+        return 0.0;
+    }
+    (1.0 - colony_cost as f64 / 2.0).clamp(0.0, 1.0)
+}
+
+/// Yield of a farm relative to an Earth-like world. Fields suffer from the
+/// host body's conditions, hydroponics do not.
+pub fn farm_yield_factor(colony_cost: f32, hydroponic: bool) -> f64 {
+    if hydroponic {
+        1.0
+    } else if colony_cost.is_finite() {
+        1.0 / (1.0 + colony_cost.max(0.0) as f64)
+    } else {
+        0.0
+    }
+}
+
+/// Yearly growth rate of a colony that goes without `shortage` of its food:
+/// growth falls off with the unmet share and turns into decline towards
+/// [`STARVATION_DECLINE_PER_YEAR`]
+pub fn fed_growth_rate(rate: f64, shortage: f64) -> f64 {
+    let shortage = shortage.clamp(0.0, 1.0);
+    rate * (1.0 - shortage) - STARVATION_DECLINE_PER_YEAR * shortage
+}
+
+/// System that feeds every colony and station once per due economy tick.
+/// Deficits are served after all surpluses have been stored; if the food
+/// within reach runs short, every connected importing colony gets the same
+/// share, and a blockaded one what its own depot holds.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn update_food_supply(
+    clock: Res<EconomyClock>,
+    mut commands: Commands,
+    mut budget: ResMut<GlobalBudget>,
+    research_state: Option<Res<ResearchState>>,
+    heat: Option<Res<HeatBalance>>,
+    farms: Query<(Entity, &Farm, &LogicalParent)>,
+    mut colonies: Query<(
+        Entity,
+        &Population,
+        Option<&CelestialBody>,
+        Option<&SurfaceTemperature>,
+        Option<&AtmosphereComposition>,
+        Option<&Name>,
+        Option<&mut FoodSupply>,
+    )>,
+    mut log: Option<ResMut<EventLog>>,
+    sim_time: Option<Res<crate::ui::SimulationTime>>,
+) {
+    if clock.due == 0 {
+        return;
+    }
+    let multiplier =
+        1.0 + research_state.map_or(0.0, |r| r.get_modifier(ModifierType::FoodProduction)) / 100.0;
+    let years = EconomyClock::TICK_YEARS * clock.due as f64;
+
+    // Farm output per host before the host's conditions: (fields, hydroponics)
+    let mut farmed: HashMap<Entity, (f64, f64)> = HashMap::new();
+    for (entity, farm, parent) in farms.iter() {
+        let throttle = heat
+            .as_ref()
+            .map_or(1.0, |h| h.throttle_for(entity, Some(parent)));
+        let output = farmed.entry(parent.0).or_default();
+        if farm.hydroponic {
+            output.1 += farm.mt_per_year * throttle;
+        } else {
+            output.0 += farm.mt_per_year * throttle;
+        }
+    }
+
+    // Local harvest against demand; surpluses go to the depots first
+    let mut balances = Vec::new();
+    for (entity, population, body, temperature, atmosphere, ..) in colonies.iter() {
+        if population.count <= 0.0 {
+            continue;
+        }
+        let cost = body.map_or(f32::INFINITY, |b| {
+            body_colony_cost(b, temperature, atmosphere)
+        });
+        let consumed = population.count * FOOD_PER_PERSON_YEAR_MT;
+        let (fields, hydroponics) = farmed.get(&entity).copied().unwrap_or_default();
+        let produced = (consumed * native_food_fraction(cost)
+            + fields * farm_yield_factor(cost, false)
+            + hydroponics * farm_yield_factor(cost, true))
+            * multiplier;
+        let surplus = (produced - consumed).max(0.0) * years;
+        if surplus > 0.0 {
+            budget.add_food_at(entity, surplus);
+        }
+        balances.push((entity, produced, consumed, budget.cut_off.contains(&entity)));
+    }
+
+    let total_deficit: f64 = balances
+        .iter()
+        .filter(|(.., cut_off)| !cut_off)
+        .map(|(_, produced, consumed, _)| (consumed - produced).max(0.0) * years)
+        .sum();
+    let served = if total_deficit > 0.0 {
+        (budget.available_food() / total_deficit).min(1.0)
+    } else {
+        1.0
+    };
+
+    for (entity, produced, consumed, cut_off) in balances {
+        let Ok((_, _, body, _, _, name, supply)) = colonies.get_mut(entity) else {
+            continue;
+        };
+        let deficit = (consumed - produced).max(0.0);
+        let wanted = if cut_off { deficit } else { deficit * served };
+        let imported = if wanted > 0.0 {
+            budget.consume_food_at(entity, wanted * years) / years
+        } else {
+            0.0
+        };
+        let updated = FoodSupply {
+            produced_mt_per_year: produced,
+            consumed_mt_per_year: consumed,
+            imported_mt_per_year: imported,
+            shortage: (deficit - imported).max(0.0) / consumed,
+        };
+        let was_short = supply.as_ref().is_some_and(|s| s.shortage > 0.0);
+        let is_short = updated.shortage > 0.0;
+        match supply {
+            Some(mut supply) => {
+                if *supply != updated {
+                    *supply = updated.clone();
+                }
+            }
+            None => {
+                commands.entity(entity).insert(updated.clone());
+            }
+        }
+
+        if was_short == is_short {
+            continue;
+        }
+        let name = body
+            .map(|b| b.name.clone())
+            .or_else(|| name.map(|n| n.to_string()))
+            .unwrap_or_else(|| "a colony".to_string());
+        let (severity, message) = if is_short {
+            (
+                LogSeverity::Warning,
+                format!(
+                    "Food shortage on {}: {:.0}% of its demand goes unmet",
+                    name,
+                    updated.shortage * 100.0
+                ),
+            )
+        } else {
+            (
+                LogSeverity::Info,
+                format!("Food supply restored on {}", name),
+            )
+        };
+        info!("{}", message);
+        if let (Some(log), Some(sim_time)) = (log.as_mut(), sim_time.as_ref()) {
+            log.push(sim_time.format_date_time(), severity, message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::economy::tick::{advance_economy_clock, ECONOMY_TICK_SECONDS};
+    use crate::plugins::solar_system_data::BodyType;
+    use crate::ui::SimulationTime;
+
+    #[test]
+    fn test_yields_follow_habitability() {
+        assert_eq!(native_food_fraction(0.0), 1.0);
+        assert!((native_food_fraction(1.0) - 0.5).abs() < 1e-9);
+        assert_eq!(native_food_fraction(3.5), 0.0);
+        assert_eq!(native_food_fraction(f32::INFINITY), 0.0);
+
+        assert_eq!(farm_yield_factor(0.0, false), 1.0);
+        assert!((farm_yield_factor(3.0, false) - 0.25).abs() < 1e-9);
+        assert_eq!(farm_yield_factor(3.0, true), 1.0);
+        assert_eq!(farm_yield_factor(f32::INFINITY, false), 0.0);
+
+        let rate = 0.01;
+        assert_eq!(fed_growth_rate(rate, 0.0), rate);
+        assert!(fed_growth_rate(rate, 0.5) < 0.0);
+        assert_eq!(fed_growth_rate(rate, 1.0), -STARVATION_DECLINE_PER_YEAR);
+    }
+
+    #[test]
+    fn test_moons_import_surplus_until_the_stockpile_runs_dry() {
+        let mut app = App::new();
+        app.init_resource::<SimulationTime>()
+            .init_resource::<EconomyClock>()
+            .init_resource::<GlobalBudget>()
+            .add_systems(Update, (advance_economy_clock, update_food_supply).chain());
+        app.world_mut().resource_mut::<GlobalBudget>().food = 0.0;
+
+        // An airless, low-gravity moon at 0 °C (colony cost 3) of 100 000
+        // people with one field farm
+        let body = CelestialBody::fixture("Callisto", BodyType::Moon);
+        let moon = app
+            .world_mut()
+            .spawn((
+                body,
+                SurfaceTemperature::default(),
+                Population::new(100_000.0),
+            ))
+            .id();
+        let demand = 100_000.0 * FOOD_PER_PERSON_YEAR_MT;
+        app.world_mut().spawn((
+            Farm {
+                mt_per_year: demand,
+                hydroponic: false,
+            },
+            LogicalParent(moon),
+        ));
+        // A station growing twice what its crew eats
+        let station = app.world_mut().spawn(Population::new(1000.0)).id();
+        app.world_mut().spawn((
+            Farm {
+                mt_per_year: 2000.0 * FOOD_PER_PERSON_YEAR_MT,
+                hydroponic: true,
+            },
+            LogicalParent(station),
+        ));
+
+        app.world_mut().resource_mut::<SimulationTime>().elapsed = ECONOMY_TICK_SECONDS;
+        app.update();
+
+        let supply = |app: &App, entity| app.world().get::<FoodSupply>(entity).cloned().unwrap();
+        let moon_supply = supply(&app, moon);
+        assert!((moon_supply.produced_mt_per_year - 0.25 * demand).abs() < 1e-12);
+        // The station's surplus covers 1000 of the 75 000 people left hungry
+        let shortage = 1.0 - 1000.0 / 75_000.0;
+        assert!((moon_supply.shortage - 0.75 * shortage).abs() < 1e-9);
+        assert_eq!(supply(&app, station).shortage, 0.0);
+        assert!(app.world().resource::<GlobalBudget>().food.abs() < 1e-12);
+
+        // With a full stockpile the moon lives on imports
+        app.world_mut().resource_mut::<GlobalBudget>().food = 100.0;
+        app.world_mut().resource_mut::<SimulationTime>().elapsed = 2.0 * ECONOMY_TICK_SECONDS;
+        app.update();
+        let moon_supply = supply(&app, moon);
+        assert_eq!(moon_supply.shortage, 0.0);
+        assert!((moon_supply.imported_mt_per_year - 0.75 * demand).abs() < 1e-12);
+    }
+}
@@ -1,10 +1,14 @@
 use bevy::prelude::*;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
 use super::components::{MineralDeposit, OrbitsBody, PlanetResources, StarSystem};
 use super::types::ResourceType;
-use crate::astronomy::SpaceCoordinates;
-use crate::plugins::solar_system::{Asteroid, CelestialBody, Comet, DwarfPlanet, Moon, Planet};
+use crate::astronomy::{KeplerOrbit, OrbitCenter, SpaceCoordinates};
+use crate::game_state::{Difficulty, GameSeed};
+use crate::plugins::solar_system::{
+    Asteroid, CelestialBody, Comet, DwarfPlanet, Moon, Planet, Ring,
+};
 use crate::plugins::solar_system_data::{AsteroidClass, BodyType};
 
 /// Default frost line distance in Astronomical Units (for backwards compatibility)
@@ -12,10 +16,24 @@ use crate::plugins::solar_system_data::{AsteroidClass, BodyType};
 /// Beyond this distance, volatiles become more common
 const DEFAULT_FROST_LINE_AU: f64 = 2.5;
 
+/// Seed of a body's resource generation: the game seed mixed with a hash of
+/// the body's name, so a body gets the same deposits however often and in
+/// whatever order its system is populated
+pub fn body_seed(game_seed: u64, body_name: &str) -> u64 {
+    // FNV-1a, stable across runs and platforms
+    let hash = body_name
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        });
+    game_seed ^ hash
+}
+
 /// System that generates resources for all celestial bodies on startup
 /// Uses realistic accretion chemistry based on distance from parent star
 /// Supports multiple star systems with different frost lines
-/// Applies metallicity bonuses from stellar composition
+/// Applies metallicity bonuses from stellar composition and the resource
+/// abundance of the difficulty
 pub fn generate_solar_system_resources(
     mut commands: Commands,
     // Query planets, dwarf planets, moons, asteroids, and comets without resources
@@ -25,6 +43,8 @@ pub fn generate_solar_system_resources(
             &CelestialBody,
             &SpaceCoordinates,
             Option<&OrbitsBody>,
+            Option<&KeplerOrbit>,
+            Option<&OrbitCenter>,
         ),
         (
             Or<(
@@ -39,17 +59,33 @@ pub fn generate_solar_system_resources(
     >,
     // Query for star systems to get frost line and metallicity information
     star_query: Query<(&StarSystem, &SpaceCoordinates)>,
+    planet_orbits: Query<&KeplerOrbit>,
+    difficulty: Option<Res<Difficulty>>,
+    game_seed: Option<Res<GameSeed>>,
 ) {
-    let mut rng = rand::thread_rng();
+    let seed = game_seed.map_or(0, |s| s.value);
+    let abundance = difficulty.map_or(1.0, |d| d.resource_abundance_multiplier());
 
-    for (entity, body, coords, orbits_body) in body_query.iter() {
+    for (entity, body, coords, orbits_body, orbit, center) in body_query.iter() {
+        let mut rng = StdRng::seed_from_u64(body_seed(seed, &body.name));
         // Determine parent star, frost line, and metallicity multiplier
         let (distance_from_star, frost_line, metallicity_multiplier) = if let Some(orbits) =
             orbits_body
         {
             // Body orbits a specific parent - calculate distance from that parent
             if let Ok((star_system, star_coords)) = star_query.get(orbits.parent) {
-                let distance = (coords.position - star_coords.position).length();
+                // Moons of exoplanets orbit their planet but take their light
+                // from its star, at the planet's distance
+                let orbit = match center {
+                    Some(center) if center.0 != orbits.parent => planet_orbits.get(center.0).ok(),
+                    _ => orbit,
+                };
+                // Bodies spawned after startup have no position until their
+                // orbit is first propagated
+                let distance = orbit.map_or_else(
+                    || (coords.position - star_coords.position).length(),
+                    |orbit| orbit.semi_major_axis,
+                );
                 let metallicity_mult = star_system.metallicity_multiplier();
                 (distance, star_system.frost_line_au, metallicity_mult)
             } else {
@@ -84,12 +120,79 @@ pub fn generate_solar_system_resources(
 
         // Apply metallicity bonus to rare metals and fissile materials
         apply_metallicity_bonus(&mut resources, metallicity_multiplier);
+        scale_resources(&mut resources, abundance);
 
         // Add resources component to entity
         commands.entity(entity).insert(resources);
     }
 }
 
+/// System that gives ring systems their deposits. Rings are loose ice and
+/// silicate dust, split by the ring's ice fraction, and like rubble piles
+/// almost all of it is within reach.
+pub fn generate_ring_resources(
+    mut commands: Commands,
+    rings: Query<(Entity, &CelestialBody, &Ring), Without<PlanetResources>>,
+    difficulty: Option<Res<Difficulty>>,
+) {
+    let abundance = difficulty.map_or(1.0, |d| d.resource_abundance_multiplier());
+    for (entity, body, ring) in rings.iter() {
+        let mut resources = ring_resources(ring, body.mass);
+        scale_resources(&mut resources, abundance);
+        commands.entity(entity).insert(resources);
+    }
+}
+
+/// System that rescales the deposits generated so far when the difficulty
+/// changes, as it does when a new game starts after the bodies were spawned.
+/// Must run before the generation systems so that deposits generated in the
+/// same update are not scaled twice.
+pub fn rescale_deposits_for_difficulty(
+    difficulty: Res<Difficulty>,
+    mut applied: Local<Option<Difficulty>>,
+    mut resources: Query<&mut PlanetResources>,
+) {
+    let previous = applied.replace(*difficulty).unwrap_or(*difficulty);
+    if previous == *difficulty {
+        return;
+    }
+    let factor =
+        difficulty.resource_abundance_multiplier() / previous.resource_abundance_multiplier();
+    info!(
+        "Difficulty changed to {}: rescaling deposits by {:.2}x",
+        difficulty.name(),
+        factor
+    );
+    for mut resources in resources.iter_mut() {
+        scale_resources(&mut resources, factor);
+    }
+}
+
+/// Scale every deposit's reserves by `factor`
+fn scale_resources(resources: &mut PlanetResources, factor: f64) {
+    if factor == 1.0 {
+        return;
+    }
+    for deposit in resources.deposits.values_mut() {
+        scale_deposit(deposit, factor);
+    }
+}
+
+/// Ice and dust deposits of a ring of the given mass
+fn ring_resources(ring: &Ring, mass_kg: f64) -> PlanetResources {
+    let ice = ring.ice_fraction.clamp(0.0, 1.0) as f64;
+    let mut resources = PlanetResources::new();
+    resources.add_deposit(
+        ResourceType::Water,
+        create_deposit_legacy(ice, 0.9, mass_kg, BodyType::Asteroid),
+    );
+    resources.add_deposit(
+        ResourceType::Silicates,
+        create_deposit_legacy(1.0 - ice, 0.9, mass_kg, BodyType::Asteroid),
+    );
+    resources
+}
+
 /// Generate resources for a celestial body based on its distance from parent star
 /// Implements the frost line rule, realistic accretion chemistry, body-specific profiles,
 /// and scientific spectral class mapping for asteroids
@@ -2184,4 +2287,77 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_ring_resources_split_by_ice_fraction() {
+        let ring = Ring {
+            inner_radius_km: 74_500.0,
+            outer_radius_km: 140_000.0,
+            ice_fraction: 0.9,
+        };
+        let resources = ring_resources(&ring, 1.0e12);
+        let water = resources.get_deposit(&ResourceType::Water).unwrap();
+        let silicates = resources.get_deposit(&ResourceType::Silicates).unwrap();
+        // 1e12 kg is 1000 Mt in total
+        assert!((water.reserve.total_mass() - 900.0).abs() < 1e-3);
+        assert!((silicates.reserve.total_mass() - 100.0).abs() < 1e-3);
+        assert_eq!(resources.deposits.len(), 2);
+    }
+
+    #[test]
+    fn test_deposits_follow_difficulty_changes() {
+        let mut app = App::new();
+        app.init_resource::<Difficulty>()
+            .add_systems(Update, rescale_deposits_for_difficulty);
+        let ring = Ring {
+            inner_radius_km: 74_500.0,
+            outer_radius_km: 140_000.0,
+            ice_fraction: 1.0,
+        };
+        let body = app.world_mut().spawn(ring_resources(&ring, 1.0e12)).id();
+        let water = |app: &App| {
+            app.world()
+                .get::<PlanetResources>(body)
+                .unwrap()
+                .get_deposit(&ResourceType::Water)
+                .unwrap()
+                .reserve
+                .total_mass()
+        };
+
+        app.update();
+        assert!((water(&app) - 1000.0).abs() < 1e-3);
+
+        *app.world_mut().resource_mut::<Difficulty>() = Difficulty::Brutal;
+        app.update();
+        assert!((water(&app) - 500.0).abs() < 1e-3);
+
+        *app.world_mut().resource_mut::<Difficulty>() = Difficulty::Relaxed;
+        app.update();
+        assert!((water(&app) - 1500.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_body_resources_are_deterministic() {
+        assert_eq!(body_seed(42, "Kepler b"), body_seed(42, "Kepler b"));
+        assert_ne!(body_seed(42, "Kepler b"), body_seed(42, "Kepler c"));
+        assert_ne!(body_seed(42, "Kepler b"), body_seed(43, "Kepler b"));
+
+        let total_iron = || {
+            let mut rng = StdRng::seed_from_u64(body_seed(42, "Kepler b"));
+            let resources = generate_resources_for_body(
+                "Kepler b",
+                BodyType::Planet,
+                TEST_BODY_MASS,
+                None,
+                1.2,
+                2.5,
+                &mut rng,
+            );
+            resources
+                .get_deposit(&ResourceType::Iron)
+                .map(|deposit| deposit.reserve.total_mass())
+        };
+        assert_eq!(total_iron(), total_iron());
+    }
 }
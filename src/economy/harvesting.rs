@@ -0,0 +1,155 @@
+//! Gas harvesting from giant planet atmospheres
+//!
+//! Aerostat platforms and scoop ships built in a gas giant's atmosphere
+//! collect Hydrogen and Helium-3 into the global stockpile. Their yield grows
+//! with the pressure at which they operate (see
+//! [`AtmosphereComposition::harvest_yield_multiplier`]), and how deep they can
+//! go is limited by technology.
+
+use bevy::prelude::*;
+
+use crate::astronomy::AtmosphereComposition;
+use crate::economy::budget::{GlobalBudget, PowerGrids};
+use crate::economy::maintenance::{condition_factor_for, Condition};
+use crate::economy::stability::{output_factor_for, Stability};
+use crate::economy::storage::PowerTier;
+use crate::economy::thermal::HeatBalance;
+use crate::economy::tick::EconomyClock;
+use crate::economy::types::ResourceType;
+use crate::plugins::solar_system::LogicalParent;
+use crate::research::types::ModifierType;
+use crate::research::ResearchState;
+
+/// Deepest harvest level in bar reachable without any technology
+pub const BASE_HARVEST_DEPTH_BAR: f32 = 10.0;
+
+/// Gas harvesting facility in the atmosphere of its [`LogicalParent`]
+#[derive(Component, Debug, Clone)]
+pub struct GasHarvester {
+    pub resource_type: ResourceType,
+    /// Harvest rate in Megatons per year at the 1 bar level
+    pub base_rate_mt_per_year: f64,
+}
+
+impl GasHarvester {
+    /// Actual rate in Megatons per year in the given atmosphere
+    pub fn rate_mt_per_year(&self, atmosphere: &AtmosphereComposition) -> f64 {
+        self.base_rate_mt_per_year * atmosphere.harvest_yield_multiplier() as f64
+    }
+}
+
+/// Deepest harvest level in bar that research allows
+pub fn harvest_depth_limit(research_state: &ResearchState) -> f32 {
+    BASE_HARVEST_DEPTH_BAR + research_state.get_modifier(ModifierType::HarvestDepth) as f32
+}
+
+/// System that keeps the harvest depth of every gas giant at the limit set
+/// by research. Deeper is always better, so operations descend as soon as a
+/// technology allows it.
+pub fn update_harvest_depths(
+    research_state: Res<ResearchState>,
+    mut atmospheres: Query<&mut AtmosphereComposition>,
+) {
+    let research_changed = research_state.is_changed();
+    let limit = harvest_depth_limit(&research_state);
+    for mut atmosphere in atmospheres.iter_mut() {
+        // Only new giants need a depth unless research moved the limit
+        if !atmosphere.is_reference_pressure || !(research_changed || atmosphere.is_added()) {
+            continue;
+        }
+        if atmosphere.max_harvest_altitude_bar != limit {
+            atmosphere.max_harvest_altitude_bar = limit;
+            atmosphere.harvest_altitude_bar = limit;
+        }
+    }
+}
+
+/// System that adds the output of every gas harvester to the stockpile once
+/// per due economy tick. Giant atmospheres are treated as inexhaustible;
+/// overheating, unstable and browned-out locations harvest at a reduced rate.
+#[allow(clippy::too_many_arguments)]
+pub fn harvest_gas(
+    mut budget: ResMut<GlobalBudget>,
+    harvesters: Query<(Entity, &GasHarvester, &LogicalParent)>,
+    atmospheres: Query<&AtmosphereComposition>,
+    heat: Res<HeatBalance>,
+    power: Option<Res<PowerGrids>>,
+    stability: Query<&Stability>,
+    conditions: Query<&Condition>,
+    clock: Res<EconomyClock>,
+) {
+    if clock.due == 0 {
+        return;
+    }
+
+    for (entity, harvester, parent) in harvesters.iter() {
+        let Ok(atmosphere) = atmospheres.get(parent.0) else {
+            continue;
+        };
+        let harvested = harvester.rate_mt_per_year(atmosphere)
+            * heat.throttle_for(entity, Some(parent))
+            * power.as_ref().map_or(1.0, |p| {
+                p.supply_for(entity, Some(parent), PowerTier::Mining)
+            })
+            * output_factor_for(&stability, entity, Some(parent))
+            * condition_factor_for(&conditions, entity)
+            * EconomyClock::TICK_YEARS
+            * clock.due as f64;
+        if harvested > 0.0 {
+            budget.add_resource_at(parent.0, harvester.resource_type, harvested);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::astronomy::AtmosphericGas;
+
+    fn jupiter_atmosphere() -> AtmosphereComposition {
+        AtmosphereComposition::new_with_body_data(
+            1000.0,
+            -108.0,
+            vec![AtmosphericGas::new("H2", 89.8), AtmosphericGas::new("He", 10.2)],
+            1.8982e27,
+            69_911.0,
+            true,
+        )
+    }
+
+    #[test]
+    fn test_research_deepens_harvesting() {
+        let mut app = App::new();
+        app.init_resource::<ResearchState>()
+            .add_systems(Update, update_harvest_depths);
+        let giant = app.world_mut().spawn(jupiter_atmosphere()).id();
+
+        app.update();
+        let atmosphere = app.world().get::<AtmosphereComposition>(giant).unwrap();
+        assert_eq!(atmosphere.max_harvest_altitude_bar, BASE_HARVEST_DEPTH_BAR);
+        assert_eq!(atmosphere.harvest_altitude_bar, BASE_HARVEST_DEPTH_BAR);
+
+        app.world_mut()
+            .resource_mut::<ResearchState>()
+            .add_modifier(ModifierType::HarvestDepth, 40.0);
+        app.update();
+        let atmosphere = app.world().get::<AtmosphereComposition>(giant).unwrap();
+        assert_eq!(atmosphere.harvest_altitude_bar, 50.0);
+
+        let harvester = GasHarvester {
+            resource_type: ResourceType::Hydrogen,
+            base_rate_mt_per_year: 2.0,
+        };
+        assert!((harvester.rate_mt_per_year(atmosphere) - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_harvesting_outside_a_giant_yields_nothing() {
+        let rocky = AtmosphereComposition::new(1013.0, 15.0, vec![]);
+        let harvester = GasHarvester {
+            resource_type: ResourceType::Helium3,
+            base_rate_mt_per_year: 1.0,
+        };
+        assert_eq!(harvester.rate_mt_per_year(&rocky), 0.0);
+    }
+}
@@ -0,0 +1,169 @@
+//! Stockpile history for trend graphs
+//!
+//! Every [`HISTORY_INTERVAL_TICKS`] economy ticks the stockpiles and the
+//! cumulative consumption counters of the [`GlobalBudget`] are sampled into a
+//! bounded ring buffer. The samples drive the resource trend plots and the
+//! monthly consumption rates shown next to the mining income.
+
+use bevy::prelude::*;
+use std::collections::{HashMap, VecDeque};
+
+use super::budget::{GlobalBudget, ResourceRateTracker, SECONDS_PER_MONTH};
+use super::tick::{EconomyClock, ECONOMY_TICK_SECONDS};
+use super::types::ResourceType;
+
+/// Economy ticks (days) between two history samples
+pub const HISTORY_INTERVAL_TICKS: u64 = 7;
+
+/// Samples kept before the oldest are dropped (about ten years of weeks)
+pub const MAX_HISTORY_SAMPLES: usize = 522;
+
+/// Stockpiles at one point in simulation time
+#[derive(Debug, Clone, PartialEq)]
+pub struct StockpileSample {
+    /// Simulation seconds at the sampled tick
+    pub elapsed_seconds: f64,
+    pub stockpiles: HashMap<ResourceType, f64>,
+    /// Cumulative amounts spent from the stockpile (see [`GlobalBudget::consumed_total`])
+    pub consumed: HashMap<ResourceType, f64>,
+}
+
+/// Ring buffer of stockpile samples, oldest first
+#[derive(Resource, Debug, Clone, Default)]
+pub struct StockpileHistory {
+    pub samples: VecDeque<StockpileSample>,
+}
+
+impl StockpileHistory {
+    /// Append a sample, dropping the oldest once the buffer is full
+    pub fn record(&mut self, sample: StockpileSample) {
+        self.samples.push_back(sample);
+        while self.samples.len() > MAX_HISTORY_SAMPLES {
+            self.samples.pop_front();
+        }
+    }
+
+    /// `(seconds, stockpile)` points of one resource, for plotting
+    pub fn series(&self, resource: ResourceType) -> Vec<(f64, f64)> {
+        self.samples
+            .iter()
+            .map(|s| {
+                (
+                    s.elapsed_seconds,
+                    s.stockpiles.get(&resource).copied().unwrap_or(0.0),
+                )
+            })
+            .collect()
+    }
+
+    /// Monthly consumption of every resource, measured between the newest
+    /// sample and the newest one at least a month older
+    pub fn monthly_consumption(&self) -> HashMap<ResourceType, f64> {
+        let mut rates = HashMap::new();
+        let Some(latest) = self.samples.back() else {
+            return rates;
+        };
+        let Some(earlier) = self
+            .samples
+            .iter()
+            .rev()
+            .find(|s| latest.elapsed_seconds - s.elapsed_seconds >= SECONDS_PER_MONTH)
+        else {
+            return rates;
+        };
+        let months = (latest.elapsed_seconds - earlier.elapsed_seconds) / SECONDS_PER_MONTH;
+        for (resource, total) in &latest.consumed {
+            let before = earlier.consumed.get(resource).copied().unwrap_or(0.0);
+            if *total > before {
+                rates.insert(*resource, (total - before) / months);
+            }
+        }
+        rates
+    }
+}
+
+/// System that samples the stockpiles whenever the economy clock crosses a
+/// history interval, and refreshes the consumption rates from the samples.
+/// Runs after the tick-driven systems so a sample sees the day's changes.
+pub fn record_stockpile_history(
+    clock: Res<EconomyClock>,
+    budget: Res<GlobalBudget>,
+    mut history: ResMut<StockpileHistory>,
+    mut tracker: ResMut<ResourceRateTracker>,
+) {
+    if clock.due == 0 {
+        return;
+    }
+    let previous = clock.processed - clock.due;
+    if clock.processed / HISTORY_INTERVAL_TICKS == previous / HISTORY_INTERVAL_TICKS {
+        return;
+    }
+
+    history.record(StockpileSample {
+        elapsed_seconds: clock.processed as f64 * ECONOMY_TICK_SECONDS,
+        stockpiles: budget.stockpiles.clone(),
+        consumed: budget.consumed_total.clone(),
+    });
+    tracker.consumption_rates = history.monthly_consumption();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(days: f64, iron: f64, iron_spent: f64) -> StockpileSample {
+        StockpileSample {
+            elapsed_seconds: days * ECONOMY_TICK_SECONDS,
+            stockpiles: HashMap::from([(ResourceType::Iron, iron)]),
+            consumed: HashMap::from([(ResourceType::Iron, iron_spent)]),
+        }
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut history = StockpileHistory::default();
+        for day in 0..(MAX_HISTORY_SAMPLES + 10) {
+            history.record(sample(day as f64, 1.0, 0.0));
+        }
+        assert_eq!(history.samples.len(), MAX_HISTORY_SAMPLES);
+        assert_eq!(history.samples[0].elapsed_seconds, 10.0 * ECONOMY_TICK_SECONDS);
+    }
+
+    #[test]
+    fn test_monthly_consumption_needs_a_month_of_samples() {
+        let mut history = StockpileHistory::default();
+        history.record(sample(0.0, 100.0, 0.0));
+        history.record(sample(7.0, 90.0, 10.0));
+        assert!(history.monthly_consumption().is_empty());
+
+        history.record(sample(60.0, 40.0, 60.0));
+        let rates = history.monthly_consumption();
+        // Measured from day 7, the newest sample a month older: 50 Mt spent
+        // over 53 days
+        assert!((rates[&ResourceType::Iron] - 50.0 / (53.0 / 30.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_samples_taken_on_interval_boundaries() {
+        let mut app = App::new();
+        app.init_resource::<EconomyClock>()
+            .init_resource::<GlobalBudget>()
+            .init_resource::<StockpileHistory>()
+            .init_resource::<ResourceRateTracker>()
+            .add_systems(Update, record_stockpile_history);
+
+        for days in [3.0, 6.0, 7.0, 20.0] {
+            app.world_mut()
+                .resource_mut::<EconomyClock>()
+                .advance(days * ECONOMY_TICK_SECONDS);
+            app.update();
+        }
+        let history = app.world().resource::<StockpileHistory>();
+        let days: Vec<f64> = history
+            .samples
+            .iter()
+            .map(|s| s.elapsed_seconds / ECONOMY_TICK_SECONDS)
+            .collect();
+        assert_eq!(days, vec![7.0, 20.0]);
+    }
+}
@@ -0,0 +1,149 @@
+//! Kardashev milestones: power output thresholds that unlock megastructure
+//! technologies once the civilization first reaches them
+
+use bevy::prelude::*;
+
+use super::budget::GlobalBudget;
+use crate::game_state::{EventLog, LogSeverity};
+use crate::research::ResearchState;
+use crate::ui::SimulationTime;
+
+/// A Kardashev index the civilization can cross
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KardashevMilestone {
+    pub index: f64,
+    pub title: &'static str,
+    pub description: &'static str,
+}
+
+/// Milestones in increasing order. Technologies name the index they need in
+/// `required_kardashev`.
+pub const KARDASHEV_MILESTONES: &[KardashevMilestone] = &[
+    KardashevMilestone {
+        index: 0.7,
+        title: "Industrial Planet",
+        description: "Heavy orbital infrastructure becomes thinkable",
+    },
+    KardashevMilestone {
+        index: 0.9,
+        title: "Interplanetary Power",
+        description: "Enough power to start enclosing the Sun in collectors",
+    },
+    KardashevMilestone {
+        index: 1.0,
+        title: "Type I Civilization",
+        description: "The power of a whole planet's sunlight, ready to move stars",
+    },
+];
+
+/// Power in watts that gives a Kardashev index, the inverse of
+/// [`GlobalBudget::kardashev_index`]
+pub fn power_for_index(index: f64) -> f64 {
+    10f64.powf(index * 10.0 + 6.0)
+}
+
+/// Highest milestone at or below `reached`
+pub fn current_milestone(reached: f64) -> Option<&'static KardashevMilestone> {
+    KARDASHEV_MILESTONES
+        .iter()
+        .rev()
+        .find(|milestone| milestone.index <= reached)
+}
+
+/// First milestone above `reached`
+pub fn next_milestone(reached: f64) -> Option<&'static KardashevMilestone> {
+    KARDASHEV_MILESTONES
+        .iter()
+        .find(|milestone| milestone.index > reached)
+}
+
+/// Sent when the civilization crosses a Kardashev milestone for the first
+/// time
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct KardashevMilestoneReached {
+    pub milestone: KardashevMilestone,
+}
+
+/// System that records Kardashev milestones as the power output crosses
+/// them. A milestone stays reached when output later drops.
+pub fn update_kardashev_milestones(
+    budget: Res<GlobalBudget>,
+    sim_time: Res<SimulationTime>,
+    mut research_state: ResMut<ResearchState>,
+    mut log: ResMut<EventLog>,
+    mut reached: EventWriter<KardashevMilestoneReached>,
+) {
+    let index = budget.kardashev_index();
+    for milestone in KARDASHEV_MILESTONES {
+        if milestone.index <= research_state.kardashev_reached || milestone.index > index {
+            continue;
+        }
+        research_state.kardashev_reached = milestone.index;
+        log.push(
+            sim_time.format_date_time(),
+            LogSeverity::Info,
+            format!(
+                "Kardashev {:.1} reached: {}. {}",
+                milestone.index, milestone.title, milestone.description
+            ),
+        );
+        reached.send(KardashevMilestoneReached {
+            milestone: *milestone,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_power_for_index_inverts_kardashev_index() {
+        let mut budget = GlobalBudget::default();
+        for milestone in KARDASHEV_MILESTONES {
+            budget.energy_grid.produced = power_for_index(milestone.index);
+            assert!((budget.kardashev_index() - milestone.index).abs() < 1e-9);
+        }
+        // Type I is 10^16 W
+        assert!((power_for_index(1.0) - 1e16).abs() < 1.0);
+        assert_eq!(next_milestone(0.7).map(|m| m.index), Some(0.9));
+        assert_eq!(current_milestone(0.95).map(|m| m.index), Some(0.9));
+        assert!(current_milestone(0.5).is_none());
+    }
+
+    #[test]
+    fn test_milestones_are_reached_once() {
+        let mut app = App::new();
+        app.init_resource::<GlobalBudget>()
+            .init_resource::<SimulationTime>()
+            .init_resource::<ResearchState>()
+            .init_resource::<EventLog>()
+            .add_event::<KardashevMilestoneReached>()
+            .add_systems(Update, update_kardashev_milestones);
+
+        // 2 PW crosses 0.7 and 0.9 at once
+        app.world_mut()
+            .resource_mut::<GlobalBudget>()
+            .energy_grid
+            .produced = 2e15;
+        app.update();
+        assert_eq!(
+            app.world().resource::<ResearchState>().kardashev_reached,
+            0.9
+        );
+        let events = app.world().resource::<Events<KardashevMilestoneReached>>();
+        assert_eq!(events.get_reader().read(events).count(), 2);
+
+        // Losing power does not take a milestone back
+        app.world_mut()
+            .resource_mut::<GlobalBudget>()
+            .energy_grid
+            .produced = 1e12;
+        app.update();
+        assert_eq!(
+            app.world().resource::<ResearchState>().kardashev_reached,
+            0.9
+        );
+        assert_eq!(app.world().resource::<EventLog>().entries.len(), 2);
+    }
+}
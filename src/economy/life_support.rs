@@ -0,0 +1,311 @@
+//! Life support
+//!
+//! What a colony needs to stay alive follows from its [`HabitatClass`]:
+//! colonists on breathable worlds need nothing, on worlds with a marginal
+//! atmosphere they live under pressure domes, and on airless, crushing or
+//! extreme worlds in sealed habitats with closed-loop life support (which
+//! also serves under domes). Buildings provide the room
+//! ([`LifeSupportModule`]); colonists beyond it stop the colony growing.
+//!
+//! Habitats lose part of the air and water they recycle. Once per economy
+//! tick each colony draws the losses from the oxygen and water stockpiles
+//! and heats or cools its habitats against the outside temperature, which
+//! its power grid carries. Whatever the stockpiles cannot cover is the
+//! colony's [`LifeSupport::shortage`], which makes it decline like hunger.
+//! The outside temperature is that of the region the colony settled
+//! ([`ColonySite`]).
+
+use bevy::prelude::*;
+
+use super::budget::GlobalBudget;
+use super::components::Population;
+use super::regions::ColonySite;
+use super::tick::EconomyClock;
+use super::types::ResourceType;
+use crate::astronomy::{habitat_class, AtmosphereComposition, HabitatClass, SurfaceTemperature};
+use crate::game_state::{EventLog, LogSeverity};
+use crate::plugins::solar_system::{CelestialBody, LogicalParent};
+
+/// Oxygen breathed per person and year in Megatons (0.84 kg a day)
+pub const OXYGEN_PER_PERSON_YEAR_MT: f64 = 0.84 * 365.25 / 1e9;
+/// Water drunk and used per person and year in Megatons (3.5 kg a day)
+pub const WATER_PER_PERSON_YEAR_MT: f64 = 3.5 * 365.25 / 1e9;
+/// Power to heat or cool habitats per person and Kelvin outside the
+/// comfortable range, in Watts
+pub const CLIMATE_W_PER_PERSON_KELVIN: f64 = 2.0;
+/// Outside temperatures in Celsius habitats need no heating or cooling for
+pub const COMFORTABLE_RANGE_C: (f32, f32) = (15.0, 25.0);
+
+/// Pressure domes or closed-loop life support a building provides to the
+/// colony it stands on
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct LifeSupportModule {
+    /// People housed under domes
+    pub domes: f64,
+    /// People supported by closed-loop life support
+    pub closed_loop: f64,
+}
+
+/// Life support of a colony over the last economy tick
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct LifeSupport {
+    pub class: HabitatClass,
+    /// People the colony's habitats hold; infinite in the open
+    pub capacity: f64,
+    /// Share of the colonists living beyond the capacity (0.0 to 1.0)
+    pub overcrowding: f64,
+    /// Oxygen drawn from the stockpile in Megatons per year
+    pub oxygen_mt_per_year: f64,
+    /// Water drawn from the stockpile in Megatons per year
+    pub water_mt_per_year: f64,
+    /// Power drawn to heat or cool the habitats in Watts
+    pub climate_w: f64,
+    /// Fraction of the oxygen and water demand that went unmet (0.0 to 1.0)
+    pub shortage: f64,
+}
+
+/// Shares of the oxygen and water breathed and drunk that habitats lose and
+/// have to replace from the stockpile
+pub fn habitat_losses(class: HabitatClass) -> (f64, f64) {
+    match class {
+        HabitatClass::Open => (0.0, 0.0),
+        // Domes leak into the thin air around them
+        HabitatClass::Domed => (0.2, 0.1),
+        HabitatClass::Sealed => (0.1, 0.05),
+    }
+}
+
+/// People the habitats of a colony hold: domes count only under a marginal
+/// atmosphere, closed loops anywhere
+pub fn habitat_capacity(class: HabitatClass, domes: f64, closed_loop: f64) -> f64 {
+    match class {
+        HabitatClass::Open => f64::INFINITY,
+        HabitatClass::Domed => domes + closed_loop,
+        HabitatClass::Sealed => closed_loop,
+    }
+}
+
+/// Yearly oxygen and water (Mt) and the climate control power (W) a colony
+/// of `population` needs in `class` habitats at `temperature_celsius`
+pub fn life_support_demand(
+    class: HabitatClass,
+    population: f64,
+    temperature_celsius: f32,
+) -> (f64, f64, f64) {
+    if class == HabitatClass::Open {
+        return (0.0, 0.0, 0.0);
+    }
+    let (oxygen_loss, water_loss) = habitat_losses(class);
+    let (low, high) = COMFORTABLE_RANGE_C;
+    let kelvin = (low - temperature_celsius)
+        .max(temperature_celsius - high)
+        .max(0.0) as f64;
+    (
+        population * OXYGEN_PER_PERSON_YEAR_MT * oxygen_loss,
+        population * WATER_PER_PERSON_YEAR_MT * water_loss,
+        population * CLIMATE_W_PER_PERSON_KELVIN * kelvin,
+    )
+}
+
+/// Share of a demand for `resource` the stockpile can serve over `years`,
+/// taking what it serves
+fn draw(budget: &mut GlobalBudget, resource: ResourceType, demand: f64, years: f64) -> f64 {
+    if demand <= 0.0 {
+        return 1.0;
+    }
+    let stockpile = budget.stockpiles.entry(resource).or_insert(0.0);
+    let served = (*stockpile / (demand * years)).min(1.0);
+    *stockpile = (*stockpile - demand * years * served).max(0.0);
+    served
+}
+
+/// System that supplies the life support of every colony once per due
+/// economy tick. If a stockpile runs short, every colony gets the same share.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn update_life_support(
+    clock: Res<EconomyClock>,
+    mut commands: Commands,
+    mut budget: ResMut<GlobalBudget>,
+    modules: Query<(&LifeSupportModule, &LogicalParent)>,
+    mut colonies: Query<(
+        Entity,
+        &Population,
+        &CelestialBody,
+        Option<&SurfaceTemperature>,
+        Option<&AtmosphereComposition>,
+        Option<&ColonySite>,
+        Option<&mut LifeSupport>,
+    )>,
+    mut log: Option<ResMut<EventLog>>,
+    sim_time: Option<Res<crate::ui::SimulationTime>>,
+) {
+    if clock.due == 0 {
+        return;
+    }
+    let years = EconomyClock::TICK_YEARS * clock.due as f64;
+
+    let mut updates = Vec::new();
+    for (entity, population, _, temperature, atmosphere, site, _) in colonies.iter() {
+        if population.count <= 0.0 {
+            continue;
+        }
+        let temperature_celsius = temperature
+            .map(|t| t.average_celsius)
+            .or_else(|| atmosphere.map(|a| a.surface_temperature_celsius))
+            .unwrap_or(-273.15)
+            + site.map_or(0.0, |s| s.temperature_offset_c);
+        let class = habitat_class(temperature_celsius, atmosphere);
+        let (domes, closed_loop) = modules
+            .iter()
+            .filter(|(_, parent)| parent.0 == entity)
+            .fold((0.0, 0.0), |(d, c), (module, _)| {
+                (d + module.domes, c + module.closed_loop)
+            });
+        let capacity = habitat_capacity(class, domes, closed_loop);
+        let (oxygen, water, climate) =
+            life_support_demand(class, population.count, temperature_celsius);
+        updates.push((
+            entity,
+            LifeSupport {
+                class,
+                capacity,
+                overcrowding: (1.0 - capacity / population.count).max(0.0),
+                oxygen_mt_per_year: oxygen,
+                water_mt_per_year: water,
+                climate_w: climate,
+                shortage: 0.0,
+            },
+        ));
+    }
+
+    let total_oxygen: f64 = updates.iter().map(|(_, u)| u.oxygen_mt_per_year).sum();
+    let total_water: f64 = updates.iter().map(|(_, u)| u.water_mt_per_year).sum();
+    let oxygen_served = draw(&mut budget, ResourceType::Oxygen, total_oxygen, years);
+    let water_served = draw(&mut budget, ResourceType::Water, total_water, years);
+    let served = oxygen_served.min(water_served);
+
+    for (entity, mut updated) in updates {
+        if updated.oxygen_mt_per_year + updated.water_mt_per_year > 0.0 {
+            updated.shortage = 1.0 - served;
+        }
+        let Ok((.., body, _, _, _, life_support)) = colonies.get_mut(entity) else {
+            continue;
+        };
+        let name = body.name.clone();
+        let was_short = life_support.as_ref().is_some_and(|l| l.shortage > 0.0);
+        let is_short = updated.shortage > 0.0;
+        match life_support {
+            Some(mut life_support) => {
+                if *life_support != updated {
+                    *life_support = updated;
+                }
+            }
+            None => {
+                commands.entity(entity).insert(updated);
+            }
+        }
+
+        if was_short == is_short {
+            continue;
+        }
+        let (severity, message) = if is_short {
+            (
+                LogSeverity::Warning,
+                format!(
+                    "Life support failing on {}: the oxygen and water stockpiles run dry",
+                    name
+                ),
+            )
+        } else {
+            (
+                LogSeverity::Info,
+                format!("Life support on {} is fully supplied again", name),
+            )
+        };
+        if let (Some(log), Some(sim_time)) = (log.as_mut(), sim_time.as_ref()) {
+            log.push(sim_time.format_date_time(), severity, message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::economy::ECONOMY_TICK_SECONDS;
+
+    #[test]
+    fn test_habitats_follow_the_atmosphere() {
+        let mars = AtmosphereComposition::new(6.0, -63.0, Vec::new());
+        assert_eq!(habitat_class(-63.0, Some(&mars)), HabitatClass::Domed);
+        assert_eq!(habitat_class(-20.0, None), HabitatClass::Sealed);
+
+        // Domes count only where the air holds them up
+        assert_eq!(habitat_capacity(HabitatClass::Domed, 1e5, 2e4), 1.2e5);
+        assert_eq!(habitat_capacity(HabitatClass::Sealed, 1e5, 2e4), 2e4);
+        assert_eq!(
+            life_support_demand(HabitatClass::Open, 1e9, -40.0),
+            (0.0, 0.0, 0.0)
+        );
+        let (oxygen, water, climate) = life_support_demand(HabitatClass::Sealed, 1e6, -5.0);
+        assert!(oxygen > 0.0 && water > oxygen);
+        assert_eq!(climate, 1e6 * CLIMATE_W_PER_PERSON_KELVIN * 20.0);
+    }
+
+    #[test]
+    fn test_life_support_draws_from_the_stockpile() {
+        let mut app = App::new();
+        app.init_resource::<GlobalBudget>()
+            .init_resource::<EconomyClock>()
+            .add_systems(Update, update_life_support);
+        let moon = app
+            .world_mut()
+            .spawn((
+                CelestialBody {
+                    radius: 1737.4,
+                    mass: 7.342e22,
+                    ..CelestialBody::fixture(
+                        "Moon",
+                        crate::plugins::solar_system_data::BodyType::Moon,
+                    )
+                },
+                Population::new(50_000.0),
+            ))
+            .id();
+        app.world_mut().spawn((
+            LifeSupportModule {
+                domes: 1e6,
+                closed_loop: 20_000.0,
+            },
+            LogicalParent(moon),
+        ));
+        let oxygen = |app: &App| {
+            app.world()
+                .resource::<GlobalBudget>()
+                .get_stockpile(&ResourceType::Oxygen)
+        };
+        let before = oxygen(&app);
+        app.world_mut()
+            .resource_mut::<EconomyClock>()
+            .advance(ECONOMY_TICK_SECONDS);
+        app.update();
+
+        let life_support = app.world().get::<LifeSupport>(moon).unwrap().clone();
+        assert_eq!(life_support.class, HabitatClass::Sealed);
+        assert_eq!(life_support.capacity, 20_000.0);
+        assert!((life_support.overcrowding - 0.6).abs() < 1e-9);
+        assert_eq!(life_support.shortage, 0.0);
+        let drawn = life_support.oxygen_mt_per_year * EconomyClock::TICK_YEARS;
+        assert!((before - oxygen(&app) - drawn).abs() < 1e-12);
+
+        // An empty stockpile leaves the colony short
+        app.world_mut()
+            .resource_mut::<GlobalBudget>()
+            .stockpiles
+            .insert(ResourceType::Oxygen, 0.0);
+        app.world_mut()
+            .resource_mut::<EconomyClock>()
+            .advance(2.0 * ECONOMY_TICK_SECONDS);
+        app.update();
+        assert_eq!(app.world().get::<LifeSupport>(moon).unwrap().shortage, 1.0);
+    }
+}
@@ -0,0 +1,414 @@
+//! Maintenance and wear
+//!
+//! Facilities and fleets have a [`Condition`] between 0 (wrecked) and 1
+//! (new). Once per economy tick every building level draws spare parts
+//! (Steel) from the stockpile, and every fleet by its dry mass. Upkeep that
+//! is paid for only ages them slowly; without spare parts, without paid
+//! maintenance crews (the treasury beyond its credit limit) or, for fleets,
+//! with crew posts empty they wear fast. Worn facilities produce at their
+//! condition ([`condition_factor_for`]) and stop altogether once broken
+//! down; broken fleets cannot leave orbit.
+//!
+//! Anything below [`REPAIR_CONDITION`] joins the [`RepairQueue`]. Each
+//! colony or station works on one repair at a time, paid in refined goods
+//! when it starts, and fleets are repaired in orbit of a colony.
+
+use bevy::prelude::*;
+use std::collections::HashSet;
+
+use super::budget::GlobalBudget;
+use super::components::Population;
+use super::production::RefinedGood;
+use super::tick::EconomyClock;
+use super::treasury::Treasury;
+use crate::construction::Building;
+use crate::fleets::{Crew, Fleet, FleetLocation, TONNES_PER_MT};
+use crate::game_state::{EventLog, LogSeverity};
+use crate::plugins::solar_system::LogicalParent;
+use crate::ui::SimulationTime;
+
+/// Steel used as spare parts per building level and year in Megatons
+pub const SPARE_PARTS_PER_LEVEL_YEAR: f64 = 0.02;
+
+/// Steel used as spare parts per tonne of fleet dry mass and year in tonnes
+pub const SPARE_PARTS_PER_TONNE_YEAR: f64 = 0.05;
+
+/// Condition lost per year with upkeep paid
+pub const AGING_PER_YEAR: f64 = 0.01;
+
+/// Condition lost per year without upkeep
+pub const WEAR_PER_YEAR: f64 = 0.25;
+
+/// Condition below which facilities and fleets are queued for repair
+pub const REPAIR_CONDITION: f64 = 0.5;
+
+/// Condition below which facilities stop and fleets cannot leave orbit
+pub const BREAKDOWN_CONDITION: f64 = 0.2;
+
+/// Days a repair takes
+pub const REPAIR_DAYS: f64 = 30.0;
+
+/// Steel and Electronics paid per building level for a repair in Megatons
+pub const REPAIR_COST_PER_LEVEL: [(RefinedGood, f64); 2] =
+    [(RefinedGood::Steel, 0.5), (RefinedGood::Electronics, 0.1)];
+
+/// Steel paid per tonne of fleet dry mass for a repair in tonnes
+pub const REPAIR_STEEL_PER_TONNE: f64 = 0.2;
+
+/// State of repair of a facility or fleet
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct Condition {
+    pub value: f64,
+}
+
+impl Default for Condition {
+    fn default() -> Self {
+        Self { value: 1.0 }
+    }
+}
+
+impl Condition {
+    pub fn is_broken(&self) -> bool {
+        self.value < BREAKDOWN_CONDITION
+    }
+
+    /// Share of its output a facility in this condition produces
+    pub fn efficiency(&self) -> f64 {
+        if self.is_broken() {
+            0.0
+        } else {
+            self.value.clamp(0.0, 1.0)
+        }
+    }
+
+    /// Age by `years`, fast when the upkeep went unpaid. Returns true if
+    /// this broke it down.
+    pub fn wear(&mut self, years: f64, maintained: bool) -> bool {
+        let was_broken = self.is_broken();
+        let rate = if maintained {
+            AGING_PER_YEAR
+        } else {
+            WEAR_PER_YEAR
+        };
+        self.value = (self.value - rate * years).max(0.0);
+        !was_broken && self.is_broken()
+    }
+}
+
+/// Output factor of an entity by its condition; entities that don't wear
+/// work at full output
+pub fn condition_factor_for(conditions: &Query<&Condition>, entity: Entity) -> f64 {
+    conditions.get(entity).map_or(1.0, Condition::efficiency)
+}
+
+/// A facility or fleet waiting for, or under, repair
+#[derive(Debug, Clone, PartialEq)]
+pub struct Repair {
+    pub entity: Entity,
+    /// Colony or station doing the work, `None` for fleets away from one
+    pub location: Option<Entity>,
+    /// Days of work left once started
+    pub days_left: f64,
+    /// Materials paid and work under way
+    pub started: bool,
+}
+
+/// Repairs in the order they were needed
+#[derive(Resource, Debug, Clone, Default)]
+pub struct RepairQueue {
+    pub repairs: Vec<Repair>,
+}
+
+impl RepairQueue {
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.repairs.iter().any(|repair| repair.entity == entity)
+    }
+}
+
+/// Upkeep of the last economy tick, for the maintenance report
+#[derive(Resource, Debug, Clone, Default)]
+pub struct MaintenanceReport {
+    /// Spare parts needed in Mt of Steel per year
+    pub spare_parts_per_year: f64,
+    /// The stockpile could not cover the spare parts
+    pub parts_short: bool,
+    /// Maintenance crews went unpaid
+    pub crews_unpaid: bool,
+}
+
+/// Refined goods a repair costs in Mt
+fn repair_cost(building: Option<&Building>, fleet: Option<&Fleet>) -> Vec<(RefinedGood, f64)> {
+    if let Some(building) = building {
+        REPAIR_COST_PER_LEVEL
+            .iter()
+            .map(|(good, per_level)| (*good, per_level * building.level as f64))
+            .collect()
+    } else if let Some(fleet) = fleet {
+        vec![(
+            RefinedGood::Steel,
+            fleet.dry_mass_t * REPAIR_STEEL_PER_TONNE / TONNES_PER_MT,
+        )]
+    } else {
+        Vec::new()
+    }
+}
+
+/// System that gives new buildings and fleets their condition
+#[allow(clippy::type_complexity)]
+pub fn track_condition(
+    mut commands: Commands,
+    untracked: Query<Entity, (Or<(With<Building>, With<Fleet>)>, Without<Condition>)>,
+) {
+    for entity in untracked.iter() {
+        commands.entity(entity).insert(Condition::default());
+    }
+}
+
+/// System that pays the upkeep of buildings and fleets once per due economy
+/// tick, wears them by whether it was paid, logs breakdowns and queues
+/// repairs
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn update_wear(
+    clock: Res<EconomyClock>,
+    sim_time: Res<SimulationTime>,
+    treasury: Option<Res<Treasury>>,
+    mut budget: ResMut<GlobalBudget>,
+    mut report: ResMut<MaintenanceReport>,
+    mut queue: ResMut<RepairQueue>,
+    mut log: ResMut<EventLog>,
+    mut buildings: Query<(
+        Entity,
+        &Building,
+        &mut Condition,
+        Option<&LogicalParent>,
+        Option<&Name>,
+    )>,
+    mut fleets: Query<(Entity, &Fleet, &mut Condition, Option<&Crew>), Without<Building>>,
+) {
+    if clock.due == 0 {
+        return;
+    }
+    let years = EconomyClock::TICK_YEARS * clock.due as f64;
+
+    let levels: f64 = buildings.iter().map(|(_, b, ..)| b.level as f64).sum();
+    let dry_mass_t: f64 = fleets.iter().map(|(_, f, ..)| f.dry_mass_t).sum();
+    let per_year = levels * SPARE_PARTS_PER_LEVEL_YEAR
+        + dry_mass_t * SPARE_PARTS_PER_TONNE_YEAR / TONNES_PER_MT;
+    let parts_paid = budget.consume_refined(RefinedGood::Steel, per_year * years);
+    let crews_paid = !treasury.is_some_and(|t| t.over_credit_limit());
+    let maintained = parts_paid && crews_paid;
+    *report = MaintenanceReport {
+        spare_parts_per_year: per_year,
+        parts_short: !parts_paid,
+        crews_unpaid: !crews_paid,
+    };
+
+    let date = sim_time.format_date_time();
+    for (entity, _, mut condition, parent, name) in buildings.iter_mut() {
+        if condition.wear(years, maintained) {
+            log.push(
+                date.clone(),
+                LogSeverity::Warning,
+                format!(
+                    "{} has broken down for lack of maintenance",
+                    name.map_or("A facility", |n| n.as_str())
+                ),
+            );
+        }
+        if condition.value < REPAIR_CONDITION && !queue.contains(entity) {
+            queue.repairs.push(Repair {
+                entity,
+                location: Some(parent.map_or(entity, |p| p.0)),
+                days_left: REPAIR_DAYS,
+                started: false,
+            });
+        }
+    }
+
+    for (entity, fleet, mut condition, crew) in fleets.iter_mut() {
+        let crewed = crew.is_none_or(|c| c.missing() == 0);
+        if condition.wear(years, maintained && crewed) {
+            log.push(
+                date.clone(),
+                LogSeverity::Warning,
+                format!("{} has broken down and cannot leave orbit", fleet.name),
+            );
+        }
+        if condition.value < REPAIR_CONDITION && !queue.contains(entity) {
+            queue.repairs.push(Repair {
+                entity,
+                location: None,
+                days_left: REPAIR_DAYS,
+                started: false,
+            });
+        }
+    }
+}
+
+/// System that works through the repair queue once per due economy tick:
+/// one repair per colony or station at a time, started once its materials
+/// can be paid
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn advance_repairs(
+    clock: Res<EconomyClock>,
+    sim_time: Res<SimulationTime>,
+    mut budget: ResMut<GlobalBudget>,
+    mut queue: ResMut<RepairQueue>,
+    mut log: ResMut<EventLog>,
+    mut conditions: Query<(
+        &mut Condition,
+        Option<&Building>,
+        Option<&Fleet>,
+        Option<&FleetLocation>,
+        Option<&Name>,
+    )>,
+    colonies: Query<&Population>,
+) {
+    if clock.due == 0 {
+        return;
+    }
+    let days = clock.due as f64;
+    let date = sim_time.format_date_time();
+
+    // Forget what no longer exists
+    queue
+        .repairs
+        .retain(|repair| conditions.contains(repair.entity));
+
+    let mut busy = HashSet::new();
+    for repair in queue.repairs.iter_mut() {
+        let Ok((_, building, fleet, fleet_location, _)) = conditions.get(repair.entity) else {
+            continue;
+        };
+        // Fleets are repaired by the colony they orbit
+        if fleet.is_some() {
+            repair.location = match fleet_location {
+                Some(FleetLocation::Orbiting(body))
+                    if colonies.get(*body).is_ok_and(|p| p.count > 0.0) =>
+                {
+                    Some(*body)
+                }
+                _ => None,
+            };
+        }
+        let Some(location) = repair.location else {
+            repair.started = false;
+            continue;
+        };
+        if !busy.insert(location) {
+            continue;
+        }
+
+        if !repair.started {
+            let cost = repair_cost(building, fleet);
+            if cost
+                .iter()
+                .any(|(good, amount)| budget.get_refined(good) < *amount)
+            {
+                continue;
+            }
+            for (good, amount) in cost {
+                budget.consume_refined(good, amount);
+            }
+            repair.started = true;
+        }
+        repair.days_left -= days;
+    }
+
+    queue.repairs.retain(|repair| {
+        if repair.days_left > 0.0 {
+            return true;
+        }
+        if let Ok((mut condition, _, fleet, _, name)) = conditions.get_mut(repair.entity) {
+            condition.value = 1.0;
+            let name = fleet
+                .map(|f| f.name.clone())
+                .or_else(|| name.map(|n| n.as_str().to_string()))
+                .unwrap_or_else(|| "A facility".to_string());
+            log.push(
+                date.clone(),
+                LogSeverity::Info,
+                format!("{} has been repaired", name),
+            );
+        }
+        false
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unmaintained_facilities_wear_out_and_break_down() {
+        let mut condition = Condition::default();
+        assert!(!condition.wear(1.0, true));
+        assert!((condition.value - 0.99).abs() < 1e-9);
+        assert!((condition.efficiency() - 0.99).abs() < 1e-9);
+
+        // Three years without upkeep take it below the repair line
+        assert!(!condition.wear(2.0, false));
+        assert!(condition.wear(1.5, false));
+        assert!(condition.is_broken());
+        assert_eq!(condition.efficiency(), 0.0);
+        // Breaking down is reported once
+        assert!(!condition.wear(1.0, false));
+    }
+
+    #[test]
+    fn test_one_repair_per_colony_at_a_time() {
+        let mut app = App::new();
+        app.init_resource::<EconomyClock>()
+            .init_resource::<SimulationTime>()
+            .init_resource::<GlobalBudget>()
+            .init_resource::<RepairQueue>()
+            .init_resource::<EventLog>()
+            .add_systems(Update, advance_repairs);
+
+        let colony = app.world_mut().spawn(Population::new(1e6)).id();
+        let building = |level| Building {
+            id: "iron_mine".to_string(),
+            level,
+        };
+        let first = app
+            .world_mut()
+            .spawn((building(1), Condition { value: 0.3 }))
+            .id();
+        let second = app
+            .world_mut()
+            .spawn((building(2), Condition { value: 0.4 }))
+            .id();
+        {
+            let mut budget = app.world_mut().resource_mut::<GlobalBudget>();
+            budget.add_refined(RefinedGood::Steel, 10.0);
+            budget.add_refined(RefinedGood::Electronics, 10.0);
+        }
+        {
+            let mut queue = app.world_mut().resource_mut::<RepairQueue>();
+            for entity in [first, second] {
+                queue.repairs.push(Repair {
+                    entity,
+                    location: Some(colony),
+                    days_left: REPAIR_DAYS,
+                    started: false,
+                });
+            }
+        }
+
+        let steel = |app: &App| {
+            app.world()
+                .resource::<GlobalBudget>()
+                .get_refined(&RefinedGood::Steel)
+        };
+        let before = steel(&app);
+        app.world_mut().resource_mut::<EconomyClock>().due = REPAIR_DAYS as u64;
+        app.update();
+        assert_eq!(app.world().get::<Condition>(first).unwrap().value, 1.0);
+        assert_eq!(app.world().get::<Condition>(second).unwrap().value, 0.4);
+        assert!((before - steel(&app) - 0.5).abs() < 1e-9);
+
+        app.update();
+        assert_eq!(app.world().get::<Condition>(second).unwrap().value, 1.0);
+        assert!(app.world().resource::<RepairQueue>().repairs.is_empty());
+    }
+}
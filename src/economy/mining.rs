@@ -1,8 +1,17 @@
-use crate::economy::budget::{GlobalBudget, ResourceRateTracker, SECONDS_PER_MONTH, SECONDS_PER_YEAR};
-use crate::economy::components::PlanetResources;
+use crate::astronomy::AtmosphereComposition;
+use crate::economy::budget::{
+    GlobalBudget, PowerGrids, ResourceRateTracker, SECONDS_PER_MONTH, SECONDS_PER_YEAR,
+};
+use crate::economy::components::{MineralDeposit, PlanetResources};
+use crate::economy::harvesting::GasHarvester;
+use crate::economy::maintenance::{condition_factor_for, Condition};
+use crate::economy::regions::SurfaceSite;
+use crate::economy::stability::{output_factor_for, Stability};
+use crate::economy::storage::PowerTier;
+use crate::economy::thermal::HeatBalance;
+use crate::economy::tick::EconomyClock;
 use crate::economy::types::ResourceType;
-use crate::plugins::solar_system::CelestialBody;
-use crate::ui::SimulationTime;
+use crate::plugins::solar_system::{CelestialBody, LogicalParent};
 use bevy::prelude::*;
 
 #[derive(Component, Debug, Clone)]
@@ -23,63 +32,97 @@ impl Default for MiningOperation {
     }
 }
 
-pub fn extract_resources(
-    mut budget: ResMut<GlobalBudget>,
-    mut query: Query<(&mut PlanetResources, &MiningOperation, &mut CelestialBody)>,
-    sim_time: Res<SimulationTime>,
-    mut last_elapsed: Local<f64>,
-) {
-    let current_elapsed = sim_time.elapsed_seconds();
-    let dt = current_elapsed - *last_elapsed;
-    *last_elapsed = current_elapsed;
-
-    if dt <= 0.0 {
-        return;
-    }
+/// Take `demand` Mt out of a deposit, cheapest tier first, and return the
+/// amount actually extracted.
+fn extract_from_deposit(deposit: &mut MineralDeposit, mut demand: f64) -> f64 {
+    let mut total_extracted = 0.0;
 
-    // 1 year = 365.25 days * 24 * 60 * 60
-    let years_elapsed = dt / 31_557_600.0;
+    // 1. Proven Crustal (Cheapest)
+    let taking_proven = demand.min(deposit.reserve.proven_crustal);
+    deposit.reserve.proven_crustal -= taking_proven;
+    total_extracted += taking_proven;
+    demand -= taking_proven;
 
-    if years_elapsed <= 0.0 {
-        return;
+    // 2. Deep Deposits (Expensive)
+    if demand > 0.0 {
+        let taking_deep = demand.min(deposit.reserve.deep_deposits);
+        deposit.reserve.deep_deposits -= taking_deep;
+        total_extracted += taking_deep;
+        demand -= taking_deep;
     }
 
-    for (mut resources, op, mut body) in query.iter_mut() {
-        if !op.active {
-            continue;
-        }
-
-        let mut total_extracted = 0.0;
-
-        if let Some(deposit) = resources.deposits.get_mut(&op.resource_type) {
-            let mut demand = op.base_rate_mt_per_year * years_elapsed;
+    // 3. Planetary Bulk (Exorbitant)
+    if demand > 0.0 {
+        let taking_bulk = demand.min(deposit.reserve.planetary_bulk);
+        deposit.reserve.planetary_bulk -= taking_bulk;
+        total_extracted += taking_bulk;
+    }
 
-            // 1. Proven Crustal (Cheapest)
-            let taking_proven = demand.min(deposit.reserve.proven_crustal);
-            deposit.reserve.proven_crustal -= taking_proven;
-            total_extracted += taking_proven;
-            demand -= taking_proven;
+    total_extracted
+}
 
-            // 2. Deep Deposits (Expensive)
-            if demand > 0.0 {
-                let taking_deep = demand.min(deposit.reserve.deep_deposits);
-                deposit.reserve.deep_deposits -= taking_deep;
-                total_extracted += taking_deep;
-                demand -= taking_deep;
+/// System that mines every active [`MiningOperation`] once per due economy
+/// tick, so the extracted totals do not depend on the frame rate.
+///
+/// An operation mines the body it is attached to: either its own entity, or
+/// for facilities such as mine buildings, the body named by its
+/// [`LogicalParent`]. Mines dig at the rate of their [`SurfaceSite`], and
+/// overheating, unstable and browned-out locations mine at a reduced rate
+/// (see [`HeatBalance`], [`Stability`] and [`PowerGrids`]).
+#[allow(clippy::too_many_arguments)]
+pub fn extract_resources(
+    mut budget: ResMut<GlobalBudget>,
+    operations: Query<(
+        Entity,
+        &MiningOperation,
+        Option<&LogicalParent>,
+        Option<&SurfaceSite>,
+    )>,
+    mut bodies: Query<(&mut PlanetResources, &mut CelestialBody)>,
+    heat: Option<Res<HeatBalance>>,
+    power: Option<Res<PowerGrids>>,
+    stability: Query<&Stability>,
+    conditions: Query<&Condition>,
+    clock: Res<EconomyClock>,
+) {
+    for _ in 0..clock.due {
+        for (entity, op, parent, site) in operations.iter() {
+            if !op.active {
+                continue;
             }
 
-            // 3. Planetary Bulk (Exorbitant)
-            if demand > 0.0 {
-                let taking_bulk = demand.min(deposit.reserve.planetary_bulk);
-                deposit.reserve.planetary_bulk -= taking_bulk;
-                total_extracted += taking_bulk;
-            }
+            let target = if bodies.contains(entity) {
+                entity
+            } else if let Some(parent) = parent {
+                parent.0
+            } else {
+                continue;
+            };
+            let Ok((mut resources, mut body)) = bodies.get_mut(target) else {
+                continue;
+            };
+            let Some(deposit) = resources.deposits.get_mut(&op.resource_type) else {
+                continue;
+            };
+            let throttle = heat
+                .as_ref()
+                .map_or(1.0, |h| h.throttle_for(entity, parent))
+                * power
+                    .as_ref()
+                    .map_or(1.0, |p| p.supply_for(entity, parent, PowerTier::Mining))
+                * output_factor_for(&stability, entity, parent)
+                * condition_factor_for(&conditions, entity)
+                * site.map_or(1.0, |s| s.yield_factor);
+            let total_extracted = extract_from_deposit(
+                deposit,
+                op.base_rate_mt_per_year * throttle * EconomyClock::TICK_YEARS,
+            );
 
-            // Add to global budget
+            // Add to the depot of the mined body
             // Note: GlobalBudget stockpiles are likely in relevant units (unknown if Mt or tons)
             // The budget uses `f64`. Assuming units match (Mt).
             if total_extracted > 0.0 {
-                budget.add_resource(op.resource_type, total_extracted);
+                budget.add_resource_at(target, op.resource_type, total_extracted);
                 // Reduce body mass (1 Mt = 1e9 kg)
                 body.mass -= total_extracted * 1e9;
             }
@@ -91,39 +134,146 @@ pub fn extract_resources(
 /// research/engineering points, writing them into [`ResourceRateTracker`].
 ///
 /// This is purely informational – it does not move any resources.
+#[allow(clippy::too_many_arguments)]
 pub fn update_resource_rates(
     mut tracker: ResMut<ResourceRateTracker>,
-    mining_ops: Query<&MiningOperation>,
-    research_buildings: Query<&crate::research::components::ResearchBuilding>,
-    engineering_facilities: Query<&crate::research::components::EngineeringFacility>,
+    mining_ops: Query<(
+        Entity,
+        &MiningOperation,
+        Option<&LogicalParent>,
+        Option<&SurfaceSite>,
+    )>,
+    harvesters: Query<(Entity, &GasHarvester, &LogicalParent)>,
+    atmospheres: Query<&AtmosphereComposition>,
+    heat: Res<HeatBalance>,
+    power: Option<Res<PowerGrids>>,
+    stability: Query<&Stability>,
+    conditions: Query<&Condition>,
+    research_buildings: Query<(
+        Entity,
+        &crate::research::components::ResearchBuilding,
+        Option<&LogicalParent>,
+    )>,
+    engineering_facilities: Query<(
+        Entity,
+        &crate::research::components::EngineeringFacility,
+        Option<&LogicalParent>,
+    )>,
     research_state: Res<crate::research::ResearchState>,
+    treasury: Option<Res<crate::economy::Treasury>>,
 ) {
+    // Share of its output a facility delivers by its power and condition
+    let running = |entity: Entity, parent: Option<&LogicalParent>, tier: PowerTier| {
+        power
+            .as_ref()
+            .map_or(1.0, |p| p.supply_for(entity, parent, tier))
+            * condition_factor_for(&conditions, entity)
+    };
+
     // --- Resource rates from mining ---
     let mut rates = std::collections::HashMap::new();
-    for op in mining_ops.iter() {
+    let mut location_rates = std::collections::HashMap::new();
+    for (entity, op, parent, site) in mining_ops.iter() {
         if !op.active {
             continue;
         }
         // base_rate_mt_per_year → per month = rate * (month / year)
-        let monthly = op.base_rate_mt_per_year * (SECONDS_PER_MONTH / SECONDS_PER_YEAR);
+        let monthly = op.base_rate_mt_per_year
+            * heat.throttle_for(entity, parent)
+            * running(entity, parent, PowerTier::Mining)
+            * output_factor_for(&stability, entity, parent)
+            * site.map_or(1.0, |s| s.yield_factor)
+            * (SECONDS_PER_MONTH / SECONDS_PER_YEAR);
         *rates.entry(op.resource_type).or_insert(0.0) += monthly;
+        let location = parent.map_or(entity, |p| p.0);
+        *location_rates.entry(location).or_insert(0.0) += monthly;
+    }
+    for (entity, harvester, parent) in harvesters.iter() {
+        let Ok(atmosphere) = atmospheres.get(parent.0) else {
+            continue;
+        };
+        let monthly = harvester.rate_mt_per_year(atmosphere)
+            * heat.throttle_for(entity, Some(parent))
+            * running(entity, Some(parent), PowerTier::Mining)
+            * output_factor_for(&stability, entity, Some(parent))
+            * (SECONDS_PER_MONTH / SECONDS_PER_YEAR);
+        *rates.entry(harvester.resource_type).or_insert(0.0) += monthly;
+        *location_rates.entry(parent.0).or_insert(0.0) += monthly;
     }
     tracker.resource_rates = rates;
+    tracker.location_rates = location_rates;
 
     // --- Research point rate ---
     let research_per_second: f64 = research_buildings
         .iter()
-        .map(|b| b.points_per_second)
+        .map(|(entity, b, parent)| {
+            b.points_per_second * running(entity, parent, PowerTier::Research)
+        })
         .sum();
-    let research_multiplier = research_state.research_speed_multiplier();
+    let research_multiplier = research_state.research_speed_multiplier()
+        * treasury.map_or(1.0, |t| t.research_factor());
     tracker.research_rate_per_month = research_per_second * SECONDS_PER_MONTH * research_multiplier;
 
     // --- Engineering point rate ---
     let engineering_per_second: f64 = engineering_facilities
         .iter()
-        .map(|f| f.points_per_second)
+        .map(|(entity, f, parent)| {
+            f.points_per_second * running(entity, parent, PowerTier::Research)
+        })
         .sum();
     let engineering_multiplier = research_state.engineering_speed_multiplier();
     tracker.engineering_rate_per_month =
         engineering_per_second * SECONDS_PER_MONTH * engineering_multiplier;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::economy::tick::{advance_economy_clock, ECONOMY_TICK_SECONDS};
+    use crate::plugins::solar_system_data::BodyType;
+    use crate::ui::SimulationTime;
+
+    fn mined_iron(frame_seconds: f64, total_seconds: f64) -> f64 {
+        let mut app = App::new();
+        app.init_resource::<SimulationTime>()
+            .init_resource::<GlobalBudget>()
+            .init_resource::<EconomyClock>()
+            .add_systems(Update, (advance_economy_clock, extract_resources).chain());
+
+        let mut resources = PlanetResources::new();
+        resources.add_deposit(
+            ResourceType::Iron,
+            MineralDeposit::new(0.05, 1.0, 10.0, 0.5, 1.0),
+        );
+        app.world_mut().spawn((
+            resources,
+            MiningOperation {
+                resource_type: ResourceType::Iron,
+                base_rate_mt_per_year: 2.0,
+                active: true,
+            },
+            CelestialBody {
+                mass: 1e24,
+                ..CelestialBody::fixture("Test", BodyType::Asteroid)
+            },
+        ));
+
+        let mut elapsed = 0.0;
+        while elapsed < total_seconds {
+            elapsed = (elapsed + frame_seconds).min(total_seconds);
+            app.world_mut().resource_mut::<SimulationTime>().elapsed = elapsed;
+            app.update();
+        }
+        app.world().resource::<GlobalBudget>().get_stockpile(&ResourceType::Iron)
+    }
+
+    #[test]
+    fn test_extraction_independent_of_frame_rate() {
+        let total = 90.0 * ECONOMY_TICK_SECONDS;
+        let slow_frames = mined_iron(7.0 * ECONOMY_TICK_SECONDS, total);
+        let fast_frames = mined_iron(0.3 * ECONOMY_TICK_SECONDS, total);
+
+        assert!(slow_frames > 0.0);
+        assert_eq!(slow_frames, fast_frames);
+    }
+}
@@ -4,27 +4,134 @@
 //! - 15 different resource types (volatiles, construction, noble gases, fissiles, specialty)
 //! - Planetary resource deposits with abundance and accessibility
 //! - Realistic resource generation based on distance from sun (frost line)
-//! - Global budget and stockpile management
-//! - Energy grid tracking and civilization scoring
+//! - Global budget and stockpile management, with stock held per colony and
+//!   station depot and an empire-wide summary
+//! - Per-colony energy grids with optional power relays, and civilization scoring
+//! - A deterministic daily economy tick independent of frame rate
+//! - Survey missions that reveal deposits over time, and deposit maps of
+//!   ground-mapped bodies
+//! - Stockpile history sampled weekly for trend graphs and consumption rates
+//! - Alerts for stockpiles running out and power deficits
+//! - Gas harvesting in giant planet atmospheres with tech-limited depth
+//! - Waste heat and radiators per colony, with overheating throttling output
+//! - Natural population growth of colonies, scaled by technology and difficulty
+//! - Founding new colonies with settlers from an existing one
+//! - Production chains refining raw resources into steel, electronics and fuel
+//! - Food grown by colonies, farms and hydroponics, shipped to colonies that
+//!   cannot feed themselves, with shortages slowing population growth
+//! - Life support from the atmosphere: pressure domes or closed-loop
+//!   habitats that hold the colonists and draw oxygen and water
+//! - Radiation from the host star, gas giant belts and storms, blocked by
+//!   magnetospheres, atmospheres and shelters
+//! - Surface regions with local climate and deposits: colonies settle the
+//!   mildest region and mines the one richest in their resource
+//! - Colony stability from food, housing, jobs, radiation and recent events,
+//!   with unrest reducing output
+//! - Population groups by origin and culture that drift from the capital's
+//!   culture on distant colonies and adapt to their world over generations
+//! - A treasury of credits with taxes, exports, maintenance, wages and
+//!   funding sliders for research and construction, monthly ledgers and debt
+//! - Batteries and capacitors buffering each grid, and brownouts that shed
+//!   mining, industry and research by per-colony priority
+//! - Kardashev milestones reached by power output, unlocking megastructure
+//!   technologies
+//! - Wear of buildings and fleets without spare parts or maintenance crews,
+//!   with breakdowns and a repair queue
+//! - Stock earmarked for queued construction projects and hulls
 
 use bevy::prelude::*;
 
+pub mod alerts;
 pub mod budget;
 pub mod components;
+pub mod demographics;
+pub mod deposit_map;
+pub mod food;
 pub mod generation;
+pub mod harvesting;
+pub mod history;
+pub mod kardashev;
+pub mod life_support;
+pub mod maintenance;
 pub mod mining;
+pub mod population;
+pub mod production;
+pub mod radiation;
+pub mod regions;
+pub mod reservations;
+pub mod stability;
+pub mod storage;
+pub mod survey;
+pub mod thermal;
+pub mod tick;
+pub mod treasury;
 pub mod types;
 
+pub use alerts::{update_alerts, ActiveAlerts, Alert, AlertKind, AlertSettings};
 pub use budget::{
-    format_power, update_civilization_score, update_power_grid, EnergyGrid, GlobalBudget,
-    ResourceRateTracker, SECONDS_PER_MONTH, SECONDS_PER_YEAR,
+    format_power, settle_depots, update_civilization_score, update_power_grid, Depot, EnergyGrid,
+    GlobalBudget, LocalGrid, PowerGrids, ResourceRateTracker, POWER_RELAY_MECHANIC,
+    SECONDS_PER_MONTH, SECONDS_PER_YEAR,
 };
 pub use components::{
-    MineralDeposit, OrbitsBody, PlanetResources, PowerGenerator, PowerSourceType, SpectralClass,
-    StarSystem,
+    HeatSource, MineralDeposit, OrbitsBody, PlanetResources, PowerConsumer, PowerGenerator,
+    PowerGridHost, PowerRelay, PowerSourceType, PowerStorage, Radiator, SpectralClass, StarSystem,
+};
+pub use demographics::{
+    capital_position, drift_cultures, drift_rate, environment_traits, INDEPENDENCE_DIVERGENCE,
+    INDEPENDENCE_MOVEMENT_SUPPORT,
+};
+pub use food::{
+    farm_yield_factor, fed_growth_rate, native_food_fraction, update_food_supply, Farm,
+    FoodSupply, FOOD_PER_PERSON_YEAR_MT,
+};
+pub use generation::{
+    generate_ring_resources, generate_solar_system_resources, rescale_deposits_for_difficulty,
+};
+pub use harvesting::{harvest_gas, update_harvest_depths, GasHarvester};
+pub use history::{record_stockpile_history, StockpileHistory, StockpileSample};
+pub use kardashev::{
+    current_milestone, next_milestone, power_for_index, update_kardashev_milestones,
+    KardashevMilestone, KardashevMilestoneReached, KARDASHEV_MILESTONES,
+};
+pub use life_support::{
+    habitat_capacity, life_support_demand, update_life_support, LifeSupport, LifeSupportModule,
+};
+pub use maintenance::{
+    advance_repairs, condition_factor_for, track_condition, update_wear, Condition,
+    MaintenanceReport, Repair, RepairQueue, BREAKDOWN_CONDITION, REPAIR_CONDITION,
 };
-pub use generation::generate_solar_system_resources;
 pub use mining::{extract_resources, update_resource_rates, MiningOperation};
+pub use population::{
+    body_colony_cost, found_requested_colonies, grow_population, population_growth_rate,
+    ColonyFounded, ColonyFoundings, BASE_POPULATION_GROWTH_PER_YEAR, FOUNDING_COLONISTS,
+};
+pub use production::{run_refineries, ChainStatus, ProductionStats, RefinedGood, Refinery};
+pub use radiation::{
+    radiation_decline, surface_dose, update_radiation, Radiation, RadiationShelter, STATION_DOSE,
+};
+pub use regions::{
+    site_colonies, site_surface_buildings, surface_regions, ColonySite, SurfaceRegion, SurfaceSite,
+    Terrain,
+};
+pub use reservations::{update_reservations, Reservations};
+pub use stability::{
+    output_factor_for, update_stability, Stability, StabilityFactor, StabilityInputs,
+    BASE_STABILITY, UNREST_STABILITY,
+};
+pub use storage::{
+    apply_power_priorities, charge_power_storage, shed_load, storage_discharge, PowerPriorities,
+    PowerPriorityChanges, PowerTier,
+};
+pub use survey::{
+    advance_survey_missions, draw_survey_probes, launch_survey_missions, log_survey_reports,
+    SurveyMission, SurveyMissions, SurveyReport,
+};
+pub use thermal::{update_heat_balance, HeatBalance, LocalHeat};
+pub use tick::{advance_economy_clock, EconomyClock, ECONOMY_TICK_SECONDS};
+pub use treasury::{
+    format_currency, update_treasury, Ledger, LedgerLine, Treasury, MAX_FUNDING,
+};
 pub use types::ResourceType;
 
 /// Plugin that adds the economy system to the Bevy app
@@ -36,22 +143,112 @@ impl Plugin for EconomyPlugin {
             // Resources
             .init_resource::<GlobalBudget>()
             .init_resource::<ResourceRateTracker>()
+            .init_resource::<EconomyClock>()
+            .init_resource::<PowerGrids>()
+            .init_resource::<HeatBalance>()
+            .init_resource::<SurveyMissions>()
+            .init_resource::<ColonyFoundings>()
+            .init_resource::<ProductionStats>()
+            .init_resource::<StockpileHistory>()
+            .init_resource::<AlertSettings>()
+            .init_resource::<ActiveAlerts>()
+            .init_resource::<Treasury>()
+            .init_resource::<PowerPriorityChanges>()
+            .init_resource::<MaintenanceReport>()
+            .init_resource::<RepairQueue>()
+            .init_resource::<Reservations>()
+            .add_event::<SurveyReport>()
+            .add_event::<ColonyFounded>()
+            .add_event::<KardashevMilestoneReached>()
             // Startup systems
             .add_systems(
                 Startup,
-                generate_solar_system_resources.after(
+                (generate_solar_system_resources, generate_ring_resources).after(
                     // Run after solar system is set up
                     crate::plugins::solar_system::setup_solar_system,
                 ),
             )
-            // Update systems
+            // Simulation ticks run on the fixed timestep alongside orbit propagation
             .add_systems(
-                Update,
+                FixedUpdate,
                 (
                     update_power_grid,
                     update_civilization_score.after(update_power_grid),
-                    extract_resources,
+                    update_kardashev_milestones.after(update_power_grid),
+                    charge_power_storage
+                        .after(advance_economy_clock)
+                        .after(update_power_grid),
+                    update_heat_balance,
+                    advance_economy_clock,
+                    extract_resources
+                        .after(advance_economy_clock)
+                        .after(update_heat_balance)
+                        .after(update_power_grid),
+                    harvest_gas
+                        .after(advance_economy_clock)
+                        .after(update_heat_balance)
+                        .after(update_power_grid),
+                    advance_survey_missions.after(advance_economy_clock),
+                    update_food_supply
+                        .after(advance_economy_clock)
+                        .after(update_heat_balance),
+                    update_life_support.after(advance_economy_clock),
+                    update_radiation.after(advance_economy_clock),
+                    grow_population
+                        .after(advance_economy_clock)
+                        .after(update_food_supply)
+                        .after(update_life_support)
+                        .after(update_radiation),
+                    drift_cultures
+                        .after(advance_economy_clock)
+                        .after(update_radiation),
+                    update_stability
+                        .after(advance_economy_clock)
+                        .after(update_food_supply)
+                        .after(update_radiation)
+                        .after(drift_cultures),
+                    run_refineries
+                        .after(extract_resources)
+                        .after(harvest_gas),
+                    update_treasury.after(advance_economy_clock),
+                    update_wear.after(update_treasury),
+                    advance_repairs.after(update_wear),
+                    record_stockpile_history
+                        .after(extract_resources)
+                        .after(harvest_gas)
+                        .after(run_refineries),
+                ),
+            )
+            // Earmarks are settled before projects draw on the stockpile
+            .add_systems(
+                FixedUpdate,
+                update_reservations
+                    .after(advance_economy_clock)
+                    .before(crate::construction::advance_construction)
+                    .before(crate::fleets::advance_shipyards),
+            )
+            // Update systems
+            .add_systems(
+                Update,
+                (
                     update_resource_rates,
+                    update_alerts.after(update_resource_rates),
+                    settle_depots,
+                    // Bodies spawned after startup (e.g. promoted belt asteroids)
+                    generate_solar_system_resources,
+                    generate_ring_resources,
+                    rescale_deposits_for_difficulty
+                        .before(generate_solar_system_resources)
+                        .before(generate_ring_resources),
+                    update_harvest_depths,
+                    launch_survey_missions,
+                    log_survey_reports,
+                    found_requested_colonies,
+                    apply_power_priorities,
+                    track_condition,
+                    draw_survey_probes,
+                    site_colonies,
+                    site_surface_buildings.after(crate::construction::apply_building_effects),
                 ),
             );
     }
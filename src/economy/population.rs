@@ -0,0 +1,322 @@
+use bevy::prelude::*;
+
+use crate::astronomy::components::SystemId;
+use crate::astronomy::{calculate_general_colony_cost, AtmosphereComposition, SurfaceTemperature};
+use crate::economy::components::Population;
+use crate::economy::food::{fed_growth_rate, FoodSupply};
+use crate::economy::life_support::LifeSupport;
+use crate::economy::radiation::{radiation_decline, Radiation};
+use crate::economy::tick::EconomyClock;
+use crate::game_state::{Difficulty, EventLog, LogSeverity};
+use crate::hotseat::{Hotseat, Seat};
+use crate::independence::Independent;
+use crate::plugins::solar_system::CelestialBody;
+use crate::plugins::solar_system_data::BodyType;
+use crate::research::types::ModifierType;
+use crate::research::ResearchState;
+
+/// Natural growth of a colony population per year, before technology and
+/// difficulty (roughly Earth's rate in the 2020s)
+pub const BASE_POPULATION_GROWTH_PER_YEAR: f64 = 0.009;
+
+/// Yearly growth rate of colony populations: the base rate raised by the
+/// `PopulationGrowth` technology bonus (%) and scaled by the difficulty
+pub fn population_growth_rate(
+    research_state: Option<&ResearchState>,
+    difficulty: Difficulty,
+) -> f64 {
+    let bonus = research_state.map_or(0.0, |r| r.get_modifier(ModifierType::PopulationGrowth));
+    BASE_POPULATION_GROWTH_PER_YEAR
+        * (1.0 + bonus / 100.0)
+        * difficulty.population_growth_multiplier()
+}
+
+/// System that grows every populated body once per due economy tick.
+/// Colonies short of food, air or water grow slower or shrink (see
+/// [`fed_growth_rate`]); overcrowded habitats slow growth, and radiation
+/// kills colonists on top (see [`radiation_decline`]).
+/// Stations are crewed to their habitat capacity instead.
+#[allow(clippy::type_complexity)]
+pub fn grow_population(
+    clock: Res<EconomyClock>,
+    research_state: Option<Res<ResearchState>>,
+    difficulty: Option<Res<Difficulty>>,
+    mut colonies: Query<
+        (
+            &mut Population,
+            Option<&FoodSupply>,
+            Option<&LifeSupport>,
+            Option<&Radiation>,
+        ),
+        With<CelestialBody>,
+    >,
+) {
+    if clock.due == 0 {
+        return;
+    }
+    let rate = population_growth_rate(
+        research_state.as_deref(),
+        difficulty.map_or(Difficulty::Normal, |d| *d),
+    );
+    let years = EconomyClock::TICK_YEARS * clock.due as f64;
+    let factor = (1.0 + rate).powf(years);
+
+    for (mut population, food, life_support, radiation) in colonies.iter_mut() {
+        if population.count <= 0.0 {
+            continue;
+        }
+        // Missing air and water hurt like hunger
+        let shortage = food
+            .map_or(0.0, |f| f.shortage)
+            .max(life_support.map_or(0.0, |l| l.shortage));
+        let overcrowding = life_support.map_or(0.0, |l| l.overcrowding);
+        population.count *= if shortage > 0.0 {
+            (1.0 + fed_growth_rate(rate, shortage)).powf(years)
+        } else if overcrowding > 0.0 {
+            // Only the colonists with room in the habitats raise families
+            (1.0 + rate * (1.0 - overcrowding)).powf(years)
+        } else {
+            factor
+        };
+        if let Some(radiation) = radiation {
+            population.count *= (1.0 - radiation_decline(radiation.dose)).powf(years);
+        }
+    }
+}
+
+/// Settlers that leave an existing colony to found a new one
+pub const FOUNDING_COLONISTS: f64 = 10_000.0;
+
+/// Bodies the player asked to found a colony on
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ColonyFoundings {
+    pub requested: Vec<Entity>,
+}
+
+impl ColonyFoundings {
+    /// Request a colony on `body`
+    pub fn request(&mut self, body: Entity) {
+        self.requested.push(body);
+    }
+}
+
+/// Sent when a new colony is founded
+#[derive(Event, Debug, Clone)]
+pub struct ColonyFounded {
+    pub body: Entity,
+    pub body_name: String,
+    /// Colony the settlers came from
+    pub source: Entity,
+}
+
+/// Why no colony can be founded on a body, or `None` if one can.
+/// Stars, rings and bodies without a finite colony cost are out of reach.
+pub fn colony_site_problem(body_type: BodyType, colony_cost: f32) -> Option<&'static str> {
+    match body_type {
+        BodyType::Star => Some("stars cannot be settled"),
+        BodyType::Ring => Some("rings cannot be settled"),
+        _ if !colony_cost.is_finite() => Some("its surface gravity is too high"),
+        _ => None,
+    }
+}
+
+/// Colony cost of a body. Bodies without a known temperature count as
+/// frozen, as in the selection panel.
+pub fn body_colony_cost(
+    body: &CelestialBody,
+    temperature: Option<&SurfaceTemperature>,
+    atmosphere: Option<&AtmosphereComposition>,
+) -> f32 {
+    let temperature_celsius = temperature
+        .map(|t| t.average_celsius)
+        .or_else(|| atmosphere.map(|a| a.surface_temperature_celsius))
+        .unwrap_or(-273.15);
+    calculate_general_colony_cost(body.surface_gravity(), temperature_celsius, atmosphere)
+}
+
+/// System that founds the requested colonies. The settlers come from the
+/// most populated colony in the same star system, which must keep at least
+/// as many people as it sends. In a hotseat game that colony must answer
+/// to the player who ordered the founding.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub fn found_requested_colonies(
+    mut commands: Commands,
+    mut foundings: ResMut<ColonyFoundings>,
+    mut bodies: Query<(
+        Entity,
+        &CelestialBody,
+        Option<&mut Population>,
+        Option<&SystemId>,
+        Option<&SurfaceTemperature>,
+        Option<&AtmosphereComposition>,
+        Option<&Independent>,
+        Option<&Seat>,
+    )>,
+    mut founded: EventWriter<ColonyFounded>,
+    mut log: Option<ResMut<EventLog>>,
+    sim_time: Option<Res<crate::ui::SimulationTime>>,
+    hotseat: Option<Res<Hotseat>>,
+) {
+    let requested: Vec<Entity> = foundings.requested.drain(..).collect();
+    for target in requested {
+        let Ok((_, body, population, system, temperature, atmosphere, independent, _)) =
+            bodies.get(target)
+        else {
+            continue;
+        };
+        let name = body.name.clone();
+        let system_id = system.map_or(0, |s| s.0);
+        let cost = body_colony_cost(body, temperature, atmosphere);
+
+        let problem = if population.is_some_and(|p| p.count > 0.0) {
+            Some("it is already a colony")
+        } else if independent.is_some() {
+            Some("it is an independent colony")
+        } else {
+            colony_site_problem(body.body_type, cost)
+        };
+        let claimed = hotseat.as_ref().and_then(|hotseat| {
+            hotseat
+                .colony_claims
+                .iter()
+                .find(|(body, _)| *body == target)
+                .map(|(_, seat)| *seat)
+        });
+        let source = bodies
+            .iter()
+            .filter(|(entity, _, population, system, .., seat)| {
+                *entity != target
+                    && population.is_some_and(|p| p.count >= 2.0 * FOUNDING_COLONISTS)
+                    && system.map_or(0, |s| s.0) == system_id
+                    && claimed.is_none_or(|claimed| seat.is_some_and(|s| s.0 == claimed))
+            })
+            .max_by(|a, b| {
+                let count = |p: Option<&Population>| p.map_or(0.0, |p| p.count);
+                count(a.2).total_cmp(&count(b.2))
+            })
+            .map(|(entity, body, ..)| (entity, body.name.clone()));
+
+        let (severity, message) = match (problem, source) {
+            (Some(problem), _) => (
+                LogSeverity::Warning,
+                format!("Cannot found a colony on {}: {}", name, problem),
+            ),
+            (None, None) => (
+                LogSeverity::Warning,
+                format!(
+                    "Cannot found a colony on {}: no colony in the system can spare settlers",
+                    name
+                ),
+            ),
+            (None, Some((source, source_name))) => {
+                let mut settlers = Vec::new();
+                if let Ok((.., Some(mut population), _, _, _, _, _)) = bodies.get_mut(source) {
+                    population.count -= FOUNDING_COLONISTS;
+                    settlers = population.settlers(&source_name);
+                }
+                match bodies.get_mut(target) {
+                    Ok((.., Some(mut population), _, _, _, _, _)) => {
+                        population.count = FOUNDING_COLONISTS;
+                        population.groups = settlers;
+                    }
+                    _ => {
+                        commands.entity(target).insert(Population {
+                            count: FOUNDING_COLONISTS,
+                            groups: settlers,
+                        });
+                    }
+                }
+                founded.send(ColonyFounded {
+                    body: target,
+                    body_name: name.clone(),
+                    source,
+                });
+                (
+                    LogSeverity::Info,
+                    format!(
+                        "Colony founded on {} with {:.0} settlers from {}",
+                        name, FOUNDING_COLONISTS, source_name
+                    ),
+                )
+            }
+        };
+        info!("{}", message);
+        if let (Some(log), Some(sim_time)) = (log.as_mut(), sim_time.as_ref()) {
+            log.push(sim_time.format_date_time(), severity, message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::economy::{advance_economy_clock, ECONOMY_TICK_SECONDS, SECONDS_PER_YEAR};
+    use crate::ui::SimulationTime;
+
+    fn colony(app: &mut App, count: f64) -> Entity {
+        app.world_mut()
+            .spawn((
+                CelestialBody::fixture("Mars", BodyType::Planet),
+                Population::new(count),
+            ))
+            .id()
+    }
+
+    #[test]
+    fn test_growth_follows_difficulty() {
+        let mut app = App::new();
+        app.init_resource::<SimulationTime>()
+            .init_resource::<EconomyClock>()
+            .insert_resource(Difficulty::Brutal)
+            .add_systems(Update, (advance_economy_clock, grow_population).chain());
+        let mars = colony(&mut app, 1000.0);
+        let empty = colony(&mut app, 0.0);
+
+        let days = (SECONDS_PER_YEAR / ECONOMY_TICK_SECONDS).floor();
+        app.world_mut().resource_mut::<SimulationTime>().elapsed = days * ECONOMY_TICK_SECONDS;
+        app.update();
+
+        let rate =
+            BASE_POPULATION_GROWTH_PER_YEAR * Difficulty::Brutal.population_growth_multiplier();
+        let expected = 1000.0 * (1.0 + rate).powf(days * EconomyClock::TICK_YEARS);
+        let count = app.world().get::<Population>(mars).unwrap().count;
+        assert!((count - expected).abs() < 1e-9);
+        assert!(count > 1000.0 && count < 1000.0 * (1.0 + BASE_POPULATION_GROWTH_PER_YEAR));
+        assert_eq!(app.world().get::<Population>(empty).unwrap().count, 0.0);
+    }
+
+    #[test]
+    fn test_colony_founded_from_largest_colony() {
+        let mut app = App::new();
+        app.init_resource::<ColonyFoundings>()
+            .add_event::<ColonyFounded>()
+            .add_systems(Update, found_requested_colonies);
+        let earth = colony(&mut app, 50_000.0);
+        let outpost = colony(&mut app, 15_000.0);
+        let site = colony(&mut app, 0.0);
+
+        app.world_mut().resource_mut::<ColonyFoundings>().request(site);
+        app.update();
+        let count = |app: &App, body| app.world().get::<Population>(body).unwrap().count;
+        assert_eq!(count(&app, site), FOUNDING_COLONISTS);
+        assert_eq!(count(&app, earth), 50_000.0 - FOUNDING_COLONISTS);
+        assert_eq!(count(&app, outpost), 15_000.0);
+        let founded = app.world().resource::<Events<ColonyFounded>>();
+        let founded: Vec<_> = founded.get_reader().read(founded).cloned().collect();
+        assert_eq!(founded.len(), 1);
+        assert_eq!((founded[0].body, founded[0].source), (site, earth));
+
+        // A second request for the same body is refused
+        app.world_mut().resource_mut::<ColonyFoundings>().request(site);
+        app.update();
+        assert_eq!(count(&app, site), FOUNDING_COLONISTS);
+        assert_eq!(count(&app, earth), 50_000.0 - FOUNDING_COLONISTS);
+    }
+
+    #[test]
+    fn test_colony_sites() {
+        assert!(colony_site_problem(BodyType::Moon, 3.5).is_none());
+        assert!(colony_site_problem(BodyType::Star, 0.0).is_some());
+        assert!(colony_site_problem(BodyType::GasGiant, f32::INFINITY).is_some());
+    }
+}
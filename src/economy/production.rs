@@ -0,0 +1,270 @@
+//! Production chains: refined goods made from raw resources
+//!
+//! Refineries and factories ([`Refinery`]) turn raw resources from the
+//! stockpile into refined goods by fixed recipes, once per economy tick.
+//! Construction and ordnance manufacture are paid in refined goods, so
+//! mined ore only becomes useful once it has been through a chain. A refinery
+//! short of one input runs at the fraction the scarcest input allows;
+//! [`ProductionStats`] records the output and the bottleneck of every chain.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::budget::{GlobalBudget, PowerGrids};
+use super::maintenance::{condition_factor_for, Condition};
+use super::stability::{output_factor_for, Stability};
+use super::storage::PowerTier;
+use super::thermal::HeatBalance;
+use super::tick::EconomyClock;
+use super::types::ResourceType;
+use crate::plugins::solar_system::LogicalParent;
+
+/// A good refined from raw resources
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum RefinedGood {
+    /// Structural steel for hulls, frames and habitats
+    Steel,
+    /// Circuits, sensors and control systems
+    Electronics,
+    /// Hydrolox propellant for missiles and ships
+    Fuel,
+}
+
+impl RefinedGood {
+    /// Returns all refined goods in a stable order
+    pub fn all() -> &'static [RefinedGood] {
+        &[
+            RefinedGood::Steel,
+            RefinedGood::Electronics,
+            RefinedGood::Fuel,
+        ]
+    }
+
+    /// Returns the display name of the good
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            RefinedGood::Steel => "Steel",
+            RefinedGood::Electronics => "Electronics",
+            RefinedGood::Fuel => "Fuel",
+        }
+    }
+
+    /// Raw resources consumed per Megaton of output
+    pub fn recipe(&self) -> &'static [(ResourceType, f64)] {
+        match self {
+            RefinedGood::Steel => &[(ResourceType::Iron, 1.1), (ResourceType::Methane, 0.05)],
+            RefinedGood::Electronics => &[
+                (ResourceType::Copper, 0.6),
+                (ResourceType::Silicates, 0.4),
+                (ResourceType::RareEarths, 0.05),
+                (ResourceType::Gold, 0.01),
+            ],
+            RefinedGood::Fuel => &[(ResourceType::Hydrogen, 0.15), (ResourceType::Oxygen, 0.85)],
+        }
+    }
+}
+
+impl std::fmt::Display for RefinedGood {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.display_name())
+    }
+}
+
+/// A refinery or factory turning raw resources into a refined good
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct Refinery {
+    pub good: RefinedGood,
+    /// Output at full supply in Megatons per year
+    pub mt_per_year: f64,
+}
+
+/// Output of one production chain over the last economy tick
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChainStatus {
+    /// Refineries working on the chain
+    pub refineries: usize,
+    /// Output at full supply in Megatons per year
+    pub capacity_mt_per_year: f64,
+    /// Actual output in Megatons per year
+    pub output_mt_per_year: f64,
+    /// Input that ran short, if any
+    pub bottleneck: Option<ResourceType>,
+}
+
+impl ChainStatus {
+    /// Output as a fraction of capacity (0.0 to 1.0)
+    pub fn utilization(&self) -> f64 {
+        if self.capacity_mt_per_year <= 0.0 {
+            0.0
+        } else {
+            (self.output_mt_per_year / self.capacity_mt_per_year).min(1.0)
+        }
+    }
+}
+
+/// Status of every production chain, refreshed each economy tick
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ProductionStats {
+    pub chains: HashMap<RefinedGood, ChainStatus>,
+}
+
+impl ProductionStats {
+    pub fn chain(&self, good: RefinedGood) -> ChainStatus {
+        self.chains.get(&good).cloned().unwrap_or_default()
+    }
+}
+
+/// Produce up to `demand` Mt of `good` from the stockpile, drawing the inputs
+/// from and storing the output in the depot at `location`, if any. Returns
+/// the amount produced and the input that limited it, if any.
+pub fn refine(
+    budget: &mut GlobalBudget,
+    location: Option<Entity>,
+    good: RefinedGood,
+    demand: f64,
+) -> (f64, Option<ResourceType>) {
+    if demand <= 0.0 {
+        return (0.0, None);
+    }
+    let mut fraction = 1.0_f64;
+    let mut bottleneck = None;
+    for (resource, per_mt) in good.recipe() {
+        let available = budget.get_stockpile(resource) / (per_mt * demand);
+        if available < fraction {
+            fraction = available;
+            bottleneck = Some(*resource);
+        }
+    }
+
+    let produced = demand * fraction;
+    if produced > 0.0 {
+        for (resource, per_mt) in good.recipe() {
+            let amount = (per_mt * produced).min(budget.get_stockpile(resource));
+            match location {
+                Some(location) => budget.consume_resource_at(location, *resource, amount),
+                None => budget.consume_resource(*resource, amount),
+            };
+        }
+        match location {
+            Some(location) => budget.add_refined_at(location, good, produced),
+            None => budget.add_refined(good, produced),
+        }
+    }
+    (produced, bottleneck)
+}
+
+/// System that runs every refinery once per due economy tick. Overheating
+/// and unstable locations refine at a reduced rate (see [`HeatBalance`] and
+/// [`Stability`]), as do browned out ones (see [`PowerGrids`]) and worn
+/// refineries (see [`Condition`]).
+#[allow(clippy::too_many_arguments)]
+pub fn run_refineries(
+    clock: Res<EconomyClock>,
+    mut budget: ResMut<GlobalBudget>,
+    mut stats: ResMut<ProductionStats>,
+    heat: Option<Res<HeatBalance>>,
+    power: Option<Res<PowerGrids>>,
+    stability: Query<&Stability>,
+    conditions: Query<&Condition>,
+    refineries: Query<(Entity, &Refinery, Option<&LogicalParent>)>,
+) {
+    if clock.due == 0 {
+        return;
+    }
+
+    let mut chains: HashMap<RefinedGood, ChainStatus> = HashMap::new();
+    for (_, refinery, _) in refineries.iter() {
+        let chain = chains.entry(refinery.good).or_default();
+        chain.refineries += 1;
+        chain.capacity_mt_per_year += refinery.mt_per_year;
+    }
+
+    let years = EconomyClock::TICK_YEARS * clock.due as f64;
+    for _ in 0..clock.due {
+        for (entity, refinery, parent) in refineries.iter() {
+            let throttle = heat
+                .as_ref()
+                .map_or(1.0, |h| h.throttle_for(entity, parent))
+                * power
+                    .as_ref()
+                    .map_or(1.0, |p| p.supply_for(entity, parent, PowerTier::Industry))
+                * output_factor_for(&stability, entity, parent)
+                * condition_factor_for(&conditions, entity);
+            let demand = refinery.mt_per_year * throttle * EconomyClock::TICK_YEARS;
+            let location = parent.map(|p| p.0);
+            let (produced, bottleneck) = refine(&mut budget, location, refinery.good, demand);
+
+            let chain = chains.entry(refinery.good).or_default();
+            chain.output_mt_per_year += produced / years;
+            if bottleneck.is_some() {
+                chain.bottleneck = bottleneck;
+            }
+        }
+    }
+
+    if stats.chains != chains {
+        stats.chains = chains;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::economy::tick::{advance_economy_clock, ECONOMY_TICK_SECONDS};
+    use crate::ui::SimulationTime;
+
+    #[test]
+    fn test_refining_is_limited_by_scarcest_input() {
+        let mut budget = GlobalBudget::new();
+        budget.stockpiles.clear();
+        budget.refined.clear();
+        budget.add_resource(ResourceType::Iron, 11.0);
+        budget.add_resource(ResourceType::Methane, 0.25);
+
+        // Methane allows 5 Mt of steel, iron 10 Mt
+        let (produced, bottleneck) = refine(&mut budget, None, RefinedGood::Steel, 8.0);
+        assert!((produced - 5.0).abs() < 1e-9);
+        assert_eq!(bottleneck, Some(ResourceType::Methane));
+        assert!((budget.get_refined(&RefinedGood::Steel) - 5.0).abs() < 1e-9);
+        assert!((budget.get_stockpile(&ResourceType::Iron) - 5.5).abs() < 1e-9);
+        assert!(budget.get_stockpile(&ResourceType::Methane).abs() < 1e-9);
+
+        // Nothing left to refine
+        let (produced, _) = refine(&mut budget, None, RefinedGood::Steel, 1.0);
+        assert!(produced < 1e-9);
+    }
+
+    #[test]
+    fn test_refineries_run_on_economy_ticks() {
+        let mut app = App::new();
+        app.init_resource::<SimulationTime>()
+            .init_resource::<EconomyClock>()
+            .init_resource::<GlobalBudget>()
+            .init_resource::<ProductionStats>()
+            .add_systems(Update, (advance_economy_clock, run_refineries).chain());
+        {
+            let mut budget = app.world_mut().resource_mut::<GlobalBudget>();
+            budget.refined.clear();
+            budget.add_resource(ResourceType::Hydrogen, 100.0);
+            budget.add_resource(ResourceType::Oxygen, 100.0);
+        }
+        app.world_mut().spawn(Refinery {
+            good: RefinedGood::Fuel,
+            mt_per_year: 365.25,
+        });
+
+        app.world_mut().resource_mut::<SimulationTime>().elapsed = 10.0 * ECONOMY_TICK_SECONDS;
+        app.update();
+
+        let budget = app.world().resource::<GlobalBudget>();
+        assert!((budget.get_refined(&RefinedGood::Fuel) - 10.0).abs() < 1e-9);
+        let chain = app
+            .world()
+            .resource::<ProductionStats>()
+            .chain(RefinedGood::Fuel);
+        assert_eq!(chain.refineries, 1);
+        assert!((chain.utilization() - 1.0).abs() < 1e-9);
+        assert_eq!(chain.bottleneck, None);
+    }
+}
@@ -0,0 +1,377 @@
+//! Radiation environment
+//!
+//! Every colony on a body has a [`Radiation`] dose, measured against open
+//! space at 1 AU from the Sun (1.0). It follows from the host star's activity
+//! and distance plus the galactic cosmic rays that reach everywhere, the
+//! belts of trapped particles around gas giants their moons sit in, and what
+//! the body blocks: a magnetosphere deflects the stellar wind, the ground
+//! hides half the sky and a thick atmosphere stops the rest. Radiation
+//! shelters (the `Shielding` building effect) protect the colonists they
+//! house. Solar storms multiply the dose for a few days.
+//!
+//! The dose counts against a colony's stability and, above [`SAFE_DOSE`],
+//! slows its growth ([`radiation_decline`]). Stations carry their own
+//! shielding and get [`STATION_DOSE`].
+
+use bevy::prelude::*;
+
+use super::components::{Population, SpectralClass, StarSystem};
+use super::tick::EconomyClock;
+use crate::astronomy::visual_scale::AU_KM;
+use crate::astronomy::{
+    AtmosphereComposition, KeplerOrbit, OrbitCenter, SpaceCoordinates, EARTH_MASS_KG,
+};
+use crate::game_state::{EventLog, LogSeverity};
+use crate::plugins::solar_system::{CelestialBody, LogicalParent, RotationSpeed};
+use crate::plugins::solar_system_data::BodyType;
+
+/// Share of the dose in open space at 1 AU from the Sun that comes from
+/// galactic cosmic rays rather than the Sun
+pub const COSMIC_RAY_SHARE: f64 = 0.3;
+/// Highest dose in open space, close in to flaring stars
+pub const MAX_SPACE_DOSE: f64 = 20.0;
+/// Dose of the belts of trapped particles at [`BELT_REFERENCE_RADII`] from a
+/// gas giant (about Europa's orbit around Jupiter)
+pub const BELT_DOSE: f64 = 4.0;
+/// Distance from a gas giant in its radii at which the belts give
+/// [`BELT_DOSE`]; they fall off with the square of the distance
+pub const BELT_REFERENCE_RADII: f64 = 10.0;
+/// Surface pressure in millibars that lets through 1/e of the radiation
+pub const ATMOSPHERE_SHIELDING_MBAR: f64 = 300.0;
+/// Share of the sky the ground hides from colonists on a surface
+pub const GROUND_SHADOW: f64 = 0.5;
+/// Share of the dose radiation shelters keep from the colonists inside
+pub const SHELTER_PROTECTION: f64 = 0.9;
+/// Dose of colonists aboard stations, which carry their own shielding
+pub const STATION_DOSE: f64 = 0.5;
+/// Dose colonists live with without harm
+pub const SAFE_DOSE: f64 = 0.1;
+/// Share of a colony lost per year and unit of dose above [`SAFE_DOSE`]
+pub const DECLINE_PER_DOSE: f64 = 0.02;
+/// Largest share of a colony radiation kills in a year
+pub const MAX_RADIATION_DECLINE: f64 = 0.1;
+
+/// Activity of a star relative to the Sun: the flare stars among red dwarfs
+/// throw out far more particles for their size
+pub fn stellar_activity(class: SpectralClass) -> f64 {
+    match class {
+        SpectralClass::O | SpectralClass::B => 2.0,
+        SpectralClass::A => 1.0,
+        SpectralClass::F => 0.8,
+        SpectralClass::G => 1.0,
+        SpectralClass::K => 1.5,
+        SpectralClass::M => 4.0,
+    }
+}
+
+/// Dose in open space at `distance_au` from a star of `activity`
+pub fn space_dose(activity: f64, distance_au: f64) -> f64 {
+    let stellar = (1.0 - COSMIC_RAY_SHARE) * activity / distance_au.max(1e-3).powi(2);
+    (COSMIC_RAY_SHARE + stellar).min(MAX_SPACE_DOSE)
+}
+
+/// Share of the stellar wind a body's own magnetosphere deflects. Gas
+/// giants have strong fields; rocky bodies need the mass to keep a molten
+/// core and a fast spin to drive a dynamo.
+pub fn magnetosphere_shielding(
+    body_type: BodyType,
+    mass_kg: f64,
+    rotation_period_days: Option<f64>,
+) -> f64 {
+    match body_type {
+        BodyType::GasGiant => 0.9,
+        BodyType::Planet | BodyType::DwarfPlanet | BodyType::Moon => {
+            let fast_spin = rotation_period_days.is_some_and(|days| days.abs() <= 3.0);
+            if mass_kg >= 0.3 * EARTH_MASS_KG && fast_spin {
+                0.7
+            } else {
+                0.0
+            }
+        }
+        _ => 0.0,
+    }
+}
+
+/// Dose of the belts of trapped particles at `orbit_km` from a gas giant of
+/// `radius_km`
+pub fn belt_dose(radius_km: f64, orbit_km: f64) -> f64 {
+    if radius_km <= 0.0 || orbit_km <= 0.0 {
+        return 0.0;
+    }
+    let radii = (orbit_km / radius_km).max(1.0);
+    (BELT_DOSE * (BELT_REFERENCE_RADII / radii).powi(2)).min(4.0 * BELT_DOSE)
+}
+
+/// Share of the radiation that reaches the ground through an atmosphere
+pub fn atmosphere_transmission(surface_pressure_mbar: f64) -> f64 {
+    (-surface_pressure_mbar.max(0.0) / ATMOSPHERE_SHIELDING_MBAR).exp()
+}
+
+/// Dose on the surface of a body: the stellar wind less what the
+/// magnetosphere deflects, plus the belts, from the half of the sky the
+/// ground leaves open and through the atmosphere
+pub fn surface_dose(
+    space_dose: f64,
+    magnetosphere: f64,
+    belt_dose: f64,
+    surface_pressure_mbar: f64,
+) -> f64 {
+    let stellar = COSMIC_RAY_SHARE.min(space_dose)
+        + (space_dose - COSMIC_RAY_SHARE).max(0.0) * (1.0 - magnetosphere);
+    (stellar + belt_dose) * GROUND_SHADOW * atmosphere_transmission(surface_pressure_mbar)
+}
+
+/// Share of a colony radiation kills per year at `dose`
+pub fn radiation_decline(dose: f64) -> f64 {
+    (DECLINE_PER_DOSE * (dose - SAFE_DOSE).max(0.0)).min(MAX_RADIATION_DECLINE)
+}
+
+/// Radiation shelters a building provides to the colony it stands on
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct RadiationShelter {
+    /// People housed behind shielding
+    pub capacity: f64,
+}
+
+/// Radiation environment of a colony over the last economy tick
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct Radiation {
+    /// Dose on the surface, including any storm
+    pub surface: f64,
+    /// Share of the colonists in radiation shelters (0.0 to 1.0)
+    pub sheltered: f64,
+    /// Dose the colonists receive
+    pub dose: f64,
+    /// Multiplier of the storm in progress, 1.0 without one
+    pub storm: f64,
+    /// Years until the storm has passed
+    pub storm_years: f64,
+}
+
+impl Default for Radiation {
+    fn default() -> Self {
+        Self {
+            surface: 0.0,
+            sheltered: 0.0,
+            dose: 0.0,
+            storm: 1.0,
+            storm_years: 0.0,
+        }
+    }
+}
+
+impl Radiation {
+    /// Start a storm multiplying the dose by `multiplier` for `days`, or
+    /// extend the one in progress
+    pub fn add_storm(&mut self, multiplier: f64, days: f64) {
+        self.storm = self.storm.max(multiplier);
+        self.storm_years = self.storm_years.max(days / 365.25);
+    }
+
+    pub fn is_storm(&self) -> bool {
+        self.storm_years > 0.0
+    }
+}
+
+/// Star a body orbits, directly or through its parents, and the body's
+/// distance from it in AU
+fn host_star(
+    body: Entity,
+    bodies: &Query<(
+        &CelestialBody,
+        &SpaceCoordinates,
+        Option<&OrbitCenter>,
+        Option<&StarSystem>,
+    )>,
+) -> Option<(Option<StarSystem>, f64)> {
+    let (_, coords, mut center, _) = bodies.get(body).ok()?;
+    // Moons of moons are as deep as systems go
+    for _ in 0..4 {
+        let (parent, parent_coords, parent_center, star_system) = bodies.get(center?.0).ok()?;
+        if parent.body_type == BodyType::Star {
+            let distance = (coords.position - parent_coords.position).length();
+            return Some((star_system.copied(), distance));
+        }
+        center = parent_center;
+    }
+    None
+}
+
+/// System that re-evaluates the radiation of every colony on a body once
+/// per due economy tick and logs storms that have passed
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn update_radiation(
+    clock: Res<EconomyClock>,
+    mut commands: Commands,
+    bodies: Query<(
+        &CelestialBody,
+        &SpaceCoordinates,
+        Option<&OrbitCenter>,
+        Option<&StarSystem>,
+    )>,
+    shelters: Query<(&RadiationShelter, &LogicalParent)>,
+    mut colonies: Query<(
+        Entity,
+        &Population,
+        &CelestialBody,
+        Option<&OrbitCenter>,
+        Option<&KeplerOrbit>,
+        Option<&RotationSpeed>,
+        Option<&AtmosphereComposition>,
+        Option<&mut Radiation>,
+    )>,
+    mut log: Option<ResMut<EventLog>>,
+    sim_time: Option<Res<crate::ui::SimulationTime>>,
+) {
+    if clock.due == 0 {
+        return;
+    }
+    let years = EconomyClock::TICK_YEARS * clock.due as f64;
+
+    for (entity, population, body, center, orbit, rotation, atmosphere, radiation) in
+        colonies.iter_mut()
+    {
+        if population.count <= 0.0 {
+            continue;
+        }
+        let (star, distance_au) = host_star(entity, &bodies).unwrap_or((None, 1.0));
+        let activity = stellar_activity(star.unwrap_or_default().spectral_class);
+        let magnetosphere = magnetosphere_shielding(
+            body.body_type,
+            body.mass,
+            rotation
+                .and_then(RotationSpeed::period_seconds)
+                .map(|seconds| seconds / 86400.0),
+        );
+        let belt = match (center.and_then(|c| bodies.get(c.0).ok()), orbit) {
+            (Some((parent, ..)), Some(orbit)) if parent.body_type == BodyType::GasGiant => {
+                belt_dose(parent.radius as f64, orbit.semi_major_axis * AU_KM)
+            }
+            _ => 0.0,
+        };
+        let natural = surface_dose(
+            space_dose(activity, distance_au),
+            magnetosphere,
+            belt,
+            atmosphere.map_or(0.0, |a| a.surface_pressure_mbar as f64),
+        );
+        let shelter_capacity: f64 = shelters
+            .iter()
+            .filter(|(_, parent)| parent.0 == entity)
+            .map(|(shelter, _)| shelter.capacity)
+            .sum();
+        let sheltered = (shelter_capacity / population.count).min(1.0);
+
+        let mut updated = radiation.as_deref().cloned().unwrap_or_default();
+        let was_storm = updated.is_storm();
+        if was_storm {
+            updated.storm_years = (updated.storm_years - years).max(0.0);
+        }
+        if !updated.is_storm() {
+            updated.storm = 1.0;
+        }
+        updated.surface = natural * updated.storm;
+        updated.sheltered = sheltered;
+        updated.dose = updated.surface * (1.0 - SHELTER_PROTECTION * sheltered);
+        let is_storm = updated.is_storm();
+
+        match radiation {
+            Some(mut radiation) => {
+                if *radiation != updated {
+                    *radiation = updated;
+                }
+            }
+            None => {
+                commands.entity(entity).insert(updated);
+            }
+        }
+
+        if was_storm && !is_storm {
+            let message = format!("The radiation storm over {} has passed", body.name);
+            if let (Some(log), Some(sim_time)) = (log.as_mut(), sim_time.as_ref()) {
+                log.push(sim_time.format_date_time(), LogSeverity::Info, message);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bodies_shield_their_surface() {
+        let sun = stellar_activity(SpectralClass::G);
+        assert_eq!(space_dose(sun, 1.0), 1.0);
+        assert_eq!(
+            space_dose(stellar_activity(SpectralClass::M), 0.05),
+            MAX_SPACE_DOSE
+        );
+
+        // Earth's field and air stop nearly everything
+        let earth_field = magnetosphere_shielding(BodyType::Planet, EARTH_MASS_KG, Some(1.0));
+        assert_eq!(earth_field, 0.7);
+        assert!(surface_dose(1.0, earth_field, 0.0, 1013.0) < 0.01);
+        // Venus is as heavy but spins too slowly
+        assert_eq!(
+            magnetosphere_shielding(BodyType::Planet, 0.8 * EARTH_MASS_KG, Some(-243.0)),
+            0.0
+        );
+
+        // The airless Moon only has the ground beneath it
+        assert_eq!(surface_dose(1.0, 0.0, 0.0, 0.0), GROUND_SHADOW);
+
+        // Europa sits in Jupiter's belts
+        let europa_belt = belt_dose(69_911.0, 671_034.0);
+        assert!((europa_belt - BELT_DOSE * (10.0 / 9.6f64).powi(2)).abs() < 0.1);
+        assert!(surface_dose(space_dose(sun, 5.2), 0.0, europa_belt, 0.0) > 2.0);
+        assert!(belt_dose(69_911.0, 1_882_700.0) < 1.0);
+
+        assert_eq!(radiation_decline(SAFE_DOSE), 0.0);
+        assert_eq!(radiation_decline(100.0), MAX_RADIATION_DECLINE);
+    }
+
+    #[test]
+    fn test_shelters_and_storms() {
+        let mut app = App::new();
+        app.init_resource::<EconomyClock>()
+            .add_systems(Update, update_radiation);
+        let moon = app
+            .world_mut()
+            .spawn((
+                CelestialBody {
+                    radius: 1737.4,
+                    mass: 7.342e22,
+                    ..CelestialBody::fixture("Moon", BodyType::Moon)
+                },
+                SpaceCoordinates::default(),
+                Population::new(10_000.0),
+            ))
+            .id();
+        app.world_mut()
+            .spawn((RadiationShelter { capacity: 5_000.0 }, LogicalParent(moon)));
+        // One economy day per update
+        let tick = |app: &mut App| {
+            let mut clock = app.world_mut().resource_mut::<EconomyClock>();
+            let elapsed = (clock.processed + 1) as f64 * crate::economy::ECONOMY_TICK_SECONDS;
+            clock.advance(elapsed);
+            app.update();
+            app.world().get::<Radiation>(moon).unwrap().clone()
+        };
+
+        // Without a known star the colony gets the dose of 1 AU from the Sun
+        let calm = tick(&mut app);
+        assert_eq!(calm.surface, GROUND_SHADOW);
+        assert_eq!(calm.sheltered, 0.5);
+        assert!((calm.dose - GROUND_SHADOW * (1.0 - 0.45)).abs() < 1e-12);
+
+        app.world_mut()
+            .get_mut::<Radiation>(moon)
+            .unwrap()
+            .add_storm(5.0, 1.5);
+        let storm = tick(&mut app);
+        assert_eq!(storm.surface, 5.0 * GROUND_SHADOW);
+        assert!(storm.is_storm());
+        assert_eq!(tick(&mut app), calm);
+    }
+}
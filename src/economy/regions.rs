@@ -0,0 +1,372 @@
+//! Surface regions
+//!
+//! A body's surface is split into a coarse grid: a polar cap around each pole
+//! and four longitude sectors in each of the northern, equatorial and southern
+//! latitude bands. Every sector is either highlands or maria (dark lowland
+//! plains), fixed deterministically from the body name like the deposit map.
+//!
+//! Regions are where things happen on a surface. A colony settles the region
+//! with the mildest climate ([`ColonySite`]), whose temperature offset its
+//! life support works against. Mines dig in the region richest in their
+//! resource ([`SurfaceSite`]): the local deposit density and the terrain
+//! (ice in polar cold traps, metals in the maria, aluminium in the highlands)
+//! scale their output, while other surface buildings stand in the colony's
+//! region.
+
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::hash_map::DefaultHasher;
+use std::f32::consts::{FRAC_PI_2, PI};
+use std::hash::{Hash, Hasher};
+
+use super::components::{MineralDeposit, PlanetResources, Population};
+use super::deposit_map::{deposit_density, deposit_sites};
+use super::life_support::COMFORTABLE_RANGE_C;
+use super::mining::MiningOperation;
+use super::types::ResourceType;
+use crate::astronomy::{AtmosphereComposition, SurfaceTemperature};
+use crate::construction::{body_site, Building, BuildingSite};
+use crate::plugins::solar_system::{CelestialBody, LogicalParent};
+use crate::plugins::solar_system_data::BodyType;
+
+/// Latitude in degrees beyond which the polar caps begin
+pub const POLAR_LATITUDE_DEG: f32 = 60.0;
+/// Latitude in degrees bounding the equatorial band
+pub const EQUATORIAL_LATITUDE_DEG: f32 = 30.0;
+/// Longitude sectors in each latitude band outside the polar caps
+pub const SECTORS_PER_BAND: usize = 4;
+/// Chance that a sector is maria rather than highlands
+pub const MARIA_SHARE: f64 = 0.4;
+/// Mining output at no and at full local deposit density
+pub const REGION_YIELD_RANGE: (f64, f64) = (0.5, 1.5);
+
+/// Kind of ground a region covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Terrain {
+    /// Polar cap with cold traps holding ices
+    Polar,
+    /// Rugged, light-coloured uplands
+    Highlands,
+    /// Dark, low-lying basalt plains
+    Maria,
+}
+
+impl Terrain {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Terrain::Polar => "Polar Cap",
+            Terrain::Highlands => "Highlands",
+            Terrain::Maria => "Maria",
+        }
+    }
+
+    /// Output multiplier the ground gives mines of `resource`
+    pub fn resource_bonus(&self, resource: ResourceType) -> f64 {
+        use ResourceType::*;
+        match (self, resource) {
+            (Terrain::Polar, Water | Ammonia | Methane | Nitrogen | CarbonDioxide) => 1.5,
+            (Terrain::Maria, Iron | Titanium | Helium3) => 1.25,
+            (Terrain::Highlands, Aluminum | Silicates) => 1.25,
+            _ => 1.0,
+        }
+    }
+
+    /// Offset of the local temperature from the body average in Celsius
+    /// from altitude
+    pub fn temperature_offset_c(&self) -> f32 {
+        match self {
+            Terrain::Highlands => -8.0,
+            Terrain::Polar | Terrain::Maria => 0.0,
+        }
+    }
+}
+
+/// One region of a body's surface
+#[derive(Debug, Clone, PartialEq)]
+pub struct SurfaceRegion {
+    pub name: String,
+    pub terrain: Terrain,
+    /// Southern and northern edge in radians
+    pub latitude: (f32, f32),
+    /// Western and eastern edge in radians
+    pub longitude: (f32, f32),
+    /// Offset of the local temperature from the body average in Celsius
+    pub temperature_offset_c: f32,
+}
+
+impl SurfaceRegion {
+    /// Latitude and longitude of the region's middle
+    pub fn center(&self) -> (f32, f32) {
+        (
+            (self.latitude.0 + self.latitude.1) / 2.0,
+            (self.longitude.0 + self.longitude.1) / 2.0,
+        )
+    }
+
+    /// Mean deposit density of `resource` across the region (0.0 to 1.0)
+    pub fn local_density(
+        &self,
+        body_name: &str,
+        resource: ResourceType,
+        deposit: &MineralDeposit,
+    ) -> f32 {
+        const SAMPLES: usize = 4;
+        let sites = deposit_sites(body_name, resource, deposit);
+        let mut total = 0.0;
+        for i in 0..SAMPLES {
+            let latitude = self.latitude.0
+                + (i as f32 + 0.5) / SAMPLES as f32 * (self.latitude.1 - self.latitude.0);
+            for j in 0..SAMPLES {
+                let longitude = self.longitude.0
+                    + (j as f32 + 0.5) / SAMPLES as f32 * (self.longitude.1 - self.longitude.0);
+                total += deposit_density(&sites, latitude, longitude);
+            }
+        }
+        total / (SAMPLES * SAMPLES) as f32
+    }
+
+    /// Output multiplier for mines of `resource` in this region
+    pub fn mining_yield(
+        &self,
+        body_name: &str,
+        resource: ResourceType,
+        deposit: &MineralDeposit,
+    ) -> f64 {
+        let (low, high) = REGION_YIELD_RANGE;
+        let density = self.local_density(body_name, resource, deposit) as f64;
+        (low + (high - low) * density) * self.terrain.resource_bonus(resource)
+    }
+}
+
+/// Region a colony has settled in
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct ColonySite {
+    /// Index into the body's [`surface_regions`]
+    pub region: usize,
+    /// Offset of the local temperature from the body average in Celsius
+    pub temperature_offset_c: f32,
+}
+
+/// Region a surface building stands in
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct SurfaceSite {
+    /// Index into the body's [`surface_regions`]
+    pub region: usize,
+    /// Output multiplier for the building's mining operation
+    pub yield_factor: f64,
+}
+
+fn terrain_seed(body_name: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    body_name.hash(&mut hasher);
+    "regions".hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Surface regions of a body: the north polar cap, the northern, equatorial
+/// and southern sectors from longitude -180° eastwards, and the south polar
+/// cap
+pub fn surface_regions(body_name: &str) -> Vec<SurfaceRegion> {
+    const SECTOR_NAMES: [&str; SECTORS_PER_BAND] = ["A", "B", "C", "D"];
+    let polar = POLAR_LATITUDE_DEG.to_radians();
+    let equatorial = EQUATORIAL_LATITUDE_DEG.to_radians();
+    let polar_cap = |name: &str, latitude| SurfaceRegion {
+        name: name.to_string(),
+        terrain: Terrain::Polar,
+        latitude,
+        longitude: (-PI, PI),
+        temperature_offset_c: -40.0,
+    };
+    let bands = [
+        ("Northern", (equatorial, polar), -10.0),
+        ("Equatorial", (-equatorial, equatorial), 10.0),
+        ("Southern", (-polar, -equatorial), -10.0),
+    ];
+
+    let mut rng = StdRng::seed_from_u64(terrain_seed(body_name));
+    let mut regions = vec![polar_cap("North Polar Cap", (polar, FRAC_PI_2))];
+    for (band, latitude, climate_offset) in bands {
+        for (sector, sector_name) in SECTOR_NAMES.iter().enumerate() {
+            let terrain = if rng.gen_bool(MARIA_SHARE) {
+                Terrain::Maria
+            } else {
+                Terrain::Highlands
+            };
+            let west = -PI + sector as f32 * 2.0 * PI / SECTORS_PER_BAND as f32;
+            regions.push(SurfaceRegion {
+                name: format!("{} {} {}", band, terrain.display_name(), sector_name),
+                terrain,
+                latitude,
+                longitude: (west, west + 2.0 * PI / SECTORS_PER_BAND as f32),
+                temperature_offset_c: climate_offset + terrain.temperature_offset_c(),
+            });
+        }
+    }
+    regions.push(polar_cap("South Polar Cap", (-FRAC_PI_2, -polar)));
+    regions
+}
+
+/// Index of the region whose climate lies closest to the comfortable range
+/// on a body averaging `average_celsius`
+pub fn mildest_region(regions: &[SurfaceRegion], average_celsius: f32) -> usize {
+    let (low, high) = COMFORTABLE_RANGE_C;
+    let discomfort = |region: &SurfaceRegion| {
+        let celsius = average_celsius + region.temperature_offset_c;
+        (low - celsius).max(celsius - high).max(0.0)
+    };
+    regions
+        .iter()
+        .enumerate()
+        .min_by(|a, b| discomfort(a.1).total_cmp(&discomfort(b.1)))
+        .map_or(0, |(index, _)| index)
+}
+
+/// Index and output multiplier of the region best for mining `resource`
+pub fn richest_region(
+    body_name: &str,
+    regions: &[SurfaceRegion],
+    resource: ResourceType,
+    deposit: &MineralDeposit,
+) -> (usize, f64) {
+    regions
+        .iter()
+        .map(|region| region.mining_yield(body_name, resource, deposit))
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap_or((0, 1.0))
+}
+
+/// Whether colonies and buildings on a body occupy regions: only solid
+/// surfaces have them, not stars, rings or the cloud tops of giants
+fn has_regions(body_type: BodyType, atmosphere: Option<&AtmosphereComposition>) -> bool {
+    body_type != BodyType::Star && body_site(body_type, atmosphere) == BuildingSite::Surface
+}
+
+/// System that settles every colony without a site in its mildest region
+#[allow(clippy::type_complexity)]
+pub fn site_colonies(
+    mut commands: Commands,
+    colonies: Query<
+        (
+            Entity,
+            &CelestialBody,
+            &Population,
+            Option<&SurfaceTemperature>,
+            Option<&AtmosphereComposition>,
+        ),
+        Without<ColonySite>,
+    >,
+) {
+    for (entity, body, population, temperature, atmosphere) in colonies.iter() {
+        if population.count <= 0.0 || !has_regions(body.body_type, atmosphere) {
+            continue;
+        }
+        let average_celsius = temperature
+            .map(|t| t.average_celsius)
+            .or_else(|| atmosphere.map(|a| a.surface_temperature_celsius))
+            .unwrap_or(-273.15);
+        let regions = surface_regions(&body.name);
+        let region = mildest_region(&regions, average_celsius);
+        commands.entity(entity).insert(ColonySite {
+            region,
+            temperature_offset_c: regions[region].temperature_offset_c,
+        });
+    }
+}
+
+/// System that places every new surface building in a region: mines in the
+/// one richest in their resource, everything else beside the colony
+#[allow(clippy::type_complexity)]
+pub fn site_surface_buildings(
+    mut commands: Commands,
+    buildings: Query<
+        (Entity, &LogicalParent, Option<&MiningOperation>),
+        (With<Building>, Without<SurfaceSite>),
+    >,
+    bodies: Query<(
+        &CelestialBody,
+        Option<&AtmosphereComposition>,
+        Option<&PlanetResources>,
+        Option<&ColonySite>,
+    )>,
+) {
+    for (entity, parent, mining) in buildings.iter() {
+        let Ok((body, atmosphere, resources, colony)) = bodies.get(parent.0) else {
+            continue;
+        };
+        if !has_regions(body.body_type, atmosphere) {
+            continue;
+        }
+        let regions = surface_regions(&body.name);
+        let deposit = mining.and_then(|op| {
+            resources
+                .and_then(|r| r.deposits.get(&op.resource_type))
+                .map(|deposit| (op.resource_type, deposit))
+        });
+        let site = match deposit {
+            Some((resource, deposit)) => {
+                let (region, yield_factor) =
+                    richest_region(&body.name, &regions, resource, deposit);
+                SurfaceSite {
+                    region,
+                    yield_factor,
+                }
+            }
+            None => SurfaceSite {
+                region: colony.map_or(0, |c| c.region),
+                yield_factor: 1.0,
+            },
+        };
+        commands.entity(entity).insert(site);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_regions_cover_the_surface() {
+        let regions = surface_regions("Mars");
+        assert_eq!(regions, surface_regions("Mars"));
+        assert_eq!(regions.len(), 2 + 3 * SECTORS_PER_BAND);
+
+        // The regions tile the sphere: their areas add up to 4π
+        let area: f32 = regions
+            .iter()
+            .map(|r| (r.latitude.1.sin() - r.latitude.0.sin()) * (r.longitude.1 - r.longitude.0))
+            .sum();
+        assert!((area - 4.0 * PI).abs() < 1e-4);
+
+        // Cold bodies settle near the equator, hot ones at the poles
+        let mars = mildest_region(&regions, -63.0);
+        assert!(regions[mars].name.starts_with("Equatorial"));
+        assert!(regions
+            .iter()
+            .all(|r| r.temperature_offset_c <= regions[mars].temperature_offset_c));
+        assert_eq!(
+            regions[mildest_region(&regions, 60.0)].terrain,
+            Terrain::Polar
+        );
+    }
+
+    #[test]
+    fn test_mines_pick_the_richest_region() {
+        let deposit = MineralDeposit::new(10.0, 100.0, 1000.0, 0.8, 0.5);
+        let regions = surface_regions("Moon");
+        let (best, factor) = richest_region("Moon", &regions, ResourceType::Iron, &deposit);
+        assert!(best < regions.len());
+        assert!(factor > REGION_YIELD_RANGE.0);
+        for region in &regions {
+            assert!(region.mining_yield("Moon", ResourceType::Iron, &deposit) <= factor);
+        }
+
+        // Polar cold traps favour ices, not metals
+        assert_eq!(regions[0].terrain, Terrain::Polar);
+        assert!(
+            Terrain::Polar.resource_bonus(ResourceType::Water)
+                > Terrain::Polar.resource_bonus(ResourceType::Iron)
+        );
+    }
+}
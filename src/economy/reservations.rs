@@ -0,0 +1,244 @@
+//! Stock reserved for queued projects
+//!
+//! Construction projects pay their cost when the work is done and hulls pay
+//! a share of theirs every day on the slipway, so without reservations a
+//! project queued later can take the materials an earlier one was waiting
+//! for. With reservations on (the default), once per economy tick every
+//! construction project and hull earmarks its outstanding cost in queue
+//! order, as far as the stock nobody has earmarked covers it. A project
+//! keeps its earmark until it is paid for; projects without one may only
+//! draw on the free stock.
+
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use super::budget::GlobalBudget;
+use super::production::RefinedGood;
+use super::tick::EconomyClock;
+use super::types::ResourceType;
+use crate::construction::{BuildingLevel, BuildingsData, ConstructionQueue};
+use crate::fleets::{hull, HullProfile, Shipyard, TONNES_PER_MT};
+
+/// Stock earmarked for queued projects in Megatons
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct Reservations {
+    /// Whether projects earmark their cost when queued
+    pub enabled: bool,
+    pub resources: HashMap<ResourceType, f64>,
+    pub refined: HashMap<RefinedGood, f64>,
+}
+
+impl Default for Reservations {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            resources: HashMap::new(),
+            refined: HashMap::new(),
+        }
+    }
+}
+
+impl Reservations {
+    pub fn reserved(&self, resource: &ResourceType) -> f64 {
+        self.resources.get(resource).copied().unwrap_or(0.0)
+    }
+
+    pub fn reserved_refined(&self, good: &RefinedGood) -> f64 {
+        self.refined.get(good).copied().unwrap_or(0.0)
+    }
+
+    /// Stock of `resource` no project has earmarked
+    pub fn free(&self, budget: &GlobalBudget, resource: &ResourceType) -> f64 {
+        (budget.get_stockpile(resource) - self.reserved(resource)).max(0.0)
+    }
+
+    /// Stock of `good` no project has earmarked
+    pub fn free_refined(&self, budget: &GlobalBudget, good: &RefinedGood) -> f64 {
+        (budget.get_refined(good) - self.reserved_refined(good)).max(0.0)
+    }
+
+    /// Whether a project can pay `resources` and `refined` now: from the
+    /// whole stock if the cost is `reserved` for it, otherwise from the free
+    /// stock
+    pub fn covers(
+        &self,
+        budget: &GlobalBudget,
+        reserved: bool,
+        resources: impl IntoIterator<Item = (ResourceType, f64)>,
+        refined: impl IntoIterator<Item = (RefinedGood, f64)>,
+    ) -> bool {
+        resources.into_iter().all(|(resource, amount)| {
+            let available = if reserved {
+                budget.get_stockpile(&resource)
+            } else {
+                self.free(budget, &resource)
+            };
+            available >= amount
+        }) && refined.into_iter().all(|(good, amount)| {
+            let available = if reserved {
+                budget.get_refined(&good)
+            } else {
+                self.free_refined(budget, &good)
+            };
+            available >= amount
+        })
+    }
+
+    /// Add an earmark whether or not the stock still covers it
+    fn hold(&mut self, resources: &[(ResourceType, f64)], refined: &[(RefinedGood, f64)]) {
+        for (resource, amount) in resources {
+            *self.resources.entry(*resource).or_insert(0.0) += amount;
+        }
+        for (good, amount) in refined {
+            *self.refined.entry(*good).or_insert(0.0) += amount;
+        }
+    }
+
+    /// Earmark a cost if the free stock covers all of it
+    fn earmark(
+        &mut self,
+        budget: &GlobalBudget,
+        resources: &[(ResourceType, f64)],
+        refined: &[(RefinedGood, f64)],
+    ) -> bool {
+        let covered = self.covers(
+            budget,
+            false,
+            resources.iter().copied(),
+            refined.iter().copied(),
+        );
+        if covered {
+            self.hold(resources, refined);
+        }
+        covered
+    }
+}
+
+type Cost = (Vec<(ResourceType, f64)>, Vec<(RefinedGood, f64)>);
+
+/// Cost of a building level
+fn level_cost(level: &BuildingLevel) -> Cost {
+    (
+        level.cost.iter().map(|(r, a)| (*r, *a)).collect(),
+        level.refined_cost.iter().map(|(g, a)| (*g, *a)).collect(),
+    )
+}
+
+/// Materials of a hull still to be paid after `progress` (0.0 to 1.0)
+fn hull_cost(profile: &HullProfile, progress: f64) -> Cost {
+    let left = (1.0 - progress).max(0.0) / TONNES_PER_MT;
+    let refined = profile
+        .cost_t
+        .iter()
+        .map(|(good, tonnes)| (*good, tonnes * left))
+        .collect();
+    (Vec::new(), refined)
+}
+
+/// System that earmarks stock for construction projects and hulls once per
+/// economy tick, before they are worked on. Projects holding an earmark keep
+/// it; the others, construction queues first and then slipways and queued
+/// hulls, take one in queue order if the free stock covers their cost.
+pub fn update_reservations(
+    clock: Res<EconomyClock>,
+    budget: Res<GlobalBudget>,
+    buildings_data: Option<Res<BuildingsData>>,
+    mut reservations: ResMut<Reservations>,
+    mut queues: Query<&mut ConstructionQueue>,
+    mut yards: Query<&mut Shipyard>,
+) {
+    if clock.due == 0 {
+        return;
+    }
+    reservations.resources.clear();
+    reservations.refined.clear();
+
+    let enabled = reservations.enabled;
+    let cost_of = |building_id: &str, level: u32| {
+        buildings_data
+            .as_ref()
+            .and_then(|data| data.get(building_id))
+            .and_then(|def| def.level(level))
+            .map(level_cost)
+    };
+
+    // Earmarks already held
+    for mut queue in queues.iter_mut() {
+        for project in queue.projects.iter_mut() {
+            project.reserved &= enabled;
+            if !project.reserved {
+                continue;
+            }
+            if let Some((resources, refined)) = cost_of(&project.building_id, project.target_level)
+            {
+                reservations.hold(&resources, &refined);
+            }
+        }
+    }
+    for mut yard in yards.iter_mut() {
+        for slipway in yard.slipways.iter_mut() {
+            slipway.reserved &= enabled;
+            if let Some(profile) = slipway.hull.as_deref().and_then(hull) {
+                if slipway.reserved {
+                    let (resources, refined) = hull_cost(profile, slipway.progress());
+                    reservations.hold(&resources, &refined);
+                }
+            }
+        }
+    }
+    if !enabled {
+        return;
+    }
+
+    // New earmarks in queue order
+    for mut queue in queues.iter_mut() {
+        for project in queue.projects.iter_mut().filter(|p| !p.reserved) {
+            if let Some((resources, refined)) = cost_of(&project.building_id, project.target_level)
+            {
+                project.reserved = reservations.earmark(&budget, &resources, &refined);
+            }
+        }
+    }
+    for mut yard in yards.iter_mut() {
+        for slipway in yard.slipways.iter_mut().filter(|s| !s.reserved) {
+            if let Some(profile) = slipway.hull.as_deref().and_then(hull) {
+                let (resources, refined) = hull_cost(profile, slipway.progress());
+                slipway.reserved = reservations.earmark(&budget, &resources, &refined);
+            }
+        }
+        // Hulls waiting for a slipway earmark afresh every tick
+        for profile in yard.queue.iter().filter_map(|id| hull(id)) {
+            let (resources, refined) = hull_cost(profile, 0.0);
+            reservations.earmark(&budget, &resources, &refined);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_earmarks_leave_free_stock_to_others() {
+        let mut budget = GlobalBudget::default();
+        budget.stockpiles.insert(ResourceType::Iron, 10.0);
+        budget.refined.insert(RefinedGood::Steel, 1.0);
+        let mut reservations = Reservations::default();
+
+        assert!(reservations.earmark(
+            &budget,
+            &[(ResourceType::Iron, 6.0)],
+            &[(RefinedGood::Steel, 0.5)]
+        ));
+        assert_eq!(reservations.free(&budget, &ResourceType::Iron), 4.0);
+        // A second project finds too little free and earmarks nothing
+        assert!(!reservations.earmark(&budget, &[(ResourceType::Iron, 6.0)], &[]));
+        assert_eq!(reservations.reserved(&ResourceType::Iron), 6.0);
+
+        // Only the project holding the earmark may draw on it
+        let cost = [(ResourceType::Iron, 6.0)];
+        assert!(reservations.covers(&budget, true, cost, []));
+        assert!(!reservations.covers(&budget, false, cost, []));
+        assert!(reservations.covers(&budget, false, [(ResourceType::Iron, 4.0)], []));
+    }
+}
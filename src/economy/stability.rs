@@ -0,0 +1,443 @@
+//! Colony stability
+//!
+//! Every populated body and station has a [`Stability`] between 0 (open
+//! revolt) and 100 (content). It is re-evaluated once per economy tick from
+//! the colony's food supply, housing, unemployment, radiation exposure and
+//! cultural drift from the capital, plus the fading effect of recent events
+//! and the policies in force.
+//! Unstable colonies mine, harvest and refine less
+//! ([`Stability::output_factor`]), and colony events can be limited to
+//! colonies in unrest (strikes, protests).
+
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use super::components::Population;
+use super::food::{native_food_fraction, FoodSupply};
+use super::population::body_colony_cost;
+use super::radiation::{Radiation, STATION_DOSE};
+use super::tick::EconomyClock;
+use crate::astronomy::{AtmosphereComposition, SurfaceTemperature};
+use crate::construction::Building;
+use crate::game_state::{EventLog, LogSeverity};
+use crate::plugins::solar_system::{CelestialBody, LogicalParent};
+use crate::policies::{Policies, PoliciesData};
+
+/// Stability of a colony with nothing to complain about
+pub const BASE_STABILITY: f64 = 75.0;
+
+/// Stability from which a colony works at full output
+pub const FULL_OUTPUT_STABILITY: f64 = 40.0;
+
+/// Stability below which a colony is in unrest
+pub const UNREST_STABILITY: f64 = 25.0;
+
+/// Workers employed per level of a building standing on the colony
+pub const JOBS_PER_BUILDING_LEVEL: f64 = 2_000.0;
+
+/// Share of a population looking for work
+pub const WORKFORCE_SHARE: f64 = 0.5;
+
+/// Time in which the effect of an event on stability halves
+pub const EVENT_HALF_LIFE_YEARS: f64 = 0.5;
+
+/// Largest effect recent events can have, either way
+const MAX_EVENT_POINTS: f64 = 30.0;
+
+/// Stability lost per point of cultural divergence from the capital
+pub const DRIFT_STABILITY_PER_POINT: f64 = 0.2;
+
+/// Share of the housing penalty colonists adapted to their world no longer
+/// mind
+pub const ADAPTED_HOUSING_RELIEF: f64 = 0.5;
+
+/// A cause of (in)stability
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StabilityFactor {
+    Food,
+    Housing,
+    Unemployment,
+    Radiation,
+    CulturalDrift,
+    RecentEvents,
+    Policies,
+}
+
+impl StabilityFactor {
+    pub const ALL: [StabilityFactor; 7] = [
+        StabilityFactor::Food,
+        StabilityFactor::Housing,
+        StabilityFactor::Unemployment,
+        StabilityFactor::Radiation,
+        StabilityFactor::CulturalDrift,
+        StabilityFactor::RecentEvents,
+        StabilityFactor::Policies,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            StabilityFactor::Food => "Food shortage",
+            StabilityFactor::Housing => "Cramped housing",
+            StabilityFactor::Unemployment => "Unemployment",
+            StabilityFactor::Radiation => "Radiation exposure",
+            StabilityFactor::CulturalDrift => "Cultural drift",
+            StabilityFactor::RecentEvents => "Recent events",
+            StabilityFactor::Policies => "Policies",
+        }
+    }
+}
+
+/// Conditions of a colony that stability is derived from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StabilityInputs {
+    /// Unmet share of the food demand (0.0 to 1.0)
+    pub food_shortage: f64,
+    /// Colony cost of the host body; infinite for stations
+    pub colony_cost: f32,
+    /// Share of the workforce without a job (0.0 to 1.0)
+    pub unemployment: f64,
+    /// Radiation dose of the colonists relative to open space (0.0 to 1.0)
+    pub radiation: f64,
+    /// Cultural divergence of the colonists from the capital (0 to 100)
+    pub cultural_drift: f64,
+    /// Share of the colonists adapted to their world (0.0 to 1.0)
+    pub adapted: f64,
+    /// Stability points from the policies covering the colony
+    pub policies: f64,
+    /// Output change from the policies covering the colony, in percent
+    pub policy_output: f64,
+}
+
+/// Stability lost to housing: sealed habitats get more cramped the more
+/// hostile the world outside. Stations are always tight on space.
+pub fn housing_penalty(colony_cost: f32) -> f64 {
+    if colony_cost.is_finite() {
+        (2.0 * colony_cost.max(0.0) as f64).min(15.0)
+    } else {
+        8.0
+    }
+}
+
+/// Radiation stability is derived from: the colony's dose, stations shielded
+/// to [`STATION_DOSE`], and no more than open space counts
+pub fn radiation_exposure(radiation: Option<&Radiation>) -> f64 {
+    radiation.map_or(STATION_DOSE, |r| r.dose).clamp(0.0, 1.0)
+}
+
+/// Share of the workforce without a job
+pub fn unemployment(population: f64, jobs: f64) -> f64 {
+    let workforce = population * WORKFORCE_SHARE;
+    if workforce <= 0.0 {
+        return 0.0;
+    }
+    (1.0 - jobs / workforce).clamp(0.0, 1.0)
+}
+
+/// Contribution of every factor to stability, in points
+pub fn stability_factors(
+    inputs: &StabilityInputs,
+    recent_events: f64,
+) -> Vec<(StabilityFactor, f64)> {
+    vec![
+        (StabilityFactor::Food, -40.0 * inputs.food_shortage),
+        (
+            StabilityFactor::Housing,
+            -housing_penalty(inputs.colony_cost) * (1.0 - ADAPTED_HOUSING_RELIEF * inputs.adapted),
+        ),
+        (StabilityFactor::Unemployment, -20.0 * inputs.unemployment),
+        (StabilityFactor::Radiation, -15.0 * inputs.radiation),
+        (
+            StabilityFactor::CulturalDrift,
+            -DRIFT_STABILITY_PER_POINT * inputs.cultural_drift,
+        ),
+        (StabilityFactor::RecentEvents, recent_events),
+        (StabilityFactor::Policies, inputs.policies),
+    ]
+}
+
+/// Stability of a colony
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct Stability {
+    /// 0 (revolt) to 100 (content)
+    pub value: f64,
+    /// Contribution of each factor at the last evaluation, in points
+    pub factors: Vec<(StabilityFactor, f64)>,
+    /// Points still owed to recent events, fading with
+    /// [`EVENT_HALF_LIFE_YEARS`]
+    pub recent_events: f64,
+    /// Output change from the policies in force, in percent
+    pub policy_output: f64,
+}
+
+impl Default for Stability {
+    fn default() -> Self {
+        Self {
+            value: BASE_STABILITY,
+            factors: Vec::new(),
+            recent_events: 0.0,
+            policy_output: 0.0,
+        }
+    }
+}
+
+impl Stability {
+    /// Record an event that shook (or lifted) the colony by `points`
+    pub fn add_event(&mut self, points: f64) {
+        self.recent_events =
+            (self.recent_events + points).clamp(-MAX_EVENT_POINTS, MAX_EVENT_POINTS);
+    }
+
+    /// Re-evaluate the stability after `years` have passed
+    pub fn evaluate(&mut self, inputs: &StabilityInputs, years: f64) {
+        self.recent_events *= 0.5_f64.powf(years / EVENT_HALF_LIFE_YEARS);
+        self.factors = stability_factors(inputs, self.recent_events);
+        let total: f64 = self.factors.iter().map(|(_, points)| points).sum();
+        self.value = (BASE_STABILITY + total).clamp(0.0, 100.0);
+        self.policy_output = inputs.policy_output;
+    }
+
+    /// Output of the colony's mines, harvesters and refineries relative to a
+    /// stable one without policies: full from [`FULL_OUTPUT_STABILITY`], half
+    /// at 0, then scaled by the policies in force
+    pub fn output_factor(&self) -> f64 {
+        let mood = if self.value >= FULL_OUTPUT_STABILITY {
+            1.0
+        } else {
+            0.5 + 0.5 * self.value / FULL_OUTPUT_STABILITY
+        };
+        mood * (1.0 + self.policy_output / 100.0).max(0.0)
+    }
+
+    pub fn is_unrest(&self) -> bool {
+        self.value < UNREST_STABILITY
+    }
+
+    /// Short description of the mood of the colony
+    pub fn describe(&self) -> &'static str {
+        match self.value {
+            v if v < UNREST_STABILITY => "Unrest",
+            v if v < 50.0 => "Restless",
+            v if v < 75.0 => "Stable",
+            _ => "Content",
+        }
+    }
+}
+
+/// Output factor of an entity: that of the colony it is, or of the colony it
+/// is attached to
+pub fn output_factor_for(
+    stability: &Query<&Stability>,
+    entity: Entity,
+    parent: Option<&LogicalParent>,
+) -> f64 {
+    stability
+        .get(entity)
+        .ok()
+        .or_else(|| parent.and_then(|p| stability.get(p.0).ok()))
+        .map_or(1.0, Stability::output_factor)
+}
+
+/// System that re-evaluates every colony's stability once per due economy
+/// tick and logs colonies falling into or recovering from unrest
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn update_stability(
+    clock: Res<EconomyClock>,
+    mut commands: Commands,
+    policies: Option<Res<Policies>>,
+    policies_data: Option<Res<PoliciesData>>,
+    buildings: Query<(&Building, &LogicalParent)>,
+    mut colonies: Query<(
+        Entity,
+        &Population,
+        Option<&CelestialBody>,
+        Option<&SurfaceTemperature>,
+        Option<&AtmosphereComposition>,
+        Option<&FoodSupply>,
+        Option<&Radiation>,
+        Option<&Name>,
+        Option<&mut Stability>,
+    )>,
+    mut log: Option<ResMut<EventLog>>,
+    sim_time: Option<Res<crate::ui::SimulationTime>>,
+) {
+    if clock.due == 0 {
+        return;
+    }
+    let years = EconomyClock::TICK_YEARS * clock.due as f64;
+    // Stability points and output change from the policies covering a colony
+    let policy_effects = |colony: Entity| match (policies.as_deref(), policies_data.as_deref()) {
+        (Some(policies), Some(data)) => (
+            policies.stability_for(colony, data),
+            policies.output_for(colony, data),
+        ),
+        _ => (0.0, 0.0),
+    };
+
+    let mut jobs: HashMap<Entity, f64> = HashMap::new();
+    for (building, parent) in buildings.iter() {
+        *jobs.entry(parent.0).or_insert(0.0) += building.level as f64 * JOBS_PER_BUILDING_LEVEL;
+    }
+
+    for (entity, population, body, temperature, atmosphere, food, radiation, name, stability) in
+        colonies.iter_mut()
+    {
+        if population.count <= 0.0 {
+            continue;
+        }
+        let colony_cost = body.map_or(f32::INFINITY, |b| {
+            body_colony_cost(b, temperature, atmosphere)
+        });
+        // Open-air economies employ their own people
+        let native_jobs = population.count * WORKFORCE_SHARE * native_food_fraction(colony_cost);
+        let (policy_stability, policy_output) = policy_effects(entity);
+        let inputs = StabilityInputs {
+            food_shortage: food.map_or(0.0, |f| f.shortage),
+            colony_cost,
+            unemployment: unemployment(
+                population.count,
+                native_jobs + jobs.get(&entity).copied().unwrap_or(0.0),
+            ),
+            radiation: radiation_exposure(radiation),
+            cultural_drift: population.cultural_drift(),
+            adapted: population.adapted_share(),
+            policies: policy_stability,
+            policy_output,
+        };
+
+        let Some(mut stability) = stability else {
+            let mut stability = Stability::default();
+            stability.evaluate(&inputs, years);
+            commands.entity(entity).insert(stability);
+            continue;
+        };
+        let was_unrest = stability.is_unrest();
+        stability.evaluate(&inputs, years);
+        if was_unrest == stability.is_unrest() {
+            continue;
+        }
+
+        let name = body
+            .map(|b| b.name.clone())
+            .or_else(|| name.map(|n| n.to_string()))
+            .unwrap_or_else(|| "a colony".to_string());
+        let (severity, message) = if stability.is_unrest() {
+            let cause = stability
+                .factors
+                .iter()
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map_or("unknown causes", |(factor, _)| factor.label());
+            (
+                LogSeverity::Warning,
+                format!(
+                    "Unrest on {} (stability {:.0}), mainly from {}",
+                    name,
+                    stability.value,
+                    cause.to_lowercase()
+                ),
+            )
+        } else {
+            (LogSeverity::Info, format!("Order restored on {}", name))
+        };
+        info!("{}", message);
+        if let (Some(log), Some(sim_time)) = (log.as_mut(), sim_time.as_ref()) {
+            log.push(sim_time.format_date_time(), severity, message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_factors_lower_stability_and_output() {
+        let earth = StabilityInputs {
+            food_shortage: 0.0,
+            colony_cost: 0.0,
+            unemployment: 0.0,
+            radiation: 0.0,
+            cultural_drift: 0.0,
+            adapted: 0.0,
+            policies: 0.0,
+            policy_output: 0.0,
+        };
+        let mut stability = Stability::default();
+        stability.evaluate(&earth, 0.0);
+        assert_eq!(stability.value, BASE_STABILITY);
+        assert_eq!(stability.output_factor(), 1.0);
+
+        // A starving, jobless, airless outpost
+        let outpost = StabilityInputs {
+            food_shortage: 1.0,
+            colony_cost: 5.0,
+            unemployment: 1.0,
+            radiation: 1.0,
+            cultural_drift: 0.0,
+            adapted: 0.0,
+            policies: 0.0,
+            policy_output: 0.0,
+        };
+        stability.evaluate(&outpost, 0.0);
+        assert_eq!(stability.value, 0.0);
+        assert!(stability.is_unrest());
+        assert_eq!(stability.output_factor(), 0.5);
+        assert_eq!(stability.factors.len(), StabilityFactor::ALL.len());
+    }
+
+    #[test]
+    fn test_event_effects_fade() {
+        let calm = StabilityInputs {
+            food_shortage: 0.0,
+            colony_cost: 0.0,
+            unemployment: 0.0,
+            radiation: 0.0,
+            cultural_drift: 0.0,
+            adapted: 0.0,
+            policies: 0.0,
+            policy_output: 0.0,
+        };
+        let mut stability = Stability::default();
+        stability.add_event(-100.0);
+        assert_eq!(stability.recent_events, -MAX_EVENT_POINTS);
+
+        stability.evaluate(&calm, EVENT_HALF_LIFE_YEARS);
+        assert!((stability.value - (BASE_STABILITY - MAX_EVENT_POINTS / 2.0)).abs() < 1e-9);
+        stability.evaluate(&calm, 10.0);
+        assert!((stability.value - BASE_STABILITY).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_policies_shift_stability_and_output() {
+        let martial_law = StabilityInputs {
+            food_shortage: 0.0,
+            colony_cost: 0.0,
+            unemployment: 0.0,
+            radiation: 0.0,
+            cultural_drift: 0.0,
+            adapted: 0.0,
+            policies: 20.0,
+            policy_output: -15.0,
+        };
+        let mut stability = Stability::default();
+        stability.evaluate(&martial_law, 0.0);
+        assert_eq!(stability.value, BASE_STABILITY + 20.0);
+        assert!((stability.output_factor() - 0.85).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_jobs_and_radiation() {
+        assert_eq!(unemployment(10_000.0, 0.0), 1.0);
+        assert!((unemployment(10_000.0, 2_000.0) - 0.6).abs() < 1e-9);
+        assert_eq!(unemployment(10_000.0, 1e6), 0.0);
+        assert_eq!(unemployment(0.0, 0.0), 0.0);
+
+        assert_eq!(housing_penalty(0.0), 0.0);
+        assert_eq!(housing_penalty(20.0), 15.0);
+        assert_eq!(radiation_exposure(None), STATION_DOSE);
+        let europa = Radiation {
+            dose: 2.2,
+            ..Default::default()
+        };
+        assert_eq!(radiation_exposure(Some(&europa)), 1.0);
+    }
+}
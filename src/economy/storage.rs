@@ -0,0 +1,183 @@
+//! Power storage and brownouts
+//!
+//! Batteries and capacitors ([`PowerStorage`]) charge from the surplus of
+//! their location once per economy day and cover its deficit until they run
+//! empty. A location still short of power after relays and storage browns
+//! out: essential demand (life support, habitats) is served first, then each
+//! [`PowerTier`] in the order of the location's [`PowerPriorities`], and the
+//! buildings of a tier produce at the share of their demand that was met.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::budget::PowerGrids;
+use super::components::PowerStorage;
+use super::tick::{EconomyClock, ECONOMY_TICK_SECONDS};
+use crate::plugins::solar_system::LogicalParent;
+
+/// Group of power consumers shed together in a brownout
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PowerTier {
+    /// Mines and gas harvesters
+    Mining,
+    /// Refineries and ordnance factories
+    Industry,
+    /// Laboratories and engineering facilities
+    Research,
+}
+
+impl PowerTier {
+    pub const ALL: [PowerTier; 3] = [PowerTier::Mining, PowerTier::Industry, PowerTier::Research];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            PowerTier::Mining => "Mining",
+            PowerTier::Industry => "Industry",
+            PowerTier::Research => "Research",
+        }
+    }
+}
+
+/// Order in which a colony or station powers its tiers during a brownout,
+/// highest priority first
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PowerPriorities(pub [PowerTier; 3]);
+
+impl Default for PowerPriorities {
+    fn default() -> Self {
+        Self(PowerTier::ALL)
+    }
+}
+
+impl PowerPriorities {
+    /// Move `tier` one place up the order
+    pub fn raise(&mut self, tier: PowerTier) {
+        if let Some(index) = self.0.iter().position(|t| *t == tier) {
+            if index > 0 {
+                self.0.swap(index, index - 1);
+            }
+        }
+    }
+}
+
+/// Priority orders picked in the interface, applied by
+/// [`apply_power_priorities`]
+#[derive(Resource, Debug, Clone, Default)]
+pub struct PowerPriorityChanges {
+    pub requested: Vec<(Entity, PowerPriorities)>,
+}
+
+impl PowerPriorityChanges {
+    /// Power the tiers of `location` in the order of `priorities`
+    pub fn request(&mut self, location: Entity, priorities: PowerPriorities) {
+        self.requested.push((location, priorities));
+    }
+}
+
+/// Power in Watts storage releases towards `deficit`, spread so the
+/// `stored` Joules last at least the day
+pub fn storage_discharge(deficit: f64, stored: f64) -> f64 {
+    deficit.min(stored / ECONOMY_TICK_SECONDS).max(0.0)
+}
+
+/// Share out `available` Watts: `essential` demand first, then each tier of
+/// `order`. Returns the fraction of each tier's demand that is met.
+pub fn shed_load(
+    available: f64,
+    essential: f64,
+    tier_demand: &HashMap<PowerTier, f64>,
+    order: &[PowerTier],
+) -> HashMap<PowerTier, f64> {
+    let mut remaining = (available - essential).max(0.0);
+    order
+        .iter()
+        .map(|tier| {
+            let demand = tier_demand.get(tier).copied().unwrap_or(0.0);
+            let supply = if demand > 0.0 {
+                (remaining / demand).min(1.0)
+            } else {
+                1.0
+            };
+            remaining = (remaining - demand).max(0.0);
+            (*tier, supply)
+        })
+        .collect()
+}
+
+/// System that hands the priority orders picked in the interface to their
+/// colonies and stations
+pub fn apply_power_priorities(mut commands: Commands, mut changes: ResMut<PowerPriorityChanges>) {
+    for (location, priorities) in changes.requested.drain(..) {
+        if let Some(mut entity) = commands.get_entity(location) {
+            entity.insert(priorities);
+        }
+    }
+}
+
+/// System that charges power storage with the surplus of its location and
+/// drains it to cover the deficit, once per due economy tick
+pub fn charge_power_storage(
+    clock: Res<EconomyClock>,
+    power_grids: Res<PowerGrids>,
+    mut storages: Query<(Entity, &mut PowerStorage, Option<&LogicalParent>)>,
+) {
+    if clock.due == 0 {
+        return;
+    }
+
+    // Energy left to store (or, when negative, still missing) per location
+    let seconds = ECONOMY_TICK_SECONDS * clock.due as f64;
+    let mut energy: HashMap<Entity, f64> = power_grids
+        .grids
+        .iter()
+        .map(|(location, grid)| (*location, grid.balance() * seconds))
+        .collect();
+
+    for (entity, mut storage, parent) in storages.iter_mut() {
+        let location = if energy.contains_key(&entity) {
+            entity
+        } else {
+            parent.map_or(entity, |p| p.0)
+        };
+        let Some(energy) = energy.get_mut(&location) else {
+            continue;
+        };
+        if *energy >= 0.0 {
+            let charged = energy.min(storage.capacity - storage.charge).max(0.0);
+            storage.charge += charged;
+            *energy -= charged;
+        } else {
+            let drained = (-*energy).min(storage.charge);
+            storage.charge -= drained;
+            *energy += drained;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_brownout_sheds_lowest_priority_first() {
+        let demand = HashMap::from([
+            (PowerTier::Mining, 100.0),
+            (PowerTier::Industry, 100.0),
+            (PowerTier::Research, 100.0),
+        ]);
+        // 50 W essential leaves 150 W for the tiers
+        let supply = shed_load(200.0, 50.0, &demand, &PowerPriorities::default().0);
+        assert_eq!(supply[&PowerTier::Mining], 1.0);
+        assert!((supply[&PowerTier::Industry] - 0.5).abs() < 1e-9);
+        assert_eq!(supply[&PowerTier::Research], 0.0);
+
+        let mut priorities = PowerPriorities::default();
+        priorities.raise(PowerTier::Research);
+        priorities.raise(PowerTier::Research);
+        assert_eq!(priorities.0[0], PowerTier::Research);
+        let supply = shed_load(200.0, 50.0, &demand, &priorities.0);
+        assert_eq!(supply[&PowerTier::Research], 1.0);
+        assert_eq!(supply[&PowerTier::Industry], 0.0);
+    }
+}
@@ -0,0 +1,382 @@
+//! Survey missions
+//!
+//! Surveying a body takes a probe launched from the most populated colony in
+//! the target's system. The probe spends time in transit, then surveys for a
+//! duration that grows with the body's size and the survey tier. Each
+//! completed mission raises the body's [`SurveyLevel`] by one step and sends a
+//! [`SurveyReport`].
+
+use bevy::prelude::*;
+
+use super::components::{PlanetResources, Population, SurveyLevel};
+use super::tick::EconomyClock;
+use super::types::ResourceType;
+use crate::astronomy::components::{CurrentStarSystem, SpaceCoordinates, SystemId};
+use crate::game_state::{EventLog, LogSeverity};
+use crate::plugins::camera::ViewMode;
+use crate::plugins::solar_system::CelestialBody;
+
+/// Cruise speed of survey probes in AU per day (~35 km/s)
+pub const SURVEY_PROBE_SPEED_AU_PER_DAY: f64 = 0.02;
+/// Days of survey work per tier for an Earth-sized body
+pub const SURVEY_DAYS_PER_TIER: f64 = 60.0;
+/// Shortest survey, for the smallest asteroids
+const MIN_SURVEY_DAYS: f64 = 5.0;
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// Days a survey probe needs on site to bring a body of `radius_km` to `level`
+pub fn survey_days(radius_km: f32, level: SurveyLevel) -> f64 {
+    let size = radius_km as f64 / EARTH_RADIUS_KM;
+    (SURVEY_DAYS_PER_TIER * level.tier() as f64 * size)
+        .ceil()
+        .max(MIN_SURVEY_DAYS)
+}
+
+/// Days a probe needs to cover `distance_au`
+pub fn transit_days(distance_au: f64) -> f64 {
+    (distance_au / SURVEY_PROBE_SPEED_AU_PER_DAY).ceil()
+}
+
+/// A survey probe on its way to, or working at, a body
+#[derive(Debug, Clone, PartialEq)]
+pub struct SurveyMission {
+    /// Body being surveyed
+    pub target: Entity,
+    pub target_name: String,
+    /// Body the probe was launched from, if any
+    pub origin: Option<Entity>,
+    /// Survey level the body reaches once the mission completes
+    pub level: SurveyLevel,
+    pub transit_days: f64,
+    pub survey_days: f64,
+    /// Days since launch
+    pub elapsed_days: f64,
+}
+
+impl SurveyMission {
+    /// Whether the probe is still travelling to the target
+    pub fn in_transit(&self) -> bool {
+        self.elapsed_days < self.transit_days
+    }
+
+    /// Fraction of the transit covered (0.0 to 1.0)
+    pub fn transit_fraction(&self) -> f64 {
+        if self.transit_days <= 0.0 {
+            1.0
+        } else {
+            (self.elapsed_days / self.transit_days).min(1.0)
+        }
+    }
+
+    /// Fraction of the whole mission done (0.0 to 1.0)
+    pub fn progress_fraction(&self) -> f32 {
+        let total = self.transit_days + self.survey_days;
+        if total <= 0.0 {
+            1.0
+        } else {
+            (self.elapsed_days / total).min(1.0) as f32
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.elapsed_days >= self.transit_days + self.survey_days
+    }
+}
+
+/// Active survey missions and launch requests from the UI
+#[derive(Resource, Debug, Clone, Default)]
+pub struct SurveyMissions {
+    pub missions: Vec<SurveyMission>,
+    /// Bodies to launch a survey mission to
+    pub requested: Vec<Entity>,
+}
+
+impl SurveyMissions {
+    /// Request a survey mission to `body`
+    pub fn request(&mut self, body: Entity) {
+        self.requested.push(body);
+    }
+
+    /// The mission currently surveying `body`, if any
+    pub fn mission_for(&self, body: Entity) -> Option<&SurveyMission> {
+        self.missions.iter().find(|m| m.target == body)
+    }
+}
+
+/// Sent when a survey mission completes
+#[derive(Event, Debug, Clone)]
+pub struct SurveyReport {
+    pub body: Entity,
+    pub body_name: String,
+    /// Survey level the body has reached
+    pub level: SurveyLevel,
+    /// Resources known at the new level, largest first (Megatons)
+    pub discovered: Vec<(ResourceType, f64)>,
+}
+
+/// System to launch requested survey missions from the most populated body
+/// in the target's star system
+#[allow(clippy::type_complexity)]
+pub fn launch_survey_missions(
+    mut survey: ResMut<SurveyMissions>,
+    bodies: Query<(
+        Entity,
+        &CelestialBody,
+        Option<&SpaceCoordinates>,
+        Option<&SurveyLevel>,
+        Option<&Population>,
+        Option<&SystemId>,
+    )>,
+) {
+    let requested: Vec<Entity> = survey.requested.drain(..).collect();
+    for target in requested {
+        if survey.mission_for(target).is_some() {
+            continue;
+        }
+        let Ok((_, body, coords, level, _, system)) = bodies.get(target) else {
+            continue;
+        };
+        let Some(next) = level.copied().unwrap_or_default().next() else {
+            continue;
+        };
+
+        let system_id = system.map_or(0, |s| s.0);
+        let origin = bodies
+            .iter()
+            .filter(|(.., population, system)| {
+                population.is_some_and(|p| p.count > 0.0) && system.map_or(0, |s| s.0) == system_id
+            })
+            .max_by(|a, b| {
+                let count = |p: Option<&Population>| p.map_or(0.0, |p| p.count);
+                count(a.4).total_cmp(&count(b.4))
+            });
+        let distance = match (origin.and_then(|o| o.2), coords) {
+            (Some(from), Some(to)) => from.position.distance(to.position),
+            _ => 0.0,
+        };
+
+        let mission = SurveyMission {
+            target,
+            target_name: body.name.clone(),
+            origin: origin.map(|o| o.0).filter(|o| *o != target),
+            level: next,
+            transit_days: transit_days(distance),
+            survey_days: survey_days(body.radius, next),
+            elapsed_days: 0.0,
+        };
+        info!(
+            "Survey probe launched to {} ({:.0} days transit, {:.0} days survey)",
+            mission.target_name, mission.transit_days, mission.survey_days
+        );
+        survey.missions.push(mission);
+    }
+}
+
+/// System that advances every survey mission by one day per economy tick and
+/// completes the finished ones
+pub fn advance_survey_missions(
+    mut commands: Commands,
+    clock: Res<EconomyClock>,
+    mut survey: ResMut<SurveyMissions>,
+    resources: Query<&PlanetResources>,
+    mut reports: EventWriter<SurveyReport>,
+) {
+    if clock.due == 0 {
+        return;
+    }
+
+    for mission in survey.missions.iter_mut() {
+        mission.elapsed_days += clock.due as f64;
+    }
+
+    let (finished, active): (Vec<_>, Vec<_>) = survey
+        .missions
+        .drain(..)
+        .partition(SurveyMission::is_complete);
+    survey.missions = active;
+
+    for mission in finished {
+        let Some(mut body) = commands.get_entity(mission.target) else {
+            continue;
+        };
+        body.insert(mission.level);
+
+        let mut discovered: Vec<(ResourceType, f64)> = resources
+            .get(mission.target)
+            .map(|r| {
+                r.deposits
+                    .iter()
+                    .map(|(resource, deposit)| {
+                        (*resource, mission.level.discovered_amount(&deposit.reserve))
+                    })
+                    .filter(|(_, amount)| *amount > 0.0)
+                    .collect()
+            })
+            .unwrap_or_default();
+        discovered.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        reports.send(SurveyReport {
+            body: mission.target,
+            body_name: mission.target_name,
+            level: mission.level,
+            discovered,
+        });
+    }
+}
+
+/// System that writes incoming survey reports to the console and the event log
+pub fn log_survey_reports(
+    mut reports: EventReader<SurveyReport>,
+    mut log: Option<ResMut<EventLog>>,
+    sim_time: Option<Res<crate::ui::SimulationTime>>,
+) {
+    for report in reports.read() {
+        let highlights: Vec<String> = report
+            .discovered
+            .iter()
+            .take(3)
+            .map(|(resource, amount)| format!("{} {:.2e} Mt", resource.display_name(), amount))
+            .collect();
+        let message = format!(
+            "Survey report for {}: {:?} complete. {}",
+            report.body_name,
+            report.level,
+            if highlights.is_empty() {
+                "No deposits found.".to_string()
+            } else {
+                highlights.join(", ")
+            }
+        );
+        info!("{}", message);
+        if let (Some(log), Some(sim_time)) = (log.as_mut(), sim_time.as_ref()) {
+            log.push(sim_time.format_date_time(), LogSeverity::Info, message);
+        }
+    }
+}
+
+/// System that draws survey probes between their origin and target in the
+/// system view
+pub fn draw_survey_probes(
+    mut gizmos: Gizmos,
+    survey: Res<SurveyMissions>,
+    view_mode: Res<ViewMode>,
+    current_system: Res<CurrentStarSystem>,
+    bodies: Query<(&GlobalTransform, Option<&SystemId>), With<CelestialBody>>,
+) {
+    if *view_mode != ViewMode::System {
+        return;
+    }
+
+    let color = Color::srgb(1.0, 0.85, 0.3);
+    for mission in survey.missions.iter() {
+        let Ok((target, system)) = bodies.get(mission.target) else {
+            continue;
+        };
+        if system.map_or(0, |s| s.0) != current_system.0 {
+            continue;
+        }
+        let target = target.translation();
+        let origin = mission
+            .origin
+            .and_then(|o| bodies.get(o).ok())
+            .map_or(target, |(t, _)| t.translation());
+
+        let position = origin.lerp(target, mission.transit_fraction() as f32);
+        if mission.in_transit() {
+            gizmos.line(position, target, color.with_alpha(0.25));
+        }
+        gizmos.sphere(position, Quat::IDENTITY, 1.5, color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::economy::tick::{advance_economy_clock, ECONOMY_TICK_SECONDS};
+    use crate::plugins::solar_system_data::BodyType;
+    use crate::ui::SimulationTime;
+    use bevy::math::DVec3;
+
+    fn body(name: &str, radius: f32) -> CelestialBody {
+        CelestialBody {
+            name: name.to_string(),
+            radius,
+            mass: 1.0,
+            body_type: BodyType::Planet,
+            visual_radius: 1.0,
+            asteroid_class: None,
+        }
+    }
+
+    #[test]
+    fn test_survey_time_scales_with_size_and_tier() {
+        let earth = survey_days(6371.0, SurveyLevel::OrbitalScan);
+        assert_eq!(earth, SURVEY_DAYS_PER_TIER);
+        assert_eq!(survey_days(6371.0, SurveyLevel::CoreSample), 3.0 * earth);
+        assert!(survey_days(3390.0, SurveyLevel::OrbitalScan) < earth);
+        assert_eq!(survey_days(0.5, SurveyLevel::OrbitalScan), MIN_SURVEY_DAYS);
+    }
+
+    #[test]
+    fn test_mission_travels_surveys_and_reports() {
+        let mut app = App::new();
+        app.init_resource::<SimulationTime>()
+            .init_resource::<EconomyClock>()
+            .init_resource::<SurveyMissions>()
+            .add_event::<SurveyReport>()
+            .add_systems(
+                Update,
+                (
+                    launch_survey_missions,
+                    advance_economy_clock,
+                    advance_survey_missions,
+                )
+                    .chain(),
+            );
+
+        app.world_mut().spawn((
+            body("Earth", 6371.0),
+            SpaceCoordinates::new(DVec3::new(1.0, 0.0, 0.0)),
+            Population::new(8e9),
+        ));
+        let mars = app
+            .world_mut()
+            .spawn((
+                body("Mars", 3390.0),
+                SpaceCoordinates::new(DVec3::new(1.5, 0.0, 0.0)),
+            ))
+            .id();
+
+        app.world_mut()
+            .resource_mut::<SurveyMissions>()
+            .request(mars);
+        app.update();
+        let mission = app
+            .world()
+            .resource::<SurveyMissions>()
+            .mission_for(mars)
+            .cloned()
+            .unwrap();
+        assert_eq!(mission.transit_days, 25.0);
+        assert_eq!(mission.level, SurveyLevel::OrbitalScan);
+
+        // Still travelling: nothing is revealed yet
+        app.world_mut().resource_mut::<SimulationTime>().elapsed = 20.0 * ECONOMY_TICK_SECONDS;
+        app.update();
+        assert!(app.world().get::<SurveyLevel>(mars).is_none());
+
+        let total = mission.transit_days + mission.survey_days;
+        app.world_mut().resource_mut::<SimulationTime>().elapsed = total * ECONOMY_TICK_SECONDS;
+        app.update();
+        assert_eq!(
+            app.world().get::<SurveyLevel>(mars),
+            Some(&SurveyLevel::OrbitalScan)
+        );
+        assert!(app.world().resource::<SurveyMissions>().missions.is_empty());
+
+        let reports = app.world().resource::<Events<SurveyReport>>();
+        let mut reader = reports.get_reader();
+        let report = reader.read(reports).next().unwrap();
+        assert_eq!(report.body_name, "Mars");
+    }
+}
@@ -0,0 +1,251 @@
+//! Thermal model: waste heat and its rejection per colony and station
+//!
+//! Every Watt a location consumes ends up as heat, and reactors add their
+//! own waste heat ([`HeatSource`]). An atmosphere carries heat away by
+//! convection; airless bodies and stations can only radiate it, which takes
+//! [`Radiator`] buildings once industry grows. A location that produces more
+//! heat than it can reject throttles its production to match.
+
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::astronomy::AtmosphereComposition;
+use crate::economy::components::{HeatSource, PowerConsumer, PowerGridHost, Radiator};
+use crate::plugins::solar_system::{CelestialBody, LogicalParent};
+use crate::plugins::solar_system_data::BodyType;
+
+/// Convective heat rejection of an atmosphere per bar of surface pressure
+pub const ATMOSPHERE_REJECTION_W_PER_BAR: f64 = 1e14;
+/// Heat an airless surface sheds without radiators (ground conduction and
+/// the odd exposed hull)
+pub const BARE_SURFACE_REJECTION_W: f64 = 1e8;
+/// Heat a structure in open space (stations, ring harvesters) sheds through
+/// its hull without dedicated radiators
+pub const OPEN_SPACE_REJECTION_W: f64 = 1e9;
+
+/// Heat balance of a single location: a body or station with everything
+/// attached to it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LocalHeat {
+    /// Display name of the body or station
+    pub name: String,
+    /// Waste heat produced (in Watts)
+    pub produced: f64,
+    /// Passive rejection of the environment (in Watts)
+    pub passive: f64,
+    /// Rejection capacity of radiator buildings (in Watts)
+    pub radiators: f64,
+}
+
+impl LocalHeat {
+    /// Total heat rejection capacity
+    pub fn rejection(&self) -> f64 {
+        self.passive + self.radiators
+    }
+
+    /// Heat produced as a fraction of rejection capacity; above 1.0 the
+    /// location overheats
+    pub fn load(&self) -> f64 {
+        let rejection = self.rejection();
+        if rejection <= 0.0 {
+            return if self.produced > 0.0 { f64::INFINITY } else { 0.0 };
+        }
+        self.produced / rejection
+    }
+
+    pub fn is_overheating(&self) -> bool {
+        self.load() > 1.0
+    }
+
+    /// Fraction of production that can keep running without overheating
+    pub fn throttle(&self) -> f64 {
+        let load = self.load();
+        if load <= 1.0 {
+            1.0
+        } else {
+            1.0 / load
+        }
+    }
+}
+
+/// Per-location heat balances, keyed by the body or station hosting them
+#[derive(Resource, Debug, Clone, Default)]
+pub struct HeatBalance {
+    pub locations: HashMap<Entity, LocalHeat>,
+}
+
+impl HeatBalance {
+    /// Production throttle of an entity: that of the location it hosts, or
+    /// of the location it is attached to
+    pub fn throttle_for(&self, entity: Entity, parent: Option<&LogicalParent>) -> f64 {
+        self.locations
+            .get(&entity)
+            .or_else(|| parent.and_then(|p| self.locations.get(&p.0)))
+            .map_or(1.0, LocalHeat::throttle)
+    }
+
+    /// Returns true if any location is overheating
+    pub fn has_overheating(&self) -> bool {
+        self.locations.values().any(LocalHeat::is_overheating)
+    }
+}
+
+/// Heat an environment rejects on its own: convection into an atmosphere,
+/// open-space radiation for stations and rings, little for bare ground
+pub fn passive_rejection(
+    body: Option<&CelestialBody>,
+    atmosphere: Option<&AtmosphereComposition>,
+) -> f64 {
+    if let Some(atmosphere) = atmosphere {
+        let bar = atmosphere.surface_pressure_mbar as f64 / 1000.0;
+        return (bar * ATMOSPHERE_REJECTION_W_PER_BAR).max(BARE_SURFACE_REJECTION_W);
+    }
+    match body {
+        Some(body) if body.body_type != BodyType::Ring => BARE_SURFACE_REJECTION_W,
+        _ => OPEN_SPACE_REJECTION_W,
+    }
+}
+
+/// Entities that host a heat balance: celestial bodies and grid hosts such
+/// as orbital stations
+type HeatLocations<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Option<&'static CelestialBody>,
+        Option<&'static Name>,
+        Option<&'static AtmosphereComposition>,
+    ),
+    Or<(With<CelestialBody>, With<PowerGridHost>)>,
+>;
+
+/// The location an entity heats: itself if it is one, otherwise the body or
+/// station it is attached to
+fn heat_location(
+    entity: Entity,
+    parent: Option<&LogicalParent>,
+    locations: &HeatLocations,
+) -> Entity {
+    if locations.contains(entity) {
+        entity
+    } else {
+        parent.map_or(entity, |p| p.0)
+    }
+}
+
+/// Get or create the heat balance of `location`
+fn local_heat<'a>(
+    balances: &'a mut HashMap<Entity, LocalHeat>,
+    location: Entity,
+    locations: &HeatLocations,
+) -> &'a mut LocalHeat {
+    balances.entry(location).or_insert_with(|| {
+        let Ok((body, name, atmosphere)) = locations.get(location) else {
+            return LocalHeat {
+                name: "Unknown".to_string(),
+                passive: OPEN_SPACE_REJECTION_W,
+                ..default()
+            };
+        };
+        LocalHeat {
+            name: body
+                .map(|b| b.name.clone())
+                .or_else(|| name.map(|n| n.to_string()))
+                .unwrap_or_else(|| "Unknown".to_string()),
+            passive: passive_rejection(body, atmosphere),
+            ..default()
+        }
+    })
+}
+
+/// System to aggregate waste heat and heat rejection per location
+pub fn update_heat_balance(
+    mut balance: ResMut<HeatBalance>,
+    consumers: Query<(Entity, &PowerConsumer, Option<&LogicalParent>)>,
+    sources: Query<(Entity, &HeatSource, Option<&LogicalParent>)>,
+    radiators: Query<(Entity, &Radiator, Option<&LogicalParent>)>,
+    locations: HeatLocations,
+) {
+    let mut balances = HashMap::new();
+
+    for (entity, consumer, parent) in consumers.iter() {
+        let location = heat_location(entity, parent, &locations);
+        local_heat(&mut balances, location, &locations).produced += consumer.demand;
+    }
+    for (entity, source, parent) in sources.iter() {
+        let location = heat_location(entity, parent, &locations);
+        local_heat(&mut balances, location, &locations).produced += source.output;
+    }
+    for (entity, radiator, parent) in radiators.iter() {
+        let location = heat_location(entity, parent, &locations);
+        local_heat(&mut balances, location, &locations).radiators += radiator.capacity;
+    }
+
+    balance.locations = balances;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn body(body_type: BodyType) -> CelestialBody {
+        CelestialBody {
+            name: "Test".to_string(),
+            radius: 1737.0,
+            mass: 7.3e22,
+            body_type,
+            visual_radius: 1.0,
+            asteroid_class: None,
+        }
+    }
+
+    #[test]
+    fn test_overheating_throttles_production() {
+        let heat = LocalHeat {
+            name: "Luna".to_string(),
+            produced: 4e8,
+            passive: BARE_SURFACE_REJECTION_W,
+            radiators: 1e8,
+        };
+        assert!(heat.is_overheating());
+        assert!((heat.throttle() - 0.5).abs() < 1e-12);
+
+        let cool = LocalHeat {
+            radiators: 3e8,
+            ..heat
+        };
+        assert!(!cool.is_overheating());
+        assert_eq!(cool.throttle(), 1.0);
+    }
+
+    #[test]
+    fn test_airless_colonies_need_radiators() {
+        let mut app = App::new();
+        app.init_resource::<HeatBalance>()
+            .add_systems(Update, update_heat_balance);
+
+        let luna = app.world_mut().spawn(body(BodyType::Moon)).id();
+        let earth = app
+            .world_mut()
+            .spawn((
+                body(BodyType::Planet),
+                AtmosphereComposition::new(1013.0, 15.0, vec![]),
+            ))
+            .id();
+        for colony in [luna, earth] {
+            app.world_mut()
+                .spawn((PowerConsumer { demand: 3e8 }, LogicalParent(colony)));
+        }
+
+        app.update();
+        let balance = app.world().resource::<HeatBalance>();
+        assert!(balance.locations[&luna].is_overheating());
+        assert!(!balance.locations[&earth].is_overheating());
+
+        app.world_mut()
+            .spawn((Radiator { capacity: 2e8 }, LogicalParent(luna)));
+        app.update();
+        let balance = app.world().resource::<HeatBalance>();
+        assert_eq!(balance.throttle_for(luna, None), 1.0);
+    }
+}
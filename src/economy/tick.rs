@@ -0,0 +1,82 @@
+//! Deterministic economy clock
+//!
+//! Economy systems advance in whole in-game days instead of per-frame deltas,
+//! so their results depend only on how much simulation time has passed and not
+//! on the frame rate or time scale it passed at.
+
+use bevy::prelude::*;
+
+use super::budget::SECONDS_PER_YEAR;
+use crate::ui::SimulationTime;
+
+/// Length of one economy tick in simulation seconds (one in-game day)
+pub const ECONOMY_TICK_SECONDS: f64 = 86_400.0;
+
+/// Upper bound on ticks processed in a single update. A larger backlog (e.g.
+/// after the clock jumps forward) is worked off over the following updates.
+pub const MAX_ECONOMY_TICKS_PER_UPDATE: u64 = 3_650;
+
+/// Counts whole economy ticks reached by the simulation clock.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct EconomyClock {
+    /// Ticks processed since the simulation started
+    pub processed: u64,
+    /// Ticks to process in the current update
+    pub due: u64,
+}
+
+impl EconomyClock {
+    /// Duration of one tick in years, the unit of most economy rates
+    pub const TICK_YEARS: f64 = ECONOMY_TICK_SECONDS / SECONDS_PER_YEAR;
+
+    /// Catch up with the simulation clock, returning the number of ticks due
+    pub fn advance(&mut self, elapsed_seconds: f64) -> u64 {
+        let reached = (elapsed_seconds.max(0.0) / ECONOMY_TICK_SECONDS).floor() as u64;
+        self.due = reached
+            .saturating_sub(self.processed)
+            .min(MAX_ECONOMY_TICKS_PER_UPDATE);
+        self.processed += self.due;
+        self.due
+    }
+}
+
+/// System that works out how many economy ticks are due this update.
+/// Tick-driven systems must run after it.
+pub fn advance_economy_clock(sim_time: Res<SimulationTime>, mut clock: ResMut<EconomyClock>) {
+    clock.advance(sim_time.elapsed_seconds());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ticks_independent_of_step_size() {
+        let total = 100.0 * ECONOMY_TICK_SECONDS + 1234.0;
+
+        let mut coarse = EconomyClock::default();
+        let coarse_ticks = coarse.advance(total);
+
+        let mut fine = EconomyClock::default();
+        let mut fine_ticks = 0;
+        let mut elapsed = 0.0;
+        while elapsed < total {
+            elapsed = (elapsed + 3_333.3).min(total);
+            fine_ticks += fine.advance(elapsed);
+        }
+
+        assert_eq!(coarse_ticks, 100);
+        assert_eq!(fine_ticks, 100);
+        assert_eq!(coarse.processed, fine.processed);
+    }
+
+    #[test]
+    fn test_large_backlog_is_spread_over_updates() {
+        let mut clock = EconomyClock::default();
+        let elapsed = (MAX_ECONOMY_TICKS_PER_UPDATE + 10) as f64 * ECONOMY_TICK_SECONDS;
+
+        assert_eq!(clock.advance(elapsed), MAX_ECONOMY_TICKS_PER_UPDATE);
+        assert_eq!(clock.advance(elapsed), 10);
+        assert_eq!(clock.advance(elapsed), 0);
+    }
+}
@@ -0,0 +1,441 @@
+//! Treasury
+//!
+//! Money is a currency of its own, counted in credits (₡). The colonies pay
+//! taxes, and trade agreements with other factions sell goods out of the
+//! stockpile and buy them in; buildings cost maintenance and wages,
+//! and research and construction are funded through two sliders. Funding
+//! below 100% saves money but slows research and construction, funding above
+//! it speeds them up at a higher cost. The treasury may run into debt, which
+//! accrues interest; once the debt exceeds a year of income, creditors halve
+//! the funding that actually arrives. Every month is booked in a ledger.
+
+use bevy::prelude::*;
+use std::collections::{HashMap, VecDeque};
+
+use super::budget::SECONDS_PER_YEAR;
+use super::components::Population;
+use super::stability::JOBS_PER_BUILDING_LEVEL;
+use super::tick::EconomyClock;
+use crate::construction::{Building, ConstructionQueue};
+use crate::game_state::{EventLog, LogSeverity};
+use crate::research::ResearchBuilding;
+use crate::trade::TradeAgreements;
+
+/// Taxes paid to the space program per person and year
+pub const TAX_PER_PERSON_YEAR: f64 = 2.0;
+
+/// Base price per Megaton of goods sold to other factions
+pub const EXPORT_PRICE_PER_MT: f64 = 1.0e8;
+
+/// Maintenance per building level and year
+pub const MAINTENANCE_PER_LEVEL_YEAR: f64 = 2.0e7;
+
+/// Wage per employed worker and year
+pub const WAGE_PER_WORKER_YEAR: f64 = 2.0e4;
+
+/// Research funding per research point at 100% funding
+pub const CREDITS_PER_RESEARCH_POINT: f64 = 1.0e6;
+
+/// Research funding per year at 100% for the research done without labs
+pub const BASE_RESEARCH_BUDGET_PER_YEAR: f64 = 2.0e9;
+
+/// Funding per year of a construction project in progress at 100%
+pub const CONSTRUCTION_BUDGET_PER_PROJECT_YEAR: f64 = 1.0e9;
+
+/// Yearly interest on debt
+pub const DEBT_INTEREST_PER_YEAR: f64 = 0.05;
+
+/// Highest setting of a funding slider
+pub const MAX_FUNDING: f64 = 1.5;
+
+/// Share of the funding that arrives once creditors cut back
+const OVER_LIMIT_FUNDING: f64 = 0.5;
+
+/// Economy ticks (days) per ledger month
+pub const LEDGER_MONTH_TICKS: u64 = 30;
+
+/// Closed monthly ledgers kept, two years
+pub const MAX_LEDGERS: usize = 24;
+
+/// Starting balance of a new treasury
+const STARTING_BALANCE: f64 = 1.0e10;
+
+/// Format an amount of credits in human-readable units (₡, k, M, B, T)
+pub fn format_currency(credits: f64) -> String {
+    let sign = if credits < 0.0 { "-" } else { "" };
+    let amount = credits.abs();
+    if amount >= 1e12 {
+        format!("{}₡{:.2} T", sign, amount / 1e12)
+    } else if amount >= 1e9 {
+        format!("{}₡{:.2} B", sign, amount / 1e9)
+    } else if amount >= 1e6 {
+        format!("{}₡{:.2} M", sign, amount / 1e6)
+    } else if amount >= 1e3 {
+        format!("{}₡{:.2} k", sign, amount / 1e3)
+    } else {
+        format!("{}₡{:.0}", sign, amount)
+    }
+}
+
+/// A line of the ledger
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LedgerLine {
+    Taxes,
+    TradeSales,
+    TradePurchases,
+    Tariffs,
+    Maintenance,
+    Wages,
+    Research,
+    Construction,
+    Intelligence,
+    Diplomacy,
+    Interest,
+}
+
+impl LedgerLine {
+    pub const ALL: [LedgerLine; 11] = [
+        LedgerLine::Taxes,
+        LedgerLine::TradeSales,
+        LedgerLine::TradePurchases,
+        LedgerLine::Tariffs,
+        LedgerLine::Maintenance,
+        LedgerLine::Wages,
+        LedgerLine::Research,
+        LedgerLine::Construction,
+        LedgerLine::Intelligence,
+        LedgerLine::Diplomacy,
+        LedgerLine::Interest,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LedgerLine::Taxes => "Taxes",
+            LedgerLine::TradeSales => "Trade sales",
+            LedgerLine::TradePurchases => "Trade purchases",
+            LedgerLine::Tariffs => "Tariffs",
+            LedgerLine::Maintenance => "Maintenance",
+            LedgerLine::Wages => "Wages",
+            LedgerLine::Research => "Research funding",
+            LedgerLine::Construction => "Construction funding",
+            LedgerLine::Intelligence => "Intelligence",
+            LedgerLine::Diplomacy => "Diplomacy",
+            LedgerLine::Interest => "Interest",
+        }
+    }
+
+    pub fn is_income(&self) -> bool {
+        matches!(self, LedgerLine::Taxes | LedgerLine::TradeSales)
+    }
+}
+
+/// Money booked over one month, per line. Amounts are positive; whether a
+/// line is income or expense follows from [`LedgerLine::is_income`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Ledger {
+    /// In-game date the month was closed on; empty for the open month
+    pub date: String,
+    pub lines: HashMap<LedgerLine, f64>,
+}
+
+impl Ledger {
+    pub fn book(&mut self, line: LedgerLine, amount: f64) {
+        *self.lines.entry(line).or_insert(0.0) += amount;
+    }
+
+    pub fn get(&self, line: LedgerLine) -> f64 {
+        self.lines.get(&line).copied().unwrap_or(0.0)
+    }
+
+    pub fn income(&self) -> f64 {
+        self.lines
+            .iter()
+            .filter(|(line, _)| line.is_income())
+            .map(|(_, amount)| amount)
+            .sum()
+    }
+
+    pub fn expenses(&self) -> f64 {
+        self.lines
+            .iter()
+            .filter(|(line, _)| !line.is_income())
+            .map(|(_, amount)| amount)
+            .sum()
+    }
+
+    pub fn net(&self) -> f64 {
+        self.income() - self.expenses()
+    }
+}
+
+/// The civilization's money
+#[derive(Resource, Debug, Clone)]
+pub struct Treasury {
+    /// Credits on hand; negative while in debt
+    pub balance: f64,
+    /// Research funding slider (1.0 = 100%, up to [`MAX_FUNDING`])
+    pub research_funding: f64,
+    /// Construction funding slider (1.0 = 100%, up to [`MAX_FUNDING`])
+    pub construction_funding: f64,
+    /// The month being booked
+    pub current: Ledger,
+    /// Closed months, oldest first
+    pub ledgers: VecDeque<Ledger>,
+    /// Income per year at the last economy tick
+    pub income_per_year: f64,
+    /// Expenses per year at the last economy tick
+    pub expenses_per_year: f64,
+}
+
+impl Default for Treasury {
+    fn default() -> Self {
+        Self {
+            balance: STARTING_BALANCE,
+            research_funding: 1.0,
+            construction_funding: 1.0,
+            current: Ledger::default(),
+            ledgers: VecDeque::new(),
+            income_per_year: 0.0,
+            expenses_per_year: 0.0,
+        }
+    }
+}
+
+impl Treasury {
+    pub fn in_debt(&self) -> bool {
+        self.balance < 0.0
+    }
+
+    /// Whether the debt exceeds a year of income, so creditors cut back
+    pub fn over_credit_limit(&self) -> bool {
+        self.in_debt() && -self.balance > self.income_per_year
+    }
+
+    /// Share of a funding slider's setting that arrives
+    fn arriving(&self, slider: f64) -> f64 {
+        if self.over_credit_limit() {
+            slider * OVER_LIMIT_FUNDING
+        } else {
+            slider
+        }
+    }
+
+    /// Speed of research relative to full funding
+    pub fn research_factor(&self) -> f64 {
+        self.arriving(self.research_funding)
+    }
+
+    /// Speed of construction relative to full funding
+    pub fn construction_factor(&self) -> f64 {
+        self.arriving(self.construction_funding)
+    }
+
+    /// Pay a one-off expense, such as recruiting an agent, and book it in
+    /// the open month
+    pub fn spend(&mut self, line: LedgerLine, amount: f64) {
+        self.balance -= amount;
+        self.current.book(line, amount);
+    }
+
+    /// Close the open month and keep it in the ledger history
+    fn close_month(&mut self, date: String) {
+        let mut ledger = std::mem::take(&mut self.current);
+        ledger.date = date;
+        self.ledgers.push_back(ledger);
+        while self.ledgers.len() > MAX_LEDGERS {
+            self.ledgers.pop_front();
+        }
+    }
+}
+
+/// Research funding per year at 100% for labs producing `lab_points_per_second`
+pub fn research_budget_per_year(lab_points_per_second: f64) -> f64 {
+    BASE_RESEARCH_BUDGET_PER_YEAR
+        + lab_points_per_second * SECONDS_PER_YEAR * CREDITS_PER_RESEARCH_POINT
+}
+
+/// System that books a day of income and expenses per due economy tick and
+/// closes the ledger at the end of every month. Logs when the treasury falls
+/// into debt, pays it off, or exceeds its credit limit.
+#[allow(clippy::too_many_arguments)]
+pub fn update_treasury(
+    clock: Res<EconomyClock>,
+    mut treasury: ResMut<Treasury>,
+    trade: Option<Res<TradeAgreements>>,
+    populations: Query<&Population>,
+    buildings: Query<&Building>,
+    labs: Query<&ResearchBuilding>,
+    queues: Query<&ConstructionQueue>,
+    mut log: Option<ResMut<EventLog>>,
+    sim_time: Option<Res<crate::ui::SimulationTime>>,
+) {
+    if clock.due == 0 {
+        return;
+    }
+    let years = EconomyClock::TICK_YEARS * clock.due as f64;
+
+    let population: f64 = populations.iter().map(|p| p.count).sum();
+    let levels: f64 = buildings.iter().map(|b| b.level as f64).sum();
+    let lab_points: f64 = labs.iter().map(|l| l.points_per_second).sum();
+    let projects = queues
+        .iter()
+        .filter(|q| q.projects.front().is_some_and(|p| !p.is_complete()))
+        .count() as f64;
+
+    let was_in_debt = treasury.in_debt();
+    let was_over_limit = treasury.over_credit_limit();
+    let research_funding = treasury.research_funding;
+    let construction_funding = treasury.construction_funding;
+    let interest = (-treasury.balance).max(0.0) * DEBT_INTEREST_PER_YEAR;
+    let per_year = [
+        (LedgerLine::Taxes, population * TAX_PER_PERSON_YEAR),
+        (
+            LedgerLine::TradeSales,
+            trade.as_ref().map_or(0.0, |t| t.sales_per_year),
+        ),
+        (
+            LedgerLine::TradePurchases,
+            trade.as_ref().map_or(0.0, |t| t.purchases_per_year),
+        ),
+        (
+            LedgerLine::Tariffs,
+            trade.as_ref().map_or(0.0, |t| t.tariffs_per_year),
+        ),
+        (LedgerLine::Maintenance, levels * MAINTENANCE_PER_LEVEL_YEAR),
+        (
+            LedgerLine::Wages,
+            levels * JOBS_PER_BUILDING_LEVEL * WAGE_PER_WORKER_YEAR,
+        ),
+        (
+            LedgerLine::Research,
+            research_funding * research_budget_per_year(lab_points),
+        ),
+        (
+            LedgerLine::Construction,
+            construction_funding * projects * CONSTRUCTION_BUDGET_PER_PROJECT_YEAR,
+        ),
+        (LedgerLine::Interest, interest),
+    ];
+
+    treasury.income_per_year = 0.0;
+    treasury.expenses_per_year = 0.0;
+    for (line, amount) in per_year {
+        if line.is_income() {
+            treasury.income_per_year += amount;
+            treasury.balance += amount * years;
+        } else {
+            treasury.expenses_per_year += amount;
+            treasury.balance -= amount * years;
+        }
+        treasury.current.book(line, amount * years);
+    }
+
+    let previous = clock.processed - clock.due;
+    if clock.processed / LEDGER_MONTH_TICKS != previous / LEDGER_MONTH_TICKS {
+        let date = sim_time
+            .as_ref()
+            .map_or_else(String::new, |t| t.format_date_time());
+        treasury.close_month(date);
+    }
+
+    let message = if treasury.over_credit_limit() && !was_over_limit {
+        Some((
+            LogSeverity::Critical,
+            format!(
+                "Debt of {} exceeds a year of income: creditors halve research and construction funding",
+                format_currency(-treasury.balance)
+            ),
+        ))
+    } else if treasury.in_debt() && !was_in_debt {
+        Some((
+            LogSeverity::Warning,
+            "The treasury has run into debt and pays interest".to_string(),
+        ))
+    } else if !treasury.in_debt() && was_in_debt {
+        Some((
+            LogSeverity::Info,
+            "The treasury's debt is paid off".to_string(),
+        ))
+    } else {
+        None
+    };
+    if let Some((severity, message)) = message {
+        info!("{}", message);
+        if let (Some(log), Some(sim_time)) = (log.as_mut(), sim_time.as_ref()) {
+            log.push(sim_time.format_date_time(), severity, message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::economy::tick::{advance_economy_clock, ECONOMY_TICK_SECONDS};
+    use crate::ui::SimulationTime;
+
+    #[test]
+    fn test_format_currency() {
+        assert_eq!(format_currency(0.0), "₡0");
+        assert_eq!(format_currency(1_500.0), "₡1.50 k");
+        assert_eq!(format_currency(2.5e9), "₡2.50 B");
+        assert_eq!(format_currency(-3.0e12), "-₡3.00 T");
+    }
+
+    #[test]
+    fn test_funding_is_cut_over_the_credit_limit() {
+        let mut treasury = Treasury {
+            research_funding: 1.2,
+            income_per_year: 1.0e9,
+            ..Default::default()
+        };
+        assert_eq!(treasury.research_factor(), 1.2);
+        treasury.balance = -0.5e9;
+        assert!(treasury.in_debt());
+        assert_eq!(treasury.research_factor(), 1.2);
+        treasury.balance = -2.0e9;
+        assert!(treasury.over_credit_limit());
+        assert_eq!(treasury.research_factor(), 0.6);
+        assert_eq!(treasury.construction_factor(), 0.5);
+    }
+
+    #[test]
+    fn test_taxes_wages_and_monthly_ledgers() {
+        let mut app = App::new();
+        app.init_resource::<SimulationTime>()
+            .init_resource::<EconomyClock>()
+            .init_resource::<Treasury>()
+            .add_systems(Update, (advance_economy_clock, update_treasury).chain());
+        app.world_mut().spawn(Population::new(1.0e9));
+        app.world_mut().spawn(Building {
+            id: "mine".to_string(),
+            level: 2,
+        });
+        {
+            let mut treasury = app.world_mut().resource_mut::<Treasury>();
+            treasury.balance = 0.0;
+            treasury.research_funding = 0.0;
+        }
+
+        app.world_mut().resource_mut::<SimulationTime>().elapsed = ECONOMY_TICK_SECONDS;
+        app.update();
+        let treasury = app.world().resource::<Treasury>();
+        let expected_income = 1.0e9 * TAX_PER_PERSON_YEAR;
+        let expected_expenses =
+            2.0 * (MAINTENANCE_PER_LEVEL_YEAR + JOBS_PER_BUILDING_LEVEL * WAGE_PER_WORKER_YEAR);
+        assert_eq!(treasury.income_per_year, expected_income);
+        assert_eq!(treasury.expenses_per_year, expected_expenses);
+        let net = (expected_income - expected_expenses) * EconomyClock::TICK_YEARS;
+        assert!((treasury.balance - net).abs() < 1e-3);
+        assert!((treasury.current.net() - net).abs() < 1e-3);
+        assert!(treasury.ledgers.is_empty());
+
+        // The rest of the month closes the ledger
+        app.world_mut().resource_mut::<SimulationTime>().elapsed =
+            LEDGER_MONTH_TICKS as f64 * ECONOMY_TICK_SECONDS;
+        app.update();
+        let treasury = app.world().resource::<Treasury>();
+        assert_eq!(treasury.ledgers.len(), 1);
+        assert!((treasury.ledgers[0].net() - treasury.balance).abs() < 1e-3);
+        assert_eq!(treasury.current, Ledger::default());
+    }
+}
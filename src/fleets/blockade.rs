@@ -0,0 +1,303 @@
+//! Blockades
+//!
+//! Hostile fleets (raiders, or the ships of a hostile faction) take up orbit
+//! around a body and control it while they have more crew than the fleets
+//! orbiting it. The colony on a body whose orbit they control, and the
+//! stations around it, are blockaded: their depots are cut off from the
+//! rest of the civilization (see [`GlobalBudget::cut_off`]), they neither
+//! ship nor import food, and trade with the blockading faction stops.
+//! Enough crew in orbit drives the hostile fleet off; otherwise it leaves
+//! once its supplies run out.
+
+use bevy::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+use super::components::FleetLocation;
+use super::crew::Crew;
+use crate::construction::OrbitalStation;
+use crate::economy::components::Population;
+use crate::economy::{EconomyClock, GlobalBudget};
+use crate::game_state::{EventLog, LogSeverity};
+use crate::plugins::solar_system::CelestialBody;
+use crate::trade::FactionId;
+use crate::ui::SimulationTime;
+
+/// A hostile fleet in orbit of a body
+#[derive(Component, Debug, Clone)]
+pub struct HostileFleet {
+    pub name: String,
+    /// Faction it sails for, `None` for raiders
+    pub faction: Option<FactionId>,
+    /// Body whose orbit it holds
+    pub body: Entity,
+    /// Crew aboard; more crew in orbit drives it off
+    pub crew: u32,
+    /// Days of supplies before it leaves
+    pub days_left: f64,
+}
+
+/// Sent when a hostile fleet leaves an orbit, so that the faction it sails
+/// for gets back the ships that survived
+#[derive(Event, Debug, Clone)]
+pub struct HostileFleetDeparted {
+    pub faction: Option<FactionId>,
+    pub crew: u32,
+    /// Whether the civilization's fleets drove it off, losing its ships
+    pub driven_off: bool,
+}
+
+/// The blockade of a colony or station
+#[derive(Debug, Clone, PartialEq)]
+pub struct Blockade {
+    /// Name of the hostile fleet
+    pub blockader: String,
+    pub faction: Option<FactionId>,
+    /// Crew in orbit that would drive it off
+    pub crew: u32,
+    /// Days the blockade has lasted
+    pub days: f64,
+}
+
+/// Colonies and stations under blockade
+#[derive(Resource, Debug, Clone, Default)]
+pub struct Blockades {
+    pub blockades: HashMap<Entity, Blockade>,
+}
+
+impl Blockades {
+    pub fn get(&self, colony: Entity) -> Option<&Blockade> {
+        self.blockades.get(&colony)
+    }
+
+    /// Whether a faction's fleets blockade any colony
+    pub fn by_faction(&self, faction: &str) -> bool {
+        self.blockades
+            .values()
+            .any(|b| b.faction.as_deref() == Some(faction))
+    }
+}
+
+/// System that settles the control of every orbit a hostile fleet holds
+/// on due economy ticks, and blockades the colonies and stations there
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn update_blockades(
+    mut commands: Commands,
+    clock: Res<EconomyClock>,
+    mut blockades: ResMut<Blockades>,
+    mut budget: ResMut<GlobalBudget>,
+    mut hostiles: Query<(Entity, &mut HostileFleet)>,
+    fleets: Query<(&FleetLocation, &Crew)>,
+    colonies: Query<(
+        Entity,
+        &Population,
+        Option<&CelestialBody>,
+        Option<&OrbitalStation>,
+    )>,
+    bodies: Query<&CelestialBody>,
+    mut departures: EventWriter<HostileFleetDeparted>,
+    (mut log, sim_time): (Option<ResMut<EventLog>>, Option<Res<SimulationTime>>),
+) {
+    if clock.due == 0 {
+        return;
+    }
+    let days = clock.due as f64;
+    let body_name = |body: Entity| {
+        bodies
+            .get(body)
+            .map_or("an unknown body".to_string(), |b| b.name.clone())
+    };
+
+    let mut messages = Vec::new();
+    let mut departed = HashSet::new();
+    for (entity, mut hostile) in hostiles.iter_mut() {
+        let defenders: u32 = fleets
+            .iter()
+            .filter(|(location, _)| **location == FleetLocation::Orbiting(hostile.body))
+            .map(|(_, crew)| crew.aboard)
+            .sum();
+        if defenders >= hostile.crew {
+            messages.push((
+                LogSeverity::Info,
+                format!(
+                    "Our fleets drove the {} out of the orbit of {}",
+                    hostile.name,
+                    body_name(hostile.body)
+                ),
+            ));
+            commands.entity(entity).despawn();
+            departed.insert(entity);
+            departures.send(HostileFleetDeparted {
+                faction: hostile.faction.clone(),
+                crew: hostile.crew,
+                driven_off: true,
+            });
+            continue;
+        }
+        hostile.days_left -= days;
+        if hostile.days_left <= 0.0 {
+            messages.push((
+                LogSeverity::Info,
+                format!(
+                    "The {} has left the orbit of {}",
+                    hostile.name,
+                    body_name(hostile.body)
+                ),
+            ));
+            commands.entity(entity).despawn();
+            departed.insert(entity);
+            departures.send(HostileFleetDeparted {
+                faction: hostile.faction.clone(),
+                crew: hostile.crew,
+                driven_off: false,
+            });
+        }
+    }
+
+    // The orbit goes to the strongest hostile fleet in it
+    let mut controlled: HashMap<Entity, &HostileFleet> = HashMap::new();
+    for (entity, hostile) in hostiles.iter() {
+        if !departed.contains(&entity) {
+            controlled
+                .entry(hostile.body)
+                .and_modify(|held| {
+                    if hostile.crew > held.crew {
+                        *held = hostile;
+                    }
+                })
+                .or_insert(hostile);
+        }
+    }
+
+    let mut blockaded = HashMap::new();
+    for (entity, population, body, station) in colonies.iter() {
+        if population.count <= 0.0 {
+            continue;
+        }
+        let host = station.map_or(entity, |s| s.host);
+        let Some(hostile) = controlled.get(&host) else {
+            continue;
+        };
+        let name = body
+            .map(|b| b.name.clone())
+            .or_else(|| station.map(|s| s.name.clone()))
+            .unwrap_or_default();
+        let days = match blockades.get(entity) {
+            Some(blockade) => blockade.days + days,
+            None => {
+                messages.push((
+                    LogSeverity::Critical,
+                    format!(
+                        "⛔ The {} blockades {}: its depot is cut off and no food gets through",
+                        hostile.name, name
+                    ),
+                ));
+                0.0
+            }
+        };
+        blockaded.insert(
+            entity,
+            Blockade {
+                blockader: hostile.name.clone(),
+                faction: hostile.faction.clone(),
+                crew: hostile.crew,
+                days,
+            },
+        );
+    }
+    for (entity, blockade) in &blockades.blockades {
+        if !blockaded.contains_key(entity) {
+            let name = colonies
+                .get(*entity)
+                .ok()
+                .and_then(|(_, _, body, station)| {
+                    body.map(|b| b.name.clone())
+                        .or_else(|| station.map(|s| s.name.clone()))
+                })
+                .unwrap_or_else(|| "a colony".to_string());
+            messages.push((
+                LogSeverity::Info,
+                format!(
+                    "The blockade of {} is lifted after {:.0} days",
+                    name, blockade.days
+                ),
+            ));
+        }
+    }
+
+    let cut_off: HashSet<Entity> = blockaded.keys().copied().collect();
+    if budget.cut_off != cut_off {
+        budget.cut_off = cut_off;
+    }
+    if blockades.blockades != blockaded {
+        blockades.blockades = blockaded;
+    }
+
+    for (severity, message) in messages {
+        info!("{}", message);
+        if let (Some(log), Some(sim_time)) = (log.as_mut(), sim_time.as_ref()) {
+            log.push(sim_time.format_date_time(), severity, message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::economy::types::ResourceType;
+
+    #[test]
+    fn test_hostile_fleets_blockade_until_driven_off() {
+        let mut app = App::new();
+        app.init_resource::<EconomyClock>()
+            .init_resource::<Blockades>()
+            .init_resource::<GlobalBudget>()
+            .add_event::<HostileFleetDeparted>()
+            .add_systems(Update, update_blockades);
+        let mars = app.world_mut().spawn(Population::new(1e6)).id();
+        app.world_mut().spawn(HostileFleet {
+            name: "raider flotilla".to_string(),
+            faction: None,
+            body: mars,
+            crew: 40,
+            days_left: 100.0,
+        });
+        app.world_mut()
+            .resource_mut::<GlobalBudget>()
+            .add_resource_at(mars, ResourceType::Iron, 10.0);
+
+        app.world_mut().resource_mut::<EconomyClock>().due = 1;
+        app.update();
+        let blockade = app.world().resource::<Blockades>().get(mars).cloned();
+        assert_eq!(blockade.unwrap().blockader, "raider flotilla");
+        let budget = app.world().resource::<GlobalBudget>();
+        assert!(budget.cut_off.contains(&mars));
+        assert_eq!(
+            budget.available_stockpile(&ResourceType::Iron),
+            budget.get_stockpile(&ResourceType::Iron) - 10.0
+        );
+
+        // Too few crew in orbit change nothing, enough drive the raiders off
+        let fleet = app
+            .world_mut()
+            .spawn((
+                FleetLocation::Orbiting(mars),
+                Crew {
+                    required: 60,
+                    aboard: 30,
+                },
+            ))
+            .id();
+        app.update();
+        assert!(app.world().resource::<Blockades>().get(mars).is_some());
+        app.world_mut().get_mut::<Crew>(fleet).unwrap().aboard = 60;
+        app.update();
+        assert!(app.world().resource::<Blockades>().blockades.is_empty());
+        assert!(app.world().resource::<GlobalBudget>().cut_off.is_empty());
+        let mut hostiles = app.world_mut().query::<&HostileFleet>();
+        assert_eq!(hostiles.iter(app.world()).count(), 0);
+        let departures = app.world().resource::<Events<HostileFleetDeparted>>();
+        let departure = departures.iter_current_update_events().next().unwrap();
+        assert!(departure.driven_off);
+        assert_eq!(departure.crew, 40);
+    }
+}
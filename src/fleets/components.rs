@@ -0,0 +1,98 @@
+use bevy::prelude::*;
+
+use super::engines::{delta_v_km_s, engine, propellant_for, EngineProfile};
+use super::transfer::Transfer;
+
+/// A group of ships moving together, with shared propellant stores
+#[derive(Component, Debug, Clone)]
+pub struct Fleet {
+    pub name: String,
+    /// Engine component design (see [`super::engines::ENGINES`])
+    pub engine: String,
+    /// Mass without propellant in tonnes
+    pub dry_mass_t: f64,
+    /// Propellant carried in tonnes
+    pub fuel_t: f64,
+    /// Propellant tank capacity in tonnes
+    pub fuel_capacity_t: f64,
+}
+
+impl Fleet {
+    pub fn engine_profile(&self) -> Option<&'static EngineProfile> {
+        engine(&self.engine)
+    }
+
+    /// Delta-v left in the tanks in km/s
+    pub fn delta_v_km_s(&self) -> f64 {
+        self.engine_profile().map_or(0.0, |engine| {
+            delta_v_km_s(
+                engine.exhaust_velocity_km_s,
+                self.dry_mass_t + self.fuel_t,
+                self.dry_mass_t,
+            )
+        })
+    }
+
+    /// Delta-v with full tanks in km/s
+    pub fn max_delta_v_km_s(&self) -> f64 {
+        self.engine_profile().map_or(0.0, |engine| {
+            delta_v_km_s(
+                engine.exhaust_velocity_km_s,
+                self.dry_mass_t + self.fuel_capacity_t,
+                self.dry_mass_t,
+            )
+        })
+    }
+
+    /// Make a burn of `delta_v` km/s, consuming propellant. Returns false
+    /// (and burns nothing) if the tanks can't provide it.
+    pub fn burn(&mut self, delta_v: f64) -> bool {
+        let Some(engine) = self.engine_profile() else {
+            return false;
+        };
+        let propellant = propellant_for(
+            engine.exhaust_velocity_km_s,
+            self.dry_mass_t + self.fuel_t,
+            delta_v,
+        );
+        // Tolerate rounding when a plan uses up the tanks exactly
+        if propellant > self.fuel_t * (1.0 + 1e-9) {
+            return false;
+        }
+        self.fuel_t = (self.fuel_t - propellant).max(0.0);
+        true
+    }
+}
+
+/// Where a fleet is
+#[derive(Component, Debug, Clone, PartialEq)]
+pub enum FleetLocation {
+    /// In a parking orbit around a body
+    Orbiting(Entity),
+    /// Waiting for its departure window or coasting to its destination
+    InTransit(Transfer),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_burns_consume_fleet_propellant() {
+        let mut fleet = Fleet {
+            name: "Survey Group".to_string(),
+            engine: "chemical_engine".to_string(),
+            dry_mass_t: 100.0,
+            fuel_t: 200.0,
+            fuel_capacity_t: 200.0,
+        };
+        let full = fleet.delta_v_km_s();
+        assert!((full - fleet.max_delta_v_km_s()).abs() < 1e-12);
+
+        assert!(fleet.burn(2.0));
+        assert!((fleet.delta_v_km_s() - (full - 2.0)).abs() < 1e-9);
+        assert!(!fleet.burn(full));
+        assert!(fleet.burn(full - 2.0));
+        assert!(fleet.fuel_t < 1e-6);
+    }
+}
@@ -0,0 +1,427 @@
+//! Crews and life support
+//!
+//! Crewed fleets take their crew from the population of the colony they
+//! orbit and carry water, oxygen and food for them. Each person uses the
+//! same daily ration; the fleet's life support system recovers part of the
+//! water, oxygen and (with hydroponics) food, so better Life Support designs
+//! stretch the supplies further. Supplies set how long a fleet can stay away
+//! from a colony, and a fleet that runs out loses crew every day until it is
+//! resupplied.
+
+use bevy::prelude::*;
+
+use super::components::{Fleet, FleetLocation};
+use super::systems::{FleetOrders, TONNES_PER_MT};
+use crate::economy::components::Population;
+use crate::economy::{EconomyClock, GlobalBudget, ResourceType};
+use crate::game_state::{EventLog, LogSeverity};
+use crate::research::ResearchState;
+use crate::ui::SimulationTime;
+
+/// Water drunk and used for hygiene per person and day in tonnes
+pub const WATER_PER_PERSON_DAY_T: f64 = 0.0035;
+/// Oxygen breathed per person and day in tonnes
+pub const OXYGEN_PER_PERSON_DAY_T: f64 = 0.00084;
+/// Food eaten per person and day in tonnes
+pub const FOOD_PER_PERSON_DAY_T: f64 = 0.0018;
+/// Share of the crew lost per day once any supply has run out
+pub const CREW_LOSS_PER_DAY: f64 = 0.02;
+
+/// Recycling performance of a life support component
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LifeSupportProfile {
+    /// Component design providing the system
+    pub component: &'static str,
+    pub name: &'static str,
+    /// Share of the used water recovered
+    pub water_recovery: f64,
+    /// Share of the breathed oxygen recovered from carbon dioxide
+    pub oxygen_recovery: f64,
+    /// Share of the food grown aboard
+    pub food_recovery: f64,
+}
+
+/// Life support systems in order of increasing performance
+pub const LIFE_SUPPORT: &[LifeSupportProfile] = &[
+    LifeSupportProfile {
+        component: "life_support",
+        name: "Life Support System",
+        water_recovery: 0.85,
+        oxygen_recovery: 0.4,
+        food_recovery: 0.0,
+    },
+    LifeSupportProfile {
+        component: "basic_life_support",
+        name: "Basic Life Support Module",
+        water_recovery: 0.93,
+        oxygen_recovery: 0.6,
+        food_recovery: 0.0,
+    },
+    LifeSupportProfile {
+        component: "advanced_life_support",
+        name: "Advanced Life Support Module",
+        water_recovery: 0.98,
+        oxygen_recovery: 0.9,
+        food_recovery: 0.5,
+    },
+];
+
+/// Profile of a life support component
+pub fn life_support(component: &str) -> Option<&'static LifeSupportProfile> {
+    LIFE_SUPPORT
+        .iter()
+        .find(|system| system.component == component)
+}
+
+/// Best life support among the engineered component designs. The ISS-class
+/// system is the 2026 baseline and always available.
+pub fn best_life_support(research_state: Option<&ResearchState>) -> &'static LifeSupportProfile {
+    LIFE_SUPPORT
+        .iter()
+        .rev()
+        .find(|system| research_state.is_some_and(|r| r.is_component_completed(system.component)))
+        .unwrap_or(&LIFE_SUPPORT[0])
+}
+
+/// People needed to operate a fleet, and those aboard
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct Crew {
+    pub required: u32,
+    pub aboard: u32,
+}
+
+impl Crew {
+    /// Crew still needed to fill every post
+    pub fn missing(&self) -> u32 {
+        self.required.saturating_sub(self.aboard)
+    }
+}
+
+/// Life support system and the supplies carried for the crew
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct LifeSupport {
+    /// Life support component design (see [`LIFE_SUPPORT`])
+    pub system: String,
+    pub water_t: f64,
+    pub oxygen_t: f64,
+    pub food_t: f64,
+    /// Storage for each of the three supplies in tonnes
+    pub capacity_t: f64,
+    /// Whether the supplies have run out
+    pub exhausted: bool,
+    /// Crew lost to the shortage that don't yet amount to a whole person,
+    /// carried over so small crews dwindle too
+    pub attrition: f64,
+}
+
+impl LifeSupport {
+    pub fn profile(&self) -> Option<&'static LifeSupportProfile> {
+        life_support(&self.system)
+    }
+
+    /// Water, oxygen and food used per day by `crew` people in tonnes
+    pub fn daily_use_t(&self, crew: u32) -> [f64; 3] {
+        let (water, oxygen, food) = self.profile().map_or((0.0, 0.0, 0.0), |p| {
+            (p.water_recovery, p.oxygen_recovery, p.food_recovery)
+        });
+        let people = crew as f64;
+        [
+            people * WATER_PER_PERSON_DAY_T * (1.0 - water),
+            people * OXYGEN_PER_PERSON_DAY_T * (1.0 - oxygen),
+            people * FOOD_PER_PERSON_DAY_T * (1.0 - food),
+        ]
+    }
+
+    /// Days the supplies last for `crew` people
+    pub fn endurance_days(&self, crew: u32) -> f64 {
+        let held = [self.water_t, self.oxygen_t, self.food_t];
+        self.daily_use_t(crew)
+            .iter()
+            .zip(held)
+            .filter(|(used, _)| **used > 0.0)
+            .map(|(used, held)| held / used)
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    /// Use up `days` of supplies for `crew` people. Returns the days that
+    /// were covered before a supply ran out.
+    pub fn consume(&mut self, crew: u32, days: f64) -> f64 {
+        let covered = self.endurance_days(crew).min(days);
+        let [water, oxygen, food] = self.daily_use_t(crew);
+        self.water_t = (self.water_t - water * covered).max(0.0);
+        self.oxygen_t = (self.oxygen_t - oxygen * covered).max(0.0);
+        self.food_t = (self.food_t - food * covered).max(0.0);
+        covered
+    }
+}
+
+/// System that feeds the crews for each day passed. Crews without supplies
+/// dwindle until their fleet is resupplied.
+pub fn consume_life_support(
+    clock: Res<EconomyClock>,
+    sim_time: Res<SimulationTime>,
+    mut log: ResMut<EventLog>,
+    mut fleets: Query<(&Fleet, &mut Crew, &mut LifeSupport)>,
+) {
+    if clock.due == 0 {
+        return;
+    }
+    let days = clock.due as f64;
+
+    for (fleet, mut crew, mut supplies) in fleets.iter_mut() {
+        if crew.aboard == 0 {
+            continue;
+        }
+        let covered = supplies.consume(crew.aboard, days);
+        if covered >= days {
+            supplies.exhausted = false;
+            supplies.attrition = 0.0;
+            continue;
+        }
+
+        let dying = crew.aboard as f64 * (1.0 - (1.0 - CREW_LOSS_PER_DAY).powf(days - covered))
+            + supplies.attrition;
+        let lost = (dying.floor() as u32).min(crew.aboard);
+        supplies.attrition = if lost < crew.aboard {
+            dying - lost as f64
+        } else {
+            0.0
+        };
+        crew.aboard -= lost;
+        if !supplies.exhausted {
+            supplies.exhausted = true;
+            log.push(
+                sim_time.format_date_time(),
+                LogSeverity::Critical,
+                format!("{} has run out of life support supplies", fleet.name),
+            );
+        }
+        if crew.aboard == 0 && lost > 0 {
+            log.push(
+                sim_time.format_date_time(),
+                LogSeverity::Critical,
+                format!("The last of the crew of {} has died", fleet.name),
+            );
+        }
+    }
+}
+
+/// System to crew and resupply the fleets ordered from the UI. Crew are
+/// drawn from the population of the colony the fleet orbits, water and
+/// oxygen from the stockpile, and food is provisioned by the colony.
+pub fn issue_crew_orders(
+    mut orders: ResMut<FleetOrders>,
+    sim_time: Res<SimulationTime>,
+    mut budget: ResMut<GlobalBudget>,
+    mut log: ResMut<EventLog>,
+    mut fleets: Query<(&Fleet, &FleetLocation, &mut Crew, Option<&mut LifeSupport>)>,
+    mut colonies: Query<&mut Population>,
+) {
+    if orders.crewings.is_empty() && orders.resupplies.is_empty() {
+        return;
+    }
+    let date = sim_time.format_date_time();
+
+    let crewings: Vec<Entity> = orders.crewings.drain(..).collect();
+    for fleet_entity in crewings {
+        let Ok((fleet, location, mut crew, _)) = fleets.get_mut(fleet_entity) else {
+            continue;
+        };
+        let FleetLocation::Orbiting(body) = *location else {
+            continue;
+        };
+        let Ok(mut population) = colonies.get_mut(body) else {
+            log.push(
+                date.clone(),
+                LogSeverity::Warning,
+                format!("{} can only take on crew at a colony", fleet.name),
+            );
+            continue;
+        };
+        let recruited = (crew.missing() as f64).min(population.count.floor().max(0.0));
+        population.count -= recruited;
+        crew.aboard += recruited as u32;
+        if crew.missing() > 0 {
+            log.push(
+                date.clone(),
+                LogSeverity::Warning,
+                format!(
+                    "{} is short of {} crew after recruiting",
+                    fleet.name,
+                    crew.missing()
+                ),
+            );
+        }
+    }
+
+    let resupplies: Vec<Entity> = orders.resupplies.drain(..).collect();
+    for fleet_entity in resupplies {
+        let Ok((fleet, location, _, Some(mut supplies))) = fleets.get_mut(fleet_entity) else {
+            continue;
+        };
+        let FleetLocation::Orbiting(body) = *location else {
+            continue;
+        };
+        if !colonies.get(body).is_ok_and(|p| p.count > 0.0) {
+            log.push(
+                date.clone(),
+                LogSeverity::Warning,
+                format!("{} can only resupply at a colony", fleet.name),
+            );
+            continue;
+        }
+
+        let supplies = &mut *supplies;
+        let capacity = supplies.capacity_t;
+        let mut short = Vec::new();
+        for (resource, held) in [
+            (ResourceType::Water, &mut supplies.water_t),
+            (ResourceType::Oxygen, &mut supplies.oxygen_t),
+        ] {
+            let wanted = (capacity - *held).max(0.0);
+            let taken = wanted.min(budget.get_stockpile(&resource) * TONNES_PER_MT);
+            if taken > 0.0 && budget.consume_resource(resource, taken / TONNES_PER_MT) {
+                *held += taken;
+            }
+            if *held < capacity {
+                short.push(resource.to_string());
+            }
+        }
+        supplies.food_t = capacity;
+        supplies.exhausted = false;
+
+        if !short.is_empty() {
+            log.push(
+                date.clone(),
+                LogSeverity::Warning,
+                format!(
+                    "{} could not fill its {} stores",
+                    fleet.name,
+                    short.join(" and ")
+                ),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::economy::tick::{advance_economy_clock, ECONOMY_TICK_SECONDS};
+
+    fn supplies(system: &str) -> LifeSupport {
+        LifeSupport {
+            system: system.to_string(),
+            water_t: 10.0,
+            oxygen_t: 10.0,
+            food_t: 10.0,
+            capacity_t: 10.0,
+            exhausted: false,
+            attrition: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_better_life_support_lasts_longer() {
+        let basic = supplies("life_support").endurance_days(100);
+        let advanced = supplies("advanced_life_support").endurance_days(100);
+        // Food is the limit without hydroponics: 10 t / (100 × 1.8 kg)
+        assert!((basic - 10.0 / (100.0 * FOOD_PER_PERSON_DAY_T)).abs() < 1e-9);
+        assert!(advanced > basic * 1.9);
+        assert_eq!(supplies("life_support").endurance_days(0), f64::INFINITY);
+
+        let mut research = ResearchState::default();
+        assert_eq!(best_life_support(Some(&research)).component, "life_support");
+        research.complete_component("advanced_life_support".to_string());
+        assert_eq!(
+            best_life_support(Some(&research)).component,
+            "advanced_life_support"
+        );
+    }
+
+    #[test]
+    fn test_crew_dwindles_without_supplies() {
+        let mut app = App::new();
+        app.init_resource::<SimulationTime>()
+            .init_resource::<EconomyClock>()
+            .init_resource::<EventLog>()
+            .add_systems(
+                Update,
+                (advance_economy_clock, consume_life_support).chain(),
+            );
+
+        let mut provisions = supplies("life_support");
+        provisions.food_t = 100.0 * FOOD_PER_PERSON_DAY_T * 5.0;
+        let fleet = app
+            .world_mut()
+            .spawn((
+                Fleet {
+                    name: "Long Haul".to_string(),
+                    engine: "chemical_engine".to_string(),
+                    dry_mass_t: 100.0,
+                    fuel_t: 0.0,
+                    fuel_capacity_t: 100.0,
+                },
+                Crew {
+                    required: 100,
+                    aboard: 100,
+                },
+                provisions,
+            ))
+            .id();
+
+        // Five days of food, then five days of attrition
+        app.world_mut().resource_mut::<SimulationTime>().elapsed = 10.0 * ECONOMY_TICK_SECONDS;
+        app.update();
+
+        let supplies = app.world().get::<LifeSupport>(fleet).unwrap();
+        assert!(supplies.exhausted);
+        assert!(supplies.food_t < 1e-9);
+        let dead = (100.0 * (1.0 - (1.0 - CREW_LOSS_PER_DAY).powf(5.0))).floor() as u32;
+        assert_eq!(app.world().get::<Crew>(fleet).unwrap().aboard, 100 - dead);
+        assert_eq!(app.world().resource::<EventLog>().entries.len(), 1);
+    }
+
+    #[test]
+    fn test_small_crew_dwindles_day_by_day() {
+        let mut app = App::new();
+        app.init_resource::<SimulationTime>()
+            .init_resource::<EconomyClock>()
+            .init_resource::<EventLog>()
+            .add_systems(
+                Update,
+                (advance_economy_clock, consume_life_support).chain(),
+            );
+
+        let mut provisions = supplies("life_support");
+        provisions.food_t = 0.0;
+        let fleet = app
+            .world_mut()
+            .spawn((
+                Fleet {
+                    name: "Skiff".to_string(),
+                    engine: "chemical_engine".to_string(),
+                    dry_mass_t: 10.0,
+                    fuel_t: 0.0,
+                    fuel_capacity_t: 10.0,
+                },
+                Crew {
+                    required: 20,
+                    aboard: 20,
+                },
+                provisions,
+            ))
+            .id();
+
+        // Less than one death a day, which must still add up
+        for day in 1..=30 {
+            app.world_mut().resource_mut::<SimulationTime>().elapsed =
+                day as f64 * ECONOMY_TICK_SECONDS;
+            app.update();
+        }
+
+        let dead = (20.0 * (1.0 - (1.0 - CREW_LOSS_PER_DAY).powf(30.0))).floor() as u32;
+        assert!(dead > 0);
+        assert_eq!(app.world().get::<Crew>(fleet).unwrap().aboard, 20 - dead);
+    }
+}
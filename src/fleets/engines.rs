@@ -0,0 +1,124 @@
+//! Engine components and the rocket equation
+
+use crate::economy::ResourceType;
+use crate::research::ResearchState;
+
+/// Performance of an engine component
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EngineProfile {
+    /// Component design providing the engine
+    pub component: &'static str,
+    pub name: &'static str,
+    /// Effective exhaust velocity in km/s (specific impulse × g₀)
+    pub exhaust_velocity_km_s: f64,
+    /// Propellant drawn from the fleet's stores
+    pub fuel: ResourceType,
+}
+
+/// Engines in order of increasing performance
+pub const ENGINES: &[EngineProfile] = &[
+    EngineProfile {
+        component: "chemical_engine",
+        name: "Chemical Rocket Engine",
+        exhaust_velocity_km_s: 3.7,
+        fuel: ResourceType::Methane,
+    },
+    EngineProfile {
+        component: "ion_engine",
+        name: "Ion Drive",
+        exhaust_velocity_km_s: 30.0,
+        fuel: ResourceType::Hydrogen,
+    },
+    EngineProfile {
+        component: "plasma_engine",
+        name: "Plasma Drive",
+        exhaust_velocity_km_s: 60.0,
+        fuel: ResourceType::Hydrogen,
+    },
+    EngineProfile {
+        component: "fusion_drive",
+        name: "Fusion Torch Drive",
+        exhaust_velocity_km_s: 300.0,
+        fuel: ResourceType::Hydrogen,
+    },
+    EngineProfile {
+        component: "fusion_drive_mk2",
+        name: "Fusion Drive Mk2",
+        exhaust_velocity_km_s: 600.0,
+        fuel: ResourceType::Hydrogen,
+    },
+    EngineProfile {
+        component: "high_efficiency_fusion_mk1",
+        name: "High-Efficiency Fusion Drive",
+        exhaust_velocity_km_s: 1_000.0,
+        fuel: ResourceType::Hydrogen,
+    },
+    EngineProfile {
+        component: "acf_drive",
+        name: "Antimatter-Catalyzed Fusion Drive",
+        exhaust_velocity_km_s: 2_000.0,
+        fuel: ResourceType::Hydrogen,
+    },
+    EngineProfile {
+        component: "antimatter_drive",
+        name: "Antimatter Drive",
+        exhaust_velocity_km_s: 10_000.0,
+        fuel: ResourceType::Hydrogen,
+    },
+];
+
+/// Profile of an engine component
+pub fn engine(component: &str) -> Option<&'static EngineProfile> {
+    ENGINES.iter().find(|engine| engine.component == component)
+}
+
+/// Best engine among the engineered component designs. Chemical rockets are
+/// the 2026 baseline and always available.
+pub fn best_engine(research_state: Option<&ResearchState>) -> &'static EngineProfile {
+    ENGINES
+        .iter()
+        .rev()
+        .find(|engine| research_state.is_some_and(|r| r.is_component_completed(engine.component)))
+        .unwrap_or(&ENGINES[0])
+}
+
+/// Delta-v in km/s from burning a mass down from `wet_mass` to `dry_mass`
+/// (Tsiolkovsky rocket equation)
+pub fn delta_v_km_s(exhaust_velocity_km_s: f64, wet_mass: f64, dry_mass: f64) -> f64 {
+    if dry_mass <= 0.0 || wet_mass <= dry_mass {
+        return 0.0;
+    }
+    exhaust_velocity_km_s * (wet_mass / dry_mass).ln()
+}
+
+/// Propellant mass burned to change the velocity of `mass` by `delta_v_km_s`
+pub fn propellant_for(exhaust_velocity_km_s: f64, mass: f64, delta_v_km_s: f64) -> f64 {
+    if exhaust_velocity_km_s <= 0.0 {
+        return f64::INFINITY;
+    }
+    mass * (1.0 - (-delta_v_km_s / exhaust_velocity_km_s).exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rocket_equation_round_trip() {
+        let dv = delta_v_km_s(3.7, 300.0, 100.0);
+        assert!((dv - 3.7 * 3f64.ln()).abs() < 1e-12);
+
+        // Burning the full delta-v uses up exactly the propellant carried
+        let burned = propellant_for(3.7, 300.0, dv);
+        assert!((burned - 200.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_best_engine_follows_completed_designs() {
+        assert_eq!(best_engine(None).component, "chemical_engine");
+
+        let mut research = ResearchState::default();
+        research.complete_component("plasma_engine".to_string());
+        assert_eq!(best_engine(Some(&research)).component, "plasma_engine");
+    }
+}
@@ -0,0 +1,106 @@
+//! Fleet movement
+//!
+//! Provides realistic in-system travel for fleets:
+//! - Delta-v budgets from the fleet's engine component and propellant stores
+//!   (Tsiolkovsky rocket equation)
+//! - Hohmann transfers planned around the shared parent body, with escape
+//!   and capture burns and a departure window from the phase angle
+//! - Departure burn, coast along the transfer ellipse (solved with the
+//!   Kepler solver) and insertion burn, each consuming propellant
+//! - Refuelling with Hydrogen or Methane from the stockpile at colonies
+//! - Crews recruited from colonies, and water, oxygen and food for them that
+//!   limit how long fleets can stay away (see [`crew`])
+//! - Ordnance built at colonies into magazines and loaded onto fleets from
+//!   colonies or supply ships (see [`ordnance`])
+//! - Shipyards with parallel slipways that build hulls from refined
+//!   materials and launch them as new fleets (see [`shipyards`])
+//! - Unmanned probes sent to bodies and other star systems for partial
+//!   survey data (see [`probes`])
+//! - Salvage recovered by fleets for materials and reverse engineering
+//!   (see [`salvage`])
+//! - Hostile fleets that blockade the colonies whose orbits they control
+//!   until enough crew drives them off (see [`blockade`])
+
+use bevy::prelude::*;
+
+pub mod blockade;
+pub mod components;
+pub mod crew;
+pub mod engines;
+pub mod ordnance;
+pub mod probes;
+pub mod salvage;
+pub mod shipyards;
+pub mod systems;
+pub mod transfer;
+
+pub use blockade::{update_blockades, Blockade, Blockades, HostileFleet, HostileFleetDeparted};
+pub use components::{Fleet, FleetLocation};
+pub use crew::{
+    best_life_support, consume_life_support, issue_crew_orders, life_support, Crew, LifeSupport,
+    LifeSupportProfile, LIFE_SUPPORT,
+};
+pub use engines::{best_engine, engine, EngineProfile, ENGINES};
+pub use ordnance::{
+    advance_reloads, ordnance, produce_ordnance, start_reloads, update_colony_magazines,
+    update_ordnance_ledger, Magazine, MagazineStorage, OrdnanceFactory, OrdnanceLedger,
+    OrdnanceProfile, Reload, SupplyShip, ORDNANCE,
+};
+pub use probes::{
+    advance_probes, launch_probes, Probe, ProbeMission, ProbeTarget, Probes, PROBE_DELTA_V_KM_S,
+};
+pub use salvage::{recover_salvage, Salvage};
+pub use shipyards::{
+    advance_shipyards, hull, issue_hull_orders, update_shipyards, HullProfile, Shipyard,
+    ShipyardFacility, Slipway, HULLS, RETOOL_DAYS,
+};
+pub use systems::{
+    advance_fleets, draw_fleets, issue_fleet_orders, orbit_nodes, BodyNodes, FleetOrders,
+    TONNES_PER_MT,
+};
+pub use transfer::{delta_v_map, plan_transfer, DeltaVBand, OrbitNode, Transfer};
+
+/// Plugin that adds fleet movement to the Bevy app
+pub struct FleetsPlugin;
+
+impl Plugin for FleetsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FleetOrders>()
+            .init_resource::<OrdnanceLedger>()
+            .init_resource::<Probes>()
+            .init_resource::<Blockades>()
+            .add_event::<HostileFleetDeparted>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    advance_fleets.after(crate::astronomy::propagate_orbits),
+                    consume_life_support.after(crate::economy::advance_economy_clock),
+                    produce_ordnance
+                        .after(crate::economy::advance_economy_clock)
+                        .after(crate::economy::update_power_grid),
+                    advance_reloads.after(crate::economy::advance_economy_clock),
+                    advance_shipyards.after(crate::economy::advance_economy_clock),
+                    update_blockades
+                        .after(crate::economy::advance_economy_clock)
+                        .before(crate::economy::update_food_supply)
+                        .before(crate::trade::carry_trade),
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    issue_fleet_orders,
+                    issue_crew_orders,
+                    draw_fleets,
+                    update_colony_magazines,
+                    start_reloads,
+                    update_ordnance_ledger,
+                    update_shipyards,
+                    issue_hull_orders.after(update_shipyards),
+                    launch_probes,
+                    advance_probes.after(launch_probes),
+                    recover_salvage,
+                ),
+            );
+    }
+}
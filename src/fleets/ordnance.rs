@@ -0,0 +1,526 @@
+//! Expendable ordnance and magazine logistics
+//!
+//! Missile factories build ordnance at colonies, paying its refined materials
+//! from the stockpile, into the colony's magazine (sized by its storage
+//! buildings). Fleets carry magazines of their own and reload from the
+//! colony they orbit or from a [`SupplyShip`] in the same orbit, a limited
+//! number of rounds per day. Rounds leave a magazine through
+//! [`Magazine::take`], whether they are loaded onto a ship or fired.
+//! [`OrdnanceLedger`] tracks the rounds held at every location.
+
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use super::components::{Fleet, FleetLocation};
+use super::systems::{FleetOrders, TONNES_PER_MT};
+use crate::economy::{
+    condition_factor_for, Condition, EconomyClock, GlobalBudget, PowerGrids, PowerTier, RefinedGood,
+};
+use crate::game_state::{EventLog, LogSeverity};
+use crate::plugins::solar_system::{CelestialBody, LogicalParent};
+use crate::research::ResearchState;
+use crate::ui::SimulationTime;
+
+/// Rounds a fleet can take on per day from a magazine in the same orbit
+pub const RELOAD_ROUNDS_PER_DAY: u32 = 20;
+
+/// A kind of expendable ordnance
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrdnanceProfile {
+    pub id: &'static str,
+    pub name: &'static str,
+    /// Mass of one round in tonnes
+    pub mass_t: f64,
+    /// Refined materials for one round in tonnes
+    pub cost_t: &'static [(RefinedGood, f64)],
+    /// Component design needed to manufacture it
+    pub required_component: &'static str,
+}
+
+/// Ordnance that can be manufactured
+pub const ORDNANCE: &[OrdnanceProfile] = &[
+    OrdnanceProfile {
+        id: "anti_ship_missile",
+        name: "Anti-Ship Missile",
+        mass_t: 1.5,
+        cost_t: &[
+            (RefinedGood::Steel, 1.0),
+            (RefinedGood::Electronics, 0.2),
+            (RefinedGood::Fuel, 0.3),
+        ],
+        required_component: "missile_launcher",
+    },
+    OrdnanceProfile {
+        id: "antimatter_torpedo",
+        name: "Antimatter Torpedo",
+        mass_t: 4.0,
+        cost_t: &[
+            (RefinedGood::Steel, 2.5),
+            (RefinedGood::Electronics, 0.5),
+            (RefinedGood::Fuel, 1.0),
+        ],
+        required_component: "antimatter_warhead",
+    },
+];
+
+/// Profile of an ordnance type
+pub fn ordnance(id: &str) -> Option<&'static OrdnanceProfile> {
+    ORDNANCE.iter().find(|profile| profile.id == id)
+}
+
+/// Display name of an ordnance type, falling back to its ID
+pub fn ordnance_name(id: &str) -> &str {
+    ordnance(id).map_or(id, |profile| profile.name)
+}
+
+/// Rounds of ordnance held by a colony or a fleet
+#[derive(Component, Debug, Clone, Default, PartialEq)]
+pub struct Magazine {
+    /// Rounds that fit in the magazine
+    pub capacity: u32,
+    /// Rounds held per ordnance type
+    pub rounds: HashMap<String, u32>,
+}
+
+impl Magazine {
+    pub fn new(capacity: u32) -> Self {
+        Self {
+            capacity,
+            rounds: HashMap::new(),
+        }
+    }
+
+    pub fn count(&self, ordnance: &str) -> u32 {
+        self.rounds.get(ordnance).copied().unwrap_or(0)
+    }
+
+    pub fn total(&self) -> u32 {
+        self.rounds.values().sum()
+    }
+
+    /// Room left for more rounds
+    pub fn free(&self) -> u32 {
+        self.capacity.saturating_sub(self.total())
+    }
+
+    /// Store up to `count` rounds; returns how many fit
+    pub fn store(&mut self, ordnance: &str, count: u32) -> u32 {
+        let stored = count.min(self.free());
+        if stored > 0 {
+            *self.rounds.entry(ordnance.to_string()).or_insert(0) += stored;
+        }
+        stored
+    }
+
+    /// Remove up to `count` rounds; returns how many were there
+    pub fn take(&mut self, ordnance: &str, count: u32) -> u32 {
+        let Some(held) = self.rounds.get_mut(ordnance) else {
+            return 0;
+        };
+        let taken = count.min(*held);
+        *held -= taken;
+        if *held == 0 {
+            self.rounds.remove(ordnance);
+        }
+        taken
+    }
+
+    /// The ordnance type held in the largest number
+    pub fn most_stocked(&self) -> Option<&str> {
+        self.rounds
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(id, _)| id.as_str())
+    }
+}
+
+/// Ordnance storage of a building, adding to its colony's magazine
+#[derive(Component, Debug, Clone, Copy)]
+pub struct MagazineStorage {
+    pub capacity: u32,
+}
+
+/// Ordnance production of a building
+#[derive(Component, Debug, Clone)]
+pub struct OrdnanceFactory {
+    pub ordnance: String,
+    pub rounds_per_year: f64,
+    /// Fraction of the next round completed
+    pub progress: f64,
+}
+
+/// A fleet that carries ordnance for other fleets to reload from
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct SupplyShip;
+
+/// A fleet taking on ordnance
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct Reload {
+    pub ordnance: String,
+    /// Rounds still to load
+    pub remaining: u32,
+}
+
+/// Ordnance held at one location
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrdnanceStock {
+    pub holder: Entity,
+    pub name: String,
+    pub is_fleet: bool,
+    pub capacity: u32,
+    pub rounds: HashMap<String, u32>,
+}
+
+/// Ordnance across the logistics network: every colony and fleet magazine
+#[derive(Resource, Debug, Clone, Default)]
+pub struct OrdnanceLedger {
+    pub stocks: Vec<OrdnanceStock>,
+}
+
+impl OrdnanceLedger {
+    /// Rounds of `ordnance` held anywhere
+    pub fn total(&self, ordnance: &str) -> u32 {
+        self.stocks
+            .iter()
+            .map(|stock| stock.rounds.get(ordnance).copied().unwrap_or(0))
+            .sum()
+    }
+}
+
+/// System to size colony magazines from their storage buildings. Rounds
+/// beyond a shrunk capacity are kept, but nothing more can be stored.
+pub fn update_colony_magazines(
+    mut commands: Commands,
+    storage: Query<(&MagazineStorage, &LogicalParent)>,
+    mut magazines: Query<(Entity, &mut Magazine), Without<Fleet>>,
+) {
+    let mut capacities: HashMap<Entity, u32> = HashMap::new();
+    for (store, parent) in storage.iter() {
+        *capacities.entry(parent.0).or_insert(0) += store.capacity;
+    }
+
+    for (entity, mut magazine) in magazines.iter_mut() {
+        let capacity = capacities.remove(&entity).unwrap_or(0);
+        if magazine.capacity != capacity {
+            magazine.capacity = capacity;
+        }
+    }
+    for (location, capacity) in capacities {
+        if let Some(mut location) = commands.get_entity(location) {
+            location.insert(Magazine::new(capacity));
+        }
+    }
+}
+
+/// Pay the materials of one round made at `location` from the stockpile, all
+/// or nothing
+fn pay_for_round(budget: &mut GlobalBudget, location: Entity, profile: &OrdnanceProfile) -> bool {
+    let affordable = profile
+        .cost_t
+        .iter()
+        .all(|(good, tonnes)| budget.get_refined(good) * TONNES_PER_MT >= *tonnes);
+    if !affordable {
+        return false;
+    }
+    for (good, tonnes) in profile.cost_t {
+        budget.consume_refined_at(location, *good, tonnes / TONNES_PER_MT);
+    }
+    true
+}
+
+/// System that advances ordnance factories once per due economy tick. A
+/// factory stalls while its ordnance is not engineered, the colony magazine
+/// is full or the stockpile lacks materials.
+pub fn produce_ordnance(
+    clock: Res<EconomyClock>,
+    research_state: Res<ResearchState>,
+    mut budget: ResMut<GlobalBudget>,
+    power: Option<Res<PowerGrids>>,
+    conditions: Query<&Condition>,
+    mut factories: Query<(Entity, &mut OrdnanceFactory, &LogicalParent)>,
+    mut magazines: Query<&mut Magazine, Without<Fleet>>,
+) {
+    if clock.due == 0 {
+        return;
+    }
+
+    for (entity, mut factory, parent) in factories.iter_mut() {
+        let Some(profile) = ordnance(&factory.ordnance) else {
+            continue;
+        };
+        if !research_state.is_component_completed(profile.required_component) {
+            continue;
+        }
+        let Ok(mut magazine) = magazines.get_mut(parent.0) else {
+            continue;
+        };
+
+        // Browned out and worn factories work at a reduced rate
+        let supply = power.as_ref().map_or(1.0, |p| {
+            p.supply_for(entity, Some(parent), PowerTier::Industry)
+        }) * condition_factor_for(&conditions, entity);
+        factory.progress +=
+            factory.rounds_per_year * supply * EconomyClock::TICK_YEARS * clock.due as f64;
+        while factory.progress >= 1.0 {
+            if magazine.free() == 0 || !pay_for_round(&mut budget, parent.0, profile) {
+                // Stalled with the next round ready to go
+                factory.progress = 1.0;
+                break;
+            }
+            magazine.store(profile.id, 1);
+            factory.progress -= 1.0;
+        }
+    }
+}
+
+/// System that turns load orders into reloads of fleets in orbit
+pub fn start_reloads(
+    mut commands: Commands,
+    mut orders: ResMut<FleetOrders>,
+    fleets: Query<&FleetLocation, (With<Fleet>, With<Magazine>)>,
+) {
+    for (fleet, ordnance, count) in orders.loads.drain(..) {
+        if count == 0 || !matches!(fleets.get(fleet), Ok(FleetLocation::Orbiting(_))) {
+            continue;
+        }
+        commands.entity(fleet).insert(Reload {
+            ordnance,
+            remaining: count,
+        });
+    }
+}
+
+/// System that moves ordnance into reloading fleets, from the colony they
+/// orbit or a supply ship in the same orbit
+#[allow(clippy::too_many_arguments)]
+pub fn advance_reloads(
+    mut commands: Commands,
+    clock: Res<EconomyClock>,
+    sim_time: Res<SimulationTime>,
+    mut log: ResMut<EventLog>,
+    mut reloading: Query<(Entity, &Fleet, &FleetLocation, &mut Reload)>,
+    supply_ships: Query<(Entity, &FleetLocation), With<SupplyShip>>,
+    bodies: Query<&CelestialBody>,
+    mut magazines: Query<&mut Magazine>,
+) {
+    if clock.due == 0 {
+        return;
+    }
+
+    for (entity, fleet, location, mut reload) in reloading.iter_mut() {
+        let FleetLocation::Orbiting(body) = *location else {
+            commands.entity(entity).remove::<Reload>();
+            continue;
+        };
+
+        // Colony first, then supply ships sharing the orbit
+        let source = std::iter::once(body)
+            .chain(
+                supply_ships
+                    .iter()
+                    .filter(|(ship, at)| {
+                        *ship != entity && **at == FleetLocation::Orbiting(body)
+                    })
+                    .map(|(ship, _)| ship),
+            )
+            .find(|source| {
+                magazines
+                    .get(*source)
+                    .is_ok_and(|m| m.count(&reload.ordnance) > 0)
+            });
+
+        let loaded = match source.and_then(|s| magazines.get_many_mut([s, entity]).ok()) {
+            Some([mut from, mut to]) => {
+                let per_update =
+                    u32::try_from(RELOAD_ROUNDS_PER_DAY as u64 * clock.due).unwrap_or(u32::MAX);
+                let wanted = per_update.min(reload.remaining).min(to.free());
+                let taken = from.take(&reload.ordnance, wanted);
+                let stored = to.store(&reload.ordnance, taken);
+                // Whatever did not fit goes back
+                from.store(&reload.ordnance, taken - stored);
+                stored
+            }
+            None => 0,
+        };
+        reload.remaining -= loaded;
+
+        let full = !magazines.get(entity).is_ok_and(|m| m.free() > 0);
+        let body_name = bodies
+            .get(body)
+            .map_or("Unknown".to_string(), |b| b.name.clone());
+        let message = if reload.remaining == 0 || full {
+            Some((
+                LogSeverity::Info,
+                format!(
+                    "{} finished loading {} at {}",
+                    fleet.name,
+                    ordnance_name(&reload.ordnance),
+                    body_name
+                ),
+            ))
+        } else if source.is_none() {
+            Some((
+                LogSeverity::Warning,
+                format!(
+                    "{} stopped loading: no {} left at {}",
+                    fleet.name,
+                    ordnance_name(&reload.ordnance),
+                    body_name
+                ),
+            ))
+        } else {
+            None
+        };
+        if let Some((severity, message)) = message {
+            log.push(sim_time.format_date_time(), severity, message);
+            commands.entity(entity).remove::<Reload>();
+        }
+    }
+}
+
+/// System to record the ordnance held at every colony and fleet
+pub fn update_ordnance_ledger(
+    mut ledger: ResMut<OrdnanceLedger>,
+    magazines: Query<(Entity, &Magazine, Option<&Fleet>, Option<&CelestialBody>)>,
+) {
+    let mut stocks: Vec<OrdnanceStock> = magazines
+        .iter()
+        .filter(|(_, magazine, ..)| magazine.capacity > 0 || magazine.total() > 0)
+        .map(|(entity, magazine, fleet, body)| OrdnanceStock {
+            holder: entity,
+            name: fleet
+                .map(|f| f.name.clone())
+                .or_else(|| body.map(|b| b.name.clone()))
+                .unwrap_or_else(|| "Unknown".to_string()),
+            is_fleet: fleet.is_some(),
+            capacity: magazine.capacity,
+            rounds: magazine.rounds.clone(),
+        })
+        .collect();
+    stocks.sort_by(|a, b| a.is_fleet.cmp(&b.is_fleet).then_with(|| a.name.cmp(&b.name)));
+    ledger.stocks = stocks;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::economy::tick::{advance_economy_clock, ECONOMY_TICK_SECONDS};
+
+    fn fleet(name: &str) -> Fleet {
+        Fleet {
+            name: name.to_string(),
+            engine: "chemical_engine".to_string(),
+            dry_mass_t: 100.0,
+            fuel_t: 0.0,
+            fuel_capacity_t: 100.0,
+        }
+    }
+
+    #[test]
+    fn test_magazine_store_and_take() {
+        let mut magazine = Magazine::new(10);
+        assert_eq!(magazine.store("anti_ship_missile", 8), 8);
+        assert_eq!(magazine.store("antimatter_torpedo", 5), 2);
+        assert_eq!(magazine.free(), 0);
+        assert_eq!(magazine.most_stocked(), Some("anti_ship_missile"));
+        assert_eq!(magazine.take("anti_ship_missile", 20), 8);
+        assert_eq!(magazine.total(), 2);
+    }
+
+    #[test]
+    fn test_factory_builds_into_colony_magazine() {
+        let mut app = App::new();
+        app.init_resource::<SimulationTime>()
+            .init_resource::<EconomyClock>()
+            .init_resource::<GlobalBudget>()
+            .init_resource::<ResearchState>()
+            .add_systems(
+                Update,
+                (
+                    update_colony_magazines,
+                    advance_economy_clock,
+                    produce_ordnance.after(advance_economy_clock),
+                ),
+            );
+        {
+            let mut budget = app.world_mut().resource_mut::<GlobalBudget>();
+            budget.add_refined(RefinedGood::Fuel, 1.0);
+        }
+
+        let colony = app.world_mut().spawn_empty().id();
+        app.world_mut().spawn((
+            OrdnanceFactory {
+                ordnance: "anti_ship_missile".to_string(),
+                rounds_per_year: 365.25,
+                progress: 0.0,
+            },
+            MagazineStorage { capacity: 3 },
+            LogicalParent(colony),
+        ));
+        app.update();
+
+        // Not engineered yet
+        app.world_mut().resource_mut::<SimulationTime>().elapsed = 10.0 * ECONOMY_TICK_SECONDS;
+        app.update();
+        assert_eq!(app.world().get::<Magazine>(colony).unwrap().total(), 0);
+
+        app.world_mut()
+            .resource_mut::<ResearchState>()
+            .complete_component("missile_launcher".to_string());
+        app.world_mut().resource_mut::<SimulationTime>().elapsed = 20.0 * ECONOMY_TICK_SECONDS;
+        app.update();
+        let magazine = app.world().get::<Magazine>(colony).unwrap();
+        assert_eq!(magazine.count("anti_ship_missile"), 3);
+        let budget = app.world().resource::<GlobalBudget>();
+        assert!((budget.get_refined(&RefinedGood::Fuel) - (1.0 - 0.9e-6)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_fleet_reloads_from_supply_ship() {
+        let mut app = App::new();
+        app.init_resource::<SimulationTime>()
+            .init_resource::<EconomyClock>()
+            .init_resource::<EventLog>()
+            .init_resource::<FleetOrders>()
+            .add_systems(
+                Update,
+                (
+                    advance_economy_clock,
+                    start_reloads,
+                    advance_reloads.after(advance_economy_clock).after(start_reloads),
+                ),
+            );
+
+        let body = app.world_mut().spawn_empty().id();
+        let mut supply = Magazine::new(100);
+        supply.store("anti_ship_missile", 100);
+        app.world_mut().spawn((
+            fleet("Supply Group"),
+            FleetLocation::Orbiting(body),
+            SupplyShip,
+            supply,
+        ));
+        let warships = app
+            .world_mut()
+            .spawn((
+                fleet("Strike Group"),
+                FleetLocation::Orbiting(body),
+                Magazine::new(50),
+            ))
+            .id();
+
+        app.world_mut()
+            .resource_mut::<FleetOrders>()
+            .load(warships, "anti_ship_missile".to_string(), 50);
+        app.world_mut().resource_mut::<SimulationTime>().elapsed = ECONOMY_TICK_SECONDS;
+        app.update();
+        let magazine = app.world().get::<Magazine>(warships).unwrap();
+        assert_eq!(magazine.count("anti_ship_missile"), RELOAD_ROUNDS_PER_DAY);
+        assert!(app.world().get::<Reload>(warships).is_some());
+
+        app.world_mut().resource_mut::<SimulationTime>().elapsed = 5.0 * ECONOMY_TICK_SECONDS;
+        app.update();
+        assert_eq!(app.world().get::<Magazine>(warships).unwrap().total(), 50);
+        assert!(app.world().get::<Reload>(warships).is_none());
+    }
+}
@@ -0,0 +1,451 @@
+//! Unmanned probes
+//!
+//! Probes are the cheap way to learn about distant bodies: a colony
+//! assembles one from a few tonnes of refined goods and launches it at once,
+//! without a shipyard, crew or fleet. Within a star system a probe flies the
+//! transfer planner's trajectory on a small kick stage; a flyby only needs
+//! the departure burn, an orbiter also the capture burn. Probes sent to
+//! other star systems cruise at a fraction of light speed and fly straight
+//! through. Arriving probes return partial survey data: a flyby an orbital
+//! scan, an orbiter one survey tier more, but never past a seismic survey.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::systems::{orbit_nodes, BodyNodes, FleetOrders, TONNES_PER_MT};
+use super::transfer::{plan_transfer, Transfer};
+use crate::astronomy::components::SystemId;
+use crate::astronomy::nearby_stars::NearbyStarsData;
+use crate::economy::components::{PlanetResources, Population, SurveyLevel};
+use crate::economy::types::ResourceType;
+use crate::economy::{GlobalBudget, RefinedGood, SurveyReport, SECONDS_PER_YEAR};
+use crate::game_state::{EventLog, LogSeverity};
+use crate::plugins::solar_system::CelestialBody;
+use crate::plugins::system_populator::PopulatedSystems;
+use crate::ui::{format_duration, SimulationTime};
+
+/// Delta-v of a probe's kick stage in km/s
+pub const PROBE_DELTA_V_KM_S: f64 = 12.0;
+/// Refined goods a probe is built from, in tonnes
+pub const PROBE_COST_T: &[(RefinedGood, f64)] =
+    &[(RefinedGood::Steel, 2.0), (RefinedGood::Electronics, 0.5)];
+/// Refined goods an interstellar probe and its drive are built from, in tonnes
+pub const INTERSTELLAR_PROBE_COST_T: &[(RefinedGood, f64)] = &[
+    (RefinedGood::Steel, 40.0),
+    (RefinedGood::Electronics, 5.0),
+    (RefinedGood::Fuel, 200.0),
+];
+/// Cruise speed of interstellar probes as a fraction of light speed
+pub const INTERSTELLAR_PROBE_SPEED_C: f64 = 0.05;
+
+/// What a probe does once it reaches a body
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProbeMission {
+    /// Passes the body on the transfer, without braking
+    Flyby,
+    /// Brakes into orbit and maps the body
+    Orbiter,
+}
+
+impl ProbeMission {
+    pub const ALL: [ProbeMission; 2] = [ProbeMission::Flyby, ProbeMission::Orbiter];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ProbeMission::Flyby => "Flyby",
+            ProbeMission::Orbiter => "Orbiter",
+        }
+    }
+
+    /// Delta-v the kick stage has to provide for `transfer` in km/s
+    pub fn delta_v_km_s(&self, transfer: &Transfer) -> f64 {
+        match self {
+            ProbeMission::Flyby => transfer.departure_dv_km_s,
+            ProbeMission::Orbiter => transfer.total_dv_km_s(),
+        }
+    }
+
+    /// Survey level a body at `current` reaches when the probe arrives,
+    /// if the probe adds anything
+    pub fn survey_level(&self, current: SurveyLevel) -> Option<SurveyLevel> {
+        match self {
+            ProbeMission::Flyby => {
+                (current == SurveyLevel::Unsurveyed).then_some(SurveyLevel::OrbitalScan)
+            }
+            ProbeMission::Orbiter => current
+                .next()
+                .filter(|level| level.tier() <= SurveyLevel::SeismicSurvey.tier()),
+        }
+    }
+}
+
+/// Where a probe is headed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProbeTarget {
+    Body(Entity),
+    /// Catalog id of another star system
+    System(usize),
+}
+
+/// A probe on its way to its target
+#[derive(Debug, Clone, PartialEq)]
+pub struct Probe {
+    pub name: String,
+    pub mission: ProbeMission,
+    pub target: ProbeTarget,
+    pub target_name: String,
+    /// Colony the probe was launched from
+    pub origin: Entity,
+    /// Trajectory within the star system; interstellar probes cruise
+    /// straight to their target
+    pub transfer: Option<Transfer>,
+    /// Simulation time of the launch in seconds
+    pub launch_time: f64,
+    /// Simulation time of the arrival in seconds
+    pub arrival_time: f64,
+}
+
+impl Probe {
+    /// Fraction of the trip completed (0.0 to 1.0)
+    pub fn progress(&self, time: f64) -> f64 {
+        let duration = self.arrival_time - self.launch_time;
+        if duration <= 0.0 {
+            1.0
+        } else {
+            ((time - self.launch_time) / duration).clamp(0.0, 1.0)
+        }
+    }
+}
+
+/// Probes in flight
+#[derive(Resource, Debug, Clone, Default)]
+pub struct Probes {
+    pub active: Vec<Probe>,
+    /// Probes launched so far, for naming
+    pub launched: u32,
+    /// Star systems reached by probes whose bodies are scanned once the
+    /// system is populated
+    pub pending_scans: Vec<usize>,
+}
+
+/// Pay the refined goods of `cost` from the stockpile, all or nothing
+fn pay(budget: &mut GlobalBudget, cost: &[(RefinedGood, f64)]) -> bool {
+    let affordable = cost
+        .iter()
+        .all(|(good, tonnes)| budget.get_refined(good) * TONNES_PER_MT >= *tonnes);
+    if affordable {
+        for (good, tonnes) in cost {
+            budget.consume_refined(*good, tonnes / TONNES_PER_MT);
+        }
+    }
+    affordable
+}
+
+/// Years an interstellar probe needs to cover `distance_ly`
+pub fn interstellar_years(distance_ly: f64) -> f64 {
+    distance_ly / INTERSTELLAR_PROBE_SPEED_C
+}
+
+/// System that builds and launches the probes ordered from the UI.
+///
+/// Probes to bodies leave from the most populated colony of the body's star
+/// system and are refused if the kick stage lacks the delta-v; interstellar
+/// probes leave from the most populated colony anywhere.
+#[allow(clippy::too_many_arguments)]
+pub fn launch_probes(
+    mut orders: ResMut<FleetOrders>,
+    mut probes: ResMut<Probes>,
+    mut budget: ResMut<GlobalBudget>,
+    mut log: ResMut<EventLog>,
+    sim_time: Res<SimulationTime>,
+    stars_data: Option<Res<NearbyStarsData>>,
+    bodies: BodyNodes,
+    colonies: Query<(Entity, &Population, Option<&SystemId>), With<CelestialBody>>,
+    systems: Query<&SystemId>,
+) {
+    if orders.probes.is_empty() && orders.interstellar_probes.is_empty() {
+        return;
+    }
+    let now = sim_time.elapsed_seconds();
+    let date = sim_time.format_date_time();
+    let name_of = |entity: Entity| {
+        bodies
+            .get(entity)
+            .map_or("Unknown".to_string(), |(_, body, ..)| body.name.clone())
+    };
+    // Most populated colony, within `system` if given
+    let origin_in = |system: Option<usize>| {
+        colonies
+            .iter()
+            .filter(|(_, population, _)| population.count > 0.0)
+            .filter(|(.., id)| system.is_none_or(|s| id.map_or(0, |id| id.0) == s))
+            .max_by(|a, b| a.1.count.total_cmp(&b.1.count))
+            .map(|(entity, ..)| entity)
+    };
+
+    let launches: Vec<(Entity, ProbeMission)> = orders.probes.drain(..).collect();
+    let nodes = if launches.is_empty() {
+        Default::default()
+    } else {
+        orbit_nodes(&bodies)
+    };
+    for (target, mission) in launches {
+        let system = systems.get(target).map_or(0, |id| id.0);
+        let Some(origin) = origin_in(Some(system)) else {
+            log.push(
+                date.clone(),
+                LogSeverity::Warning,
+                format!(
+                    "No colony in the system of {} to launch a probe",
+                    name_of(target)
+                ),
+            );
+            continue;
+        };
+        let transfer = match plan_transfer(origin, target, now, &nodes) {
+            Ok(transfer) => transfer,
+            Err(e) => {
+                log.push(
+                    date.clone(),
+                    LogSeverity::Warning,
+                    format!("Cannot send a probe to {}: {}", name_of(target), e),
+                );
+                continue;
+            }
+        };
+        let delta_v = mission.delta_v_km_s(&transfer);
+        if delta_v > PROBE_DELTA_V_KM_S {
+            log.push(
+                date.clone(),
+                LogSeverity::Warning,
+                format!(
+                    "A probe cannot reach {}: needs {:.2} km/s of delta-v, has {:.2} km/s",
+                    name_of(target),
+                    delta_v,
+                    PROBE_DELTA_V_KM_S
+                ),
+            );
+            continue;
+        }
+        if !pay(&mut budget, PROBE_COST_T) {
+            log.push(
+                date.clone(),
+                LogSeverity::Warning,
+                "Not enough refined goods to build a probe".to_string(),
+            );
+            continue;
+        }
+
+        probes.launched += 1;
+        let probe = Probe {
+            name: format!("Probe {}", probes.launched),
+            mission,
+            target: ProbeTarget::Body(target),
+            target_name: name_of(target),
+            origin,
+            launch_time: now,
+            arrival_time: transfer.arrival_time,
+            transfer: Some(transfer),
+        };
+        log.push(
+            date.clone(),
+            LogSeverity::Info,
+            format!(
+                "{} ({}) launched from {} to {}, arriving in {}",
+                probe.name,
+                mission.display_name(),
+                name_of(origin),
+                probe.target_name,
+                format_duration(probe.arrival_time - now)
+            ),
+        );
+        probes.active.push(probe);
+    }
+
+    let launches: Vec<usize> = orders.interstellar_probes.drain(..).collect();
+    for system in launches {
+        let Some(data) = stars_data.as_ref().and_then(|data| data.get_by_id(system)) else {
+            continue;
+        };
+        let Some(origin) = origin_in(None) else {
+            continue;
+        };
+        if !pay(&mut budget, INTERSTELLAR_PROBE_COST_T) {
+            log.push(
+                date.clone(),
+                LogSeverity::Warning,
+                "Not enough refined goods to build an interstellar probe".to_string(),
+            );
+            continue;
+        }
+
+        probes.launched += 1;
+        let years = interstellar_years(data.distance_ly as f64);
+        let probe = Probe {
+            name: format!("Probe {}", probes.launched),
+            mission: ProbeMission::Flyby,
+            target: ProbeTarget::System(system),
+            target_name: data.system_name.clone(),
+            origin,
+            transfer: None,
+            launch_time: now,
+            arrival_time: now + years * SECONDS_PER_YEAR,
+        };
+        log.push(
+            date.clone(),
+            LogSeverity::Info,
+            format!(
+                "{} launched from {} to {}, arriving in {:.0} years",
+                probe.name,
+                name_of(origin),
+                probe.target_name,
+                years
+            ),
+        );
+        probes.active.push(probe);
+    }
+}
+
+/// System that completes the probes reaching their targets and returns
+/// their survey data
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn advance_probes(
+    mut commands: Commands,
+    sim_time: Res<SimulationTime>,
+    mut probes: ResMut<Probes>,
+    mut log: ResMut<EventLog>,
+    populated: Option<ResMut<PopulatedSystems>>,
+    mut reports: EventWriter<SurveyReport>,
+    bodies: Query<(
+        Entity,
+        &CelestialBody,
+        Option<&SystemId>,
+        Option<&SurveyLevel>,
+        Option<&PlanetResources>,
+    )>,
+) {
+    let now = sim_time.elapsed_seconds();
+    let (arrived, active): (Vec<_>, Vec<_>) = probes
+        .active
+        .drain(..)
+        .partition(|probe| now >= probe.arrival_time);
+    probes.active = active;
+
+    for probe in arrived {
+        match probe.target {
+            ProbeTarget::Body(target) => {
+                let Ok((_, body, _, level, resources)) = bodies.get(target) else {
+                    continue;
+                };
+                let current = level.copied().unwrap_or_default();
+                let Some(level) = probe.mission.survey_level(current) else {
+                    log.push(
+                        sim_time.format_date_time(),
+                        LogSeverity::Info,
+                        format!(
+                            "{} reached {}, which is already surveyed",
+                            probe.name, body.name
+                        ),
+                    );
+                    continue;
+                };
+                commands.entity(target).insert(level);
+
+                let mut discovered: Vec<(ResourceType, f64)> = resources
+                    .map(|r| {
+                        r.deposits
+                            .iter()
+                            .map(|(resource, deposit)| {
+                                (*resource, level.discovered_amount(&deposit.reserve))
+                            })
+                            .filter(|(_, amount)| *amount > 0.0)
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                discovered.sort_by(|a, b| b.1.total_cmp(&a.1));
+                reports.send(SurveyReport {
+                    body: target,
+                    body_name: body.name.clone(),
+                    level,
+                    discovered,
+                });
+            }
+            ProbeTarget::System(system) => {
+                log.push(
+                    sim_time.format_date_time(),
+                    LogSeverity::Info,
+                    format!("{} is flying through {}", probe.name, probe.target_name),
+                );
+                if !probes.pending_scans.contains(&system) {
+                    probes.pending_scans.push(system);
+                }
+            }
+        }
+    }
+
+    // Probes reaching another star system scan every body once it is
+    // populated
+    let Some(mut populated) = populated else {
+        return;
+    };
+    let mut scanned = Vec::new();
+    for system in probes.pending_scans.iter().copied() {
+        if populated.request(system) {
+            continue;
+        }
+        let mut count = 0;
+        for (entity, _, id, level, _) in bodies.iter() {
+            if id.map_or(0, |id| id.0) != system || level.is_some() {
+                continue;
+            }
+            commands.entity(entity).insert(SurveyLevel::OrbitalScan);
+            count += 1;
+        }
+        log.push(
+            sim_time.format_date_time(),
+            LogSeverity::Info,
+            format!("Probe flyby data of {} bodies received", count),
+        );
+        scanned.push(system);
+    }
+    probes
+        .pending_scans
+        .retain(|system| !scanned.contains(system));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_probes_return_partial_survey_data() {
+        let flyby = ProbeMission::Flyby;
+        let orbiter = ProbeMission::Orbiter;
+        assert_eq!(
+            flyby.survey_level(SurveyLevel::Unsurveyed),
+            Some(SurveyLevel::OrbitalScan)
+        );
+        assert_eq!(flyby.survey_level(SurveyLevel::OrbitalScan), None);
+        assert_eq!(
+            orbiter.survey_level(SurveyLevel::OrbitalScan),
+            Some(SurveyLevel::SeismicSurvey)
+        );
+        // Core samples need a full survey mission
+        assert_eq!(orbiter.survey_level(SurveyLevel::SeismicSurvey), None);
+    }
+
+    #[test]
+    fn test_probes_are_paid_all_or_nothing() {
+        let mut budget = GlobalBudget::default();
+        budget.refined.clear();
+        budget.add_refined(RefinedGood::Steel, 1.0);
+        assert!(!pay(&mut budget, PROBE_COST_T));
+        assert_eq!(budget.get_refined(&RefinedGood::Steel), 1.0);
+
+        budget.add_refined(RefinedGood::Electronics, 1.0);
+        assert!(pay(&mut budget, PROBE_COST_T));
+        assert!(budget.get_refined(&RefinedGood::Steel) < 1.0);
+        // Alpha Centauri is decades away at the cruise speed
+        assert!((interstellar_years(4.37) - 87.4).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,180 @@
+//! Salvage and derelicts
+//!
+//! Wrecks and derelict ships (spawned by random events) drift in orbit of a
+//! body until a fleet in the same orbit recovers them. Recovered materials
+//! go to the stockpile; recovered technology starts a reverse-engineering
+//! project (see [`ReverseEngineeringProject`]) whose findings count towards
+//! the research of a related technology.
+
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use super::components::{Fleet, FleetLocation};
+use super::systems::FleetOrders;
+use crate::economy::types::ResourceType;
+use crate::economy::GlobalBudget;
+use crate::game_state::{EventLog, LogSeverity};
+use crate::plugins::solar_system::CelestialBody;
+use crate::research::{ResearchState, ReverseEngineeringProject, TechnologyId};
+use crate::ui::SimulationTime;
+
+/// A wreck or derelict in orbit of a body, waiting for a fleet to recover it
+#[derive(Component, Debug, Clone)]
+pub struct Salvage {
+    pub name: String,
+    /// Body the salvage orbits
+    pub body: Entity,
+    /// Materials recovered in Megatons
+    pub resources: HashMap<ResourceType, f64>,
+    /// Technology its reverse engineering counts towards
+    pub tech: Option<TechnologyId>,
+    /// Research points reverse engineering yields, or adds to the pool
+    /// without a technology
+    pub research_points: f64,
+}
+
+/// System that recovers the salvage fleets were ordered to pick up. The
+/// fleet has to orbit the body the salvage drifts around.
+#[allow(clippy::too_many_arguments)]
+pub fn recover_salvage(
+    mut commands: Commands,
+    mut orders: ResMut<FleetOrders>,
+    sim_time: Res<SimulationTime>,
+    mut budget: ResMut<GlobalBudget>,
+    mut research_state: ResMut<ResearchState>,
+    mut log: ResMut<EventLog>,
+    fleets: Query<(&Fleet, &FleetLocation)>,
+    salvage: Query<&Salvage>,
+    bodies: Query<&CelestialBody>,
+) {
+    if orders.salvages.is_empty() {
+        return;
+    }
+    let date = sim_time.format_date_time();
+
+    let mut recovered: Vec<Entity> = Vec::new();
+    for (fleet_entity, salvage_entity) in std::mem::take(&mut orders.salvages) {
+        let (Ok((fleet, location)), Ok(wreck)) =
+            (fleets.get(fleet_entity), salvage.get(salvage_entity))
+        else {
+            continue;
+        };
+        if recovered.contains(&salvage_entity) {
+            continue;
+        }
+        if *location != FleetLocation::Orbiting(wreck.body) {
+            let body = bodies
+                .get(wreck.body)
+                .map_or("its orbit", |body| body.name.as_str());
+            log.push(
+                date.clone(),
+                LogSeverity::Warning,
+                format!(
+                    "{} has to orbit {} to recover the {}",
+                    fleet.name, body, wreck.name
+                ),
+            );
+            continue;
+        }
+
+        for (resource, amount) in &wreck.resources {
+            budget.add_resource(*resource, amount.max(0.0));
+        }
+        match &wreck.tech {
+            Some(tech) => {
+                commands.spawn(ReverseEngineeringProject::new(
+                    tech.clone(),
+                    wreck.name.clone(),
+                    wreck.research_points,
+                ));
+            }
+            None => research_state.research_points_available += wreck.research_points,
+        }
+        log.push(
+            date.clone(),
+            LogSeverity::Info,
+            format!(
+                "{} recovered the {}{}",
+                fleet.name,
+                wreck.name,
+                if wreck.tech.is_some() {
+                    "; reverse engineering has begun"
+                } else {
+                    ""
+                }
+            ),
+        );
+        recovered.push(salvage_entity);
+        commands.entity(salvage_entity).despawn();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fleets_recover_salvage_in_their_orbit() {
+        let mut app = App::new();
+        app.init_resource::<FleetOrders>()
+            .init_resource::<SimulationTime>()
+            .init_resource::<GlobalBudget>()
+            .init_resource::<ResearchState>()
+            .init_resource::<EventLog>()
+            .add_systems(Update, recover_salvage);
+
+        let mars = app.world_mut().spawn_empty().id();
+        let venus = app.world_mut().spawn_empty().id();
+        let fleet = |name: &str| Fleet {
+            name: name.to_string(),
+            engine: "chemical_engine".to_string(),
+            dry_mass_t: 100.0,
+            fuel_t: 0.0,
+            fuel_capacity_t: 100.0,
+        };
+        let near = app
+            .world_mut()
+            .spawn((fleet("Salvage Group"), FleetLocation::Orbiting(mars)))
+            .id();
+        let far = app
+            .world_mut()
+            .spawn((fleet("Survey Group"), FleetLocation::Orbiting(venus)))
+            .id();
+        let wreck = app
+            .world_mut()
+            .spawn(Salvage {
+                name: "derelict freighter".to_string(),
+                body: mars,
+                resources: HashMap::from([(ResourceType::Iron, 50.0)]),
+                tech: Some("fusion_power".to_string()),
+                research_points: 400.0,
+            })
+            .id();
+        let iron = app
+            .world()
+            .resource::<GlobalBudget>()
+            .get_stockpile(&ResourceType::Iron);
+
+        // Out of orbit nothing happens
+        app.world_mut()
+            .resource_mut::<FleetOrders>()
+            .recover(far, wreck);
+        app.update();
+        assert!(app.world().get::<Salvage>(wreck).is_some());
+
+        app.world_mut()
+            .resource_mut::<FleetOrders>()
+            .recover(near, wreck);
+        app.update();
+        assert!(app.world().get_entity(wreck).is_none());
+        let stockpile = app
+            .world()
+            .resource::<GlobalBudget>()
+            .get_stockpile(&ResourceType::Iron);
+        assert!((stockpile - iron - 50.0).abs() < 1e-9);
+        let mut projects = app.world_mut().query::<&ReverseEngineeringProject>();
+        let project = projects.single(app.world());
+        assert_eq!(project.tech_id, "fusion_power");
+        assert_eq!(project.research_points, 400.0);
+    }
+}
@@ -0,0 +1,501 @@
+//! Shipyards and hull construction
+//!
+//! Shipyard buildings on a colony's surface or in a station's modules
+//! ([`ShipyardFacility`]) give their location a [`Shipyard`]: a number of
+//! parallel slipways and the largest hull they can lay down. Hulls ordered
+//! there wait in the shipyard queue until a slipway is free. Every day a
+//! slipway works on its hull it pays a share of the hull's refined materials
+//! from the stockpile, and stalls while they run short. A slipway set up for
+//! one design has to retool for [`RETOOL_DAYS`] before it can start another.
+//! Finished hulls leave the slipway as a new fleet in orbit.
+
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use super::components::{Fleet, FleetLocation};
+use super::crew::{best_life_support, Crew, LifeSupport};
+use super::engines::best_engine;
+use super::ordnance::{Magazine, SupplyShip};
+use super::systems::{FleetOrders, TONNES_PER_MT};
+use crate::astronomy::components::SpaceCoordinates;
+use crate::construction::OrbitalStation;
+use crate::economy::{EconomyClock, GlobalBudget, RefinedGood, Reservations};
+use crate::game_state::{EventLog, LogSeverity};
+use crate::plugins::solar_system::{CelestialBody, LogicalParent};
+use crate::research::ResearchState;
+use crate::ui::SimulationTime;
+
+/// Days a slipway needs to retool for a different design
+pub const RETOOL_DAYS: f64 = 30.0;
+
+/// A hull design that can be built at a shipyard
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HullProfile {
+    pub id: &'static str,
+    pub name: &'static str,
+    /// Mass without propellant in tonnes
+    pub tonnage_t: f64,
+    /// Propellant tank capacity in tonnes
+    pub fuel_capacity_t: f64,
+    /// Crew posts; uncrewed hulls carry no life support
+    pub crew: u32,
+    /// Storage for each of water, oxygen and food in tonnes
+    pub supplies_t: f64,
+    /// Rounds of ordnance the hull carries
+    pub magazine: u32,
+    /// Whether the hull carries ordnance for other fleets
+    pub supply_ship: bool,
+    /// Days on the slipway
+    pub build_days: f64,
+    /// Refined materials for the whole hull in tonnes
+    pub cost_t: &'static [(RefinedGood, f64)],
+}
+
+/// Hull designs in order of increasing tonnage
+pub const HULLS: &[HullProfile] = &[
+    HullProfile {
+        id: "courier",
+        name: "Courier",
+        tonnage_t: 200.0,
+        fuel_capacity_t: 300.0,
+        crew: 4,
+        supplies_t: 2.0,
+        magazine: 0,
+        supply_ship: false,
+        build_days: 60.0,
+        cost_t: &[
+            (RefinedGood::Steel, 150.0),
+            (RefinedGood::Electronics, 20.0),
+        ],
+    },
+    HullProfile {
+        id: "survey_cutter",
+        name: "Survey Cutter",
+        tonnage_t: 500.0,
+        fuel_capacity_t: 800.0,
+        crew: 12,
+        supplies_t: 10.0,
+        magazine: 0,
+        supply_ship: false,
+        build_days: 120.0,
+        cost_t: &[
+            (RefinedGood::Steel, 350.0),
+            (RefinedGood::Electronics, 60.0),
+        ],
+    },
+    HullProfile {
+        id: "frigate",
+        name: "Frigate",
+        tonnage_t: 2_000.0,
+        fuel_capacity_t: 2_000.0,
+        crew: 60,
+        supplies_t: 40.0,
+        magazine: 24,
+        supply_ship: false,
+        build_days: 240.0,
+        cost_t: &[
+            (RefinedGood::Steel, 1_500.0),
+            (RefinedGood::Electronics, 250.0),
+        ],
+    },
+    HullProfile {
+        id: "supply_ship",
+        name: "Supply Ship",
+        tonnage_t: 4_000.0,
+        fuel_capacity_t: 4_000.0,
+        crew: 30,
+        supplies_t: 60.0,
+        magazine: 200,
+        supply_ship: true,
+        build_days: 270.0,
+        cost_t: &[
+            (RefinedGood::Steel, 3_000.0),
+            (RefinedGood::Electronics, 200.0),
+        ],
+    },
+    HullProfile {
+        id: "freighter",
+        name: "Freighter",
+        tonnage_t: 8_000.0,
+        fuel_capacity_t: 6_000.0,
+        crew: 20,
+        supplies_t: 40.0,
+        magazine: 0,
+        supply_ship: false,
+        build_days: 300.0,
+        cost_t: &[
+            (RefinedGood::Steel, 6_000.0),
+            (RefinedGood::Electronics, 300.0),
+        ],
+    },
+];
+
+/// Profile of a hull design
+pub fn hull(id: &str) -> Option<&'static HullProfile> {
+    HULLS.iter().find(|profile| profile.id == id)
+}
+
+/// Slipways and hull capacity of a shipyard building
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct ShipyardFacility {
+    /// Largest hull in tonnes
+    pub tonnage_t: f64,
+    pub slipways: u32,
+}
+
+/// A slipway and the hull on it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Slipway {
+    /// Design the slipway is set up for
+    pub tooling: Option<String>,
+    /// Days of retooling left before work on the hull starts
+    pub retooling_days: f64,
+    /// Hull under construction
+    pub hull: Option<String>,
+    /// Days of work done on the hull
+    pub progress_days: f64,
+    /// The rest of the hull's materials is earmarked in the stockpile
+    pub reserved: bool,
+}
+
+impl Slipway {
+    /// Fraction of the hull completed (0.0 to 1.0)
+    pub fn progress(&self) -> f64 {
+        self.hull.as_deref().and_then(hull).map_or(0.0, |profile| {
+            (self.progress_days / profile.build_days).min(1.0)
+        })
+    }
+}
+
+/// Slipways and hull queue of a colony or station with shipyard buildings
+#[derive(Component, Debug, Clone, Default, PartialEq)]
+pub struct Shipyard {
+    /// Largest hull the slipways can take in tonnes
+    pub max_tonnage_t: f64,
+    pub slipways: Vec<Slipway>,
+    /// Hull designs waiting for a free slipway, next first
+    pub queue: Vec<String>,
+}
+
+impl Shipyard {
+    /// Resize the yard to its buildings. Hulls on slipways that are torn
+    /// down go back to the front of the queue.
+    pub fn resize(&mut self, max_tonnage_t: f64, slipways: u32) {
+        self.max_tonnage_t = max_tonnage_t;
+        let count = slipways as usize;
+        if self.slipways.len() > count {
+            let mut queue: Vec<String> = self
+                .slipways
+                .drain(count..)
+                .filter_map(|slipway| slipway.hull)
+                .collect();
+            queue.append(&mut self.queue);
+            self.queue = queue;
+        }
+        self.slipways.resize_with(count, Slipway::default);
+    }
+
+    /// Check whether a hull design can be ordered here
+    pub fn check_order(&self, hull_id: &str) -> Result<&'static HullProfile, String> {
+        let Some(profile) = hull(hull_id) else {
+            return Err(format!("Unknown hull design '{}'", hull_id));
+        };
+        if profile.tonnage_t > self.max_tonnage_t {
+            return Err(format!(
+                "{} t exceeds the {} t the slipways take",
+                profile.tonnage_t, self.max_tonnage_t
+            ));
+        }
+        Ok(profile)
+    }
+
+    /// Move queued hulls onto idle slipways, preferring a slipway already
+    /// tooled for the design
+    fn assign(&mut self) {
+        while !self.queue.is_empty() {
+            let design = &self.queue[0];
+            let idle = |slipway: &Slipway| slipway.hull.is_none();
+            let Some(index) = self
+                .slipways
+                .iter()
+                .position(|s| idle(s) && s.tooling.as_ref() == Some(design))
+                .or_else(|| self.slipways.iter().position(idle))
+            else {
+                break;
+            };
+            let design = self.queue.remove(0);
+            let slipway = &mut self.slipways[index];
+            if slipway.tooling.as_ref() != Some(&design) {
+                slipway.retooling_days = RETOOL_DAYS;
+                slipway.tooling = Some(design.clone());
+            }
+            slipway.hull = Some(design);
+            slipway.progress_days = 0.0;
+            slipway.reserved = false;
+        }
+    }
+
+    /// Work every slipway for a day, paying materials from the stockpile.
+    /// Returns the hulls finished.
+    pub fn work_day(
+        &mut self,
+        budget: &mut GlobalBudget,
+        reservations: &Reservations,
+        location: Entity,
+    ) -> Vec<&'static HullProfile> {
+        self.assign();
+        let mut finished = Vec::new();
+        for slipway in &mut self.slipways {
+            let Some(profile) = slipway.hull.as_deref().and_then(hull) else {
+                slipway.hull = None;
+                continue;
+            };
+            if slipway.retooling_days > 0.0 {
+                slipway.retooling_days = (slipway.retooling_days - 1.0).max(0.0);
+                continue;
+            }
+            if !pay_for_day(budget, reservations, location, profile, slipway.reserved) {
+                continue;
+            }
+            slipway.progress_days += 1.0;
+            if slipway.progress_days >= profile.build_days {
+                slipway.hull = None;
+                slipway.progress_days = 0.0;
+                slipway.reserved = false;
+                finished.push(profile);
+            }
+        }
+        finished
+    }
+}
+
+/// Pay one day's share of the materials of a hull built at `location` from
+/// the stockpile, all or nothing: out of the hull's earmark if it is
+/// `reserved`, otherwise out of the stock free of reservations
+fn pay_for_day(
+    budget: &mut GlobalBudget,
+    reservations: &Reservations,
+    location: Entity,
+    profile: &HullProfile,
+    reserved: bool,
+) -> bool {
+    let share = 1.0 / profile.build_days;
+    let day_cost = profile
+        .cost_t
+        .iter()
+        .map(|(good, tonnes)| (*good, tonnes * share / TONNES_PER_MT));
+    if !reservations.covers(budget, reserved, [], day_cost) {
+        return false;
+    }
+    for (good, tonnes) in profile.cost_t {
+        budget.consume_refined_at(location, *good, tonnes * share / TONNES_PER_MT);
+    }
+    true
+}
+
+/// System to size shipyards from their buildings, adding one to locations
+/// that gain their first shipyard building. Yards whose buildings are gone
+/// keep their queue but have no slipways.
+pub fn update_shipyards(
+    mut commands: Commands,
+    facilities: Query<(&ShipyardFacility, &LogicalParent)>,
+    mut yards: Query<(Entity, &mut Shipyard)>,
+) {
+    let mut capacities: HashMap<Entity, (f64, u32)> = HashMap::new();
+    for (facility, parent) in facilities.iter() {
+        let (tonnage, slipways) = capacities.entry(parent.0).or_insert((0.0, 0));
+        *tonnage = tonnage.max(facility.tonnage_t);
+        *slipways += facility.slipways;
+    }
+
+    for (entity, mut yard) in yards.iter_mut() {
+        let (tonnage, slipways) = capacities.remove(&entity).unwrap_or((0.0, 0));
+        if yard.max_tonnage_t != tonnage || yard.slipways.len() != slipways as usize {
+            yard.resize(tonnage, slipways);
+        }
+    }
+    for (location, (tonnage, slipways)) in capacities {
+        if let Some(mut location) = commands.get_entity(location) {
+            let mut yard = Shipyard::default();
+            yard.resize(tonnage, slipways);
+            location.insert(yard);
+        }
+    }
+}
+
+/// System that puts the hulls ordered from the UI into shipyard queues and
+/// takes cancelled ones out
+pub fn issue_hull_orders(
+    mut orders: ResMut<FleetOrders>,
+    mut yards: Query<&mut Shipyard>,
+    sim_time: Res<SimulationTime>,
+    mut log: ResMut<EventLog>,
+) {
+    for (location, hull_id) in orders.hulls.drain(..) {
+        let Ok(mut yard) = yards.get_mut(location) else {
+            continue;
+        };
+        match yard.check_order(&hull_id) {
+            Ok(_) => yard.queue.push(hull_id),
+            Err(reason) => log.push(
+                sim_time.format_date_time(),
+                LogSeverity::Warning,
+                format!("Cannot build a {}: {}", hull_id, reason),
+            ),
+        }
+    }
+    for (location, index) in orders.cancelled_hulls.drain(..) {
+        if let Ok(mut yard) = yards.get_mut(location) {
+            if index < yard.queue.len() {
+                yard.queue.remove(index);
+            }
+        }
+    }
+}
+
+/// Unused fleet name for a new hull of `profile`
+fn fleet_name(profile: &HullProfile, taken: &[String]) -> String {
+    (1..)
+        .map(|number| format!("{} {}", profile.name, number))
+        .find(|name| !taken.contains(name))
+        .unwrap_or_default()
+}
+
+/// System that works every shipyard once per due economy day and launches
+/// the finished hulls as fleets orbiting the yard's body
+#[allow(clippy::too_many_arguments)]
+pub fn advance_shipyards(
+    mut commands: Commands,
+    clock: Res<EconomyClock>,
+    sim_time: Res<SimulationTime>,
+    research_state: Option<Res<ResearchState>>,
+    mut budget: ResMut<GlobalBudget>,
+    reservations: Res<Reservations>,
+    mut log: ResMut<EventLog>,
+    mut yards: Query<(Entity, &mut Shipyard)>,
+    stations: Query<&OrbitalStation>,
+    bodies: Query<&CelestialBody>,
+    fleets: Query<&Fleet>,
+) {
+    if clock.due == 0 {
+        return;
+    }
+    let mut taken: Vec<String> = fleets.iter().map(|f| f.name.clone()).collect();
+    let research_state = research_state.as_deref();
+
+    for (location, mut yard) in yards.iter_mut() {
+        let mut finished = Vec::new();
+        for _ in 0..clock.due {
+            finished.extend(yard.work_day(&mut budget, &reservations, location));
+        }
+        if finished.is_empty() {
+            continue;
+        }
+        // Hulls built at a station orbit the station's host
+        let body = stations.get(location).map_or(location, |s| s.host);
+        let body_name = bodies.get(body).map_or("Unknown", |b| b.name.as_str());
+
+        for profile in finished {
+            let name = fleet_name(profile, &taken);
+            let mut fleet = commands.spawn((
+                Fleet {
+                    name: name.clone(),
+                    engine: best_engine(research_state).component.to_string(),
+                    dry_mass_t: profile.tonnage_t,
+                    fuel_t: 0.0,
+                    fuel_capacity_t: profile.fuel_capacity_t,
+                },
+                FleetLocation::Orbiting(body),
+                SpaceCoordinates::default(),
+            ));
+            if profile.crew > 0 {
+                fleet.insert((
+                    Crew {
+                        required: profile.crew,
+                        aboard: 0,
+                    },
+                    LifeSupport {
+                        system: best_life_support(research_state).component.to_string(),
+                        water_t: 0.0,
+                        oxygen_t: 0.0,
+                        food_t: 0.0,
+                        capacity_t: profile.supplies_t,
+                        exhausted: false,
+                        attrition: 0.0,
+                    },
+                ));
+            }
+            if profile.magazine > 0 {
+                fleet.insert(Magazine::new(profile.magazine));
+            }
+            if profile.supply_ship {
+                fleet.insert(SupplyShip);
+            }
+            log.push(
+                sim_time.format_date_time(),
+                LogSeverity::Info,
+                format!("{} launched from the shipyards of {}", name, body_name),
+            );
+            taken.push(name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stocked_budget() -> GlobalBudget {
+        let mut budget = GlobalBudget::default();
+        for good in RefinedGood::all() {
+            budget.refined.insert(*good, 1.0);
+        }
+        budget
+    }
+
+    #[test]
+    fn test_slipways_retool_between_designs() {
+        let mut budget = stocked_budget();
+        let none = Reservations::default();
+        let mut yard = Shipyard::default();
+        yard.resize(1_000.0, 1);
+        assert!(yard.check_order("freighter").is_err());
+        yard.queue = vec!["courier".to_string(), "survey_cutter".to_string()];
+
+        let courier = hull("courier").unwrap();
+        let days = (RETOOL_DAYS + courier.build_days) as usize;
+        let finished: Vec<_> = (0..days)
+            .flat_map(|_| yard.work_day(&mut budget, &none, Entity::PLACEHOLDER))
+            .collect();
+        assert_eq!(finished, vec![courier]);
+        let steel_used = 1.0 - budget.get_refined(&RefinedGood::Steel);
+        assert!((steel_used * TONNES_PER_MT - 150.0).abs() < 1e-3);
+
+        // The next design waits for the slipway to retool
+        yard.work_day(&mut budget, &none, Entity::PLACEHOLDER);
+        assert_eq!(yard.slipways[0].hull.as_deref(), Some("survey_cutter"));
+        assert_eq!(yard.slipways[0].retooling_days, RETOOL_DAYS - 1.0);
+        assert_eq!(yard.slipways[0].progress_days, 0.0);
+    }
+
+    #[test]
+    fn test_yards_stall_without_materials_and_resize() {
+        let mut budget = GlobalBudget::default();
+        budget.refined.clear();
+        let none = Reservations::default();
+        let mut yard = Shipyard::default();
+        yard.resize(10_000.0, 2);
+        yard.queue = vec!["frigate".to_string(), "frigate".to_string()];
+        for _ in 0..(RETOOL_DAYS as usize + 5) {
+            assert!(yard
+                .work_day(&mut budget, &none, Entity::PLACEHOLDER)
+                .is_empty());
+        }
+        assert!(yard.slipways.iter().all(|s| s.progress_days == 0.0));
+
+        // Tearing down a slipway returns its hull to the queue
+        yard.resize(10_000.0, 1);
+        assert_eq!(yard.queue, vec!["frigate".to_string()]);
+        assert_eq!(yard.slipways.len(), 1);
+    }
+}
@@ -0,0 +1,646 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use super::components::{Fleet, FleetLocation};
+use super::crew::{Crew, LifeSupport};
+use super::probes::ProbeMission;
+use super::transfer::{plan_transfer, OrbitNode};
+use crate::astronomy::components::{
+    CurrentStarSystem, KeplerOrbit, OrbitCenter, SpaceCoordinates, SystemId,
+};
+use crate::astronomy::SCALING_FACTOR;
+use crate::economy::components::Population;
+use crate::economy::{Condition, GlobalBudget, ECONOMY_TICK_SECONDS};
+use crate::game_state::{EventLog, LogSeverity};
+use crate::hotseat::HotseatView;
+use crate::plugins::camera::ViewMode;
+use crate::plugins::solar_system::{CelestialBody, LogicalParent};
+use crate::ui::{format_duration, SimulationTime};
+
+/// Tonnes per Megaton, the unit of the global stockpile
+pub const TONNES_PER_MT: f64 = 1e6;
+
+/// Fleet orders queued from the UI
+#[derive(Resource, Debug, Clone, Default)]
+pub struct FleetOrders {
+    /// Fleets to send to a body
+    pub moves: Vec<(Entity, Entity)>,
+    /// Fleets to refuel at the colony they orbit
+    pub refuels: Vec<Entity>,
+    /// Fleets to load with rounds of an ordnance type
+    pub loads: Vec<(Entity, String, u32)>,
+    /// Fleets to fill their crew posts at the colony they orbit
+    pub crewings: Vec<Entity>,
+    /// Fleets to fill their life support stores at the colony they orbit
+    pub resupplies: Vec<Entity>,
+    /// Hull designs to queue at the shipyard of a colony or station
+    pub hulls: Vec<(Entity, String)>,
+    /// Queued hulls to take out of a shipyard queue, by index
+    pub cancelled_hulls: Vec<(Entity, usize)>,
+    /// Probes to launch at a body
+    pub probes: Vec<(Entity, ProbeMission)>,
+    /// Probes to launch at another star system, by catalog id
+    pub interstellar_probes: Vec<usize>,
+    /// Fleets to recover salvage in their orbit
+    pub salvages: Vec<(Entity, Entity)>,
+}
+
+impl FleetOrders {
+    /// Order `fleet` to move to `destination`
+    pub fn move_to(&mut self, fleet: Entity, destination: Entity) {
+        self.moves.push((fleet, destination));
+    }
+
+    /// Order `fleet` to fill its tanks from the stockpile
+    pub fn refuel(&mut self, fleet: Entity) {
+        self.refuels.push(fleet);
+    }
+
+    /// Order `fleet` to load `count` rounds of `ordnance` in its orbit
+    pub fn load(&mut self, fleet: Entity, ordnance: String, count: u32) {
+        self.loads.push((fleet, ordnance, count));
+    }
+
+    /// Order `fleet` to recruit its missing crew
+    pub fn crew(&mut self, fleet: Entity) {
+        self.crewings.push(fleet);
+    }
+
+    /// Order `fleet` to fill its life support stores
+    pub fn resupply(&mut self, fleet: Entity) {
+        self.resupplies.push(fleet);
+    }
+
+    /// Queue a hull of `design` at the shipyard of `location`
+    pub fn build_hull(&mut self, location: Entity, design: String) {
+        self.hulls.push((location, design));
+    }
+
+    /// Take the hull at `index` out of the shipyard queue of `location`
+    pub fn cancel_hull(&mut self, location: Entity, index: usize) {
+        self.cancelled_hulls.push((location, index));
+    }
+
+    /// Launch a probe on `mission` to `body`
+    pub fn launch_probe(&mut self, body: Entity, mission: ProbeMission) {
+        self.probes.push((body, mission));
+    }
+
+    /// Launch a probe through the star system with catalog id `system`
+    pub fn launch_interstellar_probe(&mut self, system: usize) {
+        self.interstellar_probes.push(system);
+    }
+
+    /// Order `fleet` to recover `salvage` from its orbit
+    pub fn recover(&mut self, fleet: Entity, salvage: Entity) {
+        self.salvages.push((fleet, salvage));
+    }
+}
+
+/// Bodies a transfer can be planned between
+pub type BodyNodes<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static CelestialBody,
+        &'static SpaceCoordinates,
+        Option<&'static KeplerOrbit>,
+        Option<&'static OrbitCenter>,
+        Option<&'static LogicalParent>,
+    ),
+>;
+
+/// Orbit hierarchy of every body, for the transfer planner
+pub fn orbit_nodes(bodies: &BodyNodes) -> HashMap<Entity, OrbitNode> {
+    bodies
+        .iter()
+        .map(|(entity, body, coords, orbit, center, parent)| {
+            let node = OrbitNode {
+                parent: center.map(|c| c.0).or(parent.map(|p| p.0)),
+                mass_kg: body.mass,
+                radius_km: body.radius as f64,
+                orbit: orbit.copied(),
+                position: coords.position,
+            };
+            (entity, node)
+        })
+        .collect()
+}
+
+/// System to plan the moves and refuel the fleets ordered from the UI.
+///
+/// A move is only accepted if the fleet's tanks hold the delta-v for both
+/// burns; the propellant is consumed when the burns are made. Crewed fleets
+/// also need every post filled and the supplies to last until arrival.
+#[allow(clippy::type_complexity)]
+pub fn issue_fleet_orders(
+    mut orders: ResMut<FleetOrders>,
+    sim_time: Res<SimulationTime>,
+    mut budget: ResMut<GlobalBudget>,
+    mut log: ResMut<EventLog>,
+    mut fleets: Query<(
+        &mut Fleet,
+        &mut FleetLocation,
+        Option<&Crew>,
+        Option<&LifeSupport>,
+        Option<&Condition>,
+    )>,
+    bodies: BodyNodes,
+    colonies: Query<&Population>,
+) {
+    if orders.moves.is_empty() && orders.refuels.is_empty() {
+        return;
+    }
+    let now = sim_time.elapsed_seconds();
+    let date = sim_time.format_date_time();
+    let name_of = |entity: Entity| {
+        bodies
+            .get(entity)
+            .map_or("Unknown".to_string(), |(_, body, ..)| body.name.clone())
+    };
+
+    let moves: Vec<(Entity, Entity)> = orders.moves.drain(..).collect();
+    let nodes = if moves.is_empty() {
+        HashMap::new()
+    } else {
+        orbit_nodes(&bodies)
+    };
+    for (fleet_entity, destination) in moves {
+        let Ok((fleet, mut location, crew, supplies, condition)) = fleets.get_mut(fleet_entity)
+        else {
+            continue;
+        };
+        let FleetLocation::Orbiting(origin) = *location else {
+            log.push(
+                date.clone(),
+                LogSeverity::Warning,
+                format!("{} is already under way", fleet.name),
+            );
+            continue;
+        };
+
+        if let Some(crew) = crew.filter(|crew| crew.missing() > 0) {
+            log.push(
+                date.clone(),
+                LogSeverity::Warning,
+                format!(
+                    "{} cannot leave {}: {} crew posts are empty",
+                    fleet.name,
+                    name_of(origin),
+                    crew.missing()
+                ),
+            );
+            continue;
+        }
+
+        if condition.is_some_and(Condition::is_broken) {
+            log.push(
+                date.clone(),
+                LogSeverity::Warning,
+                format!(
+                    "{} cannot leave {}: it has broken down and awaits repair",
+                    fleet.name,
+                    name_of(origin)
+                ),
+            );
+            continue;
+        }
+
+        let transfer = match plan_transfer(origin, destination, now, &nodes) {
+            Ok(transfer) => transfer,
+            Err(e) => {
+                log.push(
+                    date.clone(),
+                    LogSeverity::Warning,
+                    format!("{} cannot move to {}: {}", fleet.name, name_of(destination), e),
+                );
+                continue;
+            }
+        };
+        let available = fleet.delta_v_km_s();
+        if transfer.total_dv_km_s() > available {
+            log.push(
+                date.clone(),
+                LogSeverity::Warning,
+                format!(
+                    "{} cannot reach {}: needs {:.2} km/s of delta-v, has {:.2} km/s",
+                    fleet.name,
+                    name_of(destination),
+                    transfer.total_dv_km_s(),
+                    available
+                ),
+            );
+            continue;
+        }
+        if let (Some(crew), Some(supplies)) = (crew, supplies) {
+            let endurance = supplies.endurance_days(crew.aboard) * ECONOMY_TICK_SECONDS;
+            if endurance < transfer.arrival_time - now {
+                log.push(
+                    date.clone(),
+                    LogSeverity::Warning,
+                    format!(
+                        "{} cannot reach {}: life support lasts {}, the trip takes {}",
+                        fleet.name,
+                        name_of(destination),
+                        format_duration(endurance),
+                        format_duration(transfer.arrival_time - now)
+                    ),
+                );
+                continue;
+            }
+        }
+
+        log.push(
+            date.clone(),
+            LogSeverity::Info,
+            format!(
+                "{} will depart for {} in {} ({:.2} km/s), arriving after {} ({:.2} km/s)",
+                fleet.name,
+                name_of(destination),
+                format_duration(transfer.departure_time - now),
+                transfer.departure_dv_km_s,
+                format_duration(transfer.arrival_time - transfer.departure_time),
+                transfer.insertion_dv_km_s
+            ),
+        );
+        *location = FleetLocation::InTransit(transfer);
+    }
+
+    let refuels: Vec<Entity> = orders.refuels.drain(..).collect();
+    for fleet_entity in refuels {
+        let Ok((mut fleet, location, ..)) = fleets.get_mut(fleet_entity) else {
+            continue;
+        };
+        let FleetLocation::Orbiting(body) = *location else {
+            continue;
+        };
+        if !colonies.get(body).is_ok_and(|p| p.count > 0.0) {
+            log.push(
+                date.clone(),
+                LogSeverity::Warning,
+                format!("{} can only refuel at a colony", fleet.name),
+            );
+            continue;
+        }
+        let Some(fuel) = fleet.engine_profile().map(|engine| engine.fuel) else {
+            continue;
+        };
+
+        let wanted = (fleet.fuel_capacity_t - fleet.fuel_t).max(0.0);
+        let taken = wanted.min(budget.get_stockpile(&fuel) * TONNES_PER_MT);
+        if taken > 0.0 && budget.consume_resource(fuel, taken / TONNES_PER_MT) {
+            fleet.fuel_t += taken;
+        }
+        if taken < wanted {
+            log.push(
+                date.clone(),
+                LogSeverity::Warning,
+                format!("{} could only load {:.0} t of {}", fleet.name, taken, fuel),
+            );
+        }
+    }
+}
+
+/// System that flies fleets along their transfers: the departure burn at
+/// the window, the coast along the transfer ellipse and the insertion burn
+/// on arrival. Fleets in orbit follow their body.
+pub fn advance_fleets(
+    sim_time: Res<SimulationTime>,
+    mut log: ResMut<EventLog>,
+    mut fleets: Query<(&mut Fleet, &mut FleetLocation, &mut SpaceCoordinates)>,
+    bodies: Query<(&CelestialBody, &SpaceCoordinates), Without<Fleet>>,
+) {
+    let now = sim_time.elapsed_seconds();
+    let position_of = |entity: Entity| bodies.get(entity).ok().map(|(_, c)| c.position);
+    let name_of = |entity: Entity| {
+        bodies
+            .get(entity)
+            .map_or("Unknown".to_string(), |(body, _)| body.name.clone())
+    };
+
+    for (mut fleet, mut location, mut coords) in fleets.iter_mut() {
+        let arrived = match &mut *location {
+            FleetLocation::Orbiting(body) => {
+                if let Some(position) = position_of(*body) {
+                    coords.position = position;
+                }
+                None
+            }
+            FleetLocation::InTransit(transfer) => {
+                if !transfer.departed && now >= transfer.departure_time {
+                    if fleet.burn(transfer.departure_dv_km_s) {
+                        transfer.departed = true;
+                        log.push(
+                            sim_time.format_date_time(),
+                            LogSeverity::Info,
+                            format!(
+                                "{} departed for {}",
+                                fleet.name,
+                                name_of(transfer.destination)
+                            ),
+                        );
+                    } else {
+                        log.push(
+                            sim_time.format_date_time(),
+                            LogSeverity::Warning,
+                            format!(
+                                "{} lacks the propellant to depart for {}",
+                                fleet.name,
+                                name_of(transfer.destination)
+                            ),
+                        );
+                        let origin = transfer.origin;
+                        *location = FleetLocation::Orbiting(origin);
+                        continue;
+                    }
+                }
+
+                if !transfer.departed {
+                    if let Some(position) = position_of(transfer.origin) {
+                        coords.position = position;
+                    }
+                    None
+                } else if now >= transfer.arrival_time {
+                    Some((transfer.destination, transfer.insertion_dv_km_s))
+                } else {
+                    if let Some(frame) = position_of(transfer.frame) {
+                        coords.position = frame + transfer.coast_offset(now);
+                    }
+                    None
+                }
+            }
+        };
+
+        if let Some((destination, insertion_dv)) = arrived {
+            let message = if fleet.burn(insertion_dv) {
+                format!("{} entered orbit at {}", fleet.name, name_of(destination))
+            } else {
+                // Only possible if the tanks were drained en route
+                fleet.fuel_t = 0.0;
+                format!(
+                    "{} reached {} on its last propellant",
+                    fleet.name,
+                    name_of(destination)
+                )
+            };
+            log.push(sim_time.format_date_time(), LogSeverity::Info, message);
+            *location = FleetLocation::Orbiting(destination);
+            if let Some(position) = position_of(destination) {
+                coords.position = position;
+            }
+        }
+    }
+}
+
+/// Number of segments of a drawn transfer arc
+const TRANSFER_ARC_SEGMENTS: usize = 48;
+
+/// System that draws fleets in the current star system, with the remaining
+/// arc of their transfer ellipse. Fleets of the other hotseat player are
+/// drawn only while sensors see them.
+pub fn draw_fleets(
+    mut gizmos: Gizmos,
+    view_mode: Res<ViewMode>,
+    sim_time: Res<SimulationTime>,
+    current_system: Res<CurrentStarSystem>,
+    hotseat_view: Option<Res<HotseatView>>,
+    fleets: Query<(Entity, &FleetLocation), With<Fleet>>,
+    bodies: Query<(&GlobalTransform, Option<&SystemId>), With<CelestialBody>>,
+) {
+    if *view_mode != ViewMode::System {
+        return;
+    }
+
+    let color = Color::srgb(0.4, 0.9, 1.0);
+    let now = sim_time.elapsed_seconds();
+    let in_view = |system: Option<&SystemId>| system.map_or(0, |s| s.0) == current_system.0;
+
+    for (entity, location) in fleets.iter() {
+        if hotseat_view.as_ref().is_some_and(|v| v.is_hidden(entity)) {
+            continue;
+        }
+        match location {
+            FleetLocation::Orbiting(body) => {
+                let Ok((transform, system)) = bodies.get(*body) else {
+                    continue;
+                };
+                if in_view(system) {
+                    gizmos.sphere(transform.translation(), Quat::IDENTITY, 1.5, color);
+                }
+            }
+            FleetLocation::InTransit(transfer) => {
+                let Ok((frame, system)) = bodies.get(transfer.frame) else {
+                    continue;
+                };
+                if !in_view(system) {
+                    continue;
+                }
+                let frame = frame.translation();
+                let to_render = |offset: bevy::math::DVec3| {
+                    let scaled = offset * SCALING_FACTOR;
+                    frame + Vec3::new(scaled.x as f32, scaled.y as f32, scaled.z as f32)
+                };
+
+                let start = now.max(transfer.departure_time);
+                let span = transfer.arrival_time - start;
+                let arc: Vec<Vec3> = (0..=TRANSFER_ARC_SEGMENTS)
+                    .map(|i| {
+                        let time = start + span * i as f64 / TRANSFER_ARC_SEGMENTS as f64;
+                        to_render(transfer.coast_offset(time))
+                    })
+                    .collect();
+                gizmos.linestrip(arc, color.with_alpha(0.3));
+
+                let position = if transfer.departed {
+                    to_render(transfer.coast_offset(now))
+                } else {
+                    bodies
+                        .get(transfer.origin)
+                        .map_or(frame, |(t, _)| t.translation())
+                };
+                gizmos.sphere(position, Quat::IDENTITY, 1.5, color);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::solar_system_data::BodyType;
+    use bevy::math::DVec3;
+
+    fn body(name: &str, mass: f64, radius: f32, body_type: BodyType) -> CelestialBody {
+        CelestialBody {
+            name: name.to_string(),
+            radius,
+            mass,
+            body_type,
+            visual_radius: 1.0,
+            asteroid_class: None,
+        }
+    }
+
+    #[test]
+    fn test_fleet_burns_coasts_and_arrives() {
+        let mut app = App::new();
+        app.init_resource::<SimulationTime>()
+            .init_resource::<EventLog>()
+            .init_resource::<GlobalBudget>()
+            .init_resource::<FleetOrders>()
+            .add_systems(Update, (issue_fleet_orders, advance_fleets).chain());
+
+        let sun = app
+            .world_mut()
+            .spawn((
+                body("Sun", 1.989e30, 696_000.0, BodyType::Star),
+                SpaceCoordinates::default(),
+            ))
+            .id();
+        let earth_orbit =
+            KeplerOrbit::circular(1.0, KeplerOrbit::mean_motion_from_period(3.1558e7));
+        let earth = app
+            .world_mut()
+            .spawn((
+                body("Earth", 5.972e24, 6371.0, BodyType::Planet),
+                SpaceCoordinates::from_xyz(1.0, 0.0, 0.0),
+                earth_orbit,
+                LogicalParent(sun),
+                Population::new(8e9),
+            ))
+            .id();
+        let mars_orbit =
+            KeplerOrbit::circular(1.524, KeplerOrbit::mean_motion_from_period(5.9355e7));
+        let mars = app
+            .world_mut()
+            .spawn((
+                body("Mars", 6.417e23, 3390.0, BodyType::Planet),
+                SpaceCoordinates::from_xyz(0.0, 1.524, 0.0),
+                mars_orbit,
+                LogicalParent(sun),
+            ))
+            .id();
+
+        let fleet = app
+            .world_mut()
+            .spawn((
+                Fleet {
+                    name: "First Fleet".to_string(),
+                    engine: "ion_engine".to_string(),
+                    dry_mass_t: 1000.0,
+                    fuel_t: 0.0,
+                    fuel_capacity_t: 500.0,
+                },
+                FleetLocation::Orbiting(earth),
+                SpaceCoordinates::default(),
+            ))
+            .id();
+        app.world_mut()
+            .resource_mut::<GlobalBudget>()
+            .add_resource(crate::economy::ResourceType::Hydrogen, 1.0);
+
+        // Refuel at Earth, then order the move
+        app.world_mut().resource_mut::<FleetOrders>().refuel(fleet);
+        app.update();
+        assert_eq!(app.world().get::<Fleet>(fleet).unwrap().fuel_t, 500.0);
+
+        app.world_mut().resource_mut::<FleetOrders>().move_to(fleet, mars);
+        app.update();
+        let Some(FleetLocation::InTransit(transfer)) =
+            app.world().get::<FleetLocation>(fleet).cloned()
+        else {
+            panic!("fleet should be under way");
+        };
+        assert!(!transfer.departed);
+
+        // Departure burn at the window
+        app.world_mut().resource_mut::<SimulationTime>().elapsed = transfer.departure_time;
+        app.update();
+        let after_departure = app.world().get::<Fleet>(fleet).unwrap().fuel_t;
+        assert!(after_departure < 500.0);
+
+        // Coasting between the orbits
+        app.world_mut().resource_mut::<SimulationTime>().elapsed =
+            (transfer.departure_time + transfer.arrival_time) / 2.0;
+        app.update();
+        let r = app
+            .world()
+            .get::<SpaceCoordinates>(fleet)
+            .unwrap()
+            .position
+            .length();
+        assert!(r > 1.0 && r < 1.524);
+
+        // Insertion burn on arrival
+        app.world_mut().resource_mut::<SimulationTime>().elapsed = transfer.arrival_time;
+        app.update();
+        assert_eq!(
+            app.world().get::<FleetLocation>(fleet),
+            Some(&FleetLocation::Orbiting(mars))
+        );
+        assert!(app.world().get::<Fleet>(fleet).unwrap().fuel_t < after_departure);
+        assert_eq!(
+            app.world().get::<SpaceCoordinates>(fleet).unwrap().position,
+            DVec3::new(0.0, 1.524, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_move_beyond_delta_v_is_refused() {
+        let mut app = App::new();
+        app.init_resource::<SimulationTime>()
+            .init_resource::<EventLog>()
+            .init_resource::<GlobalBudget>()
+            .init_resource::<FleetOrders>()
+            .add_systems(Update, issue_fleet_orders);
+
+        let sun = app
+            .world_mut()
+            .spawn((
+                body("Sun", 1.989e30, 696_000.0, BodyType::Star),
+                SpaceCoordinates::default(),
+            ))
+            .id();
+        let mut planet = |name: &str, au: f64| {
+            app.world_mut()
+                .spawn((
+                    body(name, 6e24, 6371.0, BodyType::Planet),
+                    SpaceCoordinates::from_xyz(au, 0.0, 0.0),
+                    KeplerOrbit::circular(
+                        au,
+                        KeplerOrbit::mean_motion_from_period(3.1558e7 * au.powf(1.5)),
+                    ),
+                    LogicalParent(sun),
+                ))
+                .id()
+        };
+        let earth = planet("Earth", 1.0);
+        let neptune = planet("Neptune", 30.0);
+
+        let fleet = app
+            .world_mut()
+            .spawn((
+                Fleet {
+                    name: "Chemical Fleet".to_string(),
+                    engine: "chemical_engine".to_string(),
+                    dry_mass_t: 1000.0,
+                    fuel_t: 1000.0,
+                    fuel_capacity_t: 1000.0,
+                },
+                FleetLocation::Orbiting(earth),
+                SpaceCoordinates::default(),
+            ))
+            .id();
+        app.world_mut()
+            .resource_mut::<FleetOrders>()
+            .move_to(fleet, neptune);
+        app.update();
+
+        assert_eq!(
+            app.world().get::<FleetLocation>(fleet),
+            Some(&FleetLocation::Orbiting(earth))
+        );
+        assert_eq!(app.world().resource::<EventLog>().entries.len(), 1);
+    }
+}
@@ -0,0 +1,396 @@
+//! Transfer orbits between bodies
+//!
+//! Moves are planned as Hohmann transfers around the lowest body the origin
+//! and destination share in the orbit hierarchy (the star for interplanetary
+//! moves, the planet for moves between its moons). Leaving or reaching a
+//! body orbiting that frame adds an escape or capture burn from a low
+//! parking orbit, which gets the Oberth effect. Departure waits for the
+//! phase angle at which the destination arrives at the ellipse's far end.
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::f64::consts::{PI, TAU};
+
+use crate::astronomy::components::KeplerOrbit;
+use crate::astronomy::{orbit_position_from_mean_anomaly, AU_M, G};
+
+/// Parking orbit radius as a multiple of the body radius
+pub const PARKING_ORBIT_RADII: f64 = 1.1;
+
+/// What the planner needs to know about a body
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrbitNode {
+    /// Body this one orbits, if any
+    pub parent: Option<Entity>,
+    pub mass_kg: f64,
+    pub radius_km: f64,
+    /// Orbit around the parent
+    pub orbit: Option<KeplerOrbit>,
+    /// Current position in AU
+    pub position: DVec3,
+}
+
+impl OrbitNode {
+    fn parking_radius_m(&self) -> f64 {
+        self.radius_km * 1000.0 * PARKING_ORBIT_RADII
+    }
+}
+
+/// A scheduled move: wait for the departure window, burn, coast on the
+/// transfer ellipse and burn again to enter orbit at the destination
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transfer {
+    pub origin: Entity,
+    pub destination: Entity,
+    /// Body the transfer ellipse is centred on
+    pub frame: Entity,
+    /// Transfer ellipse around the frame; `mean_anomaly_epoch` is the mean
+    /// anomaly at departure
+    pub orbit: KeplerOrbit,
+    /// Simulation time of the departure burn in seconds
+    pub departure_time: f64,
+    /// Simulation time of the insertion burn in seconds
+    pub arrival_time: f64,
+    pub departure_dv_km_s: f64,
+    pub insertion_dv_km_s: f64,
+    /// The departure burn has been made
+    pub departed: bool,
+}
+
+impl Transfer {
+    pub fn total_dv_km_s(&self) -> f64 {
+        self.departure_dv_km_s + self.insertion_dv_km_s
+    }
+
+    /// Position relative to the frame while coasting, in AU
+    pub fn coast_offset(&self, time: f64) -> DVec3 {
+        let elapsed =
+            (time - self.departure_time).clamp(0.0, self.arrival_time - self.departure_time);
+        orbit_position_from_mean_anomaly(
+            &self.orbit,
+            self.orbit.mean_anomaly_epoch + self.orbit.mean_motion * elapsed,
+        )
+    }
+
+    /// Fraction of the coast completed (0.0 to 1.0)
+    pub fn coast_fraction(&self, time: f64) -> f64 {
+        let duration = self.arrival_time - self.departure_time;
+        if duration <= 0.0 {
+            1.0
+        } else {
+            ((time - self.departure_time) / duration).clamp(0.0, 1.0)
+        }
+    }
+}
+
+/// Burns of a Hohmann transfer between circular orbits of radius `r1` and
+/// `r2` (in meters) around a body with gravitational parameter `mu`.
+/// Returns the departure and arrival delta-v in m/s and the coast time in
+/// seconds.
+pub fn hohmann(mu: f64, r1: f64, r2: f64) -> (f64, f64, f64) {
+    let a = (r1 + r2) / 2.0;
+    let dv1 = ((mu / r1).sqrt() * ((2.0 * r2 / (r1 + r2)).sqrt() - 1.0)).abs();
+    let dv2 = ((mu / r2).sqrt() * (1.0 - (2.0 * r1 / (r1 + r2)).sqrt())).abs();
+    let time = PI * (a * a * a / mu).sqrt();
+    (dv1, dv2, time)
+}
+
+/// Burn from a circular parking orbit of radius `r` around a body with
+/// gravitational parameter `mu` onto an escape trajectory leaving at
+/// `v_infinity` (all in SI units). The same burn captures on arrival.
+pub fn escape_burn(mu: f64, r: f64, v_infinity: f64) -> f64 {
+    (v_infinity * v_infinity + 2.0 * mu / r).sqrt() - (mu / r).sqrt()
+}
+
+/// Chain of `entity` and its ancestors, nearest first
+fn ancestors(entity: Entity, bodies: &HashMap<Entity, OrbitNode>) -> Vec<Entity> {
+    let mut chain = vec![entity];
+    let mut current = entity;
+    while let Some(parent) = bodies.get(&current).and_then(|node| node.parent) {
+        if chain.contains(&parent) {
+            break;
+        }
+        chain.push(parent);
+        current = parent;
+    }
+    chain
+}
+
+/// One end of a transfer: the body orbiting the frame (None when the end
+/// is the frame itself) and the transfer radius in meters
+fn transfer_end(
+    chain: &[Entity],
+    frame: Entity,
+    bodies: &HashMap<Entity, OrbitNode>,
+) -> Result<(Option<Entity>, f64), String> {
+    let index = chain.iter().position(|&e| e == frame).unwrap_or(0);
+    if index == 0 {
+        let node = &bodies[&frame];
+        return Ok((None, node.parking_radius_m()));
+    }
+    let child = chain[index - 1];
+    let orbit = bodies[&child]
+        .orbit
+        .ok_or_else(|| "body has no orbit".to_string())?;
+    Ok((Some(child), orbit.semi_major_axis * AU_M))
+}
+
+/// Angle of `child` around the frame in the reference plane
+fn angle_around(child: Entity, frame: Entity, bodies: &HashMap<Entity, OrbitNode>) -> f64 {
+    let offset = bodies[&child].position - bodies[&frame].position;
+    offset.y.atan2(offset.x)
+}
+
+/// Plan a transfer from `origin` to `destination` departing at the next
+/// window after `now`
+pub fn plan_transfer(
+    origin: Entity,
+    destination: Entity,
+    now: f64,
+    bodies: &HashMap<Entity, OrbitNode>,
+) -> Result<Transfer, String> {
+    if origin == destination {
+        return Err("already there".to_string());
+    }
+    if !bodies.contains_key(&origin) || !bodies.contains_key(&destination) {
+        return Err("unknown body".to_string());
+    }
+
+    let origin_chain = ancestors(origin, bodies);
+    let destination_chain = ancestors(destination, bodies);
+    let frame = *origin_chain
+        .iter()
+        .find(|e| destination_chain.contains(e))
+        .ok_or_else(|| "bodies are in different star systems".to_string())?;
+    let frame_node = bodies[&frame];
+    let mu = G * frame_node.mass_kg;
+    if mu <= 0.0 {
+        return Err("frame has no mass".to_string());
+    }
+
+    let (departure_body, r1) = transfer_end(&origin_chain, frame, bodies)?;
+    let (arrival_body, r2) = transfer_end(&destination_chain, frame, bodies)?;
+    let (dv1, dv2, coast) = hohmann(mu, r1, r2);
+
+    // Escape and capture burns at bodies orbiting the frame
+    let end_burn = |body: Option<Entity>, v_infinity: f64| match body {
+        Some(body) => {
+            let node = &bodies[&body];
+            escape_burn(G * node.mass_kg, node.parking_radius_m(), v_infinity)
+        }
+        None => v_infinity,
+    };
+    let departure_dv = end_burn(departure_body, dv1);
+    let insertion_dv = end_burn(arrival_body, dv2);
+
+    // Wait for the phase angle at which the destination meets the fleet at
+    // the far end of the ellipse
+    let mean_motion = |body: Entity| bodies[&body].orbit.map_or(0.0, |o| o.mean_motion);
+    let (wait, departure_angle) = match (departure_body, arrival_body) {
+        (Some(from), Some(to)) => {
+            // The destination covers the rest of the half circle during the coast
+            let lead = PI - mean_motion(to) * coast;
+            let phase = angle_around(to, frame, bodies) - angle_around(from, frame, bodies);
+            let rate = mean_motion(to) - mean_motion(from);
+            let wait = if rate.abs() < 1e-15 {
+                0.0
+            } else if rate > 0.0 {
+                (lead - phase).rem_euclid(TAU) / rate
+            } else {
+                (phase - lead).rem_euclid(TAU) / -rate
+            };
+            (wait, angle_around(from, frame, bodies) + mean_motion(from) * wait)
+        }
+        (Some(from), None) => (0.0, angle_around(from, frame, bodies)),
+        (None, Some(to)) => {
+            // Any point of the parking orbit will do: leave from the one
+            // opposite the destination's position on arrival
+            let arrival = angle_around(to, frame, bodies) + mean_motion(to) * coast;
+            (0.0, arrival - PI)
+        }
+        (None, None) => return Err("already there".to_string()),
+    };
+
+    let (argument_of_periapsis, mean_anomaly_at_departure) = if r2 >= r1 {
+        (departure_angle, 0.0)
+    } else {
+        (departure_angle + PI, PI)
+    };
+    let orbit = KeplerOrbit::new(
+        (r2 - r1).abs() / (r1 + r2),
+        (r1 + r2) / 2.0 / AU_M,
+        0.0,
+        0.0,
+        argument_of_periapsis,
+        mean_anomaly_at_departure,
+        PI / coast,
+    );
+
+    Ok(Transfer {
+        origin,
+        destination,
+        frame,
+        orbit,
+        departure_time: now + wait,
+        arrival_time: now + wait + coast,
+        departure_dv_km_s: departure_dv / 1000.0,
+        insertion_dv_km_s: insertion_dv / 1000.0,
+        departed: false,
+    })
+}
+
+/// Delta-v bands a transfer falls in, for colour-coding destinations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DeltaVBand {
+    /// Within reach of early chemical rockets
+    Low,
+    Medium,
+    /// Needs advanced engines or staging
+    High,
+}
+
+impl DeltaVBand {
+    /// Upper delta-v of the low band in km/s
+    pub const LOW_KM_S: f64 = 6.0;
+    /// Upper delta-v of the medium band in km/s
+    pub const MEDIUM_KM_S: f64 = 12.0;
+
+    pub fn of(delta_v_km_s: f64) -> Self {
+        if delta_v_km_s <= Self::LOW_KM_S {
+            DeltaVBand::Low
+        } else if delta_v_km_s <= Self::MEDIUM_KM_S {
+            DeltaVBand::Medium
+        } else {
+            DeltaVBand::High
+        }
+    }
+}
+
+/// Plan a transfer from `origin` to each of `destinations` departing at the
+/// next window after `now`, cheapest in total delta-v first. Destinations
+/// the planner cannot reach are left out.
+pub fn delta_v_map(
+    origin: Entity,
+    destinations: impl IntoIterator<Item = Entity>,
+    now: f64,
+    bodies: &HashMap<Entity, OrbitNode>,
+) -> Vec<Transfer> {
+    let mut transfers: Vec<Transfer> = destinations
+        .into_iter()
+        .filter_map(|destination| plan_transfer(origin, destination, now, bodies).ok())
+        .collect();
+    transfers.sort_by(|a, b| a.total_dv_km_s().total_cmp(&b.total_dv_km_s()));
+    transfers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::astronomy::SOLAR_MASS_KG;
+    use crate::economy::SECONDS_PER_YEAR;
+
+    fn planet(parent: Entity, mass_kg: f64, radius_km: f64, au: f64, angle: f64) -> OrbitNode {
+        let period = SECONDS_PER_YEAR * au.powf(1.5);
+        OrbitNode {
+            parent: Some(parent),
+            mass_kg,
+            radius_km,
+            orbit: Some(KeplerOrbit::circular(
+                au,
+                KeplerOrbit::mean_motion_from_period(period),
+            )),
+            position: DVec3::new(au * angle.cos(), au * angle.sin(), 0.0),
+        }
+    }
+
+    fn inner_system() -> (HashMap<Entity, OrbitNode>, Entity, Entity) {
+        let sun = Entity::from_raw(1);
+        let earth = Entity::from_raw(2);
+        let mars = Entity::from_raw(3);
+        let mut bodies = HashMap::new();
+        bodies.insert(
+            sun,
+            OrbitNode {
+                parent: None,
+                mass_kg: SOLAR_MASS_KG,
+                radius_km: 696_000.0,
+                orbit: None,
+                position: DVec3::ZERO,
+            },
+        );
+        bodies.insert(earth, planet(sun, 5.972e24, 6371.0, 1.0, 0.0));
+        bodies.insert(mars, planet(sun, 6.417e23, 3390.0, 1.524, 1.0));
+        (bodies, earth, mars)
+    }
+
+    #[test]
+    fn test_hohmann_earth_to_mars() {
+        let mu_sun = G * SOLAR_MASS_KG;
+        let (dv1, dv2, time) = hohmann(mu_sun, AU_M, 1.524 * AU_M);
+        assert!((dv1 - 2_940.0).abs() < 50.0, "departure {dv1}");
+        assert!((dv2 - 2_650.0).abs() < 50.0, "arrival {dv2}");
+        let days = time / 86_400.0;
+        assert!((days - 259.0).abs() < 3.0, "coast {days} days");
+    }
+
+    #[test]
+    fn test_transfer_waits_for_window_and_reaches_destination() {
+        let (bodies, earth, mars) = inner_system();
+        let transfer = plan_transfer(earth, mars, 0.0, &bodies).unwrap();
+
+        assert!(transfer.departure_time > 0.0);
+        // Escape from low Earth orbit is the familiar ~3.6 km/s trans-Mars injection
+        assert!((transfer.departure_dv_km_s - 3.6).abs() < 0.2);
+
+        // At arrival the fleet and Mars are at the same place
+        let mars_node = bodies[&mars];
+        let mars_orbit = mars_node.orbit.unwrap();
+        let mars_angle = 1.0 + mars_orbit.mean_motion * transfer.arrival_time;
+        let mars_then = DVec3::new(1.524 * mars_angle.cos(), 1.524 * mars_angle.sin(), 0.0);
+        let fleet_then = transfer.coast_offset(transfer.arrival_time);
+        assert!((fleet_then - mars_then).length() < 1e-3);
+
+        // At departure it is at Earth
+        let earth_orbit = bodies[&earth].orbit.unwrap();
+        let earth_angle = earth_orbit.mean_motion * transfer.departure_time;
+        let earth_then = DVec3::new(earth_angle.cos(), earth_angle.sin(), 0.0);
+        assert!((transfer.coast_offset(transfer.departure_time) - earth_then).length() < 1e-6);
+    }
+
+    #[test]
+    fn test_inward_transfer_and_unreachable_bodies() {
+        let (mut bodies, earth, mars) = inner_system();
+        let inward = plan_transfer(mars, earth, 0.0, &bodies).unwrap();
+        assert!(inward.orbit.semi_major_axis < 1.524);
+        assert!((inward.coast_fraction(inward.departure_time)).abs() < 1e-12);
+
+        let rogue = Entity::from_raw(9);
+        bodies.insert(
+            rogue,
+            OrbitNode {
+                parent: None,
+                mass_kg: 1e20,
+                radius_km: 100.0,
+                orbit: None,
+                position: DVec3::new(1e5, 0.0, 0.0),
+            },
+        );
+        assert!(plan_transfer(earth, rogue, 0.0, &bodies).is_err());
+        assert!(plan_transfer(earth, earth, 0.0, &bodies).is_err());
+    }
+
+    #[test]
+    fn test_delta_v_map_sorts_reachable_destinations() {
+        let (mut bodies, earth, mars) = inner_system();
+        let sun = Entity::from_raw(1);
+        let jupiter = Entity::from_raw(4);
+        bodies.insert(jupiter, planet(sun, 1.898e27, 69_911.0, 5.2, 2.0));
+
+        let map = delta_v_map(earth, [jupiter, earth, mars], 0.0, &bodies);
+        let order: Vec<Entity> = map.iter().map(|t| t.destination).collect();
+        assert_eq!(order, vec![mars, jupiter]);
+        assert_eq!(DeltaVBand::of(map[0].total_dv_km_s()), DeltaVBand::Low);
+        assert_eq!(DeltaVBand::of(map[1].total_dv_km_s()), DeltaVBand::High);
+    }
+}
@@ -2,10 +2,14 @@
 //!
 //! Provides resources for managing game state, including procedural generation seeds
 //! for deterministic and reproducible game worlds. This is essential for save/load
-//! functionality.
+//! functionality. Also hosts the campaign difficulty and the event log that game
+//! systems report to.
 
 use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Game menu categories
@@ -28,6 +32,8 @@ pub enum GameMenu {
     Shipbuilding,
     /// Economy and private sector
     Economy,
+    /// Government policies and colony edicts
+    Policies,
     /// Officers and managers
     Personnel,
     /// Enemy intelligence
@@ -48,6 +54,7 @@ impl GameMenu {
             GameMenu::Fleets => "🚀",
             GameMenu::Shipbuilding => "⚓",
             GameMenu::Economy => "💰",
+            GameMenu::Policies => "📜",
             GameMenu::Personnel => "👤",
             GameMenu::Intel => "🔍",
             GameMenu::Diplomacy => "🤝",
@@ -65,6 +72,7 @@ impl GameMenu {
             GameMenu::Fleets => "Fleets",
             GameMenu::Shipbuilding => "Shipbuilding",
             GameMenu::Economy => "Economy",
+            GameMenu::Policies => "Policies",
             GameMenu::Personnel => "Personnel",
             GameMenu::Intel => "Intel",
             GameMenu::Diplomacy => "Diplomacy",
@@ -82,6 +90,7 @@ impl GameMenu {
             GameMenu::Fleets,
             GameMenu::Shipbuilding,
             GameMenu::Economy,
+            GameMenu::Policies,
             GameMenu::Personnel,
             GameMenu::Intel,
             GameMenu::Diplomacy,
@@ -105,6 +114,7 @@ impl GameMenu {
             GameMenu::Fleets => "fleets",
             GameMenu::Shipbuilding => "shipbuilding",
             GameMenu::Economy => "economy",
+            GameMenu::Policies => "policies",
             GameMenu::Personnel => "personnel",
             GameMenu::Intel => "intel",
             GameMenu::Diplomacy => "diplomacy",
@@ -145,6 +155,14 @@ impl GameSeed {
         Self { value: seed }
     }
 
+    /// Generator for one of the game's random streams, seeded from the
+    /// world's game seed (0 without one). Each stream passes its own `salt`
+    /// so the streams drawn from the same seed don't repeat each other.
+    pub fn rng(world: &World, salt: u64) -> StdRng {
+        let seed = world.get_resource::<GameSeed>().map_or(0, |s| s.value);
+        StdRng::seed_from_u64(seed ^ salt)
+    }
+
     /// Create a game seed from a string (for debug/testing)
     /// Uses a simple hash of the string as the seed
     pub fn from_string(s: &str) -> Self {
@@ -166,6 +184,126 @@ impl Default for GameSeed {
     }
 }
 
+/// Difficulty of the campaign, picked on the new-game screen. Each level is a
+/// set of multipliers read by the research, colony, generation and random
+/// event systems; `Normal` leaves every rate as tuned.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default, Hash, Serialize, Deserialize)]
+pub enum Difficulty {
+    Relaxed,
+    #[default]
+    Normal,
+    Hard,
+    Brutal,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Relaxed,
+        Difficulty::Normal,
+        Difficulty::Hard,
+        Difficulty::Brutal,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Relaxed => "Relaxed",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+            Difficulty::Brutal => "Brutal",
+        }
+    }
+
+    /// Multiplier on the research points a technology costs
+    pub fn research_cost_multiplier(&self) -> f64 {
+        match self {
+            Difficulty::Relaxed => 0.75,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.3,
+            Difficulty::Brutal => 1.6,
+        }
+    }
+
+    /// Multiplier on the natural growth rate of colony populations
+    pub fn population_growth_multiplier(&self) -> f64 {
+        match self {
+            Difficulty::Relaxed => 1.25,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 0.8,
+            Difficulty::Brutal => 0.6,
+        }
+    }
+
+    /// Multiplier on the reserves of generated mineral and volatile deposits
+    pub fn resource_abundance_multiplier(&self) -> f64 {
+        match self {
+            Difficulty::Relaxed => 1.5,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 0.75,
+            Difficulty::Brutal => 0.5,
+        }
+    }
+
+    /// Multiplier on how often random events fire
+    pub fn event_frequency_multiplier(&self) -> f64 {
+        match self {
+            Difficulty::Relaxed => 0.7,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.3,
+            Difficulty::Brutal => 1.6,
+        }
+    }
+}
+
+/// Entries kept in the event log before the oldest are dropped
+pub const MAX_LOG_ENTRIES: usize = 500;
+
+/// Importance of an event log entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LogSeverity {
+    Info,
+    Warning,
+    Critical,
+}
+
+impl LogSeverity {
+    pub fn icon(&self) -> &'static str {
+        match self {
+            LogSeverity::Info => "ℹ",
+            LogSeverity::Warning => "⚠",
+            LogSeverity::Critical => "‼",
+        }
+    }
+}
+
+/// A message in the event log
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    /// In-game date the event happened, as shown in the time controls
+    pub date: String,
+    pub severity: LogSeverity,
+    pub message: String,
+}
+
+/// Chronological log of notable game events, newest last
+#[derive(Resource, Debug, Clone, Default)]
+pub struct EventLog {
+    pub entries: VecDeque<LogEntry>,
+}
+
+impl EventLog {
+    /// Append an entry, dropping the oldest once the log is full
+    pub fn push(&mut self, date: String, severity: LogSeverity, message: impl Into<String>) {
+        self.entries.push_back(LogEntry {
+            date,
+            severity,
+            message: message.into(),
+        });
+        while self.entries.len() > MAX_LOG_ENTRIES {
+            self.entries.pop_front();
+        }
+    }
+}
+
 /// Plugin that manages game state and initialization
 pub struct GameStatePlugin;
 
@@ -173,7 +311,9 @@ impl Plugin for GameStatePlugin {
     fn build(&self, app: &mut App) {
         // Initialize the game seed at startup
         app.init_resource::<GameSeed>()
-           .init_resource::<ActiveMenu>();
+           .init_resource::<ActiveMenu>()
+           .init_resource::<Difficulty>()
+           .init_resource::<EventLog>();
     }
 }
 
@@ -198,4 +338,28 @@ mod tests {
         let seed5 = GameSeed::from_string("different");
         assert_ne!(seed3.value, seed5.value);
     }
+
+    #[test]
+    fn test_difficulty_levels_get_harder() {
+        for pair in Difficulty::ALL.windows(2) {
+            let (easier, harder) = (pair[0], pair[1]);
+            assert!(harder.research_cost_multiplier() > easier.research_cost_multiplier());
+            assert!(harder.population_growth_multiplier() < easier.population_growth_multiplier());
+            assert!(harder.resource_abundance_multiplier() < easier.resource_abundance_multiplier());
+            assert!(harder.event_frequency_multiplier() > easier.event_frequency_multiplier());
+        }
+        let normal = Difficulty::default();
+        assert_eq!(normal.research_cost_multiplier(), 1.0);
+        assert_eq!(normal.resource_abundance_multiplier(), 1.0);
+    }
+
+    #[test]
+    fn test_event_log_is_bounded() {
+        let mut log = EventLog::default();
+        for i in 0..(MAX_LOG_ENTRIES + 5) {
+            log.push("01.01.2026 00:00".to_string(), LogSeverity::Info, format!("{}", i));
+        }
+        assert_eq!(log.entries.len(), MAX_LOG_ENTRIES);
+        assert_eq!(log.entries[0].message, "5");
+    }
 }
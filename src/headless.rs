@@ -0,0 +1,230 @@
+//! Headless simulation for balance testing
+//!
+//! `--headless` runs the simulation without a window, interface, rendering
+//! or sound, one economy day per update and as fast as the machine allows,
+//! for a number of in-game years. The monthly statistics are then written as
+//! CSV so economy and research balance can be compared between runs:
+//!
+//! ```text
+//! helios_ascension --headless --years 50 --seed 42 --scenario sol_2026 --out run.csv
+//! ```
+//!
+//! Random event decisions take their first choice, and the run goes on in
+//! sandbox mode past victory or defeat.
+
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use std::path::{Path, PathBuf};
+
+use crate::economy::{ECONOMY_TICK_SECONDS, SECONDS_PER_YEAR};
+use crate::game_state::{Difficulty, EventLog};
+use crate::plugins::visual_effects::NightMaterial;
+use crate::random_events::PendingEvents;
+use crate::render::rings::RingMaterial;
+use crate::scenarios::{ScenarioId, ScenarioSelection};
+use crate::stats::{StatsHistory, STATS_CSV_PATH};
+use crate::tutorial::Tutorial;
+use crate::ui::{NewGameScreen, SimulationTime, TimeScale, TimeWarp, Toasts, UiSettings};
+use crate::victory::VictoryState;
+
+/// In-game years simulated when `--years` is not given
+pub const DEFAULT_HEADLESS_YEARS: f64 = 10.0;
+
+/// Settings of a headless run, from the command line
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct HeadlessOptions {
+    pub years: f64,
+    /// Game seed; the seed from the system time if not given
+    pub seed: Option<u64>,
+    /// Scenario to start; the default start if not given
+    pub scenario: Option<ScenarioId>,
+    pub difficulty: Difficulty,
+    /// CSV file the statistics are written to
+    pub output: PathBuf,
+}
+
+impl Default for HeadlessOptions {
+    fn default() -> Self {
+        Self {
+            years: DEFAULT_HEADLESS_YEARS,
+            seed: None,
+            scenario: None,
+            difficulty: Difficulty::Normal,
+            output: PathBuf::from(STATS_CSV_PATH),
+        }
+    }
+}
+
+impl HeadlessOptions {
+    /// Parse the command line. Returns `None` without `--headless`.
+    pub fn from_args(args: impl Iterator<Item = String>) -> Result<Option<Self>, String> {
+        let args: Vec<String> = args.collect();
+        if !args.iter().any(|arg| arg == "--headless") {
+            return Ok(None);
+        }
+        let mut options = Self::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+            match arg.as_str() {
+                "--years" => {
+                    options.years = value()?
+                        .parse()
+                        .ok()
+                        .filter(|years: &f64| *years > 0.0)
+                        .ok_or("--years needs a positive number")?;
+                }
+                "--seed" => {
+                    options.seed = Some(value()?.parse().map_err(|_| "--seed needs a number")?);
+                }
+                "--scenario" => options.scenario = Some(value()?.clone()),
+                "--difficulty" => {
+                    let name = value()?;
+                    options.difficulty = Difficulty::ALL
+                        .into_iter()
+                        .find(|d| d.name().eq_ignore_ascii_case(name))
+                        .ok_or_else(|| format!("Unknown difficulty '{}'", name))?;
+                }
+                "--out" => options.output = PathBuf::from(value()?),
+                _ => {}
+            }
+        }
+        Ok(Some(options))
+    }
+}
+
+/// Progress of the headless run
+#[derive(Resource, Debug, Clone, Default)]
+pub struct HeadlessRun {
+    /// Simulation seconds elapsed when the campaign started
+    pub started_at: Option<f64>,
+}
+
+/// Plugin that drives the simulation in a headless run. The app must be
+/// built without a window, and with a manual time update strategy so every
+/// update runs the fixed-timestep schedule once.
+pub struct HeadlessPlugin(pub HeadlessOptions);
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.0.clone())
+            .init_resource::<HeadlessRun>()
+            // Owned by the interface and render plugins, which a headless
+            // run leaves out, but read by the simulation
+            .init_resource::<SimulationTime>()
+            .init_resource::<TimeScale>()
+            .init_resource::<TimeWarp>()
+            .init_resource::<NewGameScreen>()
+            .init_resource::<Toasts>()
+            .insert_resource(UiSettings::default())
+            .init_asset::<NightMaterial>()
+            .init_asset::<RingMaterial>()
+            .add_systems(Startup, start_headless_run)
+            .add_systems(
+                Update,
+                step_headless_run.after(crate::scenarios::start_scenario),
+            );
+    }
+}
+
+/// System that sets up the campaign in place of the new-game screen. The
+/// time scale stays paused; `step_headless_run` advances the clock instead.
+pub fn start_headless_run(
+    options: Res<HeadlessOptions>,
+    mut screen: ResMut<NewGameScreen>,
+    mut selection: ResMut<ScenarioSelection>,
+    mut difficulty: ResMut<Difficulty>,
+    mut tutorial: ResMut<Tutorial>,
+    mut time_scale: ResMut<TimeScale>,
+) {
+    info!(
+        "Headless run of {} years ({}, {})",
+        options.years,
+        options.scenario.as_deref().unwrap_or("default start"),
+        options.difficulty.name()
+    );
+    screen.open = false;
+    selection.pending = options.scenario.clone();
+    *difficulty = options.difficulty;
+    tutorial.enabled = false;
+    time_scale.pause();
+}
+
+/// System that advances the simulation by one economy day, answers waiting
+/// decisions and writes the statistics once the years are up
+#[allow(clippy::too_many_arguments)]
+pub fn step_headless_run(
+    options: Res<HeadlessOptions>,
+    mut run: ResMut<HeadlessRun>,
+    mut sim_time: ResMut<SimulationTime>,
+    mut pending_events: ResMut<PendingEvents>,
+    mut victory: ResMut<VictoryState>,
+    history: Res<StatsHistory>,
+    log: Res<EventLog>,
+    mut exit: EventWriter<AppExit>,
+) {
+    let started_at = *run.started_at.get_or_insert(sim_time.elapsed_seconds());
+
+    if !pending_events.queue.is_empty() && pending_events.choice.is_none() {
+        pending_events.choice = Some(0);
+    }
+    if let (Some(result), false) = (&victory.result, victory.sandbox) {
+        info!(
+            "{} on {}: {}",
+            result.outcome.name(),
+            result.date,
+            result.reason
+        );
+        victory.sandbox = true;
+    }
+
+    if sim_time.elapsed_seconds() - started_at < options.years * SECONDS_PER_YEAR {
+        sim_time.elapsed += ECONOMY_TICK_SECONDS;
+        return;
+    }
+
+    info!(
+        "Headless run finished on {}: {} monthly samples, {} log entries",
+        sim_time.format_date_time(),
+        history.samples.len(),
+        log.entries.len()
+    );
+    match history.export_csv(Path::new(&options.output)) {
+        Ok(()) => info!("Statistics written to {}", options.output.display()),
+        Err(e) => error!("{}", e),
+    }
+    exit.send(AppExit::Success);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> Result<Option<HeadlessOptions>, String> {
+        HeadlessOptions::from_args(line.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn test_parse_headless_arguments() {
+        assert_eq!(parse("helios_ascension"), Ok(None));
+        assert_eq!(
+            parse("helios_ascension --headless"),
+            Ok(Some(HeadlessOptions::default()))
+        );
+
+        let options = parse(
+            "helios_ascension --headless --years 25 --seed 7 --scenario sol_2026 --difficulty hard --out run.csv",
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(options.years, 25.0);
+        assert_eq!(options.seed, Some(7));
+        assert_eq!(options.scenario.as_deref(), Some("sol_2026"));
+        assert_eq!(options.difficulty, Difficulty::Hard);
+        assert_eq!(options.output, PathBuf::from("run.csv"));
+
+        assert!(parse("helios_ascension --headless --years").is_err());
+        assert!(parse("helios_ascension --headless --years -3").is_err());
+        assert!(parse("helios_ascension --headless --difficulty impossible").is_err());
+    }
+}
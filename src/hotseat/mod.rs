@@ -0,0 +1,49 @@
+//! Hotseat games
+//!
+//! Two players share the screen, each with colonies, stations and fleets of
+//! their own:
+//! - At the start of every month the simulation pauses and the players take
+//!   turns giving orders, the screen covered while it changes hands
+//! - Orders are player commands like any other, refused when they act on
+//!   what answers to the other player
+//! - Each player only sees the other's holdings that their sensors reach;
+//!   while the month runs, only what both players see is shown
+//! - Each player has credits and stockpiles of their own, which orders
+//!   spend during their turn. While the month runs they are pooled, and
+//!   what the month brought in or used up is shared out by population.
+//!
+//! Research is still shared: a technology either player unlocks serves both.
+
+use bevy::prelude::*;
+
+pub mod systems;
+
+pub use systems::{
+    assign_seats, begin_hotseat_turns, keep_seat_books, update_hotseat_view, Hotseat, HotseatView,
+    Seat, SeatBooks, TurnPhase, SEATS, TURN_TICKS,
+};
+
+/// Plugin that adds hotseat games
+pub struct HotseatPlugin;
+
+impl Plugin for HotseatPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Hotseat>()
+            .init_resource::<HotseatView>()
+            .add_systems(
+                FixedUpdate,
+                begin_hotseat_turns.after(crate::economy::advance_economy_clock),
+            )
+            .add_systems(
+                Update,
+                (
+                    assign_seats
+                        .after(crate::commands::apply_player_commands)
+                        .after(crate::economy::found_requested_colonies)
+                        .after(crate::construction::found_stations),
+                    update_hotseat_view.after(assign_seats),
+                    keep_seat_books.after(crate::commands::apply_player_commands),
+                ),
+            );
+    }
+}
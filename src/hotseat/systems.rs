@@ -0,0 +1,642 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+
+use crate::astronomy::components::{SpaceCoordinates, SystemId};
+use crate::commands::PlayerCommand;
+use crate::construction::OrbitalStation;
+use crate::economy::components::Population;
+use crate::economy::{ColonyFounded, EconomyClock, GlobalBudget, ResourceType, Treasury};
+use crate::fleets::{Fleet, FleetLocation};
+use crate::game_state::{EventLog, LogSeverity};
+use crate::intel::{
+    fleet_position, nearest_sensor, Sensor, SensorContacts, COLONY_SENSOR_RANGE_AU,
+    FLEET_SENSOR_RANGE_AU,
+};
+use crate::plugins::solar_system::CelestialBody;
+use crate::research::types::ModifierType;
+use crate::research::ResearchState;
+use crate::scenarios::ScenarioSelection;
+use crate::ui::{SimulationTime, TimeScale};
+
+/// Economy ticks (days) of a month, between two rounds of turns
+pub const TURN_TICKS: u64 = 30;
+
+/// Players sharing the screen
+pub const SEATS: usize = 2;
+
+/// Player a colony, station or fleet answers to, by seat
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Seat(pub usize);
+
+/// Where a hotseat game stands in the month
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TurnPhase {
+    /// The month runs, and orders wait for the next turns
+    #[default]
+    Running,
+    /// The player in this seat gives orders while the simulation is paused
+    Turn(usize),
+}
+
+/// A hotseat game: players sharing the screen, who take turns giving
+/// orders at the start of every month
+#[derive(Resource, Debug, Clone, Default)]
+pub struct Hotseat {
+    pub enabled: bool,
+    /// Names of the players, by seat
+    pub players: Vec<String>,
+    /// Body of the second player's home colony; every other colony of the
+    /// scenario starts with the first player
+    pub home: Option<String>,
+    pub phase: TurnPhase,
+    /// Month whose turns were played last, counted in [`TURN_TICKS`]
+    pub month: Option<u64>,
+    /// Bodies nobody holds where a colony was ordered, with the seat of the
+    /// player who did; the colony answers to them once it is founded
+    pub colony_claims: Vec<(Entity, usize)>,
+    /// Hosts nobody holds where a station was ordered, with the seat of the
+    /// player who did; the next station founded there answers to them.
+    /// Orders may be on their way at light speed, so these wait.
+    pub station_claims: Vec<(Entity, usize)>,
+    /// Credits and stockpiles of the players, by seat
+    pub books: Vec<SeatBooks>,
+    /// Phase whose books the treasury and stockpiles hold: the player's own
+    /// during their turn, all players' pooled while the month runs. `None`
+    /// until the books are split at the start of the game.
+    pub booked: Option<TurnPhase>,
+}
+
+/// Credits and stockpiles of one player
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SeatBooks {
+    pub balance: f64,
+    pub stockpiles: HashMap<ResourceType, f64>,
+}
+
+impl SeatBooks {
+    fn scaled(&self, factor: f64) -> Self {
+        Self {
+            balance: self.balance * factor,
+            stockpiles: self
+                .stockpiles
+                .iter()
+                .map(|(resource, amount)| (*resource, amount * factor))
+                .collect(),
+        }
+    }
+}
+
+impl Hotseat {
+    /// Name of the player in `seat`
+    pub fn player(&self, seat: usize) -> &str {
+        self.players.get(seat).map_or("?", |name| name.as_str())
+    }
+
+    /// Seat of the player whose turn it is
+    pub fn active_seat(&self) -> Option<usize> {
+        match self.phase {
+            TurnPhase::Turn(seat) => Some(seat),
+            TurnPhase::Running => None,
+        }
+    }
+
+    /// Start the turns of `month` with the first player
+    pub fn begin_month(&mut self, month: u64) {
+        self.month = Some(month);
+        self.phase = TurnPhase::Turn(0);
+    }
+
+    /// End the turn of the active player, returning whether it was the
+    /// last one and the month runs
+    pub fn end_turn(&mut self) -> bool {
+        match self.phase {
+            TurnPhase::Turn(seat) if seat + 1 < self.players.len() => {
+                self.phase = TurnPhase::Turn(seat + 1);
+                false
+            }
+            TurnPhase::Turn(_) => {
+                self.phase = TurnPhase::Running;
+                true
+            }
+            TurnPhase::Running => false,
+        }
+    }
+
+    /// Books of all players together
+    pub fn pooled(&self) -> SeatBooks {
+        let mut pooled = SeatBooks::default();
+        for books in &self.books {
+            pooled.balance += books.balance;
+            for (resource, amount) in &books.stockpiles {
+                *pooled.stockpiles.entry(*resource).or_insert(0.0) += amount;
+            }
+        }
+        pooled
+    }
+
+    /// Share out between the players what the month added to or used up
+    /// from `pooled`, the books of all players together, by the population
+    /// each holds. Nobody's stock goes below zero.
+    pub fn share_out(&mut self, pooled: &SeatBooks, population: &[f64]) {
+        let before = self.pooled();
+        let total: f64 = population.iter().sum();
+        let seats = self.books.len() as f64;
+        for (seat, books) in self.books.iter_mut().enumerate() {
+            let share = if total > 0.0 {
+                population.get(seat).copied().unwrap_or(0.0) / total
+            } else {
+                1.0 / seats
+            };
+            books.balance += (pooled.balance - before.balance) * share;
+            for (resource, amount) in &pooled.stockpiles {
+                let change = amount - before.stockpiles.get(resource).copied().unwrap_or(0.0);
+                let held = books.stockpiles.entry(*resource).or_insert(0.0);
+                *held = (*held + change * share).max(0.0);
+            }
+        }
+    }
+
+    /// Whether `command` may be given now, or why not. `owner` is the seat
+    /// and name of the colony, station or fleet the command acts on, if it
+    /// answers to a player. While the month runs only the clock and the
+    /// decisions it brings up are open; during a turn the clock waits for
+    /// the player, who may only command what answers to them.
+    pub fn authorize(
+        &self,
+        command: &PlayerCommand,
+        owner: Option<(usize, &str)>,
+    ) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+        match (self.phase, command) {
+            (
+                TurnPhase::Running,
+                PlayerCommand::SetTimeScale(_)
+                | PlayerCommand::Pause
+                | PlayerCommand::Resume
+                | PlayerCommand::WarpToNextEvent
+                | PlayerCommand::EventChoice(_)
+                | PlayerCommand::ContinueInSandbox,
+            ) => Ok(()),
+            (TurnPhase::Running, _) => Err("orders wait for the next turns".to_string()),
+            (
+                TurnPhase::Turn(seat),
+                PlayerCommand::SetTimeScale(_)
+                | PlayerCommand::Resume
+                | PlayerCommand::WarpToNextEvent,
+            ) => Err(format!(
+                "the clock waits for {} to end the turn",
+                self.player(seat)
+            )),
+            (TurnPhase::Turn(seat), _) => match owner {
+                Some((owner, name)) if owner != seat => {
+                    Err(format!("{} answers to {}", name, self.player(owner)))
+                }
+                _ => Ok(()),
+            },
+        }
+    }
+}
+
+/// Colonies, stations and fleets of other players that the player at the
+/// screen doesn't see
+#[derive(Resource, Debug, Clone, Default)]
+pub struct HotseatView {
+    pub hidden: HashSet<Entity>,
+}
+
+impl HotseatView {
+    pub fn is_hidden(&self, entity: Entity) -> bool {
+        self.hidden.contains(&entity)
+    }
+}
+
+/// System that gives the colonies, stations and fleets of a hotseat game
+/// to the players: a colony or station founded where a player ordered it
+/// to that player, a scenario colony to the second player if it is their
+/// home and to the first otherwise, and any other station or fleet to the
+/// player of the body it orbits
+#[allow(clippy::type_complexity)]
+pub fn assign_seats(
+    mut commands: Commands,
+    mut hotseat: ResMut<Hotseat>,
+    mut founded: EventReader<ColonyFounded>,
+    colonies: Query<(Entity, &CelestialBody, &Population), Without<Seat>>,
+    stations: Query<(Entity, &OrbitalStation), Without<Seat>>,
+    fleets: Query<(Entity, &FleetLocation), (With<Fleet>, Without<Seat>)>,
+    seats: Query<&Seat>,
+) {
+    if !hotseat.enabled {
+        founded.clear();
+        return;
+    }
+    // Colonies are founded on the update they are ordered, or not at all
+    let founded: HashSet<Entity> = founded.read().map(|f| f.body).collect();
+    let claimed: HashMap<Entity, usize> = hotseat
+        .colony_claims
+        .drain(..)
+        .filter(|(body, _)| founded.contains(body))
+        .collect();
+    for (&body, &seat) in &claimed {
+        commands.entity(body).insert(Seat(seat));
+    }
+    let seat_of = |entity: Entity| {
+        claimed
+            .get(&entity)
+            .copied()
+            .or_else(|| seats.get(entity).ok().map(|seat| seat.0))
+    };
+
+    for (entity, body, population) in colonies.iter() {
+        if population.count <= 0.0 || claimed.contains_key(&entity) {
+            continue;
+        }
+        let seat = usize::from(hotseat.home.as_deref() == Some(body.name.as_str()));
+        commands.entity(entity).insert(Seat(seat));
+    }
+    for (entity, station) in stations.iter() {
+        let claim = hotseat
+            .station_claims
+            .iter()
+            .position(|(host, _)| *host == station.host);
+        let seat = match claim {
+            Some(index) => hotseat.station_claims.remove(index).1,
+            None => seat_of(station.host).unwrap_or(0),
+        };
+        commands.entity(entity).insert(Seat(seat));
+    }
+    for (entity, location) in fleets.iter() {
+        let body = match location {
+            FleetLocation::Orbiting(body) => *body,
+            FleetLocation::InTransit(transfer) => transfer.origin,
+        };
+        commands
+            .entity(entity)
+            .insert(Seat(seat_of(body).unwrap_or(0)));
+    }
+}
+
+/// System that pauses a hotseat game at the start of every month for the
+/// players' turns, and keeps it paused until the last of them has ended
+pub fn begin_hotseat_turns(
+    mut hotseat: ResMut<Hotseat>,
+    clock: Res<EconomyClock>,
+    mut time_scale: ResMut<TimeScale>,
+    selection: Option<Res<ScenarioSelection>>,
+    (mut log, sim_time): (Option<ResMut<EventLog>>, Option<Res<SimulationTime>>),
+) {
+    if !hotseat.enabled {
+        return;
+    }
+    // Starting the scenario moves the clock to its date
+    if selection.is_some_and(|s| s.pending.is_some()) {
+        return;
+    }
+
+    let month = clock.processed / TURN_TICKS;
+    if hotseat.phase == TurnPhase::Running && hotseat.month != Some(month) {
+        hotseat.begin_month(month);
+        let message = format!("Turns of the month: {}", hotseat.players.join(", then "));
+        info!("{}", message);
+        if let (Some(log), Some(sim_time)) = (log.as_mut(), sim_time.as_ref()) {
+            log.push(sim_time.format_date_time(), LogSeverity::Info, message);
+        }
+    }
+    if hotseat.phase != TurnPhase::Running && !time_scale.is_paused() {
+        time_scale.pause();
+    }
+}
+
+/// System that gives the players of a hotseat game their own credits and
+/// stockpiles. During a turn the treasury and stockpiles hold the active
+/// player's books; while the month runs they hold all players' pooled, and
+/// what the month brought in or used up is shared out when the turns begin.
+pub fn keep_seat_books(
+    mut hotseat: ResMut<Hotseat>,
+    mut treasury: ResMut<Treasury>,
+    mut budget: ResMut<GlobalBudget>,
+    holdings: Query<(&Seat, &Population)>,
+    selection: Option<Res<ScenarioSelection>>,
+) {
+    if !hotseat.enabled || hotseat.booked == Some(hotseat.phase) {
+        return;
+    }
+    // The scenario sets up the treasury and stockpiles first
+    if selection.is_some_and(|s| s.pending.is_some()) {
+        return;
+    }
+
+    let held = SeatBooks {
+        balance: treasury.balance,
+        stockpiles: budget.stockpiles.clone(),
+    };
+    let seats = hotseat.players.len();
+    match hotseat.booked {
+        None => hotseat.books = vec![held.scaled(1.0 / seats.max(1) as f64); seats],
+        Some(TurnPhase::Turn(seat)) => {
+            if let Some(books) = hotseat.books.get_mut(seat) {
+                *books = held;
+            }
+        }
+        Some(TurnPhase::Running) => {
+            let mut population = vec![0.0; seats];
+            for (seat, holding) in holdings.iter() {
+                if let Some(count) = population.get_mut(seat.0) {
+                    *count += holding.count.max(0.0);
+                }
+            }
+            hotseat.share_out(&held, &population);
+        }
+    }
+
+    let loaded = match hotseat.phase {
+        TurnPhase::Turn(seat) => hotseat.books.get(seat).cloned().unwrap_or_default(),
+        TurnPhase::Running => hotseat.pooled(),
+    };
+    treasury.balance = loaded.balance;
+    budget.stockpiles = loaded.stockpiles;
+    hotseat.booked = Some(hotseat.phase);
+}
+
+/// System that hides the colonies, stations and fleets of other players
+/// that the player at the screen has no sensor in range of. While the
+/// month runs both players watch, so only what every player sees is shown.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn update_hotseat_view(
+    hotseat: Res<Hotseat>,
+    mut view: ResMut<HotseatView>,
+    research_state: Option<Res<ResearchState>>,
+    sim_time: Res<SimulationTime>,
+    colonies: Query<(
+        Entity,
+        &Seat,
+        &Population,
+        Option<&CelestialBody>,
+        Option<&OrbitalStation>,
+    )>,
+    fleets: Query<(Entity, &Seat, &Fleet, &FleetLocation)>,
+    bodies: Query<(&SpaceCoordinates, Option<&SystemId>)>,
+    contacts: Option<Res<SensorContacts>>,
+) {
+    view.hidden.clear();
+    if !hotseat.enabled {
+        return;
+    }
+    let reach = 1.0
+        + research_state
+            .as_ref()
+            .map_or(0.0, |r| r.get_modifier(ModifierType::SensorRange))
+            / 100.0;
+    let locate = |entity: Entity| {
+        bodies
+            .get(entity)
+            .ok()
+            .map(|(coords, system)| (coords.position, system.map_or(0, |s| s.0)))
+    };
+    let now = sim_time.elapsed_seconds();
+
+    // Every holding with the seat it answers to, where it is and the range
+    // of its sensors
+    let mut holdings: Vec<(Entity, usize, String, Option<(DVec3, usize)>, f64)> = Vec::new();
+    for (entity, seat, population, body, station) in colonies.iter() {
+        if population.count <= 0.0 {
+            continue;
+        }
+        let name = body
+            .map(|b| b.name.clone())
+            .or_else(|| station.map(|s| s.name.clone()))
+            .unwrap_or_default();
+        let located = locate(station.map_or(entity, |s| s.host));
+        holdings.push((entity, seat.0, name, located, COLONY_SENSOR_RANGE_AU));
+    }
+    for (entity, seat, fleet, location) in fleets.iter() {
+        let located = fleet_position(location, locate, now);
+        holdings.push((
+            entity,
+            seat.0,
+            fleet.name.clone(),
+            located,
+            FLEET_SENSOR_RANGE_AU,
+        ));
+    }
+
+    let mut sensors: Vec<Vec<Sensor>> = vec![Vec::new(); hotseat.players.len()];
+    for (_, seat, name, located, range_au) in &holdings {
+        if let (Some(sensors), Some((position, system))) = (sensors.get_mut(*seat), located) {
+            sensors.push(Sensor {
+                name: name.clone(),
+                position: *position,
+                system: *system,
+                range_au: range_au * reach,
+            });
+        }
+    }
+    let viewers: Vec<usize> = match hotseat.phase {
+        TurnPhase::Turn(seat) => vec![seat],
+        TurnPhase::Running => (0..hotseat.players.len()).collect(),
+    };
+
+    for (entity, owner, _, located, _) in &holdings {
+        let seen_by = |viewer: usize| {
+            viewer == *owner
+                || located.is_some_and(|(position, system)| {
+                    // Holdings show up at the range sensors pick up ships
+                    sensors.get(viewer).is_some_and(|sensors| {
+                        nearest_sensor(sensors, position, system, 1.0).is_some()
+                    })
+                })
+        };
+        if !viewers.iter().all(|viewer| seen_by(*viewer)) {
+            view.hidden.insert(*entity);
+        }
+    }
+
+    // Contacts are tracked by the sensors of both players, so a contact
+    // only shows if the viewers' own sensors pick it up
+    for contact in contacts.iter().flat_map(|c| c.contacts.iter()) {
+        let located = locate(contact.anchor);
+        let seen_by = |viewer: usize| {
+            located.is_some_and(|(position, system)| {
+                sensors.get(viewer).is_some_and(|sensors| {
+                    nearest_sensor(sensors, position, system, contact.kind.signature()).is_some()
+                })
+            })
+        };
+        if !viewers.iter().all(|viewer| seen_by(*viewer)) {
+            view.hidden.insert(contact.target);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn two_player_game() -> Hotseat {
+        Hotseat {
+            enabled: true,
+            players: vec!["Ada".to_string(), "Grace".to_string()],
+            home: Some("Mars".to_string()),
+            ..default()
+        }
+    }
+
+    #[test]
+    fn test_players_take_turns_each_month() {
+        let mut app = App::new();
+        app.init_resource::<EconomyClock>()
+            .init_resource::<TimeScale>()
+            .insert_resource(two_player_game())
+            .add_systems(Update, begin_hotseat_turns);
+
+        app.update();
+        let hotseat = app.world().resource::<Hotseat>().clone();
+        assert_eq!(hotseat.phase, TurnPhase::Turn(0));
+        assert!(app.world().resource::<TimeScale>().is_paused());
+
+        // Orders only go to what answers to the player at the screen
+        let refuel = PlayerCommand::RefuelFleet(Entity::from_raw(3).into());
+        assert!(hotseat.authorize(&refuel, Some((0, "First Fleet"))).is_ok());
+        assert_eq!(
+            hotseat.authorize(&refuel, Some((1, "Red Fleet"))),
+            Err("Red Fleet answers to Grace".to_string())
+        );
+        assert!(hotseat.authorize(&PlayerCommand::Resume, None).is_err());
+        assert!(hotseat
+            .authorize(&PlayerCommand::WarpToNextEvent, None)
+            .is_err());
+
+        let mut hotseat = hotseat;
+        assert!(!hotseat.end_turn());
+        assert_eq!(hotseat.phase, TurnPhase::Turn(1));
+        assert!(hotseat.end_turn());
+        assert!(hotseat
+            .authorize(&refuel, Some((0, "First Fleet")))
+            .is_err());
+        app.insert_resource(hotseat);
+        app.world_mut().resource_mut::<TimeScale>().resume();
+
+        // The rest of the month runs, then the next turns pause it again
+        app.world_mut().resource_mut::<EconomyClock>().processed = TURN_TICKS - 1;
+        app.update();
+        assert!(!app.world().resource::<TimeScale>().is_paused());
+        app.world_mut().resource_mut::<EconomyClock>().processed = TURN_TICKS;
+        app.update();
+        assert_eq!(app.world().resource::<Hotseat>().month, Some(1));
+        assert!(app.world().resource::<TimeScale>().is_paused());
+    }
+
+    #[test]
+    fn test_claims_hold_only_once_the_colony_is_founded() {
+        let mut app = App::new();
+        app.insert_resource(two_player_game())
+            .add_event::<ColonyFounded>()
+            .add_systems(Update, assign_seats);
+        let body = |name: &str| {
+            (
+                CelestialBody::fixture(name, crate::plugins::solar_system_data::BodyType::Moon),
+                Population::default(),
+            )
+        };
+        let titan = app.world_mut().spawn(body("Titan")).id();
+        let europa = app.world_mut().spawn(body("Europa")).id();
+        {
+            let mut hotseat = app.world_mut().resource_mut::<Hotseat>();
+            hotseat.colony_claims.push((titan, 1));
+            hotseat.colony_claims.push((europa, 1));
+        }
+
+        // Only Titan's founding went through
+        app.world_mut().send_event(ColonyFounded {
+            body: titan,
+            body_name: "Titan".to_string(),
+            source: titan,
+        });
+        app.update();
+        assert_eq!(app.world().get::<Seat>(titan), Some(&Seat(1)));
+        assert_eq!(app.world().get::<Seat>(europa), None);
+        assert!(app.world().resource::<Hotseat>().colony_claims.is_empty());
+    }
+
+    #[test]
+    fn test_holdings_out_of_sensor_range_are_hidden() {
+        let mut app = App::new();
+        let mut hotseat = two_player_game();
+        hotseat.phase = TurnPhase::Turn(0);
+        app.insert_resource(hotseat)
+            .init_resource::<HotseatView>()
+            .init_resource::<SimulationTime>()
+            .add_systems(Update, update_hotseat_view);
+        app.world_mut().spawn((
+            Seat(0),
+            Population::new(1e9),
+            SpaceCoordinates {
+                position: DVec3::new(1.0, 0.0, 0.0),
+            },
+        ));
+        let rival = app
+            .world_mut()
+            .spawn((
+                Seat(1),
+                Population::new(1e6),
+                SpaceCoordinates {
+                    position: DVec3::new(10.0, 0.0, 0.0),
+                },
+            ))
+            .id();
+
+        app.update();
+        assert!(app.world().resource::<HotseatView>().is_hidden(rival));
+
+        app.world_mut()
+            .get_mut::<SpaceCoordinates>(rival)
+            .unwrap()
+            .position = DVec3::new(1.5, 0.0, 0.0);
+        app.update();
+        assert!(!app.world().resource::<HotseatView>().is_hidden(rival));
+    }
+
+    #[test]
+    fn test_players_spend_from_their_own_books() {
+        let mut app = App::new();
+        let mut hotseat = two_player_game();
+        hotseat.phase = TurnPhase::Turn(0);
+        let mut budget = GlobalBudget::default();
+        budget.stockpiles.insert(ResourceType::Water, 100.0);
+        app.insert_resource(hotseat)
+            .insert_resource(Treasury {
+                balance: 1000.0,
+                ..default()
+            })
+            .insert_resource(budget)
+            .add_systems(Update, keep_seat_books);
+        app.world_mut().spawn((Seat(0), Population::new(3e6)));
+        app.world_mut().spawn((Seat(1), Population::new(1e6)));
+        let water =
+            |app: &App| app.world().resource::<GlobalBudget>().stockpiles[&ResourceType::Water];
+
+        // The first player gets half, and spends some of it
+        app.update();
+        assert_eq!(app.world().resource::<Treasury>().balance, 500.0);
+        assert_eq!(water(&app), 50.0);
+        app.world_mut().resource_mut::<Treasury>().balance -= 200.0;
+
+        app.world_mut().resource_mut::<Hotseat>().end_turn();
+        app.update();
+        assert_eq!(app.world().resource::<Treasury>().balance, 500.0);
+
+        // The month runs on the pooled books and brings in 400 credits,
+        // shared out by population
+        app.world_mut().resource_mut::<Hotseat>().end_turn();
+        app.update();
+        assert_eq!(app.world().resource::<Treasury>().balance, 800.0);
+        app.world_mut().resource_mut::<Treasury>().balance += 400.0;
+        app.world_mut().resource_mut::<Hotseat>().begin_month(1);
+        app.update();
+        assert_eq!(app.world().resource::<Treasury>().balance, 600.0);
+        assert_eq!(water(&app), 50.0);
+    }
+}
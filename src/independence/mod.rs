@@ -0,0 +1,39 @@
+//! Colony independence
+//!
+//! Provides secession of unhappy colonies:
+//! - A monthly roll for every colony in unrest, more likely the deeper the
+//!   unrest, the more colonists want independence (see the demographics
+//!   module) and the longer the light delay from the capital, and ruled out
+//!   by a garrison: the crew of the fleets in its orbit
+//! - A seceding colony takes its population, buildings and depot along and
+//!   becomes a faction that sabotages construction
+//! - Recognizing its independence in the Diplomacy window ends the sabotage
+//!   and opens trade; until then, a large enough garrison retakes it
+
+use bevy::prelude::*;
+
+pub mod systems;
+
+pub use systems::{
+    garrison_needed, process_independence_actions, secession_chance, weigh_secession,
+    IndependenceRng, Independent, IndependentColony, PendingIndependenceActions, Secessions,
+    GARRISON_CREW_PER_THOUSAND,
+};
+
+/// Plugin that lets colonies declare independence
+pub struct IndependencePlugin;
+
+impl Plugin for IndependencePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Secessions>()
+            .init_resource::<PendingIndependenceActions>()
+            .init_resource::<IndependenceRng>()
+            .add_systems(
+                FixedUpdate,
+                weigh_secession
+                    .after(crate::economy::update_stability)
+                    .before(crate::trade::update_market),
+            )
+            .add_systems(Update, process_independence_actions);
+    }
+}
@@ -0,0 +1,477 @@
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::Rng;
+
+use crate::astronomy::SpaceCoordinates;
+use crate::comms::light_delay_seconds;
+use crate::construction::{Building, ConstructionQueue};
+use crate::economy::components::Population;
+use crate::economy::demographics::HALF_DRIFT_DELAY_SECONDS;
+use crate::economy::{
+    capital_position, Depot, EconomyClock, GlobalBudget, ResourceType, Stability,
+    INDEPENDENCE_DIVERGENCE, UNREST_STABILITY,
+};
+use crate::fleets::{Crew, FleetLocation};
+use crate::game_state::{EventLog, GameSeed, LogSeverity};
+use crate::plugins::solar_system::{CelestialBody, LogicalParent};
+use crate::trade::{FactionDefinition, FactionId, FactionsData};
+use crate::ui::SimulationTime;
+
+/// Economy ticks (days) between rolls
+pub const SECESSION_INTERVAL_TICKS: u64 = 30;
+
+/// Monthly chance that a remote colony in open revolt, whose colonists all
+/// want independence, secedes
+pub const MAX_SECESSION_CHANCE: f64 = 0.25;
+
+/// Crew of the fleets in orbit per thousand colonists that keeps a colony
+/// from seceding, and retakes it once it has
+pub const GARRISON_CREW_PER_THOUSAND: f64 = 0.5;
+
+/// Sabotage attempts per year of a colony whose independence is not
+/// recognized
+pub const SECESSIONIST_HOSTILITY: f64 = 2.0;
+
+/// Counter-intelligence of an independent colony (0-1)
+pub const INDEPENDENT_SECURITY: f64 = 0.3;
+
+/// Tariff of an independent colony, in percent
+pub const INDEPENDENT_TARIFF: f64 = 15.0;
+
+/// Megatons an independent colony trades per year per million colonists
+pub const TRADE_MT_PER_MILLION: f64 = 5.0;
+
+/// Resources an independent colony buys, unless it sells them
+pub const INDEPENDENT_IMPORTS: [ResourceType; 4] = [
+    ResourceType::Water,
+    ResourceType::Nitrogen,
+    ResourceType::Iron,
+    ResourceType::Aluminum,
+];
+
+/// Stability a retaken colony loses
+pub const RETAKEN_STABILITY_LOSS: f64 = 20.0;
+
+/// A body or station whose colony declared independence
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+pub struct Independent {
+    pub faction: FactionId,
+}
+
+/// A colony that declared independence, with what it took along
+#[derive(Debug, Clone)]
+pub struct IndependentColony {
+    /// Faction it became
+    pub faction: FactionId,
+    /// Body or station it stands on
+    pub colony: Entity,
+    /// Body a station orbits, where its garrison may also wait
+    pub parent: Option<Entity>,
+    pub name: String,
+    /// Date of the declaration
+    pub declared: String,
+    pub population: Population,
+    /// Buildings with their names, restored when the colony is retaken
+    pub buildings: Vec<(Building, String)>,
+    /// Stock of its depot at the declaration
+    pub depot: Depot,
+    /// Whether the civilization recognized its independence, which opens
+    /// trade with it and rules out retaking it
+    pub recognized: bool,
+}
+
+impl IndependentColony {
+    /// Crew needed in orbit to retake it
+    pub fn garrison_needed(&self) -> f64 {
+        garrison_needed(self.population.count)
+    }
+}
+
+/// Colonies that declared independence
+#[derive(Resource, Debug, Clone, Default)]
+pub struct Secessions {
+    pub colonies: Vec<IndependentColony>,
+}
+
+impl Secessions {
+    pub fn get(&self, faction: &str) -> Option<&IndependentColony> {
+        self.colonies.iter().find(|c| c.faction == faction)
+    }
+
+    pub fn get_mut(&mut self, faction: &str) -> Option<&mut IndependentColony> {
+        self.colonies.iter_mut().find(|c| c.faction == faction)
+    }
+}
+
+/// Independence recognitions requested from the UI, applied by
+/// `process_independence_actions`
+#[derive(Resource, Debug, Clone, Default)]
+pub struct PendingIndependenceActions {
+    pub recognize: Vec<FactionId>,
+}
+
+/// Random number generator for secessions, seeded from the [`GameSeed`]
+#[derive(Resource, Debug, Clone)]
+pub struct IndependenceRng(pub StdRng);
+
+impl FromWorld for IndependenceRng {
+    fn from_world(world: &mut World) -> Self {
+        Self(GameSeed::rng(world, 0x494E_4445_5045))
+    }
+}
+
+/// Crew needed in orbit of a colony of `population` to keep it loyal
+pub fn garrison_needed(population: f64) -> f64 {
+    population.max(0.0) / 1000.0 * GARRISON_CREW_PER_THOUSAND
+}
+
+/// Monthly chance that a colony secedes. Only colonies in unrest secede,
+/// the more likely the deeper the unrest, the larger the share of
+/// colonists wanting independence and the longer the light delay from the
+/// capital; a garrison of `garrison` crew lowers the chance, and one of
+/// [`garrison_needed`] rules it out.
+pub fn secession_chance(
+    stability: f64,
+    support: f64,
+    delay_seconds: f64,
+    garrison: f64,
+    population: f64,
+) -> f64 {
+    if stability >= UNREST_STABILITY || population <= 0.0 {
+        return 0.0;
+    }
+    let unrest = ((UNREST_STABILITY - stability) / UNREST_STABILITY).clamp(0.0, 1.0);
+    let delay = delay_seconds.max(0.0);
+    let remoteness = delay / (delay + HALF_DRIFT_DELAY_SECONDS);
+    let grievance = (support.clamp(0.0, 1.0) + remoteness) / 2.0;
+    let deterrence = (garrison / garrison_needed(population)).clamp(0.0, 1.0);
+    MAX_SECESSION_CHANCE * unrest * grievance * (1.0 - deterrence)
+}
+
+/// Crew aboard the fleets orbiting a colony, or the body its station orbits
+fn garrison<'a>(
+    fleets: impl Iterator<Item = (&'a FleetLocation, &'a Crew)>,
+    colony: Entity,
+    parent: Option<Entity>,
+) -> f64 {
+    fleets
+        .filter(|(location, _)| {
+            matches!(location, FleetLocation::Orbiting(body)
+                if *body == colony || Some(*body) == parent)
+        })
+        .map(|(_, crew)| crew.aboard as f64)
+        .sum()
+}
+
+/// System that once a month lets colonies in unrest declare independence
+/// and retakes the unrecognized independent colonies a large enough
+/// garrison orbits
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn weigh_secession(
+    mut commands: Commands,
+    clock: Res<EconomyClock>,
+    mut rng: ResMut<IndependenceRng>,
+    mut secessions: ResMut<Secessions>,
+    mut budget: ResMut<GlobalBudget>,
+    mut factions: Option<ResMut<FactionsData>>,
+    colonies: Query<(
+        Entity,
+        &Population,
+        Option<&Stability>,
+        Option<&SpaceCoordinates>,
+        Option<&CelestialBody>,
+        Option<&Name>,
+        Option<&LogicalParent>,
+    )>,
+    mut retaken: Query<&mut Stability, Without<Population>>,
+    (fleets, buildings): (
+        Query<(&FleetLocation, &Crew)>,
+        Query<(Entity, &Building, &LogicalParent, Option<&Name>)>,
+    ),
+    mut log: Option<ResMut<EventLog>>,
+    sim_time: Option<Res<SimulationTime>>,
+) {
+    if clock.due == 0 {
+        return;
+    }
+    let months = clock.processed / SECESSION_INTERVAL_TICKS
+        - (clock.processed - clock.due) / SECESSION_INTERVAL_TICKS;
+    if months == 0 {
+        return;
+    }
+    let date = sim_time
+        .as_ref()
+        .map(|t| t.format_date_time())
+        .unwrap_or_default();
+    let mut messages = Vec::new();
+
+    secessions.colonies.retain(|independent| {
+        let crew = garrison(fleets.iter(), independent.colony, independent.parent);
+        if independent.recognized || crew < independent.garrison_needed() {
+            return true;
+        }
+        let colony = independent.colony;
+        commands
+            .entity(colony)
+            .remove::<Independent>()
+            .insert(independent.population.clone());
+        for (building, name) in &independent.buildings {
+            commands.spawn((
+                building.clone(),
+                LogicalParent(colony),
+                Name::new(name.clone()),
+            ));
+        }
+        for (resource, amount) in &independent.depot.stockpiles {
+            budget.add_resource_at(colony, *resource, amount.max(0.0));
+        }
+        for (good, amount) in &independent.depot.refined {
+            budget.add_refined_at(colony, *good, amount.max(0.0));
+        }
+        if let Ok(mut stability) = retaken.get_mut(colony) {
+            stability.add_event(-RETAKEN_STABILITY_LOSS);
+        }
+        if let Some(factions) = factions.as_mut() {
+            factions.remove(&independent.faction);
+        }
+        messages.push((
+            LogSeverity::Warning,
+            format!(
+                "The garrison in orbit has retaken {}, ending its independence",
+                independent.name
+            ),
+        ));
+        false
+    });
+
+    let capital = capital_position(colonies.iter().map(|(_, p, _, c, ..)| (p, c)));
+    for (entity, population, stability, coords, body, name, parent) in colonies.iter() {
+        let Some(stability) = stability else {
+            continue;
+        };
+        let delay = match (capital, coords) {
+            (Some(capital), Some(coords)) => light_delay_seconds(capital, coords.position),
+            _ => 0.0,
+        };
+        let parent = parent.map(|p| p.0);
+        let monthly = secession_chance(
+            stability.value,
+            population.independence_support(INDEPENDENCE_DIVERGENCE),
+            delay,
+            garrison(fleets.iter(), entity, parent),
+            population.count,
+        );
+        if monthly <= 0.0 {
+            continue;
+        }
+        let chance = 1.0 - (1.0 - monthly).powi(months as i32);
+        if rng.0.gen::<f64>() >= chance {
+            continue;
+        }
+
+        let name = body
+            .map(|b| b.name.clone())
+            .or_else(|| name.map(|n| n.to_string()))
+            .unwrap_or_else(|| "Outpost".to_string());
+        let faction = format!("independent_{}", name.to_lowercase().replace(' ', "_"));
+        let depot = budget.seize_depot(entity);
+        let mut seized = Vec::new();
+        for (building_entity, building, building_parent, building_name) in buildings.iter() {
+            if building_parent.0 == entity {
+                let building_name = building_name.map_or(building.id.clone(), |n| n.to_string());
+                seized.push((building.clone(), building_name));
+                commands.entity(building_entity).despawn();
+            }
+        }
+        commands
+            .entity(entity)
+            .remove::<(Population, ConstructionQueue)>()
+            .insert(Independent {
+                faction: faction.clone(),
+            });
+        if let Some(factions) = factions.as_mut() {
+            factions.insert(FactionDefinition {
+                id: faction.clone(),
+                name: format!("Free {}", name),
+                description: format!(
+                    "The colonists of {} who declared independence on {}",
+                    name, date
+                ),
+                tariff: INDEPENDENT_TARIFF,
+                capacity_mt_per_year: (population.count / 1e6 * TRADE_MT_PER_MILLION).max(1.0),
+                sells: Vec::new(),
+                buys: Vec::new(),
+                security: INDEPENDENT_SECURITY,
+                hostility: SECESSIONIST_HOSTILITY,
+                doctrine: None,
+            });
+        }
+        messages.push((
+            LogSeverity::Critical,
+            format!(
+                "🏴 {} has declared independence, taking {} colonists and {} buildings",
+                name,
+                population.count.round(),
+                seized.len()
+            ),
+        ));
+        secessions.colonies.push(IndependentColony {
+            faction,
+            colony: entity,
+            parent,
+            name,
+            declared: date.clone(),
+            population: population.clone(),
+            buildings: seized,
+            depot,
+            recognized: false,
+        });
+    }
+
+    for (severity, message) in messages {
+        info!("{}", message);
+        if let Some(log) = log.as_mut() {
+            log.push(date.clone(), severity, message);
+        }
+    }
+}
+
+/// System to recognize the independence of the colonies requested from the
+/// UI. The colony stops its sabotage and trades what its depot held.
+pub fn process_independence_actions(
+    mut pending: ResMut<PendingIndependenceActions>,
+    mut secessions: ResMut<Secessions>,
+    mut factions: Option<ResMut<FactionsData>>,
+    mut log: Option<ResMut<EventLog>>,
+    sim_time: Option<Res<SimulationTime>>,
+) {
+    if pending.recognize.is_empty() {
+        return;
+    }
+    let mut messages = Vec::new();
+    for faction in std::mem::take(&mut pending.recognize) {
+        let Some(independent) = secessions.get_mut(&faction) else {
+            continue;
+        };
+        if independent.recognized {
+            continue;
+        }
+        independent.recognized = true;
+        if let Some(definition) = factions.as_mut().and_then(|f| f.get_mut(&faction)) {
+            definition.hostility = 0.0;
+            definition.sells = ResourceType::all()
+                .iter()
+                .copied()
+                .filter(|resource| independent.depot.get(resource) > 0.0)
+                .collect();
+            definition.buys = INDEPENDENT_IMPORTS
+                .iter()
+                .copied()
+                .filter(|resource| !definition.sells.contains(resource))
+                .collect();
+        }
+        messages.push(format!(
+            "The independence of {} is recognized; trade with it is open",
+            independent.name
+        ));
+    }
+
+    for message in messages {
+        info!("{}", message);
+        if let (Some(log), Some(sim_time)) = (log.as_mut(), sim_time.as_ref()) {
+            log.push(sim_time.format_date_time(), LogSeverity::Info, message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::math::DVec3;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_remote_colony_secedes_and_garrison_retakes_it() {
+        // Only colonies in unrest secede, and a full garrison keeps them
+        assert_eq!(secession_chance(50.0, 1.0, 1e5, 0.0, 1e4), 0.0);
+        assert_eq!(secession_chance(0.0, 1.0, 1e5, 5.0, 1e4), 0.0);
+        assert!(
+            secession_chance(0.0, 1.0, 1e5, 0.0, 1e4) > secession_chance(0.0, 0.0, 0.0, 0.0, 1e4)
+        );
+
+        let mut app = App::new();
+        app.init_resource::<EconomyClock>()
+            .init_resource::<Secessions>()
+            .init_resource::<GlobalBudget>()
+            .init_resource::<FactionsData>()
+            .insert_resource(IndependenceRng(StdRng::seed_from_u64(1)))
+            .add_systems(Update, weigh_secession);
+        app.world_mut().spawn((
+            Population::new(8e9),
+            Stability::default(),
+            SpaceCoordinates::new(DVec3::new(1.0, 0.0, 0.0)),
+        ));
+        let station = app
+            .world_mut()
+            .spawn((
+                Population::new(10_000.0),
+                Stability {
+                    value: 0.0,
+                    ..default()
+                },
+                SpaceCoordinates::new(DVec3::new(30.0, 0.0, 0.0)),
+                Name::new("Neptune Station"),
+            ))
+            .id();
+        app.world_mut().spawn((
+            Building {
+                id: "mine".to_string(),
+                level: 1,
+            },
+            LogicalParent(station),
+        ));
+        app.world_mut()
+            .resource_mut::<GlobalBudget>()
+            .add_resource_at(station, ResourceType::Water, 5.0);
+
+        // Roll for ten years until the station secedes
+        for _ in 0..120 {
+            app.world_mut().resource_mut::<EconomyClock>().processed += SECESSION_INTERVAL_TICKS;
+            app.world_mut().resource_mut::<EconomyClock>().due = SECESSION_INTERVAL_TICKS;
+            app.update();
+            if app.world().get::<Independent>(station).is_some() {
+                break;
+            }
+        }
+        let faction = &app.world().get::<Independent>(station).unwrap().faction;
+        assert_eq!(faction, "independent_neptune_station");
+        assert!(app.world().get::<Population>(station).is_none());
+        assert!(app
+            .world()
+            .resource::<FactionsData>()
+            .get(faction)
+            .is_some());
+        let mut buildings = app.world_mut().query::<&Building>();
+        assert_eq!(buildings.iter(app.world()).count(), 0);
+        let budget = app.world().resource::<GlobalBudget>();
+        assert_eq!(budget.stockpile_at(station, &ResourceType::Water), 0.0);
+
+        // A garrison of five crew retakes its ten thousand colonists
+        app.world_mut().spawn((
+            FleetLocation::Orbiting(station),
+            Crew {
+                required: 5,
+                aboard: 5,
+            },
+        ));
+        app.world_mut().resource_mut::<EconomyClock>().processed += SECESSION_INTERVAL_TICKS;
+        app.update();
+        assert!(app.world().get::<Independent>(station).is_none());
+        assert_eq!(
+            app.world().get::<Population>(station).unwrap().count,
+            10_000.0
+        );
+        assert_eq!(buildings.iter(app.world()).count(), 1);
+        assert!(app.world().resource::<Secessions>().colonies.is_empty());
+    }
+}
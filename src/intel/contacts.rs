@@ -0,0 +1,514 @@
+//! Sensor contacts
+//!
+//! Colonies and fleets carry sensors that pick up objects within their
+//! range: hostile ships, derelicts and natural objects on odd orbits like
+//! infalling comets. A detected object becomes an unidentified contact with
+//! an uncertainty ellipse around its estimated position, long along the line
+//! of sight and narrow across it. The ellipse shrinks with every day of
+//! tracking, the faster the closer the nearest sensor, until the contact is
+//! identified as what it really is. Contacts no sensor sees any more grow
+//! uncertain again and are lost after a while.
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::Rng;
+use std::collections::HashMap;
+
+use super::types::ContactKind;
+use crate::astronomy::components::{CurrentStarSystem, SpaceCoordinates, SystemId};
+use crate::astronomy::{SpatialIndex, VisitingComet, SCALING_FACTOR};
+use crate::construction::OrbitalStation;
+use crate::economy::components::Population;
+use crate::economy::EconomyClock;
+use crate::fleets::{Fleet, FleetLocation, HostileFleet, Salvage};
+use crate::game_state::{EventLog, GameSeed, LogSeverity};
+use crate::hotseat::HotseatView;
+use crate::plugins::camera::ViewMode;
+use crate::plugins::solar_system::CelestialBody;
+use crate::research::types::ModifierType;
+use crate::research::ResearchState;
+use crate::ui::SimulationTime;
+
+/// Range in AU of the sensors of a populated colony or station
+pub const COLONY_SENSOR_RANGE_AU: f64 = 3.0;
+/// Range in AU of the sensors a fleet carries
+pub const FLEET_SENSOR_RANGE_AU: f64 = 1.0;
+/// Error along the line of sight on detection, as a share of the distance
+pub const RANGE_ERROR_SHARE: f64 = 0.05;
+/// Error across the line of sight as a share of the error along it
+pub const CROSS_RANGE_SHARE: f64 = 0.3;
+/// Days of tracking that shrink the error by a factor of √2; it keeps
+/// falling with the square root of the days tracked
+pub const TRACKING_DAYS: f64 = 5.0;
+/// Error along the line of sight in AU below which a contact is identified
+pub const IDENTIFY_AU: f64 = 0.01;
+/// Days without any sensor seeing a contact before it is lost
+pub const LOST_DAYS: f64 = 30.0;
+
+/// A sensor: a populated colony or station, or a fleet
+#[derive(Debug, Clone)]
+pub struct Sensor {
+    pub name: String,
+    pub position: DVec3,
+    pub system: usize,
+    pub range_au: f64,
+}
+
+/// Nearest sensor in the same star system that sees an object with the
+/// given signature at `position`, and its distance in AU
+pub fn nearest_sensor(
+    sensors: &[Sensor],
+    position: DVec3,
+    system: usize,
+    signature: f64,
+) -> Option<(&Sensor, f64)> {
+    sensors
+        .iter()
+        .filter(|sensor| sensor.system == system)
+        .map(|sensor| (sensor, sensor.position.distance(position)))
+        .filter(|(sensor, distance)| *distance <= sensor.range_au * signature)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+/// Position and star system of a fleet, from those of the bodies it is
+/// placed by (see `locate`) at `now`
+pub fn fleet_position(
+    location: &FleetLocation,
+    locate: impl Fn(Entity) -> Option<(DVec3, usize)>,
+    now: f64,
+) -> Option<(DVec3, usize)> {
+    match location {
+        FleetLocation::Orbiting(body) => locate(*body),
+        FleetLocation::InTransit(transfer) if transfer.departed => locate(transfer.frame)
+            .map(|(frame, system)| (frame + transfer.coast_offset(now), system)),
+        FleetLocation::InTransit(transfer) => locate(transfer.origin),
+    }
+}
+
+/// Semi-axes in AU of the uncertainty ellipse of an object `range_au` from
+/// the sensor, along and across the line of sight: they shrink while it is
+/// tracked and grow again while no sensor sees it
+pub fn uncertainty_au(range_au: f64, tracked_days: f64, unseen_days: f64) -> (f64, f64) {
+    let along = range_au * RANGE_ERROR_SHARE / (1.0 + tracked_days / TRACKING_DAYS).sqrt()
+        * (1.0 + unseen_days / TRACKING_DAYS);
+    (along, along * CROSS_RANGE_SHARE)
+}
+
+/// A detected object
+#[derive(Debug, Clone)]
+pub struct SensorContact {
+    /// Designation while unidentified, like "C-3"
+    pub designation: String,
+    /// The object itself
+    pub target: Entity,
+    pub kind: ContactKind,
+    /// Body the estimated position is given from: the one a ship or
+    /// derelict orbits, or the object itself
+    pub anchor: Entity,
+    /// Name of the object, known once identified
+    pub name: String,
+    pub identified: bool,
+    pub tracked_days: f64,
+    pub unseen_days: f64,
+    /// Nearest sensor that saw it last, and its distance in AU
+    pub sensor: String,
+    pub range_au: f64,
+    /// Direction of the line of sight in the ecliptic, in radians
+    pub bearing: f64,
+    /// Error of the estimate in units of the semi-axes (along, across,
+    /// out of the ecliptic), drawn on detection
+    pub error: DVec3,
+}
+
+impl SensorContact {
+    /// Semi-axes of the uncertainty ellipse in AU
+    pub fn uncertainty_au(&self) -> (f64, f64) {
+        uncertainty_au(self.range_au, self.tracked_days, self.unseen_days)
+    }
+
+    /// Estimated position relative to the anchor in AU
+    pub fn estimated_offset(&self) -> DVec3 {
+        let (along, across) = self.uncertainty_au();
+        let sight = DVec3::new(self.bearing.cos(), self.bearing.sin(), 0.0);
+        let side = DVec3::new(-self.bearing.sin(), self.bearing.cos(), 0.0);
+        sight * self.error.x * along
+            + side * self.error.y * across
+            + DVec3::Z * self.error.z * across
+    }
+
+    /// Name shown to the player: the object's once identified
+    pub fn label(&self) -> String {
+        if self.identified {
+            self.name.clone()
+        } else {
+            format!("{} (unidentified)", self.designation)
+        }
+    }
+}
+
+/// Objects the sensors track
+#[derive(Resource, Debug, Clone, Default)]
+pub struct SensorContacts {
+    pub contacts: Vec<SensorContact>,
+    /// Number of the next designation
+    pub next: u32,
+}
+
+impl SensorContacts {
+    pub fn get(&self, target: Entity) -> Option<&SensorContact> {
+        self.contacts.iter().find(|c| c.target == target)
+    }
+}
+
+/// Random number generator for the errors of sensor estimates, seeded from
+/// the [`GameSeed`]
+#[derive(Resource, Debug, Clone)]
+pub struct SensorRng(pub StdRng);
+
+impl FromWorld for SensorRng {
+    fn from_world(world: &mut World) -> Self {
+        Self(GameSeed::rng(world, 0x5345_4e53_4f52))
+    }
+}
+
+/// System that detects objects within sensor range on due economy ticks,
+/// tracks the contacts sensors see, identifies those tracked closely enough
+/// and loses those no sensor has seen for [`LOST_DAYS`]
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn track_contacts(
+    clock: Res<EconomyClock>,
+    research_state: Option<Res<ResearchState>>,
+    index: Res<SpatialIndex>,
+    mut contacts: ResMut<SensorContacts>,
+    mut rng: ResMut<SensorRng>,
+    colonies: Query<(
+        Entity,
+        &Population,
+        Option<&CelestialBody>,
+        Option<&OrbitalStation>,
+    )>,
+    fleets: Query<(&Fleet, &FleetLocation)>,
+    bodies: Query<(&SpaceCoordinates, Option<&SystemId>)>,
+    (hostiles, salvage, comets): (
+        Query<(Entity, &HostileFleet)>,
+        Query<(Entity, &Salvage)>,
+        Query<(Entity, &CelestialBody), With<VisitingComet>>,
+    ),
+    (mut log, sim_time): (Option<ResMut<EventLog>>, Option<Res<SimulationTime>>),
+) {
+    if clock.due == 0 {
+        return;
+    }
+    let days = clock.due as f64;
+    let reach = 1.0
+        + research_state
+            .as_ref()
+            .map_or(0.0, |r| r.get_modifier(ModifierType::SensorRange))
+            / 100.0;
+    let locate = |entity: Entity| {
+        bodies
+            .get(entity)
+            .ok()
+            .map(|(coords, system)| (coords.position, system.map_or(0, |s| s.0)))
+    };
+
+    // Colony sensors are looked up in the spatial index by the body they
+    // are on; all have the same range, so the nearest one in range sees
+    let colony_range = COLONY_SENSOR_RANGE_AU * reach;
+    let mut colony_sensors: HashMap<Entity, String> = HashMap::new();
+    for (entity, population, body, station) in colonies.iter() {
+        if population.count <= 0.0 {
+            continue;
+        }
+        let name = body
+            .map(|b| b.name.clone())
+            .or_else(|| station.map(|s| s.name.clone()))
+            .unwrap_or_default();
+        colony_sensors
+            .entry(station.map_or(entity, |s| s.host))
+            .or_insert(name);
+    }
+    let mut fleet_sensors = Vec::new();
+    let now = sim_time.as_ref().map_or(0.0, |t| t.elapsed_seconds());
+    for (fleet, location) in fleets.iter() {
+        if let Some((position, system)) = fleet_position(location, locate, now) {
+            fleet_sensors.push(Sensor {
+                name: fleet.name.clone(),
+                position,
+                system,
+                range_au: FLEET_SENSOR_RANGE_AU * reach,
+            });
+        }
+    }
+
+    let targets = hostiles
+        .iter()
+        .map(|(e, h)| (e, ContactKind::Ship, h.body, h.name.clone()))
+        .chain(
+            salvage
+                .iter()
+                .map(|(e, s)| (e, ContactKind::Derelict, s.body, s.name.clone())),
+        )
+        .chain(
+            comets
+                .iter()
+                .map(|(e, b)| (e, ContactKind::Object, e, b.name.clone())),
+        );
+    let mut seen = HashMap::new();
+    let mut present = HashMap::new();
+    for (target, kind, anchor, name) in targets {
+        present.insert(target, (kind, anchor, name));
+        let Some((position, system)) = locate(anchor) else {
+            continue;
+        };
+        let colony = index
+            .nearest(position, colony_range * kind.signature(), |body, entry| {
+                entry.system_id == system && colony_sensors.contains_key(&body)
+            })
+            .and_then(|(body, distance)| {
+                let entry = index.get(body)?;
+                Some((&colony_sensors[&body], entry.position, distance))
+            });
+        let fleet = nearest_sensor(&fleet_sensors, position, system, kind.signature())
+            .map(|(sensor, distance)| (&sensor.name, sensor.position, distance));
+        if let Some((name, sensor_position, distance)) = colony
+            .into_iter()
+            .chain(fleet)
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+        {
+            let sight = position - sensor_position;
+            seen.insert(target, (name.clone(), distance, sight.y.atan2(sight.x)));
+        }
+    }
+
+    let mut messages = Vec::new();
+    let contacts = &mut *contacts;
+    contacts.contacts.retain_mut(|contact| {
+        if !present.contains_key(&contact.target) {
+            return false;
+        }
+        match seen.remove(&contact.target) {
+            Some((sensor, distance, bearing)) => {
+                contact.tracked_days += days;
+                contact.unseen_days = 0.0;
+                contact.sensor = sensor;
+                contact.range_au = distance;
+                contact.bearing = bearing;
+                if !contact.identified && contact.uncertainty_au().0 <= IDENTIFY_AU {
+                    contact.identified = true;
+                    messages.push((
+                        LogSeverity::Info,
+                        format!(
+                            "Contact {} identified: {} ({})",
+                            contact.designation,
+                            contact.name,
+                            contact.kind.name().to_lowercase()
+                        ),
+                    ));
+                }
+                true
+            }
+            None => {
+                contact.unseen_days += days;
+                if contact.unseen_days < LOST_DAYS {
+                    return true;
+                }
+                messages.push((
+                    LogSeverity::Info,
+                    format!("Sensors lost track of {}", contact.label()),
+                ));
+                false
+            }
+        }
+    });
+
+    // Whatever is still left in sight is new
+    let mut detected: Vec<_> = seen.into_iter().collect();
+    detected.sort_by_key(|(target, _)| *target);
+    for (target, (sensor, distance, bearing)) in detected {
+        let (kind, anchor, name) = present.remove(&target).unwrap();
+        contacts.next += 1;
+        let designation = format!("C-{}", contacts.next);
+        let error = loop {
+            let error = DVec3::new(
+                rng.0.gen_range(-1.0..1.0),
+                rng.0.gen_range(-1.0..1.0),
+                rng.0.gen_range(-1.0..1.0),
+            );
+            if error.length_squared() <= 1.0 {
+                break error;
+            }
+        };
+        messages.push((
+            if kind == ContactKind::Ship {
+                LogSeverity::Warning
+            } else {
+                LogSeverity::Info
+            },
+            format!(
+                "📡 New sensor contact {}: {:.2} AU from {}",
+                designation, distance, sensor
+            ),
+        ));
+        contacts.contacts.push(SensorContact {
+            designation,
+            target,
+            kind,
+            anchor,
+            name,
+            identified: false,
+            tracked_days: 0.0,
+            unseen_days: 0.0,
+            sensor,
+            range_au: distance,
+            bearing,
+            error,
+        });
+    }
+
+    for (severity, message) in messages {
+        info!("{}", message);
+        if let (Some(log), Some(sim_time)) = (log.as_mut(), sim_time.as_ref()) {
+            log.push(sim_time.format_date_time(), severity, message);
+        }
+    }
+}
+
+/// System that draws the contacts in the current star system: the
+/// uncertainty ellipse around the estimated position of unidentified ones,
+/// a marker on identified ones
+pub fn draw_contacts(
+    mut gizmos: Gizmos,
+    view_mode: Res<ViewMode>,
+    current_system: Res<CurrentStarSystem>,
+    contacts: Res<SensorContacts>,
+    anchors: Query<(&GlobalTransform, Option<&SystemId>)>,
+    hotseat_view: Option<Res<HotseatView>>,
+) {
+    if *view_mode != ViewMode::System {
+        return;
+    }
+
+    for contact in &contacts.contacts {
+        if hotseat_view
+            .as_ref()
+            .is_some_and(|v| v.is_hidden(contact.target))
+        {
+            continue;
+        }
+        let Ok((transform, system)) = anchors.get(contact.anchor) else {
+            continue;
+        };
+        if system.map_or(0, |s| s.0) != current_system.0 {
+            continue;
+        }
+        let offset = contact.estimated_offset() * SCALING_FACTOR;
+        let position =
+            transform.translation() + Vec3::new(offset.x as f32, offset.y as f32, offset.z as f32);
+        if contact.identified {
+            let color = match contact.kind {
+                ContactKind::Ship => Color::srgb(1.0, 0.3, 0.3),
+                ContactKind::Derelict | ContactKind::Object => Color::srgb(0.7, 0.7, 0.7),
+            };
+            gizmos.sphere(position, Quat::IDENTITY, 1.5, color);
+            continue;
+        }
+        let (along, across) = contact.uncertainty_au();
+        let half_size = Vec2::new(
+            ((along * SCALING_FACTOR) as f32).max(1.5),
+            ((across * SCALING_FACTOR) as f32).max(1.5),
+        );
+        let alpha = if contact.unseen_days > 0.0 { 0.4 } else { 0.8 };
+        gizmos.ellipse(
+            position,
+            Quat::from_rotation_z(contact.bearing as f32),
+            half_size,
+            Color::srgba(1.0, 0.6, 0.2, alpha),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_contacts_are_tracked_until_identified() {
+        let mut app = App::new();
+        app.init_resource::<EconomyClock>()
+            .init_resource::<SensorContacts>()
+            .init_resource::<SpatialIndex>()
+            .insert_resource(SensorRng(StdRng::seed_from_u64(1)))
+            .add_systems(Update, track_contacts);
+        let earth = app
+            .world_mut()
+            .spawn((
+                Population::new(1e9),
+                SpaceCoordinates {
+                    position: DVec3::new(1.0, 0.0, 0.0),
+                },
+            ))
+            .id();
+        app.world_mut().resource_mut::<SpatialIndex>().insert(
+            earth,
+            DVec3::new(1.0, 0.0, 0.0),
+            0.0,
+            0,
+        );
+        let mars = app
+            .world_mut()
+            .spawn(SpaceCoordinates {
+                position: DVec3::new(1.5, 0.0, 0.0),
+            })
+            .id();
+        let raiders = app
+            .world_mut()
+            .spawn(HostileFleet {
+                name: "raider flotilla".to_string(),
+                faction: None,
+                body: mars,
+                crew: 40,
+                days_left: 1000.0,
+            })
+            .id();
+
+        app.world_mut().resource_mut::<EconomyClock>().due = 1;
+        app.update();
+        let contact = app
+            .world()
+            .resource::<SensorContacts>()
+            .get(raiders)
+            .cloned();
+        let contact = contact.unwrap();
+        assert!(!contact.identified);
+        assert_eq!(contact.label(), "C-1 (unidentified)");
+        assert!((contact.uncertainty_au().0 - 0.5 * RANGE_ERROR_SHARE).abs() < 1e-9);
+        assert!(contact.estimated_offset().length() <= contact.uncertainty_au().0 + 1e-9);
+
+        // Half an AU away, the error falls below the identification limit
+        // after 27 days of tracking
+        app.world_mut().resource_mut::<EconomyClock>().due = 26;
+        app.update();
+        let contacts = app.world().resource::<SensorContacts>();
+        assert!(!contacts.get(raiders).unwrap().identified);
+        app.world_mut().resource_mut::<EconomyClock>().due = 1;
+        app.update();
+        let contact = app
+            .world()
+            .resource::<SensorContacts>()
+            .get(raiders)
+            .cloned();
+        assert_eq!(contact.unwrap().label(), "raider flotilla");
+
+        // Out of range, the contact is lost after a while
+        app.world_mut()
+            .get_mut::<SpaceCoordinates>(mars)
+            .unwrap()
+            .position = DVec3::new(10.0, 0.0, 0.0);
+        app.world_mut().resource_mut::<EconomyClock>().due = LOST_DAYS as u64;
+        app.update();
+        assert!(app.world().resource::<SensorContacts>().contacts.is_empty());
+    }
+}
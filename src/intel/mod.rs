@@ -0,0 +1,45 @@
+//! Intelligence and covert operations
+//!
+//! Provides espionage against the factions of the solar system:
+//! - Agents recruited for credits, each with a skill
+//! - A covert-ops queue of infiltration, technology theft and sabotage, each
+//!   with its own cost, duration and risk
+//! - Agent networks inside factions that later operations rely on
+//! - Counter-intelligence duty against hostile sabotage of construction
+//! - Sensor contacts: objects picked up by colony and fleet sensors,
+//!   tracked until identified
+
+use bevy::prelude::*;
+
+pub mod contacts;
+pub mod systems;
+pub mod types;
+
+pub use contacts::{
+    draw_contacts, fleet_position, nearest_sensor, track_contacts, Sensor, SensorContact,
+    SensorContacts, SensorRng, COLONY_SENSOR_RANGE_AU, FLEET_SENSOR_RANGE_AU,
+};
+pub use systems::{
+    process_intel_actions, run_covert_operations, success_chance, Agent, AgentAssignment,
+    CovertOperation, FactionIntel, Intel, IntelRng, PendingIntelActions, MAX_AGENTS, RECRUIT_COST,
+};
+pub use types::{ContactKind, OperationKind};
+
+/// Plugin that adds agents and covert operations to the Bevy app
+pub struct IntelPlugin;
+
+impl Plugin for IntelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Intel>()
+            .init_resource::<IntelRng>()
+            .init_resource::<PendingIntelActions>()
+            .init_resource::<SensorContacts>()
+            .init_resource::<SensorRng>()
+            .add_systems(
+                FixedUpdate,
+                (run_covert_operations, track_contacts)
+                    .after(crate::economy::advance_economy_clock),
+            )
+            .add_systems(Update, (process_intel_actions, draw_contacts));
+    }
+}
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+
+/// A covert operation agents can run against a faction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum OperationKind {
+    /// Build an agent network inside the faction
+    Infiltrate,
+    /// Steal research data, advancing a research project
+    StealTech,
+    /// Sabotage the faction's shipyards, cutting its freighter capacity
+    Sabotage,
+}
+
+impl OperationKind {
+    pub const ALL: [OperationKind; 3] = [
+        OperationKind::Infiltrate,
+        OperationKind::StealTech,
+        OperationKind::Sabotage,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            OperationKind::Infiltrate => "Infiltrate",
+            OperationKind::StealTech => "Steal Technology",
+            OperationKind::Sabotage => "Sabotage Construction",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            OperationKind::Infiltrate => {
+                "Grows the agent network inside the faction, which later operations rely on"
+            }
+            OperationKind::StealTech => {
+                "Advances a research project by a share of its cost, or adds research points to the pool"
+            }
+            OperationKind::Sabotage => {
+                "Wrecks shipyards under construction, halving the faction's freighter capacity for a while"
+            }
+        }
+    }
+
+    /// Days an agent spends on the operation
+    pub fn days(&self) -> f64 {
+        match self {
+            OperationKind::Infiltrate => 60.0,
+            OperationKind::StealTech => 90.0,
+            OperationKind::Sabotage => 45.0,
+        }
+    }
+
+    /// Credits paid when the operation starts
+    pub fn cost(&self) -> f64 {
+        match self {
+            OperationKind::Infiltrate => 2.0e8,
+            OperationKind::StealTech => 5.0e8,
+            OperationKind::Sabotage => 4.0e8,
+        }
+    }
+
+    /// Chance of failure against a faction without security, before the
+    /// agent's skill and network are counted
+    pub fn base_risk(&self) -> f64 {
+        match self {
+            OperationKind::Infiltrate => 0.2,
+            OperationKind::StealTech => 0.4,
+            OperationKind::Sabotage => 0.5,
+        }
+    }
+
+    /// Network strength (0-1) needed in the faction before it can be run
+    pub fn required_network(&self) -> f64 {
+        match self {
+            OperationKind::Infiltrate => 0.0,
+            OperationKind::StealTech | OperationKind::Sabotage => 0.25,
+        }
+    }
+}
+
+/// What a sensor contact turns out to be once identified
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ContactKind {
+    /// A ship or flotilla under power
+    Ship,
+    /// An unpowered wreck drifting in orbit
+    Derelict,
+    /// A natural object on an odd orbit, like an infalling comet
+    Object,
+}
+
+impl ContactKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ContactKind::Ship => "Ship",
+            ContactKind::Derelict => "Derelict",
+            ContactKind::Object => "Object on an odd orbit",
+        }
+    }
+
+    /// How far sensors see it, as a share of their range
+    pub fn signature(&self) -> f64 {
+        match self {
+            ContactKind::Ship => 1.0,
+            ContactKind::Derelict => 0.4,
+            ContactKind::Object => 1.5,
+        }
+    }
+}
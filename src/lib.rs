@@ -1,7 +1,30 @@
+pub mod ai;
 pub mod astronomy;
+pub mod benchmark;
+pub mod commands;
+pub mod comms;
+pub mod console;
+pub mod construction;
+pub mod contact;
 pub mod economy;
+pub mod fleets;
 pub mod game_state;
+pub mod headless;
+pub mod hotseat;
+pub mod independence;
+pub mod intel;
+pub mod modding;
 pub mod plugins;
+pub mod policies;
+pub mod random_events;
 pub mod render;
+pub mod replay;
 pub mod research;
+pub mod scenarios;
+pub mod scripting;
+pub mod sound;
+pub mod stats;
+pub mod trade;
+pub mod tutorial;
 pub mod ui;
+pub mod victory;
@@ -1,54 +1,193 @@
+use bevy::app::ScheduleRunnerPlugin;
+use bevy::audio::AudioPlugin;
 use bevy::prelude::*;
-use bevy::window::WindowResolution;
+use bevy::render::settings::WgpuSettings;
+use bevy::render::RenderPlugin;
+use bevy::time::TimeUpdateStrategy;
+use bevy::window::{ExitCondition, WindowResolution};
+use bevy::winit::WinitPlugin;
 use bevy_egui::EguiPlugin;
+use std::time::Duration;
 
+pub mod ai;
 pub mod astronomy;
+pub mod benchmark;
+pub mod commands;
+pub mod comms;
+pub mod console;
+pub mod construction;
+pub mod contact;
 pub mod economy;
+pub mod fleets;
 pub mod game_state;
+pub mod headless;
+pub mod hotseat;
+pub mod independence;
+pub mod intel;
+pub mod modding;
 pub mod plugins;
+pub mod policies;
+pub mod random_events;
 pub mod render;
+pub mod replay;
 pub mod research;
+pub mod scenarios;
+pub mod scripting;
+pub mod sound;
+pub mod stats;
+pub mod trade;
+pub mod tutorial;
 pub mod ui;
+pub mod victory;
 
+use ai::AiPlugin;
 use astronomy::AstronomyPlugin;
+use benchmark::BenchmarkPlugin;
+use commands::CommandsPlugin;
+use comms::CommsPlugin;
+use console::ConsolePlugin;
+use construction::ConstructionPlugin;
+use contact::ContactPlugin;
 use economy::EconomyPlugin;
-use game_state::GameStatePlugin;
+use fleets::FleetsPlugin;
+use game_state::{GameSeed, GameStatePlugin};
+use headless::{HeadlessOptions, HeadlessPlugin};
+use hotseat::HotseatPlugin;
+use independence::IndependencePlugin;
+use intel::IntelPlugin;
+use modding::ModdingPlugin;
+use policies::PoliciesPlugin;
+use random_events::RandomEventsPlugin;
+use replay::ReplayPlugin;
 use research::ResearchPlugin;
+use scenarios::ScenariosPlugin;
+use scripting::ScriptingPlugin;
+use sound::SoundPlugin;
+use stats::StatsPlugin;
+use trade::TradePlugin;
+use tutorial::TutorialPlugin;
+use victory::VictoryPlugin;
 use plugins::{
-    camera::CameraPlugin, solar_system::SolarSystemPlugin, starmap::StarmapPlugin,
-    system_populator::SystemPopulatorPlugin, visual_effects::VisualEffectsPlugin,
+    camera::CameraPlugin, capture::CapturePlugin, route_planner::RoutePlannerPlugin,
+    solar_system::SolarSystemPlugin, starmap::StarmapPlugin,
+    system_populator::SystemPopulatorPlugin, system_streaming::SystemStreamingPlugin,
+    visual_effects::VisualEffectsPlugin,
 };
+use render::asteroid_belt::AsteroidBeltRenderPlugin;
 use render::backdrop::BackdropPlugin;
+use render::orbit_lines::OrbitLineRenderPlugin;
+use render::rings::RingRenderPlugin;
 use ui::UIPlugin;
 
 fn main() {
-    App::new()
+    let headless = match HeadlessOptions::from_args(std::env::args()) {
+        Ok(headless) => headless,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
+
+    let mut app = App::new();
+    match &headless {
+        // No window, GPU or audio device; every update advances the fixed
+        // timestep once
+        Some(options) => {
+            app.add_plugins(
+                DefaultPlugins
+                    .set(WindowPlugin {
+                        primary_window: None,
+                        exit_condition: ExitCondition::DontExit,
+                        ..default()
+                    })
+                    .set(RenderPlugin {
+                        render_creation: WgpuSettings {
+                            backends: None,
+                            ..default()
+                        }
+                        .into(),
+                        ..default()
+                    })
+                    .disable::<WinitPlugin>()
+                    .disable::<AudioPlugin>(),
+            )
+            .add_plugins(ScheduleRunnerPlugin::run_loop(Duration::ZERO))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(
+                Time::<Fixed>::default().timestep(),
+            ));
+            if let Some(seed) = options.seed {
+                app.insert_resource(GameSeed::new(seed));
+            }
+        }
         // Bevy default plugins with custom window configuration
-        .add_plugins(DefaultPlugins.set(WindowPlugin {
-            primary_window: Some(Window {
-                title: "Helios Ascension".to_string(),
-                resolution: WindowResolution::new(1920.0, 1080.0),
+        None => {
+            app.add_plugins(DefaultPlugins.set(WindowPlugin {
+                primary_window: Some(Window {
+                    title: "Helios Ascension".to_string(),
+                    resolution: WindowResolution::new(1920.0, 1080.0),
+                    ..default()
+                }),
                 ..default()
-            }),
-            ..default()
-        }))
-        // Debug UI (egui)
-        .add_plugins(EguiPlugin)
+            }));
+        }
+    }
+
+    app
         // Game plugins - Order matters for dependencies
         .add_plugins(GameStatePlugin)
+        // Before the plugins seeding random generators from the game seed
+        .add_plugins(ReplayPlugin)
+        .add_plugins(CommandsPlugin)
+        .add_plugins(HotseatPlugin)
+        .add_plugins(ModdingPlugin)
         .add_plugins(AstronomyPlugin)
         .add_plugins(CameraPlugin)
-        .add_plugins(BackdropPlugin)
-        .add_plugins(VisualEffectsPlugin)
         .add_plugins(SolarSystemPlugin)
         .add_plugins(StarmapPlugin)
+        .add_plugins(RoutePlannerPlugin)
         .add_plugins(EconomyPlugin)
         .add_plugins(ResearchPlugin)
+        .add_plugins(ConstructionPlugin)
+        .add_plugins(FleetsPlugin)
+        .add_plugins(CommsPlugin)
+        .add_plugins(RandomEventsPlugin)
+        .add_plugins(PoliciesPlugin)
+        .add_plugins(TradePlugin)
+        .add_plugins(IntelPlugin)
+        .add_plugins(ContactPlugin)
+        .add_plugins(IndependencePlugin)
+        .add_plugins(AiPlugin)
+        .add_plugins(ScenariosPlugin)
+        .add_plugins(VictoryPlugin)
+        .add_plugins(StatsPlugin)
+        .add_plugins(TutorialPlugin)
         .add_plugins(SystemPopulatorPlugin)
-        .add_plugins(UIPlugin)
+        .add_plugins(SystemStreamingPlugin)
+        .add_plugins(BenchmarkPlugin)
+        .add_plugins(ConsolePlugin)
+        .add_plugins(ScriptingPlugin)
         // Systems
-        .add_systems(Startup, setup)
-        .run();
+        .add_systems(Startup, setup);
+
+    match headless {
+        Some(options) => {
+            app.add_plugins(HeadlessPlugin(options));
+        }
+        // The interface, its egui context and the render-only plugins need a
+        // window; a headless run only simulates
+        None => {
+            app.add_plugins(EguiPlugin)
+                .add_plugins(CapturePlugin)
+                .add_plugins(BackdropPlugin)
+                .add_plugins(VisualEffectsPlugin)
+                .add_plugins(AsteroidBeltRenderPlugin)
+                .add_plugins(RingRenderPlugin)
+                .add_plugins(OrbitLineRenderPlugin)
+                .add_plugins(UIPlugin)
+                .add_plugins(SoundPlugin);
+        }
+    }
+    app.run();
 }
 
 fn setup(mut commands: Commands) {
@@ -0,0 +1,246 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use super::mods::ModList;
+use crate::astronomy::nearby_stars::{
+    read_nearby_stars, unknown_star_references, NearbyStarsData, NEARBY_STARS_PATH,
+};
+use crate::construction::{read_buildings, BuildingsData, BUILDINGS_PATH};
+use crate::game_state::LogSeverity;
+use crate::research::{read_technologies, TechnologiesData, TECHNOLOGIES_PATH};
+use crate::ui::Toasts;
+
+/// Real seconds between checks of the data files
+pub const HOT_RELOAD_INTERVAL_SECONDS: f32 = 1.0;
+
+/// A group of data files reloaded together
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DataSet {
+    Technologies,
+    Buildings,
+    NearbyStars,
+}
+
+impl DataSet {
+    pub const ALL: [DataSet; 3] = [
+        DataSet::Technologies,
+        DataSet::Buildings,
+        DataSet::NearbyStars,
+    ];
+
+    /// Base file and the files of enabled mods, in load order
+    pub fn files(&self, mods: Option<&ModList>) -> Vec<PathBuf> {
+        let (base, moddable) = match self {
+            DataSet::Technologies => (TECHNOLOGIES_PATH, "technologies.ron"),
+            DataSet::Buildings => (BUILDINGS_PATH, "buildings.ron"),
+            DataSet::NearbyStars => (NEARBY_STARS_PATH, "nearest_stars_raw.json"),
+        };
+        let mut files = vec![PathBuf::from(base)];
+        if let Some(mods) = mods {
+            files.extend(mods.data_files(moddable));
+        }
+        files
+    }
+}
+
+/// Modification times of watched files
+#[derive(Debug, Clone, Default)]
+pub struct FileStamps {
+    stamps: HashMap<DataSet, Vec<Option<SystemTime>>>,
+}
+
+impl FileStamps {
+    /// Record the modification times of `files`, returning whether they
+    /// changed since the last check. The first check only records them.
+    pub fn check(&mut self, set: DataSet, files: &[PathBuf]) -> bool {
+        let current: Vec<Option<SystemTime>> = files
+            .iter()
+            .map(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+            .collect();
+        match self.stamps.insert(set, current.clone()) {
+            Some(previous) => previous != current,
+            None => false,
+        }
+    }
+}
+
+/// Watches the data files and reloads them while the game runs
+#[derive(Resource, Debug, Clone)]
+pub struct HotReload {
+    pub enabled: bool,
+    pub timer: Timer,
+    pub stamps: FileStamps,
+}
+
+impl Default for HotReload {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            timer: Timer::from_seconds(HOT_RELOAD_INTERVAL_SECONDS, TimerMode::Repeating),
+            stamps: FileStamps::default(),
+        }
+    }
+}
+
+/// References in `new` that were not already broken in `old`
+fn new_problems(old: Vec<String>, new: Vec<String>) -> Vec<String> {
+    new.into_iter().filter(|p| !old.contains(p)).collect()
+}
+
+/// Report a reload that kept the previous data
+fn reject(toasts: &mut Toasts, what: &str, problems: &[String]) {
+    for problem in problems {
+        error!("{}", problem);
+    }
+    toasts.push(
+        LogSeverity::Critical,
+        format!(
+            "{} not reloaded: {}",
+            what,
+            problems.first().map_or("", String::as_str)
+        ),
+    );
+}
+
+/// Report an applied reload, with references it broke
+fn accept(toasts: &mut Toasts, message: String, problems: &[String]) {
+    info!("{}", message);
+    if problems.is_empty() {
+        toasts.push(LogSeverity::Info, message);
+        return;
+    }
+    for problem in problems {
+        warn!("{}", problem);
+    }
+    toasts.push(
+        LogSeverity::Warning,
+        format!(
+            "{}; {} unknown reference(s), e.g. {}",
+            message,
+            problems.len(),
+            problems[0]
+        ),
+    );
+}
+
+/// System that reloads technologies, buildings and the nearby star catalogue
+/// when their files change on disk. Data that fails to read, parse or
+/// validate is not applied; references to definitions that don't exist are
+/// reported, but only those the edit introduced.
+pub fn hot_reload_data(
+    time: Res<Time<Real>>,
+    mut hot_reload: ResMut<HotReload>,
+    mods: Option<Res<ModList>>,
+    technologies: Option<ResMut<TechnologiesData>>,
+    buildings: Option<ResMut<BuildingsData>>,
+    stars: Option<ResMut<NearbyStarsData>>,
+    mut toasts: ResMut<Toasts>,
+) {
+    if !hot_reload.enabled || !hot_reload.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let (Some(mut technologies), Some(mut buildings), Some(mut stars)) =
+        (technologies, buildings, stars)
+    else {
+        return;
+    };
+
+    let mods = mods.as_deref();
+    let changed: Vec<DataSet> = DataSet::ALL
+        .into_iter()
+        .filter(|set| hot_reload.stamps.check(*set, &set.files(mods)))
+        .collect();
+
+    for set in changed {
+        match set {
+            DataSet::Technologies => {
+                let (data, errors) = read_technologies(mods);
+                if !errors.is_empty() {
+                    reject(&mut toasts, "Technologies", &errors);
+                    continue;
+                }
+                let mut problems =
+                    new_problems(technologies.unknown_references(), data.unknown_references());
+                problems.extend(new_problems(
+                    buildings.unknown_references(&technologies),
+                    buildings.unknown_references(&data),
+                ));
+                let message = format!(
+                    "Reloaded {} technologies and {} components",
+                    data.technologies.len(),
+                    data.components.len()
+                );
+                let version = technologies.version + 1;
+                *technologies = TechnologiesData { version, ..data };
+                accept(&mut toasts, message, &problems);
+            }
+            DataSet::Buildings => {
+                let (data, errors) = read_buildings(mods);
+                if !errors.is_empty() {
+                    reject(&mut toasts, "Buildings", &errors);
+                    continue;
+                }
+                let problems = new_problems(
+                    buildings.unknown_references(&technologies),
+                    data.unknown_references(&technologies),
+                );
+                let message = format!("Reloaded {} buildings", data.order.len());
+                *buildings = data;
+                accept(&mut toasts, message, &problems);
+            }
+            DataSet::NearbyStars => {
+                let (data, errors) = read_nearby_stars(mods);
+                if !errors.is_empty() {
+                    reject(&mut toasts, "Nearby stars", &errors);
+                    continue;
+                }
+                // Out-of-range star indices would break system generation
+                let problems = unknown_star_references(&data);
+                if !problems.is_empty() {
+                    reject(&mut toasts, "Nearby stars", &problems);
+                    continue;
+                }
+                let message = format!("Reloaded {} nearby star systems", data.len());
+                stars.systems = data;
+                accept(&mut toasts, message, &[]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_file_stamps_detect_edits() {
+        let path = std::env::temp_dir().join(format!("helios_hot_reload_{}.ron", std::process::id()));
+        fs::write(&path, "()").unwrap();
+        let files = [path.clone()];
+
+        let mut stamps = FileStamps::default();
+        assert!(!stamps.check(DataSet::Buildings, &files));
+        assert!(!stamps.check(DataSet::Buildings, &files));
+
+        let file = fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(10))
+            .unwrap();
+        assert!(stamps.check(DataSet::Buildings, &files));
+        assert!(!stamps.check(DataSet::Buildings, &files));
+
+        // A deleted file counts as a change too
+        fs::remove_file(&path).unwrap();
+        assert!(stamps.check(DataSet::Buildings, &files));
+    }
+
+    #[test]
+    fn test_only_new_problems_are_reported() {
+        let old = vec!["a".to_string(), "b".to_string()];
+        let new = vec!["b".to_string(), "c".to_string()];
+        assert_eq!(new_problems(old, new), ["c"]);
+    }
+}
@@ -0,0 +1,53 @@
+//! Mod loading
+//!
+//! Mods live in `mods/<id>/` and ship any of the moddable data files
+//! (`technologies.ron`, `buildings.ron`, `solar_system.ron`,
+//! `nearest_stars_raw.json`, `scenarios.ron`, `events.ron`) in the same format as `assets/data`, a
+//! `script.rhai` run by the scripting plugin, plus an optional
+//! `mod.ron` manifest with a name, version, author and description. Data loaders read the base file first
+//! and then every enabled mod in load order:
+//! - Technologies, components, buildings, scenarios and events replace the
+//!   definition with the same id or are added as new ones
+//! - Celestial bodies replace the body with the same name or are added to
+//!   the home system
+//! - Nearby star systems replace the system with the same name or are added
+//!   to the starmap
+//!
+//! The load order and enabled mods are kept in `mods/load_order.ron` and
+//! edited from the mod list; changes take effect on the next start.
+//!
+//! Technologies, buildings (base files and enabled mods alike) and the
+//! nearby star catalogue (base and mods) are hot-reloaded: their files are checked every
+//! second and edits replace the loaded data in place, with a toast telling
+//! whether the new data was applied. Star systems already spawned keep the
+//! data they were generated from.
+
+use bevy::prelude::*;
+
+pub mod hot_reload;
+pub mod mods;
+
+pub use hot_reload::{hot_reload_data, DataSet, HotReload};
+pub use mods::{ModInfo, ModList, MODDABLE_FILES, MODS_DIR};
+
+/// Plugin that adds the installed mod list and data hot-reloading to the
+/// Bevy app
+pub struct ModdingPlugin;
+
+impl Plugin for ModdingPlugin {
+    fn build(&self, app: &mut App) {
+        let mods = ModList::installed();
+        for info in mods.mods.iter().filter(|m| m.enabled) {
+            info!(
+                "Mod enabled: {} {} ({})",
+                info.name,
+                info.version,
+                info.files.join(", ")
+            );
+        }
+        app.insert_resource(mods)
+            .init_resource::<HotReload>()
+            .init_resource::<crate::ui::Toasts>()
+            .add_systems(Update, hot_reload_data);
+    }
+}
@@ -0,0 +1,271 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory scanned for mods, one subdirectory per mod
+pub const MODS_DIR: &str = "mods";
+/// File in [`MODS_DIR`] recording the load order and which mods are enabled
+pub const LOAD_ORDER_FILE: &str = "load_order.ron";
+/// Manifest file inside a mod's directory
+pub const MANIFEST_FILE: &str = "mod.ron";
+/// Data files a mod can add to or override
+pub const MODDABLE_FILES: &[&str] = &[
+    "technologies.ron",
+    "buildings.ron",
+    "solar_system.ron",
+    "nearest_stars_raw.json",
+    "scenarios.ron",
+    "events.ron",
+    "anomalies.ron",
+    "aliens.ron",
+    "doctrines.ron",
+    "script.rhai",
+];
+
+/// Optional `mod.ron` describing a mod
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+struct ModManifest {
+    name: String,
+    version: String,
+    author: String,
+    description: String,
+}
+
+/// One entry of the load order file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LoadOrderEntry {
+    id: String,
+    enabled: bool,
+}
+
+/// Structure of the load order file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct LoadOrderFile {
+    mods: Vec<LoadOrderEntry>,
+}
+
+/// A mod found in the mods directory
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModInfo {
+    /// Name of the mod's directory
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub author: String,
+    pub description: String,
+    pub path: PathBuf,
+    pub enabled: bool,
+    /// Moddable data files the mod ships
+    pub files: Vec<&'static str>,
+}
+
+/// Installed mods in load order. Later mods override earlier ones, and all
+/// of them override the base game data.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ModList {
+    pub mods: Vec<ModInfo>,
+    /// Whether the load order was edited since the data was loaded
+    pub changed: bool,
+}
+
+impl ModList {
+    /// Scan `dir` for mods. Mods named in the load order file keep their
+    /// position and enabled state; new mods are appended alphabetically and
+    /// start enabled.
+    pub fn discover(dir: &Path) -> Self {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Self::default();
+        };
+        let mut found: Vec<ModInfo> = entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .filter_map(|path| read_mod(&path))
+            .collect();
+        found.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let order = match fs::read_to_string(dir.join(LOAD_ORDER_FILE)) {
+            Ok(contents) => ron::from_str::<LoadOrderFile>(&contents).unwrap_or_else(|e| {
+                warn!("Invalid mod load order in {}: {}", dir.display(), e);
+                LoadOrderFile::default()
+            }),
+            Err(_) => LoadOrderFile::default(),
+        };
+
+        let mut mods = Vec::with_capacity(found.len());
+        for entry in order.mods {
+            if let Some(index) = found.iter().position(|m| m.id == entry.id) {
+                let mut info = found.remove(index);
+                info.enabled = entry.enabled;
+                mods.push(info);
+            }
+        }
+        mods.extend(found);
+
+        Self {
+            mods,
+            changed: false,
+        }
+    }
+
+    /// Load the mods installed in [`MODS_DIR`]
+    pub fn installed() -> Self {
+        Self::discover(Path::new(MODS_DIR))
+    }
+
+    /// Paths of `file` in the enabled mods, in load order. Data loaders
+    /// apply these on top of the base file in `assets/data`.
+    pub fn data_files(&self, file: &str) -> Vec<PathBuf> {
+        self.mods
+            .iter()
+            .filter(|m| m.enabled && m.files.contains(&file))
+            .map(|m| m.path.join(file))
+            .collect()
+    }
+
+    /// Swap the mod at `index` with the one before it
+    pub fn move_up(&mut self, index: usize) {
+        if index > 0 && index < self.mods.len() {
+            self.mods.swap(index - 1, index);
+            self.changed = true;
+        }
+    }
+
+    /// Swap the mod at `index` with the one after it
+    pub fn move_down(&mut self, index: usize) {
+        if index + 1 < self.mods.len() {
+            self.mods.swap(index, index + 1);
+            self.changed = true;
+        }
+    }
+
+    /// Write the load order and enabled state to `dir`
+    pub fn save_load_order(&self, dir: &Path) {
+        let file = LoadOrderFile {
+            mods: self
+                .mods
+                .iter()
+                .map(|m| LoadOrderEntry {
+                    id: m.id.clone(),
+                    enabled: m.enabled,
+                })
+                .collect(),
+        };
+        let path = dir.join(LOAD_ORDER_FILE);
+        match ron::ser::to_string_pretty(&file, ron::ser::PrettyConfig::new()) {
+            Ok(contents) => match fs::write(&path, contents) {
+                Ok(()) => info!("Saved mod load order to {}", path.display()),
+                Err(e) => error!("Failed to write mod load order: {}", e),
+            },
+            Err(e) => error!("Failed to serialize mod load order: {}", e),
+        }
+    }
+}
+
+/// Read the manifest and data files of the mod in `path`. Directories
+/// without any moddable file are not mods.
+fn read_mod(path: &Path) -> Option<ModInfo> {
+    let id = path.file_name()?.to_string_lossy().to_string();
+    let files: Vec<&'static str> = MODDABLE_FILES
+        .iter()
+        .copied()
+        .filter(|file| path.join(file).is_file())
+        .collect();
+    if files.is_empty() {
+        return None;
+    }
+
+    let manifest = match fs::read_to_string(path.join(MANIFEST_FILE)) {
+        Ok(contents) => ron::from_str::<ModManifest>(&contents).unwrap_or_else(|e| {
+            warn!("Invalid manifest for mod '{}': {}", id, e);
+            ModManifest::default()
+        }),
+        Err(_) => ModManifest::default(),
+    };
+
+    let name = if manifest.name.is_empty() {
+        id.clone()
+    } else {
+        manifest.name
+    };
+    Some(ModInfo {
+        id,
+        name,
+        version: manifest.version,
+        author: manifest.author,
+        description: manifest.description,
+        path: path.to_path_buf(),
+        enabled: true,
+        files,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("helios_mods_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write(path: PathBuf, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn test_load_order_and_enabled_state_are_kept() {
+        let dir = scratch_dir("order");
+        write(dir.join("alpha/buildings.ron"), "(buildings: [])");
+        write(dir.join("beta/technologies.ron"), "(technologies: [])");
+        write(dir.join("beta/buildings.ron"), "(buildings: [])");
+        write(dir.join("gamma/buildings.ron"), "(buildings: [])");
+        write(
+            dir.join("beta/mod.ron"),
+            r#"(name: "Beta Tweaks", version: "1.2")"#,
+        );
+        // Not a mod: no data files
+        fs::create_dir_all(dir.join("notes")).unwrap();
+        write(
+            dir.join(LOAD_ORDER_FILE),
+            r#"(mods: [(id: "gamma", enabled: false), (id: "beta", enabled: true), (id: "gone", enabled: true)])"#,
+        );
+
+        let list = ModList::discover(&dir);
+        let ids: Vec<&str> = list.mods.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, ["gamma", "beta", "alpha"]);
+        assert_eq!(list.mods[1].name, "Beta Tweaks");
+        assert_eq!(list.mods[2].name, "alpha");
+
+        assert_eq!(
+            list.data_files("buildings.ron"),
+            [dir.join("beta/buildings.ron"), dir.join("alpha/buildings.ron")]
+        );
+        assert_eq!(
+            list.data_files("technologies.ron"),
+            [dir.join("beta/technologies.ron")]
+        );
+
+        let mut list = list;
+        list.move_down(1);
+        list.mods[0].enabled = true;
+        list.save_load_order(&dir);
+        let reloaded = ModList::discover(&dir);
+        let ids: Vec<&str> = reloaded.mods.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, ["gamma", "alpha", "beta"]);
+        assert!(reloaded.mods.iter().all(|m| m.enabled));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_missing_mods_dir_is_empty() {
+        let list = ModList::discover(Path::new("does/not/exist"));
+        assert!(list.mods.is_empty());
+    }
+}
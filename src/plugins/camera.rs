@@ -1,10 +1,13 @@
 use bevy::input::mouse::{MouseMotion, MouseWheel};
 use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
 use bevy_egui::EguiContexts;
+use std::f32::consts::{PI, TAU};
 
 use crate::astronomy::components::CurrentStarSystem;
 use crate::astronomy::SCALING_FACTOR;
 use crate::game_state::{ActiveMenu, GameMenu};
+use crate::plugins::solar_system::{CelestialBody, LogicalParent};
 use crate::plugins::starmap::SystemMetadata;
 
 /// Base zoom threshold multiplier. The actual threshold is calculated as
@@ -17,6 +20,24 @@ pub const STARMAP_THRESHOLD_MULTIPLIER: f32 = 0.6;
 /// Minimum zoom threshold in game units to ensure reasonable behavior for very small systems.
 pub const MIN_STARMAP_THRESHOLD: f32 = 50_000.0;
 
+/// Number of camera bookmarks, bound to the keys 1–9
+pub const BOOKMARK_SLOTS: usize = 9;
+
+/// Seconds the cinematic camera takes to fly from one body to the next
+pub const TOUR_TRAVEL_SECONDS: f32 = 4.0;
+
+/// Seconds the cinematic camera circles each body before moving on
+pub const TOUR_HOLD_SECONDS: f32 = 6.0;
+
+/// Yaw rate of the cinematic camera while circling a body, in rad/s
+pub const TOUR_ORBIT_SPEED: f32 = 0.08;
+
+/// Seconds the look-at point takes to glide to a newly anchored body
+pub const ANCHOR_TRANSITION_SECONDS: f32 = 0.8;
+
+/// Camera distance to a toured body as a multiple of its visual radius
+const TOUR_VIEW_DISTANCE: f32 = 20.0;
+
 /// The active view mode, driven by camera zoom level.
 ///
 /// - `System` — normal solar-system view with orbits, planets, moons.
@@ -33,13 +54,25 @@ pub struct CameraPlugin;
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<ViewMode>()
+            .init_resource::<CameraFrame>()
+            .init_resource::<CameraBookmarks>()
+            .init_resource::<CinematicMode>()
             .add_systems(Startup, spawn_camera)
             .add_systems(
                 Update,
                 (
                     orbit_camera_controls
                         // Run AFTER egui has processed input to respect UI interaction
-                        .after(bevy_egui::EguiSet::ProcessInput),
+                        .after(bevy_egui::EguiSet::ProcessInput)
+                        // egui and the input it reads need a window
+                        .run_if(any_with_component::<PrimaryWindow>),
+                    (
+                        camera_bookmark_keys.run_if(any_with_component::<PrimaryWindow>),
+                        run_cinematic_tour,
+                    )
+                        .chain()
+                        .after(orbit_camera_controls)
+                        .before(update_camera_transform),
                     update_camera_transform,
                     update_view_mode,
                 ),
@@ -53,6 +86,45 @@ pub struct GameCamera;
 #[derive(Component)]
 pub struct CameraAnchor(pub Option<Entity>);
 
+/// Reference frame the camera's orientation is kept in while anchored
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CameraFrame {
+    /// Fixed against the stars: the anchored body's moons and the star
+    /// sweep around as it orbits
+    #[default]
+    Inertial,
+    /// Turning with the anchored body's orbit around its parent, so the
+    /// parent stays in the same direction on screen
+    CoRotating,
+}
+
+impl CameraFrame {
+    pub const ALL: [CameraFrame; 2] = [CameraFrame::Inertial, CameraFrame::CoRotating];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CameraFrame::Inertial => "Inertial",
+            CameraFrame::CoRotating => "Co-rotating with orbit",
+        }
+    }
+}
+
+/// How the camera follows its anchor: the anchor it last followed, the
+/// glide to a new anchor and the anchor's last orbital heading
+#[derive(Component, Debug, Clone, Default)]
+pub struct AnchorTracking {
+    pub anchor: Option<Entity>,
+    /// Offset of the look-at point from the new anchor when the glide
+    /// started, and the seconds since. Kept relative to the anchor so a
+    /// floating-origin rebase does not disturb it.
+    pub transition: Option<(Vec3, f32)>,
+    /// Direction of the anchor from its parent in the orbital plane
+    pub heading: Option<f32>,
+    /// Angle the co-rotating frame has turned the camera about the orbital
+    /// plane's normal (Z)
+    pub turn: f32,
+}
+
 #[derive(Component)]
 pub struct OrbitCamera {
     pub radius: f32,
@@ -80,6 +152,110 @@ impl Default for OrbitCamera {
     }
 }
 
+/// A saved camera view: what it is anchored to, where it looks from and how
+/// far out it is
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraPose {
+    pub anchor: Option<Entity>,
+    pub target_center: Vec3,
+    pub radius: f32,
+    pub pitch: f32,
+    pub yaw: f32,
+}
+
+impl CameraPose {
+    pub fn capture(orbit: &OrbitCamera, anchor: &CameraAnchor) -> Self {
+        Self {
+            anchor: anchor.0,
+            target_center: orbit.target_center,
+            radius: orbit.radius,
+            pitch: orbit.pitch,
+            yaw: orbit.yaw,
+        }
+    }
+
+    /// Move the camera to the pose. The anchor is set without triggering
+    /// change detection so the automatic zoom to a newly anchored body does
+    /// not override the saved distance.
+    pub fn apply(&self, orbit: &mut OrbitCamera, anchor: &mut Mut<CameraAnchor>) {
+        orbit.target_center = self.target_center;
+        orbit.radius = self.radius.clamp(orbit.min_radius, orbit.max_radius);
+        orbit.pitch = self.pitch;
+        orbit.yaw = self.yaw;
+        anchor.bypass_change_detection().0 = self.anchor;
+    }
+}
+
+/// Saved camera views, bound to the number keys: Ctrl+1–9 saves the current
+/// view, 1–9 returns to it
+#[derive(Resource, Debug, Clone, Default)]
+pub struct CameraBookmarks {
+    pub slots: [Option<CameraPose>; BOOKMARK_SLOTS],
+}
+
+/// Cinematic mode: a camera tour of picked bodies with the UI hidden, for
+/// screenshots and videos. The camera eases from body to body, circles each
+/// for a while and loops until stopped with Escape.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct CinematicMode {
+    pub active: bool,
+    /// Bodies visited, in order
+    pub stops: Vec<Entity>,
+    /// Index of the body being flown to or circled
+    pub stop: usize,
+    /// Seconds since the camera left the previous stop
+    pub elapsed: f32,
+    /// Where the current flight started (look-at point and distance)
+    pub departure: Option<(Vec3, f32)>,
+    /// The view before the tour, restored when it ends
+    pub resume: Option<CameraPose>,
+}
+
+impl CinematicMode {
+    /// Whether the interface is hidden
+    pub fn hides_ui(&self) -> bool {
+        self.active
+    }
+
+    /// Start touring the stops from `pose`, if there are any
+    pub fn start(&mut self, pose: CameraPose) {
+        if self.stops.is_empty() {
+            return;
+        }
+        self.active = true;
+        self.stop = 0;
+        self.elapsed = 0.0;
+        self.departure = Some((pose.target_center, pose.radius));
+        self.resume = Some(pose);
+    }
+
+    /// End the tour, returning the view to restore
+    pub fn end(&mut self) -> Option<CameraPose> {
+        self.active = false;
+        self.departure = None;
+        self.resume.take()
+    }
+
+    /// Move on to the next stop, looping back to the first, leaving from
+    /// `center` at `radius`
+    pub fn next_stop(&mut self, center: Vec3, radius: f32) {
+        self.stop = (self.stop + 1) % self.stops.len().max(1);
+        self.elapsed = 0.0;
+        self.departure = Some((center, radius));
+    }
+
+    /// Eased progress of the flight to the current stop, 0..=1
+    pub fn travel_progress(&self) -> f32 {
+        ease_in_out(self.elapsed / TOUR_TRAVEL_SECONDS)
+    }
+}
+
+/// Smoothstep easing: slow start, slow arrival
+pub fn ease_in_out(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
 fn spawn_camera(mut commands: Commands) {
     commands.spawn((
         Camera3dBundle {
@@ -92,6 +268,7 @@ fn spawn_camera(mut commands: Commands) {
         },
         GameCamera,
         CameraAnchor(None),
+        AnchorTracking::default(),
         OrbitCamera::default(),
     ));
 }
@@ -99,6 +276,7 @@ fn spawn_camera(mut commands: Commands) {
 fn orbit_camera_controls(
     mut contexts: EguiContexts,
     active_menu: Res<ActiveMenu>,
+    cinematic: Res<CinematicMode>,
     mouse: Res<ButtonInput<MouseButton>>,
     mut motion_events: EventReader<MouseMotion>,
     mut scroll_events: EventReader<MouseWheel>,
@@ -107,7 +285,8 @@ fn orbit_camera_controls(
     let mut camera = query.single_mut();
 
     // Block camera control when in full-screen UI modes (i.e. menus that block world interaction)
-    if active_menu.current.blocks_world_interaction() {
+    // and while the cinematic camera is flying
+    if active_menu.current.blocks_world_interaction() || cinematic.active {
         motion_events.clear();
         scroll_events.clear();
         return;
@@ -148,27 +327,182 @@ fn orbit_camera_controls(
     }
 }
 
+/// Save (Ctrl+1–9) and recall (1–9) camera bookmarks
+fn camera_bookmark_keys(
+    mut contexts: EguiContexts,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    cinematic: Res<CinematicMode>,
+    mut bookmarks: ResMut<CameraBookmarks>,
+    mut camera_query: Query<(&mut OrbitCamera, &mut CameraAnchor), With<GameCamera>>,
+) {
+    const KEYS: [KeyCode; BOOKMARK_SLOTS] = [
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+        KeyCode::Digit5,
+        KeyCode::Digit6,
+        KeyCode::Digit7,
+        KeyCode::Digit8,
+        KeyCode::Digit9,
+    ];
+
+    if cinematic.active {
+        return;
+    }
+    // Digits typed into text fields are not bookmark keys
+    if contexts
+        .try_ctx_mut()
+        .is_some_and(|ctx| ctx.wants_keyboard_input())
+    {
+        return;
+    }
+    let Some(slot) = KEYS.iter().position(|key| keyboard.just_pressed(*key)) else {
+        return;
+    };
+    let Ok((mut orbit, mut anchor)) = camera_query.get_single_mut() else {
+        return;
+    };
+
+    if keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        bookmarks.slots[slot] = Some(CameraPose::capture(&orbit, &anchor));
+        info!("Camera bookmark {} saved", slot + 1);
+    } else if let Some(pose) = bookmarks.slots[slot] {
+        pose.apply(&mut orbit, &mut anchor);
+    }
+}
+
+/// Fly the cinematic camera along its stops. Escape ends the tour and
+/// restores the view it started from.
+fn run_cinematic_tour(
+    time: Res<Time<Real>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut cinematic: ResMut<CinematicMode>,
+    mut camera_query: Query<(&mut OrbitCamera, &mut CameraAnchor), With<GameCamera>>,
+    bodies: Query<(&GlobalTransform, &CelestialBody)>,
+) {
+    if !cinematic.active {
+        return;
+    }
+    let Ok((mut orbit, mut anchor)) = camera_query.get_single_mut() else {
+        return;
+    };
+
+    if keyboard.just_pressed(KeyCode::Escape) || cinematic.stops.is_empty() {
+        if let Some(pose) = cinematic.end() {
+            pose.apply(&mut orbit, &mut anchor);
+        }
+        return;
+    }
+
+    let target = cinematic.stops[cinematic.stop];
+    let Ok((transform, body)) = bodies.get(target) else {
+        // The body is gone; drop it from the tour
+        let stop = cinematic.stop;
+        cinematic.stops.remove(stop);
+        cinematic.stop = stop.min(cinematic.stops.len().saturating_sub(1));
+        return;
+    };
+    let center = transform.translation();
+    let radius = (body.visual_radius * TOUR_VIEW_DISTANCE).clamp(orbit.min_radius, 50_000.0);
+
+    let dt = time.delta_seconds();
+    cinematic.elapsed += dt;
+    orbit.yaw += TOUR_ORBIT_SPEED * dt;
+
+    if cinematic.elapsed < TOUR_TRAVEL_SECONDS {
+        // Fly freely, interpolating the distance logarithmically so the
+        // approach does not rush in from far out
+        let (from_center, from_radius) = cinematic.departure.unwrap_or((center, radius));
+        let t = cinematic.travel_progress();
+        anchor.bypass_change_detection().0 = None;
+        orbit.target_center = from_center.lerp(center, t);
+        orbit.radius = (from_radius.ln() + (radius.ln() - from_radius.ln()) * t).exp();
+    } else {
+        anchor.bypass_change_detection().0 = Some(target);
+        orbit.radius = radius;
+        if cinematic.elapsed >= TOUR_TRAVEL_SECONDS + TOUR_HOLD_SECONDS {
+            cinematic.next_stop(center, radius);
+        }
+    }
+}
+
+/// Heading of `body` around `parent` in the orbital (XY) plane,
+/// counterclockwise about Z from the X axis
+pub fn orbital_heading(body: Vec3, parent: Vec3) -> Option<f32> {
+    let offset = body - parent;
+    (offset.x != 0.0 || offset.y != 0.0).then(|| offset.y.atan2(offset.x))
+}
+
+/// Wrap an angle difference into -π..π
+pub fn wrap_angle(angle: f32) -> f32 {
+    (angle + PI).rem_euclid(TAU) - PI
+}
+
 fn update_camera_transform(
-    mut camera_query: Query<(&mut Transform, &mut OrbitCamera, &CameraAnchor)>,
-    target_query: Query<&GlobalTransform, Without<GameCamera>>,
+    time: Res<Time<Real>>,
+    frame: Res<CameraFrame>,
+    mut camera_query: Query<(
+        &mut Transform,
+        &mut OrbitCamera,
+        &CameraAnchor,
+        &mut AnchorTracking,
+    )>,
+    target_query: Query<(&GlobalTransform, Option<&LogicalParent>), Without<GameCamera>>,
 ) {
-    let (mut transform, mut orbit, anchor) = camera_query.single_mut();
+    let (mut transform, mut orbit, anchor, mut tracking) = camera_query.single_mut();
+
+    // A new anchor: glide over from the current look-at point
+    if tracking.anchor != anchor.0 {
+        tracking.anchor = anchor.0;
+        tracking.heading = None;
+        tracking.transition = anchor
+            .0
+            .and_then(|entity| target_query.get(entity).ok())
+            .map(|(target, _)| (orbit.target_center - target.translation(), 0.0));
+    }
 
     // Update target center if anchored
     if let Some(entity) = anchor.0 {
-        if let Ok(target_transform) = target_query.get(entity) {
-            orbit.target_center = target_transform.translation();
+        if let Ok((target_transform, parent)) = target_query.get(entity) {
+            let position = target_transform.translation();
+
+            // Turn with the body's orbit so its parent stays put on screen
+            let heading = match *frame {
+                CameraFrame::CoRotating => parent
+                    .and_then(|p| target_query.get(p.0).ok())
+                    .and_then(|(parent, _)| orbital_heading(position, parent.translation())),
+                CameraFrame::Inertial => None,
+            };
+            if let (Some(heading), Some(previous)) = (heading, tracking.heading) {
+                tracking.turn += wrap_angle(heading - previous);
+            }
+            tracking.heading = heading;
+
+            let mut offset = Vec3::ZERO;
+            if let Some((start, elapsed)) = tracking.transition {
+                let elapsed = elapsed + time.delta_seconds();
+                if elapsed < ANCHOR_TRANSITION_SECONDS {
+                    offset = start * (1.0 - ease_in_out(elapsed / ANCHOR_TRANSITION_SECONDS));
+                    tracking.transition = Some((start, elapsed));
+                } else {
+                    tracking.transition = None;
+                }
+            }
+            orbit.target_center = position + offset;
         }
     }
 
-    // Calculate camera position
-    let rot =
-        Quat::from_axis_angle(Vec3::Y, orbit.yaw) * Quat::from_axis_angle(Vec3::X, orbit.pitch);
+    // Calculate camera position, turned with the orbit about its normal
+    let turn = Quat::from_rotation_z(tracking.turn);
+    let rot = turn
+        * Quat::from_axis_angle(Vec3::Y, orbit.yaw)
+        * Quat::from_axis_angle(Vec3::X, orbit.pitch);
     let offset = rot * Vec3::Z * orbit.radius;
     let position = orbit.target_center + offset;
 
     transform.translation = position;
-    transform.look_at(orbit.target_center, Vec3::Y);
+    transform.look_at(orbit.target_center, turn * Vec3::Y);
 }
 
 /// Updates `ViewMode` based on camera zoom radius, with hysteresis to avoid
@@ -211,3 +545,61 @@ fn update_view_mode(
         *view_mode = new_mode;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ease_in_out() {
+        assert_eq!(ease_in_out(-1.0), 0.0);
+        assert_eq!(ease_in_out(0.5), 0.5);
+        assert_eq!(ease_in_out(2.0), 1.0);
+        // Slow at both ends
+        assert!(ease_in_out(0.1) < 0.1);
+        assert!(ease_in_out(0.9) > 0.9);
+    }
+
+    #[test]
+    fn test_co_rotation_follows_orbital_heading() {
+        let sun = Vec3::new(10.0, 10.0, 0.0);
+        assert_eq!(orbital_heading(sun + Vec3::X, sun), Some(0.0));
+        let quarter = orbital_heading(sun + Vec3::Y * 5.0, sun).unwrap();
+        assert!((quarter - PI / 2.0).abs() < 1e-6);
+        // Straight above the orbital plane there is no heading
+        assert_eq!(orbital_heading(sun + Vec3::Z, sun), None);
+
+        // Crossing the ±π seam turns the camera the short way round
+        let step = wrap_angle(-3.1 - 3.1);
+        assert!((step - (TAU - 6.2)).abs() < 1e-5);
+        assert!((wrap_angle(0.25) - 0.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_tour_loops_and_restores_view() {
+        let pose = CameraPose {
+            anchor: None,
+            target_center: Vec3::ZERO,
+            radius: 2000.0,
+            pitch: 0.5,
+            yaw: 0.0,
+        };
+        let mut cinematic = CinematicMode::default();
+        cinematic.start(pose);
+        assert!(!cinematic.active, "a tour without stops does not start");
+
+        cinematic.stops = vec![Entity::from_raw(1), Entity::from_raw(2)];
+        cinematic.start(pose);
+        assert!(cinematic.hides_ui());
+        assert_eq!(cinematic.departure, Some((Vec3::ZERO, 2000.0)));
+
+        cinematic.next_stop(Vec3::X, 50.0);
+        assert_eq!(cinematic.stop, 1);
+        cinematic.next_stop(Vec3::Y, 80.0);
+        assert_eq!(cinematic.stop, 0);
+        assert_eq!(cinematic.departure, Some((Vec3::Y, 80.0)));
+
+        assert_eq!(cinematic.end(), Some(pose));
+        assert!(!cinematic.hides_ui());
+    }
+}
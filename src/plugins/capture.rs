@@ -0,0 +1,259 @@
+//! Screenshot and timelapse capture
+//!
+//! F11 saves a screenshot of the window to `screenshots/`. Shift+F11 toggles
+//! the timelapse: while it runs, every in-game month renders one frame from a
+//! fixed camera looking down on the current star system and saves it to a
+//! numbered image sequence under `timelapse/`, ready to be assembled into a
+//! video (e.g. `ffmpeg -i frame_%05d.png`).
+
+use bevy::prelude::*;
+use bevy::render::view::screenshot::ScreenshotManager;
+use bevy::transform::TransformSystem;
+use bevy::window::PrimaryWindow;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::astronomy::components::{CurrentStarSystem, SystemId};
+use crate::astronomy::SCALING_FACTOR;
+use crate::economy::EconomyClock;
+use crate::game_state::LogSeverity;
+use crate::plugins::camera::GameCamera;
+use crate::plugins::solar_system::Star;
+use crate::stats::STATS_INTERVAL_TICKS;
+use crate::ui::Toasts;
+
+/// Directory screenshots are saved to, next to the executable
+pub const SCREENSHOT_DIR: &str = "screenshots";
+
+/// Directory holding one image sequence per timelapse
+pub const TIMELAPSE_DIR: &str = "timelapse";
+
+/// Default height of the timelapse camera above the star, in AU
+pub const TIMELAPSE_HEIGHT_AU: f32 = 15.0;
+
+/// State of the monthly timelapse
+#[derive(Resource, Debug, Clone)]
+pub struct Timelapse {
+    pub enabled: bool,
+    /// Camera height above the star in AU
+    pub height_au: f32,
+    /// Directory of the running sequence
+    pub directory: PathBuf,
+    /// Frames saved to the running sequence
+    pub frames: u32,
+    /// Month the last frame was taken in
+    pub last_month: Option<u64>,
+    /// A frame is rendered from the fixed camera this update
+    pub capturing: bool,
+}
+
+impl Default for Timelapse {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            height_au: TIMELAPSE_HEIGHT_AU,
+            directory: PathBuf::from(TIMELAPSE_DIR),
+            frames: 0,
+            last_month: None,
+            capturing: false,
+        }
+    }
+}
+
+impl Timelapse {
+    /// Start a new sequence in its own directory, named after `stamp`
+    pub fn start(&mut self, stamp: u64) {
+        self.enabled = true;
+        self.directory = Path::new(TIMELAPSE_DIR).join(format!("timelapse_{}", stamp));
+        self.frames = 0;
+        self.last_month = None;
+    }
+
+    /// Whether a frame is due with the economy clock at `processed` ticks:
+    /// once per month, skipping months passed within the same update
+    pub fn frame_due(&mut self, processed: u64) -> bool {
+        let month = processed / STATS_INTERVAL_TICKS;
+        let due = self.last_month.is_some_and(|last| last != month);
+        self.last_month = Some(month);
+        due
+    }
+
+    /// Path of the next frame of the sequence
+    pub fn next_frame_path(&self) -> PathBuf {
+        self.directory.join(format!("frame_{:05}.png", self.frames))
+    }
+}
+
+/// Camera transform looking straight down on `center` from `height` units
+/// above the orbital (XY) plane
+pub fn top_down_transform(center: Vec3, height: f32) -> Transform {
+    // Looking along -Z, so "up" on screen is +Y
+    Transform::from_translation(center + Vec3::Z * height).looking_at(center, Vec3::Y)
+}
+
+/// Seconds since the Unix epoch, to keep file names from separate sessions apart
+fn unix_stamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Save the window to `path`, creating its directory
+fn save_window(
+    screenshots: &mut ScreenshotManager,
+    window: Entity,
+    path: &Path,
+) -> Result<(), String> {
+    if let Some(directory) = path.parent() {
+        std::fs::create_dir_all(directory).map_err(|e| e.to_string())?;
+    }
+    screenshots
+        .save_screenshot_to_disk(window, path)
+        .map_err(|e| e.to_string())
+}
+
+/// Handle the capture keys: F11 for a screenshot, Shift+F11 for the timelapse
+fn capture_keys(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut timelapse: ResMut<Timelapse>,
+    mut screenshots: ResMut<ScreenshotManager>,
+    window: Query<Entity, With<PrimaryWindow>>,
+    mut toasts: Option<ResMut<Toasts>>,
+    mut counter: Local<u32>,
+) {
+    if !keyboard.just_pressed(KeyCode::F11) {
+        return;
+    }
+    let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+
+    let message = if shift {
+        if timelapse.enabled {
+            timelapse.enabled = false;
+            format!(
+                "🎞 Timelapse stopped: {} frames in {}",
+                timelapse.frames,
+                timelapse.directory.display()
+            )
+        } else {
+            timelapse.start(unix_stamp());
+            format!(
+                "🎞 Timelapse started: a frame every month in {}",
+                timelapse.directory.display()
+            )
+        }
+    } else {
+        let Ok(window) = window.get_single() else {
+            return;
+        };
+        let path = Path::new(SCREENSHOT_DIR)
+            .join(format!("screenshot_{}_{}.png", unix_stamp(), *counter));
+        *counter += 1;
+        match save_window(&mut screenshots, window, &path) {
+            Ok(()) => format!("📷 Screenshot saved to {}", path.display()),
+            Err(e) => {
+                warn!("Screenshot failed: {}", e);
+                format!("📷 Screenshot failed: {}", e)
+            }
+        }
+    };
+
+    info!("{}", message);
+    if let Some(toasts) = toasts.as_mut() {
+        toasts.push(LogSeverity::Info, message);
+    }
+}
+
+/// Request a timelapse frame whenever the economy clock enters a new month
+fn schedule_timelapse_frame(
+    clock: Res<EconomyClock>,
+    mut timelapse: ResMut<Timelapse>,
+    mut screenshots: ResMut<ScreenshotManager>,
+    window: Query<Entity, With<PrimaryWindow>>,
+) {
+    timelapse.capturing = false;
+    if !timelapse.enabled || !timelapse.frame_due(clock.processed) {
+        return;
+    }
+    let Ok(window) = window.get_single() else {
+        return;
+    };
+
+    let path = timelapse.next_frame_path();
+    match save_window(&mut screenshots, window, &path) {
+        Ok(()) => {
+            timelapse.frames += 1;
+            timelapse.capturing = true;
+        }
+        Err(e) => warn!("Timelapse frame failed: {}", e),
+    }
+}
+
+/// Move the game camera to the fixed timelapse view for the frame being
+/// captured. The orbit camera recomputes its transform on the next update,
+/// so the player's view is back one frame later.
+fn place_timelapse_camera(
+    timelapse: Res<Timelapse>,
+    current_system: Res<CurrentStarSystem>,
+    stars: Query<(&GlobalTransform, Option<&SystemId>), With<Star>>,
+    mut camera: Query<&mut Transform, With<GameCamera>>,
+) {
+    if !timelapse.capturing {
+        return;
+    }
+    let center = stars
+        .iter()
+        .find(|(_, system)| system.map_or(0, |s| s.0) == current_system.0)
+        .map_or(Vec3::ZERO, |(transform, _)| transform.translation());
+    let height = (timelapse.height_au as f64 * SCALING_FACTOR) as f32;
+
+    for mut transform in camera.iter_mut() {
+        *transform = top_down_transform(center, height);
+    }
+}
+
+/// Plugin that adds screenshots and the timelapse to the Bevy app
+pub struct CapturePlugin;
+
+impl Plugin for CapturePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Timelapse>()
+            .add_systems(Update, (capture_keys, schedule_timelapse_frame).chain())
+            .add_systems(
+                PostUpdate,
+                place_timelapse_camera.before(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timelapse_takes_a_frame_per_month() {
+        let mut timelapse = Timelapse::default();
+        timelapse.start(1_700_000_000);
+        assert_eq!(
+            timelapse.next_frame_path(),
+            Path::new("timelapse/timelapse_1700000000/frame_00000.png")
+        );
+
+        // The month the timelapse starts in is not captured
+        assert!(!timelapse.frame_due(10));
+        assert!(!timelapse.frame_due(29));
+        assert!(timelapse.frame_due(30));
+        assert!(!timelapse.frame_due(45));
+        // Several months passed within one update give a single frame
+        assert!(timelapse.frame_due(200));
+        assert!(!timelapse.frame_due(200));
+    }
+
+    #[test]
+    fn test_top_down_transform_looks_down() {
+        let center = Vec3::new(100.0, -50.0, 0.0);
+        let transform = top_down_transform(center, 1000.0);
+        assert_eq!(transform.translation, Vec3::new(100.0, -50.0, 1000.0));
+        assert!(transform.forward().dot(Vec3::NEG_Z) > 0.999);
+        assert!(transform.up().dot(Vec3::Y) > 0.999);
+    }
+}
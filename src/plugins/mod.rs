@@ -1,6 +1,9 @@
 pub mod camera;
+pub mod capture;
+pub mod route_planner;
 pub mod solar_system;
 pub mod solar_system_data;
 pub mod starmap;
 pub mod system_populator;
+pub mod system_streaming;
 pub mod visual_effects;
@@ -0,0 +1,341 @@
+//! Starmap route planning
+//!
+//! Plots multi-hop routes between star systems for a drive with a limited
+//! range per jump, and draws the route together with reachability rings
+//! around the origin system on the starmap.
+
+use std::collections::HashMap;
+
+use bevy::math::{DVec3, I64Vec3};
+use bevy::prelude::*;
+
+use super::camera::ViewMode;
+use super::starmap::{SelectedStarSystem, StarSystemIcon, LY_TO_AU};
+use crate::research::ResearchState;
+
+/// Performance of a propulsion technology for interstellar travel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DriveProfile {
+    /// Technology providing the drive
+    pub tech: &'static str,
+    pub name: &'static str,
+    /// Average cruise speed as a fraction of the speed of light
+    pub cruise_speed_c: f64,
+    /// Longest single jump between resupply points in light years
+    pub jump_range_ly: f64,
+}
+
+/// Drives in order of increasing performance
+pub const DRIVES: &[DriveProfile] = &[
+    DriveProfile {
+        tech: "chemical_rockets",
+        name: "Chemical Rockets",
+        cruise_speed_c: 0.00006,
+        jump_range_ly: 1.0,
+    },
+    DriveProfile {
+        tech: "ion_drive",
+        name: "Ion Drive",
+        cruise_speed_c: 0.0005,
+        jump_range_ly: 2.0,
+    },
+    DriveProfile {
+        tech: "plasma_drive",
+        name: "Plasma Drive",
+        cruise_speed_c: 0.002,
+        jump_range_ly: 3.0,
+    },
+    DriveProfile {
+        tech: "fusion_drive",
+        name: "Fusion Drive",
+        cruise_speed_c: 0.02,
+        jump_range_ly: 5.0,
+    },
+    DriveProfile {
+        tech: "advanced_fusion_drive",
+        name: "Advanced Fusion Drive",
+        cruise_speed_c: 0.05,
+        jump_range_ly: 6.0,
+    },
+    DriveProfile {
+        tech: "high_efficiency_fusion",
+        name: "High-Efficiency Fusion Drive",
+        cruise_speed_c: 0.08,
+        jump_range_ly: 7.0,
+    },
+    DriveProfile {
+        tech: "antimatter_catalyzed_fusion",
+        name: "Antimatter-Catalyzed Fusion",
+        cruise_speed_c: 0.12,
+        jump_range_ly: 8.0,
+    },
+    DriveProfile {
+        tech: "pure_antimatter_drive",
+        name: "Antimatter Drive",
+        cruise_speed_c: 0.3,
+        jump_range_ly: 10.0,
+    },
+    DriveProfile {
+        tech: "reactionless_drive",
+        name: "Reactionless Drive",
+        cruise_speed_c: 0.6,
+        jump_range_ly: 14.0,
+    },
+    DriveProfile {
+        tech: "displacement_drive",
+        name: "Displacement Drive",
+        cruise_speed_c: 5.0,
+        jump_range_ly: 20.0,
+    },
+];
+
+/// Best drive available with the researched technologies. Chemical rockets
+/// are the 2026 baseline and always available.
+pub fn best_drive(research_state: Option<&ResearchState>) -> &'static DriveProfile {
+    DRIVES
+        .iter()
+        .rev()
+        .find(|drive| research_state.is_some_and(|r| r.is_unlocked(drive.tech)))
+        .unwrap_or(&DRIVES[0])
+}
+
+/// Travel time in years for a distance at the given cruise speed
+pub fn travel_time_years(distance_ly: f64, cruise_speed_c: f64) -> f64 {
+    if cruise_speed_c <= 0.0 {
+        f64::INFINITY
+    } else {
+        distance_ly / cruise_speed_c
+    }
+}
+
+/// A plotted route through a sequence of star systems
+#[derive(Debug, Clone, PartialEq)]
+pub struct Route {
+    /// System IDs from origin to destination
+    pub systems: Vec<usize>,
+    /// Length of each leg in light years
+    pub legs_ly: Vec<f64>,
+}
+
+impl Route {
+    pub fn total_ly(&self) -> f64 {
+        self.legs_ly.iter().sum()
+    }
+
+    /// Distance covered after each leg
+    pub fn cumulative_ly(&self) -> Vec<f64> {
+        self.legs_ly
+            .iter()
+            .scan(0.0, |total, leg| {
+                *total += leg;
+                Some(*total)
+            })
+            .collect()
+    }
+}
+
+/// Shortest route from `from` to `to` that never jumps further than
+/// `max_jump_ly`. `systems` holds (system ID, position in light years).
+pub fn plot_route(
+    systems: &[(usize, DVec3)],
+    from: usize,
+    to: usize,
+    max_jump_ly: f64,
+) -> Option<Route> {
+    let start = systems.iter().position(|(id, _)| *id == from)?;
+    let goal = systems.iter().position(|(id, _)| *id == to)?;
+
+    // Systems bucketed by position on a grid one jump wide, so only the
+    // neighbouring cells are searched for the jumps from a system
+    let cell_size = max_jump_ly.max(1.0);
+    let cell = |position: DVec3| (position / cell_size).floor().as_i64vec3();
+    let mut grid: HashMap<I64Vec3, Vec<usize>> = HashMap::new();
+    for (i, (_, position)) in systems.iter().enumerate() {
+        grid.entry(cell(*position)).or_default().push(i);
+    }
+
+    // Dijkstra over the jumps within range
+    let mut distance = vec![f64::INFINITY; systems.len()];
+    let mut previous: Vec<Option<usize>> = vec![None; systems.len()];
+    let mut visited = vec![false; systems.len()];
+    distance[start] = 0.0;
+
+    while let Some(current) = (0..systems.len())
+        .filter(|i| !visited[*i] && distance[*i].is_finite())
+        .min_by(|a, b| distance[*a].total_cmp(&distance[*b]))
+    {
+        if current == goal {
+            break;
+        }
+        visited[current] = true;
+        let origin = cell(systems[current].1);
+        let neighbours = (-1..=1)
+            .flat_map(|x| (-1..=1).flat_map(move |y| (-1..=1).map(move |z| I64Vec3::new(x, y, z))))
+            .filter_map(|offset| grid.get(&(origin + offset)))
+            .flatten();
+        for &next in neighbours {
+            if visited[next] {
+                continue;
+            }
+            let jump = systems[current].1.distance(systems[next].1);
+            if jump <= max_jump_ly && distance[current] + jump < distance[next] {
+                distance[next] = distance[current] + jump;
+                previous[next] = Some(current);
+            }
+        }
+    }
+    if !distance[goal].is_finite() {
+        return None;
+    }
+
+    let mut path = vec![goal];
+    while let Some(prev) = previous[*path.last()?] {
+        path.push(prev);
+    }
+    path.reverse();
+
+    let legs_ly = path
+        .windows(2)
+        .map(|pair| systems[pair[0]].1.distance(systems[pair[1]].1))
+        .collect();
+    Some(Route {
+        systems: path.iter().map(|i| systems[*i].0).collect(),
+        legs_ly,
+    })
+}
+
+/// State of the starmap route planner
+#[derive(Resource, Debug, Clone, Default)]
+pub struct RoutePlanner {
+    /// Origin system ID
+    pub from: usize,
+    /// Destination system ID
+    pub to: Option<usize>,
+    /// Jump range override in light years; `None` uses the best drive
+    pub jump_range_ly: Option<f64>,
+    /// Number of reachability rings drawn around the selected system, or the
+    /// origin when nothing is selected (0 = none)
+    pub rings: u32,
+    /// Last plotted route
+    pub route: Option<Route>,
+}
+
+impl RoutePlanner {
+    /// Jump range in use for the given drive
+    pub fn jump_range(&self, drive: &DriveProfile) -> f64 {
+        self.jump_range_ly.unwrap_or(drive.jump_range_ly)
+    }
+}
+
+/// Star system positions in light years, as used by [`plot_route`]
+pub fn system_positions<'a>(
+    icons: impl IntoIterator<Item = &'a StarSystemIcon>,
+) -> Vec<(usize, DVec3)> {
+    icons
+        .into_iter()
+        .map(|icon| (icon.id, icon.position / LY_TO_AU))
+        .collect()
+}
+
+/// System that draws the plotted route and the reachability rings on the
+/// starmap
+pub fn draw_route_gizmos(
+    mut gizmos: Gizmos,
+    view_mode: Res<ViewMode>,
+    planner: Res<RoutePlanner>,
+    research_state: Option<Res<ResearchState>>,
+    icons: Query<(&StarSystemIcon, &GlobalTransform)>,
+    selected: Query<&StarSystemIcon, With<SelectedStarSystem>>,
+) {
+    if *view_mode != ViewMode::Starmap {
+        return;
+    }
+    let position_of = |id: usize| {
+        icons
+            .iter()
+            .find(|(icon, _)| icon.id == id)
+            .map(|(_, transform)| transform.translation())
+    };
+
+    let center = selected.get_single().map_or(planner.from, |icon| icon.id);
+    if let Some(origin) = position_of(center) {
+        let drive = best_drive(research_state.as_deref());
+        let range_au = (planner.jump_range(drive) * LY_TO_AU) as f32;
+        for ring in 1..=planner.rings {
+            let alpha = 0.5 / ring as f32;
+            gizmos.circle(
+                origin,
+                Dir3::Y,
+                range_au * ring as f32,
+                Color::srgba(0.3, 0.8, 1.0, alpha),
+            );
+        }
+    }
+
+    if let Some(route) = &planner.route {
+        let points: Vec<Vec3> = route
+            .systems
+            .iter()
+            .filter_map(|id| position_of(*id))
+            .collect();
+        gizmos.linestrip(points, Color::srgb(1.0, 0.75, 0.2));
+    }
+}
+
+/// Plugin that adds the starmap route planner
+pub struct RoutePlannerPlugin;
+
+impl Plugin for RoutePlannerPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(RoutePlanner {
+            rings: 3,
+            ..default()
+        })
+        .add_systems(Update, draw_route_gizmos);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stars() -> Vec<(usize, DVec3)> {
+        vec![
+            (0, DVec3::ZERO),
+            (1, DVec3::new(4.0, 0.0, 0.0)),
+            (2, DVec3::new(8.0, 0.0, 0.0)),
+            (3, DVec3::new(7.5, 1.0, 0.0)),
+            (4, DVec3::new(30.0, 0.0, 0.0)),
+        ]
+    }
+
+    #[test]
+    fn test_route_hops_within_jump_range() {
+        // Too far for a single 5 ly jump; goes through system 1
+        let route = plot_route(&stars(), 0, 2, 5.0).unwrap();
+        assert_eq!(route.systems, vec![0, 1, 2]);
+        assert_eq!(route.cumulative_ly(), vec![4.0, 8.0]);
+
+        // With a long-range drive the direct jump is shortest
+        let route = plot_route(&stars(), 0, 2, 10.0).unwrap();
+        assert_eq!(route.systems, vec![0, 2]);
+        assert_eq!(route.total_ly(), 8.0);
+    }
+
+    #[test]
+    fn test_unreachable_system_has_no_route() {
+        assert!(plot_route(&stars(), 0, 4, 5.0).is_none());
+        assert!(plot_route(&stars(), 0, 99, 5.0).is_none());
+        assert_eq!(plot_route(&stars(), 2, 2, 1.0).unwrap().systems, vec![2]);
+    }
+
+    #[test]
+    fn test_best_drive_follows_research() {
+        assert_eq!(best_drive(None).tech, "chemical_rockets");
+        let mut research = ResearchState::default();
+        research.unlock_tech("ion_drive".to_string());
+        research.unlock_tech("fusion_drive".to_string());
+        assert_eq!(best_drive(Some(&research)).tech, "fusion_drive");
+        assert_eq!(travel_time_years(4.2, 0.02), 210.0);
+    }
+}
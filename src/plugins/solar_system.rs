@@ -1,6 +1,5 @@
 use bevy::prelude::*;
-use bevy::render::mesh::{Indices, PrimitiveTopology, VertexAttributeValues};
-use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::mesh::VertexAttributeValues;
 use bevy::render::render_resource::{AsBindGroup, ShaderRef};
 use rand::prelude::*;
 use std::collections::hash_map::DefaultHasher;
@@ -8,15 +7,18 @@ use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 
 use super::solar_system_data::{
-    calculate_visual_radius, AsteroidClass, BodyType, SolarSystemData, MIN_VISUAL_RADIUS,
+    calculate_visual_radius, AsteroidClass, BodyType, CelestialBodyData, RingData, SolarSystemData,
+    MIN_VISUAL_RADIUS,
 };
 use crate::astronomy::components::{CurrentStarSystem, SystemId};
-use crate::economy::components::{Population, PowerGenerator, PowerSourceType};
+use crate::economy::components::{Population, PowerConsumer, PowerGenerator, PowerSourceType};
+use crate::modding::ModList;
 use crate::astronomy::{
-    orbit_position_from_mean_anomaly, KeplerOrbit, LocalOrbitAmplification, OrbitPath,
-    SpaceCoordinates, SCALING_FACTOR, SurfaceTemperature,
+    orbit_position_from_mean_anomaly, CometActivity, KeplerOrbit, LocalOrbitAmplification,
+    OrbitPath, SpaceCoordinates, StellarLuminosity, SCALING_FACTOR, SurfaceTemperature,
 };
 use crate::plugins::camera::{CameraAnchor, GameCamera};
+use crate::render::rings::{create_ring_mesh, ring_opacity, ring_visual_radii, RingMaterial};
 use crate::ui::SimulationTime;
 
 pub struct SolarSystemPlugin;
@@ -209,6 +211,20 @@ impl CelestialBody {
         
         (surface_gravity_m_s2 / G_EARTH) as f32
     }
+
+    /// Body of unit radius and mass, for tests that only need somewhere to
+    /// put a colony
+    #[cfg(test)]
+    pub fn fixture(name: &str, body_type: BodyType) -> Self {
+        Self {
+            name: name.to_string(),
+            radius: 1.0,
+            mass: 1.0,
+            body_type,
+            visual_radius: 1.0,
+            asteroid_class: None,
+        }
+    }
 }
 
 /// Logical parent for UI hierarchy, separate from spatial transform parenting
@@ -236,8 +252,17 @@ pub struct Comet;
 #[derive(Component)]
 pub struct GasGiant;
 
-#[derive(Component)]
-pub struct Ring;
+/// Planetary ring system: an annulus of ice and dust in the host planet's
+/// equatorial plane. Ring entities are transform children of their planet.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct Ring {
+    /// Inner edge in km from the planet's center
+    pub inner_radius_km: f32,
+    /// Outer edge in km from the planet's center
+    pub outer_radius_km: f32,
+    /// Mass fraction of water ice; the rest is silicate dust
+    pub ice_fraction: f32,
+}
 
 /// Axial tilt (obliquity) and north-pole direction of a celestial body.
 /// `obliquity` is the angle between the spin axis and the ecliptic normal (radians).
@@ -248,9 +273,26 @@ pub struct AxialTilt {
     pub north_pole_ra: f32,
 }
 
+/// Spin rate in radians per second; negative for retrograde rotation
 #[derive(Component)]
 pub struct RotationSpeed(pub f32);
 
+impl RotationSpeed {
+    /// Spin from a sidereal rotation period in days (negative = retrograde,
+    /// zero = not rotating)
+    pub fn from_period_days(period_days: f32) -> Self {
+        if period_days == 0.0 {
+            return Self(0.0);
+        }
+        Self(2.0 * std::f32::consts::PI / (period_days * SECONDS_PER_DAY as f32))
+    }
+
+    /// Signed sidereal rotation period in seconds, `None` if not rotating
+    pub fn period_seconds(&self) -> Option<f64> {
+        (self.0 != 0.0).then(|| std::f64::consts::TAU / self.0 as f64)
+    }
+}
+
 // Constants moved to solar_system_data.rs
 
 // Time conversion constants
@@ -540,25 +582,36 @@ struct LinearImageQueue {
     handles: Vec<Handle<Image>>,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn setup_solar_system(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     mut materials_night: ResMut<Assets<crate::plugins::visual_effects::NightMaterial>>,
     mut materials_glow: ResMut<Assets<StarGlowMaterial>>,
+    mut ring_materials: ResMut<Assets<RingMaterial>>,
     asset_server: Res<AssetServer>,
+    mods: Option<Res<ModList>>,
 ) {
     // Queue to collect normal/specular handles that must be treated as linear textures
     let mut linear_handle_queue: Vec<Handle<Image>> = Vec::new();
 
     // Load solar system data
-    let data = match SolarSystemData::load_from_file("assets/data/solar_system.ron") {
+    let mut data = match SolarSystemData::load_from_file("assets/data/solar_system.ron") {
         Ok(data) => data,
         Err(e) => {
             error!("Failed to load solar system data: {}", e);
             return;
         }
     };
+    // Bodies added or replaced by enabled mods
+    let mod_files = mods.map_or(Vec::new(), |mods| mods.data_files("solar_system.ron"));
+    for mod_path in mod_files {
+        match SolarSystemData::load_from_file(&mod_path.to_string_lossy()) {
+            Ok(mod_data) => data.merge(mod_data),
+            Err(e) => error!("Failed to load {}: {}", mod_path.display(), e),
+        }
+    }
 
     info!("Loaded {} celestial bodies", data.bodies.len());
 
@@ -571,17 +624,7 @@ pub fn setup_solar_system(
         let visual_radius = calculate_visual_radius(body_data.body_type, body_data.radius);
 
         // Calculate rotation speed (convert from days to radians per second)
-        let rotation_speed = if body_data.rotation_period != 0.0 {
-            (2.0 * std::f32::consts::PI)
-                / (body_data.rotation_period.abs() * SECONDS_PER_DAY as f32)
-                * if body_data.rotation_period < 0.0 {
-                    -1.0
-                } else {
-                    1.0
-                }
-        } else {
-            0.0
-        };
+        let rotation_speed = RotationSpeed::from_period_days(body_data.rotation_period);
 
         // Determine if this is the star (to add light)
         let is_star = body_data.body_type == BodyType::Star;
@@ -648,6 +691,20 @@ pub fn setup_solar_system(
             )
         };
 
+        // Rings get their own material and take their proportions from the planet
+        if body_data.body_type == BodyType::Ring {
+            let entity = spawn_ring_body(
+                &mut commands,
+                body_data,
+                &data,
+                base_color_texture,
+                &mut meshes,
+                &mut ring_materials,
+            );
+            entity_map.insert(body_data.name.clone(), entity);
+            continue;
+        }
+
         let has_texture = base_color_texture.is_some();
 
         // Apply procedural variation to material properties
@@ -672,20 +729,6 @@ pub fn setup_solar_system(
                 metallic: 0.0,
                 ..default()
             })
-        } else if body_data.body_type == BodyType::Ring {
-            materials.add(StandardMaterial {
-                base_color: material_color,
-                base_color_texture: base_color_texture.clone(),
-                perceptual_roughness: roughness,
-                metallic: 0.0,
-                reflectance: 0.2,
-                alpha_mode: AlphaMode::Blend,
-                cull_mode: None, // Double-sided
-                unlit: true, // Rings often look better unlit or carefully lit, but for now transparent unlit or lit?
-                // Real rings are lit by sun. But avoiding shadows casting weirdly.
-                // Let's stick to lit but standard.
-                ..default()
-            })
         } else {
             materials.add(StandardMaterial {
                 base_color: material_color,
@@ -709,17 +752,7 @@ pub fn setup_solar_system(
         let initial_pos = Vec3::ZERO;
 
         // Build entity with appropriate components
-        let mesh = if body_data.body_type == BodyType::Ring {
-            // Create a custom donut/annulus mesh for the rings
-            // Inner radius is approx 74,500km, Outer is 140,000km from center
-            // Ratio is ~0.53
-            let inner_radius = visual_radius * 0.53;
-            let outer_radius = visual_radius;
-
-            // Create ring mesh with high segment count for smoothness
-            // We'll define a helper function create_ring_mesh
-            meshes.add(create_ring_mesh(outer_radius, inner_radius, 128))
-        } else if body_data.body_type == BodyType::Asteroid
+        let mesh = if body_data.body_type == BodyType::Asteroid
             || body_data.body_type == BodyType::Comet
         {
             let seed = calculate_hash(&body_data.name);
@@ -743,7 +776,7 @@ pub fn setup_solar_system(
                 visual_radius,
                 asteroid_class: body_data.asteroid_class,
             },
-            RotationSpeed(rotation_speed),
+            rotation_speed,
         ));
 
         // Add axial tilt if present (convert degrees to radians)
@@ -757,7 +790,7 @@ pub fn setup_solar_system(
         // Add type-specific component
         match body_data.body_type {
             BodyType::Star => {
-                entity_commands.insert(Star);
+                entity_commands.insert((Star, StellarLuminosity(1.0)));
             }
             BodyType::Planet => {
                 entity_commands.insert(Planet);
@@ -777,11 +810,10 @@ pub fn setup_solar_system(
                 entity_commands.insert(Asteroid);
             }
             BodyType::Comet => {
-                entity_commands.insert(Comet);
-            }
-            BodyType::Ring => {
-                entity_commands.insert(Ring);
+                entity_commands.insert((Comet, CometActivity::default()));
             }
+            // Spawned by spawn_ring_body above
+            BodyType::Ring => {}
         }
 
         let mut surface_temperature_celsius = -200.0; // Default cold vacuum
@@ -878,7 +910,7 @@ pub fn setup_solar_system(
         } else {
             0.0
         };
-        commands.entity(entity).insert(Population { count: population_count });
+        commands.entity(entity).insert(Population::new(population_count));
 
         // Initialize power generation
         // Earth starts with ~20 TW (Type 0.73 civilization)
@@ -887,6 +919,9 @@ pub fn setup_solar_system(
                 output: 20_000_000_000_000.0, // 20 TW
                 source_type: PowerSourceType::Planet,
             });
+            commands.entity(entity).insert(PowerConsumer {
+                demand: 18_000_000_000_000.0, // 18 TW
+            });
         }
 
         entity_map.insert(body_data.name.clone(), entity);
@@ -1214,61 +1249,74 @@ fn initial_camera_focus(
     }
 }
 
-// Helper to create a flat ring (annulus) mesh
-fn create_ring_mesh(outer_radius: f32, inner_radius: f32, segments: u32) -> Mesh {
-    let mut positions = Vec::new();
-    let mut normals = Vec::new();
-    let mut uvs = Vec::new();
-    let mut indices = Vec::new();
-
-    // Create vertices
-    for i in 0..=segments {
-        let angle_fraction = i as f32 / segments as f32; // 0 to 1
-        let angle = angle_fraction * std::f32::consts::TAU;
-        let (sin, cos) = angle.sin_cos();
-
-        // Inner vertex
-        positions.push([inner_radius * cos, 0.0, inner_radius * sin]);
-        normals.push([0.0, 1.0, 0.0]); // Up-facing normal
-
-        // Outer vertex
-        positions.push([outer_radius * cos, 0.0, outer_radius * sin]);
-        normals.push([0.0, 1.0, 0.0]); // Up-facing normal
-
-        // UV Mapping:
-        // U coordinate maps to radius (0 = inner, 1 = outer)
-        // V coordinate maps to angle (0 = 0deg, 1 = 360deg)
-        uvs.push([0.0, angle_fraction]);
-        uvs.push([1.0, angle_fraction]);
-    }
+/// Spawn a ring system from its RON entry. The ring is sized against the
+/// drawn radius of its planet (see [`ring_visual_radii`]); the second pass of
+/// [`setup_solar_system`] makes it a transform child of that planet.
+fn spawn_ring_body(
+    commands: &mut Commands,
+    body_data: &CelestialBodyData,
+    data: &SolarSystemData,
+    texture: Option<Handle<Image>>,
+    meshes: &mut Assets<Mesh>,
+    ring_materials: &mut Assets<RingMaterial>,
+) -> Entity {
+    let ring_data = body_data.ring.unwrap_or(RingData {
+        inner_radius: body_data.radius * 0.53,
+        ice_fraction: 0.9,
+    });
+    let ring = Ring {
+        inner_radius_km: ring_data.inner_radius,
+        outer_radius_km: body_data.radius,
+        ice_fraction: ring_data.ice_fraction,
+    };
 
-    // Create indices (two triangles per segment)
-    for i in 0..segments {
-        let base = i * 2;
-        // Vertices at this segment: base (inner), base+1 (outer)
-        // Vertices at next segment: base+2 (inner), base+3 (outer)
-
-        // Triangle 1: Inner-Current, Outer-Current, Inner-Next
-        indices.push(base);
-        indices.push(base + 2);
-        indices.push(base + 1);
-
-        // Triangle 2: Inner-Next, Outer-Next, Outer-Current
-        indices.push(base + 2);
-        indices.push(base + 3);
-        indices.push(base + 1);
-    }
+    let (inner_radius, outer_radius) = match body_data
+        .parent
+        .as_deref()
+        .and_then(|name| data.get_body(name))
+    {
+        Some(planet) => ring_visual_radii(
+            &ring,
+            calculate_visual_radius(planet.body_type, planet.radius),
+            planet.radius,
+        ),
+        None => {
+            let outer = calculate_visual_radius(BodyType::Ring, body_data.radius);
+            (outer * ring.inner_radius_km / ring.outer_radius_km, outer)
+        }
+    };
 
-    let mut mesh = Mesh::new(
-        PrimitiveTopology::TriangleList,
-        RenderAssetUsages::default(),
+    // Dedicated textures carry their own colors
+    let color = if texture.is_some() {
+        Color::WHITE
+    } else {
+        Color::srgb(body_data.color.0, body_data.color.1, body_data.color.2)
+    };
+    let material = RingMaterial::new(
+        color,
+        ring_opacity(body_data.mass),
+        texture,
+        (calculate_hash(&body_data.name) % 1000) as f32,
     );
-    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
-    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
-    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
-    mesh.insert_indices(Indices::U32(indices));
 
-    mesh
+    commands
+        .spawn((
+            MaterialMeshBundle {
+                mesh: meshes.add(create_ring_mesh(outer_radius, inner_radius, 128)),
+                material: ring_materials.add(material),
+                ..default()
+            },
+            CelestialBody {
+                name: body_data.name.clone(),
+                radius: body_data.radius,
+                mass: body_data.mass,
+                body_type: BodyType::Ring,
+                visual_radius: outer_radius,
+                asteroid_class: None,
+            },
+            ring,
+        ))
+        .id()
 }
 
 fn calculate_hash<T: Hash>(t: &T) -> u64 {
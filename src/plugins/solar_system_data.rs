@@ -49,6 +49,15 @@ pub struct AtmosphericGasData {
     pub percentage: f32,
 }
 
+/// Ring system geometry and composition (the body's `radius` is the outer edge)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RingData {
+    /// Inner edge of the rings in km from the planet's center
+    pub inner_radius: f32,
+    /// Mass fraction of water ice; the rest is silicate dust
+    pub ice_fraction: f32,
+}
+
 /// Atmospheric data for a celestial body
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AtmosphereData {
@@ -148,6 +157,9 @@ pub struct CelestialBodyData {
     /// Atmosphere data (if the body has an atmosphere)
     #[serde(default)]
     pub atmosphere: Option<AtmosphereData>,
+    /// Ring geometry (ring bodies only)
+    #[serde(default)]
+    pub ring: Option<RingData>,
 }
 
 /// Complete solar system data
@@ -165,6 +177,16 @@ impl SolarSystemData {
         Ok(data)
     }
 
+    /// Add the bodies of `other`, replacing bodies with the same name
+    pub fn merge(&mut self, other: SolarSystemData) {
+        for body in other.bodies {
+            match self.bodies.iter_mut().find(|b| b.name == body.name) {
+                Some(existing) => *existing = body,
+                None => self.bodies.push(body),
+            }
+        }
+    }
+
     /// Get a body by name
     pub fn get_body(&self, name: &str) -> Option<&CelestialBodyData> {
         self.bodies.iter().find(|b| b.name == name)
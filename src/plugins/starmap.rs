@@ -7,11 +7,15 @@
 //!  - Individual celestial bodies and orbit paths are hidden.
 //!  - Each star system is represented by a single glowing icon/billboard.
 //!  - Double-clicking a system icon anchors the camera and allows zoom-in.
+//!  - Systems sit at their 3D galactic positions, optionally with height
+//!    lines to the galactic plane and a distance/longitude grid.
 //!
-//! Currently only the Sol system exists; more systems will be added later.
+//! Sol and the catalogued nearby stars are joined by procedurally generated
+//! systems (see [`crate::astronomy::galaxy`]) out to the configured radius.
 
 use bevy::math::DVec3;
 use bevy::prelude::*;
+use bevy::time::Real;
 use bevy::window::PrimaryWindow;
 use std::collections::HashMap;
 
@@ -19,12 +23,22 @@ use super::camera::{CameraAnchor, GameCamera, OrbitCamera, ViewMode};
 use super::solar_system::{CelestialBody, Planet, Star};
 use super::solar_system_data::{BodyType, calculate_visual_radius};
 use crate::astronomy::components::{
-    CurrentStarSystem, FloatingOrigin, KeplerOrbit, OrbitCenter, OrbitPath, SpaceCoordinates,
+    CurrentStarSystem, FloatingOrigin, OrbitCenter, OrbitPath, SpaceCoordinates,
     SystemId,
 };
+use crate::astronomy::exoplanets::{ConfirmedPlanet, ExoplanetDiscovery, RealPlanet};
+use crate::astronomy::galaxy::{generate_systems, GalaxySettings, ProceduralStars};
+use crate::astronomy::multiple_stars::{
+    pair_orbits, wide_companion_offset, HierarchyNode, StarHierarchy,
+};
 use crate::astronomy::nearby_stars::NearbyStarsData;
+use crate::astronomy::procedural::{calculate_frost_line, ring_for_giant, ProceduralBody};
+use crate::astronomy::rotation::sample_spin;
 use crate::astronomy::SCALING_FACTOR;
-use crate::game_state::{ActiveMenu, GameMenu};
+use crate::game_state::{ActiveMenu, GameMenu, GameSeed};
+use crate::render::rings::{
+    create_ring_mesh, ring_color, ring_opacity, ring_visual_radii, RingMaterial,
+};
 use rand::prelude::*;
 use std::f64::consts::PI;
 
@@ -67,6 +81,9 @@ impl Plugin for StarmapPlugin {
         app.init_resource::<CurrentStarSystem>()
             .init_resource::<FloatingOrigin>()
             .init_resource::<SystemMetadata>()
+            .init_resource::<StarmapDisplay>()
+            .init_resource::<GalaxySettings>()
+            .init_resource::<ProceduralStars>()
             .add_systems(Startup, setup_starmap)
             .add_systems(
                 Update,
@@ -77,14 +94,38 @@ impl Plugin for StarmapPlugin {
                     update_starmap_visibility,
                     update_starmap_icon_scale,
                     update_starmap_coordinates,
-                    handle_starmap_hover, // New: detect hover
-                    handle_starmap_selection,
+                    // Pointer input through egui, so only with a window
+                    (handle_starmap_hover, handle_starmap_selection)
+                        .run_if(any_with_component::<PrimaryWindow>),
                     handle_system_transition,
+                    draw_starmap_guides,
+                    rotate_starmap_camera,
                 ),
             );
     }
 }
 
+/// Display options of the starmap
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct StarmapDisplay {
+    /// Draw a line from each system down to the galactic plane
+    pub height_lines: bool,
+    /// Draw distance rings and galactic longitude spokes around Sol
+    pub grid: bool,
+    /// Slowly turn the camera around the galactic pole
+    pub auto_rotate: bool,
+}
+
+impl Default for StarmapDisplay {
+    fn default() -> Self {
+        Self {
+            height_lines: true,
+            grid: false,
+            auto_rotate: false,
+        }
+    }
+}
+
 // ── Components ──────────────────────────────────────────────────────────────
 
 /// Marker for starmap-level star system icons.
@@ -116,7 +157,37 @@ pub struct SelectedStarSystem;
 // ── Startup ─────────────────────────────────────────────────────────────────
 
 // 1 Light Year in Astronomical Units
-const LY_TO_AU: f64 = 63241.077;
+pub const LY_TO_AU: f64 = 63241.077;
+
+/// Rotation from equatorial J2000 to galactic cartesian coordinates (IAU)
+const EQUATORIAL_TO_GALACTIC: [[f64; 3]; 3] = [
+    [-0.054_875_560_4, -0.873_437_090_2, -0.483_835_015_5],
+    [0.494_109_427_9, -0.444_829_630_0, 0.746_982_244_5],
+    [-0.867_666_149_0, -0.198_076_373_4, 0.455_983_776_2],
+];
+
+/// Universe-space position of a star from its equatorial J2000 cartesian
+/// coordinates.
+///
+/// The starmap uses the galactic frame with the galactic plane as the X-Z
+/// plane: +X points to the galactic center (l = 0°), +Y to the north
+/// galactic pole and -Z towards l = 90°, so the orbit camera yaws around the
+/// galactic pole.
+pub fn galactic_position(equatorial: DVec3) -> DVec3 {
+    let [x, y, z] = EQUATORIAL_TO_GALACTIC.map(|row| DVec3::from_array(row).dot(equatorial));
+    DVec3::new(x, z, -y)
+}
+
+/// Galactic longitude and latitude in degrees of a universe-space position
+pub fn galactic_coordinates(position: DVec3) -> (f64, f64) {
+    let distance = position.length();
+    if distance == 0.0 {
+        return (0.0, 0.0);
+    }
+    let longitude = (-position.z).atan2(position.x).to_degrees().rem_euclid(360.0);
+    let latitude = (position.y / distance).asin().to_degrees();
+    (longitude, latitude)
+}
 
 struct NearbyStarData {
     name: &'static str,
@@ -125,7 +196,8 @@ struct NearbyStarData {
 }
 
 // 50 Closest Star Systems to Sol (excluding Sol)
-// Coordinates in Light Years (Equatorial J2000 Cartesian)
+// Coordinates in Light Years (Equatorial J2000 Cartesian), converted with
+// `galactic_position` when placed in the universe
 const NEARBY_STARS: &[NearbyStarData] = &[
     NearbyStarData {
         name: "Alpha Centauri",
@@ -379,13 +451,53 @@ const NEARBY_STARS: &[NearbyStarData] = &[
     },
 ];
 
-/// Spawn the starmap icon for the Sol system.
-/// It starts hidden and becomes visible when `ViewMode::Starmap` is active.
+/// System id of the first procedurally generated system; Sol is 0 and the
+/// catalog takes the ids in between
+pub const FIRST_PROCEDURAL_SYSTEM_ID: usize = NEARBY_STARS.len() + 1;
+
+/// Distance of the outermost catalog star, where procedural systems begin
+fn catalog_radius_ly() -> f64 {
+    NEARBY_STARS
+        .iter()
+        .map(|star| DVec3::from_array(star.pos_ly).length())
+        .fold(0.0, f64::max)
+}
+
+/// Icon color of a spectral type
+fn icon_color(spectral_type: &str) -> (f32, f32, f32) {
+    match spectral_type.chars().next().unwrap_or('G') {
+        'O' => (0.6, 0.8, 1.0),             // Blue
+        'B' => (0.7, 0.85, 1.0),            // Bluish White
+        'A' => (0.9, 0.9, 1.0),             // White
+        'F' => (1.0, 1.0, 0.9),             // Yellow-White
+        'G' => (1.0, 0.95, 0.7),            // Yellow
+        'K' => (1.0, 0.8, 0.6),             // Light Orange
+        'M' => (1.0, 0.6, 0.4),             // Orange-Red
+        'L' | 'T' | 'Y' => (0.8, 0.2, 0.2), // Brown/Dark Red
+        'D' => (0.75, 0.8, 1.0),            // White dwarf, faint blue-white
+        _ => (1.0, 1.0, 1.0),               // Default White
+    }
+}
+
+/// Universe-space position (AU) of a nearby star system by name
+pub fn nearby_star_position(name: &str) -> Option<DVec3> {
+    NEARBY_STARS
+        .iter()
+        .find(|star| star.name == name)
+        .map(|star| galactic_position(DVec3::from_array(star.pos_ly)) * LY_TO_AU)
+}
+
+/// Spawn the starmap icons for Sol, the catalogued nearby stars and the
+/// procedurally generated systems beyond them.
+/// They start hidden and become visible when `ViewMode::Starmap` is active.
 fn setup_starmap(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     mut system_metadata: ResMut<SystemMetadata>,
+    game_seed: Res<GameSeed>,
+    galaxy: Res<GalaxySettings>,
+    mut procedural: ResMut<ProceduralStars>,
 ) {
     // Initialize Sol's bounding radius
     system_metadata.set_bounding_radius(0, DEFAULT_BOUNDING_RADIUS_AU);
@@ -424,19 +536,7 @@ fn setup_starmap(
     for (i, star) in NEARBY_STARS.iter().enumerate() {
         let id = i + 1; // 0 is Sol
 
-        // Determine color from spectral type
-        let (r, g, b) = match star.spectral_type.chars().next().unwrap_or('G') {
-            'O' => (0.6, 0.8, 1.0),             // Blue
-            'B' => (0.7, 0.85, 1.0),            // Bluish White
-            'A' => (0.9, 0.9, 1.0),             // White
-            'F' => (1.0, 1.0, 0.9),             // Yellow-White
-            'G' => (1.0, 0.95, 0.7),            // Yellow
-            'K' => (1.0, 0.8, 0.6),             // Light Orange
-            'M' => (1.0, 0.6, 0.4),             // Orange-Red
-            'L' | 'T' | 'Y' => (0.8, 0.2, 0.2), // Brown/Dark Red
-            _ => (1.0, 1.0, 1.0),               // Default White
-        };
-
+        let (r, g, b) = icon_color(star.spectral_type);
         let material = materials.add(StandardMaterial {
             base_color: Color::srgb(r, g, b),
             emissive: Color::srgb(r * 4.0, g * 4.0, b * 4.0).into(),
@@ -445,7 +545,7 @@ fn setup_starmap(
         });
 
         // Convert LY to AU
-        let pos_au = DVec3::new(star.pos_ly[0], star.pos_ly[1], star.pos_ly[2]) * LY_TO_AU;
+        let pos_au = galactic_position(DVec3::from_array(star.pos_ly)) * LY_TO_AU;
 
         // Initial transform assumes Origin is Sol (0,0,0)
         // Starmap Scale: 1 Unit = 1 AU.
@@ -473,6 +573,61 @@ fn setup_starmap(
             },
         ));
     }
+
+    // --- Procedural systems beyond the catalog ---
+    procedural.first_id = FIRST_PROCEDURAL_SYSTEM_ID;
+    procedural.systems = generate_systems(game_seed.value, &galaxy, catalog_radius_ly());
+    info!(
+        "Generated {} procedural star systems out to {:.0} ly",
+        procedural.systems.len(),
+        galaxy.radius_ly
+    );
+
+    // Thousands of icons share one material per spectral letter
+    let mut class_materials: HashMap<char, Handle<StandardMaterial>> = HashMap::new();
+    for (i, system) in procedural.systems.iter().enumerate() {
+        let id = FIRST_PROCEDURAL_SYSTEM_ID + i;
+        let spectral = &system.primary.spectral_type;
+        let letter = spectral.chars().next().unwrap_or('G');
+        let material = class_materials
+            .entry(letter)
+            .or_insert_with(|| {
+                let (r, g, b) = icon_color(spectral);
+                materials.add(StandardMaterial {
+                    base_color: Color::srgb(r, g, b),
+                    emissive: Color::srgb(r * 4.0, g * 4.0, b * 4.0).into(),
+                    unlit: true,
+                    ..default()
+                })
+            })
+            .clone();
+
+        let pos_au = system.position_ly * LY_TO_AU;
+        let bounding_radius_au = fallback_bounding_radius(system.separation_au);
+        system_metadata.set_bounding_radius(id, bounding_radius_au);
+
+        commands.spawn((
+            PbrBundle {
+                mesh: icon_mesh.clone(),
+                material,
+                transform: Transform::from_translation(pos_au.as_vec3()),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            StarSystemIcon {
+                id,
+                name: system.name.clone(),
+                position: pos_au,
+                bounding_radius_au,
+            },
+        ));
+    }
+}
+
+/// Bounding radius of a system without detailed data: the fallback planetary
+/// extent, or the companion's separation with margin for wide binaries
+fn fallback_bounding_radius(separation_au: f64) -> f64 {
+    FALLBACK_BOUNDING_RADIUS_AU.max(separation_au * 1.5)
 }
 
 // ── Systems ─────────────────────────────────────────────────────────────────
@@ -491,16 +646,17 @@ fn tag_sol_bodies(
     }
 }
 
-const SECONDS_PER_DAY: f64 = 86400.0;
-
 /// Spawns minimal celestial bodies (Star) for non-Sol systems when visited.
+#[allow(clippy::too_many_arguments)]
 fn spawn_system_bodies(
     mut commands: Commands,
     current_system: Res<CurrentStarSystem>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
+    mut ring_materials: ResMut<Assets<RingMaterial>>,
     existing_visual_bodies: Query<&SystemId, (With<CelestialBody>, With<Handle<Mesh>>)>,
     nearby_stars: Res<NearbyStarsData>,
+    procedural: Res<ProceduralStars>,
     mut system_metadata: ResMut<SystemMetadata>,
 ) {
     if !current_system.is_changed() {
@@ -519,6 +675,28 @@ fn spawn_system_bodies(
         return;
     }
 
+    // Generated systems follow the catalog ids
+    if let Some(system) = procedural.get_by_id(sys_id) {
+        info!("Spawning procedural system: {}", system.name);
+        let system_offset = system.position_ly * LY_TO_AU;
+        let companion = system
+            .companion
+            .as_ref()
+            .map(|star| (star.spectral_type.as_str(), system.separation_au));
+        spawn_fallback_system(
+            &mut commands,
+            sys_id,
+            system_offset,
+            &system.name,
+            &system.primary.spectral_type,
+            companion,
+            &mut meshes,
+            &mut materials,
+            &mut system_metadata,
+        );
+        return;
+    }
+
     // Determine star data index
     // SystemId is 1-based index into NEARBY_STARS + Sol (0)
     let star_idx = sys_id - 1;
@@ -528,16 +706,7 @@ fn spawn_system_bodies(
     }
 
     let star_data = &NEARBY_STARS[star_idx];
-    let position_ly = Vec3::from_array([
-        star_data.pos_ly[0] as f32,
-        star_data.pos_ly[1] as f32,
-        star_data.pos_ly[2] as f32,
-    ]);
-    let system_offset = DVec3::new(
-        position_ly.x as f64 * LY_TO_AU,
-        position_ly.y as f64 * LY_TO_AU,
-        position_ly.z as f64 * LY_TO_AU,
-    );
+    let system_offset = galactic_position(DVec3::from_array(star_data.pos_ly)) * LY_TO_AU;
 
     // Check if we have detailed data for this system
     if let Some(detailed_data) = nearby_stars.get_by_name(star_data.name) {
@@ -549,6 +718,7 @@ fn spawn_system_bodies(
             detailed_data,
             &mut meshes,
             &mut materials,
+            &mut ring_materials,
             &mut system_metadata,
         );
         return;
@@ -560,13 +730,16 @@ fn spawn_system_bodies(
         &mut commands,
         sys_id,
         system_offset,
-        star_data,
+        star_data.name,
+        star_data.spectral_type,
+        None,
         &mut meshes,
         &mut materials,
         &mut system_metadata,
     );
 }
 
+#[allow(clippy::too_many_arguments)]
 fn spawn_detailed_system(
     commands: &mut Commands,
     sys_id: usize,
@@ -574,10 +747,10 @@ fn spawn_detailed_system(
     data: &crate::astronomy::nearby_stars::StarSystemData,
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &mut ResMut<Assets<StandardMaterial>>,
+    ring_materials: &mut ResMut<Assets<RingMaterial>>,
     system_metadata: &mut ResMut<SystemMetadata>,
 ) {
     let mut rng = rand::thread_rng();
-    let seconds_per_year: f64 = 365.25 * SECONDS_PER_DAY;
 
     // Calculate bounding radius: maximum of planet orbits + binary star orbits
     let mut max_radius_au: f64 = 10.0; // Default minimum
@@ -676,84 +849,63 @@ fn spawn_detailed_system(
     }
 
     // --- Phase 3: Set up binary/multiple star orbits ---
-    for binary in &data.binary_orbits {
-        if binary.primary_idx >= star_entities.len() || binary.secondary_idx >= star_entities.len()
-        {
-            warn!(
-                "Binary orbit indices out of range for system {}",
-                data.system_name
-            );
-            continue;
-        }
+    // Pairs nest tightest first; each inner pair gets its own barycenter
+    // that orbits inside the enclosing pair, the main pair uses the system
+    // barycenter.
+    let hierarchy = StarHierarchy::build(data.stars.len(), &data.binary_orbits);
+    let main_root = hierarchy.roots.first().copied();
+    let mut node_entities = star_entities.clone();
+    for node in data.stars.len()..hierarchy.nodes.len() {
+        let entity = if Some(node) == main_root {
+            barycenter
+        } else {
+            commands
+                .spawn((
+                    TransformBundle::from_transform(Transform::IDENTITY),
+                    VisibilityBundle {
+                        visibility: Visibility::Hidden,
+                        ..default()
+                    },
+                    SpaceCoordinates {
+                        position: system_offset,
+                    },
+                    SystemId(sys_id),
+                ))
+                .id()
+        };
+        node_entities.push(entity);
+    }
 
-        let primary_mass = data.stars[binary.primary_idx].mass_sol as f64;
-        let secondary_mass = data.stars[binary.secondary_idx].mass_sol as f64;
-        let total_mass = primary_mass + secondary_mass;
-
-        // For a binary, both stars orbit the barycenter.
-        // The secondary orbits at distance: a * M_primary / (M_primary + M_secondary)
-        // The primary orbits at distance: a * M_secondary / (M_primary + M_secondary)
-        let period_seconds = binary.period_years * seconds_per_year;
-        let mean_motion = 2.0 * PI / period_seconds;
-        let incl_rad = binary.inclination_deg.to_radians();
-        let arg_peri_rad = binary.arg_periastron_deg.to_radians();
-        let initial_anomaly: f64 = rng.gen_range(0.0..2.0 * PI);
-
-        // Secondary star orbit around barycenter
-        let secondary_sma = binary.semi_major_axis_au * primary_mass / total_mass;
-        let secondary_orbit = KeplerOrbit {
-            eccentricity: binary.eccentricity,
-            semi_major_axis: secondary_sma,
-            inclination: incl_rad,
-            longitude_ascending_node: rng.gen_range(0.0..2.0 * PI),
-            argument_of_periapsis: arg_peri_rad,
-            mean_anomaly_epoch: initial_anomaly,
-            mean_motion,
+    for (node, entry) in hierarchy.nodes.iter().enumerate() {
+        let HierarchyNode::Pair {
+            orbit,
+            primary,
+            secondary,
+        } = *entry
+        else {
+            continue;
         };
+        let (primary_orbit, secondary_orbit) = pair_orbits(
+            &data.binary_orbits[orbit],
+            hierarchy.mass_sol(primary, &data.stars),
+            hierarchy.mass_sol(secondary, &data.stars),
+            rng.gen_range(0.0..2.0 * PI),
+            rng.gen_range(0.0..2.0 * PI),
+        );
         commands
-            .entity(star_entities[binary.secondary_idx])
-            .insert((secondary_orbit, OrbitCenter(barycenter)));
-
-        // Primary star orbit around barycenter (opposite phase)
-        let primary_sma = binary.semi_major_axis_au * secondary_mass / total_mass;
-        let primary_orbit = KeplerOrbit {
-            eccentricity: binary.eccentricity,
-            semi_major_axis: primary_sma,
-            inclination: incl_rad,
-            longitude_ascending_node: rng.gen_range(0.0..2.0 * PI),
-            argument_of_periapsis: arg_peri_rad + PI, // Opposite side
-            mean_anomaly_epoch: initial_anomaly,
-            mean_motion,
-        };
+            .entity(node_entities[secondary])
+            .insert((secondary_orbit, OrbitCenter(node_entities[node])));
         commands
-            .entity(star_entities[binary.primary_idx])
-            .insert((primary_orbit, OrbitCenter(barycenter)));
+            .entity(node_entities[primary])
+            .insert((primary_orbit, OrbitCenter(node_entities[node])));
     }
 
-    // For single stars with no binary orbit defined, they stay at barycenter
-    // (their SpaceCoordinates = system_offset, no KeplerOrbit)
-    // If only 1 star, no binary orbits → star sits at center. Perfect.
-    // If multiple stars but some have no binary_orbit entry (e.g. Proxima),
-    // place them statically far out with an OrbitCenter:
-    if data.stars.len() > 1 {
-        let has_binary: Vec<bool> = (0..data.stars.len())
-            .map(|i| {
-                data.binary_orbits
-                    .iter()
-                    .any(|b| b.primary_idx == i || b.secondary_idx == i)
-            })
-            .collect();
-
-        for (i, &has_orbit) in has_binary.iter().enumerate() {
-            if !has_orbit && i > 0 {
-                // This is a wide companion (like Proxima) with no defined binary orbit
-                // Place it at a static offset
-                let offset = DVec3::new(100.0 * i as f64, 0.0, 50.0 * i as f64);
-                commands.entity(star_entities[i]).insert(SpaceCoordinates {
-                    position: system_offset + offset,
-                });
-            }
-        }
+    // A single star or the main pair sits at the barycenter. Companions with
+    // no binary orbit entry (e.g. Proxima) are placed at a static offset.
+    for (i, root) in hierarchy.roots.iter().enumerate().skip(1) {
+        commands.entity(node_entities[*root]).insert(SpaceCoordinates {
+            position: system_offset + wide_companion_offset(i),
+        });
     }
 
     // --- Phase 4: Spawn planets for each star ---
@@ -761,15 +913,15 @@ fn spawn_detailed_system(
         let parent_star = star_entities[star_idx];
 
         for planet in &star_data.planets {
-            let orbit = KeplerOrbit {
-                eccentricity: planet.eccentricity as f64,
-                semi_major_axis: planet.semi_major_axis_au as f64,
-                inclination: rng.gen_range(0.0..0.15),
-                longitude_ascending_node: rng.gen_range(0.0..2.0 * PI),
-                argument_of_periapsis: rng.gen_range(0.0..2.0 * PI),
-                mean_anomaly_epoch: rng.gen_range(0.0..2.0 * PI),
-                mean_motion: 2.0 * PI / (planet.period_days as f64 * SECONDS_PER_DAY),
-            };
+            let catalog_planet = ConfirmedPlanet::from_catalog(planet);
+            let orbit = catalog_planet.kepler_orbit(&mut rng);
+            let spin = sample_spin(
+                planet.semi_major_axis_au as f64,
+                star_data.mass_sol as f64,
+                planet.period_days as f64,
+                catalog_planet.body_type() == BodyType::GasGiant,
+                &mut rng,
+            );
 
             let planet_radius_km = if let Some(r) = planet.radius_earth {
                 r * 6371.0
@@ -787,7 +939,7 @@ fn spawn_detailed_system(
 
             let p_color = planet_type_to_color(&planet.planet_type);
 
-            commands.spawn((
+            let planet_entity = commands.spawn((
                 PbrBundle {
                     mesh: meshes.add(Sphere::new(planet_visual_radius).mesh().uv(32, 16)),
                     material: materials.add(StandardMaterial {
@@ -811,7 +963,13 @@ fn spawn_detailed_system(
                 },
                 SystemId(sys_id),
                 Planet,
+                RealPlanet,
+                ExoplanetDiscovery {
+                    method: catalog_planet.discovery_method.clone(),
+                    year: catalog_planet.discovery_year,
+                },
                 orbit,
+                spin.components(),
                 OrbitPath {
                     color: Color::srgba(0.4, 0.75, 1.0, 0.85),
                     visible: true,
@@ -822,7 +980,48 @@ fn spawn_detailed_system(
                 SpaceCoordinates {
                     position: system_offset,
                 },
-            ));
+            )).id();
+
+            // Same rings as the populator's data entity (see ring_for_giant)
+            if catalog_planet.body_type() == BodyType::GasGiant {
+                let catalog_radius_km = catalog_planet.estimated_radius_earth() * 6371.0;
+                let ring_system = ring_for_giant(
+                    &catalog_planet.name,
+                    catalog_radius_km,
+                    catalog_planet.semi_major_axis_au
+                        > calculate_frost_line(star_data.luminosity_sol as f64),
+                );
+                if let Some((ring, mass_kg)) = ring_system {
+                    let (inner_radius, outer_radius) =
+                        ring_visual_radii(&ring, planet_visual_radius, catalog_radius_km);
+                    let ring_entity = commands
+                        .spawn((
+                            MaterialMeshBundle {
+                                mesh: meshes.add(create_ring_mesh(outer_radius, inner_radius, 96)),
+                                material: ring_materials.add(RingMaterial::new(
+                                    ring_color(ring.ice_fraction),
+                                    ring_opacity(mass_kg),
+                                    None,
+                                    (sys_id % 1000) as f32,
+                                )),
+                                ..default()
+                            },
+                            CelestialBody {
+                                name: format!("{} Rings", planet.name),
+                                radius: ring.outer_radius_km,
+                                mass: mass_kg,
+                                body_type: BodyType::Ring,
+                                visual_radius: outer_radius,
+                                asteroid_class: None,
+                            },
+                            ring,
+                            ProceduralBody,
+                            SystemId(sys_id),
+                        ))
+                        .id();
+                    commands.entity(planet_entity).add_child(ring_entity);
+                }
+            }
         }
     }
 
@@ -830,16 +1029,47 @@ fn spawn_detailed_system(
     system_metadata.set_bounding_radius(sys_id, max_radius_au);
 }
 
+/// Spawn a bare star for a system without detailed data. A binary companion,
+/// given as spectral type and separation in AU, is placed beside it.
+#[allow(clippy::too_many_arguments)]
 fn spawn_fallback_system(
     commands: &mut Commands,
     sys_id: usize,
     system_offset: DVec3,
-    star_data: &NearbyStarData,
+    name: &str,
+    spectral: &str,
+    companion: Option<(&str, f64)>,
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &mut ResMut<Assets<StandardMaterial>>,
     system_metadata: &mut ResMut<SystemMetadata>,
 ) {
-    let spectral = star_data.spectral_type;
+    spawn_fallback_star(commands, sys_id, system_offset, name, spectral, meshes, materials);
+    if let Some((companion_spectral, separation_au)) = companion {
+        spawn_fallback_star(
+            commands,
+            sys_id,
+            system_offset + DVec3::X * separation_au,
+            &format!("{} B", name.trim_end_matches(" AB")),
+            companion_spectral,
+            meshes,
+            materials,
+        );
+    }
+
+    // For fallback systems without detailed data, use default bounding radius
+    let separation_au = companion.map_or(0.0, |(_, separation_au)| separation_au);
+    system_metadata.set_bounding_radius(sys_id, fallback_bounding_radius(separation_au));
+}
+
+fn spawn_fallback_star(
+    commands: &mut Commands,
+    sys_id: usize,
+    position: DVec3,
+    name: &str,
+    spectral: &str,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+) {
     let color = get_color_from_spectral_type(spectral);
     let radius_mult = estimate_radius_from_spectral(spectral);
     let visual_radius = calculate_visual_radius(BodyType::Star, 696340.0 * radius_mult);
@@ -858,7 +1088,7 @@ fn spawn_fallback_system(
                 ..default()
             },
             CelestialBody {
-                name: star_data.name.to_string(),
+                name: name.to_string(),
                 radius: 696340.0 * radius_mult,
                 mass: 1.989e30 * radius_mult as f64,
                 body_type: BodyType::Star,
@@ -867,9 +1097,7 @@ fn spawn_fallback_system(
             },
             SystemId(sys_id),
             Star,
-            SpaceCoordinates {
-                position: system_offset,
-            },
+            SpaceCoordinates { position },
         ))
         .with_children(|parent| {
             let intensity = 2.8e11 * radius_mult;
@@ -887,9 +1115,6 @@ fn spawn_fallback_system(
                 SystemId(sys_id),
             ));
         });
-
-    // For fallback systems without detailed data, use default bounding radius
-    system_metadata.set_bounding_radius(sys_id, FALLBACK_BOUNDING_RADIUS_AU);
 }
 
 fn get_color_from_spectral_type(spectral: &str) -> Color {
@@ -930,6 +1155,8 @@ fn estimate_radius_from_spectral(spectral: &str) -> f32 {
         3.0
     } else if spectral.starts_with('O') {
         10.0
+    } else if spectral.starts_with('D') {
+        0.012
     } else {
         0.1
     }
@@ -1419,3 +1646,115 @@ fn handle_system_transition(
         commands.entity(entity).remove::<SelectedStarSystem>();
     }
 }
+
+/// Spacing of the starmap distance rings in light years
+const GRID_RING_SPACING_LY: f64 = 5.0;
+/// Number of starmap distance rings
+const GRID_RINGS: u32 = 4;
+/// Angle between galactic longitude spokes in degrees
+const GRID_SPOKE_SPACING_DEG: f32 = 30.0;
+/// Camera yaw speed while auto-rotating, in radians per second
+const AUTO_ROTATE_SPEED: f32 = 0.05;
+
+/// Draw height lines to the galactic plane and the optional galactic grid in
+/// starmap view
+fn draw_starmap_guides(
+    mut gizmos: Gizmos,
+    view_mode: Res<ViewMode>,
+    display: Res<StarmapDisplay>,
+    floating_origin: Res<FloatingOrigin>,
+    icon_query: Query<(&GlobalTransform, &StarSystemIcon)>,
+) {
+    if *view_mode != ViewMode::Starmap {
+        return;
+    }
+
+    // Sol sits on the galactic plane (Y = 0 in universe space)
+    let origin = floating_origin.position;
+    let sol = Vec3::new(-origin.x as f32, -origin.y as f32, -origin.z as f32);
+
+    if display.height_lines {
+        for (transform, icon) in icon_query.iter() {
+            let top = transform.translation();
+            let foot = Vec3::new(top.x, sol.y, top.z);
+            let color = if icon.position.y >= 0.0 {
+                Color::srgba(0.4, 0.7, 1.0, 0.5)
+            } else {
+                Color::srgba(1.0, 0.45, 0.35, 0.5)
+            };
+            gizmos.line(top, foot, color);
+            gizmos.circle(foot, Dir3::Y, (LY_TO_AU * 0.1) as f32, color);
+        }
+    }
+
+    if display.grid {
+        let color = Color::srgba(0.5, 0.6, 0.8, 0.25);
+        let ring_spacing = (GRID_RING_SPACING_LY * LY_TO_AU) as f32;
+        for ring in 1..=GRID_RINGS {
+            gizmos.circle(sol, Dir3::Y, ring_spacing * ring as f32, color);
+        }
+        let outer = ring_spacing * GRID_RINGS as f32;
+        let spokes = (360.0 / GRID_SPOKE_SPACING_DEG) as u32;
+        for spoke in 0..spokes {
+            let longitude = (spoke as f32 * GRID_SPOKE_SPACING_DEG).to_radians();
+            let direction = Vec3::new(longitude.cos(), 0.0, -longitude.sin());
+            gizmos.line(sol, sol + direction * outer, color);
+        }
+    }
+}
+
+/// Turn the camera around the galactic pole while auto-rotate is on, so the
+/// parallax between near and far systems shows their depth
+fn rotate_starmap_camera(
+    view_mode: Res<ViewMode>,
+    display: Res<StarmapDisplay>,
+    time: Res<Time<Real>>,
+    mut camera_query: Query<&mut OrbitCamera, With<GameCamera>>,
+) {
+    if *view_mode != ViewMode::Starmap || !display.auto_rotate {
+        return;
+    }
+    if let Ok(mut orbit) = camera_query.get_single_mut() {
+        orbit.yaw += AUTO_ROTATE_SPEED * time.delta_seconds();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Unit vector of equatorial right ascension / declination in degrees
+    fn equatorial(ra_deg: f64, dec_deg: f64) -> DVec3 {
+        let (ra, dec) = (ra_deg.to_radians(), dec_deg.to_radians());
+        DVec3::new(dec.cos() * ra.cos(), dec.cos() * ra.sin(), dec.sin())
+    }
+
+    #[test]
+    fn test_galactic_frame_axes() {
+        // North galactic pole points up
+        let pole = galactic_position(equatorial(192.859_48, 27.128_25));
+        assert!((pole - DVec3::Y).length() < 1e-6);
+
+        // Galactic center (Sgr A*) lies on the plane at l = 0°
+        let center = galactic_position(equatorial(266.405, -28.936));
+        let (l, b) = galactic_coordinates(center);
+        assert!(b.abs() < 0.1);
+        assert!(!(0.1..=359.9).contains(&l));
+    }
+
+    #[test]
+    fn test_galactic_position_preserves_distance() {
+        for star in NEARBY_STARS {
+            let equatorial = DVec3::from_array(star.pos_ly);
+            let galactic = galactic_position(equatorial);
+            assert!((galactic.length() - equatorial.length()).abs() < 1e-6);
+        }
+
+        // Alpha Centauri sits close to the galactic plane, near l = 316°
+        let (l, b) = galactic_coordinates(galactic_position(DVec3::from_array(
+            NEARBY_STARS[0].pos_ly,
+        )));
+        assert!((l - 315.7).abs() < 2.0);
+        assert!(b.abs() < 3.0);
+    }
+}
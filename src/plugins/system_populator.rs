@@ -5,62 +5,151 @@
 //! 2. Filling in missing planets/bodies using procedural generation
 //! 3. Spawning asteroid belts and cometary clouds
 //! 4. Applying resource generation with metallicity bonuses
+//!
+//! Systems are populated lazily, the first time the player enters one or
+//! selects it on the starmap (or a scenario settles one of its bodies). Each
+//! system has its own random stream derived from the [`GameSeed`], and
+//! deposits are seeded per body, so a system comes out the same whenever and
+//! in whatever order it is populated. Systems collapsed by the system
+//! streaming plugin are forgotten and populated again when next opened.
 
 use bevy::math::DVec3;
 use bevy::prelude::*;
 use rand::prelude::*;
 use rand::rngs::StdRng;
 use rand::SeedableRng;
+use std::collections::HashSet;
 
 use crate::astronomy::components::{CurrentStarSystem, OrbitCenter, SystemId};
-use crate::astronomy::exoplanets::RealPlanet;
-use crate::astronomy::nearby_stars::{NearbyStarsData, PlanetData, StarData};
+use crate::astronomy::exoplanets::{ConfirmedPlanet, ExoplanetDiscovery, RealPlanet};
+use crate::astronomy::multiple_stars::{
+    pair_orbits, planet_zone, wide_companion_offset, HierarchyNode, PlanetZone, StarHierarchy,
+};
+use crate::astronomy::nearby_stars::{NearbyStarsData, PlanetData, StarData, StarSystemData};
+use crate::astronomy::rotation::sample_spin;
 use crate::astronomy::{
-    calculate_frost_line, map_star_to_system_architecture, KeplerOrbit, OrbitPath,
-    ProceduralPlanet, SpaceCoordinates,
+    calculate_frost_line, map_star_to_system_architecture, resonant_populations, CometActivity,
+    CometReservoir, KeplerOrbit, OrbitPath, ProceduralBody, ProceduralPlanet, SpaceCoordinates,
+    StellarLuminosity, EARTH_MASS_KG, SOLAR_MASS_KG,
 };
+use crate::astronomy::procedural::{moons_for_giant, ring_for_giant, ProceduralMoon};
 use crate::economy::components::{OrbitsBody, SpectralClass, StarSystem};
-use crate::economy::generation::generate_solar_system_resources;
+use crate::economy::generation::{body_seed, generate_solar_system_resources};
 use crate::game_state::GameSeed;
 use crate::plugins::solar_system::{
-    Asteroid, CelestialBody, Comet, Planet, Star,
+    CelestialBody, Comet, LogicalParent, Moon, Planet, Star,
 };
 use crate::plugins::solar_system_data::{AsteroidClass, BodyType};
+use crate::plugins::starmap::{nearby_star_position, SelectedStarSystem, StarSystemIcon, LY_TO_AU};
+use crate::render::asteroid_belt::{spawn_asteroid_belt_field, AsteroidBeltField};
+
+/// Mixed with the system id so every system has its own random stream
+const SYSTEM_SEED_SALT: u64 = 0x8c3f_21d5_a4b7_e609;
 
 pub struct SystemPopulatorPlugin;
 
 impl Plugin for SystemPopulatorPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Startup,
-            populate_nearby_systems.before(generate_solar_system_resources),
+        app.init_resource::<PopulatedSystems>().add_systems(
+            Update,
+            (request_opened_systems, populate_requested_systems)
+                .chain()
+                .before(generate_solar_system_resources),
         );
     }
 }
 
-/// Main system that populates nearby star systems with procedural bodies
-/// This runs after the initial solar system is set up and uses the GameSeed
-/// for deterministic generation
-fn populate_nearby_systems(
+/// Catalog systems whose bodies have been spawned, and those waiting to be
+#[derive(Resource, Debug, Clone, Default)]
+pub struct PopulatedSystems {
+    populated: HashSet<usize>,
+    requested: Vec<usize>,
+}
+
+impl PopulatedSystems {
+    pub fn is_populated(&self, system_id: usize) -> bool {
+        self.populated.contains(&system_id)
+    }
+
+    /// Ask for a system to be populated on this update. Returns false if it
+    /// already is.
+    pub fn request(&mut self, system_id: usize) -> bool {
+        if self.is_populated(system_id) {
+            return false;
+        }
+        if !self.requested.contains(&system_id) {
+            self.requested.push(system_id);
+        }
+        true
+    }
+
+    /// Forget a system whose entities were despawned, so it is populated
+    /// again when next requested
+    pub fn unload(&mut self, system_id: usize) {
+        self.populated.remove(&system_id);
+    }
+}
+
+/// Seed of the random stream a system is populated from
+pub fn system_seed(game_seed: u64, system_id: usize) -> u64 {
+    game_seed ^ (system_id as u64 + 1).wrapping_mul(SYSTEM_SEED_SALT)
+}
+
+/// Id of the catalog system a body belongs to, by name: the system listing
+/// it as a confirmed planet, or else the system of the longest star name the
+/// body's name starts with (generated planets are named after their star)
+pub fn catalog_system_of_body(stars_data: &NearbyStarsData, body_name: &str) -> Option<usize> {
+    let confirmed = stars_data.systems.iter().position(|system| {
+        system
+            .stars
+            .iter()
+            .flat_map(|star| &star.planets)
+            .any(|planet| planet.name == body_name)
+    });
+    let id = confirmed.or_else(|| {
+        stars_data
+            .systems
+            .iter()
+            .enumerate()
+            .flat_map(|(index, system)| system.stars.iter().map(move |star| (index, star)))
+            .filter(|(_, star)| body_name.starts_with(&format!("{} ", star.name)))
+            .max_by_key(|(_, star)| star.name.len())
+            .map(|(index, _)| index)
+    })?;
+    // Catalog ids start at 1; Sol is 0
+    Some(id + 1)
+}
+
+/// System that asks for the system the player entered, or selected on the
+/// starmap, to be populated
+pub fn request_opened_systems(
+    current_system: Res<CurrentStarSystem>,
+    selected_icons: Query<&StarSystemIcon, Added<SelectedStarSystem>>,
+    mut populated: ResMut<PopulatedSystems>,
+) {
+    if current_system.is_changed() && current_system.0 != 0 {
+        populated.request(current_system.0);
+    }
+    for icon in &selected_icons {
+        populated.request(icon.id);
+    }
+}
+
+/// System that populates the requested catalog systems with their stars,
+/// confirmed planets and procedural bodies
+pub fn populate_requested_systems(
     mut commands: Commands,
     stars_data: Res<NearbyStarsData>,
     game_seed: Res<GameSeed>,
-    _current_system: Res<CurrentStarSystem>,
+    mut populated: ResMut<PopulatedSystems>,
 ) {
-    // Use game seed for deterministic generation
-    let mut rng = StdRng::seed_from_u64(game_seed.value);
-
-    info!(
-        "Starting procedural population of nearby star systems with seed {}",
-        game_seed.value
-    );
-
-    // Start at system ID 1 (Sol is 0)
-    let mut system_id = 1;
-
-    for system_data in &stars_data.systems {
-        // Skip if this is the Sol system (already populated)
-        if system_data.system_name == "Sol" {
+    for system_id in std::mem::take(&mut populated.requested) {
+        // Generated systems beyond the catalog are only drawn, not populated
+        let Some(system_data) = stars_data.get_by_id(system_id) else {
+            continue;
+        };
+        populated.populated.insert(system_id);
+        if system_data.stars.is_empty() {
             continue;
         }
 
@@ -71,124 +160,339 @@ fn populate_nearby_systems(
             system_data.stars.len()
         );
 
-        // For simplicity, generate systems in a line along the X-axis in "galactic coordinates"
-        // Each light year = 63,241 AU, place systems at their actual distances
-        let distance_au = (system_data.distance_ly as f64) * 63241.0;
-
-        // Spawn the primary star (first star in the list)
-        if let Some(primary_star) = system_data.stars.first() {
-            let star_position = DVec3::new(distance_au, 0.0, 0.0);
+        // Place systems at their galactic position; systems missing from the
+        // starmap catalog fall back to a line along the X-axis at their distance
+        let star_position = nearby_star_position(&system_data.system_name)
+            .unwrap_or_else(|| DVec3::new(system_data.distance_ly as f64 * LY_TO_AU, 0.0, 0.0));
+
+        let mut rng = StdRng::seed_from_u64(system_seed(game_seed.value, system_id));
+        populate_system(
+            &mut commands,
+            system_data,
+            system_id,
+            star_position,
+            game_seed.value,
+            &mut rng,
+        );
+    }
+}
 
-            // Use real metallicity if available, otherwise generate random
-            let metallicity = primary_star.metallicity.unwrap_or_else(|| {
-                let random_value = rng.gen_range(-0.5..0.5);
-                info!(
-                    "  No metallicity data for '{}', using random: {:.2}",
-                    primary_star.name, random_value
-                );
-                random_value
-            });
+/// Spawn the stars of one system with their orbital hierarchy, its confirmed
+/// planets, and procedural bodies in every dynamically stable zone
+fn populate_system(
+    commands: &mut Commands,
+    system_data: &StarSystemData,
+    system_id: usize,
+    star_position: DVec3,
+    game_seed: u64,
+    rng: &mut StdRng,
+) {
+    let stars = &system_data.stars;
+    let orbits = &system_data.binary_orbits;
+    let hierarchy = StarHierarchy::build(stars.len(), orbits);
+
+    // Use real metallicity if available, otherwise generate random. Stars of
+    // one system formed from the same cloud and share the primary's value.
+    let primary_star = &stars[0];
+    let metallicity = primary_star.metallicity.unwrap_or_else(|| {
+        let random_value = rng.gen_range(-0.5..0.5);
+        info!(
+            "  No metallicity data for '{}', using random: {:.2}",
+            primary_star.name, random_value
+        );
+        random_value
+    });
 
-            if primary_star.metallicity.is_some() {
-                info!(
-                    "  Using real metallicity data for '{}': [Fe/H]={:.2}",
-                    primary_star.name, metallicity
-                );
-            }
+    if primary_star.metallicity.is_some() {
+        info!(
+            "  Using real metallicity data for '{}': [Fe/H]={:.2}",
+            primary_star.name, metallicity
+        );
+    }
 
-            let star_entity = spawn_star_entity_with_metallicity(
-                &mut commands,
-                primary_star,
+    let metallicity_mult = StarSystem::with_metallicity(
+        calculate_frost_line(primary_star.luminosity_sol as f64),
+        spectral_type_to_class(&primary_star.spectral_type),
+        metallicity,
+    )
+    .metallicity_multiplier();
+
+    // One entity per hierarchy node: the stars, then a barycenter per pair
+    let mut node_entities: Vec<Entity> = stars
+        .iter()
+        .map(|star| {
+            spawn_star_entity_with_metallicity(
+                commands,
+                star,
                 system_id,
                 star_position,
-                metallicity,
-            );
+                star.metallicity.unwrap_or(metallicity),
+            )
+        })
+        .collect();
+    for node in stars.len()..hierarchy.nodes.len() {
+        let entity = spawn_barycenter(
+            commands,
+            system_data,
+            &hierarchy,
+            node,
+            system_id,
+            star_position,
+            metallicity,
+        );
+        node_entities.push(entity);
+    }
 
-            // Get the star's frost line and metallicity multiplier
-            let frost_line = calculate_frost_line(primary_star.luminosity_sol as f64);
-            let star_system = StarSystem::with_metallicity(
-                frost_line,
-                spectral_type_to_class(&primary_star.spectral_type),
-                metallicity,
-            );
-            let metallicity_mult = star_system.metallicity_multiplier();
+    // Both halves of each pair circle its barycenter
+    for (node, entry) in hierarchy.nodes.iter().enumerate() {
+        let HierarchyNode::Pair {
+            orbit,
+            primary,
+            secondary,
+        } = *entry
+        else {
+            continue;
+        };
+        let (primary_orbit, secondary_orbit) = pair_orbits(
+            &orbits[orbit],
+            hierarchy.mass_sol(primary, stars),
+            hierarchy.mass_sol(secondary, stars),
+            rng.gen_range(0.0..std::f64::consts::TAU),
+            rng.gen_range(0.0..std::f64::consts::TAU),
+        );
+        commands
+            .entity(node_entities[primary])
+            .insert((primary_orbit, OrbitCenter(node_entities[node])));
+        commands
+            .entity(node_entities[secondary])
+            .insert((secondary_orbit, OrbitCenter(node_entities[node])));
+    }
 
-            // Spawn confirmed planets first
-            let mut existing_orbits = Vec::new();
-            for planet_data in &primary_star.planets {
-                spawn_confirmed_planet(&mut commands, planet_data, star_entity, system_id);
-                existing_orbits.push(planet_data.semi_major_axis_au as f64);
-            }
+    // Companions without a catalogued orbit (like Proxima) sit far out
+    for (i, root) in hierarchy.roots.iter().enumerate().skip(1) {
+        let position = star_position + wide_companion_offset(i);
+        commands
+            .entity(node_entities[*root])
+            .insert(SpaceCoordinates::new(position));
+    }
 
-            // Generate procedural architecture to fill gaps
-            let architecture = map_star_to_system_architecture(
-                &system_data.system_name,
-                primary_star.luminosity_sol as f64,
-                primary_star.planets.len(),
-                &existing_orbits,
-                &mut rng,
+    // Giants (name, orbit, mass in Earths) of every star and pair, which
+    // gather Trojans and resonant families once all planets are known
+    let mut giants: Vec<Vec<(String, KeplerOrbit, f32)>> = vec![Vec::new(); hierarchy.nodes.len()];
+
+    // Confirmed planets orbit the star they are listed under (S-type). The
+    // catalog has no rotation data, so every planet gets a plausible spin.
+    for (star_idx, star) in stars.iter().enumerate() {
+        let frost_line = calculate_frost_line(star.luminosity_sol as f64);
+        for planet_data in &star.planets {
+            let (planet, orbit) = spawn_confirmed_planet(
+                commands,
+                planet_data,
+                node_entities[star_idx],
+                system_id,
+                rng,
             );
-
-            info!(
-                "  Generated {} rocky planets, {} gas giants for '{}'",
-                architecture.rocky_planets.len(),
-                architecture.gas_giants.len(),
-                system_data.system_name
+            let catalog_planet = ConfirmedPlanet::from_catalog(planet_data);
+            let giant = catalog_planet.body_type() == BodyType::GasGiant;
+            let spin = sample_spin(
+                catalog_planet.semi_major_axis_au,
+                star.mass_sol as f64,
+                planet_data.period_days as f64,
+                giant,
+                rng,
             );
-
-            // Spawn procedural planets
-            for planet in &architecture.rocky_planets {
-                spawn_procedural_planet(
-                    &mut commands,
+            commands.entity(planet).insert(spin.components());
+            if giant {
+                spawn_ring_system(
+                    commands,
+                    &catalog_planet.name,
+                    catalog_planet.estimated_radius_earth() * 6371.0,
+                    catalog_planet.semi_major_axis_au > frost_line,
                     planet,
-                    star_entity,
                     system_id,
-                    metallicity_mult,
                 );
-            }
-
-            for planet in &architecture.gas_giants {
-                spawn_procedural_planet(
-                    &mut commands,
+                let moons = moons_for_giant(
+                    &catalog_planet.name,
+                    catalog_planet.estimated_mass_earth(),
+                    catalog_planet.estimated_radius_earth() * 6371.0,
+                    catalog_planet.semi_major_axis_au,
+                    star.mass_sol as f64,
+                    catalog_planet.semi_major_axis_au > frost_line,
+                );
+                let mass_kg = catalog_planet.estimated_mass_earth() as f64 * EARTH_MASS_KG;
+                spawn_moon_system(
+                    commands,
+                    &moons,
                     planet,
-                    star_entity,
+                    mass_kg,
+                    node_entities[star_idx],
                     system_id,
-                    metallicity_mult,
+                    rng,
                 );
+                giants[star_idx].push((
+                    catalog_planet.name.clone(),
+                    orbit,
+                    catalog_planet.estimated_mass_earth(),
+                ));
             }
+        }
+    }
+
+    // Fill the stable zone of every star and pair. Each architecture is
+    // generated from the luminosity its zone receives, so circumbinary
+    // frost lines use the combined light of the pair.
+    let main_root = hierarchy.roots[0];
+    for (node, entry) in hierarchy.nodes.iter().enumerate() {
+        let host = node_entities[node];
+        let (host_name, existing_orbits) = match *entry {
+            HierarchyNode::Star(star) => (
+                stars[star].name.clone(),
+                stars[star]
+                    .planets
+                    .iter()
+                    .map(|p| p.semi_major_axis_au as f64)
+                    .collect::<Vec<_>>(),
+            ),
+            HierarchyNode::Pair { orbit, .. } => (orbits[orbit].label.clone(), Vec::new()),
+        };
+        let luminosity = hierarchy.luminosity_sol(node, stars);
+        let architecture = map_star_to_system_architecture(
+            &host_name,
+            luminosity,
+            existing_orbits.len(),
+            &existing_orbits,
+            rng,
+        );
+        let stable = |sma: f64| {
+            planet_zone(&hierarchy, stars, orbits, node, sma) == PlanetZone::Stable(node)
+        };
+
+        let planets: Vec<_> = architecture
+            .rocky_planets
+            .iter()
+            .chain(&architecture.gas_giants)
+            .filter(|planet| stable(planet.semi_major_axis_au))
+            .collect();
+        info!(
+            "  {} of {} generated planets are stable around '{}'",
+            planets.len(),
+            architecture.rocky_planets.len() + architecture.gas_giants.len(),
+            host_name
+        );
 
-            // Spawn asteroid belt if present
-            if let Some(belt) = &architecture.asteroid_belt {
-                spawn_asteroid_belt(
-                    &mut commands,
-                    belt,
-                    star_entity,
+        let host_mass = hierarchy.mass_sol(node, stars);
+        for planet in planets {
+            let entity =
+                spawn_procedural_planet(commands, planet, host, system_id, metallicity_mult);
+            let spin = sample_spin(
+                planet.semi_major_axis_au,
+                host_mass,
+                planet.period_days,
+                planet.body_type() == BodyType::GasGiant,
+                rng,
+            );
+            commands.entity(entity).insert(spin.components());
+            if planet.body_type() == BodyType::GasGiant {
+                spawn_ring_system(
+                    commands,
+                    &planet.name,
+                    planet.radius_km(),
+                    planet.semi_major_axis_au > architecture.frost_line_au,
+                    entity,
                     system_id,
-                    &system_data.system_name,
-                    game_seed.value,
                 );
-            }
-
-            // Spawn cometary cloud if present
-            if let Some(cloud) = &architecture.cometary_cloud {
-                spawn_cometary_cloud(
-                    &mut commands,
-                    cloud,
-                    star_entity,
+                let moons = moons_for_giant(
+                    &planet.name,
+                    planet.mass_earth,
+                    planet.radius_km(),
+                    planet.semi_major_axis_au,
+                    host_mass,
+                    planet.semi_major_axis_au > architecture.frost_line_au,
+                );
+                spawn_moon_system(
+                    commands,
+                    &moons,
+                    entity,
+                    planet.mass_kg(),
+                    host,
                     system_id,
-                    &system_data.system_name,
-                    game_seed.value,
+                    rng,
                 );
+                giants[node].push((
+                    planet.name.clone(),
+                    planet.to_kepler_orbit(),
+                    planet.mass_earth,
+                ));
+            }
+        }
+        spawn_resonant_populations(
+            commands,
+            &mut giants[node],
+            host,
+            system_id,
+            game_seed,
+            stable,
+        );
+
+        // Spawn asteroid belt if it fits in the stable zone
+        if let Some(belt) = &architecture.asteroid_belt {
+            if stable(belt.inner_au) && stable(belt.outer_au) {
+                spawn_asteroid_belt(commands, belt, host, system_id, &host_name, game_seed);
             }
         }
 
-        system_id += 1;
+        // The cometary cloud surrounds the whole system
+        if node == main_root {
+            if let Some(cloud) = &architecture.cometary_cloud {
+                spawn_cometary_cloud(commands, cloud, host, system_id, &host_name, game_seed);
+            }
+        }
     }
+}
+
+/// Spawn the barycenter of a pair: a point that its two halves orbit and
+/// circumbinary bodies use as their star, lit by the pair's combined
+/// luminosity
+fn spawn_barycenter(
+    commands: &mut Commands,
+    system_data: &StarSystemData,
+    hierarchy: &StarHierarchy,
+    node: usize,
+    system_id: usize,
+    position: DVec3,
+    metallicity: f32,
+) -> Entity {
+    let stars = &system_data.stars;
+    let label = match hierarchy.nodes[node] {
+        HierarchyNode::Pair { orbit, .. } => system_data.binary_orbits[orbit].label.clone(),
+        HierarchyNode::Star(star) => stars[star].name.clone(),
+    };
+    let luminosity = hierarchy.luminosity_sol(node, stars);
+    let brightest = hierarchy
+        .stars_in(node)
+        .into_iter()
+        .max_by(|a, b| stars[*a].luminosity_sol.total_cmp(&stars[*b].luminosity_sol))
+        .unwrap_or(0);
+    let frost_line = calculate_frost_line(luminosity);
 
     info!(
-        "Completed procedural population of {} star systems",
-        system_id - 1
+        "  Barycenter '{}': combined L={:.3}L☉, frost_line={:.2}AU",
+        label, luminosity, frost_line
     );
+
+    commands
+        .spawn((
+            Name::new(format!("{} barycenter", label)),
+            SpaceCoordinates::new(position),
+            SystemId(system_id),
+            StarSystem::with_metallicity(
+                frost_line,
+                spectral_type_to_class(&stars[brightest].spectral_type),
+                metallicity,
+            ),
+            StellarLuminosity(luminosity),
+        ))
+        .id()
 }
 
 /// Spawn a star entity with its system properties and custom metallicity
@@ -229,60 +533,52 @@ pub fn spawn_star_entity_with_metallicity(
             SpaceCoordinates::new(position),
             SystemId(system_id),
             star_system,
+            StellarLuminosity(star_data.luminosity_sol as f64),
         ))
         .id();
 
     entity
 }
 
-/// Spawn a confirmed planet from real exoplanet data
+/// Spawn a confirmed planet from real exoplanet data, with the catalog's
+/// orbital elements, mass and radius (estimated where unmeasured). Returns
+/// the planet and its orbit.
 pub fn spawn_confirmed_planet(
     commands: &mut Commands,
     planet_data: &PlanetData,
     parent_star: Entity,
     system_id: usize,
-) -> Entity {
-    // Calculate orbital parameters
-    let period_seconds = (planet_data.period_days as f64) * 86400.0;
-    let mean_motion = std::f64::consts::TAU / period_seconds;
-
-    let orbit = KeplerOrbit::new(
-        planet_data.eccentricity as f64,
-        planet_data.semi_major_axis_au as f64,
-        0.0, // Inclination not provided, assume coplanar
-        0.0, // Random longitude of ascending node
-        0.0, // Random argument of periapsis
-        0.0, // Random mean anomaly
-        mean_motion,
-    );
-
-    // Estimate radius and mass
-    let radius_earth = planet_data.radius_earth.unwrap_or(1.0);
-    let mass_earth = planet_data.mass_earth;
+    rng: &mut impl Rng,
+) -> (Entity, KeplerOrbit) {
+    let planet = ConfirmedPlanet::from_catalog(planet_data);
+    let orbit = planet.kepler_orbit(rng);
 
     // Convert to SI units
-    const EARTH_MASS_KG: f64 = 5.972e24;
     const EARTH_RADIUS_KM: f32 = 6371.0;
-    let mass_kg = (mass_earth as f64) * EARTH_MASS_KG;
-    let radius_km = radius_earth * EARTH_RADIUS_KM;
+    let mass_kg = (planet.estimated_mass_earth() as f64) * EARTH_MASS_KG;
+    let radius_km = planet.estimated_radius_earth() * EARTH_RADIUS_KM;
 
     info!(
         "Spawning confirmed planet '{}': a={:.2}AU, M={:.1}M⊕, type={}",
-        planet_data.name,
-        planet_data.semi_major_axis_au,
-        planet_data.mass_earth,
-        planet_data.planet_type
+        planet.name,
+        planet.semi_major_axis_au,
+        planet.estimated_mass_earth(),
+        planet.planet_type
     );
 
     let entity = commands
         .spawn((
             Planet,
             RealPlanet, // Mark as confirmed planet
+            ExoplanetDiscovery {
+                method: planet.discovery_method.clone(),
+                year: planet.discovery_year,
+            },
             CelestialBody {
-                name: planet_data.name.clone(),
+                name: planet.name.clone(),
                 mass: mass_kg,
                 radius: radius_km,
-                body_type: BodyType::Planet,
+                body_type: planet.body_type(),
                 visual_radius: radius_km,
                 asteroid_class: None,
             },
@@ -295,7 +591,7 @@ pub fn spawn_confirmed_planet(
         ))
         .id();
 
-    entity
+    (entity, orbit)
 }
 
 /// Spawn a procedurally generated planet
@@ -331,6 +627,7 @@ pub fn spawn_procedural_planet(
                 asteroid_class: None,
             },
             orbit,
+            ProceduralBody,
             OrbitPath::new(Color::srgba(0.5, 0.7, 1.0, 0.4)),
             SpaceCoordinates::default(), // Will be updated by propagate_orbits
             OrbitCenter(parent_star),    // Link to parent star for orbital hierarchy
@@ -345,7 +642,135 @@ pub fn spawn_procedural_planet(
     entity
 }
 
-/// Spawn asteroids in a belt
+/// Give a giant planet its ring system, if it has one (see
+/// [`ring_for_giant`]). Rings are data-only here like the planets; they carry
+/// their own ice and dust deposits.
+fn spawn_ring_system(
+    commands: &mut Commands,
+    planet_name: &str,
+    planet_radius_km: f32,
+    beyond_frost_line: bool,
+    planet: Entity,
+    system_id: usize,
+) -> Option<Entity> {
+    let (ring, mass_kg) = ring_for_giant(planet_name, planet_radius_km, beyond_frost_line)?;
+    info!(
+        "Spawning rings of '{}': {:.0}-{:.0} km, {:.0}% ice",
+        planet_name,
+        ring.inner_radius_km,
+        ring.outer_radius_km,
+        ring.ice_fraction * 100.0
+    );
+
+    let entity = commands
+        .spawn((
+            ring,
+            CelestialBody {
+                name: format!("{} Rings", planet_name),
+                mass: mass_kg,
+                radius: ring.outer_radius_km,
+                body_type: BodyType::Ring,
+                visual_radius: ring.outer_radius_km,
+                asteroid_class: None,
+            },
+            ProceduralBody,
+            LogicalParent(planet),
+            SystemId(system_id),
+        ))
+        .id();
+    Some(entity)
+}
+
+/// Spawn the moons of a giant planet (see [`moons_for_giant`]). Like the
+/// planets they are data-only bodies; they orbit the planet but belong to
+/// its star, whose light and frost line their deposits are generated from.
+/// Close-in moons are tidally locked.
+fn spawn_moon_system(
+    commands: &mut Commands,
+    moons: &[ProceduralMoon],
+    planet: Entity,
+    planet_mass_kg: f64,
+    star: Entity,
+    system_id: usize,
+    rng: &mut impl Rng,
+) {
+    let planet_mass_sol = planet_mass_kg / SOLAR_MASS_KG;
+
+    for moon in moons {
+        info!(
+            "Spawning moon '{}': a={:.0} km, P={:.1} d, R={:.0} km{}",
+            moon.name,
+            moon.semi_major_axis_au * crate::astronomy::visual_scale::AU_KM,
+            moon.period_days,
+            moon.radius_km,
+            if moon.captured { ", captured" } else { "" }
+        );
+        let spin = sample_spin(
+            moon.semi_major_axis_au,
+            planet_mass_sol,
+            moon.period_days,
+            false,
+            rng,
+        );
+        commands.spawn((
+            (
+                Moon,
+                CelestialBody {
+                    name: moon.name.clone(),
+                    mass: moon.mass_kg,
+                    radius: moon.radius_km,
+                    body_type: BodyType::Moon,
+                    visual_radius: moon.radius_km,
+                    asteroid_class: None,
+                },
+                moon.to_kepler_orbit(),
+                ProceduralBody,
+                OrbitPath::new(Color::srgba(0.6, 0.6, 0.8, 0.3)),
+                SpaceCoordinates::default(),
+                OrbitCenter(planet),
+                OrbitsBody::new(star),
+                SystemId(system_id),
+            ),
+            spin.components(),
+        ));
+    }
+}
+
+/// Spawn the Trojan swarms and resonant families of the giants around one
+/// star or pair (see [`resonant_populations`]), where they are stable
+fn spawn_resonant_populations(
+    commands: &mut Commands,
+    giants: &mut [(String, KeplerOrbit, f32)],
+    host: Entity,
+    system_id: usize,
+    game_seed: u64,
+    stable: impl Fn(f64) -> bool,
+) {
+    giants.sort_by(|a, b| a.1.semi_major_axis.total_cmp(&b.1.semi_major_axis));
+    let last = giants.len().saturating_sub(1);
+    for (i, (name, orbit, mass_earth)) in giants.iter().enumerate() {
+        let belts = resonant_populations(name, orbit, *mass_earth, i == 0, i == last);
+        for (j, belt) in belts.into_iter().enumerate() {
+            if !stable(belt.inner_au) || !stable(belt.outer_au) {
+                continue;
+            }
+            info!(
+                "Spawning resonant asteroids of '{}': {:.2}-{:.2} AU, {} asteroids",
+                name, belt.inner_au, belt.outer_au, belt.count
+            );
+            let seed = body_seed(game_seed, name) ^ (j as u64 + 1);
+            spawn_asteroid_belt_field(
+                commands,
+                AsteroidBeltField::new(belt, host, system_id, name, seed),
+            );
+        }
+    }
+}
+
+/// Spawn an asteroid belt as a batched field.
+///
+/// Individual asteroids are not spawned here; the belt renderer draws the
+/// whole population and promotes members to real entities when zoomed in.
 pub fn spawn_asteroid_belt(
     commands: &mut Commands,
     belt: &crate::astronomy::AsteroidBelt,
@@ -354,76 +779,27 @@ pub fn spawn_asteroid_belt(
     star_name: &str,
     game_seed: u64,
 ) {
-    // Deterministic RNG seeded from system_id and belt properties to ensure reproducible generation
+    // Deterministic seed from system_id and belt properties to ensure reproducible generation
     let seed = game_seed
         .wrapping_mul(system_id as u64)
         .wrapping_mul(0x9E37_79B9_7F4A_7C15)
         ^ (belt.count as u64)
         ^ belt.inner_au.to_bits()
         ^ belt.outer_au.to_bits();
-    let mut rng = StdRng::seed_from_u64(seed);
 
     info!(
         "Spawning asteroid belt: {:.2}-{:.2} AU, {} asteroids",
         belt.inner_au, belt.outer_au, belt.count
     );
 
-    for i in 0..belt.count {
-        // Random orbital parameters within the belt
-        let semi_major_axis = rng.gen_range(belt.inner_au..belt.outer_au);
-        let eccentricity = rng.gen_range(0.0..0.2);
-        let inclination = belt.inclination + rng.gen_range(-0.05..0.05);
-
-        // Calculate orbital period using Kepler's third law
-        let period_years = semi_major_axis.powf(1.5);
-        let period_seconds = period_years * 365.25 * 86400.0;
-        let mean_motion = std::f64::consts::TAU / period_seconds;
-
-        let orbit = KeplerOrbit::new(
-            eccentricity,
-            semi_major_axis,
-            inclination,
-            rng.gen_range(0.0..std::f64::consts::TAU),
-            rng.gen_range(0.0..std::f64::consts::TAU),
-            rng.gen_range(0.0..std::f64::consts::TAU),
-            mean_motion,
-        );
-
-        // Determine asteroid class (MType, SType, VType for inner belt)
-        let asteroid_class = if rng.gen_bool(0.3) {
-            AsteroidClass::MType // Metal-rich
-        } else if rng.gen_bool(0.6) {
-            AsteroidClass::SType // Silicate-rich
-        } else {
-            AsteroidClass::VType // Basaltic
-        };
-
-        // Random size (radius 0.1 - 50 km)
-        let radius = rng.gen_range(0.1..50.0);
-        // Rough mass estimate (density ~2500 kg/m³)
-        let mass = (4.0 / 3.0) * std::f64::consts::PI * (radius as f64 * 1000.0).powi(3) * 2500.0;
-
-        commands.spawn((
-            Asteroid,
-            CelestialBody {
-                name: format!("{} Belt Asteroid {}", star_name, i + 1),
-                mass,
-                radius,
-                body_type: BodyType::Asteroid,
-                visual_radius: radius,
-                asteroid_class: Some(asteroid_class),
-            },
-            orbit,
-            OrbitPath::new(Color::srgba(0.6, 0.6, 0.5, 0.2)),
-            SpaceCoordinates::default(), // Will be updated by propagate_orbits
-            OrbitCenter(parent_star),    // Link to parent star for orbital hierarchy
-            OrbitsBody::new(parent_star),
-            SystemId(system_id),
-        ));
-    }
+    spawn_asteroid_belt_field(
+        commands,
+        AsteroidBeltField::new(belt.clone(), parent_star, system_id, star_name, seed),
+    );
 }
 
-/// Spawn comets in a cloud
+/// Spawn comets in a cloud, which also becomes the star's
+/// [`CometReservoir`] of long-period comets
 pub fn spawn_cometary_cloud(
     commands: &mut Commands,
     cloud: &crate::astronomy::CometaryCloud,
@@ -440,11 +816,17 @@ pub fn spawn_cometary_cloud(
         ^ cloud.inner_au.to_bits()
         ^ cloud.outer_au.to_bits();
     let mut rng = StdRng::seed_from_u64(seed);
+    // Separate stream for activity so orbits stay identical to earlier seeds
+    let mut activity_rng = StdRng::seed_from_u64(seed ^ 0xA5A5_5A5A_C3C3_3C3C);
 
     info!(
         "Spawning cometary cloud: {:.2}-{:.2} AU, {} comets",
         cloud.inner_au, cloud.outer_au, cloud.count
     );
+    // The cloud keeps sending long-period comets in over the game
+    commands
+        .entity(parent_star)
+        .insert(CometReservoir::new(cloud.clone()));
 
     for i in 0..cloud.count {
         // Random orbital parameters within the cloud (spherical distribution)
@@ -472,6 +854,12 @@ pub fn spawn_cometary_cloud(
         // Low density ice/rock (density ~500 kg/m³)
         let mass = (4.0 / 3.0) * std::f64::consts::PI * (radius as f64 * 1000.0).powi(3) * 500.0;
 
+        // Volatile content varies: some nuclei switch on early, some are dust-dominated
+        let activity = CometActivity {
+            activation_radius_au: activity_rng.gen_range(3.0..6.0),
+            dust_fraction: activity_rng.gen_range(0.3..0.7),
+        };
+
         commands.spawn((
             Comet,
             CelestialBody {
@@ -488,6 +876,8 @@ pub fn spawn_cometary_cloud(
             OrbitCenter(parent_star),    // Link to parent star for orbital hierarchy
             OrbitsBody::new(parent_star),
             SystemId(system_id),
+            activity,
+            ProceduralBody,
         ));
     }
 }
@@ -518,4 +908,41 @@ mod tests {
         assert_eq!(spectral_type_to_class("K1V"), SpectralClass::K);
         assert_eq!(spectral_type_to_class("A5"), SpectralClass::A);
     }
+
+    #[test]
+    fn test_catalog_system_of_body() {
+        let systems: Vec<StarSystemData> = serde_json::from_str(
+            r#"[
+                {"system_name": "Alpha Centauri", "distance_ly": 4.37, "stars": [
+                    {"name": "Alpha Centauri A", "spectral_type": "G2V", "mass_sol": 1.1,
+                     "radius_sol": 1.22, "temp_k": 5790, "luminosity_sol": 1.52},
+                    {"name": "Proxima Centauri", "spectral_type": "M5.5Ve", "mass_sol": 0.12,
+                     "radius_sol": 0.15, "temp_k": 3042, "luminosity_sol": 0.0017,
+                     "planets": [{"name": "Proxima Centauri b", "mass_earth": 1.07,
+                                  "radius_earth": null, "period_days": 11.19,
+                                  "semi_major_axis_au": 0.0485, "eccentricity": 0.02,
+                                  "type": "Terrestrial"}]}
+                ]},
+                {"system_name": "Barnard's Star", "distance_ly": 5.96, "stars": [
+                    {"name": "Barnard's Star", "spectral_type": "M4.0Ve", "mass_sol": 0.16,
+                     "radius_sol": 0.19, "temp_k": 3134, "luminosity_sol": 0.0035}
+                ]}
+            ]"#,
+        )
+        .unwrap();
+        let stars_data = NearbyStarsData { systems };
+
+        let system_of = |name| catalog_system_of_body(&stars_data, name);
+        assert_eq!(system_of("Proxima Centauri b"), Some(1));
+        assert_eq!(system_of("Alpha Centauri A c"), Some(1));
+        assert_eq!(system_of("Barnard's Star d"), Some(2));
+        assert_eq!(system_of("Mars"), None);
+
+        let mut populated = PopulatedSystems::default();
+        assert!(populated.request(2));
+        assert!(populated.request(2));
+        assert_eq!(populated.requested, vec![2]);
+        populated.populated.insert(2);
+        assert!(!populated.request(2));
+    }
 }
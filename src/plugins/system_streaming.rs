@@ -0,0 +1,224 @@
+//! System Streaming Plugin
+//!
+//! Only the star system in view keeps its bodies as fully simulated
+//! entities. When the player moves to another system:
+//! - bodies of the systems left behind that hold anything of the player's
+//!   (colonists, survey results, buildings, stations, fleets, survey
+//!   missions or the selection) are marked [`Dormant`], so their orbits are
+//!   sampled once a day instead of every tick;
+//! - every other system is collapsed into a [`SystemSummary`] of names and
+//!   types, and its entities are despawned.
+//!
+//! Entering a system wakes its dormant bodies. Collapsed catalog systems are
+//! populated again from their seed by the system populator, and the starmap
+//! respawns their meshes, so they come back as they were.
+
+use bevy::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+use crate::astronomy::components::{CurrentStarSystem, Dormant, Selected, SystemId};
+use crate::construction::{Building, OrbitalStation};
+use crate::economy::components::{Population, SurveyLevel};
+use crate::economy::SurveyMissions;
+use crate::fleets::FleetLocation;
+use crate::plugins::solar_system::{CelestialBody, LogicalParent};
+use crate::plugins::solar_system_data::BodyType;
+use crate::plugins::system_populator::{populate_requested_systems, PopulatedSystems};
+
+pub struct SystemStreamingPlugin;
+
+impl Plugin for SystemStreamingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<StreamedSystems>().add_systems(
+            Update,
+            (stream_systems, put_new_bodies_to_sleep)
+                .chain()
+                .after(populate_requested_systems),
+        );
+    }
+}
+
+/// One body or star of a collapsed system
+#[derive(Debug, Clone)]
+pub struct BodySummary {
+    pub name: String,
+    pub body_type: BodyType,
+}
+
+/// What is left of a star system whose entities were despawned
+#[derive(Debug, Clone, Default)]
+pub struct SystemSummary {
+    pub bodies: Vec<BodySummary>,
+}
+
+impl SystemSummary {
+    /// Number of bodies
+    pub fn body_count(&self) -> usize {
+        self.bodies.len()
+    }
+}
+
+/// Summaries of the collapsed star systems, by system id
+#[derive(Resource, Debug, Clone, Default)]
+pub struct StreamedSystems {
+    pub summaries: HashMap<usize, SystemSummary>,
+}
+
+impl StreamedSystems {
+    pub fn get(&self, system_id: usize) -> Option<&SystemSummary> {
+        self.summaries.get(&system_id)
+    }
+}
+
+/// Systems with anything of the player's in them: a body with colonists,
+/// survey results or the selection, or one the player's buildings, stations,
+/// fleets and probes are tied to
+#[allow(clippy::type_complexity)]
+fn occupied_systems(
+    entities: &Query<(
+        Entity,
+        &SystemId,
+        Option<&Population>,
+        Option<&SurveyLevel>,
+        Has<Selected>,
+    )>,
+    buildings: &Query<&LogicalParent, With<Building>>,
+    stations: &Query<&OrbitalStation>,
+    fleets: &Query<&FleetLocation>,
+    surveys: Option<&SurveyMissions>,
+) -> HashSet<usize> {
+    let mut anchors: HashSet<Entity> = buildings.iter().map(|parent| parent.0).collect();
+    anchors.extend(stations.iter().map(|station| station.host));
+    for location in fleets {
+        match location {
+            FleetLocation::Orbiting(body) => {
+                anchors.insert(*body);
+            }
+            FleetLocation::InTransit(transfer) => {
+                anchors.extend([transfer.origin, transfer.destination]);
+            }
+        }
+    }
+    if let Some(surveys) = surveys {
+        anchors.extend(surveys.missions.iter().map(|mission| mission.target));
+        anchors.extend(surveys.requested.iter().copied());
+    }
+
+    entities
+        .iter()
+        .filter(|(entity, _, population, survey, selected)| {
+            population.is_some_and(|p| p.count > 0.0)
+                || survey.is_some_and(|level| *level != SurveyLevel::Unsurveyed)
+                || *selected
+                || anchors.contains(entity)
+        })
+        .map(|(_, system_id, ..)| system_id.0)
+        .collect()
+}
+
+/// System that wakes the system the player moved to and puts the others to
+/// sleep, collapsing those without anything of the player's in them. Sol,
+/// which is not generated, is never collapsed.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn stream_systems(
+    mut commands: Commands,
+    current_system: Res<CurrentStarSystem>,
+    mut streamed: ResMut<StreamedSystems>,
+    mut populated: ResMut<PopulatedSystems>,
+    entities: Query<(
+        Entity,
+        &SystemId,
+        Option<&Population>,
+        Option<&SurveyLevel>,
+        Has<Selected>,
+    )>,
+    bodies: Query<(
+        Option<&CelestialBody>,
+        Has<Handle<Mesh>>,
+        Has<Parent>,
+        Has<Dormant>,
+    )>,
+    buildings: Query<&LogicalParent, With<Building>>,
+    stations: Query<&OrbitalStation>,
+    fleets: Query<&FleetLocation>,
+    surveys: Option<Res<SurveyMissions>>,
+) {
+    if !current_system.is_changed() || current_system.is_added() {
+        return;
+    }
+    streamed.summaries.remove(&current_system.0);
+    let occupied = occupied_systems(
+        &entities,
+        &buildings,
+        &stations,
+        &fleets,
+        surveys.as_deref(),
+    );
+
+    let mut collapsing: HashMap<usize, Vec<Entity>> = HashMap::new();
+    for (entity, system_id, ..) in &entities {
+        let id = system_id.0;
+        let Ok((.., dormant)) = bodies.get(entity) else {
+            continue;
+        };
+        if id == current_system.0 {
+            if dormant {
+                commands.entity(entity).remove::<Dormant>();
+            }
+        } else if id == 0 || occupied.contains(&id) {
+            if !dormant {
+                commands.entity(entity).insert(Dormant);
+            }
+        } else {
+            collapsing.entry(id).or_default().push(entity);
+        }
+    }
+
+    for (id, members) in collapsing {
+        // The starmap's meshes duplicate the bodies of populated systems
+        let data_only = populated.is_populated(id);
+        let summary = SystemSummary {
+            bodies: members
+                .iter()
+                .filter_map(|&entity| match bodies.get(entity) {
+                    Ok((Some(body), mesh, ..)) if !(data_only && mesh) => Some(BodySummary {
+                        name: body.name.clone(),
+                        body_type: body.body_type,
+                    }),
+                    _ => None,
+                })
+                .collect(),
+        };
+
+        info!(
+            "Collapsing system {} into a summary of {} bodies",
+            id,
+            summary.body_count()
+        );
+        // Children go with their parents
+        for entity in members {
+            if !bodies.get(entity).is_ok_and(|(.., child, _)| child) {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+        populated.unload(id);
+        if !summary.bodies.is_empty() {
+            streamed.summaries.insert(id, summary);
+        }
+    }
+}
+
+/// System that puts bodies spawned outside the system in view to sleep, such
+/// as those of a system selected on the starmap or settled by a scenario
+#[allow(clippy::type_complexity)]
+pub fn put_new_bodies_to_sleep(
+    mut commands: Commands,
+    current_system: Res<CurrentStarSystem>,
+    new_bodies: Query<(Entity, &SystemId), (Added<SystemId>, Without<Dormant>)>,
+) {
+    for (entity, system_id) in &new_bodies {
+        if system_id.0 != current_system.0 {
+            commands.entity(entity).insert(Dormant);
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+
+use super::types::{PolicyDefinition, PolicyId, PolicyScope};
+
+/// Resource containing all policy definitions loaded from data
+#[derive(Resource, Debug, Clone, Default)]
+pub struct PoliciesData {
+    /// All policies indexed by ID
+    pub policies: HashMap<PolicyId, PolicyDefinition>,
+    /// Policy IDs in data file order, for a stable listing in the UI
+    pub order: Vec<PolicyId>,
+}
+
+impl PoliciesData {
+    /// Get a policy definition by ID
+    pub fn get(&self, id: &str) -> Option<&PolicyDefinition> {
+        self.policies.get(id)
+    }
+
+    /// Iterate policy definitions in data file order
+    pub fn iter(&self) -> impl Iterator<Item = &PolicyDefinition> {
+        self.order.iter().filter_map(|id| self.policies.get(id))
+    }
+
+    /// Add a definition, keeping the file order
+    pub fn insert(&mut self, definition: PolicyDefinition) {
+        if !self.policies.contains_key(&definition.id) {
+            self.order.push(definition.id.clone());
+        }
+        self.policies.insert(definition.id.clone(), definition);
+    }
+}
+
+/// Structure of the policies data file
+#[derive(Debug, Deserialize)]
+struct PoliciesFile {
+    policies: Vec<PolicyDefinition>,
+}
+
+/// Check that a policy can be enacted and paid for: upkeep must be positive,
+/// and only empire policies can carry civilization-wide modifiers
+pub fn validate_policy(definition: &PolicyDefinition) -> Result<(), String> {
+    if definition.scope == PolicyScope::Colony && !definition.modifiers.is_empty() {
+        return Err(format!(
+            "colony edict '{}' has civilization-wide modifiers",
+            definition.id
+        ));
+    }
+    if definition
+        .upkeep
+        .iter()
+        .any(|u| !u.mt_per_year().is_finite() || u.mt_per_year() <= 0.0)
+    {
+        return Err(format!(
+            "policy '{}' has a non-positive upkeep",
+            definition.id
+        ));
+    }
+    if definition.excludes.contains(&definition.id) {
+        return Err(format!("policy '{}' excludes itself", definition.id));
+    }
+    Ok(())
+}
+
+/// System to load policy definitions from data file at startup
+pub fn load_policies(mut commands: Commands) {
+    info!("Loading policies...");
+
+    let path = "assets/data/policies.ron";
+    let mut policies_data = PoliciesData::default();
+
+    match fs::read_to_string(path) {
+        Ok(contents) => match ron::from_str::<PoliciesFile>(&contents) {
+            Ok(data) => {
+                for definition in data.policies {
+                    match validate_policy(&definition) {
+                        Ok(()) => policies_data.insert(definition),
+                        Err(e) => error!("Skipping invalid policy definition: {}", e),
+                    }
+                }
+                info!("Loaded {} policies", policies_data.order.len());
+            }
+            Err(e) => error!("Failed to parse policies data file: {}", e),
+        },
+        Err(e) => warn!(
+            "Policies data file not found at {}: {}. No policies will be available.",
+            path, e
+        ),
+    }
+
+    // Without the file there are simply no policies to enact
+    commands.insert_resource(policies_data);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policies_file_parses_and_validates() {
+        let contents = fs::read_to_string("assets/data/policies.ron").unwrap();
+        let data: PoliciesFile = ron::from_str(&contents).unwrap();
+
+        assert!(!data.policies.is_empty());
+        for definition in &data.policies {
+            validate_policy(definition).unwrap();
+            for excluded in &definition.excludes {
+                assert!(
+                    data.policies.iter().any(|p| &p.id == excluded),
+                    "policy '{}' excludes unknown policy '{}'",
+                    definition.id,
+                    excluded
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,39 @@
+//! Government policies and edicts
+//!
+//! Provides data-driven policies:
+//! - Empire-wide policies and per-colony edicts (research focus, austerity,
+//!   crash industrialization, martial law) loaded from
+//!   `assets/data/policies.ron`
+//! - Policy slots unlocked by researching Sociology technologies
+//! - Trade-offs applied as civilization modifiers and as stability and
+//!   output changes of the colonies a policy covers
+//! - Upkeep paid from the stockpile on economy ticks; unpaid policies lapse
+
+use bevy::prelude::*;
+
+pub mod data;
+pub mod systems;
+pub mod types;
+
+pub use data::{load_policies, PoliciesData};
+pub use systems::{
+    pay_policy_upkeep, policy_slots, process_policy_actions, EnactedPolicy, PendingPolicyActions,
+    Policies,
+};
+pub use types::{PolicyDefinition, PolicyId, PolicyScope, PolicyUpkeep};
+
+/// Plugin that adds policies and edicts to the Bevy app
+pub struct PoliciesPlugin;
+
+impl Plugin for PoliciesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Policies>()
+            .init_resource::<PendingPolicyActions>()
+            .add_systems(Startup, load_policies)
+            .add_systems(
+                FixedUpdate,
+                pay_policy_upkeep.after(crate::economy::advance_economy_clock),
+            )
+            .add_systems(Update, process_policy_actions);
+    }
+}
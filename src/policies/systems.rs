@@ -0,0 +1,471 @@
+use bevy::prelude::*;
+
+use super::data::PoliciesData;
+use super::types::{PolicyDefinition, PolicyId, PolicyScope, PolicyUpkeep};
+use crate::economy::components::Population;
+use crate::economy::{EconomyClock, GlobalBudget};
+use crate::game_state::{EventLog, LogSeverity};
+use crate::plugins::solar_system::CelestialBody;
+use crate::research::{ResearchState, TechCategory, TechnologiesData};
+
+/// A policy in force
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnactedPolicy {
+    pub id: PolicyId,
+    /// Colony an edict was enacted for; `None` for empire policies
+    pub colony: Option<Entity>,
+}
+
+/// Policies in force, in the order they were enacted
+#[derive(Resource, Debug, Clone, Default)]
+pub struct Policies {
+    pub enacted: Vec<EnactedPolicy>,
+}
+
+impl Policies {
+    /// Whether a policy is in force in a scope (`None` for the empire)
+    pub fn is_enacted(&self, id: &str, colony: Option<Entity>) -> bool {
+        self.enacted
+            .iter()
+            .any(|p| p.id == id && p.colony == colony)
+    }
+
+    /// Number of slots taken in a scope (`None` for the empire)
+    pub fn slots_used(&self, colony: Option<Entity>) -> usize {
+        self.enacted.iter().filter(|p| p.colony == colony).count()
+    }
+
+    /// Policies that apply to a colony: every empire policy and its own edicts
+    pub fn covering(&self, colony: Entity) -> impl Iterator<Item = &EnactedPolicy> {
+        self.enacted
+            .iter()
+            .filter(move |p| p.colony.is_none_or(|c| c == colony))
+    }
+
+    /// Stability points the policies in force give (or cost) a colony
+    pub fn stability_for(&self, colony: Entity, data: &PoliciesData) -> f64 {
+        self.covering(colony)
+            .filter_map(|p| data.get(&p.id))
+            .map(|def| def.stability)
+            .sum()
+    }
+
+    /// Change of a colony's output from the policies in force, in percent
+    pub fn output_for(&self, colony: Entity, data: &PoliciesData) -> f64 {
+        self.covering(colony)
+            .filter_map(|p| data.get(&p.id))
+            .map(|def| def.output)
+            .sum()
+    }
+
+    /// Check whether a policy can be enacted in a scope given the number of
+    /// slots the civilization has
+    pub fn check_enact(
+        &self,
+        definition: &PolicyDefinition,
+        colony: Option<Entity>,
+        slots: usize,
+        research: &ResearchState,
+    ) -> Result<(), String> {
+        match (definition.scope, colony) {
+            (PolicyScope::Empire, Some(_)) => {
+                return Err(format!("{} is an empire-wide policy", definition.name))
+            }
+            (PolicyScope::Colony, None) => {
+                return Err(format!("{} is enacted per colony", definition.name))
+            }
+            _ => {}
+        }
+        if let Some(tech) = &definition.required_tech {
+            if !research.is_unlocked(tech) {
+                return Err(format!("{} requires further research", definition.name));
+            }
+        }
+        if self.is_enacted(&definition.id, colony) {
+            return Err(format!("{} is already in force", definition.name));
+        }
+        if let Some(conflict) = self
+            .enacted
+            .iter()
+            .filter(|p| p.colony == colony)
+            .find(|p| definition.excludes.contains(&p.id))
+        {
+            return Err(format!(
+                "{} conflicts with {}",
+                definition.name, conflict.id
+            ));
+        }
+        if self.slots_used(colony) >= slots {
+            return Err("No free policy slot".to_string());
+        }
+        Ok(())
+    }
+
+    /// Put a policy into force and apply its modifiers
+    pub fn enact(
+        &mut self,
+        definition: &PolicyDefinition,
+        colony: Option<Entity>,
+        research: &mut ResearchState,
+    ) {
+        for modifier in &definition.modifiers {
+            research.add_modifier(modifier.modifier_type.clone(), modifier.value);
+        }
+        self.enacted.push(EnactedPolicy {
+            id: definition.id.clone(),
+            colony,
+        });
+    }
+
+    /// Repeal a policy and take back its modifiers. Returns false if it was
+    /// not in force.
+    pub fn revoke(
+        &mut self,
+        definition: &PolicyDefinition,
+        colony: Option<Entity>,
+        research: &mut ResearchState,
+    ) -> bool {
+        let Some(index) = self
+            .enacted
+            .iter()
+            .position(|p| p.id == definition.id && p.colony == colony)
+        else {
+            return false;
+        };
+        self.enacted.remove(index);
+        for modifier in &definition.modifiers {
+            research.add_modifier(modifier.modifier_type.clone(), -modifier.value);
+        }
+        true
+    }
+}
+
+/// Policy slots per scope: one for every Sociology technology researched.
+/// The empire and every colony each have this many.
+pub fn policy_slots(research: &ResearchState, technologies: &TechnologiesData) -> usize {
+    research
+        .unlocked_technologies
+        .iter()
+        .filter_map(|id| technologies.get_tech(id))
+        .filter(|tech| tech.category == TechCategory::Sociology)
+        .count()
+}
+
+/// Policy changes requested from the Policies tab, applied by
+/// [`process_policy_actions`]
+#[derive(Resource, Debug, Clone, Default)]
+pub struct PendingPolicyActions {
+    /// (policy, colony) pairs to enact; `None` for empire policies
+    pub enact: Vec<(PolicyId, Option<Entity>)>,
+    /// (policy, colony) pairs to repeal
+    pub revoke: Vec<(PolicyId, Option<Entity>)>,
+}
+
+/// Name of a colony for log messages
+fn colony_name(names: &Query<(Option<&CelestialBody>, Option<&Name>)>, colony: Entity) -> String {
+    names
+        .get(colony)
+        .ok()
+        .and_then(|(body, name)| {
+            body.map(|b| b.name.clone())
+                .or_else(|| name.map(|n| n.to_string()))
+        })
+        .unwrap_or_else(|| "a colony".to_string())
+}
+
+/// Title of a policy in a scope, e.g. "Martial Law on Luna"
+fn scoped_title(
+    definition: &PolicyDefinition,
+    colony: Option<Entity>,
+    names: &Query<(Option<&CelestialBody>, Option<&Name>)>,
+) -> String {
+    match colony {
+        Some(colony) => format!("{} on {}", definition.name, colony_name(names, colony)),
+        None => definition.name.clone(),
+    }
+}
+
+/// System that applies the enact and repeal requests of the Policies tab
+#[allow(clippy::too_many_arguments)]
+pub fn process_policy_actions(
+    mut pending: ResMut<PendingPolicyActions>,
+    mut policies: ResMut<Policies>,
+    mut research: ResMut<ResearchState>,
+    data: Option<Res<PoliciesData>>,
+    technologies: Option<Res<TechnologiesData>>,
+    names: Query<(Option<&CelestialBody>, Option<&Name>)>,
+    mut log: Option<ResMut<EventLog>>,
+    sim_time: Option<Res<crate::ui::SimulationTime>>,
+) {
+    if pending.enact.is_empty() && pending.revoke.is_empty() {
+        return;
+    }
+    let Some(data) = data else {
+        pending.enact.clear();
+        pending.revoke.clear();
+        return;
+    };
+    let slots = technologies.map_or(0, |t| policy_slots(&research, &t));
+
+    let mut messages = Vec::new();
+    for (id, colony) in std::mem::take(&mut pending.revoke) {
+        let Some(definition) = data.get(&id) else {
+            continue;
+        };
+        if policies.revoke(definition, colony, &mut research) {
+            messages.push((
+                LogSeverity::Info,
+                format!("Repealed {}", scoped_title(definition, colony, &names)),
+            ));
+        }
+    }
+    for (id, colony) in std::mem::take(&mut pending.enact) {
+        let Some(definition) = data.get(&id) else {
+            continue;
+        };
+        match policies.check_enact(definition, colony, slots, &research) {
+            Ok(()) => {
+                policies.enact(definition, colony, &mut research);
+                messages.push((
+                    LogSeverity::Info,
+                    format!("Enacted {}", scoped_title(definition, colony, &names)),
+                ));
+            }
+            Err(e) => warn!("Cannot enact {}: {}", id, e),
+        }
+    }
+
+    for (severity, message) in messages {
+        info!("{}", message);
+        if let (Some(log), Some(sim_time)) = (log.as_mut(), sim_time.as_ref()) {
+            log.push(sim_time.format_date_time(), severity, message);
+        }
+    }
+}
+
+/// Whether the stockpile holds the upkeep owed over `years`
+fn can_pay(budget: &GlobalBudget, upkeep: &[PolicyUpkeep], years: f64) -> bool {
+    upkeep.iter().all(|cost| {
+        let owed = cost.mt_per_year() * years;
+        match cost {
+            PolicyUpkeep::Resource { resource, .. } => budget.get_stockpile(resource) >= owed,
+            PolicyUpkeep::Refined { good, .. } => budget.get_refined(good) >= owed,
+            PolicyUpkeep::Food(_) => budget.food >= owed,
+        }
+    })
+}
+
+/// Take the upkeep owed over `years` out of the stockpile
+fn pay(budget: &mut GlobalBudget, upkeep: &[PolicyUpkeep], years: f64) {
+    for cost in upkeep {
+        let owed = cost.mt_per_year() * years;
+        match *cost {
+            PolicyUpkeep::Resource { resource, .. } => {
+                budget.consume_resource(resource, owed);
+            }
+            PolicyUpkeep::Refined { good, .. } => {
+                budget.consume_refined(good, owed);
+            }
+            PolicyUpkeep::Food(_) => budget.food -= owed,
+        }
+    }
+}
+
+/// System that pays the upkeep of every policy in force once per due economy
+/// tick, in the order they were enacted. A policy the stockpile cannot pay
+/// for lapses, and so does an edict whose colony has died out.
+#[allow(clippy::too_many_arguments)]
+pub fn pay_policy_upkeep(
+    clock: Res<EconomyClock>,
+    mut budget: ResMut<GlobalBudget>,
+    mut policies: ResMut<Policies>,
+    mut research: ResMut<ResearchState>,
+    data: Option<Res<PoliciesData>>,
+    colonies: Query<&Population>,
+    names: Query<(Option<&CelestialBody>, Option<&Name>)>,
+    mut log: Option<ResMut<EventLog>>,
+    sim_time: Option<Res<crate::ui::SimulationTime>>,
+) {
+    if clock.due == 0 || policies.enacted.is_empty() {
+        return;
+    }
+    let Some(data) = data else {
+        return;
+    };
+    let years = EconomyClock::TICK_YEARS * clock.due as f64;
+
+    let mut lapsed = Vec::new();
+    for policy in policies.enacted.iter() {
+        let Some(definition) = data.get(&policy.id) else {
+            continue;
+        };
+        if let Some(colony) = policy.colony {
+            if !colonies.get(colony).is_ok_and(|p| p.count > 0.0) {
+                lapsed.push((policy.clone(), "the colony is gone"));
+                continue;
+            }
+        }
+        if can_pay(&budget, &definition.upkeep, years) {
+            pay(&mut budget, &definition.upkeep, years);
+        } else {
+            lapsed.push((policy.clone(), "the stockpile cannot pay its upkeep"));
+        }
+    }
+
+    for (policy, reason) in lapsed {
+        let Some(definition) = data.get(&policy.id) else {
+            continue;
+        };
+        policies.revoke(definition, policy.colony, &mut research);
+        let message = format!(
+            "{} has lapsed: {}",
+            scoped_title(definition, policy.colony, &names),
+            reason
+        );
+        info!("{}", message);
+        if let (Some(log), Some(sim_time)) = (log.as_mut(), sim_time.as_ref()) {
+            log.push(sim_time.format_date_time(), LogSeverity::Warning, message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::economy::tick::{advance_economy_clock, ECONOMY_TICK_SECONDS};
+    use crate::economy::RefinedGood;
+    use crate::research::types::{ModifierType, TechModifierDef};
+    use crate::ui::SimulationTime;
+
+    fn research_focus() -> PolicyDefinition {
+        PolicyDefinition {
+            id: "research_focus".to_string(),
+            name: "Research Focus".to_string(),
+            description: String::new(),
+            scope: PolicyScope::Empire,
+            required_tech: Some("basic_administration".to_string()),
+            modifiers: vec![TechModifierDef {
+                modifier_type: ModifierType::ResearchSpeed,
+                value: 20.0,
+            }],
+            stability: 0.0,
+            output: -10.0,
+            upkeep: vec![PolicyUpkeep::Refined {
+                good: RefinedGood::Electronics,
+                mt_per_year: 2.0,
+            }],
+            excludes: vec!["crash_industrialization".to_string()],
+        }
+    }
+
+    fn martial_law() -> PolicyDefinition {
+        PolicyDefinition {
+            id: "martial_law".to_string(),
+            name: "Martial Law".to_string(),
+            description: String::new(),
+            scope: PolicyScope::Colony,
+            required_tech: None,
+            modifiers: vec![],
+            stability: 20.0,
+            output: -15.0,
+            upkeep: vec![],
+            excludes: vec![],
+        }
+    }
+
+    #[test]
+    fn test_enacting_checks_scope_research_slots_and_conflicts() {
+        let mut research = ResearchState::default();
+        let mut policies = Policies::default();
+        let focus = research_focus();
+        let colony = Entity::from_raw(7);
+
+        assert!(policies.check_enact(&focus, None, 1, &research).is_err());
+        research.unlock_tech("basic_administration".to_string());
+        assert!(policies
+            .check_enact(&focus, Some(colony), 1, &research)
+            .is_err());
+        assert!(policies.check_enact(&focus, None, 0, &research).is_err());
+        policies.check_enact(&focus, None, 1, &research).unwrap();
+
+        policies.enact(&focus, None, &mut research);
+        assert_eq!(research.get_modifier(ModifierType::ResearchSpeed), 20.0);
+        assert!(policies.check_enact(&focus, None, 2, &research).is_err());
+
+        let mut crash = martial_law();
+        crash.id = "crash_industrialization".to_string();
+        crash.scope = PolicyScope::Empire;
+        crash.excludes = vec!["research_focus".to_string()];
+        assert!(policies.check_enact(&crash, None, 2, &research).is_err());
+
+        // Colony edicts have slots of their own and stack with empire policies
+        policies
+            .check_enact(&martial_law(), Some(colony), 1, &research)
+            .unwrap();
+        policies.enact(&martial_law(), Some(colony), &mut research);
+        let mut data = PoliciesData::default();
+        data.insert(focus.clone());
+        data.insert(martial_law());
+        assert_eq!(policies.stability_for(colony, &data), 20.0);
+        assert_eq!(policies.output_for(colony, &data), -25.0);
+        assert_eq!(policies.output_for(Entity::from_raw(8), &data), -10.0);
+
+        assert!(policies.revoke(&focus, None, &mut research));
+        assert_eq!(research.get_modifier(ModifierType::ResearchSpeed), 0.0);
+        assert!(!policies.revoke(&focus, None, &mut research));
+    }
+
+    #[test]
+    fn test_unpaid_policies_lapse() {
+        let mut app = App::new();
+        app.init_resource::<SimulationTime>()
+            .init_resource::<EconomyClock>()
+            .init_resource::<GlobalBudget>()
+            .init_resource::<ResearchState>()
+            .init_resource::<Policies>()
+            .add_systems(Update, (advance_economy_clock, pay_policy_upkeep).chain());
+        let mut data = PoliciesData::default();
+        data.insert(research_focus());
+        data.insert(martial_law());
+        app.insert_resource(data.clone());
+
+        let colony = app.world_mut().spawn(Population::new(1000.0)).id();
+        app.world_mut()
+            .resource_scope(|world, mut policies: Mut<Policies>| {
+                let mut research = world.resource_mut::<ResearchState>();
+                policies.enact(&research_focus(), None, &mut research);
+                policies.enact(&martial_law(), Some(colony), &mut research);
+            });
+
+        // Enough electronics for one tick
+        let owed = 2.0 * EconomyClock::TICK_YEARS;
+        {
+            let mut budget = app.world_mut().resource_mut::<GlobalBudget>();
+            let stock = budget.get_refined(&RefinedGood::Electronics);
+            budget.consume_refined(RefinedGood::Electronics, stock);
+            budget.add_refined(RefinedGood::Electronics, owed);
+        }
+        app.world_mut().resource_mut::<SimulationTime>().elapsed = ECONOMY_TICK_SECONDS;
+        app.update();
+        assert_eq!(app.world().resource::<Policies>().enacted.len(), 2);
+        assert!(
+            app.world()
+                .resource::<GlobalBudget>()
+                .get_refined(&RefinedGood::Electronics)
+                .abs()
+                < 1e-12
+        );
+
+        // Out of electronics, and the colony died out
+        app.world_mut().get_mut::<Population>(colony).unwrap().count = 0.0;
+        app.world_mut().resource_mut::<SimulationTime>().elapsed = 2.0 * ECONOMY_TICK_SECONDS;
+        app.update();
+        assert!(app.world().resource::<Policies>().enacted.is_empty());
+        assert_eq!(
+            app.world()
+                .resource::<ResearchState>()
+                .get_modifier(ModifierType::ResearchSpeed),
+            0.0
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+
+use crate::economy::{RefinedGood, ResourceType};
+use crate::research::types::TechModifierDef;
+use crate::research::TechnologyId;
+
+/// Unique identifier for a policy
+pub type PolicyId = String;
+
+/// What a policy is enacted for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PolicyScope {
+    /// A law for the whole civilization
+    #[default]
+    Empire,
+    /// An edict for a single colony or station
+    Colony,
+}
+
+/// Running cost of a policy, paid from the stockpile every economy tick
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PolicyUpkeep {
+    /// Megatons of a raw resource per year
+    Resource {
+        resource: ResourceType,
+        mt_per_year: f64,
+    },
+    /// Megatons of a refined good per year
+    Refined { good: RefinedGood, mt_per_year: f64 },
+    /// Megatons of food per year
+    Food(f64),
+}
+
+impl PolicyUpkeep {
+    /// Megatons owed per year
+    pub fn mt_per_year(&self) -> f64 {
+        match *self {
+            PolicyUpkeep::Resource { mt_per_year, .. }
+            | PolicyUpkeep::Refined { mt_per_year, .. }
+            | PolicyUpkeep::Food(mt_per_year) => mt_per_year,
+        }
+    }
+
+    /// Human-readable cost, e.g. "2.0 Mt Electronics/yr"
+    pub fn describe(&self) -> String {
+        let what = match self {
+            PolicyUpkeep::Resource { resource, .. } => resource.display_name().to_string(),
+            PolicyUpkeep::Refined { good, .. } => good.display_name().to_string(),
+            PolicyUpkeep::Food(_) => "Food".to_string(),
+        };
+        format!("{:.1} Mt {}/yr", self.mt_per_year(), what)
+    }
+}
+
+/// A policy or edict, loaded from data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyDefinition {
+    /// Unique identifier
+    pub id: PolicyId,
+    /// Display name
+    pub name: String,
+    /// Flavour text shown in the Policies tab
+    pub description: String,
+    #[serde(default)]
+    pub scope: PolicyScope,
+    /// Technology required before the policy can be enacted
+    #[serde(default)]
+    pub required_tech: Option<TechnologyId>,
+    /// Empire policies only: civilization-wide modifiers while in force
+    #[serde(default)]
+    pub modifiers: Vec<TechModifierDef>,
+    /// Stability points added to (or taken from) every colony it covers
+    #[serde(default)]
+    pub stability: f64,
+    /// Change of the output of mines, harvesters and refineries it covers,
+    /// in percent
+    #[serde(default)]
+    pub output: f64,
+    #[serde(default)]
+    pub upkeep: Vec<PolicyUpkeep>,
+    /// Policies that cannot be in force alongside this one in the same scope
+    #[serde(default)]
+    pub excludes: Vec<PolicyId>,
+}
+
+impl PolicyDefinition {
+    /// One line per trade-off, e.g. "+20% Research Speed", "-10 stability"
+    pub fn effect_lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self
+            .modifiers
+            .iter()
+            .map(|m| format!("{:+.0}% {}", m.value, m.modifier_type.display_name()))
+            .collect();
+        if self.stability != 0.0 {
+            lines.push(format!("{:+.0} stability", self.stability));
+        }
+        if self.output != 0.0 {
+            lines.push(format!("{:+.0}% output", self.output));
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::research::types::ModifierType;
+
+    #[test]
+    fn test_effect_lines_list_every_trade_off() {
+        let policy = PolicyDefinition {
+            id: "research_focus".to_string(),
+            name: "Research Focus".to_string(),
+            description: String::new(),
+            scope: PolicyScope::Empire,
+            required_tech: None,
+            modifiers: vec![TechModifierDef {
+                modifier_type: ModifierType::ResearchSpeed,
+                value: 20.0,
+            }],
+            stability: 0.0,
+            output: -10.0,
+            upkeep: vec![PolicyUpkeep::Refined {
+                good: RefinedGood::Electronics,
+                mt_per_year: 2.0,
+            }],
+            excludes: vec![],
+        };
+        assert_eq!(
+            policy.effect_lines(),
+            vec!["+20% Research Speed".to_string(), "-10% output".to_string()]
+        );
+        assert_eq!(policy.upkeep[0].describe(), "2.0 Mt Electronics/yr");
+    }
+}
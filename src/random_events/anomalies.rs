@@ -0,0 +1,538 @@
+//! Anomalies and ruins
+//!
+//! A few procedurally generated bodies hide an anomaly: ruins, a buried
+//! structure, a wreck frozen into a comet. Anomalies are defined in
+//! `assets/data/anomalies.ron` as a chain of stages, each an anomaly event of
+//! `events.ron` that fires once the body is surveyed to the stage's level.
+//! The choices of a stage hand out the rewards (artifacts to reverse
+//! engineer, resources, research) and may end the investigation early;
+//! otherwise the next stage waits for a deeper survey. The stage texts are
+//! kept in a journal per anomaly, listed in the Anomalies window of the Intel
+//! menu.
+//!
+//! Which bodies hide an anomaly depends only on the game seed and the body's
+//! name, so a star system populated again hides the same ones, and the
+//! progress made on them is kept.
+
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use super::data::EventsData;
+use super::systems::{EventResolved, PendingEvents};
+use super::types::{EventEffect, EventId, EventTarget};
+use crate::astronomy::ProceduralBody;
+use crate::economy::components::SurveyLevel;
+use crate::economy::generation::body_seed;
+use crate::game_state::{EventLog, GameSeed, LogSeverity};
+use crate::modding::ModList;
+use crate::plugins::solar_system::CelestialBody;
+use crate::plugins::solar_system_data::BodyType;
+use crate::ui::{SimulationTime, TimeScale};
+
+/// Base anomalies data file
+pub const ANOMALIES_PATH: &str = "assets/data/anomalies.ron";
+
+/// Chance that a procedurally generated body hides an anomaly
+pub const ANOMALY_CHANCE: f64 = 0.03;
+
+/// Mixed with the body seed so anomalies do not follow the deposits
+const ANOMALY_SEED_SALT: u64 = 0x41_4e4f_4d41_4c59;
+
+/// Unique identifier for an anomaly
+pub type AnomalyId = String;
+
+/// One step of an investigation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnomalyStage {
+    /// Survey level the body needs before the stage fires
+    pub survey: SurveyLevel,
+    /// Anomaly event presenting the stage
+    pub event: EventId,
+}
+
+/// An anomaly, loaded from data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnomalyDefinition {
+    pub id: AnomalyId,
+    pub name: String,
+    /// Relative likelihood among the anomalies a body can hide
+    pub weight: f64,
+    /// Body types that can hide it; any when empty
+    #[serde(default)]
+    pub body_types: Vec<BodyType>,
+    pub stages: Vec<AnomalyStage>,
+}
+
+/// Resource containing all anomaly definitions loaded from data, in file
+/// order so picks are reproducible
+#[derive(Resource, Debug, Clone, Default)]
+pub struct AnomaliesData {
+    pub anomalies: Vec<AnomalyDefinition>,
+}
+
+impl AnomaliesData {
+    /// Get an anomaly definition by ID
+    pub fn get(&self, id: &str) -> Option<&AnomalyDefinition> {
+        self.anomalies.iter().find(|anomaly| anomaly.id == id)
+    }
+
+    /// Add a definition, replacing one with the same ID in place
+    pub fn insert(&mut self, definition: AnomalyDefinition) {
+        match self.anomalies.iter_mut().find(|a| a.id == definition.id) {
+            Some(existing) => *existing = definition,
+            None => self.anomalies.push(definition),
+        }
+    }
+}
+
+/// Structure of the anomalies data file
+#[derive(Debug, Deserialize)]
+struct AnomaliesFile {
+    anomalies: Vec<AnomalyDefinition>,
+}
+
+/// Progress of an investigation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnomalyStatus {
+    /// No survey has found it yet
+    Hidden,
+    /// Waiting for the survey its next stage needs
+    Open,
+    /// The dialog of a stage waits for the player
+    Pending,
+    /// Every stage is done, or a choice ended the investigation
+    Concluded,
+}
+
+impl AnomalyStatus {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            AnomalyStatus::Hidden => "Hidden",
+            AnomalyStatus::Open => "Under investigation",
+            AnomalyStatus::Pending => "Awaiting orders",
+            AnomalyStatus::Concluded => "Concluded",
+        }
+    }
+}
+
+/// An anomaly on a body and how far it has been investigated
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnomalySite {
+    pub anomaly: AnomalyId,
+    pub body: Entity,
+    /// Name of the body, which outlives the entity when the star system is
+    /// unloaded
+    pub body_name: String,
+    /// Index of the next stage
+    pub stage: usize,
+    pub status: AnomalyStatus,
+    /// Stage texts and the choices made, oldest first
+    pub journal: Vec<String>,
+}
+
+/// Every anomaly seeded so far
+#[derive(Resource, Debug, Clone, Default)]
+pub struct Anomalies {
+    pub sites: Vec<AnomalySite>,
+}
+
+impl Anomalies {
+    /// Anomalies a survey has found
+    pub fn discovered(&self) -> impl Iterator<Item = &AnomalySite> {
+        self.sites
+            .iter()
+            .filter(|site| site.status != AnomalyStatus::Hidden)
+    }
+}
+
+/// Check that an anomaly can be investigated: it needs a positive weight and
+/// stages whose anomaly events exist, in order of survey level, none of
+/// which fires before a survey
+pub fn validate_anomaly(definition: &AnomalyDefinition, events: &EventsData) -> Result<(), String> {
+    if !definition.weight.is_finite() || definition.weight <= 0.0 {
+        return Err(format!(
+            "anomaly '{}' has no positive weight",
+            definition.id
+        ));
+    }
+    if definition.stages.is_empty() {
+        return Err(format!("anomaly '{}' has no stages", definition.id));
+    }
+    let mut tier = 0;
+    for stage in &definition.stages {
+        if stage.survey.tier() < tier.max(1) {
+            return Err(format!(
+                "anomaly '{}' has a stage before a survey or before the previous one",
+                definition.id
+            ));
+        }
+        tier = stage.survey.tier();
+        match events.get(&stage.event) {
+            Some(event) if event.target == EventTarget::Anomaly => {}
+            Some(_) => {
+                return Err(format!(
+                    "anomaly '{}' uses event '{}', which is not an anomaly event",
+                    definition.id, stage.event
+                ))
+            }
+            None => {
+                return Err(format!(
+                    "anomaly '{}' uses unknown event '{}'",
+                    definition.id, stage.event
+                ))
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Anomaly hidden on a body of `body_type`, if any. `roll` and `pick` are
+/// uniform in `[0, 1)`.
+pub fn roll_anomaly(
+    data: &AnomaliesData,
+    body_type: BodyType,
+    roll: f64,
+    pick: f64,
+) -> Option<&AnomalyDefinition> {
+    if roll >= ANOMALY_CHANCE {
+        return None;
+    }
+    let eligible: Vec<&AnomalyDefinition> = data
+        .anomalies
+        .iter()
+        .filter(|a| a.body_types.is_empty() || a.body_types.contains(&body_type))
+        .collect();
+    let total: f64 = eligible.iter().map(|anomaly| anomaly.weight).sum();
+    let mut remaining = pick * total;
+    for anomaly in &eligible {
+        if remaining < anomaly.weight {
+            return Some(anomaly);
+        }
+        remaining -= anomaly.weight;
+    }
+    eligible.last().copied()
+}
+
+/// System to load anomaly definitions from data file at startup, followed
+/// by the anomalies of enabled mods. Runs after the events are loaded, to
+/// check the stages against them.
+pub fn load_anomalies(
+    mut commands: Commands,
+    events: Option<Res<EventsData>>,
+    mods: Option<Res<ModList>>,
+) {
+    let mut data = AnomaliesData::default();
+    let mut paths = vec![PathBuf::from(ANOMALIES_PATH)];
+    paths.extend(mods.map_or(Vec::new(), |mods| mods.data_files("anomalies.ron")));
+
+    for path in paths {
+        match fs::read_to_string(&path) {
+            Ok(contents) => match ron::from_str::<AnomaliesFile>(&contents) {
+                Ok(file) => {
+                    for definition in file.anomalies {
+                        let valid = events
+                            .as_ref()
+                            .map_or(Ok(()), |events| validate_anomaly(&definition, events));
+                        match valid {
+                            Ok(()) => data.insert(definition),
+                            Err(e) => error!("Skipping invalid anomaly definition: {}", e),
+                        }
+                    }
+                }
+                Err(e) => error!("Failed to parse {}: {}", path.display(), e),
+            },
+            Err(e) => warn!(
+                "Anomalies data file not found at {}: {}. No anomalies will be found.",
+                path.display(),
+                e
+            ),
+        }
+    }
+    info!("Loaded {} anomalies", data.anomalies.len());
+    commands.insert_resource(data);
+}
+
+/// System that rolls for an anomaly on every newly generated body, or finds
+/// the one seeded before the body's star system was unloaded
+pub fn seed_anomalies(
+    seed: Option<Res<GameSeed>>,
+    data: Option<Res<AnomaliesData>>,
+    mut anomalies: ResMut<Anomalies>,
+    bodies: Query<(Entity, &CelestialBody), Added<ProceduralBody>>,
+) {
+    let Some(data) = data else {
+        return;
+    };
+    let seed = seed.map_or(0, |s| s.value);
+    for (entity, body) in bodies.iter() {
+        if let Some(site) = anomalies
+            .sites
+            .iter_mut()
+            .find(|site| site.body_name == body.name)
+        {
+            site.body = entity;
+            continue;
+        }
+        let mut rng = StdRng::seed_from_u64(body_seed(seed, &body.name) ^ ANOMALY_SEED_SALT);
+        let (roll, pick) = (rng.gen::<f64>(), rng.gen::<f64>());
+        if let Some(anomaly) = roll_anomaly(&data, body.body_type, roll, pick) {
+            anomalies.sites.push(AnomalySite {
+                anomaly: anomaly.id.clone(),
+                body: entity,
+                body_name: body.name.clone(),
+                stage: 0,
+                status: AnomalyStatus::Hidden,
+                journal: Vec::new(),
+            });
+        }
+    }
+}
+
+/// System that fires the next stage of every anomaly whose body has been
+/// surveyed far enough. The simulation pauses for the stage's dialog, like
+/// for a random event.
+#[allow(clippy::too_many_arguments)]
+pub fn investigate_anomalies(
+    data: Option<Res<AnomaliesData>>,
+    events: Option<Res<EventsData>>,
+    sim_time: Res<SimulationTime>,
+    mut anomalies: ResMut<Anomalies>,
+    mut pending: ResMut<PendingEvents>,
+    mut time_scale: ResMut<TimeScale>,
+    mut log: ResMut<EventLog>,
+    surveys: Query<Option<&SurveyLevel>, With<CelestialBody>>,
+) {
+    let (Some(data), Some(events)) = (data, events) else {
+        return;
+    };
+    for site in anomalies.sites.iter_mut() {
+        if !matches!(site.status, AnomalyStatus::Hidden | AnomalyStatus::Open) {
+            continue;
+        }
+        let Some(anomaly) = data.get(&site.anomaly) else {
+            continue;
+        };
+        let Some(stage) = anomaly.stages.get(site.stage) else {
+            site.status = AnomalyStatus::Concluded;
+            continue;
+        };
+        // Bodies of unloaded star systems are surveyed no further
+        let Ok(level) = surveys.get(site.body) else {
+            continue;
+        };
+        if level.copied().unwrap_or_default().tier() < stage.survey.tier() {
+            continue;
+        }
+        let Some(event) = events.get(&stage.event) else {
+            continue;
+        };
+
+        if site.status == AnomalyStatus::Hidden {
+            log.push(
+                sim_time.format_date_time(),
+                LogSeverity::Info,
+                format!("Survey of {} found: {}", site.body_name, anomaly.name),
+            );
+        }
+        pending.present(
+            event,
+            Some(site.body),
+            Some(site.body_name.clone()),
+            &mut time_scale,
+            &mut log,
+            sim_time.format_date_time(),
+        );
+        site.status = AnomalyStatus::Pending;
+    }
+}
+
+/// System that records the choice made on an anomaly's stage in its journal
+/// and moves on to the next stage, unless the choice ended the
+/// investigation
+pub fn advance_anomalies(
+    data: Option<Res<AnomaliesData>>,
+    events: Option<Res<EventsData>>,
+    mut resolved: EventReader<EventResolved>,
+    mut anomalies: ResMut<Anomalies>,
+) {
+    let (Some(data), Some(events)) = (data, events) else {
+        resolved.clear();
+        return;
+    };
+    for resolution in resolved.read() {
+        let Some(site) = anomalies.sites.iter_mut().find(|site| {
+            site.status == AnomalyStatus::Pending && Some(site.body) == resolution.target
+        }) else {
+            continue;
+        };
+        let Some(anomaly) = data.get(&site.anomaly) else {
+            continue;
+        };
+        if anomaly
+            .stages
+            .get(site.stage)
+            .is_none_or(|stage| stage.event != resolution.id)
+        {
+            continue;
+        }
+        let Some(event) = events.get(&resolution.id) else {
+            continue;
+        };
+        let choice = event.choices.get(resolution.choice);
+
+        site.journal.push(format!(
+            "{}: {} ({})",
+            event.title,
+            event.text(Some(&site.body_name)),
+            choice.map_or("", |c| c.label.as_str())
+        ));
+        site.stage += 1;
+        let ended = choice.is_some_and(|c| c.effects.contains(&EventEffect::EndAnomaly));
+        site.status = if ended || site.stage >= anomaly.stages.len() {
+            AnomalyStatus::Concluded
+        } else {
+            AnomalyStatus::Open
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random_events::types::{EventChoice, EventDefinition};
+
+    fn stage_event(id: &str, effects: Vec<EventEffect>) -> EventDefinition {
+        EventDefinition {
+            id: id.to_string(),
+            title: id.to_string(),
+            description: "Something stirs on {colony}.".to_string(),
+            weight: 0.0,
+            target: EventTarget::Anomaly,
+            required_tech: None,
+            max_stability: None,
+            severity: LogSeverity::Info,
+            choices: vec![EventChoice {
+                label: "Dig".to_string(),
+                description: String::new(),
+                effects,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_anomaly_stages_follow_the_surveys() {
+        let mut events = EventsData::default();
+        events.insert(stage_event("ruins_found", vec![]));
+        events.insert(stage_event("ruins_opened", vec![EventEffect::EndAnomaly]));
+        let ruins = AnomalyDefinition {
+            id: "ruins".to_string(),
+            name: "Ruins".to_string(),
+            weight: 1.0,
+            body_types: vec![],
+            stages: vec![
+                AnomalyStage {
+                    survey: SurveyLevel::OrbitalScan,
+                    event: "ruins_found".to_string(),
+                },
+                AnomalyStage {
+                    survey: SurveyLevel::SeismicSurvey,
+                    event: "ruins_opened".to_string(),
+                },
+            ],
+        };
+        validate_anomaly(&ruins, &events).unwrap();
+
+        let mut app = App::new();
+        app.init_resource::<SimulationTime>()
+            .init_resource::<TimeScale>()
+            .init_resource::<EventLog>()
+            .init_resource::<PendingEvents>()
+            .init_resource::<Anomalies>()
+            .insert_resource(events)
+            .insert_resource(AnomaliesData {
+                anomalies: vec![ruins],
+            })
+            .add_event::<EventResolved>()
+            .add_systems(Update, (advance_anomalies, investigate_anomalies).chain());
+
+        let body = app
+            .world_mut()
+            .spawn((
+                CelestialBody {
+                    name: "Kepler-22 b".to_string(),
+                    mass: 1e24,
+                    radius: 6000.0,
+                    body_type: BodyType::Planet,
+                    visual_radius: 6000.0,
+                    asteroid_class: None,
+                },
+                SurveyLevel::OrbitalScan,
+            ))
+            .id();
+        app.world_mut()
+            .resource_mut::<Anomalies>()
+            .sites
+            .push(AnomalySite {
+                anomaly: "ruins".to_string(),
+                body,
+                body_name: "Kepler-22 b".to_string(),
+                stage: 0,
+                status: AnomalyStatus::Hidden,
+                journal: Vec::new(),
+            });
+        let resolve = |app: &mut App, id: &str| {
+            app.world_mut()
+                .resource_mut::<PendingEvents>()
+                .queue
+                .clear();
+            app.world_mut().send_event(EventResolved {
+                id: id.to_string(),
+                target: Some(body),
+                choice: 0,
+            });
+            app.update();
+        };
+
+        app.update();
+        let queued = app.world().resource::<PendingEvents>().queue.clone();
+        assert_eq!(queued.len(), 1);
+        assert_eq!(queued[0].id, "ruins_found");
+
+        // The second stage waits for a seismic survey
+        resolve(&mut app, "ruins_found");
+        let site = app.world().resource::<Anomalies>().sites[0].clone();
+        assert_eq!((site.stage, site.status), (1, AnomalyStatus::Open));
+        assert_eq!(site.journal.len(), 1);
+        assert!(app.world().resource::<PendingEvents>().queue.is_empty());
+
+        app.world_mut()
+            .entity_mut(body)
+            .insert(SurveyLevel::SeismicSurvey);
+        app.update();
+        resolve(&mut app, "ruins_opened");
+        let site = &app.world().resource::<Anomalies>().sites[0];
+        assert_eq!(site.status, AnomalyStatus::Concluded);
+        assert_eq!(site.journal.len(), 2);
+    }
+
+    #[test]
+    fn test_anomalies_file_parses_and_validates() {
+        let contents = fs::read_to_string("assets/data/events.ron").unwrap();
+        let file: crate::random_events::data::EventsFile = ron::from_str(&contents).unwrap();
+        let mut events = EventsData::default();
+        for definition in file.events {
+            events.insert(definition);
+        }
+
+        let contents = fs::read_to_string(ANOMALIES_PATH).unwrap();
+        let file: AnomaliesFile = ron::from_str(&contents).unwrap();
+        assert!(!file.anomalies.is_empty());
+        for definition in &file.anomalies {
+            validate_anomaly(definition, &events).unwrap();
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use super::types::{EventDefinition, EventEffect, EventId, EventTarget};
+use crate::modding::ModList;
+
+/// Base random events data file
+pub const EVENTS_PATH: &str = "assets/data/events.ron";
+
+/// Resource containing all random event definitions loaded from data
+#[derive(Resource, Debug, Clone, Default)]
+pub struct EventsData {
+    /// All events indexed by ID
+    pub events: HashMap<EventId, EventDefinition>,
+    /// Event IDs in data file order, so weighted picks are reproducible
+    pub order: Vec<EventId>,
+}
+
+impl EventsData {
+    /// Get an event definition by ID
+    pub fn get(&self, id: &str) -> Option<&EventDefinition> {
+        self.events.get(id)
+    }
+
+    /// Iterate event definitions in data file order
+    pub fn iter(&self) -> impl Iterator<Item = &EventDefinition> {
+        self.order.iter().filter_map(|id| self.events.get(id))
+    }
+
+    /// Add a definition, keeping the file order
+    pub fn insert(&mut self, definition: EventDefinition) {
+        if !self.events.contains_key(&definition.id) {
+            self.order.push(definition.id.clone());
+        }
+        self.events.insert(definition.id.clone(), definition);
+    }
+}
+
+/// Structure of the events data file
+#[derive(Debug, Deserialize)]
+pub(super) struct EventsFile {
+    pub(super) events: Vec<EventDefinition>,
+}
+
+/// Check that an event can be presented and resolved: it needs a positive
+/// weight (unless only anomalies fire it) and at least one choice, only
+/// colony events can require an unstable colony, derelicts and raiders need
+/// a body to orbit, raiders need crew and supplies, only anomaly events can end an anomaly and only contact events can
+/// sway a civilization
+pub fn validate_event(definition: &EventDefinition) -> Result<(), String> {
+    let rolled = matches!(definition.target, EventTarget::Global | EventTarget::Colony);
+    if rolled && (!definition.weight.is_finite() || definition.weight <= 0.0) {
+        return Err(format!("event '{}' has no positive weight", definition.id));
+    }
+    if definition.choices.is_empty() {
+        return Err(format!("event '{}' has no choices", definition.id));
+    }
+    if definition.max_stability.is_some() && definition.target != EventTarget::Colony {
+        return Err(format!(
+            "event '{}' limits stability without a target colony",
+            definition.id
+        ));
+    }
+    for effect in definition.choices.iter().flat_map(|c| &c.effects) {
+        match effect {
+            EventEffect::Derelict { resources, .. } => {
+                if matches!(
+                    definition.target,
+                    EventTarget::Global | EventTarget::Contact
+                ) {
+                    return Err(format!(
+                        "event '{}' leaves a derelict without a target body",
+                        definition.id
+                    ));
+                }
+                if resources.values().any(|amount| *amount < 0.0) {
+                    return Err(format!(
+                        "event '{}' leaves a derelict with negative resources",
+                        definition.id
+                    ));
+                }
+            }
+            EventEffect::Raiders { crew, days, .. } => {
+                if matches!(
+                    definition.target,
+                    EventTarget::Global | EventTarget::Contact
+                ) {
+                    return Err(format!(
+                        "event '{}' sends raiders without a target body",
+                        definition.id
+                    ));
+                }
+                if *crew == 0 || !days.is_finite() || *days <= 0.0 {
+                    return Err(format!(
+                        "event '{}' sends raiders without crew or supplies",
+                        definition.id
+                    ));
+                }
+            }
+            EventEffect::EndAnomaly if rolled => {
+                return Err(format!(
+                    "event '{}' ends an anomaly without being an anomaly event",
+                    definition.id
+                ));
+            }
+            EventEffect::Opinion(_) | EventEffect::BeginTranslation
+                if definition.target != EventTarget::Contact =>
+            {
+                return Err(format!(
+                    "event '{}' sways a civilization without being a contact event",
+                    definition.id
+                ));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// System to load random event definitions from data file at startup,
+/// followed by the events of enabled mods
+pub fn load_events(mut commands: Commands, mods: Option<Res<ModList>>) {
+    info!("Loading random events...");
+
+    let mut events_data = EventsData::default();
+    let mut paths = vec![PathBuf::from(EVENTS_PATH)];
+    paths.extend(mods.map_or(Vec::new(), |mods| mods.data_files("events.ron")));
+
+    for path in paths {
+        match fs::read_to_string(&path) {
+            Ok(contents) => match ron::from_str::<EventsFile>(&contents) {
+                Ok(data) => {
+                    for definition in data.events {
+                        match validate_event(&definition) {
+                            Ok(()) => events_data.insert(definition),
+                            Err(e) => error!("Skipping invalid event definition: {}", e),
+                        }
+                    }
+                }
+                Err(e) => error!("Failed to parse {}: {}", path.display(), e),
+            },
+            Err(e) => warn!(
+                "Events data file not found at {}: {}. No random events will occur.",
+                path.display(),
+                e
+            ),
+        }
+    }
+    info!("Loaded {} random events", events_data.order.len());
+
+    // Events that failed validation are skipped; the rest can still fire
+    commands.insert_resource(events_data);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events_file_parses_and_validates() {
+        let contents = fs::read_to_string("assets/data/events.ron").unwrap();
+        let data: EventsFile = ron::from_str(&contents).unwrap();
+
+        assert!(!data.events.is_empty());
+        for definition in &data.events {
+            validate_event(definition).unwrap();
+        }
+    }
+}
@@ -0,0 +1,57 @@
+//! Random event system
+//!
+//! Provides data-driven random events:
+//! - Event definitions (solar flares, impact threats, mine collapses,
+//!   breakthroughs) loaded from `assets/data/events.ron`
+//! - Weighted random picks on economy days, gated by technology and by
+//!   having a colony to happen to (for unrest events, an unstable one)
+//! - Choice dialogs that pause the simulation until the player decides
+//! - Consequences applied to colonies, the stockpile and research pools
+//! - Anomalies and ruins on generated bodies, investigated stage by stage as
+//!   surveys reach deeper (`assets/data/anomalies.ron`)
+
+use bevy::prelude::*;
+
+pub mod anomalies;
+pub mod data;
+pub mod systems;
+pub mod types;
+
+pub use anomalies::{
+    advance_anomalies, investigate_anomalies, load_anomalies, seed_anomalies, Anomalies,
+    AnomaliesData, AnomalyDefinition, AnomalySite, AnomalyStatus,
+};
+pub use data::{load_events, EventsData};
+pub use systems::{
+    colony_eligible, pick_event, resolve_random_events, roll_random_events, ActiveEvent,
+    EventResolved, EventRng, PendingEvents, RandomEventSettings,
+};
+pub use types::{EventChoice, EventDefinition, EventEffect, EventId, EventTarget};
+
+/// Plugin that adds random events to the Bevy app
+pub struct RandomEventsPlugin;
+
+impl Plugin for RandomEventsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RandomEventSettings>()
+            .init_resource::<EventRng>()
+            .init_resource::<PendingEvents>()
+            .init_resource::<Anomalies>()
+            .add_event::<EventResolved>()
+            .add_systems(Startup, (load_events, load_anomalies).chain())
+            .add_systems(
+                FixedUpdate,
+                roll_random_events.after(crate::economy::advance_economy_clock),
+            )
+            .add_systems(
+                Update,
+                (
+                    resolve_random_events,
+                    seed_anomalies,
+                    advance_anomalies,
+                    investigate_anomalies,
+                )
+                    .chain(),
+            );
+    }
+}
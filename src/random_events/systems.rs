@@ -0,0 +1,505 @@
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::Rng;
+use std::collections::VecDeque;
+
+use super::data::EventsData;
+use super::types::{EventDefinition, EventEffect, EventId, EventTarget};
+use crate::economy::components::{PlanetResources, Population};
+use crate::economy::{EconomyClock, GlobalBudget, Radiation, Stability, BASE_STABILITY};
+use crate::fleets::{HostileFleet, Salvage};
+use crate::game_state::{Difficulty, EventLog, GameSeed, LogSeverity};
+use crate::plugins::solar_system::CelestialBody;
+use crate::research::{ResearchState, ReverseEngineeringProject};
+use crate::ui::{SimulationTime, TimeScale};
+
+/// Player settings of the random event engine
+#[derive(Resource, Debug, Clone)]
+pub struct RandomEventSettings {
+    pub enabled: bool,
+    /// Average number of in-game days between two events
+    pub mean_days_between: f64,
+}
+
+impl Default for RandomEventSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            mean_days_between: 120.0,
+        }
+    }
+}
+
+/// Random number generator for events, seeded from the [`GameSeed`] so a
+/// campaign replays the same events
+#[derive(Resource, Debug, Clone)]
+pub struct EventRng(pub StdRng);
+
+impl FromWorld for EventRng {
+    fn from_world(world: &mut World) -> Self {
+        Self(GameSeed::rng(world, 0x4556_454e_5453))
+    }
+}
+
+/// An event waiting for the player's decision
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActiveEvent {
+    pub id: EventId,
+    /// Colony the event happens to, for colony events
+    pub target: Option<Entity>,
+    pub target_name: Option<String>,
+}
+
+/// Sent when the player picks a choice of an event
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct EventResolved {
+    pub id: EventId,
+    pub target: Option<Entity>,
+    /// Index of the picked choice
+    pub choice: usize,
+}
+
+/// Events waiting for a decision, oldest first. The dialog answers the front
+/// event by setting `choice`.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct PendingEvents {
+    pub queue: VecDeque<ActiveEvent>,
+    /// Index of the choice picked for the front event
+    pub choice: Option<usize>,
+    /// The simulation was paused for the dialog and resumes once the queue
+    /// is empty
+    pub resume_on_resolve: bool,
+}
+
+impl PendingEvents {
+    /// Log `event` and queue its dialog, pausing the simulation until the
+    /// queue is answered. `target_name` fills in `{colony}` in its text.
+    pub fn present(
+        &mut self,
+        event: &EventDefinition,
+        target: Option<Entity>,
+        target_name: Option<String>,
+        time_scale: &mut TimeScale,
+        log: &mut EventLog,
+        date: String,
+    ) {
+        log.push(
+            date,
+            event.severity,
+            format!("{}: {}", event.title, event.text(target_name.as_deref())),
+        );
+        self.queue.push_back(ActiveEvent {
+            id: event.id.clone(),
+            target,
+            target_name,
+        });
+        if !time_scale.is_paused() {
+            time_scale.pause();
+            self.resume_on_resolve = true;
+        }
+    }
+}
+
+/// Whether a colony event can happen to a colony of the given stability
+pub fn colony_eligible(event: &EventDefinition, stability: f64) -> bool {
+    event.max_stability.is_none_or(|max| stability <= max)
+}
+
+/// Pick an event by weight among those that can fire. `lowest_stability` is
+/// that of the least stable colony, `None` without colonies. `roll` is
+/// uniform in `[0, 1)`.
+pub fn pick_event<'a>(
+    events: &'a EventsData,
+    research_state: &ResearchState,
+    lowest_stability: Option<f64>,
+    roll: f64,
+) -> Option<&'a EventDefinition> {
+    let eligible: Vec<&EventDefinition> = events
+        .iter()
+        .filter(|event| match event.target {
+            EventTarget::Global => true,
+            EventTarget::Colony => {
+                lowest_stability.is_some_and(|stability| colony_eligible(event, stability))
+            }
+            // Only fired by the stages of an anomaly or by first contact
+            EventTarget::Anomaly | EventTarget::Contact => false,
+        })
+        .filter(|event| {
+            event
+                .required_tech
+                .as_ref()
+                .is_none_or(|tech| research_state.is_unlocked(tech))
+        })
+        .collect();
+
+    let total: f64 = eligible.iter().map(|event| event.weight).sum();
+    let mut remaining = roll * total;
+    for event in &eligible {
+        if remaining < event.weight {
+            return Some(event);
+        }
+        remaining -= event.weight;
+    }
+    eligible.last().copied()
+}
+
+/// System that rolls for a random event on every due economy day, more often
+/// on harder difficulties. A new event pauses the simulation and waits in
+/// [`PendingEvents`] for the player's choice.
+#[allow(clippy::too_many_arguments)]
+pub fn roll_random_events(
+    clock: Res<EconomyClock>,
+    settings: Res<RandomEventSettings>,
+    difficulty: Option<Res<Difficulty>>,
+    events: Option<Res<EventsData>>,
+    research_state: Res<ResearchState>,
+    sim_time: Res<SimulationTime>,
+    mut rng: ResMut<EventRng>,
+    mut pending: ResMut<PendingEvents>,
+    mut time_scale: ResMut<TimeScale>,
+    mut log: ResMut<EventLog>,
+    colonies: Query<(Entity, &CelestialBody, &Population, Option<&Stability>)>,
+) {
+    let Some(events) = events else {
+        return;
+    };
+    if !settings.enabled || settings.mean_days_between <= 0.0 {
+        return;
+    }
+    let frequency = difficulty.map_or(1.0, |d| d.event_frequency_multiplier());
+    let chance = (frequency / settings.mean_days_between).min(1.0);
+
+    for _ in 0..clock.due {
+        // One dialog at a time
+        if !pending.queue.is_empty() {
+            return;
+        }
+        if !rng.0.gen_bool(chance) {
+            continue;
+        }
+
+        let populated: Vec<(Entity, &CelestialBody, f64)> = colonies
+            .iter()
+            .filter(|(_, _, population, _)| population.count > 0.0)
+            .map(|(entity, body, _, stability)| {
+                (entity, body, stability.map_or(BASE_STABILITY, |s| s.value))
+            })
+            .collect();
+        let lowest_stability = populated
+            .iter()
+            .map(|(.., stability)| *stability)
+            .min_by(f64::total_cmp);
+        let roll = rng.0.gen::<f64>();
+        let Some(event) = pick_event(&events, &research_state, lowest_stability, roll) else {
+            continue;
+        };
+
+        let (target, target_name) = match event.target {
+            EventTarget::Global | EventTarget::Anomaly | EventTarget::Contact => (None, None),
+            EventTarget::Colony => {
+                let candidates: Vec<_> = populated
+                    .iter()
+                    .filter(|(.., stability)| colony_eligible(event, *stability))
+                    .collect();
+                let (entity, body, _) = candidates[rng.0.gen_range(0..candidates.len())];
+                (Some(*entity), Some(body.name.clone()))
+            }
+        };
+        pending.present(
+            event,
+            target,
+            target_name,
+            &mut time_scale,
+            &mut log,
+            sim_time.format_date_time(),
+        );
+    }
+}
+
+/// Apply the consequences of an event choice
+#[allow(clippy::too_many_arguments)]
+pub fn apply_effects(
+    effects: &[EventEffect],
+    target: Option<Entity>,
+    commands: &mut Commands,
+    budget: &mut GlobalBudget,
+    research_state: &mut ResearchState,
+    populations: &mut Query<&mut Population>,
+    resources: &mut Query<&mut PlanetResources>,
+    stabilities: &mut Query<&mut Stability>,
+    radiation: &mut Query<&mut Radiation>,
+) {
+    for effect in effects {
+        match effect {
+            EventEffect::Resource { resource, amount } => {
+                if *amount >= 0.0 {
+                    budget.add_resource(*resource, *amount);
+                } else {
+                    // Lose what there is, without going negative
+                    let loss = (-amount).min(budget.get_stockpile(resource));
+                    budget.consume_resource(*resource, loss);
+                }
+            }
+            EventEffect::Population(fraction) => {
+                if let Some(mut population) = target.and_then(|t| populations.get_mut(t).ok()) {
+                    population.count = (population.count * (1.0 + fraction)).max(0.0);
+                }
+            }
+            EventEffect::ProvenReserveLoss(fraction) => {
+                if let Some(mut resources) = target.and_then(|t| resources.get_mut(t).ok()) {
+                    let keep = (1.0 - fraction).clamp(0.0, 1.0);
+                    for deposit in resources.deposits.values_mut() {
+                        deposit.reserve.proven_crustal *= keep;
+                    }
+                }
+            }
+            EventEffect::ResearchPoints(points) => {
+                research_state.research_points_available =
+                    (research_state.research_points_available + points).max(0.0);
+            }
+            EventEffect::EngineeringPoints(points) => {
+                research_state.engineering_points_available =
+                    (research_state.engineering_points_available + points).max(0.0);
+            }
+            EventEffect::Stability(points) => {
+                if let Some(mut stability) = target.and_then(|t| stabilities.get_mut(t).ok()) {
+                    stability.add_event(*points);
+                }
+            }
+            EventEffect::RadiationStorm { multiplier, days } => {
+                if let Some(mut radiation) = target.and_then(|t| radiation.get_mut(t).ok()) {
+                    radiation.add_storm(*multiplier, *days);
+                }
+            }
+            EventEffect::Derelict {
+                name,
+                resources,
+                tech,
+                research_points,
+            } => {
+                if let Some(body) = target {
+                    commands.spawn(Salvage {
+                        name: name.clone(),
+                        body,
+                        resources: resources.clone(),
+                        tech: tech.clone(),
+                        research_points: *research_points,
+                    });
+                }
+            }
+            EventEffect::Raiders {
+                name,
+                crew,
+                days,
+                faction,
+            } => {
+                if let Some(body) = target {
+                    commands.spawn(HostileFleet {
+                        name: name.clone(),
+                        faction: faction.clone(),
+                        body,
+                        crew: *crew,
+                        days_left: *days,
+                    });
+                }
+            }
+            EventEffect::Artifact {
+                name,
+                tech,
+                research_points,
+            } => {
+                commands.spawn(ReverseEngineeringProject::new(
+                    tech.clone(),
+                    name.clone(),
+                    *research_points,
+                ));
+            }
+            // Handled by the anomaly investigation and by first contact
+            EventEffect::EndAnomaly | EventEffect::Opinion(_) | EventEffect::BeginTranslation => {}
+        }
+    }
+}
+
+/// System that applies the choice picked for the front event and resumes
+/// the simulation once no more events wait
+#[allow(clippy::too_many_arguments)]
+pub fn resolve_random_events(
+    mut commands: Commands,
+    events: Option<Res<EventsData>>,
+    sim_time: Res<SimulationTime>,
+    mut pending: ResMut<PendingEvents>,
+    mut budget: ResMut<GlobalBudget>,
+    mut research_state: ResMut<ResearchState>,
+    mut time_scale: ResMut<TimeScale>,
+    mut log: ResMut<EventLog>,
+    mut resolved: EventWriter<EventResolved>,
+    mut populations: Query<&mut Population>,
+    mut resources: Query<&mut PlanetResources>,
+    mut stabilities: Query<&mut Stability>,
+    mut radiation: Query<&mut Radiation>,
+) {
+    let Some(choice) = pending.choice.take() else {
+        return;
+    };
+    let Some(active) = pending.queue.pop_front() else {
+        return;
+    };
+
+    if let Some((event, picked)) = events
+        .as_ref()
+        .and_then(|events| events.get(&active.id))
+        .and_then(|event| event.choices.get(choice).map(|picked| (event, picked)))
+    {
+        apply_effects(
+            &picked.effects,
+            active.target,
+            &mut commands,
+            &mut budget,
+            &mut research_state,
+            &mut populations,
+            &mut resources,
+            &mut stabilities,
+            &mut radiation,
+        );
+        log.push(
+            sim_time.format_date_time(),
+            LogSeverity::Info,
+            format!("{}: {}", event.title, picked.label),
+        );
+        resolved.send(EventResolved {
+            id: active.id.clone(),
+            target: active.target,
+            choice,
+        });
+    }
+
+    if pending.queue.is_empty() && pending.resume_on_resolve {
+        pending.resume_on_resolve = false;
+        time_scale.resume();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::economy::ResourceType;
+    use crate::random_events::types::EventChoice;
+
+    fn event(id: &str, weight: f64, target: EventTarget, tech: Option<&str>) -> EventDefinition {
+        EventDefinition {
+            id: id.to_string(),
+            title: id.to_string(),
+            description: String::new(),
+            weight,
+            target,
+            required_tech: tech.map(str::to_string),
+            max_stability: None,
+            severity: LogSeverity::Info,
+            choices: vec![EventChoice {
+                label: "OK".to_string(),
+                description: String::new(),
+                effects: vec![],
+            }],
+        }
+    }
+
+    #[test]
+    fn test_pick_event_respects_weights_and_conditions() {
+        let mut events = EventsData::default();
+        events.insert(event("flare", 1.0, EventTarget::Global, None));
+        events.insert(event("collapse", 3.0, EventTarget::Colony, None));
+        events.insert(event("breakthrough", 10.0, EventTarget::Global, Some("fusion_power")));
+        let research = ResearchState::default();
+        let stable = Some(BASE_STABILITY);
+
+        assert_eq!(pick_event(&events, &research, stable, 0.1).unwrap().id, "flare");
+        assert_eq!(pick_event(&events, &research, stable, 0.5).unwrap().id, "collapse");
+        // Without colonies only global events fire
+        assert_eq!(pick_event(&events, &research, None, 0.9).unwrap().id, "flare");
+
+        let mut research = ResearchState::default();
+        research.unlock_tech("fusion_power".to_string());
+        assert_eq!(
+            pick_event(&events, &research, stable, 0.9).unwrap().id,
+            "breakthrough"
+        );
+    }
+
+    #[test]
+    fn test_unrest_events_need_an_unstable_colony() {
+        let mut strike = event("strike", 1.0, EventTarget::Colony, None);
+        strike.max_stability = Some(25.0);
+        let mut events = EventsData::default();
+        events.insert(strike);
+        let research = ResearchState::default();
+
+        assert!(pick_event(&events, &research, Some(BASE_STABILITY), 0.5).is_none());
+        assert_eq!(
+            pick_event(&events, &research, Some(10.0), 0.5).unwrap().id,
+            "strike"
+        );
+    }
+
+    #[test]
+    fn test_choice_is_applied_and_simulation_resumes() {
+        let mut app = App::new();
+        app.init_resource::<GlobalBudget>()
+            .init_resource::<ResearchState>()
+            .init_resource::<SimulationTime>()
+            .init_resource::<TimeScale>()
+            .init_resource::<EventLog>()
+            .init_resource::<PendingEvents>()
+            .add_event::<EventResolved>()
+            .add_systems(Update, resolve_random_events);
+
+        let mut flare = event("flare", 1.0, EventTarget::Colony, None);
+        flare.choices[0].effects = vec![
+            EventEffect::Population(-0.5),
+            EventEffect::Resource {
+                resource: ResourceType::Iron,
+                amount: -1e12,
+            },
+            EventEffect::ResearchPoints(100.0),
+            EventEffect::Stability(-10.0),
+            EventEffect::RadiationStorm {
+                multiplier: 5.0,
+                days: 3.0,
+            },
+        ];
+        let mut events = EventsData::default();
+        events.insert(flare);
+        app.insert_resource(events);
+
+        let colony = app
+            .world_mut()
+            .spawn((
+                Population::new(1000.0),
+                Stability::default(),
+                Radiation::default(),
+            ))
+            .id();
+        app.world_mut().resource_mut::<TimeScale>().pause();
+        {
+            let mut pending = app.world_mut().resource_mut::<PendingEvents>();
+            pending.queue.push_back(ActiveEvent {
+                id: "flare".to_string(),
+                target: Some(colony),
+                target_name: Some("Luna".to_string()),
+            });
+            pending.resume_on_resolve = true;
+            pending.choice = Some(0);
+        }
+        app.update();
+
+        assert_eq!(app.world().get::<Population>(colony).unwrap().count, 500.0);
+        let stability = app.world().get::<Stability>(colony).unwrap();
+        assert_eq!(stability.recent_events, -10.0);
+        assert_eq!(app.world().get::<Radiation>(colony).unwrap().storm, 5.0);
+        let budget = app.world().resource::<GlobalBudget>();
+        assert_eq!(budget.get_stockpile(&ResourceType::Iron), 0.0);
+        let research = app.world().resource::<ResearchState>();
+        assert_eq!(research.research_points_available, 100.0);
+        assert!(app.world().resource::<PendingEvents>().queue.is_empty());
+        assert!(!app.world().resource::<TimeScale>().is_paused());
+    }
+}
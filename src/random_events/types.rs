@@ -0,0 +1,153 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::economy::ResourceType;
+use crate::game_state::LogSeverity;
+use crate::research::TechnologyId;
+use crate::trade::FactionId;
+
+/// Unique identifier for a random event
+pub type EventId = String;
+
+/// What an event happens to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EventTarget {
+    /// The civilization as a whole
+    #[default]
+    Global,
+    /// A randomly picked populated body
+    Colony,
+    /// The body of an anomaly under investigation; such events are never
+    /// rolled, only fired by the stages of an anomaly
+    Anomaly,
+    /// An alien civilization; such events are never rolled, only fired by
+    /// detecting its signals and translating them
+    Contact,
+}
+
+/// Consequence of picking an event choice
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum EventEffect {
+    /// Add (or, when negative, remove) Megatons from the stockpile
+    Resource { resource: ResourceType, amount: f64 },
+    /// Change the target colony's population by this fraction
+    Population(f64),
+    /// Remove this fraction of the target colony's proven reserves
+    ProvenReserveLoss(f64),
+    /// Research points added to (or, when negative, taken from) the pool
+    ResearchPoints(f64),
+    /// Engineering points added to (or, when negative, taken from) the pool
+    EngineeringPoints(f64),
+    /// Stability points added to (or taken from) the target colony, fading
+    /// over the following months
+    Stability(f64),
+    /// Multiply the radiation dose on the target colony for some days
+    RadiationStorm { multiplier: f64, days: f64 },
+    /// Leave a derelict in orbit of the target colony for a fleet to
+    /// recover: materials in Megatons, and a technology reverse engineering
+    /// counts towards (or research points for the pool without one)
+    Derelict {
+        name: String,
+        #[serde(default)]
+        resources: HashMap<ResourceType, f64>,
+        #[serde(default)]
+        tech: Option<TechnologyId>,
+        #[serde(default)]
+        research_points: f64,
+    },
+    /// An artifact whose reverse engineering counts research points towards
+    /// a technology
+    Artifact {
+        name: String,
+        tech: TechnologyId,
+        research_points: f64,
+    },
+    /// A hostile fleet taking up orbit of the target body, blockading it
+    /// until more crew in orbit drive it off or its supplies run out
+    Raiders {
+        name: String,
+        crew: u32,
+        days: f64,
+        #[serde(default)]
+        faction: Option<FactionId>,
+    },
+    /// Close the investigation of the target's anomaly, skipping its later
+    /// stages
+    EndAnomaly,
+    /// Raise (or, when negative, lower) the target civilization's opinion of
+    /// the player
+    Opinion(f64),
+    /// Start translating the target civilization's signals
+    BeginTranslation,
+}
+
+/// An option offered in the event dialog
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventChoice {
+    /// Button label
+    pub label: String,
+    /// Consequences spelled out for the player
+    #[serde(default)]
+    pub description: String,
+    pub effects: Vec<EventEffect>,
+}
+
+/// A random event, loaded from data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventDefinition {
+    /// Unique identifier
+    pub id: EventId,
+    /// Dialog title
+    pub title: String,
+    /// Dialog text; `{colony}` is replaced with the target colony's name, the
+    /// name of the anomaly's body or that of the civilization in contact
+    pub description: String,
+    /// Relative likelihood among the events that can fire; unused by
+    /// anomaly events
+    #[serde(default)]
+    pub weight: f64,
+    #[serde(default)]
+    pub target: EventTarget,
+    /// Technology required before the event can fire
+    #[serde(default)]
+    pub required_tech: Option<TechnologyId>,
+    /// Colony events only: the event can only happen to a colony at or below
+    /// this stability
+    #[serde(default)]
+    pub max_stability: Option<f64>,
+    /// Severity of the event log entry
+    pub severity: LogSeverity,
+    /// Options in the dialog; the event waits until the player picks one
+    /// (headless runs take the first)
+    pub choices: Vec<EventChoice>,
+}
+
+impl EventDefinition {
+    /// Dialog text with the target colony filled in
+    pub fn text(&self, colony: Option<&str>) -> String {
+        self.description
+            .replace("{colony}", colony.unwrap_or("the colony"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_description_names_the_colony() {
+        let event = EventDefinition {
+            id: "mine_collapse".to_string(),
+            title: "Mine Collapse".to_string(),
+            description: "A shaft on {colony} gave way.".to_string(),
+            weight: 1.0,
+            target: EventTarget::Colony,
+            required_tech: None,
+            max_stability: None,
+            severity: LogSeverity::Warning,
+            choices: vec![],
+        };
+        assert_eq!(event.text(Some("Luna")), "A shaft on Luna gave way.");
+        assert_eq!(event.text(None), "A shaft on the colony gave way.");
+    }
+}
@@ -0,0 +1,911 @@
+//! Batched asteroid belt rendering
+//!
+//! Belts are drawn from their [`AsteroidBelt`] definition as a single merged
+//! mesh of small particles instead of thousands of individual entities. Each
+//! belt carries a few level-of-detail meshes that are swapped by camera
+//! distance; from far away the particles give way to a flat annulus.
+//! Individual asteroids are only promoted to real [`CelestialBody`] entities
+//! when the camera zooms in on part of the belt, and demoted again once the
+//! camera leaves (unless they are selected or anchored). The particles of the
+//! level on screen move along their members' own orbits for belts in view,
+//! and a promoted member's particle is hidden while its entity shows.
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::render_asset::RenderAssetUsages;
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use std::collections::HashMap;
+
+use crate::astronomy::components::{CurrentStarSystem, FloatingOrigin, OrbitCenter, SystemId};
+use crate::astronomy::{
+    orbit_position_from_mean_anomaly, resonant_populations, AsteroidBelt, BeltPopulation,
+    KeplerOrbit, OrbitPath, Selected, SpaceCoordinates, EARTH_MASS_KG, SCALING_FACTOR,
+};
+use crate::construction::lagrange_orbit;
+use crate::economy::components::OrbitsBody;
+use crate::economy::generation;
+use crate::game_state::GameSeed;
+use crate::plugins::camera::{CameraAnchor, GameCamera, OrbitCamera, ViewMode};
+use crate::plugins::solar_system::{setup_solar_system, Asteroid, CelestialBody, Planet, Star};
+use crate::plugins::solar_system_data::{calculate_visual_radius, AsteroidClass, BodyType};
+use crate::ui::SimulationTime;
+
+/// Number of particles drawn for Sol's main belt (the named asteroids are real entities)
+const SOL_MAIN_BELT_PARTICLES: usize = 3000;
+
+/// Camera distance thresholds (Bevy units) for each belt LOD level.
+/// Beyond the last threshold the belt is drawn as an annulus.
+const BELT_LOD_DISTANCES: [f32; 3] = [20_000.0, 120_000.0, 400_000.0];
+
+/// Segments around the annulus drawn at the coarsest LOD level
+const BELT_ANNULUS_SEGMENTS: u32 = 96;
+
+/// Every LOD level keeps one in `BELT_LOD_STRIDE^level` particles
+const BELT_LOD_STRIDE: usize = 4;
+
+/// Particle half-size (Bevy units) at the finest LOD level.
+/// Coarser levels grow the particles so the belt keeps its apparent density.
+const BELT_PARTICLE_SIZE: f32 = 1.5;
+
+/// Camera orbit radius (Bevy units) below which belt members may be promoted
+const BELT_PROMOTION_CAMERA_RADIUS: f32 = 400.0;
+
+/// Distance (AU) from the camera target within which belt members are promoted
+const BELT_PROMOTION_RADIUS_AU: f64 = 0.05;
+
+/// Upper bound on simultaneously promoted members per belt
+const BELT_MAX_PROMOTED: usize = 64;
+
+/// Plugin that renders asteroid belts as batched particle meshes.
+pub struct AsteroidBeltRenderPlugin;
+
+impl Plugin for AsteroidBeltRenderPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_sol_belt_field.after(setup_solar_system))
+            .add_systems(
+                Update,
+                (
+                    build_belt_meshes,
+                    follow_parent_star.after(crate::astronomy::interpolate_orbit_positions),
+                    update_belt_lod,
+                    promote_belt_asteroids.after(crate::astronomy::interpolate_orbit_positions),
+                    move_belt_particles
+                        .after(update_belt_lod)
+                        .after(promote_belt_asteroids),
+                ),
+            );
+    }
+}
+
+/// A belt rendered as a batched mesh instead of individual entities.
+#[derive(Component, Debug, Clone)]
+pub struct AsteroidBeltField {
+    /// Belt layout (extent, population, inclination)
+    pub belt: AsteroidBelt,
+    /// Star the belt orbits
+    pub parent_star: Entity,
+    /// Star system the belt belongs to
+    pub system_id: usize,
+    /// Name prefix for promoted asteroids: the star's, or for Trojans and
+    /// resonant families the planet's
+    pub star_name: String,
+    /// Seed from which every member is generated deterministically
+    pub seed: u64,
+    /// Every member, generated once from the seed
+    pub members: Vec<BeltAsteroid>,
+    /// Members currently promoted to real entities, keyed by member index
+    pub promoted: HashMap<usize, Entity>,
+}
+
+impl AsteroidBeltField {
+    pub fn new(
+        belt: AsteroidBelt,
+        parent_star: Entity,
+        system_id: usize,
+        star_name: &str,
+        seed: u64,
+    ) -> Self {
+        let members = (0..belt.count)
+            .map(|index| generate_member(&belt, seed, index))
+            .collect();
+        Self {
+            belt,
+            parent_star,
+            system_id,
+            star_name: star_name.to_string(),
+            seed,
+            members,
+            promoted: HashMap::new(),
+        }
+    }
+
+    /// Name of a member promoted to a real entity
+    pub fn member_name(&self, index: usize) -> String {
+        match self.belt.population {
+            BeltPopulation::Annulus => format!("{} Belt Asteroid {}", self.star_name, index + 1),
+            BeltPopulation::Trojan { point, .. } => {
+                format!("{} {} Trojan {}", self.star_name, point, index + 1)
+            }
+            BeltPopulation::Resonant {
+                orbits,
+                planet_orbits,
+                ..
+            } => format!(
+                "{} {}:{} Resonant {}",
+                self.star_name,
+                orbits,
+                planet_orbits,
+                index + 1
+            ),
+        }
+    }
+}
+
+/// Level-of-detail meshes built for a belt, finest first.
+#[derive(Component, Debug, Clone)]
+pub struct BeltLodMeshes {
+    pub levels: Vec<Handle<Mesh>>,
+    pub current: usize,
+    /// Level, simulation time and hidden (promoted) members the particles
+    /// were last placed for
+    drawn: Option<(usize, f64, Vec<usize>)>,
+}
+
+/// Marks an asteroid entity that was promoted out of a batched belt.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BeltMember {
+    /// The belt field entity this asteroid belongs to
+    pub field: Entity,
+    /// Index of the member within the belt
+    pub index: usize,
+}
+
+/// Deterministic description of a single belt asteroid.
+#[derive(Debug, Clone, Copy)]
+pub struct BeltAsteroid {
+    pub orbit: KeplerOrbit,
+    pub asteroid_class: AsteroidClass,
+    /// Radius in km
+    pub radius: f32,
+    /// Mass in kg
+    pub mass: f64,
+}
+
+/// Mean motion (rad/s) of a heliocentric orbit from Kepler's third law (1 M☉).
+fn orbit_mean_motion(semi_major_axis_au: f64) -> f64 {
+    let period_years = semi_major_axis_au.powf(1.5);
+    let period_seconds = period_years * 365.25 * 86400.0;
+    std::f64::consts::TAU / period_seconds
+}
+
+/// Mean longitude (rad) of an orbit at its epoch
+fn mean_longitude(orbit: &KeplerOrbit) -> f64 {
+    orbit.longitude_ascending_node + orbit.argument_of_periapsis + orbit.mean_anomaly_epoch
+}
+
+/// Orbit of a belt member, drawn from the belt's population.
+///
+/// Trojans librate up to about 25° around their Lagrange point and share the
+/// planet's period. Resonant members keep clear of the planet: when a Hilda
+/// reaches aphelion the planet is 180° or 60° away, when a plutino or
+/// twotino reaches perihelion the planet is 90° away.
+fn member_orbit(belt: &AsteroidBelt, rng: &mut StdRng) -> KeplerOrbit {
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_3, PI, TAU};
+
+    match belt.population {
+        BeltPopulation::Annulus => {
+            let semi_major_axis = rng.gen_range(belt.inner_au..belt.outer_au);
+            KeplerOrbit::new(
+                rng.gen_range(0.0..0.2),
+                semi_major_axis,
+                belt.inclination + rng.gen_range(-0.05..0.05),
+                rng.gen_range(0.0..TAU),
+                rng.gen_range(0.0..TAU),
+                rng.gen_range(0.0..TAU),
+                orbit_mean_motion(semi_major_axis),
+            )
+        }
+        BeltPopulation::Trojan { planet, point } => {
+            let mut orbit = lagrange_orbit(&planet, 0.0, point);
+            orbit.semi_major_axis *= 1.0 + rng.gen_range(-0.02..0.02);
+            orbit.eccentricity = rng.gen_range(0.0..0.15);
+            orbit.inclination += rng.gen_range(-0.3..0.3);
+            // Keep the mean longitude while turning the periapsis
+            let turn = rng.gen_range(0.0..TAU);
+            orbit.argument_of_periapsis += turn;
+            orbit.mean_anomaly_epoch += rng.gen_range(-0.45..0.45) - turn;
+            orbit
+        }
+        BeltPopulation::Resonant { planet, .. } => {
+            let ratio = belt.population.period_ratio();
+            let semi_major_axis =
+                planet.semi_major_axis * ratio.powf(2.0 / 3.0) * (1.0 + rng.gen_range(-0.01..0.01));
+            let mean_motion = planet.mean_motion / ratio;
+            let mean_anomaly = rng.gen_range(0.0..TAU);
+
+            // Where the planet stands when the member next passes the apse
+            // that has to avoid it
+            let (apse, offsets): (f64, &[f64]) = if ratio < 1.0 {
+                (PI, &[PI, FRAC_PI_3, -FRAC_PI_3])
+            } else {
+                (0.0, &[FRAC_PI_2, -FRAC_PI_2])
+            };
+            let wait = (apse - mean_anomaly).rem_euclid(TAU) / mean_motion;
+            let planet_longitude = mean_longitude(&planet) + planet.mean_motion * wait;
+            let apse_longitude = planet_longitude + offsets[rng.gen_range(0..offsets.len())];
+            let perihelion_longitude = apse_longitude - apse;
+
+            let node = planet.longitude_ascending_node;
+            KeplerOrbit::new(
+                rng.gen_range(0.05..0.3),
+                semi_major_axis,
+                planet.inclination + rng.gen_range(-0.2..0.2),
+                node,
+                (perihelion_longitude - node).rem_euclid(TAU),
+                mean_anomaly,
+                mean_motion,
+            )
+        }
+    }
+}
+
+/// Belt member `index` of a field
+pub fn belt_member(field: &AsteroidBeltField, index: usize) -> BeltAsteroid {
+    field.members[index]
+}
+
+/// Generate belt member `index` from the belt seed.
+///
+/// Each member has its own RNG stream so any asteroid can be rebuilt without
+/// generating the ones before it.
+fn generate_member(belt: &AsteroidBelt, seed: u64, index: usize) -> BeltAsteroid {
+    let mut rng =
+        StdRng::seed_from_u64(seed ^ (index as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+
+    let orbit = member_orbit(belt, &mut rng);
+
+    // Main belts mix metal-rich, stony and basaltic asteroids; Trojans and
+    // resonant families are dark primitive bodies from the outer system
+    let asteroid_class = match belt.population {
+        BeltPopulation::Annulus => {
+            if rng.gen_bool(0.3) {
+                AsteroidClass::MType // Metal-rich
+            } else if rng.gen_bool(0.6) {
+                AsteroidClass::SType // Silicate-rich
+            } else {
+                AsteroidClass::VType // Basaltic
+            }
+        }
+        _ => {
+            if rng.gen_bool(0.5) {
+                AsteroidClass::DType
+            } else if rng.gen_bool(0.6) {
+                AsteroidClass::PType
+            } else {
+                AsteroidClass::CType
+            }
+        }
+    };
+
+    // Random size (radius 0.1 - 50 km)
+    let radius: f32 = rng.gen_range(0.1..50.0);
+    // Rough mass estimate (density ~2500 kg/m³)
+    let mass = (4.0 / 3.0) * std::f64::consts::PI * (radius as f64 * 1000.0).powi(3) * 2500.0;
+
+    BeltAsteroid {
+        orbit,
+        asteroid_class,
+        radius,
+        mass,
+    }
+}
+
+/// Indices of the members drawn at a given LOD level.
+pub fn belt_lod_indices(count: usize, level: usize) -> impl Iterator<Item = usize> {
+    (0..count).step_by(BELT_LOD_STRIDE.pow(level as u32))
+}
+
+/// Choose the LOD level for a camera distance in Bevy units.
+pub fn belt_lod_level(camera_distance: f32) -> usize {
+    BELT_LOD_DISTANCES
+        .iter()
+        .position(|&threshold| camera_distance < threshold)
+        .unwrap_or(BELT_LOD_DISTANCES.len())
+}
+
+/// Corners of a belt particle
+const OCTAHEDRON: [Vec3; 6] = [
+    Vec3::X,
+    Vec3::NEG_X,
+    Vec3::Y,
+    Vec3::NEG_Y,
+    Vec3::Z,
+    Vec3::NEG_Z,
+];
+
+/// Corners of the particles of a LOD level after `elapsed` seconds, each on
+/// its member's own orbit around the parent star. A promoted member's
+/// particle shrinks to a point, leaving its entity to show it.
+fn particle_positions(field: &AsteroidBeltField, level: usize, elapsed: f64) -> Vec<[f32; 3]> {
+    let size = BELT_PARTICLE_SIZE * (BELT_LOD_STRIDE as f32).sqrt().powi(level as i32);
+    let mut positions = Vec::new();
+    for index in belt_lod_indices(field.belt.count, level) {
+        let orbit = &field.members[index].orbit;
+        let mean_anomaly = orbit.mean_anomaly_epoch + orbit.mean_motion * elapsed;
+        let center =
+            (orbit_position_from_mean_anomaly(orbit, mean_anomaly) * SCALING_FACTOR).as_vec3();
+        let size = if field.promoted.contains_key(&index) {
+            0.0
+        } else {
+            size
+        };
+        positions.extend(
+            OCTAHEDRON
+                .iter()
+                .map(|corner| (center + *corner * size).to_array()),
+        );
+    }
+    positions
+}
+
+/// Build one merged mesh for a belt LOD level.
+///
+/// Every member becomes a small octahedron placed at its epoch position relative
+/// to the parent star, so the whole level is a single draw call.
+fn build_belt_lod_mesh(field: &AsteroidBeltField, level: usize) -> Mesh {
+    const FACES: [[u32; 3]; 8] = [
+        [0, 2, 4],
+        [2, 1, 4],
+        [1, 3, 4],
+        [3, 0, 4],
+        [2, 0, 5],
+        [1, 2, 5],
+        [3, 1, 5],
+        [0, 3, 5],
+    ];
+
+    let positions = particle_positions(field, level, 0.0);
+    let mut normals = Vec::new();
+    let mut colors = Vec::new();
+    let mut indices = Vec::new();
+
+    for (particle, index) in belt_lod_indices(field.belt.count, level).enumerate() {
+        let member = belt_member(field, index);
+
+        // Slight per-class tint so belts read as mixed populations
+        let color = match member.asteroid_class {
+            AsteroidClass::MType => LinearRgba::new(0.55, 0.52, 0.48, 1.0),
+            AsteroidClass::SType => LinearRgba::new(0.6, 0.5, 0.38, 1.0),
+            _ => LinearRgba::new(0.45, 0.43, 0.42, 1.0),
+        };
+
+        let base = (particle * OCTAHEDRON.len()) as u32;
+        for corner in OCTAHEDRON {
+            normals.push(corner.to_array());
+            colors.push(color.to_f32_array());
+        }
+        for face in FACES {
+            indices.extend(face.iter().map(|i| base + i));
+        }
+    }
+
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    mesh.insert_indices(Indices::U32(indices));
+    mesh
+}
+
+/// Build the coarsest belt level: a flat, double-sided annulus spanning the
+/// belt in its orbital plane, tinted like the particles.
+fn build_belt_annulus_mesh(field: &AsteroidBeltField) -> Mesh {
+    let inner = (field.belt.inner_au * SCALING_FACTOR) as f32;
+    let outer = (field.belt.outer_au * SCALING_FACTOR) as f32;
+    let color = LinearRgba::new(0.32, 0.29, 0.25, 1.0).to_f32_array();
+
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut colors = Vec::new();
+    let mut indices = Vec::new();
+
+    // One ring of vertices per face so each side gets its own normal
+    for normal in [Vec3::Z, Vec3::NEG_Z] {
+        let base = positions.len() as u32;
+        for segment in 0..=BELT_ANNULUS_SEGMENTS {
+            let angle = std::f32::consts::TAU * segment as f32 / BELT_ANNULUS_SEGMENTS as f32;
+            let direction = Vec3::new(angle.cos(), angle.sin(), 0.0);
+            for radius in [inner, outer] {
+                positions.push((direction * radius).to_array());
+                normals.push(normal.to_array());
+                colors.push(color);
+            }
+        }
+        for segment in 0..BELT_ANNULUS_SEGMENTS {
+            let i = base + segment * 2;
+            let quad = if normal == Vec3::Z {
+                [i, i + 1, i + 3, i, i + 3, i + 2]
+            } else {
+                [i, i + 3, i + 1, i, i + 2, i + 3]
+            };
+            indices.extend(quad);
+        }
+    }
+
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    mesh.insert_indices(Indices::U32(indices));
+    mesh
+}
+
+/// Spawn a belt field entity; its meshes are built on the next update.
+pub fn spawn_asteroid_belt_field(commands: &mut Commands, field: AsteroidBeltField) -> Entity {
+    let system_id = field.system_id;
+    commands
+        .spawn((
+            field,
+            SpaceCoordinates::default(), // Synced with the parent star
+            SystemId(system_id),
+            SpatialBundle::default(),
+        ))
+        .id()
+}
+
+/// Spawn the unresolved population of Sol's main belt, and the Trojans and
+/// resonant families of its giants (see [`resonant_populations`]).
+/// The named main-belt asteroids and Trojans from the solar system data
+/// remain real entities.
+fn spawn_sol_belt_field(
+    mut commands: Commands,
+    game_seed: Res<GameSeed>,
+    star_query: Query<(Entity, &CelestialBody, Option<&SystemId>), With<Star>>,
+    planet_query: Query<(&CelestialBody, &KeplerOrbit, Option<&SystemId>), With<Planet>>,
+) {
+    // Sol's star is the only one spawned without (or with a zero) system id
+    let in_sol = |system_id: Option<&SystemId>| system_id.is_none_or(|s| s.0 == 0);
+    let Some((sun, body, _)) = star_query
+        .iter()
+        .find(|(_, _, system_id)| in_sol(*system_id))
+    else {
+        warn!("No star found for Sol main belt");
+        return;
+    };
+
+    let belt = AsteroidBelt {
+        inner_au: 2.1,
+        outer_au: 3.3,
+        count: SOL_MAIN_BELT_PARTICLES,
+        inclination: 0.0,
+        population: BeltPopulation::Annulus,
+    };
+    let seed = game_seed.value ^ 0x5017_BE17_0000_0000;
+    spawn_asteroid_belt_field(
+        &mut commands,
+        AsteroidBeltField::new(belt, sun, 0, &body.name, seed),
+    );
+
+    let mut giants: Vec<_> = planet_query
+        .iter()
+        .filter(|(body, _, system_id)| body.body_type == BodyType::GasGiant && in_sol(*system_id))
+        .collect();
+    giants.sort_by(|a, b| a.1.semi_major_axis.total_cmp(&b.1.semi_major_axis));
+    let last = giants.len().saturating_sub(1);
+    for (i, (planet, orbit, _)) in giants.into_iter().enumerate() {
+        let belts = resonant_populations(
+            &planet.name,
+            orbit,
+            (planet.mass / EARTH_MASS_KG) as f32,
+            i == 0,
+            i == last,
+        );
+        for (j, belt) in belts.into_iter().enumerate() {
+            let seed = generation::body_seed(seed, &planet.name) ^ (j as u64 + 1);
+            spawn_asteroid_belt_field(
+                &mut commands,
+                AsteroidBeltField::new(belt, sun, 0, &planet.name, seed),
+            );
+        }
+    }
+}
+
+/// Build the LOD meshes for newly spawned belt fields.
+fn build_belt_meshes(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    field_query: Query<(Entity, &AsteroidBeltField), Without<BeltLodMeshes>>,
+) {
+    for (entity, field) in field_query.iter() {
+        // Swarms and resonant families are no rings, so they get no annulus
+        let mut levels: Vec<Handle<Mesh>> = (0..BELT_LOD_DISTANCES.len())
+            .map(|level| meshes.add(build_belt_lod_mesh(field, level)))
+            .collect();
+        if matches!(field.belt.population, BeltPopulation::Annulus) {
+            levels.push(meshes.add(build_belt_annulus_mesh(field)));
+        }
+
+        let material = materials.add(StandardMaterial {
+            base_color: Color::WHITE, // Tinted by vertex colors
+            perceptual_roughness: 1.0,
+            ..default()
+        });
+
+        info!(
+            "Built batched belt for '{}' with {} particles",
+            field.star_name, field.belt.count
+        );
+
+        commands.entity(entity).insert((
+            levels[0].clone(),
+            material,
+            BeltLodMeshes {
+                levels,
+                current: 0,
+                drawn: None,
+            },
+        ));
+    }
+}
+
+/// Keep each belt centered on its star.
+fn follow_parent_star(
+    mut field_query: Query<(&AsteroidBeltField, &mut SpaceCoordinates)>,
+    star_query: Query<&SpaceCoordinates, (With<Star>, Without<AsteroidBeltField>)>,
+) {
+    for (field, mut coords) in field_query.iter_mut() {
+        if let Ok(star) = star_query.get(field.parent_star) {
+            if coords.position != star.position {
+                coords.position = star.position;
+            }
+        }
+    }
+}
+
+/// Move the particles of the LOD level on screen along their members'
+/// orbits, and hide those of promoted members. Only belts the camera saw last
+/// frame are touched, so the cost follows what is on screen rather than the
+/// number of belts: hidden belts, belts outside the view frustum and belts
+/// drawn as an annulus keep their last positions until they come back into
+/// view. Of those, only the shown level is rewritten, and only when the time,
+/// level or promoted members changed.
+fn move_belt_particles(
+    sim_time: Res<SimulationTime>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut field_query: Query<(&AsteroidBeltField, &mut BeltLodMeshes, &ViewVisibility)>,
+) {
+    let elapsed = sim_time.elapsed_seconds();
+    for (field, mut lod, view_visibility) in field_query.iter_mut() {
+        // The annulus has no particles
+        if !view_visibility.get() || lod.current >= BELT_LOD_DISTANCES.len() {
+            continue;
+        }
+        let mut hidden: Vec<usize> = field.promoted.keys().copied().collect();
+        hidden.sort_unstable();
+        let drawn = Some((lod.current, elapsed, hidden));
+        if lod.drawn == drawn {
+            continue;
+        }
+        if let Some(mesh) = meshes.get_mut(&lod.levels[lod.current]) {
+            mesh.insert_attribute(
+                Mesh::ATTRIBUTE_POSITION,
+                particle_positions(field, lod.current, elapsed),
+            );
+        }
+        lod.drawn = drawn;
+    }
+}
+
+/// Swap belt LOD meshes by camera distance and hide belts outside the current system.
+fn update_belt_lod(
+    view_mode: Res<ViewMode>,
+    current_system: Res<CurrentStarSystem>,
+    camera_query: Query<&GlobalTransform, With<GameCamera>>,
+    mut field_query: Query<(
+        &AsteroidBeltField,
+        &GlobalTransform,
+        &mut BeltLodMeshes,
+        &mut Handle<Mesh>,
+        &mut Visibility,
+    )>,
+) {
+    let Ok(camera) = camera_query.get_single() else {
+        return;
+    };
+
+    for (field, transform, mut lod, mut mesh, mut visibility) in field_query.iter_mut() {
+        let visible = *view_mode == ViewMode::System && field.system_id == current_system.0;
+        let target = if visible {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        if *visibility != target {
+            *visibility = target;
+        }
+        if !visible {
+            continue;
+        }
+
+        // Distance to the nearest point of the belt annulus, not its center
+        let local = transform
+            .affine()
+            .inverse()
+            .transform_point3(camera.translation());
+        let planar = Vec2::new(local.x, local.y).length();
+        let inner = (field.belt.inner_au * SCALING_FACTOR) as f32;
+        let outer = (field.belt.outer_au * SCALING_FACTOR) as f32;
+        let radial_gap = (inner - planar).max(planar - outer).max(0.0);
+        let distance = Vec2::new(radial_gap, local.z).length();
+
+        let level = belt_lod_level(distance).min(lod.levels.len() - 1);
+        if level != lod.current {
+            lod.current = level;
+            *mesh = lod.levels[level].clone();
+        }
+    }
+}
+
+/// Promote belt members near a zoomed-in camera target to real entities and
+/// demote them again once the camera leaves.
+#[allow(clippy::too_many_arguments)]
+fn promote_belt_asteroids(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    sim_time: Res<SimulationTime>,
+    current_system: Res<CurrentStarSystem>,
+    floating_origin: Res<FloatingOrigin>,
+    camera_query: Query<(&OrbitCamera, &CameraAnchor), With<GameCamera>>,
+    mut field_query: Query<(Entity, &mut AsteroidBeltField)>,
+    star_query: Query<&SpaceCoordinates, With<Star>>,
+    selected_query: Query<(), With<Selected>>,
+    mut asteroid_assets: Local<Option<(Handle<Mesh>, Handle<StandardMaterial>)>>,
+) {
+    let Ok((orbit_camera, anchor)) = camera_query.get_single() else {
+        return;
+    };
+
+    let zoomed_in = orbit_camera.radius < BELT_PROMOTION_CAMERA_RADIUS;
+    let target_au =
+        floating_origin.position + orbit_camera.target_center.as_dvec3() / SCALING_FACTOR;
+    let elapsed = sim_time.elapsed_seconds();
+
+    let (mesh, material) = asteroid_assets
+        .get_or_insert_with(|| {
+            (
+                meshes.add(Sphere::new(1.0).mesh().ico(2).unwrap()),
+                materials.add(StandardMaterial {
+                    base_color: Color::srgb(0.5, 0.47, 0.43),
+                    perceptual_roughness: 0.95,
+                    ..default()
+                }),
+            )
+        })
+        .clone();
+
+    for (field_entity, mut field) in field_query.iter_mut() {
+        let star_position = star_query
+            .get(field.parent_star)
+            .map(|s| s.position)
+            .unwrap_or(DVec3::ZERO);
+        let active = zoomed_in && field.system_id == current_system.0;
+
+        // Which members should be promoted right now
+        let mut wanted = Vec::new();
+        if active {
+            let offset = target_au - star_position;
+            let planar = DVec3::new(offset.x, offset.y, 0.0).length();
+            let in_annulus = planar > field.belt.inner_au - BELT_PROMOTION_RADIUS_AU
+                && planar < field.belt.outer_au + BELT_PROMOTION_RADIUS_AU;
+
+            if in_annulus {
+                for index in 0..field.belt.count {
+                    let member = belt_member(&field, index);
+                    let mean_anomaly =
+                        member.orbit.mean_anomaly_epoch + member.orbit.mean_motion * elapsed;
+                    let position = orbit_position_from_mean_anomaly(&member.orbit, mean_anomaly);
+                    let distance = (star_position + position - target_au).length();
+                    if distance < BELT_PROMOTION_RADIUS_AU {
+                        wanted.push((distance, index, member, star_position + position));
+                    }
+                }
+                wanted.sort_by(|a, b| a.0.total_cmp(&b.0));
+                wanted.truncate(BELT_MAX_PROMOTED);
+            }
+        }
+
+        // Demote members that left the promotion zone, keeping selected/anchored ones
+        let keep: Vec<usize> = wanted.iter().map(|w| w.1).collect();
+        field.promoted.retain(|index, entity| {
+            let pinned = selected_query.contains(*entity) || anchor.0 == Some(*entity);
+            if keep.contains(index) || pinned {
+                true
+            } else {
+                commands.entity(*entity).despawn_recursive();
+                false
+            }
+        });
+
+        for (_, index, member, position) in wanted {
+            if field.promoted.contains_key(&index) {
+                continue;
+            }
+            let visual_radius = calculate_visual_radius(BodyType::Asteroid, member.radius);
+            let entity = commands
+                .spawn((
+                    PbrBundle {
+                        mesh: mesh.clone(),
+                        material: material.clone(),
+                        transform: Transform::from_scale(Vec3::splat(visual_radius)),
+                        ..default()
+                    },
+                    Asteroid,
+                    CelestialBody {
+                        name: field.member_name(index),
+                        mass: member.mass,
+                        radius: member.radius,
+                        body_type: BodyType::Asteroid,
+                        visual_radius,
+                        asteroid_class: Some(member.asteroid_class),
+                    },
+                    member.orbit,
+                    OrbitPath::new(Color::srgba(0.6, 0.6, 0.5, 0.2)),
+                    SpaceCoordinates::new(position),
+                    OrbitCenter(field.parent_star),
+                    OrbitsBody::new(field.parent_star),
+                    SystemId(field.system_id),
+                    BeltMember {
+                        field: field_entity,
+                        index,
+                    },
+                ))
+                .id();
+            field.promoted.insert(index, entity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::render::mesh::VertexAttributeValues;
+
+    fn test_field(count: usize) -> AsteroidBeltField {
+        let belt = AsteroidBelt {
+            inner_au: 2.0,
+            outer_au: 3.0,
+            count,
+            inclination: 0.02,
+            population: BeltPopulation::Annulus,
+        };
+        AsteroidBeltField::new(belt, Entity::PLACEHOLDER, 1, "Test", 42)
+    }
+
+    #[test]
+    fn test_belt_member_is_deterministic() {
+        let field = test_field(100);
+        let a = belt_member(&field, 17);
+        let b = belt_member(&field, 17);
+        assert_eq!(a.orbit.semi_major_axis, b.orbit.semi_major_axis);
+        assert_eq!(a.radius, b.radius);
+
+        let other = belt_member(&field, 18);
+        assert_ne!(a.orbit.semi_major_axis, other.orbit.semi_major_axis);
+    }
+
+    #[test]
+    fn test_belt_members_stay_inside_belt() {
+        let field = test_field(500);
+        for index in 0..field.belt.count {
+            let member = belt_member(&field, index);
+            assert!(member.orbit.semi_major_axis >= field.belt.inner_au);
+            assert!(member.orbit.semi_major_axis < field.belt.outer_au);
+            assert!(member.orbit.eccentricity < 0.2);
+        }
+    }
+
+    #[test]
+    fn test_trojans_and_resonant_families_keep_their_places() {
+        use crate::astronomy::resonant_populations;
+        use std::f64::consts::{PI, TAU};
+
+        let jupiter = KeplerOrbit::new(0.0, 5.2, 0.0, 0.4, 1.2, 2.0, 1.68e-8);
+        let belts = resonant_populations("Jupiter", &jupiter, 318.0, true, false);
+        let field = |belt: &AsteroidBelt| {
+            AsteroidBeltField::new(belt.clone(), Entity::PLACEHOLDER, 0, "Jupiter", 7)
+        };
+
+        // L4 Trojans lead Jupiter by 60° give or take their libration
+        let trojans = field(&belts[0]);
+        assert_eq!(trojans.member_name(0), "Jupiter L4 Trojan 1");
+        for index in 0..trojans.belt.count {
+            let orbit = belt_member(&trojans, index).orbit;
+            let lead = (mean_longitude(&orbit) - mean_longitude(&jupiter)).rem_euclid(TAU);
+            assert!((lead - PI / 3.0).abs() < 0.46, "lead {}", lead);
+            assert_eq!(orbit.mean_motion, jupiter.mean_motion);
+        }
+
+        // Hildas go round three times while Jupiter goes round twice
+        let hildas = field(&belts[2]);
+        assert_eq!(hildas.member_name(4), "Jupiter 3:2 Resonant 5");
+        for index in 0..hildas.belt.count {
+            let orbit = belt_member(&hildas, index).orbit;
+            assert!((orbit.mean_motion / jupiter.mean_motion - 1.5).abs() < 1e-9);
+            assert!(orbit.semi_major_axis > hildas.belt.inner_au);
+            assert!(orbit.semi_major_axis < hildas.belt.outer_au);
+        }
+    }
+
+    #[test]
+    fn test_belt_lod_level_thresholds() {
+        assert_eq!(belt_lod_level(0.0), 0);
+        assert_eq!(belt_lod_level(BELT_LOD_DISTANCES[0] + 1.0), 1);
+        assert_eq!(belt_lod_level(1.0e9), BELT_LOD_DISTANCES.len());
+    }
+
+    #[test]
+    fn test_particles_follow_their_own_orbits() {
+        let mut field = test_field(100);
+        let elapsed = 100.0 * 86_400.0;
+        let positions = particle_positions(&field, 0, elapsed);
+
+        // Every particle sits where its member's orbit has taken it
+        for index in [0, 41, 99] {
+            let orbit = belt_member(&field, index).orbit;
+            let mean_anomaly = orbit.mean_anomaly_epoch + orbit.mean_motion * elapsed;
+            let expected =
+                (orbit_position_from_mean_anomaly(&orbit, mean_anomaly) * SCALING_FACTOR).as_vec3();
+            let corner = Vec3::from_array(positions[index * OCTAHEDRON.len()]);
+            let center = corner - OCTAHEDRON[0] * BELT_PARTICLE_SIZE;
+            assert!(center.distance(expected) < 1e-2);
+        }
+
+        // A promoted member's particle collapses to a point
+        field.promoted.insert(3, Entity::PLACEHOLDER);
+        let positions = particle_positions(&field, 0, 0.0);
+        let corners = &positions[3 * OCTAHEDRON.len()..4 * OCTAHEDRON.len()];
+        assert!(corners.iter().all(|corner| *corner == corners[0]));
+    }
+
+    #[test]
+    fn test_belt_lod_mesh_particle_counts() {
+        let field = test_field(1000);
+        assert_eq!(belt_lod_indices(1000, 0).count(), 1000);
+        assert_eq!(belt_lod_indices(1000, 1).count(), 250);
+        assert_eq!(belt_lod_indices(1000, 2).count(), 63);
+
+        let mesh = build_belt_lod_mesh(&field, 1);
+        assert_eq!(mesh.count_vertices(), 250 * 6);
+    }
+
+    #[test]
+    fn test_belt_annulus_spans_the_belt() {
+        let field = test_field(1000);
+        let mesh = build_belt_annulus_mesh(&field);
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            panic!("annulus has no positions");
+        };
+        let inner = (field.belt.inner_au * SCALING_FACTOR) as f32;
+        let outer = (field.belt.outer_au * SCALING_FACTOR) as f32;
+        for position in positions {
+            let radius = Vec2::new(position[0], position[1]).length();
+            assert!(radius > inner - 0.01 && radius < outer + 0.01);
+            assert_eq!(position[2], 0.0);
+        }
+        // Two faces of BELT_ANNULUS_SEGMENTS quads each
+        let indices = mesh.indices().map_or(0, |i| i.len());
+        assert_eq!(indices, 2 * BELT_ANNULUS_SEGMENTS as usize * 6);
+    }
+}
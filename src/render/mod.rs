@@ -1 +1,4 @@
+pub mod asteroid_belt;
 pub mod backdrop;
+pub mod orbit_lines;
+pub mod rings;
@@ -0,0 +1,374 @@
+//! Orbit paths as cached line meshes
+//!
+//! Every body with an `OrbitPath` gets a line-strip mesh of its orbit,
+//! sampled uniformly in true anomaly so eccentric orbits stay smooth near
+//! periapsis. The mesh is only rebuilt when the orbit's shape, its moon
+//! amplification or its level of detail changes; each frame merely moves the
+//! line to the parent's render position and passes the body's current true
+//! anomaly to the shader, which fades the trail out behind the body.
+//!
+//! Orbits far smaller on screen than the view fade out smoothly as the camera
+//! zooms away, and use fewer segments, so dense systems stay cheap and legible.
+
+use bevy::math::DVec3;
+use bevy::pbr::NotShadowCaster;
+use bevy::prelude::*;
+use bevy::render::mesh::{MeshVertexBufferLayoutRef, PrimitiveTopology};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{
+    AsBindGroup, RenderPipelineDescriptor, ShaderRef, SpecializedMeshPipelineError,
+};
+use std::f64::consts::TAU;
+
+use crate::astronomy::components::{CurrentStarSystem, FloatingOrigin, SystemId};
+use crate::astronomy::systems::{mean_anomaly_to_true_anomaly, orbit_position_from_true_anomaly};
+use crate::astronomy::{
+    update_orbit_visibility, update_render_transform, KeplerOrbit, LocalOrbitAmplification,
+    OrbitPath, SpaceCoordinates, SCALING_FACTOR,
+};
+use crate::plugins::camera::GameCamera;
+use crate::plugins::solar_system::LogicalParent;
+use crate::ui::SimulationTime;
+
+/// Exponent of the trail's fade behind the body
+const TRAIL_FADE_EXPONENT: f32 = 1.8;
+
+/// Apparent size (orbit radius over camera distance) below which an orbit
+/// has faded out completely, and above which it is fully drawn
+const FADE_OUT_SIZE: f32 = 0.004;
+const FADE_IN_SIZE: f32 = 0.02;
+
+/// Fewest segments an orbit is drawn with
+const MIN_SEGMENTS: u32 = 16;
+
+/// Plugin that draws orbit paths as cached meshes
+pub struct OrbitLineRenderPlugin;
+
+impl Plugin for OrbitLineRenderPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(MaterialPlugin::<OrbitLineMaterial>::default())
+            .add_systems(
+                Update,
+                (sync_orbit_lines, despawn_orphaned_orbit_lines)
+                    .chain()
+                    .after(update_orbit_visibility)
+                    .after(update_render_transform),
+            );
+    }
+}
+
+/// Material for orbit lines
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+pub struct OrbitLineMaterial {
+    /// Tint (rgb) and opacity after the zoom fade (a)
+    #[uniform(0)]
+    pub color: Vec4,
+    /// x: the body's true anomaly as a fraction of the orbit, y: fade exponent
+    #[uniform(1)]
+    pub trail: Vec4,
+}
+
+impl Material for OrbitLineMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "shaders/orbit_line.wgsl".into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode {
+        AlphaMode::Blend
+    }
+
+    fn specialize(
+        _pipeline: &bevy::pbr::MaterialPipeline<Self>,
+        descriptor: &mut RenderPipelineDescriptor,
+        _layout: &MeshVertexBufferLayoutRef,
+        _key: bevy::pbr::MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        descriptor.primitive.cull_mode = None;
+        Ok(())
+    }
+}
+
+/// Level of detail of an orbit line, from its apparent size
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrbitLod {
+    /// Filling much of the view: twice the path's segments
+    Near,
+    Normal,
+    /// Small on screen: half the segments
+    Far,
+}
+
+impl OrbitLod {
+    /// Level for an orbit whose radius over the camera distance is `apparent_size`
+    pub fn from_apparent_size(apparent_size: f32) -> Self {
+        if apparent_size > 0.5 {
+            OrbitLod::Near
+        } else if apparent_size > 0.05 {
+            OrbitLod::Normal
+        } else {
+            OrbitLod::Far
+        }
+    }
+
+    /// Segments to draw an orbit path with, adding more for eccentric orbits
+    /// to keep the periapsis region smooth
+    pub fn segments(&self, path: &OrbitPath, eccentricity: f64) -> u32 {
+        let base = if eccentricity > 0.6 {
+            (path.segments as f64 * (1.0 + eccentricity * 2.0)) as u32
+        } else {
+            path.segments
+        };
+        let segments = match self {
+            OrbitLod::Near => base * 2,
+            OrbitLod::Normal => base,
+            OrbitLod::Far => base / 2,
+        };
+        segments.max(MIN_SEGMENTS)
+    }
+}
+
+/// Opacity multiplier fading out orbits that are tiny on screen
+pub fn zoom_fade(apparent_size: f32) -> f32 {
+    let t = ((apparent_size - FADE_OUT_SIZE) / (FADE_IN_SIZE - FADE_OUT_SIZE)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Elements that determine an orbit's drawn shape
+fn orbit_shape(orbit: &KeplerOrbit) -> [f64; 5] {
+    [
+        orbit.eccentricity,
+        orbit.semi_major_axis,
+        orbit.inclination,
+        orbit.longitude_ascending_node,
+        orbit.argument_of_periapsis,
+    ]
+}
+
+/// Line-strip mesh of a full orbit around its parent in render units. U runs
+/// with the true anomaly from 0 to 1.
+pub fn create_orbit_mesh(orbit: &KeplerOrbit, amplification: f64, segments: u32) -> Mesh {
+    let mut positions = Vec::with_capacity(segments as usize + 1);
+    let mut uvs = Vec::with_capacity(segments as usize + 1);
+    for i in 0..=segments {
+        let fraction = i as f64 / segments as f64;
+        let position = orbit_position_from_true_anomaly(orbit, fraction * TAU)
+            * SCALING_FACTOR
+            * amplification;
+        positions.push(position.as_vec3().to_array());
+        uvs.push([fraction as f32, 0.0]);
+    }
+    let normals = vec![[0.0, 1.0, 0.0]; positions.len()];
+
+    let mut mesh = Mesh::new(PrimitiveTopology::LineStrip, RenderAssetUsages::default());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    mesh
+}
+
+/// The cached orbit line of a body and what its mesh was built from
+#[derive(Component, Debug, Clone)]
+pub struct OrbitLine {
+    pub line: Entity,
+    pub mesh: Handle<Mesh>,
+    pub material: Handle<OrbitLineMaterial>,
+    shape: [f64; 5],
+    amplification: f64,
+    segments: u32,
+    /// Color and trail head last written to the material
+    color: Vec4,
+    trail_head: f32,
+}
+
+/// Marker of an orbit line entity, pointing at its body
+#[derive(Component, Debug, Clone, Copy)]
+pub struct OrbitLineOf(pub Entity);
+
+/// System that creates, rebuilds, moves and fades the orbit lines
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn sync_orbit_lines(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<OrbitLineMaterial>>,
+    sim_time: Res<SimulationTime>,
+    current_system: Res<CurrentStarSystem>,
+    floating_origin: Option<Res<FloatingOrigin>>,
+    camera: Query<&GlobalTransform, With<GameCamera>>,
+    mut bodies: Query<(
+        Entity,
+        &KeplerOrbit,
+        &OrbitPath,
+        Option<&mut OrbitLine>,
+        Option<&LogicalParent>,
+        Option<&LocalOrbitAmplification>,
+        Option<&Visibility>,
+        Option<&SystemId>,
+    )>,
+    mut lines: Query<(&mut Transform, &mut Visibility), (With<OrbitLineOf>, Without<KeplerOrbit>)>,
+    parent_coords: Query<&SpaceCoordinates>,
+) {
+    let elapsed_time = sim_time.elapsed_seconds();
+    let origin_offset = floating_origin.map_or(DVec3::ZERO, |fo| fo.position);
+    let camera_position = camera.get_single().map_or(Vec3::ZERO, |t| t.translation());
+
+    for (entity, orbit, path, line, logical_parent, amplification, visibility, system_id) in
+        bodies.iter_mut()
+    {
+        let visible = path.visible
+            && system_id.map_or(0, |s| s.0) == current_system.0
+            && visibility != Some(&Visibility::Hidden);
+
+        let amp = amplification.map_or(1.0, |a| a.0 as f64);
+        let parent_offset = logical_parent
+            .and_then(|lp| parent_coords.get(lp.0).ok())
+            .map_or(Vec3::ZERO, |sc| {
+                ((sc.position - origin_offset) * SCALING_FACTOR).as_vec3()
+            });
+        let radius = (orbit.semi_major_axis * SCALING_FACTOR * amp) as f32;
+        let apparent_size = radius / camera_position.distance(parent_offset).max(1.0);
+        let segments =
+            OrbitLod::from_apparent_size(apparent_size).segments(path, orbit.eccentricity);
+
+        let Some(mut line) = line else {
+            if !visible {
+                continue;
+            }
+            let mesh = meshes.add(create_orbit_mesh(orbit, amp, segments));
+            let material = materials.add(OrbitLineMaterial {
+                color: Vec4::ZERO,
+                trail: Vec4::new(0.0, TRAIL_FADE_EXPONENT, 0.0, 0.0),
+            });
+            let line = commands
+                .spawn((
+                    MaterialMeshBundle {
+                        mesh: mesh.clone(),
+                        material: material.clone(),
+                        transform: Transform::from_translation(parent_offset),
+                        visibility: Visibility::Hidden,
+                        ..default()
+                    },
+                    NotShadowCaster,
+                    OrbitLineOf(entity),
+                    Name::new("Orbit Line"),
+                ))
+                .id();
+            commands.entity(entity).insert(OrbitLine {
+                line,
+                mesh,
+                material,
+                shape: orbit_shape(orbit),
+                amplification: amp,
+                segments,
+                color: Vec4::ZERO,
+                trail_head: 0.0,
+            });
+            continue;
+        };
+
+        let Ok((mut transform, mut line_visibility)) = lines.get_mut(line.line) else {
+            continue;
+        };
+        let wanted = if visible {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        if *line_visibility != wanted {
+            *line_visibility = wanted;
+        }
+        if !visible {
+            continue;
+        }
+
+        // Rebuild the mesh only when its shape or detail changed
+        let shape = orbit_shape(orbit);
+        if line.shape != shape || line.amplification != amp || line.segments != segments {
+            meshes.insert(&line.mesh, create_orbit_mesh(orbit, amp, segments));
+            line.shape = shape;
+            line.amplification = amp;
+            line.segments = segments;
+        }
+
+        transform.translation = parent_offset;
+
+        let mean_anomaly = orbit.mean_anomaly_epoch + orbit.mean_motion * elapsed_time;
+        let true_anomaly =
+            mean_anomaly_to_true_anomaly(mean_anomaly.rem_euclid(TAU), orbit.eccentricity);
+        let base = path.color.to_srgba();
+        let color = Vec4::new(
+            base.red,
+            base.green,
+            base.blue,
+            base.alpha * zoom_fade(apparent_size),
+        );
+        let trail_head = (true_anomaly.rem_euclid(TAU) / TAU) as f32;
+        // Touching the material re-uploads it, so only do so on a change
+        if line.color == color && line.trail_head == trail_head {
+            continue;
+        }
+        if let Some(material) = materials.get_mut(&line.material) {
+            material.color = color;
+            material.trail.x = trail_head;
+            line.color = color;
+            line.trail_head = trail_head;
+        }
+    }
+}
+
+/// System that removes the lines of despawned bodies
+pub fn despawn_orphaned_orbit_lines(
+    mut commands: Commands,
+    lines: Query<(Entity, &OrbitLineOf)>,
+    bodies: Query<(), With<OrbitLine>>,
+) {
+    for (line, body) in lines.iter() {
+        if bodies.get(body.0).is_err() {
+            commands.entity(line).despawn_recursive();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_orbit_mesh_closes_and_runs_with_anomaly() {
+        let orbit = KeplerOrbit::circular(2.0, 1e-7);
+        let mesh = create_orbit_mesh(&orbit, 1.0, 32);
+        let Some(bevy::render::mesh::VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            panic!("positions missing");
+        };
+        assert_eq!(positions.len(), 33);
+        let first = Vec3::from(positions[0]);
+        let last = Vec3::from(positions[32]);
+        assert!(first.distance(last) < 1e-2);
+        assert!((first.length() - 2.0 * SCALING_FACTOR as f32).abs() < 1e-2);
+
+        let Some(bevy::render::mesh::VertexAttributeValues::Float32x2(uvs)) =
+            mesh.attribute(Mesh::ATTRIBUTE_UV_0)
+        else {
+            panic!("uvs missing");
+        };
+        assert_eq!(uvs[0][0], 0.0);
+        assert_eq!(uvs[16][0], 0.5);
+        assert_eq!(uvs[32][0], 1.0);
+    }
+
+    #[test]
+    fn test_lod_and_zoom_fade() {
+        let path = OrbitPath::new(Color::WHITE);
+        assert_eq!(OrbitLod::from_apparent_size(0.8).segments(&path, 0.0), 128);
+        assert_eq!(OrbitLod::from_apparent_size(0.1).segments(&path, 0.0), 64);
+        assert_eq!(OrbitLod::from_apparent_size(0.001).segments(&path, 0.0), 32);
+        // Eccentric orbits get extra segments
+        assert!(OrbitLod::Normal.segments(&path, 0.9) > 64);
+
+        assert_eq!(zoom_fade(0.001), 0.0);
+        assert_eq!(zoom_fade(0.5), 1.0);
+        let middle = zoom_fade((FADE_OUT_SIZE + FADE_IN_SIZE) / 2.0);
+        assert!((middle - 0.5).abs() < 1e-4);
+    }
+}
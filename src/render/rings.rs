@@ -0,0 +1,223 @@
+//! Planetary ring rendering: a flat annulus drawn with a shader that bands
+//! untextured rings procedurally and darkens the part lying in the planet's
+//! shadow.
+//!
+//! Ring entities are transform children of their planet, so they follow its
+//! position and spin axis. Each frame the material is told where the planet
+//! and the nearest star are in render space.
+
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, MeshVertexBufferLayoutRef, PrimitiveTopology};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{
+    AsBindGroup, RenderPipelineDescriptor, ShaderRef, SpecializedMeshPipelineError,
+};
+
+use crate::plugins::solar_system::{CelestialBody, Ring, Star};
+
+/// Plugin that registers the ring material and keeps its lighting current
+pub struct RingRenderPlugin;
+
+impl Plugin for RingRenderPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(MaterialPlugin::<RingMaterial>::default())
+            .add_systems(
+                PostUpdate,
+                update_ring_lighting.after(bevy::transform::TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+/// Material for ring systems
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+pub struct RingMaterial {
+    /// Tint (rgb) and overall opacity (a)
+    #[uniform(0)]
+    pub color: Vec4,
+    /// Render-space position of the illuminating star (xyz)
+    #[uniform(1)]
+    pub sun_position: Vec4,
+    /// Render-space center (xyz) and radius (w) of the shadowing planet
+    #[uniform(2)]
+    pub planet: Vec4,
+    /// x: band pattern seed, y: 1.0 when `texture` holds a radial texture
+    #[uniform(3)]
+    pub bands: Vec4,
+    #[texture(4)]
+    #[sampler(5)]
+    pub texture: Option<Handle<Image>>,
+}
+
+impl RingMaterial {
+    /// Ring material with the given tint and opacity. Without a texture the
+    /// bands are generated from `seed`.
+    pub fn new(color: Color, opacity: f32, texture: Option<Handle<Image>>, seed: f32) -> Self {
+        let tint = color.to_srgba();
+        Self {
+            color: Vec4::new(tint.red, tint.green, tint.blue, opacity),
+            sun_position: Vec4::ZERO,
+            planet: Vec4::ZERO,
+            bands: Vec4::new(seed, if texture.is_some() { 1.0 } else { 0.0 }, 0.0, 0.0),
+            texture,
+        }
+    }
+}
+
+impl Material for RingMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "shaders/ring_material.wgsl".into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode {
+        AlphaMode::Blend
+    }
+
+    // Rings are seen from both sides
+    fn specialize(
+        _pipeline: &bevy::pbr::MaterialPipeline<Self>,
+        descriptor: &mut RenderPipelineDescriptor,
+        _layout: &MeshVertexBufferLayoutRef,
+        _key: bevy::pbr::MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        descriptor.primitive.cull_mode = None;
+        Ok(())
+    }
+}
+
+/// Opacity of a ring of the given mass: Saturn's massive rings are nearly
+/// opaque while the thin rings of the ice giants are barely visible
+pub fn ring_opacity(mass_kg: f64) -> f32 {
+    ((mass_kg.max(1.0).log10() - 13.0) / 7.0).clamp(0.15, 0.9) as f32
+}
+
+/// Tint of a generated ring: bright water ice blending into dark rock dust
+pub fn ring_color(ice_fraction: f32) -> Color {
+    let ice = Vec3::new(0.85, 0.83, 0.78);
+    let dust = Vec3::new(0.42, 0.36, 0.3);
+    let tint = dust.lerp(ice, ice_fraction.clamp(0.0, 1.0));
+    Color::srgb(tint.x, tint.y, tint.z)
+}
+
+/// Inner and outer render radii of a ring, scaled with the planet's visual
+/// radius so the ring keeps its true proportions to the drawn planet
+pub fn ring_visual_radii(ring: &Ring, planet_visual_radius: f32, planet_radius_km: f32) -> (f32, f32) {
+    let scale = planet_visual_radius / planet_radius_km.max(1.0);
+    (ring.inner_radius_km * scale, ring.outer_radius_km * scale)
+}
+
+/// Create a flat ring (annulus) mesh in the XZ plane. U runs from the inner
+/// (0) to the outer (1) edge, V around the ring.
+pub fn create_ring_mesh(outer_radius: f32, inner_radius: f32, segments: u32) -> Mesh {
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+    let mut indices = Vec::new();
+
+    // Create vertices
+    for i in 0..=segments {
+        let angle_fraction = i as f32 / segments as f32; // 0 to 1
+        let angle = angle_fraction * std::f32::consts::TAU;
+        let (sin, cos) = angle.sin_cos();
+
+        // Inner vertex
+        positions.push([inner_radius * cos, 0.0, inner_radius * sin]);
+        normals.push([0.0, 1.0, 0.0]); // Up-facing normal
+
+        // Outer vertex
+        positions.push([outer_radius * cos, 0.0, outer_radius * sin]);
+        normals.push([0.0, 1.0, 0.0]); // Up-facing normal
+
+        uvs.push([0.0, angle_fraction]);
+        uvs.push([1.0, angle_fraction]);
+    }
+
+    // Create indices (two triangles per segment)
+    for i in 0..segments {
+        let base = i * 2;
+        // Vertices at this segment: base (inner), base+1 (outer)
+        // Vertices at next segment: base+2 (inner), base+3 (outer)
+
+        // Triangle 1: Inner-Current, Outer-Current, Inner-Next
+        indices.push(base);
+        indices.push(base + 2);
+        indices.push(base + 1);
+
+        // Triangle 2: Inner-Next, Outer-Next, Outer-Current
+        indices.push(base + 2);
+        indices.push(base + 3);
+        indices.push(base + 1);
+    }
+
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    mesh.insert_indices(Indices::U32(indices));
+
+    mesh
+}
+
+/// System that passes the planet and the nearest star to every ring
+/// material so the shader can cast the planet's shadow
+pub fn update_ring_lighting(
+    rings: Query<(&Handle<RingMaterial>, &Parent), With<Ring>>,
+    planets: Query<(&GlobalTransform, &CelestialBody)>,
+    stars: Query<&GlobalTransform, With<Star>>,
+    mut materials: ResMut<Assets<RingMaterial>>,
+) {
+    for (handle, parent) in rings.iter() {
+        let Ok((planet_transform, planet)) = planets.get(parent.get()) else {
+            continue;
+        };
+        let center = planet_transform.translation();
+        let Some(sun) = stars
+            .iter()
+            .map(|t| t.translation())
+            .min_by(|a, b| a.distance_squared(center).total_cmp(&b.distance_squared(center)))
+        else {
+            continue;
+        };
+        // LOD scaling of the planet also scales its shadow
+        let radius = planet.visual_radius * planet_transform.compute_transform().scale.x;
+
+        let sun_position = sun.extend(1.0);
+        let planet_uniform = center.extend(radius);
+        // Only touch the asset when something moved, to avoid re-uploading it
+        let needs_update = materials
+            .get(handle)
+            .is_some_and(|m| m.sun_position != sun_position || m.planet != planet_uniform);
+        if needs_update {
+            if let Some(material) = materials.get_mut(handle) {
+                material.sun_position = sun_position;
+                material.planet = planet_uniform;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_proportions_follow_the_planet() {
+        let ring = Ring {
+            inner_radius_km: 74_500.0,
+            outer_radius_km: 140_000.0,
+            ice_fraction: 0.95,
+        };
+        let (inner, outer) = ring_visual_radii(&ring, 100.0, 58_232.0);
+        assert!((outer / 100.0 - 140_000.0 / 58_232.0).abs() < 1e-4);
+        assert!((inner / outer - 74_500.0 / 140_000.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_massive_rings_are_more_opaque() {
+        assert!(ring_opacity(1.5e19) > ring_opacity(1.0e16));
+        assert!(ring_opacity(1.0e15) >= 0.15);
+        assert!(ring_opacity(1.0e25) <= 0.9);
+    }
+}
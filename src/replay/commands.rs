@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::commands::PlayerCommand;
+use crate::game_state::Difficulty;
+use crate::scenarios::ScenarioId;
+
+/// File a recorded replay is written to, next to the executable
+pub const REPLAY_PATH: &str = "replay.ron";
+
+/// A command and when it was given
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayEntry {
+    /// Economy ticks processed when the command was given; playback gives it
+    /// again once as many have been processed
+    pub day: u64,
+    /// Simulation time as a Unix timestamp, for reading the file
+    pub timestamp: i64,
+    /// Elapsed simulation seconds; playback holds the clock here until the
+    /// command is given. Missing from older files.
+    #[serde(default)]
+    pub elapsed: Option<f64>,
+    pub command: PlayerCommand,
+}
+
+/// A script loaded at the start of the recorded campaign
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayScript {
+    pub path: PathBuf,
+    pub source: String,
+}
+
+/// Everything needed to re-simulate a campaign: how it started and the
+/// commands given, oldest first
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    /// Seed of the procedural generation and the random generators
+    pub seed: u64,
+    /// Scenario picked on the new-game screen, `None` for the default start
+    pub scenario: Option<ScenarioId>,
+    pub difficulty: Difficulty,
+    /// Orders travelled at the speed of light
+    #[serde(default)]
+    pub command_latency: bool,
+    /// Scripts the campaign started with, loaded in place of the files on
+    /// disk during playback. Missing from older files.
+    #[serde(default)]
+    pub scripts: Option<Vec<ReplayScript>>,
+    pub entries: Vec<ReplayEntry>,
+}
+
+impl Replay {
+    pub fn from_ron(contents: &str) -> Result<Self, String> {
+        ron::from_str(contents).map_err(|e| e.to_string())
+    }
+
+    pub fn to_ron(&self) -> Result<String, String> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::new()).map_err(|e| e.to_string())
+    }
+
+    /// Read a replay from `path`
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Self::from_ron(&contents).map_err(|e| format!("Invalid replay {}: {}", path.display(), e))
+    }
+
+    /// Write the replay to `path`
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_ron()?)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::construction::{LagrangePoint, StationKind, StationLocation};
+    use crate::economy::ResourceType;
+    use crate::trade::TradeDirection;
+
+    #[test]
+    fn test_replay_round_trips() {
+        let replay = Replay {
+            seed: 42,
+            scenario: Some("sol_2026".to_string()),
+            difficulty: Difficulty::Hard,
+            command_latency: true,
+            scripts: Some(vec![ReplayScript {
+                path: PathBuf::from("assets/scripts/bonus.rhai"),
+                source: "fn on_colony_founded(body) { log(body); }".to_string(),
+            }]),
+            entries: vec![
+                ReplayEntry {
+                    day: 3,
+                    timestamp: 1_767_484_800,
+                    elapsed: Some(259_200.0),
+                    command: PlayerCommand::QueueBuilding {
+                        site: "Mars".into(),
+                        building: "iron_mine".to_string(),
+                    },
+                },
+                ReplayEntry {
+                    day: 40,
+                    timestamp: 1_770_681_600,
+                    elapsed: Some(3_456_000.0),
+                    command: PlayerCommand::FoundStation {
+                        host: "Earth".into(),
+                        kind: StationKind::Station,
+                        location: StationLocation::Lagrange(LagrangePoint::L4),
+                    },
+                },
+                ReplayEntry {
+                    day: 41,
+                    timestamp: 1_770_768_000,
+                    elapsed: None,
+                    command: PlayerCommand::SignTrade {
+                        faction: "free_haulers_guild".to_string(),
+                        resource: ResourceType::Water,
+                        direction: TradeDirection::Import,
+                        mt_per_year: 2.5,
+                    },
+                },
+            ],
+        };
+        let contents = replay.to_ron().unwrap();
+        assert_eq!(Replay::from_ron(&contents).unwrap(), replay);
+    }
+}
@@ -0,0 +1,69 @@
+//! Deterministic replay recording
+//!
+//! Every [`PlayerCommand`](crate::commands::PlayerCommand) the interface sends, except changes of speed, is
+//! recorded with the economy day and simulation time it was given at. With
+//! the game seed, scenario, difficulty and the scripts loaded at startup it
+//! is written to a replay file when the game quits or from the main menu.
+//!
+//! Started with `--replay <path>`, the game seeds its generators from the
+//! file, skips the new-game screen, runs the recorded scripts and sends the
+//! recorded commands again on the same economy days, the clock held at the
+//! moment each was given, so the campaign is re-simulated. Commands that
+//! land on a later day are logged as desyncs; commands that name something
+//! missing are dropped with a warning.
+
+use bevy::prelude::*;
+
+pub mod commands;
+pub mod systems;
+
+pub use commands::{Replay, ReplayEntry, ReplayScript, REPLAY_PATH};
+pub use systems::{
+    hold_playback_time, play_back_commands, populate_replayed_systems, record_commands,
+    replay_path_from_args, save_replay, start_playback, ReplayPlayback, ReplayRecorder,
+};
+
+use crate::game_state::GameSeed;
+
+/// Plugin that records and plays back player commands. It must be added
+/// before the plugins whose random generators are seeded from the
+/// [`GameSeed`], so a replay's seed is in place when they are created.
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        let playback = match replay_path_from_args(std::env::args()) {
+            Some(path) => match Replay::load(&path) {
+                Ok(replay) => {
+                    app.insert_resource(GameSeed::new(replay.seed));
+                    ReplayPlayback::new(replay)
+                }
+                Err(e) => {
+                    error!("{}", e);
+                    ReplayPlayback::default()
+                }
+            },
+            None => ReplayPlayback::default(),
+        };
+
+        app.insert_resource(playback)
+            .init_resource::<ReplayRecorder>()
+            .add_systems(Startup, start_playback)
+            .add_systems(
+                Update,
+                populate_replayed_systems
+                    .before(crate::plugins::system_populator::populate_requested_systems),
+            )
+            .add_systems(
+                Update,
+                (play_back_commands, record_commands)
+                    .chain()
+                    .before(crate::commands::apply_player_commands),
+            )
+            .add_systems(
+                Update,
+                hold_playback_time.after(crate::ui::advance_simulation_time),
+            )
+            .add_systems(Last, save_replay);
+    }
+}
@@ -0,0 +1,380 @@
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use std::path::{Path, PathBuf};
+
+use super::commands::{Replay, ReplayEntry, ReplayScript, REPLAY_PATH};
+use crate::astronomy::nearby_stars::NearbyStarsData;
+use crate::commands::{named_command, NamedEntities, PlayerCommand};
+use crate::comms::CommandLatency;
+use crate::economy::EconomyClock;
+use crate::game_state::{Difficulty, EventLog, GameSeed, LogSeverity};
+use crate::plugins::system_populator::{catalog_system_of_body, PopulatedSystems};
+use crate::scenarios::ScenarioSelection;
+use crate::scripting::Scripts;
+use crate::ui::{NewGameScreen, SimulationTime};
+
+/// The commands of the running campaign
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ReplayRecorder {
+    pub entries: Vec<ReplayEntry>,
+    /// Write the replay on the next update
+    pub save_requested: bool,
+}
+
+/// A replay being played back, loaded from the file given with `--replay`
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ReplayPlayback {
+    pub replay: Option<Replay>,
+    /// Index of the next entry to give
+    pub next: usize,
+    /// Elapsed simulation seconds the clock was left at last update
+    held: f64,
+}
+
+impl ReplayPlayback {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay: Some(replay),
+            next: 0,
+            held: 0.0,
+        }
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.replay.is_some()
+    }
+
+    /// The entry to give next, if any is left
+    pub fn next_entry(&self) -> Option<&ReplayEntry> {
+        self.replay.as_ref()?.entries.get(self.next)
+    }
+}
+
+/// Replay file named on the command line with `--replay <path>`
+pub fn replay_path_from_args(mut args: impl Iterator<Item = String>) -> Option<PathBuf> {
+    args.find(|arg| arg == "--replay")?;
+    args.next().map(PathBuf::from)
+}
+
+/// System that starts the replayed campaign as it was recorded: the scenario
+/// and difficulty are set and the new-game screen skipped
+pub fn start_playback(
+    playback: Res<ReplayPlayback>,
+    mut screen: ResMut<NewGameScreen>,
+    mut selection: ResMut<ScenarioSelection>,
+    mut difficulty: ResMut<Difficulty>,
+    mut latency: ResMut<CommandLatency>,
+) {
+    let Some(replay) = &playback.replay else {
+        return;
+    };
+    info!(
+        "Playing back a replay of {} commands (seed {})",
+        replay.entries.len(),
+        replay.seed
+    );
+    screen.open = false;
+    selection.pending = replay.scenario.clone();
+    *difficulty = replay.difficulty;
+    latency.enabled = replay.command_latency;
+}
+
+/// System that asks for the star systems named by the replayed commands to be
+/// populated, once the catalog is loaded, so their bodies exist by the time
+/// the commands are given
+pub fn populate_replayed_systems(
+    playback: Res<ReplayPlayback>,
+    stars_data: Res<NearbyStarsData>,
+    mut populated: ResMut<PopulatedSystems>,
+    mut done: Local<bool>,
+) {
+    let Some(replay) = &playback.replay else {
+        return;
+    };
+    if *done || stars_data.systems.is_empty() {
+        return;
+    }
+    *done = true;
+    for entry in &replay.entries {
+        for name in entry.command.names() {
+            if let Some(system_id) = catalog_system_of_body(&stars_data, name) {
+                populated.request(system_id);
+            }
+        }
+    }
+}
+
+/// System that copies the player commands sent since the last update into
+/// the recording, with the names of the entities they target. Changes of
+/// speed are left out: playback goes at the viewer's speed.
+pub fn record_commands(
+    mut recorder: ResMut<ReplayRecorder>,
+    mut player_commands: EventReader<PlayerCommand>,
+    clock: Res<EconomyClock>,
+    sim_time: Res<SimulationTime>,
+    named: NamedEntities,
+) {
+    let timestamp = sim_time.current_timestamp();
+    let entries: Vec<ReplayEntry> = player_commands
+        .read()
+        .filter(|command| !command.is_time_control())
+        .map(|command| ReplayEntry {
+            day: clock.processed,
+            timestamp,
+            elapsed: Some(sim_time.elapsed_seconds()),
+            command: named_command(&named, command),
+        })
+        .collect();
+    recorder.entries.extend(entries);
+}
+
+/// System that keeps the simulation clock from running past the moment the
+/// next replayed command was given, so it is given at the same simulation
+/// time whatever the frame rate and speed of playback
+pub fn hold_playback_time(
+    mut playback: ResMut<ReplayPlayback>,
+    mut sim_time: ResMut<SimulationTime>,
+) {
+    let Some(elapsed) = playback.next_entry().and_then(|entry| entry.elapsed) else {
+        return;
+    };
+    // A clock already past the command (a desync) is not turned back
+    if playback.held <= elapsed && sim_time.elapsed > elapsed {
+        sim_time.elapsed = elapsed;
+    }
+    playback.held = sim_time.elapsed;
+}
+
+/// System that sends the replayed commands once their economy day and
+/// simulation time are reached, as the interface would. A command given on
+/// a later day than recorded is logged as a desync; one naming something
+/// that doesn't exist is dropped when applied.
+pub fn play_back_commands(
+    mut playback: ResMut<ReplayPlayback>,
+    clock: Res<EconomyClock>,
+    mut player_commands: EventWriter<PlayerCommand>,
+    mut log: Option<ResMut<EventLog>>,
+    sim_time: Res<SimulationTime>,
+) {
+    let playback = &mut *playback;
+    let Some(replay) = &playback.replay else {
+        return;
+    };
+
+    let mut desyncs = Vec::new();
+    while let Some(entry) = replay.entries.get(playback.next) {
+        let early = entry
+            .elapsed
+            .is_some_and(|elapsed| sim_time.elapsed_seconds() < elapsed);
+        if entry.day > clock.processed || early {
+            break;
+        }
+        playback.next += 1;
+        if entry.day < clock.processed {
+            desyncs.push(format!(
+                "command of day {} given on day {}",
+                entry.day, clock.processed
+            ));
+        }
+        player_commands.send(entry.command.clone());
+    }
+
+    for desync in desyncs {
+        let message = format!("Replay desync: {}", desync);
+        warn!("{}", message);
+        if let Some(log) = log.as_mut() {
+            log.push(sim_time.format_date_time(), LogSeverity::Warning, message);
+        }
+    }
+}
+
+/// System that writes the recording when asked to from the main menu, and
+/// when the game quits unless a replay is being played back
+#[allow(clippy::too_many_arguments)]
+pub fn save_replay(
+    mut recorder: ResMut<ReplayRecorder>,
+    playback: Res<ReplayPlayback>,
+    seed: Res<GameSeed>,
+    selection: Res<ScenarioSelection>,
+    difficulty: Res<Difficulty>,
+    latency: Res<CommandLatency>,
+    mut exits: EventReader<AppExit>,
+    mut log: Option<ResMut<EventLog>>,
+    sim_time: Res<SimulationTime>,
+    scripts: Option<Res<Scripts>>,
+) {
+    let quitting = exits.read().count() > 0;
+    let on_exit = quitting && !playback.is_playing() && !recorder.entries.is_empty();
+    if !recorder.save_requested && !on_exit {
+        return;
+    }
+    recorder.save_requested = false;
+
+    let replay = Replay {
+        seed: seed.value,
+        scenario: selection.active.clone(),
+        difficulty: *difficulty,
+        command_latency: latency.enabled,
+        scripts: scripts.map(|scripts| {
+            scripts.scripts[..scripts.startup]
+                .iter()
+                .map(|script| ReplayScript {
+                    path: script.path.clone(),
+                    source: script.source.clone(),
+                })
+                .collect()
+        }),
+        entries: recorder.entries.clone(),
+    };
+    let (severity, message) = match replay.save(Path::new(REPLAY_PATH)) {
+        Ok(()) => (
+            LogSeverity::Info,
+            format!(
+                "Replay of {} commands saved to {}",
+                replay.entries.len(),
+                REPLAY_PATH
+            ),
+        ),
+        Err(e) => (LogSeverity::Warning, e),
+    };
+    info!("{}", message);
+    if let Some(log) = log.as_mut() {
+        log.push(sim_time.format_date_time(), severity, message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::apply_player_commands;
+    use crate::construction::{PendingBlueprintActions, PendingConstructionActions};
+    use crate::contact::PendingContactActions;
+    use crate::economy::{
+        ColonyFoundings, PowerPriorityChanges, Reservations, SurveyMissions, Treasury,
+    };
+    use crate::fleets::FleetOrders;
+    use crate::independence::PendingIndependenceActions;
+    use crate::intel::PendingIntelActions;
+    use crate::plugins::solar_system::CelestialBody;
+    use crate::plugins::solar_system_data::BodyType;
+    use crate::policies::PendingPolicyActions;
+    use crate::random_events::PendingEvents;
+    use crate::research::PendingResearchActions;
+    use crate::trade::PendingTradeActions;
+    use crate::ui::TimeScale;
+
+    fn setup(app: &mut App) -> Entity {
+        app.add_event::<PlayerCommand>()
+            .init_resource::<ReplayRecorder>()
+            .init_resource::<EconomyClock>()
+            .init_resource::<SimulationTime>()
+            .init_resource::<PendingConstructionActions>()
+            .init_resource::<PendingBlueprintActions>()
+            .init_resource::<PendingResearchActions>()
+            .init_resource::<PendingPolicyActions>()
+            .init_resource::<PendingTradeActions>()
+            .init_resource::<PendingIntelActions>()
+            .init_resource::<PendingContactActions>()
+            .init_resource::<PendingIndependenceActions>()
+            .init_resource::<PendingEvents>()
+            .init_resource::<SurveyMissions>()
+            .init_resource::<ColonyFoundings>()
+            .init_resource::<FleetOrders>()
+            .init_resource::<PowerPriorityChanges>()
+            .init_resource::<Reservations>()
+            .init_resource::<Treasury>()
+            .init_resource::<TimeScale>();
+        app.world_mut()
+            .spawn(CelestialBody::fixture("Mars", BodyType::Planet))
+            .id()
+    }
+
+    #[test]
+    fn test_recorded_commands_play_back() {
+        let mut recording = App::new();
+        let mars = setup(&mut recording);
+        recording.add_systems(Update, record_commands);
+        {
+            let world = recording.world_mut();
+            world.resource_mut::<EconomyClock>().processed = 12;
+            world.send_event(PlayerCommand::QueueBuilding {
+                site: mars.into(),
+                building: "iron_mine".to_string(),
+            });
+            world.send_event(PlayerCommand::StartResearch("ion_drive".to_string()));
+            world.send_event(PlayerCommand::Funding {
+                research: 1.2,
+                construction: 1.0,
+            });
+            world.send_event(PlayerCommand::Pause);
+        }
+        recording.update();
+        let entries = recording
+            .world()
+            .resource::<ReplayRecorder>()
+            .entries
+            .clone();
+        assert_eq!(entries.len(), 3);
+        assert!(entries.iter().all(|e| e.day == 12));
+        assert_eq!(entries[0].command.names(), vec!["Mars"]);
+
+        let mut playing = App::new();
+        let mars = setup(&mut playing);
+        playing
+            .insert_resource(ReplayPlayback::new(Replay {
+                entries,
+                ..Default::default()
+            }))
+            .add_systems(Update, (play_back_commands, apply_player_commands).chain());
+        playing.world_mut().resource_mut::<EconomyClock>().processed = 11;
+        playing.update();
+        assert!(playing
+            .world()
+            .resource::<PendingConstructionActions>()
+            .queue
+            .is_empty());
+
+        playing.world_mut().resource_mut::<EconomyClock>().processed = 12;
+        playing.update();
+        let world = playing.world();
+        assert_eq!(
+            world.resource::<PendingConstructionActions>().queue,
+            vec![(mars, "iron_mine".to_string())]
+        );
+        assert_eq!(
+            world.resource::<PendingResearchActions>().start_research,
+            vec!["ion_drive".to_string()]
+        );
+        assert_eq!(world.resource::<Treasury>().research_funding, 1.2);
+        assert_eq!(world.resource::<ReplayPlayback>().next, 3);
+    }
+
+    #[test]
+    fn test_clock_holds_at_the_next_command() {
+        let mut app = App::new();
+        app.init_resource::<SimulationTime>()
+            .insert_resource(ReplayPlayback::new(Replay {
+                entries: vec![ReplayEntry {
+                    day: 0,
+                    timestamp: 0,
+                    elapsed: Some(5_000.0),
+                    command: PlayerCommand::Pause,
+                }],
+                ..Default::default()
+            }))
+            .add_systems(Update, hold_playback_time);
+
+        app.world_mut().resource_mut::<SimulationTime>().elapsed = 3_000.0;
+        app.update();
+        assert_eq!(app.world().resource::<SimulationTime>().elapsed, 3_000.0);
+        app.world_mut().resource_mut::<SimulationTime>().elapsed = 90_000.0;
+        app.update();
+        assert_eq!(app.world().resource::<SimulationTime>().elapsed, 5_000.0);
+
+        // Once the command is given the clock runs on
+        app.world_mut().resource_mut::<ReplayPlayback>().next = 1;
+        app.world_mut().resource_mut::<SimulationTime>().elapsed = 90_000.0;
+        app.update();
+        assert_eq!(app.world().resource::<SimulationTime>().elapsed, 90_000.0);
+    }
+}
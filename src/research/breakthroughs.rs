@@ -0,0 +1,243 @@
+//! Breakthroughs and dead ends
+//!
+//! Once a month every active research project may have a breakthrough, which
+//! adds a chunk of its cost to its progress at once, or run into a dead end,
+//! which loses some. Experienced teams (see [`ResearchTeam::level`]) have
+//! breakthroughs more often and dead ends less often. Outcomes are logged and
+//! drawn from a generator seeded with the [`GameSeed`], so a replay sees the
+//! same ones. They can be turned off in the research debug panel.
+
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::Rng;
+
+use super::components::{ResearchProject, ResearchTeam};
+use super::data::TechnologiesData;
+use super::ResearchDebugSettings;
+use crate::economy::EconomyClock;
+use crate::game_state::{EventLog, GameSeed, LogSeverity};
+use crate::ui::SimulationTime;
+
+/// Economy ticks (days) between rolls
+pub const BREAKTHROUGH_INTERVAL_TICKS: u64 = 30;
+
+/// Monthly chance of a breakthrough for a team without experience
+pub const BREAKTHROUGH_CHANCE: f64 = 0.03;
+
+/// Monthly chance of a dead end for a team without experience
+pub const SETBACK_CHANCE: f64 = 0.03;
+
+/// Breakthroughs become this much more likely per team level in the
+/// project's category, and dead ends this much less
+const CHANCE_PER_LEVEL: f64 = 0.25;
+
+/// Share of a project's cost a breakthrough adds, between these bounds
+const BREAKTHROUGH_SHARE: (f64, f64) = (0.1, 0.25);
+
+/// Share of a project's cost a dead end loses, between these bounds
+const SETBACK_SHARE: (f64, f64) = (0.05, 0.15);
+
+/// Random number generator for research outcomes, seeded from the
+/// [`GameSeed`]
+#[derive(Resource, Debug, Clone)]
+pub struct ResearchRng(pub StdRng);
+
+impl FromWorld for ResearchRng {
+    fn from_world(world: &mut World) -> Self {
+        Self(GameSeed::rng(world, 0x5245_5345_4152))
+    }
+}
+
+/// What a monthly roll did to a project
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResearchOutcome {
+    /// Progress gained as a share of the project's cost
+    Breakthrough(f64),
+    /// Progress lost as a share of the project's cost
+    Setback(f64),
+}
+
+/// Outcome of a roll for a team of `level` in the project's category.
+/// `roll` and `size` are uniform in `[0, 1)`; `size` picks how large the
+/// outcome is.
+pub fn roll_outcome(level: u32, roll: f64, size: f64) -> Option<ResearchOutcome> {
+    let skill = 1.0 + CHANCE_PER_LEVEL * level as f64;
+    let breakthrough = BREAKTHROUGH_CHANCE * skill;
+    let setback = SETBACK_CHANCE / skill;
+    let lerp = |(low, high): (f64, f64)| low + (high - low) * size;
+    if roll < breakthrough {
+        Some(ResearchOutcome::Breakthrough(lerp(BREAKTHROUGH_SHARE)))
+    } else if roll < breakthrough + setback {
+        Some(ResearchOutcome::Setback(lerp(SETBACK_SHARE)))
+    } else {
+        None
+    }
+}
+
+/// System that rolls for breakthroughs and dead ends on every active project
+/// once a month
+#[allow(clippy::too_many_arguments)]
+pub fn roll_research_outcomes(
+    clock: Res<EconomyClock>,
+    debug: Res<ResearchDebugSettings>,
+    tech_data: Res<TechnologiesData>,
+    mut rng: ResMut<ResearchRng>,
+    mut projects: Query<&mut ResearchProject>,
+    teams: Query<&ResearchTeam>,
+    mut log: Option<ResMut<EventLog>>,
+    sim_time: Option<Res<SimulationTime>>,
+) {
+    if clock.due == 0 || debug.disable_breakthroughs {
+        return;
+    }
+    let months_passed = clock.processed / BREAKTHROUGH_INTERVAL_TICKS
+        - (clock.processed - clock.due) / BREAKTHROUGH_INTERVAL_TICKS;
+
+    let mut messages = Vec::new();
+    for _ in 0..months_passed {
+        for mut project in projects.iter_mut() {
+            if !project.active || project.is_complete() {
+                continue;
+            }
+            let Some(tech) = tech_data.get_tech(&project.tech_id) else {
+                continue;
+            };
+            let team = teams.get(project.team_id).ok();
+            let level = team.map_or(0, |t| t.level(tech.category));
+            let (roll, size) = (rng.0.gen::<f64>(), rng.0.gen::<f64>());
+            let team_name = team.map_or("Researchers", |t| t.name.as_str());
+            match roll_outcome(level, roll, size) {
+                Some(ResearchOutcome::Breakthrough(share)) => {
+                    // Leave the completion to advance_research_projects
+                    project.progress = (project.progress + share * project.required_points)
+                        .min(project.required_points);
+                    messages.push((
+                        LogSeverity::Info,
+                        format!(
+                            "💡 Breakthrough: {} advance {} by {:.0}%",
+                            team_name,
+                            tech.name,
+                            share * 100.0
+                        ),
+                    ));
+                }
+                Some(ResearchOutcome::Setback(share)) => {
+                    project.progress =
+                        (project.progress - share * project.required_points).max(0.0);
+                    messages.push((
+                        LogSeverity::Warning,
+                        format!(
+                            "🧱 Dead end: {} lose {:.0}% of the work on {}",
+                            team_name,
+                            share * 100.0,
+                            tech.name
+                        ),
+                    ));
+                }
+                None => {}
+            }
+        }
+    }
+
+    for (severity, message) in messages {
+        info!("{}", message);
+        if let (Some(log), Some(sim_time)) = (log.as_mut(), sim_time.as_ref()) {
+            log.push(sim_time.format_date_time(), severity, message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_experience_favours_breakthroughs() {
+        let outcome = |level, roll| roll_outcome(level, roll, 0.0);
+        assert_eq!(
+            outcome(0, 0.0),
+            Some(ResearchOutcome::Breakthrough(BREAKTHROUGH_SHARE.0))
+        );
+        assert_eq!(
+            outcome(0, BREAKTHROUGH_CHANCE + 0.01),
+            Some(ResearchOutcome::Setback(SETBACK_SHARE.0))
+        );
+        assert_eq!(outcome(0, 0.5), None);
+
+        // The roll that is a dead end for a new team is a breakthrough for a
+        // veteran one
+        assert!(matches!(
+            outcome(4, BREAKTHROUGH_CHANCE + 0.01),
+            Some(ResearchOutcome::Breakthrough(_))
+        ));
+        let setback_range = |level| {
+            (0..1000)
+                .map(|i| i as f64 / 1000.0)
+                .filter(|roll| matches!(outcome(level, *roll), Some(ResearchOutcome::Setback(_))))
+                .count()
+        };
+        assert!(setback_range(4) < setback_range(0));
+    }
+
+    #[test]
+    fn test_one_roll_per_month_between_fixed_ticks() {
+        use rand::SeedableRng;
+
+        use crate::economy::{advance_economy_clock, ECONOMY_TICK_SECONDS};
+        use crate::research::{TechCategory, Technology};
+
+        let mut tech_data = TechnologiesData::default();
+        tech_data.technologies.insert(
+            "fusion".to_string(),
+            Technology {
+                id: "fusion".to_string(),
+                name: "Fusion".to_string(),
+                category: TechCategory::Physics,
+                description: String::new(),
+                research_cost: 1000.0,
+                prerequisites: vec![],
+                unlocks_components: vec![],
+                unlocks_engineering: vec![],
+                modifiers: vec![],
+                tier: 1,
+                required_kardashev: None,
+            },
+        );
+        let rng = StdRng::seed_from_u64(7);
+
+        let mut app = App::new();
+        app.init_resource::<SimulationTime>()
+            .init_resource::<EconomyClock>()
+            .init_resource::<ResearchDebugSettings>()
+            .insert_resource(tech_data)
+            .insert_resource(ResearchRng(rng.clone()))
+            .add_systems(
+                FixedUpdate,
+                (advance_economy_clock, roll_research_outcomes).chain(),
+            );
+        app.world_mut().spawn(ResearchProject::new(
+            "fusion".to_string(),
+            1000.0,
+            Entity::PLACEHOLDER,
+        ));
+        app.world_mut().resource_mut::<SimulationTime>().elapsed =
+            BREAKTHROUGH_INTERVAL_TICKS as f64 * ECONOMY_TICK_SECONDS;
+
+        // One fixed tick reaches the month, then frames render without another
+        // one while the clock still reports the ticks as due
+        app.world_mut().run_schedule(FixedUpdate);
+        for _ in 0..3 {
+            app.update();
+        }
+        assert_eq!(
+            app.world().resource::<EconomyClock>().due,
+            BREAKTHROUGH_INTERVAL_TICKS
+        );
+
+        // The project drew its roll and size once
+        let mut expected = rng;
+        let _ = (expected.gen::<f64>(), expected.gen::<f64>());
+        let mut actual = app.world().resource::<ResearchRng>().0.clone();
+        assert_eq!(actual.gen::<u64>(), expected.gen::<u64>());
+    }
+}
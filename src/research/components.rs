@@ -1,5 +1,6 @@
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 use super::types::{ModifierType, TechCategory, TechnologyId};
 
@@ -8,6 +9,9 @@ use super::types::{ModifierType, TechCategory, TechnologyId};
 pub struct ResearchBuilding {
     /// Research points generated per second
     pub points_per_second: f64,
+    /// Concurrent research projects the laboratory has room for
+    #[serde(default)]
+    pub slots: u32,
 }
 
 /// Component for entities that generate engineering points
@@ -15,22 +19,61 @@ pub struct ResearchBuilding {
 pub struct EngineeringFacility {
     /// Engineering points generated per second
     pub points_per_second: f64,
+    /// Concurrent engineering projects the workshop has room for
+    #[serde(default)]
+    pub slots: u32,
 }
 
-/// Resource tracking research team capacity (placeholder for full team system)
-#[derive(Resource, Debug, Clone)]
+/// Research projects run without any laboratory
+pub const BASE_RESEARCH_SLOTS: usize = 1;
+
+/// Engineering projects run without any workshop
+pub const BASE_ENGINEERING_SLOTS: usize = 1;
+
+/// Resource tracking how many projects can run at once, from the built
+/// research facilities and the technologies that add slots
+#[derive(Resource, Debug, Clone, PartialEq)]
 pub struct ResearchTeamCapacity {
     /// Maximum number of concurrent research projects
     pub max_research_teams: usize,
     /// Maximum number of concurrent engineering projects
     pub max_engineering_teams: usize,
+    /// Research slots of built laboratories
+    pub research_from_labs: usize,
+    /// Research slots added by technologies
+    pub research_from_techs: usize,
 }
 
 impl Default for ResearchTeamCapacity {
+    /// The capacity of a starting colony, until the first update counts
+    /// its laboratories
     fn default() -> Self {
         Self {
             max_research_teams: 3,
             max_engineering_teams: 2,
+            research_from_labs: 2,
+            research_from_techs: 0,
+        }
+    }
+}
+
+impl ResearchTeamCapacity {
+    /// Capacity from the slots of built facilities and researched modifiers
+    pub fn compute(
+        lab_slots: u32,
+        workshop_slots: u32,
+        modifiers: impl Fn(ModifierType) -> f64,
+    ) -> Self {
+        let research_from_labs = lab_slots as usize;
+        let research_from_techs = modifiers(ModifierType::ResearchSlots).max(0.0) as usize;
+        let engineering_from_techs = modifiers(ModifierType::EngineeringSlots).max(0.0) as usize;
+        Self {
+            max_research_teams: BASE_RESEARCH_SLOTS + research_from_labs + research_from_techs,
+            max_engineering_teams: BASE_ENGINEERING_SLOTS
+                + workshop_slots as usize
+                + engineering_from_techs,
+            research_from_labs,
+            research_from_techs,
         }
     }
 }
@@ -117,6 +160,71 @@ impl EngineeringProject {
     }
 }
 
+/// Engineering points a reverse-engineering project needs per research
+/// point it yields
+pub const REVERSE_ENGINEERING_EP_PER_RP: f64 = 0.5;
+
+/// Component for a reverse-engineering project: technology recovered from
+/// salvage, studied with engineering points until its findings count
+/// towards the research of `tech_id`
+#[derive(Component, Debug, Clone)]
+pub struct ReverseEngineeringProject {
+    /// Technology the findings count towards
+    pub tech_id: TechnologyId,
+    /// Salvage the technology was recovered from
+    pub source: String,
+    /// Engineering points spent
+    pub progress: f64,
+    /// Engineering points required to complete
+    pub required_points: f64,
+    /// Research points towards `tech_id` on completion
+    pub research_points: f64,
+}
+
+impl ReverseEngineeringProject {
+    pub fn new(tech_id: TechnologyId, source: String, research_points: f64) -> Self {
+        Self {
+            tech_id,
+            source,
+            progress: 0.0,
+            required_points: research_points * REVERSE_ENGINEERING_EP_PER_RP,
+            research_points,
+        }
+    }
+
+    /// Get progress percentage (0.0 to 1.0)
+    pub fn progress_percent(&self) -> f32 {
+        if self.required_points <= 0.0 {
+            return 1.0;
+        }
+        (self.progress / self.required_points).min(1.0) as f32
+    }
+
+    /// Check if project is complete
+    pub fn is_complete(&self) -> bool {
+        self.progress >= self.required_points
+    }
+}
+
+/// Efficiency bonus of a team in its specialty
+pub const SPECIALTY_BONUS: f32 = 0.2;
+
+/// Research points of completed projects a team needs in a category for its
+/// first level; level `n` needs `n²` times as many
+pub const TEAM_EXPERIENCE_PER_LEVEL: f64 = 10_000.0;
+
+/// Highest level a team reaches in a category
+pub const MAX_TEAM_LEVEL: u32 = 5;
+
+/// Efficiency bonus per level in a project's category
+pub const TEAM_LEVEL_BONUS: f32 = 0.06;
+
+/// Efficiency lost per level the team holds in other categories
+pub const OFF_CATEGORY_PENALTY: f32 = 0.015;
+
+/// Lowest share of its efficiency a team keeps outside its experience
+pub const MIN_OFF_CATEGORY_FACTOR: f32 = 0.85;
+
 /// Component for a research or engineering team
 #[derive(Component, Debug, Clone)]
 pub struct ResearchTeam {
@@ -124,12 +232,15 @@ pub struct ResearchTeam {
     pub name: String,
     /// Lead scientist/engineer name (character)
     pub lead_character: String,
-    /// Specialty category (provides bonus to this category)
+    /// Specialty category (provides bonus to this category). Follows the
+    /// category the team has most experience in.
     pub specialty: Option<TechCategory>,
     /// Efficiency multiplier (1.0 = normal, higher = faster)
     pub efficiency: f32,
     /// Whether this is a research team (true) or engineering team (false)
     pub is_research: bool,
+    /// Research points of the projects completed in each category
+    pub experience: HashMap<TechCategory, f64>,
 }
 
 impl ResearchTeam {
@@ -145,6 +256,7 @@ impl ResearchTeam {
             specialty,
             efficiency: 1.0,
             is_research: true,
+            experience: HashMap::new(),
         }
     }
 
@@ -160,17 +272,46 @@ impl ResearchTeam {
             specialty,
             efficiency: 1.0,
             is_research: false,
+            experience: HashMap::new(),
         }
     }
 
-    /// Get efficiency for a specific category
+    /// Level of the team in a category, from the experience it gathered
+    pub fn level(&self, category: TechCategory) -> u32 {
+        let experience = self.experience.get(&category).copied().unwrap_or(0.0);
+        ((experience / TEAM_EXPERIENCE_PER_LEVEL).sqrt().floor() as u32).min(MAX_TEAM_LEVEL)
+    }
+
+    /// Record a completed project and return the new level if the team
+    /// reached one. The specialty moves to the category the team is most
+    /// experienced in.
+    pub fn gain_experience(&mut self, category: TechCategory, points: f64) -> Option<u32> {
+        let before = self.level(category);
+        *self.experience.entry(category).or_insert(0.0) += points.max(0.0);
+        let after = self.level(category);
+        let specialty_level = self.specialty.map_or(0, |s| self.level(s));
+        if after > specialty_level {
+            self.specialty = Some(category);
+        }
+        (after > before).then_some(after)
+    }
+
+    /// Get efficiency for a specific category: levels in the category speed
+    /// the team up, levels in other categories slow it down a little
     pub fn category_efficiency(&self, category: TechCategory) -> f32 {
-        if let Some(spec) = self.specialty {
-            if spec == category {
-                return self.efficiency * 1.2; // 20% bonus for specialty
-            }
+        let mut efficiency = self.efficiency;
+        if self.specialty == Some(category) {
+            efficiency *= 1.0 + SPECIALTY_BONUS;
         }
-        self.efficiency
+        let levels_elsewhere: u32 = self
+            .experience
+            .keys()
+            .filter(|c| **c != category)
+            .map(|c| self.level(*c))
+            .sum();
+        efficiency
+            * (1.0 + TEAM_LEVEL_BONUS * self.level(category) as f32)
+            * (1.0 - OFF_CATEGORY_PENALTY * levels_elsewhere as f32).max(MIN_OFF_CATEGORY_FACTOR)
     }
 }
 
@@ -218,6 +359,18 @@ mod tests {
         assert_eq!(cap.max_engineering_teams, 2);
     }
 
+    #[test]
+    fn test_capacity_counts_labs_and_technologies() {
+        let capacity = ResearchTeamCapacity::compute(2, 0, |modifier| match modifier {
+            ModifierType::ResearchSlots => 1.0,
+            _ => 0.0,
+        });
+        assert_eq!(capacity.max_research_teams, BASE_RESEARCH_SLOTS + 3);
+        assert_eq!(capacity.research_from_labs, 2);
+        assert_eq!(capacity.research_from_techs, 1);
+        assert_eq!(capacity.max_engineering_teams, BASE_ENGINEERING_SLOTS);
+    }
+
     #[test]
     fn test_research_project_progress() {
         let team = Entity::from_raw(1);
@@ -263,4 +416,29 @@ mod tests {
         // Should not get bonus for other categories
         assert!((team.category_efficiency(TechCategory::Biology) - 1.0).abs() < 0.001);
     }
+
+    #[test]
+    fn test_team_experience_levels_and_specializes() {
+        let mut team = ResearchTeam::new_research(
+            "Beta Team".to_string(),
+            "Dr. Okafor".to_string(),
+            Some(TechCategory::Physics),
+        );
+        assert_eq!(team.gain_experience(TechCategory::Energy, 5_000.0), None);
+        assert_eq!(
+            team.gain_experience(TechCategory::Energy, 35_000.0),
+            Some(2)
+        );
+        assert_eq!(team.level(TechCategory::Energy), 2);
+        // The specialty follows the experience
+        assert_eq!(team.specialty, Some(TechCategory::Energy));
+
+        let energy = 1.2 * (1.0 + 2.0 * TEAM_LEVEL_BONUS);
+        assert!((team.category_efficiency(TechCategory::Energy) - energy).abs() < 0.001);
+        let physics = 1.0 - 2.0 * OFF_CATEGORY_PENALTY;
+        assert!((team.category_efficiency(TechCategory::Physics) - physics).abs() < 0.001);
+
+        team.gain_experience(TechCategory::Energy, 1e9);
+        assert_eq!(team.level(TechCategory::Energy), MAX_TEAM_LEVEL);
+    }
 }
@@ -2,8 +2,10 @@ use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
+use std::path::PathBuf;
 
 use super::types::{ComponentDefinition, Technology, TechnologyId};
+use crate::modding::ModList;
 
 /// Resource that holds all technology definitions loaded from data files
 #[derive(Resource, Debug, Clone, Default)]
@@ -12,6 +14,9 @@ pub struct TechnologiesData {
     pub technologies: HashMap<TechnologyId, Technology>,
     /// All component definitions
     pub components: HashMap<String, ComponentDefinition>,
+    /// Bumped whenever the definitions change at runtime, so views laid out
+    /// from them know to refresh
+    pub version: u64,
 }
 
 impl TechnologiesData {
@@ -43,63 +48,115 @@ impl TechnologiesData {
             false
         }
     }
+
+    /// Check whether a Kardashev milestone a technology requires has been
+    /// reached
+    pub fn check_kardashev(&self, tech_id: &str, reached: f64) -> bool {
+        self.get_tech(tech_id).is_some_and(|tech| {
+            tech.required_kardashev
+                .is_none_or(|required| reached >= required)
+        })
+    }
 }
 
-/// Data file format for technologies
+/// Data file format for technologies. Mods may leave out either list.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct TechnologiesFile {
+    #[serde(default)]
     technologies: Vec<Technology>,
+    #[serde(default)]
     components: Vec<ComponentDefinition>,
 }
 
-/// System to load technologies from data file at startup
-pub fn load_technologies(mut commands: Commands) {
-    info!("Loading technology definitions...");
-
-    let path = "assets/data/technologies.ron";
-    
-    match fs::read_to_string(path) {
-        Ok(contents) => {
-            match ron::from_str::<TechnologiesFile>(&contents) {
-                Ok(data) => {
-                    let tech_count = data.technologies.len();
-                    let component_count = data.components.len();
-
-                    let mut tech_data = TechnologiesData::default();
-
-                    // Index technologies by ID
-                    for tech in data.technologies {
-                        tech_data.technologies.insert(tech.id.clone(), tech);
-                    }
-
-                    // Index components by ID
-                    for component in data.components {
-                        tech_data.components.insert(component.id.clone(), component);
-                    }
-
-                    info!(
-                        "Loaded {} technologies and {} component definitions",
-                        tech_count, component_count
-                    );
-
-                    commands.insert_resource(tech_data);
-                }
-                Err(e) => {
-                    error!("Failed to parse technology data file: {}", e);
-                    // Insert empty resource so the game doesn't crash
-                    commands.insert_resource(TechnologiesData::default());
-                }
-            }
+impl TechnologiesData {
+    /// Add the definitions of a data file, replacing those with the same ID
+    fn merge(&mut self, data: TechnologiesFile) {
+        for tech in data.technologies {
+            self.technologies.insert(tech.id.clone(), tech);
+        }
+        for component in data.components {
+            self.components.insert(component.id.clone(), component);
         }
-        Err(e) => {
-            warn!(
-                "Technology data file not found at {}: {}. Using empty tech tree.",
-                path, e
-            );
-            // Insert empty resource so the game doesn't crash
-            commands.insert_resource(TechnologiesData::default());
+    }
+}
+
+/// Base technology data file
+pub const TECHNOLOGIES_PATH: &str = "assets/data/technologies.ron";
+
+impl TechnologiesData {
+    /// Prerequisites and component technologies that are not defined
+    pub fn unknown_references(&self) -> Vec<String> {
+        let mut unknown: Vec<String> = self
+            .technologies
+            .values()
+            .flat_map(|tech| {
+                tech.prerequisites
+                    .iter()
+                    .filter(|prereq| !self.technologies.contains_key(*prereq))
+                    .map(move |prereq| {
+                        format!("technology '{}' requires unknown '{}'", tech.id, prereq)
+                    })
+            })
+            .chain(
+                self.components
+                    .values()
+                    .filter(|c| !self.technologies.contains_key(&c.required_tech))
+                    .map(|c| {
+                        format!(
+                            "component '{}' requires unknown technology '{}'",
+                            c.id, c.required_tech
+                        )
+                    }),
+            )
+            .collect();
+        unknown.sort();
+        unknown
+    }
+}
+
+/// Read the base technologies followed by those of enabled mods. Files that
+/// can't be read or parsed are skipped and reported in the returned errors.
+pub fn read_technologies(mods: Option<&ModList>) -> (TechnologiesData, Vec<String>) {
+    let mut tech_data = TechnologiesData::default();
+    let mut errors = Vec::new();
+
+    let mut paths = vec![PathBuf::from(TECHNOLOGIES_PATH)];
+    paths.extend(mods.map_or(Vec::new(), |mods| mods.data_files("technologies.ron")));
+    for path in paths {
+        match fs::read_to_string(&path) {
+            Ok(contents) => match ron::from_str::<TechnologiesFile>(&contents) {
+                Ok(data) => tech_data.merge(data),
+                Err(e) => errors.push(format!("Failed to parse {}: {}", path.display(), e)),
+            },
+            Err(e) => errors.push(format!("Failed to read {}: {}", path.display(), e)),
         }
     }
+
+    (tech_data, errors)
+}
+
+/// System to load technologies from data file at startup, followed by the
+/// technologies of enabled mods
+pub fn load_technologies(mut commands: Commands, mods: Option<Res<ModList>>) {
+    info!("Loading technology definitions...");
+
+    let (tech_data, errors) = read_technologies(mods.as_deref());
+    for e in errors {
+        error!("{}", e);
+    }
+    for reference in tech_data.unknown_references() {
+        warn!("Technology data: {}", reference);
+    }
+
+    info!(
+        "Loaded {} technologies and {} component definitions",
+        tech_data.technologies.len(),
+        tech_data.components.len()
+    );
+
+    // Several research systems take `Res<TechnologiesData>`, so insert it
+    // even when loading failed
+    commands.insert_resource(tech_data);
 }
 
 #[cfg(test)]
@@ -121,6 +178,7 @@ mod tests {
             unlocks_engineering: vec![],
             modifiers: vec![],
             tier: 1,
+            required_kardashev: None,
         };
 
         data.technologies.insert("test_tech".to_string(), tech);
@@ -144,6 +202,7 @@ mod tests {
             unlocks_engineering: vec![],
             modifiers: vec![],
             tier: 1,
+            required_kardashev: None,
         };
 
         let tech2 = Technology {
@@ -157,6 +216,7 @@ mod tests {
             unlocks_engineering: vec![],
             modifiers: vec![],
             tier: 2,
+            required_kardashev: None,
         };
 
         data.technologies.insert("tech1".to_string(), tech1);
@@ -169,4 +229,42 @@ mod tests {
         let unlocked = vec!["tech1".to_string()];
         assert!(data.check_prerequisites("tech2", &unlocked));
     }
+
+    #[test]
+    fn test_unknown_references() {
+        let mut data = TechnologiesData::default();
+        data.technologies.insert(
+            "tech1".to_string(),
+            Technology {
+                id: "tech1".to_string(),
+                name: "Tech 1".to_string(),
+                category: TechCategory::Physics,
+                description: "First tech".to_string(),
+                research_cost: 1000.0,
+                prerequisites: vec!["missing".to_string()],
+                unlocks_components: vec![],
+                unlocks_engineering: vec![],
+                modifiers: vec![],
+                tier: 1,
+                required_kardashev: None,
+            },
+        );
+        data.components.insert(
+            "comp".to_string(),
+            ComponentDefinition {
+                id: "comp".to_string(),
+                name: "Component".to_string(),
+                description: String::new(),
+                engineering_cost: 100.0,
+                required_tech: "tech1".to_string(),
+            },
+        );
+        assert_eq!(
+            data.unknown_references(),
+            ["technology 'tech1' requires unknown 'missing'"]
+        );
+
+        data.technologies.get_mut("tech1").unwrap().prerequisites.clear();
+        assert!(data.unknown_references().is_empty());
+    }
 }
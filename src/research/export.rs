@@ -0,0 +1,310 @@
+//! Export of the tech tree for review outside the game
+//!
+//! The whole tree can be written as a GraphViz DOT file, for `dot` to lay
+//! out or for tools that read graphs, or as a self-contained SVG image laid
+//! out like the graph view: one column per tier, grouped by category. Both
+//! colour technologies by their state in the current game.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+
+use super::data::TechnologiesData;
+use super::systems::ResearchState;
+use super::types::Technology;
+
+/// File the tech tree is exported to as GraphViz DOT
+pub const TECH_TREE_DOT_PATH: &str = "tech_tree.dot";
+/// File the tech tree is exported to as an SVG image
+pub const TECH_TREE_SVG_PATH: &str = "tech_tree.svg";
+
+/// SVG layout in pixels
+const NODE_WIDTH: f64 = 220.0;
+const NODE_HEIGHT: f64 = 44.0;
+const TIER_SPACING: f64 = 300.0;
+const ROW_SPACING: f64 = 60.0;
+const CATEGORY_SPACING: f64 = 20.0;
+const MARGIN: f64 = 40.0;
+
+/// State of a technology in the current game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TechState {
+    Unlocked,
+    /// Every prerequisite and milestone is met
+    Available,
+    Locked,
+}
+
+impl TechState {
+    pub fn of(tech: &Technology, state: &ResearchState) -> Self {
+        if state.is_unlocked(&tech.id) {
+            TechState::Unlocked
+        } else if tech.prerequisites.iter().all(|p| state.is_unlocked(p))
+            && tech
+                .required_kardashev
+                .is_none_or(|required| state.kardashev_reached >= required)
+        {
+            TechState::Available
+        } else {
+            TechState::Locked
+        }
+    }
+
+    /// Fill and outline colours
+    fn colors(self) -> (&'static str, &'static str) {
+        match self {
+            TechState::Unlocked => ("#2e6b34", "#64dc64"),
+            TechState::Available => ("#6b5a1e", "#ffc800"),
+            TechState::Locked => ("#3a3a3a", "#787878"),
+        }
+    }
+}
+
+/// Technologies by tier, each tier sorted by category and name
+fn tiers(data: &TechnologiesData) -> BTreeMap<u32, Vec<&Technology>> {
+    let mut tiers: BTreeMap<u32, Vec<&Technology>> = BTreeMap::new();
+    for tech in data.technologies.values() {
+        tiers.entry(tech.tier).or_default().push(tech);
+    }
+    for techs in tiers.values_mut() {
+        techs.sort_by(|a, b| {
+            (a.category, a.name.as_str(), a.id.as_str()).cmp(&(
+                b.category,
+                b.name.as_str(),
+                b.id.as_str(),
+            ))
+        });
+    }
+    tiers
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// The tech tree as a GraphViz digraph, prerequisites pointing to the
+/// technologies they lead to and each tier on one rank
+pub fn to_dot(data: &TechnologiesData, state: &ResearchState) -> String {
+    let mut dot = String::new();
+    dot.push_str("digraph tech_tree {\n");
+    dot.push_str("    rankdir=LR;\n");
+    dot.push_str("    node [shape=box, style=\"rounded,filled\", fontcolor=white];\n");
+    let tiers = tiers(data);
+    for (tier, techs) in &tiers {
+        let _ = writeln!(dot, "    subgraph tier_{} {{", tier);
+        dot.push_str("        rank=same;\n");
+        for tech in techs {
+            let (fill, outline) = TechState::of(tech, state).colors();
+            let _ = writeln!(
+                dot,
+                "        \"{}\" [label=\"{}\\n{} · {:.0} RP\", fillcolor=\"{}\", color=\"{}\"];",
+                escape_dot(&tech.id),
+                escape_dot(&tech.name),
+                tech.category.display_name(),
+                tech.research_cost,
+                fill,
+                outline
+            );
+        }
+        dot.push_str("    }\n");
+    }
+    for techs in tiers.values() {
+        for tech in techs {
+            let mut prerequisites: Vec<_> = tech
+                .prerequisites
+                .iter()
+                .filter(|p| data.technologies.contains_key(*p))
+                .collect();
+            prerequisites.sort();
+            for prerequisite in prerequisites {
+                let _ = writeln!(
+                    dot,
+                    "    \"{}\" -> \"{}\";",
+                    escape_dot(prerequisite),
+                    escape_dot(&tech.id)
+                );
+            }
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// The tech tree as an SVG image in the layout of the graph view
+pub fn to_svg(data: &TechnologiesData, state: &ResearchState) -> String {
+    // Top-left corner of every node
+    let mut positions = std::collections::HashMap::new();
+    let mut height: f64 = 0.0;
+    let tiers = tiers(data);
+    for (column, techs) in tiers.values().enumerate() {
+        let x = MARGIN + column as f64 * TIER_SPACING;
+        let mut y = MARGIN;
+        let mut last_category = None;
+        for tech in techs {
+            if last_category.is_some_and(|category| category != tech.category) {
+                y += CATEGORY_SPACING;
+            }
+            last_category = Some(tech.category);
+            positions.insert(tech.id.as_str(), (x, y));
+            y += NODE_HEIGHT + ROW_SPACING;
+        }
+        height = height.max(y);
+    }
+    let width = MARGIN * 2.0 + tiers.len().saturating_sub(1) as f64 * TIER_SPACING + NODE_WIDTH;
+    let height = height.max(MARGIN * 2.0);
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{:.0}\" height=\"{:.0}\" \
+         viewBox=\"0 0 {:.0} {:.0}\" font-family=\"sans-serif\">",
+        width, height, width, height
+    );
+    svg.push_str("<rect width=\"100%\" height=\"100%\" fill=\"#1b1b1b\"/>\n");
+
+    // Connections from the right edge of a prerequisite to the left edge of
+    // the technology it leads to, drawn below the nodes
+    for techs in tiers.values() {
+        for tech in techs {
+            let Some(&(x, y)) = positions.get(tech.id.as_str()) else {
+                continue;
+            };
+            for prerequisite in &tech.prerequisites {
+                let Some(&(px, py)) = positions.get(prerequisite.as_str()) else {
+                    continue;
+                };
+                let (x0, y0) = (px + NODE_WIDTH, py + NODE_HEIGHT / 2.0);
+                let (x1, y1) = (x, y + NODE_HEIGHT / 2.0);
+                let bend = (x1 - x0).abs() / 2.0;
+                let color = if state.is_unlocked(prerequisite) {
+                    "#64dc64"
+                } else {
+                    "#787878"
+                };
+                let _ = writeln!(
+                    svg,
+                    "<path d=\"M{:.1},{:.1} C{:.1},{:.1} {:.1},{:.1} {:.1},{:.1}\" \
+                     fill=\"none\" stroke=\"{}\" stroke-opacity=\"0.6\"/>",
+                    x0,
+                    y0,
+                    x0 + bend,
+                    y0,
+                    x1 - bend,
+                    y1,
+                    x1,
+                    y1,
+                    color
+                );
+            }
+        }
+    }
+
+    for techs in tiers.values() {
+        for tech in techs {
+            let (x, y) = positions[tech.id.as_str()];
+            let (fill, outline) = TechState::of(tech, state).colors();
+            let _ = writeln!(svg, "<g><title>{}</title>", escape_xml(&tech.description));
+            let _ = writeln!(
+                svg,
+                "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.0}\" height=\"{:.0}\" rx=\"6\" \
+                 fill=\"{}\" stroke=\"{}\"/>",
+                x, y, NODE_WIDTH, NODE_HEIGHT, fill, outline
+            );
+            let _ = writeln!(
+                svg,
+                "<text x=\"{:.1}\" y=\"{:.1}\" font-size=\"13\" fill=\"white\">{}</text>",
+                x + 8.0,
+                y + 18.0,
+                escape_xml(&tech.name)
+            );
+            let _ = writeln!(
+                svg,
+                "<text x=\"{:.1}\" y=\"{:.1}\" font-size=\"10\" fill=\"#c8c8c8\">{} · {:.0} RP</text>",
+                x + 8.0,
+                y + 34.0,
+                escape_xml(tech.category.display_name()),
+                tech.research_cost
+            );
+            svg.push_str("</g>\n");
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Write the tech tree to `path`, as SVG if it ends in `.svg` and as DOT
+/// otherwise
+pub fn export_tech_tree(
+    data: &TechnologiesData,
+    state: &ResearchState,
+    path: &str,
+) -> Result<(), String> {
+    let contents = if path.ends_with(".svg") {
+        to_svg(data, state)
+    } else {
+        to_dot(data, state)
+    };
+    fs::write(path, contents).map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::research::types::TechCategory;
+
+    fn tech(id: &str, tier: u32, prerequisites: &[&str]) -> Technology {
+        Technology {
+            id: id.to_string(),
+            name: format!("Tech \"{}\" & co", id),
+            category: TechCategory::Physics,
+            description: String::new(),
+            research_cost: 100.0,
+            prerequisites: prerequisites.iter().map(|p| p.to_string()).collect(),
+            unlocks_components: Vec::new(),
+            unlocks_engineering: Vec::new(),
+            modifiers: Vec::new(),
+            tier,
+            required_kardashev: None,
+        }
+    }
+
+    #[test]
+    fn test_exports_color_techs_by_state() {
+        let mut data = TechnologiesData::default();
+        for tech in [
+            tech("a", 0, &[]),
+            tech("b", 1, &["a"]),
+            tech("c", 2, &["b"]),
+        ] {
+            data.technologies.insert(tech.id.clone(), tech);
+        }
+        let mut state = ResearchState::default();
+        state.unlock_tech("a".to_string());
+
+        let dot = to_dot(&data, &state);
+        assert!(dot.contains("\"a\" -> \"b\";"));
+        assert!(dot.contains("\"b\" -> \"c\";"));
+        assert!(dot.contains("Tech \\\"a\\\" & co"));
+        assert_eq!(
+            TechState::of(&data.technologies["b"], &state),
+            TechState::Available
+        );
+        assert_eq!(
+            TechState::of(&data.technologies["c"], &state),
+            TechState::Locked
+        );
+
+        let svg = to_svg(&data, &state);
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("Tech &quot;a&quot; &amp; co"));
+        assert_eq!(svg.matches("<path").count(), 2);
+        assert!(svg.contains("fill=\"#2e6b34\""));
+    }
+}
@@ -3,26 +3,39 @@
 //! Provides a comprehensive research system including:
 //! - Tech tree with 1000+ technologies across multiple categories
 //! - Separation of Research (discovery) and Engineering (application)
-//! - Research teams with limited slots (Aurora 4X style)
+//! - Research teams with limited slots (Aurora 4X style) from the built
+//!   laboratories and slot technologies, hired for credits
+//!   and gaining experience in the categories they complete projects in
 //! - Component designs that require engineering
+//! - Reverse engineering of technology recovered from salvage
 //! - Technology modifiers that affect civilization stats
 //! - Data-driven technology definitions for easy modding
+//! - Export of the tech tree as a GraphViz DOT file or an SVG image
+//! - Monthly breakthroughs and dead ends on active projects
 
 use bevy::prelude::*;
 
+pub mod breakthroughs;
 pub mod components;
 pub mod data;
+pub mod export;
 pub mod systems;
+pub mod teams;
 pub mod types;
 
+pub use breakthroughs::{roll_research_outcomes, ResearchOutcome, ResearchRng};
 pub use components::{
     ComponentDesign, EngineeringFacility, EngineeringProject, ResearchBuilding, ResearchProject,
-    ResearchTeam, ResearchTeamCapacity,
+    ResearchTeam, ResearchTeamCapacity, ReverseEngineeringProject,
 };
-pub use data::{load_technologies, TechnologiesData};
+pub use data::{load_technologies, read_technologies, TechnologiesData, TECHNOLOGIES_PATH};
+pub use export::{export_tech_tree, TechState, TECH_TREE_DOT_PATH, TECH_TREE_SVG_PATH};
 pub use systems::{
-    advance_engineering_projects, advance_research_projects, check_unlocked_technologies,
-    update_research_points, ResearchState,
+    advance_engineering_projects, advance_research_projects, advance_reverse_engineering,
+    check_unlocked_technologies, update_research_points, ResearchCompleted, ResearchState,
+};
+pub use teams::{
+    idle_team, process_team_actions, spawn_starting_teams, MAX_RESEARCH_TEAMS, TEAM_HIRE_COST,
 };
 pub use types::{TechCategory, Technology, TechnologyId};
 
@@ -37,6 +50,8 @@ pub struct ResearchDebugSettings {
     pub instant_research: bool,
     /// Instant engineering (0 cost)
     pub instant_engineering: bool,
+    /// No breakthroughs or dead ends
+    pub disable_breakthroughs: bool,
 }
 
 impl Default for ResearchDebugSettings {
@@ -46,6 +61,7 @@ impl Default for ResearchDebugSettings {
             show_all_techs: false,
             instant_research: false,
             instant_engineering: false,
+            disable_breakthroughs: false,
         }
     }
 }
@@ -150,6 +166,14 @@ pub struct PendingResearchActions {
     pub navigate_to_available_tab: bool,
     /// Updated allocation percentages: (tech_id, new_percent)
     pub update_allocations: Vec<(TechnologyId, f64)>,
+    /// Technology to select and center in the Tech Tree tab.
+    pub focus_tech: Option<TechnologyId>,
+    /// Research teams to hire, with the specialty they bring
+    pub hire_teams: Vec<Option<TechCategory>>,
+    /// Research teams to give a new name
+    pub rename_teams: Vec<(Entity, String)>,
+    /// Research teams to let go
+    pub retire_teams: Vec<Entity>,
 }
 
 /// Plugin that adds the research system to the Bevy app
@@ -164,18 +188,29 @@ impl Plugin for ResearchPlugin {
             .init_resource::<TechTreeEditState>()
             .init_resource::<PendingResearchActions>()
             .init_resource::<ResearchTeamCapacity>()
+            .init_resource::<ResearchRng>()
+            .add_event::<ResearchCompleted>()
             // Startup systems
-            .add_systems(Startup, load_technologies)
+            .add_systems(Startup, (load_technologies, spawn_starting_teams))
+            // Monthly rolls count economy ticks, so they run on the fixed step
+            // that advances the clock
+            .add_systems(
+                FixedUpdate,
+                roll_research_outcomes.after(crate::economy::advance_economy_clock),
+            )
             // Update systems
             .add_systems(
                 Update,
                 (
                     update_research_points,
+                    systems::update_team_capacity,
+                    process_team_actions,
                     systems::process_pending_research,
                     systems::process_stop_research,
                     systems::process_allocation_updates,
                     advance_research_projects,
                     advance_engineering_projects,
+                    advance_reverse_engineering,
                     check_unlocked_technologies,
                 ).chain(),
             );
@@ -1,13 +1,17 @@
 use bevy::prelude::*;
 use std::collections::{HashMap, HashSet};
 
+use crate::economy::{condition_factor_for, Condition, PowerGrids, PowerTier, Treasury};
+use crate::game_state::{Difficulty, EventLog, LogSeverity};
+use crate::plugins::solar_system::LogicalParent;
 use crate::ui::SimulationTime;
 
 use super::components::{
     ComponentDesign, EngineeringFacility, EngineeringProject, ResearchBuilding, ResearchProject,
-    ResearchTeam, ResearchTeamCapacity,
+    ResearchTeam, ResearchTeamCapacity, ReverseEngineeringProject,
 };
 use super::data::TechnologiesData;
+use super::teams::idle_team;
 use super::types::{ModifierType, TechCategory, TechnologyId};
 use super::PendingResearchActions;
 
@@ -28,6 +32,12 @@ pub struct ResearchState {
     pub rp_rate_per_second: f64,
     /// Current EP generation rate (EP per game-second) for UI display
     pub ep_rate_per_second: f64,
+    /// Highest Kardashev milestone reached, which gates megastructure
+    /// technologies
+    pub kardashev_reached: f64,
+    /// Research points from reverse engineering towards technologies not
+    /// being researched yet, given to their projects when they start
+    pub reverse_engineered: HashMap<TechnologyId, f64>,
 }
 
 impl ResearchState {
@@ -64,6 +74,11 @@ impl ResearchState {
             .unwrap_or(0.0)
     }
 
+    /// Check if a game mechanic has been unlocked by a technology
+    pub fn has_mechanic(&self, mechanic: &str) -> bool {
+        self.get_modifier(ModifierType::UnlockMechanic(mechanic.to_string())) > 0.0
+    }
+
     /// Get research speed multiplier (1.0 + bonus from modifiers)
     pub fn research_speed_multiplier(&self) -> f64 {
         1.0 + (self.get_modifier(ModifierType::ResearchSpeed) / 100.0)
@@ -89,11 +104,17 @@ const SECONDS_PER_YEAR: f64 = 31_557_600.0;
 
 /// System to compute current RP/EP generation rates and accumulate EP.
 /// RP accumulation is handled in advance_research_projects to account for allocations.
+/// RP generation scales with the treasury's research funding, and buildings
+/// of browned-out locations generate at the share of their power they get.
+#[allow(clippy::too_many_arguments)]
 pub fn update_research_points(
     sim_time: Res<SimulationTime>,
     mut research_state: ResMut<ResearchState>,
-    research_buildings: Query<&ResearchBuilding>,
-    engineering_facilities: Query<&EngineeringFacility>,
+    treasury: Option<Res<Treasury>>,
+    power: Option<Res<PowerGrids>>,
+    conditions: Query<&Condition>,
+    research_buildings: Query<(Entity, &ResearchBuilding, Option<&LogicalParent>)>,
+    engineering_facilities: Query<(Entity, &EngineeringFacility, Option<&LogicalParent>)>,
     mut last_time: Local<f64>,
 ) {
     let current_time = sim_time.elapsed_seconds();
@@ -104,32 +125,107 @@ pub fn update_research_points(
         return;
     }
 
+    // Browned out and worn laboratories work at a reduced rate
+    let supply = |entity: Entity, parent: Option<&LogicalParent>| {
+        power
+            .as_ref()
+            .map_or(1.0, |p| p.supply_for(entity, parent, PowerTier::Research))
+            * condition_factor_for(&conditions, entity)
+    };
+
     // Compute RP rate (for display; actual distribution is in advance_research_projects)
     let base_rp_rate = BASE_RP_PER_YEAR / SECONDS_PER_YEAR;
-    let building_rp: f64 = research_buildings.iter().map(|b| b.points_per_second).sum();
-    let rp_multiplier = research_state.research_speed_multiplier();
+    let building_rp: f64 = research_buildings
+        .iter()
+        .map(|(entity, b, parent)| b.points_per_second * supply(entity, parent))
+        .sum();
+    let rp_multiplier = research_state.research_speed_multiplier()
+        * treasury.map_or(1.0, |t| t.research_factor());
     research_state.rp_rate_per_second = (base_rp_rate + building_rp) * rp_multiplier;
 
     // Compute and accumulate engineering points
     let base_ep_rate = BASE_EP_PER_YEAR / SECONDS_PER_YEAR;
-    let building_ep: f64 = engineering_facilities.iter().map(|f| f.points_per_second).sum();
+    let building_ep: f64 = engineering_facilities
+        .iter()
+        .map(|(entity, f, parent)| f.points_per_second * supply(entity, parent))
+        .sum();
     let ep_multiplier = research_state.engineering_speed_multiplier();
     research_state.ep_rate_per_second = (base_ep_rate + building_ep) * ep_multiplier;
     research_state.engineering_points_available +=
         research_state.ep_rate_per_second * delta_time;
 }
 
+/// System that sizes [`ResearchTeamCapacity`] to the built laboratories and
+/// workshops and the researched slot modifiers. When the capacity shrinks,
+/// e.g. because a laboratory was demolished, the least advanced projects
+/// beyond it are paused.
+#[allow(clippy::too_many_arguments)]
+pub fn update_team_capacity(
+    research_state: Res<ResearchState>,
+    tech_data: Res<TechnologiesData>,
+    labs: Query<&ResearchBuilding>,
+    workshops: Query<&EngineeringFacility>,
+    mut capacity: ResMut<ResearchTeamCapacity>,
+    mut projects: Query<(Entity, &mut ResearchProject)>,
+    mut log: Option<ResMut<EventLog>>,
+    sim_time: Option<Res<SimulationTime>>,
+) {
+    capacity.set_if_neq(ResearchTeamCapacity::compute(
+        labs.iter().map(|lab| lab.slots).sum(),
+        workshops.iter().map(|workshop| workshop.slots).sum(),
+        |modifier| research_state.get_modifier(modifier),
+    ));
+
+    let mut active: Vec<(Entity, f32)> = projects
+        .iter()
+        .filter(|(_, p)| p.active && !p.is_complete())
+        .map(|(entity, p)| (entity, p.progress_percent()))
+        .collect();
+    if active.len() <= capacity.max_research_teams {
+        return;
+    }
+    active.sort_by(|a, b| a.1.total_cmp(&b.1));
+    let excess = active.len() - capacity.max_research_teams;
+    for (entity, _) in active.into_iter().take(excess) {
+        let Ok((_, mut project)) = projects.get_mut(entity) else {
+            continue;
+        };
+        project.active = false;
+        project.rp_allocation_percent = 0.0;
+        let message = format!(
+            "Research on {} paused: no free research slot",
+            tech_data
+                .get_tech(&project.tech_id)
+                .map_or(project.tech_id.as_str(), |t| t.name.as_str())
+        );
+        warn!("{}", message);
+        if let (Some(log), Some(sim_time)) = (log.as_mut(), sim_time.as_ref()) {
+            log.push(sim_time.format_date_time(), LogSeverity::Warning, message);
+        }
+    }
+    redistribute_allocations(&mut projects);
+}
+
+/// Sent when a research project completes and its technology is unlocked
+#[derive(Event, Debug, Clone)]
+pub struct ResearchCompleted {
+    pub tech_id: TechnologyId,
+}
+
 /// System to advance active research projects using RP income.
 ///
 /// RP is generated from a base rate plus research buildings, then distributed
 /// among active projects according to their allocation percentages.
 /// Unallocated RP accumulates in `research_points_available`.
+#[allow(clippy::too_many_arguments)]
 pub fn advance_research_projects(
     mut commands: Commands,
+    mut completed_events: EventWriter<ResearchCompleted>,
     sim_time: Res<SimulationTime>,
     mut research_state: ResMut<ResearchState>,
     tech_data: Res<TechnologiesData>,
-    mut projects: Query<(Entity, &mut ResearchProject, &ResearchTeam)>,
+    mut projects: Query<(Entity, &mut ResearchProject)>,
+    mut teams: Query<&mut ResearchTeam>,
     mut last_time: Local<f64>,
 ) {
     let current_time = sim_time.elapsed_seconds();
@@ -146,14 +242,14 @@ pub fn advance_research_projects(
     // First pass: compute total allocation of active, incomplete projects
     let total_allocation: f64 = projects
         .iter()
-        .filter(|(_, p, _)| !p.is_complete() && p.active)
-        .map(|(_, p, _)| p.rp_allocation_percent)
+        .filter(|(_, p)| !p.is_complete() && p.active)
+        .map(|(_, p)| p.rp_allocation_percent)
         .sum();
 
     let mut completed_projects = Vec::new();
 
     // Second pass: distribute RP and advance projects
-    for (entity, mut project, team) in projects.iter_mut() {
+    for (entity, mut project) in projects.iter_mut() {
         if project.is_complete() || !project.active {
             continue;
         }
@@ -171,18 +267,28 @@ pub fn advance_research_projects(
         let category_bonus = tech
             .map(|t| 1.0 + (research_state.category_research_bonus(t.category) / 100.0))
             .unwrap_or(1.0);
+        let team = teams.get_mut(project.team_id).ok();
         let team_efficiency = tech
-            .map(|t| team.category_efficiency(t.category) as f64)
+            .zip(team.as_ref())
+            .map(|(t, team)| team.category_efficiency(t.category) as f64)
             .unwrap_or(1.0);
 
         project.progress += share * category_bonus * team_efficiency;
 
         if project.is_complete() {
-            if let Some(t) = tech {
+            if let (Some(t), Some(mut team)) = (tech, team) {
                 info!(
                     "Research project completed: {} by team '{}'",
                     t.name, team.name
                 );
+                if let Some(level) = team.gain_experience(t.category, project.required_points) {
+                    info!(
+                        "{} reached level {} in {}",
+                        team.name,
+                        level,
+                        t.category.display_name()
+                    );
+                }
             }
             completed_projects.push((entity, project.tech_id.clone()));
         }
@@ -195,6 +301,9 @@ pub fn advance_research_projects(
     // Process completed projects
     for (entity, tech_id) in completed_projects {
         research_state.unlock_tech(tech_id.clone());
+        completed_events.send(ResearchCompleted {
+            tech_id: tech_id.clone(),
+        });
 
         // Apply technology modifiers
         if let Some(tech) = tech_data.get_tech(&tech_id) {
@@ -212,12 +321,10 @@ pub fn advance_research_projects(
 }
 
 /// Evenly redistribute allocation percentages among all active, incomplete projects.
-fn redistribute_allocations(
-    projects: &mut Query<(Entity, &mut ResearchProject, &ResearchTeam)>,
-) {
+fn redistribute_allocations(projects: &mut Query<(Entity, &mut ResearchProject)>) {
     let active_count = projects
         .iter()
-        .filter(|(_, p, _)| !p.is_complete() && p.active)
+        .filter(|(_, p)| !p.is_complete() && p.active)
         .count();
 
     if active_count == 0 {
@@ -225,7 +332,7 @@ fn redistribute_allocations(
     }
 
     let equal_share = 1.0 / active_count as f64;
-    for (_, mut project, _) in projects.iter_mut() {
+    for (_, mut project) in projects.iter_mut() {
         if !project.is_complete() && project.active {
             project.rp_allocation_percent = equal_share;
         }
@@ -238,7 +345,8 @@ pub fn advance_engineering_projects(
     sim_time: Res<SimulationTime>,
     mut research_state: ResMut<ResearchState>,
     tech_data: Res<TechnologiesData>,
-    mut projects: Query<(Entity, &mut EngineeringProject, &ResearchTeam)>,
+    mut projects: Query<(Entity, &mut EngineeringProject)>,
+    teams: Query<&ResearchTeam>,
     mut last_time: Local<f64>,
 ) {
     let current_time = sim_time.elapsed_seconds();
@@ -251,14 +359,15 @@ pub fn advance_engineering_projects(
 
     let mut completed_projects = Vec::new();
 
-    for (entity, mut project, team) in projects.iter_mut() {
+    for (entity, mut project) in projects.iter_mut() {
         if project.is_complete() {
             continue;
         }
+        let team = teams.get(project.team_id).ok();
 
         // Calculate effective engineering rate
         let base_rate = 1.0; // Base engineering rate per second
-        let team_efficiency = team.efficiency as f64;
+        let team_efficiency = team.map_or(1.0, |t| t.efficiency as f64);
         let global_multiplier = research_state.engineering_speed_multiplier();
 
         let effective_rate = base_rate * team_efficiency * global_multiplier;
@@ -271,7 +380,8 @@ pub fn advance_engineering_projects(
             if let Some(component) = tech_data.get_component(&project.component_id) {
                 info!(
                     "Engineering project completed: {} by team '{}'",
-                    component.name, team.name
+                    component.name,
+                    team.map_or("unassigned", |t| t.name.as_str())
                 );
                 completed_projects.push((entity, project.component_id.clone()));
             }
@@ -296,6 +406,68 @@ pub fn advance_engineering_projects(
     }
 }
 
+/// System that spends the engineering point pool on reverse-engineering
+/// projects, shared evenly. A finished project's research points go to the
+/// project researching its technology, are kept for when it starts, or join
+/// the research pool if the technology is already known.
+pub fn advance_reverse_engineering(
+    mut commands: Commands,
+    mut research_state: ResMut<ResearchState>,
+    tech_data: Res<TechnologiesData>,
+    mut projects: Query<(Entity, &mut ReverseEngineeringProject)>,
+    mut research_projects: Query<&mut ResearchProject>,
+    mut log: Option<ResMut<EventLog>>,
+    sim_time: Option<Res<SimulationTime>>,
+) {
+    let mut open = projects.iter().filter(|(_, p)| !p.is_complete()).count();
+    for (_, mut project) in projects.iter_mut() {
+        if project.is_complete() || research_state.engineering_points_available <= 0.0 {
+            continue;
+        }
+        let share = research_state.engineering_points_available / open as f64;
+        let spent = share.min(project.required_points - project.progress);
+        project.progress += spent;
+        research_state.engineering_points_available -= spent;
+        open -= 1;
+    }
+
+    let mut messages = Vec::new();
+    for (entity, project) in projects.iter() {
+        if !project.is_complete() {
+            continue;
+        }
+        let tech_name = tech_data
+            .get_tech(&project.tech_id)
+            .map_or(project.tech_id.as_str(), |t| t.name.as_str());
+        let points = project.research_points;
+        if research_state.is_unlocked(&project.tech_id) {
+            research_state.research_points_available += points;
+        } else if let Some(mut research) = research_projects
+            .iter_mut()
+            .find(|p| p.tech_id == project.tech_id)
+        {
+            research.progress = (research.progress + points).min(research.required_points);
+        } else {
+            *research_state
+                .reverse_engineered
+                .entry(project.tech_id.clone())
+                .or_insert(0.0) += points;
+        }
+        messages.push(format!(
+            "Reverse engineering of the {} is done: {:.0} RP towards {}",
+            project.source, points, tech_name
+        ));
+        commands.entity(entity).despawn();
+    }
+
+    for message in messages {
+        info!("{}", message);
+        if let (Some(log), Some(sim_time)) = (log.as_mut(), sim_time.as_ref()) {
+            log.push(sim_time.format_date_time(), LogSeverity::Info, message);
+        }
+    }
+}
+
 /// System to check and display newly unlocked technologies
 pub fn check_unlocked_technologies(
     _tech_data: Res<TechnologiesData>,
@@ -314,31 +486,35 @@ pub fn check_unlocked_technologies(
 /// System to process pending research actions queued from the UI.
 ///
 /// For each requested tech ID it spawns an entity with a [`ResearchProject`]
-/// and a default [`ResearchTeam`], which the existing
-/// [`advance_research_projects`] system will then advance every frame.
+/// worked on by the idle [`ResearchTeam`] fastest in the technology's
+/// category, which the existing [`advance_research_projects`] system will
+/// then advance every frame.
+#[allow(clippy::too_many_arguments)]
 pub fn process_pending_research(
     mut commands: Commands,
     mut pending: ResMut<PendingResearchActions>,
     tech_data: Res<TechnologiesData>,
-    research_state: Res<ResearchState>,
+    mut research_state: ResMut<ResearchState>,
     team_capacity: Res<ResearchTeamCapacity>,
-    mut existing_projects: Query<(Entity, &mut ResearchProject, &ResearchTeam)>,
+    difficulty: Option<Res<Difficulty>>,
+    mut existing_projects: Query<(Entity, &mut ResearchProject)>,
+    teams: Query<(Entity, &ResearchTeam)>,
 ) {
     if pending.start_research.is_empty() {
         return;
     }
+    let cost_multiplier = difficulty.map_or(1.0, |d| d.research_cost_multiplier());
 
     // Collect tech IDs already being researched so we don't duplicate.
     let active_tech_ids: HashSet<&str> = existing_projects
         .iter()
-        .map(|(_, p, _)| p.tech_id.as_str())
+        .map(|(_, p)| p.tech_id.as_str())
         .collect();
 
     // Count currently active research projects (for team capacity)
-    let active_count = existing_projects
-        .iter()
-        .filter(|(_, p, _)| p.active)
-        .count();
+    let active_count = existing_projects.iter().filter(|(_, p)| p.active).count();
+    // Teams already on a project, paused or not
+    let mut busy: Vec<Entity> = existing_projects.iter().map(|(_, p)| p.team_id).collect();
 
     let mut spawned = 0usize;
 
@@ -351,7 +527,7 @@ pub fn process_pending_research(
         // Check team capacity
         if active_count + spawned >= team_capacity.max_research_teams {
             warn!(
-                "Cannot start research: all {} team slots are in use",
+                "Cannot start research: all {} research slots are in use",
                 team_capacity.max_research_teams
             );
             continue;
@@ -374,25 +550,39 @@ pub fn process_pending_research(
             );
             continue;
         }
+        if !tech_data.check_kardashev(&tech_id, research_state.kardashev_reached) {
+            warn!(
+                "Cannot start research on '{}': Kardashev milestone not reached",
+                tech.name
+            );
+            continue;
+        }
+
+        let Some(team) = idle_team(teams.iter(), &busy, tech.category) else {
+            warn!(
+                "Cannot start research on '{}': no research team is idle",
+                tech.name
+            );
+            continue;
+        };
+        busy.push(team);
 
         info!("Starting research on: {}", tech.name);
 
-        // Spawn a combined entity with project + default team.
-        commands.spawn((
-            ResearchProject {
-                tech_id: tech_id.clone(),
-                progress: 0.0,
-                required_points: tech.research_cost,
-                team_id: Entity::PLACEHOLDER,
-                rp_allocation_percent: 1.0, // Will be redistributed below
-                active: true,
-            },
-            ResearchTeam::new_research(
-                format!("Research: {}", tech.name),
-                "Default Scientist".to_string(),
-                Some(tech.category),
-            ),
-        ));
+        let required_points = tech.research_cost * cost_multiplier;
+        // Findings of reverse engineering give the project a head start
+        let progress = research_state
+            .reverse_engineered
+            .remove(&tech_id)
+            .map_or(0.0, |points| points.min(required_points));
+        commands.spawn(ResearchProject {
+            tech_id: tech_id.clone(),
+            progress,
+            required_points,
+            team_id: team,
+            rp_allocation_percent: 1.0, // Will be redistributed below
+            active: true,
+        });
 
         spawned += 1;
     }
@@ -401,12 +591,12 @@ pub fn process_pending_research(
     if spawned > 0 {
         let new_active_count = existing_projects
             .iter()
-            .filter(|(_, p, _)| p.active && !p.is_complete())
+            .filter(|(_, p)| p.active && !p.is_complete())
             .count()
             + spawned;
         if new_active_count > 0 {
             let equal_share = 1.0 / new_active_count as f64;
-            for (_, mut project, _) in existing_projects.iter_mut() {
+            for (_, mut project) in existing_projects.iter_mut() {
                 if project.active && !project.is_complete() {
                     project.rp_allocation_percent = equal_share;
                 }
@@ -415,15 +605,17 @@ pub fn process_pending_research(
     }
 }
 
-/// System to process stop/resume research actions.
+/// System to process stop/resume research actions. Paused projects resume
+/// only as long as research slots are free.
 pub fn process_stop_research(
     mut pending: ResMut<PendingResearchActions>,
-    mut projects: Query<(Entity, &mut ResearchProject, &ResearchTeam)>,
+    team_capacity: Res<ResearchTeamCapacity>,
+    mut projects: Query<(Entity, &mut ResearchProject)>,
 ) {
     // Process stops
     if !pending.stop_research.is_empty() {
         let stop_ids: HashSet<String> = pending.stop_research.drain(..).collect();
-        for (_, mut project, _) in projects.iter_mut() {
+        for (_, mut project) in projects.iter_mut() {
             if stop_ids.contains(&project.tech_id) {
                 project.active = false;
                 project.rp_allocation_percent = 0.0;
@@ -437,11 +629,21 @@ pub fn process_stop_research(
     // Process resumes
     if !pending.resume_research.is_empty() {
         let resume_ids: HashSet<String> = pending.resume_research.drain(..).collect();
-        for (_, mut project, _) in projects.iter_mut() {
-            if resume_ids.contains(&project.tech_id) {
-                project.active = true;
-                info!("Resumed research on: {}", project.tech_id);
+        let mut active_count = projects.iter().filter(|(_, p)| p.active).count();
+        for (_, mut project) in projects.iter_mut() {
+            if project.active || !resume_ids.contains(&project.tech_id) {
+                continue;
+            }
+            if active_count >= team_capacity.max_research_teams {
+                warn!(
+                    "Cannot resume research on {}: all {} research slots are in use",
+                    project.tech_id, team_capacity.max_research_teams
+                );
+                continue;
             }
+            project.active = true;
+            active_count += 1;
+            info!("Resumed research on: {}", project.tech_id);
         }
         redistribute_allocations(&mut projects);
     }
@@ -450,14 +652,14 @@ pub fn process_stop_research(
 /// System to process allocation percentage updates from the UI.
 pub fn process_allocation_updates(
     mut pending: ResMut<PendingResearchActions>,
-    mut projects: Query<(Entity, &mut ResearchProject, &ResearchTeam)>,
+    mut projects: Query<(Entity, &mut ResearchProject)>,
 ) {
     if pending.update_allocations.is_empty() {
         return;
     }
 
     for (tech_id, new_alloc) in pending.update_allocations.drain(..) {
-        for (_, mut project, _) in projects.iter_mut() {
+        for (_, mut project) in projects.iter_mut() {
             if project.tech_id == tech_id && project.active {
                 project.rp_allocation_percent = new_alloc.clamp(0.0, 1.0);
             }
@@ -467,13 +669,13 @@ pub fn process_allocation_updates(
     // Normalize allocations so they sum to 1.0
     let total: f64 = projects
         .iter()
-        .filter(|(_, p, _)| p.active && !p.is_complete())
-        .map(|(_, p, _)| p.rp_allocation_percent)
+        .filter(|(_, p)| p.active && !p.is_complete())
+        .map(|(_, p)| p.rp_allocation_percent)
         .sum();
 
     if total > 0.0 && (total - 1.0).abs() > 0.001 {
         let scale = 1.0 / total;
-        for (_, mut project, _) in projects.iter_mut() {
+        for (_, mut project) in projects.iter_mut() {
             if project.active && !project.is_complete() {
                 project.rp_allocation_percent *= scale;
             }
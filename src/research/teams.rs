@@ -0,0 +1,225 @@
+//! Research teams on the payroll
+//!
+//! Teams are entities of their own that outlive the projects they work on.
+//! A new project goes to the idle team fastest in its category, and the team
+//! gains experience in that category when the project completes (see
+//! [`ResearchTeam::gain_experience`]). Teams are hired for credits, renamed
+//! and retired from the Research overview; a team on a project, even a
+//! paused one, cannot be retired.
+
+use bevy::prelude::*;
+
+use super::components::{ResearchProject, ResearchTeam};
+use super::types::TechCategory;
+use super::PendingResearchActions;
+use crate::economy::{LedgerLine, Treasury};
+use crate::game_state::{EventLog, LogSeverity};
+
+/// Credits paid to hire a research team
+pub const TEAM_HIRE_COST: f64 = 5.0e7;
+
+/// Largest number of research teams on the payroll
+pub const MAX_RESEARCH_TEAMS: usize = 8;
+
+/// Research teams a new civilization starts with
+pub const STARTING_RESEARCH_TEAMS: usize = 3;
+
+/// Names handed out to new teams, the first one not taken
+const TEAM_NAMES: [&str; 8] = [
+    "Alpha", "Beta", "Gamma", "Delta", "Epsilon", "Zeta", "Eta", "Theta",
+];
+
+/// Leads of new teams, by the name the team gets
+const LEAD_NAMES: [&str; 8] = [
+    "Dr. Amara Okafor",
+    "Dr. Lukas Brandt",
+    "Dr. Mei Tanaka",
+    "Dr. Rafael Ortiz",
+    "Dr. Ingrid Solberg",
+    "Dr. Kwame Mensah",
+    "Dr. Priya Raman",
+    "Dr. Elena Petrova",
+];
+
+/// A new research team with the first free name
+pub fn new_team<'a>(
+    taken: impl Iterator<Item = &'a ResearchTeam>,
+    specialty: Option<TechCategory>,
+) -> ResearchTeam {
+    let taken: Vec<&str> = taken.map(|team| team.name.as_str()).collect();
+    let index = (0..)
+        .find(|i| !taken.contains(&team_name(*i).as_str()))
+        .unwrap_or(0);
+    ResearchTeam::new_research(
+        team_name(index),
+        LEAD_NAMES[index % LEAD_NAMES.len()].to_string(),
+        specialty,
+    )
+}
+
+fn team_name(index: usize) -> String {
+    let name = TEAM_NAMES[index % TEAM_NAMES.len()];
+    match index / TEAM_NAMES.len() {
+        0 => format!("Team {}", name),
+        n => format!("Team {} {}", name, n + 1),
+    }
+}
+
+/// Idle research team fastest in `category`
+pub fn idle_team<'a>(
+    teams: impl Iterator<Item = (Entity, &'a ResearchTeam)>,
+    busy: &[Entity],
+    category: TechCategory,
+) -> Option<Entity> {
+    teams
+        .filter(|(entity, team)| team.is_research && !busy.contains(entity))
+        .max_by(|a, b| {
+            a.1.category_efficiency(category)
+                .total_cmp(&b.1.category_efficiency(category))
+                // Ties go to the team hired first
+                .then(b.0.cmp(&a.0))
+        })
+        .map(|(entity, _)| entity)
+}
+
+/// Startup system that gives the civilization its first research teams
+pub fn spawn_starting_teams(mut commands: Commands) {
+    let mut teams: Vec<ResearchTeam> = Vec::new();
+    for _ in 0..STARTING_RESEARCH_TEAMS {
+        let team = new_team(teams.iter(), None);
+        teams.push(team);
+    }
+    commands.spawn_batch(teams);
+}
+
+/// System that hires, renames and retires research teams as requested
+pub fn process_team_actions(
+    mut commands: Commands,
+    mut pending: ResMut<PendingResearchActions>,
+    mut teams: Query<(Entity, &mut ResearchTeam)>,
+    projects: Query<&ResearchProject>,
+    mut treasury: Option<ResMut<Treasury>>,
+    mut log: Option<ResMut<EventLog>>,
+    sim_time: Option<Res<crate::ui::SimulationTime>>,
+) {
+    if pending.hire_teams.is_empty()
+        && pending.rename_teams.is_empty()
+        && pending.retire_teams.is_empty()
+    {
+        return;
+    }
+
+    let mut messages = Vec::new();
+    let mut hired: Vec<ResearchTeam> = Vec::new();
+    for specialty in std::mem::take(&mut pending.hire_teams) {
+        let research_teams = teams.iter().filter(|(_, t)| t.is_research).count();
+        if research_teams + hired.len() >= MAX_RESEARCH_TEAMS {
+            warn!(
+                "Cannot hire more than {} research teams",
+                MAX_RESEARCH_TEAMS
+            );
+            break;
+        }
+        if let Some(treasury) = treasury.as_mut() {
+            if treasury.over_credit_limit() {
+                warn!("Cannot hire a research team beyond the credit limit");
+                break;
+            }
+            treasury.spend(LedgerLine::Research, TEAM_HIRE_COST);
+        }
+        let team = new_team(teams.iter().map(|(_, t)| t).chain(hired.iter()), specialty);
+        messages.push(format!(
+            "Hired {} led by {}",
+            team.name, team.lead_character
+        ));
+        hired.push(team);
+    }
+    commands.spawn_batch(hired);
+
+    for (entity, name) in std::mem::take(&mut pending.rename_teams) {
+        let name = name.trim();
+        let taken = teams
+            .iter()
+            .any(|(other, t)| other != entity && t.name == name);
+        if name.is_empty() || taken {
+            continue;
+        }
+        if let Ok((_, mut team)) = teams.get_mut(entity) {
+            messages.push(format!("{} is now called {}", team.name, name));
+            team.name = name.to_string();
+        }
+    }
+
+    for entity in std::mem::take(&mut pending.retire_teams) {
+        let Ok((_, team)) = teams.get(entity) else {
+            continue;
+        };
+        if projects.iter().any(|p| p.team_id == entity) {
+            warn!("Cannot retire {} while it works on a project", team.name);
+            continue;
+        }
+        messages.push(format!("Retired {}", team.name));
+        commands.entity(entity).despawn();
+    }
+
+    for message in messages {
+        info!("{}", message);
+        if let (Some(log), Some(sim_time)) = (log.as_mut(), sim_time.as_ref()) {
+            log.push(sim_time.format_date_time(), LogSeverity::Info, message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_projects_go_to_the_fastest_idle_team() {
+        let mut app = App::new();
+        app.init_resource::<PendingResearchActions>()
+            .add_systems(Startup, spawn_starting_teams)
+            .add_systems(Update, process_team_actions);
+        app.update();
+
+        let mut teams = app.world_mut().query::<(Entity, &ResearchTeam)>();
+        let names: Vec<String> = teams
+            .iter(app.world())
+            .map(|(_, t)| t.name.clone())
+            .collect();
+        assert_eq!(names.len(), STARTING_RESEARCH_TEAMS);
+        assert!(names.contains(&"Team Gamma".to_string()));
+
+        // A physicist is hired and gets the next free name
+        app.world_mut()
+            .resource_mut::<PendingResearchActions>()
+            .hire_teams
+            .push(Some(TechCategory::Physics));
+        app.update();
+        let (physicists, _) = teams
+            .iter(app.world())
+            .find(|(_, t)| t.name == "Team Delta")
+            .unwrap();
+        let all: Vec<(Entity, ResearchTeam)> = teams
+            .iter(app.world())
+            .map(|(e, t)| (e, t.clone()))
+            .collect();
+        let pick =
+            |busy: &[Entity], category| idle_team(all.iter().map(|(e, t)| (*e, t)), busy, category);
+        assert_eq!(pick(&[], TechCategory::Physics), Some(physicists));
+        assert_ne!(pick(&[physicists], TechCategory::Physics), Some(physicists));
+
+        // Teams on a project stay on the payroll
+        app.world_mut().spawn(ResearchProject::new(
+            "fusion".to_string(),
+            100.0,
+            physicists,
+        ));
+        app.world_mut()
+            .resource_mut::<PendingResearchActions>()
+            .retire_teams
+            .push(physicists);
+        app.update();
+        assert!(app.world().get::<ResearchTeam>(physicists).is_some());
+    }
+}
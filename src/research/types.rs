@@ -111,6 +111,10 @@ pub struct Technology {
     pub modifiers: Vec<TechModifierDef>,
     /// Tier/level of the technology (for UI organization)
     pub tier: u32,
+    /// Kardashev milestone the civilization must reach before research can
+    /// start
+    #[serde(default)]
+    pub required_kardashev: Option<f64>,
 }
 
 /// Definition of a technology modifier (from data file)
@@ -141,6 +145,20 @@ pub enum ModifierType {
     ShipMaintenance,
     /// Increase population growth rate (%)
     PopulationGrowth,
+    /// Deepen the gas giant harvest level (bar)
+    HarvestDepth,
+    /// Increase food output of farms and hydroponics (%)
+    FoodProduction,
+    /// Add orbital slots around every body (slots)
+    OrbitalSlots,
+    /// Add concurrent research projects (slots)
+    ResearchSlots,
+    /// Add concurrent engineering projects (slots)
+    EngineeringSlots,
+    /// Increase the sensitivity of listening posts to alien signals (%)
+    SignalDetection,
+    /// Increase the range of colony and fleet sensors (%)
+    SensorRange,
     /// Unlock new game mechanics
     UnlockMechanic(String),
 }
@@ -159,6 +177,13 @@ impl ModifierType {
             ModifierType::PowerGeneration => "Power Generation".to_string(),
             ModifierType::ShipMaintenance => "Ship Maintenance Cost".to_string(),
             ModifierType::PopulationGrowth => "Population Growth".to_string(),
+            ModifierType::HarvestDepth => "Harvest Depth".to_string(),
+            ModifierType::FoodProduction => "Food Production".to_string(),
+            ModifierType::OrbitalSlots => "Orbital Slots".to_string(),
+            ModifierType::ResearchSlots => "Research Slots".to_string(),
+            ModifierType::EngineeringSlots => "Engineering Slots".to_string(),
+            ModifierType::SignalDetection => "Signal Detection".to_string(),
+            ModifierType::SensorRange => "Sensor Range".to_string(),
             ModifierType::UnlockMechanic(name) => format!("Unlock: {}", name),
         }
     }
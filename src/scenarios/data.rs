@@ -0,0 +1,185 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+
+use super::types::{Scenario, ScenarioId, StartDate};
+use crate::modding::ModList;
+
+/// Base scenario data file
+pub const SCENARIOS_PATH: &str = "assets/data/scenarios.ron";
+
+/// Resource containing all scenarios loaded from data
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ScenariosData {
+    /// All scenarios indexed by ID
+    pub scenarios: HashMap<ScenarioId, Scenario>,
+    /// Scenario IDs in data file order, as listed on the new-game screen
+    pub order: Vec<ScenarioId>,
+}
+
+impl ScenariosData {
+    /// Get a scenario by ID
+    pub fn get(&self, id: &str) -> Option<&Scenario> {
+        self.scenarios.get(id)
+    }
+
+    /// Iterate scenarios in data file order
+    pub fn iter(&self) -> impl Iterator<Item = &Scenario> {
+        self.order.iter().filter_map(|id| self.scenarios.get(id))
+    }
+
+    /// Add a scenario, keeping the file order
+    pub fn insert(&mut self, scenario: Scenario) {
+        if !self.scenarios.contains_key(&scenario.id) {
+            self.order.push(scenario.id.clone());
+        }
+        self.scenarios.insert(scenario.id.clone(), scenario);
+    }
+}
+
+/// Structure of the scenarios data file
+#[derive(Debug, Deserialize)]
+struct ScenariosFile {
+    scenarios: Vec<Scenario>,
+}
+
+/// Check that a scenario can be started: a real date no earlier than the
+/// ephemeris epoch of the simulation clock, each colony listed once with a
+/// non-negative population, and any extinction event after the start
+pub fn validate_scenario(scenario: &Scenario) -> Result<(), String> {
+    if !scenario.start.is_valid() {
+        return Err(format!("scenario '{}' has an invalid start date", scenario.id));
+    }
+    if scenario.start.timestamp() < StartDate::default().timestamp() {
+        return Err(format!("scenario '{}' starts before 2026", scenario.id));
+    }
+    let mut bodies = HashSet::new();
+    for colony in &scenario.colonies {
+        if !bodies.insert(colony.body.as_str()) {
+            return Err(format!(
+                "scenario '{}' lists colony '{}' twice",
+                scenario.id, colony.body
+            ));
+        }
+        if !colony.population.is_finite() || colony.population < 0.0 {
+            return Err(format!(
+                "scenario '{}' has an invalid population on '{}'",
+                scenario.id, colony.body
+            ));
+        }
+        if colony.buildings.iter().any(|b| b.level == 0) {
+            return Err(format!(
+                "scenario '{}' has a level 0 building on '{}'",
+                scenario.id, colony.body
+            ));
+        }
+    }
+    if let Some(extinction) = &scenario.extinction {
+        if !extinction.date.is_valid() || extinction.date.timestamp() <= scenario.start.timestamp()
+        {
+            return Err(format!(
+                "scenario '{}' has an extinction event that is not after its start",
+                scenario.id
+            ));
+        }
+    }
+    let amounts = scenario
+        .stockpile
+        .iter()
+        .flat_map(|s| s.values())
+        .chain(scenario.refined_stockpile.iter().flat_map(|s| s.values()))
+        .chain(scenario.food_stockpile.iter());
+    for amount in amounts {
+        if !amount.is_finite() || *amount < 0.0 {
+            return Err(format!(
+                "scenario '{}' has a negative stockpile",
+                scenario.id
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// System to load scenarios from data file at startup, followed by the
+/// scenarios of enabled mods
+pub fn load_scenarios(mut commands: Commands, mods: Option<Res<ModList>>) {
+    info!("Loading scenarios...");
+
+    let mut scenarios_data = ScenariosData::default();
+    let mut paths = vec![PathBuf::from(SCENARIOS_PATH)];
+    paths.extend(mods.map_or(Vec::new(), |mods| mods.data_files("scenarios.ron")));
+    for path in paths {
+        match fs::read_to_string(&path) {
+            Ok(contents) => match ron::from_str::<ScenariosFile>(&contents) {
+                Ok(data) => {
+                    for scenario in data.scenarios {
+                        match validate_scenario(&scenario) {
+                            Ok(()) => scenarios_data.insert(scenario),
+                            Err(e) => error!("Skipping invalid scenario: {}", e),
+                        }
+                    }
+                }
+                Err(e) => error!("Failed to parse {}: {}", path.display(), e),
+            },
+            Err(e) => warn!("Failed to read {}: {}", path.display(), e),
+        }
+    }
+    info!("Loaded {} scenarios", scenarios_data.order.len());
+
+    // Scenarios that failed to load are left out of the new-game list
+    commands.insert_resource(scenarios_data);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scenarios_file_parses_and_validates() {
+        let contents = fs::read_to_string(SCENARIOS_PATH).unwrap();
+        let data: ScenariosFile = ron::from_str(&contents).unwrap();
+
+        assert!(!data.scenarios.is_empty());
+        for scenario in &data.scenarios {
+            validate_scenario(scenario).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_scenarios_reference_known_definitions() {
+        let contents = fs::read_to_string(SCENARIOS_PATH).unwrap();
+        let data: ScenariosFile = ron::from_str(&contents).unwrap();
+        let (technologies, _) = crate::research::read_technologies(None);
+        let (buildings, _) = crate::construction::read_buildings(None);
+
+        for scenario in &data.scenarios {
+            for tech in &scenario.technologies {
+                assert!(technologies.get_tech(tech).is_some(), "{}: {}", scenario.id, tech);
+            }
+            for component in &scenario.components {
+                assert!(
+                    technologies.get_component(component).is_some(),
+                    "{}: {}",
+                    scenario.id,
+                    component
+                );
+            }
+            for colony in &scenario.colonies {
+                for facility in &colony.buildings {
+                    assert!(
+                        buildings
+                            .get(&facility.building)
+                            .and_then(|b| b.level(facility.level))
+                            .is_some(),
+                        "{}: {} level {}",
+                        scenario.id,
+                        facility.building,
+                        facility.level
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,34 @@
+//! Start scenarios
+//!
+//! Provides data-driven presets for a new campaign:
+//! - Scenario definitions (start date, colonies with their population and
+//!   buildings, researched technologies, stockpile) loaded from
+//!   `assets/data/scenarios.ron` and enabled mods
+//! - A new-game screen listing them at launch
+//! - Setting up the picked scenario: the clock jumps to its start date and
+//!   colonies, research and the stockpile are replaced by its own
+//! - Extinction events a scenario can build up to, which the campaign is won
+//!   by surviving
+
+use bevy::prelude::*;
+
+pub mod data;
+pub mod systems;
+pub mod types;
+
+pub use data::{load_scenarios, validate_scenario, ScenariosData, SCENARIOS_PATH};
+pub use systems::{start_scenario, technologies_with_prerequisites, ScenarioSelection};
+pub use types::{
+    ExtinctionEvent, Scenario, ScenarioBuilding, ScenarioColony, ScenarioId, StartDate,
+};
+
+/// Plugin that adds start scenarios to the Bevy app
+pub struct ScenariosPlugin;
+
+impl Plugin for ScenariosPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ScenarioSelection>()
+            .add_systems(Startup, load_scenarios)
+            .add_systems(Update, start_scenario);
+    }
+}
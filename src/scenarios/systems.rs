@@ -0,0 +1,282 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use super::data::ScenariosData;
+use super::types::{Scenario, ScenarioId};
+use crate::construction::{Building, BuildingsData};
+use crate::economy::components::Population;
+use crate::astronomy::nearby_stars::NearbyStarsData;
+use crate::economy::{EconomyClock, GlobalBudget, ECONOMY_TICK_SECONDS};
+use crate::game_state::{EventLog, LogSeverity};
+use crate::plugins::solar_system::{CelestialBody, LogicalParent};
+use crate::plugins::system_populator::{catalog_system_of_body, PopulatedSystems};
+use crate::research::{ResearchState, TechnologiesData};
+use crate::ui::SimulationTime;
+use crate::victory::VictorySettings;
+
+/// The scenario picked on the new-game screen and the one being played
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ScenarioSelection {
+    /// Scenario to set up on the next update
+    pub pending: Option<ScenarioId>,
+    /// Scenario the campaign started from
+    pub active: Option<ScenarioId>,
+}
+
+/// Technologies to unlock for a scenario: the listed ones and everything
+/// they build on, each once, prerequisites first
+pub fn technologies_with_prerequisites(
+    scenario: &Scenario,
+    technologies: &TechnologiesData,
+) -> Vec<String> {
+    fn visit(id: &str, technologies: &TechnologiesData, out: &mut Vec<String>) {
+        if out.iter().any(|t| t == id) {
+            return;
+        }
+        if let Some(tech) = technologies.get_tech(id) {
+            for prerequisite in &tech.prerequisites {
+                visit(prerequisite, technologies, out);
+            }
+        }
+        out.push(id.to_string());
+    }
+
+    let mut out = Vec::new();
+    for id in &scenario.technologies {
+        visit(id, technologies, &mut out);
+    }
+    out
+}
+
+/// System that sets up the pending scenario: the date, colony populations
+/// and facilities, researched technologies, the stockpile and any extinction
+/// event to survive. Colonies in star systems that have not been populated
+/// yet hold the start back until their bodies are spawned.
+#[allow(clippy::too_many_arguments)]
+pub fn start_scenario(
+    mut commands: Commands,
+    mut selection: ResMut<ScenarioSelection>,
+    scenarios: Option<Res<ScenariosData>>,
+    buildings_data: Option<Res<BuildingsData>>,
+    tech_data: Option<Res<TechnologiesData>>,
+    mut sim_time: ResMut<SimulationTime>,
+    mut clock: ResMut<EconomyClock>,
+    mut budget: ResMut<GlobalBudget>,
+    mut research_state: ResMut<ResearchState>,
+    mut log: ResMut<EventLog>,
+    victory: Option<ResMut<VictorySettings>>,
+    stars_data: Option<Res<NearbyStarsData>>,
+    populated: Option<ResMut<PopulatedSystems>>,
+    mut bodies: Query<(Entity, &CelestialBody, Option<&mut Population>)>,
+) {
+    let Some(id) = selection.pending.take() else {
+        return;
+    };
+    let Some(scenario) = scenarios.as_ref().and_then(|s| s.get(&id)) else {
+        warn!("Unknown scenario '{}'", id);
+        return;
+    };
+
+    if let (Some(stars_data), Some(mut populated)) = (stars_data, populated) {
+        let mut waiting = false;
+        for colony in &scenario.colonies {
+            if bodies.iter().any(|(_, body, _)| body.name == colony.body) {
+                continue;
+            }
+            if let Some(system_id) = catalog_system_of_body(&stars_data, &colony.body) {
+                waiting |= populated.request(system_id);
+            }
+        }
+        if waiting {
+            selection.pending = Some(id);
+            return;
+        }
+    }
+
+    // Jump the clock without replaying the economy days in between
+    sim_time.set_current_timestamp(scenario.start.timestamp());
+    clock.processed = (sim_time.elapsed_seconds() / ECONOMY_TICK_SECONDS).floor() as u64;
+    clock.due = 0;
+
+    let colonies: HashMap<&str, _> = scenario
+        .colonies
+        .iter()
+        .map(|colony| (colony.body.as_str(), colony))
+        .collect();
+    let mut settled = 0;
+    for (entity, body, population) in bodies.iter_mut() {
+        let colony = colonies.get(body.name.as_str());
+        let count = colony.map_or(0.0, |c| c.population);
+        match population {
+            Some(mut population) => population.count = count,
+            None if colony.is_some() => {
+                commands.entity(entity).insert(Population::new(count));
+            }
+            None => {}
+        }
+        let Some(colony) = colony else {
+            continue;
+        };
+        settled += 1;
+
+        for facility in &colony.buildings {
+            let Some(level) = buildings_data
+                .as_ref()
+                .and_then(|data| data.get(&facility.building))
+                .and_then(|definition| definition.level(facility.level))
+            else {
+                warn!(
+                    "Scenario '{}': unknown building '{}' level {} on {}",
+                    scenario.id, facility.building, facility.level, colony.body
+                );
+                continue;
+            };
+            commands.spawn((
+                Building {
+                    id: facility.building.clone(),
+                    level: facility.level,
+                },
+                LogicalParent(entity),
+                Name::new(level.name.clone()),
+            ));
+        }
+    }
+    if settled < colonies.len() {
+        warn!(
+            "Scenario '{}': {} colonies name bodies that don't exist",
+            scenario.id,
+            colonies.len() - settled
+        );
+    }
+
+    if let Some(stockpile) = &scenario.stockpile {
+        budget.stockpiles = stockpile.clone();
+    }
+    if let Some(refined) = &scenario.refined_stockpile {
+        budget.refined = refined.clone();
+    }
+    if let Some(food) = scenario.food_stockpile {
+        budget.food = food;
+    }
+
+    if let Some(tech_data) = tech_data.as_deref() {
+        for tech_id in technologies_with_prerequisites(scenario, tech_data) {
+            if research_state.is_unlocked(&tech_id) {
+                continue;
+            }
+            if let Some(tech) = tech_data.get_tech(&tech_id) {
+                for modifier_def in &tech.modifiers {
+                    research_state
+                        .add_modifier(modifier_def.modifier_type.clone(), modifier_def.value);
+                }
+            }
+            research_state.unlock_tech(tech_id);
+        }
+    }
+    for component in &scenario.components {
+        research_state.complete_component(component.clone());
+    }
+    if let Some(mut victory) = victory {
+        victory.extinction = scenario.extinction.clone();
+    }
+
+    info!("Scenario started: {}", scenario.name);
+    log.push(
+        sim_time.format_date_time(),
+        LogSeverity::Info,
+        format!("Scenario started: {}", scenario.name),
+    );
+    selection.active = Some(id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::economy::ResourceType;
+    use crate::plugins::solar_system_data::BodyType;
+    use crate::scenarios::types::{ScenarioBuilding, ScenarioColony, StartDate};
+
+    #[test]
+    fn test_scenario_sets_date_colonies_and_stockpile() {
+        let mut app = App::new();
+        app.init_resource::<ScenarioSelection>()
+            .init_resource::<SimulationTime>()
+            .init_resource::<EconomyClock>()
+            .init_resource::<GlobalBudget>()
+            .init_resource::<ResearchState>()
+            .init_resource::<EventLog>()
+            .add_systems(Update, start_scenario);
+
+        let (buildings, _) = crate::construction::read_buildings(None);
+        app.insert_resource(buildings);
+
+        let mut scenarios = ScenariosData::default();
+        scenarios.insert(Scenario {
+            id: "mars_2080".to_string(),
+            name: "Mars 2080".to_string(),
+            description: String::new(),
+            start: StartDate {
+                year: 2080,
+                month: 1,
+                day: 1,
+            },
+            colonies: vec![ScenarioColony {
+                body: "Mars".to_string(),
+                population: 50_000.0,
+                buildings: vec![ScenarioBuilding {
+                    building: "iron_mine".to_string(),
+                    level: 1,
+                }],
+            }],
+            technologies: vec![],
+            components: vec!["basic_life_support".to_string()],
+            stockpile: Some(HashMap::from([(ResourceType::Iron, 500.0)])),
+            refined_stockpile: None,
+            food_stockpile: None,
+            extinction: None,
+            script: None,
+        });
+        app.insert_resource(scenarios);
+
+        let earth = app
+            .world_mut()
+            .spawn((
+                CelestialBody::fixture("Earth", BodyType::Planet),
+                Population::new(8.2e9),
+            ))
+            .id();
+        let mars = app
+            .world_mut()
+            .spawn(CelestialBody::fixture("Mars", BodyType::Planet))
+            .id();
+        app.world_mut().resource_mut::<ScenarioSelection>().pending = Some("mars_2080".to_string());
+        app.update();
+
+        let sim_time = app.world().resource::<SimulationTime>();
+        assert_eq!(sim_time.current_timestamp(), 3_471_292_800);
+        let clock = app.world().resource::<EconomyClock>();
+        assert_eq!(
+            clock.processed,
+            (sim_time.elapsed_seconds() / ECONOMY_TICK_SECONDS) as u64
+        );
+
+        assert_eq!(app.world().get::<Population>(earth).unwrap().count, 0.0);
+        assert_eq!(app.world().get::<Population>(mars).unwrap().count, 50_000.0);
+        let mut buildings = app.world_mut().query::<(&Building, &LogicalParent)>();
+        let placed: Vec<_> = buildings.iter(app.world()).collect();
+        assert_eq!(placed.len(), 1);
+        assert_eq!(placed[0].0.id, "iron_mine");
+        assert_eq!(placed[0].1 .0, mars);
+
+        let budget = app.world().resource::<GlobalBudget>();
+        assert_eq!(budget.get_stockpile(&ResourceType::Iron), 500.0);
+        assert_eq!(budget.get_stockpile(&ResourceType::Water), 0.0);
+        assert!(app
+            .world()
+            .resource::<ResearchState>()
+            .is_component_completed("basic_life_support"));
+        let selection = app.world().resource::<ScenarioSelection>();
+        assert_eq!(selection.active.as_deref(), Some("mars_2080"));
+        assert!(selection.pending.is_none());
+    }
+}
@@ -0,0 +1,157 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::construction::BuildingId;
+use crate::economy::{RefinedGood, ResourceType};
+use crate::research::TechnologyId;
+
+/// Unique identifier for a scenario
+pub type ScenarioId = String;
+
+/// Calendar date a scenario starts on (UTC midnight)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StartDate {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Default for StartDate {
+    fn default() -> Self {
+        Self {
+            year: 2026,
+            month: 1,
+            day: 1,
+        }
+    }
+}
+
+impl StartDate {
+    /// Whether the date exists in the proleptic Gregorian calendar
+    pub fn is_valid(&self) -> bool {
+        (1..=12).contains(&self.month)
+            && self.day >= 1
+            && self.day <= days_in_month(self.year, self.month)
+    }
+
+    /// Unix timestamp of the date
+    pub fn timestamp(&self) -> i64 {
+        days_from_civil(self.year, self.month, self.day) * 86_400
+    }
+}
+
+fn is_leap_year(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 of a Gregorian date (Howard Hinnant's algorithm)
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// A standing facility a colony starts with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioBuilding {
+    pub building: BuildingId,
+    #[serde(default = "first_level")]
+    pub level: u32,
+}
+
+fn first_level() -> u32 {
+    1
+}
+
+/// A populated body at the start of a scenario
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioColony {
+    /// Name of the celestial body
+    pub body: String,
+    pub population: f64,
+    #[serde(default)]
+    pub buildings: Vec<ScenarioBuilding>,
+}
+
+/// A catastrophe a scenario builds up to: on its date the body's whole
+/// population perishes, and the campaign is won by surviving it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExtinctionEvent {
+    pub date: StartDate,
+    /// Name of the celestial body struck
+    pub body: String,
+    /// What happens, shown on the new-game screen and in the event log
+    pub description: String,
+}
+
+/// A start preset, loaded from data
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Scenario {
+    /// Unique identifier
+    pub id: ScenarioId,
+    pub name: String,
+    pub description: String,
+    #[serde(default)]
+    pub start: StartDate,
+    /// Every populated body; bodies not listed start empty
+    pub colonies: Vec<ScenarioColony>,
+    /// Technologies researched before the start, on top of the baseline
+    #[serde(default)]
+    pub technologies: Vec<TechnologyId>,
+    /// Component designs engineered before the start
+    #[serde(default)]
+    pub components: Vec<String>,
+    /// Starting stockpile in Megatons, replacing the default one
+    #[serde(default)]
+    pub stockpile: Option<HashMap<ResourceType, f64>>,
+    /// Starting refined goods in Megatons, replacing the default ones
+    #[serde(default)]
+    pub refined_stockpile: Option<HashMap<RefinedGood, f64>>,
+    /// Starting food stockpile in Megatons, replacing the default one
+    #[serde(default)]
+    pub food_stockpile: Option<f64>,
+    /// Catastrophe the campaign must be survived through
+    #[serde(default)]
+    pub extinction: Option<ExtinctionEvent>,
+    /// Rhai script run when the scenario starts, e.g.
+    /// `"assets/scripts/scenarios/first_contact.rhai"`; its hooks stay
+    /// active for the campaign
+    #[serde(default)]
+    pub script: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_start_date_timestamps() {
+        assert_eq!(StartDate::default().timestamp(), 1_767_225_600);
+        let leap_day = StartDate {
+            year: 2080,
+            month: 2,
+            day: 29,
+        };
+        assert!(leap_day.is_valid());
+        assert_eq!(leap_day.timestamp(), 3_476_390_400);
+        assert!(!StartDate {
+            year: 2100,
+            month: 2,
+            day: 29
+        }
+        .is_valid());
+    }
+}
@@ -0,0 +1,181 @@
+//! Functions scripts can call
+//!
+//! Scripts never touch the world directly: functions that change the game
+//! queue a [`ScriptAction`] that `apply_script_actions` carries out after the
+//! script returns, and functions that read it answer from a [`ScriptView`]
+//! refreshed before every hook.
+
+use rhai::{Engine, EvalAltResult, FLOAT, INT};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::console::builtin::find_resource;
+use crate::economy::ResourceType;
+
+/// Operations a script may run in one call before it is stopped, so a
+/// runaway loop cannot hang the game
+pub const MAX_SCRIPT_OPERATIONS: u64 = 1_000_000;
+
+/// A change to the game asked for by a script
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptAction {
+    /// Present a random event, happening to the named colony for colony
+    /// events
+    SpawnEvent { id: String, colony: Option<String> },
+    /// Add to the stockpile, or take from it with a negative amount
+    ModifyBudget { resource: ResourceType, amount: f64 },
+    /// Write a line to the event log
+    Log(String),
+}
+
+/// Game state scripts can read
+#[derive(Debug, Clone, Default)]
+pub struct ScriptView {
+    pub stockpiles: HashMap<ResourceType, f64>,
+    pub unlocked: HashSet<String>,
+    pub date: String,
+}
+
+/// State shared between the engine's functions and the game
+#[derive(Debug, Clone, Default)]
+pub struct ScriptBridge {
+    actions: Arc<Mutex<Vec<ScriptAction>>>,
+    view: Arc<RwLock<ScriptView>>,
+}
+
+impl ScriptBridge {
+    fn push(&self, action: ScriptAction) {
+        self.actions.lock().unwrap().push(action);
+    }
+
+    /// Actions queued since the last call, oldest first
+    pub fn take_actions(&self) -> Vec<ScriptAction> {
+        std::mem::take(&mut *self.actions.lock().unwrap())
+    }
+
+    /// Replace what scripts see of the game
+    pub fn set_view(&self, view: ScriptView) {
+        *self.view.write().unwrap() = view;
+    }
+
+    fn modify_budget(&self, resource: &str, amount: f64) -> Result<(), Box<EvalAltResult>> {
+        let resource =
+            find_resource(resource).ok_or_else(|| format!("Unknown resource '{}'", resource))?;
+        if !amount.is_finite() {
+            return Err("The amount must be a finite number".into());
+        }
+        self.push(ScriptAction::ModifyBudget { resource, amount });
+        Ok(())
+    }
+}
+
+/// Engine with the game's functions registered:
+/// - `spawn_event(id)` and `spawn_event(id, colony)`
+/// - `modify_budget(resource, amount)`
+/// - `log(message)`
+/// - `stockpile(resource)`, `is_unlocked(tech_id)` and `date()`
+pub fn build_engine(bridge: &ScriptBridge) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_SCRIPT_OPERATIONS);
+    engine.on_print(|text| bevy::log::info!("[script] {}", text));
+
+    let b = bridge.clone();
+    engine.register_fn("spawn_event", move |id: &str| {
+        b.push(ScriptAction::SpawnEvent {
+            id: id.to_string(),
+            colony: None,
+        })
+    });
+    let b = bridge.clone();
+    engine.register_fn("spawn_event", move |id: &str, colony: &str| {
+        b.push(ScriptAction::SpawnEvent {
+            id: id.to_string(),
+            colony: Some(colony.to_string()),
+        })
+    });
+    let b = bridge.clone();
+    engine.register_fn("modify_budget", move |resource: &str, amount: FLOAT| {
+        b.modify_budget(resource, amount)
+    });
+    let b = bridge.clone();
+    engine.register_fn("modify_budget", move |resource: &str, amount: INT| {
+        b.modify_budget(resource, amount as f64)
+    });
+    let b = bridge.clone();
+    engine.register_fn("log", move |message: &str| {
+        b.push(ScriptAction::Log(message.to_string()))
+    });
+
+    let b = bridge.clone();
+    engine.register_fn(
+        "stockpile",
+        move |resource: &str| -> Result<FLOAT, Box<EvalAltResult>> {
+            let resource = find_resource(resource)
+                .ok_or_else(|| format!("Unknown resource '{}'", resource))?;
+            let view = b.view.read().unwrap();
+            Ok(view.stockpiles.get(&resource).copied().unwrap_or(0.0))
+        },
+    );
+    let b = bridge.clone();
+    engine.register_fn("is_unlocked", move |tech_id: &str| {
+        b.view.read().unwrap().unlocked.contains(tech_id)
+    });
+    let b = bridge.clone();
+    engine.register_fn("date", move || b.view.read().unwrap().date.clone());
+
+    engine
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script_functions_queue_actions() {
+        let bridge = ScriptBridge::default();
+        let mut view = ScriptView::default();
+        view.stockpiles.insert(ResourceType::Iron, 250.0);
+        bridge.set_view(view);
+        let engine = build_engine(&bridge);
+
+        engine
+            .run(
+                r#"
+                if stockpile("iron") > 100.0 {
+                    modify_budget("iron", -100);
+                    modify_budget("Carbon Dioxide", 2.5);
+                }
+                spawn_event("solar_flare");
+                spawn_event("mine_collapse", "Mars");
+                log("Done on " + date());
+                "#,
+            )
+            .unwrap();
+        assert_eq!(
+            bridge.take_actions(),
+            [
+                ScriptAction::ModifyBudget {
+                    resource: ResourceType::Iron,
+                    amount: -100.0
+                },
+                ScriptAction::ModifyBudget {
+                    resource: ResourceType::CarbonDioxide,
+                    amount: 2.5
+                },
+                ScriptAction::SpawnEvent {
+                    id: "solar_flare".to_string(),
+                    colony: None
+                },
+                ScriptAction::SpawnEvent {
+                    id: "mine_collapse".to_string(),
+                    colony: Some("Mars".to_string())
+                },
+                ScriptAction::Log("Done on ".to_string()),
+            ]
+        );
+        assert!(bridge.take_actions().is_empty());
+
+        assert!(engine.run(r#"modify_budget("unobtainium", 1)"#).is_err());
+        assert!(engine.run("loop {}").is_err());
+    }
+}
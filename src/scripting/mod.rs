@@ -0,0 +1,73 @@
+//! Scripting hooks
+//!
+//! Scenario scripts and mods can react to game events without recompiling
+//! the game. Scripts are written in [Rhai](https://rhai.rs): every `.rhai`
+//! file in `assets/scripts` is loaded at startup in name order, followed by
+//! the `script.rhai` of each enabled mod. A scenario can name its own
+//! script (`script` in `scenarios.ron`), loaded when the campaign starts
+//! from it. A script's top-level statements run once when it loads; the
+//! game then calls the hooks it defines:
+//! - `on_research_complete(tech_id)` when a technology is researched
+//! - `on_colony_founded(body_name)` when a colony is founded
+//!
+//! Scripts change the game through `spawn_event`, `modify_budget` and `log`
+//! (see [`api::build_engine`]), which take effect after the script returns.
+//! A failing script is reported in the event log and doesn't stop the
+//! others.
+
+use bevy::prelude::*;
+use std::fs;
+use std::path::Path;
+
+use crate::console::{ConsoleCommand, RegisterConsoleCommand};
+
+pub mod api;
+pub mod systems;
+
+pub use api::{build_engine, ScriptAction, ScriptBridge, ScriptView};
+pub use systems::{
+    apply_script_actions, load_scenario_script, load_scripts, run_script_hooks, script_files,
+    Script, Scripts, MOD_SCRIPT_FILE, ON_COLONY_FOUNDED, ON_RESEARCH_COMPLETE, SCRIPTS_DIR,
+};
+
+/// Console command loading a script file, e.g. to try a mod's script in a
+/// running game
+fn load_script(world: &mut World, args: &[String]) -> Result<String, String> {
+    let [path] = args else {
+        return Err("Usage: load_script <path>".to_string());
+    };
+    let path = Path::new(path);
+    let source = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    world
+        .get_resource_mut::<Scripts>()
+        .ok_or("Scripting is not running")?
+        .load(path, &source)?;
+    Ok(format!("Loaded {}", path.display()))
+}
+
+/// Plugin that loads the scripts and runs their hooks
+pub struct ScriptingPlugin;
+
+impl Plugin for ScriptingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Scripts>()
+            .add_systems(Startup, load_scripts)
+            .add_systems(
+                Update,
+                (
+                    load_scenario_script.after(crate::scenarios::start_scenario),
+                    (run_script_hooks, apply_script_actions)
+                        .chain()
+                        .after(load_scenario_script)
+                        .after(crate::economy::found_requested_colonies),
+                ),
+            )
+            .register_console_command(ConsoleCommand {
+                name: "load_script",
+                usage: "<path>",
+                description: "Load a script and run it; its hooks stay active",
+                handler: load_script,
+            });
+    }
+}
@@ -0,0 +1,427 @@
+use bevy::prelude::*;
+use rhai::{CallFnOptions, Dynamic, Engine, Scope, AST};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::api::{build_engine, ScriptAction, ScriptBridge, ScriptView};
+use crate::economy::components::Population;
+use crate::economy::{ColonyFounded, GlobalBudget};
+use crate::game_state::{EventLog, LogSeverity};
+use crate::modding::ModList;
+use crate::plugins::solar_system::CelestialBody;
+use crate::random_events::{EventTarget, EventsData, PendingEvents};
+use crate::replay::ReplayPlayback;
+use crate::research::{ResearchCompleted, ResearchState};
+use crate::scenarios::{ScenarioId, ScenarioSelection, ScenariosData};
+use crate::ui::{SimulationTime, TimeScale};
+
+/// Directory of the base game's scripts, all run in file name order
+pub const SCRIPTS_DIR: &str = "assets/scripts";
+/// Extension of script files
+pub const SCRIPT_EXTENSION: &str = "rhai";
+/// Script file a mod can ship
+pub const MOD_SCRIPT_FILE: &str = "script.rhai";
+
+/// Hook called with the id of each technology researched
+pub const ON_RESEARCH_COMPLETE: &str = "on_research_complete";
+/// Hook called with the name of each body a colony is founded on
+pub const ON_COLONY_FOUNDED: &str = "on_colony_founded";
+
+/// A compiled script
+pub struct Script {
+    /// File the script was read from
+    pub path: PathBuf,
+    pub source: String,
+    ast: AST,
+}
+
+impl Script {
+    /// Whether the script defines `hook` taking `arity` arguments
+    pub fn defines(&self, hook: &str, arity: usize) -> bool {
+        self.ast
+            .iter_functions()
+            .any(|f| f.name == hook && f.params.len() == arity)
+    }
+}
+
+/// The scripting engine and the loaded scripts
+#[derive(Resource)]
+pub struct Scripts {
+    pub engine: Engine,
+    pub bridge: ScriptBridge,
+    pub scripts: Vec<Script>,
+    /// Number of scripts loaded at startup or with the scenario, the first
+    /// in `scripts`; the others came from the console
+    pub startup: usize,
+}
+
+impl Default for Scripts {
+    fn default() -> Self {
+        let bridge = ScriptBridge::default();
+        Self {
+            engine: build_engine(&bridge),
+            bridge,
+            scripts: Vec::new(),
+            startup: 0,
+        }
+    }
+}
+
+impl Scripts {
+    /// Compile a script and run its top-level statements
+    pub fn load(&mut self, path: &Path, source: &str) -> Result<(), String> {
+        let ast = self
+            .engine
+            .compile(source)
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        self.engine
+            .run_ast(&ast)
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        self.scripts.push(Script {
+            path: path.to_path_buf(),
+            source: source.to_string(),
+            ast,
+        });
+        Ok(())
+    }
+
+    /// Load a script as one of the startup scripts: after those already
+    /// loaded at startup, before any from the console
+    pub fn load_startup(&mut self, path: &Path, source: &str) -> Result<(), String> {
+        self.load(path, source)?;
+        if let Some(script) = self.scripts.pop() {
+            self.scripts.insert(self.startup, script);
+            self.startup += 1;
+        }
+        Ok(())
+    }
+
+    /// Call `hook` in every script that defines it, in load order. Returns
+    /// the errors of the scripts that failed.
+    pub fn call_hook(&self, hook: &str, args: Vec<Dynamic>) -> Vec<String> {
+        let mut errors = Vec::new();
+        for script in self.scripts.iter().filter(|s| s.defines(hook, args.len())) {
+            // The top-level statements already ran when the script loaded
+            let options = CallFnOptions::new().eval_ast(false);
+            if let Err(e) = self.engine.call_fn_with_options::<Dynamic>(
+                options,
+                &mut Scope::new(),
+                &script.ast,
+                hook,
+                args.clone(),
+            ) {
+                errors.push(format!("{} in {}: {}", hook, script.path.display(), e));
+            }
+        }
+        errors
+    }
+}
+
+/// Script files to load: those in [`SCRIPTS_DIR`] by name, then the scripts
+/// of enabled mods in load order
+pub fn script_files(dir: &Path, mods: Option<&ModList>) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.is_file() && path.extension().is_some_and(|e| e == SCRIPT_EXTENSION)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files.extend(mods.map_or(Vec::new(), |mods| mods.data_files(MOD_SCRIPT_FILE)));
+    files
+}
+
+/// System to compile the scripts at startup. A replay being played back
+/// brings the scripts its campaign started with, which are loaded instead
+/// of the files on disk.
+pub fn load_scripts(
+    mut scripts: ResMut<Scripts>,
+    mods: Option<Res<ModList>>,
+    playback: Option<Res<ReplayPlayback>>,
+) {
+    let recorded = playback
+        .as_ref()
+        .and_then(|p| p.replay.as_ref())
+        .and_then(|replay| replay.scripts.clone());
+    let sources: Vec<(PathBuf, Result<String, String>)> = match recorded {
+        Some(recorded) => recorded
+            .into_iter()
+            .map(|script| (script.path, Ok(script.source)))
+            .collect(),
+        None => script_files(Path::new(SCRIPTS_DIR), mods.as_deref())
+            .into_iter()
+            .map(|path| {
+                let source = fs::read_to_string(&path)
+                    .map_err(|e| format!("Failed to read {}: {}", path.display(), e));
+                (path, source)
+            })
+            .collect(),
+    };
+    for (path, source) in sources {
+        match source.and_then(|source| scripts.load_startup(&path, &source)) {
+            Ok(()) => info!("Loaded script {}", path.display()),
+            Err(e) => error!("Script error: {}", e),
+        }
+    }
+}
+
+/// System that loads the script of the scenario that just started, among
+/// the startup scripts so a replay records it. A replay being played back
+/// brought it along already.
+pub fn load_scenario_script(
+    mut scripts: ResMut<Scripts>,
+    selection: Option<Res<ScenarioSelection>>,
+    scenarios: Option<Res<ScenariosData>>,
+    mut started: Local<Option<ScenarioId>>,
+) {
+    let Some(selection) = selection else {
+        return;
+    };
+    if !selection.is_changed() || selection.active == *started {
+        return;
+    }
+    *started = selection.active.clone();
+    let Some(path) = started
+        .as_ref()
+        .and_then(|id| scenarios.as_ref()?.get(id)?.script.as_ref())
+        .map(PathBuf::from)
+    else {
+        return;
+    };
+    if scripts.scripts.iter().any(|script| script.path == path) {
+        return;
+    }
+    let loaded = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+        .and_then(|source| scripts.load_startup(&path, &source));
+    match loaded {
+        Ok(()) => info!("Loaded scenario script {}", path.display()),
+        Err(e) => error!("Script error: {}", e),
+    }
+}
+
+/// System that calls the script hooks for the game events of this update
+pub fn run_script_hooks(
+    scripts: Res<Scripts>,
+    mut research_completed: EventReader<ResearchCompleted>,
+    mut colonies_founded: EventReader<ColonyFounded>,
+    budget: Option<Res<GlobalBudget>>,
+    research_state: Option<Res<ResearchState>>,
+    sim_time: Option<Res<SimulationTime>>,
+    mut log: Option<ResMut<EventLog>>,
+) {
+    let mut calls: Vec<(&str, Vec<Dynamic>)> = research_completed
+        .read()
+        .map(|event| (ON_RESEARCH_COMPLETE, vec![event.tech_id.clone().into()]))
+        .collect();
+    calls.extend(
+        colonies_founded
+            .read()
+            .map(|event| (ON_COLONY_FOUNDED, vec![event.body_name.clone().into()])),
+    );
+    if calls.is_empty() || scripts.scripts.is_empty() {
+        return;
+    }
+
+    let date = sim_time
+        .as_ref()
+        .map(|t| t.format_date_time())
+        .unwrap_or_default();
+    scripts.bridge.set_view(ScriptView {
+        stockpiles: budget.map(|b| b.stockpiles.clone()).unwrap_or_default(),
+        unlocked: research_state
+            .map(|r| r.unlocked_technologies.clone())
+            .unwrap_or_default(),
+        date: date.clone(),
+    });
+    for (hook, args) in calls {
+        for e in scripts.call_hook(hook, args) {
+            error!("Script error: {}", e);
+            if let Some(log) = log.as_mut() {
+                log.push(
+                    date.clone(),
+                    LogSeverity::Warning,
+                    format!("Script error: {}", e),
+                );
+            }
+        }
+    }
+}
+
+/// System that carries out what the scripts asked for
+#[allow(clippy::too_many_arguments)]
+pub fn apply_script_actions(
+    scripts: Res<Scripts>,
+    events: Option<Res<EventsData>>,
+    mut pending: ResMut<PendingEvents>,
+    mut budget: ResMut<GlobalBudget>,
+    mut time_scale: ResMut<TimeScale>,
+    mut log: ResMut<EventLog>,
+    sim_time: Res<SimulationTime>,
+    colonies: Query<(Entity, &CelestialBody, &Population)>,
+) {
+    for action in scripts.bridge.take_actions() {
+        let date = sim_time.format_date_time();
+        match action {
+            ScriptAction::ModifyBudget { resource, amount } => {
+                // Taking more than there is empties the stockpile
+                let stockpile = budget.stockpiles.entry(resource).or_insert(0.0);
+                *stockpile = (*stockpile + amount).max(0.0);
+            }
+            ScriptAction::Log(message) => log.push(date, LogSeverity::Info, message),
+            ScriptAction::SpawnEvent { id, colony } => {
+                let Some(event) = events.as_ref().and_then(|events| events.get(&id)) else {
+                    log.push(
+                        date,
+                        LogSeverity::Warning,
+                        format!("Script error: unknown event '{}'", id),
+                    );
+                    continue;
+                };
+                let (target, target_name) = match event.target {
+                    EventTarget::Global => (None, None),
+                    EventTarget::Colony => {
+                        // The named colony, or else the most populated one
+                        let target = colonies
+                            .iter()
+                            .filter(|(_, body, population)| {
+                                population.count > 0.0
+                                    && colony.as_ref().is_none_or(|name| *name == body.name)
+                            })
+                            .max_by(|a, b| a.2.count.total_cmp(&b.2.count));
+                        let Some((entity, body, _)) = target else {
+                            log.push(
+                                date,
+                                LogSeverity::Warning,
+                                format!("Script error: no colony for event '{}'", id),
+                            );
+                            continue;
+                        };
+                        (Some(entity), Some(body.name.clone()))
+                    }
+                    EventTarget::Anomaly | EventTarget::Contact => {
+                        log.push(
+                            date,
+                            LogSeverity::Warning,
+                            format!("Script error: '{}' cannot be fired by a script", id),
+                        );
+                        continue;
+                    }
+                };
+                pending.present(event, target, target_name, &mut time_scale, &mut log, date);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hooks_run_in_scripts_that_define_them() {
+        let mut scripts = Scripts::default();
+        scripts
+            .load(
+                Path::new("first.rhai"),
+                r#"
+                log("loaded");
+                fn on_research_complete(tech) {
+                    if tech == "fusion_power" {
+                        modify_budget("water", 500);
+                    }
+                }
+                "#,
+            )
+            .unwrap();
+        scripts
+            .load(
+                Path::new("second.rhai"),
+                r#"
+                fn on_colony_founded(body) {
+                    if body == "" {
+                        throw "unnamed colony";
+                    }
+                    log("Welcome to " + body);
+                }
+                "#,
+            )
+            .unwrap();
+        assert!(scripts.load(Path::new("broken.rhai"), "fn (").is_err());
+        assert_eq!(scripts.scripts.len(), 2);
+        assert_eq!(
+            scripts.bridge.take_actions(),
+            [ScriptAction::Log("loaded".to_string())]
+        );
+
+        // Loading ran the top level once; hooks don't run it again
+        assert!(scripts
+            .call_hook(ON_RESEARCH_COMPLETE, vec!["fusion_power".into()])
+            .is_empty());
+        assert!(scripts
+            .call_hook(ON_COLONY_FOUNDED, vec!["Mars".into()])
+            .is_empty());
+        let actions = scripts.bridge.take_actions();
+        assert_eq!(actions.len(), 2);
+        assert!(matches!(actions[0], ScriptAction::ModifyBudget { amount, .. } if amount == 500.0));
+        assert_eq!(actions[1], ScriptAction::Log("Welcome to Mars".to_string()));
+
+        let errors = scripts.call_hook(ON_COLONY_FOUNDED, vec!["".into()]);
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+    }
+
+    #[test]
+    fn test_scenario_script_loads_once_among_the_startup_scripts() {
+        let path = std::env::temp_dir().join(format!(
+            "helios_scenario_script_{}.rhai",
+            std::process::id()
+        ));
+        fs::write(&path, r#"log("scenario");"#).unwrap();
+        let mut scenarios = ScenariosData::default();
+        scenarios.insert(crate::scenarios::Scenario {
+            id: "first_contact".to_string(),
+            name: "First Contact".to_string(),
+            description: String::new(),
+            start: Default::default(),
+            colonies: vec![],
+            technologies: vec![],
+            components: vec![],
+            stockpile: None,
+            refined_stockpile: None,
+            food_stockpile: None,
+            extinction: None,
+            script: Some(path.to_string_lossy().into_owned()),
+        });
+
+        let mut app = App::new();
+        app.init_resource::<Scripts>()
+            .init_resource::<ScenarioSelection>()
+            .insert_resource(scenarios)
+            .add_systems(Update, load_scenario_script);
+        let mut scripts = app.world_mut().resource_mut::<Scripts>();
+        scripts.load(Path::new("console.rhai"), "").unwrap();
+
+        app.update();
+        assert_eq!(app.world().resource::<Scripts>().scripts.len(), 1);
+        app.world_mut().resource_mut::<ScenarioSelection>().active =
+            Some("first_contact".to_string());
+        app.update();
+        app.update();
+        fs::remove_file(&path).unwrap();
+
+        // Loaded once, ahead of the script from the console
+        let scripts = app.world().resource::<Scripts>();
+        assert_eq!(scripts.scripts.len(), 2);
+        assert_eq!(scripts.startup, 1);
+        assert_eq!(scripts.scripts[0].path, path);
+        assert_eq!(
+            scripts.bridge.take_actions(),
+            [ScriptAction::Log("scenario".to_string())]
+        );
+    }
+}
@@ -0,0 +1,209 @@
+//! Audio: ambient music, interface sounds and event stingers
+//!
+//! All sounds are synthesized (see [`synth`]) and played through
+//! `bevy_audio`. The ambient music loops for the whole session; clicks and
+//! hovers on egui widgets, completed research, Kardashev milestones and newly
+//! raised alerts play short one-shot effects. Master, music and effects
+//! volumes are part of the UI settings and edited from Menu → Options.
+
+use bevy::audio::{AddAudioSource, AudioSinkPlayback, AudioSourceBundle, Volume};
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiSet};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::economy::{ActiveAlerts, AlertKind, KardashevMilestoneReached};
+use crate::research::ResearchCompleted;
+use crate::ui::UiSettings;
+
+pub mod synth;
+
+pub use synth::{SoundKind, Synth, SynthDecoder, SAMPLE_RATE};
+
+/// Playback volumes, each 0..=1
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VolumeSettings {
+    pub master: f32,
+    pub music: f32,
+    pub effects: f32,
+}
+
+impl Default for VolumeSettings {
+    fn default() -> Self {
+        Self {
+            master: 0.8,
+            music: 0.5,
+            effects: 0.7,
+        }
+    }
+}
+
+impl VolumeSettings {
+    /// Clamp every volume into 0..=1
+    pub fn clamped(self) -> Self {
+        Self {
+            master: self.master.clamp(0.0, 1.0),
+            music: self.music.clamp(0.0, 1.0),
+            effects: self.effects.clamp(0.0, 1.0),
+        }
+    }
+
+    pub fn music_volume(&self) -> f32 {
+        self.master * self.music
+    }
+
+    pub fn effects_volume(&self) -> f32 {
+        self.master * self.effects
+    }
+}
+
+/// Handles of the synthesized sounds
+#[derive(Resource, Debug, Clone, Default)]
+pub struct SoundLibrary {
+    pub sounds: HashMap<SoundKind, Handle<Synth>>,
+}
+
+/// Marker of the entity playing the ambient music
+#[derive(Component)]
+pub struct AmbientMusic;
+
+/// Create a synth asset for every sound
+fn load_sounds(mut commands: Commands, mut assets: ResMut<Assets<Synth>>) {
+    let sounds = std::iter::once(SoundKind::AmbientMusic)
+        .chain(SoundKind::EFFECTS)
+        .map(|kind| (kind, assets.add(Synth { kind })))
+        .collect();
+    commands.insert_resource(SoundLibrary { sounds });
+}
+
+/// Start the ambient music
+fn start_ambient_music(
+    mut commands: Commands,
+    library: Res<SoundLibrary>,
+    settings: Res<UiSettings>,
+) {
+    let Some(music) = library.sounds.get(&SoundKind::AmbientMusic) else {
+        return;
+    };
+    commands.spawn((
+        AudioSourceBundle {
+            source: music.clone(),
+            settings: PlaybackSettings::LOOP
+                .with_volume(Volume::new(settings.audio.music_volume())),
+        },
+        AmbientMusic,
+        Name::new("Ambient Music"),
+    ));
+}
+
+/// Follow volume changes made in the options window
+fn apply_music_volume(settings: Res<UiSettings>, music: Query<&AudioSink, With<AmbientMusic>>) {
+    if !settings.is_changed() {
+        return;
+    }
+    for sink in music.iter() {
+        sink.set_volume(settings.audio.music_volume());
+    }
+}
+
+/// Play a one-shot effect at the effects volume
+fn play_effect(
+    commands: &mut Commands,
+    library: &SoundLibrary,
+    settings: &VolumeSettings,
+    kind: SoundKind,
+) {
+    let volume = settings.effects_volume();
+    if volume <= 0.0 {
+        return;
+    }
+    if let Some(sound) = library.sounds.get(&kind) {
+        commands.spawn(AudioSourceBundle {
+            source: sound.clone(),
+            settings: PlaybackSettings::DESPAWN.with_volume(Volume::new(volume)),
+        });
+    }
+}
+
+/// Play click sounds for widgets clicked this frame and a hover sound when
+/// the pointer moves onto something clickable. Runs after every UI system,
+/// before egui's output is consumed.
+fn play_ui_sounds(
+    mut commands: Commands,
+    mut contexts: EguiContexts,
+    library: Option<Res<SoundLibrary>>,
+    settings: Res<UiSettings>,
+    mut was_pointing: Local<bool>,
+) {
+    let Some(library) = library else {
+        return;
+    };
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    let (clicked, pointing) = ctx.output(|o| {
+        (
+            o.events
+                .iter()
+                .any(|e| matches!(e, egui::output::OutputEvent::Clicked(_))),
+            o.cursor_icon == egui::CursorIcon::PointingHand,
+        )
+    });
+
+    if clicked {
+        play_effect(&mut commands, &library, &settings.audio, SoundKind::Click);
+    } else if pointing && !*was_pointing {
+        play_effect(&mut commands, &library, &settings.audio, SoundKind::Hover);
+    }
+    *was_pointing = pointing;
+}
+
+/// Play stingers for completed research, Kardashev milestones and newly
+/// raised alerts
+fn play_stingers(
+    mut commands: Commands,
+    library: Option<Res<SoundLibrary>>,
+    settings: Res<UiSettings>,
+    mut completed: EventReader<ResearchCompleted>,
+    mut milestones: EventReader<KardashevMilestoneReached>,
+    alerts: Option<Res<ActiveAlerts>>,
+    mut known_alerts: Local<Vec<AlertKind>>,
+) {
+    let researched = completed.read().count() > 0;
+    let milestone = milestones.read().count() > 0;
+    let mut raised = false;
+    if let Some(alerts) = alerts.filter(|a| a.is_changed()) {
+        raised = alerts.alerts.iter().any(|a| !known_alerts.contains(&a.kind));
+        *known_alerts = alerts.alerts.iter().map(|a| a.kind.clone()).collect();
+    }
+    let Some(library) = library else {
+        return;
+    };
+
+    if raised {
+        play_effect(&mut commands, &library, &settings.audio, SoundKind::Alert);
+    } else if researched || milestone {
+        play_effect(
+            &mut commands,
+            &library,
+            &settings.audio,
+            SoundKind::ResearchComplete,
+        );
+    }
+}
+
+/// Plugin that adds music and sound effects to the Bevy app
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_audio_source::<Synth>()
+            .add_systems(Startup, (load_sounds, start_ambient_music).chain())
+            .add_systems(Update, (apply_music_volume, play_stingers))
+            .add_systems(
+                PostUpdate,
+                play_ui_sounds.before(EguiSet::ProcessOutput),
+            );
+    }
+}
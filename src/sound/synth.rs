@@ -0,0 +1,212 @@
+//! Procedural sounds
+//!
+//! Every sound is synthesized from sines so the game needs no audio files:
+//! short enveloped blips for the interface, note sequences for stingers and
+//! an endless pad that drifts between two chords for the ambient music.
+
+use bevy::audio::{Decodable, Source};
+use bevy::prelude::*;
+use std::f64::consts::TAU;
+use std::time::Duration;
+
+/// Output sample rate of the synthesizer
+pub const SAMPLE_RATE: u32 = 44_100;
+
+/// Seconds the ambient pad takes to move from one chord to the other
+pub const CHORD_SECONDS: f64 = 20.0;
+
+/// Pad voicings the music alternates between (A minor, F major), in Hz
+const CHORDS: [[f64; 5]; 2] = [
+    [110.0, 164.81, 220.0, 261.63, 329.63],
+    [87.31, 130.81, 174.61, 220.0, 261.63],
+];
+
+/// A sound the synthesizer can play
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SoundKind {
+    /// Endless ambient pad
+    AmbientMusic,
+    /// A widget was clicked
+    Click,
+    /// The pointer moved onto something clickable
+    Hover,
+    /// Rising arpeggio when a technology is researched
+    ResearchComplete,
+    /// Two falling beeps when a warning is raised
+    Alert,
+}
+
+impl SoundKind {
+    pub const EFFECTS: [SoundKind; 4] = [
+        SoundKind::Click,
+        SoundKind::Hover,
+        SoundKind::ResearchComplete,
+        SoundKind::Alert,
+    ];
+
+    /// Length in seconds, `None` for the endless music
+    pub fn duration(&self) -> Option<f64> {
+        match self {
+            SoundKind::AmbientMusic => None,
+            SoundKind::Click => Some(0.04),
+            SoundKind::Hover => Some(0.025),
+            SoundKind::ResearchComplete => Some(1.0),
+            SoundKind::Alert => Some(0.5),
+        }
+    }
+
+    /// Amplitude at `t` seconds, within -1..=1
+    pub fn sample(&self, t: f64) -> f32 {
+        let value = match self {
+            SoundKind::AmbientMusic => ambient(t),
+            SoundKind::Click => 0.5 * blip(1800.0, 0.008, t),
+            SoundKind::Hover => 0.2 * blip(1200.0, 0.006, t),
+            SoundKind::ResearchComplete => {
+                // C5 E5 G5 C6, each struck 0.12 s after the last
+                [523.25, 659.25, 783.99, 1046.5]
+                    .iter()
+                    .enumerate()
+                    .map(|(i, freq)| 0.12 * bell(*freq, t - 0.12 * i as f64))
+                    .sum()
+            }
+            SoundKind::Alert => 0.3 * (beep(880.0, t, 0.15) + beep(659.25, t - 0.22, 0.2)),
+        };
+        value.clamp(-1.0, 1.0) as f32
+    }
+}
+
+/// Sine decaying exponentially with time constant `tau`
+fn blip(freq: f64, tau: f64, t: f64) -> f64 {
+    (TAU * freq * t).sin() * (-t / tau).exp()
+}
+
+/// Struck tone with a soft second harmonic, silent before `t = 0`
+fn bell(freq: f64, t: f64) -> f64 {
+    if t < 0.0 {
+        return 0.0;
+    }
+    let tone = (TAU * freq * t).sin() + 0.3 * (TAU * 2.0 * freq * t).sin();
+    let attack = (t / 0.005).min(1.0);
+    tone * attack * (-t / 0.25).exp()
+}
+
+/// Hollow, square-ish beep lasting `length` seconds from `t = 0`
+fn beep(freq: f64, t: f64, length: f64) -> f64 {
+    if !(0.0..length).contains(&t) {
+        return 0.0;
+    }
+    let tone = (TAU * freq * t).sin() + (TAU * 3.0 * freq * t).sin() / 3.0;
+    // Short fades so the edges do not click
+    let fade = (t / 0.01).min(1.0).min((length - t) / 0.01);
+    tone * fade
+}
+
+/// Slowly breathing pad crossfading between the two chords
+fn ambient(t: f64) -> f64 {
+    let blend = 0.5 - 0.5 * (TAU * t / (2.0 * CHORD_SECONDS)).cos();
+    let mut value = 0.0;
+    for (chord, weight) in CHORDS.iter().zip([1.0 - blend, blend]) {
+        for (i, freq) in chord.iter().enumerate() {
+            // Each voice swells at its own slow rate
+            let lfo = 0.6 + 0.4 * (TAU * (0.05 + 0.013 * i as f64) * t + i as f64).sin();
+            let detuned = (TAU * freq * t).sin() + (TAU * freq * 1.003 * t).sin();
+            value += weight * lfo * detuned;
+        }
+    }
+    // Fade in over the first few seconds
+    0.03 * value * (t / 4.0).min(1.0)
+}
+
+/// Asset playing one synthesized sound
+#[derive(Asset, TypePath, Debug, Clone, Copy)]
+pub struct Synth {
+    pub kind: SoundKind,
+}
+
+/// Sample stream of a `Synth`
+pub struct SynthDecoder {
+    kind: SoundKind,
+    index: u64,
+    /// Samples to produce, `None` for endless sounds
+    length: Option<u64>,
+}
+
+impl Iterator for SynthDecoder {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.length.is_some_and(|length| self.index >= length) {
+            return None;
+        }
+        let t = self.index as f64 / SAMPLE_RATE as f64;
+        self.index += 1;
+        Some(self.kind.sample(t))
+    }
+}
+
+impl Source for SynthDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.kind.duration().map(Duration::from_secs_f64)
+    }
+}
+
+impl Decodable for Synth {
+    type DecoderItem = f32;
+    type Decoder = SynthDecoder;
+
+    fn decoder(&self) -> SynthDecoder {
+        SynthDecoder {
+            kind: self.kind,
+            index: 0,
+            length: self
+                .kind
+                .duration()
+                .map(|seconds| (seconds * SAMPLE_RATE as f64).ceil() as u64),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effects_end_and_stay_in_range() {
+        for kind in SoundKind::EFFECTS {
+            let samples: Vec<f32> = Synth { kind }.decoder().collect();
+            let expected = (kind.duration().unwrap() * SAMPLE_RATE as f64).ceil() as usize;
+            assert_eq!(samples.len(), expected, "{:?}", kind);
+            assert!(samples.iter().all(|s| s.abs() <= 1.0));
+            assert!(samples.iter().any(|s| s.abs() > 0.01), "{:?} is silent", kind);
+        }
+    }
+
+    #[test]
+    fn test_music_is_endless_and_quiet() {
+        let mut decoder = Synth {
+            kind: SoundKind::AmbientMusic,
+        }
+        .decoder();
+        assert_eq!(decoder.total_duration(), None);
+        // Skip to a minute in, past the fade-in and into the crossfade
+        let minute = 60 * SAMPLE_RATE as usize;
+        let peak = decoder
+            .by_ref()
+            .skip(minute)
+            .take(SAMPLE_RATE as usize)
+            .fold(0.0f32, |peak, s| peak.max(s.abs()));
+        assert!(peak > 0.05 && peak < 0.6, "peak {}", peak);
+    }
+}
@@ -0,0 +1,244 @@
+use bevy::prelude::*;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use crate::economy::components::Population;
+use crate::economy::{EconomyClock, GlobalBudget, ResourceType, SECONDS_PER_YEAR};
+use crate::research::ResearchState;
+use crate::ui::SimulationTime;
+
+/// Economy ticks (days) between two samples, one 30-day month
+pub const STATS_INTERVAL_TICKS: u64 = 30;
+
+/// Samples kept before the oldest are dropped (a century of months)
+pub const MAX_STATS_SAMPLES: usize = 1_200;
+
+/// File the history is exported to, next to the executable
+pub const STATS_CSV_PATH: &str = "stats_history.csv";
+
+/// A tracked quantity that can be plotted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Statistic {
+    Population,
+    /// Power produced, in watts
+    Power,
+    Kardashev,
+    /// Research points generated per year
+    ResearchPoints,
+    /// Stockpile of a resource, in Mt
+    Stockpile(ResourceType),
+}
+
+impl Statistic {
+    /// Statistics other than stockpiles
+    pub const GENERAL: [Statistic; 4] = [
+        Statistic::Population,
+        Statistic::Power,
+        Statistic::Kardashev,
+        Statistic::ResearchPoints,
+    ];
+
+    pub fn name(&self) -> String {
+        match self {
+            Statistic::Population => "Population".to_string(),
+            Statistic::Power => "Power (W)".to_string(),
+            Statistic::Kardashev => "Kardashev".to_string(),
+            Statistic::ResearchPoints => "Research (RP/year)".to_string(),
+            Statistic::Stockpile(resource) => format!("{} (Mt)", resource.display_name()),
+        }
+    }
+}
+
+/// Civilization-wide figures at the end of one month
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatsSample {
+    /// Calendar year, with the fraction of the year elapsed
+    pub year: f64,
+    /// In-game date as shown in the time controls
+    pub date: String,
+    pub population: f64,
+    pub power_watts: f64,
+    pub kardashev: f64,
+    pub research_points_per_year: f64,
+    pub stockpiles: HashMap<ResourceType, f64>,
+}
+
+impl StatsSample {
+    /// Value of one statistic
+    pub fn value(&self, statistic: Statistic) -> f64 {
+        match statistic {
+            Statistic::Population => self.population,
+            Statistic::Power => self.power_watts,
+            Statistic::Kardashev => self.kardashev,
+            Statistic::ResearchPoints => self.research_points_per_year,
+            Statistic::Stockpile(resource) => {
+                self.stockpiles.get(&resource).copied().unwrap_or(0.0)
+            }
+        }
+    }
+}
+
+/// Ring buffer of monthly samples, oldest first
+#[derive(Resource, Debug, Clone, Default)]
+pub struct StatsHistory {
+    pub samples: VecDeque<StatsSample>,
+}
+
+impl StatsHistory {
+    /// Append a sample, dropping the oldest once the buffer is full
+    pub fn record(&mut self, sample: StatsSample) {
+        self.samples.push_back(sample);
+        while self.samples.len() > MAX_STATS_SAMPLES {
+            self.samples.pop_front();
+        }
+    }
+
+    /// `(year, value)` points of one statistic, for plotting
+    pub fn series(&self, statistic: Statistic) -> Vec<(f64, f64)> {
+        self.samples
+            .iter()
+            .map(|s| (s.year, s.value(statistic)))
+            .collect()
+    }
+
+    /// The history as CSV, one row per sample and one stockpile column per
+    /// resource
+    pub fn to_csv(&self) -> String {
+        let mut csv =
+            String::from("date,year,population,power_w,kardashev,research_points_per_year");
+        for resource in ResourceType::all() {
+            let column = resource.display_name().to_lowercase().replace(' ', "_");
+            let _ = write!(csv, ",{}_mt", column);
+        }
+        csv.push('\n');
+        for sample in &self.samples {
+            let _ = write!(
+                csv,
+                "{},{:.4},{},{},{:.4},{}",
+                sample.date,
+                sample.year,
+                sample.population,
+                sample.power_watts,
+                sample.kardashev,
+                sample.research_points_per_year
+            );
+            for resource in ResourceType::all() {
+                let _ = write!(csv, ",{}", sample.value(Statistic::Stockpile(*resource)));
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// Write the history as CSV to `path`
+    pub fn export_csv(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_csv())
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+}
+
+/// Calendar year of a Unix timestamp, with the elapsed fraction of the year
+pub fn fractional_year(timestamp: i64) -> f64 {
+    1970.0 + timestamp as f64 / SECONDS_PER_YEAR
+}
+
+/// System that samples the civilization's figures whenever the economy clock
+/// crosses a month. Runs after the tick-driven systems so a sample sees the
+/// month's changes.
+pub fn record_stats(
+    clock: Res<EconomyClock>,
+    sim_time: Res<SimulationTime>,
+    budget: Res<GlobalBudget>,
+    research_state: Option<Res<ResearchState>>,
+    populations: Query<&Population>,
+    mut history: ResMut<StatsHistory>,
+) {
+    if clock.due == 0 {
+        return;
+    }
+    let previous = clock.processed - clock.due;
+    if clock.processed / STATS_INTERVAL_TICKS == previous / STATS_INTERVAL_TICKS {
+        return;
+    }
+
+    history.record(StatsSample {
+        year: fractional_year(sim_time.current_timestamp()),
+        date: sim_time.format_date_time(),
+        population: populations.iter().map(|p| p.count).sum(),
+        power_watts: budget.energy_grid.produced,
+        kardashev: budget.kardashev_index(),
+        research_points_per_year: research_state
+            .map_or(0.0, |r| r.rp_rate_per_second * SECONDS_PER_YEAR),
+        stockpiles: budget.stockpiles.clone(),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::economy::ECONOMY_TICK_SECONDS;
+
+    fn sample(year: f64, population: f64) -> StatsSample {
+        StatsSample {
+            year,
+            date: "01.01.2026 00:00".to_string(),
+            population,
+            power_watts: 2.0e13,
+            kardashev: 0.73,
+            research_points_per_year: 2000.0,
+            stockpiles: HashMap::from([(ResourceType::Iron, 50.0)]),
+        }
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut history = StatsHistory::default();
+        for month in 0..(MAX_STATS_SAMPLES + 3) {
+            history.record(sample(2026.0 + month as f64 / 12.0, month as f64));
+        }
+        assert_eq!(history.samples.len(), MAX_STATS_SAMPLES);
+        assert_eq!(history.series(Statistic::Population)[0].1, 3.0);
+    }
+
+    #[test]
+    fn test_csv_has_a_column_per_resource() {
+        let mut history = StatsHistory::default();
+        history.record(sample(2026.0, 8.2e9));
+        let csv = history.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        let columns = 6 + ResourceType::all().len();
+        assert_eq!(lines[0].split(',').count(), columns);
+        assert_eq!(lines[1].split(',').count(), columns);
+        assert!(lines[0].contains(",iron_mt"));
+        assert!(lines[1].starts_with("01.01.2026 00:00,2026.0000,8200000000,"));
+    }
+
+    #[test]
+    fn test_samples_taken_monthly() {
+        let mut app = App::new();
+        app.init_resource::<EconomyClock>()
+            .init_resource::<SimulationTime>()
+            .init_resource::<GlobalBudget>()
+            .init_resource::<StatsHistory>()
+            .add_systems(Update, record_stats);
+        app.world_mut().spawn(Population::new(1000.0));
+
+        for days in [10.0, 29.0, 30.0, 75.0] {
+            app.world_mut().resource_mut::<SimulationTime>().elapsed =
+                days * ECONOMY_TICK_SECONDS;
+            app.world_mut()
+                .resource_mut::<EconomyClock>()
+                .advance(days * ECONOMY_TICK_SECONDS);
+            app.update();
+        }
+        let history = app.world().resource::<StatsHistory>();
+        assert_eq!(history.samples.len(), 2);
+        assert_eq!(history.samples[0].population, 1000.0);
+        let year = fractional_year(1_767_225_600 + 75 * 86_400);
+        assert!((history.samples[1].year - year).abs() < 1e-9);
+    }
+}
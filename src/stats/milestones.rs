@@ -0,0 +1,232 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::astronomy::components::SystemId;
+use crate::economy::components::Population;
+use crate::economy::{GlobalBudget, SurveyMissions};
+use crate::game_state::{EventLog, LogSeverity};
+use crate::plugins::solar_system::CelestialBody;
+use crate::ui::{SimulationTime, Toasts};
+
+/// Homeworld of the civilization; every other populated place is off-world
+pub const HOMEWORLD: &str = "Earth";
+
+/// Kardashev index reached by the `Kardashev08` milestone
+pub const MILESTONE_KARDASHEV: f64 = 0.8;
+
+/// Population living away from the homeworld reached by the
+/// `BillionOffWorld` milestone
+pub const MILESTONE_OFF_WORLD_POPULATION: f64 = 1.0e9;
+
+/// A one-time achievement of the civilization
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Milestone {
+    /// A body or station other than the homeworld is populated
+    FirstOffWorldColony,
+    /// A survey probe is sent to a body outside the Solar System
+    FirstInterstellarProbe,
+    /// The Kardashev index reaches 0.8
+    Kardashev08,
+    /// A billion people live away from the homeworld, stations included
+    BillionOffWorld,
+}
+
+impl Milestone {
+    pub const ALL: [Milestone; 4] = [
+        Milestone::FirstOffWorldColony,
+        Milestone::FirstInterstellarProbe,
+        Milestone::Kardashev08,
+        Milestone::BillionOffWorld,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Milestone::FirstOffWorldColony => "Beyond the Cradle",
+            Milestone::FirstInterstellarProbe => "Interstellar Messenger",
+            Milestone::Kardashev08 => "Kardashev 0.8",
+            Milestone::BillionOffWorld => "A Billion Among the Stars",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Milestone::FirstOffWorldColony => "Found the first colony off Earth",
+            Milestone::FirstInterstellarProbe => "Launch the first probe to another star system",
+            Milestone::Kardashev08 => "Reach a Kardashev index of 0.8",
+            Milestone::BillionOffWorld => "Have one billion people living off Earth",
+        }
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Milestone::FirstOffWorldColony => "🏠",
+            Milestone::FirstInterstellarProbe => "🛰",
+            Milestone::Kardashev08 => "⚡",
+            Milestone::BillionOffWorld => "👥",
+        }
+    }
+}
+
+/// A milestone and the in-game date it was achieved
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MilestoneRecord {
+    pub milestone: Milestone,
+    /// In-game date as shown in the time controls
+    pub date: String,
+}
+
+/// Milestones achieved so far, in the order they were reached.
+/// Part of the campaign state and serializable for save games.
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Milestones {
+    pub achieved: Vec<MilestoneRecord>,
+}
+
+impl Milestones {
+    pub fn is_achieved(&self, milestone: Milestone) -> bool {
+        self.achieved.iter().any(|r| r.milestone == milestone)
+    }
+
+    /// When `milestone` was achieved, if it was
+    pub fn record(&self, milestone: Milestone) -> Option<&MilestoneRecord> {
+        self.achieved.iter().find(|r| r.milestone == milestone)
+    }
+
+    /// Record `milestone` on `date`. Returns false if it was already achieved.
+    pub fn achieve(&mut self, milestone: Milestone, date: String) -> bool {
+        if self.is_achieved(milestone) {
+            return false;
+        }
+        self.achieved.push(MilestoneRecord { milestone, date });
+        true
+    }
+}
+
+/// Whether a populated place is away from the homeworld. Bodies are named
+/// by their `CelestialBody`, stations by their `Name`.
+pub fn is_off_world(body: Option<&CelestialBody>, name: Option<&Name>) -> bool {
+    let place = body
+        .map(|b| b.name.as_str())
+        .or(name.map(|n| n.as_str()))
+        .unwrap_or_default();
+    place != HOMEWORLD
+}
+
+/// System that checks the milestones not yet achieved, recording each one
+/// reached and announcing it in a toast and the event log
+#[allow(clippy::too_many_arguments)]
+pub fn check_milestones(
+    mut milestones: ResMut<Milestones>,
+    sim_time: Res<SimulationTime>,
+    budget: Res<GlobalBudget>,
+    survey: Option<Res<SurveyMissions>>,
+    populations: Query<(&Population, Option<&CelestialBody>, Option<&Name>)>,
+    systems: Query<&SystemId>,
+    mut log: ResMut<EventLog>,
+    mut toasts: Option<ResMut<Toasts>>,
+) {
+    if milestones.achieved.len() == Milestone::ALL.len() {
+        return;
+    }
+
+    let mut off_world_colony = false;
+    let mut off_world_population = 0.0;
+    for (population, body, name) in populations.iter() {
+        if population.count > 0.0 && is_off_world(body, name) {
+            off_world_colony = true;
+            off_world_population += population.count;
+        }
+    }
+    let interstellar_probe = survey.is_some_and(|s| {
+        s.missions
+            .iter()
+            .any(|m| systems.get(m.target).is_ok_and(|id| id.0 != 0))
+    });
+
+    for milestone in Milestone::ALL {
+        let reached = match milestone {
+            Milestone::FirstOffWorldColony => off_world_colony,
+            Milestone::FirstInterstellarProbe => interstellar_probe,
+            Milestone::Kardashev08 => budget.kardashev_index() >= MILESTONE_KARDASHEV,
+            Milestone::BillionOffWorld => off_world_population >= MILESTONE_OFF_WORLD_POPULATION,
+        };
+        let date = sim_time.format_date_time();
+        if !reached || !milestones.achieve(milestone, date.clone()) {
+            continue;
+        }
+        let message = format!(
+            "🏆 Milestone reached: {} — {}",
+            milestone.name(),
+            milestone.description()
+        );
+        info!("{}", message);
+        if let Some(toasts) = toasts.as_mut() {
+            toasts.push(LogSeverity::Info, message.clone());
+        }
+        log.push(date, LogSeverity::Info, message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::economy::components::SurveyLevel;
+    use crate::economy::SurveyMission;
+    use crate::plugins::solar_system_data::BodyType;
+
+    fn body(app: &mut App, name: &str, count: f64, system: usize) -> Entity {
+        app.world_mut()
+            .spawn((
+                CelestialBody::fixture(name, BodyType::Planet),
+                Population::new(count),
+                SystemId(system),
+            ))
+            .id()
+    }
+
+    #[test]
+    fn test_milestones_fire_once() {
+        let mut app = App::new();
+        app.init_resource::<Milestones>()
+            .init_resource::<SimulationTime>()
+            .init_resource::<GlobalBudget>()
+            .init_resource::<SurveyMissions>()
+            .init_resource::<EventLog>()
+            .add_systems(Update, check_milestones);
+        body(&mut app, HOMEWORLD, 8.0e9, 0);
+        app.update();
+        assert!(app.world().resource::<Milestones>().achieved.is_empty());
+
+        body(&mut app, "Mars", 2.0e6, 0);
+        let proxima = body(&mut app, "Proxima Centauri b", 0.0, 1);
+        app.world_mut()
+            .resource_mut::<SurveyMissions>()
+            .missions
+            .push(SurveyMission {
+                target: proxima,
+                target_name: "Proxima Centauri b".to_string(),
+                origin: None,
+                level: SurveyLevel::OrbitalScan,
+                transit_days: 1000.0,
+                survey_days: 10.0,
+                elapsed_days: 0.0,
+            });
+        app.update();
+        app.update();
+
+        let milestones = app.world().resource::<Milestones>();
+        assert_eq!(milestones.achieved.len(), 2);
+        assert!(milestones.is_achieved(Milestone::FirstOffWorldColony));
+        assert!(milestones.is_achieved(Milestone::FirstInterstellarProbe));
+        assert!(!milestones.is_achieved(Milestone::BillionOffWorld));
+        assert_eq!(app.world().resource::<EventLog>().entries.len(), 2);
+
+        // Stations count towards the off-world population
+        app.world_mut()
+            .spawn((Population::new(1.0e9), Name::new("Ceres Station")));
+        app.update();
+        let milestones = app.world().resource::<Milestones>();
+        assert!(milestones.is_achieved(Milestone::BillionOffWorld));
+        assert_eq!(milestones.achieved.len(), 3);
+    }
+}
@@ -0,0 +1,38 @@
+//! Statistics and history tracking
+//!
+//! Every 30-day month the civilization's total population, power output,
+//! Kardashev index, research point generation and stockpiles are sampled
+//! into a bounded ring buffer. The samples are plotted over the simulation
+//! years in the History window and can be exported as CSV.
+//!
+//! Milestones (first off-world colony, first interstellar probe, Kardashev
+//! 0.8, a billion people off Earth) are checked continuously; each fires a
+//! notification once and is kept with its date for the Achievements window.
+
+use bevy::prelude::*;
+
+pub mod history;
+pub mod milestones;
+
+pub use history::{
+    record_stats, Statistic, StatsHistory, StatsSample, MAX_STATS_SAMPLES, STATS_CSV_PATH,
+    STATS_INTERVAL_TICKS,
+};
+pub use milestones::{
+    check_milestones, is_off_world, Milestone, MilestoneRecord, Milestones, HOMEWORLD,
+};
+
+/// Plugin that adds statistics tracking to the Bevy app
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<StatsHistory>()
+            .init_resource::<Milestones>()
+            .add_systems(
+                FixedUpdate,
+                record_stats.after(crate::economy::record_stockpile_history),
+            )
+            .add_systems(Update, check_milestones);
+    }
+}
@@ -0,0 +1,128 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+
+use super::types::{FactionDefinition, FactionId};
+
+/// Resource containing all faction definitions loaded from data
+#[derive(Resource, Debug, Clone, Default)]
+pub struct FactionsData {
+    /// All factions indexed by ID
+    pub factions: HashMap<FactionId, FactionDefinition>,
+    /// Faction IDs in data file order, for a stable listing in the UI
+    pub order: Vec<FactionId>,
+}
+
+impl FactionsData {
+    /// Get a faction definition by ID
+    pub fn get(&self, id: &str) -> Option<&FactionDefinition> {
+        self.factions.get(id)
+    }
+
+    /// Iterate faction definitions in data file order
+    pub fn iter(&self) -> impl Iterator<Item = &FactionDefinition> {
+        self.order.iter().filter_map(|id| self.factions.get(id))
+    }
+
+    /// Add a definition, keeping the file order
+    pub fn insert(&mut self, definition: FactionDefinition) {
+        if !self.factions.contains_key(&definition.id) {
+            self.order.push(definition.id.clone());
+        }
+        self.factions.insert(definition.id.clone(), definition);
+    }
+
+    /// Remove a faction that no longer exists
+    pub fn remove(&mut self, id: &str) -> Option<FactionDefinition> {
+        self.order.retain(|other| other != id);
+        self.factions.remove(id)
+    }
+
+    /// Get a faction definition by ID to change it
+    pub fn get_mut(&mut self, id: &str) -> Option<&mut FactionDefinition> {
+        self.factions.get_mut(id)
+    }
+}
+
+/// Structure of the factions data file
+#[derive(Debug, Deserialize)]
+struct FactionsFile {
+    factions: Vec<FactionDefinition>,
+}
+
+/// Check that a faction can carry goods, charges a sensible tariff and has
+/// sensible intelligence ratings
+pub fn validate_faction(definition: &FactionDefinition) -> Result<(), String> {
+    if !definition.capacity_mt_per_year.is_finite() || definition.capacity_mt_per_year <= 0.0 {
+        return Err(format!(
+            "faction '{}' has a non-positive trade capacity",
+            definition.id
+        ));
+    }
+    if !(0.0..100.0).contains(&definition.tariff) {
+        return Err(format!(
+            "faction '{}' has a tariff outside 0-100%",
+            definition.id
+        ));
+    }
+    if !(0.0..=1.0).contains(&definition.security) {
+        return Err(format!(
+            "faction '{}' has a security outside 0-1",
+            definition.id
+        ));
+    }
+    if !definition.hostility.is_finite() || definition.hostility < 0.0 {
+        return Err(format!("faction '{}' has a negative hostility", definition.id));
+    }
+    if definition.sells.is_empty() && definition.buys.is_empty() {
+        return Err(format!("faction '{}' trades nothing", definition.id));
+    }
+    Ok(())
+}
+
+/// System to load faction definitions from data file at startup
+pub fn load_factions(mut commands: Commands) {
+    info!("Loading factions...");
+
+    let path = "assets/data/factions.ron";
+    let mut factions_data = FactionsData::default();
+
+    match fs::read_to_string(path) {
+        Ok(contents) => match ron::from_str::<FactionsFile>(&contents) {
+            Ok(data) => {
+                for definition in data.factions {
+                    match validate_faction(&definition) {
+                        Ok(()) => factions_data.insert(definition),
+                        Err(e) => error!("Skipping invalid faction definition: {}", e),
+                    }
+                }
+                info!("Loaded {} factions", factions_data.order.len());
+            }
+            Err(e) => error!("Failed to parse factions data file: {}", e),
+        },
+        Err(e) => warn!(
+            "Factions data file not found at {}: {}. No trade partners will be available.",
+            path, e
+        ),
+    }
+
+    // Without the file the trade screen lists no partners
+    commands.insert_resource(factions_data);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_factions_file_parses_and_validates() {
+        let contents = fs::read_to_string("assets/data/factions.ron").unwrap();
+        let data: FactionsFile = ron::from_str(&contents).unwrap();
+
+        assert!(!data.factions.is_empty());
+        for definition in &data.factions {
+            validate_faction(definition).unwrap();
+        }
+    }
+}
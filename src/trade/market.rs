@@ -0,0 +1,119 @@
+//! Market prices of raw resources
+//!
+//! Every resource has a base price by its category. The price the factions
+//! ask and pay moves with the civilization's own supply: a stockpile that
+//! covers many months of consumption is a surplus and sells cheap, one that
+//! runs low is a deficit and buys dear.
+
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::economy::budget::{SECONDS_PER_MONTH, SECONDS_PER_YEAR};
+use crate::economy::treasury::EXPORT_PRICE_PER_MT;
+use crate::economy::{EconomyClock, GlobalBudget, ResourceRateTracker, ResourceType};
+
+/// Months of consumption a stockpile covers at the base price
+pub const TARGET_COVER_MONTHS: f64 = 12.0;
+
+/// Lowest price, relative to the base price, of a resource in surplus
+pub const MIN_PRICE_FACTOR: f64 = 0.5;
+
+/// Highest price, relative to the base price, of a resource in deficit
+pub const MAX_PRICE_FACTOR: f64 = 3.0;
+
+/// Price of a Megaton of a resource when supply meets demand
+pub fn base_price(resource: ResourceType) -> f64 {
+    let factor = if resource.is_noble_gas() {
+        50.0
+    } else if resource.is_precious_metal() {
+        40.0
+    } else if resource.is_fissile() {
+        20.0
+    } else if resource.is_specialty() {
+        10.0
+    } else if resource.is_construction() {
+        1.0
+    } else {
+        0.5
+    };
+    EXPORT_PRICE_PER_MT * factor
+}
+
+/// Price relative to the base price for a stockpile and its consumption
+/// per month. Nothing consumed means a glut.
+pub fn price_factor(stockpile: f64, consumption_per_month: f64) -> f64 {
+    if consumption_per_month <= 0.0 {
+        return MIN_PRICE_FACTOR;
+    }
+    let cover_months = stockpile.max(0.0) / consumption_per_month;
+    if cover_months <= 0.0 {
+        return MAX_PRICE_FACTOR;
+    }
+    (TARGET_COVER_MONTHS / cover_months)
+        .sqrt()
+        .clamp(MIN_PRICE_FACTOR, MAX_PRICE_FACTOR)
+}
+
+/// Megatons per year the civilization produces beyond its consumption;
+/// negative for a deficit
+pub fn surplus_mt_per_year(rates: &ResourceRateTracker, resource: ResourceType) -> f64 {
+    rates.get_net_rate(&resource) * SECONDS_PER_YEAR / SECONDS_PER_MONTH
+}
+
+/// Current prices per Megaton
+#[derive(Resource, Debug, Clone, Default)]
+pub struct Market {
+    pub prices: HashMap<ResourceType, f64>,
+}
+
+impl Market {
+    /// Price of a Megaton of a resource; the base price until the first
+    /// economy tick
+    pub fn price(&self, resource: ResourceType) -> f64 {
+        self.prices
+            .get(&resource)
+            .copied()
+            .unwrap_or_else(|| base_price(resource))
+    }
+}
+
+/// System that reprices every resource on due economy ticks
+pub fn update_market(
+    clock: Res<EconomyClock>,
+    mut market: ResMut<Market>,
+    budget: Res<GlobalBudget>,
+    rates: Res<ResourceRateTracker>,
+) {
+    if clock.due == 0 {
+        return;
+    }
+    for &resource in ResourceType::all() {
+        let factor = price_factor(
+            budget.get_stockpile(&resource),
+            rates.get_consumption_rate(&resource),
+        );
+        market
+            .prices
+            .insert(resource, base_price(resource) * factor);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_price_follows_supply() {
+        // A year of cover is the base price
+        assert!((price_factor(120.0, 10.0) - 1.0).abs() < 1e-9);
+        // Scarce stockpiles buy dear, up to the cap
+        assert!(price_factor(30.0, 10.0) > 1.0);
+        assert_eq!(price_factor(0.0, 10.0), MAX_PRICE_FACTOR);
+        // Gluts sell cheap
+        assert!(price_factor(480.0, 10.0) < 1.0);
+        assert_eq!(price_factor(50.0, 0.0), MIN_PRICE_FACTOR);
+
+        assert!(base_price(ResourceType::Helium3) > base_price(ResourceType::Iron));
+        assert!(base_price(ResourceType::Iron) > base_price(ResourceType::Water));
+    }
+}
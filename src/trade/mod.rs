@@ -0,0 +1,46 @@
+//! Trade with other factions
+//!
+//! Provides data-driven trade:
+//! - Factions of the solar system with the resources they buy and sell,
+//!   their tariffs and freighter capacity, loaded from
+//!   `assets/data/factions.ron`
+//! - A market pricing every resource by the civilization's own surpluses
+//!   and deficits
+//! - Export and import agreements carried on economy ticks, with the money
+//!   booked by the treasury
+
+use bevy::prelude::*;
+
+pub mod data;
+pub mod market;
+pub mod systems;
+pub mod types;
+
+pub use data::{load_factions, FactionsData};
+pub use market::{surplus_mt_per_year, update_market, Market};
+pub use systems::{
+    carry_trade, process_trade_actions, PendingTradeActions, TradeAgreement, TradeAgreements,
+};
+pub use types::{FactionDefinition, FactionId, TradeDirection};
+
+/// Plugin that adds factions and trade agreements to the Bevy app
+pub struct TradePlugin;
+
+impl Plugin for TradePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Market>()
+            .init_resource::<TradeAgreements>()
+            .init_resource::<PendingTradeActions>()
+            .add_systems(Startup, load_factions)
+            .add_systems(
+                FixedUpdate,
+                (
+                    update_market.after(crate::economy::advance_economy_clock),
+                    carry_trade
+                        .after(update_market)
+                        .before(crate::economy::update_treasury),
+                ),
+            )
+            .add_systems(Update, process_trade_actions);
+    }
+}
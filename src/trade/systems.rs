@@ -0,0 +1,379 @@
+use bevy::prelude::*;
+
+use super::data::FactionsData;
+use super::market::Market;
+use super::types::{FactionDefinition, FactionId, TradeDirection};
+use crate::economy::{EconomyClock, GlobalBudget, ResourceType, Treasury};
+use crate::fleets::Blockades;
+use crate::game_state::{EventLog, LogSeverity};
+use crate::intel::Intel;
+
+/// A standing agreement to ship a resource to or from a faction
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeAgreement {
+    pub faction: FactionId,
+    pub resource: ResourceType,
+    pub direction: TradeDirection,
+    /// Megatons agreed per year
+    pub mt_per_year: f64,
+    /// Megatons per year actually carried at the last economy tick, short of
+    /// the agreed volume when the faction's freighters are full, the
+    /// stockpile is empty or the treasury cannot pay
+    pub delivered_mt_per_year: f64,
+}
+
+impl TradeAgreement {
+    pub fn new(
+        faction: FactionId,
+        resource: ResourceType,
+        direction: TradeDirection,
+        mt_per_year: f64,
+    ) -> Self {
+        Self {
+            faction,
+            resource,
+            direction,
+            mt_per_year,
+            delivered_mt_per_year: 0.0,
+        }
+    }
+
+    fn is(&self, faction: &str, resource: ResourceType, direction: TradeDirection) -> bool {
+        self.faction == faction && self.resource == resource && self.direction == direction
+    }
+}
+
+/// Agreements in force and the money they moved at the last economy tick
+#[derive(Resource, Debug, Clone, Default)]
+pub struct TradeAgreements {
+    pub agreements: Vec<TradeAgreement>,
+    /// Export earnings per year at market prices
+    pub sales_per_year: f64,
+    /// Cost of imports per year at market prices
+    pub purchases_per_year: f64,
+    /// Tariffs paid per year on exports and imports
+    pub tariffs_per_year: f64,
+}
+
+impl TradeAgreements {
+    /// The agreement for a resource and direction with a faction
+    pub fn find(
+        &self,
+        faction: &str,
+        resource: ResourceType,
+        direction: TradeDirection,
+    ) -> Option<&TradeAgreement> {
+        self.agreements
+            .iter()
+            .find(|a| a.is(faction, resource, direction))
+    }
+
+    /// Megatons per year agreed with a faction, in both directions
+    pub fn committed(&self, faction: &str) -> f64 {
+        self.agreements
+            .iter()
+            .filter(|a| a.faction == faction)
+            .map(|a| a.mt_per_year)
+            .sum()
+    }
+
+    /// Check whether an agreement can be signed with a faction
+    pub fn check_sign(
+        &self,
+        faction: &FactionDefinition,
+        resource: ResourceType,
+        direction: TradeDirection,
+        mt_per_year: f64,
+    ) -> Result<(), String> {
+        if !faction.trades(resource, direction) {
+            let verb = match direction {
+                TradeDirection::Export => "buy",
+                TradeDirection::Import => "sell",
+            };
+            return Err(format!(
+                "{} does not {} {}",
+                faction.name,
+                verb,
+                resource.display_name()
+            ));
+        }
+        if !mt_per_year.is_finite() || mt_per_year <= 0.0 {
+            return Err("The agreed volume must be positive".to_string());
+        }
+        if self.find(&faction.id, resource, direction).is_some() {
+            return Err(format!(
+                "An agreement for {} with {} is already in force",
+                resource.display_name(),
+                faction.name
+            ));
+        }
+        Ok(())
+    }
+
+    /// Remove an agreement; returns whether it was in force
+    pub fn cancel(
+        &mut self,
+        faction: &str,
+        resource: ResourceType,
+        direction: TradeDirection,
+    ) -> bool {
+        let before = self.agreements.len();
+        self.agreements
+            .retain(|a| !a.is(faction, resource, direction));
+        self.agreements.len() != before
+    }
+}
+
+/// Agreements requested from the UI, applied by `process_trade_actions`
+#[derive(Resource, Debug, Clone, Default)]
+pub struct PendingTradeActions {
+    pub sign: Vec<TradeAgreement>,
+    pub cancel: Vec<(FactionId, ResourceType, TradeDirection)>,
+}
+
+/// System that signs and cancels the requested agreements and logs them
+pub fn process_trade_actions(
+    mut pending: ResMut<PendingTradeActions>,
+    mut agreements: ResMut<TradeAgreements>,
+    data: Option<Res<FactionsData>>,
+    mut log: Option<ResMut<EventLog>>,
+    sim_time: Option<Res<crate::ui::SimulationTime>>,
+) {
+    if pending.sign.is_empty() && pending.cancel.is_empty() {
+        return;
+    }
+    let Some(data) = data else {
+        pending.sign.clear();
+        pending.cancel.clear();
+        return;
+    };
+
+    let mut messages = Vec::new();
+    for (faction, resource, direction) in std::mem::take(&mut pending.cancel) {
+        let Some(definition) = data.get(&faction) else {
+            continue;
+        };
+        if agreements.cancel(&faction, resource, direction) {
+            messages.push(format!(
+                "Cancelled the {} agreement for {} with {}",
+                direction.label().to_lowercase(),
+                resource.display_name(),
+                definition.name
+            ));
+        }
+    }
+    for agreement in std::mem::take(&mut pending.sign) {
+        let Some(definition) = data.get(&agreement.faction) else {
+            continue;
+        };
+        match agreements.check_sign(
+            definition,
+            agreement.resource,
+            agreement.direction,
+            agreement.mt_per_year,
+        ) {
+            Ok(()) => {
+                messages.push(format!(
+                    "Signed an agreement to {} {:.1} Mt {} per year with {}",
+                    agreement.direction.label().to_lowercase(),
+                    agreement.mt_per_year,
+                    agreement.resource.display_name(),
+                    definition.name
+                ));
+                agreements.agreements.push(agreement);
+            }
+            Err(e) => warn!("Cannot sign trade agreement: {}", e),
+        }
+    }
+
+    for message in messages {
+        info!("{}", message);
+        if let (Some(log), Some(sim_time)) = (log.as_mut(), sim_time.as_ref()) {
+            log.push(sim_time.format_date_time(), LogSeverity::Info, message);
+        }
+    }
+}
+
+/// System that carries the agreed goods on due economy ticks.
+///
+/// A faction's freighters carry at most its capacity per year, less while
+/// its shipyards are sabotaged; when the agreements with it ask for more,
+/// every agreement is scaled down alike.
+/// Exports leave the stockpile only as far as it holds them outside
+/// blockaded depots, imports stop while the treasury is beyond its credit
+/// limit, and no goods move with a faction whose fleets blockade a colony.
+/// The money moved is left in [`TradeAgreements`] for `update_treasury` to
+/// book.
+#[allow(clippy::too_many_arguments)]
+pub fn carry_trade(
+    clock: Res<EconomyClock>,
+    mut trade: ResMut<TradeAgreements>,
+    mut budget: ResMut<GlobalBudget>,
+    market: Res<Market>,
+    data: Option<Res<FactionsData>>,
+    treasury: Option<Res<Treasury>>,
+    intel: Option<Res<Intel>>,
+    blockades: Option<Res<Blockades>>,
+) {
+    if clock.due == 0 {
+        return;
+    }
+    let Some(data) = data else {
+        return;
+    };
+    let years = EconomyClock::TICK_YEARS * clock.due as f64;
+    let credit = treasury.is_none_or(|t| !t.over_credit_limit());
+
+    let trade = &mut *trade;
+    trade.sales_per_year = 0.0;
+    trade.purchases_per_year = 0.0;
+    trade.tariffs_per_year = 0.0;
+    for faction in data.iter() {
+        let committed = trade.committed(&faction.id);
+        if committed <= 0.0 {
+            continue;
+        }
+        let capacity = faction.capacity_mt_per_year
+            * intel
+                .as_ref()
+                .map_or(1.0, |i| i.capacity_factor(&faction.id));
+        let blockading = blockades
+            .as_ref()
+            .is_some_and(|b| b.by_faction(&faction.id));
+        let share = if blockading {
+            0.0
+        } else {
+            (capacity / committed).min(1.0)
+        };
+        for agreement in trade
+            .agreements
+            .iter_mut()
+            .filter(|a| a.faction == faction.id)
+        {
+            let mut amount = agreement.mt_per_year * share * years;
+            match agreement.direction {
+                TradeDirection::Export => {
+                    amount = amount.min(budget.available_stockpile(&agreement.resource));
+                    if amount > 0.0 {
+                        budget.consume_resource(agreement.resource, amount);
+                    }
+                }
+                TradeDirection::Import => {
+                    if !credit {
+                        amount = 0.0;
+                    }
+                    if amount > 0.0 {
+                        budget.add_resource(agreement.resource, amount);
+                    }
+                }
+            }
+            agreement.delivered_mt_per_year = amount / years;
+
+            let value = agreement.delivered_mt_per_year * market.price(agreement.resource);
+            match agreement.direction {
+                TradeDirection::Export => trade.sales_per_year += value,
+                TradeDirection::Import => trade.purchases_per_year += value,
+            }
+            trade.tariffs_per_year += value * faction.tariff_fraction();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::economy::tick::{advance_economy_clock, ECONOMY_TICK_SECONDS};
+    use crate::ui::SimulationTime;
+
+    fn faction() -> FactionDefinition {
+        FactionDefinition {
+            id: "guild".to_string(),
+            name: "Guild".to_string(),
+            description: String::new(),
+            tariff: 10.0,
+            capacity_mt_per_year: 10.0,
+            sells: vec![ResourceType::Water],
+            buys: vec![ResourceType::Iron],
+            security: 0.0,
+            hostility: 0.0,
+            doctrine: None,
+        }
+    }
+
+    #[test]
+    fn test_check_sign() {
+        let mut trade = TradeAgreements::default();
+        let guild = faction();
+        assert!(trade
+            .check_sign(&guild, ResourceType::Iron, TradeDirection::Export, 5.0)
+            .is_ok());
+        // The guild does not sell iron, and volumes must be positive
+        assert!(trade
+            .check_sign(&guild, ResourceType::Iron, TradeDirection::Import, 5.0)
+            .is_err());
+        assert!(trade
+            .check_sign(&guild, ResourceType::Water, TradeDirection::Import, 0.0)
+            .is_err());
+
+        trade.agreements.push(TradeAgreement::new(
+            guild.id.clone(),
+            ResourceType::Iron,
+            TradeDirection::Export,
+            5.0,
+        ));
+        assert!(trade
+            .check_sign(&guild, ResourceType::Iron, TradeDirection::Export, 1.0)
+            .is_err());
+        assert!(trade.cancel("guild", ResourceType::Iron, TradeDirection::Export));
+        assert!(trade.agreements.is_empty());
+    }
+
+    #[test]
+    fn test_capacity_is_shared_between_agreements() {
+        let mut app = App::new();
+        let mut data = FactionsData::default();
+        data.insert(faction());
+        let mut budget = GlobalBudget::new();
+        budget.stockpiles.insert(ResourceType::Iron, 1000.0);
+        budget.stockpiles.insert(ResourceType::Water, 0.0);
+        let mut trade = TradeAgreements::default();
+        for (resource, direction) in [
+            (ResourceType::Iron, TradeDirection::Export),
+            (ResourceType::Water, TradeDirection::Import),
+        ] {
+            trade.agreements.push(TradeAgreement::new(
+                "guild".to_string(),
+                resource,
+                direction,
+                10.0,
+            ));
+        }
+        app.init_resource::<SimulationTime>()
+            .init_resource::<EconomyClock>()
+            .init_resource::<Market>()
+            .insert_resource(data)
+            .insert_resource(budget)
+            .insert_resource(trade)
+            .add_systems(Update, (advance_economy_clock, carry_trade).chain());
+
+        app.world_mut().resource_mut::<SimulationTime>().elapsed = ECONOMY_TICK_SECONDS;
+        app.update();
+
+        // 20 Mt/yr agreed against 10 Mt/yr of freighters: half arrives
+        let trade = app.world().resource::<TradeAgreements>();
+        for agreement in &trade.agreements {
+            assert!((agreement.delivered_mt_per_year - 5.0).abs() < 1e-9);
+        }
+        let day = 5.0 * EconomyClock::TICK_YEARS;
+        let budget = app.world().resource::<GlobalBudget>();
+        assert!((budget.get_stockpile(&ResourceType::Water) - day).abs() < 1e-9);
+        assert!((budget.get_stockpile(&ResourceType::Iron) - (1000.0 - day)).abs() < 1e-9);
+
+        let market = Market::default();
+        let sales = 5.0 * market.price(ResourceType::Iron);
+        let purchases = 5.0 * market.price(ResourceType::Water);
+        assert!((trade.sales_per_year - sales).abs() < 1e-3);
+        assert!((trade.purchases_per_year - purchases).abs() < 1e-3);
+        assert!((trade.tariffs_per_year - 0.1 * (sales + purchases)).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+
+use crate::ai::DoctrineId;
+use crate::economy::ResourceType;
+
+/// Unique identifier for a faction
+pub type FactionId = String;
+
+/// Another power of the solar system the civilization can trade with,
+/// loaded from data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FactionDefinition {
+    /// Unique identifier
+    pub id: FactionId,
+    /// Display name
+    pub name: String,
+    /// Flavour text shown in the Trade window
+    pub description: String,
+    /// Tariff levied on goods crossing its border, in percent of the market
+    /// price; paid on top of imports and taken from export earnings
+    #[serde(default)]
+    pub tariff: f64,
+    /// Megatons its freighters carry per year, shared by all agreements
+    pub capacity_mt_per_year: f64,
+    /// Resources it sells to the civilization
+    #[serde(default)]
+    pub sells: Vec<ResourceType>,
+    /// Resources it buys from the civilization
+    #[serde(default)]
+    pub buys: Vec<ResourceType>,
+    /// Strength of its counter-intelligence (0-1), raising the risk of
+    /// covert operations against it
+    #[serde(default)]
+    pub security: f64,
+    /// Sabotage attempts against the civilization's construction per year
+    #[serde(default)]
+    pub hostility: f64,
+    /// Doctrine it follows; drawn from the game seed when not given
+    #[serde(default)]
+    pub doctrine: Option<DoctrineId>,
+}
+
+impl FactionDefinition {
+    /// Whether it trades a resource in a direction
+    pub fn trades(&self, resource: ResourceType, direction: TradeDirection) -> bool {
+        match direction {
+            TradeDirection::Export => self.buys.contains(&resource),
+            TradeDirection::Import => self.sells.contains(&resource),
+        }
+    }
+
+    /// Tariff as a fraction of the market price
+    pub fn tariff_fraction(&self) -> f64 {
+        self.tariff / 100.0
+    }
+}
+
+/// Which way goods flow under an agreement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TradeDirection {
+    /// From the stockpile to the faction
+    Export,
+    /// From the faction into the stockpile
+    Import,
+}
+
+impl TradeDirection {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TradeDirection::Export => "Export",
+            TradeDirection::Import => "Import",
+        }
+    }
+}
@@ -0,0 +1,27 @@
+//! Tutorial and guided objectives
+//!
+//! A scripted chain of goals introduces the game's main loops: survey the
+//! Moon, build a mine, research a technology and found a colony. Completion
+//! is tracked from the ECS state, the menu each objective needs is
+//! highlighted in the top menu bar, and the chain can be turned off on the
+//! new-game screen or skipped at any time for sandbox play.
+
+use bevy::prelude::*;
+
+pub mod objectives;
+
+pub use objectives::{
+    track_objectives, Objective, Tutorial, TutorialProgress, TUTORIAL_SURVEY_TARGET,
+};
+
+/// Plugin that adds the tutorial objectives to the Bevy app
+pub struct TutorialPlugin;
+
+impl Plugin for TutorialPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Tutorial>().add_systems(
+            Update,
+            track_objectives.after(crate::scenarios::start_scenario),
+        );
+    }
+}
@@ -0,0 +1,263 @@
+use bevy::prelude::*;
+
+use crate::construction::{Building, BuildingsData};
+use crate::economy::components::{Population, SurveyLevel};
+use crate::game_state::{GameMenu, LogSeverity};
+use crate::plugins::solar_system::CelestialBody;
+use crate::research::ResearchState;
+use crate::scenarios::ScenarioSelection;
+use crate::stats::is_off_world;
+use crate::ui::{NewGameScreen, Toasts};
+
+/// Body the first objective asks to survey
+pub const TUTORIAL_SURVEY_TARGET: &str = "Moon";
+
+/// A guided goal of the tutorial
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Objective {
+    /// Complete a survey of the Moon
+    SurveyMoon,
+    /// Construct a new mine
+    BuildMine,
+    /// Research a new technology
+    ResearchTechnology,
+    /// Populate a new body or station away from Earth
+    FoundColony,
+}
+
+impl Objective {
+    /// The tutorial's goals, in the order they are given
+    pub const CHAIN: [Objective; 4] = [
+        Objective::SurveyMoon,
+        Objective::BuildMine,
+        Objective::ResearchTechnology,
+        Objective::FoundColony,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Objective::SurveyMoon => "Survey the Moon",
+            Objective::BuildMine => "Build a mine",
+            Objective::ResearchTechnology => "Research a technology",
+            Objective::FoundColony => "Found a colony",
+        }
+    }
+
+    /// How to complete the objective
+    pub fn hint(&self) -> &'static str {
+        match self {
+            Objective::SurveyMoon => {
+                "Open the Survey menu, select the Moon and launch a survey probe to map its deposits."
+            }
+            Objective::BuildMine => {
+                "Open the Construction menu and queue a mine on a body with known deposits."
+            }
+            Objective::ResearchTechnology => {
+                "Open the Research menu and assign research points to a technology."
+            }
+            Objective::FoundColony => {
+                "Open the Construction menu and found a station with habitat modules."
+            }
+        }
+    }
+
+    /// Menu holding the interface the objective needs, highlighted while
+    /// the objective is current
+    pub fn menu(&self) -> GameMenu {
+        match self {
+            Objective::SurveyMoon => GameMenu::Survey,
+            Objective::BuildMine | Objective::FoundColony => GameMenu::Construction,
+            Objective::ResearchTechnology => GameMenu::Research,
+        }
+    }
+
+    /// Whether only progress made after the objective became current counts
+    /// (a scenario may start with mines, technologies and colonies already)
+    fn needs_new_progress(&self) -> bool {
+        !matches!(self, Objective::SurveyMoon)
+    }
+
+    /// The tracked quantity of the objective in `progress`
+    fn measure(&self, progress: &TutorialProgress) -> usize {
+        match self {
+            Objective::SurveyMoon => progress.moon_surveyed as usize,
+            Objective::BuildMine => progress.mines,
+            Objective::ResearchTechnology => progress.technologies,
+            Objective::FoundColony => progress.colonies,
+        }
+    }
+}
+
+/// Quantities the objectives are tracked by, read from the ECS state
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TutorialProgress {
+    pub moon_surveyed: bool,
+    /// Buildings that mine a body's deposits
+    pub mines: usize,
+    /// Technologies unlocked
+    pub technologies: usize,
+    /// Populated bodies and stations away from Earth
+    pub colonies: usize,
+}
+
+/// State of the guided objectives
+#[derive(Resource, Debug, Clone)]
+pub struct Tutorial {
+    /// Disabled for sandbox play
+    pub enabled: bool,
+    /// Index of the current objective in `Objective::CHAIN`; the tutorial
+    /// is finished once it reaches the end
+    pub step: usize,
+    /// Tracked quantity when the current objective became current
+    pub baseline: Option<usize>,
+}
+
+impl Default for Tutorial {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            step: 0,
+            baseline: None,
+        }
+    }
+}
+
+impl Tutorial {
+    /// The objective to complete next, if the tutorial is not finished
+    pub fn current(&self) -> Option<Objective> {
+        Objective::CHAIN.get(self.step).copied()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.step >= Objective::CHAIN.len()
+    }
+
+    /// The objective whose menu should be highlighted, if any
+    pub fn highlighted_menu(&self) -> Option<GameMenu> {
+        if !self.enabled {
+            return None;
+        }
+        self.current().map(|o| o.menu())
+    }
+
+    /// Advance past every objective `progress` completes, returning those
+    /// completed
+    pub fn advance(&mut self, progress: &TutorialProgress) -> Vec<Objective> {
+        let mut completed = Vec::new();
+        while let Some(objective) = self.current() {
+            let value = objective.measure(progress);
+            let baseline = if objective.needs_new_progress() {
+                *self.baseline.get_or_insert(value)
+            } else {
+                0
+            };
+            if value <= baseline {
+                break;
+            }
+            completed.push(objective);
+            self.step += 1;
+            self.baseline = None;
+        }
+        completed
+    }
+}
+
+/// System that tracks the current objective from the ECS state once the
+/// campaign has started, moving on and announcing each one completed
+#[allow(clippy::too_many_arguments)]
+pub fn track_objectives(
+    mut tutorial: ResMut<Tutorial>,
+    selection: Option<Res<ScenarioSelection>>,
+    new_game: Option<Res<NewGameScreen>>,
+    research_state: Option<Res<ResearchState>>,
+    buildings_data: Option<Res<BuildingsData>>,
+    bodies: Query<(&CelestialBody, Option<&SurveyLevel>)>,
+    buildings: Query<&Building>,
+    populations: Query<(&Population, Option<&CelestialBody>, Option<&Name>)>,
+    mut toasts: Option<ResMut<Toasts>>,
+) {
+    if !tutorial.enabled || tutorial.is_finished() {
+        return;
+    }
+    if new_game.is_some_and(|s| s.open) || selection.is_some_and(|s| s.pending.is_some()) {
+        return;
+    }
+
+    let progress = TutorialProgress {
+        moon_surveyed: bodies.iter().any(|(body, level)| {
+            body.name == TUTORIAL_SURVEY_TARGET
+                && level.is_some_and(|l| *l != SurveyLevel::Unsurveyed)
+        }),
+        mines: buildings_data.map_or(0, |data| {
+            buildings
+                .iter()
+                .filter(|b| {
+                    data.get(&b.id)
+                        .is_some_and(|d| !d.cumulative_effects(b.level).mining.is_empty())
+                })
+                .count()
+        }),
+        technologies: research_state.map_or(0, |r| r.unlocked_technologies.len()),
+        colonies: populations
+            .iter()
+            .filter(|(p, body, name)| p.count > 0.0 && is_off_world(*body, *name))
+            .count(),
+    };
+
+    for objective in tutorial.advance(&progress) {
+        info!("Tutorial objective complete: {}", objective.title());
+        if let Some(toasts) = toasts.as_mut() {
+            toasts.push(
+                LogSeverity::Info,
+                format!("🎯 Objective complete: {}", objective.title()),
+            );
+        }
+    }
+    if tutorial.is_finished() {
+        if let Some(toasts) = toasts.as_mut() {
+            toasts.push(LogSeverity::Info, "🎯 Tutorial complete — the system is yours");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_objectives_complete_in_order() {
+        let mut tutorial = Tutorial::default();
+        // A scenario starting with mines, technologies and a colony
+        let mut progress = TutorialProgress {
+            moon_surveyed: false,
+            mines: 3,
+            technologies: 5,
+            colonies: 1,
+        };
+        assert!(tutorial.advance(&progress).is_empty());
+        assert_eq!(tutorial.highlighted_menu(), Some(GameMenu::Survey));
+
+        // Existing mines do not count towards the next objective
+        progress.moon_surveyed = true;
+        assert_eq!(tutorial.advance(&progress), vec![Objective::SurveyMoon]);
+        assert_eq!(tutorial.current(), Some(Objective::BuildMine));
+        assert_eq!(tutorial.baseline, Some(3));
+
+        progress.mines = 4;
+        progress.technologies = 6;
+        assert_eq!(tutorial.advance(&progress), vec![Objective::BuildMine]);
+        // The technology was researched before the objective became current
+        assert_eq!(tutorial.current(), Some(Objective::ResearchTechnology));
+
+        progress.technologies = 7;
+        progress.colonies = 2;
+        assert_eq!(
+            tutorial.advance(&progress),
+            vec![Objective::ResearchTechnology]
+        );
+        progress.colonies = 3;
+        assert_eq!(tutorial.advance(&progress), vec![Objective::FoundColony]);
+        assert!(tutorial.is_finished());
+        assert_eq!(tutorial.highlighted_menu(), None);
+    }
+}
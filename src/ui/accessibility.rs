@@ -0,0 +1,397 @@
+//! Accessibility options: colorblind palettes, shape markers for status
+//! indicators and a high-contrast theme
+//!
+//! Status colors (power, stockpile alerts, technology states) and resource
+//! category colors are read from a `Palette` kept in egui's context memory,
+//! so any UI code holding a `ui` or `ctx` can pick them up without another
+//! system parameter. The palettes for color vision deficiencies use the
+//! Okabe–Ito colors, which stay distinct under all three types.
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use serde::{Deserialize, Serialize};
+
+use super::settings::UiSettings;
+
+/// Color scheme for status and category colors
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ColorPalette {
+    #[default]
+    Standard,
+    /// Red-green, reduced sensitivity to green
+    Deuteranopia,
+    /// Red-green, reduced sensitivity to red
+    Protanopia,
+    /// Blue-yellow
+    Tritanopia,
+}
+
+impl ColorPalette {
+    pub const ALL: [ColorPalette; 4] = [
+        ColorPalette::Standard,
+        ColorPalette::Deuteranopia,
+        ColorPalette::Protanopia,
+        ColorPalette::Tritanopia,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ColorPalette::Standard => "Standard",
+            ColorPalette::Deuteranopia => "Deuteranopia",
+            ColorPalette::Protanopia => "Protanopia",
+            ColorPalette::Tritanopia => "Tritanopia",
+        }
+    }
+}
+
+/// Player-configurable accessibility options, stored with the UI settings
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccessibilitySettings {
+    pub palette: ColorPalette,
+    /// Prefix status indicators with a shape so they do not rely on color
+    pub status_shapes: bool,
+    /// Black panels, white text and strong widget outlines
+    pub high_contrast: bool,
+}
+
+/// Outcome shown by a status indicator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Good,
+    Warning,
+    Bad,
+}
+
+/// Research state of a technology
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TechStatus {
+    Unlocked,
+    Researching,
+    Available,
+    Locked,
+}
+
+impl TechStatus {
+    pub const ALL: [TechStatus; 4] = [
+        TechStatus::Unlocked,
+        TechStatus::Researching,
+        TechStatus::Available,
+        TechStatus::Locked,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TechStatus::Unlocked => "Unlocked",
+            TechStatus::Researching => "Researching",
+            TechStatus::Available => "Available",
+            TechStatus::Locked => "Locked",
+        }
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// Resource categories in the order of `Palette::categories`
+const CATEGORIES: [&str; 7] = [
+    "Volatiles",
+    "Atmospheric Gases",
+    "Construction",
+    "Fusion Fuel",
+    "Fissiles",
+    "Precious Metals",
+    "Specialty",
+];
+
+const fn rgb(r: u8, g: u8, b: u8) -> egui::Color32 {
+    egui::Color32::from_rgb(r, g, b)
+}
+
+/// Okabe–Ito category colors, brightened for the dark theme where needed
+const OKABE_ITO_CATEGORIES: [egui::Color32; 7] = [
+    rgb(86, 180, 233),  // Sky blue
+    rgb(230, 230, 230), // White
+    rgb(213, 94, 0),    // Vermillion
+    rgb(204, 121, 167), // Reddish purple
+    rgb(0, 158, 115),   // Bluish green
+    rgb(240, 228, 66),  // Yellow
+    rgb(40, 130, 210),  // Blue
+];
+
+/// Colors and markers the interface draws status and categories with
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette {
+    good: egui::Color32,
+    warning: egui::Color32,
+    bad: egui::Color32,
+    /// Indexed by `TechStatus`
+    tech: [egui::Color32; 4],
+    /// Node fills of the tech tree, indexed by `TechStatus`
+    tech_fill: [egui::Color32; 4],
+    /// Node fills on the highlighted prerequisite path
+    tech_path_fill: [egui::Color32; 4],
+    /// Indexed like `CATEGORIES`
+    categories: [egui::Color32; 7],
+    shapes: bool,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::new(&AccessibilitySettings::default())
+    }
+}
+
+impl Palette {
+    pub fn new(settings: &AccessibilitySettings) -> Self {
+        let mut palette = match settings.palette {
+            ColorPalette::Standard => Self {
+                good: egui::Color32::GREEN,
+                warning: rgb(255, 200, 80),
+                bad: egui::Color32::RED,
+                tech: [
+                    rgb(50, 200, 50),
+                    rgb(80, 160, 255),
+                    rgb(255, 200, 50),
+                    rgb(100, 100, 100),
+                ],
+                tech_fill: [
+                    rgb(25, 70, 25),
+                    rgb(15, 50, 95),
+                    rgb(70, 60, 15),
+                    rgb(45, 45, 50),
+                ],
+                tech_path_fill: [
+                    rgb(30, 90, 30),
+                    rgb(20, 60, 110),
+                    rgb(90, 75, 15),
+                    rgb(60, 60, 60),
+                ],
+                categories: [
+                    rgb(100, 200, 255), // Water blue
+                    rgb(200, 230, 255), // Air white/blue
+                    rgb(205, 127, 50),  // Bronze/rust
+                    rgb(255, 100, 200), // Plasma pink
+                    rgb(100, 255, 100), // Radioactive green
+                    rgb(255, 215, 0),   // Gold
+                    rgb(200, 100, 255), // Exotic purple
+                ],
+                shapes: false,
+            },
+            ColorPalette::Deuteranopia | ColorPalette::Protanopia => {
+                // Protanopes see reds darker, so their "bad" is brighter
+                let bad = if settings.palette == ColorPalette::Protanopia {
+                    rgb(255, 130, 40)
+                } else {
+                    rgb(235, 110, 20)
+                };
+                let tech = [
+                    rgb(86, 180, 233),
+                    rgb(204, 121, 167),
+                    rgb(240, 228, 66),
+                    rgb(110, 110, 110),
+                ];
+                Self {
+                    good: rgb(86, 180, 233),
+                    warning: rgb(240, 228, 66),
+                    bad,
+                    tech,
+                    tech_fill: tech.map(|c| scale_color(c, 0.3)),
+                    tech_path_fill: tech.map(|c| scale_color(c, 0.4)),
+                    categories: OKABE_ITO_CATEGORIES,
+                    shapes: false,
+                }
+            }
+            ColorPalette::Tritanopia => {
+                let tech = [
+                    rgb(80, 210, 210),
+                    rgb(235, 235, 235),
+                    rgb(255, 150, 190),
+                    rgb(110, 110, 110),
+                ];
+                Self {
+                    good: rgb(80, 210, 210),
+                    warning: rgb(255, 150, 190),
+                    bad: rgb(230, 40, 40),
+                    tech,
+                    tech_fill: tech.map(|c| scale_color(c, 0.3)),
+                    tech_path_fill: tech.map(|c| scale_color(c, 0.4)),
+                    categories: OKABE_ITO_CATEGORIES,
+                    shapes: false,
+                }
+            }
+        };
+        palette.shapes = settings.status_shapes;
+        palette
+    }
+
+    pub fn status(&self, status: Status) -> egui::Color32 {
+        match status {
+            Status::Good => self.good,
+            Status::Warning => self.warning,
+            Status::Bad => self.bad,
+        }
+    }
+
+    /// `text` prefixed with the status shape when shapes are enabled
+    pub fn status_text(&self, status: Status, text: impl Into<String>) -> String {
+        let text = text.into();
+        if !self.shapes {
+            return text;
+        }
+        let shape = match status {
+            Status::Good => "✔",
+            Status::Warning => "⚠",
+            Status::Bad => "✖",
+        };
+        format!("{} {}", shape, text)
+    }
+
+    pub fn tech(&self, status: TechStatus) -> egui::Color32 {
+        self.tech[status.index()]
+    }
+
+    /// Fill of a tech tree node; nodes on the highlighted path are brighter
+    pub fn tech_fill(&self, status: TechStatus, highlighted: bool) -> egui::Color32 {
+        if highlighted {
+            self.tech_path_fill[status.index()]
+        } else {
+            self.tech_fill[status.index()]
+        }
+    }
+
+    /// Marker of a technology state in legends and node labels
+    pub fn tech_marker(&self, status: TechStatus) -> &'static str {
+        if !self.shapes {
+            return "●";
+        }
+        match status {
+            TechStatus::Unlocked => "✔",
+            TechStatus::Researching => "⏳",
+            TechStatus::Available => "○",
+            TechStatus::Locked => "🔒",
+        }
+    }
+
+    pub fn shapes(&self) -> bool {
+        self.shapes
+    }
+
+    /// Color of a resource category
+    pub fn category(&self, category: &str) -> egui::Color32 {
+        CATEGORIES
+            .iter()
+            .position(|c| *c == category)
+            .map_or(egui::Color32::LIGHT_GRAY, |i| self.categories[i])
+    }
+}
+
+/// `color` with every channel multiplied by `factor`
+fn scale_color(color: egui::Color32, factor: f32) -> egui::Color32 {
+    let scale = |c: u8| (c as f32 * factor).round().min(255.0) as u8;
+    rgb(scale(color.r()), scale(color.g()), scale(color.b()))
+}
+
+/// Key of the palette in egui's context memory
+fn palette_id() -> egui::Id {
+    egui::Id::new("accessibility_palette")
+}
+
+/// The palette in use, or the standard one before settings are applied
+pub fn palette(ctx: &egui::Context) -> Palette {
+    ctx.data(|d| d.get_temp::<Palette>(palette_id()))
+        .unwrap_or_default()
+}
+
+/// Dark theme with black panels, white text and thick widget outlines
+pub fn high_contrast_visuals() -> egui::Visuals {
+    let mut visuals = egui::Visuals::dark();
+    visuals.override_text_color = Some(egui::Color32::WHITE);
+    visuals.panel_fill = egui::Color32::BLACK;
+    visuals.window_fill = egui::Color32::BLACK;
+    visuals.extreme_bg_color = egui::Color32::BLACK;
+    visuals.faint_bg_color = rgb(30, 30, 30);
+    visuals.window_stroke = egui::Stroke::new(2.0, egui::Color32::WHITE);
+    visuals.selection.bg_fill = rgb(0, 90, 200);
+    visuals.selection.stroke = egui::Stroke::new(2.0, egui::Color32::WHITE);
+    visuals.hyperlink_color = rgb(120, 200, 255);
+    for widget in [
+        &mut visuals.widgets.noninteractive,
+        &mut visuals.widgets.inactive,
+        &mut visuals.widgets.hovered,
+        &mut visuals.widgets.active,
+        &mut visuals.widgets.open,
+    ] {
+        widget.fg_stroke = egui::Stroke::new(1.5, egui::Color32::WHITE);
+    }
+    visuals.widgets.noninteractive.bg_stroke = egui::Stroke::new(1.0, rgb(200, 200, 200));
+    visuals.widgets.inactive.bg_fill = rgb(20, 20, 20);
+    visuals.widgets.inactive.weak_bg_fill = rgb(20, 20, 20);
+    visuals.widgets.inactive.bg_stroke = egui::Stroke::new(1.0, egui::Color32::WHITE);
+    visuals.widgets.hovered.bg_stroke = egui::Stroke::new(2.0, egui::Color32::YELLOW);
+    visuals.widgets.active.bg_stroke = egui::Stroke::new(2.0, egui::Color32::YELLOW);
+    visuals
+}
+
+/// Push the theme and palette into egui whenever the settings change
+pub(super) fn apply_accessibility(
+    mut contexts: EguiContexts,
+    settings: Res<UiSettings>,
+    mut applied: Local<bool>,
+) {
+    if *applied && !settings.is_changed() {
+        return;
+    }
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    let options = &settings.accessibility;
+    ctx.set_visuals(if options.high_contrast {
+        high_contrast_visuals()
+    } else {
+        egui::Visuals::dark()
+    });
+    let palette = Palette::new(options);
+    ctx.data_mut(|d| d.insert_temp(palette_id(), palette));
+    *applied = true;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_palettes_keep_status_colors_apart() {
+        for palette in ColorPalette::ALL {
+            let colors = Palette::new(&AccessibilitySettings {
+                palette,
+                ..default()
+            });
+            let status = [Status::Good, Status::Warning, Status::Bad].map(|s| colors.status(s));
+            assert_ne!(status[0], status[1], "{:?}", palette);
+            assert_ne!(status[0], status[2], "{:?}", palette);
+            assert_ne!(status[1], status[2], "{:?}", palette);
+            for category in CATEGORIES {
+                assert_ne!(colors.category(category), egui::Color32::LIGHT_GRAY);
+            }
+        }
+    }
+
+    #[test]
+    fn test_shapes_mark_status() {
+        let plain = Palette::default();
+        assert_eq!(plain.status_text(Status::Bad, "2 TW"), "2 TW");
+        assert_eq!(plain.tech_marker(TechStatus::Locked), "●");
+
+        let shaped = Palette::new(&AccessibilitySettings {
+            status_shapes: true,
+            ..default()
+        });
+        assert_eq!(shaped.status_text(Status::Bad, "2 TW"), "✖ 2 TW");
+        assert_ne!(
+            shaped.tech_marker(TechStatus::Available),
+            shaped.tech_marker(TechStatus::Locked)
+        );
+    }
+}
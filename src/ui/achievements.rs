@@ -0,0 +1,77 @@
+//! Achievements window: the campaign's milestones, with the date each was
+//! reached
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use crate::stats::{Milestone, Milestones};
+
+/// Whether the achievements window is shown
+#[derive(Resource, Debug, Clone, Default)]
+pub struct AchievementsWindow {
+    pub open: bool,
+}
+
+/// Render the achievements window
+pub(super) fn ui_achievements_window(
+    mut contexts: EguiContexts,
+    mut window: ResMut<AchievementsWindow>,
+    milestones: Res<Milestones>,
+) {
+    if !window.open {
+        return;
+    }
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    let mut open = true;
+    egui::Window::new("🏆 Achievements")
+        .id(egui::Id::new("achievements_window"))
+        .open(&mut open)
+        .default_width(340.0)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new(format!(
+                    "{} of {} milestones reached",
+                    milestones.achieved.len(),
+                    Milestone::ALL.len()
+                ))
+                .weak(),
+            );
+            ui.separator();
+            for milestone in Milestone::ALL {
+                let record = milestones.record(milestone);
+                ui.horizontal(|ui| {
+                    let icon = egui::RichText::new(milestone.icon()).size(20.0);
+                    ui.label(if record.is_some() {
+                        icon
+                    } else {
+                        icon.color(egui::Color32::DARK_GRAY)
+                    });
+                    ui.vertical(|ui| {
+                        let name = egui::RichText::new(milestone.name()).strong();
+                        match record {
+                            Some(record) => {
+                                ui.label(name.color(egui::Color32::GOLD));
+                                ui.label(milestone.description());
+                                ui.label(
+                                    egui::RichText::new(format!("Reached {}", record.date)).weak(),
+                                );
+                            }
+                            None => {
+                                ui.label(name.color(egui::Color32::GRAY));
+                                ui.label(egui::RichText::new(milestone.description()).weak());
+                            }
+                        }
+                    });
+                });
+                ui.add_space(4.0);
+            }
+        });
+
+    if !open {
+        window.open = false;
+    }
+}
@@ -0,0 +1,60 @@
+//! Anomalies section of the Intel window: every anomaly surveys have found,
+//! how far it has been investigated and the journal of its stages
+
+use bevy_egui::egui;
+
+use super::accessibility::{Palette, Status};
+use crate::random_events::{Anomalies, AnomaliesData, AnomalyStatus};
+
+/// Render the anomalies found so far
+pub(super) fn anomalies_section(
+    ui: &mut egui::Ui,
+    palette: &Palette,
+    anomalies: &Anomalies,
+    data: Option<&AnomaliesData>,
+) {
+    ui.heading("Anomalies");
+    let mut found = anomalies.discovered().peekable();
+    if found.peek().is_none() {
+        ui.label(
+            egui::RichText::new("No anomalies found yet: survey the bodies of other star systems")
+                .weak(),
+        );
+        return;
+    }
+
+    for (index, site) in found.enumerate() {
+        let anomaly = data.and_then(|data| data.get(&site.anomaly));
+        let name = anomaly.map_or(site.anomaly.as_str(), |a| a.name.as_str());
+        let stages = anomaly.map_or(site.stage, |a| a.stages.len());
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new(name).strong());
+            ui.label(format!("on {}", site.body_name));
+            match site.status {
+                AnomalyStatus::Pending => {
+                    ui.colored_label(palette.status(Status::Warning), site.status.display_name())
+                }
+                AnomalyStatus::Concluded => {
+                    ui.colored_label(palette.status(Status::Good), site.status.display_name())
+                }
+                _ => ui.label(site.status.display_name()),
+            };
+            ui.label(format!("Stage {} of {}", site.stage.min(stages), stages));
+        });
+        if site.status == AnomalyStatus::Open {
+            if let Some(next) = anomaly.and_then(|a| a.stages.get(site.stage)) {
+                ui.label(
+                    egui::RichText::new(format!("Next stage needs: {:?}", next.survey)).weak(),
+                );
+            }
+        }
+        egui::CollapsingHeader::new("Journal")
+            .id_source(("anomaly_journal", index))
+            .default_open(false)
+            .show(ui, |ui| {
+                for entry in &site.journal {
+                    ui.label(entry);
+                }
+            });
+    }
+}
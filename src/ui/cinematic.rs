@@ -0,0 +1,165 @@
+//! Camera window: the reference frame followed, the visual scale, saved
+//! camera bookmarks and the cinematic tour's stops
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use super::interaction::Selection;
+use crate::astronomy::VisualScale;
+use crate::plugins::camera::{
+    CameraAnchor, CameraBookmarks, CameraFrame, CameraPose, CinematicMode, GameCamera,
+    OrbitCamera,
+};
+use crate::plugins::solar_system::CelestialBody;
+
+/// Whether the camera window is shown
+#[derive(Resource, Debug, Clone, Default)]
+pub struct CinematicWindow {
+    pub open: bool,
+}
+
+/// Whether the interface is drawn: hidden while the cinematic camera tours
+pub(super) fn ui_visible(cinematic: Option<Res<CinematicMode>>) -> bool {
+    cinematic.is_none_or(|c| !c.hides_ui())
+}
+
+/// Render the camera window
+#[allow(clippy::too_many_arguments)]
+pub(super) fn ui_cinematic_window(
+    mut contexts: EguiContexts,
+    mut window: ResMut<CinematicWindow>,
+    mut frame: ResMut<CameraFrame>,
+    mut scale: ResMut<VisualScale>,
+    mut bookmarks: ResMut<CameraBookmarks>,
+    mut cinematic: ResMut<CinematicMode>,
+    selection: Res<Selection>,
+    bodies: Query<&CelestialBody>,
+    mut camera_query: Query<(&mut OrbitCamera, &mut CameraAnchor), With<GameCamera>>,
+) {
+    if !window.open {
+        return;
+    }
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    let Ok((mut orbit, mut anchor)) = camera_query.get_single_mut() else {
+        return;
+    };
+    let body_name = |entity: Entity| {
+        bodies
+            .get(entity)
+            .map_or_else(|_| "(removed)".to_string(), |b| b.name.clone())
+    };
+
+    let mut open = true;
+    egui::Window::new("🎬 Camera")
+        .id(egui::Id::new("cinematic_window"))
+        .open(&mut open)
+        .default_width(300.0)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(egui::RichText::new("Follow anchor").strong());
+            let mut picked = *frame;
+            for option in CameraFrame::ALL {
+                ui.radio_value(&mut picked, option, option.name());
+            }
+            // Only write on a change so the frame is not marked changed every frame
+            if picked != *frame {
+                *frame = picked;
+            }
+
+            ui.separator();
+            ui.label(egui::RichText::new("Scale").strong());
+            let mut true_scale = scale.true_scale;
+            ui.checkbox(&mut true_scale, "True scale")
+                .on_hover_text("Real body sizes and moon distances");
+            let mut share = scale.moon_amplification;
+            ui.add_enabled(
+                !true_scale,
+                egui::Slider::new(&mut share, 0.0..=1.0)
+                    .text("Moon orbit spread")
+                    .custom_formatter(|v, _| format!("{:.0}%", v * 100.0)),
+            )
+            .on_hover_text("How far moon orbits are pushed out; 0% is their real distance");
+            // Only write on a change so the bodies are not rescaled every frame
+            if true_scale != scale.true_scale || share != scale.moon_amplification {
+                scale.true_scale = true_scale;
+                scale.moon_amplification = share;
+            }
+
+            ui.separator();
+            ui.label(egui::RichText::new("Bookmarks").strong());
+            ui.label(egui::RichText::new("Ctrl+1–9 saves the view, 1–9 returns to it").weak());
+            egui::Grid::new("camera_bookmarks")
+                .num_columns(3)
+                .show(ui, |ui| {
+                    for (index, slot) in bookmarks.slots.iter_mut().enumerate() {
+                        ui.label(format!("{}", index + 1));
+                        match slot {
+                            Some(pose) => {
+                                let view = pose
+                                    .anchor
+                                    .map_or_else(|| "Free view".to_string(), body_name);
+                                ui.label(view);
+                            }
+                            None => {
+                                ui.label(egui::RichText::new("empty").weak());
+                            }
+                        }
+                        ui.horizontal(|ui| {
+                            if ui.small_button("Save").clicked() {
+                                *slot = Some(CameraPose::capture(&orbit, &anchor));
+                            }
+                            if let Some(pose) = slot {
+                                if ui.small_button("Go").clicked() {
+                                    pose.apply(&mut orbit, &mut anchor);
+                                }
+                                if ui.small_button("✖").on_hover_text("Clear").clicked() {
+                                    *slot = None;
+                                }
+                            }
+                        });
+                        ui.end_row();
+                    }
+                });
+
+            ui.separator();
+            ui.label(egui::RichText::new("Cinematic tour").strong());
+            if cinematic.stops.is_empty() {
+                ui.label(egui::RichText::new("Add bodies to tour them in order").weak());
+            }
+            let mut removed = None;
+            for (index, stop) in cinematic.stops.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label(format!("{}. {}", index + 1, body_name(*stop)));
+                    if ui.small_button("✖").on_hover_text("Remove").clicked() {
+                        removed = Some(index);
+                    }
+                });
+            }
+            if let Some(index) = removed {
+                cinematic.stops.remove(index);
+            }
+
+            ui.horizontal(|ui| {
+                let selected = selection.get().filter(|e| !cinematic.stops.contains(e));
+                if ui
+                    .add_enabled(selected.is_some(), egui::Button::new("➕ Add selected body"))
+                    .clicked()
+                {
+                    cinematic.stops.extend(selected);
+                }
+                if ui
+                    .add_enabled(!cinematic.stops.is_empty(), egui::Button::new("▶ Start tour"))
+                    .on_hover_text("Hides the interface; Escape ends the tour")
+                    .clicked()
+                {
+                    cinematic.start(CameraPose::capture(&orbit, &anchor));
+                }
+            });
+        });
+
+    if !open {
+        window.open = false;
+    }
+}
@@ -0,0 +1,641 @@
+//! Colonies overview: every populated body and station in a sortable,
+//! filterable table under the Economy menu, with click-through to the body
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use std::collections::HashMap;
+
+use super::accessibility::{self, Palette, Status};
+use super::interaction::Selection;
+use super::{format_population, format_rate_monthly, SimulationTime};
+use crate::astronomy::{HabitatClass, Selected};
+use crate::commands::PlayerCommand;
+use crate::construction::{BuildingsData, ConstructionQueue, OrbitalStation};
+use crate::economy::components::Population;
+use crate::economy::{
+    format_power, FoodSupply, HeatBalance, LifeSupport, PowerGrids, PowerPriorities,
+    ResourceRateTracker, Stability, BASE_STABILITY, SECONDS_PER_MONTH, SECONDS_PER_YEAR,
+};
+use crate::fleets::{Blockade, Blockades};
+use crate::game_state::{ActiveMenu, GameMenu};
+use crate::hotseat::HotseatView;
+use crate::plugins::camera::{CameraAnchor, GameCamera};
+use crate::plugins::solar_system::CelestialBody;
+
+/// Column the colony table is sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(super) enum ColonyColumn {
+    #[default]
+    Name,
+    Population,
+    Growth,
+    Stability,
+    Output,
+    Food,
+    LifeSupport,
+    Power,
+    Heat,
+    Construction,
+}
+
+impl ColonyColumn {
+    const ALL: [ColonyColumn; 10] = [
+        ColonyColumn::Name,
+        ColonyColumn::Population,
+        ColonyColumn::Growth,
+        ColonyColumn::Stability,
+        ColonyColumn::Output,
+        ColonyColumn::Food,
+        ColonyColumn::LifeSupport,
+        ColonyColumn::Power,
+        ColonyColumn::Heat,
+        ColonyColumn::Construction,
+    ];
+
+    fn header(&self) -> &'static str {
+        match self {
+            ColonyColumn::Name => "Colony",
+            ColonyColumn::Population => "Population",
+            ColonyColumn::Growth => "Growth",
+            ColonyColumn::Stability => "Stability",
+            ColonyColumn::Output => "Mining",
+            ColonyColumn::Food => "Food",
+            ColonyColumn::LifeSupport => "Life Support",
+            ColonyColumn::Power => "Power",
+            ColonyColumn::Heat => "Heat",
+            ColonyColumn::Construction => "Construction",
+        }
+    }
+}
+
+/// One line of the colony table
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct ColonyRow {
+    /// Body to select when the row is clicked (the host for stations)
+    pub body: Entity,
+    /// Colony body or station the power priorities belong to
+    pub location: Entity,
+    pub name: String,
+    pub population: f64,
+    /// Annualized population growth in percent, once two samples exist
+    pub growth_percent: Option<f64>,
+    /// Not yet evaluated for newly founded colonies
+    pub stability: Option<Stability>,
+    /// Resources mined and harvested at the colony in Mt per month, after
+    /// heat, power and stability; `None` without mines or harvesters
+    pub mining: Option<f64>,
+    /// Food grown locally as a share of what the colony eats
+    pub food_self_sufficiency: f64,
+    /// Share of the food demand that went unmet
+    pub food_shortage: f64,
+    /// Habitats the colony lives in, `None` for stations and colonies not
+    /// yet evaluated
+    pub habitat: Option<HabitatClass>,
+    /// Colonists as a share of the habitat capacity; 0.0 in the open
+    pub habitat_use: f64,
+    /// Share of the oxygen and water demand that went unmet
+    pub life_support_shortage: f64,
+    /// Local grid surplus in Watts
+    pub power_surplus: f64,
+    /// Order the colony powers its tiers in during a brownout
+    pub priorities: PowerPriorities,
+    /// Some tier is short of power after relays and storage
+    pub browned_out: bool,
+    /// Energy stored locally and the storage capacity in Joules
+    pub stored: f64,
+    pub storage_capacity: f64,
+    /// Waste heat as a fraction of heat rejection; above 1.0 output is throttled
+    pub heat_load: f64,
+    /// Front project of the construction queue, `None` when idle
+    pub construction: Option<String>,
+    /// Front project is waiting for resources
+    pub stalled: bool,
+    /// Hostile fleet holding the colony's orbit
+    pub blockade: Option<Blockade>,
+}
+
+fn stability_value(row: &ColonyRow) -> f64 {
+    row.stability.as_ref().map_or(BASE_STABILITY, |s| s.value)
+}
+
+/// Sort `rows` by `column`
+pub(super) fn sort_rows(rows: &mut [ColonyRow], column: ColonyColumn, ascending: bool) {
+    rows.sort_by(|a, b| {
+        let ordering = match column {
+            ColonyColumn::Name => a.name.cmp(&b.name),
+            ColonyColumn::Population => a.population.total_cmp(&b.population),
+            ColonyColumn::Growth => a
+                .growth_percent
+                .unwrap_or(0.0)
+                .total_cmp(&b.growth_percent.unwrap_or(0.0)),
+            ColonyColumn::Stability => stability_value(a).total_cmp(&stability_value(b)),
+            ColonyColumn::Output => a.mining.unwrap_or(0.0).total_cmp(&b.mining.unwrap_or(0.0)),
+            ColonyColumn::Food => a.food_self_sufficiency.total_cmp(&b.food_self_sufficiency),
+            ColonyColumn::LifeSupport => a.habitat_use.total_cmp(&b.habitat_use),
+            ColonyColumn::Power => a.power_surplus.total_cmp(&b.power_surplus),
+            ColonyColumn::Heat => a.heat_load.total_cmp(&b.heat_load),
+            // Idle colonies sort first
+            ColonyColumn::Construction => a.construction.cmp(&b.construction),
+        };
+        let ordering = if ascending { ordering } else { ordering.reverse() };
+        ordering.then_with(|| a.name.cmp(&b.name))
+    });
+}
+
+/// Heat bar of a colony: waste heat against rejection capacity, red and
+/// throttled once it overflows
+fn heat_bar(ui: &mut egui::Ui, palette: &Palette, load: f64) {
+    let (text, color) = if load > 1.0 {
+        (
+            palette.status_text(Status::Bad, format!("{:.0}% — throttled", load * 100.0)),
+            palette.status(Status::Bad),
+        )
+    } else if load > 0.8 {
+        (
+            palette.status_text(Status::Warning, format!("{:.0}%", load * 100.0)),
+            palette.status(Status::Warning),
+        )
+    } else {
+        (format!("{:.0}%", load * 100.0), egui::Color32::from_rgb(100, 180, 255))
+    };
+    ui.add(
+        egui::ProgressBar::new(load.min(1.0) as f32)
+            .desired_width(110.0)
+            .fill(color)
+            .text(text),
+    )
+    .on_hover_text("Waste heat produced as a share of what the colony can reject. Build radiators on airless bodies and stations.");
+}
+
+/// Stability cell of a colony: a colored dot and the mood, with the factors
+/// on hover
+fn stability_label(ui: &mut egui::Ui, palette: &Palette, stability: Option<&Stability>) {
+    let Some(stability) = stability else {
+        ui.label(egui::RichText::new("—").weak());
+        return;
+    };
+    let status = if stability.is_unrest() {
+        Status::Bad
+    } else if stability.value < 50.0 {
+        Status::Warning
+    } else {
+        Status::Good
+    };
+    let text = format!("● {:.0} {}", stability.value, stability.describe());
+    ui.colored_label(palette.status(status), palette.status_text(status, text))
+        .on_hover_ui(|ui| {
+            for (factor, points) in &stability.factors {
+                if points.abs() >= 0.5 {
+                    ui.label(format!("{}: {:+.0}", factor.label(), points));
+                }
+            }
+            if stability.output_factor() != 1.0 {
+                ui.label(format!(
+                    "Output at {:.0}%",
+                    stability.output_factor() * 100.0
+                ));
+            }
+        });
+}
+
+/// Food cell of a colony: self-sufficiency, or the unmet share when the
+/// stockpile could not make up the difference
+fn food_label(ui: &mut egui::Ui, palette: &Palette, self_sufficiency: f64, shortage: f64) {
+    if shortage > 0.0 {
+        ui.colored_label(
+            palette.status(Status::Bad),
+            palette.status_text(Status::Bad, format!("{:.0}% short", shortage * 100.0)),
+        )
+        .on_hover_text("The food stockpile cannot cover this colony. Build farms or hydroponics.");
+    } else if self_sufficiency < 1.0 {
+        ui.label(format!("{:.0}% grown", self_sufficiency * 100.0))
+            .on_hover_text("The rest is imported from the food stockpile");
+    } else {
+        ui.label(format!("{:.0}% grown", self_sufficiency * 100.0));
+    }
+}
+
+/// Blockade marker next to a colony's name, with the blockader on hover
+fn blockade_label(ui: &mut egui::Ui, palette: &Palette, blockade: &Blockade) {
+    ui.colored_label(
+        palette.status(Status::Bad),
+        palette.status_text(Status::Bad, "⛔"),
+    )
+    .on_hover_text(format!(
+        "Blockaded by the {} for {:.0} days: no imports, exports or food shipments get through. Fleets with {} crew aboard in orbit drive it off.",
+        blockade.blockader, blockade.days, blockade.crew
+    ));
+}
+
+/// Life support cell of a colony: the habitat and how full it is, or the
+/// unmet share of oxygen and water
+fn life_support_label(ui: &mut egui::Ui, palette: &Palette, row: &ColonyRow) {
+    let Some(habitat) = row.habitat else {
+        ui.label(egui::RichText::new("—").weak());
+        return;
+    };
+    if row.life_support_shortage > 0.0 {
+        ui.colored_label(
+            palette.status(Status::Bad),
+            palette.status_text(
+                Status::Bad,
+                format!("{:.0}% short", row.life_support_shortage * 100.0),
+            ),
+        )
+        .on_hover_text("The oxygen and water stockpiles cannot make up what the habitats lose.");
+    } else if habitat == HabitatClass::Open {
+        ui.label(habitat.label());
+    } else {
+        let status = if row.habitat_use > 1.0 {
+            Status::Warning
+        } else {
+            Status::Good
+        };
+        ui.colored_label(
+            palette.status(status),
+            palette.status_text(
+                status,
+                format!("{} {:.0}%", habitat.label(), row.habitat_use * 100.0),
+            ),
+        )
+        .on_hover_text(match habitat {
+            HabitatClass::Domed => {
+                "Colonists as a share of the room under domes and in closed-loop habitats. Overcrowded colonies stop growing; build habitat domes."
+            }
+            _ => {
+                "Colonists as a share of the room in closed-loop habitats. Overcrowded colonies stop growing; build life support plants."
+            }
+        });
+    }
+}
+
+/// Table state kept between frames
+#[derive(Default)]
+pub(super) struct ColonyTableState {
+    sort_by: ColonyColumn,
+    descending: bool,
+    filter: String,
+    deficits_only: bool,
+    /// Population sample per colony: (simulation seconds, count)
+    samples: HashMap<Entity, (f64, f64)>,
+    /// Annualized growth in percent between the last two samples
+    growth: HashMap<Entity, f64>,
+}
+
+impl ColonyTableState {
+    /// Record the population at most once per month and derive the
+    /// annualized growth from the previous sample
+    fn sample_population(&mut self, entity: Entity, elapsed: f64, count: f64) {
+        match self.samples.get(&entity).copied() {
+            Some((time, previous)) if elapsed - time >= SECONDS_PER_MONTH => {
+                if previous > 0.0 {
+                    let per_year = SECONDS_PER_YEAR / (elapsed - time);
+                    self.growth
+                        .insert(entity, (count / previous - 1.0) * per_year * 100.0);
+                }
+                self.samples.insert(entity, (elapsed, count));
+            }
+            Some(_) => {}
+            None => {
+                self.samples.insert(entity, (elapsed, count));
+            }
+        }
+    }
+}
+
+/// Power surplus of a colony, opening its storage and brownout priorities
+fn power_menu(
+    ui: &mut egui::Ui,
+    palette: &Palette,
+    row: &ColonyRow,
+    player_commands: &mut EventWriter<PlayerCommand>,
+) {
+    let (status, text) = if row.browned_out {
+        (
+            Status::Bad,
+            format!("{} — brownout", format_power(row.power_surplus)),
+        )
+    } else if row.power_surplus < 0.0 {
+        (Status::Bad, format_power(row.power_surplus))
+    } else {
+        (Status::Good, format_power(row.power_surplus))
+    };
+    let label =
+        egui::RichText::new(palette.status_text(status, text)).color(palette.status(status));
+    ui.menu_button(label, |ui| {
+        if row.storage_capacity > 0.0 {
+            ui.label(format!(
+                "Storage: {:.0}% of {:.1} GWh",
+                row.stored / row.storage_capacity * 100.0,
+                row.storage_capacity / 3.6e12
+            ));
+        } else {
+            ui.label(egui::RichText::new("No power storage").weak());
+        }
+        ui.separator();
+        ui.label("Brownout priority:");
+        for (index, tier) in row.priorities.0.iter().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(format!("{}. {}", index + 1, tier.display_name()));
+                if index > 0 && ui.small_button("⏶").on_hover_text("Power sooner").clicked() {
+                    let mut priorities = row.priorities;
+                    priorities.raise(*tier);
+                    player_commands.send(PlayerCommand::PowerPriorities {
+                        colony: row.location.into(),
+                        order: priorities.0,
+                    });
+                }
+            });
+        }
+    });
+}
+
+/// Render the colonies overview while the Economy menu is active
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub(super) fn ui_colonies_window(
+    mut contexts: EguiContexts,
+    mut state: Local<ColonyTableState>,
+    mut commands: Commands,
+    mut selection: ResMut<Selection>,
+    mut active_menu: ResMut<ActiveMenu>,
+    mut player_commands: EventWriter<PlayerCommand>,
+    sim_time: Res<SimulationTime>,
+    power_grids: Res<PowerGrids>,
+    heat_balance: Res<HeatBalance>,
+    blockades: Res<Blockades>,
+    hotseat_view: Option<Res<HotseatView>>,
+    buildings_data: Option<Res<BuildingsData>>,
+    colonies: Query<(
+        Entity,
+        &Population,
+        Option<&CelestialBody>,
+        Option<&OrbitalStation>,
+        Option<&ConstructionQueue>,
+        Option<&FoodSupply>,
+        Option<&Stability>,
+        Option<&LifeSupport>,
+        Option<&PowerPriorities>,
+    )>,
+    rates: Res<ResourceRateTracker>,
+    selected_query: Query<Entity, With<Selected>>,
+    mut anchor_query: Query<&mut CameraAnchor, With<GameCamera>>,
+) {
+    if active_menu.current != GameMenu::Economy {
+        return;
+    }
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    let palette = accessibility::palette(ctx);
+
+    let elapsed = sim_time.elapsed_seconds();
+    let mut rows = Vec::new();
+    for (entity, population, body, station, queue, food, stability, life_support, priorities) in
+        colonies.iter()
+    {
+        // Colonies of the other hotseat player beyond the reach of the sensors
+        let hidden = hotseat_view.as_ref().is_some_and(|v| v.is_hidden(entity));
+        if population.count <= 0.0 || hidden {
+            continue;
+        }
+        let (name, target) = match (body, station) {
+            (Some(body), _) => (body.name.clone(), entity),
+            (None, Some(station)) => (station.name.clone(), station.host),
+            (None, None) => continue,
+        };
+        state.sample_population(entity, elapsed, population.count);
+
+        let front = queue.and_then(|q| q.projects.front());
+        let construction = front.map(|project| {
+            let building = buildings_data
+                .as_ref()
+                .and_then(|data| data.get(&project.building_id))
+                .and_then(|def| def.level(project.target_level))
+                .map_or(project.building_id.clone(), |level| level.name.clone());
+            format!("{} ({:.0}%)", building, project.progress_fraction() * 100.0)
+        });
+
+        let grid = power_grids.grids.get(&entity);
+        rows.push(ColonyRow {
+            body: target,
+            location: entity,
+            name,
+            population: population.count,
+            growth_percent: state.growth.get(&entity).copied(),
+            stability: stability.cloned(),
+            mining: rates.location_rates.get(&entity).copied(),
+            food_self_sufficiency: food
+                .filter(|f| f.consumed_mt_per_year > 0.0)
+                .map_or(1.0, |f| f.produced_mt_per_year / f.consumed_mt_per_year),
+            food_shortage: food.map_or(0.0, |f| f.shortage),
+            habitat: life_support.map(|l| l.class),
+            habitat_use: life_support
+                .filter(|l| l.capacity.is_finite())
+                .map_or(0.0, |l| population.count / l.capacity.max(1.0)),
+            life_support_shortage: life_support.map_or(0.0, |l| l.shortage),
+            power_surplus: grid.map_or(0.0, |g| g.surplus()),
+            priorities: priorities.copied().unwrap_or_default(),
+            browned_out: grid.is_some_and(|g| g.is_browned_out()),
+            stored: grid.map_or(0.0, |g| g.stored),
+            storage_capacity: grid.map_or(0.0, |g| g.storage_capacity),
+            heat_load: heat_balance.locations.get(&entity).map_or(0.0, |h| h.load()),
+            construction,
+            stalled: front.is_some_and(|p| p.stalled),
+            blockade: blockades.get(entity).cloned(),
+        });
+    }
+
+    let filter = state.filter.to_lowercase();
+    rows.retain(|row| {
+        (filter.is_empty() || row.name.to_lowercase().contains(&filter))
+            && (!state.deficits_only
+                || row.power_surplus < 0.0
+                || row.food_shortage > 0.0
+                || row.life_support_shortage > 0.0
+                || row.habitat_use > 1.0
+                || row.stability.as_ref().is_some_and(Stability::is_unrest)
+                || row.heat_load > 1.0
+                || row.stalled
+                || row.blockade.is_some())
+    });
+    sort_rows(&mut rows, state.sort_by, !state.descending);
+
+    let mut picked: Option<Entity> = None;
+    egui::Window::new("🏙 Colonies")
+        .id(egui::Id::new("colonies_window"))
+        .default_width(640.0)
+        .resizable(true)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Filter:");
+                ui.text_edit_singleline(&mut state.filter);
+                ui.checkbox(
+                    &mut state.deficits_only,
+                    "Deficits, hunger, overcrowding, unrest, overheating, stalled and blockaded only",
+                );
+            });
+            ui.separator();
+
+            if rows.is_empty() {
+                ui.label(egui::RichText::new("No colonies").weak());
+                return;
+            }
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                egui::Grid::new("colonies_table")
+                    .num_columns(ColonyColumn::ALL.len())
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for column in ColonyColumn::ALL {
+                            let arrow = match (state.sort_by == column, state.descending) {
+                                (true, false) => " ⏶",
+                                (true, true) => " ⏷",
+                                (false, _) => "",
+                            };
+                            let header = egui::RichText::new(format!("{}{}", column.header(), arrow))
+                                .strong();
+                            if ui.selectable_label(state.sort_by == column, header).clicked() {
+                                if state.sort_by == column {
+                                    state.descending = !state.descending;
+                                } else {
+                                    state.sort_by = column;
+                                    state.descending = false;
+                                }
+                            }
+                        }
+                        ui.end_row();
+
+                        for row in &rows {
+                            ui.horizontal(|ui| {
+                                if ui
+                                    .link(&row.name)
+                                    .on_hover_text("Select and focus the camera")
+                                    .clicked()
+                                {
+                                    picked = Some(row.body);
+                                }
+                                if let Some(blockade) = &row.blockade {
+                                    blockade_label(ui, &palette, blockade);
+                                }
+                            });
+                            ui.label(format_population(row.population));
+                            match row.growth_percent {
+                                Some(growth) => ui.label(format!("{:+.2}%/yr", growth)),
+                                None => ui.label(egui::RichText::new("—").weak()),
+                            };
+                            stability_label(ui, &palette, row.stability.as_ref());
+                            match row.mining {
+                                Some(mining) => {
+                                    let (text, color) = format_rate_monthly(mining);
+                                    ui.colored_label(color, text);
+                                }
+                                None => {
+                                    ui.label(egui::RichText::new("—").weak());
+                                }
+                            }
+                            food_label(
+                                ui,
+                                &palette,
+                                row.food_self_sufficiency,
+                                row.food_shortage,
+                            );
+                            life_support_label(ui, &palette, row);
+                            power_menu(ui, &palette, row, &mut player_commands);
+                            heat_bar(ui, &palette, row.heat_load);
+                            match (&row.construction, row.stalled) {
+                                (Some(project), true) => ui.colored_label(
+                                    palette.status(Status::Warning),
+                                    palette.status_text(
+                                        Status::Warning,
+                                        format!("{} — waiting for resources", project),
+                                    ),
+                                ),
+                                (Some(project), false) => ui.label(project),
+                                (None, _) => ui.label(egui::RichText::new("Idle").weak()),
+                            };
+                            ui.end_row();
+                        }
+                    });
+            });
+        });
+
+    // Forget colonies that no longer exist
+    state.samples.retain(|e, _| colonies.contains(*e));
+    state.growth.retain(|e, _| colonies.contains(*e));
+
+    let Some(body) = picked else {
+        return;
+    };
+    for e in selected_query.iter() {
+        commands.entity(e).remove::<Selected>();
+    }
+    commands.entity(body).insert(Selected);
+    selection.select(body);
+    if let Ok(mut anchor) = anchor_query.get_single_mut() {
+        anchor.0 = Some(body);
+    }
+    active_menu.current = GameMenu::Survey;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(name: &str, population: f64, power_surplus: f64) -> ColonyRow {
+        ColonyRow {
+            body: Entity::PLACEHOLDER,
+            location: Entity::PLACEHOLDER,
+            name: name.to_string(),
+            population,
+            growth_percent: None,
+            stability: None,
+            mining: None,
+            food_self_sufficiency: 1.0,
+            food_shortage: 0.0,
+            habitat: None,
+            habitat_use: 0.0,
+            life_support_shortage: 0.0,
+            power_surplus,
+            priorities: PowerPriorities::default(),
+            browned_out: false,
+            stored: 0.0,
+            storage_capacity: 0.0,
+            heat_load: 0.0,
+            construction: None,
+            stalled: false,
+            blockade: None,
+        }
+    }
+
+    #[test]
+    fn test_sort_rows_by_column_and_direction() {
+        let mut rows = vec![
+            row("Mars", 1e6, -5.0),
+            row("Earth", 8e9, 10.0),
+            row("Luna", 1e4, 0.0),
+        ];
+        sort_rows(&mut rows, ColonyColumn::Name, true);
+        assert_eq!(rows[0].name, "Earth");
+
+        sort_rows(&mut rows, ColonyColumn::Population, false);
+        assert_eq!(rows[0].name, "Earth");
+        assert_eq!(rows[2].name, "Luna");
+
+        sort_rows(&mut rows, ColonyColumn::Power, true);
+        assert_eq!(rows[0].name, "Mars");
+    }
+
+    #[test]
+    fn test_growth_is_annualized_from_monthly_samples() {
+        let mut state = ColonyTableState::default();
+        let colony = Entity::from_raw(1);
+        state.sample_population(colony, 0.0, 1000.0);
+        // Too soon for a second sample
+        state.sample_population(colony, SECONDS_PER_MONTH / 2.0, 1005.0);
+        assert!(state.growth.is_empty());
+
+        state.sample_population(colony, SECONDS_PER_YEAR, 1100.0);
+        let growth = state.growth[&colony];
+        assert!((growth - 10.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,284 @@
+//! Command palette (Ctrl+F): fuzzy search over bodies, star systems, colonies
+//! and technologies, jumping the camera or opening the matching panel
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use super::interaction::Selection;
+use crate::astronomy::components::{CurrentStarSystem, SystemId};
+use crate::astronomy::Selected;
+use crate::economy::components::Population;
+use crate::game_state::{ActiveMenu, GameMenu};
+use crate::plugins::camera::{CameraAnchor, GameCamera, OrbitCamera, ViewMode};
+use crate::plugins::solar_system::CelestialBody;
+use crate::plugins::starmap::{SelectedStarSystem, StarSystemIcon};
+use crate::research::{PendingResearchActions, TechnologiesData};
+
+/// Results shown at most
+const MAX_RESULTS: usize = 12;
+/// Camera distance to a body as a multiple of its visual radius
+const BODY_VIEW_DISTANCE: f32 = 20.0;
+/// Camera distance when jumping to a star system (well inside starmap view)
+const STARMAP_VIEW_DISTANCE: f32 = 1_000_000.0;
+
+/// Fuzzy match score of `query` against `candidate`, or `None` when the query
+/// characters do not all appear in order. Higher scores are better matches:
+/// consecutive characters, word starts and prefixes are rewarded, gaps and
+/// long candidates are penalized.
+pub(super) fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    let query: Vec<char> = query
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+    let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous: Option<usize> = None;
+    for (index, c) in candidate.iter().enumerate() {
+        if next == query.len() {
+            break;
+        }
+        if *c != query[next] {
+            continue;
+        }
+        score += 10;
+        let word_start = index == 0 || !candidate[index - 1].is_alphanumeric();
+        if word_start {
+            score += 8;
+        }
+        match previous {
+            Some(p) if p + 1 == index => score += 6,
+            Some(p) => score -= (index - p - 1).min(5) as i32,
+            None => score -= index.min(10) as i32,
+        }
+        previous = Some(index);
+        next += 1;
+    }
+    if next < query.len() {
+        return None;
+    }
+    Some(score - (candidate.len() / 8) as i32)
+}
+
+/// What a palette entry jumps to
+#[derive(Debug, Clone, PartialEq)]
+enum PaletteTarget {
+    Body(Entity),
+    Colony(Entity),
+    StarSystem(Entity),
+    Technology(String),
+}
+
+impl PaletteTarget {
+    fn kind(&self) -> &'static str {
+        match self {
+            PaletteTarget::Body(_) => "Body",
+            PaletteTarget::Colony(_) => "Colony",
+            PaletteTarget::StarSystem(_) => "System",
+            PaletteTarget::Technology(_) => "Tech",
+        }
+    }
+}
+
+/// Open/closed state, query text and highlighted result of the palette
+#[derive(Default)]
+pub(super) struct PaletteState {
+    open: bool,
+    query: String,
+    highlighted: usize,
+}
+
+/// Render the command palette and apply the picked entry
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub(super) fn ui_command_palette(
+    mut contexts: EguiContexts,
+    mut state: Local<PaletteState>,
+    mut commands: Commands,
+    mut selection: ResMut<Selection>,
+    mut active_menu: ResMut<ActiveMenu>,
+    mut pending_research: ResMut<PendingResearchActions>,
+    tech_data: Res<TechnologiesData>,
+    current_system: Res<CurrentStarSystem>,
+    view_mode: Res<ViewMode>,
+    bodies: Query<(
+        Entity,
+        &CelestialBody,
+        Option<&SystemId>,
+        Option<&Population>,
+    )>,
+    icons: Query<(Entity, &StarSystemIcon)>,
+    selected_bodies: Query<Entity, With<Selected>>,
+    selected_systems: Query<Entity, With<SelectedStarSystem>>,
+    mut camera_query: Query<(&mut CameraAnchor, &mut OrbitCamera), With<GameCamera>>,
+) {
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    if ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::F)) {
+        state.open = !state.open;
+        state.query.clear();
+        state.highlighted = 0;
+    }
+    if !state.open {
+        return;
+    }
+    if ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape)) {
+        state.open = false;
+        return;
+    }
+
+    // Candidate entries; bodies are limited to the system being viewed
+    let mut candidates: Vec<(String, PaletteTarget)> = Vec::new();
+    for (entity, body, system, population) in bodies.iter() {
+        if system.map_or(0, |s| s.0) != current_system.0 {
+            continue;
+        }
+        let target = if population.is_some_and(|p| p.count > 0.0) {
+            PaletteTarget::Colony(entity)
+        } else {
+            PaletteTarget::Body(entity)
+        };
+        candidates.push((body.name.clone(), target));
+    }
+    for (entity, icon) in icons.iter() {
+        candidates.push((icon.name.clone(), PaletteTarget::StarSystem(entity)));
+    }
+    for tech in tech_data.technologies.values() {
+        candidates.push((
+            tech.name.clone(),
+            PaletteTarget::Technology(tech.id.clone()),
+        ));
+    }
+
+    let mut results: Vec<(i32, String, PaletteTarget)> = candidates
+        .into_iter()
+        .filter_map(|(name, target)| {
+            fuzzy_score(&state.query, &name).map(|score| (score, name, target))
+        })
+        .collect();
+    results.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    results.truncate(MAX_RESULTS);
+
+    if ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown)) {
+        state.highlighted = (state.highlighted + 1).min(results.len().saturating_sub(1));
+    }
+    if ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp)) {
+        state.highlighted = state.highlighted.saturating_sub(1);
+    }
+    state.highlighted = state.highlighted.min(results.len().saturating_sub(1));
+
+    let mut picked: Option<PaletteTarget> = None;
+    egui::Window::new("Search")
+        .id(egui::Id::new("command_palette"))
+        .title_bar(false)
+        .resizable(false)
+        .collapsible(false)
+        .fixed_size([360.0, 0.0])
+        .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 80.0))
+        .order(egui::Order::Foreground)
+        .show(ctx, |ui| {
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut state.query)
+                    .hint_text("Search bodies, systems, colonies, technologies…")
+                    .desired_width(f32::INFINITY),
+            );
+            response.request_focus();
+            if response.changed() {
+                state.highlighted = 0;
+            }
+            if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                picked = results.get(state.highlighted).map(|r| r.2.clone());
+            }
+
+            ui.separator();
+            if results.is_empty() {
+                ui.label(egui::RichText::new("No matches").weak());
+            }
+            for (index, (_, name, target)) in results.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(target.kind()).small().weak());
+                    if ui
+                        .selectable_label(index == state.highlighted, name)
+                        .clicked()
+                    {
+                        picked = Some(target.clone());
+                    }
+                });
+            }
+        });
+
+    let Some(target) = picked else {
+        return;
+    };
+    state.open = false;
+    let Ok((mut anchor, mut orbit)) = camera_query.get_single_mut() else {
+        return;
+    };
+
+    match target {
+        PaletteTarget::Body(entity) | PaletteTarget::Colony(entity) => {
+            for e in selected_bodies.iter() {
+                commands.entity(e).remove::<Selected>();
+            }
+            commands.entity(entity).insert(Selected);
+            selection.select(entity);
+            anchor.0 = Some(entity);
+            if let Ok((_, body, _, _)) = bodies.get(entity) {
+                orbit.radius = (body.visual_radius * BODY_VIEW_DISTANCE)
+                    .clamp(orbit.min_radius, orbit.max_radius);
+            }
+            active_menu.current = if matches!(target, PaletteTarget::Colony(_)) {
+                GameMenu::Construction
+            } else {
+                GameMenu::Survey
+            };
+        }
+        PaletteTarget::StarSystem(entity) => {
+            for e in selected_systems.iter() {
+                commands.entity(e).remove::<SelectedStarSystem>();
+            }
+            commands.entity(entity).insert(SelectedStarSystem);
+            anchor.0 = Some(entity);
+            if *view_mode == ViewMode::System {
+                orbit.radius = STARMAP_VIEW_DISTANCE.min(orbit.max_radius);
+            }
+            active_menu.current = GameMenu::Starmap;
+        }
+        PaletteTarget::Technology(id) => {
+            pending_research.focus_tech = Some(id);
+            active_menu.current = GameMenu::Research;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score_matches_in_order() {
+        assert!(fuzzy_score("mrs", "Mars").is_some());
+        assert!(fuzzy_score("srm", "Mars").is_none());
+        assert!(fuzzy_score("", "Anything").is_some());
+        assert!(fuzzy_score("ALPHA c", "Alpha Centauri").is_some());
+    }
+
+    #[test]
+    fn test_fuzzy_score_prefers_tight_prefix_matches() {
+        let prefix = fuzzy_score("io", "Io").unwrap();
+        let scattered = fuzzy_score("io", "Ion Drive").unwrap();
+        let inner = fuzzy_score("io", "Callisto").unwrap();
+        assert!(prefix > scattered);
+        assert!(scattered > inner);
+
+        let word_start = fuzzy_score("ac", "Alpha Centauri").unwrap();
+        let buried = fuzzy_score("ac", "Lacaille 9352").unwrap();
+        assert!(word_start > buried);
+    }
+}
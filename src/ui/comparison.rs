@@ -0,0 +1,199 @@
+//! Side-by-side comparison of the selected bodies
+//!
+//! Ctrl-clicking bodies in the ledger or the viewport selects up to
+//! `MAX_SELECTION` of them. With two or more selected, this window lines up
+//! their gravity, temperature, colony cost, best known deposits and
+//! population. It reopens whenever the selection changes.
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use super::interaction::Selection;
+use super::tooltip::{field_value, TooltipData, TooltipField};
+use super::format_mass;
+use crate::astronomy::{AtmosphereComposition, Selected, SurfaceTemperature};
+use crate::economy::components::{PlanetResources, Population, SurveyLevel};
+use crate::economy::types::ResourceType;
+use crate::plugins::solar_system::CelestialBody;
+
+/// Deposits listed per body
+const TOP_DEPOSITS: usize = 3;
+
+/// Whether the comparison window is shown
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ComparisonWindow {
+    pub open: bool,
+}
+
+/// The largest deposits the survey level has revealed, largest first
+pub fn top_deposits(
+    resources: &PlanetResources,
+    survey_level: SurveyLevel,
+    count: usize,
+) -> Vec<(ResourceType, f64)> {
+    let mut known: Vec<(ResourceType, f64)> = resources
+        .deposits
+        .iter()
+        .map(|(resource, deposit)| (*resource, survey_level.discovered_amount(&deposit.reserve)))
+        .filter(|(_, amount)| *amount > 0.0)
+        .collect();
+    known.sort_by(|a, b| b.1.total_cmp(&a.1));
+    known.truncate(count);
+    known
+}
+
+/// Render the comparison window
+#[allow(clippy::type_complexity)]
+pub(super) fn ui_comparison_window(
+    mut contexts: EguiContexts,
+    mut window: ResMut<ComparisonWindow>,
+    selection: Res<Selection>,
+    mut commands: Commands,
+    bodies: Query<(
+        &CelestialBody,
+        Option<&SurfaceTemperature>,
+        Option<&AtmosphereComposition>,
+        Option<&PlanetResources>,
+        Option<&SurveyLevel>,
+        Option<&Population>,
+    )>,
+) {
+    if selection.is_changed() && selection.count() >= 2 {
+        window.open = true;
+    }
+    if !window.open || selection.count() < 2 {
+        return;
+    }
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    let columns: Vec<_> = selection
+        .all()
+        .filter_map(|entity| bodies.get(entity).ok().map(|body| (entity, body)))
+        .collect();
+
+    let mut open = true;
+    egui::Window::new("⚖ Compare Bodies")
+        .id(egui::Id::new("comparison_window"))
+        .open(&mut open)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(egui::RichText::new("Ctrl-click bodies to add or remove them").weak());
+            ui.separator();
+            egui::Grid::new("comparison_grid")
+                .num_columns(columns.len() + 1)
+                .striped(true)
+                .spacing([16.0, 4.0])
+                .show(ui, |ui| {
+                    ui.label("");
+                    for (entity, (body, ..)) in &columns {
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new(&body.name).strong());
+                            if ui.small_button("✖").on_hover_text("Deselect").clicked() {
+                                commands.entity(*entity).remove::<Selected>();
+                            }
+                        });
+                    }
+                    ui.end_row();
+
+                    let data: Vec<TooltipData> = columns
+                        .iter()
+                        .map(|(_, (body, temperature, atmosphere, _, survey, population))| {
+                            TooltipData {
+                                body,
+                                orbit: None,
+                                temperature_celsius: temperature
+                                    .map(|t| t.average_celsius)
+                                    .or_else(|| atmosphere.map(|a| a.surface_temperature_celsius)),
+                                atmosphere: *atmosphere,
+                                survey_level: survey.copied(),
+                                population: population.map(|p| p.count),
+                            }
+                        })
+                        .collect();
+                    let value_row = |ui: &mut egui::Ui, field: TooltipField| {
+                        ui.label(field.name());
+                        for body in &data {
+                            ui.label(field_value(field, body).unwrap_or_else(|| "—".to_string()));
+                        }
+                        ui.end_row();
+                    };
+
+                    value_row(ui, TooltipField::SurfaceGravity);
+
+                    ui.label("Temperature");
+                    for body in &data {
+                        ui.label(
+                            body.temperature_celsius
+                                .map_or_else(|| "—".to_string(), |t| format!("{:.0} °C", t)),
+                        );
+                    }
+                    ui.end_row();
+
+                    value_row(ui, TooltipField::ColonyCost);
+
+                    ui.label("Top deposits");
+                    for (_, (_, _, _, resources, survey, _)) in &columns {
+                        let level = survey.copied().unwrap_or_default();
+                        let deposits = resources
+                            .map(|r| top_deposits(r, level, TOP_DEPOSITS))
+                            .unwrap_or_default();
+                        ui.vertical(|ui| {
+                            if level == SurveyLevel::Unsurveyed {
+                                ui.label(egui::RichText::new("Unsurveyed").weak());
+                            } else if deposits.is_empty() {
+                                ui.label("—");
+                            }
+                            for (resource, amount) in deposits {
+                                ui.label(format!(
+                                    "{} {}",
+                                    resource.display_name(),
+                                    format_mass(amount)
+                                ));
+                            }
+                        });
+                    }
+                    ui.end_row();
+
+                    value_row(ui, TooltipField::Population);
+                });
+        });
+
+    if !open {
+        window.open = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::economy::components::MineralDeposit;
+
+    #[test]
+    fn test_top_deposits_follow_survey_level() {
+        let mut resources = PlanetResources::new();
+        for (resource, proven, deep, bulk) in [
+            (ResourceType::Water, 10.0, 500.0, 0.0),
+            (ResourceType::Iron, 50.0, 0.0, 0.0),
+            (ResourceType::Platinum, 0.0, 0.0, 9e9),
+        ] {
+            resources.add_deposit(resource, MineralDeposit::new(proven, deep, bulk, 0.5, 0.5));
+        }
+
+        // Nothing is known before a survey
+        assert!(top_deposits(&resources, SurveyLevel::Unsurveyed, 3).is_empty());
+
+        let scanned = top_deposits(&resources, SurveyLevel::OrbitalScan, 3);
+        assert_eq!(
+            scanned,
+            vec![(ResourceType::Iron, 50.0), (ResourceType::Water, 10.0)]
+        );
+
+        let cored = top_deposits(&resources, SurveyLevel::CoreSample, 2);
+        assert_eq!(
+            cored,
+            vec![(ResourceType::Platinum, 9e9), (ResourceType::Water, 510.0)]
+        );
+    }
+}
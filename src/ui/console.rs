@@ -0,0 +1,99 @@
+//! Developer console window, toggled with the backtick key
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use super::accessibility::{self, Status};
+use crate::commands::PlayerCommand;
+use crate::console::{Console, ConsoleLineKind};
+
+/// Render the console below the top bars
+pub(super) fn ui_console(
+    mut contexts: EguiContexts,
+    mut console: ResMut<Console>,
+    mut player_commands: EventWriter<PlayerCommand>,
+    mut recall: Local<Option<usize>>,
+    mut shown: Local<bool>,
+) {
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    if ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Backtick)) {
+        console.open = !console.open;
+        *recall = None;
+    }
+    if !console.open {
+        *shown = false;
+        return;
+    }
+    // Take the keyboard once when the console opens, not on every frame
+    let opened = !std::mem::replace(&mut *shown, true);
+    let palette = accessibility::palette(ctx);
+
+    egui::Window::new("Console")
+        .anchor(egui::Align2::CENTER_TOP, [0.0, 36.0])
+        .default_size([720.0, 260.0])
+        .collapsible(false)
+        .show(ctx, |ui| {
+            egui::ScrollArea::vertical()
+                .max_height(220.0)
+                .stick_to_bottom(true)
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    for (kind, text) in &console.lines {
+                        let text = egui::RichText::new(text).monospace();
+                        match kind {
+                            ConsoleLineKind::Input => ui.label(text.weak()),
+                            ConsoleLineKind::Output => ui.label(text),
+                            ConsoleLineKind::Error => {
+                                ui.label(text.color(palette.status(Status::Bad)))
+                            }
+                        };
+                    }
+                });
+
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut console.input)
+                    .desired_width(f32::INFINITY)
+                    .font(egui::TextStyle::Monospace)
+                    .hint_text("Type help for a list of commands"),
+            );
+            // The toggle key's character is not part of any command
+            console.input.retain(|c| c != '`');
+            if opened {
+                response.request_focus();
+            }
+
+            if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                let line = std::mem::take(&mut console.input);
+                if let Some(line) = console.submit(&line) {
+                    player_commands.send(PlayerCommand::Console(line));
+                }
+                *recall = None;
+                response.request_focus();
+            }
+
+            // Arrow keys walk through the commands entered before
+            let entered = console.entered.len();
+            let step = ui.input(|i| {
+                if i.key_pressed(egui::Key::ArrowUp) {
+                    Some(true)
+                } else if i.key_pressed(egui::Key::ArrowDown) {
+                    Some(false)
+                } else {
+                    None
+                }
+            });
+            if let Some(up) = step.filter(|_| entered > 0) {
+                *recall = match (*recall, up) {
+                    (None, true) => Some(entered - 1),
+                    (Some(i), true) => Some(i.saturating_sub(1)),
+                    (Some(i), false) if i + 1 < entered => Some(i + 1),
+                    _ => None,
+                };
+                console.input = recall
+                    .map(|i| console.entered[i].clone())
+                    .unwrap_or_default();
+            }
+        });
+}
@@ -0,0 +1,581 @@
+//! Construction window: facilities, construction queue and orbital
+//! structures of the selected body
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use super::interaction::Selection;
+use crate::astronomy::{AtmosphereComposition, KeplerOrbit};
+use crate::commands::PlayerCommand;
+use crate::construction::{
+    body_site, built_level, check_queueable, check_station_site, orbital_slots, Blueprint,
+    BlueprintProgress, Blueprints, Building, BuildingLevel, BuildingSite, BuildingsData,
+    ConstructionQueue, LagrangePoint, OrbitalStation, PendingBlueprintActions, StationHost,
+    StationKind, StationLocation,
+};
+use crate::economy::components::Population;
+use crate::economy::{surface_regions, ColonySite, GlobalBudget, SurfaceSite};
+use crate::game_state::{ActiveMenu, GameMenu};
+use crate::hotseat::HotseatView;
+use crate::plugins::solar_system::{CelestialBody, LogicalParent};
+use crate::plugins::solar_system_data::BodyType;
+use crate::research::ResearchState;
+
+/// Roman numeral for building levels (falls back to digits past X)
+fn level_numeral(level: u32) -> String {
+    const NUMERALS: [&str; 10] = ["I", "II", "III", "IV", "V", "VI", "VII", "VIII", "IX", "X"];
+    level
+        .checked_sub(1)
+        .and_then(|i| NUMERALS.get(i as usize))
+        .map_or_else(|| level.to_string(), |n| n.to_string())
+}
+
+/// Hover text listing the cost and build time of a level
+fn level_cost_text(level: &BuildingLevel, budget: &GlobalBudget) -> String {
+    let mut lines = vec![format!("{} — {:.0} days", level.name, level.build_days)];
+    let mut cost: Vec<_> = level.cost.iter().collect();
+    cost.sort_by_key(|(resource, _)| resource.display_name());
+    for (resource, amount) in cost {
+        let have = budget.get_stockpile(resource);
+        let marker = if have >= *amount { "" } else { "  (short)" };
+        lines.push(format!(
+            "{}: {:.1} / {:.1}{}",
+            resource.display_name(),
+            amount,
+            have,
+            marker
+        ));
+    }
+    let mut refined_cost: Vec<_> = level.refined_cost.iter().collect();
+    refined_cost.sort_by_key(|(good, _)| **good);
+    for (good, amount) in refined_cost {
+        let have = budget.get_refined(good);
+        let marker = if have >= *amount { "" } else { "  (short)" };
+        lines.push(format!(
+            "{}: {:.1} / {:.1}{}",
+            good.display_name(),
+            amount,
+            have,
+            marker
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Facilities grid listing every building chain of `site` at `location`
+#[allow(clippy::too_many_arguments)]
+fn facilities_grid(
+    ui: &mut egui::Ui,
+    location: Entity,
+    site: BuildingSite,
+    buildings_data: &BuildingsData,
+    research_state: &ResearchState,
+    budget: &GlobalBudget,
+    player_commands: &mut EventWriter<PlayerCommand>,
+    queue: Option<&ConstructionQueue>,
+    buildings: &Query<(&Building, &LogicalParent)>,
+) {
+    egui::Grid::new(("construction_facilities", location))
+        .num_columns(3)
+        .striped(true)
+        .show(ui, |ui| {
+            for definition in buildings_data.iter().filter(|def| def.site == site) {
+                let level = built_level(buildings.iter(), location, &definition.id);
+                let next = queue.map_or(level + 1, |q| q.next_level(&definition.id, level));
+
+                ui.label(&definition.name)
+                    .on_hover_text(&definition.description);
+                if level == 0 {
+                    ui.label(egui::RichText::new("—").weak());
+                } else {
+                    ui.label(level_numeral(level));
+                }
+
+                match check_queueable(definition, next, research_state) {
+                    Ok(next_level) => {
+                        let label = if next == 1 {
+                            "Build".to_string()
+                        } else {
+                            format!("Upgrade to {}", level_numeral(next))
+                        };
+                        if ui
+                            .button(label)
+                            .on_hover_text(level_cost_text(next_level, budget))
+                            .clicked()
+                        {
+                            player_commands.send(PlayerCommand::QueueBuilding {
+                                site: location.into(),
+                                building: definition.id.clone(),
+                            });
+                        }
+                    }
+                    Err(reason) => {
+                        ui.add_enabled(
+                            false,
+                            egui::Button::new(if next > definition.max_level() {
+                                "Max level".to_string()
+                            } else {
+                                format!("Upgrade to {}", level_numeral(next))
+                            }),
+                        )
+                        .on_disabled_hover_text(reason);
+                    }
+                }
+                ui.end_row();
+            }
+        });
+}
+
+/// Surface regions of `body` with the colony and the buildings in each
+fn regions_list(
+    ui: &mut egui::Ui,
+    body: Entity,
+    body_name: &str,
+    colony: Option<&ColonySite>,
+    sites: &Query<(&SurfaceSite, &LogicalParent, &Name)>,
+) {
+    egui::Grid::new(("construction_regions", body))
+        .num_columns(3)
+        .striped(true)
+        .show(ui, |ui| {
+            for (index, region) in surface_regions(body_name).iter().enumerate() {
+                ui.label(&region.name);
+                ui.label(
+                    egui::RichText::new(format!("{:+.0} °C", region.temperature_offset_c)).weak(),
+                );
+                let mut occupants: Vec<String> = sites
+                    .iter()
+                    .filter(|(site, parent, _)| parent.0 == body && site.region == index)
+                    .map(|(site, _, name)| {
+                        if site.yield_factor == 1.0 {
+                            name.to_string()
+                        } else {
+                            format!("{} ({:.0}%)", name, site.yield_factor * 100.0)
+                        }
+                    })
+                    .collect();
+                if colony.is_some_and(|c| c.region == index) {
+                    occupants.insert(0, "Colony".to_string());
+                }
+                ui.label(occupants.join(", "));
+                ui.end_row();
+            }
+        });
+}
+
+/// Orbital slots of `body` for the selection panel: the station in each
+/// slot and a button to found one in the first free slot. A station the
+/// hotseat `view` hides still fills its slot but shows no name
+pub(super) fn orbital_slots_section(
+    ui: &mut egui::Ui,
+    body_entity: Entity,
+    body: &CelestialBody,
+    research_state: &ResearchState,
+    stations: &Query<(Entity, &OrbitalStation)>,
+    view: Option<&HotseatView>,
+    player_commands: &mut EventWriter<PlayerCommand>,
+) {
+    let slots = orbital_slots(body.body_type, research_state);
+    if slots == 0 {
+        return;
+    }
+    let occupants: Vec<(Entity, &OrbitalStation)> = stations
+        .iter()
+        .filter(|(_, s)| s.host == body_entity && s.kind.uses_orbital_slot())
+        .collect();
+
+    ui.label(
+        egui::RichText::new(format!("🛰 Orbital Slots ({}/{})", occupants.len(), slots)).strong(),
+    );
+    for slot in 0..slots as usize {
+        ui.horizontal(|ui| {
+            ui.label(format!("{}.", slot + 1));
+            match occupants.get(slot) {
+                Some((entity, _)) if view.is_some_and(|v| v.is_hidden(*entity)) => {
+                    ui.label(egui::RichText::new("Occupied").weak());
+                }
+                Some((_, station)) => {
+                    ui.label(&station.name);
+                    ui.label(egui::RichText::new(station.location.to_string()).weak());
+                }
+                None if slot == occupants.len() => {
+                    ui.label(egui::RichText::new("Empty").weak());
+                    if ui
+                        .small_button("Found Station")
+                        .on_hover_text("Queue a station core in orbit around this body")
+                        .clicked()
+                    {
+                        player_commands.send(PlayerCommand::FoundStation {
+                            host: body_entity.into(),
+                            kind: StationKind::Station,
+                            location: StationLocation::Orbit,
+                        });
+                    }
+                }
+                None => {
+                    ui.label(egui::RichText::new("Empty").weak());
+                }
+            }
+        });
+    }
+}
+
+/// Construction queue of `location` with progress bars and cancel buttons
+fn queue_list(
+    ui: &mut egui::Ui,
+    location: Entity,
+    buildings_data: &BuildingsData,
+    player_commands: &mut EventWriter<PlayerCommand>,
+    queue: Option<&ConstructionQueue>,
+) {
+    let projects = queue.map(|q| &q.projects);
+    if projects.is_none_or(|p| p.is_empty()) {
+        ui.label(egui::RichText::new("Nothing under construction").weak());
+        return;
+    }
+
+    for (index, project) in projects.into_iter().flatten().enumerate() {
+        let name = buildings_data
+            .get(&project.building_id)
+            .and_then(|def| def.level(project.target_level))
+            .map_or(project.building_id.clone(), |level| level.name.clone());
+        ui.horizontal(|ui| {
+            if ui.small_button("✖").on_hover_text("Cancel").clicked() {
+                player_commands.send(PlayerCommand::CancelBuilding {
+                    site: location.into(),
+                    index,
+                });
+            }
+            ui.label(name);
+        });
+        let text = if project.stalled {
+            "Waiting for resources".to_string()
+        } else {
+            format!(
+                "{:.0} / {:.0} days",
+                project.progress_days, project.required_days
+            )
+        };
+        ui.add(egui::ProgressBar::new(project.progress_fraction()).text(text));
+    }
+}
+
+/// Name typed for a new blueprint and RON text pasted for import
+#[derive(Default)]
+pub(super) struct BlueprintDraft {
+    name: String,
+    import: String,
+    error: Option<String>,
+}
+
+/// Blueprint controls for `location`: save its layout, apply, export or
+/// delete saved blueprints and import shared ones
+fn blueprints_section(
+    ui: &mut egui::Ui,
+    location: Entity,
+    draft: &mut BlueprintDraft,
+    blueprints: &Blueprints,
+    actions: &mut PendingBlueprintActions,
+    player_commands: &mut EventWriter<PlayerCommand>,
+    progress: Option<&BlueprintProgress>,
+) {
+    if let Some(progress) = progress {
+        ui.horizontal(|ui| {
+            ui.label(format!("Applying {}", progress.blueprint.name));
+            if ui.small_button("Stop").clicked() {
+                player_commands.send(PlayerCommand::StopBlueprint(location.into()));
+            }
+        });
+    }
+
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::TextEdit::singleline(&mut draft.name)
+                .hint_text("Blueprint name")
+                .desired_width(160.0),
+        );
+        let name = draft.name.trim();
+        if ui
+            .add_enabled(!name.is_empty(), egui::Button::new("Save layout"))
+            .on_hover_text("Save the buildings and queue here as a blueprint")
+            .clicked()
+        {
+            actions.save.push((location, name.to_string()));
+        }
+    });
+
+    for blueprint in &blueprints.blueprints {
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "{} ({} buildings)",
+                blueprint.name,
+                blueprint.entries.len()
+            ));
+            if ui
+                .add_enabled(progress.is_none(), egui::Button::new("Apply").small())
+                .on_hover_text("Queue every missing level as the stockpile allows")
+                .clicked()
+            {
+                player_commands.send(PlayerCommand::ApplyBlueprint {
+                    site: location.into(),
+                    blueprint: blueprint.name.clone(),
+                });
+            }
+            if ui
+                .small_button("📋")
+                .on_hover_text("Copy as RON for sharing")
+                .clicked()
+            {
+                match blueprint.to_ron() {
+                    Ok(text) => ui.output_mut(|o| o.copied_text = text),
+                    Err(e) => draft.error = Some(e),
+                }
+            }
+            if ui.small_button("🗑").on_hover_text("Delete").clicked() {
+                actions.delete.push(blueprint.name.clone());
+            }
+        });
+    }
+
+    egui::CollapsingHeader::new("Import")
+        .id_source(("blueprint_import", location))
+        .show(ui, |ui| {
+            ui.add(
+                egui::TextEdit::multiline(&mut draft.import)
+                    .hint_text("Paste a blueprint in RON")
+                    .desired_rows(3),
+            );
+            if ui.button("Import").clicked() {
+                match Blueprint::from_ron(&draft.import) {
+                    Ok(blueprint) => {
+                        actions.import.push(blueprint);
+                        draft.import.clear();
+                        draft.error = None;
+                    }
+                    Err(e) => draft.error = Some(e),
+                }
+            }
+        });
+    if let Some(error) = &draft.error {
+        ui.label(egui::RichText::new(error).weak());
+    }
+}
+
+/// Structure kind and location picked in the "Found" controls
+#[derive(Default)]
+pub(super) struct StationDraft {
+    kind: StationKind,
+    location: StationLocation,
+}
+
+/// Render the construction window for the selected body while the
+/// Construction menu is active
+#[allow(clippy::too_many_arguments)]
+pub(super) fn ui_construction_window(
+    mut contexts: EguiContexts,
+    (mut draft, mut blueprint_draft): (Local<StationDraft>, Local<BlueprintDraft>),
+    active_menu: Res<ActiveMenu>,
+    selection: Res<Selection>,
+    buildings_data: Option<Res<BuildingsData>>,
+    research_state: Res<ResearchState>,
+    budget: Res<GlobalBudget>,
+    mut player_commands: EventWriter<PlayerCommand>,
+    (blueprints, mut blueprint_actions, progress): (
+        Res<Blueprints>,
+        ResMut<PendingBlueprintActions>,
+        Query<&BlueprintProgress>,
+    ),
+    bodies: Query<&CelestialBody>,
+    queues: Query<&ConstructionQueue>,
+    buildings: Query<(&Building, &LogicalParent)>,
+    stations: Query<(Entity, &OrbitalStation, &Population)>,
+    orbits: Query<&KeplerOrbit>,
+    atmospheres: Query<&AtmosphereComposition>,
+    (sites, colony_sites): (
+        Query<(&SurfaceSite, &LogicalParent, &Name)>,
+        Query<&ColonySite>,
+    ),
+) {
+    if active_menu.current != GameMenu::Construction {
+        return;
+    }
+    let Some(buildings_data) = buildings_data else {
+        return;
+    };
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    let Some((body_entity, body)) = selection
+        .selected
+        .and_then(|e| bodies.get(e).ok().map(|b| (e, b)))
+    else {
+        return;
+    };
+
+    egui::Window::new(format!("🏗 Construction — {}", body.name))
+        .id(egui::Id::new("construction_window"))
+        .default_width(360.0)
+        .resizable(true)
+        .show(ctx, |ui| {
+            if body.body_type != BodyType::Star {
+                ui.heading("Facilities");
+                ui.separator();
+                let site = body_site(body.body_type, atmospheres.get(body_entity).ok());
+                facilities_grid(
+                    ui,
+                    body_entity,
+                    site,
+                    &buildings_data,
+                    &research_state,
+                    &budget,
+                    &mut player_commands,
+                    queues.get(body_entity).ok(),
+                    &buildings,
+                );
+                if site == BuildingSite::Surface {
+                    egui::CollapsingHeader::new("Regions")
+                        .id_source(("construction_regions_header", body_entity))
+                        .show(ui, |ui| {
+                            regions_list(
+                                ui,
+                                body_entity,
+                                &body.name,
+                                colony_sites.get(body_entity).ok(),
+                                &sites,
+                            );
+                        });
+                }
+
+                ui.add_space(8.0);
+                ui.heading("Queue");
+                ui.separator();
+                queue_list(
+                    ui,
+                    body_entity,
+                    &buildings_data,
+                    &mut player_commands,
+                    queues.get(body_entity).ok(),
+                );
+                egui::CollapsingHeader::new("Blueprints")
+                    .id_source(("construction_blueprints", body_entity))
+                    .show(ui, |ui| {
+                        blueprints_section(
+                            ui,
+                            body_entity,
+                            &mut blueprint_draft,
+                            &blueprints,
+                            &mut blueprint_actions,
+                            &mut player_commands,
+                            progress.get(body_entity).ok(),
+                        );
+                    });
+                ui.add_space(8.0);
+            }
+
+            ui.heading("Orbital Structures");
+            ui.separator();
+
+            for (station_entity, station, population) in
+                stations.iter().filter(|(_, s, _)| s.host == body_entity)
+            {
+                let queue = queues.get(station_entity).ok();
+                egui::CollapsingHeader::new(&station.name)
+                    .id_source(station_entity)
+                    .show(ui, |ui| {
+                        ui.label(
+                            egui::RichText::new(format!(
+                                "{} — {} — {:.0} crew",
+                                station.kind.display_name(),
+                                station.location,
+                                population.count
+                            ))
+                            .weak(),
+                        );
+                        facilities_grid(
+                            ui,
+                            station_entity,
+                            station.kind.site(),
+                            &buildings_data,
+                            &research_state,
+                            &budget,
+                            &mut player_commands,
+                            queue,
+                            &buildings,
+                        );
+                        ui.add_space(4.0);
+                        queue_list(
+                            ui,
+                            station_entity,
+                            &buildings_data,
+                            &mut player_commands,
+                            queue,
+                        );
+                        egui::CollapsingHeader::new("Blueprints")
+                            .id_source(("construction_blueprints", station_entity))
+                            .show(ui, |ui| {
+                                blueprints_section(
+                                    ui,
+                                    station_entity,
+                                    &mut blueprint_draft,
+                                    &blueprints,
+                                    &mut blueprint_actions,
+                                    &mut player_commands,
+                                    progress.get(station_entity).ok(),
+                                );
+                            });
+                    });
+            }
+
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_id_source("station_kind")
+                    .selected_text(draft.kind.display_name())
+                    .show_ui(ui, |ui| {
+                        for kind in StationKind::ALL {
+                            ui.selectable_value(&mut draft.kind, kind, kind.display_name());
+                        }
+                    });
+                if draft.kind != StationKind::Station {
+                    draft.location = StationLocation::Orbit;
+                }
+                ui.add_enabled_ui(draft.kind == StationKind::Station, |ui| {
+                    egui::ComboBox::from_id_source("station_location")
+                        .selected_text(draft.location.to_string())
+                        .show_ui(ui, |ui| {
+                            let locations = std::iter::once(StationLocation::Orbit)
+                                .chain(LagrangePoint::ALL.map(StationLocation::Lagrange));
+                            for location in locations {
+                                let label = location.to_string();
+                                ui.selectable_value(&mut draft.location, location, label);
+                            }
+                        });
+                });
+
+                let host = StationHost {
+                    body_type: body.body_type,
+                    mass: body.mass,
+                    primary_mass: 0.0,
+                    visual_radius: body.visual_radius,
+                    luminosity: 1.0,
+                    orbit: orbits.get(body_entity).ok().copied(),
+                };
+                let check = check_station_site(
+                    draft.kind,
+                    draft.location,
+                    body_entity,
+                    &host,
+                    &research_state,
+                    stations.iter().map(|(_, s, _)| s),
+                );
+                let response = ui.add_enabled(check.is_ok(), egui::Button::new("Found"));
+                if let Err(reason) = check {
+                    response.on_disabled_hover_text(reason);
+                } else if response.clicked() {
+                    player_commands.send(PlayerCommand::FoundStation {
+                        host: body_entity.into(),
+                        kind: draft.kind,
+                        location: draft.location,
+                    });
+                }
+            });
+        });
+}
@@ -0,0 +1,327 @@
+//! Right-click context menu for bodies in the 3D view
+//!
+//! Right-clicking a hovered body (without dragging the camera) opens a menu
+//! of `BodyActionKind`s. Picking one sends a `BodyAction` event, which
+//! `apply_body_actions` carries out. The watch list and other windows send
+//! the same events, so every entry point behaves alike.
+
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_egui::{egui, EguiContexts};
+
+use super::interaction::{BodyAction, BodyActionKind, Selection};
+use super::watch_list::WatchList;
+use crate::astronomy::{Hovered, Selected};
+use crate::commands::PlayerCommand;
+use crate::economy::components::{Population, SurveyLevel};
+use crate::economy::survey::SurveyMissions;
+use crate::game_state::ActiveMenu;
+use crate::hotseat::HotseatView;
+use crate::plugins::camera::{CameraAnchor, GameCamera, ViewMode};
+use crate::plugins::solar_system::CelestialBody;
+use crate::plugins::solar_system_data::BodyType;
+
+/// Cursor travel (logical pixels) past which a right press is a camera drag
+const CLICK_SLOP: f32 = 5.0;
+
+/// The open context menu, if any
+#[derive(Resource, Debug, Clone, Default)]
+pub struct BodyContextMenu {
+    /// Body the menu was opened on
+    pub target: Option<Entity>,
+    /// Screen position of the menu, taken from the pointer on the first frame
+    pub position: Option<egui::Pos2>,
+}
+
+impl BodyContextMenu {
+    pub fn open(&mut self, body: Entity) {
+        self.target = Some(body);
+        self.position = None;
+    }
+
+    pub fn close(&mut self) {
+        self.target = None;
+        self.position = None;
+    }
+}
+
+/// Why an action cannot be taken on a body right now, or `None` if it can
+pub fn action_unavailable(
+    kind: BodyActionKind,
+    body_type: BodyType,
+    survey_level: SurveyLevel,
+    surveying: bool,
+    populated: bool,
+) -> Option<&'static str> {
+    match kind {
+        BodyActionKind::QueueSurvey if body_type == BodyType::Star => {
+            Some("Stars cannot be surveyed")
+        }
+        BodyActionKind::QueueSurvey if surveying => Some("A survey mission is under way"),
+        BodyActionKind::QueueSurvey if survey_level.next().is_none() => Some("Fully surveyed"),
+        BodyActionKind::EstablishColony if populated => Some("Already a colony"),
+        BodyActionKind::EstablishColony if matches!(body_type, BodyType::Star | BodyType::Ring) => {
+            Some("Cannot be settled")
+        }
+        _ => None,
+    }
+}
+
+/// System that opens the menu when a hovered body is right-clicked.
+/// The right button also rotates the camera, so the menu only opens if the
+/// cursor stayed put between press and release.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn open_body_context_menu(
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    hovered: Query<Entity, (With<Hovered>, With<CelestialBody>)>,
+    view_mode: Res<ViewMode>,
+    active_menu: Res<ActiveMenu>,
+    mut contexts: EguiContexts,
+    mut menu: ResMut<BodyContextMenu>,
+    mut pressed: Local<Option<(Entity, Vec2)>>,
+) {
+    if *view_mode == ViewMode::Starmap || active_menu.current.blocks_world_interaction() {
+        *pressed = None;
+        return;
+    }
+    let cursor = windows.get_single().ok().and_then(|w| w.cursor_position());
+
+    if mouse_button.just_pressed(MouseButton::Right) {
+        let over_ui = contexts
+            .try_ctx_mut()
+            .is_some_and(|ctx| ctx.is_pointer_over_area() || ctx.wants_pointer_input());
+        *pressed = match (over_ui, hovered.get_single(), cursor) {
+            (false, Ok(body), Some(cursor)) => Some((body, cursor)),
+            _ => None,
+        };
+    }
+
+    if mouse_button.just_released(MouseButton::Right) {
+        if let (Some((body, start)), Some(cursor)) = (pressed.take(), cursor) {
+            if start.distance(cursor) <= CLICK_SLOP {
+                menu.open(body);
+            }
+        }
+    }
+}
+
+/// Render the context menu
+#[allow(clippy::type_complexity)]
+pub(super) fn ui_body_context_menu(
+    mut contexts: EguiContexts,
+    mut menu: ResMut<BodyContextMenu>,
+    bodies: Query<(&CelestialBody, Option<&SurveyLevel>, Option<&Population>)>,
+    survey: Res<SurveyMissions>,
+    watch_list: Res<WatchList>,
+    hotseat_view: Option<Res<HotseatView>>,
+    mut actions: EventWriter<BodyAction>,
+) {
+    let Some(target) = menu.target else {
+        return;
+    };
+    let Ok((body, level, population)) = bodies.get(target) else {
+        menu.close();
+        return;
+    };
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    let Some(position) = menu
+        .position
+        .or_else(|| ctx.input(|i| i.pointer.hover_pos()))
+    else {
+        return;
+    };
+    menu.position = Some(position);
+
+    let surveying = survey.mission_for(target).is_some();
+    // A hotseat rival's colony beyond the player's sensors reads as empty;
+    // founding there is refused when the order arrives
+    let hidden = hotseat_view.is_some_and(|v| v.is_hidden(target));
+    let populated = !hidden && population.is_some_and(|p| p.count > 0.0);
+    let mut chosen = None;
+    let response = egui::Area::new("body_context_menu".into())
+        .fixed_pos(position)
+        .order(egui::Order::Foreground)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.label(egui::RichText::new(&body.name).strong());
+                ui.separator();
+                for kind in BodyActionKind::ALL {
+                    let label =
+                        if kind == BodyActionKind::ToggleWatch && watch_list.contains(target) {
+                            "👁 Remove from watch list"
+                        } else {
+                            kind.label()
+                        };
+                    let unavailable = action_unavailable(
+                        kind,
+                        body.body_type,
+                        level.copied().unwrap_or_default(),
+                        surveying,
+                        populated,
+                    );
+                    let button = ui.add_enabled(unavailable.is_none(), egui::Button::new(label));
+                    let button = match unavailable {
+                        Some(reason) => button.on_disabled_hover_text(reason),
+                        None => button,
+                    };
+                    if button.clicked() {
+                        chosen = Some(kind);
+                    }
+                }
+            });
+        })
+        .response;
+
+    if let Some(kind) = chosen {
+        actions.send(BodyAction { body: target, kind });
+        menu.close();
+        return;
+    }
+    // Close on Escape or a click anywhere else
+    let clicked_elsewhere = ctx.input(|i| i.pointer.any_pressed()) && !response.contains_pointer();
+    if clicked_elsewhere || ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+        menu.close();
+    }
+}
+
+/// System that carries out body actions from the context menu and other
+/// windows
+#[allow(clippy::too_many_arguments)]
+pub(super) fn apply_body_actions(
+    mut actions: EventReader<BodyAction>,
+    mut commands: Commands,
+    mut selection: ResMut<Selection>,
+    selected_query: Query<Entity, With<Selected>>,
+    mut anchor_query: Query<&mut CameraAnchor, With<GameCamera>>,
+    mut player_commands: EventWriter<PlayerCommand>,
+    mut watch_list: ResMut<WatchList>,
+) {
+    for action in actions.read() {
+        let body = action.body;
+        match action.kind {
+            BodyActionKind::AnchorCamera | BodyActionKind::OpenDetails => {
+                for entity in selected_query.iter() {
+                    commands.entity(entity).remove::<Selected>();
+                }
+                commands.entity(body).insert(Selected);
+                selection.select(body);
+                if action.kind == BodyActionKind::AnchorCamera {
+                    if let Ok(mut anchor) = anchor_query.get_single_mut() {
+                        anchor.0 = Some(body);
+                    }
+                }
+            }
+            BodyActionKind::QueueSurvey => {
+                player_commands.send(PlayerCommand::Survey(body.into()));
+            }
+            BodyActionKind::EstablishColony => {
+                player_commands.send(PlayerCommand::FoundColony(body.into()));
+            }
+            BodyActionKind::ToggleWatch => {
+                watch_list.toggle(body);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unavailable_actions() {
+        use BodyActionKind::*;
+        let available = |kind, body_type, level, surveying, populated| {
+            action_unavailable(kind, body_type, level, surveying, populated).is_none()
+        };
+        assert!(available(
+            QueueSurvey,
+            BodyType::Moon,
+            SurveyLevel::OrbitalScan,
+            false,
+            false
+        ));
+        assert!(!available(
+            QueueSurvey,
+            BodyType::Moon,
+            SurveyLevel::OrbitalScan,
+            true,
+            false
+        ));
+        assert!(!available(
+            QueueSurvey,
+            BodyType::Moon,
+            SurveyLevel::CoreSample,
+            false,
+            false
+        ));
+        assert!(!available(
+            QueueSurvey,
+            BodyType::Star,
+            SurveyLevel::Unsurveyed,
+            false,
+            false
+        ));
+        assert!(available(
+            EstablishColony,
+            BodyType::Planet,
+            SurveyLevel::Unsurveyed,
+            false,
+            false
+        ));
+        assert!(!available(
+            EstablishColony,
+            BodyType::Planet,
+            SurveyLevel::Unsurveyed,
+            false,
+            true
+        ));
+        assert!(available(
+            AnchorCamera,
+            BodyType::Star,
+            SurveyLevel::Unsurveyed,
+            true,
+            true
+        ));
+    }
+
+    #[test]
+    fn test_actions_reach_their_systems() {
+        let mut app = App::new();
+        app.init_resource::<Selection>()
+            .init_resource::<WatchList>()
+            .add_event::<BodyAction>()
+            .add_event::<PlayerCommand>()
+            .add_systems(Update, apply_body_actions);
+        let old = app.world_mut().spawn(Selected).id();
+        let camera = app.world_mut().spawn((GameCamera, CameraAnchor(None))).id();
+        let moon = app.world_mut().spawn_empty().id();
+
+        for kind in BodyActionKind::ALL {
+            app.world_mut().send_event(BodyAction { body: moon, kind });
+        }
+        app.update();
+
+        let world = app.world();
+        assert_eq!(world.resource::<Selection>().selected, Some(moon));
+        assert!(world.get::<Selected>(moon).is_some());
+        assert!(world.get::<Selected>(old).is_none());
+        assert_eq!(world.get::<CameraAnchor>(camera).unwrap().0, Some(moon));
+        let sent: Vec<PlayerCommand> = world
+            .resource::<Events<PlayerCommand>>()
+            .iter_current_update_events()
+            .cloned()
+            .collect();
+        assert_eq!(
+            sent,
+            vec![
+                PlayerCommand::Survey(moon.into()),
+                PlayerCommand::FoundColony(moon.into())
+            ]
+        );
+        assert!(world.resource::<WatchList>().contains(moon));
+    }
+}
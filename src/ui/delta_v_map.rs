@@ -0,0 +1,167 @@
+//! Delta-v map window: transfer costs from a body to the other bodies of its
+//! star system, colour-coded by delta-v band, opened from the dashboard
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use super::accessibility::{self, Status};
+use super::{format_duration, SimulationTime};
+use crate::astronomy::components::SystemId;
+use crate::economy::ECONOMY_TICK_SECONDS;
+use crate::fleets::{
+    delta_v_map, orbit_nodes, BodyNodes, DeltaVBand, Transfer, PROBE_DELTA_V_KM_S,
+};
+use crate::plugins::solar_system_data::BodyType;
+
+/// egui memory key holding the body whose delta-v map is open
+fn delta_v_map_id() -> egui::Id {
+    egui::Id::new("delta_v_map_body")
+}
+
+/// Open the delta-v map window for `body`
+pub(super) fn open_delta_v_map(ctx: &egui::Context, body: Entity) {
+    ctx.data_mut(|data| data.insert_temp(delta_v_map_id(), body));
+}
+
+/// Transfers planned for the open map, replanned once a day or when the
+/// origin or filter changes
+#[derive(Default)]
+pub(super) struct DeltaVMapCache {
+    /// Show asteroids and comets too
+    show_small_bodies: bool,
+    /// Origin, filter and day the transfers were planned for
+    origin: Option<Entity>,
+    small_bodies: bool,
+    day: Option<i64>,
+    transfers: Vec<Transfer>,
+}
+
+fn band_status(band: DeltaVBand) -> Status {
+    match band {
+        DeltaVBand::Low => Status::Good,
+        DeltaVBand::Medium => Status::Warning,
+        DeltaVBand::High => Status::Bad,
+    }
+}
+
+/// Render the delta-v map of the body opened from the dashboard
+pub(super) fn ui_delta_v_map_window(
+    mut contexts: EguiContexts,
+    sim_time: Res<SimulationTime>,
+    mut cache: Local<DeltaVMapCache>,
+    bodies: BodyNodes,
+    systems: Query<&SystemId>,
+) {
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    let Some(origin) = ctx.data(|data| data.get_temp::<Entity>(delta_v_map_id())) else {
+        return;
+    };
+    let Ok((_, origin_body, ..)) = bodies.get(origin) else {
+        ctx.data_mut(|data| data.remove::<Entity>(delta_v_map_id()));
+        return;
+    };
+
+    let now = sim_time.elapsed_seconds();
+    let day = (now / ECONOMY_TICK_SECONDS).floor() as i64;
+    if cache.origin != Some(origin)
+        || cache.small_bodies != cache.show_small_bodies
+        || cache.day != Some(day)
+    {
+        let system_of = |entity: Entity| systems.get(entity).map_or(0, |id| id.0);
+        let system = system_of(origin);
+        let small_bodies = cache.show_small_bodies;
+        let destinations: Vec<Entity> = bodies
+            .iter()
+            .filter(|(entity, body, ..)| {
+                *entity != origin
+                    && system_of(*entity) == system
+                    && match body.body_type {
+                        BodyType::Star | BodyType::Ring => false,
+                        BodyType::Asteroid | BodyType::Comet => small_bodies,
+                        _ => true,
+                    }
+            })
+            .map(|(entity, ..)| entity)
+            .collect();
+        cache.transfers = delta_v_map(origin, destinations, now, &orbit_nodes(&bodies));
+        cache.origin = Some(origin);
+        cache.small_bodies = small_bodies;
+        cache.day = Some(day);
+    }
+
+    let name_of = |entity: Entity| {
+        bodies
+            .get(entity)
+            .map_or("Unknown".to_string(), |(_, body, ..)| body.name.clone())
+    };
+    let mut open = true;
+    egui::Window::new(format!("🧭 Delta-v from {}", origin_body.name))
+        .id(egui::Id::new("delta_v_map_window"))
+        .open(&mut open)
+        .default_size([560.0, 420.0])
+        .resizable(true)
+        .show(ctx, |ui| {
+            let palette = accessibility::palette(ui.ctx());
+            ui.horizontal(|ui| {
+                for band in [DeltaVBand::Low, DeltaVBand::Medium, DeltaVBand::High] {
+                    let label = match band {
+                        DeltaVBand::Low => format!("≤ {:.0} km/s", DeltaVBand::LOW_KM_S),
+                        DeltaVBand::Medium => format!("≤ {:.0} km/s", DeltaVBand::MEDIUM_KM_S),
+                        DeltaVBand::High => format!("> {:.0} km/s", DeltaVBand::MEDIUM_KM_S),
+                    };
+                    ui.colored_label(palette.status(band_status(band)), format!("■ {}", label));
+                }
+                ui.checkbox(&mut cache.show_small_bodies, "Asteroids and comets");
+            });
+            ui.label(
+                egui::RichText::new(
+                    "Hohmann transfers from a low parking orbit, departing at the next window",
+                )
+                .weak(),
+            );
+            ui.separator();
+
+            if cache.transfers.is_empty() {
+                ui.label(egui::RichText::new("No reachable destinations").weak());
+                return;
+            }
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                egui::Grid::new("delta_v_map_grid")
+                    .striped(true)
+                    .num_columns(6)
+                    .show(ui, |ui| {
+                        for header in ["Destination", "Total", "Flyby", "Window", "Trip", "Probe"] {
+                            ui.strong(header);
+                        }
+                        ui.end_row();
+
+                        for transfer in &cache.transfers {
+                            let total = transfer.total_dv_km_s();
+                            let color = palette.status(band_status(DeltaVBand::of(total)));
+                            ui.colored_label(color, name_of(transfer.destination));
+                            ui.colored_label(color, format!("{:.2} km/s", total))
+                                .on_hover_text(format!(
+                                    "Departure {:.2} km/s, insertion {:.2} km/s",
+                                    transfer.departure_dv_km_s, transfer.insertion_dv_km_s
+                                ));
+                            ui.label(format!("{:.2} km/s", transfer.departure_dv_km_s));
+                            ui.label(format_duration(transfer.departure_time - now));
+                            ui.label(format_duration(
+                                transfer.arrival_time - transfer.departure_time,
+                            ));
+                            if transfer.departure_dv_km_s <= PROBE_DELTA_V_KM_S {
+                                ui.label("✔").on_hover_text("Within reach of a flyby probe");
+                            } else {
+                                ui.label("");
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+        });
+    if !open {
+        ctx.data_mut(|data| data.remove::<Entity>(delta_v_map_id()));
+    }
+}
@@ -0,0 +1,50 @@
+//! Demographics section of the selected body panel: the population groups
+//! of a colony, how far their culture drifted and what they adapted to
+
+use bevy_egui::egui;
+
+use super::accessibility::{Palette, Status};
+use crate::economy::components::Population;
+use crate::economy::demographics::{
+    DISTINCT_CULTURE_DIVERGENCE, INDEPENDENCE_DIVERGENCE, INDEPENDENCE_MOVEMENT_SUPPORT,
+};
+
+/// Render the population groups of a colony
+pub(super) fn demographics_section(ui: &mut egui::Ui, palette: &Palette, population: &Population) {
+    if population.groups.is_empty() {
+        return;
+    }
+    ui.label(egui::RichText::new("👥 Demographics").strong());
+    for group in &population.groups {
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new(&group.culture).strong());
+            ui.label(format!("{:.0}%", group.share * 100.0));
+            ui.label(egui::RichText::new(format!("from {}", group.origin)).weak());
+        });
+        let status = if group.divergence >= INDEPENDENCE_DIVERGENCE {
+            Status::Bad
+        } else if group.divergence >= DISTINCT_CULTURE_DIVERGENCE {
+            Status::Warning
+        } else {
+            Status::Good
+        };
+        ui.colored_label(
+            palette.status(status),
+            format!(
+                "Cultural drift: {:.0} after {:.0} years",
+                group.divergence, group.years
+            ),
+        );
+        if !group.traits.is_empty() {
+            let traits: Vec<&str> = group.traits.iter().map(|t| t.display_name()).collect();
+            ui.label(format!("Adapted: {}", traits.join(", ")));
+        }
+    }
+    let support = population.independence_support(INDEPENDENCE_DIVERGENCE);
+    if support >= INDEPENDENCE_MOVEMENT_SUPPORT {
+        ui.colored_label(
+            palette.status(Status::Bad),
+            format!("⚠ Independence movement: {:.0}% support", support * 100.0),
+        );
+    }
+}
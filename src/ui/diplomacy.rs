@@ -0,0 +1,175 @@
+//! Diplomacy window: the colonies that declared independence, how well the
+//! listening posts hear, the alien civilizations detected so far, their
+//! translations and relations
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use super::accessibility::{self, Status};
+use crate::commands::PlayerCommand;
+use crate::contact::{
+    listening_sensitivity, AlienContacts, AliensData, ContactState, ListeningPost, Relation,
+    ENVOY_COST, TRANSLATION_RP_PER_DAY, TRANSLATION_TECH,
+};
+use crate::economy::{format_currency, Treasury};
+use crate::game_state::{ActiveMenu, GameMenu};
+use crate::independence::Secessions;
+use crate::research::ResearchState;
+
+/// Render the diplomacy window while the Diplomacy menu is active
+#[allow(clippy::too_many_arguments)]
+pub(super) fn ui_diplomacy_window(
+    mut contexts: EguiContexts,
+    active_menu: Res<ActiveMenu>,
+    contacts: Res<AlienContacts>,
+    mut player_commands: EventWriter<PlayerCommand>,
+    research_state: Res<ResearchState>,
+    treasury: Res<Treasury>,
+    data: Option<Res<AliensData>>,
+    posts: Query<&ListeningPost>,
+    secessions: Res<Secessions>,
+) {
+    if active_menu.current != GameMenu::Diplomacy {
+        return;
+    }
+    let Some(data) = data else {
+        return;
+    };
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    let palette = accessibility::palette(ctx);
+    let can_translate = research_state.is_unlocked(TRANSLATION_TECH);
+
+    egui::Window::new("🤝 Diplomacy")
+        .id(egui::Id::new("diplomacy_window"))
+        .default_width(420.0)
+        .resizable(true)
+        .show(ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                if !secessions.colonies.is_empty() {
+                    ui.heading("Independent Colonies");
+                    for colony in &secessions.colonies {
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new(format!("Free {}", colony.name)).strong());
+                            ui.label(format!("since {}", colony.declared));
+                        });
+                        if colony.recognized {
+                            ui.colored_label(
+                                palette.status(Status::Good),
+                                palette.status_text(Status::Good, "Recognized, open to trade"),
+                            );
+                            continue;
+                        }
+                        ui.horizontal(|ui| {
+                            ui.colored_label(
+                                palette.status(Status::Bad),
+                                palette.status_text(Status::Bad, "Secessionist"),
+                            )
+                            .on_hover_text(format!(
+                                "Sabotages construction; {:.0} crew in orbit retake it",
+                                colony.garrison_needed().ceil()
+                            ));
+                            if ui
+                                .button("Recognize independence")
+                                .on_hover_text("Ends the sabotage and opens trade, but the colony is lost for good")
+                                .clicked()
+                            {
+                                player_commands.send(PlayerCommand::RecognizeIndependence(
+                                    colony.faction.clone(),
+                                ));
+                            }
+                        });
+                    }
+                    ui.separator();
+                }
+
+                ui.heading("Listening");
+                ui.label(format!(
+                    "{} listening posts gather {:.1} points per day",
+                    posts.iter().count(),
+                    listening_sensitivity(posts.iter(), &research_state)
+                ));
+                ui.separator();
+
+                ui.heading("Civilizations");
+                let mut known = contacts.known().peekable();
+                if known.peek().is_none() {
+                    ui.label(
+                        egui::RichText::new(
+                            "No signals detected yet: build SETI arrays to listen for them",
+                        )
+                        .weak(),
+                    );
+                    return;
+                }
+
+                for civilization in known {
+                    let Some(alien) = data.get(&civilization.id) else {
+                        continue;
+                    };
+                    ui.horizontal(|ui| {
+                        ui.label(egui::RichText::new(&alien.name).strong());
+                        ui.label(format!(
+                            "{} ({:.1} ly)",
+                            alien.system_name, alien.distance_ly
+                        ));
+                        ui.label(civilization.state.display_name());
+                    });
+                    match civilization.state {
+                        ContactState::Detected => {
+                            if ui
+                                .add_enabled(can_translate, egui::Button::new("Begin translation"))
+                                .on_hover_text(format!(
+                                    "Draws up to {:.0} RP a day from the pool, {:.0} RP in all",
+                                    TRANSLATION_RP_PER_DAY, alien.translation_cost
+                                ))
+                                .on_disabled_hover_text("Needs Xenolinguistics")
+                                .clicked()
+                            {
+                                player_commands
+                                    .send(PlayerCommand::BeginTranslation(civilization.id.clone()));
+                            }
+                        }
+                        ContactState::Translating => {
+                            let fraction = civilization.translation / alien.translation_cost;
+                            ui.add(egui::ProgressBar::new(fraction as f32).text(format!(
+                                "{:.0} / {:.0} RP",
+                                civilization.translation, alien.translation_cost
+                            )));
+                        }
+                        ContactState::Contacted => {
+                            ui.label(egui::RichText::new(&alien.description).weak());
+                            ui.horizontal(|ui| {
+                                let relation = civilization.relation();
+                                let status = match relation {
+                                    Relation::Hostile => Status::Bad,
+                                    Relation::Wary => Status::Warning,
+                                    _ => Status::Good,
+                                };
+                                ui.colored_label(
+                                    palette.status(status),
+                                    palette.status_text(status, relation.display_name()),
+                                );
+                                ui.label(format!("Opinion {:+.0}", civilization.opinion));
+                                if ui
+                                    .add_enabled(
+                                        !treasury.over_credit_limit(),
+                                        egui::Button::new("Send envoy"),
+                                    )
+                                    .on_hover_text(format!("Costs {}", format_currency(ENVOY_COST)))
+                                    .on_disabled_hover_text("Not beyond the credit limit")
+                                    .clicked()
+                                {
+                                    player_commands
+                                        .send(PlayerCommand::SendEnvoy(civilization.id.clone()));
+                                }
+                            });
+                        }
+                        ContactState::Undetected => {}
+                    }
+                    ui.separator();
+                }
+            });
+        });
+}
@@ -0,0 +1,69 @@
+//! Event log window: warnings and notable events, newest first
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use super::accessibility::{self, Status};
+use crate::game_state::{EventLog, LogSeverity};
+
+/// Whether the event log window is open
+#[derive(Resource, Debug, Clone, Default)]
+pub struct EventLogWindow {
+    pub open: bool,
+}
+
+/// Text colour of a log entry in the palette in use
+pub(super) fn severity_color(ctx: &egui::Context, severity: LogSeverity) -> egui::Color32 {
+    let palette = accessibility::palette(ctx);
+    match severity {
+        LogSeverity::Info => egui::Color32::from_rgb(180, 180, 180),
+        LogSeverity::Warning => palette.status(Status::Warning),
+        LogSeverity::Critical => palette.status(Status::Bad),
+    }
+}
+
+/// Render the event log window
+pub(super) fn ui_event_log_window(
+    mut contexts: EguiContexts,
+    mut window: ResMut<EventLogWindow>,
+    mut log: ResMut<EventLog>,
+) {
+    if !window.open {
+        return;
+    }
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    let mut clear = false;
+    egui::Window::new("📜 Event Log")
+        .id(egui::Id::new("event_log_window"))
+        .open(&mut window.open)
+        .default_size([420.0, 300.0])
+        .resizable(true)
+        .show(ctx, |ui| {
+            if ui.small_button("Clear").clicked() {
+                clear = true;
+            }
+            ui.separator();
+            if log.entries.is_empty() {
+                ui.label(egui::RichText::new("Nothing to report").weak());
+                return;
+            }
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for entry in log.entries.iter().rev() {
+                    ui.horizontal_wrapped(|ui| {
+                        ui.label(egui::RichText::new(&entry.date).weak().monospace());
+                        ui.colored_label(
+                            severity_color(ui.ctx(), entry.severity),
+                            format!("{} {}", entry.severity.icon(), entry.message),
+                        );
+                    });
+                }
+            });
+        });
+
+    if clear {
+        log.entries.clear();
+    }
+}
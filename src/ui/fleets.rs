@@ -0,0 +1,528 @@
+//! Fleets window: every fleet with its location, propellant, delta-v, crew
+//! and magazine, and move, refuel, crew, resupply and load orders under the
+//! Fleets menu,
+//! with the ordnance held across the logistics network, the probes in flight,
+//! the salvage waiting for recovery and the orders on their way at the speed
+//! of light
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use super::interaction::Selection;
+use super::{format_duration, SimulationTime};
+use crate::astronomy::components::SpaceCoordinates;
+use crate::astronomy::nearby_stars::NearbyStarsData;
+use crate::commands::PlayerCommand;
+use crate::comms::{CommandLatency, Transmissions};
+use crate::construction::OrbitalStation;
+use crate::fleets::ordnance::ordnance_name;
+use crate::fleets::{
+    Crew, Fleet, FleetLocation, LifeSupport, Magazine, OrdnanceLedger, ProbeMission, Probes,
+    Reload, Salvage, SupplyShip,
+};
+use crate::game_state::{ActiveMenu, GameMenu};
+use crate::hotseat::HotseatView;
+use crate::plugins::solar_system::CelestialBody;
+
+/// Render the fleets window
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub(super) fn ui_fleets_window(
+    mut contexts: EguiContexts,
+    active_menu: Res<ActiveMenu>,
+    selection: Res<Selection>,
+    sim_time: Res<SimulationTime>,
+    ledger: Res<OrdnanceLedger>,
+    probes: Res<Probes>,
+    stars_data: Res<NearbyStarsData>,
+    mut probe_system: Local<usize>,
+    mut player_commands: EventWriter<PlayerCommand>,
+    (latency, transmissions, view): (
+        Res<CommandLatency>,
+        Res<Transmissions>,
+        Option<Res<HotseatView>>,
+    ),
+    fleets: Query<(
+        Entity,
+        &Fleet,
+        &FleetLocation,
+        Option<&Magazine>,
+        Option<&Reload>,
+        Option<&Crew>,
+        Option<&LifeSupport>,
+        &SpaceCoordinates,
+    )>,
+    supply_ships: Query<(Entity, &FleetLocation), With<SupplyShip>>,
+    magazines: Query<&Magazine>,
+    bodies: Query<&CelestialBody>,
+    stations: Query<&OrbitalStation>,
+    salvage: Query<(Entity, &Salvage)>,
+) {
+    if active_menu.current != GameMenu::Fleets {
+        return;
+    }
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    // Fleets of the other hotseat player beyond the reach of the sensors
+    let hidden = |entity: Entity| view.as_ref().is_some_and(|v| v.is_hidden(entity));
+    let name_of = |entity: Entity| {
+        bodies
+            .get(entity)
+            .map_or("Unknown".to_string(), |body| body.name.clone())
+    };
+    let target = selection
+        .selected
+        .filter(|entity| bodies.contains(*entity));
+    let now = sim_time.elapsed_seconds();
+    // Ordnance most stocked at the colony a fleet orbits, or else on a
+    // supply ship in the same orbit
+    let ordnance_at = |fleet: Entity, body: Entity| {
+        std::iter::once(body)
+            .chain(
+                supply_ships
+                    .iter()
+                    .filter(|(ship, at)| *ship != fleet && **at == FleetLocation::Orbiting(body))
+                    .map(|(ship, _)| ship),
+            )
+            .find_map(|source| {
+                magazines
+                    .get(source)
+                    .ok()
+                    .and_then(|m| m.most_stocked().map(str::to_string))
+            })
+    };
+
+    egui::Window::new("🚀 Fleets")
+        .id(egui::Id::new("fleets_window"))
+        .default_size([760.0, 320.0])
+        .resizable(true)
+        .show(ctx, |ui| {
+            if fleets.is_empty() {
+                ui.label(egui::RichText::new("No fleets").weak());
+                magazines_section(ui, &ledger);
+                probes_section(
+                    ui,
+                    &probes,
+                    &stars_data,
+                    &mut probe_system,
+                    &mut player_commands,
+                    now,
+                );
+                return;
+            }
+            ui.horizontal(|ui| {
+                match target {
+                    Some(target) => ui.label(format!("Destination: {}", name_of(target))),
+                    None => ui.label(egui::RichText::new("Select a body to send fleets to").weak()),
+                };
+                probe_buttons(ui, target, &mut player_commands);
+            });
+            ui.separator();
+
+            egui::Grid::new("fleets_grid")
+                .striped(true)
+                .num_columns(8)
+                .show(ui, |ui| {
+                    for header in [
+                        "Fleet",
+                        "Engine",
+                        "Status",
+                        "Propellant",
+                        "Delta-v",
+                        "Crew",
+                        "Ordnance",
+                        "",
+                    ] {
+                        ui.strong(header);
+                    }
+                    ui.end_row();
+
+                    for (entity, fleet, location, magazine, reload, crew, supplies, coords) in
+                        fleets.iter().filter(|(entity, ..)| !hidden(*entity))
+                    {
+                        let label = ui.label(&fleet.name);
+                        if latency.enabled {
+                            if let Some(delay) = transmissions.delay_to(coords.position) {
+                                label.on_hover_text(format!(
+                                    "Light delay from the capital: {}",
+                                    format_duration(delay)
+                                ));
+                            }
+                        }
+                        let engine = fleet.engine_profile();
+                        ui.label(engine.map_or(fleet.engine.as_str(), |e| e.name));
+
+                        let status = match location {
+                            FleetLocation::Orbiting(body) => format!("Orbiting {}", name_of(*body)),
+                            FleetLocation::InTransit(transfer) if !transfer.departed => format!(
+                                "To {}, departs in {}",
+                                name_of(transfer.destination),
+                                format_duration(transfer.departure_time - now)
+                            ),
+                            FleetLocation::InTransit(transfer) => format!(
+                                "To {}, arrives in {}",
+                                name_of(transfer.destination),
+                                format_duration(transfer.arrival_time - now)
+                            ),
+                        };
+                        ui.label(status);
+
+                        let fuel = engine.map_or(String::new(), |e| e.fuel.to_string());
+                        ui.label(format!(
+                            "{:.0} / {:.0} t {}",
+                            fleet.fuel_t, fleet.fuel_capacity_t, fuel
+                        ));
+                        ui.label(format!(
+                            "{:.2} / {:.2} km/s",
+                            fleet.delta_v_km_s(),
+                            fleet.max_delta_v_km_s()
+                        ));
+                        match (crew, supplies) {
+                            (Some(crew), Some(supplies)) => {
+                                let endurance = supplies.endurance_days(crew.aboard);
+                                let label = ui.label(format!(
+                                    "{} / {}, {}",
+                                    crew.aboard,
+                                    crew.required,
+                                    if endurance.is_finite() {
+                                        format!("{:.0} days", endurance)
+                                    } else {
+                                        "∞".to_string()
+                                    }
+                                ));
+                                label.on_hover_text(format!(
+                                    "Water {:.1} t, oxygen {:.1} t, food {:.1} t of {:.0} t each",
+                                    supplies.water_t,
+                                    supplies.oxygen_t,
+                                    supplies.food_t,
+                                    supplies.capacity_t
+                                ));
+                            }
+                            (Some(crew), None) => {
+                                ui.label(format!("{} / {}", crew.aboard, crew.required));
+                            }
+                            (None, _) => {
+                                ui.label(egui::RichText::new("-").weak());
+                            }
+                        }
+                        match (magazine, reload) {
+                            (Some(_), Some(reload)) => ui.label(format!(
+                                "Loading {} {}",
+                                reload.remaining,
+                                ordnance_name(&reload.ordnance)
+                            )),
+                            (Some(magazine), None) => ui.label(format!(
+                                "{} / {} rounds",
+                                magazine.total(),
+                                magazine.capacity
+                            )),
+                            (None, _) => ui.label(egui::RichText::new("-").weak()),
+                        };
+
+                        ui.horizontal(|ui| {
+                            let orbiting = matches!(location, FleetLocation::Orbiting(_));
+                            if let Some(target) = target {
+                                if ui
+                                    .add_enabled(orbiting, egui::Button::new("Move"))
+                                    .on_hover_text(format!("Plan a transfer to {}", name_of(target)))
+                                    .clicked()
+                                {
+                                    player_commands.send(PlayerCommand::MoveFleet {
+                                        fleet: entity.into(),
+                                        destination: target.into(),
+                                    });
+                                }
+                            }
+                            if ui
+                                .add_enabled(
+                                    orbiting && fleet.fuel_t < fleet.fuel_capacity_t,
+                                    egui::Button::new("Refuel"),
+                                )
+                                .on_hover_text("Fill the tanks from the stockpile at a colony")
+                                .clicked()
+                            {
+                                player_commands.send(PlayerCommand::RefuelFleet(entity.into()));
+                            }
+                            if let Some(crew) = crew {
+                                if ui
+                                    .add_enabled(
+                                        orbiting && crew.missing() > 0,
+                                        egui::Button::new("Crew"),
+                                    )
+                                    .on_hover_text("Recruit the missing crew from the colony")
+                                    .clicked()
+                                {
+                                    player_commands.send(PlayerCommand::CrewFleet(entity.into()));
+                                }
+                            }
+                            if let Some(supplies) = supplies {
+                                let full = supplies.water_t >= supplies.capacity_t
+                                    && supplies.oxygen_t >= supplies.capacity_t
+                                    && supplies.food_t >= supplies.capacity_t;
+                                if ui
+                                    .add_enabled(orbiting && !full, egui::Button::new("Resupply"))
+                                    .on_hover_text("Fill the life support stores at a colony")
+                                    .clicked()
+                                {
+                                    player_commands
+                                        .send(PlayerCommand::ResupplyFleet(entity.into()));
+                                }
+                            }
+                            if let (Some(magazine), FleetLocation::Orbiting(body)) =
+                                (magazine, location)
+                            {
+                                let available = ordnance_at(entity, *body);
+                                let enabled =
+                                    reload.is_none() && magazine.free() > 0 && available.is_some();
+                                let hover = available.as_deref().map_or(
+                                    "No ordnance in this orbit".to_string(),
+                                    |id| format!("Load {} into free racks", ordnance_name(id)),
+                                );
+                                if ui
+                                    .add_enabled(enabled, egui::Button::new("Load"))
+                                    .on_hover_text(hover)
+                                    .clicked()
+                                {
+                                    if let Some(id) = available {
+                                        player_commands.send(PlayerCommand::LoadOrdnance {
+                                            fleet: entity.into(),
+                                            ordnance: id,
+                                            rounds: magazine.free(),
+                                        });
+                                    }
+                                }
+                            }
+                        });
+                        ui.end_row();
+                    }
+                });
+
+            magazines_section(ui, &ledger);
+            probes_section(
+                ui,
+                &probes,
+                &stars_data,
+                &mut probe_system,
+                &mut player_commands,
+                now,
+            );
+            let fleets_orbiting = |body: Entity| {
+                fleets
+                    .iter()
+                    .filter(|(entity, _, location, ..)| {
+                        **location == FleetLocation::Orbiting(body) && !hidden(*entity)
+                    })
+                    .map(|(entity, fleet, ..)| (entity, fleet.name.clone()))
+                    .collect::<Vec<_>>()
+            };
+            salvage_section(
+                ui,
+                &salvage,
+                &mut player_commands,
+                name_of,
+                fleets_orbiting,
+            );
+            if latency.enabled {
+                let recipient = |entity: Entity| {
+                    fleets
+                        .get(entity)
+                        .map(|(_, fleet, ..)| fleet.name.clone())
+                        .or_else(|_| stations.get(entity).map(|s| s.name.clone()))
+                        .unwrap_or_else(|_| name_of(entity))
+                };
+                transmissions_section(ui, &transmissions, now, recipient);
+            }
+        });
+}
+
+/// Ordnance held at every colony and fleet magazine
+fn magazines_section(ui: &mut egui::Ui, ledger: &OrdnanceLedger) {
+    if ledger.stocks.is_empty() {
+        return;
+    }
+    ui.separator();
+    egui::CollapsingHeader::new("Magazines")
+        .default_open(false)
+        .show(ui, |ui| {
+            egui::Grid::new("magazines_grid")
+                .striped(true)
+                .num_columns(3)
+                .show(ui, |ui| {
+                    for stock in &ledger.stocks {
+                        ui.label(&stock.name);
+                        let total: u32 = stock.rounds.values().sum();
+                        ui.label(format!("{} / {}", total, stock.capacity));
+                        let mut held: Vec<_> = stock.rounds.iter().collect();
+                        held.sort();
+                        let held = held
+                            .into_iter()
+                            .map(|(id, count)| format!("{} {}", count, ordnance_name(id)))
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        ui.label(held);
+                        ui.end_row();
+                    }
+                });
+        });
+}
+
+/// Buttons launching a probe to the selected body
+fn probe_buttons(
+    ui: &mut egui::Ui,
+    target: Option<Entity>,
+    player_commands: &mut EventWriter<PlayerCommand>,
+) {
+    let Some(target) = target else {
+        return;
+    };
+    for mission in ProbeMission::ALL {
+        if ui
+            .button(format!("{} probe", mission.display_name()))
+            .on_hover_text("Build a probe at the nearest colony and launch it here")
+            .clicked()
+        {
+            player_commands.send(PlayerCommand::LaunchProbe {
+                target: target.into(),
+                mission,
+            });
+        }
+    }
+}
+
+/// Probes in flight, and probe launches to another star system
+fn probes_section(
+    ui: &mut egui::Ui,
+    probes: &Probes,
+    stars_data: &NearbyStarsData,
+    system: &mut usize,
+    player_commands: &mut EventWriter<PlayerCommand>,
+    now: f64,
+) {
+    ui.separator();
+    egui::CollapsingHeader::new(format!("Probes ({})", probes.active.len()))
+        .default_open(false)
+        .show(ui, |ui| {
+            for probe in &probes.active {
+                ui.horizontal(|ui| {
+                    ui.label(format!(
+                        "{} ({}) to {}",
+                        probe.name,
+                        probe.mission.display_name(),
+                        probe.target_name
+                    ));
+                    ui.add(
+                        egui::ProgressBar::new(probe.progress(now) as f32)
+                            .desired_width(120.0)
+                            .text(format_duration(probe.arrival_time - now)),
+                    );
+                });
+            }
+
+            ui.horizontal(|ui| {
+                let selected = stars_data
+                    .get_by_id(*system)
+                    .map_or("Choose a star system", |data| data.system_name.as_str());
+                egui::ComboBox::from_id_source("probe_system")
+                    .selected_text(selected)
+                    .show_ui(ui, |ui| {
+                        for (index, data) in stars_data.systems.iter().enumerate() {
+                            let label =
+                                format!("{} ({:.1} ly)", data.system_name, data.distance_ly);
+                            // Catalog ids start at 1; Sol is 0
+                            ui.selectable_value(system, index + 1, label);
+                        }
+                    });
+                if ui
+                    .add_enabled(*system > 0, egui::Button::new("Launch interstellar probe"))
+                    .on_hover_text("A flyby probe cruising at a twentieth of light speed")
+                    .clicked()
+                {
+                    player_commands.send(PlayerCommand::LaunchInterstellarProbe(*system));
+                }
+            });
+        });
+}
+
+/// Salvage drifting in orbit, with recovery orders for the fleets orbiting
+/// the same body
+fn salvage_section(
+    ui: &mut egui::Ui,
+    salvage: &Query<(Entity, &Salvage)>,
+    player_commands: &mut EventWriter<PlayerCommand>,
+    name_of: impl Fn(Entity) -> String,
+    fleets_orbiting: impl Fn(Entity) -> Vec<(Entity, String)>,
+) {
+    if salvage.is_empty() {
+        return;
+    }
+    ui.separator();
+    egui::CollapsingHeader::new(format!("Salvage ({})", salvage.iter().count()))
+        .default_open(true)
+        .show(ui, |ui| {
+            for (entity, wreck) in salvage.iter() {
+                let mut contents: Vec<String> = wreck
+                    .resources
+                    .iter()
+                    .map(|(resource, amount)| {
+                        format!("{:.0} Mt {}", amount, resource.display_name())
+                    })
+                    .collect();
+                contents.sort();
+                if wreck.tech.is_some() {
+                    contents.push("technology to reverse engineer".to_string());
+                } else if wreck.research_points > 0.0 {
+                    contents.push(format!("{:.0} RP of data", wreck.research_points));
+                }
+                ui.label(format!(
+                    "{} in orbit of {}: {}",
+                    wreck.name,
+                    name_of(wreck.body),
+                    contents.join(", ")
+                ));
+                ui.horizontal(|ui| {
+                    let here = fleets_orbiting(wreck.body);
+                    if here.is_empty() {
+                        ui.label(
+                            egui::RichText::new("  Send a fleet into this orbit to recover it")
+                                .weak(),
+                        );
+                    }
+                    for (fleet, name) in here {
+                        if ui.button(format!("Recover with {}", name)).clicked() {
+                            player_commands.send(PlayerCommand::RecoverSalvage {
+                                fleet: fleet.into(),
+                                salvage: entity.into(),
+                            });
+                        }
+                    }
+                });
+            }
+        });
+}
+
+/// Orders on their way from the capital
+fn transmissions_section(
+    ui: &mut egui::Ui,
+    transmissions: &Transmissions,
+    now: f64,
+    recipient: impl Fn(Entity) -> String,
+) {
+    ui.separator();
+    egui::CollapsingHeader::new(format!("Transmissions ({})", transmissions.in_flight.len()))
+        .default_open(false)
+        .show(ui, |ui| {
+            if transmissions.in_flight.is_empty() {
+                ui.label(egui::RichText::new("No orders under way").weak());
+            }
+            for transmission in &transmissions.in_flight {
+                let order = &transmission.order;
+                ui.label(format!(
+                    "{} to {}, arrives in {}",
+                    order.label(),
+                    recipient(order.recipient()),
+                    format_duration(transmission.arrival_time - now)
+                ));
+            }
+        });
+}
@@ -0,0 +1,144 @@
+//! End-of-game summary: the outcome of the campaign, its final figures and
+//! history, with the choice to keep playing in sandbox mode or quit
+
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use egui_plot::{Line, Plot, PlotPoints};
+
+use super::accessibility::{self, Status};
+use super::format_population;
+use crate::commands::PlayerCommand;
+use crate::economy::components::Population;
+use crate::economy::GlobalBudget;
+use crate::stats::{Milestones, Statistic, StatsHistory};
+use crate::victory::{Outcome, VictoryState};
+
+/// Render the summary once the campaign has ended. The rest of the interface
+/// is blocked until play continues in sandbox mode or the game quits.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn ui_game_over_screen(
+    mut contexts: EguiContexts,
+    state: Res<VictoryState>,
+    mut player_commands: EventWriter<PlayerCommand>,
+    budget: Res<GlobalBudget>,
+    history: Res<StatsHistory>,
+    milestones: Res<Milestones>,
+    populations: Query<&Population>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if state.sandbox {
+        return;
+    }
+    let Some(result) = state.result.clone() else {
+        return;
+    };
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    let palette = accessibility::palette(ctx);
+
+    // Backdrop that swallows clicks meant for the rest of the interface
+    egui::Area::new(egui::Id::new("game_over_backdrop"))
+        .order(egui::Order::Middle)
+        .fixed_pos(egui::Pos2::ZERO)
+        .show(ctx, |ui| {
+            let screen = ui.ctx().screen_rect();
+            ui.allocate_rect(screen, egui::Sense::click());
+            ui.painter()
+                .rect_filled(screen, 0.0, egui::Color32::from_black_alpha(200));
+        });
+
+    let mut sandbox = false;
+    let mut quit = false;
+    egui::Window::new(match result.outcome {
+        Outcome::Victory => "🏆 Victory",
+        Outcome::Defeat => "💀 Defeat",
+    })
+    .id(egui::Id::new("game_over_screen"))
+    .order(egui::Order::Foreground)
+    .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+    .collapsible(false)
+    .resizable(false)
+    .default_width(520.0)
+    .show(ctx, |ui| {
+        let status = match result.outcome {
+            Outcome::Victory => Status::Good,
+            Outcome::Defeat => Status::Bad,
+        };
+        ui.colored_label(
+            palette.status(status),
+            egui::RichText::new(&result.reason).heading(),
+        );
+        ui.label(egui::RichText::new(&result.date).weak());
+        ui.separator();
+
+        let population: f64 = populations.iter().map(|p| p.count).sum();
+        egui::Grid::new("game_over_stats")
+            .num_columns(2)
+            .show(ui, |ui| {
+                if let Some(first) = history.samples.front() {
+                    ui.label("Campaign began");
+                    ui.label(&first.date);
+                    ui.end_row();
+                }
+                ui.label("Population");
+                ui.label(format_population(population));
+                ui.end_row();
+                ui.label("Kardashev index");
+                ui.label(format!("{:.3}", budget.kardashev_index()));
+                ui.end_row();
+                ui.label("Milestones");
+                ui.label(format!("{}", milestones.achieved.len()));
+                ui.end_row();
+            });
+        for record in &milestones.achieved {
+            ui.label(format!(
+                "{} {} ({})",
+                record.milestone.icon(),
+                record.milestone.name(),
+                record.date
+            ));
+        }
+
+        if history.samples.len() >= 2 {
+            ui.separator();
+            for statistic in [Statistic::Population, Statistic::Kardashev] {
+                ui.label(egui::RichText::new(statistic.name()).strong());
+                let points: PlotPoints = history
+                    .series(statistic)
+                    .into_iter()
+                    .map(|(year, value)| [year, value])
+                    .collect();
+                Plot::new(("game_over_plot", statistic))
+                    .height(110.0)
+                    .x_axis_label("Year")
+                    .allow_scroll(false)
+                    .show(ui, |plot_ui| {
+                        plot_ui.line(Line::new(points).name(statistic.name()));
+                    });
+            }
+        }
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            if ui
+                .button("Continue in sandbox")
+                .on_hover_text("Keep playing without victory or defeat")
+                .clicked()
+            {
+                sandbox = true;
+            }
+            if ui.button("Quit").clicked() {
+                quit = true;
+            }
+        });
+    });
+
+    if sandbox {
+        player_commands.send(PlayerCommand::ContinueInSandbox);
+    }
+    if quit {
+        exit.send(AppExit::Success);
+    }
+}
@@ -0,0 +1,136 @@
+//! History window: monthly statistics plotted over the simulation years,
+//! with CSV export
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use egui_plot::{Line, Plot, PlotPoints};
+use std::path::Path;
+
+use super::{accessibility, Toasts};
+use crate::economy::ResourceType;
+use crate::game_state::LogSeverity;
+use crate::stats::{Statistic, StatsHistory, STATS_CSV_PATH};
+
+/// Which statistics the history window plots
+#[derive(Resource, Debug, Clone)]
+pub struct HistoryWindow {
+    pub open: bool,
+    /// Plotted statistics, one plot each
+    pub shown: Vec<Statistic>,
+}
+
+impl Default for HistoryWindow {
+    fn default() -> Self {
+        Self {
+            open: false,
+            shown: vec![Statistic::Population, Statistic::Kardashev],
+        }
+    }
+}
+
+impl HistoryWindow {
+    /// Show or hide the plot of `statistic`
+    fn toggle(&mut self, statistic: Statistic, shown: bool) {
+        if shown {
+            self.shown.push(statistic);
+        } else {
+            self.shown.retain(|s| *s != statistic);
+        }
+    }
+}
+
+/// Render the history window
+pub(super) fn ui_history_window(
+    mut contexts: EguiContexts,
+    mut window: ResMut<HistoryWindow>,
+    history: Res<StatsHistory>,
+    mut toasts: ResMut<Toasts>,
+) {
+    if !window.open {
+        return;
+    }
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    let palette = accessibility::palette(ctx);
+
+    let mut open = true;
+    egui::Window::new("📊 History")
+        .id(egui::Id::new("history_window"))
+        .open(&mut open)
+        .default_size([560.0, 420.0])
+        .resizable(true)
+        .show(ctx, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                for statistic in Statistic::GENERAL {
+                    let mut shown = window.shown.contains(&statistic);
+                    if ui.checkbox(&mut shown, statistic.name()).changed() {
+                        window.toggle(statistic, shown);
+                    }
+                }
+                for (category, resources) in ResourceType::by_category() {
+                    let color = palette.category(category);
+                    ui.menu_button(egui::RichText::new(category).color(color), |ui| {
+                        for resource in resources {
+                            let statistic = Statistic::Stockpile(resource);
+                            let mut shown = window.shown.contains(&statistic);
+                            if ui.checkbox(&mut shown, resource.display_name()).changed() {
+                                window.toggle(statistic, shown);
+                            }
+                        }
+                    });
+                }
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui
+                        .add_enabled(!history.samples.is_empty(), egui::Button::new("💾 CSV"))
+                        .on_hover_text(format!("Export the history to {}", STATS_CSV_PATH))
+                        .clicked()
+                    {
+                        match history.export_csv(Path::new(STATS_CSV_PATH)) {
+                            Ok(()) => toasts.push(
+                                LogSeverity::Info,
+                                format!(
+                                    "Exported {} months to {}",
+                                    history.samples.len(),
+                                    STATS_CSV_PATH
+                                ),
+                            ),
+                            Err(e) => toasts.push(LogSeverity::Critical, e),
+                        }
+                    }
+                });
+            });
+            ui.separator();
+
+            if history.samples.len() < 2 {
+                ui.label(
+                    egui::RichText::new("Not enough history yet — samples are taken monthly")
+                        .weak(),
+                );
+                return;
+            }
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for statistic in &window.shown {
+                    ui.label(egui::RichText::new(statistic.name()).strong());
+                    let points: PlotPoints = history
+                        .series(*statistic)
+                        .into_iter()
+                        .map(|(year, value)| [year, value])
+                        .collect();
+                    Plot::new(("history_plot", *statistic))
+                        .height(140.0)
+                        .x_axis_label("Year")
+                        .allow_scroll(false)
+                        .show(ui, |plot_ui| {
+                            plot_ui.line(Line::new(points).name(statistic.name()));
+                        });
+                    ui.add_space(6.0);
+                }
+            });
+        });
+
+    if !open {
+        window.open = false;
+    }
+}
@@ -0,0 +1,87 @@
+//! Hotseat turns: the cover shown while the screen changes hands, and the
+//! turn panel of the player giving orders
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use super::SimulationTime;
+use crate::commands::PlayerCommand;
+use crate::hotseat::{Hotseat, TurnPhase};
+
+/// Month and seat of the turn the player at the screen has begun
+#[derive(Default)]
+pub(super) struct BegunTurn(Option<(u64, usize)>);
+
+/// Render the hotseat turns: at the start of each turn the screen stays
+/// covered until the player whose turn it is takes it, then a panel ends
+/// the turn
+pub(super) fn ui_hotseat_turns(
+    mut contexts: EguiContexts,
+    mut begun: Local<BegunTurn>,
+    hotseat: Res<Hotseat>,
+    sim_time: Res<SimulationTime>,
+    mut player_commands: EventWriter<PlayerCommand>,
+) {
+    if !hotseat.enabled {
+        return;
+    }
+    let TurnPhase::Turn(seat) = hotseat.phase else {
+        return;
+    };
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    let turn = (hotseat.month.unwrap_or_default(), seat);
+    let player = hotseat.player(seat);
+
+    if begun.0 != Some(turn) {
+        // Cover that hides the other player's holdings and swallows clicks
+        egui::Area::new(egui::Id::new("hotseat_cover"))
+            .order(egui::Order::Middle)
+            .fixed_pos(egui::Pos2::ZERO)
+            .show(ctx, |ui| {
+                let screen = ui.ctx().screen_rect();
+                ui.allocate_rect(screen, egui::Sense::click());
+                ui.painter().rect_filled(screen, 0.0, egui::Color32::BLACK);
+            });
+        egui::Window::new("🎲 Hotseat")
+            .id(egui::Id::new("hotseat_handover"))
+            .order(egui::Order::Foreground)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.vertical_centered(|ui| {
+                    ui.heading(format!("{}'s turn", player));
+                    ui.label(egui::RichText::new(sim_time.format_date_time()).weak());
+                    ui.label(format!("Pass the screen to {}.", player));
+                    if ui
+                        .add_sized([160.0, 28.0], egui::Button::new("Begin turn"))
+                        .clicked()
+                    {
+                        begun.0 = Some(turn);
+                    }
+                });
+            });
+        return;
+    }
+
+    egui::Window::new(format!("🎲 {}'s turn", player))
+        .id(egui::Id::new("hotseat_turn"))
+        .anchor(egui::Align2::CENTER_TOP, [0.0, 60.0])
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new("The clock waits until every player has ended their turn")
+                    .weak(),
+            );
+            let label = match hotseat.players.get(seat + 1) {
+                Some(next) => format!("End turn and pass to {}", next),
+                None => "End turn and run the month".to_string(),
+            };
+            if ui.button(label).clicked() {
+                player_commands.send(PlayerCommand::EndTurn);
+            }
+        });
+}
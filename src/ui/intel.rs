@@ -0,0 +1,320 @@
+//! Intel window: agents and counter-intelligence duty, agent networks in
+//! the factions and what they reveal of their doctrines, the covert-ops
+//! queue, the sensor contacts and the anomalies surveys have found
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use super::accessibility::{self, Status};
+use super::anomalies::anomalies_section;
+use super::sensor_contacts::sensor_contacts_section;
+use crate::ai::{
+    read_doctrine, DoctrineReading, DoctrinesData, FactionState, FactionStates,
+    DOCTRINE_KNOWN_NETWORK, DOCTRINE_TELLS_NETWORK,
+};
+use crate::commands::PlayerCommand;
+use crate::economy::{format_currency, Treasury};
+use crate::game_state::{ActiveMenu, GameMenu};
+use crate::hotseat::HotseatView;
+use crate::intel::{
+    success_chance, AgentAssignment, Intel, OperationKind, SensorContacts, MAX_AGENTS, RECRUIT_COST,
+};
+use crate::random_events::{Anomalies, AnomaliesData};
+use crate::trade::{FactionId, FactionsData};
+
+/// Window state kept between frames: the operation being planned
+pub(super) struct IntelTabState {
+    faction: Option<FactionId>,
+    kind: OperationKind,
+}
+
+impl Default for IntelTabState {
+    fn default() -> Self {
+        Self {
+            faction: None,
+            kind: OperationKind::Infiltrate,
+        }
+    }
+}
+
+/// Render the intel window while the Intel menu is active
+#[allow(clippy::too_many_arguments)]
+pub(super) fn ui_intel_window(
+    mut contexts: EguiContexts,
+    mut state: Local<IntelTabState>,
+    active_menu: Res<ActiveMenu>,
+    intel: Res<Intel>,
+    mut player_commands: EventWriter<PlayerCommand>,
+    treasury: Res<Treasury>,
+    data: Option<Res<FactionsData>>,
+    anomalies: Res<Anomalies>,
+    anomaly_data: Option<Res<AnomaliesData>>,
+    contacts: Res<SensorContacts>,
+    hotseat_view: Option<Res<HotseatView>>,
+    (faction_states, doctrines): (Res<FactionStates>, Option<Res<DoctrinesData>>),
+) {
+    if active_menu.current != GameMenu::Intel {
+        return;
+    }
+    let Some(data) = data else {
+        return;
+    };
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    let palette = accessibility::palette(ctx);
+    let faction_name = |id: &str| data.get(id).map_or(id.to_string(), |f| f.name.clone());
+
+    egui::Window::new("🔍 Intelligence")
+        .id(egui::Id::new("intel_window"))
+        .default_width(480.0)
+        .resizable(true)
+        .show(ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                ui.heading("Agents");
+                ui.horizontal(|ui| {
+                    let can_recruit =
+                        intel.agents.len() < MAX_AGENTS && !treasury.over_credit_limit();
+                    if ui
+                        .add_enabled(can_recruit, egui::Button::new("Recruit"))
+                        .on_hover_text(format!("Costs {}", format_currency(RECRUIT_COST)))
+                        .on_disabled_hover_text(format!(
+                            "At most {} agents, and not beyond the credit limit",
+                            MAX_AGENTS
+                        ))
+                        .clicked()
+                    {
+                        player_commands.send(PlayerCommand::RecruitAgents(1));
+                    }
+                    ui.label(format!("{} of {}", intel.agents.len(), MAX_AGENTS));
+                    ui.label(format!(
+                        "Counter-intelligence foils {:.0}% of sabotage",
+                        intel.foil_chance() * 100.0
+                    ));
+                });
+                egui::Grid::new("intel_agents")
+                    .num_columns(3)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for agent in &intel.agents {
+                            ui.label(egui::RichText::new(&agent.name).strong());
+                            ui.label(format!("Skill {:.0}%", agent.skill * 100.0));
+                            match agent.assignment {
+                                AgentAssignment::Operation => {
+                                    let operation = intel
+                                        .active
+                                        .iter()
+                                        .find(|o| o.agent == Some(agent.id));
+                                    ui.label(operation.map_or("On operation".to_string(), |o| {
+                                        format!("{}: {}", o.kind.name(), faction_name(&o.faction))
+                                    }));
+                                }
+                                assignment => {
+                                    let mut on_duty =
+                                        assignment == AgentAssignment::CounterIntelligence;
+                                    if ui
+                                        .checkbox(&mut on_duty, "Counter-intelligence")
+                                        .on_hover_text(
+                                            "Agents on duty guard construction against sabotage and take no operations",
+                                        )
+                                        .changed()
+                                    {
+                                        player_commands.send(PlayerCommand::CounterIntelligence {
+                                            agent: agent.id,
+                                            on_duty,
+                                        });
+                                    }
+                                }
+                            }
+                            ui.end_row();
+                        }
+                    });
+                ui.separator();
+
+                ui.heading("Factions");
+                egui::Grid::new("intel_factions")
+                    .num_columns(5)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for faction in data.iter() {
+                            let known = intel.factions.get(&faction.id).cloned().unwrap_or_default();
+                            ui.label(&faction.name);
+                            ui.label(format!("Network {:.0}%", known.network * 100.0));
+                            ui.label(format!("Security {:.0}%", faction.security * 100.0));
+                            let state = faction_states.get(&faction.id);
+                            let doctrine = state.and_then(|s| {
+                                doctrines.as_ref().and_then(|d| d.get(&s.doctrine))
+                            });
+                            match doctrine.map(|d| read_doctrine(d, known.network)) {
+                                Some(DoctrineReading::Known(doctrine)) => {
+                                    ui.label(&doctrine.name).on_hover_ui(|ui| {
+                                        ui.label(&doctrine.description);
+                                        if let Some(state) = state {
+                                            ui.label(strength_estimate(state, known.network));
+                                        }
+                                    });
+                                }
+                                Some(DoctrineReading::Tells(tells)) => {
+                                    ui.label("Doctrine unclear").on_hover_ui(|ui| {
+                                        ui.label(format!(
+                                            "Agents report: {}. A network of {:.0}% finds out its doctrine.",
+                                            tells,
+                                            DOCTRINE_KNOWN_NETWORK * 100.0
+                                        ));
+                                        if let Some(state) = state {
+                                            ui.label(strength_estimate(state, known.network));
+                                        }
+                                    });
+                                }
+                                _ => {
+                                    ui.label(egui::RichText::new("Doctrine unknown").weak())
+                                        .on_hover_text(format!(
+                                            "A network of {:.0}% reports what the faction is up to",
+                                            DOCTRINE_TELLS_NETWORK * 100.0
+                                        ));
+                                }
+                            }
+                            if known.setback_days > 0.0 {
+                                ui.colored_label(
+                                    palette.status(Status::Good),
+                                    format!("Shipyards down {:.0} d", known.setback_days),
+                                );
+                            } else if faction.hostility > 0.0 {
+                                ui.colored_label(palette.status(Status::Warning), "Hostile")
+                                    .on_hover_text(format!(
+                                        "About {:.1} sabotage attempts and raids per year",
+                                        faction.hostility
+                                    ));
+                            } else {
+                                ui.label("");
+                            }
+                            ui.end_row();
+                        }
+                    });
+                ui.separator();
+
+                ui.heading("Covert Operations");
+                let selected = state
+                    .faction
+                    .as_deref()
+                    .map_or("Select a faction".to_string(), faction_name);
+                ui.horizontal(|ui| {
+                    egui::ComboBox::from_id_source("intel_faction")
+                        .selected_text(selected)
+                        .show_ui(ui, |ui| {
+                            for faction in data.iter() {
+                                ui.selectable_value(
+                                    &mut state.faction,
+                                    Some(faction.id.clone()),
+                                    &faction.name,
+                                );
+                            }
+                        });
+                    egui::ComboBox::from_id_source("intel_operation")
+                        .selected_text(state.kind.name())
+                        .show_ui(ui, |ui| {
+                            for kind in OperationKind::ALL {
+                                ui.selectable_value(&mut state.kind, kind, kind.name())
+                                    .on_hover_text(kind.description());
+                            }
+                        });
+                });
+                if let Some(faction) = state.faction.as_ref().and_then(|id| data.get(id)) {
+                    let kind = state.kind;
+                    let network = intel.network(&faction.id);
+                    let skill = intel
+                        .agents
+                        .iter()
+                        .filter(|a| a.assignment == AgentAssignment::Idle)
+                        .map(|a| a.skill)
+                        .fold(0.0, f64::max);
+                    ui.label(egui::RichText::new(kind.description()).weak());
+                    ui.label(format!(
+                        "{}, {:.0} days, {:.0}% success with the best idle agent",
+                        format_currency(kind.cost()),
+                        kind.days(),
+                        success_chance(kind, faction, skill, network) * 100.0
+                    ));
+                    if network < kind.required_network() {
+                        ui.colored_label(
+                            palette.status(Status::Warning),
+                            format!(
+                                "Waits for a network of {:.0}% (now {:.0}%)",
+                                kind.required_network() * 100.0,
+                                network * 100.0
+                            ),
+                        );
+                    }
+                    if ui.button("Queue").clicked() {
+                        player_commands.send(PlayerCommand::QueueOperation {
+                            kind,
+                            faction: faction.id.clone(),
+                        });
+                    }
+                }
+
+                if !intel.active.is_empty() {
+                    ui.add_space(6.0);
+                    ui.label(egui::RichText::new("Running").strong());
+                    for operation in &intel.active {
+                        let agent = operation
+                            .agent
+                            .and_then(|id| intel.agent(id))
+                            .map_or("?", |a| a.name.as_str());
+                        ui.horizontal(|ui| {
+                            ui.label(format!(
+                                "{} against {} ({})",
+                                operation.kind.name(),
+                                faction_name(&operation.faction),
+                                agent
+                            ));
+                            ui.add(
+                                egui::ProgressBar::new(operation.progress_fraction() as f32)
+                                    .desired_width(120.0)
+                                    .show_percentage(),
+                            );
+                        });
+                    }
+                }
+                if !intel.queue.is_empty() {
+                    ui.add_space(6.0);
+                    ui.label(egui::RichText::new("Queued").strong());
+                    for (index, operation) in intel.queue.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.label(format!(
+                                "{}. {} against {}",
+                                index + 1,
+                                operation.kind.name(),
+                                faction_name(&operation.faction)
+                            ));
+                            if ui.small_button("Cancel").clicked() {
+                                player_commands.send(PlayerCommand::CancelOperation(index));
+                            }
+                        });
+                    }
+                }
+                ui.separator();
+
+                sensor_contacts_section(ui, &palette, &contacts, hotseat_view.as_deref());
+                ui.separator();
+
+                anomalies_section(ui, &palette, &anomalies, anomaly_data.as_deref());
+            });
+        });
+}
+
+/// Agents' estimate of a faction's strength, closer the stronger their
+/// network inside it
+fn strength_estimate(state: &FactionState, network: f64) -> String {
+    let (colonies, _) = state.estimate(state.colonies.len() as f64, network);
+    let (output, margin) = state.estimate(state.output(), network);
+    let (warships, _) = state.estimate((state.warships + state.deployed) as f64, network);
+    let growth = state
+        .annual_growth()
+        .map(|g| format!(", growing {:+.0}% a year", g * 100.0))
+        .unwrap_or_default();
+    format!(
+        "~{:.0} colonies, output ~{:.0} ± {:.0} points a month{}, ~{:.0} warships",
+        colonies, output, margin, growth, warships
+    )
+}
@@ -1,31 +1,65 @@
 use bevy::prelude::*;
 
-/// Selection resource to track which celestial body is currently selected
+/// Most bodies that can be selected at once, and compared side by side
+pub const MAX_SELECTION: usize = 4;
+
+/// Selection resource to track which celestial bodies are currently selected.
+///
+/// A plain click selects one body; ctrl-click adds or removes bodies. The
+/// most recently added body is the primary selection, which single-body
+/// panels show.
 #[derive(Resource, Debug, Clone, Default)]
 pub struct Selection {
-    /// The currently selected entity, if any
+    /// The primary selected entity, if any
     pub selected: Option<Entity>,
+    /// Further selected entities, oldest first
+    pub others: Vec<Entity>,
 }
 
 impl Selection {
     /// Create a new empty selection
     pub fn new() -> Self {
-        Self { selected: None }
+        Self {
+            selected: None,
+            others: Vec::new(),
+        }
     }
 
-    /// Select an entity
+    /// Select an entity, replacing the whole selection
     pub fn select(&mut self, entity: Entity) {
         self.selected = Some(entity);
+        self.others.clear();
+    }
+
+    /// Add an entity to the selection as the new primary, or remove it if it
+    /// is already selected. Past `MAX_SELECTION` the oldest entity is dropped
+    /// and returned.
+    pub fn toggle(&mut self, entity: Entity) -> Option<Entity> {
+        if self.is_selected(entity) {
+            self.remove(entity);
+            return None;
+        }
+        self.others.extend(self.selected.replace(entity));
+        (self.count() > MAX_SELECTION).then(|| self.others.remove(0))
+    }
+
+    /// Remove an entity; the newest remaining one becomes the primary
+    pub fn remove(&mut self, entity: Entity) {
+        self.others.retain(|e| *e != entity);
+        if self.selected == Some(entity) {
+            self.selected = self.others.pop();
+        }
     }
 
     /// Clear the selection
     pub fn clear(&mut self) {
         self.selected = None;
+        self.others.clear();
     }
 
     /// Check if an entity is selected
     pub fn is_selected(&self, entity: Entity) -> bool {
-        self.selected == Some(entity)
+        self.selected == Some(entity) || self.others.contains(&entity)
     }
 
     /// Check if anything is selected
@@ -33,10 +67,76 @@ impl Selection {
         self.selected.is_some()
     }
 
-    /// Get the selected entity
+    /// Get the primary selected entity
     pub fn get(&self) -> Option<Entity> {
         self.selected
     }
+
+    /// Number of selected entities
+    pub fn count(&self) -> usize {
+        self.others.len() + usize::from(self.selected.is_some())
+    }
+
+    /// Every selected entity, oldest first and the primary last
+    pub fn all(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.others.iter().copied().chain(self.selected)
+    }
+
+    /// Match the selection to the `selected` entities, keeping the order of
+    /// those already selected and adding the rest as newer. Returns the
+    /// entities dropped past `MAX_SELECTION`; the selection is only written
+    /// when it changes.
+    pub fn sync(&mut self, selected: &[Entity]) -> Vec<Entity> {
+        let mut order: Vec<Entity> = self.all().filter(|e| selected.contains(e)).collect();
+        order.extend(selected.iter().filter(|e| !self.is_selected(**e)));
+        let dropped = order
+            .drain(..order.len().saturating_sub(MAX_SELECTION))
+            .collect();
+
+        if !order.iter().copied().eq(self.all()) {
+            self.selected = order.pop();
+            self.others = order;
+        }
+        dropped
+    }
+}
+
+/// Action the player can take on a body from its context menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyActionKind {
+    AnchorCamera,
+    OpenDetails,
+    QueueSurvey,
+    EstablishColony,
+    ToggleWatch,
+}
+
+impl BodyActionKind {
+    pub const ALL: [BodyActionKind; 5] = [
+        BodyActionKind::AnchorCamera,
+        BodyActionKind::OpenDetails,
+        BodyActionKind::QueueSurvey,
+        BodyActionKind::EstablishColony,
+        BodyActionKind::ToggleWatch,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            BodyActionKind::AnchorCamera => "⚓ Anchor camera",
+            BodyActionKind::OpenDetails => "🔍 Open details",
+            BodyActionKind::QueueSurvey => "🛰 Queue survey",
+            BodyActionKind::EstablishColony => "🏠 Establish colony",
+            BodyActionKind::ToggleWatch => "👁 Add to watch list",
+        }
+    }
+}
+
+/// Sent when the player picks an action for a body, e.g. from the
+/// right-click menu in the 3D view
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BodyAction {
+    pub body: Entity,
+    pub kind: BodyActionKind,
 }
 
 #[cfg(test)]
@@ -70,4 +170,58 @@ mod tests {
 
         assert!(!selection.has_selection());
     }
+
+    #[test]
+    fn test_selection_toggle_adds_and_removes() {
+        let mut selection = Selection::new();
+        let [a, b, c] = [1, 2, 3].map(Entity::from_raw);
+
+        selection.select(a);
+        assert_eq!(selection.toggle(b), None);
+        assert_eq!(selection.get(), Some(b));
+        assert_eq!(selection.count(), 2);
+        assert!(selection.is_selected(a));
+
+        // Removing the primary falls back to the newest remaining body
+        selection.toggle(c);
+        selection.toggle(c);
+        assert_eq!(selection.get(), Some(b));
+        assert_eq!(selection.all().collect::<Vec<_>>(), vec![a, b]);
+
+        // A plain select replaces everything
+        selection.select(c);
+        assert_eq!(selection.count(), 1);
+    }
+
+    #[test]
+    fn test_selection_is_capped() {
+        let mut selection = Selection::new();
+        for index in 0..MAX_SELECTION as u32 {
+            assert_eq!(selection.toggle(Entity::from_raw(index)), None);
+        }
+        let extra = Entity::from_raw(99);
+        assert_eq!(selection.toggle(extra), Some(Entity::from_raw(0)));
+        assert_eq!(selection.count(), MAX_SELECTION);
+        assert_eq!(selection.get(), Some(extra));
+    }
+
+    #[test]
+    fn test_selection_sync_keeps_order() {
+        let mut selection = Selection::new();
+        let [a, b, c] = [1, 2, 3].map(Entity::from_raw);
+        selection.select(a);
+        selection.toggle(b);
+
+        // `c` was selected in the viewport and `a` deselected
+        assert!(selection.sync(&[c, b]).is_empty());
+        assert_eq!(selection.all().collect::<Vec<_>>(), vec![b, c]);
+
+        let many: Vec<Entity> = (10..16).map(Entity::from_raw).collect();
+        let dropped = selection.sync(&many);
+        assert_eq!(dropped, many[..many.len() - MAX_SELECTION].to_vec());
+        assert_eq!(selection.count(), MAX_SELECTION);
+
+        selection.sync(&[]);
+        assert!(!selection.has_selection());
+    }
 }
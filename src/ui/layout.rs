@@ -0,0 +1,288 @@
+//! Dockable panel layout
+//!
+//! The ledger, selection panel and time controls are shown through
+//! [`DockPanel`], which renders them either docked to a screen edge
+//! (resizable and collapsible to a thin strip) or detached into a floating
+//! window. The per-panel state lives in [`PanelLayouts`] and is saved with
+//! the rest of the [`UiSettings`](super::UiSettings).
+
+use bevy_egui::egui;
+use serde::{Deserialize, Serialize};
+
+/// Width (or height, for the bottom panel) of a collapsed panel strip
+const COLLAPSED_STRIP_SIZE: f32 = 26.0;
+
+/// Screen edge a panel docks to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanelSide {
+    Left,
+    Right,
+    Bottom,
+}
+
+impl PanelSide {
+    /// Arrow pointing towards the edge, used on the collapse button
+    fn collapse_icon(&self) -> &'static str {
+        match self {
+            PanelSide::Left => "◀",
+            PanelSide::Right => "▶",
+            PanelSide::Bottom => "▼",
+        }
+    }
+
+    /// Arrow pointing away from the edge, used on the expand button
+    fn expand_icon(&self) -> &'static str {
+        match self {
+            PanelSide::Left => "▶",
+            PanelSide::Right => "◀",
+            PanelSide::Bottom => "▲",
+        }
+    }
+}
+
+/// Persisted state of one panel
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PanelLayout {
+    /// Shrunk to a strip with only an expand button
+    pub collapsed: bool,
+    /// Shown as a floating window instead of docked
+    pub detached: bool,
+    /// Docked width (side panels) or height (bottom panel) in points
+    pub size: f32,
+}
+
+impl PanelLayout {
+    pub fn docked(size: f32) -> Self {
+        Self {
+            collapsed: false,
+            detached: false,
+            size,
+        }
+    }
+}
+
+/// Layout of every dockable panel
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PanelLayouts {
+    /// Left ledger (body tree, star systems)
+    pub ledger: PanelLayout,
+    /// Right panel (selected body or star system)
+    pub selection: PanelLayout,
+    /// Bottom time controls
+    pub time_controls: PanelLayout,
+}
+
+impl Default for PanelLayouts {
+    fn default() -> Self {
+        Self {
+            ledger: PanelLayout::docked(200.0),
+            selection: PanelLayout::docked(300.0),
+            time_controls: PanelLayout::docked(80.0),
+        }
+    }
+}
+
+/// A panel that can be docked, collapsed or detached
+pub struct DockPanel<'a> {
+    id: &'static str,
+    title: &'a str,
+    side: PanelSide,
+    min_size: f32,
+    max_size: f32,
+}
+
+impl<'a> DockPanel<'a> {
+    pub fn new(id: &'static str, title: &'a str, side: PanelSide) -> Self {
+        Self {
+            id,
+            title,
+            side,
+            min_size: 60.0,
+            max_size: f32::INFINITY,
+        }
+    }
+
+    /// Limit the docked width or height
+    pub fn size_range(mut self, min_size: f32, max_size: f32) -> Self {
+        self.min_size = min_size;
+        self.max_size = max_size;
+        self
+    }
+
+    /// Show the panel according to `layout`, updating it from the controls
+    /// and the docked size. Returns `None` while collapsed.
+    pub fn show<R>(
+        self,
+        ctx: &egui::Context,
+        layout: &mut PanelLayout,
+        add_contents: impl FnOnce(&mut egui::Ui) -> R,
+    ) -> Option<R> {
+        if layout.detached {
+            return self.show_window(ctx, layout, add_contents);
+        }
+        if layout.collapsed {
+            self.show_collapsed(ctx, layout);
+            return None;
+        }
+
+        let mut collapse = false;
+        let mut detach = false;
+        let side = self.side;
+        let contents = |ui: &mut egui::Ui| {
+            ui.horizontal(|ui| {
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.small_button("⧉").on_hover_text("Detach").clicked() {
+                        detach = true;
+                    }
+                    if ui
+                        .small_button(side.collapse_icon())
+                        .on_hover_text("Collapse")
+                        .clicked()
+                    {
+                        collapse = true;
+                    }
+                });
+            });
+            add_contents(ui)
+        };
+
+        let size = layout.size.clamp(self.min_size, self.max_size);
+        let response = match self.side {
+            PanelSide::Left | PanelSide::Right => {
+                let panel = if self.side == PanelSide::Left {
+                    egui::SidePanel::left(self.id)
+                } else {
+                    egui::SidePanel::right(self.id)
+                };
+                panel
+                    .resizable(true)
+                    .default_width(size)
+                    .width_range(self.min_size..=self.max_size)
+                    .show(ctx, contents)
+            }
+            PanelSide::Bottom => egui::TopBottomPanel::bottom(self.id)
+                .resizable(true)
+                .default_height(size)
+                .height_range(self.min_size..=self.max_size)
+                .show(ctx, contents),
+        };
+
+        let rect = response.response.rect;
+        let shown_size = match self.side {
+            PanelSide::Left | PanelSide::Right => rect.width(),
+            PanelSide::Bottom => rect.height(),
+        };
+        // Ignore sub-point jitter so the settings are not rewritten every frame
+        if (shown_size - layout.size).abs() >= 1.0 {
+            layout.size = shown_size.round();
+        }
+        layout.collapsed = collapse;
+        layout.detached = detach;
+        Some(response.inner)
+    }
+
+    fn show_collapsed(&self, ctx: &egui::Context, layout: &mut PanelLayout) {
+        let id = egui::Id::new(self.id).with("collapsed");
+        let mut expand = false;
+        let mut button = |ui: &mut egui::Ui| {
+            if ui
+                .small_button(self.side.expand_icon())
+                .on_hover_text(format!("Expand {}", self.title))
+                .clicked()
+            {
+                expand = true;
+            }
+        };
+        match self.side {
+            PanelSide::Left => {
+                egui::SidePanel::left(id)
+                    .resizable(false)
+                    .exact_width(COLLAPSED_STRIP_SIZE)
+                    .show(ctx, button);
+            }
+            PanelSide::Right => {
+                egui::SidePanel::right(id)
+                    .resizable(false)
+                    .exact_width(COLLAPSED_STRIP_SIZE)
+                    .show(ctx, button);
+            }
+            PanelSide::Bottom => {
+                egui::TopBottomPanel::bottom(id)
+                    .resizable(false)
+                    .exact_height(COLLAPSED_STRIP_SIZE)
+                    .show(ctx, |ui| {
+                        ui.horizontal(|ui| {
+                            button(ui);
+                            ui.label(egui::RichText::new(self.title).weak());
+                        });
+                    });
+            }
+        }
+        if expand {
+            layout.collapsed = false;
+        }
+    }
+
+    fn show_window<R>(
+        &self,
+        ctx: &egui::Context,
+        layout: &mut PanelLayout,
+        add_contents: impl FnOnce(&mut egui::Ui) -> R,
+    ) -> Option<R> {
+        let mut open = true;
+        let mut dock = false;
+        let default_size = match self.side {
+            PanelSide::Left | PanelSide::Right => egui::vec2(layout.size, 500.0),
+            PanelSide::Bottom => egui::vec2(700.0, layout.size),
+        };
+        let response = egui::Window::new(self.title)
+            .id(egui::Id::new(self.id).with("window"))
+            .open(&mut open)
+            .resizable(true)
+            .default_size(default_size)
+            .show(ctx, |ui| {
+                if ui.small_button("📌 Dock").clicked() {
+                    dock = true;
+                }
+                ui.separator();
+                add_contents(ui)
+            });
+        // Closing a detached window docks it again rather than hiding it
+        if dock || !open {
+            layout.detached = false;
+        }
+        response.and_then(|r| r.inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_defaults_fill_missing_panels() {
+        let layouts: PanelLayouts =
+            ron::from_str("(ledger: (collapsed: true, detached: false, size: 250.0))").unwrap();
+        assert!(layouts.ledger.collapsed);
+        assert_eq!(layouts.ledger.size, 250.0);
+        assert_eq!(layouts.selection, PanelLayouts::default().selection);
+    }
+
+    #[test]
+    fn test_collapsed_panel_expands_on_click_only() {
+        let ctx = egui::Context::default();
+        let mut layout = PanelLayout {
+            collapsed: true,
+            ..PanelLayout::docked(200.0)
+        };
+        let mut shown = false;
+        let _ = ctx.run(egui::RawInput::default(), |ctx| {
+            shown = DockPanel::new("test_panel", "Test", PanelSide::Left)
+                .show(ctx, &mut layout, |_| ())
+                .is_some();
+        });
+        assert!(!shown);
+        assert!(layout.collapsed);
+    }
+}
@@ -0,0 +1,181 @@
+//! Maintenance report: upkeep, the condition of facilities and fleets and
+//! the repair queue, opened from the resources bar
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use super::accessibility::{self, Palette, Status};
+use crate::construction::OrbitalStation;
+use crate::economy::{
+    Condition, MaintenanceReport, RepairQueue, BREAKDOWN_CONDITION, REPAIR_CONDITION,
+};
+use crate::fleets::Fleet;
+use crate::plugins::solar_system::{CelestialBody, LogicalParent};
+
+/// Worn facilities and fleets listed, worst first
+const MAX_LISTED: usize = 25;
+
+/// Whether the maintenance report is shown
+#[derive(Resource, Debug, Clone, Default)]
+pub struct MaintenanceWindow {
+    pub open: bool,
+}
+
+/// Condition bar, amber below the repair line and red once broken down
+fn condition_bar(ui: &mut egui::Ui, palette: &Palette, condition: &Condition) {
+    let status = if condition.is_broken() {
+        Status::Bad
+    } else if condition.value < REPAIR_CONDITION {
+        Status::Warning
+    } else {
+        Status::Good
+    };
+    ui.add(
+        egui::ProgressBar::new(condition.value as f32)
+            .desired_width(120.0)
+            .fill(palette.status(status))
+            .text(palette.status_text(status, format!("{:.0}%", condition.value * 100.0))),
+    );
+}
+
+/// Render the maintenance report
+#[allow(clippy::type_complexity)]
+pub(super) fn ui_maintenance_window(
+    mut contexts: EguiContexts,
+    mut window: ResMut<MaintenanceWindow>,
+    report: Res<MaintenanceReport>,
+    queue: Res<RepairQueue>,
+    worn: Query<(
+        Entity,
+        &Condition,
+        Option<&Name>,
+        Option<&Fleet>,
+        Option<&LogicalParent>,
+    )>,
+    places: Query<(Option<&CelestialBody>, Option<&OrbitalStation>)>,
+) {
+    if !window.open {
+        return;
+    }
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    let palette = accessibility::palette(ctx);
+
+    let name_of = |entity: Entity| -> String {
+        if let Ok((_, _, name, fleet, _)) = worn.get(entity) {
+            if let Some(fleet) = fleet {
+                return fleet.name.clone();
+            }
+            if let Some(name) = name {
+                return name.as_str().to_string();
+            }
+        }
+        match places.get(entity) {
+            Ok((Some(body), _)) => body.name.clone(),
+            Ok((None, Some(station))) => station.name.clone(),
+            _ => "Unknown".to_string(),
+        }
+    };
+
+    let mut listed: Vec<_> = worn
+        .iter()
+        .filter(|(_, condition, ..)| condition.value < 1.0)
+        .collect();
+    listed.sort_by(|a, b| a.1.value.total_cmp(&b.1.value));
+    let broken = listed.iter().filter(|(_, c, ..)| c.is_broken()).count();
+
+    let mut open = true;
+    egui::Window::new("🔧 Maintenance")
+        .id(egui::Id::new("maintenance_window"))
+        .open(&mut open)
+        .default_width(420.0)
+        .show(ctx, |ui| {
+            ui.label(format!(
+                "Spare parts: {:.2} Mt of Steel per year",
+                report.spare_parts_per_year
+            ));
+            if report.parts_short {
+                ui.colored_label(
+                    palette.status(Status::Bad),
+                    palette.status_text(
+                        Status::Bad,
+                        "Not enough Steel for spare parts: everything is wearing out",
+                    ),
+                );
+            }
+            if report.crews_unpaid {
+                ui.colored_label(
+                    palette.status(Status::Bad),
+                    palette.status_text(
+                        Status::Bad,
+                        "Maintenance crews go unpaid beyond the credit limit",
+                    ),
+                );
+            }
+            ui.label(
+                egui::RichText::new(format!(
+                    "Below {:.0}% repairs are queued; below {:.0}% facilities stop and \
+                     fleets cannot leave orbit.",
+                    REPAIR_CONDITION * 100.0,
+                    BREAKDOWN_CONDITION * 100.0
+                ))
+                .weak(),
+            );
+            ui.separator();
+
+            ui.label(
+                egui::RichText::new(format!("Repair queue ({})", queue.repairs.len())).strong(),
+            );
+            if queue.repairs.is_empty() {
+                ui.label(egui::RichText::new("Nothing to repair").weak());
+            }
+            for repair in &queue.repairs {
+                let status = match repair.location {
+                    None => "waiting for a colony to orbit".to_string(),
+                    Some(_) if !repair.started => "waiting for its turn or materials".to_string(),
+                    Some(_) => format!("{:.0} days left", repair.days_left.max(0.0)),
+                };
+                let at = repair
+                    .location
+                    .filter(|location| *location != repair.entity)
+                    .map_or(String::new(), |location| {
+                        format!(" at {}", name_of(location))
+                    });
+                ui.label(format!("{}{} — {}", name_of(repair.entity), at, status));
+            }
+            ui.separator();
+
+            ui.label(egui::RichText::new(format!("Worn ({} broken down)", broken)).strong());
+            if listed.is_empty() {
+                ui.label(egui::RichText::new("Everything is in good repair").weak());
+                return;
+            }
+            egui::ScrollArea::vertical()
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("maintenance_worn")
+                        .num_columns(3)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for (entity, condition, _, fleet, parent) in
+                                listed.iter().take(MAX_LISTED)
+                            {
+                                ui.label(name_of(*entity));
+                                let place = match (fleet, parent) {
+                                    (Some(_), _) => "Fleet".to_string(),
+                                    (None, Some(parent)) => name_of(parent.0),
+                                    _ => String::new(),
+                                };
+                                ui.label(egui::RichText::new(place).weak());
+                                condition_bar(ui, &palette, condition);
+                                ui.end_row();
+                            }
+                        });
+                });
+        });
+
+    if !open {
+        window.open = false;
+    }
+}
@@ -14,29 +14,102 @@ use bevy::asset::Handle;
 use bevy::render::texture::Image;
 use std::collections::HashMap;
 
+pub mod accessibility;
+mod achievements;
+mod anomalies;
+mod cinematic;
+mod colonies;
+mod command_palette;
+mod comparison;
+mod console;
+mod construction;
+mod context_menu;
+mod delta_v_map;
+mod demographics;
+mod diplomacy;
+mod event_log;
+mod fleets;
+mod game_over;
+mod history;
+mod hotseat;
+mod intel;
 pub mod interaction;
-
-pub use interaction::Selection;
+pub mod layout;
+mod maintenance;
+mod mods;
+mod new_game;
+mod orbit_sandbox;
+mod performance;
+mod policies;
+mod production;
+mod resource_trends;
+mod random_events;
+mod research_teams;
+mod route_planner;
+mod sensor_contacts;
+pub mod settings;
+mod shipyards;
+mod starmap_view;
+mod surface_map;
+mod tech_layout;
+mod time_warp;
+pub mod toasts;
+mod tooltip;
+mod trade;
+mod treasury;
+mod tutorial;
+mod watch_list;
+
+pub use accessibility::{AccessibilitySettings, ColorPalette, Palette, Status, TechStatus};
+pub use achievements::AchievementsWindow;
+pub use cinematic::CinematicWindow;
+pub use comparison::ComparisonWindow;
+pub use context_menu::BodyContextMenu;
+pub use event_log::EventLogWindow;
+pub use history::HistoryWindow;
+pub use interaction::{BodyAction, BodyActionKind, Selection};
+pub use layout::{DockPanel, PanelLayout, PanelLayouts, PanelSide};
+pub use maintenance::MaintenanceWindow;
+pub use mods::ModListWindow;
+pub use new_game::NewGameScreen;
+pub use orbit_sandbox::OrbitSandbox;
+pub use production::ProductionWindow;
+pub use resource_trends::ResourceTrendsWindow;
+pub use settings::{UiSettings, UiSettingsWindow};
+pub use time_warp::{Completion, TimeWarp, UpcomingEvent};
+pub use toasts::Toasts;
+pub use tooltip::TooltipField;
+pub use treasury::TreasuryWindow;
+pub use watch_list::WatchList;
 
 use crate::astronomy::components::{CurrentStarSystem, SystemId};
 use crate::astronomy::nearby_stars::NearbyStarsData;
-use crate::astronomy::{AtmosphereComposition, Hovered, KeplerOrbit, Selected, SpaceCoordinates};
+use crate::astronomy::rotation::format_day_length;
+use crate::astronomy::{ExoplanetDiscovery, LocalDay, ProceduralBody, ProceduralStars};
+use crate::astronomy::{AtmosphereComposition, KeplerOrbit, Selected, SpaceCoordinates};
+use crate::commands::PlayerCommand;
 use crate::economy::components::{Population, SurveyLevel};
+use crate::economy::deposit_map::is_mappable;
+use crate::economy::survey::{survey_days, SurveyMissions};
 use crate::economy::{
-    format_power, GlobalBudget, PlanetResources, PowerSourceType, ResourceRateTracker, ResourceType,
+    format_currency, format_power, ActiveAlerts, AlertSettings, GlobalBudget, PlanetResources, PowerGrids, PowerSourceType, Reservations, ResourceRateTracker,
+    ResourceType, Treasury,
 };
 use crate::game_state::{ActiveMenu, GameMenu};
 use crate::plugins::camera::{CameraAnchor, GameCamera, ViewMode};
 use crate::plugins::solar_system::{CelestialBody, LogicalParent};
 use crate::plugins::solar_system_data::BodyType;
-use crate::plugins::starmap::{HoveredStarSystem, SelectedStarSystem, StarSystemIcon};
+use crate::plugins::starmap::{
+    galactic_coordinates, HoveredStarSystem, SelectedStarSystem, StarSystemIcon, LY_TO_AU,
+};
+use crate::plugins::system_streaming::StreamedSystems;
 use crate::research::{
     EngineeringProject, ResearchProject, ResearchState, ResearchTeam, ResearchTeamCapacity,
-    TechnologiesData, TechCategory, TechTreeEditState, TechEditData, ContextMenuState,
+    ReverseEngineeringProject, TechnologiesData, TechCategory, TechTreeEditState, TechEditData, ContextMenuState,
 };
 
 /// Maximum time scale: 1 year per second (365.25 * 86400 ≈ 31,557,600)
-const MAX_TIME_SCALE: f32 = 31_557_600.0;
+pub const MAX_TIME_SCALE: f32 = 31_557_600.0;
 
 /// Loaded textures for the top menu icons
 #[derive(Resource)]
@@ -58,6 +131,10 @@ fn load_menu_icons(mut commands: Commands, asset_server: Res<AssetServer>) {
     for &menu in GameMenu::all() {
         // File names follow the game's convention, e.g. "main.png", "starmap.png"
         let filename = format!("textures/ui/menu/{}.png", menu.asset_basename());
+        // Menus without an icon yet fall back to a text button
+        if !std::path::Path::new("assets").join(&filename).exists() {
+            continue;
+        }
         let handle: Handle<Image> = asset_server.load(&filename);
         map.insert(menu, handle);
     }
@@ -292,6 +369,12 @@ impl SimulationTime {
         self.start_timestamp + self.elapsed as i64
     }
 
+    /// Move the clock to a later date. The start date is kept, so orbits
+    /// computed from the elapsed time stay on the ephemeris.
+    pub fn set_current_timestamp(&mut self, timestamp: i64) {
+        self.elapsed = (timestamp - self.start_timestamp).max(0) as f64;
+    }
+
     /// Format the current date/time as DD.MM.YYYY HH:MM
     pub fn format_date_time(&self) -> String {
         let timestamp = self.current_timestamp();
@@ -391,6 +474,25 @@ impl Plugin for UIPlugin {
             .init_resource::<Selection>()
             .init_resource::<TimeScale>()
             .init_resource::<SimulationTime>()
+            .init_resource::<TimeWarp>()
+            .insert_resource(UiSettings::load(settings::SETTINGS_PATH))
+            .init_resource::<UiSettingsWindow>()
+            .init_resource::<ModListWindow>()
+            .init_resource::<NewGameScreen>()
+            .init_resource::<Toasts>()
+            .init_resource::<ResourceTrendsWindow>()
+            .init_resource::<ProductionWindow>()
+            .init_resource::<MaintenanceWindow>()
+            .init_resource::<TreasuryWindow>()
+            .init_resource::<EventLogWindow>()
+            .init_resource::<HistoryWindow>()
+            .init_resource::<AchievementsWindow>()
+            .init_resource::<CinematicWindow>()
+            .init_resource::<ComparisonWindow>()
+            .init_resource::<BodyContextMenu>()
+            .init_resource::<WatchList>()
+            .init_resource::<OrbitSandbox>()
+            .add_event::<BodyAction>()
             // ActiveMenu is now initialized in GameStatePlugin
             // to allow access in camera/starmap plugins
             // Load menu icons at startup
@@ -405,14 +507,56 @@ impl Plugin for UIPlugin {
                 (
                     ui_resources_bar,
                     ui_top_menu_bar,
-                    (ui_dashboard, ui_research_panels),
                     (
-                        ui_hover_tooltip,
+                        (ui_dashboard, ui_time_controls).chain(),
+                        ui_research_panels,
+                        construction::ui_construction_window,
+                        colonies::ui_colonies_window,
+                        trade::ui_trade_window,
+                        policies::ui_policies_window,
+                        (intel::ui_intel_window, diplomacy::ui_diplomacy_window),
+                        (fleets::ui_fleets_window, shipyards::ui_shipyards_window),
+                        resource_trends::ui_resource_trends_window,
+                        (
+                            production::ui_production_window,
+                            maintenance::ui_maintenance_window,
+                        ),
+                        treasury::ui_treasury_window,
+                        event_log::ui_event_log_window,
+                        history::ui_history_window,
+                        achievements::ui_achievements_window,
+                        cinematic::ui_cinematic_window,
+                        comparison::ui_comparison_window,
+                        (
+                            surface_map::ui_surface_map_window,
+                            delta_v_map::ui_delta_v_map_window,
+                        ),
+                        route_planner::ui_route_planner_window,
+                        starmap_view::ui_starmap_view_window,
+                        watch_list::ui_watch_list_window,
+                    ),
+                    (
+                        tooltip::ui_hover_tooltip,
                         ui_starmap_hover_tooltip,
                         ui_starmap_labels,
+                        context_menu::ui_body_context_menu,
                     ),
+                    command_palette::ui_command_palette,
+                    console::ui_console,
+                    settings::ui_settings_window,
+                    mods::ui_mod_list_window,
+                    random_events::ui_random_event_dialog,
+                    tutorial::ui_objectives_panel,
+                    new_game::ui_new_game_screen,
+                    hotseat::ui_hotseat_turns,
+                    game_over::ui_game_over_screen,
+                    orbit_sandbox::ui_orbit_sandbox_window,
+                    performance::ui_performance_hud,
+                    toasts::ui_toasts,
                 )
-                    .chain(),
+                    .chain()
+                    // The cinematic camera hides the interface
+                    .run_if(cinematic::ui_visible),
             )
             // UI utility systems
             .add_systems(
@@ -420,27 +564,38 @@ impl Plugin for UIPlugin {
                 (
                     sync_selection_with_astronomy,
                     sync_active_menu_with_view_mode,
+                    context_menu::open_body_context_menu,
+                    context_menu::apply_body_actions.before(crate::commands::apply_player_commands),
+                    time_warp::update_time_warp.before(advance_simulation_time),
                     advance_simulation_time,
                     process_menu_icons,
                     process_research_icons,
+                    settings::apply_ui_scale,
+                    performance::toggle_performance_hud,
+                    orbit_sandbox::toggle_orbit_sandbox,
+                    accessibility::apply_accessibility,
+                    settings::save_ui_settings,
                 ),
             );
     }
 } 
 
-/// System that syncs the UI selection with the astronomy Selected component
+/// System that syncs the UI selection with the astronomy Selected component.
+/// Bodies past the multi-select limit lose their `Selected` component.
 fn sync_selection_with_astronomy(
+    mut commands: Commands,
     mut selection: ResMut<Selection>,
     selected_query: Query<Entity, (With<Selected>, With<CelestialBody>)>,
 ) {
-    // If something is selected in astronomy, update UI selection
-    if let Ok(entity) = selected_query.get_single() {
-        if !selection.is_selected(entity) {
-            selection.select(entity);
-        }
-    } else if selection.has_selection() {
-        // If nothing is selected in astronomy, clear UI selection
-        selection.clear();
+    let selected: Vec<Entity> = selected_query.iter().collect();
+    let mut synced = selection.clone();
+    let dropped = synced.sync(&selected);
+    // Only write on a change so panels reading the selection stay idle
+    if !synced.all().eq(selection.all()) {
+        *selection = synced;
+    }
+    for entity in dropped {
+        commands.entity(entity).remove::<Selected>();
     }
 }
 
@@ -477,7 +632,7 @@ fn sync_active_menu_with_view_mode(
 ///
 /// Uses real (wall-clock) delta to avoid Bevy's virtual-time max-delta cap,
 /// which previously limited effective speed to ~15×.
-fn advance_simulation_time(
+pub fn advance_simulation_time(
     real_time: Res<Time<Real>>,
     time_scale: Res<TimeScale>,
     mut sim_time: ResMut<SimulationTime>,
@@ -539,18 +694,11 @@ fn get_resource_icon(resource: &ResourceType) -> &'static str {
     }
 }
 
-/// Get color for resource category
-fn get_category_color(category: &str) -> egui::Color32 {
-    match category {
-        "Volatiles" => egui::Color32::from_rgb(100, 200, 255),       // Water Blue
-        "Atmospheric Gases" => egui::Color32::from_rgb(200, 230, 255), // Air White/Blue
-        "Construction" => egui::Color32::from_rgb(205, 127, 50),     // Bronze/Rust property
-        "Fusion Fuel" => egui::Color32::from_rgb(255, 100, 200),     // Plasma/Energy Pink
-        "Fissiles" => egui::Color32::from_rgb(100, 255, 100),        // Radioactive Green
-        "Precious Metals" => egui::Color32::from_rgb(255, 215, 0),   // Gold
-        "Specialty" => egui::Color32::from_rgb(200, 100, 255),       // Exotic Purple
-        _ => egui::Color32::LIGHT_GRAY,
-    }
+
+
+/// Brightness (0..1) of pulsing alert icons at egui time `time`
+fn alert_pulse(time: f64) -> f32 {
+    (0.5 + 0.5 * (time * 4.0).sin()) as f32
 }
 
 /// Resource popup that is currently open (if any)
@@ -561,21 +709,62 @@ struct OpenResourcePopup {
 }
 
 /// Render the resources bar at the top of the screen (above the menu)
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 fn ui_resources_bar(
     mut contexts: EguiContexts,
     budget: Res<GlobalBudget>,
+    power_grids: Res<PowerGrids>,
     rate_tracker: Res<ResourceRateTracker>,
     research_state: Res<ResearchState>,
-    population_query: Query<(&Population, Option<&crate::plugins::solar_system::CelestialBody>)>,
+    population_query: Query<(
+        &Population,
+        Option<&crate::plugins::solar_system::CelestialBody>,
+        Option<&Name>,
+    )>,
     mut open_popup: Local<OpenResourcePopup>,
+    mut trends_window: ResMut<ResourceTrendsWindow>,
+    alerts: Res<ActiveAlerts>,
+    mut alert_settings: ResMut<AlertSettings>,
+    mut log_window: ResMut<EventLogWindow>,
+    mut history_window: ResMut<HistoryWindow>,
+    mut achievements_window: ResMut<AchievementsWindow>,
+    mut cinematic_window: ResMut<CinematicWindow>,
+    mut production_window: ResMut<ProductionWindow>,
+    (
+        treasury,
+        mut treasury_window,
+        tech_data,
+        mut maintenance_window,
+        reservations,
+        places,
+        mut player_commands,
+    ): (
+        Res<Treasury>,
+        ResMut<TreasuryWindow>,
+        Res<TechnologiesData>,
+        ResMut<MaintenanceWindow>,
+        Res<Reservations>,
+        Query<(
+            Option<&CelestialBody>,
+            Option<&crate::construction::OrbitalStation>,
+        )>,
+        EventWriter<PlayerCommand>,
+    ),
 ) {
     let ctx = match contexts.try_ctx_mut() {
         Some(ctx) => ctx,
         None => return,
     };
 
+    let palette = accessibility::palette(ctx);
+    let pulse = alert_pulse(ctx.input(|i| i.time));
+    let alert_color = palette.status(Status::Bad).gamma_multiply(0.4 + 0.6 * pulse);
+    if !alerts.alerts.is_empty() {
+        ctx.request_repaint();
+    }
+
     // Calculate total population
-    let total_population: f64 = population_query.iter().map(|(p, _)| p.count).sum();
+    let total_population: f64 = population_query.iter().map(|(p, _, _)| p.count).sum();
 
     egui::TopBottomPanel::top("resources_bar")
         .min_height(40.0)
@@ -589,11 +778,13 @@ fn ui_resources_bar(
                     let category_total: f64 =
                         resources.iter().map(|r| budget.get_stockpile(r)).sum();
                     let category_rate: f64 =
-                        resources.iter().map(|r| rate_tracker.get_resource_rate(r)).sum();
+                        resources.iter().map(|r| rate_tracker.get_net_rate(r)).sum();
 
                     let icon = get_resource_category_icon(category_name);
-                    let color = get_category_color(category_name);
+                    let color = palette.category(category_name);
                     let text_color = egui::Color32::from_rgb(220, 220, 220);
+                    let shortfall = resources.iter().any(|r| alerts.has_shortfall(r));
+                    let icon_color = if shortfall { alert_color } else { color };
 
                     let is_this_open = open_popup.open.as_ref().map_or(false, |(n, _)| n == category_name);
 
@@ -602,7 +793,7 @@ fn ui_resources_bar(
                         .inner_margin(egui::Margin::symmetric(5.0, 2.0))
                         .show(ui, |ui| {
                             ui.horizontal_centered(|ui| {
-                                ui.add(egui::Label::new(egui::RichText::new(icon).size(20.0).color(color)).selectable(false));
+                                ui.add(egui::Label::new(egui::RichText::new(icon).size(20.0).color(icon_color)).selectable(false));
                                 ui.vertical(|ui| {
                                     ui.add(egui::Label::new(egui::RichText::new(format_mass(category_total)).size(14.0).color(text_color)).selectable(false));
                                     let (rate_text, rate_color) = format_rate_monthly(category_rate);
@@ -702,28 +893,63 @@ fn ui_resources_bar(
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     ui.add_space(10.0);
 
-                    // Kardashev scale calculation (based on total power)
+                    // Kardashev scale (based on total power)
                     // type I: 10^16 W, Type II: 10^26 W. Scale is logarithmic.
-                    // K = (log10(Power_in_Watts) - 6) / 10 is the Carl Sagan formula.
-                    let produced_watts = budget.energy_grid.produced.max(1.0); // avoid log(0) or negative
-                    let kardashev = (produced_watts.log10() - 6.0) / 10.0;
-                    
-                    ui.add(egui::Label::new(egui::RichText::new(format!(
-                        "Type {:.3}",
-                        kardashev.max(0.0)
-                    )).size(14.0).color(egui::Color32::from_rgb(200, 100, 255))).selectable(false));
-                    
-                    ui.add(egui::Label::new(egui::RichText::new("Kardashev:").size(14.0).color(egui::Color32::LIGHT_GRAY)).selectable(false));
+                    let kardashev = budget.kardashev_index();
+                    let milestone = crate::economy::current_milestone(research_state.kardashev_reached);
+                    let kardashev_color = kardashev_badge_color(research_state.kardashev_reached);
+                    let is_kardashev_open = open_popup
+                        .open
+                        .as_ref()
+                        .is_some_and(|(n, _)| n == "Kardashev");
+
+                    // Kardashev badge (clickable with the progression panel)
+                    let response = egui::Frame::none()
+                        .inner_margin(egui::Margin::symmetric(5.0, 2.0))
+                        .show(ui, |ui| {
+                            ui.add(egui::Label::new(egui::RichText::new(format!(
+                                "Type {:.3}",
+                                kardashev
+                            )).size(14.0).color(kardashev_color)).selectable(false));
+                            if let Some(milestone) = milestone {
+                                ui.add(egui::Label::new(egui::RichText::new(milestone.title).size(14.0).strong().color(kardashev_color)).selectable(false));
+                            }
+                            ui.add(egui::Label::new(egui::RichText::new("Kardashev:").size(14.0).color(egui::Color32::LIGHT_GRAY)).selectable(false));
+                        })
+                        .response;
+
+                    let interact = response.interact(egui::Sense::click());
+
+                    if interact.hovered() || is_kardashev_open {
+                        ui.painter()
+                            .rect_stroke(interact.rect, 2.0, egui::Stroke::new(1.0, kardashev_color));
+                        interact
+                            .clone()
+                            .on_hover_cursor(egui::CursorIcon::PointingHand);
+                    }
+
+                    if interact.clicked() {
+                        if is_kardashev_open {
+                            open_popup.open = None;
+                        } else {
+                            open_popup.open = Some(("Kardashev".to_string(), interact.rect));
+                        }
+                    }
 
                     ui.separator();
 
                     // Power grid status
-                    // Color code power: Green if surplus, Red if deficit
+                    // Color code power: Green if surplus, Red if any location is in deficit
                     let net_power = budget.net_power();
-                    let power_color = if net_power >= 0.0 {
-                        egui::Color32::GREEN
+                    let power_status = if net_power >= 0.0 && !power_grids.has_deficit() {
+                        Status::Good
                     } else {
-                        egui::Color32::RED
+                        Status::Bad
+                    };
+                    let power_color = if power_status == Status::Bad && alerts.has_power_alert() {
+                        alert_color
+                    } else {
+                        palette.status(power_status)
                     };
 
                     let is_power_open = open_popup
@@ -739,7 +965,10 @@ fn ui_resources_bar(
                                 egui::Label::new(
                                     egui::RichText::new(format!(
                                         "⚡ {}",
-                                        format_power(budget.energy_grid.produced)
+                                        palette.status_text(
+                                            power_status,
+                                            format_power(budget.energy_grid.produced)
+                                        )
                                     ))
                                     .size(14.0)
                                     .strong()
@@ -820,6 +1049,102 @@ fn ui_resources_bar(
                             open_popup.open = Some(("Population".to_string(), pop_interact.rect));
                         }
                     }
+
+                    ui.separator();
+
+                    // Treasury balance
+                    let balance_status = if treasury.over_credit_limit() {
+                        Status::Bad
+                    } else if treasury.in_debt() {
+                        Status::Warning
+                    } else {
+                        Status::Good
+                    };
+                    let balance_text = format!("💳 {}", format_currency(treasury.balance));
+                    if ui
+                        .add(
+                            egui::Button::new(
+                                egui::RichText::new(balance_text)
+                                    .size(14.0)
+                                    .color(palette.status(balance_status)),
+                            )
+                            .frame(false),
+                        )
+                        .on_hover_text(format!(
+                            "Treasury: {}/yr income, {}/yr expenses",
+                            format_currency(treasury.income_per_year),
+                            format_currency(treasury.expenses_per_year)
+                        ))
+                        .clicked()
+                    {
+                        treasury_window.open = !treasury_window.open;
+                    }
+
+                    ui.separator();
+
+                    // Event log and alerts
+                    if ui
+                        .add(egui::Button::new(egui::RichText::new("📜").size(16.0)).frame(false))
+                        .on_hover_text("Event log")
+                        .clicked()
+                    {
+                        log_window.open = !log_window.open;
+                    }
+                    if ui
+                        .add(egui::Button::new(egui::RichText::new("🏭").size(16.0)).frame(false))
+                        .on_hover_text("Production chains")
+                        .clicked()
+                    {
+                        production_window.open = !production_window.open;
+                    }
+                    if ui
+                        .add(egui::Button::new(egui::RichText::new("🔧").size(16.0)).frame(false))
+                        .on_hover_text("Maintenance and repairs")
+                        .clicked()
+                    {
+                        maintenance_window.open = !maintenance_window.open;
+                    }
+                    if ui
+                        .add(egui::Button::new(egui::RichText::new("📊").size(16.0)).frame(false))
+                        .on_hover_text("History")
+                        .clicked()
+                    {
+                        history_window.open = !history_window.open;
+                    }
+                    if ui
+                        .add(egui::Button::new(egui::RichText::new("🏆").size(16.0)).frame(false))
+                        .on_hover_text("Achievements")
+                        .clicked()
+                    {
+                        achievements_window.open = !achievements_window.open;
+                    }
+                    if ui
+                        .add(egui::Button::new(egui::RichText::new("🎬").size(16.0)).frame(false))
+                        .on_hover_text("Camera bookmarks and cinematic tour")
+                        .clicked()
+                    {
+                        cinematic_window.open = !cinematic_window.open;
+                    }
+                    let (alert_text, alert_text_color) = if alerts.alerts.is_empty() {
+                        ("⚠ 0".to_string(), egui::Color32::GRAY)
+                    } else {
+                        (format!("⚠ {}", alerts.alerts.len()), alert_color)
+                    };
+                    let alert_response = ui
+                        .add(
+                            egui::Button::new(
+                                egui::RichText::new(alert_text).size(16.0).color(alert_text_color),
+                            )
+                            .frame(false),
+                        )
+                        .on_hover_text("Alerts");
+                    if alert_response.clicked() {
+                        if open_popup.open.as_ref().is_some_and(|(n, _)| n == "Alerts") {
+                            open_popup.open = None;
+                        } else {
+                            open_popup.open = Some(("Alerts".to_string(), alert_response.rect));
+                        }
+                    }
                 });
             });
         });
@@ -831,11 +1156,12 @@ fn ui_resources_bar(
             let mut still_open = true;
             // Determine color from budget - recalculate here
             let net_power = budget.net_power();
-            let power_color = if net_power >= 0.0 {
-                egui::Color32::GREEN
+            let power_status = if net_power >= 0.0 && !power_grids.has_deficit() {
+                Status::Good
             } else {
-                egui::Color32::RED
+                Status::Bad
             };
+            let power_color = palette.status(power_status);
 
             let window_response = egui::Window::new("Power Breakdown")
                 .id(egui::Id::new("power_breakdown_window"))
@@ -885,6 +1211,56 @@ fn ui_resources_bar(
                             ui.add(egui::Label::new(egui::RichText::new(format_power(budget.energy_grid.produced)).strong().color(power_color)).selectable(false));
                         });
                     });
+                    if budget.energy_grid.storage_capacity > 0.0 {
+                        ui.horizontal(|ui| {
+                            ui.add(egui::Label::new("Stored").selectable(false));
+                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                ui.add(egui::Label::new(format!(
+                                    "{:.1} / {:.1} GWh",
+                                    budget.energy_grid.stored / 3.6e12,
+                                    budget.energy_grid.storage_capacity / 3.6e12
+                                )).selectable(false));
+                            });
+                        });
+                    }
+
+                    // Per-location grids: each colony has to balance on its own
+                    let grids = power_grids.sorted();
+                    if !grids.is_empty() {
+                        ui.separator();
+                        ui.add(egui::Label::new(egui::RichText::new("By Location").strong()).selectable(false));
+                        for (_, grid) in grids {
+                            let grid_status = if grid.is_browned_out() {
+                                Status::Bad
+                            } else if grid.is_sufficient() {
+                                Status::Good
+                            } else {
+                                Status::Warning
+                            };
+                            let grid_color = palette.status(grid_status);
+                            ui.horizontal(|ui| {
+                                ui.add(egui::Label::new(&grid.name).selectable(false));
+                                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                    ui.add(egui::Label::new(egui::RichText::new(palette.status_text(grid_status, format!(
+                                        "{} / {}",
+                                        format_power(grid.produced + grid.relayed),
+                                        format_power(grid.consumed)
+                                    ))).strong().color(grid_color)).selectable(false));
+                                });
+                            })
+                            .response
+                            .on_hover_text(format!(
+                                "Produced: {}\nDemand: {}\nRelayed: {}{}\nStored: {:.1} / {:.1} GWh{}",
+                                format_power(grid.produced),
+                                format_power(grid.consumed),
+                                if grid.relayed < 0.0 { "-" } else { "+" },
+                                format_power(grid.relayed.abs()),
+                                grid.stored / 3.6e12,
+                                grid.storage_capacity / 3.6e12,
+                                if grid.is_browned_out() { "\nBrowned out: low priority tiers are throttled" } else { "" },
+                            ));
+                        }
+                    }
                 });
 
             // Close if clicked outside
@@ -898,6 +1274,90 @@ fn ui_resources_bar(
                 }
             }
 
+            if !still_open {
+                open_popup.open = None;
+            }
+        } else if cat_name == "Kardashev" {
+            let reached = research_state.kardashev_reached;
+            let kardashev_color = kardashev_badge_color(reached);
+            let mut still_open = true;
+            let window_response = egui::Window::new("Kardashev Progression")
+                .id(egui::Id::new("kardashev_progression_window"))
+                .fixed_pos(egui::pos2(anchor_rect.left(), anchor_rect.bottom() + 2.0))
+                .collapsible(false)
+                .resizable(false)
+                .title_bar(false)
+                .open(&mut still_open)
+                .frame(egui::Frame::popup(ctx.style().as_ref()))
+                .show(ctx, |ui| {
+                    ui.set_min_width(280.0);
+                    ui.add(egui::Label::new(egui::RichText::new("Kardashev Progression").size(16.0).strong().color(kardashev_color)).selectable(false));
+                    ui.label(format!(
+                        "Type {:.3} from {}",
+                        budget.kardashev_index(),
+                        format_power(budget.energy_grid.produced)
+                    ));
+                    ui.separator();
+
+                    for milestone in crate::economy::KARDASHEV_MILESTONES {
+                        let is_reached = milestone.index <= reached;
+                        let status = if is_reached { Status::Good } else { Status::Bad };
+                        ui.horizontal(|ui| {
+                            ui.label(
+                                egui::RichText::new(palette.status_text(
+                                    status,
+                                    format!("{:.1} {}", milestone.index, milestone.title),
+                                ))
+                                .strong()
+                                .color(palette.status(status)),
+                            );
+                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                ui.label(format_power(crate::economy::power_for_index(milestone.index)));
+                            });
+                        });
+                        ui.label(egui::RichText::new(milestone.description).weak());
+                        let unlocks: Vec<&str> = tech_data
+                            .technologies
+                            .values()
+                            .filter(|tech| tech.required_kardashev == Some(milestone.index))
+                            .map(|tech| tech.name.as_str())
+                            .collect();
+                        if !unlocks.is_empty() {
+                            ui.label(format!("Unlocks: {}", unlocks.join(", ")));
+                        }
+                        ui.add_space(4.0);
+                    }
+
+                    ui.separator();
+                    match crate::economy::next_milestone(reached) {
+                        Some(next) => {
+                            // Progress on the logarithmic scale since the last milestone
+                            let from = crate::economy::current_milestone(reached).map_or(0.0, |m| m.index);
+                            let progress = ((budget.kardashev_index() - from) / (next.index - from)).clamp(0.0, 1.0);
+                            let needed = crate::economy::power_for_index(next.index);
+                            ui.label(format!(
+                                "Next: {} needs {} ({} more)",
+                                next.title,
+                                format_power(needed),
+                                format_power((needed - budget.energy_grid.produced).max(0.0))
+                            ));
+                            ui.add(egui::ProgressBar::new(progress as f32).text(format!("Type {:.1}", next.index)));
+                        }
+                        None => {
+                            ui.label("Every milestone reached");
+                        }
+                    }
+                });
+
+            if let Some(inner_response) = window_response {
+                if ctx.input(|i| i.pointer.any_pressed()) {
+                    if let Some(pos) = ctx.input(|i| i.pointer.interact_pos()) {
+                        if !inner_response.response.rect.contains(pos) && !anchor_rect.contains(pos) {
+                            open_popup.open = None;
+                        }
+                    }
+                }
+            }
             if !still_open {
                 open_popup.open = None;
             }
@@ -922,10 +1382,12 @@ fn ui_resources_bar(
                     // Collect and sort populations
                     let mut pops: Vec<(String, f64)> = population_query
                         .iter()
-                        .filter(|(p, _)| p.count > 0.0)
-                        .map(|(p, body)| {
+                        .filter(|(p, _, _)| p.count > 0.0)
+                        .map(|(p, body, entity_name)| {
                             let name = if let Some(b) = body {
                                 b.name.clone()
+                            } else if let Some(n) = entity_name {
+                                n.to_string()
                             } else {
                                 "Unknown".to_string()
                             };
@@ -1061,6 +1523,47 @@ fn ui_resources_bar(
                     });
                 });
 
+            if let Some(inner_response) = window_response {
+                if ctx.input(|i| i.pointer.any_pressed()) {
+                    if let Some(pos) = ctx.input(|i| i.pointer.interact_pos()) {
+                        if !inner_response.response.rect.contains(pos) && !anchor_rect.contains(pos) {
+                            open_popup.open = None;
+                        }
+                    }
+                }
+            }
+            if !still_open {
+                open_popup.open = None;
+            }
+        } else if cat_name == "Alerts" {
+            let mut still_open = true;
+            let window_response = egui::Window::new("Alerts")
+                .id(egui::Id::new("alerts_window"))
+                .fixed_pos(egui::pos2(anchor_rect.left(), anchor_rect.bottom() + 2.0))
+                .collapsible(false)
+                .resizable(false)
+                .title_bar(false)
+                .open(&mut still_open)
+                .frame(egui::Frame::popup(ctx.style().as_ref()))
+                .show(ctx, |ui| {
+                    ui.set_min_width(260.0);
+                    ui.add(egui::Label::new(egui::RichText::new("⚠ Alerts").size(16.0).strong()).selectable(false));
+                    ui.separator();
+                    if alerts.alerts.is_empty() {
+                        ui.add(egui::Label::new(egui::RichText::new("All clear").weak()).selectable(false));
+                    }
+                    for alert in &alerts.alerts {
+                        ui.add(egui::Label::new(egui::RichText::new(&alert.message).color(egui::Color32::from_rgb(255, 200, 80))).selectable(false));
+                    }
+                    ui.separator();
+                    ui.add(
+                        egui::Slider::new(&mut alert_settings.depletion_horizon_days, 30.0..=730.0)
+                            .step_by(30.0)
+                            .suffix(" days")
+                            .text("Warn before depletion"),
+                    );
+                });
+
             if let Some(inner_response) = window_response {
                 if ctx.input(|i| i.pointer.any_pressed()) {
                     if let Some(pos) = ctx.input(|i| i.pointer.interact_pos()) {
@@ -1078,7 +1581,7 @@ fn ui_resources_bar(
             .find(|(name, _)| *name == cat_name.as_str())
         {
             let icon = get_resource_category_icon(&cat_name);
-            let color = get_category_color(&cat_name);
+            let color = palette.category(cat_name);
 
             let mut still_open = true;
             let window_response = egui::Window::new(cat_name.as_str())
@@ -1090,6 +1593,21 @@ fn ui_resources_bar(
                 .open(&mut still_open)
                 .frame(egui::Frame::popup(ctx.style().as_ref()))
                 .show(ctx, |ui| {
+                    // Colonies and stations holding stock, by name
+                    let mut depots: Vec<(String, &crate::economy::Depot)> = budget
+                        .depots
+                        .iter()
+                        .map(|(entity, depot)| {
+                            let name = match places.get(*entity) {
+                                Ok((Some(body), _)) => body.name.clone(),
+                                Ok((None, Some(station))) => station.name.clone(),
+                                _ => "Unknown".to_string(),
+                            };
+                            (name, depot)
+                        })
+                        .collect();
+                    depots.sort_by(|a, b| a.0.cmp(&b.0));
+
                     ui.set_min_width(280.0);
                     ui.horizontal(|ui| {
                         ui.add(egui::Label::new(egui::RichText::new(icon).size(18.0).color(color)).selectable(false));
@@ -1102,28 +1620,87 @@ fn ui_resources_bar(
                         ui.add_space(24.0); // icon space
                         ui.add(egui::Label::new(egui::RichText::new("Resource").strong()).selectable(false));
                         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            ui.add(egui::Label::new(egui::RichText::new("  /mo").strong().size(11.0)).selectable(false));
+                            ui.add(egui::Label::new(egui::RichText::new("Used /mo").strong().size(11.0)).selectable(false));
+                            ui.add_space(10.0);
+                            ui.add(egui::Label::new(egui::RichText::new("Mined /mo").strong().size(11.0)).selectable(false));
                             ui.add_space(10.0);
-                            ui.add(egui::Label::new(egui::RichText::new("Stockpile").strong().size(11.0)).selectable(false));
+                            ui.add(egui::Label::new(egui::RichText::new("Reserved").strong().size(11.0)).selectable(false));
+                            ui.add_space(10.0);
+                            ui.add(egui::Label::new(egui::RichText::new("Free").strong().size(11.0)).selectable(false));
                         });
                     });
 
                     for resource in &resources {
                         let amount = budget.get_stockpile(resource);
-                        let rate = rate_tracker.get_resource_rate(resource);
+                        let production = rate_tracker.get_resource_rate(resource);
+                        let consumption = rate_tracker.get_consumption_rate(resource);
                         ui.horizontal(|ui| {
                             ui.add(egui::Label::new(egui::RichText::new(get_resource_icon(resource)).size(16.0)).selectable(false));
                             ui.add(egui::Label::new(resource.display_name()).selectable(false));
                             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                                // Monthly rate
-                                let (rt, rc) = format_rate_monthly(rate);
-                                ui.add(egui::Label::new(egui::RichText::new(rt).size(11.0).color(rc)).selectable(false));
+                                // Monthly consumption and production
+                                let (ct, cc) = format_rate_monthly(-consumption);
+                                ui.add(egui::Label::new(egui::RichText::new(ct).size(11.0).color(cc)).selectable(false));
+                                ui.add_space(10.0);
+                                let (pt, pc) = format_rate_monthly(production);
+                                ui.add(egui::Label::new(egui::RichText::new(pt).size(11.0).color(pc)).selectable(false));
                                 ui.add_space(10.0);
-                                // Stockpile
-                                ui.add(egui::Label::new(egui::RichText::new(format_mass(amount)).strong()).selectable(false));
+                                // Stockpile, split into what queued projects earmarked and the rest
+                                let reserved = reservations.reserved(resource);
+                                let reserved_text = if reserved > 0.0 { format_mass(reserved) } else { "—".to_string() };
+                                ui.add(egui::Label::new(egui::RichText::new(reserved_text).size(11.0).weak()).selectable(false));
+                                ui.add_space(10.0);
+                                let mut breakdown = format!("In stock: {}", format_mass(amount));
+                                for (name, depot) in depots.iter().filter(|(_, d)| d.get(resource) > 0.0) {
+                                    breakdown.push_str(&format!("\n{}: {}", name, format_mass(depot.get(resource))));
+                                }
+                                breakdown.push_str(&format!("\nCentral reserve: {}", format_mass(budget.central_stockpile(resource))));
+                                ui.add(egui::Label::new(egui::RichText::new(format_mass(reservations.free(&budget, resource))).strong()).selectable(false))
+                                    .on_hover_text(breakdown);
                             });
                         });
                     }
+
+                    // Where the category is stored
+                    ui.separator();
+                    egui::CollapsingHeader::new("By location")
+                        .id_source(format!("res_locations_{}", cat_name))
+                        .show(ui, |ui| {
+                            let located = |stock: &dyn Fn(&ResourceType) -> f64| -> f64 {
+                                resources.iter().map(stock).sum()
+                            };
+                            egui::Grid::new(format!("res_locations_grid_{}", cat_name))
+                                .num_columns(2)
+                                .striped(true)
+                                .show(ui, |ui| {
+                                    for (name, depot) in &depots {
+                                        let held = located(&|r| depot.get(r));
+                                        if held > 0.0 {
+                                            ui.label(name.as_str());
+                                            ui.label(format_mass(held));
+                                            ui.end_row();
+                                        }
+                                    }
+                                    ui.label("Central reserve");
+                                    ui.label(format_mass(located(&|r| budget.central_stockpile(r))));
+                                    ui.end_row();
+                                });
+                        });
+
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        if ui.button("📈 Trends").clicked() {
+                            trends_window.show_resources(&resources);
+                        }
+                        let mut enabled = reservations.enabled;
+                        if ui
+                            .checkbox(&mut enabled, "Reserve for queued projects")
+                            .on_hover_text("Queued buildings and hulls earmark their cost so later orders cannot stall them")
+                            .changed()
+                        {
+                            player_commands.send(PlayerCommand::Reservations(enabled));
+                        }
+                    });
                 });
 
             // Close if clicked outside
@@ -1147,6 +1724,20 @@ fn ui_resources_bar(
     }
 }
 
+/// Colour of the Kardashev badge, brighter with every milestone reached
+fn kardashev_badge_color(reached: f64) -> egui::Color32 {
+    let milestones = crate::economy::KARDASHEV_MILESTONES
+        .iter()
+        .filter(|milestone| milestone.index <= reached)
+        .count();
+    match milestones {
+        0 => egui::Color32::from_rgb(160, 120, 200),
+        1 => egui::Color32::from_rgb(200, 100, 255),
+        2 => egui::Color32::from_rgb(230, 140, 255),
+        _ => egui::Color32::from_rgb(255, 215, 120),
+    }
+}
+
 fn format_population(count: f64) -> String {
     if count < 1_000.0 {
         return format!("{:.0}", count);
@@ -1167,6 +1758,7 @@ fn ui_top_menu_bar(
     mut view_mode: ResMut<ViewMode>,
     menu_icons: Option<Res<MenuIcons>>,
     mut icon_textures: Local<HashMap<GameMenu, egui::TextureId>>,
+    tutorial: Option<Res<crate::tutorial::Tutorial>>,
 ) {
     // Convert loaded handles to egui TextureIds before creating the UI context.
     // We cache the TextureIds in a Local<HashMap> so that `add_image` is called
@@ -1193,6 +1785,8 @@ fn ui_top_menu_bar(
         None => return,
     };
 
+    let highlighted = tutorial.and_then(|t| t.highlighted_menu());
+
     egui::TopBottomPanel::top("top_menu_bar")
         .show(ctx, |ui| {
             ui.horizontal(|ui| {
@@ -1225,6 +1819,10 @@ fn ui_top_menu_bar(
                                 ui.painter().rect_stroke(rect, 4.0, egui::Stroke::new(2.0, egui::Color32::from_rgb(100, 200, 255)));
                             }
 
+                            if highlighted == Some(menu) {
+                                tutorial::paint_tutorial_highlight(ui, resp.rect);
+                            }
+
                             let resp = resp.on_hover_text(menu.name());
                             if resp.clicked() {
                                 active_menu.current = menu;
@@ -1252,7 +1850,11 @@ fn ui_top_menu_bar(
                                 .fill(egui::Color32::from_rgb(30, 30, 35))
                             };
 
-                            if ui.add(button).clicked() {
+                            let resp = ui.add(button);
+                            if highlighted == Some(menu) {
+                                tutorial::paint_tutorial_highlight(ui, resp.rect);
+                            }
+                            if resp.clicked() {
                                 active_menu.current = menu;
                                 match menu {
                                     GameMenu::Starmap => *view_mode = ViewMode::Starmap,
@@ -1279,7 +1881,11 @@ fn ui_top_menu_bar(
                             .fill(egui::Color32::from_rgb(30, 30, 35))
                         };
 
-                        if ui.add(button).clicked() {
+                        let resp = ui.add(button);
+                        if highlighted == Some(menu) {
+                            tutorial::paint_tutorial_highlight(ui, resp.rect);
+                        }
+                        if resp.clicked() {
                             active_menu.current = menu;
                             match menu {
                                 GameMenu::Starmap => *view_mode = ViewMode::Starmap,
@@ -1350,6 +1956,33 @@ fn render_selectable_label(ui: &mut egui::Ui, is_selected: bool, name: &str) ->
     }
 }
 
+/// Select a body clicked in the ledger. Ctrl-click adds it to the selection,
+/// or removes it if it is already selected.
+fn select_from_ledger(
+    ui: &egui::Ui,
+    entity: Entity,
+    selection: &mut Selection,
+    commands: &mut Commands,
+    selected_query: &Query<Entity, With<Selected>>,
+) {
+    if ui.input(|i| i.modifiers.command) {
+        if selection.is_selected(entity) {
+            commands.entity(entity).remove::<Selected>();
+        } else {
+            commands.entity(entity).insert(Selected);
+        }
+        if let Some(dropped) = selection.toggle(entity) {
+            commands.entity(dropped).remove::<Selected>();
+        }
+        return;
+    }
+    for e in selected_query.iter() {
+        commands.entity(e).remove::<Selected>();
+    }
+    commands.entity(entity).insert(Selected);
+    selection.select(entity);
+}
+
 fn render_body_row(
     ui: &mut egui::Ui,
     entity: Entity,
@@ -1382,11 +2015,7 @@ fn render_body_row(
 
         // Use a visually distinct style for selected items
         if render_selectable_label(ui, is_selected, &body.name).clicked() {
-            for e in selected_query.iter() {
-                commands.entity(e).remove::<Selected>();
-            }
-            commands.entity(entity).insert(Selected);
-            selection.select(entity);
+            select_from_ledger(ui, entity, selection, commands, selected_query);
         }
     });
 }
@@ -1498,11 +2127,7 @@ fn render_body_tree(
 
                 // Use a visually distinct style for selected items
                 if render_selectable_label(ui, is_selected, &body.name).clicked() {
-                    for e in selected_query.iter() {
-                        commands.entity(e).remove::<Selected>();
-                    }
-                    commands.entity(entity).insert(Selected);
-                    selection.select(entity);
+                    select_from_ledger(ui, entity, selection, commands, selected_query);
                 }
             })
             .body(|ui| {
@@ -1595,71 +2220,12 @@ fn render_body_tree(
     }
 }
 
-/// System that displays a tooltip for hovered celestial bodies
-fn ui_hover_tooltip(
-    mut contexts: EguiContexts,
-    hovered_query: Query<&CelestialBody, With<Hovered>>,
-    active_menu: Res<ActiveMenu>,
-) {
-    // Don't show world tooltips when a full-screen overlay is active
-    if active_menu.current.blocks_world_interaction() {
-        return;
-    }
-
-    let ctx = match contexts.try_ctx_mut() {
-        Some(ctx) => ctx,
-        None => return,
-    };
-
-    // Display hover tooltip if a body is hovered
-    if let Ok(body) = hovered_query.get_single() {
-        // Anchor the tooltip near the mouse pointer so it appears over the 3D view
-        let tooltip_pos = ctx
-            .input(|i| i.pointer.hover_pos())
-            .map(|p| egui::pos2(p.x + 12.0, p.y + 12.0))
-            .unwrap_or(egui::pos2(100.0, 100.0));
-
-        egui::Area::new("hover_tooltip".into())
-            .fixed_pos(tooltip_pos)
-            .interactable(false)
-            .order(egui::Order::Tooltip)
-            .show(ctx, |ui| {
-                ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
-                egui::Frame::none()
-                    .fill(egui::Color32::from_rgba_unmultiplied(30, 30, 30, 240))
-                    .stroke(egui::Stroke::new(
-                        2.0,
-                        egui::Color32::from_rgb(100, 180, 255),
-                    ))
-                    .inner_margin(12.0)
-                    .show(ui, |ui| {
-                        // Use horizontal layout to prevent narrow wrapping
-                        ui.horizontal(|ui| {
-                            ui.label(
-                                egui::RichText::new(&body.name)
-                                    .size(16.0)
-                                    .color(egui::Color32::from_rgb(150, 220, 255))
-                                    .strong(),
-                            );
-                        });
-
-                        ui.horizontal(|ui| {
-                            ui.label(
-                                egui::RichText::new(format!("Type: {:?}", body.body_type))
-                                    .size(12.0)
-                                    .color(egui::Color32::from_rgb(180, 180, 180)),
-                            );
-                        });
-                    });
-            });
-    }
-}
-
 /// Display hover tooltip for star systems in starmap view
 fn ui_starmap_hover_tooltip(
     mut contexts: EguiContexts,
     hovered_query: Query<&StarSystemIcon, With<HoveredStarSystem>>,
     bodies_query: Query<(&CelestialBody, &SystemId)>,
+    streamed: Option<Res<StreamedSystems>>,
     view_mode: Res<ViewMode>,
     active_menu: Res<ActiveMenu>,
 ) {
@@ -1686,14 +2252,22 @@ fn ui_starmap_hover_tooltip(
             .map(|p| egui::pos2(p.x + 12.0, p.y + 12.0))
             .unwrap_or(egui::pos2(100.0, 100.0));
 
-        // Count bodies in this system
+        // Count bodies in this system, or in its summary if it was collapsed
         let body_count = bodies_query
             .iter()
             .filter(|(_, sys_id)| sys_id.0 == icon.id)
-            .count();
+            .count()
+            .max(
+                streamed
+                    .as_ref()
+                    .and_then(|streamed| streamed.get(icon.id))
+                    .map_or(0, |summary| summary.body_count()),
+            );
 
         // Calculate distance from Sol
-        let distance_ly = icon.position.length() / 63241.077; // AU to light years
+        let distance_ly = icon.position.length() / LY_TO_AU;
+        let (longitude, latitude) = galactic_coordinates(icon.position);
+        let height_ly = icon.position.y / LY_TO_AU;
 
         egui::Area::new(format!("starmap_hover_{}", icon.id).into())
             .fixed_pos(tooltip_pos)
@@ -1726,6 +2300,17 @@ fn ui_starmap_hover_tooltip(
                             );
                         });
 
+                        ui.horizontal(|ui| {
+                            ui.label(
+                                egui::RichText::new(format!(
+                                    "Galactic l {:.1}°, b {:+.1}° ({:+.2} ly from plane)",
+                                    longitude, latitude, height_ly
+                                ))
+                                .size(12.0)
+                                .color(egui::Color32::from_rgb(180, 180, 180)),
+                            );
+                        });
+
                         if body_count > 0 {
                             ui.horizontal(|ui| {
                                 ui.label(
@@ -1807,28 +2392,29 @@ fn format_points_rate_monthly(value: f64) -> (String, egui::Color32) {
 }
 
 /// Main UI dashboard system
-#[allow(clippy::too_many_arguments)]
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 fn ui_dashboard(
     mut commands: Commands,
     mut contexts: EguiContexts,
     // budget: Res<GlobalBudget>, // Moved to ui_resources_bar
-    mut time_scale: ResMut<TimeScale>,
-    sim_time: Res<SimulationTime>,
     mut selection: ResMut<Selection>,
-    view_mode: Res<ViewMode>,
     current_system: Res<CurrentStarSystem>,
-    nearby_stars: Res<NearbyStarsData>,
+    (nearby_stars, procedural_stars): (Res<NearbyStarsData>, Res<ProceduralStars>),
     active_menu: Res<ActiveMenu>,
     // Query for selected body information
-    mut body_query: Query<(
+    body_query: Query<(
         &CelestialBody,
         &SpaceCoordinates,
         Option<&KeplerOrbit>,
         Option<&PlanetResources>,
         Option<&AtmosphereComposition>,
-        Option<&mut SurveyLevel>,
+        Option<&SurveyLevel>,
         Option<&Population>,
         Option<&crate::astronomy::SurfaceTemperature>,
+        Option<&ExoplanetDiscovery>,
+        Has<ProceduralBody>,
+        Option<&LocalDay>,
+        Option<&crate::economy::Radiation>,
     )>,
     // Resource query for system totals
     resource_query: Query<(&SystemId, &PlanetResources)>,
@@ -1844,6 +2430,17 @@ fn ui_dashboard(
     // Starmap queries
     star_system_query: Query<(Entity, &StarSystemIcon, Option<&SelectedStarSystem>)>,
     mut anchor_query: Query<&mut CameraAnchor, With<GameCamera>>,
+    survey_missions: Res<SurveyMissions>,
+    mut ui_settings: ResMut<UiSettings>,
+    mut settings_window: ResMut<UiSettingsWindow>,
+    // Player commands: replay saving, surveys and founding stations in orbital slots
+    (mut replay_recorder, mut player_commands, research_state, stations, hotseat_view): (
+        ResMut<crate::replay::ReplayRecorder>,
+        EventWriter<PlayerCommand>,
+        Res<ResearchState>,
+        Query<(Entity, &crate::construction::OrbitalStation)>,
+        Option<Res<crate::hotseat::HotseatView>>,
+    ),
 ) {
     let ctx = match contexts.try_ctx_mut() {
         Some(ctx) => ctx,
@@ -1854,10 +2451,15 @@ fn ui_dashboard(
         return;
     }
 
+    // Work on a copy so the settings only register a change when the layout
+    // actually moves
+    let mut layouts = ui_settings.layout.clone();
+
     // Ledger Panel (Left)
-    egui::SidePanel::left("ledger_panel")
-        .min_width(200.0)
-        .show(ctx, |ui| {
+    DockPanel::new("ledger_panel", "Ledger", PanelSide::Left)
+        .size_range(200.0, 600.0)
+        .show(ctx, &mut layouts.ledger, |ui| {
+            settings::apply_font_size(ui, ui_settings.ledger_font_size);
             match active_menu.current {
                 GameMenu::Starmap => {
                     // Starmap view: show list of star systems
@@ -1993,34 +2595,53 @@ fn ui_dashboard(
                             if ui.button("📂 Load Game").clicked() {
                                 info!("Load clicked");
                             }
+                            if ui
+                                .button("🎞 Save Replay")
+                                .on_hover_text(format!(
+                                    "Write the commands given so far to {}",
+                                    crate::replay::REPLAY_PATH
+                                ))
+                                .clicked()
+                            {
+                                replay_recorder.save_requested = true;
+                            }
                             if ui.button("⚙ Options").clicked() {
-                                info!("Options clicked");
+                                settings_window.open = true;
                             }
                         }
                         GameMenu::Construction => {
-                            ui.label("Construction facilities and projects will be shown here.");
+                            ui.label("Select a body to manage its facilities and construction queue.");
                         }
                         GameMenu::Research => {
                             ui.label("Research UI requires loading...");
                             ui.label("Switch to Research view to see tech tree.");
                         }
                         GameMenu::Fleets => {
-                            ui.label("Fleet management and deployment will be shown here.");
+                            ui.label("The Fleets window lists every fleet with its propellant and delta-v.");
+                            ui.label("Select a body to send a fleet there.");
                         }
                         GameMenu::Shipbuilding => {
-                            ui.label("Ship design and construction queue will be shown here.");
+                            ui.label("The Shipyards window shows every slipway and the hull queue.");
+                            ui.label("Build a shipyard at a colony or station to lay down hulls.");
                         }
                         GameMenu::Economy => {
-                            ui.label("Economic overview and private sector management will be shown here.");
+                            ui.label("The Colonies table lists every populated body and station.");
+                            ui.label("Trade agreements with other factions are signed in the Trade window.");
+                        }
+                        GameMenu::Policies => {
+                            ui.label("Enact empire-wide policies and colony edicts in the Policies window.");
+                            ui.label("Every Sociology technology researched opens another slot.");
                         }
                         GameMenu::Personnel => {
                             ui.label("Officers, managers, and personnel assignments will be shown here.");
                         }
                         GameMenu::Intel => {
-                            ui.label("Intelligence reports on enemy factions will be shown here.");
+                            ui.label("Recruit agents and queue covert operations in the Intelligence window.");
+                            ui.label("Anomalies found by surveys of other star systems are listed there too.");
                         }
                         GameMenu::Diplomacy => {
-                            ui.label("Diplomatic relations and treaties will be shown here.");
+                            ui.label("Alien civilizations detected by SETI arrays are listed in the Diplomacy window.");
+                            ui.label("Translate their signals to open relations with them.");
                         }
                         GameMenu::Starmap | GameMenu::Survey => {
                             // Already handled above
@@ -2039,24 +2660,56 @@ fn ui_dashboard(
         // Show star system details
         render_star_system_panel(
             ctx,
+            &mut layouts.selection,
             star_icon,
             &all_bodies_query,
             &resource_query,
             &nearby_stars,
+            &procedural_stars,
         );
     } else if selection.has_selection() {
         // Show selected celestial body details
-        egui::SidePanel::right("selection_panel")
-            .min_width(300.0)
-            .max_width(400.0)
-            .show(ctx, |ui| {
+        DockPanel::new("selection_panel", "Selected Body", PanelSide::Right)
+            .size_range(300.0, 400.0)
+            .show(ctx, &mut layouts.selection, |ui| {
+                settings::apply_font_size(ui, ui_settings.selection_font_size);
                 ui.heading("Selected Body");
+                if selection.count() > 1 {
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "{} bodies selected, compared in ⚖ Compare Bodies",
+                            selection.count()
+                        ))
+                        .weak(),
+                    );
+                }
                 ui.separator();
 
                 if let Some(entity) = selection.get() {
-                    if let Ok((body, coords, orbit, resources, atmosphere, mut survey_level, population, surface_temp)) = body_query.get_mut(entity) {
+                    if let Ok((body, coords, orbit, resources, atmosphere, survey_level, population, surface_temp, discovery, procedural, local_day, radiation)) = body_query.get(entity) {
+                        // A hotseat rival's colony beyond the player's sensors
+                        // shows as the bare body
+                        let hidden = hotseat_view.as_ref().is_some_and(|v| v.is_hidden(entity));
+                        let population = population.filter(|_| !hidden);
+                        let radiation = radiation.filter(|_| !hidden);
                         // Body name and basic info
                         ui.label(egui::RichText::new(&body.name).size(18.0).strong());
+                        if let Some(discovery) = discovery {
+                            let mut tag = "🔭 Confirmed exoplanet".to_string();
+                            if let Some(method) = &discovery.method {
+                                tag.push_str(&format!(" — {}", method));
+                            }
+                            if let Some(year) = discovery.year {
+                                tag.push_str(&format!(" ({})", year));
+                            }
+                            ui.label(egui::RichText::new(tag).color(egui::Color32::from_rgb(120, 220, 120)));
+                        } else if procedural {
+                            ui.label(
+                                egui::RichText::new("🎲 Procedurally generated")
+                                    .color(egui::Color32::from_rgb(150, 170, 230)),
+                            )
+                            .on_hover_text("Not in any catalog; invented to fill the system");
+                        }
                         ui.add_space(10.0);
 
                         // Position information
@@ -2073,6 +2726,11 @@ fn ui_dashboard(
                                 }
                             }
                         });
+                        if let Some(pop) = population.filter(|pop| pop.count > 0.0) {
+                            ui.group(|ui| {
+                                demographics::demographics_section(ui, &accessibility::palette(ui.ctx()), pop);
+                            });
+                        }
 
                         ui.add_space(10.0);
 
@@ -2092,6 +2750,9 @@ fn ui_dashboard(
                                 } else {
                                     ui.label(format!("Period: {:.2} years", period_days / 365.25));
                                 }
+                                if let Some(day) = local_day {
+                                    ui.label(format!("Day length: {}", format_day_length(day)));
+                                }
                             });
 
                             ui.add_space(10.0);
@@ -2134,7 +2795,34 @@ fn ui_dashboard(
                                     ui.colored_label(cost_color, format!("{:.2}", cost));
                                 }
                             });
-                            
+
+                            let habitat = crate::astronomy::habitat_class(temp_c, atmosphere);
+                            ui.horizontal(|ui| {
+                                ui.label("Habitats:");
+                                ui.label(habitat.label());
+                            });
+
+                            // Radiation is known for colonies only
+                            if let Some(radiation) = radiation {
+                                ui.horizontal(|ui| {
+                                    ui.label("Radiation:");
+                                    let text = format!("{:.2}", radiation.dose);
+                                    if radiation.is_storm() {
+                                        ui.colored_label(egui::Color32::RED, format!("{} (storm)", text));
+                                    } else if radiation.dose > crate::economy::radiation::SAFE_DOSE {
+                                        ui.colored_label(egui::Color32::YELLOW, text);
+                                    } else {
+                                        ui.label(text);
+                                    }
+                                })
+                                .response
+                                .on_hover_text(format!(
+                                    "Dose relative to open space at 1 AU from the Sun: {:.2} on the surface, {:.0}% of the colonists in shelters",
+                                    radiation.surface,
+                                    radiation.sheltered * 100.0
+                                ));
+                            }
+
                             // Temperature display (moved out of Atmosphere section so it shows for everyone)
                             ui.horizontal(|ui| {
                                 ui.label("Temperature:");
@@ -2144,6 +2832,22 @@ fn ui_dashboard(
                         
                         ui.add_space(5.0);
 
+                        // Orbital slots for stations
+                        if !hidden && body.body_type != BodyType::Star && body.body_type != BodyType::Ring {
+                            ui.group(|ui| {
+                                construction::orbital_slots_section(
+                                    ui,
+                                    entity,
+                                    body,
+                                    &research_state,
+                                    &stations,
+                                    hotseat_view.as_deref(),
+                                    &mut player_commands,
+                                );
+                            });
+                            ui.add_space(5.0);
+                        }
+
                         // Atmosphere data if available
                         if let Some(atmosphere) = atmosphere {
                             ui.group(|ui| {
@@ -2224,7 +2928,7 @@ fn ui_dashboard(
                                 ui.add_space(5.0);
                                 
                                 // Survey Controls
-                                let current_level = survey_level.as_deref().copied().unwrap_or(SurveyLevel::Unsurveyed);
+                                let current_level = survey_level.copied().unwrap_or(SurveyLevel::Unsurveyed);
                                 
                                 ui.group(|ui| {
                                     ui.horizontal(|ui| {
@@ -2238,22 +2942,45 @@ fn ui_dashboard(
                                         ui.label(egui::RichText::new(format!("{:?}", current_level)).strong().color(status_color));
                                     });
                                     
-                                    if let Some(survey) = survey_level.as_deref_mut() {
-                                        if *survey != SurveyLevel::CoreSample {
-                                            if ui.button("Upgrade Survey").clicked() {
-                                                *survey = match *survey {
-                                                    SurveyLevel::Unsurveyed => SurveyLevel::OrbitalScan,
-                                                    SurveyLevel::OrbitalScan => SurveyLevel::SeismicSurvey,
-                                                    SurveyLevel::SeismicSurvey => SurveyLevel::CoreSample,
-                                                    _ => SurveyLevel::CoreSample,
-                                                };
-                                            }
-                                        }
-                                    } else {
-                                        if ui.button("Initialize Survey System").clicked() {
-                                            commands.entity(entity).insert(SurveyLevel::OrbitalScan);
+                                    if let Some(mission) = survey_missions.mission_for(entity) {
+                                        let status = if mission.in_transit() {
+                                            format!(
+                                                "Probe en route: {:.0} / {:.0} days",
+                                                mission.elapsed_days, mission.transit_days
+                                            )
+                                        } else {
+                                            format!(
+                                                "{:?} in progress: {:.0} / {:.0} days",
+                                                mission.level,
+                                                mission.elapsed_days - mission.transit_days,
+                                                mission.survey_days
+                                            )
+                                        };
+                                        ui.add(egui::ProgressBar::new(mission.progress_fraction()).text(status));
+                                    } else if let Some(next) = current_level.next() {
+                                        if ui
+                                            .button("Launch Survey Mission")
+                                            .on_hover_text(format!(
+                                                "Send a probe to perform a {:?} ({:.0} days on site)",
+                                                next,
+                                                survey_days(body.radius, next)
+                                            ))
+                                            .clicked()
+                                        {
+                                            player_commands.send(PlayerCommand::Survey(entity.into()));
                                         }
                                     }
+
+                                    if is_mappable(current_level) && ui.button("🗺 Surface Map").clicked() {
+                                        surface_map::open_surface_map(ui.ctx(), entity);
+                                    }
+                                    if ui
+                                        .button("🧭 Delta-v Map")
+                                        .on_hover_text("Transfer costs from here to the rest of the system")
+                                        .clicked()
+                                    {
+                                        delta_v_map::open_delta_v_map(ui.ctx(), entity);
+                                    }
                                 });
                                 
                                 ui.add_space(5.0);
@@ -2352,10 +3079,35 @@ fn ui_dashboard(
             });
     }
 
-    // Bottom panel for time controls
-    egui::TopBottomPanel::bottom("time_controls")
-        .min_height(80.0)
-        .show(ctx, |ui| {
+    if layouts != ui_settings.layout {
+        ui_settings.layout = layouts;
+    }
+}
+
+/// Bottom panel with speed controls, warp to next event and the date
+#[allow(clippy::too_many_arguments)]
+fn ui_time_controls(
+    mut contexts: EguiContexts,
+    time_scale: Res<TimeScale>,
+    mut player_commands: EventWriter<PlayerCommand>,
+    warp: Res<TimeWarp>,
+    sim_time: Res<SimulationTime>,
+    view_mode: Res<ViewMode>,
+    active_menu: Res<ActiveMenu>,
+    mut ui_settings: ResMut<UiSettings>,
+) {
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    if active_menu.current == GameMenu::Research {
+        return;
+    }
+
+    let mut layout = ui_settings.layout.time_controls.clone();
+    DockPanel::new("time_controls", "Time Controls", PanelSide::Bottom)
+        .size_range(80.0, 300.0)
+        .show(ctx, &mut layout, |ui| {
+            settings::apply_font_size(ui, ui_settings.time_controls_font_size);
             ui.heading("Time Controls");
             ui.separator();
 
@@ -2363,41 +3115,72 @@ fn ui_dashboard(
                 // Pause/Resume button
                 if time_scale.is_paused() {
                     if ui.button("▶ Resume").clicked() {
-                        time_scale.resume();
+                        player_commands.send(PlayerCommand::Resume);
                     }
                 } else if ui.button("⏸ Pause").clicked() {
-                    time_scale.pause();
+                    player_commands.send(PlayerCommand::Pause);
                 }
 
                 ui.separator();
 
                 // Preset speed buttons with meaningful labels
-                if ui.button("1 hr/s").clicked() {
-                    time_scale.scale = 3_600.0;
-                }
-                if ui.button("1 day/s").clicked() {
-                    time_scale.scale = 86_400.0;
-                }
-                if ui.button("1 wk/s").clicked() {
-                    time_scale.scale = 604_800.0;
-                }
-                if ui.button("1 mo/s").clicked() {
-                    time_scale.scale = 2_592_000.0;
-                }
-                if ui.button("1 yr/s").clicked() {
-                    time_scale.scale = 31_557_600.0;
+                let presets = [
+                    ("1 hr/s", 3_600.0),
+                    ("1 day/s", 86_400.0),
+                    ("1 wk/s", 604_800.0),
+                    ("1 mo/s", 2_592_000.0),
+                    ("1 yr/s", 31_557_600.0),
+                ];
+                for (label, scale) in presets {
+                    if ui.button(label).clicked() {
+                        player_commands.send(PlayerCommand::SetTimeScale(scale));
+                    }
                 }
 
                 ui.separator();
 
                 // Logarithmic slider for fine control
                 ui.label("Speed:");
-                ui.add(
-                    egui::Slider::new(&mut time_scale.scale, 1.0..=MAX_TIME_SCALE)
+                let mut scale = time_scale.scale;
+                let slider = ui.add(
+                    egui::Slider::new(&mut scale, 1.0..=MAX_TIME_SCALE)
                         .logarithmic(true)
                         .text("")
                         .custom_formatter(|v, _| format_time_rate(v as f32)),
                 );
+                if slider.changed() {
+                    player_commands.send(PlayerCommand::SetTimeScale(scale));
+                }
+            });
+
+            ui.horizontal(|ui| {
+                if warp.active {
+                    if ui.button("⏹ Stop Warp").clicked() {
+                        player_commands.send(PlayerCommand::SetTimeScale(warp.resume_scale()));
+                    }
+                } else if ui
+                    .add_enabled(warp.next.is_some(), egui::Button::new("⏭ Warp to Next Event"))
+                    .on_disabled_hover_text("Nothing scheduled")
+                    .clicked()
+                {
+                    player_commands.send(PlayerCommand::WarpToNextEvent);
+                }
+                match &warp.next {
+                    Some(event) => {
+                        ui.label(format!(
+                            "Next: {} in {}",
+                            event.label,
+                            format_duration(event.eta_seconds)
+                        ));
+                    }
+                    None => {
+                        ui.label(egui::RichText::new("No upcoming events").weak());
+                    }
+                }
+                if let Some(fired) = &warp.last_fired {
+                    ui.separator();
+                    ui.colored_label(egui::Color32::from_rgb(120, 220, 120), format!("✔ {}", fired));
+                }
             });
 
             ui.horizontal(|ui| {
@@ -2418,11 +3201,29 @@ fn ui_dashboard(
                 ui.colored_label(view_color, view_label);
             });
         });
+
+    if layout != ui_settings.layout.time_controls {
+        ui_settings.layout.time_controls = layout;
+    }
+}
+
+/// Format a simulation duration in seconds as minutes, hours, days or years
+pub fn format_duration(seconds: f64) -> String {
+    if seconds < 3_600.0 {
+        format!("{:.0} min", seconds / 60.0)
+    } else if seconds < 86_400.0 {
+        format!("{:.1} h", seconds / 3_600.0)
+    } else if seconds < 365.25 * 86_400.0 {
+        format!("{:.0} days", (seconds / 86_400.0).ceil())
+    } else {
+        format!("{:.1} years", seconds / (365.25 * 86_400.0))
+    }
 }
 
 /// Render detailed information panel for a selected star system
 fn render_star_system_panel(
     ctx: &egui::Context,
+    layout: &mut PanelLayout,
     star_icon: &StarSystemIcon,
     bodies_query: &Query<(
         Entity,
@@ -2433,11 +3234,11 @@ fn render_star_system_panel(
     )>,
     resource_query: &Query<(&SystemId, &PlanetResources)>,
     nearby_stars: &Res<NearbyStarsData>,
+    procedural_stars: &Res<ProceduralStars>,
 ) {
-    egui::SidePanel::right("star_system_panel")
-        .min_width(300.0)
-        .max_width(400.0)
-        .show(ctx, |ui| {
+    DockPanel::new("star_system_panel", "Selected Star System", PanelSide::Right)
+        .size_range(300.0, 400.0)
+        .show(ctx, layout, |ui| {
             ui.heading("Selected Star System");
             ui.separator();
 
@@ -2446,7 +3247,7 @@ fn render_star_system_panel(
             ui.add_space(10.0);
 
             // Distance from Sol
-            let distance_ly = star_icon.position.length() / 63241.077;
+            let distance_ly = star_icon.position.length() / LY_TO_AU;
             ui.group(|ui| {
                 ui.label(egui::RichText::new("System Info").strong());
                 ui.label(format!("Distance: {:.2} ly", distance_ly));
@@ -2455,8 +3256,32 @@ fn render_star_system_panel(
 
             ui.add_space(10.0);
 
-            // Try to find detailed system data
-            if let Some(system_data) = nearby_stars.get_by_id(star_icon.id) {
+            // Generated systems only know their spectral types and metallicity
+            if let Some(system) = procedural_stars.get_by_id(star_icon.id) {
+                ui.group(|ui| {
+                    ui.label(egui::RichText::new("Star Properties").strong());
+                    ui.label(
+                        egui::RichText::new("Procedurally generated — no survey data")
+                            .weak()
+                            .italics(),
+                    );
+                    let stars = std::iter::once(&system.primary).chain(system.companion.as_ref());
+                    for (star_idx, star) in stars.enumerate() {
+                        ui.label(format!(
+                            "  Star {}: {} ({:.2} M☉)",
+                            star_idx + 1,
+                            star.spectral_type,
+                            star.mass_sol
+                        ));
+                    }
+                    if system.companion.is_some() {
+                        ui.label(format!("  Separation: {:.1} AU", system.separation_au));
+                    }
+                    ui.label(format!("  Metallicity: [Fe/H] = {:.2}", system.metallicity));
+                });
+
+                ui.add_space(10.0);
+            } else if let Some(system_data) = nearby_stars.get_by_id(star_icon.id) {
                 // Star properties
                 ui.group(|ui| {
                     ui.label(egui::RichText::new("Star Properties").strong());
@@ -2639,25 +3464,39 @@ fn ui_research_panels(
     mut tech_data: ResMut<TechnologiesData>,
     mut debug_settings: ResMut<crate::research::ResearchDebugSettings>,
     mut edit_state: ResMut<TechTreeEditState>,
-    mut pending_research: ResMut<crate::research::PendingResearchActions>,
+    (mut pending_research, mut player_commands): (
+        ResMut<crate::research::PendingResearchActions>,
+        EventWriter<PlayerCommand>,
+    ),
     research_icons: Option<Res<ResearchIcons>>,
     mut icon_textures: Local<HashMap<TechCategory, egui::TextureId>>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
-    research_projects: Query<(Entity, &ResearchProject, &ResearchTeam)>,
-    engineering_projects: Query<(&EngineeringProject, &ResearchTeam)>,
+    research_projects: Query<(Entity, &ResearchProject)>,
+    engineering_projects: Query<&EngineeringProject>,
     all_teams: Query<(Entity, &ResearchTeam)>,
-    team_capacity: Res<ResearchTeamCapacity>,
+    (team_capacity, reverse_engineering, difficulty): (
+        Res<ResearchTeamCapacity>,
+        Query<&ReverseEngineeringProject>,
+        Option<Res<crate::game_state::Difficulty>>,
+    ),
     mut selected_tab: Local<usize>,
+    mut tree_layout: Local<tech_layout::TechTreeLayout>,
 ) {
     if active_menu.current != GameMenu::Research {
         return;
     }
+    // Technologies cost what `process_pending_research` will charge
+    let cost_multiplier = difficulty.map_or(1.0, |d| d.research_cost_multiplier());
 
     // Handle navigate-to-available-tab requests (e.g. from tree view Start Research)
     if pending_research.navigate_to_available_tab {
         *selected_tab = 2;
         pending_research.navigate_to_available_tab = false;
     }
+    // Technologies picked elsewhere (e.g. the command palette) open in the tree
+    if pending_research.focus_tech.is_some() {
+        *selected_tab = 1;
+    }
 
     // Convert loaded handles to egui TextureIds
     if let Some(icons) = &research_icons {
@@ -2691,6 +3530,7 @@ fn ui_research_panels(
                     ui.checkbox(&mut debug_settings.show_all_techs, "Show All Technologies (ignore prerequisites)");
                     ui.checkbox(&mut debug_settings.instant_research, "Instant Research");
                     ui.checkbox(&mut debug_settings.instant_engineering, "Instant Engineering");
+                    ui.checkbox(&mut debug_settings.disable_breakthroughs, "No Breakthroughs or Dead Ends");
                 });
                 ui.label(egui::RichText::new("⚠ Debug features are for development only and will be removed in release builds")
                     .small()
@@ -2721,7 +3561,7 @@ fn ui_research_panels(
         
         // Build rich active research info map
         let mut active_research: HashMap<String, ActiveProjectInfo> = HashMap::new();
-        for (entity, proj, _team) in research_projects.iter() {
+        for (entity, proj) in research_projects.iter() {
             active_research.insert(proj.tech_id.clone(), ActiveProjectInfo {
                 entity,
                 progress_percent: proj.progress_percent(),
@@ -2734,25 +3574,28 @@ fn ui_research_panels(
 
         // Tab content
         match *selected_tab {
-            0 => render_overview_tab(ui, &research_state, &tech_data, &research_projects, &engineering_projects, &all_teams, &team_capacity),
-            1 => render_tech_tree_tab(ui, &research_state, &mut tech_data, icon_textures, debug_settings.enabled, &mut edit_state, &active_research, &mut pending_research),
-            2 => render_available_research_tab(ui, &research_state, &tech_data, icon_textures, &active_research, &mut pending_research, &team_capacity),
+            0 => render_overview_tab(ui, &research_state, &tech_data, &research_projects, &engineering_projects, &reverse_engineering, &all_teams, &team_capacity, &mut player_commands),
+            1 => render_tech_tree_tab(ui, &research_state, &mut tech_data, &mut tree_layout, icon_textures, debug_settings.enabled, &mut edit_state, &active_research, &mut pending_research, &mut player_commands, cost_multiplier),
+            2 => render_available_research_tab(ui, &research_state, &tech_data, icon_textures, &active_research, &mut pending_research, &mut player_commands, &team_capacity, cost_multiplier),
             3 => render_available_engineering_tab(ui, &research_state, &tech_data, icon_textures),
-            4 => render_archive_tab(ui, &research_state, &tech_data, icon_textures),
+            4 => render_archive_tab(ui, &research_state, &tech_data, icon_textures, cost_multiplier),
             _ => {},
         }
     });
 }
 
 /// Render the Overview tab - shows active projects and team assignments
+#[allow(clippy::too_many_arguments)]
 fn render_overview_tab(
     ui: &mut egui::Ui,
     research_state: &ResearchState,
     tech_data: &TechnologiesData,
-    research_projects: &Query<(Entity, &ResearchProject, &ResearchTeam)>,
-    engineering_projects: &Query<(&EngineeringProject, &ResearchTeam)>,
+    research_projects: &Query<(Entity, &ResearchProject)>,
+    engineering_projects: &Query<&EngineeringProject>,
+    reverse_engineering: &Query<&ReverseEngineeringProject>,
     all_teams: &Query<(Entity, &ResearchTeam)>,
     team_capacity: &ResearchTeamCapacity,
+    player_commands: &mut EventWriter<PlayerCommand>,
 ) {
     ui.heading("Research & Engineering Overview");
     
@@ -2784,7 +3627,7 @@ fn render_overview_tab(
         
         // Active Research Projects
         ui.group(|ui| {
-            let active_count = research_projects.iter().filter(|(_, p, _)| p.active).count();
+            let active_count = research_projects.iter().filter(|(_, p)| p.active).count();
             let total_count = research_projects.iter().count();
             ui.label(egui::RichText::new(format!(
                 "Active Research Projects ({}/{})",
@@ -2797,11 +3640,13 @@ fn render_overview_tab(
                     .italics()
                     .color(egui::Color32::GRAY));
             } else {
-                for (_entity, project, team) in research_projects.iter() {
+                for (_entity, project) in research_projects.iter() {
                     if let Some(tech) = tech_data.get_tech(&project.tech_id) {
                         ui.horizontal(|ui| {
                             ui.label(egui::RichText::new(&tech.name).strong());
-                            ui.label(format!("(Team: {})", team.name));
+                            if let Ok((_, team)) = all_teams.get(project.team_id) {
+                                ui.label(format!("(Team: {})", team.name));
+                            }
                             if !project.active {
                                 ui.label(egui::RichText::new("⏸ PAUSED")
                                     .color(egui::Color32::YELLOW));
@@ -2836,11 +3681,13 @@ fn render_overview_tab(
                     .italics()
                     .color(egui::Color32::GRAY));
             } else {
-                for (project, team) in engineering_projects.iter() {
+                for project in engineering_projects.iter() {
                     if let Some(component) = tech_data.get_component(&project.component_id) {
                         ui.horizontal(|ui| {
                             ui.label(egui::RichText::new(&component.name).strong());
-                            ui.label(format!("(Team: {})", team.name));
+                            if let Ok((_, team)) = all_teams.get(project.team_id) {
+                                ui.label(format!("(Team: {})", team.name));
+                            }
                         });
                         
                         let progress = project.progress_percent();
@@ -2857,53 +3704,120 @@ fn render_overview_tab(
                 }
             }
         });
-        
+
         ui.add_space(10.0);
-        
-        // Research Teams
-        ui.group(|ui| {
-            ui.label(egui::RichText::new("Research & Engineering Teams").strong().size(16.0));
-            ui.separator();
-            
-            let team_count = all_teams.iter().count();
-            if team_count == 0 {
-                ui.label(egui::RichText::new("No teams available - teams will be added in future updates")
-                    .italics()
-                    .color(egui::Color32::GRAY));
-            } else {
-                for (_entity, team) in all_teams.iter() {
-                    ui.horizontal(|ui| {
-                        let icon = if team.is_research { "🔬" } else { "⚙" };
-                        ui.label(egui::RichText::new(format!("{} {}", icon, team.name)).strong());
-                        ui.label(format!("Lead: {}", team.lead_character));
-                    });
-                    
-                    if let Some(specialty) = team.specialty {
-                        ui.label(format!("  Specialty: {} ({})", 
-                            specialty.display_name(), 
-                            specialty.icon()));
-                    }
-                    ui.label(format!("  Efficiency: {:.0}%", team.efficiency * 100.0));
+
+        // Reverse engineering of recovered salvage, paid from the EP pool
+        if !reverse_engineering.is_empty() {
+            ui.group(|ui| {
+                ui.label(
+                    egui::RichText::new("Reverse Engineering")
+                        .strong()
+                        .size(16.0),
+                );
+                ui.separator();
+                for project in reverse_engineering.iter() {
+                    let tech_name = tech_data
+                        .get_tech(&project.tech_id)
+                        .map_or(project.tech_id.as_str(), |t| t.name.as_str());
+                    ui.label(egui::RichText::new(&project.source).strong());
+                    let progress = project.progress_percent();
+                    ui.add(egui::ProgressBar::new(progress).text(format!(
+                        "{:.0}% ({:.0}/{:.0} EP)",
+                        progress * 100.0,
+                        project.progress,
+                        project.required_points
+                    )));
+                    ui.label(format!(
+                        "Findings: {:.0} RP towards {}",
+                        project.research_points, tech_name
+                    ));
                     ui.add_space(5.0);
                 }
-            }
+            });
+            ui.add_space(10.0);
+        }
+
+        // Research Teams
+        ui.group(|ui| {
+            research_teams::teams_section(
+                ui,
+                tech_data,
+                research_projects,
+                all_teams,
+                player_commands,
+            );
         });
     });
 }
 
+/// Cubic bezier with horizontal tangents for a smooth S-curve between two
+/// nodes of the tech tree
+fn tech_tree_curve(
+    from: egui::Pos2,
+    to: egui::Pos2,
+    stroke: egui::Stroke,
+) -> egui::epaint::CubicBezierShape {
+    let mid_x = (from.x + to.x) * 0.5;
+    egui::epaint::CubicBezierShape::from_points_stroke(
+        [
+            from,
+            egui::Pos2::new(mid_x, from.y),
+            egui::Pos2::new(mid_x, to.y),
+            to,
+        ],
+        false,
+        egui::Color32::TRANSPARENT,
+        stroke,
+    )
+}
+
 /// Render the Tech Tree tab
+#[allow(clippy::too_many_arguments)]
 fn render_tech_tree_tab(
     ui: &mut egui::Ui,
     research_state: &ResearchState,
     tech_data: &mut TechnologiesData,
+    tree_layout: &mut tech_layout::TechTreeLayout,
     icon_textures: &HashMap<TechCategory, egui::TextureId>,
     debug_enabled: bool,
     edit_state: &mut TechTreeEditState,
     active_research: &HashMap<String, ActiveProjectInfo>,
     pending_research: &mut crate::research::PendingResearchActions,
+    player_commands: &mut EventWriter<PlayerCommand>,
+    cost_multiplier: f64,
 ) {
     ui.heading("Technology Tree - Graph View");
     ui.label("Pan: Middle mouse drag | Zoom: Mouse wheel | Click: Select tech & highlight path");
+    // Export of the whole tree, coloured by what is unlocked
+    let export_status_id = ui.id().with("tech_tree_export_status");
+    ui.horizontal(|ui| {
+        let mut export_to = None;
+        if ui
+            .button("💾 Export DOT")
+            .on_hover_text("GraphViz graph of the whole tree")
+            .clicked()
+        {
+            export_to = Some(crate::research::TECH_TREE_DOT_PATH);
+        }
+        if ui
+            .button("🖼 Export SVG")
+            .on_hover_text("Image of the whole tree")
+            .clicked()
+        {
+            export_to = Some(crate::research::TECH_TREE_SVG_PATH);
+        }
+        if let Some(path) = export_to {
+            let status = match crate::research::export_tech_tree(tech_data, research_state, path) {
+                Ok(()) => format!("Exported to {}", path),
+                Err(e) => e,
+            };
+            ui.data_mut(|data| data.insert_temp(export_status_id, status));
+        }
+        if let Some(status) = ui.data(|data| data.get_temp::<String>(export_status_id)) {
+            ui.label(egui::RichText::new(status).small().weak());
+        }
+    });
     if debug_enabled {
         ui.label(
             egui::RichText::new("Right-click: Edit/delete node | Right-click empty space: Add new tech")
@@ -2912,6 +3826,7 @@ fn render_tech_tree_tab(
         );
     }
     ui.separator();
+    let palette = accessibility::palette(ui.ctx());
     
     // Local state for pan, zoom, and selected tech (using unique ID for persistence)
     let pan_id = ui.id().with("tech_tree_pan");
@@ -2934,7 +3849,6 @@ fn render_tech_tree_tab(
     // ---------- layout constants ----------
     let tier_spacing = 350.0 * zoom;
     let node_spacing_y = 80.0 * zoom;
-    let category_spacing = 20.0 * zoom;
     
     // ---------- status line (fixed height, drawn FIRST so it reserves space at the bottom) ----------
     // We draw it at the end but must reserve its height now.
@@ -2993,7 +3907,7 @@ fn render_tech_tree_tab(
     for (_, tech) in &tech_data.technologies {
         let g = painter.layout_no_wrap(tech.name.clone(), font_name.clone(), egui::Color32::WHITE);
         max_name_w = max_name_w.max(g.size().x);
-        let cost_text = format!("{:.0} RP", tech.research_cost);
+        let cost_text = format!("{:.0} RP", tech.research_cost * cost_multiplier);
         let g2 = painter.layout_no_wrap(cost_text, font_cost.clone(), egui::Color32::WHITE);
         max_cost_w = max_cost_w.max(g2.size().x);
     }
@@ -3004,66 +3918,33 @@ fn render_tech_tree_tab(
     let node_w = (icon_sz + icon_pad + max_name_w.max(max_cost_w) + h_pad * 2.0).round();
     let node_h = (v_pad + name_row_h + row_gap + cost_row_h + v_pad).round();
 
-    // ---------- compute node positions (top-left corner) ----------
-    // Uses a barycenter heuristic: tier-0 techs are sorted by category,
-    // subsequent tiers sort by the average Y of their prerequisites so that
-    // connected nodes stay close together and lines are shorter.
-    let mut node_positions: HashMap<String, egui::Pos2> = HashMap::new();
-    
-    let mut techs_by_tier: std::collections::BTreeMap<u32, Vec<&crate::research::types::Technology>> =
-        std::collections::BTreeMap::new();
-    for (_, tech) in &tech_data.technologies {
-        techs_by_tier.entry(tech.tier).or_default().push(tech);
-    }
-    
-    for (tier_idx, (_tier, techs)) in techs_by_tier.iter().enumerate() {
-        let mut sorted_techs = techs.clone();
-        if tier_idx == 0 {
-            // Root tier: deterministic category + name sort
-            sorted_techs.sort_by_key(|t| (t.category as u8, t.name.as_str()));
-        } else {
-            // Barycenter: sort by the average Y position of prerequisites.
-            // Techs with no positioned prerequisites fall back to category sort.
-            sorted_techs.sort_by(|a, b| {
-                let avg_y = |tech: &&crate::research::types::Technology| -> f64 {
-                    let ys: Vec<f32> = tech
-                        .prerequisites
-                        .iter()
-                        .filter_map(|pid| node_positions.get(pid).map(|p| p.y))
-                        .collect();
-                    if ys.is_empty() {
-                        // Fallback: use category ordinal so it groups nicely
-                        tech.category as u8 as f64 * 1000.0
-                    } else {
-                        ys.iter().map(|y| *y as f64).sum::<f64>() / ys.len() as f64
-                    }
-                };
-                avg_y(&a)
-                    .partial_cmp(&avg_y(&b))
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            });
-        }
-        
-        let base_x = (canvas_rect.left() + pan_offset.x + (tier_idx as f32) * tier_spacing).round();
-        let mut current_y = (canvas_rect.top() + pan_offset.y).round();
-        let mut last_category: Option<TechCategory> = None;
-        
-        for tech in sorted_techs {
-            if let Some(last_cat) = last_category {
-                if last_cat != tech.category {
-                    current_y += category_spacing;
-                }
-            }
-            last_category = Some(tech.category);
-            // Store the CENTER of the node for line connections
-            node_positions.insert(
-                tech.id.clone(),
-                egui::Pos2::new(base_x + node_w / 2.0, current_y + node_h / 2.0),
-            );
-            current_y += node_h + node_spacing_y;
+    // ---------- compute node positions ----------
+    // Layered layout (see `tech_layout`), recomputed only when the
+    // technologies change. Positions are the CENTER of each node.
+    tree_layout.refresh(tech_data);
+    let row_height = node_h + node_spacing_y;
+    let slot_center = |pan: egui::Vec2, column: usize, row: f32| {
+        egui::Pos2::new(
+            (canvas_rect.left() + pan.x + column as f32 * tier_spacing).round() + node_w / 2.0,
+            (canvas_rect.top() + pan.y + row * row_height).round() + node_h / 2.0,
+        )
+    };
+
+    // ---------- focus request: select the tech and center it ----------
+    if let Some(focus_id) = pending_research.focus_tech.take() {
+        if let Some(&(column, row)) = tree_layout.nodes.get(&focus_id) {
+            pan_offset += (canvas_rect.center() - slot_center(pan_offset, column, row)).round();
+            ui.data_mut(|data| data.insert_persisted(pan_id, pan_offset));
+            selected_tech = Some(focus_id);
         }
     }
-    
+
+    let node_positions: HashMap<String, egui::Pos2> = tree_layout
+        .nodes
+        .iter()
+        .map(|(id, &(column, row))| (id.clone(), slot_center(pan_offset, column, row)))
+        .collect();
+
     // ---------- prerequisite highlight path ----------
     let mut path_techs = std::collections::HashSet::new();
     if let Some(ref sel_id) = selected_tech {
@@ -3081,44 +3962,38 @@ fn render_tech_tree_tab(
     }
     
     // ---------- draw connection lines (cubic bezier) ----------
-    // Connect right edge of prerequisite to left edge of dependent
-    for (_, tech) in &tech_data.technologies {
-        if let Some(tech_center) = node_positions.get(&tech.id) {
-            for prereq_id in &tech.prerequisites {
-                if let Some(prereq_center) = node_positions.get(prereq_id) {
-                    let is_in_path =
-                        path_techs.contains(&tech.id) && path_techs.contains(prereq_id);
-                    let is_prereq_unlocked = research_state.is_unlocked(prereq_id);
-                    let line_color = if is_in_path {
-                        egui::Color32::from_rgba_premultiplied(255, 200, 0, 255)
-                    } else if is_prereq_unlocked {
-                        egui::Color32::from_rgba_premultiplied(100, 255, 100, 80)
-                    } else {
-                        egui::Color32::from_rgba_premultiplied(120, 120, 120, 60)
-                    };
-                    let w = if is_in_path { 2.5 * zoom } else { 1.0 * zoom };
-                    // From right edge of prereq to left edge of tech
-                    let from = egui::Pos2::new(prereq_center.x + node_w / 2.0, prereq_center.y);
-                    let to = egui::Pos2::new(tech_center.x - node_w / 2.0, tech_center.y);
-                    // Cubic bezier with horizontal tangents for a smooth S-curve
-                    let mid_x = (from.x + to.x) * 0.5;
-                    let bezier = egui::epaint::CubicBezierShape::from_points_stroke(
-                        [
-                            from,
-                            egui::Pos2::new(mid_x, from.y),
-                            egui::Pos2::new(mid_x, to.y),
-                            to,
-                        ],
-                        false,
-                        egui::Color32::TRANSPARENT,
-                        egui::Stroke::new(w, line_color),
-                    );
-                    painter.add(bezier);
-                }
-            }
+    // Connect right edge of prerequisite to left edge of dependent, passing
+    // straight through the slots the layout routed the connection through
+    for edge in &tree_layout.edges {
+        let (Some(tech_center), Some(prereq_center)) =
+            (node_positions.get(&edge.to), node_positions.get(&edge.from))
+        else {
+            continue;
+        };
+        let is_in_path = path_techs.contains(&edge.to) && path_techs.contains(&edge.from);
+        let is_prereq_unlocked = research_state.is_unlocked(&edge.from);
+        let line_color = if is_in_path {
+            egui::Color32::from_rgba_premultiplied(255, 200, 0, 255)
+        } else if is_prereq_unlocked {
+            egui::Color32::from_rgba_premultiplied(100, 255, 100, 80)
+        } else {
+            egui::Color32::from_rgba_premultiplied(120, 120, 120, 60)
+        };
+        let width = if is_in_path { 2.5 * zoom } else { 1.0 * zoom };
+        let stroke = egui::Stroke::new(width, line_color);
+
+        let mut from = egui::Pos2::new(prereq_center.x + node_w / 2.0, prereq_center.y);
+        for &(column, row) in &edge.waypoints {
+            let slot = slot_center(pan_offset, column, row);
+            let enter = egui::Pos2::new(slot.x - node_w / 2.0, slot.y);
+            painter.add(tech_tree_curve(from, enter, stroke));
+            from = egui::Pos2::new(slot.x + node_w / 2.0, slot.y);
+            painter.line_segment([enter, from], stroke);
         }
+        let to = egui::Pos2::new(tech_center.x - node_w / 2.0, tech_center.y);
+        painter.add(tech_tree_curve(from, to, stroke));
     }
-    
+
     // ---------- draw nodes & collect hit-test rects ----------
     // We do NOT call ui.allocate_rect for each node (that was the bug).
     // Instead we paint directly and do manual hit-testing against the pointer.
@@ -3139,30 +4014,22 @@ fn render_tech_tree_tab(
             let is_researching = active_research.contains_key(&tech.id);
             let research_progress = active_research.get(&tech.id).map(|info| info.progress_percent);
             let can_research =
-                !is_unlocked && !is_researching && tech_data.check_prerequisites(&tech.id, &unlocked_ids);
+                !is_unlocked && !is_researching && tech_data.check_prerequisites(&tech.id, &unlocked_ids)
+                    && tech_data.check_kardashev(&tech.id, research_state.kardashev_reached);
             let is_in_path = path_techs.contains(&tech.id);
             let is_selected = selected_tech.as_ref() == Some(&tech.id);
             
-            // Node fill color — use darker/muted tones so white text is always readable
-            let node_color = if is_in_path {
-                if is_unlocked {
-                    egui::Color32::from_rgb(30, 90, 30)
-                } else if is_researching {
-                    egui::Color32::from_rgb(20, 60, 110)
-                } else if can_research {
-                    egui::Color32::from_rgb(90, 75, 15)
-                } else {
-                    egui::Color32::from_rgb(60, 60, 60)
-                }
-            } else if is_unlocked {
-                egui::Color32::from_rgb(25, 70, 25)
+            let status = if is_unlocked {
+                TechStatus::Unlocked
             } else if is_researching {
-                egui::Color32::from_rgb(15, 50, 95)
+                TechStatus::Researching
             } else if can_research {
-                egui::Color32::from_rgb(70, 60, 15)
+                TechStatus::Available
             } else {
-                egui::Color32::from_rgb(45, 45, 50)
+                TechStatus::Locked
             };
+            // Node fill color — use darker/muted tones so white text is always readable
+            let node_color = palette.tech_fill(status, is_in_path);
             
             let category_color = tech_category_color(tech.category);
             
@@ -3231,14 +4098,26 @@ fn render_tech_tree_tab(
             // --- row 2: research cost / progress (left-aligned, dimmer) ---
             let row2_y = (node_rect.top() + v_pad + name_row_h + row_gap + cost_row_h / 2.0).round();
             let (cost_text, cost_color) = if is_unlocked {
-                ("✔ Researched".to_string(), egui::Color32::from_rgb(120, 200, 120))
+                ("✔ Researched".to_string(), palette.tech(TechStatus::Unlocked))
             } else if let Some(pct) = research_progress {
                 (
-                    format!("⏳ {:.0}%  ({:.0} RP)", pct * 100.0, tech.research_cost),
-                    egui::Color32::from_rgb(100, 180, 255),
+                    format!("⏳ {:.0}%  ({:.0} RP)", pct * 100.0, tech.research_cost * cost_multiplier),
+                    palette.tech(TechStatus::Researching),
+                )
+            } else if palette.shapes() {
+                (
+                    format!(
+                        "{} {:.0} RP",
+                        palette.tech_marker(status),
+                        tech.research_cost * cost_multiplier
+                    ),
+                    egui::Color32::from_rgb(150, 180, 220),
                 )
             } else {
-                (format!("{:.0} RP", tech.research_cost), egui::Color32::from_rgb(150, 180, 220))
+                (
+                    format!("{:.0} RP", tech.research_cost * cost_multiplier),
+                    egui::Color32::from_rgb(150, 180, 220),
+                )
             };
             painter.text(
                 egui::Pos2::new(name_x, row2_y),
@@ -3407,6 +4286,7 @@ fn render_tech_tree_tab(
                 selected_tech = None;
             }
             edit_state.delete_confirm = None;
+            tech_data.version += 1;
             save_technologies_to_file(tech_data);
         }
 
@@ -3439,7 +4319,8 @@ fn render_tech_tree_tab(
             let is_unlocked = research_state.is_unlocked(&tech.id);
             let is_researching = active_research.contains_key(&tech.id);
             let can_research =
-                !is_unlocked && !is_researching && tech_data.check_prerequisites(&tech.id, &unlocked_ids);
+                !is_unlocked && !is_researching && tech_data.check_prerequisites(&tech.id, &unlocked_ids)
+                    && tech_data.check_kardashev(&tech.id, research_state.kardashev_reached);
             
             let tooltip_pos = egui::pos2(tr.right() + 8.0, tr.top());
             
@@ -3474,24 +4355,48 @@ fn render_tech_tree_tab(
                     ui.add_space(5.0);
                     ui.label(format!(
                         "Tier: {} | Cost: {:.0} RP",
-                        tech.tier, tech.research_cost
+                        tech.tier,
+                        tech.research_cost * cost_multiplier
                     ));
                     if !tech.prerequisites.is_empty() {
                         ui.add_space(5.0);
                         ui.label(egui::RichText::new("Prerequisites:").strong());
                         for prereq_id in &tech.prerequisites {
                             if let Some(prereq) = tech_data.get_tech(prereq_id) {
-                                let c = if research_state.is_unlocked(prereq_id) {
-                                    egui::Color32::from_rgb(100, 255, 100)
+                                let status = if research_state.is_unlocked(prereq_id) {
+                                    Status::Good
                                 } else {
-                                    egui::Color32::from_rgb(255, 100, 100)
+                                    Status::Bad
                                 };
                                 ui.label(
-                                    egui::RichText::new(format!("  • {}", prereq.name)).color(c),
+                                    egui::RichText::new(format!(
+                                        "  • {}",
+                                        palette.status_text(status, &prereq.name)
+                                    ))
+                                    .color(palette.status(status)),
                                 );
                             }
                         }
                     }
+                    if let Some(required) = tech.required_kardashev {
+                        let status = if research_state.kardashev_reached >= required {
+                            Status::Good
+                        } else {
+                            Status::Bad
+                        };
+                        ui.add_space(5.0);
+                        ui.label(
+                            egui::RichText::new(palette.status_text(
+                                status,
+                                format!(
+                                    "Requires Kardashev {:.1} ({})",
+                                    required,
+                                    format_power(crate::economy::power_for_index(required))
+                                ),
+                            ))
+                            .color(palette.status(status)),
+                        );
+                    }
                     if !tech.unlocks_components.is_empty() {
                         ui.add_space(5.0);
                         ui.label(egui::RichText::new("Unlocks Components:").strong());
@@ -3533,7 +4438,7 @@ fn render_tech_tree_tab(
                         ui.add_space(5.0);
                         ui.separator();
                         if ui.button("🔬 Start Research").clicked() {
-                            pending_research.start_research.push(tech.id.clone());
+                            player_commands.send(PlayerCommand::StartResearch(tech.id.clone()));
                             pending_research.navigate_to_available_tab = true;
                         }
                     }
@@ -3558,10 +4463,12 @@ fn render_tech_tree_tab(
     ui.allocate_ui_at_rect(status_rect, |ui| {
         ui.horizontal(|ui| {
             ui.label("Status:");
-            ui.colored_label(egui::Color32::from_rgb(50, 200, 50), "● Unlocked");
-            ui.colored_label(egui::Color32::from_rgb(80, 160, 255), "● Researching");
-            ui.colored_label(egui::Color32::from_rgb(255, 200, 50), "● Available");
-            ui.colored_label(egui::Color32::from_rgb(100, 100, 100), "● Locked");
+            for status in TechStatus::ALL {
+                ui.colored_label(
+                    palette.tech(status),
+                    format!("{} {}", palette.tech_marker(status), status.name()),
+                );
+            }
             ui.label(format!("| Zoom: {:.1}x", zoom));
             if debug_enabled {
                 ui.separator();
@@ -3835,9 +4742,11 @@ fn render_tech_edit_dialog(
                     unlocks_engineering: Vec::new(),
                     modifiers: Vec::new(),
                     tier,
+                    required_kardashev: None,
                 };
                 tech_data.technologies.insert(edit_data.id, new_tech);
             }
+            tech_data.version += 1;
             save_technologies_to_file(tech_data);
         }
     } else if should_close {
@@ -3908,7 +4817,58 @@ fn tech_category_color(cat: TechCategory) -> egui::Color32 {
     }
 }
 
+/// More research slots come from laboratories and from the technologies
+/// not yet researched that add slots
+fn research_capacity_hint(
+    ui: &mut egui::Ui,
+    research_state: &ResearchState,
+    tech_data: &TechnologiesData,
+    pending_research: &mut crate::research::PendingResearchActions,
+) {
+    let mut slot_techs: Vec<(&crate::research::Technology, f64)> = tech_data
+        .technologies
+        .values()
+        .filter(|tech| !research_state.is_unlocked(&tech.id))
+        .filter_map(|tech| {
+            let slots: f64 = tech
+                .modifiers
+                .iter()
+                .filter(|m| m.modifier_type == crate::research::types::ModifierType::ResearchSlots)
+                .map(|m| m.value)
+                .sum();
+            (slots > 0.0).then_some((tech, slots))
+        })
+        .collect();
+    slot_techs.sort_by(|a, b| (a.0.tier, a.0.name.as_str()).cmp(&(b.0.tier, b.0.name.as_str())));
+
+    egui::CollapsingHeader::new("More research slots")
+        .id_source("research_slot_hint")
+        .show(ui, |ui| {
+            ui.label("Every Research Laboratory level and Orbital Laboratory adds a slot.");
+            if slot_techs.is_empty() {
+                ui.label(
+                    egui::RichText::new("Every technology adding slots is researched")
+                        .italics()
+                        .color(egui::Color32::GRAY),
+                );
+            }
+            for (tech, slots) in slot_techs {
+                ui.horizontal(|ui| {
+                    if ui
+                        .link(&tech.name)
+                        .on_hover_text("Show in the tech tree")
+                        .clicked()
+                    {
+                        pending_research.focus_tech = Some(tech.id.clone());
+                    }
+                    ui.label(format!("+{:.0} (tier {})", slots, tech.tier));
+                });
+            }
+        });
+}
+
 /// Render the Available Research tab
+#[allow(clippy::too_many_arguments)]
 fn render_available_research_tab(
     ui: &mut egui::Ui,
     research_state: &ResearchState,
@@ -3916,7 +4876,9 @@ fn render_available_research_tab(
     icon_textures: &HashMap<TechCategory, egui::TextureId>,
     active_research: &HashMap<String, ActiveProjectInfo>,
     pending_research: &mut crate::research::PendingResearchActions,
+    player_commands: &mut EventWriter<PlayerCommand>,
     team_capacity: &ResearchTeamCapacity,
+    cost_multiplier: f64,
 ) {
     let active_count = active_research.values().filter(|info| info.active).count();
     let teams_available = team_capacity.max_research_teams.saturating_sub(active_count);
@@ -3925,15 +4887,27 @@ fn render_available_research_tab(
     ui.horizontal(|ui| {
         ui.label("Technologies with all prerequisites met.");
         ui.add_space(20.0);
-        ui.label(egui::RichText::new(format!(
-            "Teams: {}/{} in use | {} available",
-            active_count, team_capacity.max_research_teams, teams_available
-        )).color(if teams_available > 0 {
-            egui::Color32::from_rgb(100, 255, 100)
-        } else {
-            egui::Color32::from_rgb(255, 200, 100)
-        }));
+        ui.label(
+            egui::RichText::new(format!(
+                "Slots: {}/{} in use | {} free",
+                active_count, team_capacity.max_research_teams, teams_available
+            ))
+            .color(if teams_available > 0 {
+                egui::Color32::from_rgb(100, 255, 100)
+            } else {
+                egui::Color32::from_rgb(255, 200, 100)
+            }),
+        )
+        .on_hover_text(format!(
+            "Base: {}\nLaboratories: {}\nTechnologies: {}",
+            team_capacity.max_research_teams
+                - team_capacity.research_from_labs
+                - team_capacity.research_from_techs,
+            team_capacity.research_from_labs,
+            team_capacity.research_from_techs
+        ));
     });
+    research_capacity_hint(ui, research_state, tech_data, pending_research);
     ui.separator();
     
     egui::ScrollArea::vertical().show(ui, |ui| {
@@ -3985,24 +4959,27 @@ fn render_available_research_tab(
                                     .fixed_decimals(0)
                             );
                             if slider_resp.changed() {
-                                pending_research.update_allocations.push(
-                                    (tech_id.to_string(), alloc_pct as f64 / 100.0)
-                                );
+                                player_commands.send(PlayerCommand::AllocateResearch(
+                                    tech_id.to_string(),
+                                    alloc_pct as f64 / 100.0,
+                                ));
                             }
                             
                             ui.add_space(10.0);
                             
                             if info.active {
                                 if ui.button("⏸ Stop").on_hover_text("Pause research (preserves progress)").clicked() {
-                                    pending_research.stop_research.push(tech_id.to_string());
+                                    player_commands
+                                        .send(PlayerCommand::StopResearch(tech_id.to_string()));
                                 }
                             } else {
                                 let can_resume = teams_available > 0;
                                 let btn = ui.add_enabled(can_resume, egui::Button::new("▶ Resume"));
                                 if !can_resume {
-                                    btn.on_hover_text("No team slots available");
+                                    btn.on_hover_text("No research slots free");
                                 } else if btn.clicked() {
-                                    pending_research.resume_research.push(tech_id.to_string());
+                                    player_commands
+                                        .send(PlayerCommand::ResumeResearch(tech_id.to_string()));
                                 }
                             }
                         });
@@ -4021,7 +4998,8 @@ fn render_available_research_tab(
         for (tech_id, tech) in &tech_data.technologies {
             if !research_state.is_unlocked(tech_id) 
                 && !active_research.contains_key(tech_id)
-                && tech_data.check_prerequisites(tech_id, &unlocked_ids) {
+                && tech_data.check_prerequisites(tech_id, &unlocked_ids)
+                && tech_data.check_kardashev(tech_id, research_state.kardashev_reached) {
                 available_techs.push(tech);
             }
         }
@@ -4062,7 +5040,7 @@ fn render_available_research_tab(
                     ui.label(&tech.description);
                     
                     ui.horizontal(|ui| {
-                        ui.label(egui::RichText::new(format!("Cost: {:.0} RP", tech.research_cost))
+                        ui.label(egui::RichText::new(format!("Cost: {:.0} RP", tech.research_cost * cost_multiplier))
                             .color(egui::Color32::from_rgb(150, 200, 255)));
                         ui.label(format!("Tier: {}", tech.tier));
                     });
@@ -4086,7 +5064,7 @@ fn render_available_research_tab(
                     ui.horizontal(|ui| {
                         let btn = ui.add_enabled(can_start, egui::Button::new("🚀 Start Research"));
                         if can_start && btn.clicked() {
-                            pending_research.start_research.push(tech.id.clone());
+                            player_commands.send(PlayerCommand::StartResearch(tech.id.clone()));
                         }
                         if !can_start {
                             btn.on_hover_text("No team slots available. Stop another project first.");
@@ -4184,6 +5162,7 @@ fn render_archive_tab(
     research_state: &ResearchState,
     tech_data: &TechnologiesData,
     icon_textures: &HashMap<TechCategory, egui::TextureId>,
+    cost_multiplier: f64,
 ) {
     ui.heading("Research Archive");
     ui.label("Completed technologies and components");
@@ -4233,7 +5212,7 @@ fn render_archive_tab(
                                     ui.label("✔");
                                     ui.label(&tech.name);
                                     if tech.research_cost > 0.0 {
-                                        ui.label(egui::RichText::new(format!("({:.0} RP)", tech.research_cost))
+                                        ui.label(egui::RichText::new(format!("({:.0} RP)", tech.research_cost * cost_multiplier))
                                             .size(11.0)
                                             .color(egui::Color32::GRAY));
                                     }
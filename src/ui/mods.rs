@@ -0,0 +1,115 @@
+//! Mod list window: installed mods in load order, with their enabled state
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use std::path::Path;
+
+use crate::modding::{ModList, MODS_DIR};
+
+/// Whether the mod list window is open
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ModListWindow {
+    pub open: bool,
+}
+
+/// Render the mod list window. Edits are written to the load order file
+/// straight away and apply on the next start.
+pub(super) fn ui_mod_list_window(
+    mut contexts: EguiContexts,
+    mut window: ResMut<ModListWindow>,
+    mods: Option<ResMut<ModList>>,
+) {
+    if !window.open {
+        return;
+    }
+    let Some(mut mods) = mods else {
+        return;
+    };
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    let mut edited = false;
+    egui::Window::new("🧩 Mods")
+        .id(egui::Id::new("mod_list_window"))
+        .open(&mut window.open)
+        .default_size([420.0, 300.0])
+        .show(ctx, |ui| {
+            if mods.mods.is_empty() {
+                ui.label(
+                    egui::RichText::new(format!(
+                        "No mods installed. Place mods in {}/<name>/",
+                        MODS_DIR
+                    ))
+                    .weak(),
+                );
+                return;
+            }
+            ui.label(
+                egui::RichText::new("Later mods override earlier ones and the base game")
+                    .weak(),
+            );
+            ui.separator();
+
+            let count = mods.mods.len();
+            let mut move_up = None;
+            let mut move_down = None;
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for (index, info) in mods.mods.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        if ui.checkbox(&mut info.enabled, "").changed() {
+                            edited = true;
+                        }
+                        if ui
+                            .add_enabled(index > 0, egui::Button::new("⬆").small())
+                            .clicked()
+                        {
+                            move_up = Some(index);
+                        }
+                        if ui
+                            .add_enabled(index + 1 < count, egui::Button::new("⬇").small())
+                            .clicked()
+                        {
+                            move_down = Some(index);
+                        }
+                        let title = if info.version.is_empty() {
+                            info.name.clone()
+                        } else {
+                            format!("{} {}", info.name, info.version)
+                        };
+                        ui.strong(title).on_hover_text(info.path.display().to_string());
+                    });
+                    ui.indent(("mod", index), |ui| {
+                        if !info.author.is_empty() {
+                            ui.label(format!("by {}", info.author));
+                        }
+                        if !info.description.is_empty() {
+                            ui.label(&info.description);
+                        }
+                        ui.label(egui::RichText::new(info.files.join(", ")).weak());
+                    });
+                }
+            });
+            if let Some(index) = move_up {
+                mods.move_up(index);
+                edited = true;
+            }
+            if let Some(index) = move_down {
+                mods.move_down(index);
+                edited = true;
+            }
+
+            if mods.changed || edited {
+                ui.separator();
+                ui.label(
+                    egui::RichText::new("Restart the game to apply the new mod list")
+                        .color(egui::Color32::from_rgb(255, 200, 80)),
+                );
+            }
+        });
+
+    if edited {
+        mods.changed = true;
+        mods.save_load_order(Path::new(MODS_DIR));
+    }
+}
@@ -0,0 +1,318 @@
+//! New-game screen: picks the start scenario, difficulty, victory
+//! conditions and players of the campaign at launch
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use super::accessibility::{self, Status};
+use super::{format_population, TimeScale};
+use crate::comms::CommandLatency;
+use crate::game_state::Difficulty;
+use crate::hotseat::{Hotseat, SEATS};
+use crate::scenarios::{ScenarioColony, ScenarioId, ScenarioSelection, ScenariosData};
+use crate::tutorial::Tutorial;
+use crate::victory::{VictorySettings, BANKRUPTCY_MONTHS, SURVIVAL_YEARS};
+
+/// State of the new-game screen, shown once when the game starts
+#[derive(Resource, Debug, Clone)]
+pub struct NewGameScreen {
+    pub open: bool,
+    /// Scenario highlighted in the list
+    pub selected: Option<ScenarioId>,
+    /// Difficulty picked for the campaign
+    pub difficulty: Difficulty,
+    /// Whether the campaign starts with the tutorial objectives
+    pub tutorial: bool,
+    /// Whether orders travel at the speed of light
+    pub command_latency: bool,
+    /// Win and lose conditions picked for the campaign
+    pub victory: VictorySettings,
+    /// Whether two players take turns at this screen
+    pub hotseat: bool,
+    /// Names of the hotseat players
+    pub players: [String; SEATS],
+    /// Colony of the scenario the second player starts on
+    pub home: Option<String>,
+}
+
+impl Default for NewGameScreen {
+    fn default() -> Self {
+        Self {
+            open: true,
+            selected: None,
+            difficulty: Difficulty::Normal,
+            tutorial: true,
+            command_latency: false,
+            victory: VictorySettings::default(),
+            hotseat: false,
+            players: ["Player 1".to_string(), "Player 2".to_string()],
+            home: None,
+        }
+    }
+}
+
+/// Render the new-game screen. The simulation stays paused and the rest of
+/// the interface is blocked until a scenario is started.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn ui_new_game_screen(
+    mut contexts: EguiContexts,
+    mut screen: ResMut<NewGameScreen>,
+    mut selection: ResMut<ScenarioSelection>,
+    mut time_scale: ResMut<TimeScale>,
+    mut difficulty: ResMut<Difficulty>,
+    mut tutorial: ResMut<Tutorial>,
+    mut victory: ResMut<VictorySettings>,
+    mut latency: ResMut<CommandLatency>,
+    mut hotseat: ResMut<Hotseat>,
+    scenarios: Option<Res<ScenariosData>>,
+) {
+    if !screen.open {
+        return;
+    }
+    let Some(scenarios) = scenarios else {
+        return;
+    };
+    if scenarios.order.is_empty() {
+        // Nothing to pick from: play the default start
+        screen.open = false;
+        return;
+    }
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    if !time_scale.is_paused() {
+        time_scale.pause();
+    }
+    if screen
+        .selected
+        .as_ref()
+        .is_none_or(|id| scenarios.get(id).is_none())
+    {
+        screen.selected = scenarios.order.first().cloned();
+    }
+
+    // Backdrop that swallows clicks meant for the rest of the interface
+    egui::Area::new(egui::Id::new("new_game_backdrop"))
+        .order(egui::Order::Middle)
+        .fixed_pos(egui::Pos2::ZERO)
+        .show(ctx, |ui| {
+            let screen = ui.ctx().screen_rect();
+            ui.allocate_rect(screen, egui::Sense::click());
+            ui.painter()
+                .rect_filled(screen, 0.0, egui::Color32::from_black_alpha(200));
+        });
+
+    let mut start = false;
+    egui::Window::new("🚀 New Game")
+        .id(egui::Id::new("new_game_screen"))
+        .order(egui::Order::Foreground)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .collapsible(false)
+        .resizable(false)
+        .default_width(520.0)
+        .show(ctx, |ui| {
+            ui.label(egui::RichText::new("Choose a starting scenario").weak());
+            ui.separator();
+            ui.horizontal_top(|ui| {
+                ui.vertical(|ui| {
+                    ui.set_width(180.0);
+                    for scenario in scenarios.iter() {
+                        let picked = screen.selected.as_deref() == Some(scenario.id.as_str());
+                        if ui.selectable_label(picked, &scenario.name).clicked() {
+                            screen.selected = Some(scenario.id.clone());
+                        }
+                    }
+                });
+                ui.separator();
+                let Some(scenario) = screen.selected.as_ref().and_then(|id| scenarios.get(id))
+                else {
+                    return;
+                };
+                ui.vertical(|ui| {
+                    ui.heading(&scenario.name);
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "{:02}.{:02}.{}",
+                            scenario.start.day, scenario.start.month, scenario.start.year
+                        ))
+                        .weak(),
+                    );
+                    ui.label(&scenario.description);
+                    ui.add_space(4.0);
+                    for colony in &scenario.colonies {
+                        ui.label(format!(
+                            "• {}: {} people, {} buildings",
+                            colony.body,
+                            format_population(colony.population),
+                            colony.buildings.len()
+                        ));
+                    }
+                    if !scenario.technologies.is_empty() {
+                        ui.label(format!(
+                            "{} technologies researched",
+                            scenario.technologies.len()
+                        ));
+                    }
+                    if let Some(extinction) = &scenario.extinction {
+                        ui.add_space(4.0);
+                        ui.colored_label(
+                            accessibility::palette(ui.ctx()).status(Status::Bad),
+                            format!(
+                                "☠ {:02}.{:02}.{}: {}",
+                                extinction.date.day,
+                                extinction.date.month,
+                                extinction.date.year,
+                                extinction.description
+                            ),
+                        )
+                        .on_hover_text(format!(
+                            "Everyone on {} perishes. Survive {:.0} years after it to win.",
+                            extinction.body, SURVIVAL_YEARS
+                        ));
+                    }
+                });
+            });
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.label("Difficulty:");
+                for level in Difficulty::ALL {
+                    ui.selectable_value(&mut screen.difficulty, level, level.name())
+                        .on_hover_text(difficulty_summary(level));
+                }
+            });
+            ui.checkbox(&mut screen.tutorial, "Tutorial objectives")
+                .on_hover_text("Guide the first steps with a chain of goals; turn off for sandbox play");
+            ui.checkbox(&mut screen.command_latency, "Light-speed command delay")
+                .on_hover_text("Orders reach distant fleets and colonies after the light-time");
+            if let Some(scenario) = screen.selected.as_ref().and_then(|id| scenarios.get(id)) {
+                hotseat_ui(ui, &mut screen, &scenario.colonies);
+            }
+            ui.separator();
+            victory_conditions_ui(ui, &mut screen.victory);
+            ui.separator();
+            ui.vertical_centered(|ui| {
+                if ui
+                    .add_sized([160.0, 28.0], egui::Button::new("Start"))
+                    .clicked()
+                {
+                    start = true;
+                }
+            });
+        });
+
+    if start {
+        *difficulty = screen.difficulty;
+        tutorial.enabled = screen.tutorial;
+        latency.enabled = screen.command_latency;
+        victory.kardashev = screen.victory.kardashev;
+        victory.colonized_systems = screen.victory.colonized_systems;
+        victory.bankruptcy = screen.victory.bankruptcy;
+        if screen.hotseat {
+            let players = screen.players.iter().enumerate().map(|(seat, name)| {
+                let name = name.trim();
+                if name.is_empty() {
+                    format!("Player {}", seat + 1)
+                } else {
+                    name.to_string()
+                }
+            });
+            *hotseat = Hotseat {
+                enabled: true,
+                players: players.collect(),
+                home: screen.home.clone(),
+                ..default()
+            };
+        }
+        selection.pending = screen.selected.clone();
+        screen.open = false;
+        time_scale.resume();
+    }
+}
+
+/// Hotseat toggle, the names of the players and the colony the second one
+/// starts on; the first holds every other colony of the scenario
+fn hotseat_ui(ui: &mut egui::Ui, screen: &mut NewGameScreen, colonies: &[ScenarioColony]) {
+    let possible = colonies.len() > 1;
+    screen.hotseat &= possible;
+    ui.add_enabled(
+        possible,
+        egui::Checkbox::new(&mut screen.hotseat, "Hotseat: two players at this screen"),
+    )
+    .on_hover_text("Take turns giving orders at the start of every month")
+    .on_disabled_hover_text("Needs a scenario with two colonies or more");
+    if !screen.hotseat {
+        return;
+    }
+
+    let homes = &colonies[1..];
+    if screen
+        .home
+        .as_ref()
+        .is_none_or(|home| !homes.iter().any(|c| &c.body == home))
+    {
+        screen.home = homes.first().map(|c| c.body.clone());
+    }
+    ui.horizontal(|ui| {
+        ui.label("Player 1:");
+        ui.add(egui::TextEdit::singleline(&mut screen.players[0]).desired_width(120.0));
+    });
+    ui.horizontal(|ui| {
+        ui.label("Player 2:");
+        ui.add(egui::TextEdit::singleline(&mut screen.players[1]).desired_width(120.0));
+        egui::ComboBox::from_id_source("hotseat_home")
+            .selected_text(screen.home.clone().unwrap_or_default())
+            .show_ui(ui, |ui| {
+                for colony in homes {
+                    let label = colony.body.clone();
+                    ui.selectable_value(&mut screen.home, Some(colony.body.clone()), label);
+                }
+            });
+    });
+    ui.label(
+        egui::RichText::new("Player 2 starts on the colony picked, Player 1 on the others").weak(),
+    );
+}
+
+/// Checkboxes and targets of the win and lose conditions
+fn victory_conditions_ui(ui: &mut egui::Ui, settings: &mut VictorySettings) {
+    ui.label(egui::RichText::new("Victory conditions").strong());
+    ui.horizontal(|ui| {
+        let mut enabled = settings.kardashev.is_some();
+        ui.checkbox(&mut enabled, "Reach Kardashev");
+        let mut target = settings.kardashev.unwrap_or(1.0);
+        ui.add_enabled(
+            enabled,
+            egui::DragValue::new(&mut target)
+                .speed(0.01)
+                .range(0.8..=2.0)
+                .fixed_decimals(2),
+        );
+        settings.kardashev = enabled.then_some(target);
+    });
+    ui.horizontal(|ui| {
+        let mut enabled = settings.colonized_systems.is_some();
+        ui.checkbox(&mut enabled, "Colonize star systems");
+        let mut target = settings.colonized_systems.unwrap_or(2);
+        ui.add_enabled(enabled, egui::DragValue::new(&mut target).range(2..=10));
+        settings.colonized_systems = enabled.then_some(target);
+    });
+    ui.checkbox(&mut settings.bankruptcy, "Defeat on bankruptcy")
+        .on_hover_text(format!(
+            "Lose after {} months beyond the credit limit",
+            BANKRUPTCY_MONTHS
+        ));
+    ui.label(
+        egui::RichText::new("The campaign is always lost when no one is left alive").weak(),
+    );
+}
+
+/// Hover text listing a difficulty's multipliers
+fn difficulty_summary(difficulty: Difficulty) -> String {
+    format!(
+        "Research costs ×{:.2}\nPopulation growth ×{:.2}\nResource abundance ×{:.2}\nRandom events ×{:.2}",
+        difficulty.research_cost_multiplier(),
+        difficulty.population_growth_multiplier(),
+        difficulty.resource_abundance_multiplier(),
+        difficulty.event_frequency_multiplier()
+    )
+}
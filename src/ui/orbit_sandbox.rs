@@ -0,0 +1,286 @@
+//! Orbit sandbox: a debug window (Shift+F12) for editing the selected body's
+//! Kepler elements live
+//!
+//! Every change is written to the body's `KeplerOrbit` at once and its orbit
+//! samples are dropped, so propagation and the cached orbit line pick up the
+//! new elements even while the simulation is paused. Periapsis is compared
+//! with the parent's surface, the Roche limit and the comet breakup distance
+//! to test destruction thresholds.
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use std::f64::consts::TAU;
+
+use super::accessibility::{self, Status};
+use super::interaction::Selection;
+use crate::astronomy::systems::ISON_DESTRUCTION_DISTANCE_AU;
+use crate::astronomy::visual_scale::AU_KM;
+use crate::astronomy::{KeplerOrbit, OrbitCenter, OrbitSamples, SECONDS_PER_DAY};
+use crate::plugins::solar_system::{CelestialBody, LogicalParent};
+use crate::plugins::solar_system_data::BodyType;
+
+/// State of the orbit sandbox window
+#[derive(Resource, Debug, Clone)]
+pub struct OrbitSandbox {
+    pub open: bool,
+    /// Scale the period with the semi-major axis by Kepler's third law
+    pub kepler_period: bool,
+    /// Orbit of the body before its first edit, restored by Reset
+    original: Option<(Entity, KeplerOrbit)>,
+}
+
+impl Default for OrbitSandbox {
+    fn default() -> Self {
+        Self {
+            open: false,
+            kepler_period: true,
+            original: None,
+        }
+    }
+}
+
+/// Periapsis and apoapsis distances (AU) of an orbit
+pub fn apsides(orbit: &KeplerOrbit) -> (f64, f64) {
+    (
+        orbit.semi_major_axis * (1.0 - orbit.eccentricity),
+        orbit.semi_major_axis * (1.0 + orbit.eccentricity),
+    )
+}
+
+/// Rigid-body Roche limit (AU) of a body around its parent, from the masses
+/// (kg) and the body's radius (km); `None` for massless bodies
+pub fn roche_limit_au(parent_mass: f64, body_mass: f64, body_radius_km: f32) -> Option<f64> {
+    (body_mass > 0.0)
+        .then(|| body_radius_km as f64 * (2.0 * parent_mass / body_mass).cbrt() / AU_KM)
+}
+
+/// Mean motion after moving an orbit from `old_axis` to `new_axis`, keeping
+/// the parent's mass by Kepler's third law (T² ∝ a³)
+pub fn kepler_mean_motion(mean_motion: f64, old_axis: f64, new_axis: f64) -> f64 {
+    if old_axis <= 0.0 || new_axis <= 0.0 {
+        return mean_motion;
+    }
+    mean_motion * (old_axis / new_axis).powf(1.5)
+}
+
+/// Toggle the sandbox with Shift+F12 (F12 alone is the research debug mode)
+pub(super) fn toggle_orbit_sandbox(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut sandbox: ResMut<OrbitSandbox>,
+) {
+    let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if shift && keyboard.just_pressed(KeyCode::F12) {
+        sandbox.open = !sandbox.open;
+    }
+}
+
+/// Angle slider in degrees over a value kept in radians
+fn angle_slider(ui: &mut egui::Ui, label: &str, radians: &mut f64, max_degrees: f64) -> bool {
+    let mut degrees = radians.to_degrees();
+    let changed = ui
+        .add(
+            egui::Slider::new(&mut degrees, 0.0..=max_degrees)
+                .suffix("°")
+                .text(label),
+        )
+        .changed();
+    if changed {
+        *radians = degrees.to_radians();
+    }
+    changed
+}
+
+/// Render the sandbox window for the primary selection
+#[allow(clippy::type_complexity)]
+pub(super) fn ui_orbit_sandbox_window(
+    mut contexts: EguiContexts,
+    mut commands: Commands,
+    mut sandbox: ResMut<OrbitSandbox>,
+    selection: Res<Selection>,
+    mut orbits: Query<(
+        &CelestialBody,
+        &mut KeplerOrbit,
+        Option<&OrbitCenter>,
+        Option<&LogicalParent>,
+    )>,
+    parents: Query<&CelestialBody>,
+) {
+    if !sandbox.open {
+        return;
+    }
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    let palette = accessibility::palette(ctx);
+
+    let mut open = sandbox.open;
+    egui::Window::new("🛠 Orbit Sandbox")
+        .open(&mut open)
+        .default_width(320.0)
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new("Debug tool: edits apply to the live simulation")
+                    .small()
+                    .color(palette.status(Status::Warning)),
+            );
+            let Some(entity) = selection.selected else {
+                ui.label("Select a body to edit its orbit.");
+                return;
+            };
+            let Ok((body, mut orbit, center, logical_parent)) = orbits.get_mut(entity) else {
+                ui.label("The selected body has no orbit.");
+                return;
+            };
+            let parent = center
+                .map(|c| c.0)
+                .or(logical_parent.map(|p| p.0))
+                .and_then(|parent| parents.get(parent).ok());
+
+            ui.heading(&body.name);
+            if let Some(parent) = parent {
+                ui.label(format!("Orbiting {}", parent.name));
+            }
+            ui.separator();
+
+            let mut edited = *orbit;
+            let mut changed = ui
+                .add(
+                    egui::Slider::new(&mut edited.semi_major_axis, 1.0e-4..=1.0e4)
+                        .logarithmic(true)
+                        .suffix(" AU")
+                        .text("Semi-major axis"),
+                )
+                .changed();
+            if changed && sandbox.kepler_period {
+                edited.mean_motion = kepler_mean_motion(
+                    orbit.mean_motion,
+                    orbit.semi_major_axis,
+                    edited.semi_major_axis,
+                );
+            }
+            changed |= ui
+                .add(egui::Slider::new(&mut edited.eccentricity, 0.0..=0.99).text("Eccentricity"))
+                .changed();
+            changed |= angle_slider(ui, "Inclination", &mut edited.inclination, 180.0);
+            changed |= angle_slider(
+                ui,
+                "Ascending node",
+                &mut edited.longitude_ascending_node,
+                360.0,
+            );
+            changed |= angle_slider(
+                ui,
+                "Argument of periapsis",
+                &mut edited.argument_of_periapsis,
+                360.0,
+            );
+            changed |= angle_slider(
+                ui,
+                "Mean anomaly at epoch",
+                &mut edited.mean_anomaly_epoch,
+                360.0,
+            );
+            let mut period_days =
+                KeplerOrbit::period_from_mean_motion(edited.mean_motion) / SECONDS_PER_DAY;
+            if ui
+                .add(
+                    egui::Slider::new(&mut period_days, 0.01..=1.0e6)
+                        .logarithmic(true)
+                        .suffix(" d")
+                        .text("Period"),
+                )
+                .changed()
+            {
+                edited.mean_motion = TAU / (period_days * SECONDS_PER_DAY);
+                changed = true;
+            }
+            ui.checkbox(&mut sandbox.kepler_period, "Keep Kepler's third law")
+                .on_hover_text("Scale the period with the semi-major axis");
+
+            ui.horizontal(|ui| {
+                if ui.button("Circularize").clicked() {
+                    edited.eccentricity = 0.0;
+                    changed = true;
+                }
+                let original = sandbox
+                    .original
+                    .filter(|(original, _)| *original == entity)
+                    .map(|(_, orbit)| orbit);
+                if ui
+                    .add_enabled(original.is_some(), egui::Button::new("Reset"))
+                    .clicked()
+                {
+                    if let Some(original) = original {
+                        edited = original;
+                        changed = true;
+                    }
+                }
+            });
+
+            if changed {
+                if sandbox
+                    .original
+                    .is_none_or(|(original, _)| original != entity)
+                {
+                    sandbox.original = Some((entity, *orbit));
+                }
+                *orbit = edited;
+                // Resample at once, even while paused
+                commands.entity(entity).remove::<OrbitSamples>();
+            }
+
+            ui.separator();
+            let (periapsis, apoapsis) = apsides(&edited);
+            egui::Grid::new("orbit_sandbox_grid")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label("Periapsis");
+                    ui.label(format!("{:.6} AU", periapsis));
+                    ui.end_row();
+                    ui.label("Apoapsis");
+                    ui.label(format!("{:.6} AU", apoapsis));
+                    ui.end_row();
+
+                    let threshold = |ui: &mut egui::Ui, label: &str, limit_au: f64| {
+                        ui.label(label);
+                        let status = if periapsis < limit_au {
+                            Status::Bad
+                        } else {
+                            Status::Good
+                        };
+                        ui.colored_label(palette.status(status), format!("{:.6} AU", limit_au));
+                        ui.end_row();
+                    };
+                    if let Some(parent) = parent {
+                        threshold(ui, "Parent surface", parent.radius as f64 / AU_KM);
+                        if let Some(roche) = roche_limit_au(parent.mass, body.mass, body.radius) {
+                            threshold(ui, "Roche limit", roche);
+                        }
+                    }
+                    if body.body_type == BodyType::Comet {
+                        threshold(ui, "Comet breakup", ISON_DESTRUCTION_DISTANCE_AU);
+                    }
+                });
+        });
+    sandbox.open = open;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_orbit_sandbox_helpers() {
+        let orbit = KeplerOrbit::new(0.5, 2.0, 0.0, 0.0, 0.0, 0.0, 1.0);
+        assert_eq!(apsides(&orbit), (1.0, 3.0));
+
+        // Four times farther out takes eight times as long
+        assert!((kepler_mean_motion(1.0, 1.0, 4.0) - 0.125).abs() < 1e-12);
+        assert_eq!(kepler_mean_motion(1.0, 0.0, 4.0), 1.0);
+
+        // The Moon around the Earth: about 9,480 km
+        let roche = roche_limit_au(5.972e24, 7.342e22, 1737.4).unwrap() * AU_KM;
+        assert!((roche - 9_484.0).abs() < 10.0, "{}", roche);
+        assert_eq!(roche_limit_au(5.972e24, 0.0, 1737.4), None);
+    }
+}
@@ -0,0 +1,170 @@
+//! Performance overlay: frame time, schedule timings and entity counts from
+//! the Bevy diagnostics, toggled with F3 or from the options window
+
+use bevy::diagnostic::{
+    DiagnosticPath, DiagnosticsStore, EntityCountDiagnosticsPlugin, FrameTimeDiagnosticsPlugin,
+};
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use egui_plot::{Line, Plot, PlotPoints};
+
+use super::accessibility::{self, Status};
+use super::settings::UiSettings;
+use crate::benchmark::{BenchmarkRun, MAIN_SCHEDULE_TIME, SIMULATION_TIME};
+use crate::construction::OrbitalStation;
+use crate::fleets::Fleet;
+use crate::plugins::solar_system::CelestialBody;
+use crate::plugins::solar_system_data::BodyType;
+
+/// Frame times above this many milliseconds (below 30 FPS) show as a warning
+const SLOW_FRAME_MS: f64 = 33.3;
+
+/// Toggle the overlay with F3
+pub(super) fn toggle_performance_hud(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<UiSettings>,
+) {
+    if keyboard.just_pressed(KeyCode::F3) {
+        settings.performance_hud = !settings.performance_hud;
+    }
+}
+
+/// Smoothed value of a diagnostic
+fn smoothed(store: &DiagnosticsStore, path: &DiagnosticPath) -> Option<f64> {
+    store.get(path).and_then(|diagnostic| diagnostic.smoothed())
+}
+
+/// Render the overlay; always shown during a benchmark run
+pub(super) fn ui_performance_hud(
+    mut contexts: EguiContexts,
+    settings: Res<UiSettings>,
+    store: Res<DiagnosticsStore>,
+    benchmark: Option<Res<BenchmarkRun>>,
+    bodies: Query<&CelestialBody>,
+    fleets: Query<(), With<Fleet>>,
+    stations: Query<(), With<OrbitalStation>>,
+) {
+    if !settings.performance_hud && benchmark.is_none() {
+        return;
+    }
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    let palette = accessibility::palette(ctx);
+
+    let frame_ms = smoothed(&store, &FrameTimeDiagnosticsPlugin::FRAME_TIME);
+    let main_ms = smoothed(&store, &MAIN_SCHEDULE_TIME);
+    let mut counts = [0usize; 5];
+    for body in &bodies {
+        let slot = match body.body_type {
+            BodyType::Star => 0,
+            BodyType::Planet | BodyType::GasGiant | BodyType::DwarfPlanet => 1,
+            BodyType::Moon => 2,
+            BodyType::Asteroid => 3,
+            BodyType::Comet | BodyType::Ring => 4,
+        };
+        counts[slot] += 1;
+    }
+
+    egui::Area::new(egui::Id::new("performance_hud"))
+        .anchor(egui::Align2::RIGHT_BOTTOM, [-12.0, -96.0])
+        .order(egui::Order::Foreground)
+        .interactable(false)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.set_width(240.0);
+                ui.label(egui::RichText::new("Performance (F3)").strong());
+                if let Some(run) = &benchmark {
+                    let status = if run.warming_up() {
+                        "warming up".to_string()
+                    } else {
+                        format!("{} frames", run.samples.len())
+                    };
+                    ui.label(format!(
+                        "Benchmark: {:.0} s left, {}",
+                        run.remaining(),
+                        status
+                    ));
+                }
+                egui::Grid::new("performance_hud_grid")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("FPS");
+                        match smoothed(&store, &FrameTimeDiagnosticsPlugin::FPS) {
+                            Some(fps) => ui.label(format!("{:.0}", fps)),
+                            None => ui.label("-"),
+                        };
+                        ui.end_row();
+                        ui.label("Frame time");
+                        match frame_ms {
+                            Some(ms) => {
+                                let status = if ms > SLOW_FRAME_MS {
+                                    Status::Warning
+                                } else {
+                                    Status::Good
+                                };
+                                ui.colored_label(palette.status(status), format!("{:.2} ms", ms))
+                            }
+                            None => ui.label("-"),
+                        };
+                        ui.end_row();
+                        ui.label("Simulation")
+                            .on_hover_text("Fixed-timestep economy and research systems");
+                        ui.label(format!(
+                            "{:.2} ms",
+                            smoothed(&store, &SIMULATION_TIME).unwrap_or(0.0)
+                        ));
+                        ui.end_row();
+                        ui.label("Main schedule")
+                            .on_hover_text("All game and interface systems of the frame");
+                        ui.label(format!("{:.2} ms", main_ms.unwrap_or(0.0)));
+                        ui.end_row();
+                        if let (Some(frame), Some(main)) = (frame_ms, main_ms) {
+                            ui.label("Render & wait");
+                            ui.label(format!("{:.2} ms", (frame - main).max(0.0)));
+                            ui.end_row();
+                        }
+                        ui.separator();
+                        ui.end_row();
+                        ui.label("Entities");
+                        match smoothed(&store, &EntityCountDiagnosticsPlugin::ENTITY_COUNT) {
+                            Some(count) => ui.label(format!("{:.0}", count)),
+                            None => ui.label("-"),
+                        };
+                        ui.end_row();
+                        for (label, count) in [
+                            ("Stars", counts[0]),
+                            ("Planets", counts[1]),
+                            ("Moons", counts[2]),
+                            ("Asteroids", counts[3]),
+                            ("Comets & rings", counts[4]),
+                            ("Fleets", fleets.iter().count()),
+                            ("Stations", stations.iter().count()),
+                        ] {
+                            ui.label(label);
+                            ui.label(count.to_string());
+                            ui.end_row();
+                        }
+                    });
+
+                // Frame time history kept by the diagnostic
+                if let Some(diagnostic) = store.get(&FrameTimeDiagnosticsPlugin::FRAME_TIME) {
+                    let points: PlotPoints = diagnostic
+                        .values()
+                        .enumerate()
+                        .map(|(i, ms)| [i as f64, *ms])
+                        .collect();
+                    Plot::new("performance_hud_plot")
+                        .height(60.0)
+                        .show_axes([false, true])
+                        .allow_drag(false)
+                        .allow_zoom(false)
+                        .allow_scroll(false)
+                        .include_y(0.0)
+                        .show(ui, |plot_ui| {
+                            plot_ui.line(Line::new(points).name("Frame time (ms)"));
+                        });
+                }
+            });
+        });
+}
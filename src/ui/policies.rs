@@ -0,0 +1,210 @@
+//! Policies tab: empire-wide policies and per-colony edicts with their
+//! trade-offs, upkeep and the free slots opened by Sociology research
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use super::accessibility::{self, Palette, Status};
+use crate::commands::{PlayerCommand, Target};
+use crate::construction::OrbitalStation;
+use crate::economy::components::Population;
+use crate::game_state::{ActiveMenu, GameMenu};
+use crate::plugins::solar_system::CelestialBody;
+use crate::policies::{policy_slots, Policies, PoliciesData, PolicyDefinition, PolicyScope};
+use crate::research::{ResearchState, TechnologiesData};
+
+/// Tab state kept between frames
+#[derive(Default)]
+pub(super) struct PoliciesTabState {
+    /// Colony whose edicts are shown
+    colony: Option<Entity>,
+}
+
+/// Render the policies window while the Policies menu is active
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub(super) fn ui_policies_window(
+    mut contexts: EguiContexts,
+    mut state: Local<PoliciesTabState>,
+    active_menu: Res<ActiveMenu>,
+    policies: Res<Policies>,
+    mut player_commands: EventWriter<PlayerCommand>,
+    research: Res<ResearchState>,
+    data: Option<Res<PoliciesData>>,
+    technologies: Option<Res<TechnologiesData>>,
+    colonies: Query<(
+        Entity,
+        &Population,
+        Option<&CelestialBody>,
+        Option<&OrbitalStation>,
+    )>,
+) {
+    if active_menu.current != GameMenu::Policies {
+        return;
+    }
+    let Some(data) = data else {
+        return;
+    };
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    let palette = accessibility::palette(ctx);
+    let slots = technologies
+        .as_ref()
+        .map_or(0, |t| policy_slots(&research, t));
+
+    let mut colony_names: Vec<(Entity, String)> = colonies
+        .iter()
+        .filter(|(_, population, ..)| population.count > 0.0)
+        .filter_map(|(entity, _, body, station)| {
+            body.map(|b| b.name.clone())
+                .or_else(|| station.map(|s| s.name.clone()))
+                .map(|name| (entity, name))
+        })
+        .collect();
+    colony_names.sort_by(|a, b| a.1.cmp(&b.1));
+    if state
+        .colony
+        .is_some_and(|c| !colony_names.iter().any(|(e, _)| *e == c))
+    {
+        state.colony = None;
+    }
+
+    let view = PolicyView {
+        policies: &policies,
+        research: &research,
+        technologies: technologies.as_deref(),
+        palette: &palette,
+        slots,
+    };
+    egui::Window::new("📜 Policies")
+        .id(egui::Id::new("policies_window"))
+        .default_width(520.0)
+        .resizable(true)
+        .show(ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                ui.heading("Empire Policies");
+                ui.label(format!(
+                    "{} of {} slots in use",
+                    policies.slots_used(None),
+                    slots
+                ))
+                .on_hover_text("Every Sociology technology researched opens another slot");
+                ui.separator();
+                for definition in data.iter().filter(|d| d.scope == PolicyScope::Empire) {
+                    view.policy_row(ui, definition, None, &mut player_commands);
+                }
+
+                ui.add_space(12.0);
+                ui.heading("Colony Edicts");
+                let selected = state
+                    .colony
+                    .and_then(|c| colony_names.iter().find(|(e, _)| *e == c))
+                    .map_or("Select a colony", |(_, name)| name.as_str())
+                    .to_string();
+                egui::ComboBox::from_id_source("policies_colony")
+                    .selected_text(selected)
+                    .show_ui(ui, |ui| {
+                        for (entity, name) in &colony_names {
+                            ui.selectable_value(&mut state.colony, Some(*entity), name);
+                        }
+                    });
+                let Some(colony) = state.colony else {
+                    return;
+                };
+                ui.label(format!(
+                    "{} of {} slots in use",
+                    policies.slots_used(Some(colony)),
+                    slots
+                ));
+                ui.separator();
+                for definition in data.iter().filter(|d| d.scope == PolicyScope::Colony) {
+                    view.policy_row(ui, definition, Some(colony), &mut player_commands);
+                }
+            });
+        });
+}
+
+/// What a policy row needs to know about the civilization
+struct PolicyView<'a> {
+    policies: &'a Policies,
+    research: &'a ResearchState,
+    technologies: Option<&'a TechnologiesData>,
+    palette: &'a Palette,
+    slots: usize,
+}
+
+impl PolicyView<'_> {
+    /// A policy with its trade-offs and upkeep, and a button to enact or
+    /// repeal it in the given scope
+    fn policy_row(
+        &self,
+        ui: &mut egui::Ui,
+        definition: &PolicyDefinition,
+        colony: Option<Entity>,
+        player_commands: &mut EventWriter<PlayerCommand>,
+    ) {
+        let enacted = self.policies.is_enacted(&definition.id, colony);
+        ui.horizontal(|ui| {
+            let name = egui::RichText::new(&definition.name).strong();
+            if enacted {
+                ui.colored_label(
+                    self.palette.status(Status::Good),
+                    self.palette
+                        .status_text(Status::Good, definition.name.clone()),
+                );
+            } else {
+                ui.label(name);
+            }
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if enacted {
+                    if ui.button("Repeal").clicked() {
+                        player_commands.send(PlayerCommand::RevokePolicy {
+                            policy: definition.id.clone(),
+                            colony: colony.map(Target::from),
+                        });
+                    }
+                    return;
+                }
+                match self
+                    .policies
+                    .check_enact(definition, colony, self.slots, self.research)
+                {
+                    Ok(()) => {
+                        if ui.button("Enact").clicked() {
+                            player_commands.send(PlayerCommand::EnactPolicy {
+                                policy: definition.id.clone(),
+                                colony: colony.map(Target::from),
+                            });
+                        }
+                    }
+                    Err(reason) => {
+                        let reason = match &definition.required_tech {
+                            Some(tech) if !self.research.is_unlocked(tech) => format!(
+                                "Requires {}",
+                                self.technologies
+                                    .and_then(|t| t.get_tech(tech))
+                                    .map_or(tech.as_str(), |t| t.name.as_str())
+                            ),
+                            _ => reason,
+                        };
+                        ui.add_enabled(false, egui::Button::new("Enact"))
+                            .on_disabled_hover_text(reason);
+                    }
+                }
+            });
+        });
+        ui.label(egui::RichText::new(&definition.description).weak());
+        let effects = definition.effect_lines();
+        if !effects.is_empty() {
+            ui.label(effects.join(", "));
+        }
+        let upkeep = if definition.upkeep.is_empty() {
+            "No upkeep".to_string()
+        } else {
+            let costs: Vec<String> = definition.upkeep.iter().map(|u| u.describe()).collect();
+            format!("Upkeep: {}", costs.join(", "))
+        };
+        ui.label(egui::RichText::new(upkeep).small());
+        ui.separator();
+    }
+}
@@ -0,0 +1,150 @@
+//! Production chains window: refined goods, their recipes and how well each
+//! chain is supplied, and the food balance, opened from the resources bar
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use super::accessibility::{self, Status};
+use crate::economy::{FoodSupply, GlobalBudget, ProductionStats, RefinedGood, Reservations};
+
+/// Whether the production chains window is shown
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ProductionWindow {
+    pub open: bool,
+}
+
+/// Render the production chains window
+pub(super) fn ui_production_window(
+    mut contexts: EguiContexts,
+    mut window: ResMut<ProductionWindow>,
+    budget: Res<GlobalBudget>,
+    reservations: Res<Reservations>,
+    stats: Res<ProductionStats>,
+    food: Query<&FoodSupply>,
+) {
+    if !window.open {
+        return;
+    }
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    let palette = accessibility::palette(ctx);
+
+    let mut open = true;
+    egui::Window::new("🏭 Production Chains")
+        .id(egui::Id::new("production_window"))
+        .open(&mut open)
+        .resizable(false)
+        .show(ctx, |ui| {
+            for good in RefinedGood::all() {
+                let chain = stats.chain(*good);
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(good.display_name()).strong());
+                    ui.label(format!("{:.1} Mt in stock", budget.get_refined(good)));
+                    let reserved = reservations.reserved_refined(good);
+                    if reserved > 0.0 {
+                        ui.label(
+                            egui::RichText::new(format!("{:.1} Mt reserved", reserved)).weak(),
+                        );
+                    }
+                });
+
+                egui::Grid::new(("production_chain", *good))
+                    .num_columns(2)
+                    .spacing([12.0, 2.0])
+                    .show(ui, |ui| {
+                        ui.label("Inputs per Mt");
+                        ui.vertical(|ui| {
+                            for (resource, per_mt) in good.recipe() {
+                                let short = chain.bottleneck == Some(*resource);
+                                let text = format!(
+                                    "{} {:.2} ({:.1} Mt in stock)",
+                                    resource.display_name(),
+                                    per_mt,
+                                    budget.get_stockpile(resource)
+                                );
+                                if short {
+                                    ui.colored_label(
+                                        palette.status(Status::Bad),
+                                        palette.status_text(Status::Bad, text),
+                                    )
+                                    .on_hover_text("Bottleneck: not enough in the stockpile");
+                                } else {
+                                    ui.label(text);
+                                }
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label("Refineries");
+                        ui.label(chain.refineries.to_string());
+                        ui.end_row();
+
+                        ui.label("Output");
+                        if chain.refineries == 0 {
+                            ui.label(egui::RichText::new("No refineries built").weak());
+                        } else {
+                            ui.add(
+                                egui::ProgressBar::new(chain.utilization() as f32)
+                                    .desired_width(180.0)
+                                    .text(format!(
+                                        "{:.2} / {:.2} Mt/yr",
+                                        chain.output_mt_per_year, chain.capacity_mt_per_year
+                                    )),
+                            );
+                        }
+                        ui.end_row();
+                    });
+                ui.separator();
+            }
+
+            let (mut grown, mut eaten, mut imported, mut hungry) = (0.0, 0.0, 0.0, 0);
+            for supply in food.iter() {
+                grown += supply.produced_mt_per_year;
+                eaten += supply.consumed_mt_per_year;
+                imported += supply.imported_mt_per_year;
+                if supply.shortage > 0.0 {
+                    hungry += 1;
+                }
+            }
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new("Food").strong());
+                ui.label(format!("{:.1} Mt in stock", budget.food));
+            });
+            egui::Grid::new("production_food")
+                .num_columns(2)
+                .spacing([12.0, 2.0])
+                .show(ui, |ui| {
+                    ui.label("Grown");
+                    ui.label(format!("{:.2} Mt/yr", grown));
+                    ui.end_row();
+                    ui.label("Eaten");
+                    ui.label(format!("{:.2} Mt/yr", eaten));
+                    ui.end_row();
+                    ui.label("Shipped to importers");
+                    ui.label(format!("{:.2} Mt/yr", imported));
+                    ui.end_row();
+                    if hungry > 0 {
+                        ui.label("Shortages");
+                        ui.colored_label(
+                            palette.status(Status::Bad),
+                            palette.status_text(Status::Bad, format!("{} colonies", hungry)),
+                        );
+                        ui.end_row();
+                    }
+                });
+            ui.separator();
+
+            ui.label(
+                egui::RichText::new(
+                    "Construction and ordnance are paid in refined goods. \
+                     Build refineries in the Construction window.",
+                )
+                .weak(),
+            );
+        });
+
+    if !open {
+        window.open = false;
+    }
+}
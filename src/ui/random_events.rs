@@ -0,0 +1,86 @@
+//! Random event dialog: a modal window presenting the oldest pending event
+//! and its choices
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use super::event_log::severity_color;
+use crate::commands::PlayerCommand;
+use crate::random_events::{EventsData, PendingEvents};
+
+/// Render the dialog of the front pending event. The rest of the interface
+/// is dimmed and blocked until a choice is made.
+pub(super) fn ui_random_event_dialog(
+    mut contexts: EguiContexts,
+    pending: Res<PendingEvents>,
+    mut player_commands: EventWriter<PlayerCommand>,
+    events: Option<Res<EventsData>>,
+) {
+    if pending.choice.is_some() {
+        return;
+    }
+    let Some(active) = pending.queue.front() else {
+        return;
+    };
+    let Some(event) = events.as_ref().and_then(|events| events.get(&active.id)) else {
+        return;
+    };
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    // Backdrop that swallows clicks meant for the rest of the interface
+    egui::Area::new(egui::Id::new("random_event_backdrop"))
+        .order(egui::Order::Middle)
+        .fixed_pos(egui::Pos2::ZERO)
+        .show(ctx, |ui| {
+            let screen = ui.ctx().screen_rect();
+            ui.allocate_rect(screen, egui::Sense::click());
+            ui.painter()
+                .rect_filled(screen, 0.0, egui::Color32::from_black_alpha(140));
+        });
+
+    let mut choice = None;
+    egui::Window::new(egui::RichText::new(&event.title).color(severity_color(ctx, event.severity)))
+        .id(egui::Id::new("random_event_dialog"))
+        .order(egui::Order::Foreground)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .collapsible(false)
+        .resizable(false)
+        .default_width(380.0)
+        .show(ctx, |ui| {
+            if let Some(colony) = &active.target_name {
+                ui.label(egui::RichText::new(colony).weak());
+            }
+            ui.label(event.text(active.target_name.as_deref()));
+            // Anomaly, contact and script events can queue up behind this one
+            let waiting = pending.queue.len() - 1;
+            if waiting > 0 {
+                let text = if waiting == 1 {
+                    "1 more event waiting".to_string()
+                } else {
+                    format!("{} more events waiting", waiting)
+                };
+                ui.label(egui::RichText::new(text).small().weak());
+            }
+            ui.separator();
+            for (index, option) in event.choices.iter().enumerate() {
+                let button = ui.add_sized(
+                    [ui.available_width(), 24.0],
+                    egui::Button::new(&option.label),
+                );
+                let button = if option.description.is_empty() {
+                    button
+                } else {
+                    button.on_hover_text(&option.description)
+                };
+                if button.clicked() {
+                    choice = Some(index);
+                }
+            }
+        });
+
+    if let Some(choice) = choice {
+        player_commands.send(PlayerCommand::EventChoice(choice));
+    }
+}
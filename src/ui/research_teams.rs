@@ -0,0 +1,161 @@
+//! Research team management in the Research overview: levels per category,
+//! hiring, renaming and retiring teams
+
+use bevy::prelude::*;
+use bevy_egui::egui;
+
+use crate::commands::PlayerCommand;
+use crate::economy::format_currency;
+use crate::research::{
+    ResearchProject, ResearchTeam, TechCategory, TechnologiesData, MAX_RESEARCH_TEAMS,
+    TEAM_HIRE_COST,
+};
+
+/// Specialty picked for the next hire, and the team being renamed
+#[derive(Debug, Clone, Default)]
+struct TeamsDraft {
+    specialty: Option<TechCategory>,
+    renaming: Option<(Entity, String)>,
+}
+
+/// Render the teams section of the Research overview
+pub(super) fn teams_section(
+    ui: &mut egui::Ui,
+    tech_data: &TechnologiesData,
+    research_projects: &Query<(Entity, &ResearchProject)>,
+    all_teams: &Query<(Entity, &ResearchTeam)>,
+    player_commands: &mut EventWriter<PlayerCommand>,
+) {
+    let draft_id = ui.id().with("research_teams_draft");
+    let mut draft: TeamsDraft = ui.data_mut(|data| data.get_temp(draft_id).unwrap_or_default());
+
+    let mut teams: Vec<(Entity, &ResearchTeam)> =
+        all_teams.iter().filter(|(_, t)| t.is_research).collect();
+    teams.sort_by(|a, b| a.1.name.cmp(&b.1.name));
+
+    ui.label(
+        egui::RichText::new(format!(
+            "Research Teams ({}/{})",
+            teams.len(),
+            MAX_RESEARCH_TEAMS
+        ))
+        .strong()
+        .size(16.0),
+    );
+    ui.separator();
+    if teams.is_empty() {
+        ui.label(
+            egui::RichText::new("No research teams: hire one to start research")
+                .italics()
+                .color(egui::Color32::GRAY),
+        );
+    }
+
+    for (entity, team) in &teams {
+        let project = research_projects
+            .iter()
+            .find(|(_, p)| p.team_id == *entity)
+            .map(|(_, p)| p);
+        ui.horizontal(|ui| {
+            match &mut draft.renaming {
+                Some((renamed, name)) if renamed == entity => {
+                    let response = ui.text_edit_singleline(name);
+                    let submitted =
+                        response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    if ui.button("✔").clicked() || submitted {
+                        player_commands.send(PlayerCommand::RenameTeam {
+                            team: (*entity).into(),
+                            name: name.clone(),
+                        });
+                        draft.renaming = None;
+                    } else if ui.button("✖").clicked() {
+                        draft.renaming = None;
+                    }
+                }
+                _ => {
+                    ui.label(egui::RichText::new(format!("🔬 {}", team.name)).strong());
+                    if ui.small_button("✏").on_hover_text("Rename").clicked() {
+                        draft.renaming = Some((*entity, team.name.clone()));
+                    }
+                }
+            }
+            ui.label(format!("Lead: {}", team.lead_character));
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                let retire = ui
+                    .add_enabled(project.is_none(), egui::Button::new("Retire"))
+                    .on_disabled_hover_text("Teams on a project cannot be retired");
+                if retire.clicked() {
+                    player_commands.send(PlayerCommand::RetireTeam((*entity).into()));
+                }
+            });
+        });
+
+        let status = match project {
+            Some(project) => format!(
+                "  Working on {}{}",
+                tech_data
+                    .get_tech(&project.tech_id)
+                    .map_or(project.tech_id.as_str(), |t| t.name.as_str()),
+                if project.active { "" } else { " (paused)" }
+            ),
+            None => "  Idle".to_string(),
+        };
+        ui.label(status);
+        if let Some(specialty) = team.specialty {
+            ui.label(format!(
+                "  Specialty: {} {} ({:.0}% efficiency)",
+                specialty.icon(),
+                specialty.display_name(),
+                team.category_efficiency(specialty) * 100.0
+            ));
+        }
+        let mut levels: Vec<(TechCategory, u32)> = TechCategory::all()
+            .iter()
+            .map(|c| (*c, team.level(*c)))
+            .filter(|(_, level)| *level > 0)
+            .collect();
+        levels.sort_by_key(|(_, level)| std::cmp::Reverse(*level));
+        if !levels.is_empty() {
+            let text = levels
+                .iter()
+                .map(|(category, level)| {
+                    format!("{} {} {}", category.icon(), category.display_name(), level)
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            ui.label(egui::RichText::new(format!("  Levels: {}", text)).weak());
+        }
+        ui.add_space(5.0);
+    }
+
+    ui.separator();
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_id_source("research_team_specialty")
+            .selected_text(
+                draft
+                    .specialty
+                    .map_or("No specialty", |category| category.display_name()),
+            )
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut draft.specialty, None, "No specialty");
+                for category in TechCategory::all() {
+                    ui.selectable_value(
+                        &mut draft.specialty,
+                        Some(*category),
+                        format!("{} {}", category.icon(), category.display_name()),
+                    );
+                }
+            });
+        let hire = ui
+            .add_enabled(
+                teams.len() < MAX_RESEARCH_TEAMS,
+                egui::Button::new("➕ Hire team"),
+            )
+            .on_hover_text(format!("Costs {}", format_currency(TEAM_HIRE_COST)));
+        if hire.clicked() {
+            player_commands.send(PlayerCommand::HireTeam(draft.specialty));
+        }
+    });
+
+    ui.data_mut(|data| data.insert_temp(draft_id, draft));
+}
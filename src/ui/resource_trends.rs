@@ -0,0 +1,115 @@
+//! Resource trend graphs: stockpile history over simulation time, opened from
+//! the resource category popups
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use egui_plot::{Legend, Line, Plot, PlotPoints};
+
+use super::accessibility;
+use crate::economy::{ResourceType, StockpileHistory, SECONDS_PER_YEAR};
+
+/// Which resources the trends window plots
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ResourceTrendsWindow {
+    pub open: bool,
+    /// Plotted resources
+    pub shown: Vec<ResourceType>,
+}
+
+impl ResourceTrendsWindow {
+    /// Open the window showing the given resources
+    pub fn show_resources(&mut self, resources: &[ResourceType]) {
+        self.open = true;
+        self.shown = resources.to_vec();
+    }
+}
+
+/// Plot points of a series with time converted to years since the start
+fn plot_points(series: &[(f64, f64)]) -> PlotPoints {
+    series
+        .iter()
+        .map(|(seconds, amount)| [seconds / SECONDS_PER_YEAR, *amount])
+        .collect()
+}
+
+/// Render the resource trends window
+pub(super) fn ui_resource_trends_window(
+    mut contexts: EguiContexts,
+    mut window: ResMut<ResourceTrendsWindow>,
+    history: Res<StockpileHistory>,
+) {
+    if !window.open {
+        return;
+    }
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    let palette = accessibility::palette(ctx);
+
+    let mut open = true;
+    egui::Window::new("📈 Resource Trends")
+        .id(egui::Id::new("resource_trends_window"))
+        .open(&mut open)
+        .default_size([520.0, 320.0])
+        .resizable(true)
+        .show(ctx, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                for (category, resources) in ResourceType::by_category() {
+                    let color = palette.category(category);
+                    ui.menu_button(egui::RichText::new(category).color(color), |ui| {
+                        for resource in resources {
+                            let mut shown = window.shown.contains(&resource);
+                            if ui.checkbox(&mut shown, resource.display_name()).changed() {
+                                if shown {
+                                    window.shown.push(resource);
+                                } else {
+                                    window.shown.retain(|r| *r != resource);
+                                }
+                            }
+                        }
+                    });
+                }
+            });
+            ui.separator();
+
+            if history.samples.len() < 2 {
+                ui.label(
+                    egui::RichText::new("Not enough history yet — samples are taken weekly")
+                        .weak(),
+                );
+                return;
+            }
+
+            Plot::new("resource_trends_plot")
+                .legend(Legend::default())
+                .x_axis_label("Years")
+                .y_axis_label("Stockpile (Mt)")
+                .show(ui, |plot_ui| {
+                    // Resource order keeps the legend stable as lines are toggled
+                    for resource in ResourceType::all()
+                        .iter()
+                        .filter(|r| window.shown.contains(r))
+                    {
+                        let points = plot_points(&history.series(*resource));
+                        plot_ui.line(Line::new(points).name(resource.display_name()));
+                    }
+                });
+        });
+
+    if !open {
+        window.open = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plot_points_are_in_years() {
+        let points = plot_points(&[(0.0, 5.0), (SECONDS_PER_YEAR * 2.0, 7.0)]);
+        let points = points.points();
+        assert_eq!(points[1].x, 2.0);
+        assert_eq!(points[1].y, 7.0);
+    }
+}
@@ -0,0 +1,169 @@
+//! Route planner window shown on the starmap
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use crate::plugins::camera::ViewMode;
+use crate::plugins::route_planner::{
+    best_drive, plot_route, system_positions, travel_time_years, RoutePlanner,
+};
+use crate::plugins::starmap::{SelectedStarSystem, StarSystemIcon};
+use crate::research::ResearchState;
+
+/// Format a travel time given in years
+fn format_travel_time(years: f64) -> String {
+    if years < 1.0 {
+        format!("{:.0} days", years * 365.25)
+    } else if years < 1000.0 {
+        format!("{:.1} years", years)
+    } else {
+        format!("{:.1}k years", years / 1000.0)
+    }
+}
+
+/// Render the route planner window in starmap view
+pub(super) fn ui_route_planner_window(
+    mut contexts: EguiContexts,
+    view_mode: Res<ViewMode>,
+    mut planner: ResMut<RoutePlanner>,
+    research_state: Option<Res<ResearchState>>,
+    icons: Query<&StarSystemIcon>,
+    selected: Query<&StarSystemIcon, With<SelectedStarSystem>>,
+) {
+    if *view_mode != ViewMode::Starmap {
+        return;
+    }
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    let mut systems: Vec<&StarSystemIcon> = icons.iter().collect();
+    systems.sort_by_key(|icon| icon.id);
+    let name_of = |id: usize| {
+        systems
+            .iter()
+            .find(|icon| icon.id == id)
+            .map_or("Unknown", |icon| icon.name.as_str())
+    };
+    let drive = best_drive(research_state.as_deref());
+
+    let mut changed = false;
+    egui::Window::new("🧭 Route Planner")
+        .id(egui::Id::new("route_planner_window"))
+        .default_open(false)
+        .anchor(egui::Align2::LEFT_BOTTOM, egui::vec2(10.0, -10.0))
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(format!(
+                "Drive: {} ({}c, {:.0} ly jumps)",
+                drive.name, drive.cruise_speed_c, drive.jump_range_ly
+            ));
+            ui.separator();
+
+            let mut from = planner.from;
+            egui::ComboBox::from_label("From")
+                .selected_text(name_of(from))
+                .show_ui(ui, |ui| {
+                    for icon in &systems {
+                        ui.selectable_value(&mut from, icon.id, &icon.name);
+                    }
+                });
+            let mut to = planner.to;
+            egui::ComboBox::from_label("To")
+                .selected_text(to.map_or("—", name_of))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut to, None, "—");
+                    for icon in &systems {
+                        ui.selectable_value(&mut to, Some(icon.id), &icon.name);
+                    }
+                });
+            if let Ok(icon) = selected.get_single() {
+                ui.horizontal(|ui| {
+                    if ui.small_button("Selected as origin").clicked() {
+                        from = icon.id;
+                    }
+                    if ui.small_button("Selected as destination").clicked() {
+                        to = Some(icon.id);
+                    }
+                });
+            }
+
+            let mut custom_range = planner.jump_range_ly.is_some();
+            let mut range = planner.jump_range(drive);
+            ui.checkbox(&mut custom_range, "Custom jump range");
+            ui.add_enabled(
+                custom_range,
+                egui::Slider::new(&mut range, 0.5..=30.0).suffix(" ly"),
+            );
+            let jump_range_ly = custom_range.then_some(range);
+
+            let mut rings = planner.rings;
+            ui.add(egui::Slider::new(&mut rings, 0..=5).text("Reachability rings"));
+            planner.rings = rings;
+
+            if from != planner.from || to != planner.to || jump_range_ly != planner.jump_range_ly {
+                planner.from = from;
+                planner.to = to;
+                planner.jump_range_ly = jump_range_ly;
+                changed = true;
+            }
+
+            ui.separator();
+            let Some(to) = planner.to else {
+                ui.label(egui::RichText::new("Pick a destination to plot a route").weak());
+                return;
+            };
+            let Some(route) = &planner.route else {
+                ui.label(
+                    egui::RichText::new(format!(
+                        "{} is out of reach with {:.1} ly jumps",
+                        name_of(to),
+                        planner.jump_range(drive)
+                    ))
+                    .color(egui::Color32::from_rgb(255, 120, 100)),
+                );
+                return;
+            };
+
+            egui::Grid::new("route_legs").striped(true).show(ui, |ui| {
+                ui.label(egui::RichText::new("System").strong());
+                ui.label(egui::RichText::new("Leg").strong());
+                ui.label(egui::RichText::new("Total").strong());
+                ui.label(egui::RichText::new("Time").strong());
+                ui.end_row();
+
+                ui.label(name_of(planner.from));
+                ui.end_row();
+                for ((id, leg), total) in route
+                    .systems
+                    .iter()
+                    .skip(1)
+                    .zip(&route.legs_ly)
+                    .zip(route.cumulative_ly())
+                {
+                    ui.label(name_of(*id));
+                    ui.label(format!("{:.2} ly", leg));
+                    ui.label(format!("{:.2} ly", total));
+                    ui.label(format_travel_time(travel_time_years(
+                        total,
+                        drive.cruise_speed_c,
+                    )));
+                    ui.end_row();
+                }
+            });
+            ui.label(format!(
+                "{} jumps, {:.2} ly, {}",
+                route.legs_ly.len(),
+                route.total_ly(),
+                format_travel_time(travel_time_years(route.total_ly(), drive.cruise_speed_c))
+            ));
+        });
+
+    // Replot when the inputs change or the drive improves
+    if changed || research_state.as_ref().is_some_and(|r| r.is_changed()) {
+        planner.route = planner.to.and_then(|to| {
+            let positions = system_positions(systems.iter().copied());
+            plot_route(&positions, planner.from, to, planner.jump_range(drive))
+        });
+    }
+}
@@ -0,0 +1,80 @@
+//! Sensor contacts section of the Intel window: what the sensors picked up,
+//! how well each contact is tracked and what the identified ones turned out
+//! to be
+
+use bevy_egui::egui;
+
+use super::accessibility::{Palette, Status};
+use crate::hotseat::HotseatView;
+use crate::intel::contacts::IDENTIFY_AU;
+use crate::intel::{ContactKind, SensorContact, SensorContacts};
+
+/// Render the contacts the sensors track, leaving out those the hotseat
+/// `view` hides
+pub(super) fn sensor_contacts_section(
+    ui: &mut egui::Ui,
+    palette: &Palette,
+    contacts: &SensorContacts,
+    view: Option<&HotseatView>,
+) {
+    ui.heading("Sensor Contacts");
+    let shown: Vec<&SensorContact> = contacts
+        .contacts
+        .iter()
+        .filter(|c| !view.is_some_and(|v| v.is_hidden(c.target)))
+        .collect();
+    if shown.is_empty() {
+        ui.label(egui::RichText::new("Nothing on the sensors").weak());
+        return;
+    }
+
+    for contact in shown {
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new(contact.label()).strong());
+            if contact.identified {
+                ui.label(contact.kind.name());
+            } else {
+                ui.label(egui::RichText::new("Unknown").weak());
+            }
+            ui.label(format!(
+                "{:.2} AU from {}",
+                contact.range_au, contact.sensor
+            ));
+        });
+        let (along, across) = contact.uncertainty_au();
+        if contact.unseen_days > 0.0 {
+            ui.colored_label(
+                palette.status(Status::Warning),
+                palette.status_text(
+                    Status::Warning,
+                    format!(
+                        "Out of sensor range for {:.0} days, ± {:.3} AU",
+                        contact.unseen_days, along
+                    ),
+                ),
+            );
+        } else if contact.identified {
+            let status = if contact.kind == ContactKind::Ship {
+                Status::Bad
+            } else {
+                Status::Good
+            };
+            ui.colored_label(
+                palette.status(status),
+                palette.status_text(
+                    status,
+                    format!("Identified after {:.0} days", contact.tracked_days),
+                ),
+            );
+        } else {
+            ui.label(format!(
+                "Tracked {:.0} days: ± {:.3} AU along the line of sight, ± {:.3} AU across",
+                contact.tracked_days, along, across
+            ))
+            .on_hover_text(format!(
+                "The uncertainty shrinks as the contact is tracked, the faster the closer the sensor. It is identified once it falls below {} AU.",
+                IDENTIFY_AU
+            ));
+        }
+    }
+}
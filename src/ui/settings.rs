@@ -0,0 +1,348 @@
+//! UI scaling, font size, accessibility, volume, trajectory, tooltip and
+//! performance overlay settings
+//!
+//! The global scale is applied through `EguiSettings::scale_factor`, which
+//! multiplies egui's pixels-per-point on top of the window's own DPI factor.
+//! Font sizes are set per panel so a cramped ledger can be shrunk without
+//! making the selection panel unreadable. Settings are stored in a RON file
+//! in the working directory and written back whenever they change.
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiSettings};
+use serde::{Deserialize, Serialize};
+
+use super::accessibility::{AccessibilitySettings, ColorPalette};
+use super::layout::PanelLayouts;
+use super::mods::ModListWindow;
+use super::tooltip::{default_tooltip_fields, TooltipField};
+use crate::astronomy::TrajectorySettings;
+use crate::sound::VolumeSettings;
+
+/// File the UI settings are persisted to
+pub const SETTINGS_PATH: &str = "settings.ron";
+
+/// Smallest and largest global UI scale
+pub const MIN_UI_SCALE: f32 = 0.5;
+pub const MAX_UI_SCALE: f32 = 3.0;
+
+/// Smallest and largest body font size in points
+pub const MIN_FONT_SIZE: f32 = 8.0;
+pub const MAX_FONT_SIZE: f32 = 32.0;
+
+/// egui's default body font size
+pub const DEFAULT_FONT_SIZE: f32 = 14.0;
+
+/// Player-configurable UI scale and per-panel font sizes
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiSettings {
+    /// Multiplier on egui's pixels-per-point (1.0 = native)
+    pub ui_scale: f32,
+    /// Body font size of the left ledger panel
+    pub ledger_font_size: f32,
+    /// Body font size of the right selection panel
+    pub selection_font_size: f32,
+    /// Body font size of the bottom time controls
+    pub time_controls_font_size: f32,
+    /// Docked, collapsed and detached state of the main panels
+    pub layout: PanelLayouts,
+    /// Colorblind palette, status shapes and high-contrast theme
+    pub accessibility: AccessibilitySettings,
+    /// Music and sound effect volumes
+    pub audio: VolumeSettings,
+    /// Predicted trails and ghosts of selected bodies
+    pub trajectory: TrajectorySettings,
+    /// Extra rows of the body hover tooltip
+    pub tooltip_fields: Vec<TooltipField>,
+    /// Frame time and entity count overlay (F3)
+    pub performance_hud: bool,
+}
+
+impl Default for UiSettings {
+    fn default() -> Self {
+        Self {
+            ui_scale: 1.0,
+            ledger_font_size: DEFAULT_FONT_SIZE,
+            selection_font_size: DEFAULT_FONT_SIZE,
+            time_controls_font_size: DEFAULT_FONT_SIZE,
+            layout: PanelLayouts::default(),
+            accessibility: AccessibilitySettings::default(),
+            audio: VolumeSettings::default(),
+            trajectory: TrajectorySettings::default(),
+            tooltip_fields: default_tooltip_fields(),
+            performance_hud: false,
+        }
+    }
+}
+
+impl UiSettings {
+    /// Clamp every value into its supported range
+    pub fn clamped(mut self) -> Self {
+        self.ui_scale = self.ui_scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+        for size in [
+            &mut self.ledger_font_size,
+            &mut self.selection_font_size,
+            &mut self.time_controls_font_size,
+        ] {
+            *size = size.clamp(MIN_FONT_SIZE, MAX_FONT_SIZE);
+        }
+        self.audio = self.audio.clamped();
+        self.trajectory = self.trajectory.clamped();
+        // Rows keep a fixed order however they were picked
+        self.tooltip_fields.sort();
+        self.tooltip_fields.dedup();
+        self
+    }
+
+    /// Parse settings from RON, falling back to defaults for missing fields
+    pub fn from_ron(contents: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str::<Self>(contents).map(Self::clamped)
+    }
+
+    /// Load settings from `path`, using defaults if the file is missing or invalid
+    pub fn load(path: &str) -> Self {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => Self::from_ron(&contents).unwrap_or_else(|e| {
+                warn!("Invalid UI settings in {}: {}; using defaults", path, e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Write settings to `path`
+    pub fn save(&self, path: &str) {
+        match ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::new()) {
+            Ok(contents) => match std::fs::write(path, contents) {
+                Ok(()) => info!("Saved UI settings to {}", path),
+                Err(e) => error!("Failed to write UI settings file: {}", e),
+            },
+            Err(e) => error!("Failed to serialize UI settings: {}", e),
+        }
+    }
+}
+
+/// Whether the options window is open
+#[derive(Resource, Debug, Clone, Default)]
+pub struct UiSettingsWindow {
+    pub open: bool,
+}
+
+/// Scale the text styles of `ui` so body text is `size` points
+pub fn apply_font_size(ui: &mut egui::Ui, size: f32) {
+    let factor = size / DEFAULT_FONT_SIZE;
+    let defaults = egui::Style::default().text_styles;
+    for (style, font) in ui.style_mut().text_styles.iter_mut() {
+        if let Some(default) = defaults.get(style) {
+            font.size = default.size * factor;
+        }
+    }
+}
+
+/// Push the global scale into egui whenever it changes
+pub(super) fn apply_ui_scale(settings: Res<UiSettings>, mut egui_settings: ResMut<EguiSettings>) {
+    if settings.is_changed() {
+        egui_settings.scale_factor = settings.ui_scale;
+    }
+}
+
+/// Persist the settings after the player edits them. Writes are held back
+/// while the mouse is down so dragging a slider or panel edge saves once.
+pub(super) fn save_ui_settings(
+    settings: Res<UiSettings>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut pending: Local<bool>,
+) {
+    if settings.is_changed() && !settings.is_added() {
+        *pending = true;
+    }
+    if *pending && !mouse.pressed(MouseButton::Left) {
+        settings.save(SETTINGS_PATH);
+        *pending = false;
+    }
+}
+
+/// Options window with the scale and font size sliders
+pub(super) fn ui_settings_window(
+    mut contexts: EguiContexts,
+    mut window: ResMut<UiSettingsWindow>,
+    mut settings: ResMut<UiSettings>,
+    mut mods_window: ResMut<ModListWindow>,
+) {
+    if !window.open {
+        return;
+    }
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    let mut edited = settings.clone();
+    egui::Window::new("⚙ Options")
+        .open(&mut window.open)
+        .resizable(false)
+        .collapsible(false)
+        .show(ctx, |ui| {
+            ui.heading("Interface");
+            ui.add(
+                egui::Slider::new(&mut edited.ui_scale, MIN_UI_SCALE..=MAX_UI_SCALE)
+                    .step_by(0.05)
+                    .text("UI scale"),
+            );
+            ui.checkbox(&mut edited.performance_hud, "Performance overlay (F3)");
+            ui.separator();
+            ui.label(egui::RichText::new("Font sizes").strong());
+            for (label, size) in [
+                ("Ledger", &mut edited.ledger_font_size),
+                ("Selection panel", &mut edited.selection_font_size),
+                ("Time controls", &mut edited.time_controls_font_size),
+            ] {
+                ui.add(
+                    egui::Slider::new(size, MIN_FONT_SIZE..=MAX_FONT_SIZE)
+                        .step_by(1.0)
+                        .text(label),
+                );
+            }
+            ui.separator();
+            ui.heading("Accessibility");
+            egui::ComboBox::from_label("Color palette")
+                .selected_text(edited.accessibility.palette.name())
+                .show_ui(ui, |ui| {
+                    for palette in ColorPalette::ALL {
+                        ui.selectable_value(
+                            &mut edited.accessibility.palette,
+                            palette,
+                            palette.name(),
+                        );
+                    }
+                });
+            ui.checkbox(
+                &mut edited.accessibility.status_shapes,
+                "Shapes on status indicators",
+            )
+            .on_hover_text("Mark power, heat and technology states with ✔ ⚠ ✖ ○ 🔒 as well as color");
+            ui.checkbox(&mut edited.accessibility.high_contrast, "High-contrast theme");
+            ui.separator();
+            ui.heading("Audio");
+            for (label, volume) in [
+                ("Master volume", &mut edited.audio.master),
+                ("Music", &mut edited.audio.music),
+                ("Effects", &mut edited.audio.effects),
+            ] {
+                ui.add(egui::Slider::new(volume, 0.0..=1.0).text(label));
+            }
+            ui.separator();
+            ui.heading("Trajectory prediction");
+            ui.checkbox(
+                &mut edited.trajectory.enabled,
+                "Show predicted paths of selected bodies",
+            );
+            ui.add_enabled_ui(edited.trajectory.enabled, |ui| {
+                ui.add(
+                    egui::Slider::new(
+                        &mut edited.trajectory.horizon_days,
+                        TrajectorySettings::MIN_HORIZON_DAYS
+                            ..=TrajectorySettings::MAX_HORIZON_DAYS,
+                    )
+                    .logarithmic(true)
+                    .suffix(" days")
+                    .text("Horizon"),
+                );
+                ui.add(
+                    egui::Slider::new(
+                        &mut edited.trajectory.ghosts,
+                        1..=TrajectorySettings::MAX_GHOSTS,
+                    )
+                    .text("Ghosts"),
+                );
+            });
+            ui.separator();
+            ui.heading("Body tooltips");
+            for field in TooltipField::ALL {
+                let mut shown = edited.tooltip_fields.contains(&field);
+                if ui.checkbox(&mut shown, field.name()).changed() {
+                    if shown {
+                        edited.tooltip_fields.push(field);
+                        edited.tooltip_fields.sort();
+                    } else {
+                        edited.tooltip_fields.retain(|f| *f != field);
+                    }
+                }
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button("Reset panel layout").clicked() {
+                    edited.layout = PanelLayouts::default();
+                }
+                if ui.button("Reset to defaults").clicked() {
+                    edited = UiSettings::default();
+                }
+            });
+            ui.separator();
+            if ui.button("🧩 Mods").clicked() {
+                mods_window.open = true;
+            }
+        });
+
+    // Only touch the resource on an actual edit so change detection does not
+    // save the file every frame the window is open
+    if edited != *settings {
+        *settings = edited;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_fields_use_defaults() {
+        let settings = UiSettings::from_ron("(ui_scale: 1.5)").unwrap();
+        assert_eq!(settings.ui_scale, 1.5);
+        assert_eq!(settings.ledger_font_size, DEFAULT_FONT_SIZE);
+    }
+
+    #[test]
+    fn test_out_of_range_values_are_clamped() {
+        let settings = UiSettings::from_ron("(ui_scale: 10.0, selection_font_size: 2.0)").unwrap();
+        assert_eq!(settings.ui_scale, MAX_UI_SCALE);
+        assert_eq!(settings.selection_font_size, MIN_FONT_SIZE);
+
+        let settings =
+            UiSettings::from_ron("(tooltip_fields: [Population, OrbitalPeriod, Population])")
+                .unwrap();
+        assert_eq!(
+            settings.tooltip_fields,
+            vec![TooltipField::OrbitalPeriod, TooltipField::Population]
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let settings = UiSettings {
+            ui_scale: 1.25,
+            ledger_font_size: 12.0,
+            selection_font_size: 18.0,
+            time_controls_font_size: 16.0,
+            layout: PanelLayouts::default(),
+            accessibility: AccessibilitySettings {
+                palette: ColorPalette::Deuteranopia,
+                status_shapes: true,
+                high_contrast: false,
+            },
+            audio: VolumeSettings {
+                master: 0.6,
+                music: 0.0,
+                effects: 1.0,
+            },
+            trajectory: TrajectorySettings {
+                enabled: false,
+                horizon_days: 365.0,
+                ghosts: 4,
+            },
+            tooltip_fields: vec![TooltipField::SurfaceGravity, TooltipField::SurveyLevel],
+            performance_hud: true,
+        };
+        let contents = ron::to_string(&settings).unwrap();
+        assert_eq!(UiSettings::from_ron(&contents).unwrap(), settings);
+    }
+}
@@ -0,0 +1,149 @@
+//! Shipbuilding window: the slipways and hull queue of every shipyard, with
+//! hull orders under the Shipbuilding menu
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use crate::commands::PlayerCommand;
+use crate::construction::OrbitalStation;
+use crate::fleets::{Shipyard, HULLS};
+use crate::game_state::{ActiveMenu, GameMenu};
+use crate::plugins::solar_system::CelestialBody;
+
+/// Render the shipbuilding window
+pub(super) fn ui_shipyards_window(
+    mut contexts: EguiContexts,
+    active_menu: Res<ActiveMenu>,
+    mut design: Local<Option<&'static str>>,
+    mut player_commands: EventWriter<PlayerCommand>,
+    yards: Query<(Entity, &Shipyard)>,
+    stations: Query<&OrbitalStation>,
+    bodies: Query<&CelestialBody>,
+) {
+    if active_menu.current != GameMenu::Shipbuilding {
+        return;
+    }
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    let name_of = |entity: Entity| {
+        stations
+            .get(entity)
+            .map(|station| station.name.clone())
+            .or_else(|_| bodies.get(entity).map(|body| body.name.clone()))
+            .unwrap_or_else(|_| "Unknown".to_string())
+    };
+    let design = design.get_or_insert(HULLS[0].id);
+
+    egui::Window::new("🛠 Shipyards")
+        .id(egui::Id::new("shipyards_window"))
+        .default_size([520.0, 360.0])
+        .resizable(true)
+        .show(ctx, |ui| {
+            if yards.is_empty() {
+                ui.label(
+                    egui::RichText::new("No shipyards - build one at a colony or station").weak(),
+                );
+                return;
+            }
+
+            ui.horizontal(|ui| {
+                ui.label("Design:");
+                let selected = crate::fleets::hull(design).map_or("-", |hull| hull.name);
+                egui::ComboBox::from_id_source("hull_design")
+                    .selected_text(selected)
+                    .show_ui(ui, |ui| {
+                        for hull in HULLS {
+                            ui.selectable_value(design, hull.id, hull.name);
+                        }
+                    });
+                if let Some(hull) = crate::fleets::hull(design) {
+                    let cost: Vec<String> = hull
+                        .cost_t
+                        .iter()
+                        .map(|(good, tonnes)| format!("{:.0} t {}", tonnes, good))
+                        .collect();
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "{:.0} t, {:.0} days, {}",
+                            hull.tonnage_t,
+                            hull.build_days,
+                            cost.join(", ")
+                        ))
+                        .weak(),
+                    );
+                }
+            });
+            ui.separator();
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for (entity, yard) in yards.iter() {
+                    let name = name_of(entity);
+                    ui.horizontal(|ui| {
+                        ui.strong(&name);
+                        ui.label(format!(
+                            "{} slipways, up to {:.0} t",
+                            yard.slipways.len(),
+                            yard.max_tonnage_t
+                        ));
+                        let check = yard.check_order(design);
+                        let button = ui.add_enabled(check.is_ok(), egui::Button::new("Build"));
+                        let button = match &check {
+                            Ok(hull) => button.on_hover_text(format!("Queue a {}", hull.name)),
+                            Err(reason) => button.on_disabled_hover_text(reason),
+                        };
+                        if button.clicked() {
+                            player_commands.send(PlayerCommand::QueueHull {
+                                shipyard: entity.into(),
+                                hull: design.to_string(),
+                            });
+                        }
+                    });
+
+                    for (index, slipway) in yard.slipways.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.label(format!("Slipway {}:", index + 1));
+                            let hull = slipway.hull.as_deref().and_then(crate::fleets::hull);
+                            match hull {
+                                Some(hull) if slipway.retooling_days > 0.0 => {
+                                    ui.label(format!(
+                                        "Retooling for {}, {:.0} days",
+                                        hull.name, slipway.retooling_days
+                                    ));
+                                }
+                                Some(hull) => {
+                                    ui.add(
+                                        egui::ProgressBar::new(slipway.progress() as f32)
+                                            .desired_width(160.0)
+                                            .text(hull.name),
+                                    );
+                                }
+                                None => {
+                                    ui.label(egui::RichText::new("Idle").weak());
+                                }
+                            }
+                        });
+                    }
+
+                    for (index, queued) in yard.queue.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            let hull = crate::fleets::hull(queued);
+                            ui.label(format!(
+                                "{}. {}",
+                                index + 1,
+                                hull.map_or(queued.as_str(), |hull| hull.name)
+                            ));
+                            if ui.small_button("✖").on_hover_text("Cancel").clicked() {
+                                player_commands.send(PlayerCommand::CancelHull {
+                                    shipyard: entity.into(),
+                                    index,
+                                });
+                            }
+                        });
+                    }
+                    ui.separator();
+                }
+            });
+        });
+}
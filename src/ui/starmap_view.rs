@@ -0,0 +1,56 @@
+//! Starmap view options: height lines, galactic grid and camera tilt
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use crate::plugins::camera::{GameCamera, OrbitCamera, ViewMode};
+use crate::plugins::starmap::StarmapDisplay;
+
+/// Camera pitch presets (radians): looking down from the north galactic pole,
+/// at an angle, and along the galactic plane
+const TILT_PRESETS: [(&str, f32); 3] = [("Top", -1.5), ("Oblique", -0.6), ("Edge-on", 0.0)];
+
+/// Render the starmap view options in starmap view
+pub(super) fn ui_starmap_view_window(
+    mut contexts: EguiContexts,
+    view_mode: Res<ViewMode>,
+    mut display: ResMut<StarmapDisplay>,
+    mut camera_query: Query<&mut OrbitCamera, With<GameCamera>>,
+) {
+    if *view_mode != ViewMode::Starmap {
+        return;
+    }
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    // Edit a copy so change detection only fires on actual changes
+    let mut options = *display;
+    egui::Window::new("✦ Starmap View")
+        .id(egui::Id::new("starmap_view_window"))
+        .default_open(false)
+        .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-10.0, -10.0))
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.checkbox(&mut options.height_lines, "Height above galactic plane");
+            ui.checkbox(&mut options.grid, "Galactic grid (5 ly rings, 30° spokes)");
+            ui.checkbox(&mut options.auto_rotate, "Auto-rotate");
+
+            let Ok(mut orbit) = camera_query.get_single_mut() else {
+                return;
+            };
+            ui.horizontal(|ui| {
+                ui.label("Tilt:");
+                for (label, pitch) in TILT_PRESETS {
+                    if ui.button(label).clicked() {
+                        orbit.pitch = pitch;
+                    }
+                }
+            });
+            ui.label(egui::RichText::new("Right-drag to rotate and tilt").weak());
+        });
+
+    if options != *display {
+        *display = options;
+    }
+}
@@ -0,0 +1,150 @@
+//! Surface map window: deposit heatmap of a ground-mapped body
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use crate::economy::components::SurveyLevel;
+use crate::economy::deposit_map::{density_grid, deposit_sites, is_mappable};
+use crate::economy::{PlanetResources, ResourceType};
+use crate::plugins::solar_system::CelestialBody;
+
+/// Heatmap resolution (equirectangular, 2:1)
+const MAP_WIDTH: usize = 180;
+const MAP_HEIGHT: usize = 90;
+
+/// egui memory key holding the body whose surface map is open
+fn surface_map_id() -> egui::Id {
+    egui::Id::new("surface_map_body")
+}
+
+/// Open the surface map window for `body`
+pub(super) fn open_surface_map(ctx: &egui::Context, body: Entity) {
+    ctx.data_mut(|data| data.insert_temp(surface_map_id(), body));
+}
+
+/// Map a density (0.0 to 1.0) onto a dark-blue to yellow heat ramp
+fn heat_color(density: f32) -> egui::Color32 {
+    let t = density.clamp(0.0, 1.0);
+    let lerp = |a: f32, b: f32, t: f32| (a + (b - a) * t) as u8;
+    if t < 0.5 {
+        let t = t * 2.0;
+        egui::Color32::from_rgb(
+            lerp(12.0, 180.0, t),
+            lerp(20.0, 40.0, t),
+            lerp(60.0, 60.0, t),
+        )
+    } else {
+        let t = (t - 0.5) * 2.0;
+        egui::Color32::from_rgb(
+            lerp(180.0, 255.0, t),
+            lerp(40.0, 230.0, t),
+            lerp(60.0, 90.0, t),
+        )
+    }
+}
+
+/// Heatmap texture currently shown, with the body and resource it belongs to
+#[derive(Default)]
+pub(super) struct SurfaceMapTexture {
+    key: Option<(Entity, ResourceType)>,
+    handle: Option<egui::TextureHandle>,
+    selected: Option<ResourceType>,
+}
+
+/// Render the surface map window of the body opened from the dashboard
+pub(super) fn ui_surface_map_window(
+    mut contexts: EguiContexts,
+    mut texture: Local<SurfaceMapTexture>,
+    bodies: Query<(&CelestialBody, &PlanetResources, Option<&SurveyLevel>)>,
+) {
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    let Some(entity) = ctx.data(|data| data.get_temp::<Entity>(surface_map_id())) else {
+        return;
+    };
+    let Ok((body, resources, level)) = bodies.get(entity) else {
+        ctx.data_mut(|data| data.remove::<Entity>(surface_map_id()));
+        return;
+    };
+    let level = level.copied().unwrap_or_default();
+
+    let mut available: Vec<ResourceType> = resources
+        .deposits
+        .iter()
+        .filter(|(_, deposit)| level.discovered_amount(&deposit.reserve) > 0.0)
+        .map(|(resource, _)| *resource)
+        .collect();
+    available.sort_by_key(|resource| resource.display_name());
+    if texture.selected.is_none_or(|r| !available.contains(&r)) {
+        texture.selected = available.first().copied();
+    }
+
+    let mut open = true;
+    egui::Window::new(format!("🗺 Surface Map — {}", body.name))
+        .id(egui::Id::new("surface_map_window"))
+        .open(&mut open)
+        .resizable(false)
+        .show(ctx, |ui| {
+            if !is_mappable(level) {
+                ui.label(
+                    egui::RichText::new("A seismic survey is needed to locate deposits.").weak(),
+                );
+                return;
+            }
+            let Some(mut resource) = texture.selected else {
+                ui.label(egui::RichText::new("No deposits found").weak());
+                return;
+            };
+
+            egui::ComboBox::from_label("Resource")
+                .selected_text(resource.display_name())
+                .show_ui(ui, |ui| {
+                    for candidate in &available {
+                        ui.selectable_value(&mut resource, *candidate, candidate.display_name());
+                    }
+                });
+            texture.selected = Some(resource);
+
+            let sites = deposit_sites(&body.name, resource, &resources.deposits[&resource]);
+            if texture.key != Some((entity, resource)) || texture.handle.is_none() {
+                let pixels = density_grid(&sites, MAP_WIDTH, MAP_HEIGHT)
+                    .into_iter()
+                    .map(heat_color)
+                    .collect();
+                let image = egui::ColorImage {
+                    size: [MAP_WIDTH, MAP_HEIGHT],
+                    pixels,
+                };
+                texture.handle = Some(ui.ctx().load_texture(
+                    "surface_map",
+                    image,
+                    egui::TextureOptions::LINEAR,
+                ));
+                texture.key = Some((entity, resource));
+            }
+            if let Some(handle) = &texture.handle {
+                ui.image((handle.id(), egui::vec2(360.0, 180.0)));
+            }
+
+            ui.add_space(4.0);
+            ui.label(egui::RichText::new("Deposit sites").strong());
+            for (index, site) in sites.iter().enumerate() {
+                let latitude = site.latitude.to_degrees();
+                let longitude = site.longitude.to_degrees();
+                ui.label(format!(
+                    "Site {}: {:.0}°{} {:.0}°{} — {:.0}% density",
+                    index + 1,
+                    latitude.abs(),
+                    if latitude >= 0.0 { "N" } else { "S" },
+                    longitude.abs(),
+                    if longitude >= 0.0 { "E" } else { "W" },
+                    site.strength * 100.0
+                ));
+            }
+        });
+
+    if !open {
+        ctx.data_mut(|data| data.remove::<Entity>(surface_map_id()));
+    }
+}
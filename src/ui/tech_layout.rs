@@ -0,0 +1,398 @@
+//! Layered layout of the tech tree graph view
+//!
+//! Technologies are laid out Sugiyama-style: every tier is a column, and a
+//! prerequisite more than one tier before the technology it leads to is
+//! routed through placeholder slots in the tiers in between, so every
+//! connection joins neighbouring columns. The order within the columns is
+//! found by barycenter sweeps, keeping the order with the fewest crossing
+//! connections, and rows are then pulled towards their neighbours. The
+//! layout is recomputed only when the technology definitions change.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::research::{TechnologiesData, TechnologyId};
+
+/// Barycenter sweeps (down and up) tried when ordering the columns
+const ORDER_SWEEPS: usize = 12;
+/// Passes pulling rows towards their neighbours
+const PLACEMENT_PASSES: usize = 4;
+/// Rows between two technologies in a column
+const NODE_SEPARATION: f32 = 1.0;
+/// Rows between a routed connection and anything next to it
+const ROUTE_SEPARATION: f32 = 0.35;
+
+/// Connection from a prerequisite to a technology it leads to
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct LayoutEdge {
+    pub from: TechnologyId,
+    pub to: TechnologyId,
+    /// Column and row of the slots the connection passes through
+    pub waypoints: Vec<(usize, f32)>,
+}
+
+/// Column and row of every technology, and the routes between them
+#[derive(Debug, Clone, Default)]
+pub(super) struct TechTreeLayout {
+    /// `TechnologiesData::version` the layout was computed for
+    version: Option<u64>,
+    pub nodes: HashMap<TechnologyId, (usize, f32)>,
+    pub edges: Vec<LayoutEdge>,
+}
+
+/// Technology or routing slot in a column
+struct Vertex {
+    tech: Option<TechnologyId>,
+    column: usize,
+    /// Connected vertices in the previous and the next column
+    upper: Vec<usize>,
+    lower: Vec<usize>,
+}
+
+impl TechTreeLayout {
+    /// Lay the tree out again if the technologies changed since
+    pub fn refresh(&mut self, data: &TechnologiesData) {
+        if self.version != Some(data.version) {
+            *self = Self::compute(data);
+        }
+    }
+
+    pub fn compute(data: &TechnologiesData) -> Self {
+        let mut techs: Vec<_> = data.technologies.values().collect();
+        techs.sort_by(|a, b| {
+            (a.tier, a.category, a.name.as_str(), a.id.as_str()).cmp(&(
+                b.tier,
+                b.category,
+                b.name.as_str(),
+                b.id.as_str(),
+            ))
+        });
+        let columns: BTreeMap<u32, usize> = techs
+            .iter()
+            .map(|tech| tech.tier)
+            .collect::<std::collections::BTreeSet<_>>()
+            .into_iter()
+            .enumerate()
+            .map(|(column, tier)| (tier, column))
+            .collect();
+
+        let mut vertices: Vec<Vertex> = Vec::new();
+        let mut index: HashMap<&str, usize> = HashMap::new();
+        for tech in &techs {
+            index.insert(tech.id.as_str(), vertices.len());
+            vertices.push(Vertex {
+                tech: Some(tech.id.clone()),
+                column: columns[&tech.tier],
+                upper: Vec::new(),
+                lower: Vec::new(),
+            });
+        }
+
+        // Chains of vertices every connection runs through
+        let mut chains: Vec<(TechnologyId, TechnologyId, Vec<usize>)> = Vec::new();
+        for tech in &techs {
+            let to = index[tech.id.as_str()];
+            for prerequisite in &tech.prerequisites {
+                let Some(&from) = index.get(prerequisite.as_str()) else {
+                    continue;
+                };
+                let (start, end) = (vertices[from].column, vertices[to].column);
+                let mut chain = vec![from];
+                if start < end {
+                    for column in start + 1..end {
+                        chain.push(vertices.len());
+                        vertices.push(Vertex {
+                            tech: None,
+                            column,
+                            upper: Vec::new(),
+                            lower: Vec::new(),
+                        });
+                    }
+                    chain.push(to);
+                    for pair in chain.windows(2) {
+                        vertices[pair[0]].lower.push(pair[1]);
+                        vertices[pair[1]].upper.push(pair[0]);
+                    }
+                } else {
+                    // Connections within a tier or backwards are drawn
+                    // directly and left out of the ordering
+                    chain.push(to);
+                }
+                chains.push((prerequisite.clone(), tech.id.clone(), chain));
+            }
+        }
+
+        let mut layers: Vec<Vec<usize>> = vec![Vec::new(); columns.len()];
+        for (v, vertex) in vertices.iter().enumerate() {
+            layers[vertex.column].push(v);
+        }
+        let layers = order_layers(&vertices, layers);
+        let rows = place_rows(&vertices, &layers);
+
+        let nodes = vertices
+            .iter()
+            .enumerate()
+            .filter_map(|(v, vertex)| {
+                let tech = vertex.tech.clone()?;
+                Some((tech, (vertex.column, rows[v])))
+            })
+            .collect();
+        let edges = chains
+            .into_iter()
+            .map(|(from, to, chain)| LayoutEdge {
+                from,
+                to,
+                waypoints: chain[1..chain.len() - 1]
+                    .iter()
+                    .map(|&v| (vertices[v].column, rows[v]))
+                    .collect(),
+            })
+            .collect();
+
+        Self {
+            version: Some(data.version),
+            nodes,
+            edges,
+        }
+    }
+}
+
+/// Position of every vertex within its column
+fn positions(vertices: &[Vertex], layers: &[Vec<usize>]) -> Vec<usize> {
+    let mut positions = vec![0; vertices.len()];
+    for layer in layers {
+        for (position, &v) in layer.iter().enumerate() {
+            positions[v] = position;
+        }
+    }
+    positions
+}
+
+/// Connections crossing between all neighbouring columns
+fn crossings(vertices: &[Vertex], layers: &[Vec<usize>]) -> usize {
+    let positions = positions(vertices, layers);
+    let mut total = 0;
+    for (column, layer) in layers.iter().enumerate().skip(1) {
+        let mut edges: Vec<(usize, usize)> = layers[column - 1]
+            .iter()
+            .flat_map(|&u| vertices[u].lower.iter().map(move |&w| (u, w)))
+            .map(|(u, w)| (positions[u], positions[w]))
+            .collect();
+        edges.sort_unstable();
+        // Count inversions of the lower ends with a Fenwick tree
+        let mut tree = vec![0usize; layer.len() + 1];
+        for (seen, &(_, lower)) in edges.iter().enumerate() {
+            let mut i = lower + 1;
+            let mut not_above = 0;
+            while i > 0 {
+                not_above += tree[i];
+                i &= i - 1;
+            }
+            total += seen - not_above;
+            let mut i = lower + 1;
+            while i < tree.len() {
+                tree[i] += 1;
+                i += i & i.wrapping_neg();
+            }
+        }
+    }
+    total
+}
+
+/// Sort every column by the mean position of the neighbours in `neighbours`
+fn sweep(
+    vertices: &[Vertex],
+    layers: &mut [Vec<usize>],
+    columns: impl Iterator<Item = usize>,
+    neighbours: fn(&Vertex) -> &[usize],
+) {
+    for column in columns {
+        let positions = positions(vertices, layers);
+        let barycenter = |v: usize| -> f64 {
+            let linked = neighbours(&vertices[v]);
+            if linked.is_empty() {
+                positions[v] as f64
+            } else {
+                linked.iter().map(|&u| positions[u] as f64).sum::<f64>() / linked.len() as f64
+            }
+        };
+        let mut keyed: Vec<(f64, usize)> =
+            layers[column].iter().map(|&v| (barycenter(v), v)).collect();
+        keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+        layers[column] = keyed.into_iter().map(|(_, v)| v).collect();
+    }
+}
+
+/// Order the columns with barycenter sweeps, keeping the order with the
+/// fewest crossings
+fn order_layers(vertices: &[Vertex], mut layers: Vec<Vec<usize>>) -> Vec<Vec<usize>> {
+    let count = layers.len();
+    let mut best = layers.clone();
+    let mut fewest = crossings(vertices, &layers);
+    for _ in 0..ORDER_SWEEPS {
+        if fewest == 0 {
+            break;
+        }
+        sweep(vertices, &mut layers, 1..count, |v| &v.upper);
+        sweep(
+            vertices,
+            &mut layers,
+            (0..count.saturating_sub(1)).rev(),
+            |v| &v.lower,
+        );
+        let found = crossings(vertices, &layers);
+        if found < fewest {
+            fewest = found;
+            best = layers.clone();
+        }
+    }
+    best
+}
+
+/// Rows needed between two neighbouring vertices of a column
+fn separation(vertices: &[Vertex], a: usize, b: usize) -> f32 {
+    if vertices[a].tech.is_some() && vertices[b].tech.is_some() {
+        NODE_SEPARATION
+    } else {
+        ROUTE_SEPARATION
+    }
+}
+
+/// Rows of every vertex: stacked in column order, then pulled towards the
+/// mean row of their neighbours as far as the separations allow
+#[allow(clippy::type_complexity)]
+fn place_rows(vertices: &[Vertex], layers: &[Vec<usize>]) -> Vec<f32> {
+    let mut rows = vec![0.0f32; vertices.len()];
+    for layer in layers {
+        for pair in layer.windows(2) {
+            rows[pair[1]] = rows[pair[0]] + separation(vertices, pair[0], pair[1]);
+        }
+    }
+
+    for _ in 0..PLACEMENT_PASSES {
+        let passes: [(fn(&Vertex) -> &[usize], bool); 2] =
+            [(|v| &v.upper, true), (|v| &v.lower, false)];
+        for (neighbours, downwards) in passes {
+            let order: Vec<usize> = if downwards {
+                (0..layers.len()).collect()
+            } else {
+                (0..layers.len()).rev().collect()
+            };
+            for column in order {
+                let layer = &layers[column];
+                let wanted: Vec<f32> = layer
+                    .iter()
+                    .map(|&v| {
+                        let linked = neighbours(&vertices[v]);
+                        if linked.is_empty() {
+                            rows[v]
+                        } else {
+                            linked.iter().map(|&u| rows[u]).sum::<f32>() / linked.len() as f32
+                        }
+                    })
+                    .collect();
+                // The mean of the placements pushed down and pushed up keeps
+                // every separation and does not drift either way
+                let mut down = wanted.clone();
+                for i in 1..layer.len() {
+                    down[i] =
+                        down[i].max(down[i - 1] + separation(vertices, layer[i - 1], layer[i]));
+                }
+                let mut up = wanted;
+                for i in (0..layer.len().saturating_sub(1)).rev() {
+                    up[i] = up[i].min(up[i + 1] - separation(vertices, layer[i], layer[i + 1]));
+                }
+                for (i, &v) in layer.iter().enumerate() {
+                    rows[v] = (down[i] + up[i]) / 2.0;
+                }
+            }
+        }
+    }
+
+    let top = rows.iter().copied().fold(f32::INFINITY, f32::min);
+    if top.is_finite() {
+        for row in &mut rows {
+            *row -= top;
+        }
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::research::types::{TechCategory, Technology};
+
+    fn data(techs: &[(&str, u32, &[&str])]) -> TechnologiesData {
+        let mut data = TechnologiesData::default();
+        for (id, tier, prerequisites) in techs {
+            data.technologies.insert(
+                id.to_string(),
+                Technology {
+                    id: id.to_string(),
+                    name: id.to_string(),
+                    category: TechCategory::Physics,
+                    description: String::new(),
+                    research_cost: 100.0,
+                    prerequisites: prerequisites.iter().map(|p| p.to_string()).collect(),
+                    unlocks_components: Vec::new(),
+                    unlocks_engineering: Vec::new(),
+                    modifiers: Vec::new(),
+                    tier: *tier,
+                    required_kardashev: None,
+                },
+            );
+        }
+        data
+    }
+
+    #[test]
+    fn test_ordering_removes_crossings() {
+        // Sorted by name, "a" leads to "z" at the bottom and "b" to "y" at
+        // the top, so the connections cross until the columns are reordered
+        let data = data(&[
+            ("a", 0, &[]),
+            ("b", 0, &[]),
+            ("y", 1, &["b"]),
+            ("z", 1, &["a"]),
+        ]);
+        let layout = TechTreeLayout::compute(&data);
+        let row = |id: &str| layout.nodes[id].1;
+        assert_eq!(row("a") < row("b"), row("z") < row("y"));
+        assert_eq!(layout.nodes["z"].0, 1);
+    }
+
+    #[test]
+    fn test_long_connections_are_routed_through_tiers() {
+        let mut data = data(&[
+            ("a", 0, &[]),
+            ("b", 1, &["a"]),
+            ("c", 2, &["b"]),
+            ("d", 3, &["a", "c"]),
+        ]);
+        let mut layout = TechTreeLayout::default();
+        layout.refresh(&data);
+        let edge = layout
+            .edges
+            .iter()
+            .find(|e| e.from == "a" && e.to == "d")
+            .unwrap();
+        let columns: Vec<usize> = edge.waypoints.iter().map(|(column, _)| *column).collect();
+        assert_eq!(columns, vec![1, 2]);
+        // Routes keep clear of the technologies in their columns
+        for (column, row) in &edge.waypoints {
+            for &(c, r) in layout.nodes.values() {
+                if c == *column {
+                    assert!((r - row).abs() >= ROUTE_SEPARATION - 1e-4);
+                }
+            }
+        }
+
+        // The layout is only recomputed for a new version of the data
+        data.technologies.remove("d");
+        layout.refresh(&data);
+        assert!(layout.nodes.contains_key("d"));
+        data.version += 1;
+        layout.refresh(&data);
+        assert!(!layout.nodes.contains_key("d"));
+    }
+}
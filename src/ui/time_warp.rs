@@ -0,0 +1,376 @@
+//! Time warp to the next event
+//!
+//! Collects the upcoming completions (construction projects, survey missions,
+//! research and engineering projects) with an estimate of when each fires.
+//! While warping, the time scale is set each frame so the clock lands on the
+//! next event instead of skipping past it, and the simulation pauses as soon
+//! as one of the tracked items completes. Items that merely drop out, like a
+//! project stalled on the stockpile or a cancelled one, don't end the warp.
+
+use bevy::prelude::*;
+use bevy::time::Real;
+
+use super::{SimulationTime, TimeScale, MAX_TIME_SCALE};
+use crate::construction::{Building, BuildingId, BuildingsData, ConstructionQueue};
+use crate::economy::components::SurveyLevel;
+use crate::economy::survey::SurveyMissions;
+use crate::economy::tick::ECONOMY_TICK_SECONDS;
+use crate::hotseat::Hotseat;
+use crate::plugins::solar_system::{CelestialBody, LogicalParent};
+use crate::research::{
+    EngineeringProject, ResearchProject, ResearchState, ResearchTeam, TechnologiesData,
+};
+
+/// Slowest warp speed (1 hr/s), used once the estimate has run out but the
+/// event has not fired yet
+pub const MIN_WARP_SCALE: f32 = 3_600.0;
+
+/// What an upcoming event completes; identifies the tracked item across
+/// frames and tells whether it completed once it is gone
+#[derive(Debug, Clone, PartialEq)]
+pub enum Completion {
+    /// A building on a body reaching a level
+    Construction {
+        body: Entity,
+        building: BuildingId,
+        level: u32,
+    },
+    /// A body reaching a survey level
+    Survey {
+        body: Entity,
+        level: SurveyLevel,
+    },
+    Research(String),
+    Engineering(String),
+}
+
+/// A completion expected in the future
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpcomingEvent {
+    pub completion: Completion,
+    pub label: String,
+    /// Estimated simulation seconds until the event fires
+    pub eta_seconds: f64,
+}
+
+/// State of the warp-to-next-event control
+#[derive(Resource, Debug, Clone, Default)]
+pub struct TimeWarp {
+    /// Whether the clock is currently warping
+    pub active: bool,
+    /// Soonest upcoming event
+    pub next: Option<UpcomingEvent>,
+    /// Label of the event that ended the last warp
+    pub last_fired: Option<String>,
+    /// Speed to resume at once the warp ends
+    restore_scale: f32,
+    /// Scale set by the warp last frame; any other value means the player
+    /// changed the speed and the warp is cancelled
+    applied_scale: f32,
+    /// Items tracked last frame
+    tracked: Vec<UpcomingEvent>,
+}
+
+impl TimeWarp {
+    /// Start warping, remembering the current speed
+    pub fn start(&mut self, time_scale: &TimeScale) {
+        self.active = true;
+        self.last_fired = None;
+        self.restore_scale = if time_scale.is_paused() {
+            time_scale.last_active_scale
+        } else {
+            time_scale.scale
+        };
+        self.applied_scale = time_scale.scale;
+    }
+
+    /// Stop warping, returning the speed from before the warp to restore
+    pub fn stop(&mut self) -> f32 {
+        self.active = false;
+        self.restore_scale
+    }
+
+    /// Speed from before the warp, which stopping it returns to
+    pub fn resume_scale(&self) -> f32 {
+        self.restore_scale
+    }
+}
+
+/// Seconds until the economy tick on which an item needing `remaining_days`
+/// more daily ticks completes
+pub fn seconds_until_ticks(elapsed_seconds: f64, remaining_days: f64) -> f64 {
+    let ticks = remaining_days.ceil().max(1.0);
+    let next_tick = (elapsed_seconds / ECONOMY_TICK_SECONDS).floor() + 1.0;
+    (next_tick + ticks - 1.0) * ECONOMY_TICK_SECONDS - elapsed_seconds
+}
+
+/// Time scale that covers `eta_seconds` in one frame of `real_delta` seconds,
+/// so the clock stops at the event rather than jumping past it
+pub fn warp_scale(eta_seconds: f64, real_delta: f32) -> f32 {
+    let scale = eta_seconds / real_delta.max(1e-3) as f64;
+    (scale as f32).clamp(MIN_WARP_SCALE, MAX_TIME_SCALE)
+}
+
+/// System that tracks upcoming events and drives the warp
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub(super) fn update_time_warp(
+    sim_time: Res<SimulationTime>,
+    real_time: Res<Time<Real>>,
+    mut time_scale: ResMut<TimeScale>,
+    mut warp: ResMut<TimeWarp>,
+    survey: Option<Res<SurveyMissions>>,
+    research_state: Option<Res<ResearchState>>,
+    tech_data: Option<Res<TechnologiesData>>,
+    buildings_data: Option<Res<BuildingsData>>,
+    queues: Query<(
+        Entity,
+        &ConstructionQueue,
+        Option<&CelestialBody>,
+        Option<&Name>,
+    )>,
+    research_projects: Query<&ResearchProject>,
+    engineering_projects: Query<&EngineeringProject>,
+    teams: Query<&ResearchTeam>,
+    hotseat: Option<Res<Hotseat>>,
+    (buildings, survey_levels): (Query<(&Building, &LogicalParent)>, Query<&SurveyLevel>),
+) {
+    let elapsed = sim_time.elapsed_seconds();
+    let mut events: Vec<UpcomingEvent> = Vec::new();
+
+    for (entity, queue, body, name) in queues.iter() {
+        // Stalled projects wait on the stockpile, not on time
+        let Some(project) = queue.projects.front().filter(|p| !p.stalled) else {
+            continue;
+        };
+        let location = body
+            .map(|b| b.name.as_str())
+            .or(name.map(|n| n.as_str()))
+            .unwrap_or("Unknown");
+        let building = buildings_data
+            .as_ref()
+            .and_then(|data| data.get(&project.building_id))
+            .and_then(|def| def.level(project.target_level))
+            .map_or(project.building_id.as_str(), |level| level.name.as_str());
+        let remaining = project.required_days - project.progress_days;
+        events.push(UpcomingEvent {
+            completion: Completion::Construction {
+                body: entity,
+                building: project.building_id.clone(),
+                level: project.target_level,
+            },
+            label: format!("{} on {}", building, location),
+            eta_seconds: seconds_until_ticks(elapsed, remaining.max(0.0)),
+        });
+    }
+
+    if let Some(survey) = &survey {
+        for mission in &survey.missions {
+            let remaining = mission.transit_days + mission.survey_days - mission.elapsed_days;
+            events.push(UpcomingEvent {
+                completion: Completion::Survey {
+                    body: mission.target,
+                    level: mission.level,
+                },
+                label: format!("Survey of {}", mission.target_name),
+                eta_seconds: seconds_until_ticks(elapsed, remaining),
+            });
+        }
+    }
+
+    if let Some(research_state) = &research_state {
+        let total_allocation: f64 = research_projects
+            .iter()
+            .filter(|p| p.active && !p.is_complete())
+            .map(|p| p.rp_allocation_percent)
+            .sum();
+        for project in research_projects.iter() {
+            if !project.active || project.is_complete() || total_allocation <= 0.0 {
+                continue;
+            }
+            let tech = tech_data
+                .as_ref()
+                .and_then(|d| d.get_tech(&project.tech_id));
+            let team = teams.get(project.team_id).ok();
+            let bonus = tech.map_or(1.0, |t| {
+                (1.0 + research_state.category_research_bonus(t.category) / 100.0)
+                    * team.map_or(1.0, |team| team.category_efficiency(t.category) as f64)
+            });
+            let rate = research_state.rp_rate_per_second
+                * (project.rp_allocation_percent / total_allocation)
+                * bonus;
+            if rate <= 0.0 {
+                continue;
+            }
+            events.push(UpcomingEvent {
+                completion: Completion::Research(project.tech_id.clone()),
+                label: format!(
+                    "Research: {}",
+                    tech.map_or(project.tech_id.as_str(), |t| t.name.as_str())
+                ),
+                eta_seconds: (project.required_points - project.progress) / rate,
+            });
+        }
+
+        for project in engineering_projects.iter() {
+            if project.is_complete() {
+                continue;
+            }
+            // Mirrors advance_engineering_projects: one point per second
+            let efficiency = teams
+                .get(project.team_id)
+                .map_or(1.0, |team| team.efficiency as f64);
+            let rate = efficiency * research_state.engineering_speed_multiplier();
+            if rate <= 0.0 {
+                continue;
+            }
+            events.push(UpcomingEvent {
+                completion: Completion::Engineering(project.component_id.clone()),
+                label: format!("Engineering: {}", project.component_id),
+                eta_seconds: (project.required_points - project.progress) / rate,
+            });
+        }
+    }
+
+    // An item tracked last frame that is gone now has fired if what it
+    // completes is in place; stalled or cancelled items just drop out
+    let completed = |completion: &Completion| match completion {
+        Completion::Construction {
+            body,
+            building,
+            level,
+        } => buildings
+            .iter()
+            .any(|(b, parent)| parent.0 == *body && b.id == *building && b.level >= *level),
+        Completion::Survey { body, level } => survey_levels.get(*body).is_ok_and(|l| l == level),
+        Completion::Research(tech_id) => research_state
+            .as_ref()
+            .is_some_and(|r| r.is_unlocked(tech_id)),
+        Completion::Engineering(component_id) => research_state
+            .as_ref()
+            .is_some_and(|r| r.is_component_completed(component_id)),
+    };
+    let fired = warp
+        .tracked
+        .iter()
+        .filter(|old| !events.iter().any(|e| e.completion == old.completion))
+        .find(|old| completed(&old.completion))
+        .map(|old| old.label.clone());
+    warp.tracked = events.clone();
+    let next = events
+        .into_iter()
+        .min_by(|a, b| a.eta_seconds.total_cmp(&b.eta_seconds));
+
+    // The clock waits while a hotseat player takes their turn
+    if hotseat.is_some_and(|h| h.active_seat().is_some()) {
+        warp.active = false;
+    }
+    if warp.active {
+        if time_scale.scale != warp.applied_scale {
+            // The player picked another speed or paused
+            warp.active = false;
+        } else if fired.is_some() || next.is_none() {
+            warp.last_fired = fired;
+            time_scale.scale = warp.stop();
+            time_scale.pause();
+        } else if let Some(event) = &next {
+            time_scale.scale = warp_scale(event.eta_seconds, real_time.delta_seconds());
+            warp.applied_scale = time_scale.scale;
+        }
+    }
+    warp.next = next;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::construction::ConstructionProject;
+
+    #[test]
+    fn test_seconds_until_ticks_lands_on_tick_boundaries() {
+        let day = ECONOMY_TICK_SECONDS;
+        assert_eq!(seconds_until_ticks(0.0, 1.0), day);
+        assert_eq!(seconds_until_ticks(0.25 * day, 3.0), 2.75 * day);
+        // Partial days still need a whole tick
+        assert_eq!(seconds_until_ticks(10.0 * day, 0.5), day);
+    }
+
+    #[test]
+    fn test_warp_scale_slows_down_near_the_event() {
+        assert_eq!(warp_scale(1e12, 0.016), MAX_TIME_SCALE);
+        assert_eq!(warp_scale(16_000.0 * 10.0, 0.016), 10_000_000.0);
+        assert_eq!(warp_scale(0.0, 0.016), MIN_WARP_SCALE);
+    }
+
+    #[test]
+    fn test_warp_pauses_when_construction_completes() {
+        let mut app = App::new();
+        app.init_resource::<SimulationTime>()
+            .init_resource::<TimeScale>()
+            .init_resource::<TimeWarp>()
+            .init_resource::<Time<Real>>()
+            .add_systems(Update, update_time_warp);
+
+        let mut queue = ConstructionQueue::default();
+        queue
+            .projects
+            .push_back(ConstructionProject::new("mine".to_string(), 1, 3.0));
+        let body = app.world_mut().spawn((queue, Name::new("Mars"))).id();
+        let mut queue = ConstructionQueue::default();
+        queue
+            .projects
+            .push_back(ConstructionProject::new("smelter".to_string(), 1, 10.0));
+        app.world_mut().spawn((queue, Name::new("Venus")));
+
+        app.update();
+        let next = app.world().resource::<TimeWarp>().next.clone().unwrap();
+        assert_eq!(next.eta_seconds, 3.0 * ECONOMY_TICK_SECONDS);
+        assert_eq!(next.label, "mine on Mars");
+
+        let mut time_scale = app.world().resource::<TimeScale>().clone();
+        app.world_mut()
+            .resource_mut::<TimeWarp>()
+            .start(&time_scale);
+        app.update();
+        time_scale = app.world().resource::<TimeScale>().clone();
+        assert!(time_scale.scale >= MIN_WARP_SCALE);
+
+        // A project stalled on the stockpile drops out without ending the warp
+        let set_stalled = |app: &mut App, stalled: bool| {
+            app.world_mut()
+                .get_mut::<ConstructionQueue>(body)
+                .unwrap()
+                .projects[0]
+                .stalled = stalled;
+            app.update();
+        };
+        set_stalled(&mut app, true);
+        let warp = app.world().resource::<TimeWarp>();
+        assert!(warp.active);
+        assert_eq!(warp.last_fired, None);
+        assert_eq!(warp.next.as_ref().unwrap().label, "smelter on Venus");
+        set_stalled(&mut app, false);
+
+        // The project completes and leaves the queue
+        app.world_mut()
+            .get_mut::<ConstructionQueue>(body)
+            .unwrap()
+            .projects
+            .clear();
+        app.world_mut().spawn((
+            Building {
+                id: "mine".to_string(),
+                level: 1,
+            },
+            LogicalParent(body),
+        ));
+        app.update();
+        let warp = app.world().resource::<TimeWarp>();
+        assert!(!warp.active);
+        assert_eq!(warp.last_fired.as_deref(), Some("mine on Mars"));
+        let time_scale = app.world().resource::<TimeScale>();
+        assert!(time_scale.is_paused());
+        let mut resumed = time_scale.clone();
+        resumed.resume();
+        assert_eq!(resumed.scale, 1.0);
+    }
+}
@@ -0,0 +1,108 @@
+//! Toasts: short notices stacked in the top right corner that fade out on
+//! their own
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use super::event_log::severity_color;
+use crate::game_state::LogSeverity;
+
+/// Seconds of real time a toast stays on screen
+pub const TOAST_SECONDS: f32 = 5.0;
+/// Toasts shown at once; older ones are dropped
+const MAX_TOASTS: usize = 5;
+
+/// A notice waiting to expire
+#[derive(Debug, Clone, PartialEq)]
+pub struct Toast {
+    pub severity: LogSeverity,
+    pub message: String,
+    /// Real seconds left on screen
+    pub remaining: f32,
+}
+
+/// Toasts on screen, oldest first
+#[derive(Resource, Debug, Clone, Default)]
+pub struct Toasts {
+    pub toasts: Vec<Toast>,
+}
+
+impl Toasts {
+    /// Show `message` for [`TOAST_SECONDS`]
+    pub fn push(&mut self, severity: LogSeverity, message: impl Into<String>) {
+        self.toasts.push(Toast {
+            severity,
+            message: message.into(),
+            remaining: TOAST_SECONDS,
+        });
+        if self.toasts.len() > MAX_TOASTS {
+            self.toasts.remove(0);
+        }
+    }
+
+    /// Count down by `seconds` and drop expired toasts
+    pub fn tick(&mut self, seconds: f32) {
+        for toast in &mut self.toasts {
+            toast.remaining -= seconds;
+        }
+        self.toasts.retain(|toast| toast.remaining > 0.0);
+    }
+}
+
+/// Render the toasts, counting down in real time so they also expire while
+/// the simulation is paused
+pub(super) fn ui_toasts(
+    mut contexts: EguiContexts,
+    time: Res<Time<Real>>,
+    mut toasts: ResMut<Toasts>,
+) {
+    if toasts.toasts.is_empty() {
+        return;
+    }
+    toasts.tick(time.delta_seconds());
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    egui::Area::new(egui::Id::new("toasts"))
+        .anchor(egui::Align2::RIGHT_TOP, [-12.0, 72.0])
+        .order(egui::Order::Tooltip)
+        .interactable(false)
+        .show(ctx, |ui| {
+            for toast in toasts.toasts.iter().rev() {
+                // Fade out over the last half second
+                ui.scope(|ui| {
+                    ui.set_opacity((toast.remaining / 0.5).clamp(0.0, 1.0));
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.set_max_width(360.0);
+                        ui.label(
+                            egui::RichText::new(&toast.message)
+                                .color(severity_color(ui.ctx(), toast.severity)),
+                        );
+                    });
+                });
+                ui.add_space(4.0);
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toasts_expire_and_are_capped() {
+        let mut toasts = Toasts::default();
+        for i in 0..MAX_TOASTS + 2 {
+            toasts.push(LogSeverity::Info, format!("notice {}", i));
+        }
+        assert_eq!(toasts.toasts.len(), MAX_TOASTS);
+        assert_eq!(toasts.toasts[0].message, "notice 2");
+
+        toasts.tick(TOAST_SECONDS - 1.0);
+        toasts.push(LogSeverity::Warning, "late");
+        toasts.tick(1.0);
+        assert_eq!(toasts.toasts.len(), 1);
+        assert_eq!(toasts.toasts[0].message, "late");
+    }
+}
@@ -0,0 +1,276 @@
+//! Hover tooltip for celestial bodies
+//!
+//! Beyond the name and type, the tooltip shows the extra rows picked in the
+//! options window (`UiSettings::tooltip_fields`). A row is left out when the
+//! body has nothing to show for it, e.g. a star has no colony cost.
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use serde::{Deserialize, Serialize};
+
+use super::{format_population, UiSettings};
+use crate::astronomy::{
+    calculate_general_colony_cost, AtmosphereComposition, Hovered, KeplerOrbit,
+    SurfaceTemperature,
+};
+use crate::economy::components::{Population, SurveyLevel};
+use crate::game_state::ActiveMenu;
+use crate::plugins::solar_system::CelestialBody;
+use crate::plugins::solar_system_data::BodyType;
+
+/// Optional data row of the body tooltip
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum TooltipField {
+    OrbitalPeriod,
+    SurfaceGravity,
+    ColonyCost,
+    SurveyLevel,
+    Population,
+}
+
+impl TooltipField {
+    pub const ALL: [TooltipField; 5] = [
+        TooltipField::OrbitalPeriod,
+        TooltipField::SurfaceGravity,
+        TooltipField::ColonyCost,
+        TooltipField::SurveyLevel,
+        TooltipField::Population,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TooltipField::OrbitalPeriod => "Orbital period",
+            TooltipField::SurfaceGravity => "Surface gravity",
+            TooltipField::ColonyCost => "Colony cost",
+            TooltipField::SurveyLevel => "Survey level",
+            TooltipField::Population => "Population",
+        }
+    }
+}
+
+/// Rows shown until the player picks their own
+pub fn default_tooltip_fields() -> Vec<TooltipField> {
+    vec![TooltipField::OrbitalPeriod, TooltipField::Population]
+}
+
+/// What the tooltip knows about the hovered body
+pub struct TooltipData<'a> {
+    pub body: &'a CelestialBody,
+    pub orbit: Option<&'a KeplerOrbit>,
+    pub temperature_celsius: Option<f32>,
+    pub atmosphere: Option<&'a AtmosphereComposition>,
+    pub survey_level: Option<SurveyLevel>,
+    pub population: Option<f64>,
+}
+
+/// Orbital period in days, or years beyond one year, as the selection panel shows it
+fn format_period(period_days: f64) -> String {
+    if period_days < 365.0 {
+        format!("{:.1} days", period_days)
+    } else {
+        format!("{:.2} years", period_days / 365.25)
+    }
+}
+
+/// Text of a tooltip row, or `None` if the body has nothing to show for it
+pub fn field_value(field: TooltipField, data: &TooltipData) -> Option<String> {
+    let star = data.body.body_type == BodyType::Star;
+    match field {
+        TooltipField::OrbitalPeriod => data.orbit.map(|orbit| {
+            format_period(KeplerOrbit::period_from_mean_motion(orbit.mean_motion) / 86_400.0)
+        }),
+        TooltipField::SurfaceGravity => {
+            (data.body.radius > 0.0).then(|| format!("{:.2} g", data.body.surface_gravity()))
+        }
+        TooltipField::ColonyCost if !star => {
+            // Bodies without a known temperature count as frozen, as in the selection panel
+            let temperature = data.temperature_celsius.unwrap_or(-273.15);
+            let cost = calculate_general_colony_cost(
+                data.body.surface_gravity(),
+                temperature,
+                data.atmosphere,
+            );
+            Some(if cost.is_infinite() {
+                "Uninhabitable".to_string()
+            } else {
+                format!("{:.2}", cost)
+            })
+        }
+        TooltipField::SurveyLevel if !star => {
+            Some(format!("{:?}", data.survey_level.unwrap_or_default()))
+        }
+        TooltipField::Population => data
+            .population
+            .filter(|count| *count > 0.0)
+            .map(format_population),
+        TooltipField::ColonyCost | TooltipField::SurveyLevel => None,
+    }
+}
+
+/// System that displays a tooltip for hovered celestial bodies
+#[allow(clippy::type_complexity)]
+pub(super) fn ui_hover_tooltip(
+    mut contexts: EguiContexts,
+    hovered_query: Query<
+        (
+            &CelestialBody,
+            Option<&KeplerOrbit>,
+            Option<&SurfaceTemperature>,
+            Option<&AtmosphereComposition>,
+            Option<&SurveyLevel>,
+            Option<&Population>,
+        ),
+        With<Hovered>,
+    >,
+    settings: Res<UiSettings>,
+    active_menu: Res<ActiveMenu>,
+) {
+    // Don't show world tooltips when a full-screen overlay is active
+    if active_menu.current.blocks_world_interaction() {
+        return;
+    }
+
+    let ctx = match contexts.try_ctx_mut() {
+        Some(ctx) => ctx,
+        None => return,
+    };
+
+    // Display hover tooltip if a body is hovered
+    let Ok((body, orbit, surface_temp, atmosphere, survey_level, population)) =
+        hovered_query.get_single()
+    else {
+        return;
+    };
+    let data = TooltipData {
+        body,
+        orbit,
+        temperature_celsius: surface_temp
+            .map(|t| t.average_celsius)
+            .or_else(|| atmosphere.map(|a| a.surface_temperature_celsius)),
+        atmosphere,
+        survey_level: survey_level.copied(),
+        population: population.map(|p| p.count),
+    };
+    let rows: Vec<(TooltipField, String)> = settings
+        .tooltip_fields
+        .iter()
+        .filter_map(|&field| field_value(field, &data).map(|value| (field, value)))
+        .collect();
+
+    // Anchor the tooltip near the mouse pointer so it appears over the 3D view
+    let tooltip_pos = ctx
+        .input(|i| i.pointer.hover_pos())
+        .map(|p| egui::pos2(p.x + 12.0, p.y + 12.0))
+        .unwrap_or(egui::pos2(100.0, 100.0));
+
+    egui::Area::new("hover_tooltip".into())
+        .fixed_pos(tooltip_pos)
+        .interactable(false)
+        .order(egui::Order::Tooltip)
+        .show(ctx, |ui| {
+            ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+            egui::Frame::none()
+                .fill(egui::Color32::from_rgba_unmultiplied(30, 30, 30, 240))
+                .stroke(egui::Stroke::new(
+                    2.0,
+                    egui::Color32::from_rgb(100, 180, 255),
+                ))
+                .inner_margin(12.0)
+                .show(ui, |ui| {
+                    // Use horizontal layout to prevent narrow wrapping
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            egui::RichText::new(&body.name)
+                                .size(16.0)
+                                .color(egui::Color32::from_rgb(150, 220, 255))
+                                .strong(),
+                        );
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            egui::RichText::new(format!("Type: {:?}", body.body_type))
+                                .size(12.0)
+                                .color(egui::Color32::from_rgb(180, 180, 180)),
+                        );
+                    });
+
+                    if rows.is_empty() {
+                        return;
+                    }
+                    ui.add_space(4.0);
+                    egui::Grid::new("hover_tooltip_rows")
+                        .num_columns(2)
+                        .spacing([12.0, 2.0])
+                        .show(ui, |ui| {
+                            for (field, value) in &rows {
+                                ui.label(
+                                    egui::RichText::new(field.name())
+                                        .size(12.0)
+                                        .color(egui::Color32::from_rgb(150, 150, 150)),
+                                );
+                                ui.label(
+                                    egui::RichText::new(value)
+                                        .size(12.0)
+                                        .color(egui::Color32::from_rgb(220, 220, 220)),
+                                );
+                                ui.end_row();
+                            }
+                        });
+                });
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn body(body_type: BodyType, radius: f32, mass: f64) -> CelestialBody {
+        CelestialBody {
+            name: "Test".to_string(),
+            radius,
+            mass,
+            body_type,
+            visual_radius: 10.0,
+            asteroid_class: None,
+        }
+    }
+
+    #[test]
+    fn test_rows_show_only_what_the_body_has() {
+        let earth = body(BodyType::Planet, 6371.0, 5.972e24);
+        let period = 86_400.0 * 30.0;
+        let orbit = KeplerOrbit::circular(1.0, KeplerOrbit::mean_motion_from_period(period));
+        let data = TooltipData {
+            body: &earth,
+            orbit: Some(&orbit),
+            temperature_celsius: Some(15.0),
+            atmosphere: None,
+            survey_level: None,
+            population: Some(0.0),
+        };
+        assert_eq!(
+            field_value(TooltipField::OrbitalPeriod, &data).as_deref(),
+            Some("30.0 days")
+        );
+        assert_eq!(
+            field_value(TooltipField::SurfaceGravity, &data).as_deref(),
+            Some("1.00 g")
+        );
+        assert_eq!(
+            field_value(TooltipField::SurveyLevel, &data).as_deref(),
+            Some("Unsurveyed")
+        );
+        // An empty world has no population row
+        assert_eq!(field_value(TooltipField::Population, &data), None);
+
+        let sun = body(BodyType::Star, 696_000.0, 1.989e30);
+        let data = TooltipData {
+            body: &sun,
+            orbit: None,
+            ..data
+        };
+        assert_eq!(field_value(TooltipField::OrbitalPeriod, &data), None);
+        assert_eq!(field_value(TooltipField::ColonyCost, &data), None);
+    }
+}
@@ -0,0 +1,328 @@
+//! Trade window under the Economy menu: market prices of every resource,
+//! the factions with their agreements, and a form to sign new ones
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use super::accessibility::{self, Status};
+use crate::commands::PlayerCommand;
+use crate::economy::{format_currency, GlobalBudget, ResourceRateTracker, ResourceType};
+use crate::game_state::{ActiveMenu, GameMenu};
+use crate::trade::market::base_price;
+use crate::trade::{
+    surplus_mt_per_year, FactionDefinition, FactionId, FactionsData, Market, TradeAgreements,
+    TradeDirection,
+};
+
+/// Window state kept between frames: the agreement being drafted
+pub(super) struct TradeTabState {
+    faction: Option<FactionId>,
+    direction: TradeDirection,
+    resource: Option<ResourceType>,
+    mt_per_year: f64,
+}
+
+impl Default for TradeTabState {
+    fn default() -> Self {
+        Self {
+            faction: None,
+            direction: TradeDirection::Export,
+            resource: None,
+            mt_per_year: 1.0,
+        }
+    }
+}
+
+/// Render the trade window while the Economy menu is active
+#[allow(clippy::too_many_arguments)]
+pub(super) fn ui_trade_window(
+    mut contexts: EguiContexts,
+    mut state: Local<TradeTabState>,
+    active_menu: Res<ActiveMenu>,
+    trade: Res<TradeAgreements>,
+    mut player_commands: EventWriter<PlayerCommand>,
+    market: Res<Market>,
+    budget: Res<GlobalBudget>,
+    rates: Res<ResourceRateTracker>,
+    data: Option<Res<FactionsData>>,
+) {
+    if active_menu.current != GameMenu::Economy {
+        return;
+    }
+    let Some(data) = data else {
+        return;
+    };
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    let palette = accessibility::palette(ctx);
+
+    egui::Window::new("🚢 Trade")
+        .id(egui::Id::new("trade_window"))
+        .default_width(520.0)
+        .resizable(true)
+        .show(ctx, |ui| {
+            ui.label(format!(
+                "Sales {}/yr, purchases {}/yr, tariffs {}/yr",
+                format_currency(trade.sales_per_year),
+                format_currency(trade.purchases_per_year),
+                format_currency(trade.tariffs_per_year)
+            ));
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                egui::CollapsingHeader::new("Market")
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        egui::Grid::new("trade_market")
+                            .num_columns(4)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.label(egui::RichText::new("Resource").strong());
+                                ui.label(egui::RichText::new("Stockpile").strong());
+                                ui.label(egui::RichText::new("Surplus").strong());
+                                ui.label(egui::RichText::new("Price").strong());
+                                ui.end_row();
+                                for &resource in ResourceType::all() {
+                                    let surplus = surplus_mt_per_year(&rates, resource);
+                                    let price = market.price(resource);
+                                    ui.label(resource.display_name());
+                                    ui.label(format!("{:.1} Mt", budget.get_stockpile(&resource)));
+                                    ui.label(format!("{:+.1} Mt/yr", surplus));
+                                    let status = if price > base_price(resource) * 1.05 {
+                                        Status::Bad
+                                    } else {
+                                        Status::Good
+                                    };
+                                    ui.colored_label(
+                                        palette.status(status),
+                                        format!("{}/Mt", format_currency(price)),
+                                    )
+                                    .on_hover_text(format!(
+                                        "Base price {}/Mt",
+                                        format_currency(base_price(resource))
+                                    ));
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                ui.separator();
+
+                for faction in data.iter() {
+                    let committed = trade.committed(&faction.id);
+                    let status = if committed > faction.capacity_mt_per_year {
+                        Status::Warning
+                    } else {
+                        Status::Good
+                    };
+                    ui.horizontal(|ui| {
+                        ui.label(egui::RichText::new(&faction.name).strong());
+                        ui.label(format!("Tariff {:.0}%", faction.tariff));
+                        ui.colored_label(
+                            palette.status(status),
+                            format!(
+                                "Freighters {:.1}/{:.1} Mt/yr",
+                                committed, faction.capacity_mt_per_year
+                            ),
+                        )
+                        .on_hover_text(
+                            "Agreements beyond the freighter capacity are all scaled down alike",
+                        );
+                    });
+                    ui.label(egui::RichText::new(&faction.description).weak());
+                    ui.label(format!("Buys: {}", resource_list(&faction.buys)));
+                    ui.label(format!("Sells: {}", resource_list(&faction.sells)));
+                    for agreement in trade.agreements.iter().filter(|a| a.faction == faction.id) {
+                        ui.horizontal(|ui| {
+                            let short =
+                                agreement.delivered_mt_per_year < agreement.mt_per_year * 0.999;
+                            let status = if short { Status::Warning } else { Status::Good };
+                            ui.label(format!(
+                                "{} {}",
+                                agreement.direction.label(),
+                                agreement.resource.display_name()
+                            ));
+                            ui.colored_label(
+                                palette.status(status),
+                                format!(
+                                    "{:.1} of {:.1} Mt/yr",
+                                    agreement.delivered_mt_per_year, agreement.mt_per_year
+                                ),
+                            );
+                            ui.label(format!(
+                                "{}/yr",
+                                format_currency(
+                                    agreement.delivered_mt_per_year
+                                        * market.price(agreement.resource)
+                                )
+                            ));
+                            if ui.small_button("Cancel").clicked() {
+                                player_commands.send(PlayerCommand::CancelTrade {
+                                    faction: faction.id.clone(),
+                                    resource: agreement.resource,
+                                    direction: agreement.direction,
+                                });
+                            }
+                        });
+                    }
+                    ui.separator();
+                }
+
+                ui.heading("New Agreement");
+                draft_agreement(
+                    ui,
+                    &mut state,
+                    &data,
+                    &trade,
+                    &market,
+                    &rates,
+                    &mut player_commands,
+                );
+            });
+        });
+}
+
+/// Form to draft an agreement and sign it
+fn draft_agreement(
+    ui: &mut egui::Ui,
+    state: &mut TradeTabState,
+    data: &FactionsData,
+    trade: &TradeAgreements,
+    market: &Market,
+    rates: &ResourceRateTracker,
+    player_commands: &mut EventWriter<PlayerCommand>,
+) {
+    if state
+        .faction
+        .as_ref()
+        .is_some_and(|id| data.get(id).is_none())
+    {
+        state.faction = None;
+    }
+    let selected = state
+        .faction
+        .as_ref()
+        .and_then(|id| data.get(id))
+        .map_or("Select a faction", |f| f.name.as_str())
+        .to_string();
+    egui::ComboBox::from_id_source("trade_faction")
+        .selected_text(selected)
+        .show_ui(ui, |ui| {
+            for faction in data.iter() {
+                ui.selectable_value(&mut state.faction, Some(faction.id.clone()), &faction.name);
+            }
+        });
+    let Some(faction) = state.faction.as_ref().and_then(|id| data.get(id)) else {
+        return;
+    };
+
+    ui.horizontal(|ui| {
+        ui.radio_value(&mut state.direction, TradeDirection::Export, "Export");
+        ui.radio_value(&mut state.direction, TradeDirection::Import, "Import");
+    });
+    let offered = match state.direction {
+        TradeDirection::Export => &faction.buys,
+        TradeDirection::Import => &faction.sells,
+    };
+    if state.resource.is_some_and(|r| !offered.contains(&r)) {
+        state.resource = None;
+    }
+    egui::ComboBox::from_id_source("trade_resource")
+        .selected_text(
+            state
+                .resource
+                .map_or("Select a resource", |r| r.display_name()),
+        )
+        .show_ui(ui, |ui| {
+            for &resource in offered {
+                ui.selectable_value(&mut state.resource, Some(resource), resource.display_name());
+            }
+        });
+    let Some(resource) = state.resource else {
+        return;
+    };
+
+    ui.horizontal(|ui| {
+        ui.label("Volume");
+        ui.add(
+            egui::DragValue::new(&mut state.mt_per_year)
+                .range(0.1..=faction.capacity_mt_per_year)
+                .speed(0.1)
+                .suffix(" Mt/yr"),
+        );
+        let surplus = surplus_mt_per_year(rates, resource);
+        let suggested = match state.direction {
+            TradeDirection::Export => surplus,
+            TradeDirection::Import => -surplus,
+        };
+        if suggested > 0.0
+            && ui
+                .button("Match")
+                .on_hover_text(match state.direction {
+                    TradeDirection::Export => "Export the surplus",
+                    TradeDirection::Import => "Import the deficit",
+                })
+                .clicked()
+        {
+            state.mt_per_year = suggested.min(faction.capacity_mt_per_year);
+        }
+    });
+    ui.label(estimate(
+        faction,
+        resource,
+        state.direction,
+        state.mt_per_year,
+        market,
+    ));
+
+    match trade.check_sign(faction, resource, state.direction, state.mt_per_year) {
+        Ok(()) => {
+            if ui.button("Sign").clicked() {
+                player_commands.send(PlayerCommand::SignTrade {
+                    faction: faction.id.clone(),
+                    resource,
+                    direction: state.direction,
+                    mt_per_year: state.mt_per_year,
+                });
+            }
+        }
+        Err(reason) => {
+            ui.add_enabled(false, egui::Button::new("Sign"))
+                .on_disabled_hover_text(reason);
+        }
+    }
+}
+
+/// Money an agreement would move per year at today's prices, tariff included
+fn estimate(
+    faction: &FactionDefinition,
+    resource: ResourceType,
+    direction: TradeDirection,
+    mt_per_year: f64,
+    market: &Market,
+) -> String {
+    let value = mt_per_year * market.price(resource);
+    let tariff = value * faction.tariff_fraction();
+    match direction {
+        TradeDirection::Export => format!(
+            "Earns {}/yr after {} tariff",
+            format_currency(value - tariff),
+            format_currency(tariff)
+        ),
+        TradeDirection::Import => format!(
+            "Costs {}/yr including {} tariff",
+            format_currency(value + tariff),
+            format_currency(tariff)
+        ),
+    }
+}
+
+/// Comma-separated resource names, or "nothing"
+fn resource_list(resources: &[ResourceType]) -> String {
+    if resources.is_empty() {
+        return "nothing".to_string();
+    }
+    resources
+        .iter()
+        .map(|r| r.display_name())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,162 @@
+//! Treasury window: balance, funding sliders and the monthly ledgers,
+//! opened from the balance in the resources bar
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use super::accessibility::{self, Status};
+use crate::commands::PlayerCommand;
+use crate::economy::{format_currency, Ledger, LedgerLine, Treasury, MAX_FUNDING};
+
+/// Closed months shown next to the open one
+const LEDGER_COLUMNS: usize = 5;
+
+/// Whether the treasury window is shown
+#[derive(Resource, Debug, Clone, Default)]
+pub struct TreasuryWindow {
+    pub open: bool,
+}
+
+/// Render the treasury window
+pub(super) fn ui_treasury_window(
+    mut contexts: EguiContexts,
+    mut window: ResMut<TreasuryWindow>,
+    treasury: Res<Treasury>,
+    mut player_commands: EventWriter<PlayerCommand>,
+) {
+    if !window.open {
+        return;
+    }
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    let palette = accessibility::palette(ctx);
+
+    let mut open = true;
+    egui::Window::new("💳 Treasury")
+        .id(egui::Id::new("treasury_window"))
+        .open(&mut open)
+        .resizable(false)
+        .show(ctx, |ui| {
+            let status = if treasury.over_credit_limit() {
+                Status::Bad
+            } else if treasury.in_debt() {
+                Status::Warning
+            } else {
+                Status::Good
+            };
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new("Balance").strong());
+                ui.colored_label(
+                    palette.status(status),
+                    palette.status_text(status, format_currency(treasury.balance)),
+                );
+            });
+            ui.label(format!(
+                "Income {}/yr, expenses {}/yr",
+                format_currency(treasury.income_per_year),
+                format_currency(treasury.expenses_per_year)
+            ));
+            if treasury.over_credit_limit() {
+                ui.colored_label(
+                    palette.status(Status::Bad),
+                    "Debt exceeds a year of income: only half the funding arrives",
+                );
+            } else if treasury.in_debt() {
+                ui.colored_label(
+                    palette.status(Status::Warning),
+                    "In debt: interest is due on the balance",
+                );
+            }
+            ui.separator();
+
+            ui.label(egui::RichText::new("Funding").strong());
+            let (mut research, mut construction) =
+                (treasury.research_funding, treasury.construction_funding);
+            let changed = egui::Grid::new("treasury_funding")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label("Research");
+                    let research_changed = ui
+                        .add(funding_slider(&mut research))
+                        .on_hover_text("Speed of research, and its share of the expenses")
+                        .changed();
+                    ui.end_row();
+                    ui.label("Construction");
+                    let construction_changed = ui
+                        .add(funding_slider(&mut construction))
+                        .on_hover_text(
+                            "Speed of construction projects, and their share of the expenses",
+                        )
+                        .changed();
+                    ui.end_row();
+                    research_changed || construction_changed
+                })
+                .inner;
+            if changed {
+                player_commands.send(PlayerCommand::Funding {
+                    research,
+                    construction,
+                });
+            }
+            ui.separator();
+
+            ui.label(egui::RichText::new("Ledger").strong());
+            let closed: Vec<&Ledger> = treasury.ledgers.iter().rev().take(LEDGER_COLUMNS).collect();
+            egui::Grid::new("treasury_ledger")
+                .num_columns(closed.len() + 2)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label("");
+                    ui.label(egui::RichText::new("This month").strong());
+                    for ledger in &closed {
+                        // Only the date of "DD.MM.YYYY HH:MM"
+                        let date = ledger.date.split(' ').next().unwrap_or_default();
+                        ui.label(egui::RichText::new(date).strong());
+                    }
+                    ui.end_row();
+
+                    for line in LedgerLine::ALL {
+                        ui.label(line.label());
+                        for ledger in
+                            std::iter::once(&treasury.current).chain(closed.iter().copied())
+                        {
+                            let amount = ledger.get(line);
+                            if line.is_income() {
+                                ui.label(format_currency(amount));
+                            } else {
+                                ui.label(format_currency(-amount));
+                            }
+                        }
+                        ui.end_row();
+                    }
+
+                    ui.label(egui::RichText::new("Net").strong());
+                    for ledger in std::iter::once(&treasury.current).chain(closed.iter().copied()) {
+                        let status = if ledger.net() < 0.0 {
+                            Status::Bad
+                        } else {
+                            Status::Good
+                        };
+                        ui.colored_label(palette.status(status), format_currency(ledger.net()));
+                    }
+                    ui.end_row();
+                });
+        });
+    if !open {
+        window.open = false;
+    }
+}
+
+/// Slider for a funding setting, shown in percent
+fn funding_slider(value: &mut f64) -> egui::Slider<'_> {
+    egui::Slider::new(value, 0.0..=MAX_FUNDING)
+        .custom_formatter(|v, _| format!("{:.0}%", v * 100.0))
+        .custom_parser(|s| {
+            s.trim_end_matches('%')
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .map(|v| v / 100.0)
+        })
+}
@@ -0,0 +1,70 @@
+//! Objectives panel: the tutorial's chain of goals with the current one's
+//! hint, and the highlight drawn around the menu it needs
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use super::{alert_pulse, NewGameScreen};
+use crate::tutorial::{Objective, Tutorial};
+
+/// Draw the pulsing tutorial highlight around a widget
+pub(super) fn paint_tutorial_highlight(ui: &egui::Ui, rect: egui::Rect) {
+    let pulse = alert_pulse(ui.input(|i| i.time));
+    let color = egui::Color32::GOLD.gamma_multiply(0.3 + 0.7 * pulse);
+    ui.painter()
+        .rect_stroke(rect.expand(2.0), 4.0, egui::Stroke::new(2.5, color));
+    ui.ctx().request_repaint();
+}
+
+/// Render the objectives panel while the tutorial is enabled
+pub(super) fn ui_objectives_panel(
+    mut contexts: EguiContexts,
+    mut tutorial: ResMut<Tutorial>,
+    new_game: Res<NewGameScreen>,
+) {
+    if !tutorial.enabled || new_game.open {
+        return;
+    }
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    let mut dismiss = false;
+    egui::Window::new("🎯 Objectives")
+        .id(egui::Id::new("objectives_panel"))
+        .anchor(egui::Align2::RIGHT_TOP, [-12.0, 150.0])
+        .default_width(260.0)
+        .resizable(false)
+        .show(ctx, |ui| {
+            for (index, objective) in Objective::CHAIN.iter().enumerate() {
+                if index < tutorial.step {
+                    ui.label(
+                        egui::RichText::new(format!("✔ {}", objective.title()))
+                            .color(egui::Color32::from_rgb(120, 200, 120)),
+                    );
+                } else if index == tutorial.step {
+                    ui.label(egui::RichText::new(format!("▶ {}", objective.title())).strong());
+                    ui.label(egui::RichText::new(objective.hint()).small());
+                } else {
+                    ui.label(
+                        egui::RichText::new(format!("○ {}", objective.title()))
+                            .color(egui::Color32::GRAY),
+                    );
+                }
+            }
+            ui.separator();
+            if tutorial.is_finished() {
+                ui.label("All objectives complete.");
+                dismiss = ui.button("Close").clicked();
+            } else {
+                dismiss = ui
+                    .small_button("Skip tutorial")
+                    .on_hover_text("Turn off the objectives for sandbox play")
+                    .clicked();
+            }
+        });
+
+    if dismiss {
+        tutorial.enabled = false;
+    }
+}
@@ -0,0 +1,124 @@
+//! Watch list of bodies the player wants to keep an eye on
+//!
+//! Bodies are added from the right-click menu in the 3D view. While the list
+//! is not empty, a small window shows their population and survey progress.
+//! Clicking a name opens the body's details.
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+use super::format_population;
+use super::interaction::{BodyAction, BodyActionKind};
+use crate::economy::components::{Population, SurveyLevel};
+use crate::economy::survey::SurveyMissions;
+use crate::plugins::solar_system::CelestialBody;
+
+/// Watched bodies, in the order they were added
+#[derive(Resource, Debug, Clone, Default)]
+pub struct WatchList {
+    pub bodies: Vec<Entity>,
+}
+
+impl WatchList {
+    pub fn contains(&self, body: Entity) -> bool {
+        self.bodies.contains(&body)
+    }
+
+    /// Add `body` to the list, or remove it if it is already watched.
+    /// Returns whether the body is watched now.
+    pub fn toggle(&mut self, body: Entity) -> bool {
+        if let Some(index) = self.bodies.iter().position(|b| *b == body) {
+            self.bodies.remove(index);
+            false
+        } else {
+            self.bodies.push(body);
+            true
+        }
+    }
+}
+
+/// Render the watch list window
+pub(super) fn ui_watch_list_window(
+    mut contexts: EguiContexts,
+    mut watch_list: ResMut<WatchList>,
+    bodies: Query<(&CelestialBody, Option<&Population>, Option<&SurveyLevel>)>,
+    survey: Res<SurveyMissions>,
+    mut actions: EventWriter<BodyAction>,
+) {
+    // Forget bodies that no longer exist (e.g. destroyed asteroids)
+    if watch_list.bodies.iter().any(|b| bodies.get(*b).is_err()) {
+        watch_list.bodies.retain(|b| bodies.get(*b).is_ok());
+    }
+    if watch_list.bodies.is_empty() {
+        return;
+    }
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    let mut removed = None;
+    egui::Window::new("👁 Watch List")
+        .id(egui::Id::new("watch_list_window"))
+        .resizable(false)
+        .default_pos(egui::pos2(320.0, 80.0))
+        .show(ctx, |ui| {
+            egui::Grid::new("watch_list_grid")
+                .num_columns(4)
+                .striped(true)
+                .show(ui, |ui| {
+                    for &entity in &watch_list.bodies {
+                        let Ok((body, population, level)) = bodies.get(entity) else {
+                            continue;
+                        };
+                        if ui.link(&body.name).on_hover_text("Open details").clicked() {
+                            actions.send(BodyAction {
+                                body: entity,
+                                kind: BodyActionKind::OpenDetails,
+                            });
+                        }
+                        ui.label(
+                            population
+                                .filter(|p| p.count > 0.0)
+                                .map_or_else(|| "—".to_string(), |p| format_population(p.count)),
+                        );
+                        match survey.mission_for(entity) {
+                            Some(mission) => ui.add(
+                                egui::ProgressBar::new(mission.progress_fraction())
+                                    .desired_width(80.0)
+                                    .text(format!("{:?}", mission.level)),
+                            ),
+                            None => ui.label(format!("{:?}", level.copied().unwrap_or_default())),
+                        };
+                        if ui
+                            .small_button("✖")
+                            .on_hover_text("Stop watching")
+                            .clicked()
+                        {
+                            removed = Some(entity);
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+
+    if let Some(entity) = removed {
+        watch_list.toggle(entity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggle_adds_and_removes() {
+        let mut world = World::new();
+        let (mars, ceres) = (world.spawn_empty().id(), world.spawn_empty().id());
+        let mut list = WatchList::default();
+        assert!(list.toggle(mars));
+        assert!(list.toggle(ceres));
+        assert!(!list.toggle(mars));
+        assert_eq!(list.bodies, vec![ceres]);
+        assert!(!list.contains(mars));
+    }
+}
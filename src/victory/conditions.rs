@@ -0,0 +1,230 @@
+use bevy::prelude::*;
+
+use crate::scenarios::ExtinctionEvent;
+
+/// Years the civilization must endure after an extinction event strikes
+pub const SURVIVAL_YEARS: f64 = 5.0;
+
+/// Consecutive months beyond the credit limit that bankrupt the civilization
+pub const BANKRUPTCY_MONTHS: u32 = 12;
+
+/// Economy ticks (days) between two evaluations, one 30-day month
+pub const VICTORY_INTERVAL_TICKS: u64 = 30;
+
+/// Win and lose conditions of the campaign, picked on the new-game screen
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct VictorySettings {
+    /// Kardashev index that wins the campaign
+    pub kardashev: Option<f64>,
+    /// Number of star systems with a populated colony that wins the campaign
+    pub colonized_systems: Option<usize>,
+    /// Whether a year beyond the credit limit loses the campaign
+    pub bankruptcy: bool,
+    /// Catastrophe of the scenario; surviving it wins the campaign
+    pub extinction: Option<ExtinctionEvent>,
+}
+
+impl Default for VictorySettings {
+    fn default() -> Self {
+        Self {
+            kardashev: Some(1.0),
+            colonized_systems: Some(2),
+            bankruptcy: true,
+            extinction: None,
+        }
+    }
+}
+
+/// How the campaign ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Victory,
+    Defeat,
+}
+
+impl Outcome {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Outcome::Victory => "Victory",
+            Outcome::Defeat => "Defeat",
+        }
+    }
+}
+
+/// The end of a campaign, with why and when it came
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameResult {
+    pub outcome: Outcome,
+    pub reason: String,
+    /// In-game date as shown in the time controls
+    pub date: String,
+}
+
+/// Civilization-wide figures the conditions are evaluated against
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VictoryInputs {
+    pub population: f64,
+    pub kardashev: f64,
+    /// Distinct star systems with a populated colony
+    pub colonized_systems: usize,
+    /// Years since the extinction event struck, if it has
+    pub years_since_extinction: Option<f64>,
+    pub months_over_credit_limit: u32,
+}
+
+/// Evaluate the conditions, defeats first. Returns the outcome and its
+/// reason once one is met.
+pub fn evaluate(settings: &VictorySettings, inputs: &VictoryInputs) -> Option<(Outcome, String)> {
+    if inputs.population <= 0.0 {
+        return Some((
+            Outcome::Defeat,
+            "Humanity has perished: no one is left alive".to_string(),
+        ));
+    }
+    if settings.bankruptcy && inputs.months_over_credit_limit >= BANKRUPTCY_MONTHS {
+        return Some((
+            Outcome::Defeat,
+            format!(
+                "Bankruptcy: the treasury stayed beyond its credit limit for {} months",
+                inputs.months_over_credit_limit
+            ),
+        ));
+    }
+
+    if let Some(target) = settings.kardashev {
+        if inputs.kardashev >= target {
+            return Some((
+                Outcome::Victory,
+                format!("The civilization reached Kardashev {:.1}", target),
+            ));
+        }
+    }
+    if let Some(target) = settings.colonized_systems {
+        if inputs.colonized_systems >= target {
+            return Some((
+                Outcome::Victory,
+                format!(
+                    "Colonies thrive in {} star systems",
+                    inputs.colonized_systems
+                ),
+            ));
+        }
+    }
+    if let Some(extinction) = &settings.extinction {
+        if inputs
+            .years_since_extinction
+            .is_some_and(|years| years >= SURVIVAL_YEARS)
+        {
+            return Some((
+                Outcome::Victory,
+                format!(
+                    "Humanity endured {:.0} years after the loss of {}",
+                    SURVIVAL_YEARS, extinction.body
+                ),
+            ));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scenarios::StartDate;
+
+    fn inputs() -> VictoryInputs {
+        VictoryInputs {
+            population: 8.0e9,
+            kardashev: 0.7,
+            colonized_systems: 1,
+            years_since_extinction: None,
+            months_over_credit_limit: 0,
+        }
+    }
+
+    #[test]
+    fn test_evaluate_conditions() {
+        let settings = VictorySettings::default();
+        assert_eq!(evaluate(&settings, &inputs()), None);
+
+        let reached = VictoryInputs {
+            kardashev: 1.05,
+            ..inputs()
+        };
+        assert_eq!(
+            evaluate(&settings, &reached).map(|(o, _)| o),
+            Some(Outcome::Victory)
+        );
+        let disabled = VictorySettings {
+            kardashev: None,
+            ..settings.clone()
+        };
+        assert_eq!(evaluate(&disabled, &reached), None);
+
+        let spread = VictoryInputs {
+            colonized_systems: 2,
+            ..inputs()
+        };
+        assert_eq!(
+            evaluate(&settings, &spread).map(|(o, _)| o),
+            Some(Outcome::Victory)
+        );
+
+        // Defeats win over victories reached in the same month
+        let broke = VictoryInputs {
+            months_over_credit_limit: BANKRUPTCY_MONTHS,
+            ..reached.clone()
+        };
+        assert_eq!(
+            evaluate(&settings, &broke).map(|(o, _)| o),
+            Some(Outcome::Defeat)
+        );
+        let lenient = VictorySettings {
+            bankruptcy: false,
+            ..settings.clone()
+        };
+        assert_eq!(
+            evaluate(&lenient, &broke).map(|(o, _)| o),
+            Some(Outcome::Victory)
+        );
+        let extinct = VictoryInputs {
+            population: 0.0,
+            ..inputs()
+        };
+        assert_eq!(
+            evaluate(&settings, &extinct).map(|(o, _)| o),
+            Some(Outcome::Defeat)
+        );
+    }
+
+    #[test]
+    fn test_surviving_extinction_wins() {
+        let settings = VictorySettings {
+            kardashev: None,
+            colonized_systems: None,
+            extinction: Some(ExtinctionEvent {
+                date: StartDate {
+                    year: 2150,
+                    month: 1,
+                    day: 1,
+                },
+                body: "Earth".to_string(),
+                description: String::new(),
+            }),
+            ..Default::default()
+        };
+        let struck = VictoryInputs {
+            years_since_extinction: Some(2.0),
+            ..inputs()
+        };
+        assert_eq!(evaluate(&settings, &struck), None);
+        let survived = VictoryInputs {
+            years_since_extinction: Some(SURVIVAL_YEARS),
+            ..inputs()
+        };
+        assert_eq!(
+            evaluate(&settings, &survived).map(|(o, _)| o),
+            Some(Outcome::Victory)
+        );
+    }
+}
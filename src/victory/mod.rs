@@ -0,0 +1,38 @@
+//! Victory conditions and game-over handling
+//!
+//! Configurable ways for a campaign to end:
+//! - Victory by reaching a Kardashev index, by colonizing a number of star
+//!   systems, or by surviving a scenario's extinction event for some years
+//! - Defeat when no one is left alive, or after a year of bankruptcy beyond
+//!   the credit limit
+//!
+//! The conditions are evaluated once a month. When one is met the simulation
+//! pauses for the end-of-game summary, from which play can continue in
+//! sandbox mode.
+
+use bevy::prelude::*;
+
+pub mod conditions;
+pub mod systems;
+
+pub use conditions::{
+    evaluate, GameResult, Outcome, VictoryInputs, VictorySettings, BANKRUPTCY_MONTHS,
+    SURVIVAL_YEARS, VICTORY_INTERVAL_TICKS,
+};
+pub use systems::{check_victory_conditions, strike_extinction_event, VictoryState};
+
+/// Plugin that adds win and lose conditions to the Bevy app
+pub struct VictoryPlugin;
+
+impl Plugin for VictoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<VictorySettings>()
+            .init_resource::<VictoryState>()
+            .add_systems(
+                FixedUpdate,
+                (strike_extinction_event, check_victory_conditions)
+                    .chain()
+                    .after(crate::economy::update_treasury),
+            );
+    }
+}
@@ -0,0 +1,205 @@
+use bevy::prelude::*;
+use std::collections::HashSet;
+
+use super::conditions::{
+    evaluate, GameResult, Outcome, VictoryInputs, VictorySettings, VICTORY_INTERVAL_TICKS,
+};
+use crate::astronomy::components::SystemId;
+use crate::economy::components::Population;
+use crate::economy::{EconomyClock, GlobalBudget, Treasury, SECONDS_PER_YEAR};
+use crate::game_state::{EventLog, LogSeverity};
+use crate::plugins::solar_system::CelestialBody;
+use crate::ui::{SimulationTime, TimeScale, Toasts};
+
+/// Progress of the campaign towards its end
+#[derive(Resource, Debug, Clone, Default)]
+pub struct VictoryState {
+    /// How the campaign ended, once it has
+    pub result: Option<GameResult>,
+    /// Whether play continues past the end without further checks
+    pub sandbox: bool,
+    /// Unix timestamp the extinction event struck at
+    pub extinction_struck_at: Option<i64>,
+    /// Consecutive months the treasury ended beyond its credit limit
+    pub months_over_credit_limit: u32,
+}
+
+impl VictoryState {
+    /// Whether the conditions are still evaluated
+    pub fn in_play(&self) -> bool {
+        self.result.is_none() && !self.sandbox
+    }
+}
+
+/// System that strikes the scenario's extinction event once its date is
+/// reached, wiping out the population of the body it names
+pub fn strike_extinction_event(
+    settings: Res<VictorySettings>,
+    mut state: ResMut<VictoryState>,
+    sim_time: Res<SimulationTime>,
+    mut bodies: Query<(&CelestialBody, &mut Population)>,
+    mut log: Option<ResMut<EventLog>>,
+    mut toasts: Option<ResMut<Toasts>>,
+) {
+    let Some(extinction) = &settings.extinction else {
+        return;
+    };
+    if state.extinction_struck_at.is_some()
+        || sim_time.current_timestamp() < extinction.date.timestamp()
+    {
+        return;
+    }
+    state.extinction_struck_at = Some(sim_time.current_timestamp());
+
+    let mut lost = 0.0;
+    for (body, mut population) in bodies.iter_mut() {
+        if body.name == extinction.body {
+            lost += population.count;
+            population.count = 0.0;
+        }
+    }
+    let message = format!(
+        "☠ {} — {} is lost with {:.0} people",
+        extinction.description, extinction.body, lost
+    );
+    warn!("{}", message);
+    if let Some(toasts) = toasts.as_mut() {
+        toasts.push(LogSeverity::Critical, message.clone());
+    }
+    if let Some(log) = log.as_mut() {
+        log.push(sim_time.format_date_time(), LogSeverity::Critical, message);
+    }
+}
+
+/// System that evaluates the win and lose conditions once a month. When one
+/// is met the result is recorded, announced and the simulation paused for the
+/// end-of-game summary.
+#[allow(clippy::too_many_arguments)]
+pub fn check_victory_conditions(
+    clock: Res<EconomyClock>,
+    settings: Res<VictorySettings>,
+    mut state: ResMut<VictoryState>,
+    sim_time: Res<SimulationTime>,
+    budget: Res<GlobalBudget>,
+    treasury: Option<Res<Treasury>>,
+    populations: Query<(&Population, Option<&SystemId>)>,
+    mut time_scale: Option<ResMut<TimeScale>>,
+    mut log: Option<ResMut<EventLog>>,
+    mut toasts: Option<ResMut<Toasts>>,
+) {
+    if clock.due == 0 || !state.in_play() {
+        return;
+    }
+    let months_passed = clock.processed / VICTORY_INTERVAL_TICKS
+        - (clock.processed - clock.due) / VICTORY_INTERVAL_TICKS;
+    if months_passed == 0 {
+        return;
+    }
+
+    if treasury.as_ref().is_some_and(|t| t.over_credit_limit()) {
+        state.months_over_credit_limit += months_passed as u32;
+    } else {
+        state.months_over_credit_limit = 0;
+    }
+
+    let mut population = 0.0;
+    let mut systems = HashSet::new();
+    for (colony, system) in populations.iter() {
+        if colony.count > 0.0 {
+            population += colony.count;
+            // Stations without a system orbit in Sol
+            systems.insert(system.map_or(0, |s| s.0));
+        }
+    }
+    let inputs = VictoryInputs {
+        population,
+        kardashev: budget.kardashev_index(),
+        colonized_systems: systems.len(),
+        years_since_extinction: state
+            .extinction_struck_at
+            .map(|struck| (sim_time.current_timestamp() - struck) as f64 / SECONDS_PER_YEAR),
+        months_over_credit_limit: state.months_over_credit_limit,
+    };
+    let Some((outcome, reason)) = evaluate(&settings, &inputs) else {
+        return;
+    };
+
+    let date = sim_time.format_date_time();
+    let (severity, icon) = match outcome {
+        Outcome::Victory => (LogSeverity::Info, "🏆"),
+        Outcome::Defeat => (LogSeverity::Critical, "💀"),
+    };
+    let message = format!("{} {}: {}", icon, outcome.name(), reason);
+    info!("{}", message);
+    if let Some(toasts) = toasts.as_mut() {
+        toasts.push(severity, message.clone());
+    }
+    if let Some(log) = log.as_mut() {
+        log.push(date.clone(), severity, message);
+    }
+    if let Some(time_scale) = time_scale.as_mut() {
+        time_scale.pause();
+    }
+    state.result = Some(GameResult {
+        outcome,
+        reason,
+        date,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::solar_system_data::BodyType;
+    use crate::scenarios::{ExtinctionEvent, StartDate};
+
+    #[test]
+    fn test_extinction_strikes_and_defeats() {
+        let mut app = App::new();
+        app.init_resource::<EconomyClock>()
+            .init_resource::<VictoryState>()
+            .init_resource::<SimulationTime>()
+            .init_resource::<GlobalBudget>()
+            .insert_resource(VictorySettings {
+                extinction: Some(ExtinctionEvent {
+                    date: StartDate::default(),
+                    body: "Earth".to_string(),
+                    description: "An impact sterilizes Earth".to_string(),
+                }),
+                ..Default::default()
+            })
+            .add_systems(
+                Update,
+                (strike_extinction_event, check_victory_conditions).chain(),
+            );
+        let earth = app
+            .world_mut()
+            .spawn((
+                CelestialBody::fixture("Earth", BodyType::Planet),
+                Population::new(8.0e9),
+            ))
+            .id();
+        {
+            let mut clock = app.world_mut().resource_mut::<EconomyClock>();
+            clock.processed = VICTORY_INTERVAL_TICKS;
+            clock.due = 1;
+        }
+        app.update();
+
+        assert_eq!(
+            app.world().get::<Population>(earth).map(|p| p.count),
+            Some(0.0)
+        );
+        let state = app.world().resource::<VictoryState>();
+        assert!(state.extinction_struck_at.is_some());
+        assert_eq!(
+            state.result.as_ref().map(|r| r.outcome),
+            Some(Outcome::Defeat)
+        );
+
+        // Sandbox play goes on without further checks
+        app.world_mut().resource_mut::<VictoryState>().sandbox = true;
+        app.update();
+        assert!(!app.world().resource::<VictoryState>().in_play());
+    }
+}